@@ -242,6 +242,7 @@ fn benchmark_enrichment_tables_geoip(c: &mut Criterion) {
         Geoip::new(GeoipConfig {
             path: path.into(),
             locale: "en".to_string(),
+            auto_update: None,
         })
         .unwrap()
     };
@@ -49,6 +49,7 @@ fn dedupe(c: &mut Criterion) {
                 fields: Some(FieldMatchConfig::IgnoreFields(vec!["message".into()])),
                 cache: cache.clone(),
                 time_settings: None,
+                probabilistic: None,
             },
         },
         // Modification of previous where field "message" is matched.
@@ -59,6 +60,7 @@ fn dedupe(c: &mut Criterion) {
                 fields: Some(FieldMatchConfig::MatchFields(vec!["message".into()])),
                 cache: cache.clone(),
                 time_settings: None,
+                probabilistic: None,
             },
         },
         // Modification of previous where deduplication with max age is used.
@@ -72,6 +74,7 @@ fn dedupe(c: &mut Criterion) {
                     max_age_ms: Duration::from_secs(5),
                     refresh_on_drop: false,
                 }),
+                probabilistic: None,
             },
         },
         // Modification of previous where refresh on drop is enabled.
@@ -85,6 +88,7 @@ fn dedupe(c: &mut Criterion) {
                     max_age_ms: Duration::from_secs(5),
                     refresh_on_drop: true,
                 }),
+                probabilistic: None,
             },
         },
         // Measurement where ignore fields do not exist in the event.
@@ -101,6 +105,7 @@ fn dedupe(c: &mut Criterion) {
                     "bcdea".into(),
                 ])),
                 time_settings: None,
+                probabilistic: None,
             },
         },
         // Modification of previous where match fields do not exist in the
@@ -118,6 +123,7 @@ fn dedupe(c: &mut Criterion) {
                     "bcdea".into(),
                 ])),
                 time_settings: None,
+                probabilistic: None,
             },
         },
     ] {
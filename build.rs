@@ -114,6 +114,9 @@ fn main() {
 
     #[cfg(feature = "protobuf-build")]
     {
+        println!(
+            "cargo:rerun-if-changed=proto/third-party/google/cloud/bigquery/storage/v1/storage.proto"
+        );
         println!("cargo:rerun-if-changed=proto/third-party/google/pubsub/v1/pubsub.proto");
         println!("cargo:rerun-if-changed=proto/third-party/google/rpc/status.proto");
         println!("cargo:rerun-if-changed=proto/vector/dd_metric.proto");
@@ -144,6 +147,7 @@ fn main() {
                     "proto/vector/ddsketch_full.proto",
                     "proto/vector/dd_metric.proto",
                     "proto/vector/dd_trace.proto",
+                    "proto/third-party/google/cloud/bigquery/storage/v1/storage.proto",
                     "proto/third-party/google/pubsub/v1/pubsub.proto",
                     "proto/third-party/google/rpc/status.proto",
                     "proto/vector/vector.proto",
@@ -44,6 +44,22 @@ pub struct OctetCountingDecoderOptions {
 pub struct OctetCountingDecoder {
     other: LinesCodec,
     octet_decoding: Option<State>,
+    detection: Detection,
+}
+
+/// How the octet counting frames are told apart from the newline delimited ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Detection {
+    /// Each frame starting with a non zero digit is octet counted.
+    PerFrame,
+    /// The framing of the first frame is used for the whole stream, once detected.
+    PerStream(Option<StreamFraming>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StreamFraming {
+    OctetCounting,
+    NewlineDelimited,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -59,6 +75,7 @@ impl OctetCountingDecoder {
         Self {
             other: LinesCodec::new(),
             octet_decoding: None,
+            detection: Detection::PerFrame,
         }
     }
 
@@ -67,9 +84,21 @@ impl OctetCountingDecoder {
         Self {
             other: LinesCodec::new_with_max_length(max_length),
             octet_decoding: None,
+            detection: Detection::PerFrame,
         }
     }
 
+    /// Detects whether the stream is octet counted from its first frame, instead of from each
+    /// frame.
+    ///
+    /// This keeps the newline delimited messages starting with a digit from being taken for
+    /// octet counted ones, and allows the octet counted frames to be followed by newlines, as
+    /// some senders do.
+    pub fn detect_per_stream(mut self) -> Self {
+        self.detection = Detection::PerStream(None);
+        self
+    }
+
     /// Decode a frame.
     fn octet_decode(
         &mut self,
@@ -232,6 +261,10 @@ impl OctetCountingDecoder {
         &mut self,
         src: &mut BytesMut,
     ) -> Option<Result<Option<Bytes>, LinesCodecError>> {
+        if let Detection::PerStream(framing) = self.detection {
+            return self.checked_stream_decode(framing, src);
+        }
+
         if let Some(&first_byte) = src.first()
             && (49..=57).contains(&first_byte)
         {
@@ -244,6 +277,48 @@ impl OctetCountingDecoder {
         self.octet_decoding
             .map(|state| self.octet_decode(state, src))
     }
+
+    /// `None` if the stream is not octet counting encoded.
+    fn checked_stream_decode(
+        &mut self,
+        framing: Option<StreamFraming>,
+        src: &mut BytesMut,
+    ) -> Option<Result<Option<Bytes>, LinesCodecError>> {
+        let framing = match framing {
+            Some(framing) => framing,
+            None => {
+                let framing = match src.first()? {
+                    b'1'..=b'9' => {
+                        trace!("Octet counting encoded stream detected.");
+                        StreamFraming::OctetCounting
+                    }
+                    _ => StreamFraming::NewlineDelimited,
+                };
+                self.detection = Detection::PerStream(Some(framing));
+                framing
+            }
+        };
+
+        if framing == StreamFraming::NewlineDelimited {
+            return None;
+        }
+
+        if self.octet_decoding.is_none() {
+            // Skip the newlines following the previous frame.
+            let newlines = src
+                .iter()
+                .take_while(|&&b| b == b'\n' || b == b'\r')
+                .count();
+            src.advance(newlines);
+            if src.is_empty() {
+                return Some(Ok(None));
+            }
+            self.octet_decoding = Some(State::NotDiscarding);
+        }
+
+        self.octet_decoding
+            .map(|state| self.octet_decode(state, src))
+    }
 }
 
 impl Default for OctetCountingDecoder {
@@ -326,6 +401,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_detection_keeps_newline_framing() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128).detect_per_stream();
+        let mut buffer = BytesMut::with_capacity(64);
+
+        buffer.put(&b"<13>first message\n12 not a length\n"[..]);
+        let result = decoder.decode(&mut buffer);
+        assert_eq!(
+            Ok(Some("<13>first message".into())),
+            result.map_err(|_| false)
+        );
+        let result = decoder.decode(&mut buffer);
+        assert_eq!(
+            Ok(Some("12 not a length".into())),
+            result.map_err(|_| false)
+        );
+    }
+
+    #[test]
+    fn stream_detection_skips_newlines_between_frames() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128).detect_per_stream();
+        let mut buffer = BytesMut::with_capacity(64);
+
+        buffer.put(&b"5 first\n6 second\r\n"[..]);
+        let result = decoder.decode(&mut buffer);
+        assert_eq!(Ok(Some("first".into())), result.map_err(|_| false));
+        let result = decoder.decode(&mut buffer);
+        assert_eq!(Ok(Some("second".into())), result.map_err(|_| false));
+        let result = decoder.decode(&mut buffer);
+        assert_eq!(Ok(None), result.map_err(|_| false));
+        assert!(buffer.is_empty());
+    }
+
     #[test]
     fn octet_decode_moves_past_invalid_length() {
         let mut decoder = OctetCountingDecoder::new_with_max_length(16);
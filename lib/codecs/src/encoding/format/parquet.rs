@@ -0,0 +1,311 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow_json::{ReaderBuilder, reader::infer_json_schema_from_iterator};
+use arrow_schema::{DataType as ArrowDataType, Field, Schema, SchemaRef, TimeUnit};
+use bytes::Bytes;
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, GzipLevel, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use vector_config::configurable_component;
+use vector_core::{config::DataType, event::Event, schema};
+
+use crate::encoding::BuildError;
+
+/// The number of rows decoded into each Arrow record batch, and so written to each Parquet row
+/// group at most.
+const RECORD_BATCH_SIZE: usize = 8192;
+
+/// Config used to build a `ParquetSerializer`.
+#[derive(Debug, Clone, Default)]
+pub struct ParquetSerializerConfig {
+    /// Options for the Parquet serializer.
+    pub parquet: ParquetSerializerOptions,
+}
+
+impl ParquetSerializerConfig {
+    /// Creates a new `ParquetSerializerConfig`.
+    pub const fn new(parquet: ParquetSerializerOptions) -> Self {
+        Self { parquet }
+    }
+
+    /// Build the `ParquetSerializer` from this configuration.
+    pub fn build(&self) -> Result<ParquetSerializer, BuildError> {
+        let schema = self.parquet.schema.as_ref().map(|fields| {
+            Arc::new(Schema::new(
+                fields
+                    .iter()
+                    .map(|(name, field_type)| Field::new(name, field_type.data_type(), true))
+                    .collect::<Vec<_>>(),
+            ))
+        });
+        if schema
+            .as_ref()
+            .is_some_and(|schema| schema.fields().is_empty())
+        {
+            return Err("Parquet schema must contain at least one field".into());
+        }
+
+        let properties = WriterProperties::builder()
+            .set_compression(self.parquet.compression.into())
+            .build();
+
+        Ok(ParquetSerializer { schema, properties })
+    }
+
+    /// The data type of events that are accepted by `ParquetSerializer`.
+    pub fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Apache Parquet serializer options.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct ParquetSerializerOptions {
+    /// The columns of the Parquet files, by name.
+    ///
+    /// Fields of the events that aren't listed are not written, and missing fields are written as
+    /// null values. Columns are written in alphabetical order.
+    ///
+    /// If not set, the schema is inferred from the events of each batch.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "The type of the column."))]
+    #[configurable(metadata(docs::examples = "example_schema()"))]
+    pub schema: Option<BTreeMap<String, ParquetFieldType>>,
+
+    /// The compression codec used for the column chunks of the Parquet files.
+    #[serde(default)]
+    pub compression: ParquetCompression,
+}
+
+fn example_schema() -> BTreeMap<String, ParquetFieldType> {
+    BTreeMap::from([
+        ("message".to_string(), ParquetFieldType::String),
+        ("status".to_string(), ParquetFieldType::Int64),
+        ("timestamp".to_string(), ParquetFieldType::Timestamp),
+    ])
+}
+
+/// The type of a Parquet column.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetFieldType {
+    /// A boolean value.
+    Boolean,
+
+    /// A 64-bit signed integer.
+    Int64,
+
+    /// A 64-bit floating point number.
+    Float64,
+
+    /// A UTF-8 string.
+    ///
+    /// Numbers and booleans are converted to strings.
+    String,
+
+    /// A timestamp with microsecond precision, in UTC.
+    ///
+    /// Values are parsed from RFC 3339 strings, which is how Vector encodes timestamps.
+    Timestamp,
+}
+
+impl ParquetFieldType {
+    fn data_type(self) -> ArrowDataType {
+        match self {
+            Self::Boolean => ArrowDataType::Boolean,
+            Self::Int64 => ArrowDataType::Int64,
+            Self::Float64 => ArrowDataType::Float64,
+            Self::String => ArrowDataType::Utf8,
+            Self::Timestamp => {
+                ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+            }
+        }
+    }
+}
+
+/// The compression codec used for the column chunks of Parquet files.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    /// No compression.
+    None,
+
+    /// [Snappy][snappy] compression.
+    ///
+    /// [snappy]: https://github.com/google/snappy
+    #[default]
+    Snappy,
+
+    /// [Gzip][gzip] compression.
+    ///
+    /// [gzip]: https://www.gzip.org/
+    Gzip,
+
+    /// [Zstandard][zstd] compression.
+    ///
+    /// [zstd]: https://facebook.github.io/zstd/
+    Zstd,
+
+    /// [LZ4][lz4] compression, without framing.
+    ///
+    /// [lz4]: https://lz4.org/
+    Lz4,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+            ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+            ParquetCompression::Lz4 => Compression::LZ4_RAW,
+        }
+    }
+}
+
+/// Serializer that converts a batch of `Event`s to a file in the Apache Parquet format.
+#[derive(Debug, Clone)]
+pub struct ParquetSerializer {
+    schema: Option<SchemaRef>,
+    properties: WriterProperties,
+}
+
+impl ParquetSerializer {
+    /// Encodes the events as a single Parquet file.
+    ///
+    /// The events are converted to JSON objects and decoded into Arrow record batches using either
+    /// the configured schema or one inferred from the events themselves.
+    pub fn encode(&self, events: Vec<Event>) -> Result<Bytes, vector_common::Error> {
+        if events.is_empty() {
+            return Ok(Bytes::new());
+        }
+
+        let rows = events
+            .into_iter()
+            .map(|event| serde_json::to_value(event.into_log()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let schema = match &self.schema {
+            Some(schema) => Arc::clone(schema),
+            None => Arc::new(infer_json_schema_from_iterator(rows.iter().map(Ok))?),
+        };
+
+        let mut decoder = ReaderBuilder::new(Arc::clone(&schema))
+            .with_batch_size(RECORD_BATCH_SIZE)
+            .with_coerce_primitive(true)
+            .build_decoder()?;
+        let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(self.properties.clone()))?;
+
+        for chunk in rows.chunks(RECORD_BATCH_SIZE) {
+            decoder.serialize(chunk)?;
+            if let Some(batch) = decoder.flush()? {
+                writer.write(&batch)?;
+            }
+        }
+
+        Ok(writer.into_inner()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::Field,
+    };
+    use vector_core::event::{Event, LogEvent};
+    use vrl::btreemap;
+
+    use super::*;
+
+    fn read_rows(bytes: Bytes) -> Vec<Vec<(String, Field)>> {
+        let reader = SerializedFileReader::new(bytes).unwrap();
+        reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().into_columns())
+            .collect()
+    }
+
+    fn events() -> Vec<Event> {
+        vec![
+            Event::Log(LogEvent::from(btreemap! {
+                "message" => "first",
+                "status" => 200,
+            })),
+            Event::Log(LogEvent::from(btreemap! {
+                "message" => "second",
+                "status" => 500,
+                "error" => true,
+            })),
+        ]
+    }
+
+    #[test]
+    fn infers_schema() {
+        // Inferred columns are ordered by when they were first seen.
+        let serializer = ParquetSerializerConfig::default().build().unwrap();
+        let rows = read_rows(serializer.encode(events()).unwrap());
+
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    ("message".to_string(), Field::Str("first".to_string())),
+                    ("status".to_string(), Field::Long(200)),
+                    ("error".to_string(), Field::Null),
+                ],
+                vec![
+                    ("message".to_string(), Field::Str("second".to_string())),
+                    ("status".to_string(), Field::Long(500)),
+                    ("error".to_string(), Field::Bool(true)),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_explicit_schema() {
+        let serializer = ParquetSerializerConfig::new(ParquetSerializerOptions {
+            schema: Some(BTreeMap::from([
+                ("message".to_string(), ParquetFieldType::String),
+                ("status".to_string(), ParquetFieldType::String),
+                ("missing".to_string(), ParquetFieldType::Float64),
+            ])),
+            compression: ParquetCompression::Zstd,
+        })
+        .build()
+        .unwrap();
+        let rows = read_rows(serializer.encode(events()).unwrap());
+
+        assert_eq!(
+            rows[1],
+            vec![
+                ("message".to_string(), Field::Str("second".to_string())),
+                ("missing".to_string(), Field::Null),
+                ("status".to_string(), Field::Str("500".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_schema() {
+        let config = ParquetSerializerConfig::new(ParquetSerializerOptions {
+            schema: Some(BTreeMap::new()),
+            compression: ParquetCompression::default(),
+        });
+        assert!(config.build().is_err());
+    }
+}
@@ -17,6 +17,11 @@ pub use format::{
 };
 #[cfg(feature = "opentelemetry")]
 pub use format::{OtlpSerializer, OtlpSerializerConfig};
+#[cfg(feature = "parquet")]
+pub use format::{
+    ParquetCompression, ParquetFieldType, ParquetSerializer, ParquetSerializerConfig,
+    ParquetSerializerOptions,
+};
 pub use framing::{
     BoxedFramer, BoxedFramingError, BytesEncoder, BytesEncoderConfig, CharacterDelimitedEncoder,
     CharacterDelimitedEncoderConfig, CharacterDelimitedEncoderOptions, Framer, FramingConfig,
@@ -24,6 +29,8 @@ pub use framing::{
     NewlineDelimitedEncoderConfig, VarintLengthDelimitedEncoder,
     VarintLengthDelimitedEncoderConfig,
 };
+#[cfg(feature = "parquet")]
+pub use serializer::{BatchSerializer, BatchSerializerConfig};
 pub use serializer::{Serializer, SerializerConfig};
 
 /// An error that occurred while building an encoder.
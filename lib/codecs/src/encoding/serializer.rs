@@ -1,5 +1,7 @@
 //! Serializer configuration and implementation for encoding structured events as bytes.
 
+#[cfg(feature = "parquet")]
+use bytes::Bytes;
 use bytes::BytesMut;
 use vector_config::configurable_component;
 use vector_core::{config::DataType, event::Event, schema};
@@ -15,6 +17,8 @@ use super::format::{
 };
 #[cfg(feature = "opentelemetry")]
 use super::format::{OtlpSerializer, OtlpSerializerConfig};
+#[cfg(feature = "parquet")]
+use super::format::{ParquetSerializer, ParquetSerializerConfig, ParquetSerializerOptions};
 use super::framing::{
     CharacterDelimitedEncoderConfig, FramingConfig, LengthDelimitedEncoderConfig,
     VarintLengthDelimitedEncoderConfig,
@@ -513,6 +517,102 @@ impl tokio_util::codec::Encoder<Event> for Serializer {
     }
 }
 
+/// Batch serializer configuration.
+///
+/// Batch serializers encode a whole batch of events at once, for formats such as Apache Parquet
+/// that can't be produced by concatenating individually encoded events.
+#[cfg(feature = "parquet")]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "codec", rename_all = "snake_case")]
+#[configurable(metadata(
+    docs::enum_tag_description = "The codec to use for encoding batches of events."
+))]
+pub enum BatchSerializerConfig {
+    /// Encodes a batch of events as an [Apache Parquet][apache_parquet] file.
+    ///
+    /// The events are converted to Arrow record batches before being written, using either the
+    /// configured schema or one inferred from the events of the batch.
+    ///
+    /// [apache_parquet]: https://parquet.apache.org/
+    Parquet {
+        /// Apache Parquet-specific encoder options.
+        #[serde(default)]
+        parquet: ParquetSerializerOptions,
+    },
+}
+
+#[cfg(feature = "parquet")]
+impl BatchSerializerConfig {
+    /// Build the `BatchSerializer` from this configuration.
+    pub fn build(
+        &self,
+    ) -> Result<BatchSerializer, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        match self {
+            BatchSerializerConfig::Parquet { parquet } => Ok(BatchSerializer::Parquet(
+                ParquetSerializerConfig::new(parquet.clone()).build()?,
+            )),
+        }
+    }
+
+    /// The data type of events that are accepted by this `BatchSerializer`.
+    pub fn input_type(&self) -> DataType {
+        match self {
+            BatchSerializerConfig::Parquet { parquet } => {
+                ParquetSerializerConfig::new(parquet.clone()).input_type()
+            }
+        }
+    }
+
+    /// The schema required by the batch serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        match self {
+            BatchSerializerConfig::Parquet { parquet } => {
+                ParquetSerializerConfig::new(parquet.clone()).schema_requirement()
+            }
+        }
+    }
+
+    /// The file extension of the encoded batches.
+    pub const fn file_extension(&self) -> &'static str {
+        match self {
+            BatchSerializerConfig::Parquet { .. } => "parquet",
+        }
+    }
+}
+
+/// Serialize batches of structured events as bytes.
+#[cfg(feature = "parquet")]
+#[derive(Debug, Clone)]
+pub enum BatchSerializer {
+    /// Uses a `ParquetSerializer` for serialization.
+    Parquet(ParquetSerializer),
+}
+
+#[cfg(feature = "parquet")]
+impl BatchSerializer {
+    /// Encodes the batch of events.
+    pub fn encode(&self, events: Vec<Event>) -> Result<Bytes, vector_common::Error> {
+        match self {
+            BatchSerializer::Parquet(serializer) => serializer.encode(events),
+        }
+    }
+
+    /// Get the HTTP content type.
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            BatchSerializer::Parquet(_) => "application/vnd.apache.parquet",
+        }
+    }
+
+    /// Get the file extension of the encoded batches.
+    pub const fn file_extension(&self) -> &'static str {
+        match self {
+            BatchSerializer::Parquet(_) => "parquet",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,6 +132,27 @@ impl CheckpointsView {
         }
     }
 
+    /// Loads the given checkpoints, unless there is a more recently updated checkpoint for the
+    /// same file. Returns the number of checkpoints loaded.
+    fn merge_state(&self, state: State) -> usize {
+        match state {
+            State::V1 { checkpoints } => {
+                let mut merged = 0;
+                for checkpoint in checkpoints {
+                    let newer_exists = self
+                        .modified_times
+                        .get(&checkpoint.fingerprint)
+                        .is_some_and(|modified| *modified.value() >= checkpoint.modified);
+                    if !newer_exists {
+                        self.load(checkpoint);
+                        merged += 1;
+                    }
+                }
+                merged
+            }
+        }
+    }
+
     fn get_state(&self) -> State {
         State::V1 {
             checkpoints: self
@@ -271,6 +292,36 @@ impl Checkpointer {
         }
     }
 
+    /// Read the persisted checkpoints as JSON, for importing them into another data directory.
+    ///
+    /// Unlike `read_checkpoints`, this never moves the checkpoints file of an interrupted process,
+    /// so that checkpoints can be exported while Vector is running.
+    pub async fn export_checkpoints(&self) -> Result<Vec<u8>, io::Error> {
+        let state = match self.read_checkpoints_file(&self.tmp_file_path).await {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                self.read_checkpoints_file(&self.stable_file_path).await?
+            }
+            result => result?,
+        };
+        serde_json::to_vec_pretty(&state).map_err(io::Error::other)
+    }
+
+    /// Merge checkpoints exported from another data directory with the persisted checkpoints,
+    /// and persist the result.
+    ///
+    /// For files with checkpoints in both, the most recently updated checkpoint is kept. Returns
+    /// the number of checkpoints imported.
+    pub async fn import_checkpoints(&mut self, exported: &[u8]) -> Result<usize, io::Error> {
+        let state: State = serde_json::from_slice(exported)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        self.read_checkpoints(None).await;
+        let imported = self.checkpoints.merge_state(state);
+        self.write_checkpoints().await?;
+
+        Ok(imported)
+    }
+
     async fn read_checkpoints_file(&self, path: &Path) -> Result<State, io::Error> {
         // Possible optimization: mmap the file into a slice and pass it into serde_json instead of
         // calling read_to_end. Need to investigate if this would work with tokio::fs::File
@@ -528,6 +579,48 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_checkpointer_export_import() {
+        let now = Utc::now();
+        let exported_only = FileFingerprint::FirstLinesChecksum(1234);
+        let in_both = FileFingerprint::FirstLinesChecksum(78910);
+
+        let source_dir = tempdir().unwrap();
+        let exported = {
+            let chkptr = Checkpointer::new(source_dir.path());
+            for fingerprint in [exported_only, in_both] {
+                chkptr.checkpoints.load(Checkpoint {
+                    fingerprint,
+                    position: 100,
+                    modified: now - Duration::seconds(10),
+                });
+            }
+            chkptr.write_checkpoints().await.unwrap();
+            chkptr.export_checkpoints().await.unwrap()
+        };
+
+        let target_dir = tempdir().unwrap();
+        {
+            let chkptr = Checkpointer::new(target_dir.path());
+            chkptr.checkpoints.load(Checkpoint {
+                fingerprint: in_both,
+                position: 200,
+                modified: now,
+            });
+            chkptr.write_checkpoints().await.unwrap();
+        }
+        {
+            let mut chkptr = Checkpointer::new(target_dir.path());
+            assert_eq!(chkptr.import_checkpoints(&exported).await.unwrap(), 1);
+        }
+        {
+            let mut chkptr = Checkpointer::new(target_dir.path());
+            chkptr.read_checkpoints(None).await;
+            assert_eq!(chkptr.get_checkpoint(exported_only), Some(100));
+            assert_eq!(chkptr.get_checkpoint(in_both), Some(200));
+        }
+    }
+
     // guards against accidental changes to the checkpoint deserialization and tests deserializing
     // old checkpoint versions
     #[tokio::test]
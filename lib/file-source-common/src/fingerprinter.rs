@@ -690,6 +690,8 @@ mod test {
 
         fn emit_files_open(&self, _: usize) {}
 
+        fn emit_files_lag(&self, _: &'static str, _: u64) {}
+
         fn emit_path_globbing_failed(&self, _: &Path, _: &Error) {
             panic!()
         }
@@ -27,6 +27,8 @@ pub trait FileSourceInternalEvents: Send + Sync + Clone + 'static {
 
     fn emit_files_open(&self, count: usize);
 
+    fn emit_files_lag(&self, priority: &'static str, lag_bytes: u64);
+
     fn emit_path_globbing_failed(&self, path: &Path, error: &Error);
 
     fn emit_file_line_too_long(
@@ -1,7 +1,7 @@
 use std::{
     cmp,
     collections::{BTreeMap, HashMap},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
     time::{self, Duration},
 };
@@ -28,7 +28,7 @@ use tracing::{debug, error, info, trace};
 
 use crate::{
     file_watcher::{FileWatcher, RawLineResult},
-    paths_provider::PathsProvider,
+    paths_provider::{FilePriority, PathsProvider},
 };
 
 /// `FileServer` is a Source which cooperatively schedules reads over files,
@@ -49,6 +49,7 @@ where
     pub ignore_checkpoints: bool,
     pub read_from: ReadFrom,
     pub ignore_before: Option<DateTime<Utc>>,
+    pub seed_checkpoints_before: Option<DateTime<Utc>>,
     pub max_line_bytes: usize,
     pub line_delimiter: Bytes,
     pub data_dir: PathBuf,
@@ -140,7 +141,8 @@ where
         let checkpoints = checkpointer.view();
 
         for (_key, path, file_id) in existing_files {
-            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, true)
+            let priority = self.paths_provider.priority(&path);
+            self.watch_new_file(path, file_id, priority, &mut fp_map, &checkpoints, true)
                 .await;
         }
         self.emitter.emit_files_open(fp_map.len());
@@ -165,6 +167,9 @@ where
         // we do not re-scan for major file changes (new files, moves, deletes),
         // or write new checkpoints, on every iteration.
         let mut next_glob_time = time::Instant::now();
+        // Whether a file couldn't be read to the end in the previous pass, in which case the
+        // files are read according to their priority until we catch up.
+        let mut maxed_out_reading_single_file = false;
         loop {
             // Glob find files to follow, but not too often.
             let now_time = time::Instant::now();
@@ -191,7 +196,9 @@ where
                         .fingerprint_or_emit(&path, &mut known_small_files, &self.emitter)
                         .await
                     {
+                        let priority = self.paths_provider.priority(&path);
                         if let Some(watcher) = fp_map.get_mut(&file_id) {
+                            watcher.set_priority(priority);
                             // file fingerprint matches a watched file
                             let was_found_this_cycle = watcher.file_findable();
                             watcher.set_file_findable(true);
@@ -230,13 +237,30 @@ where
                             }
                         } else {
                             // untracked file fingerprint
-                            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, false)
-                                .await;
+                            self.watch_new_file(
+                                path,
+                                file_id,
+                                priority,
+                                &mut fp_map,
+                                &checkpoints,
+                                false,
+                            )
+                            .await;
                             self.emitter.emit_files_open(fp_map.len());
                         }
                     }
                 }
                 stats.record("discovery", start.elapsed());
+
+                // Report how far behind we are, per priority. This stats every watched file, so
+                // it's only done when the files have priorities.
+                if self.paths_provider.has_priorities() {
+                    let start = time::Instant::now();
+                    for (priority, lag_bytes) in files_lag(fp_map.values()).await {
+                        self.emitter.emit_files_lag(priority.as_str(), lag_bytes);
+                    }
+                    stats.record("lag", start.elapsed());
+                }
             }
 
             // Cleanup the known_small_files
@@ -281,13 +305,15 @@ where
 
             // Collect lines by polling files.
             let mut global_bytes_read: usize = 0;
-            let mut maxed_out_reading_single_file = false;
+            let behind = maxed_out_reading_single_file;
+            maxed_out_reading_single_file = false;
             for (&file_id, watcher) in &mut fp_map {
                 if !watcher.should_read() {
                     continue;
                 }
 
                 let start = time::Instant::now();
+                let max_read_bytes = read_budget(self.max_read_bytes, watcher.priority(), behind);
                 let mut bytes_read: usize = 0;
                 while let Ok(RawLineResult {
                     raw_line: Some(line),
@@ -320,7 +346,7 @@ where
                         end_offset: watcher.get_file_position(),
                     });
 
-                    if bytes_read > self.max_read_bytes {
+                    if bytes_read > max_read_bytes {
                         maxed_out_reading_single_file = true;
                         break;
                     }
@@ -434,6 +460,7 @@ where
         &self,
         path: PathBuf,
         file_id: FileFingerprint,
+        priority: FilePriority,
         fp_map: &mut IndexMap<FileFingerprint, FileWatcher>,
         checkpoints: &CheckpointsView,
         startup: bool,
@@ -442,7 +469,12 @@ where
         // once the file is actually opened and we determine it is compressed, older than we're
         // configured to read, etc.
         let fallback = if startup {
-            self.read_from
+            // Files without a checkpoint that were last modified before the seed time are
+            // considered as already read, for example by a previous Vector instance.
+            match self.seed_checkpoints_before {
+                Some(seed_before) if modified_before(&path, seed_before).await => ReadFrom::End,
+                _ => self.read_from,
+            }
         } else {
             // Always read new files that show up while we're running from the beginning. There's
             // not a good way to determine if they were moved or just created and written very
@@ -480,6 +512,7 @@ where
                     self.emitter.emit_file_added(&path);
                 }
                 watcher.set_file_findable(true);
+                watcher.set_priority(priority);
                 fp_map.insert(file_id, watcher);
             }
             Err(error) => self.emitter.emit_file_watch_error(&path, error),
@@ -512,6 +545,13 @@ async fn checkpoint_writer(
     checkpointer
 }
 
+async fn modified_before(path: &Path, time: DateTime<Utc>) -> bool {
+    fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| DateTime::<Utc>::from(modified) < time)
+}
+
 pub fn calculate_ignore_before(ignore_older_secs: Option<u64>) -> Option<DateTime<Utc>> {
     ignore_older_secs.map(|secs| Utc::now() - chrono::Duration::seconds(secs as i64))
 }
@@ -567,6 +607,34 @@ impl TimingStats {
     }
 }
 
+/// Returns the number of bytes a file can read in a single pass.
+///
+/// Priorities only apply while we are behind, so that files are otherwise read at the same pace.
+const fn read_budget(max_read_bytes: usize, priority: FilePriority, behind: bool) -> usize {
+    if behind {
+        priority.max_read_bytes(max_read_bytes)
+    } else {
+        max_read_bytes
+    }
+}
+
+/// Returns the number of bytes that have been written to the files but not read yet, per priority.
+async fn files_lag(
+    watchers: impl IntoIterator<Item = &FileWatcher>,
+) -> BTreeMap<FilePriority, u64> {
+    let mut lag = FilePriority::ALL
+        .into_iter()
+        .map(|priority| (priority, 0))
+        .collect::<BTreeMap<_, u64>>();
+    for watcher in watchers {
+        if let Ok(metadata) = fs::metadata(&watcher.path).await {
+            *lag.entry(watcher.priority()).or_default() +=
+                metadata.len().saturating_sub(watcher.get_file_position());
+        }
+    }
+    lag
+}
+
 fn scale(bytes: u64) -> String {
     let units = ["", "k", "m", "g"];
     let mut bytes = bytes as f32;
@@ -597,3 +665,64 @@ pub struct Line {
     pub start_offset: u64,
     pub end_offset: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn read_budget_applies_priority_only_when_behind() {
+        for priority in FilePriority::ALL {
+            assert_eq!(read_budget(1000, priority, false), 1000);
+        }
+        assert_eq!(read_budget(1000, FilePriority::Low, true), 500);
+        assert_eq!(read_budget(1000, FilePriority::Normal, true), 1000);
+        assert_eq!(read_budget(1000, FilePriority::High, true), 4000);
+        assert_eq!(read_budget(1, FilePriority::Low, true), 1);
+    }
+
+    async fn watcher(dir: &Path, name: &str, content: &str, priority: FilePriority) -> FileWatcher {
+        let path = dir.join(name);
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        let mut watcher =
+            FileWatcher::new(path, ReadFrom::Beginning, None, 100_000, Bytes::from("\n"))
+                .await
+                .unwrap();
+        watcher.set_priority(priority);
+        watcher
+    }
+
+    #[tokio::test]
+    async fn files_lag_is_reported_per_priority() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut high = watcher(
+            dir.path(),
+            "high.log",
+            "first\nsecond\n",
+            FilePriority::High,
+        )
+        .await;
+        let low_1 = watcher(dir.path(), "low_1.log", "first\n", FilePriority::Low).await;
+        let low_2 = watcher(dir.path(), "low_2.log", "second\n", FilePriority::Low).await;
+
+        let lag = files_lag([&high, &low_1, &low_2]).await;
+        assert_eq!(
+            lag,
+            BTreeMap::from([
+                (FilePriority::Low, 13),
+                (FilePriority::Normal, 0),
+                (FilePriority::High, 13),
+            ])
+        );
+
+        // Reading a line only leaves the rest of the file behind.
+        high.read_line().await.unwrap().raw_line.unwrap();
+        let lag = files_lag([&high]).await;
+        assert_eq!(lag[&FilePriority::High], 7);
+    }
+}
@@ -14,6 +14,7 @@ use tokio::{
 use tracing::debug;
 use vector_common::constants::GZIP_MAGIC;
 
+use crate::paths_provider::FilePriority;
 use file_source_common::{
     AsyncFileInfo, FilePosition, PortableFileExt, ReadFrom,
     buffer::{ReadResult, read_until_with_max_size},
@@ -61,6 +62,7 @@ pub struct FileWatcher {
     max_line_bytes: usize,
     line_delimiter: Bytes,
     buf: BytesMut,
+    priority: FilePriority,
 }
 
 impl FileWatcher {
@@ -170,6 +172,7 @@ impl FileWatcher {
             max_line_bytes,
             line_delimiter,
             buf: BytesMut::new(),
+            priority: FilePriority::default(),
         })
     }
 
@@ -219,6 +222,14 @@ impl FileWatcher {
         self.is_dead
     }
 
+    pub fn set_priority(&mut self, priority: FilePriority) {
+        self.priority = priority;
+    }
+
+    pub fn priority(&self) -> FilePriority {
+        self.priority
+    }
+
     pub fn get_file_position(&self) -> FilePosition {
         self.file_position
     }
@@ -1,6 +1,6 @@
 //! [`Glob`] based paths provider implementation.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use file_source_common::internal_events::FileSourceInternalEvents;
 pub use glob::MatchOptions;
@@ -29,6 +29,61 @@ pub trait PathsProvider {
 
     /// Provides a set of paths.
     fn paths(&self) -> Self::IntoIter;
+
+    /// Provides the read priority of a path previously returned by [`Self::paths`].
+    fn priority(&self, _path: &Path) -> FilePriority {
+        FilePriority::default()
+    }
+
+    /// Whether the paths have read priorities, in which case how far behind the reads of each
+    /// priority are is reported.
+    fn has_priorities(&self) -> bool {
+        false
+    }
+}
+
+/// The share of read bandwidth a file gets relative to other files.
+///
+/// The priority only applies while the files are being written faster than they are read, which
+/// is when a file couldn't be read to the end in a single pass. Otherwise, all files are read up
+/// to `max_read_bytes` per pass.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FilePriority {
+    /// Read at most half of `max_read_bytes` per pass while behind.
+    Low,
+
+    /// Read at most `max_read_bytes` per pass.
+    #[default]
+    Normal,
+
+    /// Read at most four times `max_read_bytes` per pass while behind.
+    High,
+}
+
+impl FilePriority {
+    /// All the priorities, from lowest to highest.
+    pub const ALL: [Self; 3] = [Self::Low, Self::Normal, Self::High];
+
+    /// Returns the number of bytes a file of this priority can read in a single pass.
+    pub const fn max_read_bytes(self, max_read_bytes: usize) -> usize {
+        match self {
+            Self::Low => {
+                let bytes = max_read_bytes / 2;
+                if bytes == 0 { 1 } else { bytes }
+            }
+            Self::Normal => max_read_bytes,
+            Self::High => max_read_bytes.saturating_mul(4),
+        }
+    }
+
+    /// Returns the name of the priority, as used in metric tags.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
 }
 
 /// A glob-based path provider.
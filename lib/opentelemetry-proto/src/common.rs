@@ -1,9 +1,12 @@
 use bytes::Bytes;
+use chrono::SecondsFormat;
 use ordered_float::NotNan;
 use vector_core::event::metric::TagValue;
 use vrl::value::{ObjectMap, Value};
 
-use super::proto::common::v1::{KeyValue, any_value::Value as PBValue};
+use super::proto::common::v1::{
+    AnyValue, ArrayValue, KeyValue, KeyValueList, any_value::Value as PBValue,
+};
 
 impl From<PBValue> for Value {
     fn from(av: PBValue) -> Self {
@@ -52,6 +55,46 @@ pub fn kv_list_into_value(arr: Vec<KeyValue>) -> Value {
     )
 }
 
+impl From<Value> for AnyValue {
+    fn from(value: Value) -> Self {
+        let value = match value {
+            Value::Bytes(b) => match String::from_utf8(b.to_vec()) {
+                Ok(s) => PBValue::StringValue(s),
+                Err(error) => PBValue::BytesValue(error.into_bytes()),
+            },
+            Value::Regex(regex) => PBValue::StringValue(regex.as_str().to_string()),
+            Value::Integer(i) => PBValue::IntValue(i),
+            Value::Float(f) => PBValue::DoubleValue(f.into_inner()),
+            Value::Boolean(b) => PBValue::BoolValue(b),
+            Value::Timestamp(ts) => {
+                PBValue::StringValue(ts.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            Value::Object(_) => PBValue::KvlistValue(KeyValueList {
+                values: value_into_kv_list(value),
+            }),
+            Value::Array(arr) => PBValue::ArrayValue(ArrayValue {
+                values: arr.into_iter().map(Into::into).collect(),
+            }),
+            Value::Null => return AnyValue { value: None },
+        };
+        AnyValue { value: Some(value) }
+    }
+}
+
+/// Converts an object into a list of key/value pairs. Any other value results in an empty list.
+pub fn value_into_kv_list(value: Value) -> Vec<KeyValue> {
+    match value {
+        Value::Object(obj) => obj
+            .into_iter()
+            .map(|(key, value)| KeyValue {
+                key: key.into(),
+                value: Some(value.into()),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 pub fn to_hex(d: &[u8]) -> String {
     if d.is_empty() {
         return "".to_string();
@@ -59,10 +102,49 @@ pub fn to_hex(d: &[u8]) -> String {
     hex::encode(d)
 }
 
+/// Decodes a hex encoded identifier, such as a trace or span ID. Invalid identifiers are
+/// treated as missing.
+pub fn from_hex(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_value_into_any_value_roundtrip() {
+        let value = Value::from(vrl::btreemap! {
+            "string" => "foo",
+            "int" => 42,
+            "bool" => true,
+            "array" => vec![Value::from(1), Value::from("two")],
+            "nested" => vrl::btreemap! { "key" => "value" },
+        });
+
+        let any_value = AnyValue::from(value.clone());
+        assert_eq!(Value::from(any_value.value.unwrap()), value);
+    }
+
+    #[test]
+    fn test_value_into_any_value_non_utf8_bytes() {
+        let any_value = AnyValue::from(Value::Bytes(Bytes::from_static(&[0xff, 0xfe])));
+        assert_eq!(any_value.value, Some(PBValue::BytesValue(vec![0xff, 0xfe])));
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(
+            from_hex("4ac52aadf321c2e531db005df08792f5"),
+            vec![
+                0x4a, 0xc5, 0x2a, 0xad, 0xf3, 0x21, 0xc2, 0xe5, 0x31, 0xdb, 0x00, 0x5d, 0xf0, 0x87,
+                0x92, 0xf5,
+            ]
+        );
+        assert!(from_hex("not hex").is_empty());
+        assert!(from_hex("").is_empty());
+    }
+
     #[test]
     fn test_pb_double_value_nan_handling() {
         // Test that NaN values are converted to Value::Null instead of panicking
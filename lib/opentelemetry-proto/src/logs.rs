@@ -4,12 +4,16 @@ use vector_core::{
     config::{LegacyKey, LogNamespace, log_schema},
     event::{Event, LogEvent},
 };
-use vrl::{core::Value, path};
+use vrl::{
+    core::Value,
+    metadata_path, path,
+    value::{KeyString, ObjectMap},
+};
 
-use super::common::{kv_list_into_value, to_hex};
+use super::common::{from_hex, kv_list_into_value, to_hex, value_into_kv_list};
 use crate::proto::{
-    common::v1::{InstrumentationScope, any_value::Value as PBValue},
-    logs::v1::{LogRecord, ResourceLogs, SeverityNumber},
+    common::v1::{InstrumentationScope, KeyValue, any_value::Value as PBValue},
+    logs::v1::{LogRecord, ResourceLogs, ScopeLogs, SeverityNumber},
     resource::v1::Resource,
 };
 
@@ -46,10 +50,52 @@ impl ResourceLogs {
     }
 }
 
-struct ResourceLog {
-    resource: Option<Resource>,
-    scope: Option<InstrumentationScope>,
-    log_record: LogRecord,
+impl ResourceLogs {
+    /// Groups log records by the resource and instrumentation scope they belong to, preserving
+    /// the order in which they first appear.
+    pub fn group(logs: impl IntoIterator<Item = ResourceLog>) -> Vec<Self> {
+        let mut resource_logs: Vec<Self> = Vec::new();
+
+        for log in logs {
+            let index = match resource_logs
+                .iter()
+                .position(|resource_log| resource_log.resource == log.resource)
+            {
+                Some(index) => index,
+                None => {
+                    resource_logs.push(Self {
+                        resource: log.resource,
+                        scope_logs: Vec::new(),
+                        schema_url: String::new(),
+                    });
+                    resource_logs.len() - 1
+                }
+            };
+
+            let scope_logs = &mut resource_logs[index].scope_logs;
+            match scope_logs
+                .iter_mut()
+                .find(|scope_log| scope_log.scope == log.scope)
+            {
+                Some(scope_log) => scope_log.log_records.push(log.log_record),
+                None => scope_logs.push(ScopeLogs {
+                    scope: log.scope,
+                    log_records: vec![log.log_record],
+                    schema_url: String::new(),
+                }),
+            }
+        }
+
+        resource_logs
+    }
+}
+
+/// A single log record, along with the resource and instrumentation scope it belongs to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceLog {
+    pub resource: Option<Resource>,
+    pub scope: Option<InstrumentationScope>,
+    pub log_record: LogRecord,
 }
 
 // https://github.com/open-telemetry/opentelemetry-specification/blob/v1.15.0/specification/logs/data-model.md
@@ -236,3 +282,317 @@ impl ResourceLog {
         log.into()
     }
 }
+
+// This is the inverse of the conversion above, so that log events received by the `opentelemetry`
+// source are sent unchanged by the `opentelemetry` sink. Other log events are mapped by sending
+// their message as the body, and their remaining fields as attributes.
+impl From<LogEvent> for ResourceLog {
+    fn from(mut log: LogEvent) -> Self {
+        let timestamp = log.remove_timestamp();
+
+        let (body, mut fields, extra_attributes) = match log.namespace() {
+            LogNamespace::Vector => {
+                let fields = log
+                    .remove(metadata_path!(SOURCE_NAME))
+                    .and_then(Value::into_object)
+                    .unwrap_or_default();
+                let (body, _metadata) = log.into_parts();
+                (Some(body), fields, ObjectMap::new())
+            }
+            LogNamespace::Legacy => {
+                let body = log_schema()
+                    .message_key_target_path()
+                    .and_then(|key| log.remove(key));
+                if let Some(key) = log_schema().source_type_key_target_path() {
+                    log.remove(key);
+                }
+
+                let (value, _metadata) = log.into_parts();
+                let mut extra_attributes = value.into_object().unwrap_or_default();
+                let fields = [
+                    RESOURCE_KEY,
+                    ATTRIBUTES_KEY,
+                    SCOPE_KEY,
+                    TRACE_ID_KEY,
+                    SPAN_ID_KEY,
+                    SEVERITY_TEXT_KEY,
+                    SEVERITY_NUMBER_KEY,
+                    OBSERVED_TIMESTAMP_KEY,
+                    DROPPED_ATTRIBUTES_COUNT_KEY,
+                    FLAGS_KEY,
+                ]
+                .into_iter()
+                .filter_map(|key| {
+                    extra_attributes
+                        .remove(key)
+                        .map(|value| (KeyString::from(key), value))
+                })
+                .collect();
+                (body, fields, extra_attributes)
+            }
+        };
+
+        let mut attributes = fields
+            .remove(ATTRIBUTES_KEY)
+            .map(value_into_kv_list)
+            .unwrap_or_default();
+        for (key, value) in extra_attributes {
+            if !attributes.iter().any(|attribute| attribute.key == key[..]) {
+                attributes.push(KeyValue {
+                    key: key.into(),
+                    value: Some(value.into()),
+                });
+            }
+        }
+
+        let severity_text = string_field(&mut fields, SEVERITY_TEXT_KEY);
+        let severity_number = fields
+            .remove(SEVERITY_NUMBER_KEY)
+            .and_then(|value| value.as_integer())
+            .and_then(|number| i32::try_from(number).ok())
+            .filter(|number| SeverityNumber::try_from(*number).is_ok())
+            .unwrap_or_else(|| severity_number_from_text(&severity_text) as i32);
+
+        let observed_time_unix_nano = fields
+            .remove(OBSERVED_TIMESTAMP_KEY)
+            .map_or(0, timestamp_into_unix_nano);
+        // Without a schema definition, the timestamp can't be found by meaning in the Vector
+        // namespace, so we fall back to where the `opentelemetry` source puts it.
+        let time_unix_nano = timestamp
+            .or_else(|| fields.remove("timestamp"))
+            .map_or(0, timestamp_into_unix_nano);
+
+        let resource = fields
+            .remove(RESOURCE_KEY)
+            .map(value_into_kv_list)
+            .filter(|attributes| !attributes.is_empty())
+            .map(|attributes| Resource {
+                attributes,
+                dropped_attributes_count: 0,
+            });
+        let scope = fields
+            .remove(SCOPE_KEY)
+            .and_then(Value::into_object)
+            .map(scope_from_object);
+
+        ResourceLog {
+            resource,
+            scope,
+            log_record: LogRecord {
+                time_unix_nano,
+                observed_time_unix_nano,
+                severity_number,
+                severity_text,
+                body: body.filter(|body| !body.is_null()).map(Into::into),
+                attributes,
+                dropped_attributes_count: integer_field(&mut fields, DROPPED_ATTRIBUTES_COUNT_KEY),
+                flags: integer_field(&mut fields, FLAGS_KEY),
+                trace_id: hex_field(&mut fields, TRACE_ID_KEY),
+                span_id: hex_field(&mut fields, SPAN_ID_KEY),
+            },
+        }
+    }
+}
+
+fn scope_from_object(mut scope: ObjectMap) -> InstrumentationScope {
+    InstrumentationScope {
+        name: string_field(&mut scope, NAME_KEY),
+        version: string_field(&mut scope, VERSION_KEY),
+        attributes: scope
+            .remove(ATTRIBUTES_KEY)
+            .map(value_into_kv_list)
+            .unwrap_or_default(),
+        dropped_attributes_count: integer_field(&mut scope, DROPPED_ATTRIBUTES_COUNT_KEY),
+    }
+}
+
+pub(crate) fn string_field(fields: &mut ObjectMap, key: &str) -> String {
+    fields
+        .remove(key)
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+pub(crate) fn integer_field<T: TryFrom<i64> + Default>(fields: &mut ObjectMap, key: &str) -> T {
+    fields
+        .remove(key)
+        .and_then(|value| value.as_integer())
+        .and_then(|value| T::try_from(value).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn hex_field(fields: &mut ObjectMap, key: &str) -> Vec<u8> {
+    fields
+        .remove(key)
+        .map(|value| from_hex(&value.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+pub(crate) fn timestamp_into_unix_nano(timestamp: Value) -> u64 {
+    timestamp
+        .as_timestamp()
+        .and_then(DateTime::timestamp_nanos_opt)
+        .and_then(|nanos| u64::try_from(nanos).ok())
+        .unwrap_or_default()
+}
+
+/// Infers the severity number from commonly used severity names, for log events which only have
+/// a severity text.
+fn severity_number_from_text(severity_text: &str) -> SeverityNumber {
+    match severity_text.to_ascii_lowercase().as_str() {
+        "trace" => SeverityNumber::Trace,
+        "debug" => SeverityNumber::Debug,
+        "info" | "information" | "notice" => SeverityNumber::Info,
+        "warn" | "warning" => SeverityNumber::Warn,
+        "error" | "err" => SeverityNumber::Error,
+        "fatal" | "critical" | "crit" | "alert" | "emerg" | "emergency" | "panic" => {
+            SeverityNumber::Fatal
+        }
+        _ => SeverityNumber::Unspecified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::common::v1::AnyValue;
+
+    fn string_value(s: &str) -> Option<AnyValue> {
+        Some(AnyValue {
+            value: Some(PBValue::StringValue(s.to_string())),
+        })
+    }
+
+    fn resource_logs() -> ResourceLogs {
+        ResourceLogs {
+            resource: Some(Resource {
+                attributes: vec![KeyValue {
+                    key: "service.name".to_string(),
+                    value: string_value("checkout"),
+                }],
+                dropped_attributes_count: 0,
+            }),
+            scope_logs: vec![ScopeLogs {
+                scope: Some(InstrumentationScope {
+                    name: "some.scope".to_string(),
+                    version: "1.0.0".to_string(),
+                    attributes: Vec::new(),
+                    dropped_attributes_count: 0,
+                }),
+                log_records: vec![LogRecord {
+                    time_unix_nano: 1_579_134_612_000_000_011,
+                    observed_time_unix_nano: 1_579_134_612_000_000_012,
+                    severity_number: SeverityNumber::Warn as i32,
+                    severity_text: "WARN".to_string(),
+                    body: string_value("payment declined"),
+                    attributes: vec![KeyValue {
+                        key: "order.id".to_string(),
+                        value: string_value("1234"),
+                    }],
+                    dropped_attributes_count: 0,
+                    flags: 1,
+                    trace_id: vec![1; 16],
+                    span_id: vec![2; 8],
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn log_event_roundtrip() {
+        for log_namespace in [LogNamespace::Legacy, LogNamespace::Vector] {
+            let logs = resource_logs()
+                .into_event_iter(log_namespace)
+                .map(|event| ResourceLog::from(event.into_log()));
+
+            assert_eq!(ResourceLogs::group(logs), vec![resource_logs()]);
+        }
+    }
+
+    #[test]
+    fn legacy_log_event_fields_into_attributes() {
+        let mut log = LogEvent::from_str_legacy("hello world");
+        log.insert("host", "localhost");
+        log.insert("level", "info");
+        log.insert(
+            log_schema().timestamp_key_target_path().unwrap(),
+            Utc.timestamp_nanos(1_579_134_612_000_000_011),
+        );
+
+        let resource_log = ResourceLog::from(log);
+        assert_eq!(resource_log.resource, None);
+        assert_eq!(resource_log.scope, None);
+
+        let record = resource_log.log_record;
+        assert_eq!(record.body, string_value("hello world"));
+        assert_eq!(record.time_unix_nano, 1_579_134_612_000_000_011);
+        assert_eq!(
+            record.attributes,
+            vec![
+                KeyValue {
+                    key: "host".to_string(),
+                    value: string_value("localhost"),
+                },
+                KeyValue {
+                    key: "level".to_string(),
+                    value: string_value("info"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn severity_number_inferred_from_text() {
+        let mut log = LogEvent::default();
+        log.insert(SEVERITY_TEXT_KEY, "Error");
+
+        let record = ResourceLog::from(log).log_record;
+        assert_eq!(record.severity_text, "Error");
+        assert_eq!(record.severity_number, SeverityNumber::Error as i32);
+        assert_eq!(record.body, None);
+    }
+
+    #[test]
+    fn group_by_resource_and_scope() {
+        let resource = |name: &str| {
+            Some(Resource {
+                attributes: vec![KeyValue {
+                    key: "service.name".to_string(),
+                    value: string_value(name),
+                }],
+                dropped_attributes_count: 0,
+            })
+        };
+        let scope = |name: &str| {
+            Some(InstrumentationScope {
+                name: name.to_string(),
+                ..Default::default()
+            })
+        };
+        let log = |resource, scope, body: &str| ResourceLog {
+            resource,
+            scope,
+            log_record: LogRecord {
+                body: string_value(body),
+                ..Default::default()
+            },
+        };
+
+        let grouped = ResourceLogs::group([
+            log(resource("a"), scope("x"), "1"),
+            log(resource("b"), scope("x"), "2"),
+            log(resource("a"), scope("y"), "3"),
+            log(resource("a"), scope("x"), "4"),
+        ]);
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].resource, resource("a"));
+        assert_eq!(grouped[0].scope_logs.len(), 2);
+        assert_eq!(grouped[0].scope_logs[0].log_records.len(), 2);
+        assert_eq!(grouped[0].scope_logs[1].log_records.len(), 1);
+        assert_eq!(grouped[1].resource, resource("b"));
+        assert_eq!(grouped[1].scope_logs[0].log_records.len(), 1);
+    }
+}
@@ -4,15 +4,16 @@ use chrono::{DateTime, TimeZone, Utc};
 use vector_core::event::{Event, TraceEvent};
 use vrl::{
     event_path,
-    value::{KeyString, Value},
+    value::{KeyString, ObjectMap, Value},
 };
 
 use super::{
-    common::{kv_list_into_value, to_hex},
+    common::{kv_list_into_value, to_hex, value_into_kv_list},
+    logs::{hex_field, integer_field, string_field, timestamp_into_unix_nano},
     proto::{
         resource::v1::Resource,
         trace::v1::{
-            ResourceSpans, Span, Status as SpanStatus,
+            ResourceSpans, ScopeSpans, Span, Status as SpanStatus,
             span::{Event as SpanEvent, Link},
         },
     },
@@ -42,9 +43,39 @@ impl ResourceSpans {
     }
 }
 
-struct ResourceSpan {
-    resource: Option<Resource>,
-    span: Span,
+impl ResourceSpans {
+    /// Groups spans by the resource they belong to, preserving the order in which they first
+    /// appear.
+    pub fn group(spans: impl IntoIterator<Item = ResourceSpan>) -> Vec<Self> {
+        let mut resource_spans: Vec<Self> = Vec::new();
+
+        for span in spans {
+            match resource_spans
+                .iter_mut()
+                .find(|resource_span| resource_span.resource == span.resource)
+            {
+                Some(resource_span) => resource_span.scope_spans[0].spans.push(span.span),
+                None => resource_spans.push(Self {
+                    resource: span.resource,
+                    scope_spans: vec![ScopeSpans {
+                        scope: None,
+                        spans: vec![span.span],
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                }),
+            }
+        }
+
+        resource_spans
+    }
+}
+
+/// A single span, along with the resource it belongs to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceSpan {
+    pub resource: Option<Resource>,
+    pub span: Span,
 }
 
 // Unlike log events(log body + metadata), trace spans are just metadata, so we don't handle log_namespace here,
@@ -157,3 +188,94 @@ impl From<SpanStatus> for Value {
         Value::Object(obj)
     }
 }
+
+// This is the inverse of the conversion above. The instrumentation scope isn't kept when receiving
+// spans, so spans are sent without one.
+impl From<TraceEvent> for ResourceSpan {
+    fn from(trace: TraceEvent) -> Self {
+        let (mut fields, _metadata) = trace.into_parts();
+
+        let resource = fields
+            .remove(RESOURCE_KEY)
+            .map(value_into_kv_list)
+            .filter(|attributes| !attributes.is_empty())
+            .map(|attributes| Resource {
+                attributes,
+                dropped_attributes_count: 0,
+            });
+
+        let span = Span {
+            trace_id: hex_field(&mut fields, TRACE_ID_KEY),
+            span_id: hex_field(&mut fields, SPAN_ID_KEY),
+            trace_state: string_field(&mut fields, "trace_state"),
+            parent_span_id: hex_field(&mut fields, "parent_span_id"),
+            name: string_field(&mut fields, "name"),
+            kind: integer_field(&mut fields, "kind"),
+            start_time_unix_nano: fields
+                .remove("start_time_unix_nano")
+                .map_or(0, timestamp_into_unix_nano),
+            end_time_unix_nano: fields
+                .remove("end_time_unix_nano")
+                .map_or(0, timestamp_into_unix_nano),
+            attributes: fields
+                .remove(ATTRIBUTES_KEY)
+                .map(value_into_kv_list)
+                .unwrap_or_default(),
+            dropped_attributes_count: integer_field(&mut fields, DROPPED_ATTRIBUTES_COUNT_KEY),
+            events: array_field(&mut fields, "events")
+                .map(|mut event| SpanEvent {
+                    time_unix_nano: event
+                        .remove("time_unix_nano")
+                        .map_or(0, timestamp_into_unix_nano),
+                    name: string_field(&mut event, "name"),
+                    attributes: event
+                        .remove(ATTRIBUTES_KEY)
+                        .map(value_into_kv_list)
+                        .unwrap_or_default(),
+                    dropped_attributes_count: integer_field(
+                        &mut event,
+                        DROPPED_ATTRIBUTES_COUNT_KEY,
+                    ),
+                })
+                .collect(),
+            dropped_events_count: integer_field(&mut fields, "dropped_events_count"),
+            links: array_field(&mut fields, "links")
+                .map(|mut link| Link {
+                    trace_id: hex_field(&mut link, TRACE_ID_KEY),
+                    span_id: hex_field(&mut link, SPAN_ID_KEY),
+                    trace_state: string_field(&mut link, "trace_state"),
+                    attributes: link
+                        .remove(ATTRIBUTES_KEY)
+                        .map(value_into_kv_list)
+                        .unwrap_or_default(),
+                    dropped_attributes_count: integer_field(
+                        &mut link,
+                        DROPPED_ATTRIBUTES_COUNT_KEY,
+                    ),
+                })
+                .collect(),
+            dropped_links_count: integer_field(&mut fields, "dropped_links_count"),
+            status: fields
+                .remove("status")
+                .and_then(Value::into_object)
+                .map(|mut status| SpanStatus {
+                    message: string_field(&mut status, "message"),
+                    code: integer_field(&mut status, "code"),
+                }),
+        };
+
+        ResourceSpan { resource, span }
+    }
+}
+
+fn array_field(fields: &mut ObjectMap, key: &str) -> impl Iterator<Item = ObjectMap> {
+    fields
+        .remove(key)
+        .and_then(|value| match value {
+            Value::Array(values) => Some(values),
+            _ => None,
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(Value::into_object)
+}
@@ -1,6 +1,9 @@
 #![deny(warnings)]
 
-use std::{collections::BTreeMap, convert::TryFrom};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+};
 
 use indexmap::IndexMap;
 use snafu::ResultExt;
@@ -34,6 +37,12 @@ pub mod proto {
     }
 }
 
+/// The types of the protobuf exposition format, the only one supporting the native histograms.
+#[allow(warnings)] // Ignore some clippy warnings
+pub mod client_proto {
+    include!(concat!(env!("OUT_DIR"), "/io.prometheus.client.rs"));
+}
+
 #[derive(Debug, snafu::Snafu, PartialEq)]
 pub enum ParserError {
     #[snafu(display("{}, line: `{}`", kind, line))]
@@ -244,6 +253,10 @@ impl GroupKind {
 pub struct MetricGroup {
     pub name: String,
     pub metrics: GroupKind,
+    /// The metadata of the group given by a remote_write request.
+    pub metadata: Option<proto::MetricMetadata>,
+    /// The exemplars of the metrics of the group given by a remote_write request.
+    pub exemplars: MetricMap<Vec<proto::Exemplar>>,
 }
 
 fn try_f64_to_u64(f: f64) -> Result<u64, ParserError> {
@@ -260,7 +273,12 @@ fn try_f64_to_u64(f: f64) -> Result<u64, ParserError> {
 impl MetricGroup {
     fn new(name: String, kind: MetricKind) -> Self {
         let metrics = GroupKind::new(kind);
-        MetricGroup { name, metrics }
+        MetricGroup {
+            name,
+            metrics,
+            metadata: None,
+            exemplars: IndexMap::default(),
+        }
     }
 
     // For cases where a metric group was not defined with `# TYPE ...`.
@@ -275,6 +293,8 @@ impl MetricGroup {
         MetricGroup {
             name,
             metrics: GroupKind::new_untyped(key, value),
+            metadata: None,
+            exemplars: IndexMap::default(),
         }
     }
 
@@ -324,34 +344,41 @@ pub fn parse_text(input: &str) -> Result<Vec<MetricGroup>, ParserError> {
 }
 
 #[derive(Default)]
-struct MetricGroupSet(IndexMap<String, GroupKind>);
+struct MetricGroupSet {
+    groups: IndexMap<String, GroupKind>,
+    metadata: HashMap<String, proto::MetricMetadata>,
+    exemplars: HashMap<String, MetricMap<Vec<proto::Exemplar>>>,
+}
 
 impl MetricGroupSet {
     fn get_group<'a>(&'a mut self, name: &str) -> (usize, &'a String, &'a mut GroupKind) {
         let len = name.len();
-        let name = if self.0.contains_key(name) {
+        let name = if self.groups.contains_key(name) {
             name
-        } else if name.ends_with("_bucket") && self.0.contains_key(&name[..len - 7]) {
+        } else if name.ends_with("_bucket") && self.groups.contains_key(&name[..len - 7]) {
             &name[..len - 7]
-        } else if name.ends_with("_sum") && self.0.contains_key(&name[..len - 4]) {
+        } else if name.ends_with("_sum") && self.groups.contains_key(&name[..len - 4]) {
             &name[..len - 4]
-        } else if name.ends_with("_count") && self.0.contains_key(&name[..len - 6]) {
+        } else if name.ends_with("_count") && self.groups.contains_key(&name[..len - 6]) {
             &name[..len - 6]
         } else {
-            self.0
+            self.groups
                 .insert(name.into(), GroupKind::new(MetricKind::Untyped));
             name
         };
-        self.0.get_full_mut(name).unwrap()
+        self.groups.get_full_mut(name).unwrap()
     }
 
     fn insert_metadata(
         &mut self,
-        name: String,
-        kind: MetricKind,
+        metadata: proto::MetricMetadata,
         metadata_conflict_strategy: MetadataConflictStrategy,
     ) -> Result<(), ParserError> {
-        match self.0.get(&name) {
+        let name = metadata.metric_family_name.clone();
+        let kind = proto::MetricType::try_from(metadata.r#type)
+            .unwrap_or(proto::MetricType::Unknown)
+            .into();
+        match self.groups.get(&name) {
             Some(group) if !group.matches_kind(kind) => {
                 if matches!(metadata_conflict_strategy, MetadataConflictStrategy::Reject) {
                     Err(ParserError::MultipleMetricKinds { name })
@@ -361,7 +388,8 @@ impl MetricGroupSet {
             }
             Some(_) => Ok(()), // metadata already exists and is the right type
             None => {
-                self.0.insert(name, GroupKind::new(kind));
+                self.groups.insert(name.clone(), GroupKind::new(kind));
+                self.metadata.insert(name, metadata);
                 Ok(())
             }
         }
@@ -388,15 +416,49 @@ impl MetricGroupSet {
                 labels: metric.labels,
             };
             let group = GroupKind::new_untyped(key, metric.value);
-            self.0.insert(metric.name, group);
+            self.groups.insert(metric.name, group);
         }
         Ok(())
     }
 
-    fn finish(self) -> Vec<MetricGroup> {
-        self.0
+    /// Attaches the exemplars of a series to the metric of its sample at the given timestamp.
+    fn insert_exemplars(
+        &mut self,
+        name: &str,
+        labels: &BTreeMap<String, String>,
+        timestamp: i64,
+        exemplars: Vec<proto::Exemplar>,
+    ) {
+        let (_, basename, group) = self.get_group(name);
+        // The buckets and quantiles are merged into the metric, as for the samples.
+        let mut labels = labels.clone();
+        match group {
+            GroupKind::Histogram(_) => labels.remove("le"),
+            GroupKind::Summary(_) => labels.remove("quantile"),
+            _ => None,
+        };
+        let basename = basename.clone();
+        let key = GroupKey {
+            timestamp: Some(timestamp),
+            labels,
+        };
+        self.exemplars
+            .entry(basename)
+            .or_default()
+            .entry(key)
+            .or_default()
+            .extend(exemplars);
+    }
+
+    fn finish(mut self) -> Vec<MetricGroup> {
+        self.groups
             .into_iter()
-            .map(|(name, metrics)| MetricGroup { name, metrics })
+            .map(|(name, metrics)| MetricGroup {
+                metadata: self.metadata.remove(&name),
+                exemplars: self.exemplars.remove(&name).unwrap_or_default(),
+                name,
+                metrics,
+            })
             .collect()
     }
 }
@@ -410,11 +472,7 @@ pub fn parse_request(
     let mut groups = MetricGroupSet::default();
 
     for metadata in request.metadata {
-        let name = metadata.metric_family_name;
-        let kind = proto::MetricType::try_from(metadata.r#type)
-            .unwrap_or(proto::MetricType::Unknown)
-            .into();
-        groups.insert_metadata(name, kind, metadata_conflict_strategy)?;
+        groups.insert_metadata(metadata, metadata_conflict_strategy)?;
     }
 
     for timeseries in request.timeseries {
@@ -428,9 +486,20 @@ pub fn parse_request(
             None => return Err(ParserError::RequestNoNameLabel),
         };
 
+        // The exemplars go along with the latest sample of the series.
+        let latest = timeseries
+            .samples
+            .iter()
+            .map(|sample| sample.timestamp)
+            .max();
         for sample in timeseries.samples {
             groups.insert_sample(&name, &labels, sample)?;
         }
+        if let Some(timestamp) = latest
+            && !timeseries.exemplars.is_empty()
+        {
+            groups.insert_exemplars(&name, &labels, timestamp, timeseries.exemplars);
+        }
     }
 
     Ok(groups.finish())
@@ -756,6 +825,7 @@ mod test {
                     samples: vec![
                         $( proto::Sample { value: $sample as f64, timestamp: $timestamp as i64 }, )*
                     ],
+                    ..Default::default()
                 }, )* ],
             }
         };
@@ -921,6 +991,52 @@ mod test {
         });
     }
 
+    #[test]
+    fn parse_request_metadata_and_exemplars() {
+        let exemplar = proto::Exemplar {
+            labels: vec![proto::Label {
+                name: "trace_id".into(),
+                value: "abc".into(),
+            }],
+            value: 0.7,
+            timestamp: 1395066367600,
+        };
+        let mut request = write_request!(
+            ["one" = Histogram],
+            [
+                [__name__ => "one_bucket", le => "1"] => [ 15 @ 1395066367700 ],
+                [__name__ => "one_bucket", le => "+Inf"] => [ 19 @ 1395066367700 ],
+                [__name__ => "two"] => [ 3 @ 1395066367600, 4 @ 1395066367700 ]
+            ]
+        );
+        request.metadata[0].help = "The durations.".into();
+        request.metadata[0].unit = "seconds".into();
+        request.timeseries[0].exemplars.push(exemplar.clone());
+        request.timeseries[2].exemplars.push(exemplar.clone());
+
+        let parsed = parse_request(request, MetadataConflictStrategy::Ignore).unwrap();
+        assert_eq!(parsed.len(), 2);
+        let metadata = parsed[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata.help, "The durations.");
+        assert_eq!(metadata.unit, "seconds");
+        assert!(parsed[1].metadata.is_none());
+
+        // The exemplars go along with the metric, without the bucket label, and the latest sample.
+        let key = |timestamp| GroupKey {
+            timestamp: Some(timestamp),
+            labels: labels!(),
+        };
+        assert_eq!(
+            parsed[0].exemplars.get(&key(1395066367700)),
+            Some(&vec![exemplar.clone()])
+        );
+        assert_eq!(parsed[1].exemplars.len(), 1);
+        assert_eq!(
+            parsed[1].exemplars.get(&key(1395066367700)),
+            Some(&vec![exemplar])
+        );
+    }
+
     #[test]
     fn parse_request_conflicting_metadata() {
         let request = proto::WriteRequest {
@@ -947,6 +1063,7 @@ mod test {
                     value: 12345.0,
                     timestamp: 1395066367500,
                 }],
+                ..Default::default()
             }],
         };
 
@@ -94,8 +94,19 @@
 //!
 //! This ensures logs from different components are rate limited independently,
 //! while avoiding resource/cost implications from high-cardinality tags.
-
-use std::fmt;
+//!
+//! # Adjusting the windows at runtime
+//!
+//! The windows can be overridden at runtime, globally or for specific components, through the
+//! [`RateLimitHandle`] shared by the layers. The window of an event is, in order of precedence:
+//! its `internal_log_rate_secs` field, the window of its component, the global window of the
+//! handle, and finally the default window of the layer.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
 
 use dashmap::DashMap;
 use tracing_core::{
@@ -125,6 +136,43 @@ const MESSAGE_FIELD: &str = "message";
 // for these keys
 const COMPONENT_ID_FIELD: &str = "component_id";
 
+/// The rate limit windows overriding the default one of the layers, in seconds. A window of zero
+/// disables the rate limiting.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitOverrides {
+    /// The window of all of the events.
+    pub window_secs: Option<u64>,
+    /// The windows of the events of specific components, by component ID.
+    pub components: HashMap<String, u64>,
+}
+
+/// A handle to the rate limit windows of the layers created with it, which can be changed while
+/// they're running.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimitHandle(Arc<RwLock<RateLimitOverrides>>);
+
+impl RateLimitHandle {
+    /// Returns the windows currently in effect.
+    pub fn get(&self) -> RateLimitOverrides {
+        self.0.read().expect("rate limits poisoned").clone()
+    }
+
+    /// Replaces the windows. The events already being suppressed are suppressed until the end of
+    /// their new window.
+    pub fn set(&self, overrides: RateLimitOverrides) {
+        *self.0.write().expect("rate limits poisoned") = overrides;
+    }
+
+    fn window_secs(&self, keys: &RateLimitedSpanKeys) -> Option<u64> {
+        let overrides = self.0.read().expect("rate limits poisoned");
+        let component = match &keys.component_id {
+            Some(TraceValue::String(component_id)) => overrides.components.get(component_id),
+            _ => None,
+        };
+        component.copied().or(overrides.window_secs)
+    }
+}
+
 #[derive(Eq, PartialEq, Hash, Clone)]
 struct RateKeyIdentifier {
     callsite: Identifier,
@@ -139,6 +187,7 @@ where
     events: DashMap<RateKeyIdentifier, State>,
     inner: L,
     internal_log_rate_limit: u64,
+    overrides: RateLimitHandle,
     _subscriber: std::marker::PhantomData<S>,
 }
 
@@ -151,6 +200,7 @@ where
         RateLimitedLayer {
             events: Default::default(),
             internal_log_rate_limit: 10,
+            overrides: RateLimitHandle::default(),
             inner: layer,
             _subscriber: std::marker::PhantomData,
         }
@@ -167,6 +217,12 @@ where
         self.internal_log_rate_limit = internal_log_rate_limit;
         self
     }
+
+    /// Sets the handle through which the windows are overridden at runtime.
+    pub fn with_overrides(mut self, overrides: RateLimitHandle) -> Self {
+        self.overrides = overrides;
+        self
+    }
 }
 
 impl<S, L> Layer<S> for RateLimitedLayer<S, L>
@@ -235,11 +291,6 @@ where
             return self.inner.on_event(event, ctx);
         }
 
-        let limit = match limit_visitor.limit_secs {
-            Some(limit_secs) => limit_secs, // override the cli limit
-            None => self.internal_log_rate_limit,
-        };
-
         // Build a composite key from event fields and span context to determine the rate limit group.
         // This multi-step process ensures we capture all relevant contextual information:
         //
@@ -271,6 +322,14 @@ where
                 })
         };
 
+        let limit = match limit_visitor.limit_secs {
+            Some(limit_secs) => limit_secs, // override the cli limit
+            None => self
+                .overrides
+                .window_secs(&rate_limit_key_values)
+                .unwrap_or(self.internal_log_rate_limit),
+        };
+
         // Build the key to represent this event, given its span fields, and see if we're already rate limiting it. If
         // not, we'll initialize an entry for it.
         let metadata = event.metadata();
@@ -289,6 +348,8 @@ where
 
             State::new(message, limit)
         });
+        // The window may have been changed since the event was last seen.
+        state.limit = limit;
 
         // Update our suppressed state for this event, and see if we should still be suppressing it.
         //
@@ -796,6 +857,57 @@ mod test {
         );
     }
 
+    #[test]
+    #[serial]
+    fn override_rate_limit_at_runtime() {
+        let overrides = RateLimitHandle::default();
+        let events: Arc<Mutex<Vec<RecordedEvent>>> = Default::default();
+        let recorder = RecordingLayer::new(Arc::clone(&events));
+        let sub = tracing_subscriber::registry::Registry::default().with(
+            RateLimitedLayer::new(recorder)
+                .with_default_limit(100)
+                .with_overrides(overrides.clone()),
+        );
+        let log = || {
+            for key in &["foo", "bar"] {
+                info!(
+                    message = format!("Hello {key}!").as_str(),
+                    component_id = &key
+                );
+            }
+        };
+        tracing::subscriber::with_default(sub, || {
+            log();
+            log();
+            overrides.set(RateLimitOverrides {
+                window_secs: Some(1),
+                components: HashMap::from([("bar".to_owned(), 0)]),
+            });
+            MockClock::advance(Duration::from_secs(1));
+            log();
+            log();
+        });
+
+        let events = events.lock().unwrap();
+
+        // The global window of `foo` is shortened, while the rate limiting of `bar` is disabled.
+        assert_eq!(
+            *events,
+            vec![
+                event!("Hello foo!", component_id: "foo"),
+                event!("Hello bar!", component_id: "bar"),
+                event!("Internal log [Hello foo!] is being suppressed to avoid flooding."),
+                event!("Internal log [Hello bar!] is being suppressed to avoid flooding."),
+                event!("Internal log [Hello foo!] has been suppressed 1 times."),
+                event!("Hello foo!", component_id: "foo"),
+                event!("Internal log [Hello bar!] has been suppressed 1 times."),
+                event!("Hello bar!", component_id: "bar"),
+                event!("Internal log [Hello foo!] is being suppressed to avoid flooding."),
+                event!("Hello bar!", component_id: "bar"),
+            ]
+        );
+    }
+
     #[test]
     #[serial]
     fn rate_limit_by_event_key() {
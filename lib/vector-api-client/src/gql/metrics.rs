@@ -2,7 +2,10 @@
 
 use graphql_client::GraphQLQuery;
 
-use crate::BoxedSubscription;
+use crate::{BoxedSubscription, QueryResult};
+
+/// Shorthand for a Chrono datetime, set to UTC.
+type DateTime = chrono::DateTime<chrono::Utc>;
 
 /// UptimeSubscription returns uptime metrics to determine how long the Vector
 /// instance has been running.
@@ -147,6 +150,63 @@ impl component_sent_events_throughputs_subscription::ComponentSentEventsThroughp
 )]
 pub struct ComponentErrorsTotalsSubscription;
 
+/// ComponentErrorsByTypeSubscription contains metrics on the number of errors by error type, and
+/// on the number of discarded events, against specific components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_by_type.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsByTypeSubscription;
+
+/// ComponentBufferUtilizationsSubscription contains metrics on the utilization of the buffers of
+/// specific components, and whether they're the bottleneck of the backpressure.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_buffer_utilizations.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentBufferUtilizationsSubscription;
+
+/// ComponentMetricsHistoryQuery returns the samples of the throughput and errors of the
+/// components kept in the history of the metrics, over a window of time.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/queries/component_metrics_history.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentMetricsHistoryQuery;
+
+/// Extension methods for metrics queries
+pub trait MetricsQueryExt {
+    /// Executes a query of the history of the metrics of a component, or of all of them, over
+    /// the last `window_secs` seconds, or the whole retention of the history.
+    async fn component_metrics_history_query(
+        &self,
+        component_id: Option<String>,
+        window_secs: Option<i64>,
+    ) -> QueryResult<ComponentMetricsHistoryQuery>;
+}
+
+impl MetricsQueryExt for crate::Client {
+    async fn component_metrics_history_query(
+        &self,
+        component_id: Option<String>,
+        window_secs: Option<i64>,
+    ) -> QueryResult<ComponentMetricsHistoryQuery> {
+        let request_body =
+            ComponentMetricsHistoryQuery::build_query(component_metrics_history_query::Variables {
+                component_id,
+                window_secs,
+            });
+        self.query::<ComponentMetricsHistoryQuery>(&request_body)
+            .await
+    }
+}
+
 /// Extension methods for metrics subscriptions
 pub trait MetricsSubscriptionExt {
     /// Executes an uptime metrics subscription.
@@ -210,6 +270,18 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
+
+    /// Executes a component errors by type subscription.
+    fn component_errors_by_type_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsByTypeSubscription>;
+
+    /// Executes a component buffer utilizations subscription.
+    fn component_buffer_utilizations_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentBufferUtilizationsSubscription>;
 }
 
 impl MetricsSubscriptionExt for crate::SubscriptionClient {
@@ -338,4 +410,28 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<ComponentErrorsTotalsSubscription>(&request_body)
     }
+
+    /// Executes a component errors by type subscription.
+    fn component_errors_by_type_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentErrorsByTypeSubscription> {
+        let request_body = ComponentErrorsByTypeSubscription::build_query(
+            component_errors_by_type_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsByTypeSubscription>(&request_body)
+    }
+
+    /// Executes a component buffer utilizations subscription.
+    fn component_buffer_utilizations_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentBufferUtilizationsSubscription> {
+        let request_body = ComponentBufferUtilizationsSubscription::build_query(
+            component_buffer_utilizations_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentBufferUtilizationsSubscription>(&request_body)
+    }
 }
@@ -81,6 +81,7 @@ fn create_disk_v2_variant(_max_events: usize, max_size: u64) -> BufferType {
     BufferType::DiskV2 {
         max_size: NonZeroU64::new(max_size).unwrap(),
         when_full: WhenFull::DropNewest,
+        encryption: None,
     }
 }
 
@@ -94,6 +95,7 @@ fn create_in_memory_variant(bound_by: BoundBy, max_events: usize, max_size: u64)
     BufferType::Memory {
         size,
         when_full: WhenFull::DropNewest,
+        encryption: None,
     }
 }
 
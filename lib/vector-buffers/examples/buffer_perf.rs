@@ -275,6 +275,7 @@ where
             BufferType::DiskV2 {
                 max_size: max_size_bytes,
                 when_full,
+                encryption: None,
             }
         }
         s => panic!(
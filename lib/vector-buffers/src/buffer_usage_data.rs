@@ -11,7 +11,10 @@ use tracing::{Instrument, Span};
 use vector_common::internal_event::emit;
 
 use crate::{
-    internal_events::{BufferCreated, BufferEventsDropped, BufferEventsReceived, BufferEventsSent},
+    internal_events::{
+        BufferActiveStage, BufferCompacted, BufferCreated, BufferEventsDropped,
+        BufferEventsReceived, BufferEventsSent, BufferUtilization,
+    },
     spawn_named,
 };
 
@@ -60,6 +63,23 @@ impl CategorySnapshot {
     }
 }
 
+/// Gets the ratio of the capacity of a buffer stage that is in use.
+///
+/// The ratio is based on the byte size limit of the stage if it has one, and on its event limit
+/// otherwise.
+#[expect(clippy::cast_precision_loss)]
+fn utilization(current: &CategorySnapshot, max_size: &CategorySnapshot) -> Option<f64> {
+    let (current, max) = if max_size.event_byte_size > 0 {
+        (current.event_byte_size, max_size.event_byte_size)
+    } else if max_size.event_count > 0 {
+        (current.event_count, max_size.event_count)
+    } else {
+        return None;
+    };
+
+    Some((current as f64 / max as f64).min(1.0))
+}
+
 /// Per-category metrics.
 ///
 /// This tracks the number of events, and their size in the buffer, that a given category has interacted with. A
@@ -185,6 +205,13 @@ impl BufferUsageHandle {
             self.state.current.decrement(count, byte_size);
         }
     }
+
+    /// Increments the number of compactions performed by this buffer component, and the number of
+    /// bytes on disk they reclaimed.
+    pub fn increment_compaction_count_and_reclaimed_bytes(&self, reclaimed_bytes: u64) {
+        increment_counter(&self.state.compactions, 1);
+        increment_counter(&self.state.reclaimed_bytes, reclaimed_bytes);
+    }
 }
 
 #[derive(Debug, Default)]
@@ -196,6 +223,8 @@ struct BufferUsageData {
     dropped_intentional: CategoryMetrics,
     max_size: CategoryMetrics,
     current: CategoryMetrics,
+    compactions: AtomicU64,
+    reclaimed_bytes: AtomicU64,
 }
 
 impl BufferUsageData {
@@ -283,7 +312,9 @@ impl BufferUsage {
 
     /// Installs a reporter for the configured stages which periodically reports buffer usage metrics.
     ///
-    /// Metrics are reported every 2 seconds.
+    /// Metrics are reported every 2 seconds. When more than one stage is configured, the active
+    /// stage of the buffer is reported as well: this is the last stage holding events, as events
+    /// only reach a stage once all of the stages before it have overflowed.
     ///
     /// The `buffer_id` should be a unique name -- ideally the `component_id` of the sink using this buffer -- but is
     /// not used for anything other than reporting, and so has no _requirement_ to be unique.
@@ -295,6 +326,7 @@ impl BufferUsage {
 
         let task = async move {
             let mut interval = interval(Duration::from_secs(2));
+            let mut active_stage = 0;
             loop {
                 interval.tick().await;
 
@@ -311,6 +343,14 @@ impl BufferUsage {
                     });
 
                     let current = stage.current.get();
+                    if let Some(utilization) = utilization(&current, &max_size) {
+                        emit(BufferUtilization {
+                            buffer_id: buffer_id.clone(),
+                            idx: stage.idx,
+                            utilization,
+                        });
+                    }
+
                     let received = stage.received.consume();
                     if received.has_updates() {
                         emit(BufferEventsReceived {
@@ -362,6 +402,27 @@ impl BufferUsage {
                             total_byte_size: current.event_byte_size,
                         });
                     }
+
+                    let compactions = stage.compactions.swap(0, ORDERING);
+                    let reclaimed_bytes = stage.reclaimed_bytes.swap(0, ORDERING);
+                    if compactions > 0 {
+                        emit(BufferCompacted {
+                            buffer_id: buffer_id.clone(),
+                            idx: stage.idx,
+                            count: compactions,
+                            reclaimed_bytes,
+                        });
+                    }
+                }
+
+                if stages.len() > 1 {
+                    let previous_idx = active_stage;
+                    active_stage = active_stage_idx(&stages);
+                    emit(BufferActiveStage {
+                        buffer_id: buffer_id.clone(),
+                        idx: active_stage,
+                        previous_idx,
+                    });
                 }
             }
         };
@@ -370,6 +431,16 @@ impl BufferUsage {
     }
 }
 
+/// Gets the index of the last stage holding events, or of the first stage if none of them do.
+fn active_stage_idx(stages: &[Arc<BufferUsageData>]) -> usize {
+    stages
+        .iter()
+        .filter(|stage| stage.current.get().event_count > 0)
+        .map(|stage| stage.idx)
+        .max()
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use std::thread;
@@ -403,6 +474,30 @@ mod tests {
         assert_eq!(counter.load(ORDERING), 0);
     }
 
+    #[test]
+    fn test_active_stage_and_utilization() {
+        let mut usage = BufferUsage::from_span(Span::none());
+        // Stages are added in reverse order when building a buffer topology.
+        let overflow = usage.add_stage(1);
+        let base = usage.add_stage(0);
+        overflow.set_buffer_limits(Some(1000), None);
+        base.set_buffer_limits(None, Some(10));
+        assert_eq!(active_stage_idx(&usage.stages), 0);
+
+        base.increment_received_event_count_and_byte_size(10, 100);
+        overflow.increment_received_event_count_and_byte_size(2, 250);
+        assert_eq!(active_stage_idx(&usage.stages), 1);
+
+        let stage_utilization = |stage: &BufferUsageData| {
+            utilization(&stage.current.get(), &stage.max_size.get()).unwrap()
+        };
+        assert!((stage_utilization(&usage.stages[0]) - 0.25).abs() < f64::EPSILON);
+        assert!((stage_utilization(&usage.stages[1]) - 1.0).abs() < f64::EPSILON);
+
+        overflow.increment_sent_event_count_and_byte_size(2, 250);
+        assert_eq!(active_stage_idx(&usage.stages), 0);
+    }
+
     #[test]
     fn test_decrement_counter_prevents_negatives() {
         let counter = AtomicU64::new(100);
@@ -8,7 +8,9 @@ use std::{
 use serde::{Deserialize, Deserializer, Serialize, de};
 use snafu::{ResultExt, Snafu};
 use tracing::Span;
-use vector_common::{config::ComponentKey, finalization::Finalizable};
+use vector_common::{
+    config::ComponentKey, finalization::Finalizable, sensitive_string::SensitiveString,
+};
 use vector_config::configurable_component;
 
 use crate::{
@@ -28,6 +30,8 @@ pub enum BufferBuildError {
     FailedToBuildTopology { source: TopologyError },
     #[snafu(display("`max_events` must be greater than zero"))]
     InvalidMaxEvents,
+    #[snafu(display("only one disk buffer stage can be configured"))]
+    MultipleDiskStages,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -38,7 +42,7 @@ enum BufferTypeKind {
     DiskV2,
 }
 
-const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
+const ALL_FIELDS: [&str; 5] = ["type", "max_events", "max_size", "when_full", "encryption"];
 
 struct BufferTypeVisitor;
 
@@ -51,6 +55,7 @@ impl BufferTypeVisitor {
         let mut max_events: Option<NonZeroUsize> = None;
         let mut max_size: Option<NonZeroU64> = None;
         let mut when_full: Option<WhenFull> = None;
+        let mut encryption: Option<DiskBufferEncryption> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
                 "type" => {
@@ -77,6 +82,12 @@ impl BufferTypeVisitor {
                     }
                     when_full = Some(map.next_value()?);
                 }
+                "encryption" => {
+                    if encryption.is_some() {
+                        return Err(de::Error::duplicate_field("encryption"));
+                    }
+                    encryption = Some(map.next_value()?);
+                }
                 other => {
                     return Err(de::Error::unknown_field(other, &ALL_FIELDS));
                 }
@@ -86,6 +97,12 @@ impl BufferTypeVisitor {
         let when_full = when_full.unwrap_or_default();
         match kind {
             BufferTypeKind::Memory => {
+                if encryption.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "encryption",
+                        &["type", "max_events", "max_size", "when_full"],
+                    ));
+                }
                 let size = match (max_events, max_size) {
                     (Some(_), Some(_)) => {
                         return Err(de::Error::unknown_field(
@@ -117,12 +134,13 @@ impl BufferTypeVisitor {
                 if max_events.is_some() {
                     return Err(de::Error::unknown_field(
                         "max_events",
-                        &["type", "max_size", "when_full"],
+                        &["type", "max_size", "when_full", "encryption"],
                     ));
                 }
                 Ok(BufferType::DiskV2 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
+                    encryption,
                 })
             }
         }
@@ -163,6 +181,7 @@ pub struct DiskUsage {
     id: ComponentKey,
     data_dir: PathBuf,
     max_size: NonZeroU64,
+    encryption: Option<DiskBufferEncryption>,
 }
 
 impl DiskUsage {
@@ -172,9 +191,17 @@ impl DiskUsage {
             id,
             data_dir,
             max_size,
+            encryption: None,
         }
     }
 
+    /// Sets the encryption configuration of the buffer.
+    #[must_use]
+    pub fn with_encryption(mut self, encryption: Option<DiskBufferEncryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
     /// Gets the component key for the component this buffer is attached to.
     pub fn id(&self) -> &ComponentKey {
         &self.id
@@ -189,6 +216,35 @@ impl DiskUsage {
     pub fn data_dir(&self) -> &Path {
         self.data_dir.as_path()
     }
+
+    /// Gets the encryption configuration of the buffer, if its records are encrypted.
+    pub fn encryption(&self) -> Option<&DiskBufferEncryption> {
+        self.encryption.as_ref()
+    }
+}
+
+/// Encryption of the buffered events at rest.
+///
+/// The events are encrypted with AES-256-GCM before they're written to disk.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DiskBufferEncryption {
+    /// The key used to encrypt the buffered events.
+    ///
+    /// Must be a base64-encoded, 32-byte key. The key can be sourced from a secrets backend with
+    /// the `SECRET[<backend>.<secret_key>]` syntax.
+    #[configurable(metadata(docs::examples = "SECRET[vault.disk_buffer_key]"))]
+    pub key: SensitiveString,
+
+    /// The keys previously used to encrypt the buffered events.
+    ///
+    /// When the key is rotated, the events already buffered remain readable as long as the key they
+    /// were encrypted with is listed here, while the new events are encrypted with the current key.
+    /// A previous key can be removed once the buffer no longer holds events encrypted with it.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "SECRET[vault.previous_disk_buffer_key]"))]
+    pub previous_keys: Vec<SensitiveString>,
 }
 
 /// Enumeration to define exactly what terms the bounds of the buffer is expressed in: length, or
@@ -210,7 +266,7 @@ pub enum MemoryBufferSize {
 
 /// A specific type of buffer stage.
 #[configurable_component(no_deser)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 #[configurable(metadata(docs::enum_tag_description = "The type of buffer to use."))]
 pub enum BufferType {
@@ -250,6 +306,10 @@ pub enum BufferType {
         #[configurable(derived)]
         #[serde(default)]
         when_full: WhenFull,
+
+        #[configurable(derived)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<DiskBufferEncryption>,
     },
 }
 
@@ -281,13 +341,20 @@ impl BufferType {
             None => None,
             Some(global_data_dir) => match self {
                 Self::Memory { .. } => None,
-                Self::DiskV2 { max_size, .. } => {
+                Self::DiskV2 {
+                    max_size,
+                    encryption,
+                    ..
+                } => {
                     let data_dir = crate::variants::disk_v2::get_disk_v2_data_dir_path(
                         &global_data_dir,
                         id.id(),
                     );
 
-                    Some(DiskUsage::new(id.clone(), data_dir, *max_size))
+                    Some(
+                        DiskUsage::new(id.clone(), data_dir, *max_size)
+                            .with_encryption(encryption.clone()),
+                    )
                 }
             },
         }
@@ -308,16 +375,20 @@ impl BufferType {
     where
         T: Bufferable + Clone + Finalizable,
     {
-        match *self {
+        match self {
             BufferType::Memory { size, when_full } => {
-                builder.stage(MemoryBuffer::new(size), when_full);
+                builder.stage(MemoryBuffer::new(*size), *when_full);
             }
             BufferType::DiskV2 {
                 when_full,
                 max_size,
+                encryption,
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV2Buffer::new(id, data_dir, max_size), when_full);
+                builder.stage(
+                    DiskV2Buffer::new(id, data_dir, *max_size).with_encryption(encryption.clone()),
+                    *when_full,
+                );
             }
         }
 
@@ -339,10 +410,8 @@ impl BufferType {
 /// functionality to allow chaining buffers together, you'll see "buffer topology" used in internal
 /// documentation to correctly reflect the internal structure.
 ///
-// TODO: We need to limit chained buffers to only allowing a single copy of each buffer type to be
-// defined, otherwise, for example, two instances of the same disk buffer type in a single chained
-// buffer topology would try to both open the same buffer files on disk, which wouldn't work or
-// would go horribly wrong.
+/// A chained buffer topology can only hold a single disk buffer stage, as the disk buffer stages of
+/// a sink would otherwise open the same buffer files.
 #[configurable_component]
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[serde(untagged)]
@@ -386,11 +455,11 @@ impl BufferConfig {
     ///
     /// # Errors
     ///
-    /// If the buffer is configured with anything other than a single stage, an error variant will
-    /// be thrown.
+    /// If the stages of the buffer don't form a valid buffer topology, an error variant will be
+    /// thrown.
     ///
-    /// If a disk buffer stage is configured and the data directory provided is `None`, an error
-    /// variant will be thrown.
+    /// If a disk buffer stage is configured and the data directory provided is `None`, or if more
+    /// than one disk buffer stage is configured, an error variant will be thrown.
     #[allow(clippy::needless_pass_by_value)]
     pub async fn build<T>(
         &self,
@@ -401,6 +470,15 @@ impl BufferConfig {
     where
         T: Bufferable + Clone + Finalizable,
     {
+        let disk_stages = self
+            .stages()
+            .iter()
+            .filter(|stage| matches!(stage, BufferType::DiskV2 { .. }))
+            .count();
+        if disk_stages > 1 {
+            return Err(BufferBuildError::MultipleDiskStages);
+        }
+
         let mut builder = TopologyBuilder::default();
 
         for stage in self.stages() {
@@ -418,7 +496,10 @@ impl BufferConfig {
 mod test {
     use std::num::{NonZeroU64, NonZeroUsize};
 
-    use crate::{BufferConfig, BufferType, MemoryBufferSize, WhenFull};
+    use tracing::Span;
+
+    use super::{BufferBuildError, DiskBufferEncryption};
+    use crate::{BufferConfig, BufferType, MemoryBufferSize, WhenFull, test::SizedRecord};
 
     fn check_single_stage(source: &str, expected: BufferType) {
         let config: BufferConfig = serde_yaml::from_str(source).unwrap();
@@ -561,7 +642,58 @@ max_events: 42
             BufferType::DiskV2 {
                 max_size: NonZeroU64::new(1024).unwrap(),
                 when_full: WhenFull::Block,
+                encryption: None,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_disk_encryption() {
+        check_single_stage(
+            r"
+          type: disk
+          max_size: 1024
+          encryption:
+            key: current
+            previous_keys: [previous]
+          ",
+            BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024).unwrap(),
+                when_full: WhenFull::Block,
+                encryption: Some(DiskBufferEncryption {
+                    key: "current".to_string().into(),
+                    previous_keys: vec!["previous".to_string().into()],
+                }),
             },
         );
+
+        let source = r"type: memory
+encryption:
+  key: current
+";
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert_eq!(error.to_string(), BUFFER_CONFIG_NO_MATCH_ERR);
+    }
+
+    #[tokio::test]
+    async fn build_rejects_multiple_disk_stages() {
+        let disk_stage = |when_full| BufferType::DiskV2 {
+            max_size: NonZeroU64::new(268_435_488).unwrap(),
+            when_full,
+            encryption: None,
+        };
+        let config = BufferConfig::Chained(vec![
+            BufferType::Memory {
+                size: MemoryBufferSize::MaxEvents(NonZeroUsize::new(100).unwrap()),
+                when_full: WhenFull::Overflow,
+            },
+            disk_stage(WhenFull::Overflow),
+            disk_stage(WhenFull::DropNewest),
+        ]);
+
+        let result = config
+            .build::<SizedRecord>(None, String::from("test"), Span::none())
+            .await;
+        assert!(matches!(result, Err(BufferBuildError::MultipleDiskStages)));
     }
 }
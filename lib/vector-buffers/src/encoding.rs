@@ -5,6 +5,9 @@ use bytes::{Buf, BufMut};
 /// Converts back and forth between user-friendly metadata types and the on-disk integer representation.
 pub trait AsMetadata: Sized {
     /// Converts this metadata value into its integer representation.
+    ///
+    /// The highest bit is reserved by the disk buffer to flag encrypted records, and must not be
+    /// set.
     fn into_u32(self) -> u32;
 
     /// Converts an integer representation of metadata into its real type, if possible.
@@ -180,6 +180,75 @@ impl InternalEvent for BufferEventsDropped {
     }
 }
 
+pub struct BufferCompacted {
+    pub buffer_id: String,
+    pub idx: usize,
+    pub count: u64,
+    pub reclaimed_bytes: u64,
+}
+
+impl InternalEvent for BufferCompacted {
+    fn emit(self) {
+        debug!(
+            message = "Buffer compacted.",
+            count = %self.count,
+            reclaimed_bytes = %self.reclaimed_bytes,
+            buffer_id = %self.buffer_id,
+            stage = %self.idx,
+        );
+        counter!(
+            "buffer_compactions_total",
+            "buffer_id" => self.buffer_id.clone(),
+            "stage" => self.idx.to_string()
+        )
+        .increment(self.count);
+        counter!(
+            "buffer_reclaimed_bytes_total",
+            "buffer_id" => self.buffer_id,
+            "stage" => self.idx.to_string()
+        )
+        .increment(self.reclaimed_bytes);
+    }
+}
+
+pub struct BufferUtilization {
+    pub buffer_id: String,
+    pub idx: usize,
+    pub utilization: f64,
+}
+
+impl InternalEvent for BufferUtilization {
+    fn emit(self) {
+        gauge!(
+            "buffer_utilization",
+            "buffer_id" => self.buffer_id,
+            "stage" => self.idx.to_string()
+        )
+        .set(self.utilization);
+    }
+}
+
+pub struct BufferActiveStage {
+    pub buffer_id: String,
+    pub idx: usize,
+    pub previous_idx: usize,
+}
+
+impl InternalEvent for BufferActiveStage {
+    #[expect(clippy::cast_precision_loss)]
+    fn emit(self) {
+        if self.idx != self.previous_idx {
+            info!(
+                message = "Buffer active stage changed.",
+                buffer_id = %self.buffer_id,
+                stage = %self.idx,
+                previous_stage = %self.previous_idx,
+            );
+        }
+        gauge!("buffer_active_stage", "buffer_id" => self.buffer_id).set(self.idx as f64);
+    }
+}
+
 pub struct BufferReadError {
     pub error_code: &'static str,
     pub error: String,
@@ -31,6 +31,10 @@ pub mod test;
 pub mod topology;
 
 pub(crate) mod variants;
+pub use variants::{
+    BufferedRecords, CompactionError, CompactionSummary, DiskBufferStatus, InspectionError,
+    compact_disk_buffer, disk_buffer_status, read_disk_buffer,
+};
 
 use std::fmt::Debug;
 
@@ -66,7 +70,6 @@ pub enum WhenFull {
     /// potentially be able to buffer the event, but it may also block or drop the event.
     ///
     /// This mode can only be used when two or more buffer stages are configured.
-    #[configurable(metadata(docs::hidden))]
     Overflow,
 }
 
@@ -131,6 +131,15 @@ impl<T: InMemoryBufferable> LimitedSender<T> {
         self.inner.limiter.available_permits()
     }
 
+    /// Gets the total number of items that this channel can hold.
+    ///
+    /// When the channel is limited by size, this is the maximum size in bytes.
+    pub fn capacity(&self) -> usize {
+        match self.inner.limit {
+            MemoryBufferSize::MaxSize(limit) | MemoryBufferSize::MaxEvents(limit) => limit.get(),
+        }
+    }
+
     /// Sends an item into the channel.
     ///
     /// # Errors
@@ -313,6 +322,7 @@ mod tests {
         let (mut tx, mut rx) = limited(MemoryBufferSize::MaxEvents(NonZeroUsize::new(2).unwrap()));
 
         assert_eq!(2, tx.available_capacity());
+        assert_eq!(2, tx.capacity());
 
         let msg = Sample::new(42);
 
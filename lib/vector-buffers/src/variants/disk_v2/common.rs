@@ -1,5 +1,6 @@
 use std::{
     path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 
@@ -7,6 +8,7 @@ use crc32fast::Hasher;
 use snafu::Snafu;
 
 use super::{
+    encryption::{ENCRYPTION_OVERHEAD, RecordCipher},
     io::{Filesystem, ProductionFilesystem},
     ledger::LEDGER_LEN,
     record::RECORD_HEADER_LEN,
@@ -30,6 +32,14 @@ pub const MINIMUM_MAX_RECORD_SIZE: usize = align16(RECORD_HEADER_LEN + 1);
 // have it configured.
 pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
 
+// Checking once a minute whether a drained data file should be compacted is frequent enough to
+// reclaim disk space in a timely fashion, while not adding any meaningful overhead.
+pub const DEFAULT_COMPACTION_INTERVAL: Duration = Duration::from_secs(60);
+
+// A quarter of the default maximum data file size: rolling over data files smaller than this isn't
+// worth the churn of creating a new data file.
+pub const DEFAULT_COMPACTION_THRESHOLD: u64 = 32 * 1024 * 1024;
+
 // Using 256KB as it aligns nicely with the I/O size exposed by major cloud providers.  This may not
 // be the underlying block size used by the OS, but it still aligns well with what will happen on
 // the "backend" for cloud providers, which is simply a useful default for when we want to look at
@@ -153,6 +163,24 @@ pub struct DiskBufferConfig<FS> {
     /// amount of data written since the last flush would be lost.
     pub(crate) flush_interval: Duration,
 
+    /// Interval at which the buffer checks whether the current data file should be compacted.
+    ///
+    /// Data files are only deleted once the writer has moved on from them, so after a backlog has
+    /// drained, the data file being written to holds on to its disk space until it fills up. When
+    /// the buffer is drained and that data file is at least `compaction_threshold` bytes in size,
+    /// the writer is asked to roll over to a new data file, which allows the reader to delete it.
+    ///
+    /// When `None`, compaction is disabled.
+    pub(crate) compaction_interval: Option<Duration>,
+
+    /// Minimum size, in bytes, of a drained data file for it to be compacted.
+    pub(crate) compaction_threshold: u64,
+
+    /// Cipher used to encrypt records before they're written to disk.
+    ///
+    /// When `None`, records are written as-is, and encrypted records can't be read.
+    pub(crate) encryption: Option<Arc<RecordCipher>>,
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -173,6 +201,9 @@ where
     pub(crate) max_record_size: Option<usize>,
     pub(crate) write_buffer_size: Option<usize>,
     pub(crate) flush_interval: Option<Duration>,
+    pub(crate) compaction_interval: Option<Option<Duration>>,
+    pub(crate) compaction_threshold: Option<u64>,
+    pub(crate) encryption: Option<Arc<RecordCipher>>,
     pub(crate) filesystem: FS,
 }
 
@@ -188,6 +219,9 @@ impl DiskBufferConfigBuilder {
             max_record_size: None,
             write_buffer_size: None,
             flush_interval: None,
+            compaction_interval: None,
+            compaction_threshold: None,
+            encryption: None,
             filesystem: ProductionFilesystem,
         }
     }
@@ -268,6 +302,43 @@ where
         self
     }
 
+    /// Sets the interval at which the buffer checks whether the current data file should be
+    /// compacted, or disables compaction when `None`.
+    ///
+    /// Data files are only deleted once the writer has moved on from them, so after a backlog has
+    /// drained, the data file being written to holds on to its disk space until it fills up. When
+    /// the buffer is drained and that data file is at least `compaction_threshold` bytes in size,
+    /// the writer is asked to roll over to a new data file, which allows the reader to delete it.
+    ///
+    /// Defaults to 60 seconds.
+    #[allow(dead_code)]
+    pub fn compaction_interval(mut self, interval: Option<Duration>) -> Self {
+        self.compaction_interval = Some(interval);
+        self
+    }
+
+    /// Sets the minimum size, in bytes, of a drained data file for it to be compacted.
+    ///
+    /// Defaults to 32MB.
+    #[allow(dead_code)]
+    pub fn compaction_threshold(mut self, amount: u64) -> Self {
+        self.compaction_threshold = Some(amount);
+        self
+    }
+
+    /// Sets the cipher used to encrypt records before they're written to disk, or disables
+    /// encryption when `None`.
+    ///
+    /// Records that were written before encryption was enabled remain readable, but encrypted
+    /// records can only be read with a cipher holding the key they were encrypted with.
+    ///
+    /// Defaults to `None`.
+    #[allow(dead_code)]
+    pub fn encryption(mut self, cipher: Option<Arc<RecordCipher>>) -> Self {
+        self.encryption = cipher;
+        self
+    }
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -287,6 +358,9 @@ where
             max_record_size: self.max_record_size,
             write_buffer_size: self.write_buffer_size,
             flush_interval: self.flush_interval,
+            compaction_interval: self.compaction_interval,
+            compaction_threshold: self.compaction_threshold,
+            encryption: self.encryption,
             filesystem,
         }
     }
@@ -301,6 +375,13 @@ where
         let max_record_size = self.max_record_size.unwrap_or(DEFAULT_MAX_RECORD_SIZE);
         let write_buffer_size = self.write_buffer_size.unwrap_or(DEFAULT_WRITE_BUFFER_SIZE);
         let flush_interval = self.flush_interval.unwrap_or(DEFAULT_FLUSH_INTERVAL);
+        let compaction_interval = self
+            .compaction_interval
+            .unwrap_or(Some(DEFAULT_COMPACTION_INTERVAL));
+        let compaction_threshold = self
+            .compaction_threshold
+            .unwrap_or(DEFAULT_COMPACTION_THRESHOLD);
+        let encryption = self.encryption;
         let filesystem = self.filesystem;
 
         // Validate the input parameters.
@@ -344,6 +425,19 @@ where
             });
         }
 
+        // Encrypted records carry the key ID, nonce, and tag along with their payload, which must
+        // still leave room for at least one byte of payload.
+        if encryption.is_some() && max_record_size <= MINIMUM_MAX_RECORD_SIZE + ENCRYPTION_OVERHEAD
+        {
+            return Err(BuildError::InvalidParameter {
+                param_name: "max_record_size",
+                reason: format!(
+                    "must be greater than {} bytes when encryption is enabled",
+                    MINIMUM_MAX_RECORD_SIZE + ENCRYPTION_OVERHEAD
+                ),
+            });
+        }
+
         let Ok(max_record_size_converted) = u64::try_from(max_record_size) else {
             return Err(BuildError::InvalidParameter {
                 param_name: "max_record_size",
@@ -365,6 +459,20 @@ where
             });
         }
 
+        if compaction_interval.is_some_and(|interval| interval.is_zero()) {
+            return Err(BuildError::InvalidParameter {
+                param_name: "compaction_interval",
+                reason: "cannot be zero".to_string(),
+            });
+        }
+
+        if compaction_threshold == 0 {
+            return Err(BuildError::InvalidParameter {
+                param_name: "compaction_threshold",
+                reason: "cannot be zero".to_string(),
+            });
+        }
+
         // Users configure the `max_size` of their disk buffers, which translates to the `max_buffer_size` field here,
         // and represents the maximum desired size of a disk buffer in terms of on-disk usage. In order to meet this
         // request, we do a few things internally and also enforce a lower bound on `max_buffer_size` to ensure we can
@@ -412,6 +520,9 @@ where
             max_record_size,
             write_buffer_size,
             flush_interval,
+            compaction_interval,
+            compaction_threshold,
+            encryption,
             filesystem,
         })
     }
@@ -0,0 +1,280 @@
+//! Reclamation of the disk space used by data files that have been fully processed.
+//!
+//! Data files are only deleted by the reader once the writer has moved on from them and all of
+//! their records have been acknowledged. This means that, after a large backlog has drained, the
+//! data file currently being written to holds on to all of the disk space it used until it fills
+//! up, which can take a long time for low-volume buffers.
+//!
+//! While the buffer is running, a background task periodically checks whether the buffer has
+//! drained, and if the current data file is large enough, asks the writer to roll over to a new
+//! data file on its next write, which in turn lets the reader delete the drained data file.
+//!
+//! When the buffer is not in use, [`compact_disk_buffer`] can be used to delete every data file
+//! that holds no unacknowledged records, including any data file left behind by a crash.
+
+use std::{
+    io::{self, ErrorKind},
+    path::Path,
+    sync::Arc,
+};
+
+use crc32fast::Hasher;
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    fs,
+    time::{Instant, MissedTickBehavior, interval_at},
+};
+
+use super::{
+    DiskBufferConfig, DiskBufferConfigBuilder, Filesystem, LedgerLoadCreateError,
+    common::{BuildError, MAX_FILE_ID},
+    io::AsyncFile,
+    ledger::Ledger,
+    record::{RecordStatus, validate_record_archive},
+};
+use crate::buffer_usage_data::BufferUsageHandle;
+
+/// Error that occurred while compacting a disk buffer.
+#[derive(Debug, Snafu)]
+pub enum CompactionError {
+    /// The buffer configuration was invalid.
+    #[snafu(display("invalid buffer configuration: {}", source))]
+    InvalidConfig { source: BuildError },
+
+    /// Failed to load the ledger.
+    ///
+    /// This notably happens when the buffer is in use by a running Vector process.
+    #[snafu(display("failed to load ledger: {}", source))]
+    LedgerLoad { source: LedgerLoadCreateError },
+
+    /// A general I/O error occurred while deleting data files or updating the ledger.
+    #[snafu(display("compaction I/O error: {}", source))]
+    Io { source: io::Error },
+}
+
+/// Summary of the disk space reclaimed by compacting a disk buffer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CompactionSummary {
+    /// Number of data files deleted.
+    pub data_files_deleted: usize,
+
+    /// Total size, in bytes, of the deleted data files.
+    pub reclaimed_bytes: u64,
+}
+
+/// Spawns a background task which periodically checks if the current data file should be
+/// compacted.
+///
+/// The task only holds a weak reference to the ledger, and stops once the buffer is dropped or
+/// the writer is done.
+pub(super) fn spawn_compactor<FS>(ledger: &Arc<Ledger<FS>>)
+where
+    FS: Filesystem + 'static,
+{
+    let Some(period) = ledger.config().compaction_interval else {
+        return;
+    };
+    let ledger = Arc::downgrade(ledger);
+
+    tokio::spawn(async move {
+        let mut interval = interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let Some(ledger) = ledger.upgrade() else {
+                break;
+            };
+            if ledger.is_writer_done() {
+                break;
+            }
+
+            match should_compact(&ledger).await {
+                Ok(true) => {
+                    debug!("Buffer drained. Requesting compaction of current data file.");
+                    ledger.request_compaction();
+                }
+                Ok(false) => {}
+                Err(error) => {
+                    debug!(%error, "Failed to check current data file for compaction.");
+                }
+            }
+        }
+    });
+}
+
+/// Whether the buffer is drained, and the current data file is large enough to be compacted.
+async fn should_compact<FS>(ledger: &Ledger<FS>) -> io::Result<bool>
+where
+    FS: Filesystem,
+{
+    // Until the reader has caught up to the writer, data files keep being deleted as they are read.
+    let (reader_file_id, writer_file_id) = ledger.get_current_reader_writer_file_id();
+    if reader_file_id != writer_file_id || ledger.get_total_records() > 0 {
+        return Ok(false);
+    }
+
+    // We query the data file directly, rather than through `Filesystem`, as the futures it returns
+    // aren't guaranteed to be `Send`.
+    let data_file_len = match fs::metadata(ledger.get_current_writer_data_file_path()).await {
+        Ok(metadata) => metadata.len(),
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(error),
+    };
+
+    Ok(data_file_len >= ledger.config().compaction_threshold)
+}
+
+/// Compacts the disk buffer stored in the given directory.
+///
+/// Deletes all data files which no longer hold any unacknowledged records: data files left behind
+/// outside of the range tracked by the ledger, and, if the buffer is fully drained, all remaining
+/// data files.
+///
+/// # Errors
+///
+/// If the buffer is in use by another Vector process, or an I/O error occurs while deleting data
+/// files or updating the ledger, an error variant will be returned describing the error.
+pub async fn compact_disk_buffer(data_dir: &Path) -> Result<CompactionSummary, CompactionError> {
+    let config = DiskBufferConfigBuilder::from_path(data_dir)
+        .compaction_interval(None)
+        .build()
+        .context(InvalidConfigSnafu)?;
+
+    compact_buffer_offline(config).await
+}
+
+pub(super) async fn compact_buffer_offline<FS>(
+    config: DiskBufferConfig<FS>,
+) -> Result<CompactionSummary, CompactionError>
+where
+    FS: Filesystem,
+{
+    // Loading the ledger acquires the buffer lock, so nothing else can be using the data files.
+    let ledger = Ledger::load_or_create(config, BufferUsageHandle::noop())
+        .await
+        .context(LedgerLoadSnafu)?;
+
+    let (reader_file_id, writer_file_id) = ledger.get_current_reader_writer_file_id();
+    let drained = ledger.get_total_records() == 0
+        && is_writer_data_file_accounted(&ledger)
+            .await
+            .context(IoSnafu)?;
+
+    let mut summary = CompactionSummary::default();
+    let mut entries = fs::read_dir(&ledger.config().data_dir)
+        .await
+        .context(IoSnafu)?;
+    while let Some(entry) = entries.next_entry().await.context(IoSnafu)? {
+        let Some(file_id) = entry.file_name().to_str().and_then(parse_data_file_id) else {
+            continue;
+        };
+        if !drained && is_live_data_file(file_id, reader_file_id, writer_file_id) {
+            continue;
+        }
+
+        let data_file_len = entry.metadata().await.context(IoSnafu)?.len();
+        let data_file_path = ledger.get_data_file_path(file_id);
+        debug!(
+            data_file_path = data_file_path.to_string_lossy().as_ref(),
+            data_file_len, "Deleting fully processed data file."
+        );
+        ledger
+            .filesystem()
+            .delete_file(&data_file_path)
+            .await
+            .context(IoSnafu)?;
+
+        summary.data_files_deleted += 1;
+        summary.reclaimed_bytes += data_file_len;
+    }
+
+    // With all data files deleted, the reader picks up on the writer's data file, which the
+    // writer recreates when it opens.
+    if drained {
+        while ledger.get_current_reader_file_id() != writer_file_id {
+            ledger.increment_acked_reader_file_id();
+        }
+    }
+    ledger.flush().context(IoSnafu)?;
+
+    Ok(summary)
+}
+
+/// Whether every record in the writer's current data file is accounted for in the ledger.
+///
+/// The writer may have crashed after writing records, but before updating the ledger, in which
+/// case the reader will pick up those records on the next start even though the ledger considers
+/// the buffer drained.
+async fn is_writer_data_file_accounted<FS>(ledger: &Ledger<FS>) -> io::Result<bool>
+where
+    FS: Filesystem,
+{
+    let data_file_path = ledger.get_current_writer_data_file_path();
+    match ledger
+        .filesystem()
+        .open_file_readable(&data_file_path)
+        .await
+    {
+        Ok(data_file) if data_file.metadata().await?.len() == 0 => return Ok(true),
+        Ok(_) => {}
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(true),
+        Err(error) => return Err(error),
+    }
+
+    let data_file_mmap = ledger
+        .filesystem()
+        .open_mmap_readable(&data_file_path)
+        .await?;
+    let next_writer_record_id = ledger.state().get_next_writer_record_id();
+    Ok(matches!(
+        validate_record_archive(data_file_mmap.as_ref(), &Hasher::new()),
+        RecordStatus::Valid { id, .. } if id < next_writer_record_id
+    ))
+}
+
+fn parse_data_file_id(file_name: &str) -> Option<u16> {
+    file_name
+        .strip_prefix("buffer-data-")?
+        .strip_suffix(".dat")?
+        .parse()
+        .ok()
+        .filter(|file_id| *file_id < MAX_FILE_ID)
+}
+
+/// Whether the data file is within the range of data files between the reader and the writer,
+/// accounting for file IDs wrapping around.
+fn is_live_data_file(file_id: u16, reader_file_id: u16, writer_file_id: u16) -> bool {
+    let distance = |from: u16, to: u16| {
+        (u32::from(to) + u32::from(MAX_FILE_ID) - u32::from(from)) % u32::from(MAX_FILE_ID)
+    };
+
+    distance(reader_file_id, file_id) <= distance(reader_file_id, writer_file_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_live_data_file, parse_data_file_id};
+
+    #[test]
+    fn data_file_ids() {
+        assert_eq!(parse_data_file_id("buffer-data-3.dat"), Some(3));
+        assert_eq!(parse_data_file_id("buffer-data-3.dat.tmp"), None);
+        assert_eq!(parse_data_file_id("buffer.db"), None);
+        assert_eq!(parse_data_file_id("buffer-data-65535.dat"), None);
+    }
+
+    #[test]
+    fn live_data_files() {
+        assert!(is_live_data_file(2, 2, 2));
+        assert!(is_live_data_file(3, 2, 4));
+        assert!(!is_live_data_file(1, 2, 4));
+        assert!(!is_live_data_file(5, 2, 4));
+
+        // The writer wrapped around to the first file ID.
+        assert!(is_live_data_file(5, 4, 1));
+        assert!(is_live_data_file(0, 4, 1));
+        assert!(!is_live_data_file(2, 4, 1));
+    }
+}
@@ -0,0 +1,278 @@
+//! Encryption of the records of a disk buffer at rest.
+//!
+//! When encryption is enabled, the payload of each record is sealed with AES-256-GCM before the
+//! record is checksummed and written to a data file:
+//!
+//! ```text
+//! encrypted payload:
+//!   key_id:     uint8[4] (first bytes of the SHA-256 digest of the key)
+//!   nonce:      uint8[12]
+//!   ciphertext: uint8[payload_len]
+//!   tag:        uint8[16]
+//! ```
+//!
+//! The key ID allows for rotating the key: records are always sealed with the current key, while
+//! the records sealed with any of the previous keys can still be opened, so that data files written
+//! before a rotation remain readable until they're drained. The ID and the metadata of the record
+//! are authenticated along with the payload, so that encrypted payloads can't be moved between
+//! records without being detected.
+//!
+//! Encrypted records are marked with a flag in their metadata, which lets a buffer hold both plain
+//! and encrypted records when encryption is enabled for a buffer that already holds records.
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, MAX_TAG_LEN, NONCE_LEN, Nonce, UnboundKey},
+    digest::{SHA256, digest},
+    rand::{SecureRandom, SystemRandom},
+};
+use snafu::Snafu;
+
+use crate::config::DiskBufferEncryption;
+
+/// Flag set in the metadata of the records whose payload is encrypted.
+///
+/// The flag is stripped from the metadata before it's handed to the decoder, so it must never be
+/// used by the metadata of a `Bufferable` type.
+pub(crate) const ENCRYPTED_RECORD_FLAG: u32 = 1 << 31;
+
+const KEY_ID_LEN: usize = 4;
+
+/// Number of bytes added to the payload of a record by its encryption.
+pub(crate) const ENCRYPTION_OVERHEAD: usize = KEY_ID_LEN + NONCE_LEN + MAX_TAG_LEN;
+
+/// Error that occurred when encrypting or decrypting a record.
+#[derive(Debug, Snafu)]
+pub enum EncryptionError {
+    /// One of the configured keys is invalid.
+    #[snafu(display("invalid encryption key: {}", reason))]
+    InvalidKey { reason: String },
+
+    /// The record was encrypted with a key that isn't configured.
+    #[snafu(display("record was encrypted with an unknown key (key ID: {:08x})", key_id))]
+    UnknownKey { key_id: u32 },
+
+    /// The record is encrypted, but no encryption is configured for the buffer.
+    #[snafu(display("record is encrypted but buffer encryption is not configured"))]
+    NotConfigured,
+
+    /// The payload could not be encrypted.
+    #[snafu(display("failed to encrypt record payload"))]
+    Seal,
+
+    /// The payload could not be decrypted, either because it's truncated or because it failed
+    /// authentication.
+    #[snafu(display("failed to decrypt record payload"))]
+    Open,
+}
+
+struct EncryptionKey {
+    id: u32,
+    key: LessSafeKey,
+}
+
+/// Encrypts and decrypts the payloads of records.
+pub struct RecordCipher {
+    /// The configured keys, starting with the current one.
+    keys: Vec<EncryptionKey>,
+    rng: SystemRandom,
+}
+
+impl RecordCipher {
+    /// Creates a new [`RecordCipher`] from the given encryption configuration.
+    ///
+    /// # Errors
+    ///
+    /// If any of the keys is not a base64-encoded, 32-byte key, or if a key is configured more than
+    /// once, an error variant will be returned describing the error.
+    pub fn from_config(config: &DiskBufferEncryption) -> Result<Self, EncryptionError> {
+        let mut keys: Vec<EncryptionKey> = Vec::with_capacity(1 + config.previous_keys.len());
+        for encoded in std::iter::once(&config.key).chain(&config.previous_keys) {
+            let key = STANDARD.decode(encoded.inner().trim()).map_err(|error| {
+                EncryptionError::InvalidKey {
+                    reason: format!("key must be base64-encoded: {error}"),
+                }
+            })?;
+            let unbound =
+                UnboundKey::new(&AES_256_GCM, &key).map_err(|_| EncryptionError::InvalidKey {
+                    reason: format!("key must be 32 bytes long, got {} bytes", key.len()),
+                })?;
+            let id = key_id(&key);
+            if keys.iter().any(|existing| existing.id == id) {
+                return Err(EncryptionError::InvalidKey {
+                    reason: "keys must not be configured more than once".to_string(),
+                });
+            }
+            keys.push(EncryptionKey {
+                id,
+                key: LessSafeKey::new(unbound),
+            });
+        }
+
+        Ok(Self {
+            keys,
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Encrypts the payload of a record with the current key, writing the encrypted payload to
+    /// `sealed`.
+    ///
+    /// `metadata` must already include [`ENCRYPTED_RECORD_FLAG`].
+    pub(super) fn seal(
+        &self,
+        id: u64,
+        metadata: u32,
+        payload: &[u8],
+        sealed: &mut Vec<u8>,
+    ) -> Result<(), EncryptionError> {
+        let current = &self.keys[0];
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| EncryptionError::Seal)?;
+
+        sealed.clear();
+        sealed.reserve(payload.len() + ENCRYPTION_OVERHEAD);
+        sealed.extend_from_slice(&current.id.to_be_bytes());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(payload);
+
+        let tag = current
+            .key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(associated_data(id, metadata)),
+                &mut sealed[KEY_ID_LEN + NONCE_LEN..],
+            )
+            .map_err(|_| EncryptionError::Seal)?;
+        sealed.extend_from_slice(tag.as_ref());
+
+        Ok(())
+    }
+
+    /// Decrypts the payload of a record with the key it was encrypted with.
+    pub(super) fn open(
+        &self,
+        id: u64,
+        metadata: u32,
+        sealed: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError> {
+        if sealed.len() < ENCRYPTION_OVERHEAD {
+            return Err(EncryptionError::Open);
+        }
+        let (key_id, rest) = sealed.split_at(KEY_ID_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let key_id = u32::from_be_bytes(key_id.try_into().expect("key ID should be 4 bytes"));
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.id == key_id)
+            .ok_or(EncryptionError::UnknownKey { key_id })?;
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| EncryptionError::Open)?;
+
+        let mut payload = ciphertext.to_vec();
+        let payload_len = key
+            .key
+            .open_in_place(
+                nonce,
+                Aad::from(associated_data(id, metadata)),
+                &mut payload,
+            )
+            .map_err(|_| EncryptionError::Open)?
+            .len();
+        payload.truncate(payload_len);
+
+        Ok(payload)
+    }
+}
+
+impl fmt::Debug for RecordCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key_ids = self
+            .keys
+            .iter()
+            .map(|key| format!("{:08x}", key.id))
+            .collect::<Vec<_>>();
+        f.debug_struct("RecordCipher")
+            .field("key_ids", &key_ids)
+            .finish_non_exhaustive()
+    }
+}
+
+fn key_id(key: &[u8]) -> u32 {
+    let digest = digest(&SHA256, key);
+    u32::from_be_bytes(
+        digest.as_ref()[..KEY_ID_LEN]
+            .try_into()
+            .expect("digest should be longer than a key ID"),
+    )
+}
+
+fn associated_data(id: u64, metadata: u32) -> [u8; 12] {
+    let mut aad = [0; 12];
+    aad[..8].copy_from_slice(&id.to_be_bytes());
+    aad[8..].copy_from_slice(&metadata.to_be_bytes());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(key: &[u8], previous_keys: &[&[u8]]) -> DiskBufferEncryption {
+        DiskBufferEncryption {
+            key: STANDARD.encode(key).into(),
+            previous_keys: previous_keys
+                .iter()
+                .map(|key| STANDARD.encode(key).into())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn round_trips_with_rotated_keys() {
+        let old = RecordCipher::from_config(&config(&[1; 32], &[])).unwrap();
+        let new = RecordCipher::from_config(&config(&[2; 32], &[&[1; 32]])).unwrap();
+        let metadata = ENCRYPTED_RECORD_FLAG | 1;
+
+        let mut sealed = Vec::new();
+        old.seal(42, metadata, b"buffered events", &mut sealed)
+            .unwrap();
+        assert_eq!(sealed.len(), b"buffered events".len() + ENCRYPTION_OVERHEAD);
+        assert_eq!(new.open(42, metadata, &sealed).unwrap(), b"buffered events");
+
+        // The record ID is authenticated along with the payload.
+        assert!(matches!(
+            new.open(43, metadata, &sealed),
+            Err(EncryptionError::Open)
+        ));
+
+        new.seal(44, metadata, b"newer events", &mut sealed)
+            .unwrap();
+        assert!(matches!(
+            old.open(44, metadata, &sealed),
+            Err(EncryptionError::UnknownKey { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        for config in [
+            config(&[1; 16], &[]),
+            config(&[1; 32], &[&[1; 32]]),
+            DiskBufferEncryption {
+                key: "not base64!".to_string().into(),
+                previous_keys: Vec::new(),
+            },
+        ] {
+            assert!(matches!(
+                RecordCipher::from_config(&config),
+                Err(EncryptionError::InvalidKey { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Read-only inspection of a disk buffer.
+//!
+//! Inspecting a disk buffer reads the ledger and the data files directly, without acquiring the
+//! buffer lock, so that a buffer can be inspected while it is in use by a running Vector process,
+//! such as when trying to figure out why a sink is not making progress. As a consequence, the
+//! results are only a best-effort snapshot: the reader and writer may make progress while the data
+//! files are being read.
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use rkyv::AlignedVec;
+use snafu::{ResultExt, Snafu};
+use tokio::fs;
+
+use super::{
+    backed_archive::BackedArchive,
+    common::MAX_FILE_ID,
+    encryption::{EncryptionError, RecordCipher},
+    ledger::{ArchivedLedgerState, LedgerState},
+    reader::{ReaderError, RecordReader},
+};
+use crate::{Bufferable, config::DiskBufferEncryption};
+
+/// Error that occurred while inspecting a disk buffer.
+#[derive(Debug, Snafu)]
+pub enum InspectionError {
+    /// The data directory does not contain a ledger, and so does not hold a disk buffer.
+    #[snafu(display("no disk buffer found in {}", data_dir.display()))]
+    NotFound { data_dir: PathBuf },
+
+    /// The ledger could not be deserialized.
+    #[snafu(display("failed to deserialize ledger: {}", reason))]
+    InvalidLedger { reason: String },
+
+    /// A general I/O error occurred while reading the ledger or the data files.
+    #[snafu(display("inspection I/O error: {}", source))]
+    Io { source: io::Error },
+
+    /// The encryption configuration of the buffer is invalid.
+    #[snafu(display("invalid buffer encryption: {}", source))]
+    InvalidEncryption { source: EncryptionError },
+}
+
+/// The state of a disk buffer, as tracked by its ledger.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DiskBufferStatus {
+    /// Number of events written to the buffer that have not yet been acknowledged.
+    pub buffered_events: u64,
+
+    /// Number of data files between the reader and the writer, inclusive.
+    pub data_files: usize,
+
+    /// Total size, in bytes, of the data files on disk.
+    ///
+    /// As data files are only deleted once they have been fully processed, this may include the
+    /// size of records that have already been acknowledged.
+    pub disk_usage_bytes: u64,
+}
+
+/// The records held by a disk buffer.
+#[derive(Debug)]
+pub struct BufferedRecords<T> {
+    /// The state of the disk buffer.
+    pub status: DiskBufferStatus,
+
+    /// Total size, in bytes, of the records that have not yet been acknowledged.
+    pub buffered_bytes: u64,
+
+    /// Number of records that have not yet been acknowledged.
+    pub buffered_records: u64,
+
+    /// The oldest records that have not yet been acknowledged.
+    pub head: Vec<T>,
+
+    /// The newest records that have not yet been acknowledged, excluding those in `head`.
+    pub tail: Vec<T>,
+
+    /// Number of records which were read, but could not be decoded.
+    pub undecodable_records: u64,
+
+    /// Number of data files which could not be read past a corrupted record.
+    pub corrupted_data_files: usize,
+}
+
+/// Gets the state of the disk buffer stored in the given directory.
+///
+/// # Errors
+///
+/// If the directory does not hold a disk buffer, or an I/O error occurs while reading the ledger,
+/// an error variant will be returned describing the error.
+pub async fn disk_buffer_status(data_dir: &Path) -> Result<DiskBufferStatus, InspectionError> {
+    let ledger = load_ledger_snapshot(data_dir).await?;
+    disk_buffer_status_inner(data_dir, ledger.get_archive_ref()).await
+}
+
+/// Reads the records held by the disk buffer stored in the given directory.
+///
+/// Up to `head` of the oldest unacknowledged records, and up to `tail` of the newest ones which
+/// aren't part of the oldest, are decoded and returned. Like the buffer reader does, the rest of a
+/// data file is skipped after a corrupted record.
+///
+/// Encrypted records are decrypted with the given encryption configuration, and counted as
+/// undecodable records without it.
+///
+/// # Errors
+///
+/// If the directory does not hold a disk buffer, if the encryption configuration is invalid, or if
+/// an I/O error occurs while reading the ledger or the data files, an error variant will be
+/// returned describing the error.
+pub async fn read_disk_buffer<T>(
+    data_dir: &Path,
+    encryption: Option<&DiskBufferEncryption>,
+    head: usize,
+    tail: usize,
+) -> Result<BufferedRecords<T>, InspectionError>
+where
+    T: Bufferable,
+{
+    let cipher = encryption
+        .map(RecordCipher::from_config)
+        .transpose()
+        .context(InvalidEncryptionSnafu)?
+        .map(Arc::new);
+    let ledger = load_ledger_snapshot(data_dir).await?;
+    let state = ledger.get_archive_ref();
+    let status = disk_buffer_status_inner(data_dir, state).await?;
+    let last_reader_record_id = state.get_last_reader_record_id();
+
+    let mut records = BufferedRecords {
+        status,
+        buffered_bytes: 0,
+        buffered_records: 0,
+        head: Vec::with_capacity(head),
+        tail: Vec::with_capacity(tail),
+        undecodable_records: 0,
+        corrupted_data_files: 0,
+    };
+    let mut newest = VecDeque::with_capacity(tail);
+
+    for file_id in live_data_file_ids(state) {
+        let data_file = match fs::File::open(get_data_file_path(data_dir, file_id)).await {
+            Ok(data_file) => data_file,
+            // The reader may have deleted the data file since we loaded the ledger.
+            Err(error) if error.kind() == ErrorKind::NotFound => continue,
+            Err(error) => return Err(error).context(IoSnafu),
+        };
+
+        let mut reader = RecordReader::<_, T>::new(data_file, cipher.clone());
+        loop {
+            let token = match reader.try_next_record(true).await {
+                Ok(Some(token)) => token,
+                Ok(None) | Err(ReaderError::PartialWrite) => break,
+                Err(ReaderError::Io { source }) => return Err(source).context(IoSnafu),
+                Err(error) => {
+                    debug!(%error, file_id, "Skipping rest of corrupted data file.");
+                    records.corrupted_data_files += 1;
+                    break;
+                }
+            };
+
+            // Records at the start of the reader's data file may already have been acknowledged.
+            if token.record_id() <= last_reader_record_id {
+                continue;
+            }
+
+            records.buffered_records += 1;
+            records.buffered_bytes += u64::try_from(token.record_bytes())
+                .expect("Vector only supports 64-bit architectures.");
+
+            let in_head = records.head.len() < head;
+            if !in_head && tail == 0 {
+                continue;
+            }
+
+            match reader.read_record(token) {
+                Ok(record) if in_head => records.head.push(record),
+                Ok(record) => push_bounded(&mut newest, record, tail),
+                Err(error) => {
+                    debug!(%error, file_id, "Failed to decode buffered record.");
+                    records.undecodable_records += 1;
+                }
+            }
+        }
+    }
+
+    records.tail.extend(newest);
+
+    Ok(records)
+}
+
+async fn load_ledger_snapshot(
+    data_dir: &Path,
+) -> Result<BackedArchive<AlignedVec, LedgerState>, InspectionError> {
+    let ledger_bytes = match fs::read(data_dir.join("buffer.db")).await {
+        Ok(ledger_bytes) => ledger_bytes,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            return Err(InspectionError::NotFound {
+                data_dir: data_dir.to_path_buf(),
+            });
+        }
+        Err(error) => return Err(error).context(IoSnafu),
+    };
+
+    // The archived ledger state has to be aligned in memory to be accessed.
+    let mut backing = AlignedVec::with_capacity(ledger_bytes.len());
+    backing.extend_from_slice(&ledger_bytes);
+
+    BackedArchive::from_backing(backing).map_err(|e| InspectionError::InvalidLedger {
+        reason: e.into_inner(),
+    })
+}
+
+async fn disk_buffer_status_inner(
+    data_dir: &Path,
+    state: &ArchivedLedgerState,
+) -> Result<DiskBufferStatus, InspectionError> {
+    let next_writer_record_id = state.get_next_writer_record_id();
+    let last_reader_record_id = state.get_last_reader_record_id();
+
+    let mut status = DiskBufferStatus {
+        buffered_events: next_writer_record_id.wrapping_sub(last_reader_record_id) - 1,
+        ..Default::default()
+    };
+
+    for file_id in live_data_file_ids(state) {
+        match fs::metadata(get_data_file_path(data_dir, file_id)).await {
+            Ok(metadata) => {
+                status.data_files += 1;
+                status.disk_usage_bytes += metadata.len();
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error).context(IoSnafu),
+        }
+    }
+
+    Ok(status)
+}
+
+/// Gets the IDs of the data files from the reader's to the writer's, in the order they are read.
+fn live_data_file_ids(state: &ArchivedLedgerState) -> impl Iterator<Item = u16> {
+    let reader_file_id = state.get_current_reader_file_id();
+    let writer_file_id = state.get_current_writer_file_id();
+
+    let mut next = Some(reader_file_id);
+    std::iter::from_fn(move || {
+        let file_id = next?;
+        next = (file_id != writer_file_id).then(|| (file_id + 1) % MAX_FILE_ID);
+        Some(file_id)
+    })
+}
+
+fn get_data_file_path(data_dir: &Path, file_id: u16) -> PathBuf {
+    data_dir.join(format!("buffer-data-{file_id}.dat"))
+}
+
+fn push_bounded<T>(records: &mut VecDeque<T>, record: T, limit: usize) {
+    if records.len() == limit {
+        records.pop_front();
+    }
+    records.push_back(record);
+}
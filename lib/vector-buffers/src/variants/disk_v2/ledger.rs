@@ -121,7 +121,7 @@ impl Default for LedgerState {
 }
 
 impl ArchivedLedgerState {
-    fn get_current_writer_file_id(&self) -> u16 {
+    pub(super) fn get_current_writer_file_id(&self) -> u16 {
         self.writer_current_data_file.load(Ordering::Acquire)
     }
 
@@ -143,7 +143,7 @@ impl ArchivedLedgerState {
         previous.wrapping_add(amount)
     }
 
-    fn get_current_reader_file_id(&self) -> u16 {
+    pub(super) fn get_current_reader_file_id(&self) -> u16 {
         self.reader_current_data_file.load(Ordering::Acquire)
     }
 
@@ -223,6 +223,8 @@ where
     writer_notify: Notify,
     // Tracks when writer has fully shutdown.
     writer_done: AtomicBool,
+    // Tracks when the writer should roll over to a new data file so the current one can be deleted.
+    compaction_requested: AtomicBool,
     // Number of pending record acknowledgements that have yeet to be consumed by the reader.
     pending_acks: AtomicU64,
     // The file ID offset of the reader past the acknowledged reader file ID.
@@ -411,6 +413,25 @@ where
         self.writer_done.load(Ordering::Acquire)
     }
 
+    /// Requests that the writer roll over to a new data file before its next write.
+    ///
+    /// This allows the reader to delete the current data file once it has been fully processed,
+    /// instead of waiting for the writer to fill it up.
+    pub fn request_compaction(&self) {
+        self.compaction_requested.store(true, Ordering::Release);
+    }
+
+    /// Consumes any pending compaction request, returning `true` if one was pending.
+    pub fn take_compaction_request(&self) -> bool {
+        self.compaction_requested.swap(false, Ordering::AcqRel)
+    }
+
+    /// Tracks the statistics of a data file being compacted.
+    pub fn track_compaction(&self, reclaimed_bytes: u64) {
+        self.usage_handle
+            .increment_compaction_count_and_reclaimed_bytes(reclaimed_bytes);
+    }
+
     /// Increments the pending acknowledgement counter by the given amount.
     pub fn increment_pending_acks(&self, amount: u64) {
         self.pending_acks.fetch_add(amount, Ordering::AcqRel);
@@ -640,6 +661,7 @@ where
             reader_notify: Notify::new(),
             writer_notify: Notify::new(),
             writer_done: AtomicBool::new(false),
+            compaction_requested: AtomicBool::new(false),
             pending_acks: AtomicU64::new(0),
             unacked_reader_file_id_offset: AtomicU16::new(0),
             last_flush: AtomicCell::new(Instant::now()),
@@ -184,6 +184,9 @@ use vector_common::finalization::Finalizable;
 
 mod backed_archive;
 mod common;
+mod compaction;
+mod encryption;
+mod inspect;
 mod io;
 mod ledger;
 mod reader;
@@ -197,6 +200,11 @@ mod tests;
 use self::ledger::Ledger;
 pub use self::{
     common::{DiskBufferConfig, DiskBufferConfigBuilder},
+    compaction::{CompactionError, CompactionSummary, compact_disk_buffer},
+    encryption::{EncryptionError, RecordCipher},
+    inspect::{
+        BufferedRecords, DiskBufferStatus, InspectionError, disk_buffer_status, read_disk_buffer,
+    },
     io::{Filesystem, ProductionFilesystem},
     ledger::LedgerLoadCreateError,
     reader::{BufferReader, ReaderError},
@@ -205,6 +213,7 @@ pub use self::{
 use crate::{
     Bufferable,
     buffer_usage_data::BufferUsageHandle,
+    config::DiskBufferEncryption,
     topology::{
         builder::IntoBuffer,
         channel::{ReceiverAdapter, SenderAdapter},
@@ -260,6 +269,7 @@ where
             .context(WriterSeekFailedSnafu)?;
 
         let finalizer = Arc::clone(&ledger).spawn_finalizer();
+        compaction::spawn_compactor(&ledger);
 
         let mut reader = BufferReader::new(Arc::clone(&ledger), finalizer);
         reader
@@ -301,6 +311,7 @@ pub struct DiskV2Buffer {
     id: String,
     data_dir: PathBuf,
     max_size: NonZeroU64,
+    encryption: Option<DiskBufferEncryption>,
 }
 
 impl DiskV2Buffer {
@@ -309,8 +320,16 @@ impl DiskV2Buffer {
             id,
             data_dir,
             max_size,
+            encryption: None,
         }
     }
+
+    /// Sets the encryption configuration of the buffer, encrypting its records at rest.
+    #[must_use]
+    pub fn with_encryption(mut self, encryption: Option<DiskBufferEncryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
 }
 
 #[async_trait]
@@ -331,6 +350,7 @@ where
             &self.data_dir,
             self.id.as_str(),
             self.max_size,
+            self.encryption.as_ref(),
         )
         .await?;
 
@@ -343,6 +363,7 @@ async fn build_disk_v2_buffer<T>(
     data_dir: &Path,
     id: &str,
     max_size: NonZeroU64,
+    encryption: Option<&DiskBufferEncryption>,
 ) -> Result<
     (
         BufferWriter<T, ProductionFilesystem>,
@@ -356,8 +377,13 @@ where
     usage_handle.set_buffer_limits(Some(max_size.get()), None);
 
     let buffer_path = get_disk_v2_data_dir_path(data_dir, id);
+    let cipher = encryption
+        .map(RecordCipher::from_config)
+        .transpose()?
+        .map(Arc::new);
     let config = DiskBufferConfigBuilder::from_path(buffer_path)
         .max_buffer_size(max_size.get())
+        .encryption(cipher)
         .build()?;
     Buffer::from_config(config, usage_handle)
         .await
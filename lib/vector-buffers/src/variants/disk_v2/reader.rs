@@ -16,6 +16,7 @@ use vector_common::{finalization::BatchNotifier, finalizer::OrderedFinalizer};
 use super::{
     Filesystem,
     common::create_crc32c_hasher,
+    encryption::{ENCRYPTED_RECORD_FLAG, EncryptionError, RecordCipher},
     ledger::Ledger,
     record::{ArchivedRecord, Record, RecordStatus, validate_record_archive},
 };
@@ -108,6 +109,13 @@ where
     #[snafu(display("record version not compatible: {}", reason))]
     Incompatible { reason: String },
 
+    /// The record could not be decrypted.
+    ///
+    /// This can occur when the key the record was encrypted with is no longer configured, or when
+    /// the encrypted payload fails authentication.
+    #[snafu(display("failed to decrypt record: {}", source))]
+    Decryption { source: EncryptionError },
+
     /// The reader detected that a data file contains a partially-written record.
     ///
     /// Records should never be partially written to a data file (we don't split records across data
@@ -145,6 +153,7 @@ where
             ReaderError::Checksum { .. } => "checksum_mismatch",
             ReaderError::Decode { .. } => "decode_failed",
             ReaderError::Incompatible { .. } => "incompatible_record_version",
+            ReaderError::Decryption { .. } => "decryption_failed",
             ReaderError::PartialWrite => "partial_write",
             ReaderError::EmptyRecord => "empty_record",
         }
@@ -160,6 +169,7 @@ where
             | ReaderError::Checksum { .. }
             | ReaderError::Decode { .. }
             | ReaderError::Incompatible { .. }
+            | ReaderError::Decryption { .. }
             | ReaderError::PartialWrite => Some(BufferReadError { error_code, error }),
         }
     }
@@ -199,6 +209,7 @@ pub(super) struct RecordReader<R, T> {
     reader: BufReader<R>,
     aligned_buf: AlignedVec,
     checksummer: Hasher,
+    encryption: Option<Arc<RecordCipher>>,
     current_record_id: u64,
     _t: PhantomData<T>,
 }
@@ -212,11 +223,14 @@ where
     ///
     /// Internally, the reader is wrapped in a [`BufReader`], so callers should not pass in an
     /// already buffered reader.
-    pub fn new(reader: R) -> Self {
+    ///
+    /// Encrypted records are decrypted with the given cipher.
+    pub fn new(reader: R, encryption: Option<Arc<RecordCipher>>) -> Self {
         Self {
             reader: BufReader::with_capacity(256 * 1024, reader),
             aligned_buf: AlignedVec::new(),
             checksummer: create_crc32c_hasher(),
+            encryption,
             current_record_id: 0,
             _t: PhantomData,
         }
@@ -374,7 +388,7 @@ where
         // - `try_next_record` does all the archive checks, checksum validation, etc
         let record = unsafe { archived_root::<Record<'_>>(&self.aligned_buf) };
 
-        decode_record_payload(record)
+        decode_record_payload(record, self.encryption.as_deref())
     }
 }
 
@@ -788,7 +802,10 @@ where
                 "Opened data file for reading."
             );
 
-            self.reader = Some(RecordReader::new(data_file));
+            self.reader = Some(RecordReader::new(
+                data_file,
+                self.ledger.config().encryption.clone(),
+            ));
             return Ok(());
         }
     }
@@ -854,7 +871,10 @@ where
                     let record = try_as_record_archive(data_file_mmap.as_ref())
                         .expect("record was already validated");
 
-                    let Ok(item) = decode_record_payload::<T>(record) else {
+                    let Ok(item) = decode_record_payload::<T>(
+                        record,
+                        self.ledger.config().encryption.as_deref(),
+                    ) else {
                         // If there's an error decoding the item, just fall back to the slow path,
                         // because this file might actually be where we left off, so we don't want
                         // to incorrectly skip ahead or anything.
@@ -1134,12 +1154,18 @@ where
 
 pub(crate) fn decode_record_payload<T: Bufferable>(
     record: &ArchivedRecord<'_>,
+    encryption: Option<&RecordCipher>,
 ) -> Result<T, ReaderError<T>> {
     // Try and convert the raw record metadata into the true metadata type used by `T`, and then
     // also verify that `T` is able to decode records with the metadata used for this record in particular.
-    let metadata = T::Metadata::from_u32(record.metadata()).ok_or(ReaderError::Incompatible {
-        reason: format!("invalid metadata for {}", std::any::type_name::<T>()),
-    })?;
+    //
+    // Encrypted records are flagged in their metadata, so we strip the flag first.
+    let encrypted = record.metadata() & ENCRYPTED_RECORD_FLAG != 0;
+    let metadata = T::Metadata::from_u32(record.metadata() & !ENCRYPTED_RECORD_FLAG).ok_or(
+        ReaderError::Incompatible {
+            reason: format!("invalid metadata for {}", std::any::type_name::<T>()),
+        },
+    )?;
 
     if !T::can_decode(metadata) {
         return Err(ReaderError::Incompatible {
@@ -1150,6 +1176,14 @@ pub(crate) fn decode_record_payload<T: Bufferable>(
         });
     }
 
-    // Now we can finally try decoding.
-    T::decode(metadata, record.payload()).context(DecodeSnafu)
+    // Now we can finally try decoding, decrypting the payload first if need be.
+    if encrypted {
+        let payload = encryption
+            .ok_or(EncryptionError::NotConfigured)
+            .and_then(|cipher| cipher.open(record.id(), record.metadata(), record.payload()))
+            .context(DecryptionSnafu)?;
+        T::decode(metadata, &payload[..]).context(DecodeSnafu)
+    } else {
+        T::decode(metadata, record.payload()).context(DecodeSnafu)
+    }
 }
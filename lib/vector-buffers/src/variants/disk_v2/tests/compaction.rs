@@ -0,0 +1,144 @@
+use tokio::fs;
+
+use super::{create_default_buffer_v2, read_next_some};
+use crate::{
+    assert_buffer_is_empty, assert_reader_writer_v2_file_positions,
+    test::{SizedRecord, acknowledge, with_temp_dir},
+    variants::disk_v2::compact_disk_buffer,
+};
+
+#[tokio::test]
+async fn writer_rolls_over_drained_data_file_when_compaction_requested() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Create a regular buffer, no customizations required.
+            let (mut writer, mut reader, ledger) = create_default_buffer_v2(data_dir).await;
+            assert_buffer_is_empty!(ledger);
+
+            // Write and fully process a record, which leaves the first data file drained.
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+
+            let first_read = read_next_some(&mut reader).await;
+            acknowledge(first_read).await;
+
+            let first_data_file_path = ledger.get_current_writer_data_file_path();
+            assert!(fs::try_exists(&first_data_file_path).await.unwrap());
+
+            // Request a compaction, which should make the writer roll over to the next data file
+            // on its next write, despite the current data file not being full.
+            ledger.request_compaction();
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            assert_reader_writer_v2_file_positions!(ledger, 0, 1);
+
+            // Once the reader moves on to the next data file, the drained one gets deleted.
+            let second_read = read_next_some(&mut reader).await;
+            assert_eq!(second_read, SizedRecord::new(64));
+            assert_reader_writer_v2_file_positions!(ledger, 1, 1);
+            assert!(!fs::try_exists(&first_data_file_path).await.unwrap());
+
+            acknowledge(second_read).await;
+            writer.close();
+            assert_eq!(reader.next().await.expect("read should not fail"), None);
+            assert_buffer_is_empty!(ledger);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn writer_ignores_compaction_request_for_empty_data_file() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, _reader, ledger) =
+                create_default_buffer_v2::<_, SizedRecord>(data_dir).await;
+
+            // Nothing has been written to the current data file yet, so there's nothing to reclaim.
+            ledger.request_compaction();
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            assert_reader_writer_v2_file_positions!(ledger, 0, 0);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn offline_compaction_deletes_drained_and_orphaned_data_files() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, ledger) = create_default_buffer_v2(data_dir.clone()).await;
+
+            // Write and fully process a record, which leaves the first data file drained.
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+
+            let first_read = read_next_some(&mut reader).await;
+            acknowledge(first_read).await;
+            writer.close();
+            assert_eq!(reader.next().await.expect("read should not fail"), None);
+            assert_buffer_is_empty!(ledger);
+            ledger.flush().expect("should not fail to flush ledger");
+
+            let drained_data_file_path = ledger.get_data_file_path(0);
+            let drained_data_file_len = fs::metadata(&drained_data_file_path).await.unwrap().len();
+
+            // Leave behind a data file that the ledger isn't tracking anymore.
+            let orphaned_data_file_path = ledger.get_data_file_path(3);
+            fs::write(&orphaned_data_file_path, [0; 128]).await.unwrap();
+
+            // The buffer is still in use, so we shouldn't be able to compact it.
+            assert!(compact_disk_buffer(&data_dir).await.is_err());
+
+            drop(writer);
+            drop(reader);
+            drop(ledger);
+            tokio::task::yield_now().await;
+
+            let summary = compact_disk_buffer(&data_dir)
+                .await
+                .expect("compaction should not fail");
+            assert_eq!(summary.data_files_deleted, 2);
+            assert_eq!(summary.reclaimed_bytes, drained_data_file_len + 128);
+            assert!(!fs::try_exists(&drained_data_file_path).await.unwrap());
+            assert!(!fs::try_exists(&orphaned_data_file_path).await.unwrap());
+
+            // The buffer should still be usable after being compacted.
+            let (mut writer, mut reader, ledger) = create_default_buffer_v2(data_dir).await;
+            assert_buffer_is_empty!(ledger);
+
+            writer
+                .write_record(SizedRecord::new(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+
+            let read = read_next_some(&mut reader).await;
+            assert_eq!(read, SizedRecord::new(64));
+            acknowledge(read).await;
+            writer.close();
+            assert_eq!(reader.next().await.expect("read should not fail"), None);
+            assert_buffer_is_empty!(ledger);
+        }
+    })
+    .await;
+}
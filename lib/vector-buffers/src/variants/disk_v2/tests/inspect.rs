@@ -0,0 +1,72 @@
+use super::{create_default_buffer_v2, read_next_some};
+use crate::{
+    test::{SizedRecord, acknowledge, with_temp_dir},
+    variants::disk_v2::{InspectionError, disk_buffer_status, read_disk_buffer},
+};
+
+#[tokio::test]
+async fn inspect_reports_unacknowledged_records_while_buffer_in_use() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, _ledger) =
+                create_default_buffer_v2(data_dir.clone()).await;
+
+            for size in [32, 48, 64, 80, 96] {
+                writer
+                    .write_record(SizedRecord::new(size))
+                    .await
+                    .expect("write should not fail");
+            }
+            writer.flush().await.expect("writer flush should not fail");
+
+            // Read and acknowledge the first record, and read, but don't acknowledge, the second.
+            let first_read = read_next_some(&mut reader).await;
+            acknowledge(first_read).await;
+            let second_read = read_next_some(&mut reader).await;
+            assert_eq!(second_read, SizedRecord::new(48));
+
+            // Inspection doesn't need the buffer lock, which is still held by our buffer.
+            let status = disk_buffer_status(&data_dir)
+                .await
+                .expect("status should not fail");
+            assert_eq!(status.buffered_events, 4);
+            assert_eq!(status.data_files, 1);
+
+            let records = read_disk_buffer::<SizedRecord>(&data_dir, None, 1, 2)
+                .await
+                .expect("inspection should not fail");
+            assert_eq!(records.status, status);
+            assert_eq!(records.buffered_records, 4);
+            assert_eq!(records.head, vec![SizedRecord::new(48)]);
+            assert_eq!(
+                records.tail,
+                vec![SizedRecord::new(80), SizedRecord::new(96)]
+            );
+            assert_eq!(records.undecodable_records, 0);
+            assert_eq!(records.corrupted_data_files, 0);
+
+            // When asking for more records than are buffered, each record is only returned once.
+            let records = read_disk_buffer::<SizedRecord>(&data_dir, None, 3, 3)
+                .await
+                .expect("inspection should not fail");
+            assert_eq!(records.head.len(), 3);
+            assert_eq!(records.tail, vec![SizedRecord::new(96)]);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn inspect_fails_without_ledger() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let result = disk_buffer_status(&data_dir).await;
+            assert!(matches!(result, Err(InspectionError::NotFound { .. })));
+        }
+    })
+    .await;
+}
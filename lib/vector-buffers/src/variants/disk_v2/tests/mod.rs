@@ -24,7 +24,9 @@ type FilesystemUnderTest = ProductionFilesystem;
 
 mod acknowledgements;
 mod basic;
+mod compaction;
 mod initialization;
+mod inspect;
 mod invariants;
 mod known_errors;
 mod model;
@@ -52,6 +52,9 @@ pub fn arb_buffer_config() -> impl Strategy<Value = DiskBufferConfig<TestFilesys
                 // readers to make progress, and we're not testing anything about whether or not the
                 // ledger makes it to disk durably.
                 .flush_interval(Duration::from_secs(10))
+                // The model doesn't account for the writer rolling over to a new data file before
+                // the current one is full.
+                .compaction_interval(None)
                 .filesystem(TestFilesystem::default())
         })
         .prop_filter_map(
@@ -1,17 +1,31 @@
-use std::io::Cursor;
+use std::{io::Cursor, sync::Arc};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
 
 use crate::{
+    config::DiskBufferEncryption,
     test::SizedRecord,
-    variants::disk_v2::{reader::RecordReader, writer::RecordWriter},
+    variants::disk_v2::{ReaderError, RecordCipher, reader::RecordReader, writer::RecordWriter},
 };
 
+fn cipher(key: u8, previous_keys: &[u8]) -> Arc<RecordCipher> {
+    let config = DiskBufferEncryption {
+        key: STANDARD.encode([key; 32]).into(),
+        previous_keys: previous_keys
+            .iter()
+            .map(|key| STANDARD.encode([*key; 32]).into())
+            .collect(),
+    };
+    Arc::new(RecordCipher::from_config(&config).expect("key should be valid"))
+}
+
 #[tokio::test]
 async fn roundtrip_through_record_writer_and_record_reader() {
     // Create a duplex stream that's more than big enough to ship a record through.
     let (writer_io, reader_io) = tokio::io::duplex(4096);
 
-    let mut record_writer = RecordWriter::new(writer_io, 0, 16_384, u64::MAX, 2048);
-    let mut record_reader = RecordReader::new(reader_io);
+    let mut record_writer = RecordWriter::new(writer_io, 0, 16_384, u64::MAX, 2048, None);
+    let mut record_reader = RecordReader::new(reader_io, None);
 
     let record = SizedRecord::new(73);
 
@@ -42,10 +56,62 @@ async fn roundtrip_through_record_writer_and_record_reader() {
 async fn record_reader_always_returns_none_when_no_data() {
     let reader_io = Cursor::new(Vec::new());
 
-    let mut record_reader = RecordReader::<_, SizedRecord>::new(reader_io);
+    let mut record_reader = RecordReader::<_, SizedRecord>::new(reader_io, None);
     let read_token = record_reader
         .try_next_record(false)
         .await
         .expect("read should not fail");
     assert!(read_token.is_none());
 }
+
+#[tokio::test]
+async fn roundtrip_encrypted_records_across_key_rotation() {
+    let mut data_file = Vec::new();
+    for (id, cipher) in [
+        (1, None),
+        (2, Some(cipher(1, &[]))),
+        (3, Some(cipher(2, &[1]))),
+    ] {
+        let mut record_writer =
+            RecordWriter::new(Cursor::new(Vec::new()), 0, 16_384, u64::MAX, 2048, cipher);
+        record_writer
+            .write_record(id, SizedRecord::new(73))
+            .await
+            .expect("write should not fail");
+        record_writer.flush().await.expect("flush should not fail");
+        data_file.extend_from_slice(record_writer.get_ref().get_ref());
+    }
+
+    // Plain records, and records encrypted with either the current or a previous key, can be read.
+    let mut record_reader =
+        RecordReader::new(Cursor::new(data_file.clone()), Some(cipher(2, &[1])));
+    for id in 1..=3 {
+        let read_token = record_reader
+            .try_next_record(false)
+            .await
+            .expect("read should not fail")
+            .expect("record should be present");
+        assert_eq!(id, read_token.record_id());
+        let record = record_reader
+            .read_record(read_token)
+            .expect("read should not fail");
+        assert_eq!(SizedRecord::new(73), record);
+    }
+
+    // Encrypted records can't be read once their key is no longer configured.
+    let mut record_reader = RecordReader::<_, SizedRecord>::new(Cursor::new(data_file), None);
+    let mut read_results = Vec::new();
+    for _ in 1..=2 {
+        let read_token = record_reader
+            .try_next_record(false)
+            .await
+            .expect("read should not fail")
+            .expect("record should be present");
+        read_results.push(record_reader.read_record(read_token));
+    }
+    assert_eq!(read_results[0], Ok(SizedRecord::new(73)));
+    assert!(matches!(
+        read_results[1],
+        Err(ReaderError::Decryption { .. })
+    ));
+}
@@ -25,6 +25,7 @@ use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use super::{
     common::{DiskBufferConfig, create_crc32c_hasher},
+    encryption::{ENCRYPTED_RECORD_FLAG, ENCRYPTION_OVERHEAD, EncryptionError, RecordCipher},
     io::Filesystem,
     ledger::Ledger,
     record::{Record, RecordStatus, validate_record_archive},
@@ -95,6 +96,10 @@ where
         source: <T as Encodable>::EncodeError,
     },
 
+    /// The writer failed to encrypt the encoded record.
+    #[snafu(display("failed to encrypt record: {}", source))]
+    FailedToEncrypt { source: EncryptionError },
+
     /// The writer failed to serialize the record.
     ///
     /// As records are encoded and then wrapped in a container which carries metadata about the size
@@ -360,6 +365,8 @@ impl<W: fmt::Debug> fmt::Debug for TrackingBufWriter<W> {
 pub(super) struct RecordWriter<W, T> {
     writer: TrackingBufWriter<W>,
     encode_buf: Vec<u8>,
+    encrypt_buf: Vec<u8>,
+    encryption: Option<Arc<RecordCipher>>,
     ser_buf: AlignedVec,
     ser_scratch: AlignedVec,
     checksummer: Hasher,
@@ -384,6 +391,7 @@ where
         write_buffer_size: usize,
         max_data_file_size: u64,
         max_record_size: usize,
+        encryption: Option<Arc<RecordCipher>>,
     ) -> Self {
         // These should also be getting checked at a higher level, but we're double-checking them here to be absolutely sure.
         let max_record_size_converted = u64::try_from(max_record_size)
@@ -405,11 +413,20 @@ where
         // This could lead to us reducing the encode buffer size limit by slightly more than necessary, since
         // `RECORD_HEADER_LEN` might be overaligned compared to what it would be necessary when we look at the
         // encoded/serialized record... but that's OK, but it's only going to differ by 8 bytes at most.
-        let max_record_size = max_record_size - RECORD_HEADER_LEN;
+        //
+        // Likewise, encrypting a record adds the key ID, nonce, and tag to its payload.
+        let encryption_overhead = if encryption.is_some() {
+            ENCRYPTION_OVERHEAD
+        } else {
+            0
+        };
+        let max_record_size = max_record_size - RECORD_HEADER_LEN - encryption_overhead;
 
         Self {
             writer: TrackingBufWriter::with_capacity(write_buffer_size, writer),
             encode_buf: Vec::with_capacity(16_384),
+            encrypt_buf: Vec::new(),
+            encryption,
             ser_buf: AlignedVec::with_capacity(16_384),
             ser_scratch: AlignedVec::with_capacity(16_384),
             checksummer: create_crc32c_hasher(),
@@ -480,9 +497,26 @@ where
             });
         }
 
-        let metadata = T::get_metadata().into_u32();
-        let wrapped_record =
-            Record::with_checksum(id, metadata, &self.encode_buf, &self.checksummer);
+        let mut metadata = T::get_metadata().into_u32();
+        debug_assert_eq!(
+            metadata & ENCRYPTED_RECORD_FLAG,
+            0,
+            "record metadata must not use the encryption flag"
+        );
+
+        // When encryption is enabled, the encoded record is encrypted, and it's the encrypted
+        // payload which gets checksummed and written to disk.
+        let payload = match &self.encryption {
+            Some(cipher) => {
+                metadata |= ENCRYPTED_RECORD_FLAG;
+                cipher
+                    .seal(id, metadata, &self.encode_buf, &mut self.encrypt_buf)
+                    .context(FailedToEncryptSnafu)?;
+                &self.encrypt_buf[..]
+            }
+            None => &self.encode_buf[..],
+        };
+        let wrapped_record = Record::with_checksum(id, metadata, payload, &self.checksummer);
 
         // Push 8 dummy bytes where our length delimiter will sit.  We'll fix this up after
         // serialization.  Notably, `AlignedSerializer` will report the serializer position as
@@ -665,15 +699,8 @@ where
             }
         })?;
 
-        // Now we can actually decode it as `T`.
-        let record_metadata = T::Metadata::from_u32(wrapped_record.metadata()).ok_or(
-            WriterError::InconsistentState {
-                reason: "failed to decode record metadata immediately after encoding it"
-                    .to_string(),
-            },
-        )?;
-
-        T::decode(record_metadata, wrapped_record.payload()).map_err(|_| {
+        // Now we can actually decode it as `T`, decrypting it first if need be.
+        decode_record_payload(wrapped_record, self.encryption.as_deref()).map_err(|_| {
             WriterError::InconsistentState {
                 reason: "failed to decode record immediately after encoding it".to_string(),
             }
@@ -882,11 +909,10 @@ where
                 // next writer record ID should be.
                 let record = try_as_record_archive(data_file_mmap.as_ref())
                     .expect("record was already validated");
-                let item = decode_record_payload::<T>(record).map_err(|e| {
-                    WriterError::FailedToValidate {
+                let item = decode_record_payload::<T>(record, self.config.encryption.as_deref())
+                    .map_err(|e| WriterError::FailedToValidate {
                         reason: e.to_string(),
-                    }
-                })?;
+                    })?;
 
                 // Since we have a valid record, checksum and all, see if the writer record ID
                 // in the ledger lines up with the record ID we have here.  Specifically, the record
@@ -1026,15 +1052,26 @@ where
         // need to skip to the next file, we honor that here.
         let mut should_open_next = self.should_skip();
         if self.writer.is_some() {
-            if self.can_write() {
+            // The compactor may have asked us to move on from the current data file, even though it
+            // isn't full, so that the reader can delete it now that it has been fully processed.
+            let should_compact = self.data_file_size > 0 && self.ledger.take_compaction_request();
+            if self.can_write() && !should_compact {
                 return Ok(());
             }
 
-            // Our current data file is full, so we need to open a new one.  Signal to the loop
-            // that we we want to try and open the next file, and not the current file,
-            // essentially to avoid marking the writer as already having moved on to the next
-            // file before we're sure it isn't already an existing file on disk waiting to be
-            // read.
+            if should_compact {
+                debug!(
+                    data_file_size = self.data_file_size,
+                    "Compacting drained data file. Rolling to the next data file."
+                );
+                self.ledger.track_compaction(self.data_file_size);
+            }
+
+            // Our current data file is full, or being compacted, so we need to open a new one.
+            // Signal to the loop that we we want to try and open the next file, and not the
+            // current file, essentially to avoid marking the writer as already having moved on to
+            // the next file before we're sure it isn't already an existing file on disk waiting to
+            // be read.
             //
             // We still flush ourselves to disk, etc, to make sure all of the data is there.
             should_open_next = true;
@@ -1129,6 +1166,7 @@ where
                     self.config.write_buffer_size,
                     self.config.max_data_file_size,
                     self.config.max_record_size,
+                    self.config.encryption.clone(),
                 ));
                 self.data_file_size = data_file_size;
 
@@ -1,5 +1,8 @@
 pub(crate) mod disk_v2;
-pub use disk_v2::DiskV2Buffer;
+pub use disk_v2::{
+    BufferedRecords, CompactionError, CompactionSummary, DiskBufferStatus, DiskV2Buffer,
+    InspectionError, compact_disk_buffer, disk_buffer_status, read_disk_buffer,
+};
 
 pub(crate) mod in_memory;
 pub use in_memory::MemoryBuffer;
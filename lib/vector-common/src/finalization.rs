@@ -3,7 +3,14 @@
 //! as it flows through transforms, being duplicated and merged, and
 //! then report its status when the last copy is delivered or dropped.
 
-use std::{cmp, future::Future, mem, pin::Pin, sync::Arc, task::Poll};
+use std::{
+    cmp,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::Poll,
+};
 
 use crossbeam_utils::atomic::AtomicCell;
 use futures::future::FutureExt;
@@ -86,6 +93,15 @@ impl EventFinalizers {
         }
     }
 
+    /// Updates the status of all event finalizers in the collection, and records why the events
+    /// weren't delivered on the batches that keep track of it.
+    pub fn update_failure(&self, status: EventStatus, failure: &DeliveryFailure) {
+        for finalizer in &self.0 {
+            finalizer.update_status(status);
+            finalizer.batch.record_failure(failure);
+        }
+    }
+
     /// Consumes all event finalizers and updates their underlying batches immediately.
     pub fn update_sources(&mut self) {
         let finalizers = mem::take(&mut self.0);
@@ -188,6 +204,39 @@ impl BatchStatusReceiver {
     }
 }
 
+/// Why the events of a batch weren't delivered, as reported by the sink that failed to send them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryFailure {
+    /// The error returned for the request holding the events, or the description of its response.
+    pub message: String,
+    /// The status code of the response to the request holding the events, if there is one.
+    pub status_code: Option<u16>,
+}
+
+/// The first failure recorded for the events of a batch, shared between its notifier and the
+/// receiver of its status, so that it can be read once the status is received.
+#[derive(Clone, Debug, Default)]
+pub struct BatchFailure(Arc<Mutex<Option<DeliveryFailure>>>);
+
+impl BatchFailure {
+    /// Takes the recorded failure, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned.
+    #[must_use]
+    pub fn take(&self) -> Option<DeliveryFailure> {
+        self.0.lock().expect("mutex should not be poisoned").take()
+    }
+
+    fn record(&self, failure: &DeliveryFailure) {
+        self.0
+            .lock()
+            .expect("mutex should not be poisoned")
+            .get_or_insert_with(|| failure.clone());
+    }
+}
+
 /// A batch notifier contains the status of the current batch along with
 /// a one-shot notifier to send that status back to the source. It is
 /// shared among all events of a batch.
@@ -198,9 +247,23 @@ impl BatchNotifier {
     /// Creates a new `BatchNotifier` along with the receiver used to await its finalization status.
     #[must_use]
     pub fn new_with_receiver() -> (Self, BatchStatusReceiver) {
+        Self::new_with_failure(None)
+    }
+
+    /// Creates a new `BatchNotifier` along with the receiver used to await its finalization status,
+    /// and the failure reported by the sink that couldn't deliver its events, if any.
+    #[must_use]
+    pub fn new_with_failure_receiver() -> (Self, BatchStatusReceiver, BatchFailure) {
+        let failure = BatchFailure::default();
+        let (batch, receiver) = Self::new_with_failure(Some(failure.clone()));
+        (batch, receiver, failure)
+    }
+
+    fn new_with_failure(failure: Option<BatchFailure>) -> (Self, BatchStatusReceiver) {
         let (sender, receiver) = oneshot::channel();
         let notifier = OwnedBatchNotifier {
             status: AtomicCell::new(BatchStatus::Delivered),
+            failure,
             notifier: Some(sender),
         };
         (Self(Arc::new(notifier)), BatchStatusReceiver(receiver))
@@ -250,12 +313,20 @@ impl BatchNotifier {
                 .unwrap_or_else(|_| unreachable!());
         }
     }
+
+    /// Records why the events of the batch weren't delivered, if the notifier keeps track of it.
+    fn record_failure(&self, failure: &DeliveryFailure) {
+        if let Some(recorded) = &self.0.failure {
+            recorded.record(failure);
+        }
+    }
 }
 
 /// The non-shared data underlying the shared `BatchNotifier`
 #[derive(Debug)]
 pub struct OwnedBatchNotifier {
     status: AtomicCell<BatchStatus>,
+    failure: Option<BatchFailure>,
     notifier: Option<oneshot::Sender<BatchStatus>>,
 }
 
@@ -507,6 +578,21 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[test]
+    fn records_first_failure() {
+        let (batch, mut receiver, failure) = BatchNotifier::new_with_failure_receiver();
+        let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
+        let failure_with_status = |status_code| DeliveryFailure {
+            message: "Bad Request".to_owned(),
+            status_code: Some(status_code),
+        };
+        finalizer.update_failure(EventStatus::Rejected, &failure_with_status(400));
+        finalizer.update_failure(EventStatus::Rejected, &failure_with_status(422));
+        drop(finalizer);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+        assert_eq!(failure.take(), Some(failure_with_status(400)));
+    }
+
     fn make_finalizer() -> (EventFinalizers, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
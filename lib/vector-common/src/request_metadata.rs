@@ -263,6 +263,8 @@ pub struct RequestMetadata {
     ///
     /// This is akin to the bytes sent/received over the network, regardless of whether or not compression was used.
     request_wire_size: usize,
+    /// Hashes of the keys whose requests must be sent one at a time, in order, sorted and deduplicated.
+    ordering_keys: Vec<u64>,
 }
 
 impl RequestMetadata {
@@ -280,9 +282,22 @@ impl RequestMetadata {
             events_estimated_json_encoded_byte_size,
             request_encoded_size,
             request_wire_size,
+            ordering_keys: Vec::new(),
         }
     }
 
+    /// Sets the ordering keys of the request.
+    ///
+    /// The driver of the sink doesn't send the request while another request with one of the same
+    /// keys is in flight, or waiting to be sent before it.
+    #[must_use]
+    pub fn with_ordering_keys(mut self, mut ordering_keys: Vec<u64>) -> Self {
+        ordering_keys.sort_unstable();
+        ordering_keys.dedup();
+        self.ordering_keys = ordering_keys;
+        self
+    }
+
     #[must_use]
     pub const fn event_count(&self) -> usize {
         self.event_count
@@ -315,6 +330,11 @@ impl RequestMetadata {
         self.request_wire_size
     }
 
+    #[must_use]
+    pub fn ordering_keys(&self) -> &[u64] {
+        &self.ordering_keys
+    }
+
     /// Constructs a `RequestMetadata` by summation of the "batch" of `RequestMetadata` provided.
     #[must_use]
     pub fn from_batch<T: IntoIterator<Item = RequestMetadata>>(metadata_iter: T) -> Self {
@@ -332,6 +352,8 @@ impl<'a> Add<&'a RequestMetadata> for RequestMetadata {
 
     /// Adds the other `RequestMetadata` to this one.
     fn add(self, other: &'a Self::Output) -> Self::Output {
+        let mut ordering_keys = self.ordering_keys;
+        ordering_keys.extend_from_slice(&other.ordering_keys);
         Self::Output {
             event_count: self.event_count + other.event_count,
             events_byte_size: self.events_byte_size + other.events_byte_size,
@@ -339,7 +361,9 @@ impl<'a> Add<&'a RequestMetadata> for RequestMetadata {
                 + &other.events_estimated_json_encoded_byte_size,
             request_encoded_size: self.request_encoded_size + other.request_encoded_size,
             request_wire_size: self.request_wire_size + other.request_wire_size,
+            ordering_keys: Vec::new(),
         }
+        .with_ordering_keys(ordering_keys)
     }
 }
 
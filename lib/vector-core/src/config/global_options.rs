@@ -5,7 +5,7 @@ use vector_common::TimeZone;
 use vector_config::{configurable_component, impl_generate_config_from_default};
 
 use super::{
-    super::default_data_dir, AcknowledgementsConfig, LogSchema, Telemetry,
+    super::default_data_dir, AcknowledgementsConfig, InternalLogRateLimit, LogSchema, Telemetry,
     metrics_expiration::PerMetricSetExpiration, proxy::ProxyConfig,
 };
 use crate::serde::bool_or_struct;
@@ -139,6 +139,11 @@ pub struct GlobalOptions {
     /// the global default value, defined using `expire_metrics_secs`.
     #[serde(skip_serializing_if = "crate::serde::is_default")]
     pub expire_metrics_per_metric_set: Option<Vec<PerMetricSetExpiration>>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    #[configurable(metadata(docs::common = false, docs::required = false))]
+    pub internal_log_rate_limit: InternalLogRateLimit,
 }
 
 impl_generate_config_from_default!(GlobalOptions);
@@ -244,6 +249,26 @@ impl GlobalOptions {
             errors.push("conflicting values for 'expire_metrics_secs' found".to_owned());
         }
 
+        if conflicts(
+            self.internal_log_rate_limit.window_secs.as_ref(),
+            with.internal_log_rate_limit.window_secs.as_ref(),
+        ) {
+            errors.push(
+                "conflicting values for 'internal_log_rate_limit.window_secs' found".to_owned(),
+            );
+        }
+
+        for (component, window_secs) in &with.internal_log_rate_limit.components {
+            if conflicts(
+                self.internal_log_rate_limit.components.get(component),
+                Some(window_secs),
+            ) {
+                errors.push(format!(
+                    "conflicting values for 'internal_log_rate_limit.components.{component}' found"
+                ));
+            }
+        }
+
         let data_dir = if self.data_dir.is_none() || self.data_dir == default_data_dir() {
             with.data_dir
         } else if with.data_dir != default_data_dir() && self.data_dir != with.data_dir {
@@ -275,6 +300,20 @@ impl GlobalOptions {
             (None, None) => None,
         };
 
+        let internal_log_rate_limit = InternalLogRateLimit {
+            window_secs: self
+                .internal_log_rate_limit
+                .window_secs
+                .or(with.internal_log_rate_limit.window_secs),
+            components: self
+                .internal_log_rate_limit
+                .components
+                .iter()
+                .chain(&with.internal_log_rate_limit.components)
+                .map(|(component, window_secs)| (component.clone(), *window_secs))
+                .collect(),
+        };
+
         if errors.is_empty() {
             Ok(Self {
                 data_dir,
@@ -287,6 +326,7 @@ impl GlobalOptions {
                 expire_metrics: self.expire_metrics.or(with.expire_metrics),
                 expire_metrics_secs: self.expire_metrics_secs.or(with.expire_metrics_secs),
                 expire_metrics_per_metric_set: merged_expire_metrics_per_metric_set,
+                internal_log_rate_limit,
             })
         } else {
             Err(errors)
@@ -448,6 +488,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merges_internal_log_rate_limit() {
+        let merge = |a: &str, b: &str| {
+            let a: GlobalOptions = toml::from_str(a).unwrap();
+            let b: GlobalOptions = toml::from_str(b).unwrap();
+            a.merge(b).map(|result| result.internal_log_rate_limit)
+        };
+
+        let merged = merge(
+            "internal_log_rate_limit.window_secs = 30",
+            "internal_log_rate_limit.components = { out = 60 }",
+        )
+        .unwrap();
+        assert_eq!(merged.window_secs, Some(30));
+        assert_eq!(merged.components.get("out"), Some(&60));
+
+        assert_eq!(
+            merge(
+                "internal_log_rate_limit.components = { out = 60, in = 0 }",
+                "internal_log_rate_limit.components = { out = 120 }",
+            ),
+            Err(vec![
+                "conflicting values for 'internal_log_rate_limit.components.out' found".into()
+            ])
+        );
+    }
+
     #[test]
     fn diff_detects_changed_keys() {
         let old = GlobalOptions {
@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use vector_config::configurable_component;
+
+/// Rate limiting of the internal logs.
+///
+/// The internal logs emitted repeatedly from the same place in the code, by the same component,
+/// are only emitted once per window. The number of logs suppressed is logged once the window
+/// expires.
+#[configurable_component]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct InternalLogRateLimit {
+    /// The window of the rate limit.
+    ///
+    /// Overrides the `--internal-log-rate-limit` command-line option. Set to `0` to disable the
+    /// rate limiting.
+    #[serde(skip_serializing_if = "crate::serde::is_default")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::examples = 30))]
+    pub window_secs: Option<u64>,
+
+    /// The windows of the rate limits of specific components, overriding the global one.
+    ///
+    /// Set the window of a component to `0` to disable the rate limiting of its logs.
+    #[serde(skip_serializing_if = "crate::serde::is_default")]
+    #[configurable(metadata(
+        docs::additional_props_description = "The window of the rate limit of the component, in seconds."
+    ))]
+    pub components: BTreeMap<String, u64>,
+}
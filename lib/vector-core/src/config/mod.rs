@@ -5,6 +5,7 @@ use bytes::Bytes;
 use chrono::{DateTime, Utc};
 
 mod global_options;
+mod internal_log_rate_limit;
 mod log_schema;
 pub(crate) mod metrics_expiration;
 pub mod output_id;
@@ -12,6 +13,7 @@ pub mod proxy;
 mod telemetry;
 
 pub use global_options::{GlobalOptions, WildcardMatching};
+pub use internal_log_rate_limit::InternalLogRateLimit;
 pub use log_schema::{LogSchema, init_log_schema, log_schema};
 use lookup::{PathPrefix, lookup_v2::ValuePath, path};
 pub use output_id::OutputId;
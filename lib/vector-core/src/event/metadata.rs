@@ -78,6 +78,12 @@ pub(super) struct Inner {
     /// An internal vector id that can be used to identify this event across all components.
     #[derivative(PartialEq = "ignore")]
     pub(crate) source_event_id: Option<Uuid>,
+
+    /// The id of the trace of the event, if it's sampled by a tracing session. This is kept by the
+    /// events derived from it, to follow its path through the topology.
+    #[serde(default, skip)]
+    #[derivative(PartialEq = "ignore")]
+    pub(crate) trace_id: Option<Uuid>,
 }
 
 /// Metric Origin metadata for submission to Datadog.
@@ -254,6 +260,7 @@ impl Default for Inner {
             dropped_fields: ObjectMap::new(),
             datadog_origin_metadata: None,
             source_event_id: Some(Uuid::now_v7()),
+            trace_id: None,
         }
     }
 }
@@ -331,6 +338,16 @@ impl EventMetadata {
         self
     }
 
+    /// Returns the id of the trace of the event, if it's traced.
+    pub fn trace_id(&self) -> Option<Uuid> {
+        self.0.trace_id
+    }
+
+    /// Sets the id of the trace of the event.
+    pub fn set_trace_id(&mut self, trace_id: Uuid) {
+        self.get_mut().trace_id = Some(trace_id);
+    }
+
     /// Replaces the existing `source_event_id` with the given one.
     #[must_use]
     pub fn with_source_event_id(mut self, source_event_id: Option<Uuid>) -> Self {
@@ -357,6 +374,11 @@ impl EventMetadata {
             }
             _ => {} // Keep the existing value.
         }
+
+        // Keep the trace of the event merged into this one if this isn't traced.
+        if inner.trace_id.is_none() {
+            inner.trace_id = other.trace_id;
+        }
     }
 
     /// Update the finalizer(s) status.
@@ -3,8 +3,8 @@ use std::{convert::TryInto, fmt::Debug, sync::Arc};
 pub use array::{EventArray, EventContainer, LogArray, MetricArray, TraceArray, into_event_stream};
 pub use estimated_json_encoded_size_of::EstimatedJsonEncodedSizeOf;
 pub use finalization::{
-    BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers, EventStatus,
-    Finalizable,
+    BatchFailure, BatchNotifier, BatchStatus, BatchStatusReceiver, DeliveryFailure, EventFinalizer,
+    EventFinalizers, EventStatus, Finalizable,
 };
 pub use log_event::LogEvent;
 pub use metadata::{DatadogMetricOriginMetadata, EventMetadata, WithMetadata};
@@ -38,6 +38,7 @@ mod ser;
 #[cfg(test)]
 mod test;
 mod trace;
+pub mod trace_sampling;
 pub mod util;
 #[cfg(feature = "vrl")]
 mod vrl_target;
@@ -0,0 +1,97 @@
+//! The sampling of the events traced through the topology.
+//!
+//! While at least one tracing session is active, the sources assign a trace ID to one in every N
+//! of the events they send, N being the smallest rate of the sessions. The trace ID is kept by the
+//! events derived from the traced ones, so that their path through the transforms can be followed.
+
+use std::{
+    num::NonZeroU64,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use uuid::Uuid;
+
+use super::EventMetadata;
+
+/// The rates of the active sessions.
+static RATES: Mutex<Vec<NonZeroU64>> = Mutex::new(Vec::new());
+
+/// The smallest rate of the active sessions, or zero if there are none.
+static RATE: AtomicU64 = AtomicU64::new(0);
+
+/// The number of events sent by the sources since the first session started.
+static SENT: AtomicU64 = AtomicU64::new(0);
+
+/// An active tracing session, which stops when dropped.
+#[derive(Debug)]
+pub struct TraceSampling {
+    rate: NonZeroU64,
+}
+
+impl TraceSampling {
+    /// Starts tracing one in every `rate` events sent by the sources.
+    pub fn start(rate: NonZeroU64) -> Self {
+        let mut rates = RATES.lock().expect("trace sampling rates poisoned");
+        rates.push(rate);
+        update_rate(&rates);
+        Self { rate }
+    }
+}
+
+impl Drop for TraceSampling {
+    fn drop(&mut self) {
+        let mut rates = RATES.lock().expect("trace sampling rates poisoned");
+        if let Some(index) = rates.iter().position(|&rate| rate == self.rate) {
+            rates.swap_remove(index);
+        }
+        update_rate(&rates);
+    }
+}
+
+fn update_rate(rates: &[NonZeroU64]) {
+    let rate = rates.iter().min().map_or(0, |rate| rate.get());
+    RATE.store(rate, Ordering::Release);
+}
+
+/// Assigns a trace ID to the event if it's sampled by the active sessions.
+pub fn sample(metadata: &mut EventMetadata) {
+    let rate = RATE.load(Ordering::Acquire);
+    if rate != 0
+        && metadata.trace_id().is_none()
+        && SENT.fetch_add(1, Ordering::Relaxed) % rate == 0
+    {
+        metadata.set_trace_id(Uuid::now_v7());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_while_a_session_is_active() {
+        let traced = |count| {
+            (0..count)
+                .filter(|_| {
+                    let mut metadata = EventMetadata::default();
+                    sample(&mut metadata);
+                    metadata.trace_id().is_some()
+                })
+                .count()
+        };
+
+        assert_eq!(traced(10), 0);
+
+        let session = TraceSampling::start(NonZeroU64::new(5).unwrap());
+        let all = TraceSampling::start(NonZeroU64::MIN);
+        assert_eq!(traced(10), 10);
+        drop(all);
+        assert_eq!(traced(10), 2);
+        drop(session);
+
+        assert_eq!(traced(10), 0);
+    }
+}
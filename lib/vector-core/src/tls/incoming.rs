@@ -24,7 +24,7 @@ use tonic::transport::{Certificate, server::Connected};
 
 use super::{
     CreateAcceptorSnafu, HandshakeSnafu, IncomingListenerSnafu, MaybeTlsSettings, MaybeTlsStream,
-    SslBuildSnafu, TcpBindSnafu, TlsError, TlsSettings,
+    ProxyProtocolSnafu, SslBuildSnafu, TcpBindSnafu, TlsError, TlsSettings, proxy_protocol,
 };
 use crate::tcp::{self, TcpKeepaliveConfig};
 
@@ -54,6 +54,7 @@ impl MaybeTlsSettings {
             listener,
             acceptor,
             origin_filter: None,
+            proxy_protocol: false,
         })
     }
 
@@ -73,6 +74,7 @@ impl MaybeTlsSettings {
             listener,
             acceptor,
             origin_filter: Some(allow_origin),
+            proxy_protocol: false,
         })
     }
 }
@@ -81,6 +83,7 @@ pub struct MaybeTlsListener {
     listener: TcpListener,
     acceptor: Option<SslAcceptor>,
     origin_filter: Option<Vec<IpNet>>,
+    proxy_protocol: bool,
 }
 
 impl MaybeTlsListener {
@@ -90,7 +93,12 @@ impl MaybeTlsListener {
             .accept()
             .await
             .map(|(stream, peer_addr)| {
-                MaybeTlsIncomingStream::new(stream, peer_addr, self.acceptor.clone())
+                MaybeTlsIncomingStream::new(
+                    stream,
+                    peer_addr,
+                    self.acceptor.clone(),
+                    self.proxy_protocol,
+                )
             })
             .context(IncomingListenerSnafu)?;
 
@@ -175,6 +183,16 @@ impl MaybeTlsListener {
         self.origin_filter = allowlist;
         self
     }
+
+    /// Reads the PROXY protocol header at the start of the connections, before their TLS
+    /// handshake, and uses the address of the client it holds as their peer address.
+    ///
+    /// The allowlist is still checked against the address of the proxy.
+    #[must_use]
+    pub const fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
 }
 
 impl From<TcpListener> for MaybeTlsListener {
@@ -183,6 +201,7 @@ impl From<TcpListener> for MaybeTlsListener {
             listener,
             acceptor: None,
             origin_filter: None,
+            proxy_protocol: false,
         }
     }
 }
@@ -191,13 +210,18 @@ pub struct MaybeTlsIncomingStream<S> {
     state: StreamState<S>,
     // BoxFuture doesn't allow access to the inner stream, but users
     // of MaybeTlsIncomingStream want access to the peer address while
-    // still handshaking, so we have to cache it here.
+    // still handshaking, so we have to cache it here. It is replaced by the address of the
+    // client once the PROXY protocol header is read.
     peer_addr: SocketAddr,
 }
 
+/// The accepted stream, and the address of the client given by its PROXY protocol header.
+type AcceptFuture<S> =
+    BoxFuture<'static, Result<(MaybeTlsStream<S>, Option<SocketAddr>), TlsError>>;
+
 enum StreamState<S> {
     Accepted(MaybeTlsStream<S>),
-    Accepting(BoxFuture<'static, Result<SslStream<S>, TlsError>>),
+    Accepting(AcceptFuture<S>),
     AcceptError(String),
     Closed,
 }
@@ -247,33 +271,51 @@ impl<S> MaybeTlsIncomingStream<S> {
 
 impl MaybeTlsIncomingStream<TcpStream> {
     pub(super) fn new(
-        stream: TcpStream,
+        mut stream: TcpStream,
         peer_addr: SocketAddr,
         acceptor: Option<SslAcceptor>,
+        proxy_protocol: bool,
     ) -> Self {
-        let state = match acceptor {
-            Some(acceptor) => StreamState::Accepting(
+        let state = match (acceptor, proxy_protocol) {
+            (None, false) => StreamState::Accepted(MaybeTlsStream::Raw(stream)),
+            (acceptor, proxy_protocol) => StreamState::Accepting(
                 async move {
+                    let client_addr = if proxy_protocol {
+                        proxy_protocol::read_header(&mut stream)
+                            .await
+                            .context(ProxyProtocolSnafu)?
+                    } else {
+                        None
+                    };
+                    let Some(acceptor) = acceptor else {
+                        return Ok((MaybeTlsStream::Raw(stream), client_addr));
+                    };
                     let ssl = Ssl::new(acceptor.context()).context(SslBuildSnafu)?;
                     let mut stream = SslStream::new(ssl, stream).context(SslBuildSnafu)?;
                     Pin::new(&mut stream)
                         .accept()
                         .await
                         .context(HandshakeSnafu)?;
-                    Ok(stream)
+                    Ok((MaybeTlsStream::Tls(stream), client_addr))
                 }
                 .boxed(),
             ),
-            None => StreamState::Accepted(MaybeTlsStream::Raw(stream)),
         };
         Self { state, peer_addr }
     }
 
+    fn accepted(&mut self, stream: MaybeTlsStream<TcpStream>, client_addr: Option<SocketAddr>) {
+        self.state = StreamState::Accepted(stream);
+        if let Some(client_addr) = client_addr {
+            self.peer_addr = client_addr;
+        }
+    }
+
     // Explicit handshake method
     pub async fn handshake(&mut self) -> crate::tls::Result<()> {
         if let StreamState::Accepting(fut) = &mut self.state {
-            let stream = fut.await?;
-            self.state = StreamState::Accepted(MaybeTlsStream::Tls(stream));
+            let (stream, client_addr) = fut.await?;
+            self.accepted(stream, client_addr);
         }
 
         Ok(())
@@ -317,8 +359,8 @@ impl MaybeTlsIncomingStream<TcpStream> {
             return match &mut this.state {
                 StreamState::Accepted(stream) => poll_fn(Pin::new(stream), cx),
                 StreamState::Accepting(fut) => match std::task::ready!(fut.as_mut().poll(cx)) {
-                    Ok(stream) => {
-                        this.state = StreamState::Accepted(MaybeTlsStream::Tls(stream));
+                    Ok((stream, client_addr)) => {
+                        this.accepted(stream, client_addr);
                         continue;
                     }
                     Err(error) => {
@@ -366,8 +408,8 @@ impl AsyncWrite for MaybeTlsIncomingStream<TcpStream> {
                 poll_result => poll_result,
             },
             StreamState::Accepting(fut) => match std::task::ready!(fut.as_mut().poll(cx)) {
-                Ok(stream) => {
-                    this.state = StreamState::Accepted(MaybeTlsStream::Tls(stream));
+                Ok((stream, client_addr)) => {
+                    this.accepted(stream, client_addr);
                     Poll::Pending
                 }
                 Err(error) => {
@@ -15,6 +15,7 @@ use crate::tcp::{self, TcpKeepaliveConfig};
 mod incoming;
 mod maybe_tls;
 mod outgoing;
+mod proxy_protocol;
 mod settings;
 
 pub use incoming::{CertificateMetadata, MaybeTlsIncomingStream, MaybeTlsListener};
@@ -83,6 +84,8 @@ pub enum TlsError {
     Handshake { source: openssl::ssl::Error },
     #[snafu(display("Incoming listener failed: {}", source))]
     IncomingListener { source: tokio::io::Error },
+    #[snafu(display("Could not read the PROXY protocol header: {}", source))]
+    ProxyProtocol { source: tokio::io::Error },
     #[snafu(display("Creating the TLS acceptor failed: {}", source))]
     CreateAcceptor { source: ErrorStack },
     #[snafu(display("Error building SSL context: {}", source))]
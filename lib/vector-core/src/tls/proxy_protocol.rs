@@ -0,0 +1,172 @@
+//! The header of the versions 1 and 2 of the [PROXY protocol][proxy_protocol], sent by load
+//! balancers at the start of the connections to give the address of the original client.
+//!
+//! The header is read without reading any of the bytes after it, which can be the start of a TLS
+//! handshake.
+//!
+//! [proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use tokio::io::{self, AsyncRead, AsyncReadExt};
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+const V1_MAX_LENGTH: usize = 107;
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// Reads the header at the start of the stream, returning the address of the client, if the
+/// connection was proxied.
+pub(super) async fn read_header<S: AsyncRead + Unpin>(
+    stream: &mut S,
+) -> io::Result<Option<SocketAddr>> {
+    // Both versions of the header are longer than the signature of the version 2.
+    let mut start = [0; V2_SIGNATURE.len()];
+    stream.read_exact(&mut start).await?;
+
+    if start == V2_SIGNATURE {
+        let mut header = [0; 4];
+        stream.read_exact(&mut header).await?;
+        let mut addresses = vec![0; u16::from_be_bytes([header[2], header[3]]) as usize];
+        stream.read_exact(&mut addresses).await?;
+        parse_v2(header[0], header[1], &addresses)
+    } else if start.starts_with(V1_PREFIX) {
+        let mut line = start.to_vec();
+        while !line.ends_with(b"\r\n") {
+            if line.len() == V1_MAX_LENGTH {
+                return Err(invalid("the version 1 header is too long"));
+            }
+            line.push(stream.read_u8().await?);
+        }
+        parse_v1(&line[..line.len() - 2])
+    } else {
+        Err(invalid("the connection doesn't start with a header"))
+    }
+}
+
+/// Parses a header such as `PROXY TCP4 192.0.2.1 198.51.100.1 56324 443`.
+fn parse_v1(line: &[u8]) -> io::Result<Option<SocketAddr>> {
+    let line = std::str::from_utf8(line).map_err(|_| invalid("the header isn't valid UTF-8"))?;
+    let mut fields = line.split(' ').skip(1);
+    match fields.next() {
+        Some("UNKNOWN") => Ok(None),
+        Some("TCP4" | "TCP6") => {
+            let (Some(source), Some(_), Some(source_port), Some(_), None) = (
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+            ) else {
+                return Err(invalid("the header must have four addresses and ports"));
+            };
+            let source = source
+                .parse::<IpAddr>()
+                .map_err(|_| invalid("the source address is invalid"))?;
+            let source_port = source_port
+                .parse::<u16>()
+                .map_err(|_| invalid("the source port is invalid"))?;
+            Ok(Some(SocketAddr::new(source, source_port)))
+        }
+        _ => Err(invalid("the protocol is unsupported")),
+    }
+}
+
+fn parse_v2(version_command: u8, family: u8, addresses: &[u8]) -> io::Result<Option<SocketAddr>> {
+    if version_command >> 4 != 2 {
+        return Err(invalid("the version is unsupported"));
+    }
+    match version_command & 0x0f {
+        // The connections made by the proxy itself, such as health checks.
+        0 => return Ok(None),
+        1 => {}
+        _ => return Err(invalid("the command is unsupported")),
+    }
+
+    let source = match family >> 4 {
+        1 => {
+            let Some(addresses) = addresses.get(..12) else {
+                return Err(invalid("the IPv4 addresses are truncated"));
+            };
+            let ip = Ipv4Addr::from(<[u8; 4]>::try_from(&addresses[..4]).unwrap());
+            SocketAddr::new(ip.into(), u16::from_be_bytes([addresses[8], addresses[9]]))
+        }
+        2 => {
+            let Some(addresses) = addresses.get(..36) else {
+                return Err(invalid("the IPv6 addresses are truncated"));
+            };
+            let ip = Ipv6Addr::from(<[u8; 16]>::try_from(&addresses[..16]).unwrap());
+            SocketAddr::new(
+                ip.into(),
+                u16::from_be_bytes([addresses[32], addresses[33]]),
+            )
+        }
+        // The unspecified and Unix socket addresses aren't useful as peer addresses.
+        _ => return Ok(None),
+    };
+    Ok(Some(source))
+}
+
+fn invalid(reason: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn read(mut bytes: &[u8]) -> (io::Result<Option<SocketAddr>>, &[u8]) {
+        let result = read_header(&mut bytes).await;
+        (result, bytes)
+    }
+
+    #[tokio::test]
+    async fn reads_v1_header() {
+        let (result, rest) = read(b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\nhello").await;
+        assert_eq!(result.unwrap(), Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(rest, b"hello");
+
+        let (result, rest) = read(b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\n").await;
+        assert_eq!(
+            result.unwrap(),
+            Some("[2001:db8::1]:56324".parse().unwrap())
+        );
+        assert!(rest.is_empty());
+
+        let (result, _) = read(b"PROXY UNKNOWN\r\n").await;
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reads_v2_header() {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend([0x21, 0x11, 0, 12]);
+        header.extend([192, 0, 2, 1, 198, 51, 100, 1]);
+        header.extend(56324u16.to_be_bytes());
+        header.extend(443u16.to_be_bytes());
+        header.extend(b"hello");
+
+        let (result, rest) = read(&header).await;
+        assert_eq!(result.unwrap(), Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(rest, b"hello");
+
+        let mut local = V2_SIGNATURE.to_vec();
+        local.extend([0x20, 0x00, 0, 0]);
+        let (result, _) = read(&local).await;
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_headers() {
+        let too_long = [b"PROXY TCP4 ".as_slice(), &[b'1'; 200]].concat();
+        for header in [
+            &b"<13>1 2019-02-13T19:48:34+00:00 host app - - - hello\n"[..],
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 56324\r\n",
+            b"PROXY TCP4 not-an-address 198.51.100.1 56324 443\r\n",
+            b"PROXY UDP4 192.0.2.1 198.51.100.1 56324 443\r\n",
+            too_long.as_slice(),
+        ] {
+            let (result, _) = read(header).await;
+            assert!(result.is_err(), "{header:?} was accepted");
+        }
+    }
+}
@@ -35,10 +35,11 @@ pub use vrl;
 pub mod config {
     pub use vector_common::config::ComponentKey;
     pub use vector_core::config::{
-        AcknowledgementsConfig, DataType, GlobalOptions, Input, LegacyKey, LogNamespace, LogSchema,
-        MEMORY_BUFFER_DEFAULT_MAX_EVENTS, OutputId, SourceAcknowledgementsConfig, SourceOutput,
-        Tags, Telemetry, TransformOutput, WildcardMatching, clone_input_definitions,
-        init_log_schema, init_telemetry, log_schema, proxy, telemetry,
+        AcknowledgementsConfig, DataType, GlobalOptions, Input, InternalLogRateLimit, LegacyKey,
+        LogNamespace, LogSchema, MEMORY_BUFFER_DEFAULT_MAX_EVENTS, OutputId,
+        SourceAcknowledgementsConfig, SourceOutput, Tags, Telemetry, TransformOutput,
+        WildcardMatching, clone_input_definitions, init_log_schema, init_telemetry, log_schema,
+        proxy, telemetry,
     };
 }
 
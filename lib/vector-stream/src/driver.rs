@@ -1,4 +1,10 @@
-use std::{collections::VecDeque, fmt, future::poll_fn, task::Poll};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    future::poll_fn,
+    sync::{Arc, Mutex},
+    task::Poll,
+};
 
 use futures::{FutureExt, Stream, StreamExt, TryFutureExt, poll};
 use tokio::{pin, select};
@@ -11,10 +17,17 @@ use vector_common::{
     },
     request_metadata::{GroupedCountByteSize, MetaDescriptive},
 };
-use vector_core::event::{EventFinalizers, EventStatus, Finalizable};
+use vector_core::event::{DeliveryFailure, EventFinalizers, EventStatus, Finalizable};
 
 use super::FuturesUnorderedCount;
 
+/// The maximum number of requests waiting for the in-flight requests with the same ordering keys,
+/// after which no more requests are taken from the input stream.
+const MAX_WAITING_REQUESTS: usize = 128;
+
+/// The ordering keys of the in-flight requests, with the number of requests holding each of them.
+type InFlightKeys = Arc<Mutex<HashMap<u64, usize>>>;
+
 pub trait DriverResponse {
     fn event_status(&self) -> EventStatus;
     fn events_sent(&self) -> &GroupedCountByteSize;
@@ -25,6 +38,12 @@ pub trait DriverResponse {
     fn bytes_sent(&self) -> Option<usize> {
         None
     }
+
+    /// Describes why the events of the request weren't delivered, such as the status code and body
+    /// of an HTTP response, if the response says so.
+    fn failure(&self) -> Option<DeliveryFailure> {
+        None
+    }
 }
 
 /// Drives the interaction between a stream of items and a service which processes them
@@ -40,6 +59,10 @@ pub trait DriverResponse {
 /// This capability is parameterized so any implementation which can define how to interpret the
 /// response for each request, as well as define how many events a request is compromised of, can be
 /// used with `Driver`.
+///
+/// The requests whose metadata holds ordering keys are sent one at a time for each of their keys,
+/// in the order they were received, while the requests of different keys are still sent
+/// concurrently.
 pub struct Driver<St, Svc> {
     input: St,
     service: Svc,
@@ -85,9 +108,14 @@ where
     /// An error is currently only returned if a service returns an error from `poll_ready`
     pub async fn run(self) -> Result<(), ()> {
         let mut in_flight = FuturesUnorderedCount::new();
-        let mut next_batch: Option<VecDeque<St::Item>> = None;
+        let mut batch: VecDeque<St::Item> = VecDeque::new();
+        let mut next_request: Option<St::Item> = None;
         let mut seq_num = 0usize;
 
+        // The requests waiting for in-flight requests with the same ordering keys.
+        let mut waiting: VecDeque<St::Item> = VecDeque::new();
+        let in_flight_keys = InFlightKeys::default();
+
         let Self {
             input,
             mut service,
@@ -104,10 +132,11 @@ where
             // Core behavior of the loop:
             // - always check to see if we have any response futures that have completed
             //  -- if so, handling acking as many events as we can (ordering matters)
-            // - if we have a "current" batch, try to send each request in it to the service
+            // - if we have a request that can be sent, try to send it and the following ones in
+            //   the "current" batch to the service
             //   -- if we can't drain all requests from the batch due to lack of service readiness,
-            //   then put the batch back and try to send the rest of it when the service is ready
-            //   again
+            //   then keep the request and try to send it and the rest of the batch when the
+            //   service is ready again
             // - if we have no "current" batch, but there is an available batch from our input
             //   stream, grab that batch and store it as our current batch
             //
@@ -119,6 +148,13 @@ where
             // more code, and requires a more careful eye than blindly doing
             // "get_next_batch().await; process_batch().await", but it does make doing the complex
             // logic easier than if we tried to interleave it ourselves with an imperative-style loop.
+            //
+            // The requests with ordering keys whose previous requests are still in flight are set
+            // aside as waiting, and sent before the rest of the batch once the keys are free.
+
+            if next_request.is_none() {
+                next_request = Self::next_request(&mut batch, &mut waiting, &in_flight_keys);
+            }
 
             select! {
                 // Using `biased` ensures we check the branches in the order they're written, since
@@ -129,13 +165,10 @@ where
                 // One or more of our service calls have completed.
                 Some(_count) = in_flight.next(), if !in_flight.is_empty() => {}
 
-                // We've got an input batch to process and the service is ready to accept a request.
-                maybe_ready = poll_fn(|cx| service.poll_ready(cx)), if next_batch.is_some() => {
-                    let mut batch = next_batch.take()
-                        .unwrap_or_else(|| unreachable!("batch should be populated"));
-
+                // We've got a request to send and the service is ready to accept it.
+                maybe_ready = poll_fn(|cx| service.poll_ready(cx)), if next_request.is_some() => {
                     let mut maybe_ready = Some(maybe_ready);
-                    while !batch.is_empty() {
+                    while let Some(mut req) = next_request.take() {
                         // Make sure the service is ready to take another request.
                         let maybe_ready = match maybe_ready.take() {
                             Some(ready) => Poll::Ready(ready),
@@ -149,12 +182,11 @@ where
                                 return Err(())
                             }
                             Poll::Pending => {
-                                next_batch = Some(batch);
+                                next_request = Some(req);
                                 break
                             },
                         };
 
-                        let mut req = batch.pop_front().unwrap_or_else(|| unreachable!("batch should not be empty"));
                         seq_num += 1;
                         let request_id = seq_num;
 
@@ -167,26 +199,38 @@ where
                         let bytes_sent = bytes_sent.clone();
                         let events_sent = events_sent.clone();
                         let event_count = req.get_metadata().event_count();
+                        let ordering_keys = req.get_metadata().ordering_keys().to_vec();
+                        let release_keys = (!ordering_keys.is_empty()).then(|| {
+                            Self::update_in_flight_keys(&in_flight_keys, &ordering_keys, true);
+                            Arc::clone(&in_flight_keys)
+                        });
 
                         let fut = svc.call(req)
                             .err_into()
-                            .map(move |result| Self::handle_response(
-                                result,
-                                request_id,
-                                finalizers,
-                                event_count,
-                                bytes_sent.as_ref(),
-                                &events_sent,
-                            ))
+                            .map(move |result| {
+                                Self::handle_response(
+                                    result,
+                                    request_id,
+                                    finalizers,
+                                    event_count,
+                                    bytes_sent.as_ref(),
+                                    &events_sent,
+                                );
+                                if let Some(in_flight_keys) = release_keys {
+                                    Self::update_in_flight_keys(&in_flight_keys, &ordering_keys, false);
+                                }
+                            })
                             .instrument(info_span!("request", request_id).or_current());
 
                         in_flight.push(fut);
+
+                        next_request = Self::next_request(&mut batch, &mut waiting, &in_flight_keys);
                     }
                 }
 
                 // We've received some items from the input stream.
-                Some(reqs) = batched_input.next(), if next_batch.is_none() => {
-                    next_batch = Some(reqs.into());
+                Some(reqs) = batched_input.next(), if batch.is_empty() && next_request.is_none() => {
+                    batch = reqs.into();
                 }
 
                 else => break
@@ -196,6 +240,61 @@ where
         Ok(())
     }
 
+    /// Takes the next request that can be sent, either the oldest waiting request whose ordering
+    /// keys are free, or the next request of the batch. The requests of the batch whose ordering
+    /// keys aren't free are moved to the waiting requests, until there are too many of them.
+    fn next_request(
+        batch: &mut VecDeque<St::Item>,
+        waiting: &mut VecDeque<St::Item>,
+        in_flight_keys: &InFlightKeys,
+    ) -> Option<St::Item> {
+        let in_flight_keys = in_flight_keys.lock().expect("mutex should not be poisoned");
+        let is_free = |keys: &[u64], waiting_keys: &HashSet<u64>| {
+            keys.iter()
+                .all(|key| !in_flight_keys.contains_key(key) && !waiting_keys.contains(key))
+        };
+
+        let mut waiting_keys = HashSet::new();
+        let free = waiting.iter().position(|req| {
+            let keys = req.get_metadata().ordering_keys();
+            is_free(keys, &waiting_keys) || {
+                waiting_keys.extend(keys);
+                false
+            }
+        });
+        if let Some(index) = free {
+            return waiting.remove(index);
+        }
+
+        while let Some(req) = batch.pop_front() {
+            let keys = req.get_metadata().ordering_keys();
+            if is_free(keys, &waiting_keys) {
+                return Some(req);
+            }
+            if waiting.len() >= MAX_WAITING_REQUESTS {
+                batch.push_front(req);
+                return None;
+            }
+            waiting_keys.extend(keys);
+            waiting.push_back(req);
+        }
+        None
+    }
+
+    fn update_in_flight_keys(in_flight_keys: &InFlightKeys, keys: &[u64], sent: bool) {
+        let mut in_flight_keys = in_flight_keys.lock().expect("mutex should not be poisoned");
+        for key in keys {
+            if sent {
+                *in_flight_keys.entry(*key).or_default() += 1;
+            } else if let Some(count) = in_flight_keys.get_mut(key) {
+                *count -= 1;
+                if *count == 0 {
+                    in_flight_keys.remove(key);
+                }
+            }
+        }
+    }
+
     fn handle_response(
         result: Result<Svc::Response, Svc::Error>,
         request_id: usize,
@@ -206,12 +305,21 @@ where
     ) {
         match result {
             Err(error) => {
+                let failure = DeliveryFailure {
+                    message: format!("{error:?}"),
+                    status_code: None,
+                };
                 Self::emit_call_error(Some(error), request_id, event_count);
-                finalizers.update_status(EventStatus::Rejected);
+                finalizers.update_failure(EventStatus::Rejected, &failure);
             }
             Ok(response) => {
                 trace!(message = "Service call succeeded.", request_id);
-                finalizers.update_status(response.event_status());
+                match response.failure() {
+                    Some(failure) if response.event_status() != EventStatus::Delivered => {
+                        finalizers.update_failure(response.event_status(), &failure);
+                    }
+                    _ => finalizers.update_status(response.event_status()),
+                }
                 if response.event_status() == EventStatus::Delivered {
                     if let Some(bytes_sent) = bytes_sent
                         && let Some(byte_size) = response.bytes_sent()
@@ -245,10 +353,11 @@ where
 #[cfg(test)]
 mod tests {
     use std::{
+        collections::{HashMap, HashSet},
         future::Future,
         pin::Pin,
         sync::{
-            Arc,
+            Arc, Mutex,
             atomic::{AtomicUsize, Ordering},
         },
         task::{Context, Poll, ready},
@@ -455,4 +564,89 @@ mod tests {
         tokio::task::yield_now().await;
         assert_eq!(input_total, counter.load(Ordering::SeqCst));
     }
+
+    #[derive(Debug)]
+    struct OrderedRequest(usize, RequestMetadata);
+
+    impl Finalizable for OrderedRequest {
+        fn take_finalizers(&mut self) -> EventFinalizers {
+            EventFinalizers::default()
+        }
+    }
+
+    impl MetaDescriptive for OrderedRequest {
+        fn get_metadata(&self) -> &RequestMetadata {
+            &self.1
+        }
+
+        fn metadata_mut(&mut self) -> &mut RequestMetadata {
+            &mut self.1
+        }
+    }
+
+    // Service checking that the requests of each key are never in flight concurrently, and
+    // recording the order in which they are sent.
+    #[derive(Default)]
+    struct OrderedService {
+        sent: Arc<Mutex<HashMap<u64, Vec<usize>>>>,
+        in_flight: Arc<Mutex<HashSet<u64>>>,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    impl Service<OrderedRequest> for OrderedService {
+        type Response = DelayResponse;
+        type Error = ();
+        type Future =
+            Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + Sync>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: OrderedRequest) -> Self::Future {
+            let key = req.1.ordering_keys()[0];
+            {
+                let mut in_flight = self.in_flight.lock().unwrap();
+                assert!(in_flight.insert(key), "key {key} should not be in flight");
+                self.max_in_flight
+                    .fetch_max(in_flight.len(), Ordering::Relaxed);
+            }
+            self.sent
+                .lock()
+                .unwrap()
+                .entry(key)
+                .or_default()
+                .push(req.0);
+
+            let in_flight = Arc::clone(&self.in_flight);
+            Box::pin(async move {
+                sleep(Duration::from_millis((req.0 % 4) as u64)).await;
+                in_flight.lock().unwrap().remove(&key);
+                Ok(DelayResponse::new())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn driver_orders_requests_per_key() {
+        let input_stream = stream::iter((0..300).map(|i| {
+            OrderedRequest(
+                i,
+                RequestMetadata::default().with_ordering_keys(vec![(i % 3) as u64]),
+            )
+        }));
+        let service = OrderedService::default();
+        let sent = Arc::clone(&service.sent);
+        let max_in_flight = Arc::clone(&service.max_in_flight);
+
+        assert_eq!(Driver::new(input_stream, service).run().await, Ok(()));
+
+        let sent = sent.lock().unwrap();
+        for key in 0..3 {
+            let expected = (0..300).filter(|i| i % 3 == key).collect::<Vec<_>>();
+            assert_eq!(sent[&(key as u64)], expected);
+        }
+        // The requests of different keys are still sent concurrently.
+        assert_eq!(max_in_flight.load(Ordering::Relaxed), 3);
+    }
 }
@@ -31,6 +31,7 @@ pub struct EventFormatter {
     component_id_label: ColoredString,
     component_kind_label: ColoredString,
     component_type_label: ColoredString,
+    trace_id_label: ColoredString,
 }
 
 impl EventFormatter {
@@ -41,6 +42,7 @@ impl EventFormatter {
             component_id_label: "component_id".green(),
             component_kind_label: "component_kind".green(),
             component_type_label: "component_type".green(),
+            trace_id_label: "trace_id".green(),
         }
     }
 
@@ -49,18 +51,26 @@ impl EventFormatter {
         component_id: &str,
         component_kind: &str,
         component_type: &str,
+        trace_id: Option<&str>,
         event: &'a str,
     ) -> Cow<'a, str> {
         if self.meta {
             match self.format {
                 TapEncodingFormat::Json => format!(
-                    r#"{{"{}":"{}","{}":"{}","{}":"{}","event":{}}}"#,
+                    r#"{{"{}":"{}","{}":"{}","{}":"{}",{}"event":{}}}"#,
                     self.component_id_label,
                     component_id.green(),
                     self.component_kind_label,
                     component_kind.green(),
                     self.component_type_label,
                     component_type.green(),
+                    trace_id
+                        .map(|trace_id| format!(
+                            r#""{}":"{}","#,
+                            self.trace_id_label,
+                            trace_id.green()
+                        ))
+                        .unwrap_or_default(),
                     event
                 )
                 .into(),
@@ -71,25 +81,35 @@ impl EventFormatter {
                     // include it in the map to correctly preserve color
                     // formatting
                     format!(
-                        "{}{}: {}\n{}: {}\n{}: {}\n",
+                        "{}{}: {}\n{}: {}\n{}: {}\n{}",
                         serde_yaml::to_string(&value).unwrap(),
                         self.component_id_label,
                         component_id.green(),
                         self.component_kind_label,
                         component_kind.green(),
                         self.component_type_label,
-                        component_type.green()
+                        component_type.green(),
+                        trace_id
+                            .map(|trace_id| format!(
+                                "{}: {}\n",
+                                self.trace_id_label,
+                                trace_id.green()
+                            ))
+                            .unwrap_or_default()
                     )
                     .into()
                 }
                 TapEncodingFormat::Logfmt => format!(
-                    "{}={} {}={} {}={} {}",
+                    "{}={} {}={} {}={} {}{}",
                     self.component_id_label,
                     component_id.green(),
                     self.component_kind_label,
                     component_kind.green(),
                     self.component_type_label,
                     component_type.green(),
+                    trace_id
+                        .map(|trace_id| format!("{}={} ", self.trace_id_label, trace_id.green()))
+                        .unwrap_or_default(),
                     event
                 )
                 .into(),
@@ -143,6 +163,7 @@ impl<'a> TapRunner<'a> {
         &self,
         interval: i64,
         limit: i64,
+        trace_sample_rate: Option<i64>,
         duration_ms: Option<u64>,
         quiet: bool,
     ) -> Result<(), TapExecutorError> {
@@ -157,6 +178,7 @@ impl<'a> TapRunner<'a> {
                 self.format,
                 limit,
                 interval,
+                trace_sample_rate,
             );
         }
 
@@ -225,6 +247,7 @@ impl<'a> TapRunner<'a> {
                             ev.component_id.as_ref(),
                             ev.component_kind.as_ref(),
                             ev.component_type.as_ref(),
+                            ev.trace_id.as_deref(),
                             ev.string.as_ref()
                         )
                     );
@@ -236,6 +259,7 @@ impl<'a> TapRunner<'a> {
                             ev.component_id.as_ref(),
                             ev.component_kind.as_ref(),
                             ev.component_type.as_ref(),
+                            ev.trace_id.as_deref(),
                             ev.string.as_ref()
                         )
                     );
@@ -247,6 +271,7 @@ impl<'a> TapRunner<'a> {
                             ev.component_id.as_ref(),
                             ev.component_kind.as_ref(),
                             ev.component_type.as_ref(),
+                            ev.trace_id.as_deref(),
                             ev.string.as_ref()
                         )
                     );
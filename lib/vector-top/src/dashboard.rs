@@ -123,12 +123,37 @@ fn format_metric_bytes(total: i64, throughput: i64, human_metrics: bool) -> Stri
     }
 }
 
+fn format_buffer(buffer: Option<state::BufferMetrics>, human_metrics: bool) -> String {
+    let Some(buffer) = buffer else {
+        return "--".to_string();
+    };
+    let format = |n: i64, bytes: bool| match n {
+        0 => "0".to_string(),
+        n if bytes && human_metrics => n.human_format_bytes(),
+        n if human_metrics => n.human_format(),
+        n => n.thousands_format(),
+    };
+    // Buffers are limited either by their number of events or by their size.
+    let (used, max) = match (buffer.max_byte_size, buffer.max_events) {
+        (Some(max), _) => (format(buffer.byte_size, true), format(max, true)),
+        (None, Some(max)) => (format(buffer.events, false), format(max, false)),
+        (None, None) => return "--".to_string(),
+    };
+    format!(
+        "{used}/{max} ({}%)",
+        buffer.percentage().unwrap_or_default()
+    )
+}
+
 const NUM_COLUMNS: usize = if is_allocation_tracing_enabled() {
-    10
+    12
 } else {
-    9
+    11
 };
 
+/// The index of the errors column, under which the errors by type are listed.
+const ERRORS_COLUMN: usize = 8;
+
 static HEADER: [&str; NUM_COLUMNS] = [
     "ID",
     "Output",
@@ -139,6 +164,8 @@ static HEADER: [&str; NUM_COLUMNS] = [
     "Events Out",
     "Bytes Out",
     "Errors",
+    "Discarded",
+    "Buffer",
     #[cfg(feature = "allocation-tracing")]
     "Memory Used",
 ];
@@ -176,6 +203,7 @@ impl<'a> Widgets<'a> {
         area: Rect,
         connection_status: &ConnectionStatus,
         uptime: Duration,
+        bottlenecks: &[&str],
     ) {
         let mut text = vec![
             Span::from(self.url_string),
@@ -190,6 +218,15 @@ impl<'a> Widgets<'a> {
             " | Uptime: {}",
             humantime::format_duration(uptime)
         ))]);
+        if !bottlenecks.is_empty() {
+            text.extend(vec![
+                Span::from(" | "),
+                Span::styled(
+                    format!("Bottleneck: {}", bottlenecks.join(", ")),
+                    Style::default().fg(Color::Red),
+                ),
+            ]);
+        }
 
         let text = vec![Line::from(text)];
 
@@ -254,12 +291,24 @@ impl<'a> Widgets<'a> {
                 } else {
                     r.errors.thousands_format()
                 },
+                if self.human_metrics {
+                    r.discarded_events_total.human_format()
+                } else {
+                    r.discarded_events_total.thousands_format()
+                },
+                format_buffer(r.buffer, self.human_metrics),
                 #[cfg(feature = "allocation-tracing")]
                 r.allocated_bytes.human_format_bytes(),
             ];
 
             data.extend_from_slice(&formatted_metrics);
-            items.push(Row::new(data).style(Style::default()));
+            // The components holding back the others are highlighted.
+            let style = if r.is_bottleneck {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            items.push(Row::new(data).style(style));
 
             // Add output rows
             if r.has_displayable_outputs() {
@@ -278,32 +327,53 @@ impl<'a> Widgets<'a> {
                     items.push(Row::new(data).style(Style::default()));
                 }
             }
+
+            // Add error type rows
+            if !r.error_types.is_empty() {
+                for (error_type, errors) in r.error_types.iter() {
+                    let mut data = [""; NUM_COLUMNS]
+                        .into_iter()
+                        .map(Cell::from)
+                        .collect::<Vec<_>>();
+                    data[1] = Cell::from(error_type.as_str());
+                    data[ERRORS_COLUMN] = Cell::from(if self.human_metrics {
+                        errors.human_format()
+                    } else {
+                        errors.thousands_format()
+                    });
+                    items.push(Row::new(data).style(Style::default().fg(Color::Gray)));
+                }
+            }
         }
 
         let widths: &[Constraint] = if is_allocation_tracing_enabled() {
             &[
-                Constraint::Percentage(13), // ID
-                Constraint::Percentage(8),  // Output
+                Constraint::Percentage(11), // ID
+                Constraint::Percentage(7),  // Output
                 Constraint::Percentage(4),  // Kind
-                Constraint::Percentage(9),  // Type
-                Constraint::Percentage(10), // Events In
-                Constraint::Percentage(12), // Bytes In
-                Constraint::Percentage(10), // Events Out
-                Constraint::Percentage(12), // Bytes Out
-                Constraint::Percentage(8),  // Errors
-                Constraint::Percentage(14), // Allocated Bytes
+                Constraint::Percentage(8),  // Type
+                Constraint::Percentage(9),  // Events In
+                Constraint::Percentage(10), // Bytes In
+                Constraint::Percentage(9),  // Events Out
+                Constraint::Percentage(10), // Bytes Out
+                Constraint::Percentage(6),  // Errors
+                Constraint::Percentage(6),  // Discarded
+                Constraint::Percentage(10), // Buffer
+                Constraint::Percentage(10), // Allocated Bytes
             ]
         } else {
             &[
-                Constraint::Percentage(13), // ID
-                Constraint::Percentage(12), // Output
-                Constraint::Percentage(9),  // Kind
+                Constraint::Percentage(12), // ID
+                Constraint::Percentage(10), // Output
+                Constraint::Percentage(6),  // Kind
                 Constraint::Percentage(6),  // Type
-                Constraint::Percentage(12), // Events In
-                Constraint::Percentage(14), // Bytes In
-                Constraint::Percentage(12), // Events Out
-                Constraint::Percentage(14), // Bytes Out
-                Constraint::Percentage(8),  // Errors
+                Constraint::Percentage(10), // Events In
+                Constraint::Percentage(11), // Bytes In
+                Constraint::Percentage(10), // Events Out
+                Constraint::Percentage(11), // Bytes Out
+                Constraint::Percentage(6),  // Errors
+                Constraint::Percentage(6),  // Discarded
+                Constraint::Percentage(12), // Buffer
             ]
         };
         let w = Table::new(items, widths)
@@ -345,7 +415,19 @@ impl<'a> Widgets<'a> {
             .constraints(self.constraints.clone())
             .split(size);
 
-        self.title(f, rects[0], &state.connection_status, state.uptime);
+        let bottlenecks = state
+            .components
+            .values()
+            .filter(|r| r.is_bottleneck)
+            .map(|r| r.key.id())
+            .collect::<Vec<_>>();
+        self.title(
+            f,
+            rects[0],
+            &state.connection_status,
+            state.uptime,
+            &bottlenecks,
+        );
 
         // Require a minimum of 80 chars of line width to display the table
         if size.width >= 80 {
@@ -509,6 +591,35 @@ mod tests {
         assert_eq!(N.human_format(), "1.10 T");
     }
 
+    #[test]
+    /// Buffers should be formatted against their capacity
+    fn format_buffers() {
+        assert_eq!(format_buffer(None, true), "--");
+        assert_eq!(
+            format_buffer(
+                Some(state::BufferMetrics {
+                    events: 250,
+                    max_events: Some(1_000),
+                    ..Default::default()
+                }),
+                false
+            ),
+            "250/1,000 (25%)"
+        );
+        assert_eq!(
+            format_buffer(
+                Some(state::BufferMetrics {
+                    events: 3,
+                    byte_size: 1024,
+                    max_byte_size: Some(1024 * 1024),
+                    ..Default::default()
+                }),
+                true
+            ),
+            "1.00 KiB/1.00 MiB (0%)"
+        );
+    }
+
     #[test]
     /// Should format bytes
     fn format_bytes() {
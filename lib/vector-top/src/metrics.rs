@@ -59,6 +59,10 @@ async fn component_added(
                     #[cfg(feature = "allocation-tracing")]
                     allocated_bytes: 0,
                     errors: 0,
+                    error_types: Vec::new(),
+                    discarded_events_total: 0,
+                    buffer: None,
+                    is_bottleneck: false,
                 }))
                 .await;
         }
@@ -397,6 +401,80 @@ async fn errors_totals(
     }
 }
 
+async fn errors_by_type(
+    client: Arc<SubscriptionClient>,
+    tx: state::EventTx,
+    interval: i64,
+    components_patterns: Arc<Vec<Pattern>>,
+) {
+    tokio::pin! {
+        let stream = client.component_errors_by_type_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors_by_type;
+            _ = tx
+                .send(state::EventType::ErrorsByType(
+                    c.into_iter()
+                        .filter(|c| {
+                            component_matches_patterns(&c.component_id, &components_patterns)
+                        })
+                        .map(|c| state::ErrorsByTypeMetric {
+                            key: ComponentKey::from(c.component_id.as_str()),
+                            error_types: c
+                                .error_types
+                                .into_iter()
+                                .map(|e| (e.error_type, e.errors_total as i64))
+                                .collect(),
+                            discarded_events_total: c.discarded_events_total as i64,
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn buffer_utilizations(
+    client: Arc<SubscriptionClient>,
+    tx: state::EventTx,
+    interval: i64,
+    components_patterns: Arc<Vec<Pattern>>,
+) {
+    tokio::pin! {
+        let stream = client.component_buffer_utilizations_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_buffer_utilizations;
+            _ = tx
+                .send(state::EventType::BufferUtilizations(
+                    c.into_iter()
+                        .filter(|c| {
+                            component_matches_patterns(&c.component_id, &components_patterns)
+                        })
+                        .map(|c| state::BufferUtilizationMetric {
+                            key: ComponentKey::from(c.component_id.as_str()),
+                            // Only the components with a buffer have a capacity.
+                            buffer: (c.buffer_max_events.is_some()
+                                || c.buffer_max_byte_size.is_some())
+                            .then(|| state::BufferMetrics {
+                                events: c.buffer_events as i64,
+                                byte_size: c.buffer_byte_size as i64,
+                                max_events: c.buffer_max_events.map(|max| max as i64),
+                                max_byte_size: c.buffer_max_byte_size.map(|max| max as i64),
+                            }),
+                            is_bottleneck: c.is_bottleneck,
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
 async fn uptime_changed(client: Arc<SubscriptionClient>, tx: state::EventTx) {
     tokio::pin! {
         let stream = client.uptime_subscription();
@@ -493,6 +571,18 @@ pub fn subscribe(
             interval,
             Arc::clone(&components_patterns),
         )),
+        tokio::spawn(errors_by_type(
+            Arc::clone(&client),
+            tx.clone(),
+            interval,
+            Arc::clone(&components_patterns),
+        )),
+        tokio::spawn(buffer_utilizations(
+            Arc::clone(&client),
+            tx.clone(),
+            interval,
+            Arc::clone(&components_patterns),
+        )),
         tokio::spawn(uptime_changed(Arc::clone(&client), tx)),
     ]
 }
@@ -542,6 +632,10 @@ pub async fn init_components(
                     #[cfg(feature = "allocation-tracing")]
                     allocated_bytes: 0,
                     errors: 0,
+                    error_types: Vec::new(),
+                    discarded_events_total: 0,
+                    buffer: None,
+                    is_bottleneck: false,
                 },
             )
         })
@@ -22,6 +22,20 @@ pub struct SentEventsMetric {
     pub outputs: HashMap<String, i64>,
 }
 
+#[derive(Debug)]
+pub struct ErrorsByTypeMetric {
+    pub key: ComponentKey,
+    pub error_types: Vec<(String, i64)>,
+    pub discarded_events_total: i64,
+}
+
+#[derive(Debug)]
+pub struct BufferUtilizationMetric {
+    pub key: ComponentKey,
+    pub buffer: Option<BufferMetrics>,
+    pub is_bottleneck: bool,
+}
+
 #[derive(Debug)]
 pub enum EventType {
     InitializeState(State),
@@ -40,6 +54,8 @@ pub enum EventType {
     /// Interval in ms + identified overall metric + output-specific metrics
     SentEventsThroughputs(i64, Vec<SentEventsMetric>),
     ErrorsTotals(Vec<IdentifiedMetric>),
+    ErrorsByType(Vec<ErrorsByTypeMetric>),
+    BufferUtilizations(Vec<BufferUtilizationMetric>),
     #[cfg(feature = "allocation-tracing")]
     AllocatedBytes(Vec<IdentifiedMetric>),
     ComponentAdded(ComponentRow),
@@ -112,6 +128,30 @@ impl From<i64> for OutputMetrics {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferMetrics {
+    pub events: i64,
+    pub byte_size: i64,
+    pub max_events: Option<i64>,
+    pub max_byte_size: Option<i64>,
+}
+
+impl BufferMetrics {
+    /// The percentage of the capacity of the buffer in use, by events or bytes, whichever is
+    /// higher.
+    pub fn percentage(&self) -> Option<i64> {
+        let events = self
+            .max_events
+            .filter(|max| *max > 0)
+            .map(|max| self.events * 100 / max);
+        let bytes = self
+            .max_byte_size
+            .filter(|max| *max > 0)
+            .map(|max| self.byte_size * 100 / max);
+        events.max(bytes)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ComponentRow {
     pub key: ComponentKey,
@@ -129,6 +169,10 @@ pub struct ComponentRow {
     #[cfg(feature = "allocation-tracing")]
     pub allocated_bytes: i64,
     pub errors: i64,
+    pub error_types: Vec<(String, i64)>,
+    pub discarded_events_total: i64,
+    pub buffer: Option<BufferMetrics>,
+    pub is_bottleneck: bool,
 }
 
 impl ComponentRow {
@@ -233,6 +277,26 @@ pub async fn updater(mut event_rx: EventRx) -> StateRx {
                         }
                     }
                 }
+                EventType::ErrorsByType(rows) => {
+                    for m in rows {
+                        if let Some(r) = state.components.get_mut(&m.key) {
+                            r.error_types = m.error_types;
+                            r.discarded_events_total = m.discarded_events_total;
+                        }
+                    }
+                }
+                EventType::BufferUtilizations(rows) => {
+                    // The bottleneck moves as the backpressure does.
+                    for r in state.components.values_mut() {
+                        r.is_bottleneck = false;
+                    }
+                    for m in rows {
+                        if let Some(r) = state.components.get_mut(&m.key) {
+                            r.buffer = m.buffer;
+                            r.is_bottleneck = m.is_bottleneck;
+                        }
+                    }
+                }
                 #[cfg(feature = "allocation-tracing")]
                 EventType::AllocatedBytes(rows) => {
                     for (key, v) in rows {
@@ -0,0 +1,185 @@
+use std::{
+    fmt,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use chrono::format::{Item, ParseError, StrftimeItems};
+use lru::LruCache;
+use regex::Regex;
+
+/// The number of patterns of each kind cached when the program isn't compiled with a
+/// [`PatternCache`].
+pub const DEFAULT_PATTERN_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// The kinds of patterns kept by a [`PatternCache`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternKind {
+    Regex,
+    Grok,
+    DateFormat,
+}
+
+impl PatternKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Regex => "regex",
+            Self::Grok => "grok",
+            Self::DateFormat => "date_format",
+        }
+    }
+}
+
+/// Receives the outcome of the lookups of a [`PatternCache`], typically to report them as metrics.
+pub trait PatternCacheObserver: fmt::Debug + Send + Sync {
+    fn lookup(&self, kind: PatternKind, hit: bool);
+}
+
+type Entries<T> = Mutex<LruCache<String, Arc<T>>>;
+
+/// Least recently used caches of the patterns that functions only know at runtime, such as a
+/// regex read from a field, so that they aren't parsed again for each event.
+///
+/// The cache is set as an external context of the compilation, and shared by all the functions of
+/// the program. Clones share the same entries.
+#[derive(Clone)]
+pub struct PatternCache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    regexes: Entries<Regex>,
+    groks: Entries<grok::Pattern>,
+    date_formats: Entries<Vec<Item<'static>>>,
+    /// The default patterns that grok patterns are built from.
+    grok: grok::Grok,
+    observer: Option<Arc<dyn PatternCacheObserver>>,
+}
+
+impl fmt::Debug for PatternCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatternCache")
+            .field("observer", &self.inner.observer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for PatternCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATTERN_CACHE_SIZE, None)
+    }
+}
+
+impl PatternCache {
+    /// Creates caches keeping up to `capacity` patterns of each kind.
+    pub fn new(capacity: NonZeroUsize, observer: Option<Arc<dyn PatternCacheObserver>>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                regexes: Mutex::new(LruCache::new(capacity)),
+                groks: Mutex::new(LruCache::new(capacity)),
+                date_formats: Mutex::new(LruCache::new(capacity)),
+                grok: grok::Grok::with_default_patterns(),
+                observer,
+            }),
+        }
+    }
+
+    pub fn regex(&self, pattern: &str) -> Result<Arc<Regex>, regex::Error> {
+        self.get_or_insert(&self.inner.regexes, PatternKind::Regex, pattern, || {
+            Regex::new(pattern)
+        })
+    }
+
+    pub fn grok(&self, pattern: &str) -> Result<Arc<grok::Pattern>, grok::Error> {
+        self.get_or_insert(&self.inner.groks, PatternKind::Grok, pattern, || {
+            self.inner.grok.compile(pattern, true)
+        })
+    }
+
+    /// Returns the items of a `strftime` format.
+    pub fn date_format(&self, format: &str) -> Result<Arc<Vec<Item<'static>>>, ParseError> {
+        self.get_or_insert(
+            &self.inner.date_formats,
+            PatternKind::DateFormat,
+            format,
+            || StrftimeItems::new(format).parse_to_owned(),
+        )
+    }
+
+    fn get_or_insert<T, E>(
+        &self,
+        entries: &Entries<T>,
+        kind: PatternKind,
+        pattern: &str,
+        parse: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Arc<T>, E> {
+        let cached = entries
+            .lock()
+            .expect("mutex should not be poisoned")
+            .get(pattern)
+            .cloned();
+        if let Some(observer) = &self.inner.observer {
+            observer.lookup(kind, cached.is_some());
+        }
+        if let Some(cached) = cached {
+            return Ok(cached);
+        }
+
+        // The pattern is parsed without holding the lock, and invalid patterns aren't cached.
+        let parsed = Arc::new(parse()?);
+        entries
+            .lock()
+            .expect("mutex should not be poisoned")
+            .put(pattern.to_owned(), Arc::clone(&parsed));
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Lookups(Mutex<Vec<(PatternKind, bool)>>);
+
+    impl PatternCacheObserver for Lookups {
+        fn lookup(&self, kind: PatternKind, hit: bool) {
+            self.0.lock().unwrap().push((kind, hit));
+        }
+    }
+
+    #[test]
+    fn caches_recently_used_patterns() {
+        let lookups = Arc::new(Lookups::default());
+        let cache = PatternCache::new(NonZeroUsize::new(2).unwrap(), Some(lookups.clone()));
+
+        let first = cache.regex("^a+$").unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.regex("^a+$").unwrap()));
+        cache.regex("^b+$").unwrap();
+        cache.regex("^c+$").unwrap();
+        // The least recently used pattern was evicted.
+        assert!(!Arc::ptr_eq(&first, &cache.regex("^a+$").unwrap()));
+
+        assert!(cache.regex("(").is_err());
+        cache.date_format("%Y-%m-%d").unwrap();
+        cache.date_format("%Y-%m-%d").unwrap();
+        assert!(cache.date_format("%Q").is_err());
+        cache.grok("%{INT:number}").unwrap();
+
+        assert_eq!(
+            vec![
+                (PatternKind::Regex, false),
+                (PatternKind::Regex, true),
+                (PatternKind::Regex, false),
+                (PatternKind::Regex, false),
+                (PatternKind::Regex, false),
+                (PatternKind::Regex, false),
+                (PatternKind::DateFormat, false),
+                (PatternKind::DateFormat, true),
+                (PatternKind::DateFormat, false),
+                (PatternKind::Grok, false),
+            ],
+            *lookups.0.lock().unwrap()
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use vrl::prelude::*;
+
+use crate::encryption::{EncryptionKeys, encryption_not_enabled};
+
+fn decrypt_field(keys: &EncryptionKeys, value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+    Ok(Value::from(keys.decrypt(&value)?))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecryptField;
+
+impl Function for DecryptField {
+    fn identifier(&self) -> &'static str {
+        "decrypt_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        // The keys are only available to the programs of the `remap` transform.
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let keys = ctx
+            .get_external_context::<EncryptionKeys>()
+            .cloned()
+            .ok_or_else(|| encryption_not_enabled(ctx.span()))?;
+        let value = arguments.required("value");
+        Ok(DecryptFieldFn { keys, value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecryptFieldFn {
+    keys: EncryptionKeys,
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for DecryptFieldFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        decrypt_field(&self.keys, value)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The value can be corrupted, or encrypted with a key that isn't configured anymore.
+        TypeDef::bytes().fallible()
+    }
+}
@@ -0,0 +1,63 @@
+use std::{error::Error, fmt, net::IpAddr, sync::Arc};
+
+use vrl::{
+    diagnostic::{Label, Span},
+    prelude::*,
+};
+
+use crate::{dns_lookup::DnsLookup, reverse_dns::ReverseDns};
+
+pub type DnsError = Box<dyn Error + Send + Sync>;
+
+/// A DNS lookup of a program.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DnsQuery {
+    /// Resolves the addresses of a host name.
+    Lookup(String),
+    /// Resolves the host names of an address.
+    Reverse(IpAddr),
+}
+
+/// Resolves the DNS lookups of a [`DnsResolver`], typically caching them and bounding the lookups
+/// in progress so that the program isn't stalled by a slow name server.
+pub trait DnsBackend: fmt::Debug + Send + Sync {
+    /// Returns the addresses or the host names the query resolves to.
+    fn resolve(&self, query: &DnsQuery) -> Result<Vec<String>, DnsError>;
+}
+
+/// DNS lookups shared across the events processed by a VRL program.
+///
+/// The resolver is set as an external context of the compilation, replacing the `dns_lookup` and
+/// `reverse_dns` functions of the standard library, which block on each lookup, by the ones of
+/// [`functions`]. Clones share the same backend.
+#[derive(Clone, Debug)]
+pub struct DnsResolver {
+    backend: Arc<dyn DnsBackend>,
+}
+
+impl DnsResolver {
+    pub fn new(backend: Arc<dyn DnsBackend>) -> Self {
+        Self { backend }
+    }
+
+    pub fn resolve(&self, query: &DnsQuery) -> Result<Vec<String>, DnsError> {
+        self.backend.resolve(query)
+    }
+}
+
+/// The functions resolving their lookups with a [`DnsResolver`].
+pub fn functions() -> Vec<Box<dyn Function>> {
+    vec![Box::new(DnsLookup) as _, Box::new(ReverseDns) as _]
+}
+
+/// The error returned when compiling a DNS function for a program without a resolver.
+pub(crate) fn dns_not_enabled(span: Span) -> Box<dyn DiagnosticMessage> {
+    Box::new(ExpressionError::Error {
+        message: "cached DNS lookups are not enabled".to_owned(),
+        labels: vec![Label::primary(
+            "cached DNS lookups require the `dns` option of the transform",
+            span,
+        )],
+        notes: vec![],
+    })
+}
@@ -0,0 +1,68 @@
+use vrl::prelude::*;
+
+use crate::dns::{DnsQuery, DnsResolver, dns_not_enabled};
+
+fn dns_lookup(resolver: &DnsResolver, value: Value) -> Resolved {
+    let host = value.try_bytes_utf8_lossy()?;
+    let addresses = resolver
+        .resolve(&DnsQuery::Lookup(host.into_owned()))
+        .map_err(|error| format!("unable to resolve host name: {error}"))?;
+    Ok(Value::Array(
+        addresses.into_iter().map(Value::from).collect(),
+    ))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DnsLookup;
+
+impl Function for DnsLookup {
+    fn identifier(&self) -> &'static str {
+        "dns_lookup"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        // The resolver is only available to the programs of the `remap` transform, and the result
+        // depends on the name servers.
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let resolver = ctx
+            .get_external_context::<DnsResolver>()
+            .cloned()
+            .ok_or_else(|| dns_not_enabled(ctx.span()))?;
+        let value = arguments.required("value");
+        Ok(DnsLookupFn { resolver, value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DnsLookupFn {
+    resolver: DnsResolver,
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for DnsLookupFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        dns_lookup(&self.resolver, value)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The lookup can fail, time out, or be rejected when too many lookups are in progress.
+        TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible()
+    }
+}
@@ -0,0 +1,106 @@
+use vrl::prelude::*;
+
+use crate::encryption::{Algorithm, EncryptionKeys, encryption_not_enabled};
+
+fn encrypt_field(keys: &EncryptionKeys, key: &str, algorithm: Algorithm, value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+    Ok(Value::from(keys.encrypt(key, algorithm, &value)?))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncryptField;
+
+impl Function for EncryptField {
+    fn identifier(&self) -> &'static str {
+        "encrypt_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        // The keys are only available to the programs of the `remap` transform, and the result
+        // depends on a random nonce.
+        &[]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let keys = ctx
+            .get_external_context::<EncryptionKeys>()
+            .cloned()
+            .ok_or_else(|| encryption_not_enabled(ctx.span()))?;
+
+        let names = keys.names().map(Value::from).collect::<Vec<_>>();
+        let key = arguments
+            .required_enum("key", &names, state)?
+            .try_bytes_utf8_lossy()
+            .expect("key not bytes")
+            .into_owned();
+
+        let algorithms = Algorithm::ALL
+            .into_iter()
+            .map(|algorithm| Value::from(algorithm.as_str()))
+            .collect::<Vec<_>>();
+        let algorithm = arguments
+            .optional_enum("algorithm", &algorithms, state)?
+            .map(|algorithm| {
+                let algorithm = algorithm
+                    .try_bytes_utf8_lossy()
+                    .expect("algorithm not bytes");
+                Algorithm::from_name(&algorithm).expect("algorithm validated against the variants")
+            })
+            .unwrap_or(Algorithm::Aes256Gcm);
+
+        let value = arguments.required("value");
+        Ok(EncryptFieldFn {
+            keys,
+            key,
+            algorithm,
+            value,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptFieldFn {
+    keys: EncryptionKeys,
+    key: String,
+    algorithm: Algorithm,
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for EncryptFieldFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        encrypt_field(&self.keys, &self.key, self.algorithm, value)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The key and the algorithm are checked when compiling, leaving only the generation of
+        // the nonce to fail.
+        TypeDef::bytes().fallible()
+    }
+}
@@ -0,0 +1,283 @@
+//! The keys of the `encrypt_field` and `decrypt_field` functions, and the envelope of the values
+//! they encrypt:
+//!
+//! ```text
+//! envelope (base64-encoded):
+//!   version:    uint8 (1)
+//!   algorithm:  uint8 (1: AES-256-GCM, 2: CHACHA20-POLY1305)
+//!   key_len:    uint8
+//!   key:        uint8[key_len] (name of the key)
+//!   nonce:      uint8[12]
+//!   ciphertext: uint8[value_len]
+//!   tag:        uint8[16]
+//! ```
+//!
+//! The header, up to the name of the key, tells `decrypt_field` which key and algorithm a value was
+//! encrypted with, so that the values encrypted before a key is rotated remain readable as long as
+//! the previous key is still configured under its name. The header is authenticated along with the
+//! value.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{
+    aead::{self, AES_256_GCM, Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+use vrl::{
+    diagnostic::{Label, Span},
+    prelude::*,
+};
+
+const ENVELOPE_VERSION: u8 = 1;
+
+/// The AEAD algorithms values can be encrypted with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl Algorithm {
+    pub const ALL: [Self; 2] = [Self::Aes256Gcm, Self::ChaCha20Poly1305];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Aes256Gcm => "AES-256-GCM",
+            Self::ChaCha20Poly1305 => "CHACHA20-POLY1305",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.as_str() == name)
+    }
+
+    const fn id(self) -> u8 {
+        match self {
+            Self::Aes256Gcm => 1,
+            Self::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|algorithm| algorithm.id() == id)
+    }
+}
+
+/// A key, bound to each of the algorithms.
+struct Key {
+    aes_256_gcm: LessSafeKey,
+    chacha20_poly1305: LessSafeKey,
+}
+
+impl Key {
+    const fn bound_to(&self, algorithm: Algorithm) -> &LessSafeKey {
+        match algorithm {
+            Algorithm::Aes256Gcm => &self.aes_256_gcm,
+            Algorithm::ChaCha20Poly1305 => &self.chacha20_poly1305,
+        }
+    }
+}
+
+/// The keys available to the `encrypt_field` and `decrypt_field` functions, by name.
+///
+/// The programs only refer to the keys by name, so that the keys themselves never appear in them.
+#[derive(Clone, Default)]
+pub struct EncryptionKeys {
+    keys: Arc<BTreeMap<String, Key>>,
+    rng: SystemRandom,
+}
+
+impl EncryptionKeys {
+    /// Creates the keys from their base64-encoded, 32-byte values, by name.
+    pub fn new<'a>(keys: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<Self, String> {
+        let bind = |algorithm: &'static aead::Algorithm, name: &str, key: &[u8]| {
+            UnboundKey::new(algorithm, key)
+                .map(LessSafeKey::new)
+                .map_err(|_| {
+                    format!(
+                        "encryption key {name:?} must be 32 bytes long, got {} bytes",
+                        key.len()
+                    )
+                })
+        };
+
+        let mut decoded = BTreeMap::new();
+        for (name, key) in keys {
+            if name.is_empty() || name.len() > usize::from(u8::MAX) {
+                return Err(format!(
+                    "encryption key name {name:?} must be between 1 and 255 bytes long"
+                ));
+            }
+            let key = STANDARD.decode(key.trim()).map_err(|error| {
+                format!("encryption key {name:?} must be base64-encoded: {error}")
+            })?;
+            decoded.insert(
+                name.to_owned(),
+                Key {
+                    aes_256_gcm: bind(&AES_256_GCM, name, &key)?,
+                    chacha20_poly1305: bind(&CHACHA20_POLY1305, name, &key)?,
+                },
+            );
+        }
+
+        Ok(Self {
+            keys: Arc::new(decoded),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.keys.keys().map(String::as_str)
+    }
+
+    /// Encrypts a value with a key, returning its base64-encoded envelope.
+    pub fn encrypt(
+        &self,
+        name: &str,
+        algorithm: Algorithm,
+        value: &[u8],
+    ) -> Result<String, String> {
+        let key = self
+            .keys
+            .get(name)
+            .ok_or_else(|| format!("unknown encryption key {name:?}"))?;
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| "unable to generate a nonce".to_owned())?;
+
+        let mut envelope = vec![ENVELOPE_VERSION, algorithm.id(), name.len() as u8];
+        envelope.extend_from_slice(name.as_bytes());
+        let header_len = envelope.len();
+        envelope.extend_from_slice(&nonce);
+        envelope.extend_from_slice(value);
+
+        let (header, rest) = envelope.split_at_mut(header_len);
+        let tag = key
+            .bound_to(algorithm)
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&*header),
+                &mut rest[NONCE_LEN..],
+            )
+            .map_err(|_| "unable to encrypt the value".to_owned())?;
+        envelope.extend_from_slice(tag.as_ref());
+
+        Ok(STANDARD.encode(envelope))
+    }
+
+    /// Decrypts a value from its base64-encoded envelope, with the key it was encrypted with.
+    pub fn decrypt(&self, envelope: &[u8]) -> Result<Vec<u8>, String> {
+        let envelope = STANDARD
+            .decode(envelope)
+            .map_err(|error| format!("encrypted value must be base64-encoded: {error}"))?;
+        let invalid = || "invalid encrypted value".to_owned();
+
+        let (&[version, algorithm, name_len], rest) =
+            envelope.split_first_chunk().ok_or_else(invalid)?;
+        if version != ENVELOPE_VERSION {
+            return Err(format!("unsupported encrypted value version {version}"));
+        }
+        let algorithm = Algorithm::from_id(algorithm)
+            .ok_or_else(|| format!("unsupported encryption algorithm {algorithm}"))?;
+        let header_len = 3 + usize::from(name_len);
+        if envelope.len() < header_len + NONCE_LEN {
+            return Err(invalid());
+        }
+        let name = String::from_utf8_lossy(&rest[..usize::from(name_len)]);
+        let key = self
+            .keys
+            .get(name.as_ref())
+            .ok_or_else(|| format!("value was encrypted with unknown key {name:?}"))?;
+
+        let (header, rest) = envelope.split_at(header_len);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+        let mut value = ciphertext.to_vec();
+        let value_len = key
+            .bound_to(algorithm)
+            .open_in_place(nonce, Aad::from(header), &mut value)
+            .map_err(|_| {
+                "unable to decrypt the value, which is corrupted or was encrypted with another key"
+                    .to_owned()
+            })?
+            .len();
+        value.truncate(value_len);
+
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for EncryptionKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionKeys")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The error returned when compiling an encryption function for a program without keys.
+pub(crate) fn encryption_not_enabled(span: Span) -> Box<dyn DiagnosticMessage> {
+    Box::new(ExpressionError::Error {
+        message: "encryption is not enabled".to_owned(),
+        labels: vec![Label::primary(
+            "encryption functions require the `encryption_keys` option of the transform",
+            span,
+        )],
+        notes: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(keys: &[(&str, &[u8])]) -> EncryptionKeys {
+        let encoded = keys
+            .iter()
+            .map(|(name, key)| (*name, STANDARD.encode(key)))
+            .collect::<Vec<_>>();
+        EncryptionKeys::new(encoded.iter().map(|(name, key)| (*name, key.as_str()))).unwrap()
+    }
+
+    #[test]
+    fn round_trips_with_rotated_keys() {
+        let old = keys(&[("v1", &[1; 32])]);
+        let new = keys(&[("v1", &[1; 32]), ("v2", &[2; 32])]);
+
+        for algorithm in Algorithm::ALL {
+            let envelope = old
+                .encrypt("v1", algorithm, b"4111 1111 1111 1111")
+                .unwrap();
+            assert_eq!(
+                new.decrypt(envelope.as_bytes()).unwrap(),
+                b"4111 1111 1111 1111"
+            );
+
+            let envelope = new.encrypt("v2", algorithm, b"secret").unwrap();
+            assert!(old.decrypt(envelope.as_bytes()).is_err());
+        }
+
+        // The name of the key is authenticated along with the value.
+        let mut envelope = STANDARD
+            .decode(old.encrypt("v1", Algorithm::Aes256Gcm, b"secret").unwrap())
+            .unwrap();
+        envelope[4] = b'2';
+        let swapped = keys(&[("v2", &[1; 32])]);
+        assert!(
+            swapped
+                .decrypt(STANDARD.encode(envelope).as_bytes())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        for (name, key) in [("short", "AQID"), ("", "AQID"), ("invalid", "not base64!")] {
+            assert!(EncryptionKeys::new([(name, key)]).is_err());
+        }
+    }
+}
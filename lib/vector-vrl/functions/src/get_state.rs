@@ -0,0 +1,68 @@
+use vrl::prelude::*;
+
+use crate::state::{StateStore, state_not_enabled};
+
+fn get_state(store: &StateStore, key: Value) -> Resolved {
+    let key_str = key.as_str().expect("key must be a string");
+    let value = store
+        .get(key_str.as_ref())
+        .map_err(|error| format!("failed reading state: {error}"))?;
+    Ok(value.unwrap_or(Value::Null))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetState;
+
+impl Function for GetState {
+    fn identifier(&self) -> &'static str {
+        "get_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Get the number of events seen for a host",
+            source: r#"get_state!("host:my-host")"#,
+            result: Ok("null"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let store = ctx
+            .get_external_context::<StateStore>()
+            .cloned()
+            .ok_or_else(|| state_not_enabled(ctx.span()))?;
+        let key = arguments.required("key");
+        Ok(GetStateFn { store, key }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetStateFn {
+    store: StateStore,
+    key: Box<dyn Expression>,
+}
+
+impl FunctionExpression for GetStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        get_state(&self.store, key)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // Reading the state from its storage can fail.
+        TypeDef::any().fallible()
+    }
+}
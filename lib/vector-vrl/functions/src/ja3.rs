@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use md5::{Digest, Md5};
+use vrl::{btreemap, prelude::*, value::kind::Field};
+
+use crate::tls::{tls_code, tls_codes};
+
+fn ja3(
+    version: Value,
+    ciphers: Value,
+    extensions: Value,
+    curves: Option<Value>,
+    point_formats: Option<Value>,
+) -> Resolved {
+    let join = |value: Option<Value>, field| -> Result<String, ExpressionError> {
+        let codes = match value {
+            Some(value) => tls_codes(value, field)?,
+            None => Vec::new(),
+        };
+        Ok(codes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("-"))
+    };
+
+    let string = [
+        tls_code(version, "version")?.to_string(),
+        join(Some(ciphers), "ciphers")?,
+        join(Some(extensions), "extensions")?,
+        join(curves, "curves")?,
+        join(point_formats, "point_formats")?,
+    ]
+    .join(",");
+    let hash = format!("{:x}", Md5::digest(string.as_bytes()));
+
+    Ok(Value::from(btreemap! {
+        "string" => string,
+        "hash" => hash,
+    }))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ja3;
+
+impl Function for Ja3 {
+    fn identifier(&self) -> &'static str {
+        "ja3"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "version",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "ciphers",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "extensions",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "curves",
+                kind: kind::ARRAY,
+                required: false,
+            },
+            Parameter {
+                keyword: "point_formats",
+                kind: kind::ARRAY,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Fingerprint a TLS client hello",
+            source: r#"ja3!(769, [47, 53, 5, 10, 49161, 49162, 49171, 49172, 50, 56, 19, 4], [0, 10, 11], curves: [23, 24, 25], point_formats: [0])"#,
+            result: Ok(
+                r#"{"hash": "ada70206e40642a3e4461f35503241d5", "string": "769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0"}"#,
+            ),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let version = arguments.required("version");
+        let ciphers = arguments.required("ciphers");
+        let extensions = arguments.required("extensions");
+        let curves = arguments.optional("curves");
+        let point_formats = arguments.optional("point_formats");
+        Ok(Ja3Fn {
+            version,
+            ciphers,
+            extensions,
+            curves,
+            point_formats,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Ja3Fn {
+    version: Box<dyn Expression>,
+    ciphers: Box<dyn Expression>,
+    extensions: Box<dyn Expression>,
+    curves: Option<Box<dyn Expression>>,
+    point_formats: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for Ja3Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let version = self.version.resolve(ctx)?;
+        let ciphers = self.ciphers.resolve(ctx)?;
+        let extensions = self.extensions.resolve(ctx)?;
+        let curves = self
+            .curves
+            .as_ref()
+            .map(|curves| curves.resolve(ctx))
+            .transpose()?;
+        let point_formats = self
+            .point_formats
+            .as_ref()
+            .map(|point_formats| point_formats.resolve(ctx))
+            .transpose()?;
+        ja3(version, ciphers, extensions, curves, point_formats)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The fields can hold values that aren't TLS codes.
+        TypeDef::object(BTreeMap::from([
+            (Field::from("string"), Kind::bytes()),
+            (Field::from("hash"), Kind::bytes()),
+        ]))
+        .fallible()
+    }
+}
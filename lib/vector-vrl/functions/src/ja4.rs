@@ -0,0 +1,248 @@
+use vrl::prelude::*;
+
+use crate::tls::{tls_code, tls_codes, truncated_sha256};
+
+/// The code of the server name indication extension.
+const SERVER_NAME: u16 = 0x0000;
+/// The code of the application-layer protocol negotiation extension.
+const ALPN: u16 = 0x0010;
+
+/// The part of a JA4 fingerprint hashing an empty list.
+const EMPTY_HASH: &str = "000000000000";
+
+/// The transport protocols of the hellos.
+const PROTOCOLS: [&str; 3] = ["tcp", "quic", "dtls"];
+
+/// Returns the JA4 notation of a TLS version.
+const fn tls_version(version: u16) -> &'static str {
+    match version {
+        0x0304 => "13",
+        0x0303 => "12",
+        0x0302 => "11",
+        0x0301 => "10",
+        0x0300 => "s3",
+        0x0002 => "s2",
+        0xfeff => "d1",
+        0xfefd => "d2",
+        0xfefc => "d3",
+        _ => "00",
+    }
+}
+
+/// Returns the first and last characters of the first ALPN value, or the first and last
+/// characters of its hex encoding when those aren't alphanumeric.
+fn alpn_chars(alpn: &[u8]) -> String {
+    match (alpn.first(), alpn.last()) {
+        (Some(&first), Some(&last))
+            if first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() =>
+        {
+            format!("{}{}", char::from(first), char::from(last))
+        }
+        (Some(&first), Some(&last)) => format!("{:x}{:x}", first >> 4, last & 0x0f),
+        _ => "00".to_owned(),
+    }
+}
+
+fn hex_list(codes: &[u16]) -> String {
+    codes
+        .iter()
+        .map(|code| format!("{code:04x}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+struct Hello {
+    protocol: char,
+    version: u16,
+    ciphers: Vec<u16>,
+    extensions: Vec<u16>,
+    signature_algorithms: Vec<u16>,
+    supported_versions: Vec<u16>,
+    /// The first protocol of the application-layer protocol negotiation extension.
+    alpn: Option<Vec<u8>>,
+}
+
+impl Hello {
+    fn fingerprint(mut self) -> String {
+        let version = self
+            .supported_versions
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or(self.version);
+        let server_name = if self.extensions.contains(&SERVER_NAME) {
+            'd'
+        } else {
+            'i'
+        };
+        let alpn = alpn_chars(self.alpn.as_deref().unwrap_or_default());
+        let a = format!(
+            "{}{}{server_name}{:02}{:02}{alpn}",
+            self.protocol,
+            tls_version(version),
+            self.ciphers.len().min(99),
+            self.extensions.len().min(99),
+        );
+
+        self.ciphers.sort_unstable();
+        let b = if self.ciphers.is_empty() {
+            EMPTY_HASH.to_owned()
+        } else {
+            truncated_sha256(&hex_list(&self.ciphers))
+        };
+
+        // The server name and the protocols are already part of the first part of the fingerprint,
+        // and the signature algorithms keep their order.
+        self.extensions
+            .retain(|extension| !matches!(*extension, SERVER_NAME | ALPN));
+        self.extensions.sort_unstable();
+        let c = if self.extensions.is_empty() {
+            EMPTY_HASH.to_owned()
+        } else if self.signature_algorithms.is_empty() {
+            truncated_sha256(&hex_list(&self.extensions))
+        } else {
+            truncated_sha256(&format!(
+                "{}_{}",
+                hex_list(&self.extensions),
+                hex_list(&self.signature_algorithms)
+            ))
+        };
+
+        format!("{a}_{b}_{c}")
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ja4;
+
+impl Function for Ja4 {
+    fn identifier(&self) -> &'static str {
+        "ja4"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "version",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "ciphers",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "extensions",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "signature_algorithms",
+                kind: kind::ARRAY,
+                required: false,
+            },
+            Parameter {
+                keyword: "supported_versions",
+                kind: kind::ARRAY,
+                required: false,
+            },
+            Parameter {
+                keyword: "alpn",
+                kind: kind::ARRAY,
+                required: false,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Fingerprint a TLS client hello",
+            source: r#"ja4!(771, [2570, 4865, 4866, 4867, 49195, 49199, 49196, 49200, 52393, 52392, 49171, 49172, 156, 157, 47, 53], [2570, 0, 23, 65281, 10, 11, 35, 16, 5, 13, 18, 51, 45, 43, 27, 17513, 21], signature_algorithms: [1027, 2052, 1025, 1283, 2053, 1281, 2054, 1537], supported_versions: [2570, 772, 771], alpn: ["h2", "http/1.1"])"#,
+            result: Ok(r#""t13d1516h2_8daaf6152771_e5627efa2ab1""#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let protocols = PROTOCOLS.map(Value::from);
+        let protocol = arguments
+            .optional_enum("protocol", &protocols, state)?
+            .map(|protocol| match protocol.as_str().as_deref() {
+                Some("quic") => 'q',
+                Some("dtls") => 'd',
+                _ => 't',
+            })
+            .unwrap_or('t');
+
+        Ok(Ja4Fn {
+            protocol,
+            version: arguments.required("version"),
+            ciphers: arguments.required("ciphers"),
+            extensions: arguments.required("extensions"),
+            signature_algorithms: arguments.optional("signature_algorithms"),
+            supported_versions: arguments.optional("supported_versions"),
+            alpn: arguments.optional("alpn"),
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Ja4Fn {
+    protocol: char,
+    version: Box<dyn Expression>,
+    ciphers: Box<dyn Expression>,
+    extensions: Box<dyn Expression>,
+    signature_algorithms: Option<Box<dyn Expression>>,
+    supported_versions: Option<Box<dyn Expression>>,
+    alpn: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for Ja4Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let mut optional_codes = |expr: &Option<Box<dyn Expression>>, field| match expr {
+            Some(expr) => tls_codes(expr.resolve(ctx)?, field),
+            None => Ok(Vec::new()),
+        };
+        let signature_algorithms =
+            optional_codes(&self.signature_algorithms, "signature_algorithms")?;
+        let supported_versions = optional_codes(&self.supported_versions, "supported_versions")?;
+
+        let alpn = match &self.alpn {
+            Some(alpn) => alpn
+                .resolve(ctx)?
+                .try_array()?
+                .into_iter()
+                .next()
+                .map(|alpn| alpn.try_bytes().map(|alpn| alpn.to_vec()))
+                .transpose()?,
+            None => None,
+        };
+
+        let hello = Hello {
+            protocol: self.protocol,
+            version: tls_code(self.version.resolve(ctx)?, "version")?,
+            ciphers: tls_codes(self.ciphers.resolve(ctx)?, "ciphers")?,
+            extensions: tls_codes(self.extensions.resolve(ctx)?, "extensions")?,
+            signature_algorithms,
+            supported_versions,
+            alpn,
+        };
+        Ok(Value::from(hello.fingerprint()))
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The fields can hold values that aren't TLS codes.
+        TypeDef::bytes().fallible()
+    }
+}
@@ -2,10 +2,28 @@
 
 use vrl::{compiler::Function, path::OwnedTargetPath};
 
+pub mod cache;
+pub mod decrypt_field;
+pub mod dns;
+pub mod dns_lookup;
+pub mod encrypt_field;
+pub mod encryption;
 pub mod get_secret;
+pub mod get_state;
+pub mod ja3;
+pub mod ja4;
+pub mod match_dynamic;
+pub mod parse_grok_dynamic;
+pub mod parse_leef;
+pub mod parse_timestamp_dynamic;
 pub mod remove_secret;
+pub mod reverse_dns;
 pub mod set_secret;
 pub mod set_semantic_meaning;
+pub mod set_state;
+pub mod state;
+mod tls;
+pub mod user_function;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
@@ -22,5 +40,15 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(get_secret::GetSecret) as _,
         Box::new(remove_secret::RemoveSecret) as _,
         Box::new(set_secret::SetSecret) as _,
+        Box::new(get_state::GetState) as _,
+        Box::new(set_state::SetState) as _,
+        Box::new(encrypt_field::EncryptField) as _,
+        Box::new(decrypt_field::DecryptField) as _,
+        Box::new(ja3::Ja3) as _,
+        Box::new(ja4::Ja4) as _,
+        Box::new(match_dynamic::MatchDynamic) as _,
+        Box::new(parse_grok_dynamic::ParseGrokDynamic) as _,
+        Box::new(parse_leef::ParseLeef) as _,
+        Box::new(parse_timestamp_dynamic::ParseTimestampDynamic) as _,
     ]
 }
@@ -0,0 +1,84 @@
+use vrl::prelude::*;
+
+use crate::cache::PatternCache;
+
+fn match_dynamic(cache: &PatternCache, value: Value, pattern: Value) -> Resolved {
+    let value = value.try_bytes_utf8_lossy()?;
+    let pattern = pattern.try_bytes_utf8_lossy()?;
+    let regex = cache
+        .regex(&pattern)
+        .map_err(|error| format!("invalid regex pattern: {error}"))?;
+    Ok(regex.is_match(&value).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MatchDynamic;
+
+impl Function for MatchDynamic {
+    fn identifier(&self) -> &'static str {
+        "match_dynamic"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Match with a pattern built at runtime",
+            source: r#"prefix = "user"; match_dynamic!("user-1234", "^" + prefix + "-\\d+$")"#,
+            result: Ok("true"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let cache = ctx
+            .get_external_context::<PatternCache>()
+            .cloned()
+            .unwrap_or_default();
+        let value = arguments.required("value");
+        let pattern = arguments.required("pattern");
+        Ok(MatchDynamicFn {
+            cache,
+            value,
+            pattern,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MatchDynamicFn {
+    cache: PatternCache,
+    value: Box<dyn Expression>,
+    pattern: Box<dyn Expression>,
+}
+
+impl FunctionExpression for MatchDynamicFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let pattern = self.pattern.resolve(ctx)?;
+        match_dynamic(&self.cache, value, pattern)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The pattern can be an invalid regex.
+        TypeDef::boolean().fallible()
+    }
+}
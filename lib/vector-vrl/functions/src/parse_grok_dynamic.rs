@@ -0,0 +1,92 @@
+use vrl::prelude::*;
+
+use crate::cache::PatternCache;
+
+fn parse_grok_dynamic(cache: &PatternCache, value: Value, pattern: Value) -> Resolved {
+    let value = value.try_bytes_utf8_lossy()?;
+    let pattern = pattern.try_bytes_utf8_lossy()?;
+    let pattern = cache
+        .grok(&pattern)
+        .map_err(|error| format!("invalid grok pattern: {error}"))?;
+    let matches = pattern
+        .match_against(&value)
+        .ok_or_else(|| "unable to parse value with grok pattern".to_owned())?;
+    Ok(Value::Object(
+        matches
+            .iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect(),
+    ))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseGrokDynamic;
+
+impl Function for ParseGrokDynamic {
+    fn identifier(&self) -> &'static str {
+        "parse_grok_dynamic"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Parse with a pattern built at runtime",
+            source: r#"field = "status"; parse_grok_dynamic!("status=200", field + "=%{INT:" + field + "}")"#,
+            result: Ok(r#"{ "status": "200" }"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let cache = ctx
+            .get_external_context::<PatternCache>()
+            .cloned()
+            .unwrap_or_default();
+        let value = arguments.required("value");
+        let pattern = arguments.required("pattern");
+        Ok(ParseGrokDynamicFn {
+            cache,
+            value,
+            pattern,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseGrokDynamicFn {
+    cache: PatternCache,
+    value: Box<dyn Expression>,
+    pattern: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ParseGrokDynamicFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let pattern = self.pattern.resolve(ctx)?;
+        parse_grok_dynamic(&self.cache, value, pattern)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The pattern can be invalid, or not match the value.
+        TypeDef::object(Collection::from_unknown(Kind::bytes())).fallible()
+    }
+}
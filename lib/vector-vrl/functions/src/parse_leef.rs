@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+
+use vrl::{
+    prelude::*,
+    value::{
+        KeyString, ObjectMap,
+        kind::{Collection, Field},
+    },
+};
+
+/// The delimiter of the attributes of LEEF 1.0 events, and of LEEF 2.0 events not setting one.
+const DEFAULT_DELIMITER: char = '\t';
+
+/// The names of the fields of the LEEF header, after the version.
+const HEADER_FIELDS: [&str; 4] = ["deviceVendor", "deviceProduct", "deviceVersion", "eventId"];
+
+fn parse_leef(value: Value) -> Resolved {
+    let value = value.try_bytes_utf8_lossy()?;
+    // Anything before the LEEF header, such as a syslog header, is ignored.
+    let start = value
+        .find("LEEF:")
+        .ok_or("unable to parse LEEF: no LEEF header found")?;
+    let mut rest = &value[start + "LEEF:".len()..];
+
+    let mut event = ObjectMap::new();
+    let version = next_header_field(&mut rest).ok_or("unable to parse LEEF: missing version")?;
+    for name in HEADER_FIELDS {
+        let field = next_header_field(&mut rest)
+            .ok_or_else(|| format!("unable to parse LEEF: missing header field {name:?}"))?;
+        event.insert(name.into(), Value::from(unescape(&field, None)));
+    }
+
+    let delimiter = if version.starts_with('1') {
+        DEFAULT_DELIMITER
+    } else {
+        let delimiter = next_header_field(&mut rest)
+            .ok_or("unable to parse LEEF: missing delimiter of LEEF 2.0 event")?;
+        parse_delimiter(&delimiter)?
+    };
+    event.insert("leefVersion".into(), Value::from(version));
+
+    for attribute in split_unescaped(rest, delimiter) {
+        if attribute.trim().is_empty() {
+            continue;
+        }
+        // Only the first unescaped `=` separates the key from the value.
+        let (key, value) = find_unescaped(attribute, '=')
+            .map(|index| (attribute[..index].trim(), &attribute[index + 1..]))
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| format!("unable to parse LEEF: invalid attribute {attribute:?}"))?;
+        event.insert(
+            KeyString::from(unescape(key, Some(delimiter))),
+            Value::from(unescape(value, Some(delimiter))),
+        );
+    }
+
+    Ok(Value::Object(event))
+}
+
+/// Takes the next field of the header, ended by an unescaped `|`.
+fn next_header_field(rest: &mut &str) -> Option<String> {
+    if rest.is_empty() {
+        return None;
+    }
+    let field = match find_unescaped(rest, '|') {
+        Some(end) => {
+            let field = &rest[..end];
+            *rest = &rest[end + 1..];
+            field
+        }
+        None => std::mem::take(rest),
+    };
+    Some(field.to_owned())
+}
+
+/// Parses the delimiter of a LEEF 2.0 event, which is either a character or the hexadecimal code
+/// of a character prefixed with `x` or `0x`.
+fn parse_delimiter(delimiter: &str) -> Result<char, String> {
+    let mut chars = delimiter.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => return Ok(DEFAULT_DELIMITER),
+        (Some(delimiter), None) => return Ok(delimiter),
+        _ => {}
+    }
+    let lowercase = delimiter.to_ascii_lowercase();
+    lowercase
+        .strip_prefix("0x")
+        .or_else(|| lowercase.strip_prefix('x'))
+        .and_then(|code| u32::from_str_radix(code, 16).ok())
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("unable to parse LEEF: invalid delimiter {delimiter:?}"))
+}
+
+/// Finds the first occurrence of a separator not escaped with a backslash.
+fn find_unescaped(value: &str, separator: char) -> Option<usize> {
+    let mut escaped = false;
+    value.char_indices().find_map(|(index, char)| {
+        let found = char == separator && !escaped;
+        escaped = char == '\\' && !escaped;
+        found.then_some(index)
+    })
+}
+
+/// Splits a string on the occurrences of a separator not escaped with a backslash.
+fn split_unescaped(value: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut escaped = false;
+    value.split(move |char| {
+        let split = char == separator && !escaped;
+        escaped = char == '\\' && !escaped;
+        split
+    })
+}
+
+/// Removes the backslashes escaping the special characters of a field, which are the backslash,
+/// `|`, `=`, the delimiter of the attributes, and the line breaks and tabs written as `\n`, `\r`,
+/// and `\t`.
+fn unescape(value: &str, delimiter: Option<char>) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unescaped.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped) if Some(escaped) == delimiter => unescaped.push(escaped),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(escaped @ ('\\' | '|' | '=')) => unescaped.push(escaped),
+            // Backslashes not escaping a special character are kept as is.
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseLeef;
+
+impl Function for ParseLeef {
+    fn identifier(&self) -> &'static str {
+        "parse_leef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "Parse a LEEF 1.0 event",
+                source: r#"parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tusrName=joe.black")"#,
+                result: Ok(
+                    r#"{"deviceProduct": "MSExchange", "deviceVendor": "Microsoft", "deviceVersion": "4.0 SP1", "dst": "172.50.123.1", "eventId": "15345", "leefVersion": "1.0", "sev": "5", "src": "192.0.2.0", "usrName": "joe.black"}"#,
+                ),
+            },
+            Example {
+                title: "Parse a LEEF 2.0 event with a custom delimiter",
+                source: r#"parse_leef!(s'<13>Oct 16 12:00:00 host LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^msg=a \^ b^path=C:\\temp')"#,
+                result: Ok(
+                    r#"{"deviceProduct": "StealthWatch", "deviceVendor": "Lancope", "deviceVersion": "1.0", "eventId": "41", "leefVersion": "2.0", "msg": "a ^ b", "path": "C:\\temp", "src": "10.0.1.8"}"#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        Ok(ParseLeefFn { value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseLeefFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ParseLeefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        parse_leef(value)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        let mut fields = Collection::from(
+            HEADER_FIELDS
+                .into_iter()
+                .chain(["leefVersion"])
+                .map(|name| (Field::from(name), Kind::bytes()))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        fields.set_unknown(Kind::bytes());
+        // The value can be missing the LEEF header, or have invalid attributes.
+        TypeDef::object(fields).fallible()
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{
+    Local, TimeZone as _, Utc,
+    format::{Parsed, parse},
+};
+use vrl::{compiler::TimeZone, prelude::*};
+
+use crate::cache::PatternCache;
+
+fn parse_timestamp_dynamic(
+    cache: &PatternCache,
+    value: Value,
+    format: Value,
+    timezone: Option<Value>,
+    default_timezone: &TimeZone,
+) -> Resolved {
+    let value = value.try_bytes_utf8_lossy()?;
+    let format = format.try_bytes_utf8_lossy()?;
+    let timezone = match timezone {
+        Some(timezone) => {
+            let timezone = timezone.try_bytes_utf8_lossy()?;
+            TimeZone::parse(&timezone).ok_or_else(|| format!("unknown timezone {timezone:?}"))?
+        }
+        None => *default_timezone,
+    };
+    let items = cache
+        .date_format(&format)
+        .map_err(|error| format!("invalid format {format:?}: {error}"))?;
+
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, &value, items.iter())
+        .map_err(|error| format!("unable to parse {value:?} with format {format:?}: {error}"))?;
+    let timestamp = match parsed.to_datetime() {
+        Ok(timestamp) => timestamp.with_timezone(&Utc),
+        // Timestamps without an offset are in the given time zone.
+        Err(_) => {
+            let naive = parsed
+                .to_naive_datetime_with_offset(0)
+                .map_err(|error| format!("unable to parse {value:?}: {error}"))?;
+            let local = match timezone {
+                TimeZone::Local => Local
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|timestamp| timestamp.with_timezone(&Utc)),
+                TimeZone::Named(tz) => tz
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|timestamp| timestamp.with_timezone(&Utc)),
+            };
+            local.ok_or_else(|| format!("{value:?} doesn't exist in the time zone"))?
+        }
+    };
+    Ok(Value::from(timestamp))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseTimestampDynamic;
+
+impl Function for ParseTimestampDynamic {
+    fn identifier(&self) -> &'static str {
+        "parse_timestamp_dynamic"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "format",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Parse with a format read from the event",
+            source: r#"parse_timestamp_dynamic!("16/10/2019 12:00:00", format: "%d/%m/%Y %H:%M:%S", timezone: "Asia/Taipei")"#,
+            result: Ok("t'2019-10-16T04:00:00Z'"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let cache = ctx
+            .get_external_context::<PatternCache>()
+            .cloned()
+            .unwrap_or_default();
+        let value = arguments.required("value");
+        let format = arguments.required("format");
+        let timezone = arguments.optional("timezone");
+        Ok(ParseTimestampDynamicFn {
+            cache,
+            value,
+            format,
+            timezone,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseTimestampDynamicFn {
+    cache: PatternCache,
+    value: Box<dyn Expression>,
+    format: Box<dyn Expression>,
+    timezone: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for ParseTimestampDynamicFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let format = self.format.resolve(ctx)?;
+        let timezone = self
+            .timezone
+            .as_ref()
+            .map(|timezone| timezone.resolve(ctx))
+            .transpose()?;
+        parse_timestamp_dynamic(&self.cache, value, format, timezone, ctx.timezone())
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The format can be invalid, or not match the value.
+        TypeDef::timestamp().fallible()
+    }
+}
@@ -0,0 +1,76 @@
+use std::net::IpAddr;
+
+use vrl::prelude::*;
+
+use crate::dns::{DnsQuery, DnsResolver, dns_not_enabled};
+
+fn reverse_dns(resolver: &DnsResolver, value: Value) -> Resolved {
+    let address = value.try_bytes_utf8_lossy()?;
+    let address = address
+        .parse::<IpAddr>()
+        .map_err(|error| format!("unable to parse IP address: {error}"))?;
+    let names = resolver
+        .resolve(&DnsQuery::Reverse(address))
+        .map_err(|error| format!("unable to resolve IP address: {error}"))?;
+    let name = names
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("no host name found for {address}"))?;
+    Ok(Value::from(name))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReverseDns;
+
+impl Function for ReverseDns {
+    fn identifier(&self) -> &'static str {
+        "reverse_dns"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        // The resolver is only available to the programs of the `remap` transform, and the result
+        // depends on the name servers.
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let resolver = ctx
+            .get_external_context::<DnsResolver>()
+            .cloned()
+            .ok_or_else(|| dns_not_enabled(ctx.span()))?;
+        let value = arguments.required("value");
+        Ok(ReverseDnsFn { resolver, value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReverseDnsFn {
+    resolver: DnsResolver,
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ReverseDnsFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        reverse_dns(&self.resolver, value)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // The value can be an invalid address, and the lookup can fail, time out, or be rejected
+        // when too many lookups are in progress.
+        TypeDef::bytes().fallible()
+    }
+}
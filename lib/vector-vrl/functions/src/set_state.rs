@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use vrl::prelude::*;
+
+use crate::state::{StateStore, state_not_enabled};
+
+fn set_state(store: &StateStore, key: Value, value: Value, ttl: Option<Value>) -> Resolved {
+    let key_str = key.as_str().expect("key must be a string");
+    let ttl = match ttl {
+        Some(ttl) => {
+            let secs = ttl.try_integer()?;
+            let secs =
+                u64::try_from(secs).map_err(|_| format!("ttl must not be negative, got {secs}"))?;
+            Some(Duration::from_secs(secs))
+        }
+        None => None,
+    };
+
+    store
+        .set(key_str.as_ref(), value, ttl)
+        .map_err(|error| format!("failed writing state: {error}"))?;
+    Ok(Value::Null)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SetState;
+
+impl Function for SetState {
+    fn identifier(&self) -> &'static str {
+        "set_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "ttl",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "Count the events seen for a host over an hour",
+            source: r#"set_state!("host:my-host", 1, ttl: 3600)"#,
+            result: Ok("null"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let store = ctx
+            .get_external_context::<StateStore>()
+            .cloned()
+            .ok_or_else(|| state_not_enabled(ctx.span()))?;
+        let key = arguments.required("key");
+        let value = arguments.required("value");
+        let ttl = arguments.optional("ttl");
+        Ok(SetStateFn {
+            store,
+            key,
+            value,
+            ttl,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SetStateFn {
+    store: StateStore,
+    key: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+    ttl: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for SetStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        let value = self.value.resolve(ctx)?;
+        let ttl = self.ttl.as_ref().map(|ttl| ttl.resolve(ctx)).transpose()?;
+        set_state(&self.store, key, value, ttl)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        // Writing the state to its storage can fail.
+        TypeDef::null().fallible()
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use vrl::{
+    diagnostic::{Label, Span},
+    prelude::*,
+};
+
+pub type StateError = Box<dyn Error + Send + Sync>;
+
+/// A value kept in the state of a VRL program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateEntry {
+    pub value: Value,
+    /// When the entry expires, if it was set with a TTL.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl StateEntry {
+    pub fn expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Storage of the entries of a [`StateStore`], typically persisted by the component running the
+/// program.
+pub trait StateBackend: fmt::Debug + Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<StateEntry>, StateError>;
+
+    fn insert(&self, key: &str, entry: StateEntry) -> Result<(), StateError>;
+
+    fn remove(&self, key: &str) -> Result<(), StateError>;
+}
+
+/// Key-value state shared across the events processed by a VRL program.
+///
+/// The store is set as an external context of the compilation, making the `get_state` and
+/// `set_state` functions available to the program. Its backend is attached once the program is
+/// run, as the same program can be compiled without running it. Clones share the same backend.
+#[derive(Clone, Debug, Default)]
+pub struct StateStore {
+    backend: Arc<OnceLock<Arc<dyn StateBackend>>>,
+}
+
+impl StateStore {
+    /// Attaches the backend storing the entries, unless one is already attached.
+    pub fn attach(&self, backend: Arc<dyn StateBackend>) {
+        _ = self.backend.set(backend);
+    }
+
+    fn backend(&self) -> Result<&dyn StateBackend, StateError> {
+        self.backend
+            .get()
+            .map(AsRef::as_ref)
+            .ok_or_else(|| "state is not available".into())
+    }
+
+    /// Returns the value of the key, unless it is unset or expired.
+    pub fn get(&self, key: &str) -> Result<Option<Value>, StateError> {
+        let backend = self.backend()?;
+        match backend.get(key)? {
+            Some(entry) if entry.expired(SystemTime::now()) => {
+                backend.remove(key)?;
+                Ok(None)
+            }
+            entry => Ok(entry.map(|entry| entry.value)),
+        }
+    }
+
+    /// Sets the value of the key, expiring after the TTL if any.
+    pub fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StateError> {
+        let expires_at = ttl.map(|ttl| SystemTime::now() + ttl);
+        self.backend()?
+            .insert(key, StateEntry { value, expires_at })
+    }
+}
+
+/// The error returned when compiling a state function for a program without state.
+pub(crate) fn state_not_enabled(span: Span) -> Box<dyn DiagnosticMessage> {
+    Box::new(ExpressionError::Error {
+        message: "state is not enabled".to_owned(),
+        labels: vec![Label::primary(
+            "state functions require the `state` option of the transform",
+            span,
+        )],
+        notes: vec![],
+    })
+}
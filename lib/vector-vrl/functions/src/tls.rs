@@ -0,0 +1,39 @@
+//! Helpers shared by the functions fingerprinting TLS client hellos.
+
+use ring::digest::{SHA256, digest};
+use vrl::prelude::*;
+
+/// Whether a code is one of the GREASE values of RFC 8701, which clients add to their hellos at
+/// random, and are left out of the fingerprints.
+pub(crate) const fn is_grease(code: u16) -> bool {
+    code & 0x0f0f == 0x0a0a && code >> 8 == code & 0xff
+}
+
+/// Reads a code of a hello field, such as a version or a cipher suite.
+pub(crate) fn tls_code(value: Value, field: &str) -> Result<u16, ExpressionError> {
+    let code = value.try_integer()?;
+    u16::try_from(code)
+        .map_err(|_| format!("`{field}` must only contain TLS codes, got {code}").into())
+}
+
+/// Reads the codes of a hello field, leaving out the GREASE values.
+pub(crate) fn tls_codes(value: Value, field: &str) -> Result<Vec<u16>, ExpressionError> {
+    let mut codes = Vec::new();
+    for code in value.try_array()? {
+        let code = tls_code(code, field)?;
+        if !is_grease(code) {
+            codes.push(code);
+        }
+    }
+    Ok(codes)
+}
+
+/// Returns the first 12 characters of the hex-encoded SHA-256 hash of a value.
+pub(crate) fn truncated_sha256(value: &str) -> String {
+    digest(&SHA256, value.as_bytes())
+        .as_ref()
+        .iter()
+        .take(6)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
@@ -0,0 +1,293 @@
+//! Functions defined in VRL, in libraries included by the programs calling them:
+//!
+//! ```text
+//! # Parses the access logs of the load balancers.
+//! fn parse_lb_log(message) {
+//!     parsed = parse_regex!(message, r'^(?P<client>\S+) (?P<status>\d+)$')
+//!     parsed.status = to_int!(parsed.status)
+//!     parsed
+//! }
+//! ```
+//!
+//! The body of a function is compiled as a program of its own, run with the arguments bound to the
+//! variables named after the parameters, and returning the value of its last expression.
+
+use std::{collections::HashSet, sync::Arc};
+
+use vrl::{
+    compiler::{Program, TargetValue, state::RuntimeState},
+    prelude::*,
+    value::{ObjectMap, Secrets},
+};
+
+/// A function defined in a library, before its body is compiled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionDefinition {
+    pub name: String,
+    pub parameters: Vec<String>,
+    /// The source of the body, starting by binding the arguments to their variables.
+    pub source: String,
+    /// The line of the library the function is defined at.
+    pub line: usize,
+}
+
+/// Parses the functions defined in a library, in order.
+pub fn parse_library(library: &str) -> Result<Vec<FunctionDefinition>, String> {
+    let mut definitions: Vec<FunctionDefinition> = Vec::new();
+    let mut rest = skip_blank(library);
+    while !rest.is_empty() {
+        let line = library[..library.len() - rest.len()].matches('\n').count() + 1;
+        let error = |reason: &str| format!("invalid function definition at line {line}: {reason}");
+
+        let header = rest
+            .strip_prefix("fn")
+            .filter(|header| header.starts_with(char::is_whitespace))
+            .ok_or_else(|| error("expected `fn <name>(<parameters>) { <body> }`"))?;
+        let (name, header) = split_ident(header.trim_start())
+            .ok_or_else(|| error("expected the name of the function"))?;
+        let (parameters, header) = header
+            .trim_start()
+            .strip_prefix('(')
+            .and_then(|header| header.split_once(')'))
+            .ok_or_else(|| error("expected the parameters of the function in parentheses"))?;
+        let body = header
+            .trim_start()
+            .strip_prefix('{')
+            .ok_or_else(|| error("expected the body of the function in braces"))?;
+        let end = find_closing_brace(body).ok_or_else(|| error("unclosed body"))?;
+
+        let parameters = if parameters.trim().is_empty() {
+            Vec::new()
+        } else {
+            parameters
+                .split(',')
+                .map(|parameter| {
+                    split_ident(parameter.trim())
+                        .filter(|(_, rest)| rest.is_empty())
+                        .map(|(parameter, _)| parameter.to_owned())
+                        .ok_or_else(|| error(&format!("invalid parameter {parameter:?}")))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let mut unique = HashSet::new();
+        if let Some(duplicate) = parameters
+            .iter()
+            .find(|parameter| !unique.insert(*parameter))
+        {
+            return Err(error(&format!("duplicate parameter {duplicate:?}")));
+        }
+        if definitions.iter().any(|definition| definition.name == name) {
+            return Err(error(&format!("function {name:?} is already defined")));
+        }
+
+        // The arguments are bound on the line of the header, so that the lines of the body match
+        // the lines of the library.
+        let bindings = parameters
+            .iter()
+            .map(|parameter| format!("{parameter} = .{parameter};"))
+            .collect::<String>();
+        definitions.push(FunctionDefinition {
+            name: name.to_owned(),
+            parameters,
+            source: format!("{bindings}{}", &body[..end]),
+            line,
+        });
+        rest = skip_blank(&body[end + 1..]);
+    }
+    Ok(definitions)
+}
+
+/// Skips the whitespace and the comments.
+fn skip_blank(mut rest: &str) -> &str {
+    loop {
+        rest = rest.trim_start();
+        match rest.strip_prefix('#') {
+            Some(comment) => rest = comment.split_once('\n').map_or("", |(_, rest)| rest),
+            None => return rest,
+        }
+    }
+}
+
+/// Splits the identifier a string starts with.
+fn split_ident(value: &str) -> Option<(&str, &str)> {
+    let end = value
+        .find(|char: char| !char.is_ascii_alphanumeric() && char != '_')
+        .unwrap_or(value.len());
+    let ident = &value[..end];
+    ident
+        .starts_with(|char: char| char.is_ascii_alphabetic() || char == '_')
+        .then(|| (ident, &value[end..]))
+}
+
+/// Finds the brace closing a body, skipping the strings and the comments.
+fn find_closing_brace(body: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut chars = body.char_indices();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            '#' => {
+                chars.find(|(_, char)| *char == '\n');
+            }
+            // Both the string literals and the raw string literals, such as `r'...'`.
+            '"' | '\'' => {
+                let quote = char;
+                let mut escaped = false;
+                chars.find(|(_, char)| {
+                    let closed = *char == quote && !escaped;
+                    escaped = *char == '\\' && !escaped;
+                    closed
+                })?;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A function defined in a library, callable by the programs including the library.
+#[derive(Clone, Debug)]
+pub struct UserFunction {
+    identifier: &'static str,
+    parameters: &'static [Parameter],
+    program: Arc<Program>,
+}
+
+impl UserFunction {
+    /// Creates the function from its definition, and the program compiled from its body.
+    ///
+    /// The functions are identified by static strings, so the name and the parameters are leaked,
+    /// which is why the libraries should only be compiled once for each version of their source.
+    pub fn new(definition: &FunctionDefinition, program: Program) -> Self {
+        let parameters = definition
+            .parameters
+            .iter()
+            .map(|parameter| Parameter {
+                keyword: String::leak(parameter.clone()),
+                kind: kind::ANY,
+                required: true,
+            })
+            .collect::<Vec<_>>();
+        Self {
+            identifier: String::leak(definition.name.clone()),
+            parameters: Vec::leak(parameters),
+            program: Arc::new(program),
+        }
+    }
+}
+
+impl Function for UserFunction {
+    fn identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        self.parameters
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let arguments = self
+            .parameters
+            .iter()
+            .map(|parameter| arguments.required(parameter.keyword))
+            .collect();
+        Ok(UserFunctionFn {
+            function: self.clone(),
+            arguments,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UserFunctionFn {
+    function: UserFunction,
+    arguments: Vec<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for UserFunctionFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let mut arguments = ObjectMap::new();
+        for (parameter, argument) in self.function.parameters.iter().zip(&self.arguments) {
+            arguments.insert(parameter.keyword.into(), argument.resolve(ctx)?);
+        }
+
+        // The body only sees its arguments, and not the event of the caller.
+        let mut target = TargetValue {
+            value: Value::Object(arguments),
+            metadata: Value::Object(ObjectMap::new()),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let timezone = *ctx.timezone();
+        self.function
+            .program
+            .resolve(&mut Context::new(&mut target, &mut state, &timezone))
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        let program = &self.function.program;
+        let info = program.info();
+        TypeDef::from(program.final_type_info().result.kind().clone())
+            .maybe_fallible(info.fallible || info.abortable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_library() {
+        let library = r##"# Comments and braces in strings are skipped.
+fn greet(name, greeting) {
+    # }
+    greeting + ", " + name + "}"
+}
+
+fn parse_status(message) {
+    parsed = parse_regex!(message, r'status=(?P<status>\d+)\'}')
+    if parsed.status == "500" { "error" } else { "ok" }
+}
+fn now_utc() { now() }
+"##;
+
+        let definitions = parse_library(library).unwrap();
+        let names = definitions
+            .iter()
+            .map(|definition| (definition.name.as_str(), definition.line))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("greet", 2), ("parse_status", 7), ("now_utc", 11)]);
+        assert_eq!(
+            definitions[0].source,
+            "name = .name;greeting = .greeting;\n    # }\n    greeting + \", \" + name + \"}\"\n"
+        );
+        assert_eq!(definitions[2].source, " now() ");
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        for (library, reason) in [
+            ("greet(name) { name }", "expected `fn"),
+            ("fn greet(name) name }", "expected the body"),
+            ("fn greet(name) { name", "unclosed body"),
+            ("fn greet(1name) { name }", "invalid parameter"),
+            ("fn greet(name, name) { name }", "duplicate parameter"),
+            ("fn f() { 1 }\nfn f() { 2 }", "already defined"),
+        ] {
+            let error = parse_library(library).unwrap_err();
+            assert!(error.contains(reason), "{library:?}: {error}");
+        }
+    }
+}
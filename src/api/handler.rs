@@ -3,8 +3,22 @@ use std::sync::{
     atomic::{self, AtomicBool},
 };
 
+use bytes::Bytes;
+use serde::Serialize;
 use serde_json::json;
-use warp::{Rejection, Reply, reply::json};
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{Json, WithStatus, json},
+};
+
+use vector_lib::config::InternalLogRateLimit;
+
+use crate::{
+    config::{self, Format},
+    signal::{SignalTo, SignalTx},
+    trace,
+};
 
 // Health handler, responds with '{ ok: true }' when running and '{ ok: false}'
 // when shutting down
@@ -21,3 +35,332 @@ pub(super) async fn health(running: Arc<AtomicBool>) -> Result<impl Reply, Rejec
         ))
     }
 }
+
+/// The outcome of validating a config sent to the API.
+#[derive(Debug, Default, Serialize)]
+struct ConfigDiagnostics {
+    valid: bool,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl ConfigDiagnostics {
+    fn valid(warnings: Vec<String>) -> Self {
+        Self {
+            valid: true,
+            errors: Vec::new(),
+            warnings,
+        }
+    }
+
+    fn invalid(errors: Vec<String>) -> Self {
+        Self {
+            errors,
+            ..Default::default()
+        }
+    }
+
+    fn reply(self, status: StatusCode) -> WithStatus<Json> {
+        warp::reply::with_status(json(&self), status)
+    }
+}
+
+// Validates the config in the request body, responding with its diagnostics without applying it.
+pub(super) async fn validate_config(
+    origin: Option<String>,
+    content_type: Option<String>,
+    body: Bytes,
+    signal_tx: SignalTx,
+) -> Result<impl Reply, Rejection> {
+    let reply = match load_config(origin, content_type, &body, &signal_tx).await {
+        Ok((_, _, warnings)) => ConfigDiagnostics::valid(warnings).reply(StatusCode::OK),
+        Err((status, errors)) => ConfigDiagnostics::invalid(errors).reply(status),
+    };
+    Ok(reply)
+}
+
+// Validates the config in the request body and, if valid, reloads Vector with it. The reload is
+// applied asynchronously, in the same way as reloads triggered by signals or providers.
+pub(super) async fn reload_config(
+    origin: Option<String>,
+    content_type: Option<String>,
+    body: Bytes,
+    signal_tx: SignalTx,
+) -> Result<impl Reply, Rejection> {
+    let (input, format, warnings) = match load_config(origin, content_type, &body, &signal_tx).await
+    {
+        Ok(loaded) => loaded,
+        Err((status, errors)) => return Ok(ConfigDiagnostics::invalid(errors).reply(status)),
+    };
+
+    // The config is loaded again from its input, rather than from the validated builder, so that
+    // its secrets are watched and retrieved again when they change.
+    let reply = if signal_tx
+        .send(SignalTo::ReloadFromInput(input, format))
+        .is_ok()
+    {
+        info!(message = "Reloading config received through the API.");
+        ConfigDiagnostics::valid(warnings).reply(StatusCode::ACCEPTED)
+    } else {
+        ConfigDiagnostics::invalid(vec!["Vector is shutting down.".into()])
+            .reply(StatusCode::SERVICE_UNAVAILABLE)
+    };
+    Ok(reply)
+}
+
+// Responds with the rate limits of the internal logs currently in effect.
+pub(super) async fn internal_log_rate_limit() -> Result<impl Reply, Rejection> {
+    Ok(json(&trace::internal_log_rate_limit()))
+}
+
+// Replaces the rate limits of the internal logs with the ones in the request body. They're in effect
+// until they're changed again, by this endpoint or by a reload of a config changing them.
+pub(super) async fn set_internal_log_rate_limit(
+    origin: Option<String>,
+    rate_limit: InternalLogRateLimit,
+) -> Result<impl Reply, Rejection> {
+    if origin.is_some() {
+        return Ok(warp::reply::with_status(
+            json(&json!({"errors": ["The rate limits can't be changed from web browsers."]})),
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    trace::set_internal_log_rate_limit(&rate_limit);
+    info!(
+        message = "Changed the rate limits of the internal logs through the API.",
+        window_secs = ?rate_limit.window_secs,
+        components = ?rate_limit.components,
+        internal_log_rate_limit = false
+    );
+    Ok(warp::reply::with_status(json(&rate_limit), StatusCode::OK))
+}
+
+async fn load_config(
+    origin: Option<String>,
+    content_type: Option<String>,
+    body: &[u8],
+    signal_tx: &SignalTx,
+) -> Result<(String, Format, Vec<String>), (StatusCode, Vec<String>)> {
+    // Web browsers always send an origin along with `POST` requests, while the clients these
+    // endpoints are meant for don't.
+    if origin.is_some() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            vec!["The config can't be changed from web browsers.".into()],
+        ));
+    }
+
+    let format = match content_type.as_deref().map(format_from_content_type) {
+        None => Format::default(),
+        Some(Some(format)) => format,
+        Some(None) => {
+            return Err((
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                vec![format!(
+                    "Unsupported content type {:?}, expected a TOML, YAML or JSON config.",
+                    content_type.unwrap_or_default()
+                )],
+            ));
+        }
+    };
+    let input = std::str::from_utf8(body).map_err(|error| {
+        (
+            StatusCode::BAD_REQUEST,
+            vec![format!("Config isn't valid UTF-8: {error}.")],
+        )
+    })?;
+
+    let (_, warnings) =
+        config::load_builder_from_str_with_secrets(input, format, &mut signal_tx.subscribe())
+            .await
+            .map_err(|errors| (StatusCode::UNPROCESSABLE_ENTITY, errors))?;
+    Ok((input.to_owned(), format, warnings))
+}
+
+fn format_from_content_type(content_type: &str) -> Option<Format> {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    match essence.to_ascii_lowercase().as_str() {
+        "application/toml" | "text/toml" => Some(Format::Toml),
+        "application/json" | "text/json" => Some(Format::Json),
+        "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+            Some(Format::Yaml)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::SignalHandler;
+
+    async fn response_parts(reply: impl Reply) -> (StatusCode, serde_json::Value) {
+        let response = reply.into_response();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn formats_from_content_types() {
+        assert_eq!(
+            format_from_content_type("application/json"),
+            Some(Format::Json)
+        );
+        assert_eq!(
+            format_from_content_type("application/yaml; charset=utf-8"),
+            Some(Format::Yaml)
+        );
+        assert_eq!(format_from_content_type("text/TOML"), Some(Format::Toml));
+        assert_eq!(format_from_content_type("text/plain"), None);
+    }
+
+    #[tokio::test]
+    async fn invalid_config_is_not_reloaded() {
+        let (handler, mut signal_rx) = SignalHandler::new();
+        let reply = reload_config(
+            None,
+            Some("application/toml".into()),
+            Bytes::from_static(b"[sinks.out]\ntype = \"blackhole\"\ninputs = [\"in\"]\n"),
+            handler.clone_tx(),
+        )
+        .await
+        .unwrap();
+
+        let (status, body) = response_parts(reply).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["valid"], false);
+        assert!(!body["errors"].as_array().unwrap().is_empty());
+        assert!(signal_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_secret_backends_reading_the_host() {
+        let config = indoc::indoc! {r#"
+            [secret.local]
+            type = "exec"
+            command = ["/path/to/script"]
+
+            [sinks.out]
+            type = "blackhole"
+            inputs = ["SECRET[local.input]"]
+        "#};
+
+        let (handler, _signal_rx) = SignalHandler::new();
+        let reply = validate_config(
+            None,
+            Some("application/toml".into()),
+            Bytes::from_static(config.as_bytes()),
+            handler.clone_tx(),
+        )
+        .await
+        .unwrap();
+
+        let (status, body) = response_parts(reply).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            body["errors"][0]
+                .as_str()
+                .unwrap()
+                .contains("can't read secrets from the host")
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_from_browsers() {
+        let (handler, mut signal_rx) = SignalHandler::new();
+        let reply = reload_config(
+            Some("https://example.com".into()),
+            Some("application/toml".into()),
+            Bytes::from_static(b""),
+            handler.clone_tx(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response_parts(reply).await.0, StatusCode::FORBIDDEN);
+        assert!(signal_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn unsupported_content_type() {
+        let (handler, _signal_rx) = SignalHandler::new();
+        let reply = validate_config(
+            None,
+            Some("text/plain".into()),
+            Bytes::from_static(b""),
+            handler.clone_tx(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            response_parts(reply).await.0,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+
+    #[tokio::test]
+    async fn sets_internal_log_rate_limit() {
+        let rate_limit = InternalLogRateLimit {
+            window_secs: Some(30),
+            components: [("out".to_owned(), 0)].into(),
+        };
+
+        let reply =
+            set_internal_log_rate_limit(Some("https://example.com".into()), rate_limit.clone())
+                .await
+                .unwrap();
+        assert_eq!(response_parts(reply).await.0, StatusCode::FORBIDDEN);
+
+        let reply = set_internal_log_rate_limit(None, rate_limit).await.unwrap();
+        let (status, body) = response_parts(reply).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({"window_secs": 30, "components": {"out": 0}}));
+
+        trace::set_internal_log_rate_limit(&InternalLogRateLimit::default());
+    }
+
+    #[cfg(all(feature = "sources-demo_logs", feature = "sinks-blackhole"))]
+    #[tokio::test]
+    async fn valid_config_is_reloaded() {
+        let config = indoc::indoc! {r#"
+            [sources.in]
+            type = "demo_logs"
+            format = "json"
+
+            [sinks.out]
+            type = "blackhole"
+            inputs = ["in"]
+        "#};
+
+        let (handler, mut signal_rx) = SignalHandler::new();
+        let reply = validate_config(
+            None,
+            Some("application/toml".into()),
+            Bytes::from_static(config.as_bytes()),
+            handler.clone_tx(),
+        )
+        .await
+        .unwrap();
+        let (status, body) = response_parts(reply).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["valid"], true);
+        assert!(signal_rx.try_recv().is_err());
+
+        let reply = reload_config(
+            None,
+            Some("application/toml".into()),
+            Bytes::from_static(config.as_bytes()),
+            handler.clone_tx(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response_parts(reply).await.0, StatusCode::ACCEPTED);
+        assert!(matches!(
+            signal_rx.try_recv(),
+            Ok(SignalTo::ReloadFromInput(_, Format::Toml))
+        ));
+    }
+}
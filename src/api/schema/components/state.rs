@@ -50,6 +50,24 @@ pub fn get_sinks() -> Vec<sink::Sink> {
     })
 }
 
+/// Returns the components upstream of each of the transforms and sinks
+pub fn get_upstreams() -> HashMap<ComponentKey, Vec<ComponentKey>> {
+    filter_components(|(component_key, components)| {
+        let inputs = match components {
+            Component::Source(_) => return None,
+            Component::Transform(t) => &t.0.inputs,
+            Component::Sink(s) => &s.0.inputs,
+        };
+        let upstreams = inputs
+            .iter()
+            .map(|output_id| output_id.component.clone())
+            .collect();
+        Some((component_key.clone(), upstreams))
+    })
+    .into_iter()
+    .collect()
+}
+
 /// Returns the current component component_keys as a HashSet
 pub fn get_component_keys() -> HashSet<ComponentKey> {
     COMPONENTS
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use async_graphql::Object;
 use chrono::{DateTime, Utc};
+use uuid::Uuid;
 use vector_lib::{encode_logfmt, event, tap::topology::TapOutput};
 use vrl::event_path;
 
@@ -18,6 +19,10 @@ impl Log {
         Self { output, event }
     }
 
+    pub fn get_trace_id(&self) -> Option<Uuid> {
+        self.event.metadata().trace_id()
+    }
+
     pub fn get_message(&self) -> Option<Cow<'_, str>> {
         Some(self.event.get(event_path!("message"))?.to_string_lossy())
     }
@@ -45,6 +50,11 @@ impl Log {
         self.output.component_kind
     }
 
+    /// Id of the trace of the log event, if it's traced
+    async fn trace_id(&self) -> Option<String> {
+        self.get_trace_id().map(|id| id.to_string())
+    }
+
     /// Log message
     async fn message(&self) -> Option<String> {
         self.get_message().map(Into::into)
@@ -1,6 +1,7 @@
 use async_graphql::{Enum, Object};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
+use uuid::Uuid;
 use vector_lib::{encode_logfmt, event, tap::topology::TapOutput};
 
 use super::EventEncodingType;
@@ -15,6 +16,10 @@ impl Metric {
     pub const fn new(output: TapOutput, event: event::Metric) -> Self {
         Self { output, event }
     }
+
+    pub fn get_trace_id(&self) -> Option<Uuid> {
+        self.event.metadata().trace_id()
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Enum)]
@@ -70,6 +75,11 @@ impl Metric {
         self.output.component_kind
     }
 
+    /// Id of the trace of the metric event, if it's traced
+    async fn trace_id(&self) -> Option<String> {
+        self.get_trace_id().map(|id| id.to_string())
+    }
+
     /// Metric timestamp
     async fn timestamp(&self) -> Option<&DateTime<Utc>> {
         self.event.data().timestamp()
@@ -4,7 +4,10 @@ pub mod metric;
 pub mod output;
 pub mod trace;
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    num::NonZeroU64,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use async_graphql::{Context, Subscription};
 use encoding::EventEncodingType;
@@ -13,9 +16,12 @@ use output::{OutputEventsPayload, from_tap_payload_to_output_events};
 use rand::{Rng, SeedableRng, rngs::SmallRng};
 use tokio::{select, sync::mpsc, time};
 use tokio_stream::wrappers::ReceiverStream;
-use vector_lib::tap::{
-    controller::{TapController, TapPatterns},
-    topology::WatchRx,
+use vector_lib::{
+    event::trace_sampling::TraceSampling,
+    tap::{
+        controller::{TapController, TapPatterns},
+        topology::WatchRx,
+    },
 };
 
 #[derive(Debug, Default)]
@@ -24,6 +30,11 @@ pub struct EventsSubscription;
 #[Subscription]
 impl EventsSubscription {
     /// A stream of events emitted from matched component ID patterns
+    ///
+    /// With a trace sample rate of N, one in every N of the events sent by the sources is traced,
+    /// and only the traced events are streamed, grouped by trace. The events derived from a traced
+    /// event keep its trace ID, so that the transforms it went through, and what each of them
+    /// emitted, can be followed.
     pub async fn output_events_by_component_id_patterns<'a>(
         &'a self,
         ctx: &'a Context<'a>,
@@ -31,6 +42,7 @@ impl EventsSubscription {
         inputs_patterns: Option<Vec<String>>,
         #[graphql(default = 500)] interval: u32,
         #[graphql(default = 100, validator(minimum = 1, maximum = 10_000))] limit: u32,
+        #[graphql(validator(minimum = 1))] trace_sample_rate: Option<u32>,
     ) -> impl Stream<Item = Vec<OutputEventsPayload>> + 'a {
         let watch_rx = ctx.data_unchecked::<WatchRx>().clone();
 
@@ -39,18 +51,26 @@ impl EventsSubscription {
             for_inputs: inputs_patterns.unwrap_or_default().into_iter().collect(),
         };
         // Client input is confined to `u32` to provide sensible bounds.
-        create_events_stream(watch_rx, patterns, interval as u64, limit as usize)
+        create_events_stream(
+            watch_rx,
+            patterns,
+            interval as u64,
+            limit as usize,
+            trace_sample_rate.and_then(|rate| NonZeroU64::new(rate.into())),
+        )
     }
 }
 
 /// Creates an events stream based on component ids, and a provided interval. Will emit
 /// control messages that bubble up the application if the sink goes away. The stream contains
-/// all matching events; filtering should be done at the caller level.
+/// all matching events; filtering should be done at the caller level. With a trace sample rate,
+/// the events are traced by the sources and only the traced ones are streamed, grouped by trace.
 pub(crate) fn create_events_stream(
     watch_rx: WatchRx,
     patterns: TapPatterns,
     interval: u64,
     limit: usize,
+    trace_sample_rate: Option<NonZeroU64>,
 ) -> impl Stream<Item = Vec<OutputEventsPayload>> {
     // Channel for receiving individual tap payloads. Since we can process at most `limit` per
     // interval, this is capped to the same value.
@@ -68,6 +88,9 @@ pub(crate) fn create_events_stream(
         // event handlers and topology observation that the tap controller provides.
         let _tap_controller = TapController::new(watch_rx, tap_tx, patterns);
 
+        // The sources trace events while the tracing session is active.
+        let trace_sampling = trace_sample_rate.map(TraceSampling::start);
+
         // A tick interval to represent when to 'cut' the results back to the client.
         let mut interval = time::interval(time::Duration::from_millis(interval));
 
@@ -107,6 +130,13 @@ pub(crate) fn create_events_stream(
                             debug!(message = "Couldn't send notification.", error = ?err);
                             break;
                         }
+                    } else if trace_sampling.is_some() {
+                        // Traced events aren't sampled, so that their traces are complete. The
+                        // trace sample rate bounds their number instead.
+                        if payload.trace_id().is_some() && limit > results.len() {
+                            results.push(SortableOutputEventsPayload { batch, payload });
+                            batch += 1;
+                        }
                     } else {
                         // Wrap tap in a 'sortable' wrapper, using the batch as a key, to
                         // re-sort after random eviction.
@@ -135,8 +165,9 @@ pub(crate) fn create_events_stream(
                         batch = 0;
 
                         // Since events will appear out of order per the random sampling
-                        // strategy, drain the existing results and sort by timestamp.
-                        results.sort_by_key(|r| r.batch);
+                        // strategy, drain the existing results and sort by timestamp. Traced
+                        // events are grouped by trace.
+                        results.sort_by_key(|r| (r.payload.trace_id(), r.batch));
                         let results = results.drain(..)
                             .map(|r| r.payload)
                             .collect();
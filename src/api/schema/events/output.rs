@@ -1,4 +1,5 @@
 use async_graphql::{Object, Union};
+use uuid::Uuid;
 use vector_lib::tap::{controller::TapPayload, notification::Notification};
 
 use crate::api::schema::events::{log::Log, metric::Metric, trace::Trace};
@@ -46,6 +47,18 @@ pub enum OutputEventsPayload {
     Trace(Trace),
 }
 
+impl OutputEventsPayload {
+    /// The id of the trace of the event, if it's a traced event.
+    pub fn trace_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Log(log) => log.get_trace_id(),
+            Self::Metric(metric) => metric.get_trace_id(),
+            Self::Trace(trace) => trace.get_trace_id(),
+            Self::Notification(_) => None,
+        }
+    }
+}
+
 /// Convert an `api::TapPayload` to the equivalent GraphQL type.
 pub(crate) fn from_tap_payload_to_output_events(t: TapPayload) -> Vec<OutputEventsPayload> {
     match t {
@@ -1,4 +1,5 @@
 use async_graphql::Object;
+use uuid::Uuid;
 use vector_lib::{encode_logfmt, event, tap::topology::TapOutput};
 use vrl::event_path;
 
@@ -14,6 +15,10 @@ impl Trace {
     pub const fn new(output: TapOutput, event: event::TraceEvent) -> Self {
         Self { output, event }
     }
+
+    pub fn get_trace_id(&self) -> Option<Uuid> {
+        self.event.metadata().trace_id()
+    }
 }
 
 #[Object]
@@ -34,6 +39,11 @@ impl Trace {
         self.output.component_kind
     }
 
+    /// Id of the trace of the trace event, if it's traced
+    async fn trace_id(&self) -> Option<String> {
+        self.get_trace_id().map(|id| id.to_string())
+    }
+
     /// Trace event as an encoded string format
     async fn string(&self, encoding: EventEncodingType) -> String {
         match encoding {
@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use async_graphql::Object;
+
+use crate::{
+    config::ComponentKey,
+    event::{Metric, MetricValue},
+};
+
+/// The utilization above which a component is saturated, spending nearly all of its time
+/// processing or sending its events rather than waiting for more.
+const SATURATED_UTILIZATION: f64 = 0.95;
+
+/// The ratio of its capacity above which a buffer is saturated.
+const SATURATED_BUFFER: f64 = 0.9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentBufferUtilization {
+    component_key: ComponentKey,
+    events: f64,
+    byte_size: f64,
+    max_events: Option<f64>,
+    max_byte_size: Option<f64>,
+    utilization: Option<f64>,
+    bottleneck: bool,
+}
+
+impl ComponentBufferUtilization {
+    const fn new(component_key: ComponentKey) -> Self {
+        Self {
+            component_key,
+            events: 0.0,
+            byte_size: 0.0,
+            max_events: None,
+            max_byte_size: None,
+            utilization: None,
+            bottleneck: false,
+        }
+    }
+
+    /// The ratio of the capacity of the buffer in use, by events or bytes, whichever is higher.
+    fn ratio(&self) -> Option<f64> {
+        let events = self
+            .max_events
+            .filter(|max| *max > 0.0)
+            .map(|max| self.events / max);
+        let bytes = self
+            .max_byte_size
+            .filter(|max| *max > 0.0)
+            .map(|max| self.byte_size / max);
+        match (events, bytes) {
+            (Some(events), Some(bytes)) => Some(events.max(bytes)),
+            (events, bytes) => events.or(bytes),
+        }
+    }
+
+    fn is_saturated(&self) -> bool {
+        self.utilization
+            .is_some_and(|utilization| utilization >= SATURATED_UTILIZATION)
+            || self.ratio().is_some_and(|ratio| ratio >= SATURATED_BUFFER)
+    }
+}
+
+#[Object]
+impl ComponentBufferUtilization {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Number of events in the buffer of the component
+    async fn buffer_events(&self) -> f64 {
+        self.events
+    }
+
+    /// Number of bytes in the buffer of the component
+    async fn buffer_byte_size(&self) -> f64 {
+        self.byte_size
+    }
+
+    /// Maximum number of events in the buffer of the component, if limited by events
+    async fn buffer_max_events(&self) -> Option<f64> {
+        self.max_events
+    }
+
+    /// Maximum number of bytes in the buffer of the component, if limited by bytes
+    async fn buffer_max_byte_size(&self) -> Option<f64> {
+        self.max_byte_size
+    }
+
+    /// Ratio of the capacity of the buffer in use, by events or bytes, whichever is higher
+    async fn buffer_utilization(&self) -> Option<f64> {
+        self.ratio()
+    }
+
+    /// Ratio of the time the component spends processing or sending events, rather than waiting
+    /// for more
+    async fn utilization(&self) -> Option<f64> {
+        self.utilization
+    }
+
+    /// Whether the component is the bottleneck of the backpressure. The component is saturated,
+    /// while none of the components downstream of it are.
+    async fn is_bottleneck(&self) -> bool {
+        self.bottleneck
+    }
+}
+
+/// Returns the buffer utilization of the components having a buffer or a utilization, given the
+/// components upstream of each of the components.
+pub fn component_buffer_utilizations(
+    metrics: Vec<Metric>,
+    upstreams: &HashMap<ComponentKey, Vec<ComponentKey>>,
+) -> Vec<ComponentBufferUtilization> {
+    let mut components = BTreeMap::new();
+    for metric in metrics {
+        let &MetricValue::Gauge { value } = metric.value() else {
+            continue;
+        };
+        let Some(id) = metric.tag_value("component_id") else {
+            continue;
+        };
+        let mut component = || {
+            components
+                .entry(id.clone())
+                .or_insert_with(|| ComponentBufferUtilization::new(ComponentKey::from(id.as_str())))
+        };
+        // The stages of the buffers are summed.
+        match metric.name() {
+            "buffer_events" => component().events += value,
+            "buffer_byte_size" => component().byte_size += value,
+            "buffer_max_event_size" => {
+                *component().max_events.get_or_insert(0.0) += value;
+            }
+            "buffer_max_byte_size" => {
+                *component().max_byte_size.get_or_insert(0.0) += value;
+            }
+            "utilization" => component().utilization = Some(value),
+            _ => {}
+        }
+    }
+
+    // A saturated component holds back the components upstream of it, which are saturated too
+    // while they wait to send their events, so the bottleneck is the most downstream one.
+    let saturated = components
+        .values()
+        .filter(|component| component.is_saturated())
+        .map(|component| &component.component_key)
+        .collect::<HashSet<_>>();
+    let held_back = upstreams
+        .iter()
+        .filter(|(key, _)| saturated.contains(key))
+        .flat_map(|(_, upstreams)| upstreams)
+        .collect::<HashSet<_>>();
+    let bottlenecks = saturated
+        .into_iter()
+        .filter(|key| !held_back.contains(key))
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    components
+        .into_values()
+        .map(|mut component| {
+            component.bottleneck = bottlenecks.contains(&component.component_key);
+            component
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::metric_tags;
+
+    use super::*;
+    use crate::event::MetricKind;
+
+    fn gauge(name: &str, component_id: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+            .with_tags(Some(metric_tags!("component_id" => component_id)))
+    }
+
+    fn utilizations(metrics: Vec<Metric>) -> BTreeMap<String, ComponentBufferUtilization> {
+        let upstreams = HashMap::from([
+            (
+                ComponentKey::from("transform"),
+                vec![ComponentKey::from("in")],
+            ),
+            (
+                ComponentKey::from("out"),
+                vec![ComponentKey::from("transform")],
+            ),
+        ]);
+        component_buffer_utilizations(metrics, &upstreams)
+            .into_iter()
+            .map(|component| (component.component_key.id().to_string(), component))
+            .collect()
+    }
+
+    #[test]
+    fn sums_buffer_stages() {
+        let components = utilizations(vec![
+            gauge("buffer_events", "out", 10.0),
+            gauge("buffer_events", "out", 5.0),
+            gauge("buffer_max_event_size", "out", 100.0),
+            gauge("buffer_max_event_size", "out", 50.0),
+            gauge("buffer_byte_size", "out", 1024.0),
+            gauge("component_allocated_bytes", "out", 1.0),
+        ]);
+
+        let out = &components["out"];
+        assert_eq!(out.events, 15.0);
+        assert_eq!(out.max_events, Some(150.0));
+        assert_eq!(out.byte_size, 1024.0);
+        assert_eq!(out.max_byte_size, None);
+        assert_eq!(out.ratio(), Some(0.1));
+        assert!(!out.bottleneck);
+    }
+
+    #[test]
+    fn most_downstream_saturated_component_is_the_bottleneck() {
+        let components = utilizations(vec![
+            gauge("utilization", "transform", 0.99),
+            gauge("buffer_events", "out", 95.0),
+            gauge("buffer_max_event_size", "out", 100.0),
+            gauge("utilization", "out", 0.5),
+        ]);
+        assert!(!components["transform"].bottleneck);
+        assert!(components["out"].bottleneck);
+
+        let components = utilizations(vec![
+            gauge("utilization", "transform", 0.99),
+            gauge("buffer_events", "out", 10.0),
+            gauge("buffer_max_event_size", "out", 100.0),
+        ]);
+        assert!(components["transform"].bottleneck);
+        assert!(!components["out"].bottleneck);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use async_graphql::Object;
 use chrono::{DateTime, Utc};
 
@@ -69,3 +71,176 @@ impl ComponentErrorsTotal {
         ErrorsTotal::new(self.metric.clone())
     }
 }
+
+pub struct ErrorTypeTotal {
+    error_type: String,
+    errors_total: f64,
+}
+
+#[Object]
+impl ErrorTypeTotal {
+    /// Type of the errors
+    async fn error_type(&self) -> &str {
+        &self.error_type
+    }
+
+    /// Total error count of the type
+    async fn errors_total(&self) -> f64 {
+        self.errors_total
+    }
+}
+
+pub struct ComponentErrorsByType {
+    component_key: ComponentKey,
+    error_types: Vec<ErrorTypeTotal>,
+    discarded_events_total: f64,
+    unintentional_discarded_events_total: f64,
+}
+
+#[Object]
+impl ComponentErrorsByType {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Errors of the component by type, most frequent first
+    async fn error_types(&self) -> &[ErrorTypeTotal] {
+        &self.error_types
+    }
+
+    /// Total count of the events discarded by the component
+    async fn discarded_events_total(&self) -> f64 {
+        self.discarded_events_total
+    }
+
+    /// Total count of the events discarded by the component unintentionally, such as on errors
+    async fn unintentional_discarded_events_total(&self) -> f64 {
+        self.unintentional_discarded_events_total
+    }
+}
+
+/// Returns the errors of the components by type, and their discarded events. The errors without a
+/// type are counted under the name of their metric.
+pub fn component_errors_by_type(metrics: Vec<Metric>) -> Vec<ComponentErrorsByType> {
+    let mut components = BTreeMap::<String, (BTreeMap<String, f64>, f64, f64)>::new();
+    for metric in metrics {
+        let &MetricValue::Counter { value } = metric.value() else {
+            continue;
+        };
+        let Some(id) = metric.tag_value("component_id") else {
+            continue;
+        };
+        if metric.name().ends_with("_errors_total") {
+            let error_type = metric
+                .tag_value("error_type")
+                .unwrap_or_else(|| metric.name().to_string());
+            let (error_types, _, _) = components.entry(id).or_default();
+            *error_types.entry(error_type).or_default() += value;
+        } else if metric.name() == "component_discarded_events_total" {
+            let (_, discarded, unintentional) = components.entry(id).or_default();
+            *discarded += value;
+            if metric.tag_matches("intentional", "false") {
+                *unintentional += value;
+            }
+        }
+    }
+
+    components
+        .into_iter()
+        .map(|(id, (error_types, discarded, unintentional))| {
+            let mut error_types = error_types
+                .into_iter()
+                .map(|(error_type, errors_total)| ErrorTypeTotal {
+                    error_type,
+                    errors_total,
+                })
+                .collect::<Vec<_>>();
+            error_types.sort_by(|a, b| b.errors_total.total_cmp(&a.errors_total));
+            ComponentErrorsByType {
+                component_key: ComponentKey::from(id),
+                error_types,
+                discarded_events_total: discarded,
+                unintentional_discarded_events_total: unintentional,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::metric_tags;
+
+    use super::*;
+    use crate::event::MetricKind;
+
+    fn counter(name: &str, tags: crate::event::MetricTags, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value })
+            .with_tags(Some(tags))
+    }
+
+    #[test]
+    fn breaks_down_errors_by_type() {
+        let components = component_errors_by_type(vec![
+            counter(
+                "component_errors_total",
+                metric_tags!(
+                    "component_id" => "out",
+                    "error_type" => "request_failed",
+                    "stage" => "sending",
+                ),
+                2.0,
+            ),
+            counter(
+                "component_errors_total",
+                metric_tags!(
+                    "component_id" => "out",
+                    "error_type" => "encoder_failed",
+                    "stage" => "processing",
+                ),
+                1.0,
+            ),
+            counter(
+                "component_errors_total",
+                metric_tags!(
+                    "component_id" => "out",
+                    "error_type" => "request_failed",
+                    "stage" => "receiving",
+                ),
+                3.0,
+            ),
+            counter(
+                "buffer_errors_total",
+                metric_tags!("component_id" => "out"),
+                4.0,
+            ),
+            counter(
+                "component_discarded_events_total",
+                metric_tags!("component_id" => "out", "intentional" => "false"),
+                5.0,
+            ),
+            counter(
+                "component_discarded_events_total",
+                metric_tags!("component_id" => "out", "intentional" => "true"),
+                6.0,
+            ),
+        ]);
+
+        assert_eq!(components.len(), 1);
+        let out = &components[0];
+        assert_eq!(out.component_key.id(), "out");
+        assert_eq!(
+            out.error_types
+                .iter()
+                .map(|error_type| (error_type.error_type.as_str(), error_type.errors_total))
+                .collect::<Vec<_>>(),
+            [
+                ("request_failed", 5.0),
+                ("buffer_errors_total", 4.0),
+                ("encoder_failed", 1.0)
+            ]
+        );
+        assert_eq!(out.discarded_events_total, 11.0);
+        assert_eq!(out.unintentional_discarded_events_total, 5.0);
+    }
+}
@@ -0,0 +1,288 @@
+//! The history of the throughput and errors of the components, sampled at a fixed resolution and
+//! kept in memory for the configured retention, so that clients can draw their recent trend.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{LazyLock, RwLock},
+};
+
+use async_graphql::{Object, SimpleObject};
+use chrono::{DateTime, TimeDelta, Utc};
+use tokio::sync::oneshot;
+use tokio_stream::StreamExt;
+
+use super::filter::get_all_metrics;
+use crate::{
+    config::api::MetricsHistory,
+    event::{Metric, MetricValue},
+};
+
+static HISTORY: LazyLock<RwLock<History>> = LazyLock::new(Default::default);
+
+const INVARIANT: &str = "Couldn't acquire lock on the metrics history. Please report this.";
+
+/// A sample of the metrics of a component, over the interval since the previous one.
+#[derive(Debug, Clone, PartialEq, SimpleObject)]
+pub struct MetricsSample {
+    /// End of the interval of the sample
+    timestamp: DateTime<Utc>,
+    /// Events received per second
+    received_events_throughput: f64,
+    /// Events sent per second
+    sent_events_throughput: f64,
+    /// Bytes received per second
+    received_bytes_throughput: f64,
+    /// Bytes sent per second
+    sent_bytes_throughput: f64,
+    /// Errors over the interval
+    errors: f64,
+    /// Events discarded over the interval
+    discarded_events: f64,
+}
+
+/// The samples of the metrics of a component, oldest first.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ComponentMetricsHistory {
+    /// Component id
+    component_id: String,
+    /// Samples of the metrics of the component, oldest first
+    samples: Vec<MetricsSample>,
+}
+
+/// The totals of the counters of a component.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Totals {
+    received_events: f64,
+    sent_events: f64,
+    received_bytes: f64,
+    sent_bytes: f64,
+    errors: f64,
+    discarded_events: f64,
+}
+
+impl Totals {
+    /// The sample of the interval between the previous totals and these ones. A counter lower than
+    /// its previous value was reset, so all of its value was counted over the interval.
+    fn sample(&self, previous: &Self, timestamp: DateTime<Utc>, secs: f64) -> MetricsSample {
+        let delta = |current: f64, previous: f64| {
+            if current >= previous {
+                current - previous
+            } else {
+                current
+            }
+        };
+        MetricsSample {
+            timestamp,
+            received_events_throughput: delta(self.received_events, previous.received_events)
+                / secs,
+            sent_events_throughput: delta(self.sent_events, previous.sent_events) / secs,
+            received_bytes_throughput: delta(self.received_bytes, previous.received_bytes) / secs,
+            sent_bytes_throughput: delta(self.sent_bytes, previous.sent_bytes) / secs,
+            errors: delta(self.errors, previous.errors),
+            discarded_events: delta(self.discarded_events, previous.discarded_events),
+        }
+    }
+}
+
+/// Sums the counters of the metrics of each component.
+fn component_totals(metrics: Vec<Metric>) -> BTreeMap<String, Totals> {
+    let mut components = BTreeMap::<_, Totals>::new();
+    for metric in metrics {
+        let &MetricValue::Counter { value } = metric.value() else {
+            continue;
+        };
+        let Some(id) = metric.tag_value("component_id") else {
+            continue;
+        };
+        let totals = components.entry(id).or_default();
+        match metric.name() {
+            "component_received_events_total" => totals.received_events += value,
+            "component_sent_events_total" => totals.sent_events += value,
+            "component_received_bytes_total" => totals.received_bytes += value,
+            "component_sent_bytes_total" => totals.sent_bytes += value,
+            "component_discarded_events_total" => totals.discarded_events += value,
+            name if name.ends_with("_errors_total") => totals.errors += value,
+            _ => {}
+        }
+    }
+    components
+}
+
+#[derive(Debug, Default)]
+struct History {
+    retention: TimeDelta,
+    previous: Option<(DateTime<Utc>, BTreeMap<String, Totals>)>,
+    components: BTreeMap<String, VecDeque<MetricsSample>>,
+}
+
+impl History {
+    fn new(retention: TimeDelta) -> Self {
+        Self {
+            retention,
+            ..Default::default()
+        }
+    }
+
+    /// Records the samples of the components since the previous totals, and expires the samples
+    /// older than the retention. The components without previous totals are only sampled from the
+    /// next totals on, as the interval over which their counters grew isn't known.
+    fn record(&mut self, timestamp: DateTime<Utc>, totals: BTreeMap<String, Totals>) {
+        if let Some((previous_timestamp, previous)) = &self.previous {
+            let secs = (timestamp - *previous_timestamp).num_milliseconds() as f64 / 1000.0;
+            if secs > 0.0 {
+                for (id, totals) in &totals {
+                    if let Some(previous) = previous.get(id) {
+                        self.components
+                            .entry(id.clone())
+                            .or_default()
+                            .push_back(totals.sample(previous, timestamp, secs));
+                    }
+                }
+            }
+        }
+
+        let oldest = timestamp.checked_sub_signed(self.retention);
+        self.components.retain(|_, samples| {
+            while samples
+                .front()
+                .is_some_and(|sample| oldest.is_some_and(|oldest| sample.timestamp <= oldest))
+            {
+                samples.pop_front();
+            }
+            !samples.is_empty()
+        });
+        self.previous = Some((timestamp, totals));
+    }
+
+    /// The samples of the components taken since the given time, or all of them.
+    fn samples(
+        &self,
+        component_id: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Vec<ComponentMetricsHistory> {
+        self.components
+            .iter()
+            .filter(|(id, _)| component_id.is_none_or(|component_id| component_id == *id))
+            .map(|(id, samples)| ComponentMetricsHistory {
+                component_id: id.clone(),
+                samples: samples
+                    .iter()
+                    .filter(|sample| since.is_none_or(|since| sample.timestamp > since))
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|component| !component.samples.is_empty())
+            .collect()
+    }
+}
+
+/// Starts recording the history of the metrics of the components, replacing the previous one,
+/// until the returned sender is dropped. Returns `None` if the history is disabled.
+pub fn record_history(config: MetricsHistory) -> Option<oneshot::Sender<()>> {
+    let retention = TimeDelta::from_std(config.retention()).unwrap_or(TimeDelta::MAX);
+    *HISTORY.write().expect(INVARIANT) = History::new(retention);
+    if !config.is_enabled() {
+        return None;
+    }
+
+    let (shutdown, mut rx) = oneshot::channel::<()>();
+    let resolution = config.resolution().as_millis().min(i32::MAX as u128) as i32;
+    tokio::spawn(async move {
+        let mut metrics = Box::pin(get_all_metrics(resolution));
+        loop {
+            tokio::select! {
+                _ = &mut rx => break,
+                Some(metrics) = metrics.next() => {
+                    let totals = component_totals(metrics);
+                    HISTORY.write().expect(INVARIANT).record(Utc::now(), totals);
+                }
+                else => break,
+            }
+        }
+    });
+    Some(shutdown)
+}
+
+#[derive(Default)]
+pub struct MetricsHistoryQuery;
+
+#[Object]
+impl MetricsHistoryQuery {
+    /// Samples of the throughput and errors of the components, oldest first, over the last
+    /// `window_secs` seconds or the whole retention of the history
+    async fn component_metrics_history(
+        &self,
+        component_id: Option<String>,
+        #[graphql(validator(minimum = 1))] window_secs: Option<i32>,
+    ) -> Vec<ComponentMetricsHistory> {
+        let since = window_secs.map(|secs| Utc::now() - TimeDelta::seconds(i64::from(secs)));
+        HISTORY
+            .read()
+            .expect(INVARIANT)
+            .samples(component_id.as_deref(), since)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::metric_tags;
+
+    use super::*;
+    use crate::event::MetricKind;
+
+    fn counter(name: &str, component_id: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value })
+            .with_tags(Some(metric_tags!("component_id" => component_id)))
+    }
+
+    fn totals(received_events: f64, errors: f64) -> BTreeMap<String, Totals> {
+        component_totals(vec![
+            counter("component_received_events_total", "in", received_events),
+            counter("component_errors_total", "in", errors),
+            counter("buffer_errors_total", "in", errors),
+        ])
+    }
+
+    #[test]
+    fn records_samples_within_retention() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let at = |secs| start + TimeDelta::seconds(secs);
+        let mut history = History::new(TimeDelta::seconds(15));
+
+        history.record(at(0), totals(100.0, 0.0));
+        assert!(history.samples(None, None).is_empty());
+
+        history.record(at(10), totals(200.0, 1.0));
+        history.record(at(20), totals(250.0, 1.0));
+        // The counters were reset.
+        history.record(at(30), totals(50.0, 0.0));
+
+        let components = history.samples(None, None);
+        assert_eq!(components.len(), 1);
+        let samples = &components[0].samples;
+        let throughputs = samples
+            .iter()
+            .map(|sample| sample.received_events_throughput)
+            .collect::<Vec<_>>();
+        assert_eq!(throughputs, [5.0, 5.0]);
+        assert_eq!(samples[0].timestamp, at(20));
+        assert_eq!(samples[0].errors, 0.0);
+
+        let recent = history.samples(Some("in"), Some(at(20)));
+        assert_eq!(recent[0].samples.len(), 1);
+        assert!(history.samples(Some("out"), None).is_empty());
+    }
+
+    #[test]
+    fn sums_counters_by_component() {
+        let totals = totals(10.0, 2.0);
+        assert_eq!(
+            totals["in"],
+            Totals {
+                received_events: 10.0,
+                errors: 4.0,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -1,6 +1,8 @@
 mod allocated_bytes;
+mod buffer;
 mod errors;
 pub mod filter;
+mod history;
 mod output;
 mod received_bytes;
 mod received_events;
@@ -16,9 +18,11 @@ mod host;
 
 pub use allocated_bytes::{AllocatedBytes, ComponentAllocatedBytes};
 use async_graphql::{Interface, Subscription};
+pub use buffer::ComponentBufferUtilization;
 use chrono::{DateTime, Utc};
-pub use errors::{ComponentErrorsTotal, ErrorsTotal};
+pub use errors::{ComponentErrorsByType, ComponentErrorsTotal, ErrorTypeTotal, ErrorsTotal};
 pub use filter::*;
+pub use history::{MetricsHistoryQuery, record_history};
 pub use output::*;
 pub use received_bytes::{
     ComponentReceivedBytesThroughput, ComponentReceivedBytesTotal, ReceivedBytesTotal,
@@ -34,7 +38,7 @@ use tokio_stream::{Stream, StreamExt};
 pub use transform::{IntoTransformMetrics, TransformMetrics};
 pub use uptime::Uptime;
 
-use crate::config::ComponentKey;
+use crate::{api::schema::components::state, config::ComponentKey};
 
 #[derive(Interface)]
 #[graphql(field(name = "timestamp", ty = "Option<DateTime<Utc>>"))]
@@ -271,6 +275,25 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component error metrics by error type, with the events discarded by the components, over
+    /// `interval`.
+    async fn component_errors_by_type(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentErrorsByType>> + use<> {
+        get_all_metrics(interval).map(errors::component_errors_by_type)
+    }
+
+    /// Component buffer utilization over `interval`, highlighting the component that's the
+    /// bottleneck of the backpressure.
+    async fn component_buffer_utilizations(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentBufferUtilization>> + use<> {
+        get_all_metrics(interval)
+            .map(|metrics| buffer::component_buffer_utilizations(metrics, &state::get_upstreams()))
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
@@ -3,7 +3,7 @@ pub mod events;
 pub mod filter;
 mod health;
 mod meta;
-mod metrics;
+pub mod metrics;
 mod relay;
 pub mod sort;
 
@@ -14,6 +14,7 @@ pub struct Query(
     health::HealthQuery,
     components::ComponentsQuery,
     #[cfg(feature = "sources-host_metrics")] metrics::MetricsQuery,
+    metrics::MetricsHistoryQuery,
     meta::MetaQuery,
 );
 
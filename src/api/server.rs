@@ -21,10 +21,15 @@ use crate::{
     config::{self, api},
     http::build_http_trace_layer,
     internal_events::{SocketBindError, SocketMode},
+    signal::SignalTx,
 };
 
+/// The maximum size of a config sent to the `/config` endpoints.
+const MAX_CONFIG_BYTES: u64 = 10 * 1024 * 1024;
+
 pub struct Server {
     _shutdown: oneshot::Sender<()>,
+    _history: Option<oneshot::Sender<()>>,
     addr: SocketAddr,
 }
 
@@ -35,9 +40,10 @@ impl Server {
         config: &config::Config,
         watch_rx: topology::WatchRx,
         running: Arc<AtomicBool>,
+        signal_tx: SignalTx,
         handle: &Handle,
     ) -> crate::Result<Self> {
-        let routes = make_routes(config.api, watch_rx, running);
+        let routes = make_routes(config.api, watch_rx, running, signal_tx);
 
         let (_shutdown, rx) = oneshot::channel();
         // warp uses `tokio::spawn` and so needs us to enter the runtime context.
@@ -77,7 +83,14 @@ impl Server {
         // Spawn the server in the background.
         handle.spawn(server);
 
-        Ok(Self { _shutdown, addr })
+        // Record the history of the metrics until the server is dropped.
+        let _history = schema::metrics::record_history(config.api.metrics_history);
+
+        Ok(Self {
+            _shutdown,
+            _history,
+            addr,
+        })
     }
 
     /// Returns a copy of the SocketAddr that the server was started on.
@@ -97,6 +110,7 @@ fn make_routes(
     api: api::Options,
     watch_tx: topology::WatchRx,
     running: Arc<AtomicBool>,
+    signal_tx: SignalTx,
 ) -> BoxedFilter<(impl Reply,)> {
     // Routes...
 
@@ -106,9 +120,51 @@ fn make_routes(
         .and_then(handler::health);
 
     // 404.
+    let not_found_config = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
+    let not_found_rate_limit = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
     let not_found_graphql = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
+    // Config validation and reload. Both endpoints take a full config in the request body, in the
+    // format given by its content type. The `Origin` header is passed along so that requests sent
+    // by web browsers can be rejected, as the CORS policy below would otherwise allow any web page
+    // to change the config. Validating a config builds it and retrieves its secrets, so both
+    // endpoints are only mounted if enabled.
+    let config_body = warp::post()
+        .and(warp::header::optional::<String>("origin"))
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::body::content_length_limit(MAX_CONFIG_BYTES))
+        .and(warp::body::bytes())
+        .and(with_signal_tx(signal_tx));
+    let config = if api.config_reload {
+        warp::path!("config" / "validate")
+            .and(config_body.clone())
+            .and_then(handler::validate_config)
+            .or(warp::path!("config" / "reload")
+                .and(config_body)
+                .and_then(handler::reload_config))
+            .boxed()
+    } else {
+        not_found_config.boxed()
+    };
+
+    // Rate limits of the internal logs. They can be changed along with the config, as they change
+    // which logs Vector emits.
+    let internal_log_rate_limit = warp::path!("internal_log_rate_limit")
+        .and(warp::get())
+        .and_then(handler::internal_log_rate_limit);
+    let set_internal_log_rate_limit = if api.config_reload {
+        warp::path!("internal_log_rate_limit")
+            .and(warp::put())
+            .and(warp::header::optional::<String>("origin"))
+            .and(warp::body::content_length_limit(MAX_CONFIG_BYTES))
+            .and(warp::body::json())
+            .and_then(handler::set_internal_log_rate_limit)
+            .boxed()
+    } else {
+        not_found_rate_limit.boxed()
+    };
+
     // GraphQL subscription handler. Creates a Warp WebSocket handler and for each connection,
     // parses the required headers for GraphQL and builds per-connection context based on the
     // provided `WatchTx` channel sender. This allows GraphQL resolvers to subscribe to
@@ -168,9 +224,12 @@ fn make_routes(
         not_found.boxed()
     };
 
-    // Wire up the health + GraphQL endpoints. Provides a permissive CORS policy to allow for
+    // Wire up the health, config + GraphQL endpoints. Provides a permissive CORS policy to allow for
     // cross-origin interaction with the Vector API.
     health
+        .or(config)
+        .or(internal_log_rate_limit)
+        .or(set_internal_log_rate_limit)
         .or(graphql_handler)
         .or(graphql_playground)
         .or(not_found)
@@ -202,3 +261,9 @@ fn with_shared(
 ) -> impl Filter<Extract = (Arc<AtomicBool>,), Error = Infallible> + Clone {
     warp::any().map(move || Arc::<AtomicBool>::clone(&shared))
 }
+
+fn with_signal_tx(
+    signal_tx: SignalTx,
+) -> impl Filter<Extract = (SignalTx,), Error = Infallible> + Clone {
+    warp::any().map(move || signal_tx.clone())
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    num::NonZeroU64,
     time::Duration,
 };
 
@@ -176,6 +177,7 @@ async fn integration_test_source_log() {
         TapPatterns::new(HashSet::from(["in".to_string()]), HashSet::new()),
         500,
         100,
+        None,
     );
 
     let source_tap_events: Vec<_> = source_tap_stream.take(2).collect().await;
@@ -235,6 +237,7 @@ async fn integration_test_source_metric() {
         TapPatterns::new(HashSet::from(["to_metric".to_string()]), HashSet::new()),
         500,
         100,
+        None,
     );
 
     let source_tap_events: Vec<_> = source_tap_stream.take(2).collect().await;
@@ -285,6 +288,7 @@ async fn integration_test_transform() {
         TapPatterns::new(HashSet::from(["transform".to_string()]), HashSet::new()),
         500,
         100,
+        None,
     );
 
     let transform_tap_events: Vec<_> = transform_tap_stream.take(2).collect().await;
@@ -341,6 +345,7 @@ async fn integration_test_transform_input() {
         ),
         500,
         100,
+        None,
     );
 
     let tap_events: Vec<_> = tap_stream.take(4).collect().await;
@@ -416,6 +421,7 @@ async fn integration_test_sink() {
         TapPatterns::new(HashSet::new(), HashSet::from(["out".to_string()])),
         500,
         100,
+        None,
     );
 
     let tap_events: Vec<_> = tap_stream.take(2).collect().await;
@@ -479,6 +485,7 @@ async fn integration_test_tap_non_default_output() {
         ),
         500,
         100,
+        None,
     );
 
     let transform_tap_events: Vec<_> = transform_tap_remap_dropped_stream.take(2).collect().await;
@@ -551,6 +558,7 @@ async fn integration_test_tap_multiple_outputs() {
         TapPatterns::new(HashSet::from(["transform*".to_string()]), HashSet::new()),
         500,
         100,
+        None,
     );
 
     let transform_tap_notifications = transform_tap_all_outputs_stream.next().await.unwrap();
@@ -587,3 +595,73 @@ async fn integration_test_tap_multiple_outputs() {
 
     assert!(default_output_found && dropped_output_found);
 }
+
+#[tokio::test]
+async fn integration_test_tap_traced_events() {
+    trace_init();
+
+    let mut config = Config::builder();
+    config.add_source(
+        "in",
+        DemoLogsConfig {
+            interval: Duration::from_secs_f64(0.01),
+            count: 200,
+            format: OutputFormat::Shuffle {
+                sequence: false,
+                lines: vec!["test1".to_string()],
+            },
+            ..Default::default()
+        },
+    );
+    config.add_transform(
+        "transform",
+        &["in"],
+        RemapConfig {
+            source: Some(".message = \"test2\"".to_string()),
+            ..Default::default()
+        },
+    );
+    config.add_sink(
+        "out",
+        &["transform"],
+        BlackholeConfig {
+            print_interval_secs: Duration::from_secs(1),
+            rate: None,
+            acknowledgements: Default::default(),
+        },
+    );
+
+    let (topology, _) = start_topology(config.build().unwrap(), false).await;
+
+    let tap_stream = create_events_stream(
+        topology.watch(),
+        TapPatterns::new(HashSet::from(["*".to_string()]), HashSet::new()),
+        500,
+        100,
+        NonZeroU64::new(1),
+    );
+
+    // Each trace contains the event sent by the source and the one emitted by the transform.
+    let mut traces = HashMap::<_, Vec<_>>::new();
+    let mut tap_stream = tap_stream.take(10);
+    while let Some(tap_events) = tap_stream.next().await {
+        for payload in tap_events {
+            if let OutputEventsPayload::Log(log) = payload {
+                let trace_id = log.get_trace_id().expect("Only traced events are streamed");
+                traces
+                    .entry(trace_id)
+                    .or_default()
+                    .push(log.get_message().unwrap_or_default().into_owned());
+            }
+        }
+        if traces.values().any(|messages| messages.len() == 2) {
+            break;
+        }
+    }
+
+    assert!(
+        traces
+            .values()
+            .any(|messages| messages == &["test1".to_string(), "test2".to_string()])
+    );
+}
@@ -20,7 +20,7 @@ use tokio::{
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 #[cfg(feature = "api")]
-use crate::{api, internal_events::ApiStarted};
+use crate::{api, internal_events::ApiStarted, signal::SignalTx};
 use crate::{
     cli::{LogFormat, Opts, RootOpts, WatchConfigMethod, handle_config_errors},
     config::{self, ComponentConfig, ComponentType, Config, ConfigPath},
@@ -130,12 +130,13 @@ impl ApplicationConfig {
 
     /// Configure the API server, if applicable
     #[cfg(feature = "api")]
-    pub fn setup_api(&self, handle: &Handle) -> Option<api::Server> {
+    pub fn setup_api(&self, handle: &Handle, signal_tx: SignalTx) -> Option<api::Server> {
         if self.api.enabled {
             match api::Server::start(
                 self.topology.config(),
                 self.topology.watch(),
                 std::sync::Arc::clone(&self.topology.running),
+                signal_tx,
                 handle,
             ) {
                 Ok(api_server) => {
@@ -257,9 +258,12 @@ impl Application {
 
         let topology_controller = SharedTopologyController::new(TopologyController {
             #[cfg(feature = "api")]
-            api_server: config.setup_api(handle),
+            api_server: config.setup_api(handle, signals.handler.clone_tx()),
+            #[cfg(feature = "api")]
+            signal_tx: signals.handler.clone_tx(),
             topology: config.topology,
             config_paths: config.config_paths.clone(),
+            config_input: None,
             require_healthy: root_opts.require_healthy,
             extra_context: config.extra_context,
         });
@@ -349,14 +353,9 @@ async fn handle_signal(
                 .topology
                 .extend_reload_set(components_to_reload);
 
-            // Reload paths
-            if let Some(paths) = config::process_paths(config_paths) {
-                topology_controller.config_paths = paths;
-            }
-
-            // Reload config
-            let new_config = config::load_from_paths_with_provider_and_secrets(
-                &topology_controller.config_paths,
+            let new_config = load_from_paths(
+                &mut topology_controller,
+                config_paths,
                 signal_handler,
                 allow_empty_config,
             )
@@ -365,20 +364,61 @@ async fn handle_signal(
             reload_config_from_result(topology_controller, new_config).await
         }
         Ok(SignalTo::ReloadFromConfigBuilder(config_builder)) => {
-            let topology_controller = topology_controller.lock().await;
-            reload_config_from_result(topology_controller, config_builder.build()).await
+            let mut topology_controller = topology_controller.lock().await;
+            let new_config = config_builder.build();
+            if new_config.is_ok() {
+                topology_controller.config_input = None;
+            }
+            reload_config_from_result(topology_controller, new_config).await
         }
-        Ok(SignalTo::ReloadFromDisk) => {
+        Ok(SignalTo::ReloadFromInput(input, format)) => {
             let mut topology_controller = topology_controller.lock().await;
-
-            // Reload paths
-            if let Some(paths) = config::process_paths(config_paths) {
-                topology_controller.config_paths = paths;
+            let new_config = config::load_from_str_with_secrets(
+                &input,
+                format,
+                signal_handler,
+                allow_empty_config,
+            )
+            .await;
+            if new_config.is_ok() {
+                topology_controller.config_input = Some((input, format));
             }
+            reload_config_from_result(topology_controller, new_config).await
+        }
+        Ok(SignalTo::ReloadSecrets) => {
+            let mut topology_controller = topology_controller.lock().await;
 
-            // Reload config
-            let new_config = config::load_from_paths_with_provider_and_secrets(
-                &topology_controller.config_paths,
+            // Only the secrets changed, so the running config is loaded again from where it came
+            // from, without reloading the components with external files.
+            let new_config = match topology_controller.config_input.clone() {
+                Some((input, format)) => {
+                    config::load_from_str_with_secrets(
+                        &input,
+                        format,
+                        signal_handler,
+                        allow_empty_config,
+                    )
+                    .await
+                }
+                None => {
+                    load_from_paths(
+                        &mut topology_controller,
+                        config_paths,
+                        signal_handler,
+                        allow_empty_config,
+                    )
+                    .await
+                }
+            };
+
+            reload_config_from_result(topology_controller, new_config).await
+        }
+        Ok(SignalTo::ReloadFromDisk) => {
+            let mut topology_controller = topology_controller.lock().await;
+
+            let new_config = load_from_paths(
+                &mut topology_controller,
+                config_paths,
                 signal_handler,
                 allow_empty_config,
             )
@@ -420,6 +460,31 @@ async fn handle_signal(
     }
 }
 
+/// Loads the config from the config paths, which then replace any config received as a string.
+async fn load_from_paths(
+    topology_controller: &mut TopologyController,
+    config_paths: &[ConfigPath],
+    signal_handler: &mut SignalHandler,
+    allow_empty_config: bool,
+) -> Result<Config, Vec<String>> {
+    // Reload paths
+    if let Some(paths) = config::process_paths(config_paths) {
+        topology_controller.config_paths = paths;
+    }
+
+    // Reload config
+    let new_config = config::load_from_paths_with_provider_and_secrets(
+        &topology_controller.config_paths,
+        signal_handler,
+        allow_empty_config,
+    )
+    .await;
+    if new_config.is_ok() {
+        topology_controller.config_input = None;
+    }
+    new_config
+}
+
 async fn reload_config_from_result(
     mut topology_controller: MutexGuard<'_, TopologyController>,
     config: Result<Config, Vec<String>>,
@@ -0,0 +1,366 @@
+#![allow(missing_docs)]
+use std::path::PathBuf;
+
+use clap::Parser;
+use vector_lib::{
+    buffers::{
+        CompactionSummary, compact_disk_buffer, config::DiskUsage, disk_buffer_status,
+        read_disk_buffer,
+    },
+    event::{EventArray, EventContainer},
+};
+
+use crate::config;
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+enum Command {
+    /// List the disk buffers of the configured sinks, along with the number of events they hold.
+    Ls(ListOpts),
+
+    /// Show the state of the disk buffer of a sink, and the events it holds.
+    ///
+    /// The buffer data files are read directly, so buffers can be inspected while Vector is
+    /// running, for example to debug a sink which is not making progress.
+    Inspect(InspectOpts),
+
+    /// Reclaim the disk space used by data that has already been processed in disk buffers.
+    ///
+    /// Deletes all data files of the configured disk buffers that no longer hold any
+    /// unacknowledged events. Vector must not be running with the given configuration, as disk
+    /// buffers in use cannot be compacted.
+    Compact(CompactOpts),
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub(crate) struct ConfigOpts {
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.yaml` will be targeted.
+    #[arg(
+        id = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        value_delimiter(',')
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format.
+    #[arg(id = "config-toml", long, value_delimiter(','))]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[arg(id = "config-json", long, value_delimiter(','))]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[arg(id = "config-yaml", long, value_delimiter(','))]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[arg(
+        id = "config-dir",
+        short = 'C',
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        value_delimiter(',')
+    )]
+    config_dirs: Vec<PathBuf>,
+}
+
+impl ConfigOpts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::Toml)),
+            (&self.paths_json, Some(config::Format::Json)),
+            (&self.paths_yaml, Some(config::Format::Yaml)),
+        ])
+        .map(|(path, hint)| config::ConfigPath::File(path, hint))
+        .chain(
+            self.config_dirs
+                .iter()
+                .map(|dir| config::ConfigPath::Dir(dir.to_path_buf())),
+        )
+        .collect()
+    }
+
+    /// Loads the configuration, printing the errors if it's invalid.
+    #[allow(clippy::print_stderr)]
+    pub(crate) fn load(&self) -> Result<config::Config, exitcode::ExitCode> {
+        let paths = self.paths_with_formats();
+        let paths = config::process_paths(&paths).ok_or(exitcode::CONFIG)?;
+
+        config::load_from_paths(&paths).map_err(|errs| {
+            for err in errs {
+                eprintln!("{err}");
+            }
+            exitcode::CONFIG
+        })
+    }
+
+    /// Loads the configuration, and gets the disk buffers of the given sinks, or of all sinks if
+    /// none are given.
+    fn disk_buffers(&self, sinks: &[String]) -> Result<Vec<DiskUsage>, exitcode::ExitCode> {
+        let config = self.load()?;
+
+        let global_data_dir = config.global.data_dir.clone();
+        Ok(config
+            .sinks()
+            .filter(|(id, _)| sinks.is_empty() || sinks.iter().any(|sink| sink == id.id()))
+            .flat_map(|(id, sink)| {
+                sink.buffer
+                    .stages()
+                    .iter()
+                    .filter_map(|stage| stage.disk_usage(global_data_dir.clone(), id))
+            })
+            .collect())
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct ListOpts {
+    #[command(flatten)]
+    config: ConfigOpts,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct InspectOpts {
+    #[command(flatten)]
+    config: ConfigOpts,
+
+    /// The ID of the sink whose disk buffer to inspect.
+    sink: String,
+
+    /// Print the given number of the oldest events in the buffer, as JSON.
+    #[arg(long, default_value_t = 0)]
+    head: usize,
+
+    /// Print the given number of the newest events in the buffer, as JSON.
+    #[arg(long, default_value_t = 0)]
+    tail: usize,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct CompactOpts {
+    #[command(flatten)]
+    config: ConfigOpts,
+
+    /// Only compact the disk buffers of the given sinks.
+    ///
+    /// By default, the disk buffers of all sinks are compacted.
+    #[arg(id = "sink", long, value_delimiter(','))]
+    sinks: Vec<String>,
+}
+
+pub(crate) async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match &opts.command {
+        Command::Ls(opts) => list(opts).await,
+        Command::Inspect(opts) => inspect(opts).await,
+        Command::Compact(opts) => compact(opts).await,
+    }
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+async fn list(opts: &ListOpts) -> exitcode::ExitCode {
+    let disk_buffers = match opts.config.disk_buffers(&[]) {
+        Ok(disk_buffers) => disk_buffers,
+        Err(code) => return code,
+    };
+
+    if disk_buffers.is_empty() {
+        println!("No disk buffers configured.");
+        return exitcode::OK;
+    }
+
+    println!(
+        "{:<32} {:>16} {:>16} {:>16}  DATA DIR",
+        "SINK", "EVENTS", "DISK USAGE", "MAX SIZE"
+    );
+    let mut failed = false;
+    for buffer in disk_buffers {
+        // Sinks which never buffered anything to disk don't have a data directory yet.
+        if !buffer.data_dir().exists() {
+            println!(
+                "{:<32} {:>16} {:>16} {:>16}  {}",
+                buffer.id().id(),
+                0,
+                0,
+                buffer.max_size(),
+                buffer.data_dir().display()
+            );
+            continue;
+        }
+
+        match disk_buffer_status(buffer.data_dir()).await {
+            Ok(status) => println!(
+                "{:<32} {:>16} {:>16} {:>16}  {}",
+                buffer.id().id(),
+                status.buffered_events,
+                status.disk_usage_bytes,
+                buffer.max_size(),
+                buffer.data_dir().display()
+            ),
+            Err(error) => {
+                eprintln!(
+                    "Failed to read disk buffer of sink \"{}\": {error}",
+                    buffer.id()
+                );
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        exitcode::IOERR
+    } else {
+        exitcode::OK
+    }
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+async fn inspect(opts: &InspectOpts) -> exitcode::ExitCode {
+    let disk_buffers = match opts.config.disk_buffers(std::slice::from_ref(&opts.sink)) {
+        Ok(disk_buffers) => disk_buffers,
+        Err(code) => return code,
+    };
+    let Some(buffer) = disk_buffers.first() else {
+        eprintln!("Sink \"{}\" does not have a disk buffer.", opts.sink);
+        return exitcode::USAGE;
+    };
+
+    // Each record holds at least one event, so reading as many records as events is enough.
+    let records = match read_disk_buffer::<EventArray>(
+        buffer.data_dir(),
+        buffer.encryption(),
+        opts.head,
+        opts.tail,
+    )
+    .await
+    {
+        Ok(records) => records,
+        Err(error) => {
+            eprintln!(
+                "Failed to read disk buffer of sink \"{}\": {error}",
+                buffer.id()
+            );
+            return exitcode::IOERR;
+        }
+    };
+
+    println!("Sink: {}", buffer.id());
+    println!("Data directory: {}", buffer.data_dir().display());
+    println!("Buffered events: {}", records.status.buffered_events);
+    println!("Buffered records: {}", records.buffered_records);
+    println!("Buffered bytes: {}", records.buffered_bytes);
+    println!(
+        "Disk usage: {} bytes in {} data file(s), out of {} bytes",
+        records.status.disk_usage_bytes,
+        records.status.data_files,
+        buffer.max_size()
+    );
+    if records.undecodable_records > 0 {
+        println!("Undecodable records: {}", records.undecodable_records);
+    }
+    if records.corrupted_data_files > 0 {
+        println!("Corrupted data files: {}", records.corrupted_data_files);
+    }
+
+    let head = records
+        .head
+        .into_iter()
+        .flat_map(EventContainer::into_events)
+        .take(opts.head)
+        .collect::<Vec<_>>();
+    let mut tail = records
+        .tail
+        .into_iter()
+        .flat_map(EventContainer::into_events)
+        .collect::<Vec<_>>();
+    tail.drain(..tail.len().saturating_sub(opts.tail));
+
+    for (title, events) in [("Oldest events", head), ("Newest events", tail)] {
+        if events.is_empty() {
+            continue;
+        }
+
+        println!("\n{title}:");
+        for event in events {
+            match serde_json::to_string(&event) {
+                Ok(json) => println!("{json}"),
+                Err(error) => eprintln!("Failed to serialize event: {error}"),
+            }
+        }
+    }
+
+    exitcode::OK
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+async fn compact(opts: &CompactOpts) -> exitcode::ExitCode {
+    let disk_buffers = match opts.config.disk_buffers(&opts.sinks) {
+        Ok(disk_buffers) => disk_buffers,
+        Err(code) => return code,
+    };
+
+    if disk_buffers.is_empty() {
+        println!("No disk buffers to compact.");
+        return exitcode::OK;
+    }
+
+    let mut total = CompactionSummary::default();
+    let mut failed = false;
+    for buffer in disk_buffers {
+        // Sinks which never buffered anything to disk have nothing to compact.
+        if !buffer.data_dir().exists() {
+            continue;
+        }
+
+        match compact_disk_buffer(buffer.data_dir()).await {
+            Ok(summary) => {
+                println!(
+                    "Compacted disk buffer of sink \"{}\": deleted {} data file(s), reclaimed {} bytes.",
+                    buffer.id(),
+                    summary.data_files_deleted,
+                    summary.reclaimed_bytes
+                );
+                total.data_files_deleted += summary.data_files_deleted;
+                total.reclaimed_bytes += summary.reclaimed_bytes;
+            }
+            Err(error) => {
+                eprintln!(
+                    "Failed to compact disk buffer of sink \"{}\": {error}",
+                    buffer.id()
+                );
+                failed = true;
+            }
+        }
+    }
+
+    println!(
+        "Deleted {} data file(s), reclaimed {} bytes in total.",
+        total.data_files_deleted, total.reclaimed_bytes
+    );
+
+    if failed {
+        exitcode::IOERR
+    } else {
+        exitcode::OK
+    }
+}
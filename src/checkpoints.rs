@@ -0,0 +1,161 @@
+#![allow(missing_docs)]
+use std::path::PathBuf;
+
+use clap::Parser;
+use vector_lib::file_source_common::Checkpointer;
+
+use crate::buffer::ConfigOpts;
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+enum Command {
+    /// Export the file checkpoints of a source as JSON.
+    ///
+    /// Checkpoints can be exported while Vector is running.
+    Export(ExportOpts),
+
+    /// Import file checkpoints exported from another host or data directory into a source.
+    ///
+    /// The imported checkpoints are merged with the existing ones, keeping the most recently
+    /// updated checkpoint of each file. Vector must not be running with the given configuration,
+    /// as it would overwrite the imported checkpoints.
+    ///
+    /// Only checkpoints of files fingerprinted by checksum can be used on another host, as
+    /// device and inode numbers differ between hosts.
+    Import(ImportOpts),
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct ExportOpts {
+    #[command(flatten)]
+    config: ConfigOpts,
+
+    /// The ID of the source whose checkpoints to export.
+    source: String,
+
+    /// Write the checkpoints to the given file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+struct ImportOpts {
+    #[command(flatten)]
+    config: ConfigOpts,
+
+    /// The ID of the source to import the checkpoints into.
+    source: String,
+
+    /// The file holding the exported checkpoints.
+    input: PathBuf,
+}
+
+pub(crate) async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match &opts.command {
+        Command::Export(opts) => export(opts).await,
+        Command::Import(opts) => import(opts).await,
+    }
+}
+
+/// Loads the configuration, and gets the directory holding the checkpoints of the given source.
+#[allow(clippy::print_stderr)]
+fn checkpoints_dir(config: &ConfigOpts, source: &str) -> Result<PathBuf, exitcode::ExitCode> {
+    let config = config.load()?;
+
+    let Some((key, source_config)) = config.sources().find(|(key, _)| key.id() == source) else {
+        eprintln!("Source \"{source}\" not found.");
+        return Err(exitcode::USAGE);
+    };
+    match source_config.inner.checkpoints_dir(&config.global, key) {
+        Some(Ok(data_dir)) => Ok(data_dir),
+        Some(Err(error)) => {
+            eprintln!("Failed to resolve the data directory of source \"{source}\": {error}");
+            Err(exitcode::CONFIG)
+        }
+        None => {
+            eprintln!("Source \"{source}\" does not keep file checkpoints.");
+            Err(exitcode::USAGE)
+        }
+    }
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+async fn export(opts: &ExportOpts) -> exitcode::ExitCode {
+    let data_dir = match checkpoints_dir(&opts.config, &opts.source) {
+        Ok(data_dir) => data_dir,
+        Err(code) => return code,
+    };
+
+    let exported = match Checkpointer::new(&data_dir).export_checkpoints().await {
+        Ok(exported) => exported,
+        Err(error) => {
+            eprintln!(
+                "Failed to read the checkpoints in {}: {error}",
+                data_dir.display()
+            );
+            return exitcode::IOERR;
+        }
+    };
+
+    match &opts.output {
+        Some(path) => {
+            if let Err(error) = tokio::fs::write(path, exported).await {
+                eprintln!("Failed to write {}: {error}", path.display());
+                return exitcode::IOERR;
+            }
+        }
+        None => println!("{}", String::from_utf8_lossy(&exported)),
+    }
+
+    exitcode::OK
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+async fn import(opts: &ImportOpts) -> exitcode::ExitCode {
+    let data_dir = match checkpoints_dir(&opts.config, &opts.source) {
+        Ok(data_dir) => data_dir,
+        Err(code) => return code,
+    };
+
+    let exported = match tokio::fs::read(&opts.input).await {
+        Ok(exported) => exported,
+        Err(error) => {
+            eprintln!("Failed to read {}: {error}", opts.input.display());
+            return exitcode::NOINPUT;
+        }
+    };
+
+    if let Err(error) = tokio::fs::create_dir_all(&data_dir).await {
+        eprintln!("Failed to create {}: {error}", data_dir.display());
+        return exitcode::CANTCREAT;
+    }
+
+    match Checkpointer::new(&data_dir)
+        .import_checkpoints(&exported)
+        .await
+    {
+        Ok(count) => {
+            println!(
+                "Imported {count} checkpoint(s) into source \"{}\".",
+                opts.source
+            );
+            exitcode::OK
+        }
+        Err(error) => {
+            eprintln!(
+                "Failed to import the checkpoints into {}: {error}",
+                data_dir.display()
+            );
+            exitcode::IOERR
+        }
+    }
+}
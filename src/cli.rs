@@ -4,6 +4,10 @@ use std::{num::NonZeroU64, path::PathBuf};
 
 use clap::{ArgAction, CommandFactory, FromArgMatches, Parser};
 
+#[cfg(any(feature = "sources-file", feature = "sources-kubernetes_logs"))]
+use crate::checkpoints;
+#[cfg(feature = "sources-kafka")]
+use crate::kafka;
 #[cfg(windows)]
 use crate::service;
 #[cfg(feature = "api-client")]
@@ -12,8 +16,8 @@ use crate::tap;
 use crate::top;
 
 use crate::{
-    config, convert_config, generate, generate_schema, get_version, graph, list, signal, unit_test,
-    validate,
+    buffer, config, convert_config, generate, generate_schema, get_version, graph, list, signal,
+    unit_test, validate, vrl_cli,
 };
 
 #[derive(Parser, Debug)]
@@ -311,7 +315,8 @@ pub enum SubCommand {
     GenerateSchema(generate_schema::Opts),
 
     /// Output a provided Vector configuration file/dir as a single JSON object, useful for checking in to version control.
-    #[command(hide = true)]
+    ///
+    /// The `schema` subcommand outputs the JSON Schema of the configuration instead.
     Config(config::Opts),
 
     /// List available components, then exit.
@@ -324,6 +329,17 @@ pub enum SubCommand {
     /// Output the topology as visual representation using the DOT language which can be rendered by GraphViz
     Graph(graph::Opts),
 
+    /// Manage the disk buffers of a Vector configuration.
+    Buffer(buffer::Opts),
+
+    /// Export and import the file checkpoints of sources.
+    #[cfg(any(feature = "sources-file", feature = "sources-kubernetes_logs"))]
+    Checkpoints(checkpoints::Opts),
+
+    /// Manage the Kafka consumer groups used by the `kafka` source.
+    #[cfg(feature = "sources-kafka")]
+    Kafka(kafka::cli::Opts),
+
     /// Display topology and metrics in the console, for a local or remote Vector instance
     #[cfg(feature = "top")]
     Top(top::Opts),
@@ -337,7 +353,7 @@ pub enum SubCommand {
     Service(service::Opts),
 
     /// Vector Remap Language CLI
-    Vrl(vrl::cli::Opts),
+    Vrl(vrl_cli::Opts),
 }
 
 impl SubCommand {
@@ -347,11 +363,16 @@ impl SubCommand {
         color: bool,
     ) -> exitcode::ExitCode {
         match self {
-            Self::Config(c) => config::cmd(c),
+            Self::Buffer(b) => buffer::cmd(b).await,
+            #[cfg(any(feature = "sources-file", feature = "sources-kubernetes_logs"))]
+            Self::Checkpoints(c) => checkpoints::cmd(c).await,
+            Self::Config(c) => config::cmd(c, &mut signals.handler).await,
             Self::ConvertConfig(opts) => convert_config::cmd(opts),
             Self::Generate(g) => generate::cmd(g),
             Self::GenerateSchema(opts) => generate_schema::cmd(opts),
             Self::Graph(g) => graph::cmd(g),
+            #[cfg(feature = "sources-kafka")]
+            Self::Kafka(k) => kafka::cli::cmd(k),
             Self::List(l) => list::cmd(l),
             #[cfg(windows)]
             Self::Service(s) => service::cmd(s),
@@ -364,7 +385,7 @@ impl SubCommand {
             Self::Vrl(s) => {
                 let mut functions = vrl::stdlib::all();
                 functions.extend(vector_vrl_functions::all());
-                vrl::cli::cmd::cmd(s, functions)
+                vrl_cli::cmd(s, functions)
             }
         }
     }
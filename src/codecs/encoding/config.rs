@@ -9,6 +9,11 @@ use vector_lib::{
 
 #[cfg(feature = "codecs-opentelemetry")]
 use vector_lib::codecs::BytesEncoder;
+#[cfg(feature = "codecs-parquet")]
+use {
+    crate::codecs::{BatchEncoder, Encoder},
+    vector_lib::codecs::encoding::BatchSerializerConfig,
+};
 
 /// Encoding configuration.
 #[configurable_component]
@@ -138,6 +143,25 @@ impl EncodingConfigWithFraming {
 
         Ok((framer, serializer))
     }
+
+    /// Build the `BatchEncoder` for this config.
+    ///
+    /// If a batch serializer is given, whole batches are serialized with it instead of being
+    /// encoded event by event.
+    #[cfg(feature = "codecs-parquet")]
+    pub fn build_batch_encoder(
+        &self,
+        batch_encoding: Option<&BatchSerializerConfig>,
+        sink_type: SinkType,
+    ) -> crate::Result<BatchEncoder> {
+        match batch_encoding {
+            Some(batch_encoding) => Ok(BatchEncoder::Batch(batch_encoding.build()?)),
+            None => {
+                let (framer, serializer) = self.build(sink_type)?;
+                Ok(Encoder::<Framer>::new(framer, serializer).into())
+            }
+        }
+    }
 }
 
 /// The way a sink processes outgoing events.
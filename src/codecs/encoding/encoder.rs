@@ -1,5 +1,7 @@
 use bytes::BytesMut;
 use tokio_util::codec::Encoder as _;
+#[cfg(feature = "codecs-parquet")]
+use vector_lib::codecs::encoding::BatchSerializer;
 use vector_lib::codecs::{
     CharacterDelimitedEncoder, NewlineDelimitedEncoder, TextSerializerConfig,
     encoding::{Error, Framer, Serializer},
@@ -186,6 +188,44 @@ impl tokio_util::codec::Encoder<Event> for Encoder<()> {
     }
 }
 
+/// An encoder for whole batches of events.
+///
+/// Batches are either encoded event by event into byte frames, or serialized at once for formats
+/// that describe the whole batch, such as Apache Parquet.
+#[derive(Debug, Clone)]
+pub enum BatchEncoder {
+    /// Encodes each event of the batch into a byte frame.
+    Framed(Encoder<Framer>),
+
+    /// Serializes the whole batch at once.
+    #[cfg(feature = "codecs-parquet")]
+    Batch(BatchSerializer),
+}
+
+impl BatchEncoder {
+    /// Get the HTTP content type.
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Framed(encoder) => encoder.content_type(),
+            #[cfg(feature = "codecs-parquet")]
+            Self::Batch(serializer) => serializer.content_type(),
+        }
+    }
+}
+
+impl From<Encoder<Framer>> for BatchEncoder {
+    fn from(encoder: Encoder<Framer>) -> Self {
+        Self::Framed(encoder)
+    }
+}
+
+#[cfg(feature = "codecs-parquet")]
+impl From<BatchSerializer> for BatchEncoder {
+    fn from(serializer: BatchSerializer) -> Self {
+        Self::Batch(serializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::BufMut;
@@ -0,0 +1,56 @@
+//! The HTTP/2 settings of the gRPC connections between Vector instances.
+
+use std::time::Duration;
+
+use vector_lib::configurable::configurable_component;
+
+/// HTTP/2 settings of the gRPC connections.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Http2Config {
+    /// The interval between the HTTP/2 `PING` frames sent on the connections.
+    ///
+    /// The pings keep the connections open through the proxies and load balancers closing the idle
+    /// ones, and detect the connections that are broken. If not set, no pings are sent.
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::examples = 30))]
+    pub keepalive_interval_secs: Option<u64>,
+
+    /// How long to wait for the acknowledgement of a ping before closing the connection.
+    #[serde(default = "default_keepalive_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub keepalive_timeout_secs: u64,
+
+    /// Whether to adapt the flow control windows of the connections to their bandwidth-delay
+    /// product.
+    ///
+    /// The default windows limit the throughput of the connections with a high latency, such as the
+    /// ones across regions.
+    #[serde(default)]
+    pub adaptive_window: bool,
+}
+
+impl Default for Http2Config {
+    fn default() -> Self {
+        Self {
+            keepalive_interval_secs: None,
+            keepalive_timeout_secs: default_keepalive_timeout_secs(),
+            adaptive_window: false,
+        }
+    }
+}
+
+const fn default_keepalive_timeout_secs() -> u64 {
+    20
+}
+
+impl Http2Config {
+    pub fn keepalive_interval(&self) -> Option<Duration> {
+        self.keepalive_interval_secs.map(Duration::from_secs)
+    }
+
+    pub const fn keepalive_timeout(&self) -> Duration {
+        Duration::from_secs(self.keepalive_timeout_secs)
+    }
+}
@@ -22,6 +22,8 @@ pub(crate) mod s3;
 pub(crate) mod websocket;
 
 pub(crate) mod backoff;
+#[cfg(any(feature = "sources-vector", feature = "sinks-vector"))]
+pub(crate) mod grpc;
 #[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt",))]
 /// Common MQTT configuration shared by MQTT components.
 pub mod mqtt;
@@ -104,6 +104,19 @@ pub enum ConfigurationError {
     /// Credentials provided were incomplete
     #[snafu(display("Username and password must be either both or neither provided."))]
     IncompleteCredentials,
+    /// Invalid topic template provided error
+    #[snafu(display("Invalid topic template {template:?}: {reason}."))]
+    InvalidTopicTemplate {
+        /// The topic template
+        template: String,
+        /// Why the topic template is invalid
+        reason: &'static str,
+    },
+    /// Invalid shared subscription group provided error
+    #[snafu(display(
+        "Shared subscription group must not be empty, and must not contain `/`, `+` or `#`."
+    ))]
+    InvalidSharedGroup,
 }
 
 #[derive(Clone)]
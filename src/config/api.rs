@@ -1,4 +1,7 @@
-use std::net::{Ipv4Addr, SocketAddr};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
 
 use url::Url;
 use vector_lib::configurable::configurable_component;
@@ -36,10 +39,69 @@ pub struct Options {
     #[serde(default = "default_graphql", skip_serializing_if = "is_true")]
     #[configurable(metadata(docs::common = true, docs::required = false))]
     pub graphql: bool,
+
+    /// Whether the `/config/validate` and `/config/reload` endpoints are enabled for the API.
+    ///
+    /// The `/config/reload` endpoint replaces the running configuration with the one sent in the
+    /// request body, allowing anyone who can reach the API to change what Vector does, so only
+    /// enable it if the address set using the `bind` parameter is only reachable by trusted
+    /// clients. The configuration is replaced again by the configuration files on the next reload
+    /// triggered by a `SIGHUP` signal or by watching them.
+    ///
+    /// Secrets of configurations sent to these endpoints can't be retrieved with the `directory`,
+    /// `exec`, or `file` backends, which would run commands or read files on the host.
+    #[serde(default)]
+    #[configurable(metadata(docs::common = false, docs::required = false))]
+    pub config_reload: bool,
+
+    /// The history of the throughput and errors of the components, kept in memory to be queried
+    /// over a window of time.
+    #[configurable(derived)]
+    #[configurable(metadata(docs::common = false, docs::required = false))]
+    pub metrics_history: MetricsHistory,
 }
 
 impl_generate_config_from_default!(Options);
 
+/// Options for the history of the metrics of the components.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsHistory {
+    /// How long the samples of the metrics are kept.
+    ///
+    /// Set to `0` to disable the history.
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub retention_secs: u64,
+
+    /// The interval between the samples of the metrics.
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub resolution_secs: u64,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self {
+            retention_secs: 900,
+            resolution_secs: 10,
+        }
+    }
+}
+
+impl MetricsHistory {
+    pub const fn is_enabled(&self) -> bool {
+        self.retention_secs > 0
+    }
+
+    pub const fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_secs)
+    }
+
+    pub fn resolution(&self) -> Duration {
+        Duration::from_secs(self.resolution_secs.max(1))
+    }
+}
+
 impl Default for Options {
     fn default() -> Self {
         Self {
@@ -47,6 +109,8 @@ impl Default for Options {
             playground: default_playground(),
             address: default_address(),
             graphql: default_graphql(),
+            config_reload: false,
+            metrics_history: MetricsHistory::default(),
         }
     }
 }
@@ -102,11 +166,25 @@ impl Options {
             }
         };
 
+        // Prefer non default history
+        let metrics_history = match (
+            self.metrics_history == MetricsHistory::default(),
+            other.metrics_history == MetricsHistory::default(),
+        ) {
+            (false, false) if self.metrics_history != other.metrics_history => {
+                return Err("Conflicting `api` metrics history.".to_owned());
+            }
+            (false, _) => self.metrics_history,
+            (true, _) => other.metrics_history,
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
             graphql: self.graphql & other.graphql,
+            config_reload: self.config_reload | other.config_reload,
+            metrics_history,
         };
 
         *self = options;
@@ -121,6 +199,8 @@ fn bool_merge() {
         address: None,
         playground: false,
         graphql: false,
+        config_reload: true,
+        metrics_history: MetricsHistory::default(),
     };
 
     a.merge(Options::default()).unwrap();
@@ -131,7 +211,9 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
-            graphql: false
+            graphql: false,
+            config_reload: true,
+            metrics_history: MetricsHistory::default(),
         }
     );
 }
@@ -144,6 +226,8 @@ fn bind_merge() {
         address: Some(address),
         playground: true,
         graphql: true,
+        config_reload: false,
+        metrics_history: MetricsHistory::default(),
     };
 
     a.merge(Options::default()).unwrap();
@@ -155,6 +239,8 @@ fn bind_merge() {
             address: Some(address),
             playground: true,
             graphql: true,
+            config_reload: false,
+            metrics_history: MetricsHistory::default(),
         }
     );
 }
@@ -173,3 +259,27 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn metrics_history_merge() {
+    let history = MetricsHistory {
+        retention_secs: 3600,
+        resolution_secs: 60,
+    };
+    let mut a = Options::default();
+    a.merge(Options {
+        metrics_history: history,
+        ..Options::default()
+    })
+    .unwrap();
+    assert_eq!(a.metrics_history, history);
+
+    let b = Options {
+        metrics_history: MetricsHistory {
+            retention_secs: 0,
+            ..MetricsHistory::default()
+        },
+        ..Options::default()
+    };
+    assert!(a.merge(b).is_err());
+}
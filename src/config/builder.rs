@@ -8,6 +8,7 @@ use super::api;
 use super::{
     BoxedSink, BoxedSource, BoxedTransform, ComponentKey, Config, EnrichmentTableOuter,
     HealthcheckOptions, SinkOuter, SourceOuter, TestDefinition, TransformOuter, compiler, schema,
+    templates::{ComponentTemplate, TemplateInstance},
 };
 use crate::{enrichment_tables::EnrichmentTables, providers::Providers, secrets::SecretBackends};
 
@@ -49,6 +50,14 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub transforms: IndexMap<ComponentKey, TransformOuter<String>>,
 
+    /// All configured component templates.
+    #[serde(default)]
+    pub templates: IndexMap<String, ComponentTemplate>,
+
+    /// All configured instances of the component templates.
+    #[serde(default)]
+    pub instances: IndexMap<String, TemplateInstance>,
+
     /// All configured unit tests.
     #[serde(default)]
     pub tests: Vec<TestDefinition<String>>,
@@ -120,6 +129,8 @@ impl From<Config> for ConfigBuilder {
             sources,
             sinks,
             transforms,
+            templates: IndexMap::new(),
+            instances: IndexMap::new(),
             provider: None,
             tests,
             secret,
@@ -246,6 +257,16 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate transform id found: {k}"));
             }
         });
+        with.templates.keys().for_each(|k| {
+            if self.templates.contains_key(k) {
+                errors.push(format!("duplicate template name found: {k}"));
+            }
+        });
+        with.instances.keys().for_each(|k| {
+            if self.instances.contains_key(k) {
+                errors.push(format!("duplicate template instance name found: {k}"));
+            }
+        });
         with.tests.iter().for_each(|wt| {
             if self.tests.iter().any(|t| t.name == wt.name) {
                 errors.push(format!("duplicate test name found: {}", wt.name));
@@ -264,6 +285,8 @@ impl ConfigBuilder {
         self.sources.extend(with.sources);
         self.sinks.extend(with.sinks);
         self.transforms.extend(with.transforms);
+        self.templates.extend(with.templates);
+        self.instances.extend(with.instances);
         self.tests.extend(with.tests);
         self.secret.extend(with.secret);
 
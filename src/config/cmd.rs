@@ -1,14 +1,19 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use serde_json::Value;
 
-use super::{ConfigBuilder, load_builder_from_paths, load_source_from_paths, process_paths};
-use crate::{cli::handle_config_errors, config};
+use super::{
+    ConfigBuilder, load_builder_from_paths, load_source_from_paths, process_paths, render,
+};
+use crate::{cli::handle_config_errors, config, generate_schema, signal};
 
 #[derive(Parser, Debug, Clone)]
-#[command(rename_all = "kebab-case")]
+#[command(rename_all = "kebab-case", args_conflicts_with_subcommands = true)]
 pub struct Opts {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Pretty print JSON
     #[arg(short, long)]
     pretty: bool,
@@ -56,6 +61,23 @@ pub struct Opts {
     pub config_dirs: Vec<PathBuf>,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+#[command(rename_all = "kebab-case")]
+enum Command {
+    /// Output the JSON Schema of the configuration, including all the sources, transforms and sinks
+    /// built into this Vector binary, to validate configurations without running Vector.
+    ///
+    /// This is the same schema as the one output by the `generate-schema` command.
+    Schema(generate_schema::Opts),
+
+    /// Output the configuration as loaded by Vector, with the environment variables interpolated,
+    /// the secrets resolved and the files merged into a single configuration.
+    ///
+    /// The secrets, the values of the options and headers holding credentials, and the credentials
+    /// of URIs are masked.
+    Render(render::Opts),
+}
+
 impl Opts {
     fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
         config::merge_path_lists(vec![
@@ -157,11 +179,19 @@ fn serialize_to_json(
     }
 }
 
-/// Function used by the `vector config` subcommand for outputting a normalized configuration.
+/// Function used by the `vector config` subcommand for outputting a normalized configuration, the
+/// configuration schema with `vector config schema`, or the loaded configuration with
+/// `vector config render`.
 /// The purpose of this func is to combine user configuration after processing all paths,
 /// Pipelines expansions, etc. The JSON result of this serialization can itself be used as a config,
 /// which also makes it useful for version control or treating as a singular unit of configuration.
-pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+pub async fn cmd(opts: &Opts, signal_handler: &mut signal::SignalHandler) -> exitcode::ExitCode {
+    match &opts.command {
+        Some(Command::Schema(opts)) => return generate_schema::cmd(opts),
+        Some(Command::Render(opts)) => return render::cmd(opts, signal_handler).await,
+        None => {}
+    }
+
     let paths = opts.paths_with_formats();
     // Start by serializing to a `ConfigBuilder`. This will leverage validation in config
     // builder fields which we'll use to error out if required.
@@ -211,6 +241,27 @@ mod tests {
         generate::{TransformInputsStrategy, generate_example},
     };
 
+    #[test]
+    fn parses_subcommands() {
+        use clap::Parser;
+
+        use super::{Command, Opts};
+
+        let opts = Opts::try_parse_from(["config", "schema", "-o", "schema.json"]).unwrap();
+        assert!(matches!(
+            opts.command,
+            Some(Command::Schema(ref schema)) if schema.output_path == Some("schema.json".into())
+        ));
+
+        let opts =
+            Opts::try_parse_from(["config", "render", "-f", "yaml", "-c", "vector.yaml"]).unwrap();
+        assert!(matches!(opts.command, Some(Command::Render(_))));
+
+        let opts = Opts::try_parse_from(["config", "--pretty", "-c", "vector.yaml"]).unwrap();
+        assert!(opts.command.is_none());
+        assert!(opts.pretty);
+    }
+
     #[test]
     fn test_array_override() {
         let mut json = json!({
@@ -2,13 +2,19 @@ use indexmap::{IndexMap, IndexSet};
 use vector_lib::id::Inputs;
 
 use super::{
-    Config, OutputId, builder::ConfigBuilder, graph::Graph, transform::get_transform_output_ids,
-    validation,
+    Config, OutputId, builder::ConfigBuilder, dead_letter, graph::Graph, templates,
+    transform::get_transform_output_ids, validation,
 };
 
 pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<String>> {
     let mut errors = Vec::new();
 
+    // the components of the template instances are validated like any other component, so the
+    // templates are expanded first
+    if let Err(template_errors) = templates::expand(&mut builder) {
+        errors.extend(template_errors);
+    }
+
     // component names should not have dots in the configuration file
     // but components can expand (like route) to have components with a dot
     // so this check should be done before expanding components
@@ -43,15 +49,22 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         schema,
         healthchecks,
         enrichment_tables,
-        sources,
+        mut sources,
         sinks,
         transforms,
+        templates: _,
+        instances: _,
         tests,
         provider: _,
         secret,
         graceful_shutdown_duration,
         allow_empty: _,
     } = builder;
+
+    if let Err(dead_letter_errors) = dead_letter::add_sources(&mut sources, &sinks) {
+        errors.extend(dead_letter_errors);
+    }
+
     let all_sinks = sinks
         .clone()
         .into_iter()
@@ -91,6 +104,8 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
 
     if let Err(e) = graph.check_for_cycles() {
         errors.push(e);
+    } else if let Err(loop_errors) = dead_letter::check_loops(&graph, &sinks) {
+        errors.extend(loop_errors);
     }
 
     // Inputs are resolved from string into OutputIds as part of graph construction, so update them
@@ -0,0 +1,611 @@
+//! Dead-letter outputs, which sinks send the events they couldn't deliver to, and the other outputs
+//! of the sinks, such as the ones of the responses to their requests.
+//!
+//! Sinks declare their outputs with [`SinkConfig::outputs`], and any sink with the `dead_letter`
+//! option sends the events it fails to deliver to its `dropped` output. The configuration compiler
+//! adds a source named `<sink_id>.<output>` for each of them, so that other components can consume
+//! the events like those of any other output. The sink and the source share a queue, looked up by
+//! the key of the source, which outlives both of them so that either can be rebuilt on reload.
+//!
+//! The sinks never wait for the queue: events sent while it's full are dropped, as are the events
+//! sent after the source stopped while Vector shuts down.
+//!
+//! The events sent to a sink with the `dead_letter` option are tracked by a [`DeadLetterTracker`],
+//! which keeps a copy of each event until the sink finalizes it. The driver of the sink records
+//! the error or the response that failed the request on the finalizers of its events, which is
+//! added to the events sent to the output.
+//!
+//! [`SinkConfig::outputs`]: super::SinkConfig::outputs
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use futures::StreamExt;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::Instrument;
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{DataType, LogNamespace, log_schema},
+    configurable::NamedComponent,
+    event::{
+        BatchFailure, BatchNotifier, BatchStatus, EventArray, EventContainer, EventFinalizer,
+        EventFinalizers, EventStatus, LogEvent, ObjectMap, Value,
+    },
+    finalizer::UnorderedFinalizer,
+    internal_event::{
+        ComponentEventsDropped, CountByteSize, InternalEventHandle as _, UNINTENTIONAL,
+    },
+    lookup::{PathPrefix, event_path, metadata_path},
+    schema,
+    source::Source,
+};
+use vrl::{path, path::ValuePath};
+
+use super::{
+    ComponentKey, SinkOuter, SourceConfig, SourceContext, SourceOuter, SourceOutput, graph::Graph,
+};
+use crate::{
+    event::Event,
+    internal_events::{EventsReceived, StreamClosedError},
+};
+
+/// The number of batches of events that can be queued for a dead-letter output.
+const QUEUE_CAPACITY: usize = 100;
+
+/// The maximum number of undelivered events sent to a dead-letter output at once.
+const MAX_UNDELIVERED_BATCH_SIZE: usize = 1000;
+
+type Receiver = Arc<tokio::sync::Mutex<mpsc::Receiver<Vec<Event>>>>;
+
+static QUEUES: LazyLock<Mutex<HashMap<ComponentKey, (mpsc::Sender<Vec<Event>>, Receiver)>>> =
+    LazyLock::new(Default::default);
+
+fn queue(key: &ComponentKey) -> (mpsc::Sender<Vec<Event>>, Receiver) {
+    let mut queues = QUEUES.lock().expect("mutex should not be poisoned");
+    let (tx, rx) = queues.entry(key.clone()).or_insert_with(|| {
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        (tx, Arc::new(tokio::sync::Mutex::new(rx)))
+    });
+    (tx.clone(), Arc::clone(rx))
+}
+
+/// The name of the output that the sinks with the `dead_letter` option send the events they fail
+/// to deliver to.
+pub const DROPPED_OUTPUT: &str = "dropped";
+
+/// The name of the output that the sinks with the `capture_responses` option send the responses to
+/// their requests to.
+pub const RESPONSES_OUTPUT: &str = "responses";
+
+/// Why a sink couldn't deliver an event.
+#[derive(Clone, Copy, Debug)]
+pub struct Failure<'a> {
+    /// A short description of the failure, such as `rejected`.
+    pub reason: &'a str,
+    pub message: &'a str,
+    /// The status code of the response of the service the event was sent to, if the sink knows it.
+    pub status_code: Option<u16>,
+}
+
+/// Sends events to an output of a sink.
+#[derive(Clone, Debug)]
+pub struct DeadLetterSender {
+    sink: ComponentKey,
+    sink_type: &'static str,
+    tx: mpsc::Sender<Vec<Event>>,
+}
+
+impl DeadLetterSender {
+    pub fn new(sink: &ComponentKey, sink_type: &'static str, output: &str) -> Self {
+        let (tx, _) = queue(&sink.join(output));
+        Self {
+            sink: sink.clone(),
+            sink_type,
+            tx,
+        }
+    }
+
+    /// Annotates an event with why it wasn't delivered, the same way as the events rerouted by the
+    /// `remap` transform.
+    pub fn annotate(&self, event: &mut Event, failure: Failure<'_>) {
+        let timestamp = Utc::now();
+        let mut dropped = ObjectMap::new();
+        dropped.insert("reason".into(), failure.reason.into());
+        dropped.insert("message".into(), failure.message.into());
+        if let Some(status_code) = failure.status_code {
+            dropped.insert("status_code".into(), i64::from(status_code).into());
+        }
+        dropped.insert("component_id".into(), self.sink.id().into());
+        dropped.insert("component_type".into(), self.sink_type.into());
+        dropped.insert("component_kind".into(), "sink".into());
+        dropped.insert("timestamp".into(), timestamp.into());
+
+        match event {
+            Event::Log(log) => match log.namespace() {
+                LogNamespace::Legacy => {
+                    if let Some(metadata_key) = log_schema().metadata_key() {
+                        log.insert(
+                            (PathPrefix::Event, metadata_key.concat(path!("dropped"))),
+                            dropped,
+                        );
+                    }
+                }
+                LogNamespace::Vector => {
+                    log.insert(metadata_path!("vector", "dropped"), dropped);
+                }
+            },
+            Event::Metric(metric) => {
+                if let Some(metadata_key) = log_schema().metadata_key() {
+                    let timestamp = timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+                    let status_code = failure.status_code.map(|code| code.to_string());
+                    for (name, value) in [
+                        ("reason", Some(failure.reason)),
+                        ("message", Some(failure.message)),
+                        ("status_code", status_code.as_deref()),
+                        ("component_id", Some(self.sink.id())),
+                        ("component_type", Some(self.sink_type)),
+                        ("component_kind", Some("sink")),
+                        ("timestamp", Some(timestamp.as_str())),
+                    ] {
+                        if let Some(value) = value {
+                            metric.replace_tag(
+                                format!("{metadata_key}.dropped.{name}"),
+                                value.to_owned(),
+                            );
+                        }
+                    }
+                }
+            }
+            Event::Trace(trace) => {
+                trace.maybe_insert(log_schema().metadata_key_target_path(), || dropped.into());
+            }
+        }
+    }
+
+    /// Sends the response to a request of the sink to the output, as a log with its `status_code`,
+    /// `headers` and `body`, returning whether it was queued.
+    pub fn send_response(&self, response: &http::Response<Bytes>) -> bool {
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                (name.as_str().into(), Value::from(value))
+            })
+            .collect::<ObjectMap>();
+
+        let mut log = LogEvent::default();
+        log.insert(
+            event_path!("status_code"),
+            i64::from(response.status().as_u16()),
+        );
+        log.insert(event_path!("headers"), headers);
+        log.insert(event_path!("body"), response.body().clone());
+        log.insert(event_path!("component_id"), self.sink.id());
+        log.insert(event_path!("component_type"), self.sink_type);
+        log.maybe_insert(log_schema().timestamp_key_target_path(), Utc::now());
+        self.send(vec![log.into()])
+    }
+
+    /// Sends events to the output, returning whether they were queued.
+    pub fn send(&self, events: Vec<Event>) -> bool {
+        if events.is_empty() {
+            return true;
+        }
+        let count = events.len();
+        let queued = self.tx.try_send(events).is_ok();
+        if !queued {
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+                count,
+                reason: "The output of the sink is full.",
+            });
+        }
+        queued
+    }
+}
+
+/// An event sent to a sink, waiting for the sink to finalize it.
+#[derive(Debug)]
+struct TrackedEvent {
+    /// The copy of the event sent to the output if the sink fails to deliver it.
+    event: Event,
+    /// The original finalizers of the event.
+    finalizers: EventFinalizers,
+    failure: BatchFailure,
+}
+
+/// Sends the events that a sink fails to deliver to its `dropped` output.
+///
+/// The events are waited for by a single task, which ends once the tracker is dropped and the
+/// events left are finalized.
+#[derive(Debug)]
+pub struct DeadLetterTracker {
+    finalizer: UnorderedFinalizer<TrackedEvent>,
+}
+
+impl DeadLetterTracker {
+    /// Creates a tracker, which waits for the events in the background until it's dropped.
+    pub fn new(sender: DeadLetterSender) -> Self {
+        let (finalizer, finalized) = UnorderedFinalizer::new(None);
+        tokio::spawn(
+            async move {
+                let mut finalized = finalized.ready_chunks(MAX_UNDELIVERED_BATCH_SIZE);
+                while let Some(events) = finalized.next().await {
+                    send_undelivered(&sender, events);
+                }
+            }
+            .in_current_span(),
+        );
+        Self { finalizer }
+    }
+
+    /// Tracks the events of an array sent to the sink.
+    ///
+    /// The events get their own finalizers, whose status is waited for before updating the
+    /// original finalizers of the events. The copies of the events share the values of logs and
+    /// traces with the events sent to the sink, so they're only duplicated if the sink modifies
+    /// them, and are dropped as soon as the sink delivers them.
+    pub fn track(&self, mut events: EventArray) -> EventArray {
+        let finalizers = events
+            .iter_events_mut()
+            .map(|mut event| event.metadata_mut().take_finalizers())
+            .collect::<Vec<_>>();
+        let copies = events.clone().into_events();
+        for ((mut event, finalizers), copy) in events.iter_events_mut().zip(finalizers).zip(copies)
+        {
+            let (batch, receiver, failure) = BatchNotifier::new_with_failure_receiver();
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new(batch));
+            let tracked = TrackedEvent {
+                event: copy,
+                finalizers,
+                failure,
+            };
+            self.finalizer.add(tracked, receiver);
+        }
+        events
+    }
+}
+
+/// Sends the finalized events that the sink didn't deliver to the output, with why they weren't,
+/// and updates the original finalizers of the events. The events sent to the output are considered
+/// delivered.
+fn send_undelivered(sender: &DeadLetterSender, events: Vec<(BatchStatus, TrackedEvent)>) {
+    let mut undelivered = Vec::new();
+    for (status, tracked) in events {
+        let TrackedEvent {
+            mut event,
+            finalizers,
+            failure,
+        } = tracked;
+        let (status, reason, default_message) = match status {
+            BatchStatus::Delivered => {
+                finalizers.update_status(EventStatus::Delivered);
+                continue;
+            }
+            BatchStatus::Errored => (
+                EventStatus::Errored,
+                "errored",
+                "The event couldn't be sent.",
+            ),
+            BatchStatus::Rejected => (EventStatus::Rejected, "rejected", "The event was rejected."),
+        };
+        let failure = failure.take();
+        sender.annotate(
+            &mut event,
+            Failure {
+                reason,
+                message: failure
+                    .as_ref()
+                    .map_or(default_message, |failure| failure.message.as_str()),
+                status_code: failure.as_ref().and_then(|failure| failure.status_code),
+            },
+        );
+        undelivered.push((event, (finalizers, status)));
+    }
+
+    let (events, finalizers): (Vec<_>, Vec<_>) = undelivered.into_iter().unzip();
+    let queued = sender.send(events);
+    for (finalizers, status) in finalizers {
+        finalizers.update_status(if queued {
+            EventStatus::Delivered
+        } else {
+            status
+        });
+    }
+}
+
+/// The source emitting the events sent to a dead-letter output of a sink.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeadLetterSourceConfig {
+    /// The sink sending the events.
+    sink: ComponentKey,
+}
+
+impl NamedComponent for DeadLetterSourceConfig {
+    fn get_component_name(&self) -> &'static str {
+        "dead_letter"
+    }
+}
+
+#[async_trait]
+#[typetag::serde(name = "dead_letter")]
+impl SourceConfig for DeadLetterSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        let (_, rx) = queue(&cx.key);
+        let mut shutdown = cx.shutdown;
+        let mut out = cx.out;
+
+        Ok(Box::pin(async move {
+            let events_received = register!(EventsReceived);
+            let mut rx = rx.lock().await;
+            let mut shutting_down = false;
+            loop {
+                let events = if shutting_down {
+                    // The events queued before shutting down are still sent.
+                    match rx.try_recv() {
+                        Ok(events) => events,
+                        Err(_) => break,
+                    }
+                } else {
+                    tokio::select! {
+                        biased;
+                        Some(events) = rx.recv() => events,
+                        _ = &mut shutdown => {
+                            shutting_down = true;
+                            continue;
+                        }
+                    }
+                };
+                let count = events.len();
+                events_received.emit(CountByteSize(
+                    count,
+                    events.estimated_json_encoded_size_of(),
+                ));
+                if out.send_batch(events).await.is_err() {
+                    emit!(StreamClosedError { count });
+                    return Err(());
+                }
+            }
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self, _global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        vec![SourceOutput::new_maybe_logs(
+            DataType::all_bits(),
+            schema::Definition::any(),
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Adds the sources emitting the events sent to the outputs of the sinks.
+pub(super) fn add_sources(
+    sources: &mut IndexMap<ComponentKey, SourceOuter>,
+    sinks: &IndexMap<ComponentKey, SinkOuter<String>>,
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for (key, sink) in sinks {
+        for output in sink.outputs() {
+            let source_key = key.join(output);
+            if sources.contains_key(&source_key) {
+                errors.push(format!(
+                    "Source \"{source_key}\" conflicts with the {output} output of sink \"{key}\"."
+                ));
+                continue;
+            }
+            sources.insert(
+                source_key,
+                SourceOuter::new(DeadLetterSourceConfig { sink: key.clone() }),
+            );
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that the events sent to the outputs of a sink can't be sent back to it, which would loop
+/// forever for the events that the sink rejects or whose requests get responses.
+pub(super) fn check_loops(
+    graph: &Graph,
+    sinks: &IndexMap<ComponentKey, SinkOuter<String>>,
+) -> Result<(), Vec<String>> {
+    // The sinks that the outputs of each sink lead to.
+    let next = sinks
+        .iter()
+        .map(|(key, sink)| {
+            let reachable = sink
+                .outputs()
+                .into_iter()
+                .flat_map(|output| graph.paths_to_sink_from(&key.join(output)))
+                .filter_map(|path| path.last().cloned())
+                .collect::<HashSet<_>>();
+            (key, reachable)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut errors = Vec::new();
+    for key in sinks.keys() {
+        let mut visited = HashSet::new();
+        let mut pending = next[key].iter().collect::<Vec<_>>();
+        while let Some(sink) = pending.pop() {
+            if sink == key {
+                errors.push(format!(
+                    "Sink \"{key}\" receives the events of its own outputs."
+                ));
+                break;
+            }
+            if visited.insert(sink) {
+                pending.extend(next.get(sink).into_iter().flatten());
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::DeliveryFailure;
+
+    use super::*;
+    use crate::config::{Format, load_from_str};
+
+    #[test]
+    fn adds_dropped_output() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "test_basic"
+
+            [sinks.out]
+            type = "test_basic"
+            inputs = ["in"]
+            dead_letter = true
+
+            [sinks.retry]
+            type = "test_basic"
+            inputs = ["out.dropped"]
+        "#,
+            Format::Toml,
+        )
+        .unwrap();
+        assert!(config.source(&ComponentKey::from("out.dropped")).is_some());
+
+        let errors = load_from_str(
+            r#"
+            [sources.in]
+            type = "test_basic"
+
+            [sinks.out]
+            type = "test_basic"
+            inputs = ["in", "out.dropped"]
+            dead_letter = true
+        "#,
+            Format::Toml,
+        )
+        .unwrap_err();
+        assert_eq!(
+            vec!["Sink \"out\" receives the events of its own outputs.".to_owned()],
+            errors
+        );
+    }
+
+    #[test]
+    fn sends_to_the_source_of_the_output() {
+        let sink = ComponentKey::from("dead_letter_test_sink");
+        let sender = DeadLetterSender::new(&sink, "test", "rejected");
+        let mut event = Event::from(LogEvent::from("message"));
+        sender.annotate(
+            &mut event,
+            Failure {
+                reason: "rejected",
+                message: "invalid field",
+                status_code: Some(400),
+            },
+        );
+        let log = event.as_log();
+        assert_eq!(
+            log["metadata.dropped.component_id"],
+            "dead_letter_test_sink".into()
+        );
+        assert_eq!(log["metadata.dropped.component_kind"], "sink".into());
+        assert_eq!(log["metadata.dropped.message"], "invalid field".into());
+        assert_eq!(log["metadata.dropped.status_code"], 400.into());
+        assert!(log.contains("metadata.dropped.timestamp"));
+        assert!(sender.send(vec![event]));
+
+        let (_, rx) = queue(&sink.join("rejected"));
+        let events = rx.try_lock().unwrap().try_recv().unwrap();
+        assert_eq!(events[0].as_log()["message"], "message".into());
+    }
+
+    #[test]
+    fn drops_events_when_full() {
+        let sender = DeadLetterSender::new(&"dead_letter_full_sink".into(), "test", "rejected");
+        for _ in 0..QUEUE_CAPACITY {
+            assert!(sender.send(vec![LogEvent::from("message").into()]));
+        }
+        assert!(!sender.send(vec![LogEvent::from("message").into()]));
+    }
+
+    #[test]
+    fn sends_responses() {
+        let sink = ComponentKey::from("dead_letter_responses_sink");
+        let sender = DeadLetterSender::new(&sink, "http", RESPONSES_OUTPUT);
+        let response = http::Response::builder()
+            .status(201)
+            .header("Location", "/incidents/42")
+            .body(Bytes::from_static(b"{\"id\":42}"))
+            .unwrap();
+        assert!(sender.send_response(&response));
+
+        let (_, rx) = queue(&sink.join(RESPONSES_OUTPUT));
+        let events = rx.try_lock().unwrap().try_recv().unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["status_code"], 201.into());
+        assert_eq!(log["headers.location"], "/incidents/42".into());
+        assert_eq!(log["body"], "{\"id\":42}".into());
+        assert_eq!(log["component_type"], "http".into());
+        assert!(log.contains("timestamp"));
+    }
+
+    #[tokio::test]
+    async fn sends_undelivered_events_once_finalized() {
+        let sink = ComponentKey::from("dead_letter_tracked_sink");
+        let sender = DeadLetterSender::new(&sink, "test", DROPPED_OUTPUT);
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let events = EventArray::from(vec![
+            LogEvent::from("delivered").with_batch_notifier(&batch),
+            LogEvent::from("rejected").with_batch_notifier(&batch),
+        ]);
+        drop(batch);
+
+        let tracker = DeadLetterTracker::new(sender);
+        let mut events = tracker.track(events).into_events().collect::<Vec<_>>();
+        let rejected = events.pop().unwrap();
+        rejected.metadata().finalizers().update_failure(
+            EventStatus::Rejected,
+            &DeliveryFailure {
+                message: "400 Bad Request: invalid field".to_owned(),
+                status_code: Some(400),
+            },
+        );
+        drop((events, rejected));
+
+        // The rejected event was sent to the output, so the batch is delivered.
+        assert_eq!(BatchStatus::Delivered, receiver.await);
+        let (_, rx) = queue(&sink.join(DROPPED_OUTPUT));
+        let dropped = rx.lock().await.recv().await.unwrap();
+        assert_eq!(1, dropped.len());
+        let log = dropped[0].as_log();
+        assert_eq!(log["message"], "rejected".into());
+        assert_eq!(log["metadata.dropped.reason"], "rejected".into());
+        assert_eq!(
+            log["metadata.dropped.message"],
+            "400 Bad Request: invalid field".into()
+        );
+        assert_eq!(log["metadata.dropped.status_code"], 400.into());
+        assert_eq!(log["metadata.dropped.component_type"], "test".into());
+    }
+}
@@ -25,7 +25,11 @@ impl ConfigDiff {
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources, &components_to_reload),
             transforms: Difference::new(&old.transforms, &new.transforms, &components_to_reload),
-            sinks: Difference::new(&old.sinks, &new.sinks, &components_to_reload),
+            sinks: Difference::new(&old.sinks, &new.sinks, &components_to_reload).with_rewired(
+                &old.sinks,
+                &new.sinks,
+                &components_to_reload,
+            ),
             enrichment_tables: Difference::from_enrichment_tables(
                 &old.enrichment_tables,
                 &new.enrichment_tables,
@@ -73,6 +77,10 @@ pub struct Difference {
     pub to_remove: HashSet<ComponentKey>,
     pub to_change: HashSet<ComponentKey>,
     pub to_add: HashSet<ComponentKey>,
+    /// The components whose inputs are their only change, which are reconnected to their new
+    /// inputs without being rebuilt. Only sinks are rewired, as the outputs of the transforms are
+    /// built from the definitions of their inputs.
+    pub to_rewire: HashSet<ComponentKey>,
 }
 
 impl Difference {
@@ -110,6 +118,7 @@ impl Difference {
             to_remove,
             to_change,
             to_add,
+            to_rewire: HashSet::new(),
         }
     }
 
@@ -154,9 +163,33 @@ impl Difference {
             to_remove,
             to_change,
             to_add,
+            to_rewire: HashSet::new(),
         }
     }
 
+    /// Moves the changed components whose inputs are their only change to `to_rewire`.
+    fn with_rewired<C>(
+        mut self,
+        old: &IndexMap<ComponentKey, C>,
+        new: &IndexMap<ComponentKey, C>,
+        need_change: &HashSet<ComponentKey>,
+    ) -> Self
+    where
+        C: serde::Serialize,
+    {
+        let to_rewire = self
+            .to_change
+            .iter()
+            .filter(|&n| {
+                !need_change.contains(n) && without_inputs(&old[n]) == without_inputs(&new[n])
+            })
+            .cloned()
+            .collect::<HashSet<_>>();
+        self.to_change.retain(|n| !to_rewire.contains(n));
+        self.to_rewire = to_rewire;
+        self
+    }
+
     /// Checks whether or not any components are being changed or added.
     pub fn any_changed_or_added(&self) -> bool {
         !(self.to_change.is_empty() && self.to_add.is_empty())
@@ -169,7 +202,10 @@ impl Difference {
 
     /// Checks whether the given component is present at all.
     pub fn contains(&self, id: &ComponentKey) -> bool {
-        self.to_add.contains(id) || self.to_change.contains(id) || self.to_remove.contains(id)
+        self.to_add.contains(id)
+            || self.to_change.contains(id)
+            || self.to_remove.contains(id)
+            || self.to_rewire.contains(id)
     }
 
     /// Checks whether the given component is present as a change or addition.
@@ -205,6 +241,15 @@ impl Difference {
     }
 }
 
+/// Serializes a component without its inputs, to compare the rest of its configuration.
+fn without_inputs<C: serde::Serialize>(component: &C) -> serde_json::Value {
+    let mut value = serde_json::to_value(component).unwrap();
+    if let Some(component) = value.as_object_mut() {
+        component.remove("inputs");
+    }
+    value
+}
+
 /// Helper function to extract component keys from enrichment tables.
 fn extract_table_component_keys(
     tables: &IndexMap<ComponentKey, EnrichmentTableOuter<OutputId>>,
@@ -317,4 +362,65 @@ mod tests {
             HashSet::from_iter(["memory_table".into(), "memory_table_source".into()])
         );
     }
+
+    #[test]
+    fn diff_rewires_sinks_with_only_changed_inputs() {
+        let old_config: Config = serde_yaml::from_str::<ConfigBuilder>(indoc! {r#"
+            sources:
+              in1:
+                type: "test_basic"
+              in2:
+                type: "test_basic"
+
+            sinks:
+              rewired:
+                type: "test_basic"
+                inputs: ["in1"]
+              changed:
+                type: "test_basic"
+                inputs: ["in1"]
+              unchanged:
+                type: "test_basic"
+                inputs: ["in1"]
+        "#})
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let new_config: Config = serde_yaml::from_str::<ConfigBuilder>(indoc! {r#"
+            sources:
+              in1:
+                type: "test_basic"
+              in2:
+                type: "test_basic"
+
+            sinks:
+              rewired:
+                type: "test_basic"
+                inputs: ["in1", "in2"]
+              changed:
+                type: "test_basic"
+                inputs: ["in2"]
+                data: "changed"
+              unchanged:
+                type: "test_basic"
+                inputs: ["in1"]
+        "#})
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let diff = ConfigDiff::new(&old_config, &new_config, HashSet::new());
+        assert_eq!(diff.sinks.to_rewire, HashSet::from_iter(["rewired".into()]));
+        assert_eq!(diff.sinks.to_change, HashSet::from_iter(["changed".into()]));
+        assert!(!diff.sinks.contains(&"unchanged".into()));
+
+        let diff = ConfigDiff::new(
+            &old_config,
+            &new_config,
+            HashSet::from_iter(["rewired".into()]),
+        );
+        assert!(diff.sinks.to_rewire.is_empty());
+        assert!(diff.sinks.is_changed(&"rewired".into()));
+    }
 }
@@ -68,6 +68,8 @@ where
                     healthcheck: Default::default(),
                     buffer: Default::default(),
                     proxy: Default::default(),
+                    dead_letter: false,
+                    acknowledgement_timeout_secs: None,
                     inner: sink,
                 },
             )
@@ -82,6 +84,7 @@ where
                     graph: self.graph.clone(),
                     sink_acknowledgements: false,
                     proxy: Default::default(),
+                    wal: None,
                     inner: source,
                 },
             )
@@ -161,6 +161,7 @@ pub async fn load_from_paths_with_provider_and_secrets(
 
     validation::check_provider(&builder)?;
     signal_handler.clear();
+    secrets_backends_loader.watch(signal_handler);
 
     // If there's a provider, overwrite the existing config builder with the remote variant.
     if let Some(mut provider) = builder.provider {
@@ -202,6 +203,7 @@ pub async fn load_from_str_with_secrets(
 
     builder.allow_empty = allow_empty;
     signal_handler.clear();
+    secrets_backends_loader.watch(signal_handler);
     let (new_config, build_warnings) = builder.build_with_warnings()?;
 
     validation::check_buffer_preconditions(&new_config).await?;
@@ -213,6 +215,49 @@ pub async fn load_from_str_with_secrets(
     Ok(new_config)
 }
 
+/// Loads and validates a config builder from a string, resolving secrets from the backends it
+/// configures. The build warnings are returned along with the builder.
+///
+/// Unlike [`load_from_str_with_secrets`], this doesn't take over the signal handler, so that the
+/// running config keeps watching its secrets until the input is applied by sending it as a
+/// [`signal::SignalTo::ReloadFromInput`]. For the same reason, providers aren't supported.
+///
+/// The input is expected to come from clients that aren't trusted with the host, so secret
+/// backends running commands or reading files are rejected before any secret is retrieved.
+pub async fn load_builder_from_str_with_secrets(
+    input: &str,
+    format: Format,
+    signal_rx: &mut signal::SignalRx,
+) -> Result<(ConfigBuilder, Vec<String>), Vec<String>> {
+    // Load secret backends first
+    let mut secrets_backends_loader = load_secret_backends_from_input(input.as_bytes(), format)?;
+    secrets_backends_loader.check_remote_backends()?;
+    // And then, if needed, retrieve secrets from configured backends
+    let builder = if secrets_backends_loader.has_secrets_to_retrieve() {
+        debug!(message = "Secret placeholders found, retrieving secrets from configured backends.");
+        let resolved_secrets = secrets_backends_loader
+            .retrieve(signal_rx)
+            .await
+            .map_err(|e| vec![e])?;
+        load_builder_from_input_with_secrets(input.as_bytes(), format, resolved_secrets)?
+    } else {
+        debug!(message = "No secret placeholder found, skipping secret resolution.");
+        load_builder_from_input(input.as_bytes(), format)?
+    };
+
+    if builder.provider.is_some() {
+        return Err(vec![
+            "Providers can't be configured when loading a config from a string.".to_owned(),
+        ]);
+    }
+
+    let (config, build_warnings) = builder.clone().build_with_warnings()?;
+
+    validation::check_buffer_preconditions(&config).await?;
+
+    Ok((builder, build_warnings))
+}
+
 fn loader_from_input<T, L, R>(mut loader: L, input: R, format: Format) -> Result<T, Vec<String>>
 where
     T: serde::de::DeserializeOwned,
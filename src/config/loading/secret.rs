@@ -42,6 +42,8 @@ pub(crate) struct SecretBackendOuter {
 pub struct SecretBackendLoader {
     backends: IndexMap<ComponentKey, SecretBackends>,
     pub(crate) secret_keys: HashMap<String, HashSet<String>>,
+    #[serde(skip)]
+    retrieved: HashMap<String, HashMap<String, String>>,
 }
 
 impl SecretBackendLoader {
@@ -49,6 +51,7 @@ impl SecretBackendLoader {
         Self {
             backends: IndexMap::new(),
             secret_keys: HashMap::new(),
+            retrieved: HashMap::new(),
         }
     }
 
@@ -72,6 +75,8 @@ impl SecretBackendLoader {
                     format!("Error while retrieving secret from backend \"{backend_name}\": {e}.",)
                 })
                 .await?;
+            self.retrieved
+                .insert(backend_name.clone(), backend_secrets.clone());
 
             for (k, v) in backend_secrets {
                 trace!(message = "Successfully retrieved a secret.", backend = ?backend_name, key = ?k);
@@ -85,6 +90,44 @@ impl SecretBackendLoader {
     pub(crate) fn has_secrets_to_retrieve(&self) -> bool {
         !self.secret_keys.is_empty()
     }
+
+    /// Checks that none of the backends runs commands or reads files on the host, for configs
+    /// received from clients that can't be trusted with them.
+    pub(crate) fn check_remote_backends(&self) -> Result<(), Vec<String>> {
+        let errors = self
+            .backends
+            .iter()
+            .filter(|(_, backend)| {
+                matches!(
+                    backend,
+                    SecretBackends::Directory(_)
+                        | SecretBackends::Exec(_)
+                        | SecretBackends::File(_)
+                )
+            })
+            .map(|(key, _)| {
+                format!("Secret backend \"{key}\" can't read secrets from the host in this config.")
+            })
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Watches the retrieved secrets for changes, for the backends that support it.
+    pub(crate) fn watch(&self, signal_handler: &mut signal::SignalHandler) {
+        for (backend_name, secrets) in &self.retrieved {
+            let Some(backend) = self.backends.get(&ComponentKey::from(backend_name.clone())) else {
+                continue;
+            };
+            if let Some(signals) = backend.watch(secrets.clone()) {
+                debug!(message = "Watching secrets for changes.", backend = ?backend_name);
+                signal_handler.add(signals);
+            }
+        }
+    }
 }
 
 impl Process for SecretBackendLoader {
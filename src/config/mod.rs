@@ -31,6 +31,7 @@ pub mod api;
 mod builder;
 mod cmd;
 mod compiler;
+pub mod dead_letter;
 mod diff;
 pub mod dot_graph;
 mod enrichment_table;
@@ -38,10 +39,12 @@ pub mod format;
 mod graph;
 mod loading;
 pub mod provider;
+mod render;
 pub mod schema;
 mod secret;
 mod sink;
 mod source;
+mod templates;
 mod transform;
 pub mod unit_test;
 mod validation;
@@ -54,14 +57,14 @@ pub use diff::ConfigDiff;
 pub use enrichment_table::{EnrichmentTableConfig, EnrichmentTableOuter};
 pub use format::{Format, FormatHint};
 pub use loading::{
-    COLLECTOR, CONFIG_PATHS, load, load_builder_from_paths, load_from_paths,
-    load_from_paths_with_provider_and_secrets, load_from_str, load_from_str_with_secrets,
-    load_source_from_paths, merge_path_lists, process_paths,
+    COLLECTOR, CONFIG_PATHS, load, load_builder_from_paths, load_builder_from_str_with_secrets,
+    load_from_paths, load_from_paths_with_provider_and_secrets, load_from_str,
+    load_from_str_with_secrets, load_source_from_paths, merge_path_lists, process_paths,
 };
 pub use provider::ProviderConfig;
 pub use secret::SecretBackend;
 pub use sink::{BoxedSink, SinkConfig, SinkContext, SinkHealthcheckOptions, SinkOuter};
-pub use source::{BoxedSource, SourceConfig, SourceContext, SourceOuter};
+pub use source::{BoxedSource, SourceConfig, SourceContext, SourceOuter, SourceWalConfig};
 pub use transform::{
     BoxedTransform, TransformConfig, TransformContext, TransformOuter, get_transform_output_ids,
 };
@@ -211,6 +214,18 @@ impl Config {
             .or_else(|| self.enrichment_tables.get(id).map(|s| &s.inputs[..]))
     }
 
+    /// Returns a copy of the sources and transforms of the configuration, for computing their
+    /// schema definitions outside of a topology build without filling the caches of the
+    /// transforms, as the enrichment tables can only be used by the transforms during a build.
+    pub(crate) fn schema_copy(&self) -> Self {
+        Self {
+            schema: self.schema,
+            sources: self.sources.clone(),
+            transforms: self.transforms.clone(),
+            ..Default::default()
+        }
+    }
+
     pub fn propagate_acknowledgements(&mut self) -> Result<(), Vec<String>> {
         let inputs: Vec<_> = self
             .sinks
@@ -444,11 +459,13 @@ impl TestDefinition<String> {
                 let TestOutput {
                     extract_from,
                     conditions,
+                    metrics,
+                    snapshot,
                 } = old;
 
-                (extract_from.to_vec(), conditions)
+                (extract_from.to_vec(), conditions, metrics, snapshot)
             })
-            .filter_map(|(extract_from, conditions)| {
+            .filter_map(|(extract_from, conditions, metrics, snapshot)| {
                 let mut outputs = Vec::new();
                 for from in extract_from {
                     if no_outputs_from.contains(&from) {
@@ -469,6 +486,8 @@ impl TestDefinition<String> {
                     Some(TestOutput {
                         extract_from: outputs.into(),
                         conditions,
+                        metrics,
+                        snapshot,
                     })
                 }
             })
@@ -523,6 +542,8 @@ impl TestDefinition<OutputId> {
                     .collect::<Vec<_>>()
                     .into(),
                 conditions: old.conditions,
+                metrics: old.metrics,
+                snapshot: old.snapshot,
             })
             .collect();
 
@@ -594,6 +615,41 @@ pub struct TestOutput<T: 'static = OutputId> {
 
     /// The conditions to run against the output to validate that they were transformed as expected.
     pub conditions: Option<Vec<conditions::AnyCondition>>,
+
+    /// The metrics expected in the output, each of which must match at least one of the output
+    /// events.
+    ///
+    /// An output event matches an expected metric if it's a metric with the same name, namespace,
+    /// kind, and value, and with all of the tags of the expected metric, though it can have other
+    /// tags. The timestamp and the interval are only compared when set on the expected metric.
+    pub metrics: Option<Vec<Metric>>,
+
+    /// A snapshot of the events expected in the output, stored in a JSON file.
+    ///
+    /// The output events must be the same as the events of the snapshot, in the same order. Run
+    /// `vector test` with `--update-snapshots` to write the output events to the snapshot instead.
+    pub snapshot: Option<TestSnapshot>,
+}
+
+/// A snapshot of the events expected from a unit test output.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TestSnapshot {
+    /// The path of the JSON file holding the events of the snapshot.
+    ///
+    /// Relative paths are resolved from the current working directory.
+    #[configurable(metadata(docs::examples = "tests/snapshots/parse_logs.json"))]
+    pub path: PathBuf,
+
+    /// The fields of the events left out of the snapshot, as dot-separated paths.
+    ///
+    /// This is meant for the fields whose values change with each run, such as the timestamps set
+    /// when the events are created.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "timestamp"))]
+    #[configurable(metadata(docs::examples = "tags.host"))]
+    pub ignore_fields: Vec<String>,
 }
 
 #[cfg(all(test, feature = "sources-file", feature = "sinks-console"))]
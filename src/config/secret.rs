@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use enum_dispatch::enum_dispatch;
+use futures::stream::BoxStream;
 use vector_lib::configurable::NamedComponent;
 
 use crate::signal;
@@ -13,4 +14,15 @@ pub trait SecretBackend: NamedComponent + core::fmt::Debug + Send + Sync {
         secret_keys: HashSet<String>,
         signal_rx: &mut signal::SignalRx,
     ) -> crate::Result<HashMap<String, String>>;
+
+    /// Watches the secrets returned by `retrieve` for changes.
+    ///
+    /// Backends able to detect when a secret rotates return a stream of signals to send, such as
+    /// a configuration reload once a secret changed.
+    fn watch(
+        &self,
+        _secrets: HashMap<String, String>,
+    ) -> Option<BoxStream<'static, signal::SignalTo>> {
+        None
+    }
 }
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, path::PathBuf, time::Duration};
+use std::{cell::RefCell, num::NonZeroU64, path::PathBuf, time::Duration};
 
 use async_trait::async_trait;
 use dyn_clone::DynClone;
@@ -17,7 +17,10 @@ use vector_lib::{
     sink::VectorSink,
 };
 
-use super::{ComponentKey, ProxyConfig, Resource, dot_graph::GraphConfig, schema};
+use super::{
+    ComponentKey, ProxyConfig, Resource, dead_letter::DROPPED_OUTPUT, dot_graph::GraphConfig,
+    schema,
+};
 use crate::{
     extra_context::ExtraContext,
     sinks::{Healthcheck, util::UriSerde},
@@ -85,6 +88,32 @@ where
     #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
     pub proxy: ProxyConfig,
 
+    /// Whether or not to send the events that the sink fails to deliver to the `dropped` output.
+    ///
+    /// The events that the sink rejects, or still fails to send once its retries are exhausted,
+    /// are sent to the `<sink_id>.dropped` output, which other components can use as an input.
+    /// Like for the events rerouted by the `remap` transform, the reason of the failure, the sink
+    /// and the time of the failure are added to the `dropped` field of the metadata of the events,
+    /// along with the error of the request and the status code of its response, if there is one.
+    ///
+    /// The events sent to the output are considered delivered by the sink.
+    #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+    #[configurable(metadata(docs::advanced))]
+    pub dead_letter: bool,
+
+    /// The time after which the events that the sink holds without acknowledging them are
+    /// reported as stuck.
+    ///
+    /// A warning is logged for each batch of events that the sink holds for longer, with the
+    /// number of events of the batch and how long the sink has held them, and the age of the
+    /// oldest event that the sink holds is reported by the
+    /// `oldest_unacknowledged_event_age_seconds` gauge. The events are acknowledged once the sink
+    /// delivered them or failed to, whether or not `acknowledgements` are enabled.
+    #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+    #[configurable(metadata(docs::advanced))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub acknowledgement_timeout_secs: Option<NonZeroU64>,
+
     #[serde(flatten)]
     #[configurable(metadata(docs::hidden))]
     pub inner: BoxedSink,
@@ -107,6 +136,8 @@ where
             inner: inner.into(),
             proxy: Default::default(),
             graph: Default::default(),
+            dead_letter: false,
+            acknowledgement_timeout_secs: None,
         }
     }
 
@@ -145,6 +176,15 @@ where
         &self.proxy
     }
 
+    /// Gets the names of the outputs of the sink, including its dead-letter outputs.
+    pub fn outputs(&self) -> Vec<&'static str> {
+        let mut outputs = self.inner.outputs();
+        if self.dead_letter {
+            outputs.push(DROPPED_OUTPUT);
+        }
+        outputs
+    }
+
     pub(super) fn map_inputs<U>(self, f: impl Fn(&T) -> U) -> SinkOuter<U>
     where
         U: Configurable + Serialize,
@@ -166,6 +206,8 @@ where
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
             graph: self.graph,
+            dead_letter: self.dead_letter,
+            acknowledgement_timeout_secs: self.acknowledgement_timeout_secs,
         }
     }
 }
@@ -267,12 +309,23 @@ pub trait SinkConfig: DynClone + NamedComponent + core::fmt::Debug + Send + Sync
 
     /// Gets the acknowledgements configuration for this sink.
     fn acknowledgements(&self) -> &AcknowledgementsConfig;
+
+    /// Gets the names of the outputs the sink sends events to, such as the events it couldn't
+    /// deliver or the responses to its requests.
+    ///
+    /// The events sent to an output are emitted by a source named `<sink_id>.<output>`, which other
+    /// components can use as an input. See [`crate::config::dead_letter`].
+    fn outputs(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 dyn_clone::clone_trait_object!(SinkConfig);
 
 #[derive(Clone, Debug)]
 pub struct SinkContext {
+    /// The key of the sink, which is only unset when the sink is built outside of a topology.
+    pub key: Option<ComponentKey>,
     pub healthcheck: SinkHealthcheckOptions,
     pub globals: GlobalOptions,
     pub enrichment_tables: vector_lib::enrichment::TableRegistry,
@@ -289,6 +342,7 @@ pub struct SinkContext {
 impl Default for SinkContext {
     fn default() -> Self {
         Self {
+            key: None,
             healthcheck: Default::default(),
             globals: Default::default(),
             enrichment_tables: Default::default(),
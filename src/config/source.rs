@@ -1,7 +1,13 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+};
 
 use async_trait::async_trait;
 use dyn_clone::DynClone;
+use tracing::Span;
 use vector_config::{Configurable, GenerateError, Metadata, NamedComponent};
 use vector_config_common::{
     attributes::CustomAttribute,
@@ -9,6 +15,11 @@ use vector_config_common::{
 };
 use vector_config_macros::configurable_component;
 use vector_lib::{
+    buffers::{
+        BufferConfig, BufferType, WhenFull,
+        config::{BufferBuildError, DiskUsage},
+        topology::channel::{BufferReceiver, BufferSender},
+    },
     config::{
         AcknowledgementsConfig, GlobalOptions, LogNamespace, SourceAcknowledgementsConfig,
         SourceOutput,
@@ -16,8 +27,10 @@ use vector_lib::{
     source::Source,
 };
 
-use super::{ComponentKey, ProxyConfig, Resource, dot_graph::GraphConfig, schema};
-use crate::{SourceSender, extra_context::ExtraContext, shutdown::ShutdownSignal};
+use super::{ComponentKey, OutputId, ProxyConfig, Resource, dot_graph::GraphConfig, schema};
+use crate::{
+    SourceSender, event::EventArray, extra_context::ExtraContext, shutdown::ShutdownSignal,
+};
 
 pub type BoxedSource = Box<dyn SourceConfig>;
 
@@ -60,6 +73,10 @@ pub struct SourceOuter {
     #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
     pub graph: GraphConfig,
 
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal: Option<SourceWalConfig>,
+
     #[serde(default, skip)]
     pub sink_acknowledgements: bool,
 
@@ -73,12 +90,79 @@ impl SourceOuter {
         Self {
             proxy: Default::default(),
             graph: Default::default(),
+            wal: None,
             sink_acknowledgements: false,
             inner: inner.into(),
         }
     }
 }
 
+/// Write-ahead log configuration.
+///
+/// The events received by the source are persisted to a log on disk before they're acknowledged to
+/// the client, and are replayed from the log on restart until they're delivered. This provides
+/// durability for the sources whose clients can't send their events again, even when the sinks use
+/// memory buffers.
+///
+/// When the log is full, the source stops accepting events until the events of the log are
+/// delivered.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SourceWalConfig {
+    /// The maximum size of the write-ahead log of each output of the source.
+    ///
+    /// Must be at least ~256 megabytes (268435488 bytes).
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_size: NonZeroU64,
+}
+
+impl SourceWalConfig {
+    /// Gets the disk usage of the write-ahead log of a source output.
+    pub fn disk_usage(
+        &self,
+        global_data_dir: Option<PathBuf>,
+        output: &OutputId,
+    ) -> Option<DiskUsage> {
+        self.buffer_type().disk_usage(
+            global_data_dir.map(|data_dir| wal_data_dir(&data_dir)),
+            &ComponentKey::from(output.to_string()),
+        )
+    }
+
+    /// Builds the write-ahead log of a source output.
+    ///
+    /// The log is a disk buffer, which blocks the source when it's full.
+    pub async fn build(
+        &self,
+        global_data_dir: Option<PathBuf>,
+        output: &OutputId,
+        span: Span,
+    ) -> Result<(BufferSender<EventArray>, BufferReceiver<EventArray>), BufferBuildError> {
+        BufferConfig::Single(self.buffer_type())
+            .build(
+                global_data_dir.map(|data_dir| wal_data_dir(&data_dir)),
+                output.to_string(),
+                span,
+            )
+            .await
+    }
+
+    fn buffer_type(&self) -> BufferType {
+        BufferType::DiskV2 {
+            max_size: self.max_size,
+            when_full: WhenFull::Block,
+            encryption: None,
+        }
+    }
+}
+
+/// Gets the directory holding the write-ahead logs of the sources, apart from the buffers of the
+/// sinks.
+fn wal_data_dir(global_data_dir: &Path) -> PathBuf {
+    global_data_dir.join("wal")
+}
+
 /// Generalized interface for describing and building source components.
 #[async_trait]
 #[typetag::serde(tag = "type")]
@@ -120,6 +204,18 @@ pub trait SourceConfig: DynClone + NamedComponent + core::fmt::Debug + Send + Sy
     /// well as emit contextual warnings when end-to-end acknowledgements are enabled, but the
     /// topology as configured does not actually support the use of end-to-end acknowledgements.
     fn can_acknowledge(&self) -> bool;
+
+    /// Gets the directory in which this source persists file checkpoints, if it does.
+    ///
+    /// This is used by the `vector checkpoints` command to export and import the checkpoints of
+    /// the source.
+    fn checkpoints_dir(
+        &self,
+        _globals: &GlobalOptions,
+        _key: &ComponentKey,
+    ) -> Option<crate::Result<PathBuf>> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(SourceConfig);
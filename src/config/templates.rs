@@ -0,0 +1,401 @@
+//! Component templates, which define a set of components parameterized by values substituted when
+//! the templates are instantiated.
+//!
+//! The configuration compiler expands each instance of a template into the components of the
+//! template before validating the configuration. The IDs of the components are prefixed with the
+//! name of the instance, as are the inputs referring to the other components of the template, so
+//! that a template can be instantiated any number of times.
+
+use std::{collections::HashSet, sync::LazyLock};
+
+use indexmap::IndexMap;
+use regex::{Captures, Regex};
+use serde::de::DeserializeOwned;
+use vector_lib::configurable::configurable_component;
+
+use super::{ComponentKey, builder::ConfigBuilder};
+
+/// The parameter holding the name of the instance of a template.
+const INSTANCE_PARAM: &str = "instance";
+
+static PARAM_REFERENCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"@\{([[:word:]]+)\}").unwrap());
+
+/// Component templates, defining a set of sources, transforms, and sinks parameterized by values
+/// set when instantiating the templates in `instances`.
+///
+/// The parameters are referenced as `@{name}` in the string values of the components of a template.
+/// A string made of a single reference is replaced with the value of the parameter whatever its
+/// type, so that a parameter can hold a list of inputs. The `instance` parameter holds the name of
+/// the instance.
+///
+/// The ID of each component of an instance is the name of the instance and the ID of the component
+/// in the template, joined with an underscore. The inputs referring to the other components of the
+/// template are renamed accordingly.
+#[configurable_component(global_option("templates"))]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+#[configurable(metadata(docs::common = false, docs::required = false))]
+pub struct ComponentTemplate {
+    /// The parameters of the template, by name.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "A parameter of the template."))]
+    pub params: IndexMap<String, TemplateParam>,
+
+    /// The sources of the template, by ID.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "A source of the template."))]
+    pub sources: IndexMap<String, toml::Value>,
+
+    /// The transforms of the template, by ID.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "A transform of the template."))]
+    pub transforms: IndexMap<String, toml::Value>,
+
+    /// The sinks of the template, by ID.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "A sink of the template."))]
+    pub sinks: IndexMap<String, toml::Value>,
+}
+
+/// A parameter of a component template.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateParam {
+    /// The value of the parameter for the instances not setting it.
+    ///
+    /// The parameters without a default value must be set by all of the instances of the template.
+    pub default: Option<toml::Value>,
+}
+
+/// Instances of the component templates defined in `templates`, by name.
+///
+/// The components of the instances are added to the configuration as if they were defined in the
+/// `sources`, `transforms`, and `sinks` sections.
+#[configurable_component(global_option("instances"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+#[configurable(metadata(docs::common = false, docs::required = false))]
+pub struct TemplateInstance {
+    /// The name of the template to instantiate.
+    #[configurable(metadata(docs::examples = "app_pipeline"))]
+    pub template: String,
+
+    /// The values of the parameters of the template, by name.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "The value of a parameter of the template."
+    ))]
+    pub params: IndexMap<String, toml::Value>,
+}
+
+/// Expands the instances of the templates into their components, consuming the templates.
+pub fn expand(builder: &mut ConfigBuilder) -> Result<(), Vec<String>> {
+    let templates = std::mem::take(&mut builder.templates);
+    let instances = std::mem::take(&mut builder.instances);
+    let mut errors = Vec::new();
+
+    for (name, template) in &templates {
+        if template.params.contains_key(INSTANCE_PARAM) {
+            errors.push(format!(
+                "Template \"{name}\" declares the reserved parameter \"{INSTANCE_PARAM}\"."
+            ));
+        }
+    }
+
+    for (name, instance) in &instances {
+        match templates.get(&instance.template) {
+            Some(template) => expand_instance(builder, name, instance, template, &mut errors),
+            None => errors.push(format!(
+                "Template instance \"{name}\" refers to unknown template \"{}\".",
+                instance.template
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn expand_instance(
+    builder: &mut ConfigBuilder,
+    name: &str,
+    instance: &TemplateInstance,
+    template: &ComponentTemplate,
+    errors: &mut Vec<String>,
+) {
+    for param in instance.params.keys() {
+        if !template.params.contains_key(param) {
+            errors.push(format!(
+                "Template instance \"{name}\" sets unknown parameter \"{param}\" of template \
+                 \"{}\".",
+                instance.template
+            ));
+        }
+    }
+
+    let mut params = IndexMap::new();
+    params.insert(INSTANCE_PARAM.to_owned(), toml::Value::from(name));
+    for (param, definition) in &template.params {
+        match instance.params.get(param).or(definition.default.as_ref()) {
+            Some(value) => {
+                params.insert(param.clone(), value.clone());
+            }
+            None => errors.push(format!(
+                "Template instance \"{name}\" doesn't set required parameter \"{param}\" of \
+                 template \"{}\".",
+                instance.template
+            )),
+        }
+    }
+
+    let local_ids = template
+        .sources
+        .keys()
+        .chain(template.transforms.keys())
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    let instantiate = |kind: &str, id: &str, value: &toml::Value, errors: &mut Vec<String>| {
+        let mut value = value.clone();
+        let mut param_errors = Vec::new();
+        substitute(&mut value, &params, &mut param_errors);
+        rename_inputs(&mut value, name, &local_ids);
+        errors.extend(
+            param_errors
+                .into_iter()
+                .map(|error| format!("Template instance \"{name}\": {kind} \"{id}\": {error}")),
+        );
+        (ComponentKey::from(format!("{name}_{id}")), value)
+    };
+
+    for (id, value) in &template.sources {
+        let (key, value) = instantiate("source", id, value, errors);
+        if let Some(source) = deserialize(name, "source", id, value, errors) {
+            insert(&mut builder.sources, "source", key, source, errors);
+        }
+    }
+    for (id, value) in &template.transforms {
+        let (key, value) = instantiate("transform", id, value, errors);
+        if let Some(transform) = deserialize(name, "transform", id, value, errors) {
+            insert(&mut builder.transforms, "transform", key, transform, errors);
+        }
+    }
+    for (id, value) in &template.sinks {
+        let (key, value) = instantiate("sink", id, value, errors);
+        if let Some(sink) = deserialize(name, "sink", id, value, errors) {
+            insert(&mut builder.sinks, "sink", key, sink, errors);
+        }
+    }
+}
+
+/// Substitutes the references to the parameters in the strings of a value.
+fn substitute(
+    value: &mut toml::Value,
+    params: &IndexMap<String, toml::Value>,
+    errors: &mut Vec<String>,
+) {
+    match value {
+        toml::Value::String(string) => {
+            let reference = PARAM_REFERENCE_REGEX
+                .captures(string)
+                .filter(|captures| captures[0].len() == string.len())
+                .map(|captures| captures[1].to_owned());
+            if let Some(param) = reference {
+                match params.get(&param) {
+                    Some(param) => *value = param.clone(),
+                    None => errors.push(format!("Unknown parameter \"{param}\".")),
+                }
+                return;
+            }
+
+            let substituted =
+                PARAM_REFERENCE_REGEX.replace_all(string, |captures: &Captures<'_>| {
+                    match params.get(&captures[1]) {
+                        Some(toml::Value::String(param)) => param.clone(),
+                        Some(
+                            param @ (toml::Value::Integer(_)
+                            | toml::Value::Float(_)
+                            | toml::Value::Boolean(_)),
+                        ) => param.to_string(),
+                        Some(_) => {
+                            errors.push(format!(
+                                "Parameter \"{}\" can't be substituted within a string, as it \
+                                 isn't a string, a number, or a boolean.",
+                                &captures[1]
+                            ));
+                            String::new()
+                        }
+                        None => {
+                            errors.push(format!("Unknown parameter \"{}\".", &captures[1]));
+                            String::new()
+                        }
+                    }
+                });
+            *string = substituted.into_owned();
+        }
+        toml::Value::Array(array) => {
+            for value in array {
+                substitute(value, params, errors);
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                substitute(value, params, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Prefixes the inputs of a component referring to the other components of its template with the
+/// name of the instance, keeping the output they refer to.
+fn rename_inputs(value: &mut toml::Value, instance: &str, local_ids: &HashSet<&str>) {
+    let Some(toml::Value::Array(inputs)) = value.get_mut("inputs") else {
+        return;
+    };
+    for input in inputs {
+        if let toml::Value::String(input) = input
+            && let Some(id) = input.split('.').next()
+            && local_ids.contains(id)
+        {
+            *input = format!("{instance}_{input}");
+        }
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(
+    instance: &str,
+    kind: &str,
+    id: &str,
+    value: toml::Value,
+    errors: &mut Vec<String>,
+) -> Option<T> {
+    value
+        .try_into()
+        .map_err(|error| {
+            errors.push(format!(
+                "Template instance \"{instance}\": {kind} \"{id}\": {error}"
+            ))
+        })
+        .ok()
+}
+
+fn insert<T>(
+    components: &mut IndexMap<ComponentKey, T>,
+    kind: &str,
+    key: ComponentKey,
+    component: T,
+    errors: &mut Vec<String>,
+) {
+    if components.contains_key(&key) {
+        errors.push(format!("duplicate {kind} id found: {key}"));
+    } else {
+        components.insert(key, component);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_instances() {
+        let mut builder = ConfigBuilder::from_toml(
+            r#"
+            [sources.in]
+            type = "test_basic"
+
+            [templates.pipeline.params.inputs]
+            [templates.pipeline.params.suffix]
+            default = "default"
+
+            [templates.pipeline.transforms.first]
+            type = "test_basic"
+            inputs = "@{inputs}"
+            suffix = "@{instance}-@{suffix}"
+            increase = 1.0
+
+            [templates.pipeline.transforms.second]
+            type = "test_basic"
+            inputs = ["first"]
+            suffix = "second"
+            increase = 1.0
+
+            [templates.pipeline.sinks.out]
+            type = "test_basic"
+            inputs = ["second", "in"]
+
+            [instances.a]
+            template = "pipeline"
+            params.inputs = ["in"]
+            params.suffix = "custom"
+
+            [instances.b]
+            template = "pipeline"
+            params.inputs = ["a_second"]
+            "#,
+        );
+        expand(&mut builder).unwrap();
+
+        assert!(builder.templates.is_empty() && builder.instances.is_empty());
+        assert_eq!(
+            builder
+                .transforms
+                .keys()
+                .map(|key| key.id())
+                .collect::<Vec<_>>(),
+            ["a_first", "a_second", "b_first", "b_second"]
+        );
+        assert_eq!(
+            builder.sinks.keys().map(|key| key.id()).collect::<Vec<_>>(),
+            ["a_out", "b_out"]
+        );
+        let inputs = |key: &str| &builder.transforms[&ComponentKey::from(key)].inputs;
+        assert_eq!(*inputs("a_first"), vec!["in"]);
+        assert_eq!(*inputs("a_second"), vec!["a_first"]);
+        assert_eq!(*inputs("b_first"), vec!["a_second"]);
+        assert_eq!(
+            builder.sinks[&ComponentKey::from("b_out")].inputs,
+            vec!["b_second", "in"]
+        );
+
+        let config = builder.build().unwrap();
+        assert_eq!(config.transforms().count(), 4);
+    }
+
+    #[test]
+    fn reports_invalid_instances() {
+        let mut builder = ConfigBuilder::from_toml(
+            r#"
+            [templates.pipeline.params.required]
+
+            [templates.pipeline.sources.in]
+            type = "test_basic"
+            data = "@{unknown}"
+
+            [instances.a]
+            template = "pipeline"
+            params.other = "value"
+
+            [instances.b]
+            template = "missing"
+            "#,
+        );
+
+        assert_eq!(
+            expand(&mut builder).unwrap_err(),
+            [
+                "Template instance \"a\" sets unknown parameter \"other\" of template \
+                 \"pipeline\".",
+                "Template instance \"a\" doesn't set required parameter \"required\" of \
+                 template \"pipeline\".",
+                "Template instance \"a\": source \"in\": Unknown parameter \"unknown\".",
+                "Template instance \"b\" refers to unknown template \"missing\".",
+            ]
+        );
+    }
+}
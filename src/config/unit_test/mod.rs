@@ -30,15 +30,14 @@ use vrl::{
 };
 
 pub use self::unit_test_components::{
-    UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkResult, UnitTestSourceConfig,
-    UnitTestStreamSinkConfig, UnitTestStreamSourceConfig,
+    UnitTestAssertion, UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkResult,
+    UnitTestSourceConfig, UnitTestStreamSinkConfig, UnitTestStreamSourceConfig,
 };
 use super::{OutputId, compiler::expand_globs, graph::Graph, transform::get_transform_output_ids};
 use crate::{
-    conditions::Condition,
     config::{
         self, ComponentKey, Config, ConfigBuilder, ConfigPath, SinkOuter, SourceOuter,
-        TestDefinition, TestInput, TestOutput, loading,
+        TestDefinition, TestInput, TestOutput, TestSnapshot, loading,
     },
     event::{Event, EventMetadata, LogEvent},
     signal,
@@ -97,6 +96,7 @@ fn init_log_schema_from_paths(
 
 pub async fn build_unit_tests_main(
     paths: &[ConfigPath],
+    update_snapshots: bool,
     signal_handler: &mut signal::SignalHandler,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
     init_log_schema_from_paths(paths, false)?;
@@ -111,11 +111,18 @@ pub async fn build_unit_tests_main(
         loading::load_builder_from_paths(paths)?
     };
 
-    build_unit_tests(config_builder).await
+    build_unit_tests_with_snapshots(config_builder, update_snapshots).await
 }
 
-pub async fn build_unit_tests(
+pub async fn build_unit_tests(config_builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<String>> {
+    build_unit_tests_with_snapshots(config_builder, false).await
+}
+
+/// Builds the unit tests, whose snapshots are overwritten with the events of their outputs instead
+/// of being compared to them if `update_snapshots` is set.
+pub async fn build_unit_tests_with_snapshots(
     mut config_builder: ConfigBuilder,
+    update_snapshots: bool,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
     // Sanitize config by removing existing sources and sinks
     config_builder.sources = Default::default();
@@ -124,7 +131,8 @@ pub async fn build_unit_tests(
     let test_definitions = std::mem::take(&mut config_builder.tests);
     let mut tests = Vec::new();
     let mut build_errors = Vec::new();
-    let metadata = UnitTestBuildMetadata::initialize(&mut config_builder)?;
+    let mut metadata = UnitTestBuildMetadata::initialize(&mut config_builder)?;
+    metadata.update_snapshots = update_snapshots;
 
     for mut test_definition in test_definitions {
         let test_name = test_definition.name.clone();
@@ -162,6 +170,8 @@ pub struct UnitTestBuildMetadata {
     template_sources: IndexMap<ComponentKey, UnitTestSourceConfig>,
     // A mapping from transform name to unit test sink name.
     sink_ids: HashMap<OutputId, String>,
+    // Whether the snapshots of the test outputs are overwritten instead of compared.
+    update_snapshots: bool,
 }
 
 impl UnitTestBuildMetadata {
@@ -225,6 +235,7 @@ impl UnitTestBuildMetadata {
             source_ids,
             template_sources,
             sink_ids,
+            update_snapshots: false,
         })
     }
 
@@ -285,7 +296,7 @@ impl UnitTestBuildMetadata {
         let mut template_sinks = IndexMap::new();
         let mut test_result_rxs = Vec::new();
         // Add sinks with checks
-        for (ids, checks) in outputs {
+        for (ids, (checks, snapshots)) in outputs {
             let (tx, rx) = oneshot::channel();
             let sink_ids = ids.clone();
             let sink_config = UnitTestSinkConfig {
@@ -293,6 +304,8 @@ impl UnitTestBuildMetadata {
                 transform_ids: ids.iter().map(|id| id.to_string()).collect(),
                 result_tx: Arc::new(Mutex::new(Some(tx))),
                 check: UnitTestSinkCheck::Checks(checks),
+                snapshots,
+                update_snapshots: self.update_snapshots,
             };
 
             test_result_rxs.push(rx);
@@ -307,6 +320,7 @@ impl UnitTestBuildMetadata {
                 transform_ids: vec![id.to_string()],
                 result_tx: Arc::new(Mutex::new(Some(tx))),
                 check: UnitTestSinkCheck::NoOutputs,
+                ..Default::default()
             };
 
             test_result_rxs.push(rx);
@@ -550,10 +564,12 @@ fn build_and_validate_inputs(
     }
 }
 
+type OutputChecks = (Vec<Vec<UnitTestAssertion>>, Vec<TestSnapshot>);
+
 fn build_outputs(
     test_outputs: &[TestOutput],
-) -> Result<IndexMap<Vec<OutputId>, Vec<Vec<Condition>>>, Vec<String>> {
-    let mut outputs: IndexMap<Vec<OutputId>, Vec<Vec<Condition>>> = IndexMap::new();
+) -> Result<IndexMap<Vec<OutputId>, OutputChecks>, Vec<String>> {
+    let mut outputs: IndexMap<Vec<OutputId>, OutputChecks> = IndexMap::new();
     let mut errors = Vec::new();
 
     for output in test_outputs {
@@ -566,17 +582,27 @@ fn build_outputs(
             .enumerate()
         {
             match condition.build(&Default::default()) {
-                Ok(condition) => conditions.push(condition),
+                Ok(condition) => conditions.push(UnitTestAssertion::Condition(index, condition)),
                 Err(error) => errors.push(format!(
                     "failed to create test condition '{index}': {error}"
                 )),
             }
         }
+        conditions.extend(
+            output
+                .metrics
+                .iter()
+                .flatten()
+                .cloned()
+                .enumerate()
+                .map(|(index, metric)| UnitTestAssertion::Metric(index, metric)),
+        );
 
-        outputs
+        let (checks, snapshots) = outputs
             .entry(output.extract_from.clone().to_vec())
-            .and_modify(|existing_conditions| existing_conditions.push(conditions.clone()))
-            .or_insert(vec![conditions.clone()]);
+            .or_default();
+        checks.push(conditions);
+        snapshots.extend(output.snapshot.clone());
     }
 
     if errors.is_empty() {
@@ -886,6 +886,148 @@ async fn test_metric_input() {
     assert!(tests.remove(0).run().await.errors.is_empty());
 }
 
+#[tokio::test]
+async fn test_metric_outputs() {
+    crate::test_util::trace_init();
+
+    let config = |expected_value: f64| -> ConfigBuilder {
+        toml::from_str(&format!(
+            indoc! { r#"
+              [transforms.foo]
+                inputs = ["ignored"]
+                type = "remap"
+                reroute_dropped = true
+                source = '''
+                if .name == "dropped" {{
+                  abort
+                }}
+                .tags.env = "prod"
+                '''
+
+              [[tests]]
+                name = "metric outputs"
+                no_outputs_from = ["foo.dropped"]
+
+                [[tests.inputs]]
+                  insert_at = "foo"
+                  type = "metric"
+                  [tests.inputs.metric]
+                    kind = "incremental"
+                    name = "kept"
+                    tags = {{ host = "a" }}
+                    counter = {{ value = 1.0 }}
+
+                [[tests.outputs]]
+                  extract_from = "foo"
+                  [[tests.outputs.metrics]]
+                    kind = "incremental"
+                    name = "kept"
+                    tags = {{ env = "prod" }}
+                    counter = {{ value = {:?} }}
+
+              [[tests]]
+                name = "dropped metric outputs"
+
+                [[tests.inputs]]
+                  insert_at = "foo"
+                  type = "metric"
+                  [tests.inputs.metric]
+                    kind = "absolute"
+                    name = "dropped"
+                    gauge = {{ value = 2.0 }}
+
+                [[tests.outputs]]
+                  extract_from = "foo.dropped"
+                  [[tests.outputs.metrics]]
+                    kind = "absolute"
+                    name = "dropped"
+                    gauge = {{ value = 2.0 }}
+          "#},
+            expected_value
+        ))
+        .unwrap()
+    };
+
+    let mut tests = build_unit_tests(config(1.0)).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+    assert!(tests.remove(0).run().await.errors.is_empty());
+
+    let mut tests = build_unit_tests(config(2.0)).await.unwrap();
+    let errors = tests.remove(0).run().await.errors;
+    assert!(
+        errors.iter().any(|error| error.contains(
+            "metric[0]: metric does not match: value is Counter { value: 1.0 } instead of \
+             Counter { value: 2.0 }"
+        )),
+        "{errors:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_snapshot_outputs() {
+    crate::test_util::trace_init();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshots").join("foo.json");
+    let config = |suffix: &str| -> ConfigBuilder {
+        toml::from_str(&format!(
+            indoc! { r#"
+              [transforms.foo]
+                inputs = ["ignored"]
+                type = "remap"
+                source = '''
+                .message = string!(.message) + "{}"
+                '''
+
+              [[tests]]
+                name = "snapshot outputs"
+
+                [[tests.inputs]]
+                  insert_at = "foo"
+                  type = "raw"
+                  value = "hello"
+
+                [[tests.outputs]]
+                  extract_from = "foo"
+                  snapshot.path = {:?}
+                  snapshot.ignore_fields = ["timestamp"]
+          "#},
+            suffix, path
+        ))
+        .unwrap()
+    };
+
+    // The snapshot must be created before the events are compared to it.
+    let mut tests = build_unit_tests(config(" world")).await.unwrap();
+    let errors = tests.remove(0).run().await.errors;
+    assert!(
+        errors
+            .iter()
+            .any(|error| error.contains("could not read the snapshot")),
+        "{errors:?}"
+    );
+
+    let mut tests = build_unit_tests_with_snapshots(config(" world"), true)
+        .await
+        .unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(snapshot, serde_json::json!([{"message": "hello world"}]));
+
+    let mut tests = build_unit_tests(config(" world")).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+
+    let mut tests = build_unit_tests(config(" there")).await.unwrap();
+    let errors = tests.remove(0).run().await.errors;
+    assert!(
+        errors.iter().any(|error| error.contains(
+            r#"event[0] is {"message":"hello there"} instead of {"message":"hello world"}"#
+        )),
+        "{errors:?}"
+    );
+}
+
 #[tokio::test]
 async fn test_success_over_gap() {
     crate::test_util::trace_init();
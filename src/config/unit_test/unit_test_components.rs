@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{fs, sync::Arc};
 
 use futures::{Sink, Stream, stream};
 use futures_util::{FutureExt, StreamExt, future, stream::BoxStream};
@@ -6,7 +6,7 @@ use tokio::sync::{Mutex, oneshot};
 use vector_lib::{
     config::{DataType, Input, LogNamespace},
     configurable::configurable_component,
-    event::Event,
+    event::{Event, Metric},
     schema,
     sink::{StreamSink, VectorSink},
 };
@@ -15,6 +15,7 @@ use crate::{
     conditions::Condition,
     config::{
         AcknowledgementsConfig, SinkConfig, SinkContext, SourceConfig, SourceContext, SourceOutput,
+        TestSnapshot,
     },
     sinks::Healthcheck,
     sources,
@@ -114,10 +115,107 @@ impl SourceConfig for UnitTestStreamSourceConfig {
     }
 }
 
+/// An assertion on the events received by a unit test sink, which passes if any of them satisfies
+/// it.
+#[derive(Clone)]
+pub enum UnitTestAssertion {
+    /// A condition matching the event, with its index in the conditions of its output.
+    Condition(usize, Condition),
+
+    /// A metric the event must match, with its index in the metrics of its output.
+    Metric(usize, Metric),
+}
+
+impl UnitTestAssertion {
+    fn check_with_context(&self, event: &Event) -> Result<(), String> {
+        match self {
+            Self::Condition(_, condition) => condition.check_with_context(event.clone()).0,
+            Self::Metric(_, expected) => check_metric(expected, event),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Self::Condition(index, _) => format!("condition[{index}]"),
+            Self::Metric(index, _) => format!("metric[{index}]"),
+        }
+    }
+}
+
+/// Checks that an event is a metric matching the expected one.
+///
+/// The metric can have more tags than the expected one, and its timestamp and interval are only
+/// compared when they are set on the expected metric.
+fn check_metric(expected: &Metric, event: &Event) -> Result<(), String> {
+    let Event::Metric(metric) = event else {
+        return Err("event is not a metric type".to_string());
+    };
+
+    let mut mismatches = Vec::new();
+    if metric.name() != expected.name() {
+        mismatches.push(format!(
+            "name is {:?} instead of {:?}",
+            metric.name(),
+            expected.name()
+        ));
+    }
+    if metric.namespace() != expected.namespace() {
+        mismatches.push(format!(
+            "namespace is {:?} instead of {:?}",
+            metric.namespace(),
+            expected.namespace()
+        ));
+    }
+    if metric.kind() != expected.kind() {
+        mismatches.push(format!(
+            "kind is {:?} instead of {:?}",
+            metric.kind(),
+            expected.kind()
+        ));
+    }
+    if metric.value() != expected.value() {
+        mismatches.push(format!(
+            "value is {:?} instead of {:?}",
+            metric.value(),
+            expected.value()
+        ));
+    }
+    for (key, value) in expected
+        .tags()
+        .into_iter()
+        .flat_map(|tags| tags.iter_single())
+    {
+        let actual = metric.tag_value(key);
+        if actual.as_deref() != Some(value) {
+            mismatches.push(format!("tag {key:?} is {actual:?} instead of {value:?}"));
+        }
+    }
+    if expected.timestamp().is_some() && metric.timestamp() != expected.timestamp() {
+        mismatches.push(format!(
+            "timestamp is {:?} instead of {:?}",
+            metric.timestamp(),
+            expected.timestamp()
+        ));
+    }
+    if expected.interval_ms().is_some() && metric.interval_ms() != expected.interval_ms() {
+        mismatches.push(format!(
+            "interval_ms is {:?} instead of {:?}",
+            metric.interval_ms(),
+            expected.interval_ms()
+        ));
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("metric does not match: {}", mismatches.join(", ")))
+    }
+}
+
 #[derive(Clone, Default)]
 pub enum UnitTestSinkCheck {
-    /// Check all events that are received against the list of conditions.
-    Checks(Vec<Vec<Condition>>),
+    /// Check all events that are received against the list of assertions.
+    Checks(Vec<Vec<UnitTestAssertion>>),
 
     /// Check that no events were received.
     NoOutputs,
@@ -152,6 +250,14 @@ pub struct UnitTestSinkConfig {
     #[serde(skip)]
     #[derivative(Debug = "ignore")]
     pub check: UnitTestSinkCheck,
+
+    /// Snapshots the received events are compared to.
+    #[serde(skip)]
+    pub snapshots: Vec<TestSnapshot>,
+
+    /// Whether the snapshots are overwritten with the received events instead of being compared.
+    #[serde(skip)]
+    pub update_snapshots: bool,
 }
 
 impl_generate_config_from_default!(UnitTestSinkConfig);
@@ -166,6 +272,8 @@ impl SinkConfig for UnitTestSinkConfig {
             transform_ids: self.transform_ids.clone(),
             result_tx: tx,
             check: self.check.clone(),
+            snapshots: self.snapshots.clone(),
+            update_snapshots: self.update_snapshots,
         };
         let healthcheck = future::ok(()).boxed();
 
@@ -187,6 +295,8 @@ pub struct UnitTestSink {
     // None for NoOp test sinks
     pub result_tx: Option<oneshot::Sender<UnitTestSinkResult>>,
     pub check: UnitTestSinkCheck,
+    pub snapshots: Vec<TestSnapshot>,
+    pub update_snapshots: bool,
 }
 
 #[async_trait::async_trait]
@@ -211,16 +321,17 @@ impl StreamSink<Event> for UnitTestSink {
                 } else {
                     for (i, check) in checks.iter().enumerate() {
                         let mut check_errors = Vec::new();
-                        for (j, condition) in check.iter().enumerate() {
+                        for assertion in check.iter() {
                             let mut condition_errors = Vec::new();
                             for event in output_events.iter() {
-                                match condition.check_with_context(event.clone()).0 {
+                                match assertion.check_with_context(event) {
                                     Ok(_) => {
                                         condition_errors.clear();
                                         break;
                                     }
                                     Err(error) => {
-                                        condition_errors.push(format!("  condition[{j}]: {error}"));
+                                        condition_errors
+                                            .push(format!("  {}: {error}", assertion.name()));
                                     }
                                 }
                             }
@@ -240,6 +351,17 @@ impl StreamSink<Event> for UnitTestSink {
                         result.test_errors.extend(check_errors);
                     }
 
+                    for snapshot in &self.snapshots {
+                        if let Err(error) =
+                            check_snapshot(snapshot, &output_events, self.update_snapshots)
+                        {
+                            result.test_errors.push(format!(
+                                "snapshot {:?} for transforms {:?} failed: {error}",
+                                snapshot.path, self.transform_ids
+                            ));
+                        }
+                    }
+
                     // If there are errors, add a summary of events received
                     if !result.test_errors.is_empty() {
                         result.test_errors.push(format!(
@@ -270,6 +392,99 @@ impl StreamSink<Event> for UnitTestSink {
     }
 }
 
+/// Compares the events to a snapshot, or overwrites the snapshot with them if `update` is set.
+fn check_snapshot(snapshot: &TestSnapshot, events: &[Event], update: bool) -> Result<(), String> {
+    let without_ignored_fields = |mut value: serde_json::Value| {
+        for field in &snapshot.ignore_fields {
+            remove_json_field(&mut value, field);
+        }
+        value
+    };
+    let actual = events
+        .iter()
+        .map(|event| without_ignored_fields(event_to_json(event)))
+        .collect::<Vec<_>>();
+
+    if update {
+        let mut content =
+            serde_json::to_string_pretty(&actual).map_err(|error| error.to_string())?;
+        content.push('\n');
+        if let Some(dir) = snapshot
+            .path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            fs::create_dir_all(dir)
+                .map_err(|error| format!("could not create the snapshot directory: {error}"))?;
+        }
+        fs::write(&snapshot.path, content)
+            .map_err(|error| format!("could not write the snapshot: {error}"))?;
+        info!(message = "Updated unit test snapshot.", path = ?snapshot.path);
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&snapshot.path).map_err(|error| {
+        format!("could not read the snapshot: {error}. Run with `--update-snapshots` to create it.")
+    })?;
+    let expected = serde_json::from_str::<Vec<serde_json::Value>>(&content)
+        .map_err(|error| format!("the snapshot is not a JSON array of events: {error}"))?
+        .into_iter()
+        .map(without_ignored_fields)
+        .collect::<Vec<_>>();
+
+    if actual == expected {
+        return Ok(());
+    }
+    let mut mismatches = Vec::new();
+    if actual.len() != expected.len() {
+        mismatches.push(format!(
+            "  received {} events instead of {}",
+            actual.len(),
+            expected.len()
+        ));
+    }
+    for (index, (actual, expected)) in actual.iter().zip(&expected).enumerate() {
+        if actual != expected {
+            mismatches.push(format!(
+                "  event[{index}] is {actual} instead of {expected}"
+            ));
+        }
+    }
+    Err(format!(
+        "events do not match the snapshot. Run with `--update-snapshots` to update it if the \
+         changes are expected.\n{}",
+        mismatches.join("\n")
+    ))
+}
+
+fn event_to_json(event: &Event) -> serde_json::Value {
+    match event {
+        Event::Log(log) => serde_json::to_value(log),
+        Event::Metric(metric) => serde_json::to_value(metric),
+        Event::Trace(trace) => serde_json::to_value(trace),
+    }
+    .unwrap_or_else(|_| serde_json::Value::Object(Default::default()))
+}
+
+/// Removes a field, given as a dot-separated path, from a JSON value.
+fn remove_json_field(value: &mut serde_json::Value, path: &str) {
+    let mut segments = path.trim_start_matches('.').split('.').peekable();
+    let mut current = value;
+    while let Some(segment) = segments.next() {
+        let Some(object) = current.as_object_mut() else {
+            return;
+        };
+        if segments.peek().is_none() {
+            object.remove(segment);
+            return;
+        }
+        match object.get_mut(segment) {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+}
+
 /// Configuration for the `unit_test_stream` sink.
 #[configurable_component(sink("unit_test_stream", "Unit test stream."))]
 #[derive(Clone, Default)]
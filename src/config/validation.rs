@@ -204,9 +204,10 @@ pub async fn check_buffer_preconditions(config: &Config) -> Result<(), Vec<Strin
     // Notably, this does *not* cover other data usage by Vector on the same mountpoint because we
     // don't always know the upper bound of that usage i.e. file checkpoint state.
 
-    // Grab all configured disk buffers, and if none are present, simply return early.
+    // Grab all configured disk buffers, including the write-ahead logs of the sources, and if none
+    // are present, simply return early.
     let global_data_dir = config.global.data_dir.clone();
-    let configured_disk_buffers = config
+    let mut configured_disk_buffers = config
         .sinks()
         .flat_map(|(id, sink)| {
             sink.buffer
@@ -215,6 +216,23 @@ pub async fn check_buffer_preconditions(config: &Config) -> Result<(), Vec<Strin
                 .filter_map(|stage| stage.disk_usage(global_data_dir.clone(), id))
         })
         .collect::<Vec<_>>();
+    for (id, source) in config.sources() {
+        if let Some(wal) = &source.wal {
+            configured_disk_buffers.extend(
+                source
+                    .inner
+                    .outputs(config.schema.log_namespace())
+                    .into_iter()
+                    .filter_map(|output| {
+                        let output = OutputId {
+                            component: id.clone(),
+                            port: output.port,
+                        };
+                        wal.disk_usage(global_data_dir.clone(), &output)
+                    }),
+            );
+        }
+    }
 
     if configured_disk_buffers.is_empty() {
         return Ok(());
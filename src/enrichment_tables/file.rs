@@ -1,5 +1,12 @@
 //! Handles enrichment tables for `type = file`.
-use std::{collections::HashMap, fs, hash::Hasher, path::PathBuf, time::SystemTime};
+use std::{
+    collections::HashMap,
+    fs,
+    hash::Hasher,
+    io::Read,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 use bytes::Bytes;
 use tracing::trace;
@@ -13,6 +20,10 @@ use vrl::value::{ObjectMap, Value};
 
 use crate::config::EnrichmentTableConfig;
 
+mod remote;
+
+pub use remote::HttpSettings;
+
 /// File encoding configuration.
 #[configurable_component]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -49,14 +60,24 @@ impl Default for Encoding {
 
 /// File-specific settings.
 #[configurable_component]
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default)]
 pub struct FileSettings {
     /// The path of the enrichment table file.
     ///
     /// Currently, only [CSV][csv] files are supported.
     ///
+    /// Exactly one of `path` or `http` must be set.
+    ///
     /// [csv]: https://en.wikipedia.org/wiki/Comma-separated_values
-    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    /// Fetches the enrichment table from an HTTP endpoint, and refreshes it periodically.
+    ///
+    /// Exactly one of `path` or `http` must be set.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpSettings>,
 
     /// File encoding configuration.
     #[configurable(derived)]
@@ -65,7 +86,7 @@ pub struct FileSettings {
 
 /// Configuration for the `file` enrichment table.
 #[configurable_component(enrichment_table("file"))]
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default)]
 pub struct FileConfig {
     /// File-specific settings.
     #[configurable(derived)]
@@ -181,6 +202,36 @@ impl FileConfig {
 
     /// Load the configured file into memory. Required to create a new file enrichment table.
     pub fn load_file(&self, timezone: TimeZone) -> crate::Result<FileData> {
+        let path = self.path()?;
+        let file = fs::File::open(path)?;
+        let modified = file.metadata()?.modified()?;
+        let data = self.read_data(timezone, file, modified)?;
+
+        trace!(
+            "Loaded enrichment file {} with headers {:?}.",
+            path.to_str().unwrap_or("path with invalid utf"),
+            data.headers
+        );
+
+        Ok(data)
+    }
+
+    /// Returns the configured path, if the table isn't fetched over HTTP.
+    fn path(&self) -> crate::Result<&Path> {
+        match (&self.file.path, &self.file.http) {
+            (Some(path), None) => Ok(path),
+            (None, Some(_)) => Err("the table is fetched over HTTP, not from `file.path`".into()),
+            _ => Err("exactly one of `file.path` or `file.http` must be set".into()),
+        }
+    }
+
+    /// Parses the table data from the reader, according to the configured encoding.
+    fn read_data(
+        &self,
+        timezone: TimeZone,
+        data: impl Read,
+        modified: SystemTime,
+    ) -> crate::Result<FileData> {
         let Encoding::Csv {
             include_headers,
             delimiter,
@@ -189,7 +240,7 @@ impl FileConfig {
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(include_headers)
             .delimiter(delimiter as u8)
-            .from_path(&self.file.path)?;
+            .from_reader(data);
 
         let first_row = reader.records().next();
         let headers = if include_headers {
@@ -219,18 +270,10 @@ impl FileConfig {
             })
             .collect::<crate::Result<Vec<_>>>()?;
 
-        trace!(
-            "Loaded enrichment file {} with headers {:?}.",
-            self.file.path.to_str().unwrap_or("path with invalid utf"),
-            headers
-        );
-
-        let file = reader.into_inner();
-
         Ok(FileData {
             headers,
             data,
-            modified: file.metadata()?.modified()?,
+            modified,
         })
     }
 }
@@ -240,6 +283,11 @@ impl EnrichmentTableConfig for FileConfig {
         &self,
         globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        if let (None, Some(http)) = (&self.file.path, &self.file.http) {
+            let table = remote::HttpFile::new(self.clone(), http, globals).await?;
+            return Ok(Box::new(table));
+        }
+
         Ok(Box::new(File::new(
             self.clone(),
             self.load_file(globals.timezone())?,
@@ -669,7 +717,10 @@ impl Table for File {
 
     /// Checks the modified timestamp of the data file to see if data has changed.
     fn needs_reload(&self) -> bool {
-        matches!(fs::metadata(&self.config.file.path)
+        let Some(path) = &self.config.file.path else {
+            return false;
+        };
+        matches!(fs::metadata(path)
             .and_then(|metadata| metadata.modified()),
             Ok(modified) if modified > self.last_modified)
     }
@@ -700,7 +751,8 @@ mod tests {
 
         let config = FileConfig {
             file: FileSettings {
-                path,
+                path: Some(path),
+                http: None,
                 encoding: Encoding::Csv {
                     include_headers: true,
                     delimiter: default_delimiter(),
@@ -729,7 +781,8 @@ mod tests {
 
         let config = FileConfig {
             file: FileSettings {
-                path,
+                path: Some(path),
+                http: None,
                 encoding: Encoding::Csv {
                     include_headers: false,
                     delimiter: default_delimiter(),
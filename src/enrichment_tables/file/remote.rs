@@ -0,0 +1,409 @@
+//! Fetches file enrichment tables from an HTTP endpoint, and refreshes them periodically.
+use std::{
+    num::NonZeroU64,
+    sync::{Arc, Mutex, RwLock, Weak},
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+use hyper::{
+    Body, Request, StatusCode,
+    header::{ETAG, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use tokio::time::{Instant, MissedTickBehavior};
+use vector_lib::{
+    TimeZone,
+    configurable::configurable_component,
+    enrichment::{Case, Condition, IndexHandle, Table},
+};
+use vrl::value::{ObjectMap, Value};
+
+use super::{File, FileConfig};
+use crate::{
+    config::GlobalOptions,
+    http::{Auth, HttpClient, MaybeAuth},
+    sinks::util::UriSerde,
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// Settings for fetching the enrichment table from an HTTP endpoint.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HttpSettings {
+    /// The URL the enrichment table is fetched from, with a `GET` request.
+    #[configurable(metadata(docs::examples = "https://example.com/lookups/users.csv"))]
+    pub url: UriSerde,
+
+    /// How often the enrichment table is refreshed, in seconds.
+    ///
+    /// Refreshes are conditional requests, based on the `ETag` and `Last-Modified` headers of the
+    /// previous response, so an unchanged table isn't downloaded and parsed again. The refreshed
+    /// table replaces the previous one at once, without reloading the configuration.
+    #[serde(default = "default_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Refresh Interval"))]
+    pub interval_secs: NonZeroU64,
+
+    /// The timeout for each request, in seconds.
+    #[serde(default = "default_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub timeout_secs: NonZeroU64,
+
+    #[configurable(derived)]
+    pub auth: Option<Auth>,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+}
+
+const fn default_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(300).unwrap()
+}
+
+const fn default_timeout_secs() -> NonZeroU64 {
+    NonZeroU64::new(10).unwrap()
+}
+
+/// Fetches the table with conditional requests.
+struct Fetcher {
+    client: HttpClient,
+    url: UriSerde,
+    auth: Option<Auth>,
+    timeout: Duration,
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+}
+
+impl Fetcher {
+    fn new(settings: &HttpSettings, globals: &GlobalOptions) -> crate::Result<Self> {
+        let url = settings.url.with_default_parts();
+        let auth = settings.auth.choose_one(&url.auth)?;
+        let tls = TlsSettings::from_options(settings.tls.as_ref())?;
+        Ok(Self {
+            client: HttpClient::new(tls, &globals.proxy)?,
+            url,
+            auth,
+            timeout: Duration::from_secs(settings.timeout_secs.get()),
+            etag: None,
+            last_modified: None,
+        })
+    }
+
+    /// Fetches the table, returning `None` if it didn't change since the previous fetch.
+    async fn fetch(&mut self) -> crate::Result<Option<Bytes>> {
+        let mut request = Request::get(self.url.uri.clone()).body(Body::empty())?;
+        let headers = request.headers_mut();
+        if let Some(etag) = &self.etag {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let timeout = self.timeout;
+        tokio::time::timeout(timeout, async {
+            let response = self.client.send(request).await?;
+            let status = response.status();
+            if status == StatusCode::NOT_MODIFIED {
+                return Ok(None);
+            }
+            if !status.is_success() {
+                return Err(format!("unexpected response status {status}").into());
+            }
+
+            let etag = response.headers().get(ETAG).cloned();
+            let last_modified = response.headers().get(LAST_MODIFIED).cloned();
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            self.etag = etag;
+            self.last_modified = last_modified;
+            Ok::<_, crate::Error>(Some(body))
+        })
+        .await
+        .map_err(|_| format!("request timed out after {timeout:?}"))?
+    }
+}
+
+struct Shared {
+    config: FileConfig,
+    timezone: TimeZone,
+    /// The current table, replaced as a whole when refreshed.
+    table: RwLock<Arc<File>>,
+    /// Serializes refreshes and added indexes, so indexes added during a refresh aren't lost.
+    update: Mutex<()>,
+}
+
+impl Shared {
+    fn current(&self) -> Arc<File> {
+        Arc::clone(&self.table.read().expect("lock poisoned"))
+    }
+
+    fn parse(&self, body: &[u8]) -> crate::Result<File> {
+        let data = self
+            .config
+            .read_data(self.timezone, body, SystemTime::now())?;
+        Ok(File::new(self.config.clone(), data))
+    }
+
+    /// Replaces the table with the refreshed data, indexed like the current table.
+    fn refresh(&self, body: &[u8]) -> crate::Result<()> {
+        let mut table = self.parse(body)?;
+
+        let _update = self.update.lock().expect("lock poisoned");
+        for (case, fields) in self.current().index_fields() {
+            let fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
+            table.add_index(case, &fields)?;
+        }
+        *self.table.write().expect("lock poisoned") = Arc::new(table);
+        Ok(())
+    }
+}
+
+/// A file enrichment table fetched from an HTTP endpoint. Clones share the same table, which is
+/// refreshed in the background for as long as any clone is alive.
+#[derive(Clone)]
+pub(super) struct HttpFile {
+    shared: Arc<Shared>,
+}
+
+impl HttpFile {
+    /// Fetches the table, and starts refreshing it in the background.
+    pub(super) async fn new(
+        config: FileConfig,
+        settings: &HttpSettings,
+        globals: &GlobalOptions,
+    ) -> crate::Result<Self> {
+        let mut fetcher = Fetcher::new(settings, globals)?;
+        let body = fetcher
+            .fetch()
+            .await?
+            .ok_or("the initial request returned `304 Not Modified`")?;
+
+        let timezone = globals.timezone();
+        let data = config.read_data(timezone, body.as_ref(), SystemTime::now())?;
+        let shared = Arc::new(Shared {
+            table: RwLock::new(Arc::new(File::new(config.clone(), data))),
+            config,
+            timezone,
+            update: Mutex::new(()),
+        });
+
+        let interval = Duration::from_secs(settings.interval_secs.get());
+        tokio::spawn(refresh(Arc::downgrade(&shared), fetcher, interval));
+
+        Ok(Self { shared })
+    }
+}
+
+async fn refresh(shared: Weak<Shared>, mut fetcher: Fetcher, period: Duration) {
+    let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        if shared.strong_count() == 0 {
+            break;
+        }
+
+        let result = match fetcher.fetch().await {
+            Ok(Some(body)) => match shared.upgrade() {
+                Some(shared) => shared.refresh(&body),
+                None => break,
+            },
+            Ok(None) => {
+                debug!(message = "Enrichment table not modified.", url = %fetcher.url.uri);
+                Ok(())
+            }
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            // The previously loaded table is still used.
+            error!(
+                message = "Unable to refresh enrichment table.",
+                url = %fetcher.url.uri,
+                %error,
+            );
+        }
+    }
+}
+
+impl Table for HttpFile {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<ObjectMap, String> {
+        self.shared
+            .current()
+            .find_table_row(case, condition, select, wildcard, index)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, String> {
+        self.shared
+            .current()
+            .find_table_rows(case, condition, select, wildcard, index)
+    }
+
+    /// Indexes are only ever appended, so the handles stay valid across refreshes.
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        let _update = self.shared.update.lock().expect("lock poisoned");
+        let mut table = self.shared.table.write().expect("lock poisoned");
+        Arc::make_mut(&mut table).add_index(case, fields)
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.shared.current().index_fields()
+    }
+
+    /// The table is refreshed in the background instead.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for HttpFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} fetched over HTTP", self.shared.current())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, net::SocketAddr};
+
+    use warp::{Filter, http::Response};
+
+    use super::*;
+    use crate::{
+        enrichment_tables::file::{Encoding, FileSettings},
+        test_util::next_addr,
+    };
+
+    /// Serves the versioned table, answering `304 Not Modified` when the `If-None-Match` header
+    /// matches its version.
+    fn mock_server(table: Arc<Mutex<(u32, &'static str)>>) -> SocketAddr {
+        let address = next_addr();
+
+        let route = warp::get()
+            .and(warp::path("users.csv"))
+            .and(warp::header::optional::<String>("if-none-match"))
+            .map(move |if_none_match: Option<String>| {
+                let (version, body) = *table.lock().unwrap();
+                let etag = format!("\"{version}\"");
+                let response = Response::builder().header("ETag", &etag);
+                if if_none_match.as_deref() == Some(etag.as_str()) {
+                    response.status(304).body(String::new()).unwrap()
+                } else {
+                    response.body(body.to_owned()).unwrap()
+                }
+            });
+
+        tokio::spawn(warp::serve(route).bind(address));
+        address
+    }
+
+    fn settings(address: SocketAddr) -> HttpSettings {
+        HttpSettings {
+            url: format!("http://{address}/users.csv").parse().unwrap(),
+            interval_secs: default_interval_secs(),
+            timeout_secs: default_timeout_secs(),
+            auth: None,
+            tls: None,
+        }
+    }
+
+    fn user(id: &str, name: &str) -> ObjectMap {
+        ObjectMap::from([
+            ("id".into(), Value::from(id)),
+            ("name".into(), Value::from(name)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn refreshes_table_and_keeps_indexes() {
+        let table = Arc::new(Mutex::new((1, "id,name\n1,alice\n2,bob\n")));
+        let settings = settings(mock_server(Arc::clone(&table)));
+        let globals = GlobalOptions::default();
+        let config = FileConfig {
+            file: FileSettings {
+                path: None,
+                http: Some(settings.clone()),
+                encoding: Encoding::default(),
+            },
+            schema: HashMap::new(),
+        };
+
+        let mut file = HttpFile::new(config, &settings, &globals).await.unwrap();
+        let handle = file.add_index(Case::Sensitive, &["id"]).unwrap();
+        let condition = [Condition::Equals {
+            field: "id",
+            value: Value::from("2"),
+        }];
+        assert_eq!(
+            Ok(user("2", "bob")),
+            file.find_table_row(Case::Sensitive, &condition, None, None, Some(handle))
+        );
+
+        let mut fetcher = Fetcher::new(&settings, &globals).unwrap();
+        assert!(fetcher.fetch().await.unwrap().is_some());
+        assert!(fetcher.fetch().await.unwrap().is_none());
+
+        *table.lock().unwrap() = (2, "id,name\n2,carol\n");
+        let body = fetcher.fetch().await.unwrap().unwrap();
+        file.shared.refresh(&body).unwrap();
+
+        assert_eq!(
+            vec![(Case::Sensitive, vec!["id".to_string()])],
+            file.index_fields()
+        );
+        assert_eq!(
+            Ok(user("2", "carol")),
+            file.find_table_row(Case::Sensitive, &condition, None, None, Some(handle))
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_table_when_refresh_is_invalid() {
+        let table = Arc::new(Mutex::new((1, "id,name\n1,alice\n")));
+        let settings = settings(mock_server(table));
+        let config = FileConfig {
+            file: FileSettings {
+                path: None,
+                http: Some(settings.clone()),
+                encoding: Encoding::default(),
+            },
+            schema: HashMap::new(),
+        };
+
+        let mut file = HttpFile::new(config, &settings, &GlobalOptions::default())
+            .await
+            .unwrap();
+        let handle = file.add_index(Case::Sensitive, &["id"]).unwrap();
+
+        // The indexed column is missing from the refreshed data.
+        assert!(file.shared.refresh(b"name\ncarol\n").is_err());
+
+        let condition = [Condition::Equals {
+            field: "id",
+            value: Value::from("1"),
+        }];
+        assert_eq!(
+            Ok(user("1", "alice")),
+            file.find_table_row(Case::Sensitive, &condition, None, None, Some(handle))
+        );
+    }
+}
@@ -4,7 +4,14 @@
 //!
 //! [maxmind]: https://dev.maxmind.com/geoip/geoip2/downloadable
 //! [geolite]: https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access
-use std::{collections::BTreeMap, fs, net::IpAddr, path::PathBuf, sync::Arc, time::SystemTime};
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use maxminddb::{
     Reader,
@@ -17,7 +24,14 @@ use vector_lib::{
 };
 use vrl::value::{ObjectMap, Value};
 
-use crate::config::{EnrichmentTableConfig, GenerateConfig};
+use crate::{
+    config::{EnrichmentTableConfig, GenerateConfig},
+    internal_events::GeoipDatabaseAge,
+};
+
+mod update;
+
+pub use update::AutoUpdateConfig;
 
 // MaxMind GeoIP database files have a type field we can use to recognize specific
 // products. If it is an unknown type, an error will be returned.
@@ -72,6 +86,13 @@ pub struct GeoipConfig {
     /// [locale_docs]: https://support.maxmind.com/hc/en-us/articles/4414877149467-IP-Geolocation-Data#h_01FRRGRYTGZB29ERDBZCX3MR8Q
     #[serde(default = "default_locale")]
     pub locale: String,
+
+    /// Automatically downloads the database from MaxMind, and keeps it up to date.
+    ///
+    /// The database is written to `path`, and downloaded at startup if it doesn't exist yet.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_update: Option<AutoUpdateConfig>,
 }
 
 fn default_locale() -> String {
@@ -91,6 +112,7 @@ impl GenerateConfig for GeoipConfig {
         toml::Value::try_from(Self {
             path: "/path/to/GeoLite2-City.mmdb".into(),
             locale: default_locale(),
+            auto_update: None,
         })
         .unwrap()
     }
@@ -99,25 +121,39 @@ impl GenerateConfig for GeoipConfig {
 impl EnrichmentTableConfig for GeoipConfig {
     async fn build(
         &self,
-        _: &crate::config::GlobalOptions,
+        globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
-        Ok(Box::new(Geoip::new(self.clone())?))
+        let Some(auto_update) = &self.auto_update else {
+            return Ok(Box::new(Geoip::new(self.clone())?));
+        };
+
+        let mut updater = update::Updater::new(auto_update, &self.path, globals)?;
+        if !self.path.exists() {
+            updater.update().await?;
+        }
+
+        let table = Geoip::new(self.clone())?;
+        emit!(GeoipDatabaseAge {
+            edition_id: &auto_update.edition_id,
+            age: table.database().age(),
+        });
+        tokio::spawn(updater.run(Arc::downgrade(&table.database)));
+
+        Ok(Box::new(table))
     }
 }
 
-#[derive(Clone)]
-/// A struct that implements [vector_lib::enrichment::Table] to handle loading enrichment data from a GeoIP database.
-pub struct Geoip {
-    config: GeoipConfig,
-    dbreader: Arc<maxminddb::Reader<Vec<u8>>>,
-    dbkind: DatabaseKind,
+/// A loaded GeoIP database.
+struct Database {
+    reader: Reader<Vec<u8>>,
+    kind: DatabaseKind,
     last_modified: SystemTime,
 }
 
-impl Geoip {
-    /// Creates a new GeoIP struct from the provided config.
-    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
-        let dbreader = Arc::new(Reader::open_readfile(&config.path)?);
+impl Database {
+    /// Opens the database, checking that it is supported.
+    fn open(path: &Path) -> crate::Result<Self> {
+        let dbreader = Reader::open_readfile(path)?;
         let dbkind =
             DatabaseKind::try_from(dbreader.metadata.database_type.as_str()).map_err(|_| {
                 format!(
@@ -136,17 +172,46 @@ impl Geoip {
         };
 
         match result {
-            Ok(_) => Ok(Geoip {
-                last_modified: fs::metadata(&config.path)?.modified()?,
-                dbreader,
-                dbkind,
-                config,
+            Ok(_) => Ok(Database {
+                last_modified: fs::metadata(path)?.modified()?,
+                reader: dbreader,
+                kind: dbkind,
             }),
             Err(error) => Err(error.into()),
         }
     }
 
+    /// The time elapsed since the database was built.
+    fn age(&self) -> Duration {
+        let built = UNIX_EPOCH + Duration::from_secs(self.reader.metadata.build_epoch);
+        SystemTime::now().duration_since(built).unwrap_or_default()
+    }
+}
+
+#[derive(Clone)]
+/// A struct that implements [vector_lib::enrichment::Table] to handle loading enrichment data from a GeoIP database.
+pub struct Geoip {
+    config: GeoipConfig,
+    /// The current database, replaced as a whole when it is updated.
+    database: Arc<RwLock<Arc<Database>>>,
+}
+
+impl Geoip {
+    /// Creates a new GeoIP struct from the provided config.
+    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
+        let database = Database::open(&config.path)?;
+        Ok(Geoip {
+            config,
+            database: Arc::new(RwLock::new(Arc::new(database))),
+        })
+    }
+
+    fn database(&self) -> Arc<Database> {
+        Arc::clone(&self.database.read().expect("lock poisoned"))
+    }
+
     fn lookup(&self, ip: IpAddr, select: Option<&[String]>) -> Option<ObjectMap> {
+        let database = self.database();
         let mut map = ObjectMap::new();
         let mut add_field = |key: &str, value: Option<Value>| {
             if select
@@ -163,9 +228,9 @@ impl Geoip {
             };
         }
 
-        match self.dbkind {
+        match database.kind {
             DatabaseKind::Asn | DatabaseKind::Isp => {
-                let data = self.dbreader.lookup::<Isp>(ip).ok()??;
+                let data = database.reader.lookup::<Isp>(ip).ok()??;
 
                 add_field!("autonomous_system_number", data.autonomous_system_number);
                 add_field!(
@@ -176,7 +241,7 @@ impl Geoip {
                 add_field!("organization", data.organization);
             }
             DatabaseKind::City => {
-                let data = self.dbreader.lookup::<City>(ip).ok()??;
+                let data = database.reader.lookup::<City>(ip).ok()??;
 
                 add_field!(
                     "city_name",
@@ -226,12 +291,12 @@ impl Geoip {
                 add_field!("postal_code", data.postal.and_then(|p| p.code));
             }
             DatabaseKind::ConnectionType => {
-                let data = self.dbreader.lookup::<ConnectionType>(ip).ok()??;
+                let data = database.reader.lookup::<ConnectionType>(ip).ok()??;
 
                 add_field!("connection_type", data.connection_type);
             }
             DatabaseKind::AnonymousIp => {
-                let data = self.dbreader.lookup::<AnonymousIp>(ip).ok()??;
+                let data = database.reader.lookup::<AnonymousIp>(ip).ok()??;
 
                 add_field!("is_anonymous", data.is_anonymous);
                 add_field!("is_anonymous_vpn", data.is_anonymous_vpn);
@@ -328,7 +393,7 @@ impl Table for Geoip {
     fn needs_reload(&self) -> bool {
         matches!(fs::metadata(&self.config.path)
             .and_then(|metadata| metadata.modified()),
-            Ok(modified) if modified > self.last_modified)
+            Ok(modified) if modified > self.database().last_modified)
     }
 }
 
@@ -475,6 +540,7 @@ mod tests {
         let result = Geoip::new(GeoipConfig {
             path: "tests/data/custom-type.mmdb".into(),
             locale: default_locale(),
+            auto_update: None,
         });
 
         assert!(result.is_err());
@@ -509,6 +575,7 @@ mod tests {
         Geoip::new(GeoipConfig {
             path: database.into(),
             locale: default_locale(),
+            auto_update: None,
         })
         .unwrap()
         .find_table_rows(
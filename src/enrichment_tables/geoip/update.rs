@@ -0,0 +1,421 @@
+//! Downloads GeoIP databases from MaxMind, and keeps them up to date.
+use std::{
+    ffi::OsString,
+    io::Read,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, Weak},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use hyper::{Body, Request, Uri, header::LOCATION};
+use tokio::time::{Instant, MissedTickBehavior};
+use vector_lib::{configurable::configurable_component, sensitive_string::SensitiveString};
+
+use super::Database;
+use crate::{
+    config::GlobalOptions,
+    http::{Auth, HttpClient},
+    internal_events::{GeoipDatabaseAge, GeoipDatabaseUpdateError, GeoipDatabaseUpdated},
+};
+
+/// The maximum number of redirects followed when downloading, as MaxMind redirects downloads to
+/// its storage.
+const MAX_REDIRECTS: usize = 5;
+
+/// Settings for automatically updating the database from MaxMind.
+#[configurable_component]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AutoUpdateConfig {
+    /// The MaxMind account ID.
+    #[configurable(metadata(docs::examples = "${MAXMIND_ACCOUNT_ID}"))]
+    #[configurable(metadata(docs::examples = "123456"))]
+    pub account_id: String,
+
+    /// The MaxMind license key.
+    #[configurable(metadata(docs::examples = "${MAXMIND_LICENSE_KEY}"))]
+    pub license_key: SensitiveString,
+
+    /// The edition ID of the database to download.
+    #[configurable(metadata(docs::examples = "GeoLite2-City"))]
+    #[configurable(metadata(docs::examples = "GeoIP2-ISP"))]
+    pub edition_id: String,
+
+    /// How often to check for a new version of the database, in seconds.
+    ///
+    /// The checksum of the latest version is compared to the one of the current database first,
+    /// so the database is only downloaded when it changed.
+    #[serde(default = "default_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Update Interval"))]
+    pub interval_secs: NonZeroU64,
+
+    /// The timeout for each download, in seconds.
+    #[serde(default = "default_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub timeout_secs: NonZeroU64,
+
+    /// The base URL of the MaxMind download service.
+    #[serde(default = "default_endpoint")]
+    #[configurable(metadata(docs::examples = "https://download.maxmind.com"))]
+    pub endpoint: String,
+}
+
+const fn default_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(24 * 60 * 60).unwrap()
+}
+
+const fn default_timeout_secs() -> NonZeroU64 {
+    NonZeroU64::new(300).unwrap()
+}
+
+fn default_endpoint() -> String {
+    "https://download.maxmind.com".to_string()
+}
+
+/// Appends the extension to the path, such as `.sha256` to `GeoLite2-City.mmdb`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+pub(super) struct Updater {
+    client: HttpClient,
+    auth: Auth,
+    url: String,
+    edition_id: String,
+    interval: Duration,
+    timeout: Duration,
+    path: PathBuf,
+    /// The checksum of the archive the current database was extracted from.
+    checksum: Option<String>,
+}
+
+impl Updater {
+    pub(super) fn new(
+        config: &AutoUpdateConfig,
+        path: &Path,
+        globals: &GlobalOptions,
+    ) -> crate::Result<Self> {
+        let url = format!(
+            "{}/geoip/databases/{}/download",
+            config.endpoint.trim_end_matches('/'),
+            config.edition_id
+        );
+        // Checks that the URL is valid.
+        url.parse::<Uri>()?;
+
+        // The checksum is only known if the database was downloaded by a previous run.
+        let checksum = std::fs::read_to_string(with_suffix(path, ".sha256"))
+            .ok()
+            .map(|checksum| checksum.trim().to_owned());
+
+        Ok(Self {
+            client: HttpClient::new(None, &globals.proxy)?,
+            auth: Auth::Basic {
+                user: config.account_id.clone(),
+                password: config.license_key.clone(),
+            },
+            url,
+            edition_id: config.edition_id.clone(),
+            interval: Duration::from_secs(config.interval_secs.get()),
+            timeout: Duration::from_secs(config.timeout_secs.get()),
+            path: path.to_owned(),
+            checksum,
+        })
+    }
+
+    /// Downloads the file with the given suffix, following redirects to the storage.
+    async fn download(&self, suffix: &str) -> crate::Result<Bytes> {
+        let mut uri = format!("{}?suffix={suffix}", self.url).parse::<Uri>()?;
+        let mut redirects = 0;
+        loop {
+            let mut request = Request::get(uri.clone()).body(Body::empty())?;
+            // The credentials are only sent to MaxMind, not to the storage it redirects to.
+            if redirects == 0 {
+                self.auth.apply(&mut request);
+            }
+
+            let response = self.client.send(request).await?;
+            let status = response.status();
+            if status.is_redirection() && redirects < MAX_REDIRECTS {
+                uri = response
+                    .headers()
+                    .get(LOCATION)
+                    .ok_or("redirect without a location")?
+                    .to_str()?
+                    .parse()?;
+                redirects += 1;
+                continue;
+            }
+            if !status.is_success() {
+                return Err(format!("unexpected response status {status}").into());
+            }
+            return Ok(hyper::body::to_bytes(response.into_body()).await?);
+        }
+    }
+
+    /// Downloads the latest version of the database if it changed, writing it to the path.
+    pub(super) async fn update(&mut self) -> crate::Result<Option<Database>> {
+        tokio::time::timeout(self.timeout, async {
+            // The checksum file has the form `<sha256>  <archive name>`.
+            let checksum = self.download("tar.gz.sha256").await?;
+            let checksum = std::str::from_utf8(&checksum)?
+                .split_whitespace()
+                .next()
+                .ok_or("empty checksum")?
+                .to_lowercase();
+            if self.checksum.as_ref() == Some(&checksum) {
+                return Ok(None);
+            }
+
+            let archive = self.download("tar.gz").await?;
+            let actual = hex_encode(&openssl::sha::sha256(&archive));
+            if actual != checksum {
+                return Err(format!("checksum mismatch, expected {checksum}, got {actual}").into());
+            }
+
+            let database = extract_database(&archive)?;
+            let tmp_path = with_suffix(&self.path, ".tmp");
+            tokio::fs::write(&tmp_path, database).await?;
+            let database = {
+                let tmp_path = tmp_path.clone();
+                tokio::task::spawn_blocking(move || Database::open(&tmp_path)).await??
+            };
+            tokio::fs::rename(&tmp_path, &self.path).await?;
+            tokio::fs::write(with_suffix(&self.path, ".sha256"), &checksum).await?;
+
+            self.checksum = Some(checksum);
+            emit!(GeoipDatabaseUpdated {
+                edition_id: &self.edition_id
+            });
+            Ok::<_, crate::Error>(Some(database))
+        })
+        .await
+        .map_err(|_| format!("update timed out after {:?}", self.timeout))?
+    }
+
+    /// Updates the database periodically, for as long as the table is alive.
+    pub(super) async fn run(mut self, database: Weak<RwLock<Arc<Database>>>) {
+        let mut interval = tokio::time::interval_at(Instant::now() + self.interval, self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if database.strong_count() == 0 {
+                break;
+            }
+
+            let result = self.update().await;
+            let Some(current) = database.upgrade() else {
+                break;
+            };
+            match result {
+                Ok(Some(updated)) => *current.write().expect("lock poisoned") = Arc::new(updated),
+                Ok(None) => {}
+                // The current database is still used.
+                Err(error) => emit!(GeoipDatabaseUpdateError {
+                    edition_id: &self.edition_id,
+                    error: &error,
+                }),
+            }
+
+            let age = current.read().expect("lock poisoned").age();
+            emit!(GeoipDatabaseAge {
+                edition_id: &self.edition_id,
+                age,
+            });
+        }
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Reads a NUL-terminated field of a tar header.
+fn header_field(field: &[u8]) -> &str {
+    let len = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    std::str::from_utf8(&field[..len])
+        .unwrap_or_default()
+        .trim()
+}
+
+/// Extracts the database from the `.tar.gz` archive MaxMind distributes it in.
+fn extract_database(archive: &[u8]) -> crate::Result<Vec<u8>> {
+    let mut tar = Vec::new();
+    GzDecoder::new(archive).read_to_end(&mut tar)?;
+
+    // Each entry is a 512-byte header followed by its content, padded to 512 bytes.
+    let mut offset = 0;
+    while let Some(header) = tar.get(offset..offset + 512) {
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let name = header_field(&header[..100]);
+        let size = usize::from_str_radix(header_field(&header[124..136]), 8)?;
+        let is_file = matches!(header[156], 0 | b'0');
+        let start = offset + 512;
+        let content = tar
+            .get(start..start + size)
+            .ok_or("truncated database archive")?;
+        if is_file && name.ends_with(".mmdb") {
+            return Ok(content.to_vec());
+        }
+
+        offset = start + size.div_ceil(512) * 512;
+    }
+
+    Err("no database found in the archive".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::Write,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    use flate2::{Compression, write::GzEncoder};
+    use vector_lib::enrichment::{Case, Condition, Table};
+    use vrl::value::Value;
+    use warp::{Filter, http::Response};
+
+    use super::*;
+    use crate::{
+        config::EnrichmentTableConfig, enrichment_tables::geoip::GeoipConfig, test_util::next_addr,
+    };
+
+    /// Packs the database in a `.tar.gz` archive, in a directory like MaxMind does.
+    fn archive(database: &str, license: &[u8]) -> Vec<u8> {
+        let content = std::fs::read(database).unwrap();
+
+        let mut tar = Vec::new();
+        for (name, content) in [
+            ("GeoIP2-City_20240101/LICENSE.txt", license),
+            ("GeoIP2-City_20240101/GeoIP2-City.mmdb", &content),
+        ] {
+            let mut header = [0; 512];
+            header[..name.len()].copy_from_slice(name.as_bytes());
+            let size = format!("{:011o}", content.len());
+            header[124..135].copy_from_slice(size.as_bytes());
+            header[156] = b'0';
+            tar.extend_from_slice(&header);
+            tar.extend_from_slice(content);
+            tar.resize(tar.len().div_ceil(512) * 512, 0);
+        }
+        tar.resize(tar.len() + 1024, 0);
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&tar).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Serves the archive as MaxMind does, redirecting to the storage.
+    fn mock_maxmind(archive: Arc<Mutex<Vec<u8>>>) -> SocketAddr {
+        let address = next_addr();
+
+        let download = warp::get()
+            .and(warp::path!(
+                "geoip" / "databases" / "GeoIP2-City" / "download"
+            ))
+            .and(warp::header::exact(
+                "Authorization",
+                "Basic MTIzNDU2OmxpY2Vuc2Uta2V5",
+            ))
+            .and(warp::query::<HashMap<String, String>>())
+            .map(move |query: HashMap<String, String>| {
+                let location = match query["suffix"].as_str() {
+                    "tar.gz" => "/storage/archive",
+                    _ => "/storage/checksum",
+                };
+                Response::builder()
+                    .status(302)
+                    .header("Location", format!("http://{address}{location}"))
+                    .body(Vec::new())
+                    .unwrap()
+            });
+        let storage = {
+            let archive = Arc::clone(&archive);
+            warp::get()
+                .and(warp::path!("storage" / "archive"))
+                .map(move || archive.lock().unwrap().clone())
+        };
+        let checksum = warp::get()
+            .and(warp::path!("storage" / "checksum"))
+            .map(move || {
+                let checksum = hex_encode(&openssl::sha::sha256(&archive.lock().unwrap()));
+                format!("{checksum}  GeoIP2-City_20240101.tar.gz\n")
+            });
+
+        tokio::spawn(warp::serve(download.or(storage).or(checksum)).bind(address));
+        address
+    }
+
+    fn config(address: SocketAddr, path: PathBuf) -> GeoipConfig {
+        GeoipConfig {
+            path,
+            locale: "en".to_string(),
+            auto_update: Some(AutoUpdateConfig {
+                account_id: "123456".to_string(),
+                license_key: "license-key".to_string().into(),
+                edition_id: "GeoIP2-City".to_string(),
+                interval_secs: default_interval_secs(),
+                timeout_secs: default_timeout_secs(),
+                endpoint: format!("http://{address}"),
+            }),
+        }
+    }
+
+    #[test]
+    fn extracts_database() {
+        let database =
+            extract_database(&archive("tests/data/GeoIP2-City-Test.mmdb", b"license")).unwrap();
+
+        assert_eq!(
+            std::fs::read("tests/data/GeoIP2-City-Test.mmdb").unwrap(),
+            database
+        );
+    }
+
+    #[tokio::test]
+    async fn downloads_database_when_changed() {
+        let database = "tests/data/GeoIP2-City-Test.mmdb";
+        let archive = Arc::new(Mutex::new(archive(database, b"license")));
+        let address = mock_maxmind(Arc::clone(&archive));
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(address, dir.path().join("GeoIP2-City.mmdb"));
+
+        // The database doesn't exist yet, so it is downloaded at startup.
+        let table = config.build(&GlobalOptions::default()).await.unwrap();
+        let row = table
+            .find_table_row(
+                Case::Insensitive,
+                &[Condition::Equals {
+                    field: "ip",
+                    value: "2.125.160.216".into(),
+                }],
+                Some(&["city_name".to_string()]),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(Some(&Value::from("Boxford")), row.get("city_name"));
+
+        let globals = GlobalOptions::default();
+        let auto_update = config.auto_update.as_ref().unwrap();
+        let mut updater = Updater::new(auto_update, &config.path, &globals).unwrap();
+        assert!(updater.update().await.unwrap().is_none());
+
+        *archive.lock().unwrap() = self::archive(database, b"updated license");
+        assert!(updater.update().await.unwrap().is_some());
+        assert!(updater.update().await.unwrap().is_none());
+    }
+}
@@ -20,12 +20,16 @@ pub mod geoip;
 #[cfg(feature = "enrichment-tables-mmdb")]
 pub mod mmdb;
 
+#[cfg(feature = "enrichment-tables-redis")]
+pub mod redis;
+
 /// Configuration options for an [enrichment table](https://vector.dev/docs/reference/glossary/#enrichment-tables) to be used in a
 /// [`remap`](https://vector.dev/docs/reference/configuration/transforms/remap/) transform. Currently supported are:
 ///
 /// * [CSV](https://en.wikipedia.org/wiki/Comma-separated_values) files
 /// * [MaxMind](https://www.maxmind.com/en/home) databases
 /// * In-memory storage
+/// * [Redis](https://redis.io/) keys
 ///
 /// For the lookup in the enrichment tables to be as performant as possible, the data is indexed according
 /// to the fields that are used in the search. Note that indices can only be created for fields for which an
@@ -65,13 +69,20 @@ pub enum EnrichmentTables {
     /// [maxmind]: https://www.maxmind.com/
     #[cfg(feature = "enrichment-tables-mmdb")]
     Mmdb(mmdb::MmdbConfig),
+
+    /// Exposes data looked up in [Redis][redis] as an enrichment table.
+    ///
+    /// [redis]: https://redis.io/
+    #[cfg(feature = "enrichment-tables-redis")]
+    Redis(redis::RedisConfig),
 }
 
 impl GenerateConfig for EnrichmentTables {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self::File(file::FileConfig {
             file: file::FileSettings {
-                path: "path/to/file".into(),
+                path: Some("path/to/file".into()),
+                http: None,
                 encoding: file::Encoding::default(),
             },
             schema: Default::default(),
@@ -84,13 +95,15 @@ impl EnrichmentTables {
     /// Gets the files to watch to trigger reload
     pub fn files_to_watch(&self) -> Vec<&PathBuf> {
         match self {
-            EnrichmentTables::File(file_config) => vec![&file_config.file.path],
+            EnrichmentTables::File(file_config) => file_config.file.path.iter().collect(),
             #[cfg(feature = "enrichment-tables-memory")]
             EnrichmentTables::Memory(_) => vec![],
             #[cfg(feature = "enrichment-tables-geoip")]
             EnrichmentTables::Geoip(geoip_config) => vec![&geoip_config.path],
             #[cfg(feature = "enrichment-tables-mmdb")]
             EnrichmentTables::Mmdb(mmdb_config) => vec![&mmdb_config.path],
+            #[cfg(feature = "enrichment-tables-redis")]
+            EnrichmentTables::Redis(_) => vec![],
         }
     }
 }
@@ -0,0 +1,428 @@
+//! Handles enrichment tables for `type = redis`.
+//! Records are looked up in Redis by key, and cached locally for a configurable TTL.
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use redis::{Client, Connection};
+use vector_lib::{
+    configurable::configurable_component,
+    enrichment::{Case, Condition, IndexHandle, Table},
+};
+use vrl::value::{KeyString, ObjectMap, Value};
+
+use crate::config::{EnrichmentTableConfig, GenerateConfig};
+
+/// The Redis data type of the looked up keys.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RedisDataType {
+    /// The keys hold hashes, retrieved with `HGETALL`.
+    ///
+    /// The fields of the hash are the fields of the record.
+    #[default]
+    Hash,
+
+    /// The keys hold strings, retrieved with `GET`.
+    ///
+    /// The record has a `key` field holding the looked up key, and a `value` field holding the
+    /// string.
+    String,
+}
+
+/// Configuration for the `redis` enrichment table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[configurable_component(enrichment_table("redis"))]
+pub struct RedisConfig {
+    /// The URL of the Redis endpoint to connect to.
+    ///
+    /// The URL _must_ take the form of `protocol://server:port/db` where the protocol can either be
+    /// `redis` or `rediss` for connections secured via TLS.
+    #[configurable(metadata(docs::examples = "redis://127.0.0.1:6379/0"))]
+    pub endpoint: String,
+
+    /// The prefix prepended to the looked up key, such as `users:`.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "users:"))]
+    pub key_prefix: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub data_type: RedisDataType,
+
+    /// How long looked up records are cached locally, in seconds.
+    ///
+    /// Keys that are not found are cached as well. Set to `0` to look up every key in Redis.
+    #[serde(default = "default_cache_ttl_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub cache_ttl_secs: u64,
+
+    /// The maximum number of records cached locally.
+    #[serde(default = "default_cache_max_entries")]
+    #[configurable(metadata(docs::type_unit = "records"))]
+    pub cache_max_entries: NonZeroUsize,
+
+    /// The maximum number of idle connections kept open to Redis.
+    ///
+    /// Lookups that miss the local cache reuse an idle connection, or open a new one.
+    #[serde(default = "default_max_idle_connections")]
+    #[configurable(metadata(docs::type_unit = "connections"))]
+    pub max_idle_connections: NonZeroUsize,
+
+    /// The timeout for connecting to Redis and for each lookup, in seconds.
+    ///
+    /// Lookups that miss the local cache block the `remap` transform until Redis responds.
+    #[serde(default = "default_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub timeout_secs: u64,
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    60
+}
+
+const fn default_cache_max_entries() -> NonZeroUsize {
+    NonZeroUsize::new(10_000).unwrap()
+}
+
+const fn default_max_idle_connections() -> NonZeroUsize {
+    NonZeroUsize::new(8).unwrap()
+}
+
+const fn default_timeout_secs() -> u64 {
+    1
+}
+
+impl GenerateConfig for RedisConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            endpoint: "redis://127.0.0.1:6379/0".into(),
+            key_prefix: String::new(),
+            data_type: RedisDataType::default(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+            cache_max_entries: default_cache_max_entries(),
+            max_idle_connections: default_max_idle_connections(),
+            timeout_secs: default_timeout_secs(),
+        })
+        .unwrap()
+    }
+}
+
+impl EnrichmentTableConfig for RedisConfig {
+    async fn build(
+        &self,
+        _: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Redis::new(self.clone())?))
+    }
+}
+
+/// A record fetched from Redis, `None` if the key wasn't found.
+struct CachedRecord {
+    fetched_at: Instant,
+    record: Option<ObjectMap>,
+}
+
+struct Inner {
+    config: RedisConfig,
+    client: Client,
+    connections: Mutex<Vec<Connection>>,
+    cache: Mutex<HashMap<String, CachedRecord>>,
+}
+
+/// A struct that implements [vector_lib::enrichment::Table] to handle looking up records in Redis.
+#[derive(Clone)]
+pub struct Redis {
+    inner: Arc<Inner>,
+}
+
+impl Redis {
+    /// Creates a new Redis struct from the provided config.
+    pub fn new(config: RedisConfig) -> crate::Result<Self> {
+        let client = Client::open(config.endpoint.as_str())?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                config,
+                client,
+                connections: Mutex::new(Vec::new()),
+                cache: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.inner.config.cache_ttl_secs)
+    }
+
+    fn cached(&self, key: &str, now: Instant) -> Option<Option<ObjectMap>> {
+        let cache = self.inner.cache.lock().expect("mutex poisoned");
+        cache
+            .get(key)
+            .filter(|cached| now.duration_since(cached.fetched_at) < self.cache_ttl())
+            .map(|cached| cached.record.clone())
+    }
+
+    fn cache(&self, key: String, record: Option<ObjectMap>, now: Instant) {
+        if self.inner.config.cache_ttl_secs == 0 {
+            return;
+        }
+
+        let mut cache = self.inner.cache.lock().expect("mutex poisoned");
+        if cache.len() >= self.inner.config.cache_max_entries.get() && !cache.contains_key(&key) {
+            let ttl = self.cache_ttl();
+            cache.retain(|_, cached| now.duration_since(cached.fetched_at) < ttl);
+            if cache.len() >= self.inner.config.cache_max_entries.get() {
+                return;
+            }
+        }
+        cache.insert(
+            key,
+            CachedRecord {
+                fetched_at: now,
+                record,
+            },
+        );
+    }
+
+    /// Runs the command on an idle connection, or a new one. The connection is kept for later
+    /// lookups, unless the command failed.
+    fn query<T: redis::FromRedisValue>(&self, command: &redis::Cmd) -> redis::RedisResult<T> {
+        let idle = self.inner.connections.lock().expect("mutex poisoned").pop();
+        let mut connection = match idle {
+            Some(connection) => connection,
+            None => {
+                let timeout = Duration::from_secs(self.inner.config.timeout_secs);
+                let connection = self.inner.client.get_connection_with_timeout(timeout)?;
+                connection.set_read_timeout(Some(timeout))?;
+                connection.set_write_timeout(Some(timeout))?;
+                connection
+            }
+        };
+
+        let result = command.query(&mut connection)?;
+
+        let mut connections = self.inner.connections.lock().expect("mutex poisoned");
+        if connections.len() < self.inner.config.max_idle_connections.get() {
+            connections.push(connection);
+        }
+        Ok(result)
+    }
+
+    fn fetch(&self, key: &str) -> redis::RedisResult<Option<ObjectMap>> {
+        let redis_key = format!("{}{}", self.inner.config.key_prefix, key);
+        Ok(match self.inner.config.data_type {
+            RedisDataType::Hash => {
+                let fields: HashMap<String, Vec<u8>> =
+                    self.query(redis::cmd("HGETALL").arg(&redis_key))?;
+                (!fields.is_empty()).then(|| {
+                    fields
+                        .into_iter()
+                        .map(|(field, value)| {
+                            (KeyString::from(field), Value::Bytes(Bytes::from(value)))
+                        })
+                        .collect()
+                })
+            }
+            RedisDataType::String => {
+                let value: Option<Vec<u8>> = self.query(redis::cmd("GET").arg(&redis_key))?;
+                value.map(|value| {
+                    ObjectMap::from([
+                        (KeyString::from("key"), Value::from(key)),
+                        (KeyString::from("value"), Value::Bytes(Bytes::from(value))),
+                    ])
+                })
+            }
+        })
+    }
+
+    fn lookup(&self, key: &str, select: Option<&[String]>) -> Result<Option<ObjectMap>, String> {
+        let now = Instant::now();
+        let record = match self.cached(key, now) {
+            Some(record) => record,
+            None => {
+                let record = self
+                    .fetch(key)
+                    .map_err(|error| format!("Redis lookup failed: {error}"))?;
+                self.cache(key.to_owned(), record.clone(), now);
+                record
+            }
+        };
+
+        Ok(record.map(|record| match select {
+            Some(fields) => record
+                .into_iter()
+                .filter(|(field, _)| fields.iter().any(|selected| selected == field.as_str()))
+                .collect(),
+            None => record,
+        }))
+    }
+}
+
+impl Table for Redis {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<ObjectMap, String> {
+        let mut rows = self.find_table_rows(case, condition, select, wildcard, index)?;
+
+        match rows.pop() {
+            Some(row) if rows.is_empty() => Ok(row),
+            Some(_) => Err("More than 1 row found".to_string()),
+            None => Err("Key not found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        _: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _wildcard: Option<&Value>,
+        _: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, String> {
+        match condition.first() {
+            Some(_) if condition.len() > 1 => Err("Only one condition is allowed".to_string()),
+            Some(Condition::Equals { value, .. }) => {
+                let key = value.to_string_lossy();
+                Ok(self.lookup(&key, select)?.into_iter().collect())
+            }
+            Some(_) => Err("Only equality condition is allowed".to_string()),
+            None => Err("Key condition must be specified".to_string()),
+        }
+    }
+
+    fn add_index(&mut self, _: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        match fields.len() {
+            0 => Err("Key field is required".to_string()),
+            1 => Ok(IndexHandle(0)),
+            _ => Err("Only one field is allowed".to_string()),
+        }
+    }
+
+    /// Returns a list of the field names that are in each index
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        Vec::new()
+    }
+
+    /// Doesn't need reload, records are looked up in Redis
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for Redis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Redis({})", self.inner.config.endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redis(config: RedisConfig) -> Redis {
+        Redis::new(config).unwrap()
+    }
+
+    fn config() -> RedisConfig {
+        toml::from_str(r#"endpoint = "redis://127.0.0.1:1/0""#).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisConfig>();
+    }
+
+    #[test]
+    fn serves_cached_records() {
+        let table = redis(config());
+        let record = ObjectMap::from([(KeyString::from("org"), Value::from("acme"))]);
+        let now = Instant::now();
+        table.cache("user-1".to_owned(), Some(record.clone()), now);
+        table.cache("user-2".to_owned(), None, now);
+
+        let condition = [Condition::Equals {
+            field: "user",
+            value: Value::from("user-1"),
+        }];
+        assert_eq!(
+            table
+                .find_table_row(Case::Sensitive, &condition, None, None, None)
+                .unwrap(),
+            record
+        );
+        assert_eq!(
+            table
+                .find_table_row(
+                    Case::Sensitive,
+                    &condition,
+                    Some(&["other".to_owned()]),
+                    None,
+                    None
+                )
+                .unwrap(),
+            ObjectMap::new()
+        );
+
+        let condition = [Condition::Equals {
+            field: "user",
+            value: Value::from("user-2"),
+        }];
+        assert!(
+            table
+                .find_table_rows(Case::Sensitive, &condition, None, None, None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn limits_cache_size() {
+        let table = redis(RedisConfig {
+            cache_max_entries: NonZeroUsize::new(1).unwrap(),
+            ..config()
+        });
+        let now = Instant::now();
+        table.cache("user-1".to_owned(), None, now);
+        table.cache("user-2".to_owned(), None, now);
+
+        assert!(table.cached("user-1", now).is_some());
+        assert!(table.cached("user-2", now).is_none());
+    }
+
+    #[test]
+    fn rejects_unsupported_conditions() {
+        let table = redis(config());
+        let condition = [
+            Condition::Equals {
+                field: "user",
+                value: Value::from("user-1"),
+            },
+            Condition::Equals {
+                field: "org",
+                value: Value::from("acme"),
+            },
+        ];
+        assert_eq!(
+            table
+                .find_table_rows(Case::Sensitive, &condition, None, None, None)
+                .unwrap_err(),
+            "Only one condition is allowed"
+        );
+        assert_eq!(
+            table
+                .find_table_rows(Case::Sensitive, &[], None, None, None)
+                .unwrap_err(),
+            "Key condition must be specified"
+        );
+    }
+}
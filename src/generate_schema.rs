@@ -7,11 +7,11 @@ use vector_lib::configurable::schema::generate_root_schema;
 
 use crate::config::ConfigBuilder;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(rename_all = "kebab-case")]
 /// Command line options for the `generate-schema` command.
 pub struct Opts {
-    /// File path to
+    /// File path to write the schema to, instead of stdout.
     #[arg(short, long)]
     pub(crate) output_path: Option<PathBuf>,
 }
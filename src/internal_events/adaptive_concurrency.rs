@@ -1,6 +1,14 @@
 use std::time::Duration;
 
-use metrics::{Histogram, histogram};
+use metrics::{Gauge, Histogram, Label, gauge, histogram};
+
+/// The `endpoint` tag of the metrics of a controller adapting the concurrency of a single endpoint.
+fn endpoint_labels(endpoint: &Option<String>) -> Vec<Label> {
+    endpoint
+        .iter()
+        .map(|endpoint| Label::new("endpoint", endpoint.clone()))
+        .collect()
+}
 
 #[derive(Clone, Copy)]
 pub struct AdaptiveConcurrencyLimitData {
@@ -13,14 +21,16 @@ pub struct AdaptiveConcurrencyLimitData {
 }
 
 registered_event! {
-    AdaptiveConcurrencyLimit => {
+    AdaptiveConcurrencyLimit {
+        endpoint: Option<String>,
+    } => {
         // These are histograms, as they may have a number of different
         // values over each reporting interval, and each of those values
         // is valuable for diagnosis.
-        limit: Histogram = histogram!("adaptive_concurrency_limit"),
-        reached_limit: Histogram = histogram!("adaptive_concurrency_reached_limit"),
-        back_pressure: Histogram = histogram!("adaptive_concurrency_back_pressure"),
-        past_rtt_mean: Histogram = histogram!("adaptive_concurrency_past_rtt_mean"),
+        limit: Histogram = histogram!("adaptive_concurrency_limit", endpoint_labels(&self.endpoint)),
+        reached_limit: Histogram = histogram!("adaptive_concurrency_reached_limit", endpoint_labels(&self.endpoint)),
+        back_pressure: Histogram = histogram!("adaptive_concurrency_back_pressure", endpoint_labels(&self.endpoint)),
+        past_rtt_mean: Histogram = histogram!("adaptive_concurrency_past_rtt_mean", endpoint_labels(&self.endpoint)),
     }
 
     fn emit(&self, data: AdaptiveConcurrencyLimitData) {
@@ -35,8 +45,10 @@ registered_event! {
 }
 
 registered_event! {
-    AdaptiveConcurrencyInFlight => {
-        in_flight: Histogram = histogram!("adaptive_concurrency_in_flight"),
+    AdaptiveConcurrencyInFlight {
+        endpoint: Option<String>,
+    } => {
+        in_flight: Histogram = histogram!("adaptive_concurrency_in_flight", endpoint_labels(&self.endpoint)),
     }
 
     fn emit(&self, in_flight: u64) {
@@ -45,8 +57,10 @@ registered_event! {
 }
 
 registered_event! {
-    AdaptiveConcurrencyObservedRtt => {
-        observed_rtt: Histogram = histogram!("adaptive_concurrency_observed_rtt"),
+    AdaptiveConcurrencyObservedRtt {
+        endpoint: Option<String>,
+    } => {
+        observed_rtt: Histogram = histogram!("adaptive_concurrency_observed_rtt", endpoint_labels(&self.endpoint)),
     }
 
     fn emit(&self, rtt: Duration) {
@@ -55,11 +69,25 @@ registered_event! {
 }
 
 registered_event! {
-    AdaptiveConcurrencyAveragedRtt => {
-        averaged_rtt: Histogram = histogram!("adaptive_concurrency_averaged_rtt"),
+    AdaptiveConcurrencyAveragedRtt {
+        endpoint: Option<String>,
+    } => {
+        averaged_rtt: Histogram = histogram!("adaptive_concurrency_averaged_rtt", endpoint_labels(&self.endpoint)),
     }
 
     fn emit(&self, rtt: Duration) {
         self.averaged_rtt.record(rtt);
     }
 }
+
+registered_event! {
+    AdaptiveConcurrencyHealth {
+        endpoint: Option<String>,
+    } => {
+        health: Gauge = gauge!("adaptive_concurrency_health", endpoint_labels(&self.endpoint)),
+    }
+
+    fn emit(&self, health: f64) {
+        self.health.set(health);
+    }
+}
@@ -0,0 +1,14 @@
+use vector_lib::internal_event::{ComponentEventsDropped, Count, INTENTIONAL, Registered};
+
+vector_lib::registered_event!(
+    AggregateV2LateEventsDropped => {
+        events_dropped: Registered<ComponentEventsDropped<'static, INTENTIONAL>>
+            = register!(ComponentEventsDropped::<INTENTIONAL>::from(
+                "Event arrived after its window closed."
+            )),
+    }
+
+    fn emit(&self, data: Count) {
+        self.events_dropped.emit(data);
+    }
+);
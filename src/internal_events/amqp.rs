@@ -45,6 +45,27 @@ pub mod source {
         }
     }
 
+    #[derive(Debug)]
+    pub struct AmqpConnectionError {
+        pub error: crate::Error,
+    }
+
+    impl InternalEvent for AmqpConnectionError {
+        fn emit(self) {
+            error!(message = "Failed to consume from the queue, reconnecting.",
+                   error = %self.error,
+                   error_type = error_type::CONNECTION_FAILED,
+                   stage = error_stage::RECEIVING,
+            );
+            counter!(
+                "component_errors_total",
+                "error_type" => error_type::CONNECTION_FAILED,
+                "stage" => error_stage::RECEIVING,
+            )
+            .increment(1);
+        }
+    }
+
     #[derive(Debug)]
     pub struct AmqpAckError {
         pub error: lapin::Error,
@@ -0,0 +1,59 @@
+use metrics::{counter, gauge};
+use vector_lib::internal_event::{ComponentEventsDropped, INTENTIONAL, InternalEvent};
+
+pub struct CardinalityLimitRejectingEvent<'a> {
+    pub limit: &'a str,
+}
+
+impl InternalEvent for CardinalityLimitRejectingEvent<'_> {
+    fn emit(self) {
+        debug!(
+            message = "Event for a new series exceeding the limit; discarding event.",
+            limit = self.limit,
+        );
+        counter!(
+            "cardinality_limit_exceeded_total",
+            "limit" => self.limit.to_owned(),
+            "action" => "drop_event",
+        )
+        .increment(1);
+
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: 1,
+            reason: "Series limit exceeded."
+        })
+    }
+}
+
+pub struct CardinalityLimitRewritingSeries<'a> {
+    pub limit: &'a str,
+    pub action: &'static str,
+}
+
+impl InternalEvent for CardinalityLimitRewritingSeries<'_> {
+    fn emit(self) {
+        debug!(
+            message = "Rewriting series of event exceeding the limit.",
+            limit = self.limit,
+            action = self.action,
+        );
+        counter!(
+            "cardinality_limit_exceeded_total",
+            "limit" => self.limit.to_owned(),
+            "action" => self.action,
+        )
+        .increment(1);
+    }
+}
+
+pub struct CardinalityLimitActiveSeries<'a> {
+    pub limit: &'a str,
+    pub count: usize,
+}
+
+impl InternalEvent for CardinalityLimitActiveSeries<'_> {
+    fn emit(self) {
+        gauge!("cardinality_limit_active_series", "limit" => self.limit.to_owned())
+            .set(self.count as f64);
+    }
+}
@@ -103,6 +103,36 @@ impl InternalEvent for EncoderSerializeError<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct EncoderBatchSerializeError<'a> {
+    pub error: &'a crate::Error,
+    pub count: usize,
+}
+
+impl InternalEvent for EncoderBatchSerializeError<'_> {
+    fn emit(self) {
+        let reason = "Failed serializing batch.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = "encoder_serialize",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::SENDING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "encoder_serialize",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::SENDING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+            count: self.count,
+            reason,
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct EncoderWriteError<'a, E> {
     pub error: &'a E,
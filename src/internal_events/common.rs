@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use metrics::{counter, histogram};
 pub use vector_lib::internal_event::EventsReceived;
@@ -145,3 +145,24 @@ impl<E: std::fmt::Display> InternalEvent for SinkRequestBuildError<E> {
         .increment(1);
     }
 }
+
+#[derive(Debug)]
+pub struct SinkAcknowledgementTimeout {
+    /// The sequence number of the batch among the ones received by the sink.
+    pub batch: u64,
+    pub count: usize,
+    pub held: Duration,
+}
+
+impl InternalEvent for SinkAcknowledgementTimeout {
+    fn emit(self) {
+        warn!(
+            message = "Events held by the sink without being acknowledged past the timeout.",
+            batch = self.batch,
+            count = self.count,
+            held_secs = self.held.as_secs(),
+            internal_log_rate_limit = true,
+        );
+        counter!("acknowledgement_timeouts_total").increment(1);
+    }
+}
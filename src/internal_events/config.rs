@@ -1,4 +1,6 @@
-use metrics::counter;
+use std::time::Duration;
+
+use metrics::{counter, histogram};
 use vector_lib::internal_event::InternalEvent;
 
 #[derive(Debug)]
@@ -86,3 +88,23 @@ impl InternalEvent for ConfigReloaded {
         Some("ConfigReloaded")
     }
 }
+
+#[derive(Debug)]
+pub struct ComponentBuilt {
+    pub duration: Duration,
+}
+
+impl InternalEvent for ComponentBuilt {
+    fn emit(self) {
+        debug!(
+            message = "Component built.",
+            duration_ms = self.duration.as_millis() as u64,
+        );
+
+        histogram!("component_build_duration_seconds").record(self.duration);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("ComponentBuilt")
+    }
+}
@@ -1,3 +1,4 @@
+use metrics::gauge;
 use vector_lib::internal_event::{ComponentEventsDropped, INTENTIONAL, InternalEvent};
 
 #[derive(Debug)]
@@ -13,3 +14,14 @@ impl InternalEvent for DedupeEventsDropped {
         });
     }
 }
+
+#[derive(Debug)]
+pub struct DedupeCacheCardinality {
+    pub estimated_cardinality: usize,
+}
+
+impl InternalEvent for DedupeCacheCardinality {
+    fn emit(self) {
+        gauge!("dedupe_estimated_cardinality").set(self.estimated_cardinality as f64);
+    }
+}
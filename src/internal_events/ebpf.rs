@@ -0,0 +1,75 @@
+use metrics::counter;
+use vector_lib::internal_event::{
+    ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
+};
+
+#[derive(Debug)]
+pub struct EbpfReadError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for EbpfReadError {
+    fn emit(self) {
+        error!(
+            message = "Could not read from the perf buffers.",
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::RECEIVING,
+            "error_type" => error_type::READER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct EbpfInvalidSample {
+    pub length: usize,
+}
+
+impl InternalEvent for EbpfInvalidSample {
+    fn emit(self) {
+        error!(
+            message = "Invalid sample from the perf buffers, discarding.",
+            length = %self.length,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::PROCESSING,
+            "error_type" => error_type::PARSER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct EbpfSamplesLost {
+    pub count: u64,
+}
+
+impl InternalEvent for EbpfSamplesLost {
+    fn emit(self) {
+        let reason = "The perf buffer of a CPU was full.";
+        error!(
+            message = reason,
+            count = %self.count,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::RECEIVING,
+            "error_type" => error_type::CONDITION_FAILED,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+            count: self.count as usize,
+            reason,
+        });
+    }
+}
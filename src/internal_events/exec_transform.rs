@@ -0,0 +1,35 @@
+use metrics::counter;
+use vector_lib::internal_event::{
+    ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
+};
+
+use crate::transforms::exec::transform::ExecError;
+
+#[derive(Debug)]
+pub struct ExecTransformError {
+    pub error: ExecError,
+    pub event_dropped: bool,
+}
+
+impl InternalEvent for ExecTransformError {
+    fn emit(self) {
+        let reason = "Process failed to transform the event.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = self.error.error_code(),
+            error_type = error_type::COMMAND_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => self.error.error_code(),
+            "error_type" => error_type::COMMAND_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        if self.event_dropped {
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+        }
+    }
+}
@@ -37,6 +37,18 @@ impl InternalEvent for FileOpen {
     }
 }
 
+#[derive(Debug)]
+pub struct FileLag {
+    pub priority: &'static str,
+    pub lag_bytes: u64,
+}
+
+impl InternalEvent for FileLag {
+    fn emit(self) {
+        gauge!("files_lag_bytes", "priority" => self.priority).set(self.lag_bytes as f64);
+    }
+}
+
 #[derive(Debug)]
 pub struct FileBytesSent<'a> {
     pub byte_size: usize,
@@ -117,7 +129,7 @@ mod source {
         json_size::JsonSize,
     };
 
-    use super::{FileOpen, InternalEvent};
+    use super::{FileLag, FileOpen, InternalEvent};
 
     #[derive(Debug)]
     pub struct FileBytesReceived<'a> {
@@ -604,6 +616,13 @@ mod source {
             emit!(FileOpen { count });
         }
 
+        fn emit_files_lag(&self, priority: &'static str, lag_bytes: u64) {
+            emit!(FileLag {
+                priority,
+                lag_bytes
+            });
+        }
+
         fn emit_path_globbing_failed(&self, path: &Path, error: &Error) {
             emit!(PathGlobbingError { path, error });
         }
@@ -0,0 +1,30 @@
+use metrics::counter;
+use vector_lib::internal_event::{
+    ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
+};
+
+#[derive(Debug)]
+pub struct GcpBigqueryRowEncodingError<'a, E> {
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for GcpBigqueryRowEncodingError<'_, E> {
+    fn emit(self) {
+        let reason = "Failed to encode the event as a row of the table.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = "invalid_row",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "invalid_row",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use metrics::{counter, gauge};
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct GeoipDatabaseAge<'a> {
+    pub edition_id: &'a str,
+    pub age: Duration,
+}
+
+impl InternalEvent for GeoipDatabaseAge<'_> {
+    fn emit(self) {
+        gauge!("geoip_database_age_seconds", "edition_id" => self.edition_id.to_owned())
+            .set(self.age.as_secs_f64());
+    }
+}
+
+#[derive(Debug)]
+pub struct GeoipDatabaseUpdated<'a> {
+    pub edition_id: &'a str,
+}
+
+impl InternalEvent for GeoipDatabaseUpdated<'_> {
+    fn emit(self) {
+        info!(
+            message = "GeoIP database updated.",
+            edition_id = %self.edition_id,
+        );
+        counter!("geoip_database_updates_total", "edition_id" => self.edition_id.to_owned())
+            .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GeoipDatabaseUpdateError<'a> {
+    pub edition_id: &'a str,
+    pub error: &'a crate::Error,
+}
+
+impl InternalEvent for GeoipDatabaseUpdateError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to update GeoIP database.",
+            edition_id = %self.edition_id,
+            error = %self.error,
+            error_code = "geoip_database_update",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "geoip_database_update_errors_total",
+            "edition_id" => self.edition_id.to_owned(),
+            "error_code" => "geoip_database_update",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
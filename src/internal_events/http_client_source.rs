@@ -86,3 +86,27 @@ impl InternalEvent for HttpClientHttpError {
         .increment(1);
     }
 }
+
+#[derive(Debug)]
+pub struct HttpClientCursorWriteError<'a> {
+    pub error: std::io::Error,
+    pub path: &'a std::path::Path,
+}
+
+impl InternalEvent for HttpClientCursorWriteError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to persist the pagination cursor.",
+            path = %self.path.display(),
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
@@ -114,6 +114,32 @@ impl InternalEvent for KafkaReadError {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaTransactionError {
+    pub error: rdkafka::error::KafkaError,
+    pub operation: &'static str,
+}
+
+impl InternalEvent for KafkaTransactionError {
+    fn emit(self) {
+        error!(
+            message = "Kafka transaction operation failed.",
+            error = %self.error,
+            operation = self.operation,
+            error_code = "kafka_transaction",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "kafka_transaction",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        )
+        .increment(1);
+    }
+}
+
 #[derive(Debug)]
 pub struct KafkaStatisticsReceived<'a> {
     pub statistics: &'a rdkafka::Statistics,
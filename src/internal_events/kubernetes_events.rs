@@ -0,0 +1,26 @@
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct KubernetesEventsWatchError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for KubernetesEventsWatchError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to watch Kubernetes Events.",
+            error = %self.error,
+            error_code = "watch_events",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "watch_events",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
@@ -206,6 +206,8 @@ impl<E: std::fmt::Display> InternalEvent for KubernetesLifecycleError<E> {
 #[derive(Debug)]
 pub struct KubernetesMergedLineTooBigError<'a> {
     pub event: &'a Value,
+    pub pod_uid: &'a str,
+    pub container_name: &'a str,
     pub configured_limit: usize,
     pub encountered_size_so_far: usize,
 }
@@ -215,6 +217,8 @@ impl InternalEvent for KubernetesMergedLineTooBigError<'_> {
         error!(
             message = "Found line that exceeds max_merged_line_bytes; discarding.",
             event = ?self.event,
+            pod_uid = self.pod_uid,
+            container_name = self.container_name,
             configured_limit = self.configured_limit,
             encountered_size_so_far = self.encountered_size_so_far,
             error_type = error_type::CONDITION_FAILED,
@@ -233,3 +237,69 @@ impl InternalEvent for KubernetesMergedLineTooBigError<'_> {
         });
     }
 }
+
+#[derive(Debug)]
+pub struct KubernetesMergedLineTooBigWarning<'a> {
+    pub event: &'a Value,
+    pub pod_uid: &'a str,
+    pub container_name: &'a str,
+    pub configured_limit: usize,
+    pub encountered_size_so_far: usize,
+    pub truncated: bool,
+}
+
+impl InternalEvent for KubernetesMergedLineTooBigWarning<'_> {
+    fn emit(self) {
+        if self.truncated {
+            warn!(
+                message = "Found line that exceeds max_merged_line_bytes; truncating.",
+                event = ?self.event,
+                pod_uid = self.pod_uid,
+                container_name = self.container_name,
+                configured_limit = self.configured_limit,
+                encountered_size_so_far = self.encountered_size_so_far,
+            );
+        } else {
+            warn!(
+                message = "Found line that exceeds max_merged_line_bytes; emitting raw parts.",
+                event = ?self.event,
+                pod_uid = self.pod_uid,
+                container_name = self.container_name,
+                configured_limit = self.configured_limit,
+                encountered_size_so_far = self.encountered_size_so_far,
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesLogsLinesRateLimited<'a> {
+    pub pod_namespace: &'a str,
+    pub pod_name: &'a str,
+    pub container_name: &'a str,
+    pub count: u64,
+}
+
+impl InternalEvent for KubernetesLogsLinesRateLimited<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Lines exceeded the rate limit of their container; discarding.",
+            pod_namespace = self.pod_namespace,
+            pod_name = self.pod_name,
+            container_name = self.container_name,
+            count = self.count,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "kubernetes_logs_rate_limited_lines_total",
+            "pod_namespace" => self.pod_namespace.to_owned(),
+            "pod_name" => self.pod_name.to_owned(),
+            "container_name" => self.container_name.to_owned(),
+        )
+        .increment(self.count);
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count as usize,
+            reason: "Lines exceeded the rate limit of their container.",
+        });
+    }
+}
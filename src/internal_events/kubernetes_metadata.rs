@@ -0,0 +1,16 @@
+use vector_lib::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct KubernetesMetadataStateUnavailable<'a> {
+    pub source: &'a str,
+}
+
+impl InternalEvent for KubernetesMetadataStateUnavailable<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Source of the Kubernetes metadata is not running; passing events through.",
+            source = self.source,
+            internal_log_rate_limit = true,
+        );
+    }
+}
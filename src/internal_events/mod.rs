@@ -4,6 +4,8 @@ pub mod prelude;
 mod adaptive_concurrency;
 #[cfg(feature = "transforms-aggregate")]
 mod aggregate;
+#[cfg(feature = "transforms-aggregate_v2")]
+mod aggregate_v2;
 #[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
 mod amqp;
 #[cfg(feature = "sources-apache_metrics")]
@@ -28,6 +30,8 @@ mod aws_kinesis_firehose;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 mod aws_sqs;
 mod batch;
+#[cfg(feature = "transforms-cardinality_limit")]
+mod cardinality_limit;
 mod codecs;
 mod common;
 mod conditions;
@@ -45,20 +49,32 @@ mod demo_logs;
 mod dnstap;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+mod ebpf;
 mod encoding_transcode;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
 mod exec;
+#[cfg(feature = "transforms-exec")]
+mod exec_transform;
 #[cfg(any(feature = "sources-file_descriptor", feature = "sources-stdin"))]
 mod file_descriptor;
 #[cfg(feature = "transforms-filter")]
 mod filter;
 #[cfg(feature = "sources-fluent")]
 mod fluent;
+#[cfg(feature = "sinks-gcp")]
+mod gcp_bigquery;
 #[cfg(feature = "sources-gcp_pubsub")]
 mod gcp_pubsub;
-#[cfg(any(feature = "sources-vector", feature = "sources-opentelemetry"))]
+#[cfg(feature = "enrichment-tables-geoip")]
+mod geoip;
+#[cfg(any(
+    feature = "sources-grpc_server",
+    feature = "sources-opentelemetry",
+    feature = "sources-vector"
+))]
 mod grpc;
 mod heartbeat;
 #[cfg(feature = "sources-host_metrics")]
@@ -75,8 +91,12 @@ mod internal_logs;
 mod journald;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
 mod kafka;
+#[cfg(feature = "sources-kubernetes_events")]
+mod kubernetes_events;
 #[cfg(feature = "sources-kubernetes_logs")]
 mod kubernetes_logs;
+#[cfg(feature = "transforms-kubernetes_metadata")]
+mod kubernetes_metadata;
 #[cfg(feature = "transforms-log_to_metric")]
 mod log_to_metric;
 #[cfg(feature = "sources-heroku_logs")]
@@ -89,10 +109,20 @@ mod lua;
 mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
-#[cfg(feature = "sinks-mqtt")]
+#[cfg(any(feature = "sinks-mqtt", feature = "sources-mqtt"))]
 mod mqtt;
+#[cfg(feature = "sources-nats")]
+mod nats;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
+#[cfg(any(
+    feature = "sources-aws_s3",
+    feature = "sources-azure_blob",
+    feature = "sources-gcp_cloud_storage",
+))]
+mod object_storage;
 mod open;
 #[cfg(any(
     feature = "sources-kubernetes_logs",
@@ -100,6 +130,8 @@ mod open;
     feature = "sinks-datadog_events",
 ))]
 mod parser;
+#[cfg(feature = "sources-postgres_cdc")]
+mod postgres_cdc;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
@@ -117,10 +149,14 @@ mod redis;
 mod reduce;
 #[cfg(feature = "transforms-remap")]
 mod remap;
+#[cfg(feature = "transforms-route")]
+mod route;
 #[cfg(feature = "transforms-impl-sample")]
 mod sample;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sources-snmp_trap")]
+mod snmp_trap;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
@@ -128,6 +164,8 @@ mod splunk_hec;
 mod statsd_sink;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sample")]
+mod tail_sample;
 mod tcp;
 mod template;
 #[cfg(feature = "transforms-throttle")]
@@ -135,6 +173,8 @@ mod throttle;
 mod udp;
 #[cfg(unix)]
 mod unix;
+#[cfg(feature = "transforms-wasm")]
+mod wasm;
 #[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
 mod websocket;
 #[cfg(feature = "sinks-websocket-server")]
@@ -151,6 +191,8 @@ mod file;
 
 #[cfg(windows)]
 mod windows;
+#[cfg(all(windows, feature = "sources-windows_eventlog"))]
+mod windows_eventlog;
 
 pub mod config;
 #[cfg(any(feature = "transforms-log_to_metric", feature = "sinks-loki"))]
@@ -160,6 +202,8 @@ pub(crate) use mongodb_metrics::*;
 
 #[cfg(feature = "transforms-aggregate")]
 pub(crate) use self::aggregate::*;
+#[cfg(feature = "transforms-aggregate_v2")]
+pub(crate) use self::aggregate_v2::*;
 #[cfg(feature = "sources-amqp")]
 pub(crate) use self::amqp::*;
 #[cfg(feature = "sources-apache_metrics")]
@@ -183,6 +227,8 @@ pub(crate) use self::aws_kinesis::*;
 pub(crate) use self::aws_kinesis_firehose::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(feature = "transforms-cardinality_limit")]
+pub(crate) use self::cardinality_limit::*;
 pub(crate) use self::codecs::*;
 #[cfg(feature = "sources-datadog_agent")]
 pub(crate) use self::datadog_agent::*;
@@ -198,10 +244,14 @@ pub(crate) use self::demo_logs::*;
 pub(crate) use self::dnstap::*;
 #[cfg(feature = "sources-docker_logs")]
 pub(crate) use self::docker_logs::*;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+pub(crate) use self::ebpf::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub(crate) use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
 pub(crate) use self::exec::*;
+#[cfg(feature = "transforms-exec")]
+pub(crate) use self::exec_transform::*;
 #[cfg(any(feature = "transforms-log_to_metric", feature = "sinks-loki"))]
 pub use self::expansion::*;
 #[cfg(any(
@@ -216,9 +266,17 @@ pub(crate) use self::file_descriptor::*;
 pub(crate) use self::filter::*;
 #[cfg(feature = "sources-fluent")]
 pub(crate) use self::fluent::*;
+#[cfg(feature = "sinks-gcp")]
+pub(crate) use self::gcp_bigquery::*;
 #[cfg(feature = "sources-gcp_pubsub")]
 pub(crate) use self::gcp_pubsub::*;
-#[cfg(any(feature = "sources-vector", feature = "sources-opentelemetry"))]
+#[cfg(feature = "enrichment-tables-geoip")]
+pub(crate) use self::geoip::*;
+#[cfg(any(
+    feature = "sources-grpc_server",
+    feature = "sources-opentelemetry",
+    feature = "sources-vector"
+))]
 pub(crate) use self::grpc::*;
 #[cfg(feature = "sources-host_metrics")]
 pub(crate) use self::host_metrics::*;
@@ -232,8 +290,12 @@ pub(crate) use self::internal_logs::*;
 pub(crate) use self::journald::*;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
 pub(crate) use self::kafka::*;
+#[cfg(feature = "sources-kubernetes_events")]
+pub(crate) use self::kubernetes_events::*;
 #[cfg(feature = "sources-kubernetes_logs")]
 pub(crate) use self::kubernetes_logs::*;
+#[cfg(feature = "transforms-kubernetes_metadata")]
+pub(crate) use self::kubernetes_metadata::*;
 #[cfg(feature = "transforms-log_to_metric")]
 pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "sources-heroku_logs")]
@@ -244,16 +306,28 @@ pub(crate) use self::loki::*;
 pub(crate) use self::lua::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
-#[cfg(feature = "sinks-mqtt")]
+#[cfg(any(feature = "sinks-mqtt", feature = "sources-mqtt"))]
 pub(crate) use self::mqtt::*;
+#[cfg(feature = "sources-nats")]
+pub(crate) use self::nats::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
+#[cfg(any(
+    feature = "sources-aws_s3",
+    feature = "sources-azure_blob",
+    feature = "sources-gcp_cloud_storage",
+))]
+pub(crate) use self::object_storage::*;
 #[cfg(any(
     feature = "sources-kubernetes_logs",
     feature = "transforms-log_to_metric",
     feature = "sinks-datadog_events",
 ))]
 pub(crate) use self::parser::*;
+#[cfg(feature = "sources-postgres_cdc")]
+pub(crate) use self::postgres_cdc::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(
@@ -270,20 +344,28 @@ pub(crate) use self::redis::*;
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-remap")]
 pub(crate) use self::remap::*;
+#[cfg(feature = "transforms-route")]
+pub(crate) use self::route::*;
 #[cfg(feature = "transforms-impl-sample")]
 pub(crate) use self::sample::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
+#[cfg(feature = "sources-snmp_trap")]
+pub(crate) use self::snmp_trap::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
 #[cfg(feature = "sinks-statsd")]
 pub(crate) use self::statsd_sink::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
+#[cfg(feature = "transforms-tail_sample")]
+pub(crate) use self::tail_sample::*;
 #[cfg(feature = "transforms-throttle")]
 pub(crate) use self::throttle::*;
 #[cfg(unix)]
 pub(crate) use self::unix::*;
+#[cfg(feature = "transforms-wasm")]
+pub(crate) use self::wasm::*;
 #[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
 pub(crate) use self::websocket::*;
 #[cfg(feature = "sinks-websocket-server")]
@@ -292,6 +374,8 @@ pub(crate) use self::websocket_server::*;
 pub(crate) use self::window::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
+#[cfg(all(windows, feature = "sources-windows_eventlog"))]
+pub(crate) use self::windows_eventlog::*;
 pub use self::{
     adaptive_concurrency::*, batch::*, common::*, conditions::*, encoding_transcode::*,
     heartbeat::*, http::*, open::*, process::*, socket::*, tcp::*, template::*, udp::*,
@@ -31,3 +31,87 @@ impl InternalEvent for MqttConnectionError {
         Some("MqttConnectionError")
     }
 }
+
+#[derive(Debug)]
+pub struct MqttSubscribeError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for MqttSubscribeError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to subscribe to MQTT topic.",
+            error = %self.error,
+            error_code = "mqtt_subscribe_error",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "mqtt_subscribe_error",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("MqttSubscribeError")
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttReceiveError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for MqttReceiveError<E> {
+    fn emit(self) {
+        error!(
+            message = "MQTT connection error.",
+            error = %self.error,
+            error_code = "mqtt_connection_error",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "mqtt_connection_error",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("MqttReceiveError")
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttAckError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for MqttAckError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge MQTT message.",
+            error = %self.error,
+            error_code = "mqtt_ack_error",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "mqtt_ack_error",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("MqttAckError")
+    }
+}
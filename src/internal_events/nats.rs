@@ -0,0 +1,50 @@
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct NatsReceiveError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for NatsReceiveError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to pull JetStream messages.",
+            error = %self.error,
+            error_code = "pull_messages",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "pull_messages",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct NatsAckError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for NatsAckError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge JetStream message.",
+            error = %self.error,
+            error_code = "ack_message",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "ack_message",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
@@ -0,0 +1,54 @@
+use std::net::SocketAddr;
+
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct NetflowDecodeError<E> {
+    pub error: E,
+    pub peer_addr: SocketAddr,
+}
+
+impl<E: std::fmt::Display> InternalEvent for NetflowDecodeError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode flow packet.",
+            error = %self.error,
+            peer_addr = %self.peer_addr,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowTemplateNotFound {
+    pub template_id: u16,
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for NetflowTemplateNotFound {
+    fn emit(self) {
+        error!(
+            message = "Discarding flow records received before their template.",
+            template_id = self.template_id,
+            peer_addr = %self.peer_addr,
+            error_code = "template_not_found",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "template_not_found",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
@@ -0,0 +1,122 @@
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct ObjectStoragePollError<'a, E> {
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ObjectStoragePollError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to poll bucket for objects.",
+            error = %self.error,
+            error_code = "failed_polling_bucket",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "failed_polling_bucket",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectProcessingError<'a, E> {
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ObjectProcessingError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to process object.",
+            error = %self.error,
+            error_code = "failed_processing_object",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "failed_processing_object",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectCheckpointWriteError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for ObjectCheckpointWriteError {
+    fn emit(self) {
+        error!(
+            message = "Failed writing checkpoints.",
+            error = %self.error,
+            error_code = "writing_checkpoints",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "writing_checkpoints",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectNotificationReceiveError<'a, E> {
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ObjectNotificationReceiveError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to receive object notifications.",
+            error = %self.error,
+            error_code = "failed_receiving_notifications",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "failed_receiving_notifications",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectNotificationAcknowledgeError<'a, E> {
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ObjectNotificationAcknowledgeError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge object notifications.",
+            error = %self.error,
+            error_code = "failed_acknowledging_notifications",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "failed_acknowledging_notifications",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
@@ -0,0 +1,50 @@
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct PostgresCdcReplicationError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for PostgresCdcReplicationError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read changes from the replication slot.",
+            error = %self.error,
+            error_code = "read_changes",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "read_changes",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresCdcDecodeError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for PostgresCdcDecodeError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode a change.",
+            error = %self.error,
+            error_code = "decode_change",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "decode_change",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
@@ -32,3 +32,54 @@ impl InternalEvent for RedisReceiveEventError {
         .increment(1);
     }
 }
+
+#[derive(Debug)]
+pub struct RedisAckError {
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for RedisAckError {
+    fn emit(self) {
+        let error_code = self.error.code().unwrap_or("UNKNOWN").to_string();
+        error!(
+            message = "Failed to acknowledge stream entries.",
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => error_code,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisStreamEntryMissingField<'a> {
+    pub id: &'a str,
+    pub field: &'a str,
+}
+
+impl InternalEvent for RedisStreamEntryMissingField<'_> {
+    fn emit(self) {
+        error!(
+            message = "Stream entry is missing the message field, skipping it.",
+            id = %self.id,
+            field = %self.field,
+            error_code = "missing_field",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "missing_field",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
@@ -1,4 +1,4 @@
-use metrics::counter;
+use metrics::{Counter, counter};
 use vector_lib::internal_event::{
     ComponentEventsDropped, INTENTIONAL, InternalEvent, UNINTENTIONAL, error_stage, error_type,
 };
@@ -53,3 +53,44 @@ impl InternalEvent for RemapMappingAbort {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct RemapStateWriteError {
+    pub error: sled::Error,
+}
+
+impl InternalEvent for RemapStateWriteError {
+    fn emit(self) {
+        error!(
+            message = "Failed writing remap state.",
+            error = %self.error,
+            error_code = "writing_state",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "writing_state",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+registered_event! {
+    RemapPatternCacheLookup {
+        cache: &'static str,
+    } => {
+        hits: Counter = counter!("remap_pattern_cache_hits_total", "cache" => self.cache),
+        misses: Counter = counter!("remap_pattern_cache_misses_total", "cache" => self.cache),
+    }
+
+    fn emit(&self, hit: bool) {
+        if hit {
+            self.hits.increment(1);
+        } else {
+            self.misses.increment(1);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use metrics::{Counter, counter};
+use vector_lib::registered_event;
+
+registered_event!(
+    RouteEvents {
+        route: String,
+    } => {
+        matched: Counter = counter!("route_matched_events_total", "route" => self.route.clone()),
+        unmatched: Counter = counter!("route_unmatched_events_total", "route" => self.route),
+    }
+
+    fn emit(&self, matched: bool) {
+        if matched {
+            self.matched.increment(1);
+        } else {
+            self.unmatched.increment(1);
+        }
+    }
+);
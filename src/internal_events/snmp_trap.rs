@@ -0,0 +1,28 @@
+use std::net::SocketAddr;
+
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct SnmpTrapMessageError<E> {
+    pub error: E,
+    pub peer_addr: SocketAddr,
+}
+
+impl<E: std::fmt::Display> InternalEvent for SnmpTrapMessageError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to process SNMP message.",
+            error = %self.error,
+            peer_addr = %self.peer_addr,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
@@ -7,7 +7,9 @@ pub use self::source::*;
 
 #[cfg(feature = "sinks-splunk_hec")]
 mod sink {
-    use metrics::{counter, gauge};
+    use std::time::Duration;
+
+    use metrics::{counter, gauge, histogram};
     use serde_json::Error;
     use vector_lib::internal_event::{
         ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
@@ -167,6 +169,55 @@ mod sink {
         }
     }
 
+    pub struct SplunkIndexerAcknowledgementAckDelivered {
+        pub latency: Duration,
+    }
+
+    impl InternalEvent for SplunkIndexerAcknowledgementAckDelivered {
+        fn emit(self) {
+            histogram!("splunk_ack_latency_seconds").record(self.latency);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SplunkIndexerAcknowledgementChannelRotated {
+        pub reason: &'static str,
+    }
+
+    impl InternalEvent for SplunkIndexerAcknowledgementChannelRotated {
+        fn emit(self) {
+            debug!(
+                message = "Rotated indexer acknowledgement channel.",
+                reason = self.reason
+            );
+            counter!("splunk_channel_rotations_total", "reason" => self.reason).increment(1);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SplunkIndexerAcknowledgementChannelLost {
+        pub count: usize,
+    }
+
+    impl InternalEvent for SplunkIndexerAcknowledgementChannelLost {
+        fn emit(self) {
+            error!(
+                message = "Indexer acknowledgement channel was lost by Splunk. Pending acknowledgements are errored.",
+                count = %self.count,
+                error_code = "indexer_ack_channel_lost",
+                error_type = error_type::ACKNOWLEDGMENT_FAILED,
+                stage = error_stage::SENDING,
+            );
+            counter!(
+                "component_errors_total",
+                "error_code" => "indexer_ack_channel_lost",
+                "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+                "stage" => error_stage::SENDING,
+            )
+            .increment(1);
+        }
+    }
+
     pub struct SplunkEventTimestampInvalidType<'a> {
         pub r#type: &'a str,
     }
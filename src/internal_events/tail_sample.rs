@@ -0,0 +1,15 @@
+use vector_lib::internal_event::{ComponentEventsDropped, INTENTIONAL, InternalEvent};
+
+#[derive(Debug)]
+pub struct TailSampleEventsDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for TailSampleEventsDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count,
+            reason: "Trace was not sampled by any policy.",
+        });
+    }
+}
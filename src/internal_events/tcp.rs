@@ -90,6 +90,16 @@ impl InternalEvent for TcpSocketTlsConnectionError {
                     error = %self.error,
                 );
             }
+            // The health checks of load balancers close the connections before sending the
+            // PROXY protocol header.
+            TlsError::ProxyProtocol { ref source }
+                if source.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                debug!(
+                    message = "Connection error, probably a healthcheck.",
+                    error = %self.error,
+                );
+            }
             _ => {
                 error!(
                     message = "Connection error.",
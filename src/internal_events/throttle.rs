@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use metrics::counter;
-use vector_lib::internal_event::{ComponentEventsDropped, INTENTIONAL, InternalEvent};
+use vector_lib::internal_event::{
+    ComponentEventsDropped, INTENTIONAL, InternalEvent, error_stage, error_type,
+};
 
 #[derive(Debug)]
 pub(crate) struct ThrottleEventDiscarded {
@@ -31,3 +35,27 @@ impl InternalEvent for ThrottleEventDiscarded {
         })
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ThrottleRedisUnavailable {
+    pub error: String,
+    pub retry_interval: Duration,
+}
+
+impl InternalEvent for ThrottleRedisUnavailable {
+    fn emit(self) {
+        error!(
+            message = "Redis rate limiter unavailable, falling back to the local rate limiter.",
+            error = %self.error,
+            retry_interval_secs = self.retry_interval.as_secs(),
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
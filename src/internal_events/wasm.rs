@@ -0,0 +1,42 @@
+use metrics::counter;
+use vector_lib::internal_event::{
+    ComponentEventsDropped, InternalEvent, UNINTENTIONAL, error_stage, error_type,
+};
+use wasmtime::Trap;
+
+#[derive(Debug)]
+pub struct WasmProcessError {
+    pub error: wasmtime::Error,
+}
+
+impl InternalEvent for WasmProcessError {
+    fn emit(self) {
+        let reason = "Error in WebAssembly module.";
+        error!(
+            message = reason,
+            error = ?self.error,
+            error_code = wasm_error_code(&self.error),
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => wasm_error_code(&self.error),
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
+
+fn wasm_error_code(error: &wasmtime::Error) -> &'static str {
+    match error.downcast_ref::<Trap>() {
+        Some(Trap::OutOfFuel) => "out_of_fuel",
+        Some(Trap::MemoryOutOfBounds) => "memory_out_of_bounds",
+        Some(Trap::StackOverflow) => "stack_overflow",
+        Some(Trap::UnreachableCodeReached) => "unreachable",
+        Some(_) => "trap",
+        None => "invalid_output",
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use metrics::counter;
+use vector_lib::internal_event::{InternalEvent, error_stage, error_type};
+
+#[derive(Debug)]
+pub struct WindowsEventLogReadError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for WindowsEventLogReadError {
+    fn emit(self) {
+        error!(
+            message = "Could not read from the event log.",
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::RECEIVING,
+            "error_type" => error_type::READER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WindowsEventLogParseError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for WindowsEventLogParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Invalid event from the event log, discarding.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::PROCESSING,
+            "error_type" => error_type::PARSER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct WindowsEventLogBookmarkError<'a> {
+    pub error: std::io::Error,
+    pub path: &'a Path,
+}
+
+impl InternalEvent for WindowsEventLogBookmarkError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Could not save event log bookmark.",
+            path = ?self.path,
+            error = %self.error,
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::PROCESSING,
+            "error_type" => error_type::IO_FAILED,
+        )
+        .increment(1);
+    }
+}
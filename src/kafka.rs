@@ -1,7 +1,11 @@
 #![allow(missing_docs)]
 use std::path::{Path, PathBuf};
+#[cfg(feature = "sources-kafka")]
+use std::time::Duration;
 
 use rdkafka::{ClientConfig, ClientContext, Statistics, consumer::ConsumerContext};
+#[cfg(feature = "sources-kafka")]
+use rdkafka::{TopicPartitionList, consumer::Consumer, error::KafkaResult};
 use snafu::Snafu;
 use tracing::Span;
 use vector_lib::{configurable::configurable_component, sensitive_string::SensitiveString};
@@ -11,6 +15,9 @@ use crate::{
     tls::{PEM_START_MARKER, TlsEnableableConfig},
 };
 
+#[cfg(feature = "sources-kafka")]
+pub mod cli;
+
 #[derive(Debug, Snafu)]
 enum KafkaError {
     #[snafu(display("invalid path: {:?}", path))]
@@ -40,6 +47,83 @@ pub enum KafkaCompression {
     Zstd,
 }
 
+/// The offset to start consuming a partition from.
+#[cfg(feature = "sources-kafka")]
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[configurable(metadata(docs::enum_tag_description = "The kind of start offset."))]
+pub enum KafkaStartOffset {
+    /// The offset committed for the consumer group.
+    ///
+    /// If the consumer group has no committed offset for the partition, `auto_offset_reset` is
+    /// used instead.
+    #[default]
+    Committed,
+
+    /// The earliest offset retained in the partition.
+    Earliest,
+
+    /// The end of the partition, so that only new messages are consumed.
+    Latest,
+
+    /// The offset of the first message with a timestamp equal to or later than the given one.
+    ///
+    /// If there is no such message, the end of the partition is used.
+    Timestamp {
+        /// The timestamp, in milliseconds since the Unix epoch.
+        #[configurable(metadata(docs::examples = 1735689600000_i64))]
+        timestamp_ms: i64,
+    },
+
+    /// An absolute offset.
+    Offset {
+        /// The offset of the first message to consume.
+        #[configurable(metadata(docs::examples = 42))]
+        offset: i64,
+    },
+}
+
+/// Builds the list of the given partitions along with their start offsets.
+///
+/// Timestamps are resolved to offsets by querying the brokers. Other start offsets are kept as
+/// logical offsets, which librdkafka resolves when the partitions are assigned.
+#[cfg(feature = "sources-kafka")]
+pub(crate) fn start_offsets<'a, X: ConsumerContext, C: Consumer<X>>(
+    consumer: &C,
+    partitions: impl IntoIterator<Item = (&'a str, i32, KafkaStartOffset)>,
+    timeout: Duration,
+) -> KafkaResult<TopicPartitionList> {
+    let mut tpl = TopicPartitionList::new();
+    let mut timestamps = TopicPartitionList::new();
+    for (topic, partition, start_offset) in partitions {
+        let offset = match start_offset {
+            KafkaStartOffset::Committed => rdkafka::Offset::Stored,
+            KafkaStartOffset::Earliest => rdkafka::Offset::Beginning,
+            KafkaStartOffset::Latest => rdkafka::Offset::End,
+            KafkaStartOffset::Offset { offset } => rdkafka::Offset::Offset(offset),
+            KafkaStartOffset::Timestamp { timestamp_ms } => {
+                timestamps.add_partition_offset(
+                    topic,
+                    partition,
+                    rdkafka::Offset::Offset(timestamp_ms),
+                )?;
+                continue;
+            }
+        };
+        tpl.add_partition_offset(topic, partition, offset)?;
+    }
+
+    if timestamps.count() > 0 {
+        for element in consumer.offsets_for_times(timestamps, timeout)?.elements() {
+            element.error()?;
+            tpl.add_partition_offset(element.topic(), element.partition(), element.offset())?;
+        }
+    }
+
+    Ok(tpl)
+}
+
 /// Kafka authentication configuration.
 #[configurable_component]
 #[derive(Clone, Debug, Default)]
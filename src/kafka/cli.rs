@@ -0,0 +1,184 @@
+#![allow(missing_docs)]
+use std::time::Duration;
+
+use clap::{ArgGroup, Parser};
+use rdkafka::{
+    ClientConfig, Offset, TopicPartitionList,
+    consumer::{BaseConsumer, CommitMode, Consumer},
+};
+
+use super::{KafkaStartOffset, start_offsets};
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+enum Command {
+    /// Reset the committed offsets of a Kafka consumer group.
+    ///
+    /// All the consumers of the group must be stopped, as Kafka rejects offset commits from
+    /// outside of a consumer group with active members.
+    Seek(SeekOpts),
+}
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+#[command(group(
+    ArgGroup::new("to")
+        .required(true)
+        .args(["to_earliest", "to_latest", "to_timestamp", "to_offset"])
+))]
+struct SeekOpts {
+    /// A comma-separated list of Kafka bootstrap servers, in the form of `host:port`.
+    #[arg(long)]
+    bootstrap_servers: String,
+
+    /// The consumer group to reset the offsets of.
+    #[arg(long)]
+    group_id: String,
+
+    /// The topic to reset the offsets of.
+    #[arg(long)]
+    topic: String,
+
+    /// The partitions to reset the offsets of. All the partitions of the topic by default.
+    #[arg(long, value_delimiter(','))]
+    partitions: Vec<i32>,
+
+    /// Reset the offsets to the earliest offsets retained in the partitions.
+    #[arg(long)]
+    to_earliest: bool,
+
+    /// Reset the offsets to the ends of the partitions.
+    #[arg(long)]
+    to_latest: bool,
+
+    /// Reset the offsets to the first messages with a timestamp equal to or later than the given
+    /// one, in milliseconds since the Unix epoch.
+    #[arg(long, value_name = "TIMESTAMP_MS")]
+    to_timestamp: Option<i64>,
+
+    /// Reset the offsets to the given absolute offset.
+    #[arg(long, value_name = "OFFSET")]
+    to_offset: Option<i64>,
+
+    /// An option set directly on the underlying librdkafka client, in the form of `key=value`.
+    /// Can be repeated, for example to configure authentication.
+    #[arg(short = 'X', long = "librdkafka-option", value_name = "KEY=VALUE", value_parser = parse_librdkafka_option)]
+    librdkafka_options: Vec<(String, String)>,
+
+    /// Timeout for requests to the Kafka brokers, in milliseconds.
+    #[arg(long, default_value = "10000")]
+    timeout_ms: u64,
+
+    /// Print the new offsets without committing them.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl SeekOpts {
+    fn start_offset(&self) -> KafkaStartOffset {
+        if self.to_earliest {
+            KafkaStartOffset::Earliest
+        } else if let Some(timestamp_ms) = self.to_timestamp {
+            KafkaStartOffset::Timestamp { timestamp_ms }
+        } else if let Some(offset) = self.to_offset {
+            KafkaStartOffset::Offset { offset }
+        } else {
+            KafkaStartOffset::Latest
+        }
+    }
+}
+
+fn parse_librdkafka_option(option: &str) -> Result<(String, String), String> {
+    option
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("Expected an option in the form of `key=value`, got {option:?}."))
+}
+
+pub(crate) fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match &opts.command {
+        Command::Seek(opts) => seek(opts),
+    }
+}
+
+#[allow(clippy::print_stderr)]
+fn seek(opts: &SeekOpts) -> exitcode::ExitCode {
+    match reset_offsets(opts) {
+        Ok(()) => exitcode::OK,
+        Err(error) => {
+            eprintln!("Failed to reset the consumer group offsets: {error}");
+            exitcode::UNAVAILABLE
+        }
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn reset_offsets(opts: &SeekOpts) -> crate::Result<()> {
+    let timeout = Duration::from_millis(opts.timeout_ms);
+
+    let mut client_config = ClientConfig::new();
+    client_config
+        .set("bootstrap.servers", &opts.bootstrap_servers)
+        .set("group.id", &opts.group_id)
+        .set("enable.auto.commit", "false")
+        .set("client.id", "vector");
+    for (key, value) in &opts.librdkafka_options {
+        client_config.set(key, value);
+    }
+    let consumer: BaseConsumer = client_config.create()?;
+
+    let partitions = if opts.partitions.is_empty() {
+        let metadata = consumer.fetch_metadata(Some(&opts.topic), timeout)?;
+        metadata
+            .topics()
+            .iter()
+            .flat_map(|topic| topic.partitions())
+            .map(|partition| partition.id())
+            .collect()
+    } else {
+        opts.partitions.clone()
+    };
+    if partitions.is_empty() {
+        return Err(format!("Topic {:?} has no partitions.", opts.topic).into());
+    }
+
+    let start_offset = opts.start_offset();
+    let tpl = start_offsets(
+        &consumer,
+        partitions
+            .iter()
+            .map(|&partition| (opts.topic.as_str(), partition, start_offset)),
+        timeout,
+    )?;
+
+    // Logical offsets can't be committed, so they are resolved from the partition watermarks.
+    let mut offsets = TopicPartitionList::new();
+    for element in tpl.elements() {
+        let offset = match element.offset() {
+            Offset::Offset(offset) => offset,
+            offset => {
+                let (low, high) =
+                    consumer.fetch_watermarks(&opts.topic, element.partition(), timeout)?;
+                if offset == Offset::Beginning {
+                    low
+                } else {
+                    high
+                }
+            }
+        };
+        println!("{}:{} {}", opts.topic, element.partition(), offset);
+        offsets.add_partition_offset(&opts.topic, element.partition(), Offset::Offset(offset))?;
+    }
+
+    if !opts.dry_run {
+        consumer.commit(&offsets, CommitMode::Sync)?;
+    }
+    Ok(())
+}
@@ -10,5 +10,6 @@
 pub mod meta_cache;
 pub mod pod_manager_logic;
 pub mod reflector;
+pub mod shared_state;
 
 pub use reflector::custom_reflector;
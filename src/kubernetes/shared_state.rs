@@ -0,0 +1,54 @@
+//! The state of the Kubernetes watchers of a component, shared with the other components so they
+//! can look up the metadata of the Pods without watching them again.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex, RwLock, Weak},
+};
+
+use k8s_openapi::api::core::v1::{Namespace, Pod};
+use kube::runtime::reflector::store::Store;
+
+/// The slots of the shared states, by the ID of the component sharing them.
+static SHARED_STATES: LazyLock<Mutex<HashMap<String, Arc<SharedStateSlot>>>> =
+    LazyLock::new(Default::default);
+
+/// The state of the Pod and Namespace watchers of a component.
+pub struct KubernetesState {
+    pub pods: Store<Pod>,
+    pub namespaces: Store<Namespace>,
+}
+
+/// The slot holding the state shared by a component, which is only set while the component is
+/// running.
+///
+/// The slot only holds a weak reference to the state, so that the state is dropped along with the
+/// component, and a restarted component can share its new state through the same slot.
+#[derive(Default)]
+pub struct SharedStateSlot(RwLock<Weak<KubernetesState>>);
+
+impl SharedStateSlot {
+    /// Returns the slot of the state shared by a component.
+    pub fn get(component_id: &str) -> Arc<Self> {
+        let mut states = SHARED_STATES.lock().expect("mutex should not be poisoned");
+        Arc::clone(states.entry(component_id.to_owned()).or_default())
+    }
+
+    /// Shares the state of a component, until the returned state is dropped.
+    pub fn share(component_id: &str, state: KubernetesState) -> Arc<KubernetesState> {
+        let state = Arc::new(state);
+        *Self::get(component_id)
+            .0
+            .write()
+            .expect("lock should not be poisoned") = Arc::downgrade(&state);
+        state
+    }
+
+    /// Returns the shared state, if the component sharing it is running.
+    pub fn load(&self) -> Option<Arc<KubernetesState>> {
+        self.0
+            .read()
+            .expect("lock should not be poisoned")
+            .upgrade()
+    }
+}
@@ -72,6 +72,9 @@ pub mod app;
 pub mod async_read;
 #[cfg(feature = "aws-config")]
 pub mod aws;
+pub mod buffer;
+#[cfg(any(feature = "sources-file", feature = "sources-kubernetes_logs"))]
+pub mod checkpoints;
 #[allow(unreachable_pub)]
 pub mod codecs;
 pub mod common;
@@ -127,6 +130,7 @@ pub(crate) mod utilization;
 pub mod validate;
 #[cfg(windows)]
 pub mod vector_windows;
+pub mod vrl_cli;
 
 pub use source_sender::SourceSender;
 pub use vector_lib::{Error, Result, event, metrics, schema, shutdown, tcp, tls};
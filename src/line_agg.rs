@@ -70,6 +70,29 @@ pub struct Config {
 }
 
 impl Config {
+    /// Decide whether a line continues the group started by a line matching `start_pattern`.
+    pub fn decide(&self, line: &[u8]) -> Decision {
+        let condition_matched = self.condition_pattern.is_match(line);
+        match (self.mode, condition_matched) {
+            // All consecutive lines matching this pattern are included in
+            // the group.
+            (Mode::ContinueThrough, true) => Decision::Continue,
+            (Mode::ContinueThrough, false) => Decision::EndExclude,
+            // All consecutive lines matching this pattern, plus one
+            // additional line, are included in the group.
+            (Mode::ContinuePast, true) => Decision::Continue,
+            (Mode::ContinuePast, false) => Decision::EndInclude,
+            // All consecutive lines not matching this pattern are included
+            // in the group.
+            (Mode::HaltBefore, true) => Decision::EndExclude,
+            (Mode::HaltBefore, false) => Decision::Continue,
+            // All consecutive lines, up to and including the first line
+            // matching this pattern, are included in the group.
+            (Mode::HaltWith, true) => Decision::EndInclude,
+            (Mode::HaltWith, false) => Decision::Continue,
+        }
+    }
+
     /// Build `Config` from legacy `file` source line aggregator configuration
     /// params.
     pub fn for_legacy(marker: Regex, timeout_ms: u64) -> Self {
@@ -289,10 +312,14 @@ pub enum Emit<T> {
     Two(T, T),
 }
 
-/// A helper enum
-enum Decision {
+/// What to do with a line following the start of a group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The line is part of the group, and more lines may follow.
     Continue,
+    /// The line is the last line of the group.
     EndInclude,
+    /// The group ended with the previous line, this line is not part of it.
     EndExclude,
 }
 
@@ -309,47 +336,25 @@ where
     ) -> Option<(K, Emit<(Bytes, C, Option<C>)>)> {
         // Check if we already have the buffered data for the source.
         match self.buffers.entry(src) {
-            Entry::Occupied(mut entry) => {
-                let condition_matched = self.config.condition_pattern.is_match(line.as_ref());
-                let decision = match (self.config.mode, condition_matched) {
-                    // All consecutive lines matching this pattern are included in
-                    // the group.
-                    (Mode::ContinueThrough, true) => Decision::Continue,
-                    (Mode::ContinueThrough, false) => Decision::EndExclude,
-                    // All consecutive lines matching this pattern, plus one
-                    // additional line, are included in the group.
-                    (Mode::ContinuePast, true) => Decision::Continue,
-                    (Mode::ContinuePast, false) => Decision::EndInclude,
-                    // All consecutive lines not matching this pattern are included
-                    // in the group.
-                    (Mode::HaltBefore, true) => Decision::EndExclude,
-                    (Mode::HaltBefore, false) => Decision::Continue,
-                    // All consecutive lines, up to and including the first line
-                    // matching this pattern, are included in the group.
-                    (Mode::HaltWith, true) => Decision::EndInclude,
-                    (Mode::HaltWith, false) => Decision::Continue,
-                };
-
-                match decision {
-                    Decision::Continue => {
-                        let buffered = entry.get_mut();
-                        self.timeouts.reset(&buffered.0, self.config.timeout);
-                        buffered.1.add_next_line(line, context);
-                        None
-                    }
-                    Decision::EndInclude => {
-                        let (src, (key, mut buffered)) = entry.remove_entry();
-                        self.timeouts.remove(&key);
-                        buffered.add_next_line(line, context);
-                        Some((src, Emit::One(buffered.merge())))
-                    }
-                    Decision::EndExclude => {
-                        let (src, (key, buffered)) = entry.remove_entry();
-                        self.timeouts.remove(&key);
-                        Some((src, Emit::Two(buffered.merge(), (line, context, None))))
-                    }
+            Entry::Occupied(mut entry) => match self.config.decide(line.as_ref()) {
+                Decision::Continue => {
+                    let buffered = entry.get_mut();
+                    self.timeouts.reset(&buffered.0, self.config.timeout);
+                    buffered.1.add_next_line(line, context);
+                    None
                 }
-            }
+                Decision::EndInclude => {
+                    let (src, (key, mut buffered)) = entry.remove_entry();
+                    self.timeouts.remove(&key);
+                    buffered.add_next_line(line, context);
+                    Some((src, Emit::One(buffered.merge())))
+                }
+                Decision::EndExclude => {
+                    let (src, (key, buffered)) = entry.remove_entry();
+                    self.timeouts.remove(&key);
+                    Some((src, Emit::Two(buffered.merge(), (line, context, None))))
+                }
+            },
             Entry::Vacant(entry) => {
                 // This line is a candidate for buffering, or passing through.
                 if self.config.start_pattern.is_match(line.as_ref()) {
@@ -184,6 +184,73 @@ pub(crate) fn from_tls_auth_config(
     }
 }
 
+/// The storage backend of a JetStream stream.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NatsStreamStorage {
+    /// The messages are stored on disk.
+    #[default]
+    File,
+
+    /// The messages are stored in memory.
+    Memory,
+}
+
+/// Settings of the JetStream stream, created if it doesn't exist.
+///
+/// An existing stream is used as is, even if its settings differ.
+#[configurable_component]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NatsStreamConfig {
+    /// The subjects whose messages are stored in the stream.
+    ///
+    /// By default, this is the `subject` of the component.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "events.>"))]
+    pub subjects: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub storage: NatsStreamStorage,
+
+    /// The maximum age of the messages kept in the stream, in seconds.
+    ///
+    /// By default, the messages are kept until other limits of the stream are reached.
+    #[configurable(metadata(docs::human_name = "Maximum Age"))]
+    pub max_age_secs: Option<u64>,
+}
+
+impl NatsStreamConfig {
+    pub(crate) fn to_stream_config(
+        &self,
+        name: &str,
+        default_subject: &str,
+    ) -> async_nats::jetstream::stream::Config {
+        let subjects = if self.subjects.is_empty() {
+            vec![default_subject.to_owned()]
+        } else {
+            self.subjects.clone()
+        };
+        let storage = match self.storage {
+            NatsStreamStorage::File => async_nats::jetstream::stream::StorageType::File,
+            NatsStreamStorage::Memory => async_nats::jetstream::stream::StorageType::Memory,
+        };
+
+        async_nats::jetstream::stream::Config {
+            name: name.to_owned(),
+            subjects,
+            storage,
+            max_age: self
+                .max_age_secs
+                .map(std::time::Duration::from_secs)
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,4 +394,36 @@ mod tests {
         )
         .unwrap_err();
     }
+
+    #[test]
+    fn stream_config_defaults_to_the_subject() {
+        let config: NatsStreamConfig = toml::from_str("").unwrap();
+        let stream = config.to_stream_config("EVENTS", "events.>");
+        assert_eq!(stream.name, "EVENTS");
+        assert_eq!(stream.subjects, vec!["events.>".to_owned()]);
+        assert_eq!(
+            stream.storage,
+            async_nats::jetstream::stream::StorageType::File
+        );
+        assert_eq!(stream.max_age, std::time::Duration::ZERO);
+
+        let config: NatsStreamConfig = toml::from_str(
+            r#"
+            subjects = ["logs.>", "metrics.>"]
+            storage = "memory"
+            max_age_secs = 3600
+            "#,
+        )
+        .unwrap();
+        let stream = config.to_stream_config("EVENTS", "events.>");
+        assert_eq!(
+            stream.subjects,
+            vec!["logs.>".to_owned(), "metrics.>".to_owned()]
+        );
+        assert_eq!(
+            stream.storage,
+            async_nats::jetstream::stream::StorageType::Memory
+        );
+        assert_eq!(stream.max_age, std::time::Duration::from_secs(3600));
+    }
 }
@@ -1,8 +1,12 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use aws_sdk_secretsmanager::{Client, config};
 use vector_lib::configurable::{component::GenerateConfig, configurable_component};
 
+use super::cache;
 use crate::{
     aws::{AwsAuthentication, ClientBuilder, RegionOrEndpoint, create_client},
     config::{ProxyConfig, SecretBackend},
@@ -38,6 +42,14 @@ pub struct AwsSecretsManagerBackend {
 
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
+
+    /// How long to cache secrets for, in seconds.
+    ///
+    /// The cache is kept across configuration reloads, so that reloading doesn't fetch secrets again
+    /// until they expire. By default, secrets aren't cached.
+    #[configurable(metadata(docs::examples = 300))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub cache_ttl_secs: Option<u64>,
 }
 
 impl GenerateConfig for AwsSecretsManagerBackend {
@@ -47,17 +59,14 @@ impl GenerateConfig for AwsSecretsManagerBackend {
             region: Default::default(),
             auth: Default::default(),
             tls: None,
+            cache_ttl_secs: None,
         })
         .unwrap()
     }
 }
 
-impl SecretBackend for AwsSecretsManagerBackend {
-    async fn retrieve(
-        &mut self,
-        secret_keys: HashSet<String>,
-        _: &mut signal::SignalRx,
-    ) -> crate::Result<HashMap<String, String>> {
+impl AwsSecretsManagerBackend {
+    async fn fetch_secret_string(&self) -> crate::Result<String> {
         let client = create_client::<SecretsManagerClientBuilder>(
             &SecretsManagerClientBuilder {},
             &self.auth,
@@ -82,6 +91,28 @@ impl SecretBackend for AwsSecretsManagerBackend {
                 &self.secret_id
             ))?;
 
+        Ok(secret_string)
+    }
+}
+
+impl SecretBackend for AwsSecretsManagerBackend {
+    async fn retrieve(
+        &mut self,
+        secret_keys: HashSet<String>,
+        _: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        let cache_key = cache::aws_key("aws_secrets_manager", &self.region, &self.secret_id);
+        let secret_string = match self.cache_ttl_secs.and_then(|_| cache::get(&cache_key)) {
+            Some(secret_string) => secret_string,
+            None => {
+                let secret_string = self.fetch_secret_string().await?;
+                if let Some(ttl) = self.cache_ttl_secs {
+                    cache::insert(cache_key, secret_string.clone(), Duration::from_secs(ttl));
+                }
+                secret_string
+            }
+        };
+
         let output = serde_json::from_str::<HashMap<String, String>>(secret_string.as_str())?;
 
         let mut secrets = HashMap::new();
@@ -0,0 +1,161 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use aws_sdk_ssm::{Client, config};
+use vector_lib::configurable::{component::GenerateConfig, configurable_component};
+
+use super::cache;
+use crate::{
+    aws::{AwsAuthentication, ClientBuilder, RegionOrEndpoint, create_client},
+    config::{ProxyConfig, SecretBackend},
+    signal,
+    tls::TlsConfig,
+};
+
+/// The maximum number of parameters a single `GetParameters` request can resolve.
+const MAX_PARAMETERS_PER_REQUEST: usize = 10;
+
+pub(crate) struct SsmClientBuilder;
+
+impl ClientBuilder for SsmClientBuilder {
+    type Client = Client;
+
+    fn build(&self, config: &aws_types::SdkConfig) -> Self::Client {
+        let config = config::Builder::from(config).build();
+        Client::from_conf(config)
+    }
+}
+
+/// Configuration for the `aws_ssm_parameter_store` secrets backend.
+///
+/// Each secret key is resolved from the parameter with the same name, prefixed with `prefix`.
+/// `SecureString` parameters are decrypted.
+#[configurable_component(secrets("aws_ssm_parameter_store"))]
+#[derive(Clone, Debug)]
+pub struct AwsSsmParameterStoreBackend {
+    /// A prefix added to secret keys to build the names of the parameters to resolve.
+    ///
+    /// As secret keys can't contain `/`, this is required to resolve parameters in a hierarchy.
+    #[configurable(metadata(docs::examples = "/vector/production/"))]
+    #[serde(default)]
+    pub prefix: String,
+
+    #[serde(flatten)]
+    #[configurable(derived)]
+    pub region: RegionOrEndpoint,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub auth: AwsAuthentication,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+
+    /// How long to cache secrets for, in seconds.
+    ///
+    /// The cache is kept across configuration reloads, so that reloading doesn't fetch secrets again
+    /// until they expire. By default, secrets aren't cached.
+    #[configurable(metadata(docs::examples = 300))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub cache_ttl_secs: Option<u64>,
+}
+
+impl GenerateConfig for AwsSsmParameterStoreBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(AwsSsmParameterStoreBackend {
+            prefix: String::from("/vector/"),
+            region: Default::default(),
+            auth: Default::default(),
+            tls: None,
+            cache_ttl_secs: None,
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for AwsSsmParameterStoreBackend {
+    async fn retrieve(
+        &mut self,
+        secret_keys: HashSet<String>,
+        _: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        let mut secrets = HashMap::new();
+        let mut keys_by_name = HashMap::new();
+        for k in secret_keys.into_iter() {
+            let name = format!("{}{k}", self.prefix);
+            match self
+                .cache_ttl_secs
+                .and_then(|_| cache::get(&self.cache_key(&name)))
+            {
+                Some(secret) => {
+                    secrets.insert(k, secret);
+                }
+                None => {
+                    keys_by_name.insert(name, k);
+                }
+            }
+        }
+
+        if keys_by_name.is_empty() {
+            return Ok(secrets);
+        }
+
+        let client = create_client::<SsmClientBuilder>(
+            &SsmClientBuilder {},
+            &self.auth,
+            self.region.region(),
+            self.region.endpoint(),
+            &ProxyConfig::default(),
+            self.tls.as_ref(),
+            None,
+        )
+        .await?;
+
+        let names = keys_by_name.keys().cloned().collect::<Vec<_>>();
+        for names in names.chunks(MAX_PARAMETERS_PER_REQUEST) {
+            let output = client
+                .get_parameters()
+                .set_names(Some(names.to_vec()))
+                .with_decryption(true)
+                .send()
+                .await?;
+
+            if let Some(invalid) = output.invalid_parameters.filter(|names| !names.is_empty()) {
+                return Err(format!("parameters '{}' do not exist", invalid.join("', '")).into());
+            }
+
+            for parameter in output.parameters.unwrap_or_default() {
+                let (Some(name), Some(secret)) = (parameter.name, parameter.value) else {
+                    continue;
+                };
+                let Some(k) = keys_by_name.remove(&name) else {
+                    continue;
+                };
+                if secret.is_empty() {
+                    return Err(format!("value of parameter '{name}' was empty").into());
+                }
+                if let Some(ttl) = self.cache_ttl_secs {
+                    cache::insert(
+                        self.cache_key(&name),
+                        secret.clone(),
+                        Duration::from_secs(ttl),
+                    );
+                }
+                secrets.insert(k, secret);
+            }
+        }
+
+        if let Some(name) = keys_by_name.keys().next() {
+            return Err(format!("parameter '{name}' could not be retrieved").into());
+        }
+        Ok(secrets)
+    }
+}
+
+impl AwsSsmParameterStoreBackend {
+    fn cache_key(&self, name: &str) -> String {
+        cache::aws_key("aws_ssm_parameter_store", &self.region, name)
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::aws::RegionOrEndpoint;
+
+/// Secret values cached by backends with a cache TTL. The cache is shared by the whole process, so
+/// that reloading the configuration doesn't fetch the same secrets again until they expire.
+static CACHE: LazyLock<Mutex<HashMap<String, CachedSecret>>> = LazyLock::new(Default::default);
+
+struct CachedSecret {
+    value: String,
+    expires_at: Instant,
+}
+
+/// Builds the cache key of a secret stored in an AWS service.
+pub(super) fn aws_key(service: &str, region: &RegionOrEndpoint, id: &str) -> String {
+    format!(
+        "{service}/{}/{}/{id}",
+        region.region.as_deref().unwrap_or_default(),
+        region.endpoint.as_deref().unwrap_or_default(),
+    )
+}
+
+/// Returns the cached value of a secret, unless it expired.
+pub(super) fn get(key: &str) -> Option<String> {
+    let mut cache = CACHE.lock().expect("mutex poisoned");
+    match cache.get(key) {
+        Some(cached) if cached.expires_at > Instant::now() => Some(cached.value.clone()),
+        Some(_) => {
+            cache.remove(key);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Caches the value of a secret for the given time to live.
+pub(super) fn insert(key: String, value: String, ttl: Duration) {
+    let expires_at = Instant::now() + ttl;
+    CACHE
+        .lock()
+        .expect("mutex poisoned")
+        .insert(key, CachedSecret { value, expires_at });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_secrets_expire() {
+        insert(
+            "cache-test/fresh".into(),
+            "value".into(),
+            Duration::from_secs(60),
+        );
+        insert("cache-test/expired".into(), "value".into(), Duration::ZERO);
+
+        assert_eq!(get("cache-test/fresh").as_deref(), Some("value"));
+        assert_eq!(get("cache-test/expired"), None);
+        assert_eq!(get("cache-test/missing"), None);
+    }
+
+    #[test]
+    fn aws_keys_include_region_and_endpoint() {
+        let region = RegionOrEndpoint::with_region("us-east-1".into());
+        let endpoint = RegionOrEndpoint::with_both("us-east-1", "http://localhost:4566");
+
+        assert_ne!(
+            aws_key("aws_secrets_manager", &region, "secret"),
+            aws_key("aws_secrets_manager", &endpoint, "secret")
+        );
+    }
+}
@@ -11,10 +11,19 @@ use crate::{
 
 #[cfg(feature = "secrets-aws-secrets-manager")]
 mod aws_secrets_manager;
+#[cfg(feature = "secrets-aws-ssm-parameter-store")]
+mod aws_ssm_parameter_store;
+#[cfg(any(
+    feature = "secrets-aws-secrets-manager",
+    feature = "secrets-aws-ssm-parameter-store"
+))]
+mod cache;
 mod directory;
 mod exec;
 mod file;
 mod test;
+#[cfg(feature = "secrets-vault")]
+mod vault;
 
 ///	Configuration options to retrieve secrets from external backend in order to avoid storing secrets in plaintext
 /// in Vector config. Multiple backends can be configured. Use `SECRET[<backend_name>.<secret_key>]` to tell Vector to retrieve the secret. This placeholder is replaced by the secret
@@ -50,6 +59,9 @@ mod test;
 ///
 /// Secrets are loaded when Vector starts or if Vector receives a `SIGHUP` signal triggering its
 /// configuration reload process.
+///
+/// When `type` is `vault`, the secret is also checked for changes periodically, and the configuration
+/// is reloaded when a secret referenced in it rotates.
 #[allow(clippy::large_enum_variant)]
 #[configurable_component(global_option("secret"))]
 #[derive(Clone, Debug)]
@@ -74,6 +86,14 @@ pub enum SecretBackends {
     #[cfg(feature = "secrets-aws-secrets-manager")]
     AwsSecretsManager(aws_secrets_manager::AwsSecretsManagerBackend),
 
+    /// AWS SSM Parameter Store.
+    #[cfg(feature = "secrets-aws-ssm-parameter-store")]
+    AwsSsmParameterStore(aws_ssm_parameter_store::AwsSsmParameterStoreBackend),
+
+    /// HashiCorp Vault.
+    #[cfg(feature = "secrets-vault")]
+    Vault(vault::VaultBackend),
+
     /// Test.
     #[configurable(metadata(docs::hidden))]
     Test(test::TestBackend),
@@ -0,0 +1,632 @@
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU64,
+    time::{Duration, Instant},
+};
+
+use futures::stream::BoxStream;
+use http::{Method, Request, StatusCode};
+use hyper::Body;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+use snafu::{ResultExt, Snafu};
+use vector_lib::{
+    configurable::{component::GenerateConfig, configurable_component},
+    sensitive_string::SensitiveString,
+};
+
+use crate::{
+    config::{ProxyConfig, SecretBackend},
+    http::{HttpClient, HttpError},
+    signal,
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// Configuration for the `vault` secrets backend.
+///
+/// Secrets are read from a single secret of a [KV version 2][kv_v2] secrets engine. The keys of
+/// that secret are the secret keys used in `SECRET[<backend_name>.<secret_key>]`.
+///
+/// [kv_v2]: https://developer.hashicorp.com/vault/docs/secrets/kv/kv-v2
+#[configurable_component(secrets("vault"))]
+#[derive(Clone, Debug)]
+pub struct VaultBackend {
+    /// The address of the Vault server.
+    #[configurable(metadata(docs::examples = "https://vault.example.com:8200"))]
+    pub address: String,
+
+    /// The Vault Enterprise namespace to send requests to.
+    #[configurable(metadata(docs::examples = "admin/observability"))]
+    pub namespace: Option<String>,
+
+    /// The mount path of the KV version 2 secrets engine.
+    #[serde(default = "default_mount")]
+    pub mount: String,
+
+    /// The path of the secret to read, relative to the mount path of the secrets engine.
+    #[configurable(metadata(docs::examples = "vector/production"))]
+    pub secret_path: String,
+
+    #[configurable(derived)]
+    pub authentication: VaultAuthentication,
+
+    /// How often to check the secret for changes and to renew the Vault token, in seconds.
+    ///
+    /// This must be shorter than the TTL of the Vault token, so that it's renewed before it
+    /// expires.
+    #[serde(default = "default_poll_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub poll_interval_secs: NonZeroU64,
+
+    /// Whether to reload the configuration when a secret referenced in it changes in Vault.
+    #[serde(default = "crate::serde::default_true")]
+    pub reload_on_rotation: bool,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+}
+
+/// The strategy used to authenticate against Vault.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+#[configurable(metadata(docs::enum_tag_description = "The authentication strategy to use."))]
+pub enum VaultAuthentication {
+    /// Authenticate with a Vault token.
+    ///
+    /// The token is renewed periodically, if it's renewable.
+    Token {
+        /// The Vault token.
+        #[configurable(metadata(docs::examples = "${VAULT_TOKEN}"))]
+        token: SensitiveString,
+    },
+
+    /// Authenticate with the [AppRole][approle] auth method.
+    ///
+    /// The token obtained by logging in is renewed periodically, and Vector logs in again once it
+    /// can't be renewed anymore.
+    ///
+    /// [approle]: https://developer.hashicorp.com/vault/docs/auth/approle
+    #[serde(rename = "approle")]
+    AppRole {
+        /// The role ID of the AppRole.
+        role_id: String,
+
+        /// The secret ID of the AppRole.
+        #[configurable(metadata(docs::examples = "${VAULT_SECRET_ID}"))]
+        secret_id: SensitiveString,
+
+        /// The mount path of the AppRole auth method.
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+    },
+}
+
+fn default_mount() -> String {
+    "secret".to_string()
+}
+
+fn default_approle_mount() -> String {
+    "approle".to_string()
+}
+
+const fn default_poll_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(60).unwrap()
+}
+
+impl GenerateConfig for VaultBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(VaultBackend {
+            address: "https://vault.example.com:8200".to_string(),
+            namespace: None,
+            mount: default_mount(),
+            secret_path: "vector".to_string(),
+            authentication: VaultAuthentication::Token {
+                token: "${VAULT_TOKEN}".to_string().into(),
+            },
+            poll_interval_secs: default_poll_interval_secs(),
+            reload_on_rotation: true,
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for VaultBackend {
+    async fn retrieve(
+        &mut self,
+        secret_keys: HashSet<String>,
+        _: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        let mut client = VaultClient::new(self)?;
+        let data = client.read_secret(&self.mount, &self.secret_path).await?;
+
+        let mut secrets = HashMap::new();
+        for k in secret_keys.into_iter() {
+            match data.get(&k) {
+                Some(Value::String(secret)) if !secret.is_empty() => {
+                    secrets.insert(k, secret.clone());
+                }
+                Some(Value::String(_)) => {
+                    return Err(format!(
+                        "value for key '{k}' in secret '{}' was empty",
+                        self.secret_path
+                    )
+                    .into());
+                }
+                Some(_) => {
+                    return Err(format!(
+                        "value for key '{k}' in secret '{}' is not a string",
+                        self.secret_path
+                    )
+                    .into());
+                }
+                None => {
+                    return Err(format!(
+                        "key '{k}' in secret '{}' does not exist",
+                        self.secret_path
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(secrets)
+    }
+
+    fn watch(
+        &self,
+        mut secrets: HashMap<String, String>,
+    ) -> Option<BoxStream<'static, signal::SignalTo>> {
+        let mut client = VaultClient::new(self)
+            .map_err(|error| error!(message = "Failed to watch secrets in Vault.", %error))
+            .ok()?;
+        let backend = self.clone();
+
+        Some(Box::pin(async_stream::stream! {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(backend.poll_interval_secs.get()));
+            // The first tick completes immediately, and the secrets were just retrieved.
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let data = match client.read_secret(&backend.mount, &backend.secret_path).await {
+                    Ok(data) => data,
+                    Err(error) => {
+                        warn!(
+                            message = "Failed to check secret in Vault for changes.",
+                            secret_path = %backend.secret_path,
+                            %error,
+                        );
+                        continue;
+                    }
+                };
+
+                if !update_secrets(&mut secrets, &data) {
+                    continue;
+                }
+
+                if backend.reload_on_rotation {
+                    info!(
+                        message = "Secret changed in Vault, reloading configuration.",
+                        secret_path = %backend.secret_path,
+                    );
+                    yield signal::SignalTo::ReloadSecrets;
+                } else {
+                    info!(
+                        message = "Secret changed in Vault, the new value is used on the next configuration reload.",
+                        secret_path = %backend.secret_path,
+                    );
+                }
+            }
+        }))
+    }
+}
+
+/// Updates the secrets to their values in the given secret data, returning whether any of them
+/// changed. Secrets no longer in the secret data are removed, so that they are only reported once.
+fn update_secrets(secrets: &mut HashMap<String, String>, data: &HashMap<String, Value>) -> bool {
+    let mut changed = false;
+    secrets.retain(|key, value| match data.get(key) {
+        Some(Value::String(new_value)) => {
+            if new_value != value {
+                value.clone_from(new_value);
+                changed = true;
+            }
+            true
+        }
+        _ => {
+            changed = true;
+            false
+        }
+    });
+    changed
+}
+
+#[derive(Debug, Snafu)]
+enum VaultError {
+    #[snafu(display("Failed to build Vault request: {source}"))]
+    BuildRequest { source: http::Error },
+    #[snafu(display("Failed to send Vault request: {source}"))]
+    SendRequest { source: HttpError },
+    #[snafu(display("Failed to read Vault response: {source}"))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Vault request to '{path}' failed with status {status}: {body}"))]
+    UnexpectedStatus {
+        path: String,
+        status: StatusCode,
+        body: String,
+    },
+    #[snafu(display("Failed to parse Vault response from '{path}': {source}"))]
+    ParseResponse {
+        path: String,
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Deserialize)]
+struct AuthResponse {
+    auth: AuthData,
+}
+
+#[derive(Deserialize)]
+struct AuthData {
+    client_token: String,
+    lease_duration: u64,
+    renewable: bool,
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    data: LookupData,
+}
+
+#[derive(Deserialize)]
+struct LookupData {
+    ttl: u64,
+    renewable: bool,
+}
+
+#[derive(Deserialize)]
+struct KvResponse {
+    data: KvData,
+}
+
+#[derive(Deserialize)]
+struct KvData {
+    data: HashMap<String, Value>,
+}
+
+/// A Vault token, along with when it's due for renewal.
+struct Token {
+    value: SensitiveString,
+    renew_at: Option<Instant>,
+}
+
+impl Token {
+    fn new(value: SensitiveString, renewable: bool, ttl_secs: u64) -> Self {
+        // Tokens with a TTL of zero, such as root tokens, never expire.
+        let renew_at =
+            (renewable && ttl_secs > 0).then(|| Instant::now() + Duration::from_secs(ttl_secs) / 2);
+        Self { value, renew_at }
+    }
+}
+
+impl From<AuthData> for Token {
+    fn from(auth: AuthData) -> Self {
+        Self::new(
+            auth.client_token.into(),
+            auth.renewable,
+            auth.lease_duration,
+        )
+    }
+}
+
+struct VaultClient {
+    client: HttpClient,
+    address: String,
+    namespace: Option<String>,
+    authentication: VaultAuthentication,
+    token: Option<Token>,
+}
+
+impl VaultClient {
+    fn new(backend: &VaultBackend) -> crate::Result<Self> {
+        let tls = TlsSettings::from_options(backend.tls.as_ref())?;
+        Ok(Self {
+            client: HttpClient::new(tls, &ProxyConfig::default())?,
+            address: backend.address.trim_end_matches('/').to_string(),
+            namespace: backend.namespace.clone(),
+            authentication: backend.authentication.clone(),
+            token: None,
+        })
+    }
+
+    /// Reads the data of a KV version 2 secret.
+    async fn read_secret(
+        &mut self,
+        mount: &str,
+        path: &str,
+    ) -> Result<HashMap<String, Value>, VaultError> {
+        let token = self.token().await?;
+        let response: KvResponse = self
+            .request(
+                Method::GET,
+                &format!("{mount}/data/{path}"),
+                Some(&token),
+                None,
+            )
+            .await?;
+        Ok(response.data.data)
+    }
+
+    /// Returns a valid token, logging in or renewing the current token as needed.
+    async fn token(&mut self) -> Result<String, VaultError> {
+        let renew = match &self.token {
+            Some(token) => token
+                .renew_at
+                .is_some_and(|renew_at| Instant::now() >= renew_at),
+            None => {
+                self.token = Some(self.login().await?);
+                false
+            }
+        };
+
+        if renew {
+            match self.renew().await {
+                Ok(token) => self.token = Some(token),
+                Err(error) => {
+                    // Tokens can't be renewed past their maximum TTL, after which logging in
+                    // again is the only way to get a valid token.
+                    warn!(message = "Failed to renew Vault token, logging in again.", %error);
+                    self.token = Some(self.login().await?);
+                }
+            }
+        }
+
+        Ok(self
+            .token
+            .as_ref()
+            .expect("token should be set")
+            .value
+            .inner()
+            .to_string())
+    }
+
+    async fn login(&self) -> Result<Token, VaultError> {
+        match &self.authentication {
+            VaultAuthentication::Token { token } => {
+                let response: LookupResponse = self
+                    .request(
+                        Method::GET,
+                        "auth/token/lookup-self",
+                        Some(token.inner()),
+                        None,
+                    )
+                    .await?;
+                Ok(Token::new(
+                    token.clone(),
+                    response.data.renewable,
+                    response.data.ttl,
+                ))
+            }
+            VaultAuthentication::AppRole {
+                role_id,
+                secret_id,
+                mount,
+            } => {
+                let body = serde_json::json!({
+                    "role_id": role_id,
+                    "secret_id": secret_id.inner(),
+                });
+                let response: AuthResponse = self
+                    .request(
+                        Method::POST,
+                        &format!("auth/{mount}/login"),
+                        None,
+                        Some(body),
+                    )
+                    .await?;
+                Ok(response.auth.into())
+            }
+        }
+    }
+
+    async fn renew(&self) -> Result<Token, VaultError> {
+        let token = self.token.as_ref().map(|token| token.value.inner());
+        let response: AuthResponse = self
+            .request(
+                Method::POST,
+                "auth/token/renew-self",
+                token,
+                Some(serde_json::json!({})),
+            )
+            .await?;
+        Ok(response.auth.into())
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> Result<T, VaultError> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(format!("{}/v1/{path}", self.address));
+        if let Some(namespace) = &self.namespace {
+            builder = builder.header("X-Vault-Namespace", namespace);
+        }
+        if let Some(token) = token {
+            builder = builder.header("X-Vault-Token", token);
+        }
+        let request = builder
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .context(BuildRequestSnafu)?;
+
+        let response = self.client.send(request).await.context(SendRequestSnafu)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadResponseSnafu)?;
+
+        if !status.is_success() {
+            return Err(VaultError::UnexpectedStatus {
+                path: path.to_string(),
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+
+        serde_json::from_slice(&body).context(ParseResponseSnafu { path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    use futures::StreamExt;
+    use tokio::sync::broadcast;
+    use warp::Filter;
+
+    use super::*;
+    use crate::test_util::next_addr;
+
+    /// Serves a mock Vault server with the AppRole auth method and a KV version 2 secret.
+    fn mock_vault(secret: Arc<Mutex<Value>>) -> SocketAddr {
+        let address = next_addr();
+
+        let login = warp::post()
+            .and(warp::path!("v1" / "auth" / "approle" / "login"))
+            .and(warp::body::json())
+            .map(|body: Value| {
+                assert_eq!(body["role_id"], "vector");
+                assert_eq!(body["secret_id"], "secret-id");
+                warp::reply::json(&serde_json::json!({
+                    "auth": {
+                        "client_token": "approle-token",
+                        "lease_duration": 3600,
+                        "renewable": true,
+                    },
+                }))
+            });
+        let read = warp::get()
+            .and(warp::path!("v1" / "secret" / "data" / "vector"))
+            .and(warp::header::exact("X-Vault-Token", "approle-token"))
+            .map(move || {
+                warp::reply::json(&serde_json::json!({
+                    "data": {
+                        "data": secret.lock().unwrap().clone(),
+                        "metadata": { "version": 1 },
+                    },
+                }))
+            });
+
+        tokio::spawn(warp::serve(login.or(read)).bind(address));
+        address
+    }
+
+    fn backend(address: SocketAddr) -> VaultBackend {
+        VaultBackend {
+            address: format!("http://{address}/"),
+            namespace: None,
+            mount: default_mount(),
+            secret_path: "vector".to_string(),
+            authentication: VaultAuthentication::AppRole {
+                role_id: "vector".to_string(),
+                secret_id: "secret-id".to_string().into(),
+                mount: default_approle_mount(),
+            },
+            poll_interval_secs: NonZeroU64::new(1).unwrap(),
+            reload_on_rotation: true,
+            tls: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn retrieves_secrets_with_approle() {
+        let secret = serde_json::json!({ "username": "vector", "password": "hunter2" });
+        let address = mock_vault(Arc::new(Mutex::new(secret)));
+
+        let (_tx, mut rx) = broadcast::channel(1);
+        let secrets = backend(address)
+            .retrieve(HashSet::from(["password".to_string()]), &mut rx)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            secrets,
+            HashMap::from([("password".to_string(), "hunter2".to_string())])
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_or_invalid_secret_keys() {
+        let secret = serde_json::json!({ "empty": "", "number": 1 });
+        let address = mock_vault(Arc::new(Mutex::new(secret)));
+
+        let (_tx, mut rx) = broadcast::channel(1);
+        for key in ["empty", "number", "missing"] {
+            let result = backend(address)
+                .retrieve(HashSet::from([key.to_string()]), &mut rx)
+                .await;
+            assert!(result.is_err(), "key '{key}' should fail");
+        }
+    }
+
+    #[tokio::test]
+    async fn reloads_when_secret_rotates() {
+        let secret = Arc::new(Mutex::new(serde_json::json!({ "password": "hunter2" })));
+        let address = mock_vault(Arc::clone(&secret));
+
+        let secrets = HashMap::from([("password".to_string(), "hunter2".to_string())]);
+        let mut signals = backend(address).watch(secrets).unwrap();
+
+        *secret.lock().unwrap() = serde_json::json!({ "password": "correct-horse" });
+        let signal = tokio::time::timeout(Duration::from_secs(5), signals.next())
+            .await
+            .unwrap();
+        assert_eq!(signal, Some(signal::SignalTo::ReloadSecrets));
+    }
+
+    #[test]
+    fn update_secrets_reports_changes_once() {
+        let mut secrets = HashMap::from([
+            ("username".to_string(), "vector".to_string()),
+            ("password".to_string(), "hunter2".to_string()),
+        ]);
+        let data: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "username": "vector",
+            "password": "correct-horse",
+        }))
+        .unwrap();
+
+        assert!(update_secrets(&mut secrets, &data));
+        assert_eq!(secrets["password"], "correct-horse");
+        assert!(!update_secrets(&mut secrets, &data));
+
+        assert!(update_secrets(&mut secrets, &HashMap::new()));
+        assert!(secrets.is_empty());
+    }
+
+    #[test]
+    fn rejects_zero_poll_interval() {
+        let error = toml::from_str::<VaultBackend>(
+            r#"
+            address = "https://vault.example.com:8200"
+            secret_path = "vector"
+            poll_interval_secs = 0
+            authentication.strategy = "token"
+            authentication.token = "token"
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("nonzero"), "{error}");
+    }
+}
@@ -6,7 +6,7 @@ use snafu::Snafu;
 use tokio::{runtime::Runtime, sync::broadcast};
 use tokio_stream::{Stream, StreamExt};
 
-use super::config::{ComponentKey, ConfigBuilder};
+use super::config::{ComponentKey, ConfigBuilder, Format};
 
 pub type ShutdownTx = broadcast::Sender<()>;
 pub type SignalTx = broadcast::Sender<SignalTo>;
@@ -20,8 +20,14 @@ pub enum SignalTo {
     ReloadComponents(HashSet<ComponentKey>),
     /// Signal to reload config from a string.
     ReloadFromConfigBuilder(ConfigBuilder),
+    /// Signal to reload config from the given input, which replaces the config files until they
+    /// are reloaded.
+    ReloadFromInput(String, Format),
     /// Signal to reload config from the filesystem and reload components with external files.
     ReloadFromDisk,
+    /// Signal to retrieve the secrets of the running config again, and reload it with their new
+    /// values.
+    ReloadSecrets,
     /// Signal to reload all enrichment tables.
     ReloadEnrichmentTables,
     /// Signal to shutdown process.
@@ -38,7 +44,11 @@ impl PartialEq for SignalTo {
             (ReloadComponents(a), ReloadComponents(b)) => a == b,
             // TODO: This will require a lot of plumbing but ultimately we can derive equality for config builders.
             (ReloadFromConfigBuilder(_), ReloadFromConfigBuilder(_)) => true,
+            (ReloadFromInput(a, a_format), ReloadFromInput(b, b_format)) => {
+                a == b && a_format == b_format
+            }
             (ReloadFromDisk, ReloadFromDisk) => true,
+            (ReloadSecrets, ReloadSecrets) => true,
             (ReloadEnrichmentTables, ReloadEnrichmentTables) => true,
             (Shutdown(a), Shutdown(b)) => a == b,
             (Quit, Quit) => true,
@@ -92,6 +92,17 @@ pub struct AmqpSinkConfig {
     /// Maximum number of AMQP channels to keep active (channels are created as needed).
     #[serde(default = "default_max_channels")]
     pub(crate) max_channels: u32,
+
+    /// Publish the messages with the `mandatory` flag.
+    ///
+    /// The messages that can't be routed to any queue are returned by the broker instead of being
+    /// dropped, and their events are rejected.
+    #[serde(default)]
+    pub(crate) mandatory: bool,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) request: TowerRequestConfig,
 }
 
 const fn default_max_channels() -> u32 {
@@ -108,6 +119,8 @@ impl Default for AmqpSinkConfig {
             connection: AmqpConfig::default(),
             acknowledgements: AcknowledgementsConfig::default(),
             max_channels: default_max_channels(),
+            mandatory: false,
+            request: TowerRequestConfig::default(),
         }
     }
 }
@@ -4,7 +4,7 @@ use std::task::{Context, Poll};
 
 use bytes::Bytes;
 use futures::future::BoxFuture;
-use lapin::{BasicProperties, options::BasicPublishOptions};
+use lapin::{BasicProperties, options::BasicPublishOptions, publisher_confirm::Confirmation};
 use snafu::Snafu;
 
 use super::channel::AmqpSinkChannels;
@@ -12,6 +12,7 @@ use crate::sinks::prelude::*;
 
 /// The request contains the data to send to `AMQP` together
 /// with the information need to route the message.
+#[derive(Clone)]
 pub(super) struct AmqpRequest {
     body: Bytes,
     exchange: String,
@@ -57,15 +58,16 @@ impl MetaDescriptive for AmqpRequest {
     }
 }
 
-/// A successful response from `AMQP`.
+/// A response from `AMQP`, once the broker confirmed the message.
 pub(super) struct AmqpResponse {
     byte_size: usize,
     json_size: GroupedCountByteSize,
+    event_status: EventStatus,
 }
 
 impl DriverResponse for AmqpResponse {
     fn event_status(&self) -> EventStatus {
-        EventStatus::Delivered
+        self.event_status
     }
 
     fn events_sent(&self) -> &GroupedCountByteSize {
@@ -78,8 +80,10 @@ impl DriverResponse for AmqpResponse {
 }
 
 /// The tower service that handles the actual sending of data to `AMQP`.
+#[derive(Clone)]
 pub(super) struct AmqpService {
     pub(super) channels: AmqpSinkChannels,
+    pub(super) mandatory: bool,
 }
 
 #[derive(Debug, Snafu)]
@@ -116,6 +120,10 @@ impl Service<AmqpRequest> for AmqpService {
 
     fn call(&mut self, req: AmqpRequest) -> Self::Future {
         let channel = self.channels.clone();
+        let options = BasicPublishOptions {
+            mandatory: self.mandatory,
+            ..Default::default()
+        };
 
         Box::pin(async move {
             let channel = channel.get().await.map_err(|error| AmqpError::PoolError {
@@ -127,7 +135,7 @@ impl Service<AmqpRequest> for AmqpService {
                 .basic_publish(
                     &req.exchange,
                     &req.routing_key,
-                    BasicPublishOptions::default(),
+                    options,
                     req.body.as_ref(),
                     req.properties,
                 )
@@ -135,11 +143,27 @@ impl Service<AmqpRequest> for AmqpService {
 
             match fut {
                 Ok(result) => match result.await {
-                    Ok(lapin::publisher_confirm::Confirmation::Nack(_)) => Err(AmqpError::Nack),
+                    Ok(Confirmation::Nack(_)) => Err(AmqpError::Nack),
                     Err(error) => Err(AmqpError::AcknowledgementFailed { error }),
+                    // The message couldn't be routed to any queue, which retrying won't change.
+                    Ok(Confirmation::Ack(Some(returned))) => {
+                        warn!(
+                            message = "Message was returned by the broker.",
+                            reply_code = returned.reply_code,
+                            reply_text = %returned.reply_text,
+                            exchange = %req.exchange,
+                            routing_key = %req.routing_key,
+                        );
+                        Ok(AmqpResponse {
+                            json_size: req.metadata.into_events_estimated_json_encoded_byte_size(),
+                            byte_size,
+                            event_status: EventStatus::Rejected,
+                        })
+                    }
                     Ok(_) => Ok(AmqpResponse {
                         json_size: req.metadata.into_events_estimated_json_encoded_byte_size(),
                         byte_size,
+                        event_status: EventStatus::Delivered,
                     }),
                 },
                 Err(error) => Err(AmqpError::DeliveryFailed { error }),
@@ -9,7 +9,7 @@ use super::{
     config::{AmqpPropertiesConfig, AmqpSinkConfig},
     encoder::AmqpEncoder,
     request_builder::AmqpRequestBuilder,
-    service::AmqpService,
+    service::{AmqpError, AmqpRequest, AmqpResponse, AmqpService},
 };
 use crate::sinks::prelude::*;
 
@@ -33,6 +33,8 @@ pub(super) struct AmqpSink {
     properties: Option<AmqpPropertiesConfig>,
     transformer: Transformer,
     encoder: crate::codecs::Encoder<()>,
+    mandatory: bool,
+    request: TowerRequestConfig,
 }
 
 impl AmqpSink {
@@ -51,6 +53,8 @@ impl AmqpSink {
             properties: config.properties,
             transformer,
             encoder,
+            mandatory: config.mandatory,
+            request: config.request,
         })
     }
 
@@ -103,9 +107,12 @@ impl AmqpSink {
                 transformer: self.transformer.clone(),
             },
         };
-        let service = ServiceBuilder::new().service(AmqpService {
-            channels: self.channels.clone(),
-        });
+        let service = ServiceBuilder::new()
+            .settings(self.request.into_settings(), AmqpRetryLogic)
+            .service(AmqpService {
+                channels: self.channels.clone(),
+                mandatory: self.mandatory,
+            });
 
         input
             .filter_map(|event| std::future::ready(self.make_amqp_event(event)))
@@ -132,3 +139,18 @@ impl StreamSink<Event> for AmqpSink {
         self.run_inner(input).await
     }
 }
+
+/// The messages negatively acknowledged by the broker, or whose publishing failed, are published
+/// again.
+#[derive(Clone)]
+pub(super) struct AmqpRetryLogic;
+
+impl RetryLogic for AmqpRetryLogic {
+    type Error = AmqpError;
+    type Request = AmqpRequest;
+    type Response = AmqpResponse;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+}
@@ -1,19 +1,25 @@
+use std::sync::Arc;
+
 use aws_sdk_s3::Client as S3Client;
+use futures::FutureExt;
 use tower::ServiceBuilder;
 use vector_lib::{
     TimeZone,
     codecs::{
         TextSerializerConfig,
-        encoding::{Framer, FramingConfig},
+        encoding::{BatchSerializerConfig, FramingConfig},
     },
     configurable::configurable_component,
     sink::VectorSink,
 };
 
-use super::sink::S3RequestOptions;
+use super::{
+    delta_lake::{DeltaLakeService, DeltaLakeTable, normalize_table_prefix},
+    sink::S3RequestOptions,
+};
 use crate::{
     aws::{AwsAuthentication, RegionOrEndpoint},
-    codecs::{Encoder, EncodingConfigWithFraming, SinkType},
+    codecs::{EncodingConfigWithFraming, SinkType},
     config::{AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext},
     sinks::{
         Healthcheck,
@@ -92,7 +98,8 @@ pub struct S3SinkConfig {
 
     /// The filename extension to use in the object key.
     ///
-    /// This overrides setting the extension based on the configured `compression`.
+    /// This overrides setting the extension based on the configured `compression`, or on the
+    /// configured `batch_encoding`.
     #[configurable(metadata(docs::examples = "json"))]
     pub filename_extension: Option<String>,
 
@@ -105,6 +112,31 @@ pub struct S3SinkConfig {
     #[serde(flatten)]
     pub encoding: EncodingConfigWithFraming,
 
+    /// Batch encoding configuration.
+    ///
+    /// When set, each batch of events is written as a single object encoded with this codec, such
+    /// as an Apache Parquet file, instead of being encoded event by event with `encoding`. The
+    /// options of `encoding` that change events, such as `except_fields`, still apply.
+    ///
+    /// Parquet files are compressed internally, so `compression` is not applied to them. Hive-style
+    /// partitioned prefixes, such as `year=%Y/month=%m/day=%d/`, can be used in `key_prefix` to
+    /// allow querying the objects as a partitioned table from Athena, and `except_fields` to leave
+    /// the partition columns out of the files.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch_encoding: Option<BatchSerializerConfig>,
+
+    /// Table format configuration.
+    ///
+    /// When set, each object is committed to a table in the given format once it's written, so
+    /// that its events can be queried right away, without a separate job to commit the objects.
+    ///
+    /// This requires `batch_encoding` to be set, and `key_prefix` to start with the prefix of the
+    /// table.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub table_format: Option<TableFormatConfig>,
+
     /// Compression configuration.
     ///
     /// All compression algorithms use the default compression level unless otherwise specified.
@@ -157,6 +189,29 @@ pub struct S3SinkConfig {
     pub retry_strategy: RetryStrategy,
 }
 
+/// The format of a table that objects are committed to.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[configurable(metadata(
+    docs::enum_tag_description = "The format of the table that objects are committed to."
+))]
+pub enum TableFormatConfig {
+    /// Commit objects to a [Delta Lake][delta_lake] table.
+    ///
+    /// Each object is added to the table with its own commit, appended to the transaction log of
+    /// the table with a conditional write so that concurrent writers can't overwrite each other's
+    /// commits. The table must already exist, and its partition columns must match the Hive-style
+    /// `column=value` directories of `key_prefix`.
+    ///
+    /// [delta_lake]: https://delta.io/
+    DeltaLake {
+        /// The prefix of the object keys of the table, which contains its `_delta_log` directory.
+        #[configurable(metadata(docs::examples = "tables/logs/"))]
+        table_prefix: String,
+    },
+}
+
 pub(super) fn default_key_prefix() -> String {
     "date=%F".to_string()
 }
@@ -176,6 +231,8 @@ impl GenerateConfig for S3SinkConfig {
             options: S3Options::default(),
             region: RegionOrEndpoint::default(),
             encoding: (None::<FramingConfig>, TextSerializerConfig::default()).into(),
+            batch_encoding: None,
+            table_format: None,
             compression: Compression::gzip_default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
@@ -201,7 +258,10 @@ impl SinkConfig for S3SinkConfig {
     }
 
     fn input(&self) -> Input {
-        Input::new(self.encoding.config().1.input_type())
+        match &self.batch_encoding {
+            Some(batch_encoding) => Input::new(batch_encoding.input_type()),
+            None => Input::new(self.encoding.config().1.input_type()),
+        }
     }
 
     fn acknowledgements(&self) -> &AcknowledgementsConfig {
@@ -215,6 +275,9 @@ impl S3SinkConfig {
         service: S3Service,
         cx: SinkContext,
     ) -> crate::Result<VectorSink> {
+        self.validate_table_format()?;
+        let table = self.table(service.client());
+
         // Build our S3 client/service, which is what we'll ultimately feed
         // requests into in order to ship files to S3.  We build this here in
         // order to configure the client/service with retries, concurrency
@@ -246,27 +309,88 @@ impl S3SinkConfig {
         let partitioner = S3KeyPartitioner::new(key_prefix, ssekms_key_id, None);
 
         let transformer = self.encoding.transformer();
-        let (framer, serializer) = self.encoding.build(SinkType::MessageBased)?;
-        let encoder = Encoder::<Framer>::new(framer, serializer);
+        let encoder = self
+            .encoding
+            .build_batch_encoder(self.batch_encoding.as_ref(), SinkType::MessageBased)?;
+        let (filename_extension, compression) = match &self.batch_encoding {
+            Some(batch_encoding) => (
+                self.filename_extension
+                    .clone()
+                    .or_else(|| Some(batch_encoding.file_extension().to_string())),
+                Compression::None,
+            ),
+            None => (self.filename_extension.clone(), self.compression),
+        };
 
         let request_options = S3RequestOptions {
             bucket: self.bucket.clone(),
             api_options: self.options.clone(),
-            filename_extension: self.filename_extension.clone(),
+            filename_extension,
             filename_time_format: self.filename_time_format.clone(),
             filename_append_uuid: self.filename_append_uuid,
             encoder: (transformer, encoder),
-            compression: self.compression,
+            compression,
             filename_tz_offset: offset,
         };
 
-        let sink = S3Sink::new(service, request_options, partitioner, batch_settings);
-
-        Ok(VectorSink::from_event_streamsink(sink))
+        match table {
+            Some(table) => {
+                let service = DeltaLakeService::new(service, table);
+                let sink = S3Sink::new(service, request_options, partitioner, batch_settings);
+                Ok(VectorSink::from_event_streamsink(sink))
+            }
+            None => {
+                let sink = S3Sink::new(service, request_options, partitioner, batch_settings);
+                Ok(VectorSink::from_event_streamsink(sink))
+            }
+        }
     }
 
     pub fn build_healthcheck(&self, client: S3Client) -> crate::Result<Healthcheck> {
-        s3_common::config::build_healthcheck(self.bucket.clone(), client)
+        let healthcheck =
+            s3_common::config::build_healthcheck(self.bucket.clone(), client.clone())?;
+
+        Ok(match self.table(client) {
+            Some(table) => async move {
+                healthcheck.await?;
+                table.healthcheck().await
+            }
+            .boxed(),
+            None => healthcheck,
+        })
+    }
+
+    fn validate_table_format(&self) -> crate::Result<()> {
+        let Some(TableFormatConfig::DeltaLake { table_prefix }) = &self.table_format else {
+            return Ok(());
+        };
+        if self.batch_encoding.is_none() {
+            return Err("`table_format` requires `batch_encoding` to be set".into());
+        }
+        let table_prefix = normalize_table_prefix(table_prefix);
+        if !self
+            .key_prefix
+            .trim_start_matches('/')
+            .starts_with(&table_prefix)
+        {
+            return Err(format!(
+                "`key_prefix` must start with the table prefix {table_prefix:?} to commit objects to the table"
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn table(&self, client: S3Client) -> Option<Arc<DeltaLakeTable>> {
+        self.table_format
+            .as_ref()
+            .map(|table_format| match table_format {
+                TableFormatConfig::DeltaLake { table_prefix } => Arc::new(DeltaLakeTable::new(
+                    client,
+                    self.bucket.clone(),
+                    table_prefix,
+                )),
+            })
     }
 
     pub async fn create_service(&self, proxy: &ProxyConfig) -> crate::Result<S3Service> {
@@ -283,10 +407,35 @@ impl S3SinkConfig {
 
 #[cfg(test)]
 mod tests {
-    use super::S3SinkConfig;
+    use vector_lib::codecs::encoding::ParquetSerializerOptions;
+
+    use super::*;
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<S3SinkConfig>();
     }
+
+    #[test]
+    fn validates_table_format() {
+        let mut config: S3SinkConfig = toml::from_str(
+            r#"
+            bucket = "bucket"
+            key_prefix = "tables/logs/date=%F/"
+            encoding.codec = "json"
+            table_format.type = "delta_lake"
+            table_format.table_prefix = "tables/logs"
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate_table_format().is_err());
+
+        config.batch_encoding = Some(BatchSerializerConfig::Parquet {
+            parquet: ParquetSerializerOptions::default(),
+        });
+        assert!(config.validate_table_format().is_ok());
+
+        config.key_prefix = "date=%F/".to_string();
+        assert!(config.validate_table_format().is_err());
+    }
 }
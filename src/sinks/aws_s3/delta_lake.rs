@@ -0,0 +1,398 @@
+//! Commits the objects written by the sink to a Delta Lake table.
+//!
+//! Each object is added to the table with a new commit in its transaction log, written with a
+//! conditional put so that commits from concurrent writers are never overwritten.
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use aws_sdk_s3::Client as S3Client;
+use aws_smithy_runtime_api::client::{orchestrator::HttpResponse, result::SdkError};
+use aws_smithy_types::byte_stream::ByteStream;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use serde::Deserialize;
+use serde_json::json;
+use snafu::Snafu;
+use tokio::sync::Mutex;
+use tower::Service;
+
+use crate::sinks::s3_common::service::{S3Request, S3Response};
+
+/// The number of versions tried when committing before giving up, if they were all committed by
+/// other writers first.
+const MAX_COMMIT_ATTEMPTS: usize = 10;
+
+/// The characters encoded in the paths of the files added to the table.
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'=');
+
+#[derive(Debug, Snafu)]
+pub enum DeltaLakeError {
+    #[snafu(display("Object {key:?} is not under the Delta Lake table prefix {table_prefix:?}"))]
+    ObjectOutsideTable { key: String, table_prefix: String },
+
+    #[snafu(display(
+        "No Delta Lake table found at s3://{bucket}/{table_prefix}, it must be created before objects are committed to it"
+    ))]
+    TableNotFound {
+        bucket: String,
+        table_prefix: String,
+    },
+
+    #[snafu(display("Failed to read the Delta Lake transaction log: {source}"))]
+    ReadLog { source: crate::Error },
+
+    #[snafu(display("Failed to write to the Delta Lake transaction log: {source}"))]
+    WriteLog { source: crate::Error },
+
+    #[snafu(display(
+        "Gave up committing to the Delta Lake table after {attempts} versions were committed by other writers first"
+    ))]
+    TooManyConflicts { attempts: usize },
+}
+
+/// The content of the `_last_checkpoint` file of the transaction log.
+#[derive(Deserialize)]
+struct LastCheckpoint {
+    version: u64,
+}
+
+/// A Delta Lake table stored in an S3 bucket.
+pub struct DeltaLakeTable {
+    client: S3Client,
+    bucket: String,
+    table_prefix: String,
+    /// The version of the next commit, once known.
+    ///
+    /// The lock is held while committing, so that the commits of the sink are made one at a time.
+    next_version: Mutex<Option<u64>>,
+}
+
+impl DeltaLakeTable {
+    pub fn new(client: S3Client, bucket: String, table_prefix: &str) -> Self {
+        Self {
+            client,
+            bucket,
+            table_prefix: normalize_table_prefix(table_prefix),
+            next_version: Mutex::new(None),
+        }
+    }
+
+    fn log_prefix(&self) -> String {
+        format!("{}_delta_log/", self.table_prefix)
+    }
+
+    fn log_key(&self, version: u64) -> String {
+        format!("{}{version:020}.json", self.log_prefix())
+    }
+
+    /// Checks that the table exists.
+    pub async fn healthcheck(&self) -> crate::Result<()> {
+        self.latest_version().await?;
+        Ok(())
+    }
+
+    /// Finds the latest version of the table in its transaction log.
+    async fn latest_version(&self) -> Result<u64, DeltaLakeError> {
+        let log_prefix = self.log_prefix();
+
+        // Start from the last checkpoint, if there is one, to avoid listing the whole log.
+        let checkpoint = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(format!("{log_prefix}_last_checkpoint"))
+            .send()
+            .await
+        {
+            Ok(output) => {
+                let body = output
+                    .body
+                    .collect()
+                    .await
+                    .map_err(|error| DeltaLakeError::ReadLog {
+                        source: error.into(),
+                    })?
+                    .into_bytes();
+                let checkpoint =
+                    serde_json::from_slice::<LastCheckpoint>(&body).map_err(|error| {
+                        DeltaLakeError::ReadLog {
+                            source: error.into(),
+                        }
+                    })?;
+                Some(checkpoint.version)
+            }
+            Err(SdkError::ServiceError(error)) if error.err().is_no_such_key() => None,
+            Err(error) => {
+                return Err(DeltaLakeError::ReadLog {
+                    source: error.into(),
+                });
+            }
+        };
+
+        let mut latest = checkpoint;
+        let mut continuation_token = None;
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(&log_prefix)
+                .set_start_after(checkpoint.map(|version| self.log_key(version)))
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .map_err(|error| DeltaLakeError::ReadLog {
+                    source: error.into(),
+                })?;
+
+            let versions = output
+                .contents()
+                .iter()
+                .filter_map(|object| object.key()?.strip_prefix(&log_prefix))
+                .filter_map(parse_log_version);
+            latest = latest.max(versions.max());
+
+            continuation_token = output.next_continuation_token().map(ToOwned::to_owned);
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        latest.ok_or_else(|| DeltaLakeError::TableNotFound {
+            bucket: self.bucket.clone(),
+            table_prefix: self.table_prefix.clone(),
+        })
+    }
+
+    /// Adds the object to the table, returning the version of the commit.
+    pub async fn commit(&self, key: &str, size: usize) -> Result<u64, DeltaLakeError> {
+        let path = key.strip_prefix(&self.table_prefix).ok_or_else(|| {
+            DeltaLakeError::ObjectOutsideTable {
+                key: key.to_string(),
+                table_prefix: self.table_prefix.clone(),
+            }
+        })?;
+        let actions = commit_actions(path, size, Utc::now().timestamp_millis());
+
+        let mut next_version = self.next_version.lock().await;
+        let mut version = match *next_version {
+            Some(version) => version,
+            None => self.latest_version().await? + 1,
+        };
+
+        for _ in 0..MAX_COMMIT_ATTEMPTS {
+            let result = self
+                .client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(self.log_key(version))
+                .if_none_match("*")
+                .body(ByteStream::from(actions.clone().into_bytes()))
+                .send()
+                .await;
+
+            match result {
+                Ok(_) => {
+                    *next_version = Some(version + 1);
+                    return Ok(version);
+                }
+                // Another writer committed this version first, so the commit is retried as the
+                // next one. Appends never conflict with each other.
+                Err(error) if is_conflict(&error) => version += 1,
+                Err(error) => {
+                    return Err(DeltaLakeError::WriteLog {
+                        source: error.into(),
+                    });
+                }
+            }
+        }
+
+        *next_version = Some(version);
+        Err(DeltaLakeError::TooManyConflicts {
+            attempts: MAX_COMMIT_ATTEMPTS,
+        })
+    }
+}
+
+fn is_conflict<E>(error: &SdkError<E, HttpResponse>) -> bool {
+    // S3 answers with `412 Precondition Failed` if the object exists, or with `409 Conflict` if
+    // it's being written concurrently.
+    matches!(
+        error
+            .raw_response()
+            .map(|response| response.status().as_u16()),
+        Some(409 | 412)
+    )
+}
+
+/// Strips any leading `/` from the prefix, and makes sure a non-empty prefix ends with one.
+pub(super) fn normalize_table_prefix(table_prefix: &str) -> String {
+    let table_prefix = table_prefix.trim_start_matches('/');
+    if table_prefix.is_empty() || table_prefix.ends_with('/') {
+        table_prefix.to_string()
+    } else {
+        format!("{table_prefix}/")
+    }
+}
+
+/// Parses the version of a commit from its file name in the transaction log.
+fn parse_log_version(file_name: &str) -> Option<u64> {
+    let version = file_name.strip_suffix(".json")?;
+    if version.len() != 20 || !version.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    version.parse().ok()
+}
+
+/// Parses the partition values of a file from the Hive-style `key=value` directories of its path.
+fn partition_values(path: &str) -> BTreeMap<String, String> {
+    let Some((directories, _)) = path.rsplit_once('/') else {
+        return BTreeMap::new();
+    };
+    directories
+        .split('/')
+        .filter_map(|directory| directory.split_once('='))
+        .map(|(column, value)| {
+            (
+                percent_decode_str(column).decode_utf8_lossy().into_owned(),
+                percent_decode_str(value).decode_utf8_lossy().into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Builds the actions of the commit adding the file to the table, as newline-delimited JSON.
+fn commit_actions(path: &str, size: usize, timestamp: i64) -> String {
+    let commit_info = json!({
+        "commitInfo": {
+            "timestamp": timestamp,
+            "operation": "WRITE",
+            "operationParameters": { "mode": "Append" },
+            "isBlindAppend": true,
+            "engineInfo": format!("Vector/{}", crate::get_version()),
+        }
+    });
+    let add = json!({
+        "add": {
+            "path": utf8_percent_encode(path, PATH_ENCODE_SET).to_string(),
+            "partitionValues": partition_values(path),
+            "size": size,
+            "modificationTime": timestamp,
+            "dataChange": true,
+        }
+    });
+    format!("{commit_info}\n{add}\n")
+}
+
+/// A service committing the objects written by the inner service to a Delta Lake table.
+#[derive(Clone)]
+pub struct DeltaLakeService<S> {
+    inner: S,
+    table: Arc<DeltaLakeTable>,
+}
+
+impl<S> DeltaLakeService<S> {
+    pub const fn new(inner: S, table: Arc<DeltaLakeTable>) -> Self {
+        Self { inner, table }
+    }
+}
+
+impl<S> Service<S3Request> for DeltaLakeService<S>
+where
+    S: Service<S3Request, Response = S3Response>,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::Error>,
+{
+    type Response = S3Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: S3Request) -> Self::Future {
+        let key = request.metadata.s3_key.clone();
+        let size = request.body.len();
+        let table = Arc::clone(&self.table);
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = future.await.map_err(Into::into)?;
+            let version = table.commit(&key, size).await?;
+            debug!(message = "Committed object to Delta Lake table.", %key, %version);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_table_prefix() {
+        assert_eq!(normalize_table_prefix(""), "");
+        assert_eq!(normalize_table_prefix("/tables/logs"), "tables/logs/");
+        assert_eq!(normalize_table_prefix("tables/logs/"), "tables/logs/");
+    }
+
+    #[test]
+    fn parses_log_versions() {
+        assert_eq!(parse_log_version("00000000000000000012.json"), Some(12));
+        assert_eq!(parse_log_version("00000000000000000012.crc"), None);
+        assert_eq!(
+            parse_log_version("00000000000000000010.checkpoint.parquet"),
+            None
+        );
+        assert_eq!(parse_log_version("_last_checkpoint"), None);
+        assert_eq!(parse_log_version("12.json"), None);
+    }
+
+    #[test]
+    fn parses_partition_values() {
+        assert_eq!(
+            partition_values("year=2024/month=05/service=api%20gateway/1715000000-abc.parquet"),
+            BTreeMap::from([
+                ("month".to_string(), "05".to_string()),
+                ("service".to_string(), "api gateway".to_string()),
+                ("year".to_string(), "2024".to_string()),
+            ])
+        );
+        assert!(partition_values("logs/1715000000.parquet").is_empty());
+        assert!(partition_values("1715000000.parquet").is_empty());
+    }
+
+    #[test]
+    fn builds_commit_actions() {
+        let actions = commit_actions("date=2024-05-06/10:00:00.parquet", 1234, 1715000000000);
+        let actions = actions
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0]["commitInfo"]["operation"], "WRITE");
+        assert_eq!(
+            actions[1]["add"],
+            json!({
+                "path": "date=2024-05-06/10%3A00%3A00.parquet",
+                "partitionValues": { "date": "2024-05-06" },
+                "size": 1234,
+                "modificationTime": 1715000000000i64,
+                "dataChange": true,
+            })
+        );
+    }
+}
@@ -433,6 +433,8 @@ async fn s3_flush_on_exhaustion() {
             options: S3Options::default(),
             region: RegionOrEndpoint::with_both("us-east-1", s3_address()),
             encoding: (None::<FramingConfig>, TextSerializerConfig::default()).into(),
+            batch_encoding: None,
+            table_format: None,
             compression: Compression::None,
             batch,
             request: TowerRequestConfig::default(),
@@ -526,6 +528,8 @@ fn config(bucket: &str, batch_size: usize) -> S3SinkConfig {
         options: S3Options::default(),
         region: RegionOrEndpoint::with_both("us-east-1", s3_address()),
         encoding: (None::<FramingConfig>, TextSerializerConfig::default()).into(),
+        batch_encoding: None,
+        table_format: None,
         compression: Compression::None,
         batch,
         request: TowerRequestConfig::default(),
@@ -1,4 +1,5 @@
 mod config;
+mod delta_lake;
 mod sink;
 
 mod integration_tests;
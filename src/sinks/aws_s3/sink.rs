@@ -3,10 +3,10 @@ use std::io;
 use bytes::Bytes;
 use chrono::{FixedOffset, Utc};
 use uuid::Uuid;
-use vector_lib::{codecs::encoding::Framer, event::Finalizable, request_metadata::RequestMetadata};
+use vector_lib::{event::Finalizable, request_metadata::RequestMetadata};
 
 use crate::{
-    codecs::{Encoder, Transformer},
+    codecs::{BatchEncoder, Transformer},
     event::Event,
     sinks::{
         s3_common::{
@@ -28,7 +28,7 @@ pub struct S3RequestOptions {
     pub filename_append_uuid: bool,
     pub filename_extension: Option<String>,
     pub api_options: S3Options,
-    pub encoder: (Transformer, Encoder<Framer>),
+    pub encoder: (Transformer, BatchEncoder),
     pub compression: Compression,
     pub filename_tz_offset: Option<FixedOffset>,
 }
@@ -36,7 +36,7 @@ pub struct S3RequestOptions {
 impl RequestBuilder<(S3PartitionKey, Vec<Event>)> for S3RequestOptions {
     type Metadata = S3Metadata;
     type Events = Vec<Event>;
-    type Encoder = (Transformer, Encoder<Framer>);
+    type Encoder = (Transformer, BatchEncoder);
     type Payload = Bytes;
     type Request = S3Request;
     type Error = io::Error; // TODO: this is ugly.
@@ -94,6 +94,11 @@ impl RequestBuilder<(S3PartitionKey, Vec<Event>)> for S3RequestOptions {
         let ssekms_key_id = s3metadata.partition_key.ssekms_key_id.clone();
         let mut s3_options = self.api_options.clone();
         s3_options.ssekms_key_id = ssekms_key_id;
+        if let BatchEncoder::Batch(serializer) = &self.encoder.1 {
+            s3_options
+                .content_type
+                .get_or_insert_with(|| serializer.content_type().to_string());
+        }
 
         let extension = self
             .filename_extension
@@ -121,6 +121,13 @@ impl SinkConfig for AxiomConfig {
             method: HttpMethod::Post,
             tls: self.tls.clone(),
             request,
+            retry_policy: Default::default(),
+            capture_responses: false,
+            body: None,
+            per_event: false,
+            per_endpoint_concurrency: false,
+            ordering: Default::default(),
+            ordering_key_field: None,
             acknowledgements: self.acknowledgements,
             batch: self.batch,
             headers: None,
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use azure_storage_blobs::prelude::*;
 use tower::ServiceBuilder;
 use vector_lib::{
-    codecs::{JsonSerializerConfig, NewlineDelimitedEncoderConfig, encoding::Framer},
+    codecs::{
+        JsonSerializerConfig, NewlineDelimitedEncoderConfig, encoding::BatchSerializerConfig,
+    },
     configurable::configurable_component,
     sensitive_string::SensitiveString,
 };
@@ -11,7 +13,7 @@ use vector_lib::{
 use super::request_builder::AzureBlobRequestOptions;
 use crate::{
     Result,
-    codecs::{Encoder, EncodingConfigWithFraming, SinkType},
+    codecs::{EncodingConfigWithFraming, SinkType},
     config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
     sinks::{
         Healthcheck, VectorSink,
@@ -113,6 +115,20 @@ pub struct AzureBlobSinkConfig {
     #[serde(flatten)]
     pub encoding: EncodingConfigWithFraming,
 
+    /// Batch encoding configuration.
+    ///
+    /// When set, each batch of events is written as a single blob encoded with this codec, such as
+    /// an Apache Parquet file, instead of being encoded event by event with `encoding`. The options
+    /// of `encoding` that change events, such as `except_fields`, still apply.
+    ///
+    /// Parquet files are compressed internally, so `compression` is not applied to them. Hive-style
+    /// partitioned prefixes, such as `year=%Y/month=%m/day=%d/`, can be used in `blob_prefix` to
+    /// allow querying the blobs as a partitioned table, and `except_fields` to leave the partition
+    /// columns out of the files.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch_encoding: Option<BatchSerializerConfig>,
+
     /// Compression configuration.
     ///
     /// All compression algorithms use the default compression level unless otherwise specified.
@@ -153,6 +169,7 @@ impl GenerateConfig for AzureBlobSinkConfig {
             blob_time_format: Some(String::from("%s")),
             blob_append_uuid: Some(true),
             encoding: (Some(NewlineDelimitedEncoderConfig::new()), JsonSerializerConfig::default()).into(),
+            batch_encoding: None,
             compression: Compression::gzip_default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
@@ -180,7 +197,11 @@ impl SinkConfig for AzureBlobSinkConfig {
     }
 
     fn input(&self) -> Input {
-        Input::new(self.encoding.config().1.input_type() & DataType::Log)
+        let input_type = match &self.batch_encoding {
+            Some(batch_encoding) => batch_encoding.input_type(),
+            None => self.encoding.config().1.input_type(),
+        };
+        Input::new(input_type & DataType::Log)
     }
 
     fn acknowledgements(&self) -> &AcknowledgementsConfig {
@@ -212,15 +233,21 @@ impl AzureBlobSinkConfig {
             .unwrap_or(DEFAULT_FILENAME_APPEND_UUID);
 
         let transformer = self.encoding.transformer();
-        let (framer, serializer) = self.encoding.build(SinkType::MessageBased)?;
-        let encoder = Encoder::<Framer>::new(framer, serializer);
+        let encoder = self
+            .encoding
+            .build_batch_encoder(self.batch_encoding.as_ref(), SinkType::MessageBased)?;
+        // Batch encodings are compressed internally, if at all.
+        let compression = match self.batch_encoding {
+            Some(_) => Compression::None,
+            None => self.compression,
+        };
 
         let request_options = AzureBlobRequestOptions {
             container_name: self.container_name.clone(),
             blob_time_format,
             blob_append_uuid,
             encoder: (transformer, encoder),
-            compression: self.compression,
+            compression,
         };
 
         let sink = AzureBlobSink::new(
@@ -1,12 +1,10 @@
 use bytes::Bytes;
 use chrono::Utc;
 use uuid::Uuid;
-use vector_lib::{
-    EstimatedJsonEncodedSizeOf, codecs::encoding::Framer, request_metadata::RequestMetadata,
-};
+use vector_lib::{EstimatedJsonEncodedSizeOf, request_metadata::RequestMetadata};
 
 use crate::{
-    codecs::{Encoder, Transformer},
+    codecs::{BatchEncoder, Transformer},
     event::{Event, Finalizable},
     sinks::{
         azure_common::config::{AzureBlobMetadata, AzureBlobRequest},
@@ -22,14 +20,14 @@ pub struct AzureBlobRequestOptions {
     pub container_name: String,
     pub blob_time_format: String,
     pub blob_append_uuid: bool,
-    pub encoder: (Transformer, Encoder<Framer>),
+    pub encoder: (Transformer, BatchEncoder),
     pub compression: Compression,
 }
 
 impl RequestBuilder<(String, Vec<Event>)> for AzureBlobRequestOptions {
     type Metadata = AzureBlobMetadata;
     type Events = Vec<Event>;
-    type Encoder = (Transformer, Encoder<Framer>);
+    type Encoder = (Transformer, BatchEncoder);
     type Payload = Bytes;
     type Request = AzureBlobRequest;
     type Error = std::io::Error;
@@ -73,7 +71,10 @@ impl RequestBuilder<(String, Vec<Event>)> for AzureBlobRequestOptions {
             formatted_ts.to_string()
         };
 
-        let extension = self.compression.extension();
+        let extension = match &self.encoder.1 {
+            BatchEncoder::Framed(_) => self.compression.extension(),
+            BatchEncoder::Batch(serializer) => serializer.file_extension(),
+        };
         azure_metadata.partition_key = format!(
             "{}{}.{}",
             azure_metadata.partition_key, blob_name, extension
@@ -4,7 +4,7 @@ use vector_lib::{
     EstimatedJsonEncodedSizeOf,
     codecs::{
         NewlineDelimitedEncoder, TextSerializerConfig,
-        encoding::{Framer, FramingConfig},
+        encoding::{BatchSerializerConfig, Framer, FramingConfig, ParquetSerializerOptions},
     },
     partition::Partitioner,
     request_metadata::GroupedCountByteSize,
@@ -12,10 +12,11 @@ use vector_lib::{
 
 use super::{config::AzureBlobSinkConfig, request_builder::AzureBlobRequestOptions};
 use crate::{
-    codecs::{Encoder, EncodingConfigWithFraming},
+    codecs::{Encoder, EncodingConfigWithFraming, SinkType},
     event::{Event, LogEvent},
     sinks::util::{
         Compression,
+        encoding::Encoder as _,
         request_builder::{EncodeResult, RequestBuilder},
     },
 };
@@ -28,6 +29,7 @@ fn default_config(encoding: EncodingConfigWithFraming) -> AzureBlobSinkConfig {
         blob_time_format: Default::default(),
         blob_append_uuid: Default::default(),
         encoding,
+        batch_encoding: None,
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
@@ -68,7 +70,8 @@ fn azure_blob_build_request_without_compression() {
             Encoder::<Framer>::new(
                 NewlineDelimitedEncoder::default().into(),
                 TextSerializerConfig::default().build().into(),
-            ),
+            )
+            .into(),
         ),
         compression,
     };
@@ -116,7 +119,8 @@ fn azure_blob_build_request_with_compression() {
             Encoder::<Framer>::new(
                 NewlineDelimitedEncoder::default().into(),
                 TextSerializerConfig::default().build().into(),
-            ),
+            )
+            .into(),
         ),
         compression,
     };
@@ -164,7 +168,8 @@ fn azure_blob_build_request_with_time_format() {
             Encoder::<Framer>::new(
                 NewlineDelimitedEncoder::default().into(),
                 TextSerializerConfig::default().build().into(),
-            ),
+            )
+            .into(),
         ),
         compression,
     };
@@ -215,7 +220,8 @@ fn azure_blob_build_request_with_uuid() {
             Encoder::<Framer>::new(
                 NewlineDelimitedEncoder::default().into(),
                 TextSerializerConfig::default().build().into(),
-            ),
+            )
+            .into(),
         ),
         compression,
     };
@@ -234,3 +240,56 @@ fn azure_blob_build_request_with_uuid() {
     assert_eq!(request.content_encoding, None);
     assert_eq!(request.content_type, "text/plain");
 }
+
+#[test]
+fn azure_blob_build_request_with_batch_encoding() {
+    let log = Event::Log(LogEvent::from("test message"));
+    let container_name = String::from("logs");
+    let batch_encoding = BatchSerializerConfig::Parquet {
+        parquet: ParquetSerializerOptions::default(),
+    };
+    let sink_config = AzureBlobSinkConfig {
+        blob_prefix: "blob".try_into().unwrap(),
+        container_name: container_name.clone(),
+        batch_encoding: Some(batch_encoding.clone()),
+        ..default_config((None::<FramingConfig>, TextSerializerConfig::default()).into())
+    };
+
+    let key = sink_config
+        .key_partitioner()
+        .unwrap()
+        .partition(&log)
+        .expect("key wasn't provided");
+
+    let request_options = AzureBlobRequestOptions {
+        container_name,
+        blob_time_format: String::from(""),
+        blob_append_uuid: false,
+        encoder: (
+            Default::default(),
+            sink_config
+                .encoding
+                .build_batch_encoder(Some(&batch_encoding), SinkType::MessageBased)
+                .unwrap(),
+        ),
+        compression: Compression::None,
+    };
+
+    let mut body = Vec::new();
+    let (_, byte_size) = request_options
+        .encoder()
+        .encode_input(vec![log.clone()], &mut body)
+        .unwrap();
+    assert!(body.starts_with(b"PAR1"));
+
+    let (metadata, request_metadata_builder, _events) =
+        request_options.split_input((key, vec![log]));
+
+    let payload = EncodeResult::uncompressed(Bytes::from(body), byte_size);
+    let request_metadata = request_metadata_builder.build(&payload);
+    let request = request_options.build_request(metadata, request_metadata, payload);
+
+    assert_eq!(request.metadata.partition_key, "blob.parquet".to_string());
+    assert_eq!(request.content_encoding, None);
+    assert_eq!(request.content_type, "application/vnd.apache.parquet");
+}
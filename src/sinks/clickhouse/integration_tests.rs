@@ -25,7 +25,7 @@ use crate::{
     codecs::{TimestampFormat, Transformer},
     config::{SinkConfig, SinkContext, log_schema},
     sinks::{
-        clickhouse::config::ClickhouseConfig,
+        clickhouse::config::{ClickhouseConfig, Protocol},
         util::{BatchConfig, Compression, TowerRequestConfig},
     },
     test_util::{
@@ -38,6 +38,10 @@ fn clickhouse_address() -> String {
     std::env::var("CLICKHOUSE_ADDRESS").unwrap_or_else(|_| "http://localhost:8123".into())
 }
 
+fn clickhouse_native_address() -> String {
+    std::env::var("CLICKHOUSE_NATIVE_ADDRESS").unwrap_or_else(|_| "tcp://localhost:9000".into())
+}
+
 #[tokio::test]
 async fn insert_events() {
     trace_init();
@@ -392,6 +396,75 @@ async fn templated_table() {
     }
 }
 
+async fn insert_events_native(compression: Compression) {
+    trace_init();
+
+    let table = random_table_name();
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(2);
+
+    let config = ClickhouseConfig {
+        endpoint: clickhouse_native_address().parse().unwrap(),
+        protocol: Protocol::Native,
+        table: table.clone().try_into().unwrap(),
+        compression,
+        insert_deduplication_token: true,
+        batch,
+        request: TowerRequestConfig {
+            retry_attempts: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(clickhouse_address());
+    client
+        .create_table(
+            &table,
+            "host LowCardinality(String), timestamp DateTime64(6), message String, \
+             items Array(String), count Nullable(UInt32)",
+        )
+        .await;
+
+    let (sink, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    let (mut first, mut first_receiver) = make_event();
+    first.as_mut_log().insert("items", vec!["item1", "item2"]);
+    first.as_mut_log().insert("count", 3);
+    let (second, mut second_receiver) = make_event();
+
+    run_and_assert_sink_compliance(sink, stream::iter(vec![first, second]), &SINK_TAGS).await;
+
+    let output = client.select_all(&table).await;
+    assert_eq!(2, output.rows);
+    for row in &output.data {
+        assert_eq!(row["host"], "example.com");
+        assert_eq!(row["message"], "raw log line");
+    }
+    assert_eq!(
+        output.data[0]["items"],
+        serde_json::json!(["item1", "item2"])
+    );
+    assert_eq!(output.data[0]["count"], 3);
+    assert_eq!(output.data[1]["items"], serde_json::json!([]));
+    assert_eq!(output.data[1]["count"], Value::Null);
+
+    assert_eq!(first_receiver.try_recv(), Ok(BatchStatus::Delivered));
+    assert_eq!(second_receiver.try_recv(), Ok(BatchStatus::Delivered));
+}
+
+#[tokio::test]
+async fn insert_events_native_compressed() {
+    insert_events_native(Compression::gzip_default()).await;
+}
+
+#[tokio::test]
+async fn insert_events_native_uncompressed() {
+    insert_events_native(Compression::None).await;
+}
+
 fn make_event() -> (Event, BatchStatusReceiver) {
     let (batch, receiver) = BatchNotifier::new_with_receiver();
     let mut event = LogEvent::from("raw log line").with_batch_notifier(&batch);
@@ -7,11 +7,15 @@
 //! `INSERT INTO my_db.my_table FORMAT JSONEachRow`. The event payload is encoded as new-line
 //! delimited JSON.
 //!
+//! With the `native` protocol, events are instead sent over the ClickHouse native TCP protocol as
+//! columnar blocks, see the `native` module.
+//!
 //! This sink only supports logs for now but could support metrics and traces as well in the future.
 
 pub mod config;
 #[cfg(all(test, feature = "clickhouse-integration-tests"))]
 mod integration_tests;
+mod native;
 mod request_builder;
 mod service;
 mod sink;
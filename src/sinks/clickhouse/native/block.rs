@@ -0,0 +1,603 @@
+//! Columnar encoding of events into blocks of the ClickHouse native format.
+//!
+//! The structure of the block is dictated by the server: each column of the table gets filled
+//! with the event field of the same name, or with the default value of its type if the field is
+//! missing.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use super::protocol::{NativeError, write_block_info, write_string, write_varint};
+use crate::event::{Event, Value};
+
+/// `SharedDictionariesWithAdditionalKeys`, the only `LowCardinality` serialization version.
+const LOW_CARDINALITY_KEYS_VERSION: u64 = 1;
+const LOW_CARDINALITY_HAS_ADDITIONAL_KEYS: u64 = 1 << 9;
+const LOW_CARDINALITY_NEED_UPDATE_DICTIONARY: u64 = 1 << 10;
+
+/// The type of a column, as far as encoding is concerned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ColumnType {
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    String,
+    FixedString(usize),
+    Date,
+    Date32,
+    DateTime,
+    DateTime64(u32),
+    Uuid,
+    Nullable(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+}
+
+impl ColumnType {
+    fn parse(type_name: &str) -> Option<Self> {
+        let type_name = type_name.trim();
+
+        if let Some((name, args)) = type_name
+            .strip_suffix(')')
+            .and_then(|type_name| type_name.split_once('('))
+        {
+            return match name {
+                "Nullable" => Some(Self::Nullable(Box::new(Self::parse(args)?))),
+                "Array" => Some(Self::Array(Box::new(Self::parse(args)?))),
+                "LowCardinality" => match Self::parse(args)? {
+                    inner @ (Self::String | Self::FixedString(_)) => {
+                        Some(Self::LowCardinality(Box::new(inner)))
+                    }
+                    Self::Nullable(inner)
+                        if matches!(*inner, Self::String | Self::FixedString(_)) =>
+                    {
+                        Some(Self::LowCardinality(Box::new(Self::Nullable(inner))))
+                    }
+                    _ => None,
+                },
+                "FixedString" => args.trim().parse().ok().map(Self::FixedString),
+                // The time zone only matters when displaying values.
+                "DateTime" => Some(Self::DateTime),
+                "DateTime64" => {
+                    let precision = args.split(',').next()?.trim().parse().ok()?;
+                    (precision <= 9).then_some(Self::DateTime64(precision))
+                }
+                _ => None,
+            };
+        }
+
+        Some(match type_name {
+            "UInt8" => Self::UInt8,
+            "UInt16" => Self::UInt16,
+            "UInt32" => Self::UInt32,
+            "UInt64" => Self::UInt64,
+            "Int8" => Self::Int8,
+            "Int16" => Self::Int16,
+            "Int32" => Self::Int32,
+            "Int64" => Self::Int64,
+            "Float32" => Self::Float32,
+            "Float64" => Self::Float64,
+            "Bool" => Self::Bool,
+            "String" => Self::String,
+            "Date" => Self::Date,
+            "Date32" => Self::Date32,
+            "DateTime" => Self::DateTime,
+            "UUID" => Self::Uuid,
+            _ => return None,
+        })
+    }
+}
+
+/// A column of the table data is inserted into, as described by the server.
+#[derive(Clone, Debug)]
+pub struct ColumnHeader {
+    pub name: String,
+    type_name: String,
+    column_type: ColumnType,
+}
+
+impl ColumnHeader {
+    pub fn new(name: String, type_name: String) -> Result<Self, NativeError> {
+        let column_type =
+            ColumnType::parse(&type_name).ok_or_else(|| NativeError::UnsupportedType {
+                column: name.clone(),
+                type_name: type_name.clone(),
+            })?;
+        Ok(Self {
+            name,
+            type_name,
+            column_type,
+        })
+    }
+}
+
+/// Encodes the events into a block with the given columns.
+pub fn encode_block(columns: &[ColumnHeader], events: &[Event]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_block_info(&mut buf);
+    write_varint(&mut buf, columns.len() as u64);
+    write_varint(&mut buf, events.len() as u64);
+
+    for column in columns {
+        write_string(&mut buf, &column.name);
+        write_string(&mut buf, &column.type_name);
+
+        let values = events
+            .iter()
+            .map(|event| field(event.as_log().value(), &column.name))
+            .collect::<Vec<_>>();
+        write_prefix(&mut buf, &column.column_type);
+        write_column(&mut buf, &column.column_type, &values);
+    }
+
+    buf
+}
+
+/// Looks up the field a column is filled with, either a top-level field named after the column
+/// or a nested one if the column name is a dotted path.
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    let object = value.as_object()?;
+    object.get(name).or_else(|| {
+        let (head, tail) = name.split_once('.')?;
+        field(object.get(head)?, tail)
+    })
+}
+
+fn is_null(value: Option<&Value>) -> bool {
+    matches!(value, None | Some(Value::Null))
+}
+
+/// Writes the serialization state prefix of a column, which precedes all of its data.
+fn write_prefix(buf: &mut Vec<u8>, column_type: &ColumnType) {
+    match column_type {
+        ColumnType::Nullable(inner) | ColumnType::Array(inner) => write_prefix(buf, inner),
+        ColumnType::LowCardinality(_) => {
+            buf.extend_from_slice(&LOW_CARDINALITY_KEYS_VERSION.to_le_bytes())
+        }
+        _ => {}
+    }
+}
+
+fn write_column(buf: &mut Vec<u8>, column_type: &ColumnType, values: &[Option<&Value>]) {
+    match column_type {
+        ColumnType::UInt8 | ColumnType::Int8 => {
+            write_fixed(buf, values, |value| (to_i64(value) as u8).to_le_bytes())
+        }
+        ColumnType::UInt16 | ColumnType::Int16 => {
+            write_fixed(buf, values, |value| (to_i64(value) as u16).to_le_bytes())
+        }
+        ColumnType::UInt32 | ColumnType::Int32 => {
+            write_fixed(buf, values, |value| (to_i64(value) as u32).to_le_bytes())
+        }
+        ColumnType::UInt64 => write_fixed(buf, values, |value| to_u64(value).to_le_bytes()),
+        ColumnType::Int64 => write_fixed(buf, values, |value| to_i64(value).to_le_bytes()),
+        ColumnType::Float32 => {
+            write_fixed(buf, values, |value| (to_f64(value) as f32).to_le_bytes())
+        }
+        ColumnType::Float64 => write_fixed(buf, values, |value| to_f64(value).to_le_bytes()),
+        ColumnType::Bool => write_fixed(buf, values, |value| [u8::from(to_bool(value))]),
+        ColumnType::String => {
+            for value in values {
+                write_string(buf, to_bytes(*value));
+            }
+        }
+        ColumnType::FixedString(size) => {
+            for value in values {
+                let bytes = to_bytes(*value);
+                let len = bytes.len().min(*size);
+                buf.extend_from_slice(&bytes[..len]);
+                buf.resize(buf.len() + size - len, 0);
+            }
+        }
+        ColumnType::Date => write_fixed(buf, values, |value| {
+            (days_since_epoch(value).clamp(0, i64::from(u16::MAX)) as u16).to_le_bytes()
+        }),
+        ColumnType::Date32 => write_fixed(buf, values, |value| {
+            (days_since_epoch(value) as i32).to_le_bytes()
+        }),
+        ColumnType::DateTime => write_fixed(buf, values, |value| {
+            let seconds = to_timestamp(value).map_or(0, |timestamp| timestamp.timestamp());
+            (seconds.clamp(0, i64::from(u32::MAX)) as u32).to_le_bytes()
+        }),
+        ColumnType::DateTime64(precision) => write_fixed(buf, values, |value| {
+            let ticks = to_timestamp(value).map_or(0, |timestamp| {
+                timestamp
+                    .timestamp()
+                    .saturating_mul(10i64.pow(*precision))
+                    .saturating_add(
+                        i64::from(timestamp.timestamp_subsec_nanos()) / 10i64.pow(9 - precision),
+                    )
+            });
+            ticks.to_le_bytes()
+        }),
+        ColumnType::Uuid => {
+            for value in values {
+                let uuid = to_uuid(*value);
+                let (high, low) = uuid.as_u64_pair();
+                buf.extend_from_slice(&high.to_le_bytes());
+                buf.extend_from_slice(&low.to_le_bytes());
+            }
+        }
+        ColumnType::Nullable(inner) => {
+            buf.extend(values.iter().map(|value| u8::from(is_null(*value))));
+            let values = values
+                .iter()
+                .map(|value| value.filter(|value| !value.is_null()))
+                .collect::<Vec<_>>();
+            write_column(buf, inner, &values);
+        }
+        ColumnType::Array(inner) => {
+            let mut items = Vec::new();
+            for value in values {
+                match value {
+                    None | Some(Value::Null) => {}
+                    Some(Value::Array(array)) => items.extend(array.iter().map(Some)),
+                    Some(value) => items.push(Some(*value)),
+                }
+                buf.extend_from_slice(&(items.len() as u64).to_le_bytes());
+            }
+            write_column(buf, inner, &items);
+        }
+        ColumnType::LowCardinality(inner) => write_low_cardinality(buf, inner, values),
+    }
+}
+
+fn write_fixed<const N: usize>(
+    buf: &mut Vec<u8>,
+    values: &[Option<&Value>],
+    encode: impl Fn(Option<&Value>) -> [u8; N],
+) {
+    buf.reserve(values.len() * N);
+    for value in values {
+        buf.extend_from_slice(&encode(*value));
+    }
+}
+
+/// Writes a dictionary encoded column, made of the dictionary of distinct values followed by the
+/// index of each value in that dictionary.
+fn write_low_cardinality(buf: &mut Vec<u8>, inner: &ColumnType, values: &[Option<&Value>]) {
+    let (dictionary_type, nullable) = match inner {
+        ColumnType::Nullable(inner) => (inner.as_ref(), true),
+        inner => (inner, false),
+    };
+
+    let mut dictionary: Vec<Option<&Value>> = Vec::new();
+    let mut positions = HashMap::<Cow<'_, [u8]>, u64>::new();
+    if nullable {
+        // The first key of a nullable dictionary stands for null.
+        dictionary.push(None);
+    }
+
+    let indexes = values
+        .iter()
+        .map(|value| {
+            if nullable && is_null(*value) {
+                return 0;
+            }
+            let next = dictionary.len() as u64;
+            *positions.entry(to_bytes(*value)).or_insert_with(|| {
+                dictionary.push(*value);
+                next
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let index_type = match dictionary.len() {
+        len if len <= 1 << 8 => 0,
+        len if len <= 1 << 16 => 1,
+        len if len <= 1 << 32 => 2,
+        _ => 3,
+    };
+    let flags =
+        index_type | LOW_CARDINALITY_HAS_ADDITIONAL_KEYS | LOW_CARDINALITY_NEED_UPDATE_DICTIONARY;
+    buf.extend_from_slice(&flags.to_le_bytes());
+
+    buf.extend_from_slice(&(dictionary.len() as u64).to_le_bytes());
+    write_column(buf, dictionary_type, &dictionary);
+
+    buf.extend_from_slice(&(indexes.len() as u64).to_le_bytes());
+    for index in indexes {
+        match index_type {
+            0 => buf.push(index as u8),
+            1 => buf.extend_from_slice(&(index as u16).to_le_bytes()),
+            2 => buf.extend_from_slice(&(index as u32).to_le_bytes()),
+            _ => buf.extend_from_slice(&index.to_le_bytes()),
+        }
+    }
+}
+
+fn to_i64(value: Option<&Value>) -> i64 {
+    match value {
+        Some(Value::Integer(value)) => *value,
+        Some(Value::Float(value)) => value.into_inner() as i64,
+        Some(Value::Boolean(value)) => i64::from(*value),
+        Some(Value::Timestamp(value)) => value.timestamp(),
+        Some(Value::Bytes(value)) => std::str::from_utf8(value)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_default(),
+        _ => 0,
+    }
+}
+
+fn to_u64(value: Option<&Value>) -> u64 {
+    match value {
+        Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_else(|| to_i64(value) as u64),
+        _ => to_i64(value) as u64,
+    }
+}
+
+fn to_f64(value: Option<&Value>) -> f64 {
+    match value {
+        Some(Value::Float(value)) => value.into_inner(),
+        Some(Value::Bytes(value)) => std::str::from_utf8(value)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_default(),
+        value => to_i64(value) as f64,
+    }
+}
+
+fn to_bool(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Boolean(value)) => *value,
+        Some(Value::Bytes(value)) => value.as_ref() == b"true" || value.as_ref() == b"1",
+        value => to_i64(value) != 0,
+    }
+}
+
+fn to_bytes(value: Option<&Value>) -> Cow<'_, [u8]> {
+    match value {
+        None | Some(Value::Null) => Cow::Borrowed(&[]),
+        Some(Value::Bytes(bytes)) => Cow::Borrowed(bytes.as_ref()),
+        Some(value @ (Value::Object(_) | Value::Array(_))) => {
+            Cow::Owned(serde_json::to_vec(value).unwrap_or_default())
+        }
+        Some(value) => Cow::Owned(value.to_string_lossy().into_owned().into_bytes()),
+    }
+}
+
+fn to_timestamp(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    match value? {
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        Value::Integer(seconds) => DateTime::from_timestamp(*seconds, 0),
+        Value::Float(seconds) => {
+            let seconds = seconds.into_inner();
+            DateTime::from_timestamp(
+                seconds.floor() as i64,
+                (seconds.fract() * 1_000_000_000.0) as u32,
+            )
+        }
+        Value::Bytes(bytes) => DateTime::parse_from_rfc3339(std::str::from_utf8(bytes).ok()?)
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+fn days_since_epoch(value: Option<&Value>) -> i64 {
+    let date = match value {
+        Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()),
+        _ => None,
+    }
+    .or_else(|| to_timestamp(value).map(|timestamp| timestamp.date_naive()));
+
+    date.map_or(0, |date| {
+        (date - DateTime::UNIX_EPOCH.date_naive()).num_days()
+    })
+}
+
+fn to_uuid(value: Option<&Value>) -> Uuid {
+    match value {
+        Some(Value::Bytes(bytes)) => Uuid::try_parse_ascii(bytes).unwrap_or_default(),
+        _ => Uuid::nil(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::btreemap;
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn columns(columns: &[(&str, &str)]) -> Vec<ColumnHeader> {
+        columns
+            .iter()
+            .map(|(name, type_name)| {
+                ColumnHeader::new(name.to_string(), type_name.to_string()).unwrap()
+            })
+            .collect()
+    }
+
+    fn column_data(block: &[u8], header: &[u8]) -> Vec<u8> {
+        let start = block
+            .windows(header.len())
+            .position(|window| window == header)
+            .unwrap();
+        block[start + header.len()..].to_vec()
+    }
+
+    fn column_header(name: &str, type_name: &str) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_string(&mut buf, name);
+        write_string(&mut buf, type_name);
+        buf
+    }
+
+    #[test]
+    fn parse_types() {
+        let cases = [
+            ("UInt64", Some(ColumnType::UInt64)),
+            ("DateTime('UTC')", Some(ColumnType::DateTime)),
+            (
+                "DateTime64(6, 'Europe/Paris')",
+                Some(ColumnType::DateTime64(6)),
+            ),
+            ("FixedString(16)", Some(ColumnType::FixedString(16))),
+            (
+                "Array(Nullable(String))",
+                Some(ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(
+                    ColumnType::String,
+                ))))),
+            ),
+            (
+                "LowCardinality(Nullable(String))",
+                Some(ColumnType::LowCardinality(Box::new(ColumnType::Nullable(
+                    Box::new(ColumnType::String),
+                )))),
+            ),
+            ("LowCardinality(UInt64)", None),
+            ("Map(String, String)", None),
+        ];
+
+        for (type_name, expected) in cases {
+            assert_eq!(ColumnType::parse(type_name), expected, "{type_name}");
+        }
+    }
+
+    #[test]
+    fn encode_scalars() {
+        let events = vec![
+            Event::from(LogEvent::from(btreemap! {
+                "id" => 1,
+                "message" => "foo",
+                "nested" => btreemap! { "level" => "info" },
+            })),
+            Event::from(LogEvent::from(btreemap! {
+                "id" => "2",
+                "ignored" => true,
+            })),
+        ];
+        let block = encode_block(
+            &columns(&[("id", "UInt32"), ("message", "String")]),
+            &events,
+        );
+
+        let mut expected = Vec::new();
+        write_block_info(&mut expected);
+        write_varint(&mut expected, 2);
+        write_varint(&mut expected, 2);
+        expected.extend(column_header("id", "UInt32"));
+        expected.extend_from_slice(&1u32.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend(column_header("message", "String"));
+        write_string(&mut expected, "foo");
+        write_string(&mut expected, "");
+        assert_eq!(block, expected);
+
+        let block = encode_block(&columns(&[("nested.level", "String")]), &events);
+        let mut expected = Vec::new();
+        write_string(&mut expected, "info");
+        write_string(&mut expected, "");
+        assert_eq!(
+            column_data(&block, &column_header("nested.level", "String")),
+            expected
+        );
+    }
+
+    #[test]
+    fn encode_nullable_and_array() {
+        let events = vec![
+            Event::from(LogEvent::from(btreemap! {
+                "value" => 7,
+                "tags" => vec!["a", "b"],
+            })),
+            Event::from(LogEvent::from(btreemap! {
+                "tags" => "c",
+            })),
+        ];
+
+        let block = encode_block(&columns(&[("value", "Nullable(Int64)")]), &events);
+        let mut expected = vec![0, 1];
+        expected.extend_from_slice(&7i64.to_le_bytes());
+        expected.extend_from_slice(&0i64.to_le_bytes());
+        assert_eq!(
+            column_data(&block, &column_header("value", "Nullable(Int64)")),
+            expected
+        );
+
+        let block = encode_block(&columns(&[("tags", "Array(String)")]), &events);
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        write_string(&mut expected, "a");
+        write_string(&mut expected, "b");
+        write_string(&mut expected, "c");
+        assert_eq!(
+            column_data(&block, &column_header("tags", "Array(String)")),
+            expected
+        );
+    }
+
+    #[test]
+    fn encode_low_cardinality() {
+        let events = ["x", "y", "x"]
+            .into_iter()
+            .map(|host| Event::from(LogEvent::from(btreemap! { "host" => host })))
+            .chain([Event::from(LogEvent::default())])
+            .collect::<Vec<_>>();
+
+        let block = encode_block(
+            &columns(&[("host", "LowCardinality(Nullable(String))")]),
+            &events,
+        );
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&LOW_CARDINALITY_KEYS_VERSION.to_le_bytes());
+        expected.extend_from_slice(
+            &(LOW_CARDINALITY_HAS_ADDITIONAL_KEYS | LOW_CARDINALITY_NEED_UPDATE_DICTIONARY)
+                .to_le_bytes(),
+        );
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        write_string(&mut expected, "");
+        write_string(&mut expected, "x");
+        write_string(&mut expected, "y");
+        expected.extend_from_slice(&4u64.to_le_bytes());
+        expected.extend_from_slice(&[1, 2, 1, 0]);
+        assert_eq!(
+            column_data(
+                &block,
+                &column_header("host", "LowCardinality(Nullable(String))")
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn encode_timestamps() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-02T03:04:05.123456Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let events = vec![Event::from(LogEvent::from(btreemap! {
+            "timestamp" => timestamp,
+        }))];
+
+        let block = encode_block(&columns(&[("timestamp", "DateTime64(3)")]), &events);
+        assert_eq!(
+            column_data(&block, &column_header("timestamp", "DateTime64(3)")),
+            1_704_164_645_123i64.to_le_bytes()
+        );
+
+        let block = encode_block(&columns(&[("timestamp", "Date")]), &events);
+        assert_eq!(
+            column_data(&block, &column_header("timestamp", "Date")),
+            19_724u16.to_le_bytes()
+        );
+    }
+}
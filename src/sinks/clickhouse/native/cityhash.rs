@@ -0,0 +1,228 @@
+//! CityHash128 v1.0.2.
+//!
+//! ClickHouse checksums compressed frames with this exact (and outdated) version of CityHash, so
+//! it can't be swapped for a newer implementation.
+
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+const K3: u64 = 0xc949_d7c7_509e_6557;
+
+fn fetch64(s: &[u8]) -> u64 {
+    u64::from_le_bytes(s[..8].try_into().expect("slice is at least 8 bytes"))
+}
+
+fn fetch32(s: &[u8]) -> u64 {
+    u64::from(u32::from_le_bytes(
+        s[..4].try_into().expect("slice is at least 4 bytes"),
+    ))
+}
+
+const fn rotate(val: u64, shift: u32) -> u64 {
+    if shift == 0 {
+        val
+    } else {
+        val.rotate_right(shift)
+    }
+}
+
+const fn shift_mix(val: u64) -> u64 {
+    val ^ (val >> 47)
+}
+
+const fn hash_len16(u: u64, v: u64) -> u64 {
+    const K_MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+    let mut a = (u ^ v).wrapping_mul(K_MUL);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(K_MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(K_MUL)
+}
+
+fn hash_len0to16(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len > 8 {
+        let a = fetch64(s);
+        let b = fetch64(&s[len - 8..]);
+        return hash_len16(a, (b.wrapping_add(len as u64)).rotate_right(len as u32)) ^ b;
+    }
+    if len >= 4 {
+        let a = fetch32(s);
+        return hash_len16((len as u64).wrapping_add(a << 3), fetch32(&s[len - 4..]));
+    }
+    if len > 0 {
+        let a = u32::from(s[0]);
+        let b = u32::from(s[len >> 1]);
+        let c = u32::from(s[len - 1]);
+        let y = a.wrapping_add(b << 8);
+        let z = (len as u32).wrapping_add(c << 2);
+        return shift_mix(u64::from(y).wrapping_mul(K2) ^ u64::from(z).wrapping_mul(K3))
+            .wrapping_mul(K2);
+    }
+    K2
+}
+
+const fn weak_hash_len32_with_seeds(
+    w: u64,
+    x: u64,
+    y: u64,
+    z: u64,
+    mut a: u64,
+    mut b: u64,
+) -> (u64, u64) {
+    a = a.wrapping_add(w);
+    b = rotate(b.wrapping_add(a).wrapping_add(z), 21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(rotate(a, 44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+fn weak_hash_len32_with_seeds_bytes(s: &[u8], a: u64, b: u64) -> (u64, u64) {
+    weak_hash_len32_with_seeds(
+        fetch64(s),
+        fetch64(&s[8..]),
+        fetch64(&s[16..]),
+        fetch64(&s[24..]),
+        a,
+        b,
+    )
+}
+
+fn city_murmur(s: &[u8], seed: (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+    let (mut a, mut b) = seed;
+    let mut c;
+    let mut d;
+
+    if len <= 16 {
+        a = shift_mix(a.wrapping_mul(K1)).wrapping_mul(K1);
+        c = b.wrapping_mul(K1).wrapping_add(hash_len0to16(s));
+        d = shift_mix(a.wrapping_add(if len >= 8 { fetch64(s) } else { c }));
+    } else {
+        c = hash_len16(fetch64(&s[len - 8..]).wrapping_add(K1), a);
+        d = hash_len16(
+            b.wrapping_add(len as u64),
+            c.wrapping_add(fetch64(&s[len - 16..])),
+        );
+        a = a.wrapping_add(d);
+        let mut offset = 0;
+        loop {
+            a ^= shift_mix(fetch64(&s[offset..]).wrapping_mul(K1)).wrapping_mul(K1);
+            a = a.wrapping_mul(K1);
+            b ^= a;
+            c ^= shift_mix(fetch64(&s[offset + 8..]).wrapping_mul(K1)).wrapping_mul(K1);
+            c = c.wrapping_mul(K1);
+            d ^= c;
+            offset += 16;
+            if len - offset <= 16 {
+                break;
+            }
+        }
+    }
+    a = hash_len16(a, c);
+    b = hash_len16(d, b);
+    (a ^ b, hash_len16(b, a))
+}
+
+fn city_hash128_with_seed(data: &[u8], start: usize, seed: (u64, u64)) -> (u64, u64) {
+    let mut len = data.len() - start;
+    if len < 128 {
+        return city_murmur(&data[start..], seed);
+    }
+
+    // The tail hashing reads up to 31 bytes before the unprocessed part of the input, so the
+    // input is addressed through offsets into `data` rather than by re-slicing it.
+    let mut s = start;
+    let (mut x, mut y) = seed;
+    let mut z = (len as u64).wrapping_mul(K1);
+    let mut v = (0u64, 0u64);
+    let mut w = (0u64, 0u64);
+    v.0 = rotate(y ^ K1, 49)
+        .wrapping_mul(K1)
+        .wrapping_add(fetch64(&data[s..]));
+    v.1 = rotate(v.0, 42)
+        .wrapping_mul(K1)
+        .wrapping_add(fetch64(&data[s + 8..]));
+    w.0 = rotate(y.wrapping_add(z), 35)
+        .wrapping_mul(K1)
+        .wrapping_add(x);
+    w.1 = rotate(x.wrapping_add(fetch64(&data[s + 88..])), 53).wrapping_mul(K1);
+
+    loop {
+        for _ in 0..2 {
+            x = rotate(
+                x.wrapping_add(y)
+                    .wrapping_add(v.0)
+                    .wrapping_add(fetch64(&data[s + 16..])),
+                37,
+            )
+            .wrapping_mul(K1);
+            y = rotate(
+                y.wrapping_add(v.1).wrapping_add(fetch64(&data[s + 48..])),
+                42,
+            )
+            .wrapping_mul(K1);
+            x ^= w.1;
+            y ^= v.0;
+            z = rotate(z ^ w.0, 33);
+            v = weak_hash_len32_with_seeds_bytes(
+                &data[s..],
+                v.1.wrapping_mul(K1),
+                x.wrapping_add(w.0),
+            );
+            w = weak_hash_len32_with_seeds_bytes(&data[s + 32..], z.wrapping_add(w.1), y);
+            std::mem::swap(&mut z, &mut x);
+            s += 64;
+        }
+        len -= 128;
+        if len < 128 {
+            break;
+        }
+    }
+
+    y = y
+        .wrapping_add(rotate(w.0, 37).wrapping_mul(K0))
+        .wrapping_add(z);
+    x = x.wrapping_add(rotate(v.0.wrapping_add(z), 49).wrapping_mul(K0));
+
+    // Hash up to 4 chunks of 32 bytes each from the end of the input.
+    let mut tail_done = 0;
+    while tail_done < len {
+        tail_done += 32;
+        let chunk = s + len - tail_done;
+        y = rotate(y.wrapping_sub(x), 42)
+            .wrapping_mul(K0)
+            .wrapping_add(v.1);
+        w.0 = w.0.wrapping_add(fetch64(&data[chunk + 16..]));
+        x = rotate(x, 49).wrapping_mul(K0).wrapping_add(w.0);
+        w.0 = w.0.wrapping_add(v.0);
+        v = weak_hash_len32_with_seeds_bytes(&data[chunk..], v.0, v.1);
+    }
+
+    x = hash_len16(x, v.0);
+    y = hash_len16(y, w.0);
+    (
+        hash_len16(x.wrapping_add(v.1), w.1).wrapping_add(y),
+        hash_len16(x.wrapping_add(w.1), y.wrapping_add(v.1)),
+    )
+}
+
+/// Computes the CityHash128 of `data`, returned as its `(low, high)` halves.
+pub fn city_hash128(data: &[u8]) -> (u64, u64) {
+    let len = data.len();
+    if len >= 16 {
+        city_hash128_with_seed(data, 16, (fetch64(data) ^ K3, fetch64(&data[8..])))
+    } else if len >= 8 {
+        city_hash128_with_seed(
+            &[],
+            0,
+            (
+                fetch64(data) ^ (len as u64).wrapping_mul(K0),
+                fetch64(&data[len - 8..]) ^ K1,
+            ),
+        )
+    } else {
+        city_hash128_with_seed(data, 0, (K0, K1))
+    }
+}
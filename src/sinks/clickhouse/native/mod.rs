@@ -0,0 +1,15 @@
+//! Support for the ClickHouse native TCP protocol.
+//!
+//! Instead of sending rows encoded as JSON over HTTP, events are encoded into columnar blocks
+//! following the structure of the table, which the server sends back in reply to the `INSERT`
+//! query. Blocks are LZ4 compressed unless compression is disabled.
+
+mod block;
+mod cityhash;
+mod protocol;
+mod service;
+mod sink;
+
+pub(super) use protocol::{Connection, ConnectionOptions};
+pub(super) use service::{NativeRetryLogic, NativeService};
+pub(super) use sink::NativeSink;
@@ -0,0 +1,629 @@
+//! Client side of the ClickHouse native TCP protocol.
+//!
+//! Only the subset of the protocol needed to insert blocks of data is implemented. The client
+//! announces [`CLIENT_REVISION`], and the server adapts the packets it sends to that revision.
+//!
+//! Reference: <https://clickhouse.com/docs/en/native-protocol/basics>
+
+use std::net::SocketAddr;
+
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufStream},
+    net::TcpStream,
+};
+use vector_lib::tls::{MaybeTlsSettings, MaybeTlsStream};
+
+use super::{block::ColumnHeader, cityhash::city_hash128};
+use crate::dns;
+
+/// The protocol revision implemented by this client.
+pub const CLIENT_REVISION: u64 = 54429;
+
+const CLIENT_NAME: &str = "Vector";
+
+const REVISION_WITH_SERVER_TIMEZONE: u64 = 54058;
+const REVISION_WITH_SERVER_DISPLAY_NAME: u64 = 54372;
+const REVISION_WITH_VERSION_PATCH: u64 = 54401;
+
+/// The maximum size of the uncompressed data in a compressed frame, matching the default
+/// `max_compress_block_size` of ClickHouse.
+const MAX_COMPRESS_BLOCK_SIZE: usize = 1024 * 1024;
+
+/// The size of the header of a compressed frame: method, compressed size and uncompressed size.
+const COMPRESSED_HEADER_SIZE: usize = 9;
+const CHECKSUM_SIZE: usize = 16;
+const LZ4_METHOD: u8 = 0x82;
+
+mod client_packet {
+    pub const HELLO: u64 = 0;
+    pub const QUERY: u64 = 1;
+    pub const DATA: u64 = 2;
+    pub const PING: u64 = 4;
+}
+
+mod server_packet {
+    pub const HELLO: u64 = 0;
+    pub const DATA: u64 = 1;
+    pub const EXCEPTION: u64 = 2;
+    pub const PROGRESS: u64 = 3;
+    pub const PONG: u64 = 4;
+    pub const END_OF_STREAM: u64 = 5;
+    pub const PROFILE_INFO: u64 = 6;
+    pub const TABLE_COLUMNS: u64 = 11;
+}
+
+/// Errors that can occur while talking to ClickHouse over the native protocol.
+#[derive(Debug, Snafu)]
+pub enum NativeError {
+    #[snafu(display("Failed to resolve {}: {}", host, source))]
+    Resolve { host: String, source: dns::DnsError },
+
+    #[snafu(display("No addresses found for {}", host))]
+    NoAddresses { host: String },
+
+    #[snafu(display("Failed to connect to {}: {}", address, source))]
+    Connect {
+        address: SocketAddr,
+        source: vector_lib::tls::TlsError,
+    },
+
+    #[snafu(display("Connection error: {}", source))]
+    Io { source: std::io::Error },
+
+    #[snafu(display("Server exception {} ({}): {}", code, name, message))]
+    Server {
+        code: i32,
+        name: String,
+        message: String,
+    },
+
+    #[snafu(display("Unexpected packet {} received from the server", packet))]
+    UnexpectedPacket { packet: u64 },
+
+    #[snafu(display("Invalid compressed frame: {}", reason))]
+    InvalidCompressedFrame { reason: String },
+
+    #[snafu(display("Unsupported column type {:?} for column {:?}", type_name, column))]
+    UnsupportedType { column: String, type_name: String },
+}
+
+/// Options used to open connections to the server.
+#[derive(Clone)]
+pub struct ConnectionOptions {
+    pub host: String,
+    pub port: u16,
+    pub tls: MaybeTlsSettings,
+    pub user: String,
+    pub password: String,
+    pub compression: bool,
+}
+
+/// An established and authenticated connection to a ClickHouse server.
+pub struct Connection {
+    stream: BufStream<MaybeTlsStream<TcpStream>>,
+    compression: bool,
+}
+
+impl Connection {
+    /// Connects to the server and performs the handshake.
+    pub async fn connect(options: &ConnectionOptions) -> Result<Self, NativeError> {
+        let ip = dns::Resolver
+            .lookup_ip(options.host.clone())
+            .await
+            .context(ResolveSnafu {
+                host: options.host.clone(),
+            })?
+            .next()
+            .ok_or_else(|| NativeError::NoAddresses {
+                host: options.host.clone(),
+            })?;
+        let address = SocketAddr::new(ip, options.port);
+
+        let stream = options
+            .tls
+            .connect(&options.host, &address)
+            .await
+            .context(ConnectSnafu { address })?;
+
+        let mut connection = Self {
+            stream: BufStream::new(stream),
+            compression: options.compression,
+        };
+        connection.handshake(options).await?;
+        Ok(connection)
+    }
+
+    async fn handshake(&mut self, options: &ConnectionOptions) -> Result<(), NativeError> {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, client_packet::HELLO);
+        write_string(&mut buf, CLIENT_NAME);
+        write_varint(&mut buf, client_version().0);
+        write_varint(&mut buf, client_version().1);
+        write_varint(&mut buf, CLIENT_REVISION);
+        // The database is part of every query, so the default one is used for the session.
+        write_string(&mut buf, "");
+        write_string(&mut buf, &options.user);
+        write_string(&mut buf, &options.password);
+        self.send(&buf).await?;
+
+        match read_varint(&mut self.stream).await? {
+            server_packet::HELLO => {
+                let _name = read_string(&mut self.stream).await?;
+                let _major = read_varint(&mut self.stream).await?;
+                let _minor = read_varint(&mut self.stream).await?;
+                // The server sends its own revision but speaks the lowest of both.
+                let revision = read_varint(&mut self.stream).await?.min(CLIENT_REVISION);
+                if revision >= REVISION_WITH_SERVER_TIMEZONE {
+                    let _timezone = read_string(&mut self.stream).await?;
+                }
+                if revision >= REVISION_WITH_SERVER_DISPLAY_NAME {
+                    let _display_name = read_string(&mut self.stream).await?;
+                }
+                if revision >= REVISION_WITH_VERSION_PATCH {
+                    let _patch = read_varint(&mut self.stream).await?;
+                }
+                Ok(())
+            }
+            server_packet::EXCEPTION => Err(read_exception(&mut self.stream).await?),
+            packet => Err(NativeError::UnexpectedPacket { packet }),
+        }
+    }
+
+    /// Checks that the connection is alive.
+    pub async fn ping(&mut self) -> Result<(), NativeError> {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, client_packet::PING);
+        self.send(&buf).await?;
+
+        loop {
+            match read_varint(&mut self.stream).await? {
+                server_packet::PONG => return Ok(()),
+                server_packet::PROGRESS => read_progress(&mut self.stream).await?,
+                server_packet::EXCEPTION => return Err(read_exception(&mut self.stream).await?),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        }
+    }
+
+    /// Runs an `INSERT` query.
+    ///
+    /// Once the server replied with the structure of the table, `encode` is called with its
+    /// columns to build the block that gets inserted. Returns the number of bytes of the block
+    /// sent to the server.
+    pub async fn insert<F>(
+        &mut self,
+        query_id: &str,
+        query: &str,
+        settings: &[(&'static str, String)],
+        encode: F,
+    ) -> Result<usize, NativeError>
+    where
+        F: FnOnce(&[ColumnHeader]) -> Result<Vec<u8>, NativeError>,
+    {
+        let mut buf = Vec::new();
+        self.write_query(&mut buf, query_id, query, settings);
+        self.write_data(&mut buf, &empty_block());
+        self.send(&buf).await?;
+
+        let columns = loop {
+            match read_varint(&mut self.stream).await? {
+                server_packet::DATA => break self.read_header_block().await?,
+                server_packet::TABLE_COLUMNS => {
+                    let _table = read_string(&mut self.stream).await?;
+                    let _description = read_string(&mut self.stream).await?;
+                }
+                server_packet::PROGRESS => read_progress(&mut self.stream).await?,
+                server_packet::PROFILE_INFO => read_profile_info(&mut self.stream).await?,
+                server_packet::EXCEPTION => return Err(read_exception(&mut self.stream).await?),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        };
+
+        let block = encode(&columns)?;
+
+        let mut buf = Vec::with_capacity(block.len());
+        self.write_data(&mut buf, &block);
+        self.write_data(&mut buf, &empty_block());
+        self.send(&buf).await?;
+
+        loop {
+            match read_varint(&mut self.stream).await? {
+                server_packet::END_OF_STREAM => return Ok(block.len()),
+                server_packet::TABLE_COLUMNS => {
+                    let _table = read_string(&mut self.stream).await?;
+                    let _description = read_string(&mut self.stream).await?;
+                }
+                server_packet::PROGRESS => read_progress(&mut self.stream).await?,
+                server_packet::PROFILE_INFO => read_profile_info(&mut self.stream).await?,
+                server_packet::EXCEPTION => return Err(read_exception(&mut self.stream).await?),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        }
+    }
+
+    fn write_query(
+        &self,
+        buf: &mut Vec<u8>,
+        query_id: &str,
+        query: &str,
+        settings: &[(&'static str, String)],
+    ) {
+        write_varint(buf, client_packet::QUERY);
+        write_string(buf, query_id);
+
+        // Client info.
+        let (major, minor, patch) = client_version();
+        buf.push(1); // Initial query.
+        write_string(buf, ""); // Initial user.
+        write_string(buf, query_id); // Initial query ID.
+        write_string(buf, "0.0.0.0:0"); // Initial address.
+        buf.push(1); // TCP interface.
+        write_string(buf, ""); // OS user.
+        write_string(buf, &crate::get_hostname().unwrap_or_default());
+        write_string(buf, CLIENT_NAME);
+        write_varint(buf, major);
+        write_varint(buf, minor);
+        write_varint(buf, CLIENT_REVISION);
+        write_string(buf, ""); // Quota key.
+        write_varint(buf, patch);
+
+        // Settings, serialized as strings and terminated by an empty name.
+        for (name, value) in settings {
+            write_string(buf, name);
+            write_varint(buf, 0); // Flags.
+            write_string(buf, value);
+        }
+        write_string(buf, "");
+
+        write_varint(buf, 2); // Complete processing stage.
+        write_varint(buf, u64::from(self.compression));
+        write_string(buf, query);
+    }
+
+    fn write_data(&self, buf: &mut Vec<u8>, block: &[u8]) {
+        write_varint(buf, client_packet::DATA);
+        write_string(buf, ""); // Temporary table name.
+        if self.compression {
+            for chunk in block.chunks(MAX_COMPRESS_BLOCK_SIZE) {
+                write_compressed_frame(buf, chunk);
+            }
+        } else {
+            buf.extend_from_slice(block);
+        }
+    }
+
+    async fn read_header_block(&mut self) -> Result<Vec<ColumnHeader>, NativeError> {
+        let _table = read_string(&mut self.stream).await?;
+        if self.compression {
+            let data = read_compressed_frame(&mut self.stream).await?;
+            read_header_columns(&mut data.as_slice()).await
+        } else {
+            read_header_columns(&mut self.stream).await
+        }
+    }
+
+    async fn send(&mut self, buf: &[u8]) -> Result<(), NativeError> {
+        self.stream.write_all(buf).await.context(IoSnafu)?;
+        self.stream.flush().await.context(IoSnafu)
+    }
+}
+
+fn client_version() -> (u64, u64, u64) {
+    let mut parts = crate::built_info::PKG_VERSION
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// An empty block, marking the end of the data sent for a query.
+fn empty_block() -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_block_info(&mut buf);
+    write_varint(&mut buf, 0);
+    write_varint(&mut buf, 0);
+    buf
+}
+
+pub fn write_block_info(buf: &mut Vec<u8>) {
+    write_varint(buf, 1);
+    buf.push(0); // is_overflows
+    write_varint(buf, 2);
+    buf.extend_from_slice(&(-1i32).to_le_bytes()); // bucket_num
+    write_varint(buf, 0);
+}
+
+pub fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+pub fn write_string(buf: &mut Vec<u8>, value: impl AsRef<[u8]>) {
+    let value = value.as_ref();
+    write_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let (low, high) = city_hash128(data);
+    let mut checksum = [0; CHECKSUM_SIZE];
+    checksum[..8].copy_from_slice(&low.to_le_bytes());
+    checksum[8..].copy_from_slice(&high.to_le_bytes());
+    checksum
+}
+
+/// Writes `data` as a single LZ4 compressed frame.
+fn write_compressed_frame(buf: &mut Vec<u8>, data: &[u8]) {
+    let compressed = lz4_flex::block::compress(data);
+
+    let mut frame = Vec::with_capacity(COMPRESSED_HEADER_SIZE + compressed.len());
+    frame.push(LZ4_METHOD);
+    frame.extend_from_slice(&((COMPRESSED_HEADER_SIZE + compressed.len()) as u32).to_le_bytes());
+    frame.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&compressed);
+
+    buf.extend_from_slice(&checksum(&frame));
+    buf.extend_from_slice(&frame);
+}
+
+/// Reads a single compressed frame and returns its decompressed content.
+async fn read_compressed_frame<R>(reader: &mut R) -> Result<Vec<u8>, NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut expected = [0; CHECKSUM_SIZE];
+    reader.read_exact(&mut expected).await.context(IoSnafu)?;
+
+    let mut header = [0; COMPRESSED_HEADER_SIZE];
+    reader.read_exact(&mut header).await.context(IoSnafu)?;
+    let method = header[0];
+    let compressed_size = u32::from_le_bytes(header[1..5].try_into().unwrap()) as usize;
+    let uncompressed_size = u32::from_le_bytes(header[5..9].try_into().unwrap()) as usize;
+    if compressed_size < COMPRESSED_HEADER_SIZE {
+        return Err(NativeError::InvalidCompressedFrame {
+            reason: format!("compressed size {compressed_size} is too small"),
+        });
+    }
+
+    let mut frame = header.to_vec();
+    frame.resize(compressed_size, 0);
+    reader
+        .read_exact(&mut frame[COMPRESSED_HEADER_SIZE..])
+        .await
+        .context(IoSnafu)?;
+
+    if checksum(&frame) != expected {
+        return Err(NativeError::InvalidCompressedFrame {
+            reason: "checksum mismatch".to_string(),
+        });
+    }
+
+    let data = &frame[COMPRESSED_HEADER_SIZE..];
+    match method {
+        LZ4_METHOD => lz4_flex::block::decompress(data, uncompressed_size).map_err(|error| {
+            NativeError::InvalidCompressedFrame {
+                reason: error.to_string(),
+            }
+        }),
+        // No compression.
+        0x02 => Ok(data.to_vec()),
+        method => Err(NativeError::InvalidCompressedFrame {
+            reason: format!("unsupported compression method {method:#x}"),
+        }),
+    }
+}
+
+/// Reads the columns of a block without rows, as sent by the server to describe the structure of
+/// the table data is inserted into.
+async fn read_header_columns<R>(reader: &mut R) -> Result<Vec<ColumnHeader>, NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    // Block info.
+    loop {
+        match read_varint(reader).await? {
+            0 => break,
+            1 => {
+                reader.read_u8().await.context(IoSnafu)?;
+            }
+            2 => {
+                reader.read_i32_le().await.context(IoSnafu)?;
+            }
+            field => {
+                return Err(NativeError::Io {
+                    source: std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("unknown block info field {field}"),
+                    ),
+                });
+            }
+        }
+    }
+
+    let num_columns = read_varint(reader).await?;
+    let num_rows = read_varint(reader).await?;
+    if num_rows != 0 {
+        return Err(NativeError::Io {
+            source: std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("expected an empty header block, got {num_rows} rows"),
+            ),
+        });
+    }
+
+    let mut columns = Vec::with_capacity(num_columns as usize);
+    for _ in 0..num_columns {
+        let name = read_string(reader).await?;
+        let type_name = read_string(reader).await?;
+        columns.push(ColumnHeader::new(name, type_name)?);
+    }
+    Ok(columns)
+}
+
+async fn read_exception<R>(reader: &mut R) -> Result<NativeError, NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    let code = reader.read_i32_le().await.context(IoSnafu)?;
+    let name = read_string(reader).await?;
+    let message = read_string(reader).await?;
+    let _stack_trace = read_string(reader).await?;
+    let has_nested = reader.read_u8().await.context(IoSnafu)? != 0;
+    if has_nested {
+        // Only the outermost exception is reported, but the nested ones need to be consumed.
+        Box::pin(read_exception(reader)).await?;
+    }
+    Ok(NativeError::Server {
+        code,
+        name,
+        message,
+    })
+}
+
+async fn read_progress<R>(reader: &mut R) -> Result<(), NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    // rows, bytes, total_rows, written_rows, written_bytes
+    for _ in 0..5 {
+        read_varint(reader).await?;
+    }
+    Ok(())
+}
+
+async fn read_profile_info<R>(reader: &mut R) -> Result<(), NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    // rows, blocks, bytes
+    for _ in 0..3 {
+        read_varint(reader).await?;
+    }
+    reader.read_u8().await.context(IoSnafu)?; // applied_limit
+    read_varint(reader).await?; // rows_before_limit
+    reader.read_u8().await.context(IoSnafu)?; // calculated_rows_before_limit
+    Ok(())
+}
+
+async fn read_varint<R>(reader: &mut R) -> Result<u64, NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = reader.read_u8().await.context(IoSnafu)?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(NativeError::Io {
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, "varint is too long"),
+    })
+}
+
+async fn read_string<R>(reader: &mut R) -> Result<String, NativeError>
+where
+    R: AsyncRead + Unpin,
+{
+    let len = read_varint(reader).await? as usize;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf).await.context(IoSnafu)?;
+    String::from_utf8(buf).map_err(|error| NativeError::Io {
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            assert_eq!(read_varint(&mut buf.as_slice()).await.unwrap(), value);
+        }
+
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 300);
+        assert_eq!(buf, [0xac, 0x02]);
+    }
+
+    #[tokio::test]
+    async fn compressed_frame_roundtrip() {
+        let data = b"ClickHouse ClickHouse ClickHouse ClickHouse".repeat(10);
+
+        let mut buf = Vec::new();
+        write_compressed_frame(&mut buf, &data);
+        assert_eq!(buf[CHECKSUM_SIZE], LZ4_METHOD);
+        assert_eq!(
+            u32::from_le_bytes(
+                buf[CHECKSUM_SIZE + 1..CHECKSUM_SIZE + 5]
+                    .try_into()
+                    .unwrap()
+            ) as usize,
+            buf.len() - CHECKSUM_SIZE
+        );
+
+        let decompressed = read_compressed_frame(&mut buf.as_slice()).await.unwrap();
+        assert_eq!(decompressed, data);
+
+        buf[CHECKSUM_SIZE + COMPRESSED_HEADER_SIZE] ^= 0xff;
+        assert!(matches!(
+            read_compressed_frame(&mut buf.as_slice()).await,
+            Err(NativeError::InvalidCompressedFrame { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn header_columns() {
+        let mut buf = Vec::new();
+        write_block_info(&mut buf);
+        write_varint(&mut buf, 2);
+        write_varint(&mut buf, 0);
+        write_string(&mut buf, "host");
+        write_string(&mut buf, "LowCardinality(String)");
+        write_string(&mut buf, "timestamp");
+        write_string(&mut buf, "DateTime64(3, 'UTC')");
+
+        let columns = read_header_columns(&mut buf.as_slice()).await.unwrap();
+        let names = columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["host", "timestamp"]);
+    }
+
+    #[tokio::test]
+    async fn server_exception() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&60i32.to_le_bytes());
+        write_string(&mut buf, "DB::Exception");
+        write_string(&mut buf, "Table default.missing does not exist");
+        write_string(&mut buf, "");
+        buf.push(1);
+        buf.extend_from_slice(&1000i32.to_le_bytes());
+        write_string(&mut buf, "DB::Exception");
+        write_string(&mut buf, "nested");
+        write_string(&mut buf, "");
+        buf.push(0);
+
+        let mut reader = buf.as_slice();
+        let error = read_exception(&mut reader).await.unwrap();
+        assert!(matches!(error, NativeError::Server { code: 60, .. }));
+        assert!(reader.is_empty());
+    }
+}
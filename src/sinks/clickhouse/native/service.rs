@@ -0,0 +1,300 @@
+//! Service implementation for the `clickhouse` sink when using the native protocol.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use tower::Service;
+use uuid::Uuid;
+use vector_lib::{
+    ByteSizeOf,
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata},
+    stream::DriverResponse,
+};
+
+use super::{
+    block::encode_block,
+    protocol::{Connection, ConnectionOptions, NativeError},
+};
+use crate::{
+    internal_events::EndpointBytesSent,
+    sinks::{
+        clickhouse::{config::QuerySettingsConfig, sink::PartitionKey},
+        prelude::{RequestMetadataBuilder, RetryLogic},
+    },
+};
+
+const NATIVE_PROTOCOL: &str = "clickhouse";
+
+/// Error codes of exceptions caused by the data itself, which would fail again if retried.
+///
+/// Reference: <https://github.com/ClickHouse/ClickHouse/blob/master/src/Common/ErrorCodes.cpp>
+const NON_RETRIABLE_ERROR_CODES: &[i32] = &[
+    6,   // CANNOT_PARSE_TEXT
+    16,  // NO_SUCH_COLUMN_IN_TABLE
+    27,  // CANNOT_PARSE_INPUT_ASSERTION_FAILED
+    53,  // TYPE_MISMATCH
+    60,  // UNKNOWN_TABLE
+    81,  // UNKNOWN_DATABASE
+    117, // INCORRECT_DATA
+    192, // UNKNOWN_USER
+    193, // WRONG_PASSWORD
+    516, // AUTHENTICATION_FAILED
+];
+
+#[derive(Clone, Default)]
+pub struct NativeRetryLogic;
+
+impl RetryLogic for NativeRetryLogic {
+    type Error = NativeError;
+    type Request = NativeRequest;
+    type Response = NativeResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            NativeError::Server { code, .. } => !NON_RETRIABLE_ERROR_CODES.contains(code),
+            NativeError::UnsupportedType { .. } => false,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeRequest {
+    pub key: PartitionKey,
+    pub events: Vec<Event>,
+    /// Generated once per request, so that retries of the request carry the same token.
+    pub deduplication_token: Option<String>,
+    pub finalizers: EventFinalizers,
+    pub metadata: RequestMetadata,
+}
+
+impl NativeRequest {
+    pub fn new(key: PartitionKey, mut events: Vec<Event>, deduplication_token: bool) -> Self {
+        let finalizers = events.take_finalizers();
+        let metadata_builder = RequestMetadataBuilder::from_events(&events);
+        let events_size = NonZeroUsize::new(events.size_of()).unwrap_or(NonZeroUsize::MIN);
+        let metadata = metadata_builder.with_request_size(events_size);
+        Self {
+            key,
+            events,
+            deduplication_token: deduplication_token.then(|| Uuid::new_v4().to_string()),
+            finalizers,
+            metadata,
+        }
+    }
+}
+
+impl Finalizable for NativeRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for NativeRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub struct NativeResponse {
+    events_byte_size: GroupedCountByteSize,
+    bytes_sent: usize,
+}
+
+impl DriverResponse for NativeResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        &self.events_byte_size
+    }
+
+    fn bytes_sent(&self) -> Option<usize> {
+        Some(self.bytes_sent)
+    }
+}
+
+/// Inserts blocks over a pool of native protocol connections.
+#[derive(Clone)]
+pub struct NativeService {
+    options: Arc<ConnectionOptions>,
+    idle: Arc<Mutex<Vec<Connection>>>,
+    settings: Arc<Vec<(&'static str, String)>>,
+    endpoint: String,
+}
+
+impl NativeService {
+    pub fn new(
+        options: ConnectionOptions,
+        insert_random_shard: bool,
+        query_settings: QuerySettingsConfig,
+    ) -> Self {
+        let endpoint = format!("{}:{}", options.host, options.port);
+        Self {
+            options: Arc::new(options),
+            idle: Arc::default(),
+            settings: Arc::new(query_settings_to_strings(
+                insert_random_shard,
+                query_settings,
+            )),
+            endpoint,
+        }
+    }
+
+    async fn connection(&self) -> Result<Connection, NativeError> {
+        let idle = self.idle.lock().expect("poisoned lock").pop();
+        match idle {
+            Some(connection) => Ok(connection),
+            None => Connection::connect(&self.options).await,
+        }
+    }
+
+    fn release(&self, connection: Connection) {
+        self.idle.lock().expect("poisoned lock").push(connection);
+    }
+}
+
+fn query_settings_to_strings(
+    insert_random_shard: bool,
+    query_settings: QuerySettingsConfig,
+) -> Vec<(&'static str, String)> {
+    let async_insert = query_settings.async_insert_settings;
+    let bool_setting = |value: bool| if value { "1" } else { "0" }.to_string();
+
+    let mut settings = Vec::new();
+    if insert_random_shard {
+        settings.push(("insert_distributed_one_random_shard", bool_setting(true)));
+    }
+    if let Some(enabled) = async_insert.enabled {
+        settings.push(("async_insert", bool_setting(enabled)));
+    }
+    if let Some(wait) = async_insert.wait_for_processing {
+        settings.push(("wait_for_async_insert", bool_setting(wait)));
+    }
+    if let Some(timeout) = async_insert.wait_for_processing_timeout {
+        settings.push(("wait_for_async_insert_timeout", timeout.to_string()));
+    }
+    if let Some(deduplicate) = async_insert.deduplicate {
+        settings.push(("async_insert_deduplicate", bool_setting(deduplicate)));
+    }
+    if let Some(max_data_size) = async_insert.max_data_size {
+        settings.push(("async_insert_max_data_size", max_data_size.to_string()));
+    }
+    if let Some(max_query_number) = async_insert.max_query_number {
+        settings.push((
+            "async_insert_max_query_number",
+            max_query_number.to_string(),
+        ));
+    }
+    settings
+}
+
+impl Service<NativeRequest> for NativeService {
+    type Response = NativeResponse;
+    type Error = NativeError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: NativeRequest) -> Self::Future {
+        let service = self.clone();
+        let future = async move {
+            let query = format!(
+                "INSERT INTO \"{}\".\"{}\" VALUES",
+                request.key.database.replace('\"', "\\\""),
+                request.key.table.replace('\"', "\\\"")
+            );
+            let mut settings = service.settings.as_ref().clone();
+            if let Some(token) = &request.deduplication_token {
+                settings.push(("insert_deduplication_token", token.clone()));
+            }
+
+            let mut connection = service.connection().await?;
+            // A connection that failed is left in an unknown state, so it is dropped rather than
+            // released back to the pool.
+            let bytes_sent = connection
+                .insert(&Uuid::new_v4().to_string(), &query, &settings, |columns| {
+                    Ok(encode_block(columns, &request.events))
+                })
+                .await?;
+            service.release(connection);
+
+            emit!(EndpointBytesSent {
+                byte_size: bytes_sent,
+                protocol: NATIVE_PROTOCOL,
+                endpoint: &service.endpoint,
+            });
+
+            Ok(NativeResponse {
+                events_byte_size: request
+                    .metadata
+                    .into_events_estimated_json_encoded_byte_size(),
+                bytes_sent,
+            })
+        };
+
+        Box::pin(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::clickhouse::config::AsyncInsertSettingsConfig;
+
+    #[test]
+    fn query_settings() {
+        let settings = query_settings_to_strings(
+            true,
+            QuerySettingsConfig {
+                async_insert_settings: AsyncInsertSettingsConfig {
+                    enabled: Some(true),
+                    wait_for_processing: Some(false),
+                    wait_for_processing_timeout: Some(500),
+                    ..AsyncInsertSettingsConfig::default()
+                },
+            },
+        );
+        assert_eq!(
+            settings,
+            [
+                ("insert_distributed_one_random_shard", "1".to_string()),
+                ("async_insert", "1".to_string()),
+                ("wait_for_async_insert", "0".to_string()),
+                ("wait_for_async_insert_timeout", "500".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn retriable_errors() {
+        let logic = NativeRetryLogic;
+        let server_error = |code| NativeError::Server {
+            code,
+            name: "DB::Exception".to_string(),
+            message: String::new(),
+        };
+
+        assert!(logic.is_retriable_error(&server_error(159)));
+        assert!(!logic.is_retriable_error(&server_error(53)));
+        assert!(!logic.is_retriable_error(&NativeError::UnsupportedType {
+            column: "attributes".to_string(),
+            type_name: "Map(String, String)".to_string(),
+        }));
+        assert!(logic.is_retriable_error(&NativeError::Io {
+            source: std::io::ErrorKind::ConnectionReset.into(),
+        }));
+    }
+}
@@ -0,0 +1,82 @@
+//! Implementation of the `clickhouse` sink when using the native protocol.
+
+use super::service::NativeRequest;
+use crate::sinks::{
+    clickhouse::{
+        config::Format,
+        sink::{KeyPartitioner, PartitionKey},
+    },
+    prelude::*,
+};
+
+pub struct NativeSink<S> {
+    batch_settings: BatcherSettings,
+    service: S,
+    database: Template,
+    table: Template,
+    transformer: Transformer,
+    deduplication_token: bool,
+}
+
+impl<S> NativeSink<S>
+where
+    S: Service<NativeRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: std::fmt::Debug + Into<crate::Error> + Send,
+{
+    pub const fn new(
+        batch_settings: BatcherSettings,
+        service: S,
+        database: Template,
+        table: Template,
+        transformer: Transformer,
+        deduplication_token: bool,
+    ) -> Self {
+        Self {
+            batch_settings,
+            service,
+            database,
+            table,
+            transformer,
+            deduplication_token,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+        let transformer = self.transformer;
+        let deduplication_token = self.deduplication_token;
+
+        input
+            .map(|mut event| {
+                transformer.transform(&mut event);
+                event
+            })
+            .batched_partitioned(
+                // The format is only meaningful for the HTTP interface.
+                KeyPartitioner::new(self.database, self.table, Format::default()),
+                || batch_settings.as_byte_size_config(),
+            )
+            .filter_map(|(key, batch)| async move { key.map(move |k| (k, batch)) })
+            .map(move |(key, events): (PartitionKey, Vec<Event>)| {
+                NativeRequest::new(key, events, deduplication_token)
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> StreamSink<Event> for NativeSink<S>
+where
+    S: Service<NativeRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: std::fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -14,8 +14,8 @@ use crate::{
     sinks::{
         HealthcheckError,
         elasticsearch::{
-            ElasticsearchAuthConfig, ElasticsearchCommonMode, ElasticsearchConfig,
-            OpenSearchServiceType, ParseError,
+            DataStreamIndexTemplate, ElasticsearchAuthConfig, ElasticsearchCommonMode,
+            ElasticsearchConfig, OpenSearchServiceType, ParseError,
         },
         util::{UriSerde, auth::Auth, http::RequestConfig},
     },
@@ -348,6 +348,57 @@ impl ElasticsearchCommon {
             }
         }
     }
+
+    /// Creates the index template of the data streams, unless it already exists and shouldn't be
+    /// overwritten.
+    pub async fn create_index_template(
+        &self,
+        client: HttpClient,
+        index_template: &DataStreamIndexTemplate,
+    ) -> crate::Result<()> {
+        let path = format!("/_index_template/{}", index_template.name);
+        if !index_template.overwrite {
+            let response = send(
+                Request::head(format!("{}{path}", self.base_url)),
+                Bytes::new(),
+                self.auth.as_ref(),
+                #[cfg(feature = "aws-core")]
+                &self.service_type,
+                &self.request,
+                client.clone(),
+            )
+            .await?;
+            if response.status() == StatusCode::OK {
+                debug!(message = "Index template already exists.", name = %index_template.name);
+                return Ok(());
+            }
+        }
+
+        let response = send(
+            Request::put(format!("{}{path}", self.base_url))
+                .header("Content-Type", "application/json"),
+            Bytes::from(serde_json::to_vec(&index_template.body())?),
+            self.auth.as_ref(),
+            #[cfg(feature = "aws-core")]
+            &self.service_type,
+            &self.request,
+            client,
+        )
+        .await?;
+        let status = response.status();
+        if status.is_success() {
+            info!(message = "Created index template.", name = %index_template.name);
+            Ok(())
+        } else {
+            let body = body::to_bytes(response.into_body()).await?;
+            Err(format!(
+                "Failed to create index template {:?}, {status}: {}",
+                index_template.name,
+                String::from_utf8_lossy(&body)
+            )
+            .into())
+        }
+    }
 }
 
 #[cfg(feature = "aws-core")]
@@ -425,12 +476,30 @@ async fn get(
     client: HttpClient,
     path: &str,
 ) -> crate::Result<Response<Body>> {
-    let mut builder = Request::get(format!("{base_url}{path}"));
+    send(
+        Request::get(format!("{base_url}{path}")),
+        Bytes::new(),
+        auth,
+        #[cfg(feature = "aws-core")]
+        service_type,
+        request,
+        client,
+    )
+    .await
+}
 
+async fn send(
+    mut builder: http::request::Builder,
+    body: Bytes,
+    auth: Option<&Auth>,
+    #[cfg(feature = "aws-core")] service_type: &OpenSearchServiceType,
+    request: &RequestConfig,
+    client: HttpClient,
+) -> crate::Result<Response<Body>> {
     for (header, value) in &request.headers {
         builder = builder.header(&header[..], &value[..]);
     }
-    let mut request = builder.body(Bytes::new())?;
+    let mut request = builder.body(body)?;
 
     if let Some(auth) = auth {
         match auth {
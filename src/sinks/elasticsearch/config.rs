@@ -13,7 +13,10 @@ use vrl::value::Kind;
 
 use crate::{
     codecs::Transformer,
-    config::{AcknowledgementsConfig, DataType, Input, SinkConfig, SinkContext},
+    config::{
+        AcknowledgementsConfig, DataType, Input, SinkConfig, SinkContext,
+        dead_letter::{DeadLetterSender, RESPONSES_OUTPUT},
+    },
     event::{EventRef, LogEvent, Value},
     http::{HttpClient, QueryParameters},
     internal_events::TemplateRenderingError,
@@ -40,6 +43,9 @@ use crate::{
 /// The field name for the timestamp required by data stream mode
 pub const DATA_STREAM_TIMESTAMP_KEY: &str = "@timestamp";
 
+/// The name of the output the rejected documents are sent to.
+pub const REJECTED_OUTPUT: &str = "rejected";
+
 /// The Amazon OpenSearch service type, either managed or serverless; primarily, selects the
 /// correct AWS service to use when calculating the AWS v4 signature + disables features
 /// unsupported by serverless: Elasticsearch API version autodetection, health checks
@@ -134,6 +140,28 @@ pub struct ElasticsearchConfig {
     #[configurable(metadata(docs::advanced))]
     pub request_retry_partial: bool,
 
+    /// Whether or not to send the documents rejected by Elasticsearch to the `rejected` output.
+    ///
+    /// The documents that Elasticsearch refuses to index, such as the ones that don't match the
+    /// mapping of their index, are sent as logs to the `<sink_id>.rejected` output, which other
+    /// components can use as an input, for example to store them for reprocessing. Like for the
+    /// events rerouted by the `remap` transform, the reason of the rejection is added to the
+    /// `dropped` field of the metadata of the logs.
+    ///
+    /// The documents that failed with a retriable error aren't rejected.
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub reroute_rejected: bool,
+
+    /// Whether or not to send the responses to the bulk requests to the `responses` output.
+    ///
+    /// The responses are sent as logs with their `status_code`, `headers`, and `body` to the
+    /// `<sink_id>.responses` output, which other components can use as an input, for example to
+    /// keep the identifiers assigned to the documents or the details of their errors.
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub capture_responses: bool,
+
     /// The name of the event key that should map to Elasticsearch’s [`_id` field][es_id].
     ///
     /// By default, the `_id` field is not set, which allows Elasticsearch to set this
@@ -244,6 +272,8 @@ impl Default for ElasticsearchConfig {
             api_version: Default::default(),
             suppress_type_name: false,
             request_retry_partial: false,
+            reroute_rejected: false,
+            capture_responses: false,
             id_key: None,
             pipeline: None,
             mode: Default::default(),
@@ -267,6 +297,16 @@ impl Default for ElasticsearchConfig {
 }
 
 impl ElasticsearchConfig {
+    fn data_stream_index_template(&self) -> Option<&DataStreamIndexTemplate> {
+        match self.mode {
+            ElasticsearchMode::Bulk => None,
+            ElasticsearchMode::DataStream => self
+                .data_stream
+                .as_ref()
+                .and_then(|data_stream| data_stream.index_template.as_ref()),
+        }
+    }
+
     pub fn common_mode(&self) -> crate::Result<ElasticsearchCommonMode> {
         match self.mode {
             ElasticsearchMode::Bulk => Ok(ElasticsearchCommonMode::Bulk {
@@ -388,6 +428,14 @@ pub struct DataStreamConfig {
     /// This ensures that fields match the name of the data stream that is receiving events.
     #[serde(default = "DataStreamConfig::default_sync_fields")]
     pub sync_fields: bool,
+
+    /// An index template to create when the sink starts, so that the data streams receiving events
+    /// are created with the expected settings and mappings.
+    ///
+    /// If the template can't be created on any of the endpoints, the sink fails to start.
+    #[serde(default)]
+    #[configurable(derived)]
+    pub index_template: Option<DataStreamIndexTemplate>,
 }
 
 impl Default for DataStreamConfig {
@@ -398,6 +446,7 @@ impl Default for DataStreamConfig {
             namespace: Self::default_namespace(),
             auto_routing: Self::default_auto_routing(),
             sync_fields: Self::default_sync_fields(),
+            index_template: None,
         }
     }
 }
@@ -541,6 +590,68 @@ impl DataStreamConfig {
     }
 }
 
+/// Elasticsearch data stream index template configuration.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct DataStreamIndexTemplate {
+    /// The name of the index template.
+    #[configurable(metadata(docs::examples = "vector-logs"))]
+    pub name: String,
+
+    /// The patterns of the names of the data streams the index template applies to.
+    #[configurable(metadata(docs::examples = "logs-nginx-*"))]
+    pub index_patterns: Vec<String>,
+
+    /// The priority of the index template.
+    ///
+    /// The index template with the highest priority applies when several of them match a data
+    /// stream. The built-in index templates of Elasticsearch have a priority of `100`.
+    #[serde(default = "DataStreamIndexTemplate::default_priority")]
+    pub priority: u64,
+
+    /// The component templates the index template is composed of, in the order they are merged.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "logs-mappings"))]
+    #[configurable(metadata(docs::examples = "logs-settings"))]
+    pub composed_of: Vec<String>,
+
+    /// The name of the [index lifecycle management (ILM) policy][ilm] that manages the backing indices
+    /// of the data streams.
+    ///
+    /// [ilm]: https://www.elastic.co/guide/en/elasticsearch/reference/current/index-lifecycle-management.html
+    #[configurable(metadata(docs::examples = "logs"))]
+    pub lifecycle_policy: Option<String>,
+
+    /// Whether to replace an existing index template with the same name.
+    ///
+    /// By default, an existing index template is left untouched.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+impl DataStreamIndexTemplate {
+    const fn default_priority() -> u64 {
+        200
+    }
+
+    /// The body of the request creating the index template.
+    pub fn body(&self) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "index_patterns": self.index_patterns,
+            "data_stream": {},
+            "priority": self.priority,
+            "composed_of": self.composed_of,
+        });
+        if let Some(policy) = &self.lifecycle_policy {
+            body["template"] = serde_json::json!({
+                "settings": { "index.lifecycle.name": policy },
+            });
+        }
+        body
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "elasticsearch")]
 impl SinkConfig for ElasticsearchConfig {
@@ -550,8 +661,28 @@ impl SinkConfig for ElasticsearchConfig {
 
         let client = HttpClient::new(common.tls_settings.clone(), cx.proxy())?;
 
+        if let Some(index_template) = self.data_stream_index_template() {
+            futures::future::select_ok(commons.iter().map(|common| {
+                common
+                    .create_index_template(client.clone(), index_template)
+                    .boxed()
+            }))
+            .await?;
+        }
+
         let request_limits = self.request.tower.into_settings();
 
+        let rejected = cx
+            .key
+            .as_ref()
+            .filter(|_| self.reroute_rejected)
+            .map(|key| DeadLetterSender::new(key, "elasticsearch", REJECTED_OUTPUT));
+        let responses = cx
+            .key
+            .as_ref()
+            .filter(|_| self.capture_responses)
+            .map(|key| DeadLetterSender::new(key, "elasticsearch", RESPONSES_OUTPUT));
+
         let health_config = self.endpoint_health.clone().unwrap_or_default();
 
         let services = commons
@@ -561,7 +692,12 @@ impl SinkConfig for ElasticsearchConfig {
                 let endpoint = common.base_url.clone();
 
                 let http_request_builder = HttpRequestBuilder::new(&common, self);
-                let service = ElasticsearchService::new(client.clone(), http_request_builder);
+                let service = ElasticsearchService::new(
+                    client.clone(),
+                    http_request_builder,
+                    rejected.clone(),
+                    responses.clone(),
+                );
 
                 (endpoint, service)
             })
@@ -600,11 +736,23 @@ impl SinkConfig for ElasticsearchConfig {
     fn acknowledgements(&self) -> &AcknowledgementsConfig {
         &self.acknowledgements
     }
+
+    fn outputs(&self) -> Vec<&'static str> {
+        let mut outputs = Vec::new();
+        if self.reroute_rejected {
+            outputs.push(REJECTED_OUTPUT);
+        }
+        if self.capture_responses {
+            outputs.push(RESPONSES_OUTPUT);
+        }
+        outputs
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{ComponentKey, Format, OutputId, load_from_str};
 
     #[test]
     fn generate_config() {
@@ -645,6 +793,32 @@ mod tests {
         assert!(config.data_stream.is_some());
     }
 
+    #[test]
+    fn parse_data_stream_index_template() {
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoints = [""]
+            mode = "data_stream"
+            data_stream.index_template.name = "vector-logs"
+            data_stream.index_template.index_patterns = ["logs-vector-*"]
+            data_stream.index_template.lifecycle_policy = "logs"
+        "#,
+        )
+        .unwrap();
+        let index_template = config.data_stream_index_template().unwrap();
+        assert_eq!(
+            index_template.body(),
+            serde_json::json!({
+                "index_patterns": ["logs-vector-*"],
+                "data_stream": {},
+                "priority": 200,
+                "composed_of": [],
+                "template": { "settings": { "index.lifecycle.name": "logs" } },
+            })
+        );
+        assert!(!index_template.overwrite);
+    }
+
     #[test]
     fn parse_distribution() {
         toml::from_str::<ElasticsearchConfig>(
@@ -757,4 +931,62 @@ mod tests {
         assert!(matches!(config.auth, Some(ElasticsearchAuthConfig::Aws(_))));
         assert_eq!(config.api_version, ElasticsearchApiVersion::Auto);
     }
+
+    #[test]
+    fn adds_rejected_output() {
+        let config = load_from_str(
+            r#"
+            [sources.in]
+            type = "test_basic"
+
+            [sinks.es]
+            type = "elasticsearch"
+            inputs = ["in"]
+            endpoints = ["http://localhost:9200"]
+            reroute_rejected = true
+
+            [sinks.out]
+            type = "test_basic"
+            inputs = ["es.rejected"]
+        "#,
+            Format::Toml,
+        )
+        .unwrap();
+        let rejected = ComponentKey::from("es.rejected");
+        assert!(config.source(&rejected).is_some());
+        assert_eq!(
+            vec![OutputId::from(rejected)],
+            config
+                .sink(&ComponentKey::from("out"))
+                .unwrap()
+                .inputs
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn rejects_rejected_output_loops() {
+        let errors = load_from_str(
+            r#"
+            [sources.in]
+            type = "test_basic"
+
+            [transforms.retry]
+            type = "test_basic"
+            inputs = ["es.rejected"]
+
+            [sinks.es]
+            type = "elasticsearch"
+            inputs = ["in", "retry"]
+            endpoints = ["http://localhost:9200"]
+            reroute_rejected = true
+        "#,
+            Format::Toml,
+        )
+        .unwrap_err();
+        assert_eq!(
+            vec!["Sink \"es\" receives the events of its own outputs.".to_owned()],
+            errors
+        );
+    }
 }
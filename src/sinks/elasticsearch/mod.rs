@@ -72,6 +72,9 @@ pub enum ElasticsearchMode {
     ///
     /// If the mode is set to `data_stream` and a `timestamp` field is present in a message,
     /// Vector renames this field to the expected `@timestamp` to comply with the Elastic Common Schema.
+    ///
+    /// Documents rejected with a `409 Conflict` status because they already exist, for example
+    /// when a request is retried with `id_key` set, are considered delivered.
     DataStream,
 }
 
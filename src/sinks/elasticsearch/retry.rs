@@ -18,6 +18,56 @@ use crate::{
     },
 };
 
+/// Whether a bulk response body reports failed items.
+///
+/// The `create` actions that failed because the document already exists aren't considered as
+/// failed, since the document was already written, for example by an earlier attempt of a retried
+/// request.
+pub(super) fn bulk_response_has_errors(body: &str) -> bool {
+    body.contains("\"errors\":true")
+        && !EsResultResponse::parse(body).is_ok_and(|response| response.only_create_conflicts())
+}
+
+/// A document of a bulk request that Elasticsearch refused to index.
+pub(super) struct RejectedItem {
+    /// The position of the document in the request.
+    pub(super) position: usize,
+    pub(super) status: u16,
+    pub(super) reason: String,
+}
+
+/// Gets the documents of a bulk request that Elasticsearch rejected, such as the ones that don't
+/// match the mapping of their index, and whether they are the only failed items of the response.
+///
+/// The items that failed with a retriable error aren't rejected, and the `create` conflicts aren't
+/// failed.
+pub(super) fn rejected_items(body: &str) -> Option<(Vec<RejectedItem>, bool)> {
+    let response = EsResultResponse::parse(body).ok()?;
+    let mut rejected = Vec::new();
+    let mut only_rejected = true;
+    for (position, item) in response.items.iter().enumerate() {
+        let result = item.result();
+        let Some(error) = &result.error else {
+            continue;
+        };
+        match result
+            .status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+        {
+            Some(StatusCode::CONFLICT) if matches!(item, EsResultItem::Create(_)) => (),
+            Some(status) if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS => {
+                rejected.push(RejectedItem {
+                    position,
+                    status: status.as_u16(),
+                    reason: format!("{}: {}", error.err_type, error.reason),
+                });
+            }
+            _ => only_rejected = false,
+        }
+    }
+    Some((rejected, only_rejected))
+}
+
 #[derive(Deserialize, Debug)]
 struct EsResultResponse {
     items: Vec<EsResultItem>,
@@ -40,6 +90,16 @@ impl EsResultResponse {
         })
     }
 
+    /// Whether the only failed items are `create` actions for documents that already exist.
+    fn only_create_conflicts(&self) -> bool {
+        self.items.iter().all(|item| {
+            let result = item.result();
+            result.error.is_none()
+                || (matches!(item, EsResultItem::Create(_))
+                    && result.status == Some(StatusCode::CONFLICT.as_u16()))
+        })
+    }
+
     /// Selects the first error since logging all errors would be quite verbose and many are duplicates.
     /// If partial retry is enabled and we don't retry, this is because there is no retriable error in the
     /// response, thus all errors are equally interesting so logging the first is sufficient.
@@ -130,7 +190,7 @@ impl RetryLogic for ElasticsearchRetryLogic {
             _ if status.is_success() => {
                 let body = String::from_utf8_lossy(response.http_response.body());
 
-                if body.contains("\"errors\":true") {
+                if bulk_response_has_errors(&body) {
                     match EsResultResponse::parse(&body) {
                         Ok(resp) => {
                             if self.retry_partial {
@@ -254,6 +314,50 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn handles_create_conflicts_as_successes() {
+        let json = "{\"took\":3,\"errors\":true,\"items\":[{\"create\":{\"_index\":\".ds-logs-generic-default-2024.01.01-000001\",\"_id\":\"1\",\"status\":409,\"error\":{\"type\":\"version_conflict_engine_exception\",\"reason\":\"[1]: version conflict, document already exists (current version [1])\"}}},{\"create\":{\"_index\":\".ds-logs-generic-default-2024.01.01-000001\",\"_id\":\"2\",\"status\":201}}]}";
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .body(Bytes::from(json))
+            .unwrap();
+        let logic = ElasticsearchRetryLogic {
+            retry_partial: false,
+        };
+        assert!(matches!(
+            logic.should_retry_response(&ElasticsearchResponse {
+                http_response: response,
+                event_status: EventStatus::Delivered,
+                events_byte_size: CountByteSize(2, JsonSize::new(1)).into(),
+            }),
+            RetryAction::Successful
+        ));
+
+        // Conflicts of other actions are still errors.
+        let json = json.replace("create", "index");
+        assert!(bulk_response_has_errors(&json));
+    }
+
+    #[test]
+    fn finds_rejected_items() {
+        let json = "{\"took\":3,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test\",\"_id\":\"1\",\"status\":201}},{\"index\":{\"_index\":\"test\",\"_id\":\"2\",\"status\":400,\"error\":{\"type\":\"mapper_parsing_exception\",\"reason\":\"failed to parse field [count] of type [long]\"}}},{\"create\":{\"_index\":\"test\",\"_id\":\"3\",\"status\":409,\"error\":{\"type\":\"version_conflict_engine_exception\",\"reason\":\"document already exists\"}}}]}";
+        let (rejected, only_rejected) = rejected_items(json).unwrap();
+        assert!(only_rejected);
+        assert_eq!(1, rejected.len());
+        assert_eq!(1, rejected[0].position);
+        assert_eq!(400, rejected[0].status);
+        assert_eq!(
+            "mapper_parsing_exception: failed to parse field [count] of type [long]",
+            rejected[0].reason
+        );
+
+        // Retriable errors aren't rejections.
+        let json = json.replace("201}", "429,\"error\":{\"type\":\"es_rejected_execution_exception\",\"reason\":\"rejected execution\"}}");
+        let (rejected, only_rejected) = rejected_items(&json).unwrap();
+        assert!(!only_rejected);
+        assert_eq!(1, rejected.len());
+    }
+
     #[test]
     fn get_index_error_reason() {
         let json = "{\"took\":185,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test-hgw28jv10u\",\"_type\":\"log_lines\",\"_id\":\"3GhQLXEBE62DvOOUKdFH\",\"status\":400,\"error\":{\"type\":\"illegal_argument_exception\",\"reason\":\"mapper [message] of different type, current_type [long], merged_type [text]\"}}}]}";
@@ -17,10 +17,15 @@ use vector_lib::{
 
 use super::{ElasticsearchCommon, ElasticsearchConfig};
 use crate::{
-    event::{EventFinalizers, EventStatus, Finalizable},
+    config::dead_letter::{DeadLetterSender, Failure},
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
     http::HttpClient,
     sinks::{
-        elasticsearch::{encoder::ProcessedEvent, request_builder::ElasticsearchRequestBuilder},
+        elasticsearch::{
+            encoder::ProcessedEvent,
+            request_builder::ElasticsearchRequestBuilder,
+            retry::{bulk_response_has_errors, rejected_items},
+        },
         util::{
             Compression, ElementCount,
             auth::Auth,
@@ -77,12 +82,16 @@ pub struct ElasticsearchService {
         BoxFuture<'static, Result<http::Request<Bytes>, crate::Error>>,
         ElasticsearchRequest,
     >,
+    rejected: Option<DeadLetterSender>,
+    responses: Option<DeadLetterSender>,
 }
 
 impl ElasticsearchService {
     pub fn new(
         http_client: HttpClient<Body>,
         http_request_builder: HttpRequestBuilder,
+        rejected: Option<DeadLetterSender>,
+        responses: Option<DeadLetterSender>,
     ) -> ElasticsearchService {
         let http_request_builder = Arc::new(http_request_builder);
         let batch_service = HttpBatchService::new(http_client, move |req| {
@@ -91,7 +100,11 @@ impl ElasticsearchService {
                 Box::pin(async move { request_builder.build_request(req).await });
             future
         });
-        ElasticsearchService { batch_service }
+        ElasticsearchService {
+            batch_service,
+            rejected,
+            responses,
+        }
     }
 }
 
@@ -192,13 +205,26 @@ impl Service<ElasticsearchRequest> for ElasticsearchService {
     // Emission of internal events for errors and dropped events is handled upstream by the caller.
     fn call(&mut self, mut req: ElasticsearchRequest) -> Self::Future {
         let mut http_service = self.batch_service.clone();
+        let rejected = self.rejected.clone();
+        let responses = self.responses.clone();
         Box::pin(async move {
             http_service.ready().await?;
             let events_byte_size =
                 std::mem::take(req.metadata_mut()).into_events_estimated_json_encoded_byte_size();
+            let original_events = rejected.is_some().then(|| req.original_events.clone());
             let http_response = http_service.call(req).await?;
+            if let Some(responses) = responses {
+                responses.send_response(&http_response);
+            }
 
-            let event_status = get_event_status(&http_response);
+            let mut event_status = get_event_status(&http_response);
+            if event_status == EventStatus::Rejected
+                && let Some(rejected) = rejected
+                && let Some(events) = original_events
+                && reroute_rejected(&rejected, &http_response, events)
+            {
+                event_status = EventStatus::Delivered;
+            }
             Ok(ElasticsearchResponse {
                 event_status,
                 http_response,
@@ -221,11 +247,47 @@ fn emit_bad_response_error(response: &Response<Bytes>) {
     );
 }
 
+/// Sends the documents rejected by Elasticsearch to the rejected output, returning whether they
+/// were the only failed documents of the request and were all sent.
+fn reroute_rejected(
+    rejected: &DeadLetterSender,
+    response: &Response<Bytes>,
+    events: Vec<ProcessedEvent>,
+) -> bool {
+    if !response.status().is_success() {
+        return false;
+    }
+    let body = String::from_utf8_lossy(response.body());
+    let Some((items, only_rejected)) = rejected_items(&body) else {
+        return false;
+    };
+
+    let count = items.len();
+    let mut events = events.into_iter().map(Some).collect::<Vec<_>>();
+    let logs = items
+        .into_iter()
+        .filter_map(|item| {
+            let mut event = Event::from(events.get_mut(item.position)?.take()?.log);
+            rejected.annotate(
+                &mut event,
+                Failure {
+                    reason: "rejected",
+                    message: &item.reason,
+                    status_code: Some(item.status),
+                },
+            );
+            Some(event)
+        })
+        .collect::<Vec<_>>();
+    let all_found = logs.len() == count;
+    rejected.send(logs) && all_found && only_rejected
+}
+
 fn get_event_status(response: &Response<Bytes>) -> EventStatus {
     let status = response.status();
     if status.is_success() {
         let body = String::from_utf8_lossy(response.body());
-        if body.contains("\"errors\":true") {
+        if bulk_response_has_errors(&body) {
             emit_bad_response_error(response);
             EventStatus::Rejected
         } else {
@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use http::uri::{Scheme, Uri};
+use indoc::indoc;
+use snafu::{ResultExt, Snafu};
+use tonic::transport::{Certificate, ClientTlsConfig, Endpoint, Identity};
+
+use super::{
+    proto::{GetWriteStreamRequest, WriteStreamView, big_query_write_client::BigQueryWriteClient},
+    schema::RowEncoder,
+    service::{AuthInterceptor, BigqueryRetryLogic, BigqueryService, WriteClient},
+    sink::BigquerySink,
+};
+use crate::{
+    gcp::{GcpAuthConfig, Scope},
+    sinks::{UriParseSnafu, prelude::*},
+};
+
+/// The endpoint of the BigQuery Storage Write API.
+const BIGQUERY_STORAGE_URL: &str = "https://bigquerystorage.googleapis.com";
+
+// 10MB maximum request size, leaving room for the writer schema:
+// https://cloud.google.com/bigquery/quotas#write-api-limits
+const MAX_BATCH_PAYLOAD_SIZE: usize = 9_500_000;
+
+#[derive(Debug, Snafu)]
+enum BigqueryConfigError {
+    #[snafu(display("Could not create endpoint: {}", source))]
+    Endpoint { source: tonic::transport::Error },
+    #[snafu(display("Could not set up endpoint TLS settings: {}", source))]
+    EndpointTls { source: tonic::transport::Error },
+    #[snafu(display("Failed to get the schema of the table: {}", source))]
+    GetSchema { source: tonic::Status },
+    #[snafu(display("The schema of the table is missing."))]
+    MissingSchema,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BigqueryDefaultBatchSettings;
+
+impl SinkBatchSettings for BigqueryDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(10_000);
+    const MAX_BYTES: Option<usize> = Some(MAX_BATCH_PAYLOAD_SIZE);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+/// Configuration for the `gcp_bigquery` sink.
+#[configurable_component(sink(
+    "gcp_bigquery",
+    "Store log events in a GCP BigQuery table with the Storage Write API."
+))]
+#[derive(Clone, Debug)]
+pub struct BigqueryConfig {
+    /// The project of the table.
+    #[configurable(metadata(docs::examples = "vector-123456"))]
+    pub project: String,
+
+    /// The dataset of the table.
+    #[configurable(metadata(docs::examples = "observability"))]
+    pub dataset: String,
+
+    /// The table to write the events to.
+    ///
+    /// The table must already exist. The fields of the events are written to the columns named
+    /// after them, once sanitized: lowercased, with the characters other than ASCII letters, digits,
+    /// and underscores replaced by underscores, and prefixed with an underscore if they start with
+    /// a digit. The fields without a column are ignored.
+    #[configurable(metadata(docs::examples = "logs"))]
+    pub table: String,
+
+    /// The endpoint of the BigQuery Storage Write API.
+    ///
+    /// The scheme (`http` or `https`) must be specified. No path should be included.
+    #[serde(default = "default_endpoint")]
+    #[configurable(metadata(docs::examples = "https://bigquerystorage.googleapis.com"))]
+    pub endpoint: String,
+
+    #[serde(default, flatten)]
+    pub auth: GcpAuthConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<BigqueryDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    pub encoding: Transformer,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_endpoint() -> String {
+    BIGQUERY_STORAGE_URL.to_string()
+}
+
+impl GenerateConfig for BigqueryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(indoc! {r#"
+            project = "my-project"
+            dataset = "my-dataset"
+            table = "my-table"
+        "#})
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_bigquery")]
+impl SinkConfig for BigqueryConfig {
+    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let auth = self.auth.build(Scope::BigQuery).await?;
+        let client = self.client(AuthInterceptor { auth: auth.clone() })?;
+        let table = format!(
+            "projects/{}/datasets/{}/tables/{}",
+            self.project, self.dataset, self.table
+        );
+
+        // The rows are encoded according to the schema of the table, which is read from its
+        // default write stream.
+        let table_schema = client
+            .clone()
+            .get_write_stream(GetWriteStreamRequest {
+                name: format!("{table}/streams/_default"),
+                view: WriteStreamView::Full as i32,
+            })
+            .await
+            .context(GetSchemaSnafu)?
+            .into_inner()
+            .table_schema
+            .ok_or(BigqueryConfigError::MissingSchema)?;
+        let encoder = RowEncoder::new(&table_schema)?;
+
+        auth.spawn_regenerate_token();
+
+        let healthcheck = healthcheck(client.clone(), table.clone()).boxed();
+
+        let protocol = self
+            .endpoint
+            .parse::<Uri>()
+            .ok()
+            .and_then(|uri| uri.scheme_str().map(ToOwned::to_owned))
+            .unwrap_or_else(|| "https".to_owned());
+        let service = ServiceBuilder::new()
+            .settings(self.request.into_settings(), BigqueryRetryLogic)
+            .service(BigqueryService::new(
+                client,
+                table,
+                encoder.writer_schema().clone(),
+                protocol,
+                self.endpoint.clone(),
+            ));
+
+        let batch_settings = self
+            .batch
+            .validate()?
+            .limit_max_bytes(MAX_BATCH_PAYLOAD_SIZE)?
+            .into_batcher_settings()?;
+
+        let sink = BigquerySink {
+            service,
+            batch_settings,
+            transformer: self.encoding.clone(),
+            encoder: Arc::new(encoder),
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+impl BigqueryConfig {
+    fn client(&self, interceptor: AuthInterceptor) -> crate::Result<WriteClient> {
+        let uri: Uri = self.endpoint.parse().context(UriParseSnafu)?;
+
+        let tls = TlsSettings::from_options(self.tls.as_ref())?;
+        let host = uri.host().unwrap_or("bigquerystorage.googleapis.com");
+        let mut tls_config = ClientTlsConfig::new().domain_name(host);
+        if let Some((cert, key)) = tls.identity_pem() {
+            tls_config = tls_config.identity(Identity::from_pem(cert, key));
+        }
+        for authority in tls.authorities_pem() {
+            tls_config = tls_config.ca_certificate(Certificate::from_pem(authority));
+        }
+
+        let mut endpoint: Endpoint = uri.to_string().parse().context(EndpointSnafu)?;
+        if uri.scheme() != Some(&Scheme::HTTP) {
+            endpoint = endpoint.tls_config(tls_config).context(EndpointTlsSnafu)?;
+        }
+
+        Ok(BigQueryWriteClient::with_interceptor(
+            endpoint.connect_lazy(),
+            interceptor,
+        ))
+    }
+}
+
+async fn healthcheck(mut client: WriteClient, table: String) -> crate::Result<()> {
+    client
+        .get_write_stream(GetWriteStreamRequest {
+            name: format!("{table}/streams/_default"),
+            view: WriteStreamView::Basic as i32,
+        })
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<BigqueryConfig>();
+    }
+}
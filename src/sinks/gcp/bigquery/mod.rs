@@ -0,0 +1,44 @@
+//! The `gcp_bigquery` sink.
+//!
+//! Writes log events to a BigQuery table with the [Storage Write API][write_api]. The events are
+//! encoded as rows of the table and appended to a committed write stream, at offsets that make the
+//! retries of the requests exactly-once.
+//!
+//! [write_api]: https://cloud.google.com/bigquery/docs/write-api
+
+mod config;
+mod schema;
+mod service;
+mod sink;
+
+pub use config::BigqueryConfig;
+
+// prost emits some generated code that includes clones on `Arc`
+// objects, which causes a clippy ding on this block. We don't
+// directly control the generated code, so allow this lint here.
+#[allow(clippy::clone_on_ref_ptr)]
+// https://github.com/hyperium/tonic/issues/1350
+#[allow(clippy::missing_const_for_fn)]
+#[allow(warnings)]
+mod proto {
+    pub mod google {
+        pub mod rpc {
+            include!(concat!(env!("OUT_DIR"), "/google.rpc.rs"));
+        }
+
+        pub mod cloud {
+            pub mod bigquery {
+                pub mod storage {
+                    pub mod v1 {
+                        include!(concat!(
+                            env!("OUT_DIR"),
+                            "/google.cloud.bigquery.storage.v1.rs"
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    pub use google::cloud::bigquery::storage::v1::*;
+}
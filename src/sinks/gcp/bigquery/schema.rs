@@ -0,0 +1,516 @@
+//! Mapping of the events to rows of the BigQuery table.
+//!
+//! The rows are serialized as Protocol Buffers messages, whose descriptor is derived from the
+//! schema of the table. The fields of the events are matched to the columns of the table by their
+//! sanitized names, see [`sanitize_field_name`].
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use prost::Message as _;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, FieldDescriptor, MessageDescriptor, prost::Message as _,
+};
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    field_descriptor_proto::{Label, Type as ProtoType},
+};
+use snafu::{ResultExt, Snafu};
+use vrl::value::{ObjectMap, Value};
+
+use super::proto::{
+    ProtoSchema, TableFieldSchema, TableSchema,
+    table_field_schema::{Mode, Type},
+};
+use crate::event::LogEvent;
+
+/// The name of the message of the rows.
+const ROW_MESSAGE: &str = "Row";
+
+#[derive(Debug, Snafu)]
+pub(super) enum SchemaError {
+    #[snafu(display("The table has no columns."))]
+    NoColumns,
+    #[snafu(display(
+        "Column `{}` can't be written: its name isn't a valid Protocol Buffers field name.",
+        column
+    ))]
+    InvalidColumnName { column: String },
+    #[snafu(display("Column `{}` has the unsupported type {}.", column, r#type))]
+    UnsupportedType { column: String, r#type: String },
+    #[snafu(display("Invalid row descriptor: {}", source))]
+    Descriptor {
+        source: prost_reflect::DescriptorError,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum RowEncodingError {
+    #[snafu(display("The event isn't an object."))]
+    NotAnObject,
+    #[snafu(display("Missing value for the required column `{}`.", column))]
+    MissingValue { column: String },
+    #[snafu(display("Invalid {} value for the {} column `{}`.", kind, r#type, column))]
+    InvalidValue {
+        column: String,
+        r#type: &'static str,
+        kind: &'static str,
+    },
+}
+
+/// Converts the name of a field of the events to the name of the column it's written to.
+///
+/// Column names only contain letters, digits, and underscores, and don't start with a digit, so
+/// the other characters are replaced with underscores, and an underscore is prepended to names
+/// starting with a digit. Column names are case-insensitive, so the name is also lowercased.
+pub(super) fn sanitize_field_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len() + 1);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.push('_');
+    }
+    sanitized.extend(name.chars().map(|c| {
+        if c.is_ascii_alphanumeric() {
+            c.to_ascii_lowercase()
+        } else {
+            '_'
+        }
+    }));
+    sanitized
+}
+
+/// Encodes the events into rows of the table.
+#[derive(Debug)]
+pub(super) struct RowEncoder {
+    columns: Columns,
+    writer_schema: ProtoSchema,
+}
+
+impl RowEncoder {
+    pub(super) fn new(table_schema: &TableSchema) -> Result<Self, SchemaError> {
+        if table_schema.fields.is_empty() {
+            return Err(SchemaError::NoColumns);
+        }
+
+        let descriptor = message_descriptor(ROW_MESSAGE, ROW_MESSAGE, &table_schema.fields)?;
+        let file = FileDescriptorProto {
+            name: Some("vector_bigquery_row.proto".to_owned()),
+            message_type: vec![descriptor.clone()],
+            syntax: Some("proto2".to_owned()),
+            ..Default::default()
+        };
+        let pool = DescriptorPool::decode(
+            FileDescriptorSet { file: vec![file] }
+                .encode_to_vec()
+                .as_slice(),
+        )
+        .context(DescriptorSnafu)?;
+        let message = pool
+            .get_message_by_name(ROW_MESSAGE)
+            .expect("row message should be in its descriptor pool");
+
+        Ok(Self {
+            columns: Columns::new(&table_schema.fields, message),
+            writer_schema: ProtoSchema {
+                proto_descriptor: Some(descriptor),
+            },
+        })
+    }
+
+    /// The schema sent with the rows.
+    pub(super) const fn writer_schema(&self) -> &ProtoSchema {
+        &self.writer_schema
+    }
+
+    /// Serializes the event as a row of the table.
+    ///
+    /// The fields of the event that don't match a column are ignored.
+    pub(super) fn encode(&self, log: &LogEvent) -> Result<Vec<u8>, RowEncodingError> {
+        let object = log.as_map().ok_or(RowEncodingError::NotAnObject)?;
+        Ok(self.columns.encode(object)?.encode_to_vec())
+    }
+}
+
+/// Builds the descriptor of the message of the columns, nesting the messages of the `STRUCT`
+/// columns in it.
+fn message_descriptor(
+    name: &str,
+    full_name: &str,
+    columns: &[TableFieldSchema],
+) -> Result<DescriptorProto, SchemaError> {
+    let mut descriptor = DescriptorProto {
+        name: Some(name.to_owned()),
+        ..Default::default()
+    };
+
+    for (number, column) in (1..).zip(columns) {
+        if !is_valid_field_name(&column.name) {
+            return InvalidColumnNameSnafu {
+                column: column.name.clone(),
+            }
+            .fail();
+        }
+
+        let r#type = Type::try_from(column.r#type).unwrap_or(Type::TypeUnspecified);
+        let mut field = FieldDescriptorProto {
+            name: Some(column.name.clone()),
+            number: Some(number),
+            label: Some(match Mode::try_from(column.mode) {
+                Ok(Mode::Repeated) => Label::Repeated,
+                _ => Label::Optional,
+            } as i32),
+            ..Default::default()
+        };
+        let proto_type = match r#type {
+            Type::String
+            | Type::Json
+            | Type::Geography
+            | Type::Numeric
+            | Type::Bignumeric
+            | Type::Datetime
+            | Type::Time
+            | Type::Interval => ProtoType::String,
+            Type::Int64 | Type::Timestamp => ProtoType::Int64,
+            Type::Date => ProtoType::Int32,
+            Type::Double => ProtoType::Double,
+            Type::Bool => ProtoType::Bool,
+            Type::Bytes => ProtoType::Bytes,
+            Type::Struct => {
+                let nested_name = format!("Struct_{}", column.name);
+                let nested_full_name = format!("{full_name}.{nested_name}");
+                descriptor.nested_type.push(message_descriptor(
+                    &nested_name,
+                    &nested_full_name,
+                    &column.fields,
+                )?);
+                // The descriptor is sent without a package, so the nested messages are
+                // referenced from the root.
+                field.type_name = Some(format!(".{nested_full_name}"));
+                ProtoType::Message
+            }
+            Type::Range | Type::TypeUnspecified => {
+                return UnsupportedTypeSnafu {
+                    column: column.name.clone(),
+                    r#type: r#type.as_str_name(),
+                }
+                .fail();
+            }
+        };
+        field.r#type = Some(proto_type as i32);
+        descriptor.field.push(field);
+    }
+
+    Ok(descriptor)
+}
+
+fn is_valid_field_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The columns of a table, or of a `STRUCT` column.
+#[derive(Debug)]
+struct Columns {
+    message: MessageDescriptor,
+    columns: Vec<Column>,
+    /// The index of the columns by their lowercased names.
+    by_name: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct Column {
+    name: String,
+    r#type: Type,
+    mode: Mode,
+    field: FieldDescriptor,
+    /// The columns of a `STRUCT` column.
+    columns: Option<Columns>,
+}
+
+impl Columns {
+    fn new(schema: &[TableFieldSchema], message: MessageDescriptor) -> Self {
+        let columns = schema
+            .iter()
+            .map(|column| {
+                let field = message
+                    .get_field_by_name(&column.name)
+                    .expect("each column should have a field");
+                let r#type = Type::try_from(column.r#type).unwrap_or(Type::TypeUnspecified);
+                let columns = (r#type == Type::Struct).then(|| {
+                    let nested = field
+                        .kind()
+                        .as_message()
+                        .cloned()
+                        .expect("struct columns should be messages");
+                    Columns::new(&column.fields, nested)
+                });
+                Column {
+                    name: column.name.clone(),
+                    r#type,
+                    mode: Mode::try_from(column.mode).unwrap_or(Mode::Nullable),
+                    field,
+                    columns,
+                }
+            })
+            .collect::<Vec<_>>();
+        let by_name = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| (column.name.to_ascii_lowercase(), index))
+            .collect();
+
+        Self {
+            message,
+            columns,
+            by_name,
+        }
+    }
+
+    fn encode(&self, object: &ObjectMap) -> Result<DynamicMessage, RowEncodingError> {
+        let mut message = DynamicMessage::new(self.message.clone());
+        for (key, value) in object {
+            if value.is_null() {
+                continue;
+            }
+            if let Some(&index) = self.by_name.get(&sanitize_field_name(key)) {
+                let column = &self.columns[index];
+                message.set_field(&column.field, column.convert(value)?);
+            }
+        }
+
+        if let Some(column) = self
+            .columns
+            .iter()
+            .find(|column| column.mode == Mode::Required && !message.has_field(&column.field))
+        {
+            return MissingValueSnafu {
+                column: column.name.clone(),
+            }
+            .fail();
+        }
+
+        Ok(message)
+    }
+}
+
+impl Column {
+    fn convert(&self, value: &Value) -> Result<prost_reflect::Value, RowEncodingError> {
+        if self.mode != Mode::Repeated {
+            return self.convert_one(value);
+        }
+        let values = match value {
+            Value::Array(values) => values
+                .iter()
+                .filter(|value| !value.is_null())
+                .map(|value| self.convert_one(value))
+                .collect::<Result<_, _>>()?,
+            value => vec![self.convert_one(value)?],
+        };
+        Ok(prost_reflect::Value::List(values))
+    }
+
+    fn convert_one(&self, value: &Value) -> Result<prost_reflect::Value, RowEncodingError> {
+        use prost_reflect::Value as Proto;
+
+        let converted = match (self.r#type, value) {
+            (Type::Json, value) => serde_json::to_string(value).ok().map(Proto::String),
+            (Type::Datetime, Value::Timestamp(timestamp)) => Some(Proto::String(
+                timestamp.format("%Y-%m-%d %H:%M:%S%.6f").to_string(),
+            )),
+            (Type::Time, Value::Timestamp(timestamp)) => {
+                Some(Proto::String(timestamp.format("%H:%M:%S%.6f").to_string()))
+            }
+            (
+                Type::String
+                | Type::Geography
+                | Type::Numeric
+                | Type::Bignumeric
+                | Type::Datetime
+                | Type::Time
+                | Type::Interval,
+                Value::Object(_) | Value::Array(_),
+            ) => serde_json::to_string(value).ok().map(Proto::String),
+            (
+                Type::String
+                | Type::Geography
+                | Type::Numeric
+                | Type::Bignumeric
+                | Type::Datetime
+                | Type::Time
+                | Type::Interval,
+                value,
+            ) => Some(Proto::String(value.to_string_lossy().into_owned())),
+            (Type::Int64, Value::Integer(integer)) => Some(Proto::I64(*integer)),
+            (Type::Int64, Value::Float(float)) if float.fract() == 0.0 => {
+                Some(Proto::I64(float.into_inner() as i64))
+            }
+            (Type::Int64, Value::Bytes(bytes)) => parse(bytes).map(Proto::I64),
+            (Type::Double, Value::Float(float)) => Some(Proto::F64(float.into_inner())),
+            (Type::Double, Value::Integer(integer)) => Some(Proto::F64(*integer as f64)),
+            (Type::Double, Value::Bytes(bytes)) => parse(bytes).map(Proto::F64),
+            (Type::Bool, Value::Boolean(boolean)) => Some(Proto::Bool(*boolean)),
+            (Type::Bool, Value::Bytes(bytes)) => parse(bytes).map(Proto::Bool),
+            (Type::Bytes, Value::Bytes(bytes)) => Some(Proto::Bytes(bytes.clone())),
+            (Type::Timestamp, Value::Timestamp(timestamp)) => {
+                Some(Proto::I64(timestamp.timestamp_micros()))
+            }
+            (Type::Timestamp, Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+                .map(|timestamp| Proto::I64(timestamp.timestamp_micros())),
+            (Type::Date, Value::Timestamp(timestamp)) => days_since_epoch(timestamp.date_naive()),
+            (Type::Date, Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+                .and_then(days_since_epoch),
+            (Type::Struct, Value::Object(object)) => match &self.columns {
+                Some(columns) => Some(Proto::Message(columns.encode(object)?)),
+                None => None,
+            },
+            _ => None,
+        };
+
+        converted.ok_or_else(|| RowEncodingError::InvalidValue {
+            column: self.name.clone(),
+            r#type: self.r#type.as_str_name(),
+            kind: value.kind_str(),
+        })
+    }
+}
+
+fn parse<T: std::str::FromStr>(bytes: &[u8]) -> Option<T> {
+    std::str::from_utf8(bytes).ok()?.trim().parse().ok()
+}
+
+fn days_since_epoch(date: NaiveDate) -> Option<prost_reflect::Value> {
+    let epoch = DateTime::<Utc>::UNIX_EPOCH.date_naive();
+    i32::try_from(date.signed_duration_since(epoch).num_days())
+        .ok()
+        .map(prost_reflect::Value::I32)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vrl::btreemap;
+
+    use super::*;
+
+    fn column(name: &str, r#type: Type, mode: Mode) -> TableFieldSchema {
+        TableFieldSchema {
+            name: name.to_owned(),
+            r#type: r#type as i32,
+            mode: mode as i32,
+            ..Default::default()
+        }
+    }
+
+    fn encoder() -> RowEncoder {
+        RowEncoder::new(&TableSchema {
+            fields: vec![
+                column("message", Type::String, Mode::Required),
+                column("timestamp", Type::Timestamp, Mode::Nullable),
+                column("status_code", Type::Int64, Mode::Nullable),
+                column("tags", Type::String, Mode::Repeated),
+                TableFieldSchema {
+                    fields: vec![column("name", Type::String, Mode::Nullable)],
+                    ..column("host", Type::Struct, Mode::Nullable)
+                },
+            ],
+        })
+        .unwrap()
+    }
+
+    fn decode(encoder: &RowEncoder, row: &[u8]) -> DynamicMessage {
+        DynamicMessage::decode(encoder.columns.message.clone(), row).unwrap()
+    }
+
+    #[test]
+    fn sanitizes_field_names() {
+        assert_eq!(sanitize_field_name("message"), "message");
+        assert_eq!(sanitize_field_name("User-Agent"), "user_agent");
+        assert_eq!(sanitize_field_name("@timestamp"), "_timestamp");
+        assert_eq!(sanitize_field_name("2xx"), "_2xx");
+        assert_eq!(sanitize_field_name(""), "_");
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let encoder = encoder();
+        let timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let log = LogEvent::from(btreemap! {
+            "message" => "hello",
+            "Timestamp" => timestamp,
+            "status-code" => "200",
+            "tags" => vec!["a", "b"],
+            "host" => btreemap! { "name" => "vector" },
+            "ignored" => true,
+        });
+
+        let row = decode(&encoder, &encoder.encode(&log).unwrap());
+        assert_eq!(
+            row.get_field_by_name("message").unwrap().as_str(),
+            Some("hello")
+        );
+        assert_eq!(
+            row.get_field_by_name("timestamp").unwrap().as_i64(),
+            Some(timestamp.timestamp_micros())
+        );
+        assert_eq!(
+            row.get_field_by_name("status_code").unwrap().as_i64(),
+            Some(200)
+        );
+        assert_eq!(
+            row.get_field_by_name("tags")
+                .unwrap()
+                .as_list()
+                .unwrap()
+                .len(),
+            2
+        );
+        let host = row.get_field_by_name("host").unwrap();
+        assert_eq!(
+            host.as_message()
+                .unwrap()
+                .get_field_by_name("name")
+                .unwrap()
+                .as_str(),
+            Some("vector")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rows() {
+        let encoder = encoder();
+
+        let missing = LogEvent::from(btreemap! { "status_code" => 200 });
+        assert!(matches!(
+            encoder.encode(&missing),
+            Err(RowEncodingError::MissingValue { .. })
+        ));
+
+        let invalid = LogEvent::from(btreemap! { "message" => "hello", "status_code" => "OK" });
+        assert!(matches!(
+            encoder.encode(&invalid),
+            Err(RowEncodingError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_schemas() {
+        let schema = |column| TableSchema {
+            fields: vec![column],
+        };
+        assert!(matches!(
+            RowEncoder::new(&schema(column("a-b", Type::String, Mode::Nullable))),
+            Err(SchemaError::InvalidColumnName { .. })
+        ));
+        assert!(matches!(
+            RowEncoder::new(&schema(column("range", Type::Range, Mode::Nullable))),
+            Err(SchemaError::UnsupportedType { .. })
+        ));
+        assert!(matches!(
+            RowEncoder::new(&TableSchema::default()),
+            Err(SchemaError::NoColumns)
+        ));
+    }
+}
@@ -0,0 +1,339 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use prost::Message;
+use snafu::{OptionExt, ResultExt, Snafu};
+use tokio::sync::Mutex;
+use tonic::{
+    Code, Request, Status, codegen::InterceptedService, metadata::MetadataValue,
+    service::Interceptor, transport::Channel,
+};
+
+use super::proto::{
+    AppendRowsRequest, CreateWriteStreamRequest, ProtoRows, ProtoSchema, WriteStream,
+    append_rows_request::{ProtoData, Rows},
+    append_rows_response::Response,
+    big_query_write_client::BigQueryWriteClient,
+    write_stream::Type as WriteStreamType,
+};
+use crate::{gcp::GcpAuthenticator, internal_events::EndpointBytesSent, sinks::prelude::*};
+
+pub(super) type WriteClient = BigQueryWriteClient<InterceptedService<Channel, AuthInterceptor>>;
+
+/// Adds the GCP credentials to the gRPC requests.
+#[derive(Clone)]
+pub(super) struct AuthInterceptor {
+    pub(super) auth: GcpAuthenticator,
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let (key, value) = match &self.auth {
+            GcpAuthenticator::Credentials(_) => {
+                ("authorization", self.auth.make_token().unwrap_or_default())
+            }
+            GcpAuthenticator::ApiKey(api_key) => ("x-goog-api-key", api_key.to_string()),
+            GcpAuthenticator::None => return Ok(request),
+        };
+        let value = MetadataValue::try_from(value).map_err(|_| {
+            Status::new(
+                Code::FailedPrecondition,
+                "Invalid token text returned by GCP",
+            )
+        })?;
+        request.metadata_mut().insert(key, value);
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum BigqueryError {
+    #[snafu(display("Failed to create a write stream: {}", source))]
+    CreateWriteStream { source: Status },
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: Status },
+    #[snafu(display("The append response is missing."))]
+    MissingResponse,
+    #[snafu(display("Failed to append rows ({:?}): {}", code, message))]
+    Append { code: Code, message: String },
+}
+
+impl BigqueryError {
+    /// Whether the request can be retried.
+    ///
+    /// The write stream is replaced after the errors specific to it, so these can be retried too.
+    fn is_retriable(&self) -> bool {
+        match self {
+            Self::CreateWriteStream { source } => is_transient(source.code()),
+            Self::Request { source } => is_transient(source.code()),
+            Self::MissingResponse => true,
+            Self::Append { code, .. } => is_transient(*code) || is_stream_error(*code),
+        }
+    }
+}
+
+const fn is_transient(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unknown
+            | Code::Cancelled
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted
+            | Code::Internal
+            | Code::Unavailable
+    )
+}
+
+/// Whether the append failed because of the state of the write stream, rather than the rows.
+const fn is_stream_error(code: Code) -> bool {
+    matches!(
+        code,
+        Code::NotFound | Code::FailedPrecondition | Code::OutOfRange | Code::AlreadyExists
+    )
+}
+
+#[derive(Clone, Default)]
+pub(super) struct BigqueryRequest {
+    /// Identifies the request across its retries.
+    pub(super) id: u64,
+    pub(super) rows: Vec<Vec<u8>>,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) metadata: RequestMetadata,
+}
+
+impl Finalizable for BigqueryRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for BigqueryRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub(super) struct BigqueryResponse {
+    events_byte_size: GroupedCountByteSize,
+}
+
+impl DriverResponse for BigqueryResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        &self.events_byte_size
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct BigqueryRetryLogic;
+
+impl RetryLogic for BigqueryRetryLogic {
+    type Error = BigqueryError;
+    type Request = BigqueryRequest;
+    type Response = BigqueryResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_retriable()
+    }
+}
+
+/// The committed write stream the rows are appended to.
+struct WriteStreamState {
+    name: String,
+    /// The offset of the next rows to append.
+    offset: i64,
+    /// The request whose rows may or may not have been appended at `offset`, as its response
+    /// wasn't received.
+    in_doubt: Option<u64>,
+}
+
+/// Appends the rows of the requests to a committed write stream, at the offset following the rows
+/// of the previous requests.
+///
+/// The offset of the rows is what makes their writes exactly-once: if the rows of a retried
+/// request were already appended, BigQuery rejects them as already existing. The requests must be
+/// sent one at a time for the offsets to be consecutive.
+#[derive(Clone)]
+pub(super) struct BigqueryService {
+    client: WriteClient,
+    table: String,
+    writer_schema: ProtoSchema,
+    stream: Arc<Mutex<Option<WriteStreamState>>>,
+    protocol: String,
+    endpoint: String,
+}
+
+impl BigqueryService {
+    pub(super) fn new(
+        client: WriteClient,
+        table: String,
+        writer_schema: ProtoSchema,
+        protocol: String,
+        endpoint: String,
+    ) -> Self {
+        Self {
+            client,
+            table,
+            writer_schema,
+            stream: Default::default(),
+            protocol,
+            endpoint,
+        }
+    }
+
+    async fn create_write_stream(&mut self) -> Result<WriteStreamState, BigqueryError> {
+        let request = CreateWriteStreamRequest {
+            parent: self.table.clone(),
+            write_stream: Some(WriteStream {
+                r#type: WriteStreamType::Committed as i32,
+                ..Default::default()
+            }),
+        };
+        let stream = self
+            .client
+            .create_write_stream(request)
+            .await
+            .context(CreateWriteStreamSnafu)?
+            .into_inner();
+        debug!(message = "Created a write stream.", stream = %stream.name);
+
+        Ok(WriteStreamState {
+            name: stream.name,
+            offset: 0,
+            in_doubt: None,
+        })
+    }
+
+    async fn append(&mut self, request: BigqueryRequest) -> Result<usize, BigqueryError> {
+        let stream = Arc::clone(&self.stream);
+        let mut write_stream = stream.lock().await;
+
+        // If the rows of another request may have been appended at the offset, there is no telling
+        // whether the rows of this one are already appended, so they are appended to a new stream.
+        if write_stream
+            .as_ref()
+            .is_some_and(|state| state.in_doubt.is_some_and(|id| id != request.id))
+        {
+            *write_stream = None;
+        }
+        if write_stream.is_none() {
+            *write_stream = Some(self.create_write_stream().await?);
+        }
+        let state = write_stream
+            .as_mut()
+            .expect("write stream should have been created");
+
+        let row_count = request.rows.len() as i64;
+        let append = AppendRowsRequest {
+            write_stream: state.name.clone(),
+            offset: Some(state.offset),
+            rows: Some(Rows::ProtoRows(ProtoData {
+                writer_schema: Some(self.writer_schema.clone()),
+                rows: Some(ProtoRows {
+                    serialized_rows: request.rows,
+                }),
+            })),
+            trace_id: "vector".to_owned(),
+            ..Default::default()
+        };
+        let byte_size = append.encoded_len();
+
+        let mut grpc_request = Request::new(futures::stream::iter([append]));
+        // Routes the request to the region of the stream.
+        if let Ok(params) = MetadataValue::try_from(format!("write_stream={}", state.name)) {
+            grpc_request
+                .metadata_mut()
+                .insert("x-goog-request-params", params);
+        }
+
+        let result = async {
+            self.client
+                .append_rows(grpc_request)
+                .await?
+                .into_inner()
+                .message()
+                .await
+        }
+        .await;
+        let response = match result {
+            Ok(response) => response.context(MissingResponseSnafu)?,
+            Err(source) => {
+                state.in_doubt = Some(request.id);
+                return Err(BigqueryError::Request { source });
+            }
+        };
+
+        match response.response {
+            Some(Response::AppendResult(_)) => {}
+            // The rows were appended by a previous attempt whose response wasn't received.
+            Some(Response::Error(status))
+                if Code::from(status.code) == Code::AlreadyExists
+                    && state.in_doubt == Some(request.id) =>
+            {
+                debug!(
+                    message = "Rows were already appended.",
+                    stream = %state.name,
+                    offset = state.offset,
+                );
+            }
+            Some(Response::Error(status)) => {
+                let code = Code::from(status.code);
+                if is_stream_error(code) {
+                    *write_stream = None;
+                } else {
+                    state.in_doubt = None;
+                }
+                return Err(BigqueryError::Append {
+                    code,
+                    message: status.message,
+                });
+            }
+            None => {
+                state.in_doubt = Some(request.id);
+                return Err(BigqueryError::MissingResponse);
+            }
+        }
+
+        state.offset += row_count;
+        state.in_doubt = None;
+        Ok(byte_size)
+    }
+}
+
+impl Service<BigqueryRequest> for BigqueryService {
+    type Response = BigqueryResponse;
+    type Error = BigqueryError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Emission of an internal event in case of errors is handled upstream by the caller.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    // Emission of internal events for errors and dropped events is handled upstream by the caller.
+    fn call(&mut self, mut request: BigqueryRequest) -> Self::Future {
+        let mut service = self.clone();
+        let metadata = std::mem::take(request.metadata_mut());
+        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
+
+        Box::pin(async move {
+            let byte_size = service.append(request).await?;
+            emit!(EndpointBytesSent {
+                byte_size,
+                protocol: &service.protocol,
+                endpoint: &service.endpoint,
+            });
+            Ok(BigqueryResponse { events_byte_size })
+        })
+    }
+}
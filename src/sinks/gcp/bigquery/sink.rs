@@ -0,0 +1,121 @@
+use std::{fmt, num::NonZeroUsize, sync::Arc};
+
+use vector_lib::stream::batcher::data::BatchReduce;
+
+use super::{schema::RowEncoder, service::BigqueryRequest};
+use crate::{internal_events::GcpBigqueryRowEncodingError, sinks::prelude::*};
+
+/// The ordering key of all the requests, as they append to the same write stream.
+const WRITE_STREAM_ORDERING_KEY: u64 = 0;
+
+/// An event encoded as a row of the table.
+struct Row {
+    row: Vec<u8>,
+    finalizers: EventFinalizers,
+    byte_size: usize,
+    json_byte_size: GroupedCountByteSize,
+}
+
+/// The rows of a request.
+struct Rows {
+    rows: Vec<Vec<u8>>,
+    finalizers: EventFinalizers,
+    byte_size: usize,
+    json_byte_size: GroupedCountByteSize,
+}
+
+impl Default for Rows {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            finalizers: Default::default(),
+            byte_size: 0,
+            json_byte_size: telemetry().create_request_count_byte_size(),
+        }
+    }
+}
+
+pub(super) struct BigquerySink<S> {
+    pub(super) service: S,
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) transformer: Transformer,
+    pub(super) encoder: Arc<RowEncoder>,
+}
+
+impl<S> BigquerySink<S>
+where
+    S: Service<BigqueryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let transformer = self.transformer;
+        let encoder = self.encoder;
+        let mut next_id = 0;
+
+        input
+            .filter_map(move |mut event| {
+                transformer.transform(&mut event);
+
+                let mut json_byte_size = telemetry().create_request_count_byte_size();
+                json_byte_size.add_event(&event, event.estimated_json_encoded_size_of());
+                let row = match encoder.encode(event.as_log()) {
+                    Ok(row) => Some(Row {
+                        row,
+                        byte_size: event.size_of(),
+                        finalizers: event.take_finalizers(),
+                        json_byte_size,
+                    }),
+                    Err(error) => {
+                        emit!(GcpBigqueryRowEncodingError { error: &error });
+                        None
+                    }
+                };
+                future::ready(row)
+            })
+            .batched(self.batch_settings.as_reducer_config(
+                |row: &Row| row.row.len(),
+                BatchReduce::new(|rows: &mut Rows, row: Row| {
+                    rows.rows.push(row.row);
+                    rows.finalizers.merge(row.finalizers);
+                    rows.byte_size += row.byte_size;
+                    rows.json_byte_size += row.json_byte_size;
+                }),
+            ))
+            .map(move |rows| {
+                let metadata = RequestMetadataBuilder::new(
+                    rows.rows.len(),
+                    rows.byte_size,
+                    rows.json_byte_size,
+                )
+                .with_ordering_keys(vec![WRITE_STREAM_ORDERING_KEY]);
+                let encoded_size = rows.rows.iter().map(Vec::len).sum::<usize>();
+                let encoded_size = NonZeroUsize::new(encoded_size).unwrap_or(NonZeroUsize::MIN);
+
+                next_id += 1;
+                BigqueryRequest {
+                    id: next_id,
+                    rows: rows.rows,
+                    finalizers: rows.finalizers,
+                    metadata: metadata.with_request_size(encoded_size),
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for BigquerySink<S>
+where
+    S: Service<BigqueryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -12,14 +12,14 @@ use tower::ServiceBuilder;
 use uuid::Uuid;
 use vector_lib::{
     TimeZone,
-    codecs::encoding::Framer,
+    codecs::encoding::BatchSerializerConfig,
     configurable::configurable_component,
     event::{EventFinalizers, Finalizable},
     request_metadata::RequestMetadata,
 };
 
 use crate::{
-    codecs::{Encoder, EncodingConfigWithFraming, SinkType, Transformer},
+    codecs::{BatchEncoder, EncodingConfigWithFraming, SinkType, Transformer},
     config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
     event::Event,
     gcp::{GcpAuthConfig, GcpAuthenticator, Scope},
@@ -144,13 +144,28 @@ pub struct GcsSinkConfig {
 
     /// The filename extension to use in the object key.
     ///
-    /// If not specified, the extension is determined by the compression scheme used.
+    /// If not specified, the extension is determined by the batch encoding or, without one, by the
+    /// compression scheme used.
     #[configurable(metadata(docs::advanced))]
     filename_extension: Option<String>,
 
     #[serde(flatten)]
     encoding: EncodingConfigWithFraming,
 
+    /// Batch encoding configuration.
+    ///
+    /// When set, each batch of events is written as a single object encoded with this codec, such
+    /// as an Apache Parquet file, instead of being encoded event by event with `encoding`. The
+    /// options of `encoding` that change events, such as `except_fields`, still apply.
+    ///
+    /// Parquet files are compressed internally, so `compression` is not applied to them. Hive-style
+    /// partitioned prefixes, such as `year=%Y/month=%m/day=%d/`, can be used in `key_prefix` to
+    /// allow querying the objects as a partitioned BigQuery external table, and `except_fields` to
+    /// leave the partition columns out of the files.
+    #[configurable(derived)]
+    #[serde(default)]
+    batch_encoding: Option<BatchSerializerConfig>,
+
     /// Compression configuration.
     ///
     /// All compression algorithms use the default compression level unless otherwise specified.
@@ -210,6 +225,7 @@ fn default_config(encoding: EncodingConfigWithFraming) -> GcsSinkConfig {
         filename_append_uuid: true,
         filename_extension: Default::default(),
         encoding,
+        batch_encoding: None,
         compression: Compression::gzip_default(),
         batch: Default::default(),
         endpoint: Default::default(),
@@ -254,7 +270,11 @@ impl SinkConfig for GcsSinkConfig {
     }
 
     fn input(&self) -> Input {
-        Input::new(self.encoding.config().1.input_type() & DataType::Log)
+        let input_type = match &self.batch_encoding {
+            Some(batch_encoding) => batch_encoding.input_type(),
+            None => self.encoding.config().1.input_type(),
+        };
+        Input::new(input_type & DataType::Log)
     }
 
     fn acknowledgements(&self) -> &AcknowledgementsConfig {
@@ -311,7 +331,7 @@ struct RequestSettings {
     extension: String,
     time_format: String,
     append_uuid: bool,
-    encoder: (Transformer, Encoder<Framer>),
+    encoder: (Transformer, BatchEncoder),
     compression: Compression,
     tz_offset: Option<FixedOffset>,
 }
@@ -319,7 +339,7 @@ struct RequestSettings {
 impl RequestBuilder<(String, Vec<Event>)> for RequestSettings {
     type Metadata = (String, EventFinalizers);
     type Events = Vec<Event>;
-    type Encoder = (Transformer, Encoder<Framer>);
+    type Encoder = (Transformer, BatchEncoder);
     type Payload = Bytes;
     type Request = GcsRequest;
     type Error = io::Error;
@@ -389,14 +409,19 @@ impl RequestBuilder<(String, Vec<Event>)> for RequestSettings {
 impl RequestSettings {
     fn new(config: &GcsSinkConfig, cx: SinkContext) -> crate::Result<Self> {
         let transformer = config.encoding.transformer();
-        let (framer, serializer) = config.encoding.build(SinkType::MessageBased)?;
-        let encoder = Encoder::<Framer>::new(framer, serializer);
+        let encoder = config
+            .encoding
+            .build_batch_encoder(config.batch_encoding.as_ref(), SinkType::MessageBased)?;
+        // Batch encodings are compressed internally, if at all.
+        let compression = match config.batch_encoding {
+            Some(_) => Compression::None,
+            None => config.compression,
+        };
         let acl = config
             .acl
             .map(|acl| HeaderValue::from_str(&to_string(acl)).unwrap());
         let content_type = HeaderValue::from_str(encoder.content_type()).unwrap();
-        let content_encoding = config
-            .compression
+        let content_encoding = compression
             .content_encoding()
             .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
         let storage_class = config.storage_class.unwrap_or_default();
@@ -411,10 +436,12 @@ impl RequestSettings {
                     .collect::<Result<Vec<_>, _>>()
             })
             .unwrap_or_else(|| Ok(vec![]))?;
-        let extension = config
-            .filename_extension
-            .clone()
-            .unwrap_or_else(|| config.compression.extension().into());
+        let extension = config.filename_extension.clone().unwrap_or_else(|| {
+            config.batch_encoding.as_ref().map_or_else(
+                || compression.extension().into(),
+                |batch_encoding| batch_encoding.file_extension().into(),
+            )
+        });
         let time_format = config.filename_time_format.clone();
         let append_uuid = config.filename_append_uuid;
         let offset = config
@@ -431,7 +458,7 @@ impl RequestSettings {
             extension,
             time_format,
             append_uuid,
-            compression: config.compression,
+            compression,
             encoder: (transformer, encoder),
             tz_offset: offset,
         })
@@ -453,7 +480,7 @@ mod tests {
         EstimatedJsonEncodedSizeOf,
         codecs::{
             JsonSerializerConfig, NewlineDelimitedEncoderConfig, TextSerializerConfig,
-            encoding::FramingConfig,
+            encoding::{FramingConfig, ParquetSerializerOptions},
         },
         partition::Partitioner,
         request_metadata::GroupedCountByteSize,
@@ -572,4 +599,23 @@ mod tests {
         let req = build_request(None, true, Compression::gzip_default());
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
+
+    #[test]
+    fn gcs_build_request_with_batch_encoding() {
+        let sink_config = GcsSinkConfig {
+            batch_encoding: Some(BatchSerializerConfig::Parquet {
+                parquet: ParquetSerializerOptions::default(),
+            }),
+            ..default_config((None::<FramingConfig>, JsonSerializerConfig::default()).into())
+        };
+        let request_settings = request_settings(&sink_config, SinkContext::default());
+
+        assert_eq!(request_settings.extension, "parquet");
+        assert_eq!(request_settings.compression, Compression::None);
+        assert_eq!(request_settings.content_encoding, None);
+        assert_eq!(
+            request_settings.content_type,
+            HeaderValue::from_static("application/vnd.apache.parquet")
+        );
+    }
 }
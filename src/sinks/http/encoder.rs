@@ -27,6 +27,7 @@ pub(super) struct HttpEncoder {
     transformer: Transformer,
     payload_prefix: String,
     payload_suffix: String,
+    body: Option<Template>,
 }
 
 impl HttpEncoder {
@@ -42,8 +43,41 @@ impl HttpEncoder {
             transformer,
             payload_prefix,
             payload_suffix,
+            body: None,
         }
     }
+
+    /// Renders the body of the requests from the template instead of encoding the events.
+    pub(super) fn with_body(mut self, body: Option<Template>) -> Self {
+        self.body = body;
+        self
+    }
+
+    fn encode_body(
+        &self,
+        template: &Template,
+        events: Vec<Event>,
+        writer: &mut dyn io::Write,
+    ) -> io::Result<(usize, GroupedCountByteSize)> {
+        let mut byte_size = telemetry().create_request_count_byte_size();
+        let mut body = BytesMut::new();
+        let n_events = events.len();
+
+        for event in events {
+            let rendered = template.render(&event).map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("body"),
+                    drop_event: true,
+                });
+                io::Error::other("unable to render the body")
+            })?;
+            byte_size.add_event(&event, event.estimated_json_encoded_size_of());
+            body.put(rendered);
+        }
+
+        write_all(writer, n_events, &body).map(|()| (body.len(), byte_size))
+    }
 }
 
 impl SinkEncoder<Vec<Event>> for HttpEncoder {
@@ -52,6 +86,10 @@ impl SinkEncoder<Vec<Event>> for HttpEncoder {
         events: Vec<Event>,
         writer: &mut dyn io::Write,
     ) -> io::Result<(usize, GroupedCountByteSize)> {
+        if let Some(template) = &self.body {
+            return self.encode_body(template, events, writer);
+        }
+
         let mut encoder = self.encoder.clone();
         let mut byte_size = telemetry().create_request_count_byte_size();
         let mut body = BytesMut::new();
@@ -7,6 +7,7 @@ mod batch;
 pub mod config;
 mod encoder;
 mod request_builder;
+mod retry;
 mod service;
 mod sink;
 
@@ -5,11 +5,16 @@ use std::io;
 use bytes::Bytes;
 
 use super::encoder::HttpEncoder;
-use crate::sinks::{http::sink::PartitionKey, prelude::*, util::http::HttpRequest};
+use crate::sinks::{
+    http::sink::PartitionKey,
+    prelude::*,
+    util::{http::HttpRequest, ordering::OrderingKeys},
+};
 
 pub(super) struct HttpRequestBuilder {
     pub(super) encoder: HttpEncoder,
     pub(super) compression: Compression,
+    pub(super) ordering_keys: OrderingKeys,
 }
 
 impl RequestBuilder<(PartitionKey, Vec<Event>)> for HttpRequestBuilder {
@@ -35,7 +40,8 @@ impl RequestBuilder<(PartitionKey, Vec<Event>)> for HttpRequestBuilder {
         let (partition_key, mut events) = input;
 
         let finalizers = events.take_finalizers();
-        let builder = RequestMetadataBuilder::from_events(&events);
+        let builder = RequestMetadataBuilder::from_events(&events)
+            .with_ordering_keys(self.ordering_keys.keys(&events));
         ((partition_key, finalizers), builder, events)
     }
 
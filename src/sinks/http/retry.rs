@@ -0,0 +1,280 @@
+//! The handling of the responses of the `http` sink by status code.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use http::{StatusCode, header::RETRY_AFTER};
+
+use super::sink::PartitionKey;
+use crate::{
+    http::HttpError,
+    sinks::{
+        prelude::*,
+        util::{
+            http::{HttpRequest, HttpResponse, HttpStatusRetryLogic},
+            retries::{RetryAction, RetryLogic},
+        },
+    },
+};
+
+/// Configuration for the handling of the responses by status code.
+///
+/// By default, the requests are retried when the status of the response is `408`, `429`, or a
+/// `5xx` one other than `501`, and the other statuses than the `2xx` ones reject the events.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RetryPolicyConfig {
+    /// The status codes of the responses that deliver the events, in addition to the `2xx` ones.
+    ///
+    /// For example, `404` when the endpoint answers so for the resources already resolved.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 404))]
+    pub success_codes: Vec<u16>,
+
+    /// The status codes of the responses whose requests are retried, in addition to the default
+    /// ones.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 409))]
+    pub retry_codes: Vec<u16>,
+
+    /// The status codes of the responses whose requests are not retried, and which reject the
+    /// events.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 500))]
+    pub reject_codes: Vec<u16>,
+
+    /// Whether to wait for the delay of the `Retry-After` header of the retried responses,
+    /// instead of the backoff.
+    ///
+    /// The delay is capped at `request.retry_max_duration_secs`.
+    #[serde(default)]
+    pub respect_retry_after: bool,
+}
+
+impl RetryPolicyConfig {
+    pub(super) fn build(&self) -> crate::Result<HttpSinkRetryLogic> {
+        let codes = |codes: &[u16]| {
+            codes
+                .iter()
+                .map(|&code| {
+                    StatusCode::from_u16(code)
+                        .map_err(|_| format!("Invalid status code `{code}` in `retry_policy`."))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let success = codes(&self.success_codes)?;
+        let retry = codes(&self.retry_codes)?;
+        let reject = codes(&self.reject_codes)?;
+        let mut configured = HashSet::new();
+        for code in success.iter().chain(&retry).chain(&reject) {
+            if !configured.insert(code) {
+                return Err(format!(
+                    "The status code `{}` is configured more than once in `retry_policy`.",
+                    code.as_u16()
+                )
+                .into());
+            }
+        }
+
+        Ok(HttpSinkRetryLogic {
+            policy: Arc::new(StatusPolicy {
+                success,
+                retry,
+                reject,
+                respect_retry_after: self.respect_retry_after,
+            }),
+            default: HttpStatusRetryLogic::new(status as fn(&HttpResponse) -> StatusCode),
+        })
+    }
+}
+
+fn status(response: &HttpResponse) -> StatusCode {
+    response.http_response.status()
+}
+
+#[derive(Debug)]
+struct StatusPolicy {
+    success: Vec<StatusCode>,
+    retry: Vec<StatusCode>,
+    reject: Vec<StatusCode>,
+    respect_retry_after: bool,
+}
+
+/// The retry logic of the `http` sink, falling back to the default one for the statuses that
+/// aren't configured.
+#[derive(Clone, Debug)]
+pub(super) struct HttpSinkRetryLogic {
+    policy: Arc<StatusPolicy>,
+    default: HttpStatusRetryLogic<
+        fn(&HttpResponse) -> StatusCode,
+        HttpRequest<PartitionKey>,
+        HttpResponse,
+    >,
+}
+
+impl HttpSinkRetryLogic {
+    /// Wraps the response of the service, so that it delivers the events when its status is one of
+    /// the success codes.
+    pub(super) fn wrap_response(&self, response: HttpResponse) -> HttpSinkResponse {
+        let delivered = self
+            .policy
+            .success
+            .contains(&response.http_response.status());
+        HttpSinkResponse {
+            response,
+            delivered,
+        }
+    }
+}
+
+impl RetryLogic for HttpSinkRetryLogic {
+    type Error = HttpError;
+    type Request = HttpRequest<PartitionKey>;
+    type Response = HttpSinkResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        self.default.is_retriable_error(error)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction<Self::Request> {
+        let status = response.response.http_response.status();
+        if response.delivered {
+            RetryAction::Successful
+        } else if self.policy.retry.contains(&status) {
+            RetryAction::Retry(format!("Http status: {status}").into())
+        } else if self.policy.reject.contains(&status) {
+            RetryAction::DontRetry(format!("Http status: {status}").into())
+        } else {
+            self.default.should_retry_response(&response.response)
+        }
+    }
+
+    fn retry_delay(&self, response: &Self::Response) -> Option<Duration> {
+        if !self.policy.respect_retry_after {
+            return None;
+        }
+        let retry_after = response
+            .response
+            .http_response
+            .headers()
+            .get(RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim();
+        // The header holds either a number of seconds or an HTTP date.
+        match retry_after.parse::<u64>() {
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            Err(_) => {
+                let date = DateTime::parse_from_rfc2822(retry_after).ok()?;
+                Some(
+                    (date.with_timezone(&Utc) - Utc::now())
+                        .to_std()
+                        .unwrap_or_default(),
+                )
+            }
+        }
+    }
+}
+
+/// A response of the `http` sink, whose events are delivered when its status is one of the
+/// success codes.
+pub(super) struct HttpSinkResponse {
+    response: HttpResponse,
+    delivered: bool,
+}
+
+impl DriverResponse for HttpSinkResponse {
+    fn event_status(&self) -> EventStatus {
+        if self.delivered {
+            EventStatus::Delivered
+        } else {
+            self.response.event_status()
+        }
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        self.response.events_sent()
+    }
+
+    fn bytes_sent(&self) -> Option<usize> {
+        self.response.bytes_sent()
+    }
+
+    fn failure(&self) -> Option<DeliveryFailure> {
+        if self.delivered {
+            None
+        } else {
+            self.response.failure()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::Response;
+
+    use super::*;
+
+    fn response(logic: &HttpSinkRetryLogic, status: u16, retry_after: &str) -> HttpSinkResponse {
+        let http_response = Response::builder()
+            .status(status)
+            .header(RETRY_AFTER, retry_after)
+            .body(Bytes::new())
+            .unwrap();
+        logic.wrap_response(HttpResponse {
+            http_response,
+            events_byte_size: GroupedCountByteSize::new_untagged(),
+            raw_byte_size: 0,
+        })
+    }
+
+    #[test]
+    fn handles_the_configured_status_codes() {
+        let logic = RetryPolicyConfig {
+            success_codes: vec![404],
+            retry_codes: vec![409],
+            reject_codes: vec![503],
+            respect_retry_after: true,
+        }
+        .build()
+        .unwrap();
+
+        let not_found = response(&logic, 404, "");
+        assert_eq!(not_found.event_status(), EventStatus::Delivered);
+        assert!(matches!(
+            logic.should_retry_response(&not_found),
+            RetryAction::Successful
+        ));
+        for (status, retried) in [(409, true), (503, false), (429, true), (400, false)] {
+            let action = logic.should_retry_response(&response(&logic, status, ""));
+            assert_eq!(matches!(action, RetryAction::Retry(_)), retried, "{status}");
+        }
+
+        let too_many_requests = response(&logic, 429, "120");
+        assert_eq!(
+            logic.retry_delay(&too_many_requests),
+            Some(Duration::from_secs(120))
+        );
+        let past = response(&logic, 429, "Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(logic.retry_delay(&past), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn rejects_invalid_status_codes() {
+        for config in [
+            RetryPolicyConfig {
+                success_codes: vec![42],
+                ..Default::default()
+            },
+            RetryPolicyConfig {
+                success_codes: vec![404],
+                reject_codes: vec![404],
+                ..Default::default()
+            },
+        ] {
+            assert!(config.build().is_err());
+        }
+    }
+}
@@ -53,6 +53,30 @@ fn generate_config() {
     crate::test_util::test_generate_config::<HttpSinkConfig>();
 }
 
+#[test]
+fn adds_responses_output() {
+    let config = crate::config::load_from_str(
+        r#"
+        [sources.in]
+        type = "test_basic"
+
+        [sinks.webhook]
+        type = "http"
+        inputs = ["in"]
+        uri = "http://localhost:8080/incidents"
+        encoding.codec = "json"
+        capture_responses = true
+
+        [sinks.out]
+        type = "test_basic"
+        inputs = ["webhook.responses"]
+    "#,
+        crate::config::Format::Toml,
+    )
+    .unwrap();
+    assert!(config.source(&"webhook.responses".into()).is_some());
+}
+
 fn default_cfg(encoding: EncodingConfigWithFraming) -> HttpSinkConfig {
     HttpSinkConfig {
         uri: Default::default(),
@@ -65,7 +89,14 @@ fn default_cfg(encoding: EncodingConfigWithFraming) -> HttpSinkConfig {
         payload_suffix: Default::default(),
         batch: Default::default(),
         request: Default::default(),
+        retry_policy: Default::default(),
+        capture_responses: false,
+        body: None,
+        per_event: false,
+        per_endpoint_concurrency: false,
         tls: Default::default(),
+        ordering: Default::default(),
+        ordering_key_field: None,
         acknowledgements: Default::default(),
     }
 }
@@ -472,6 +503,59 @@ async fn fails_on_permanent_error() {
     .await;
 }
 
+#[tokio::test]
+async fn delivers_on_configured_success_status() {
+    components::assert_sink_compliance(&HTTP_SINK_TAGS, async {
+        let (in_addr, sink) = build_sink(
+            r#"
+            [retry_policy]
+            success_codes = [404]
+        "#,
+        )
+        .await;
+
+        let (_rx, trigger, server) = build_test_server_status(in_addr, StatusCode::NOT_FOUND);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (_input_lines, events) = random_lines_with_stream(100, 10, Some(batch));
+        let pump = sink.run(events);
+
+        tokio::spawn(server);
+
+        pump.await.unwrap();
+        drop(trigger);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn sends_templated_body_per_event() {
+    let (in_addr, sink) = build_sink(
+        r#"
+        per_event = true
+        body = "{{ message }} happened"
+    "#,
+    )
+    .await;
+    let (rx, trigger, server) = build_test_server(in_addr);
+    tokio::spawn(server);
+
+    let events = ["deploy", "rollback"].map(|message| Event::Log(LogEvent::from(message)));
+    components::run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+    drop(trigger);
+
+    let requests = atomic::AtomicUsize::new(0);
+    let output_lines = get_received_gzip(rx, |_| {
+        requests.fetch_add(1, atomic::Ordering::Relaxed);
+    })
+    .await;
+
+    assert_eq!(requests.into_inner(), 2);
+    assert_eq!(output_lines, ["deploy happened", "rollback happened"]);
+}
+
 #[tokio::test]
 async fn json_gzip_compression() {
     json_compression("gzip").await;
@@ -15,6 +15,7 @@ use crate::{
     sinks::{
         kafka::sink::{KafkaSink, healthcheck},
         prelude::*,
+        util::ordering::SinkOrdering,
     },
 };
 
@@ -130,6 +131,18 @@ pub struct KafkaSinkConfig {
     #[configurable(metadata(docs::examples = "headers"))]
     pub headers_key: Option<ConfigTargetPath>,
 
+    #[configurable(derived)]
+    #[configurable(metadata(docs::advanced))]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    pub ordering: SinkOrdering,
+
+    /// The field whose value groups the events sent in order when `ordering` is `per_key`.
+    ///
+    /// Defaults to `key_field`, so that the records of each key are produced in order.
+    #[configurable(metadata(docs::advanced))]
+    #[configurable(metadata(docs::examples = "user_id"))]
+    pub ordering_key_field: Option<ConfigTargetPath>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -137,6 +150,40 @@ pub struct KafkaSinkConfig {
         skip_serializing_if = "crate::serde::is_default"
     )]
     pub acknowledgements: AcknowledgementsConfig,
+
+    #[configurable(derived)]
+    #[configurable(metadata(docs::advanced))]
+    #[serde(default)]
+    pub transactional: Option<KafkaTransactionalConfig>,
+}
+
+/// Transactional delivery configuration.
+///
+/// When set, events are produced within Kafka transactions. Each batch of events, as bounded by
+/// the `batch` settings, is committed atomically once all its records are delivered, and the
+/// transaction is aborted if any of them fails. Consumers reading with the `read_committed`
+/// isolation level never see the records of aborted transactions.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaTransactionalConfig {
+    /// The transactional ID of the producer.
+    ///
+    /// It must be unique to each Vector instance writing to the cluster, and stay the same across
+    /// restarts, so that transactions left in progress by a previous run are aborted.
+    #[configurable(metadata(docs::examples = "vector-kafka-sink-0"))]
+    pub transactional_id: String,
+
+    /// Maximum time a transaction may remain open, in milliseconds.
+    ///
+    /// This is also the timeout for committing and aborting transactions. The
+    /// `message_timeout_ms` option is capped to this value, as required by `librdkafka`.
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[serde(default = "default_transaction_timeout_ms")]
+    #[configurable(metadata(docs::examples = 60000))]
+    #[configurable(metadata(docs::human_name = "Transaction Timeout"))]
+    pub transaction_timeout_ms: Duration,
 }
 
 const fn default_socket_timeout_ms() -> Duration {
@@ -147,6 +194,10 @@ const fn default_message_timeout_ms() -> Duration {
     Duration::from_millis(300000) // default in librdkafka
 }
 
+const fn default_transaction_timeout_ms() -> Duration {
+    Duration::from_millis(60000) // default in librdkafka
+}
+
 const fn default_rate_limit_duration_secs() -> u64 {
     1
 }
@@ -184,6 +235,22 @@ impl KafkaSinkConfig {
                 self.message_timeout_ms.as_millis().to_string(),
             );
 
+        if let Some(transactional) = &self.transactional {
+            let message_timeout_ms = self
+                .message_timeout_ms
+                .min(transactional.transaction_timeout_ms);
+            client_config
+                .set("transactional.id", &transactional.transactional_id)
+                .set(
+                    "transaction.timeout.ms",
+                    transactional.transaction_timeout_ms.as_millis().to_string(),
+                )
+                .set(
+                    "message.timeout.ms",
+                    message_timeout_ms.as_millis().to_string(),
+                );
+        }
+
         if let Some(value) = self.batch.timeout_secs {
             // Delay in milliseconds to wait for messages in the producer queue to accumulate before
             // constructing message batches (MessageSets) to transmit to brokers. A higher value
@@ -270,7 +337,10 @@ impl GenerateConfig for KafkaSinkConfig {
             rate_limit_num: default_rate_limit_num(),
             librdkafka_options: Default::default(),
             headers_key: None,
+            ordering: SinkOrdering::default(),
+            ordering_key_field: None,
             acknowledgements: Default::default(),
+            transactional: None,
         })
         .unwrap()
     }
@@ -305,4 +375,25 @@ mod tests {
     fn generate_config() {
         KafkaSinkConfig::generate_config();
     }
+
+    #[test]
+    fn transactional_client_config() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "topic"
+            encoding.codec = "json"
+
+            [transactional]
+            transactional_id = "vector-0"
+            "#,
+        )
+        .unwrap();
+        let client_config = config.to_rdkafka().unwrap();
+
+        assert_eq!(client_config.get("transactional.id"), Some("vector-0"));
+        assert_eq!(client_config.get("transaction.timeout.ms"), Some("60000"));
+        // Capped to the transaction timeout.
+        assert_eq!(client_config.get("message.timeout.ms"), Some("60000"));
+    }
 }
@@ -3,5 +3,6 @@ pub(crate) mod request_builder;
 pub(crate) mod service;
 pub(crate) mod sink;
 pub(crate) mod tests;
+pub(crate) mod transaction;
 
 pub use self::config::KafkaSinkConfig;
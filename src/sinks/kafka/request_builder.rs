@@ -7,12 +7,14 @@ use crate::{
     sinks::{
         kafka::service::{KafkaRequest, KafkaRequestMetadata},
         prelude::*,
+        util::ordering::OrderingKeys,
     },
 };
 
 pub struct KafkaRequestBuilder {
     pub key_field: Option<OwnedTargetPath>,
     pub headers_key: Option<OwnedTargetPath>,
+    pub ordering_keys: OrderingKeys,
     pub encoder: (Transformer, Encoder<()>),
 }
 
@@ -37,7 +39,8 @@ impl RequestBuilder<(String, Event)> for KafkaRequestBuilder {
         input: (String, Event),
     ) -> (Self::Metadata, RequestMetadataBuilder, Self::Events) {
         let (topic, mut event) = input;
-        let builder = RequestMetadataBuilder::from_event(&event);
+        let builder = RequestMetadataBuilder::from_event(&event)
+            .with_ordering_keys(self.ordering_keys.keys([&event]));
 
         let metadata = KafkaRequestMetadata {
             finalizers: event.take_finalizers(),
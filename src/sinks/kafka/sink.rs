@@ -6,7 +6,7 @@ use rdkafka::{
     producer::{BaseProducer, FutureProducer, Producer},
 };
 use snafu::{ResultExt, Snafu};
-use tower::limit::RateLimit;
+use tower::limit::{ConcurrencyLimit, RateLimit};
 use tracing::Span;
 use vrl::path::OwnedTargetPath;
 
@@ -15,8 +15,13 @@ use crate::{
     config::SinkHealthcheckOptions,
     kafka::KafkaStatisticsContext,
     sinks::{
-        kafka::{request_builder::KafkaRequestBuilder, service::KafkaService},
+        kafka::{
+            request_builder::KafkaRequestBuilder,
+            service::KafkaService,
+            transaction::{KafkaRequestSizer, KafkaTransactionRequest, KafkaTransactionalService},
+        },
         prelude::*,
+        util::ordering::OrderingKeys,
     },
 };
 
@@ -30,10 +35,22 @@ pub(super) enum BuildError {
 pub struct KafkaSink {
     transformer: Transformer,
     encoder: Encoder<()>,
-    service: RateLimit<KafkaService>,
+    service: KafkaSinkService,
     topic: Template,
     key_field: Option<OwnedTargetPath>,
     headers_key: Option<OwnedTargetPath>,
+    ordering_keys: OrderingKeys,
+}
+
+enum KafkaSinkService {
+    /// Each record is produced on its own.
+    Record(RateLimit<KafkaService>),
+
+    /// Batches of records are produced within transactions, one at a time.
+    Transactional {
+        batch_settings: BatcherSettings,
+        service: RateLimit<ConcurrencyLimit<KafkaTransactionalService>>,
+    },
 }
 
 pub(crate) fn create_producer(
@@ -56,18 +73,39 @@ impl KafkaSink {
         let serializer = config.encoding.build()?;
         let encoder = Encoder::<()>::new(serializer);
 
+        let rate_limit = ServiceBuilder::new().rate_limit(
+            config.rate_limit_num,
+            Duration::from_secs(config.rate_limit_duration_secs),
+        );
+        let service = match &config.transactional {
+            None => KafkaSinkService::Record(rate_limit.service(KafkaService::new(producer))),
+            Some(transactional) => KafkaSinkService::Transactional {
+                batch_settings: config.batch.into_batcher_settings()?,
+                service: rate_limit
+                    .concurrency_limit(1)
+                    .service(KafkaTransactionalService::new(
+                        producer,
+                        transactional.transaction_timeout_ms,
+                    )),
+            },
+        };
+
+        let ordering_keys = OrderingKeys::new(
+            config.ordering,
+            config
+                .ordering_key_field
+                .as_ref()
+                .or(config.key_field.as_ref()),
+        )?;
+
         Ok(KafkaSink {
             headers_key: config.headers_key.map(|key| key.0),
             transformer,
             encoder,
-            service: ServiceBuilder::new()
-                .rate_limit(
-                    config.rate_limit_num,
-                    Duration::from_secs(config.rate_limit_duration_secs),
-                )
-                .service(KafkaService::new(producer)),
+            service,
             topic: config.topic,
             key_field: config.key_field.map(|key| key.0),
+            ordering_keys,
         })
     }
 
@@ -75,10 +113,11 @@ impl KafkaSink {
         let request_builder = KafkaRequestBuilder {
             key_field: self.key_field,
             headers_key: self.headers_key,
+            ordering_keys: self.ordering_keys,
             encoder: (self.transformer, self.encoder),
         };
 
-        input
+        let requests = input
             .filter_map(|event| {
                 // Compute the topic.
                 future::ready(
@@ -104,11 +143,25 @@ impl KafkaSink {
                     }
                     Ok(req) => Some(req),
                 }
-            })
-            .into_driver(self.service)
-            .protocol("kafka")
-            .run()
-            .await
+            });
+
+        match self.service {
+            KafkaSinkService::Record(service) => {
+                requests.into_driver(service).protocol("kafka").run().await
+            }
+            KafkaSinkService::Transactional {
+                batch_settings,
+                service,
+            } => {
+                requests
+                    .batched(batch_settings.as_item_size_config(KafkaRequestSizer))
+                    .map(KafkaTransactionRequest::new)
+                    .into_driver(service)
+                    .protocol("kafka")
+                    .run()
+                    .await
+            }
+        }
     }
 }
 
@@ -19,7 +19,11 @@ mod integration_test {
         lookup::lookup_v2::ConfigTargetPath,
     };
 
-    use super::super::{config::KafkaSinkConfig, sink::KafkaSink, *};
+    use super::super::{
+        config::{KafkaSinkConfig, KafkaTransactionalConfig},
+        sink::KafkaSink,
+        *,
+    };
     use crate::{
         event::{ObjectMap, Value},
         kafka::{KafkaAuthConfig, KafkaCompression, KafkaSaslConfig},
@@ -63,7 +67,10 @@ mod integration_test {
             rate_limit_num: i64::MAX as u64,
             librdkafka_options: HashMap::new(),
             headers_key: None,
+            ordering: Default::default(),
+            ordering_key_field: None,
             acknowledgements: Default::default(),
+            transactional: None,
         };
         self::sink::healthcheck(config, Default::default())
             .await
@@ -91,7 +98,10 @@ mod integration_test {
             rate_limit_num: i64::MAX as u64,
             librdkafka_options: HashMap::new(),
             headers_key: None,
+            ordering: Default::default(),
+            ordering_key_field: None,
             acknowledgements: Default::default(),
+            transactional: None,
         };
         self::sink::healthcheck(config, Default::default())
             .await
@@ -194,7 +204,10 @@ mod integration_test {
             batch,
             librdkafka_options,
             headers_key: None,
+            ordering: Default::default(),
+            ordering_key_field: None,
             acknowledgements: Default::default(),
+            transactional: None,
         };
         config.clone().to_rdkafka()?;
         self::sink::healthcheck(config.clone(), Default::default()).await?;
@@ -311,6 +324,84 @@ mod integration_test {
         .await;
     }
 
+    #[tokio::test]
+    async fn kafka_happy_path_transactional() {
+        crate::test_util::trace_init();
+
+        let server = kafka_address(9091);
+        let topic = format!("test-{}", random_string(10));
+        let mut batch = BatchConfig::default();
+        batch.max_events = Some(100);
+        let config = KafkaSinkConfig {
+            bootstrap_servers: server.clone(),
+            topic: Template::try_from(topic.clone()).unwrap(),
+            healthcheck_topic: None,
+            key_field: None,
+            encoding: TextSerializerConfig::default().into(),
+            batch,
+            compression: KafkaCompression::None,
+            auth: KafkaAuthConfig::default(),
+            socket_timeout_ms: Duration::from_millis(60000),
+            message_timeout_ms: Duration::from_millis(300000),
+            rate_limit_duration_secs: 1,
+            rate_limit_num: i64::MAX as u64,
+            librdkafka_options: HashMap::new(),
+            headers_key: None,
+            ordering: Default::default(),
+            ordering_key_field: None,
+            acknowledgements: Default::default(),
+            transactional: Some(KafkaTransactionalConfig {
+                transactional_id: format!("vector-{}", random_string(10)),
+                transaction_timeout_ms: Duration::from_millis(60000),
+            }),
+        };
+
+        let num_events = 1000;
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(100, num_events, Some(batch));
+
+        assert_sink_compliance(&SINK_TAGS, async move {
+            let sink = KafkaSink::new(config).unwrap();
+            let sink = VectorSink::from_event_streamsink(sink);
+            sink.run(events).await
+        })
+        .await
+        .expect("Running sink failed");
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        // Only records of committed transactions are visible to `read_committed` consumers.
+        let mut client_config = rdkafka::ClientConfig::new();
+        client_config.set("bootstrap.servers", server.as_str());
+        client_config.set("group.id", random_string(10));
+        client_config.set("isolation.level", "read_committed");
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition(&topic, 0)
+            .set_offset(Offset::Beginning)
+            .unwrap();
+
+        let consumer: BaseConsumer = client_config.create().unwrap();
+        consumer.assign(&tpl).unwrap();
+
+        let mut failures = 0;
+        let mut out = Vec::new();
+        while failures < 100 {
+            match consumer.poll(Duration::from_secs(3)) {
+                Some(Ok(msg)) => {
+                    let s: &str = msg.payload_view().unwrap().unwrap();
+                    out.push(s.to_owned());
+                }
+                None if out.len() >= input.len() => break,
+                _ => {
+                    failures += 1;
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+
+        assert_eq!(out, input);
+    }
+
     async fn kafka_happy_path(
         server: String,
         sasl: Option<KafkaSaslConfig>,
@@ -350,7 +441,10 @@ mod integration_test {
             rate_limit_num: i64::MAX as u64,
             librdkafka_options: HashMap::new(),
             headers_key: Some(headers_key.clone()),
+            ordering: Default::default(),
+            ordering_key_field: None,
             acknowledgements: Default::default(),
+            transactional: None,
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
         println!("Topic name generated in test: {topic:?}");
@@ -0,0 +1,536 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::future::join_all;
+use rdkafka::{
+    ClientContext,
+    error::{KafkaError, KafkaResult},
+    producer::{FutureProducer, Producer},
+};
+use vector_lib::stream::batcher::limiter::ItemBatchSize;
+
+use crate::{
+    internal_events::KafkaTransactionError,
+    kafka::KafkaStatisticsContext,
+    sinks::{
+        kafka::service::{KafkaRequest, KafkaResponse, KafkaService},
+        prelude::*,
+    },
+};
+
+/// Sizes requests by their payload and key, the same way the broker accounts for them.
+#[derive(Clone, Copy, Default)]
+pub struct KafkaRequestSizer;
+
+impl ItemBatchSize<KafkaRequest> for KafkaRequestSizer {
+    fn size(&self, item: &KafkaRequest) -> usize {
+        item.body.len() + item.metadata.key.as_ref().map_or(0, |key| key.len())
+    }
+}
+
+/// A batch of records produced within a single transaction.
+pub struct KafkaTransactionRequest {
+    requests: Vec<KafkaRequest>,
+    finalizers: EventFinalizers,
+    request_metadata: RequestMetadata,
+}
+
+impl KafkaTransactionRequest {
+    pub fn new(mut requests: Vec<KafkaRequest>) -> Self {
+        let finalizers =
+            requests
+                .iter_mut()
+                .fold(EventFinalizers::default(), |mut finalizers, request| {
+                    finalizers.merge(request.take_finalizers());
+                    finalizers
+                });
+        let request_metadata = RequestMetadata::from_batch(
+            requests
+                .iter()
+                .map(|request| request.get_metadata().clone()),
+        );
+
+        Self {
+            requests,
+            finalizers,
+            request_metadata,
+        }
+    }
+}
+
+impl Finalizable for KafkaTransactionRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl MetaDescriptive for KafkaTransactionRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.request_metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.request_metadata
+    }
+}
+
+/// The transaction operations of a producer.
+pub trait TransactionalProducer: Clone + Send + Sync + 'static {
+    fn init_transactions(&self, timeout: Duration) -> KafkaResult<()>;
+
+    fn begin_transaction(&self) -> KafkaResult<()>;
+
+    fn commit_transaction(&self, timeout: Duration) -> KafkaResult<()>;
+
+    fn abort_transaction(&self, timeout: Duration) -> KafkaResult<()>;
+
+    /// Returns how to recover from an error returned by one of the transaction operations.
+    fn recovery(&self, error: &KafkaError) -> Recovery {
+        Recovery::of(error)
+    }
+}
+
+impl<C: ClientContext + 'static> TransactionalProducer for FutureProducer<C> {
+    fn init_transactions(&self, timeout: Duration) -> KafkaResult<()> {
+        Producer::init_transactions(self, timeout)
+    }
+
+    fn begin_transaction(&self) -> KafkaResult<()> {
+        Producer::begin_transaction(self)
+    }
+
+    fn commit_transaction(&self, timeout: Duration) -> KafkaResult<()> {
+        Producer::commit_transaction(self, timeout)
+    }
+
+    fn abort_transaction(&self, timeout: Duration) -> KafkaResult<()> {
+        Producer::abort_transaction(self, timeout)
+    }
+}
+
+/// How to recover from a failed transaction operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recovery {
+    /// The operation can be called again.
+    Retry,
+
+    /// The transaction must be aborted, after which a new one can begin.
+    Abort,
+
+    /// The producer can no longer be used, which happens when another producer with the same
+    /// transactional ID fenced it off.
+    Fatal,
+}
+
+impl Recovery {
+    fn of(error: &KafkaError) -> Self {
+        match error {
+            KafkaError::Transaction(error) if error.is_fatal() => Self::Fatal,
+            KafkaError::Transaction(error) if error.txn_requires_abort() => Self::Abort,
+            KafkaError::Transaction(error) if error.is_retriable() => Self::Retry,
+            _ => Self::Abort,
+        }
+    }
+}
+
+/// The number of times an operation that failed with a retriable error is attempted, before the
+/// transaction is aborted.
+const MAX_OPERATION_ATTEMPTS: usize = 3;
+
+/// Produces each batch of records within a Kafka transaction, which is only committed once all of
+/// its records have been delivered and is aborted otherwise.
+///
+/// A producer can only have a single transaction in progress, so this service must not be called
+/// concurrently.
+#[derive(Clone)]
+pub struct KafkaTransactionalService<P = FutureProducer<KafkaStatisticsContext>, S = KafkaService> {
+    producer: P,
+    service: S,
+    timeout: Duration,
+
+    /// Whether the transactions of the producer have been initialized.
+    initialized: Arc<AtomicBool>,
+}
+
+impl KafkaTransactionalService {
+    pub(crate) fn new(
+        kafka_producer: FutureProducer<KafkaStatisticsContext>,
+        timeout: Duration,
+    ) -> Self {
+        Self::with_service(
+            kafka_producer.clone(),
+            KafkaService::new(kafka_producer),
+            timeout,
+        )
+    }
+}
+
+impl<P: TransactionalProducer, S> KafkaTransactionalService<P, S> {
+    fn with_service(producer: P, service: S, timeout: Duration) -> Self {
+        Self {
+            producer,
+            service,
+            timeout,
+            initialized: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Runs a blocking transaction operation of the producer on the blocking thread pool,
+    /// attempting it again as long as it fails with a retriable error.
+    async fn run_blocking(
+        &self,
+        operation: &'static str,
+        run: fn(&P, Duration) -> KafkaResult<()>,
+    ) -> KafkaResult<()> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let producer = self.producer.clone();
+            let timeout = self.timeout;
+            let result = tokio::task::spawn_blocking(move || run(&producer, timeout))
+                .await
+                // The operation panicked, which leaves the state of the transaction unknown.
+                .unwrap_or(Err(KafkaError::Canceled));
+
+            match result {
+                Ok(()) => return Ok(()),
+                Err(error) => {
+                    emit!(KafkaTransactionError {
+                        error: error.clone(),
+                        operation,
+                    });
+                    if attempts >= MAX_OPERATION_ATTEMPTS
+                        || self.producer.recovery(&error) != Recovery::Retry
+                    {
+                        return Err(error);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn initialize(&self) -> KafkaResult<()> {
+        if !self.initialized.load(Ordering::Acquire) {
+            // Fences off any previous producer using the same transactional ID, and aborts the
+            // transaction it left in progress.
+            self.run_blocking("init_transactions", P::init_transactions)
+                .await?;
+            self.initialized.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    fn begin(&self) -> KafkaResult<()> {
+        self.producer.begin_transaction().inspect_err(|error| {
+            emit!(KafkaTransactionError {
+                error: error.clone(),
+                operation: "begin_transaction",
+            })
+        })
+    }
+
+    async fn commit(&self) -> KafkaResult<()> {
+        self.run_blocking("commit_transaction", P::commit_transaction)
+            .await
+    }
+
+    async fn abort(&self) -> KafkaResult<()> {
+        self.run_blocking("abort_transaction", P::abort_transaction)
+            .await
+    }
+}
+
+impl<P, S> Service<KafkaTransactionRequest> for KafkaTransactionalService<P, S>
+where
+    P: TransactionalProducer,
+    S: Service<KafkaRequest, Response = KafkaResponse, Error = KafkaError>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    S::Future: Send,
+{
+    type Response = KafkaResponse;
+    type Error = KafkaError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: KafkaTransactionRequest) -> Self::Future {
+        let this = self.clone();
+
+        Box::pin(async move {
+            // The events of batches that aren't committed are reported as errored, so that sources
+            // with end-to-end acknowledgements enabled can deliver them again.
+            let errored = || KafkaResponse {
+                event_byte_size: telemetry().create_request_count_byte_size(),
+                raw_byte_size: 0,
+                event_status: EventStatus::Errored,
+            };
+
+            if let Err(error) = this.initialize().await {
+                // Initialization is attempted again with the next batch.
+                return if this.producer.recovery(&error) == Recovery::Fatal {
+                    Err(error)
+                } else {
+                    Ok(errored())
+                };
+            }
+
+            if let Err(error) = this.begin() {
+                if this.producer.recovery(&error) == Recovery::Fatal {
+                    return Err(error);
+                }
+                // A transaction may have been left in progress by a failed abort, which has to be
+                // aborted before a new one can begin.
+                warn!(message = "Aborting Kafka transaction.");
+                return this.abort().await.map(|()| errored());
+            }
+
+            let mut service = this.service.clone();
+            let responses = join_all(
+                request
+                    .requests
+                    .into_iter()
+                    .map(|record| service.call(record)),
+            )
+            .await;
+
+            let mut raw_byte_size = 0;
+            let mut delivered = true;
+            for response in responses {
+                match response {
+                    Ok(response) if response.event_status == EventStatus::Delivered => {
+                        raw_byte_size += response.raw_byte_size;
+                    }
+                    Ok(_) => delivered = false,
+                    Err(error) => {
+                        debug!(message = "Failed to deliver record within transaction.", %error);
+                        delivered = false;
+                    }
+                }
+            }
+
+            if delivered {
+                match this.commit().await {
+                    Ok(()) => {
+                        return Ok(KafkaResponse {
+                            event_byte_size: request
+                                .request_metadata
+                                .into_events_estimated_json_encoded_byte_size(),
+                            raw_byte_size,
+                            event_status: EventStatus::Delivered,
+                        });
+                    }
+                    Err(error) if this.producer.recovery(&error) == Recovery::Fatal => {
+                        return Err(error);
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            // None of the records of an aborted transaction are visible to `read_committed`
+            // consumers.
+            warn!(message = "Aborting Kafka transaction.");
+            this.abort().await.map(|()| errored())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::Mutex,
+    };
+
+    use bytes::Bytes;
+    use futures::future;
+    use rdkafka::types::RDKafkaErrorCode;
+
+    use super::*;
+    use crate::sinks::kafka::service::KafkaRequestMetadata;
+
+    /// A producer whose operations fail with the given errors, in order, and succeed otherwise.
+    #[derive(Clone, Default)]
+    struct MockProducer {
+        errors: Arc<Mutex<HashMap<&'static str, VecDeque<RDKafkaErrorCode>>>>,
+        calls: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl MockProducer {
+        fn failing(operation: &'static str, errors: &[RDKafkaErrorCode]) -> Self {
+            let producer = Self::default();
+            producer
+                .errors
+                .lock()
+                .unwrap()
+                .insert(operation, errors.iter().copied().collect());
+            producer
+        }
+
+        fn call(&self, operation: &'static str) -> KafkaResult<()> {
+            self.calls.lock().unwrap().push(operation);
+            match self
+                .errors
+                .lock()
+                .unwrap()
+                .get_mut(operation)
+                .and_then(VecDeque::pop_front)
+            {
+                Some(code) => Err(KafkaError::Global(code)),
+                None => Ok(()),
+            }
+        }
+
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl TransactionalProducer for MockProducer {
+        fn init_transactions(&self, _timeout: Duration) -> KafkaResult<()> {
+            self.call("init")
+        }
+
+        fn begin_transaction(&self) -> KafkaResult<()> {
+            self.call("begin")
+        }
+
+        fn commit_transaction(&self, _timeout: Duration) -> KafkaResult<()> {
+            self.call("commit")
+        }
+
+        fn abort_transaction(&self, _timeout: Duration) -> KafkaResult<()> {
+            self.call("abort")
+        }
+
+        fn recovery(&self, error: &KafkaError) -> Recovery {
+            match error.rdkafka_error_code() {
+                Some(RDKafkaErrorCode::RequestTimedOut) => Recovery::Retry,
+                Some(RDKafkaErrorCode::ProducerFenced) => Recovery::Fatal,
+                _ => Recovery::Abort,
+            }
+        }
+    }
+
+    /// A record service which delivers every record with the given status.
+    #[derive(Clone)]
+    struct MockRecords(EventStatus);
+
+    impl Service<KafkaRequest> for MockRecords {
+        type Response = KafkaResponse;
+        type Error = KafkaError;
+        type Future = future::Ready<Result<KafkaResponse, KafkaError>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: KafkaRequest) -> Self::Future {
+            future::ok(KafkaResponse {
+                event_byte_size: telemetry().create_request_count_byte_size(),
+                raw_byte_size: request.body.len(),
+                event_status: self.0,
+            })
+        }
+    }
+
+    fn request() -> KafkaTransactionRequest {
+        KafkaTransactionRequest::new(vec![KafkaRequest {
+            body: Bytes::from("record"),
+            metadata: KafkaRequestMetadata {
+                finalizers: EventFinalizers::default(),
+                key: None,
+                timestamp_millis: None,
+                headers: None,
+                topic: "topic".to_owned(),
+            },
+            request_metadata: RequestMetadata::default(),
+        }])
+    }
+
+    async fn send(
+        producer: &MockProducer,
+        records: EventStatus,
+    ) -> Result<KafkaResponse, KafkaError> {
+        let mut service = KafkaTransactionalService::with_service(
+            producer.clone(),
+            MockRecords(records),
+            Duration::from_secs(1),
+        );
+        service.call(request()).await
+    }
+
+    #[tokio::test]
+    async fn commits_delivered_records() {
+        let producer = MockProducer::default();
+        let response = send(&producer, EventStatus::Delivered).await.unwrap();
+        assert_eq!(response.event_status, EventStatus::Delivered);
+        assert_eq!(response.raw_byte_size, 6);
+        assert_eq!(producer.calls(), ["init", "begin", "commit"]);
+    }
+
+    #[tokio::test]
+    async fn aborts_undelivered_records() {
+        let producer = MockProducer::default();
+        let response = send(&producer, EventStatus::Errored).await.unwrap();
+        assert_eq!(response.event_status, EventStatus::Errored);
+        assert_eq!(producer.calls(), ["init", "begin", "abort"]);
+    }
+
+    #[tokio::test]
+    async fn retries_retriable_commit_errors() {
+        let producer = MockProducer::failing("commit", &[RDKafkaErrorCode::RequestTimedOut]);
+        let response = send(&producer, EventStatus::Delivered).await.unwrap();
+        assert_eq!(response.event_status, EventStatus::Delivered);
+        assert_eq!(producer.calls(), ["init", "begin", "commit", "commit"]);
+    }
+
+    #[tokio::test]
+    async fn aborts_once_commit_retries_are_exhausted() {
+        let producer = MockProducer::failing("commit", &[RDKafkaErrorCode::RequestTimedOut; 3]);
+        let response = send(&producer, EventStatus::Delivered).await.unwrap();
+        assert_eq!(response.event_status, EventStatus::Errored);
+        assert_eq!(
+            producer.calls(),
+            ["init", "begin", "commit", "commit", "commit", "abort"]
+        );
+    }
+
+    #[tokio::test]
+    async fn aborts_abortable_commit_errors() {
+        let producer =
+            MockProducer::failing("commit", &[RDKafkaErrorCode::InvalidTransactionalState]);
+        let response = send(&producer, EventStatus::Delivered).await.unwrap();
+        assert_eq!(response.event_status, EventStatus::Errored);
+        assert_eq!(producer.calls(), ["init", "begin", "commit", "abort"]);
+    }
+
+    #[tokio::test]
+    async fn aborts_when_begin_fails() {
+        let producer = MockProducer::failing("begin", &[RDKafkaErrorCode::State]);
+        let response = send(&producer, EventStatus::Delivered).await.unwrap();
+        assert_eq!(response.event_status, EventStatus::Errored);
+        assert_eq!(producer.calls(), ["init", "begin", "abort"]);
+    }
+
+    #[tokio::test]
+    async fn fails_on_fatal_errors() {
+        let producer = MockProducer::failing("commit", &[RDKafkaErrorCode::ProducerFenced]);
+        let error = send(&producer, EventStatus::Delivered).await.err().unwrap();
+        assert_eq!(
+            error.rdkafka_error_code(),
+            Some(RDKafkaErrorCode::ProducerFenced)
+        );
+        assert_eq!(producer.calls(), ["init", "begin", "commit"]);
+    }
+}
@@ -6,7 +6,7 @@ use vector_lib::{codecs::JsonSerializerConfig, tls::TlsEnableableConfig};
 
 use super::{ConfigSnafu, ConnectSnafu, NatsError, sink::NatsSink};
 use crate::{
-    nats::{NatsAuthConfig, NatsConfigError, from_tls_auth_config},
+    nats::{NatsAuthConfig, NatsConfigError, NatsStreamConfig, from_tls_auth_config},
     sinks::{prelude::*, util::service::TowerRequestConfigDefaults},
 };
 
@@ -61,6 +61,19 @@ pub struct JetStreamConfig {
     #[configurable(metadata(docs::templateable))]
     #[serde(default)]
     pub(super) headers: Option<NatsHeaderConfig>,
+
+    /// The name of the stream the messages are published to.
+    ///
+    /// This is only used to create the stream with `create_stream`.
+    #[configurable(metadata(docs::examples = "EVENTS"))]
+    pub(super) stream: Option<String>,
+
+    /// Creates the stream if it doesn't exist.
+    ///
+    /// By default, the stream must already exist. When the `subject` is templated, the
+    /// `subjects` of the stream must be set.
+    #[configurable(derived)]
+    pub(super) create_stream: Option<NatsStreamConfig>,
 }
 
 impl From<bool> for JetStreamConfig {
@@ -169,6 +182,8 @@ impl GenerateConfig for NatsSinkConfig {
                 headers: Some(NatsHeaderConfig {
                     message_id: Some(Template::try_from("{{ event_id }}").unwrap()),
                 }),
+                stream: None,
+                create_stream: None,
             },
         })
         .unwrap()
@@ -222,6 +237,21 @@ impl NatsSinkConfig {
             .collect()
     }
 
+    pub(super) fn stream_config(
+        &self,
+        stream_config: &NatsStreamConfig,
+    ) -> Result<async_nats::jetstream::stream::Config, NatsError> {
+        let name = self
+            .jetstream
+            .stream
+            .as_deref()
+            .ok_or(NatsError::MissingStreamName)?;
+        if stream_config.subjects.is_empty() && self.subject.is_dynamic() {
+            return Err(NatsError::MissingStreamSubjects);
+        }
+        Ok(stream_config.to_stream_config(name, self.subject.get_ref()))
+    }
+
     #[cfg(not(test))]
     fn create_connect_options(&self) -> Result<async_nats::ConnectOptions, NatsError> {
         let mut options: async_nats::ConnectOptions = self.try_into().context(ConfigSnafu)?;
@@ -240,9 +270,17 @@ impl NatsSinkConfig {
         let connection = self.connect(options).await?;
 
         if self.jetstream.enabled {
-            Ok(NatsPublisher::JetStream(async_nats::jetstream::new(
-                connection,
-            )))
+            let jetstream = async_nats::jetstream::new(connection);
+            if let Some(stream_config) = &self.jetstream.create_stream {
+                let stream_config = self.stream_config(stream_config)?;
+                jetstream
+                    .get_or_create_stream(stream_config)
+                    .await
+                    .map_err(|error| NatsError::CreateStream {
+                        source: error.into(),
+                    })?;
+            }
+            Ok(NatsPublisher::JetStream(jetstream))
         } else {
             Ok(NatsPublisher::Core(connection))
         }
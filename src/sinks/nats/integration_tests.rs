@@ -465,6 +465,7 @@ async fn nats_jetstream_message_id_valid() {
     conf.jetstream = JetStreamConfig {
         enabled: true,
         headers: Some(header_config),
+        ..Default::default()
     };
 
     let sink = NatsSink::new(conf.clone()).await.unwrap();
@@ -29,4 +29,12 @@ enum NatsError {
     ServerError { source: async_nats::Error },
     #[snafu(display("NATS Publish Error: {}", source))]
     PublishError { source: async_nats::Error },
+    #[snafu(display("NATS Stream Creation Error: {}", source))]
+    CreateStream { source: async_nats::Error },
+    #[snafu(display("`jetstream.stream` must be set to create the stream"))]
+    MissingStreamName,
+    #[snafu(display(
+        "`jetstream.create_stream.subjects` must be set when the subject is templated"
+    ))]
+    MissingStreamSubjects,
 }
@@ -1,6 +1,65 @@
-use super::config::NatsSinkConfig;
+use super::{NatsError, config::NatsSinkConfig};
 
 #[test]
 fn generate_config() {
     crate::test_util::test_generate_config::<NatsSinkConfig>();
 }
+
+fn parse_config(config: &str) -> NatsSinkConfig {
+    toml::from_str(&format!(
+        r#"
+        url = "nats://127.0.0.1:4222"
+        encoding.codec = "json"
+        {config}
+        "#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn stream_config_defaults_to_the_subject() {
+    let config = parse_config(
+        r#"
+        subject = "events.logs"
+        jetstream.enabled = true
+        jetstream.stream = "EVENTS"
+        jetstream.create_stream.storage = "memory"
+        "#,
+    );
+    let stream = config
+        .stream_config(config.jetstream.create_stream.as_ref().unwrap())
+        .unwrap();
+    assert_eq!(stream.name, "EVENTS");
+    assert_eq!(stream.subjects, vec!["events.logs".to_owned()]);
+}
+
+#[test]
+fn stream_config_requires_a_name() {
+    let config = parse_config(
+        r#"
+        subject = "events.logs"
+        jetstream.enabled = true
+        jetstream.create_stream = {}
+        "#,
+    );
+    assert!(matches!(
+        config.stream_config(config.jetstream.create_stream.as_ref().unwrap()),
+        Err(NatsError::MissingStreamName)
+    ));
+}
+
+#[test]
+fn stream_config_requires_subjects_with_templated_subject() {
+    let config = parse_config(
+        r#"
+        subject = "events.{{ service }}"
+        jetstream.enabled = true
+        jetstream.stream = "EVENTS"
+        jetstream.create_stream = {}
+        "#,
+    );
+    assert!(matches!(
+        config.stream_config(config.jetstream.create_stream.as_ref().unwrap()),
+        Err(NatsError::MissingStreamSubjects)
+    ));
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+
+use http::HeaderMap;
+use vector_lib::opentelemetry::proto::collector::logs::v1::ExportLogsServiceRequest;
+
+use super::{
+    OpenTelemetrySinkError,
+    request_builder::OtlpRequestBuilder,
+    service::{OtlpPayload, OtlpRequest, OtlpResponse, OtlpService},
+    sink::OpenTelemetrySink,
+};
+use crate::{
+    http::{HttpClient, build_proxy_connector},
+    sinks::{
+        prelude::*,
+        util::{RealtimeEventBasedDefaultBatchSettings, UriSerde, http::validate_headers},
+    },
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+/// The transport used to send OTLP data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum OtlpTransport {
+    /// OTLP over gRPC.
+    Grpc,
+
+    /// OTLP over HTTP, encoded as Protobuf.
+    HttpProtobuf,
+}
+
+/// Configuration for sending log events and traces as OTLP.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpSinkConfig {
+    /// The endpoint to send OTLP data to.
+    ///
+    /// With the `grpc` protocol, this is the address of the OTLP/gRPC server, such as an
+    /// OpenTelemetry collector listening on port 4317.
+    ///
+    /// With the `http_protobuf` protocol, this is the base URL of the OTLP/HTTP server. Log events
+    /// are sent to the `/v1/logs` path, and traces to the `/v1/traces` path, relative to it.
+    #[configurable(metadata(docs::examples = "http://localhost:4317"))]
+    #[configurable(metadata(docs::examples = "https://otlp.example.com:4318"))]
+    pub endpoint: UriSerde,
+
+    /// Compression to apply to requests.
+    ///
+    /// Only `none` and `gzip` are supported, as these are the only compression algorithms OTLP
+    /// servers are required to support.
+    #[serde(default)]
+    pub compression: Compression,
+
+    /// Additional headers to add to every request.
+    ///
+    /// With the `grpc` protocol, these are sent as request metadata. This is typically used to
+    /// authenticate against vendor OTLP endpoints.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "A request header and its value."
+    ))]
+    #[configurable(metadata(docs::examples = "headers_examples()"))]
+    pub headers: BTreeMap<String, String>,
+
+    /// Resource attributes to set on the data sent.
+    ///
+    /// Values are [templates][template] rendered against each event, which allows mapping fields
+    /// of events to resource attributes, such as `service.name`. The attributes override the
+    /// resource attributes an event already has, such as those received by the `opentelemetry`
+    /// source. If a template fails to render, the attribute is left out for that event.
+    ///
+    /// [template]: https://vector.dev/docs/reference/configuration/template-syntax/
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "An OpenTelemetry resource attribute."
+    ))]
+    #[configurable(metadata(docs::examples = "resource_attributes_examples()"))]
+    pub resource_attributes: BTreeMap<String, Template>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+fn headers_examples() -> BTreeMap<String, String> {
+    vrl::btreemap! {
+        "Authorization" => "Bearer ${OTLP_TOKEN}",
+    }
+}
+
+fn resource_attributes_examples() -> BTreeMap<String, String> {
+    vrl::btreemap! {
+        "service.name" => "{{ service }}",
+        "deployment.environment" => "production",
+    }
+}
+
+impl OtlpSinkConfig {
+    pub(super) fn build(
+        &self,
+        cx: SinkContext,
+        transport: OtlpTransport,
+    ) -> crate::Result<(VectorSink, Healthcheck)> {
+        if !matches!(self.compression, Compression::None | Compression::Gzip(_)) {
+            return Err(OpenTelemetrySinkError::UnsupportedCompression.into());
+        }
+
+        let tls = MaybeTlsSettings::from_config(self.tls.as_ref(), false)?;
+        let endpoint = self.endpoint.with_default_parts();
+        let headers = validate_headers(&self.headers)?
+            .into_iter()
+            .map(|(name, value)| (name.inner().clone(), value))
+            .collect::<HeaderMap>();
+
+        let service = match transport {
+            OtlpTransport::Grpc => {
+                let proxy = build_proxy_connector(tls, cx.proxy())?;
+                let client = hyper::Client::builder().http2_only(true).build(proxy);
+                OtlpService::grpc(
+                    client,
+                    endpoint.uri,
+                    self.compression.is_compressed(),
+                    headers,
+                )
+            }
+            OtlpTransport::HttpProtobuf => OtlpService::http(
+                HttpClient::new(tls, cx.proxy())?,
+                endpoint.append_path("v1/logs")?.uri,
+                endpoint.append_path("v1/traces")?.uri,
+                self.compression,
+                headers,
+            ),
+        };
+
+        let healthcheck = healthcheck(service.clone()).boxed();
+
+        let service = ServiceBuilder::new()
+            .settings(self.request.into_settings(), OtlpRetryLogic)
+            .service(service);
+
+        let sink = OpenTelemetrySink {
+            batch_settings: self.batch.into_batcher_settings()?,
+            request_builder: OtlpRequestBuilder {
+                resource_attributes: self.resource_attributes.clone(),
+            },
+            service,
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    pub(super) fn input(&self) -> Input {
+        Input::new(DataType::Log | DataType::Trace)
+    }
+}
+
+/// Checks that the endpoint accepts OTLP data, by sending an empty logs export request.
+async fn healthcheck(mut service: OtlpService) -> crate::Result<()> {
+    service
+        .export(OtlpPayload::Logs(ExportLogsServiceRequest::default()))
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct OtlpRetryLogic;
+
+impl RetryLogic for OtlpRetryLogic {
+    type Error = OpenTelemetrySinkError;
+    type Request = OtlpRequest;
+    type Response = OtlpResponse;
+
+    fn is_retriable_error(&self, err: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        // Lists of retryable responses taken from
+        //
+        // <https://opentelemetry.io/docs/specs/otlp/#failures>
+        // <https://opentelemetry.io/docs/specs/otlp/#retryable-response-codes>
+        match err {
+            OpenTelemetrySinkError::GrpcRequest { source } => matches!(
+                source.code(),
+                Cancelled
+                    | DeadlineExceeded
+                    | ResourceExhausted
+                    | Aborted
+                    | OutOfRange
+                    | Unavailable
+                    | DataLoss
+            ),
+            OpenTelemetrySinkError::HttpRequest { .. } => true,
+            OpenTelemetrySinkError::HttpResponse { status, .. } => {
+                matches!(status.as_u16(), 429 | 502 | 503 | 504)
+            }
+            OpenTelemetrySinkError::Compression { .. }
+            | OpenTelemetrySinkError::UnsupportedCompression => false,
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use http::StatusCode;
 use indoc::indoc;
+use snafu::Snafu;
 use vector_config::component::GenerateConfig;
 use vector_lib::{
     codecs::{
@@ -11,14 +13,23 @@ use vector_lib::{
 use crate::{
     codecs::{EncodingConfigWithFraming, Transformer},
     config::{AcknowledgementsConfig, Input, SinkConfig, SinkContext},
+    http::HttpError,
     sinks::{
         Healthcheck, VectorSink,
         http::config::{HttpMethod, HttpSinkConfig},
     },
 };
 
+mod config;
+mod request_builder;
+mod service;
+mod sink;
+
+pub use config::OtlpSinkConfig;
+use config::OtlpTransport;
+
 /// Configuration for the `OpenTelemetry` sink.
-#[configurable_component(sink("opentelemetry", "Deliver OTLP data over HTTP."))]
+#[configurable_component(sink("opentelemetry", "Deliver OTLP data over gRPC or HTTP."))]
 #[derive(Clone, Debug, Default)]
 pub struct OpenTelemetryConfig {
     /// Protocol configuration
@@ -27,7 +38,6 @@ pub struct OpenTelemetryConfig {
 }
 
 /// The protocol used to send data to OpenTelemetry.
-/// Currently only HTTP is supported, but we plan to support gRPC.
 /// The proto definitions are defined [here](https://github.com/vectordotdev/vector/blob/master/lib/opentelemetry-proto/src/proto/opentelemetry-proto/opentelemetry/proto/README.md).
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -35,7 +45,20 @@ pub struct OpenTelemetryConfig {
 #[configurable(metadata(docs::enum_tag_description = "The communication protocol."))]
 pub enum Protocol {
     /// Send data over HTTP.
+    ///
+    /// Events are encoded with the configured codec, and must already be shaped as OTLP data to be
+    /// accepted by OpenTelemetry collectors.
     Http(HttpSinkConfig),
+
+    /// Send log events and traces as OTLP over gRPC.
+    ///
+    /// Events are converted to OTLP log records and spans.
+    Grpc(OtlpSinkConfig),
+
+    /// Send log events and traces as OTLP over HTTP, encoded as Protobuf.
+    ///
+    /// Events are converted to OTLP log records and spans.
+    HttpProtobuf(OtlpSinkConfig),
 }
 
 impl Default for Protocol {
@@ -55,7 +78,14 @@ impl Default for Protocol {
             payload_suffix: Default::default(),
             batch: Default::default(),
             request: Default::default(),
+            retry_policy: Default::default(),
+            capture_responses: false,
+            body: None,
+            per_event: false,
+            per_endpoint_concurrency: false,
             tls: Default::default(),
+            ordering: Default::default(),
+            ordering_key_field: None,
             acknowledgements: Default::default(),
         })
     }
@@ -79,26 +109,46 @@ impl SinkConfig for OpenTelemetryConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         match &self.protocol {
             Protocol::Http(config) => config.build(cx).await,
+            Protocol::Grpc(config) => config.build(cx, OtlpTransport::Grpc),
+            Protocol::HttpProtobuf(config) => config.build(cx, OtlpTransport::HttpProtobuf),
         }
     }
 
     fn input(&self) -> Input {
         match &self.protocol {
             Protocol::Http(config) => config.input(),
+            Protocol::Grpc(config) | Protocol::HttpProtobuf(config) => config.input(),
         }
     }
 
     fn acknowledgements(&self) -> &AcknowledgementsConfig {
         match self.protocol {
             Protocol::Http(ref config) => config.acknowledgements(),
+            Protocol::Grpc(ref config) | Protocol::HttpProtobuf(ref config) => {
+                &config.acknowledgements
+            }
         }
     }
 }
 
-#[cfg(test)]
-mod test {
-    #[test]
-    fn generate_config() {
-        crate::test_util::test_generate_config::<super::OpenTelemetryConfig>();
-    }
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(super)))]
+pub enum OpenTelemetrySinkError {
+    #[snafu(display("Request failed: {}", source))]
+    GrpcRequest { source: tonic::Status },
+
+    #[snafu(display("Request failed: {}", source))]
+    HttpRequest { source: HttpError },
+
+    #[snafu(display("Request failed with status {}: {}", status, body))]
+    HttpResponse { status: StatusCode, body: String },
+
+    #[snafu(display("Failed to compress request: {}", source))]
+    Compression { source: std::io::Error },
+
+    #[snafu(display("Only gzip compression is supported for OTLP."))]
+    UnsupportedCompression,
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,153 @@
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
+use prost::Message;
+use vector_lib::opentelemetry::{
+    logs::ResourceLog,
+    proto::{
+        collector::{logs::v1::ExportLogsServiceRequest, trace::v1::ExportTraceServiceRequest},
+        common::v1::{AnyValue, KeyValue, any_value::Value as PBValue},
+        logs::v1::ResourceLogs,
+        resource::v1::Resource,
+        trace::v1::ResourceSpans,
+    },
+    spans::ResourceSpan,
+};
+
+use super::service::{OtlpPayload, OtlpRequest};
+use crate::sinks::prelude::*;
+
+/// The OTLP signal an event is sent as.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OtlpSignal {
+    Logs,
+    Traces,
+}
+
+/// Partitions events by the OTLP signal they are sent as, since each signal is exported
+/// separately.
+#[derive(Default)]
+pub struct OtlpSignalPartitioner;
+
+impl Partitioner for OtlpSignalPartitioner {
+    type Item = Event;
+    type Key = OtlpSignal;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        match item {
+            Event::Log(_) => OtlpSignal::Logs,
+            Event::Trace(_) => OtlpSignal::Traces,
+            Event::Metric(_) => panic!("unexpected metric"),
+        }
+    }
+}
+
+/// Converts batches of events into OTLP export requests.
+#[derive(Clone, Debug)]
+pub struct OtlpRequestBuilder {
+    pub resource_attributes: BTreeMap<String, Template>,
+}
+
+impl OtlpRequestBuilder {
+    pub fn build(&self, signal: OtlpSignal, events: Vec<Event>) -> OtlpRequest {
+        let mut finalizers = EventFinalizers::default();
+        let mut events_byte_size = 0;
+        let mut json_byte_size = telemetry().create_request_count_byte_size();
+        let events_count = events.len();
+
+        let events = events
+            .into_iter()
+            .map(|mut event| {
+                finalizers.merge(event.take_finalizers());
+                events_byte_size += event.size_of();
+                json_byte_size.add_event(&event, event.estimated_json_encoded_size_of());
+
+                let resource_attributes = self.render_resource_attributes(&event);
+                (event, resource_attributes)
+            })
+            .collect::<Vec<_>>();
+
+        let payload = match signal {
+            OtlpSignal::Logs => OtlpPayload::Logs(ExportLogsServiceRequest {
+                resource_logs: ResourceLogs::group(events.into_iter().map(
+                    |(event, resource_attributes)| {
+                        let mut log = ResourceLog::from(event.into_log());
+                        log.resource = merge_resource(log.resource, resource_attributes);
+                        log
+                    },
+                )),
+            }),
+            OtlpSignal::Traces => OtlpPayload::Traces(ExportTraceServiceRequest {
+                resource_spans: ResourceSpans::group(events.into_iter().map(
+                    |(event, resource_attributes)| {
+                        let mut span = ResourceSpan::from(event.into_trace());
+                        span.resource = merge_resource(span.resource, resource_attributes);
+                        span
+                    },
+                )),
+            }),
+        };
+
+        let request_size = match &payload {
+            OtlpPayload::Logs(request) => request.encoded_len(),
+            OtlpPayload::Traces(request) => request.encoded_len(),
+        };
+        let metadata = RequestMetadataBuilder::new(events_count, events_byte_size, json_byte_size)
+            .with_request_size(
+                NonZeroUsize::new(request_size).expect("payload should never be zero length"),
+            );
+
+        OtlpRequest {
+            finalizers,
+            metadata,
+            payload,
+        }
+    }
+
+    /// Renders the configured resource attributes for the event. Attributes whose template fails
+    /// to render are left out.
+    fn render_resource_attributes(&self, event: &Event) -> Vec<KeyValue> {
+        self.resource_attributes
+            .iter()
+            .filter_map(|(key, template)| {
+                let value = template
+                    .render_string(event)
+                    .map_err(|error| {
+                        emit!(TemplateRenderingError {
+                            error,
+                            field: Some(format!("resource_attributes.{key}").as_str()),
+                            drop_event: false,
+                        })
+                    })
+                    .ok()?;
+
+                Some(KeyValue {
+                    key: key.clone(),
+                    value: Some(AnyValue {
+                        value: Some(PBValue::StringValue(value)),
+                    }),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Adds the configured resource attributes to the resource of an event, overriding any resource
+/// attributes of the event with the same key.
+fn merge_resource(resource: Option<Resource>, attributes: Vec<KeyValue>) -> Option<Resource> {
+    if attributes.is_empty() {
+        return resource;
+    }
+
+    let mut resource = resource.unwrap_or_default();
+    for attribute in attributes {
+        match resource
+            .attributes
+            .iter_mut()
+            .find(|existing| existing.key == attribute.key)
+        {
+            Some(existing) => *existing = attribute,
+            None => resource.attributes.push(attribute),
+        }
+    }
+    Some(resource)
+}
@@ -0,0 +1,356 @@
+use std::{
+    io::Write,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{HeaderMap, Uri, header};
+use hyper::client::HttpConnector;
+use hyper_openssl::HttpsConnector;
+use hyper_proxy::ProxyConnector;
+use prost::Message;
+use snafu::ResultExt;
+use tonic::{body::BoxBody, metadata::MetadataMap};
+use vector_lib::opentelemetry::proto::collector::{
+    logs::v1::{
+        ExportLogsServiceRequest, ExportLogsServiceResponse, logs_service_client::LogsServiceClient,
+    },
+    trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+        trace_service_client::TraceServiceClient,
+    },
+};
+
+use super::{
+    CompressionSnafu, GrpcRequestSnafu, HttpRequestSnafu, OpenTelemetrySinkError,
+    request_builder::OtlpSignal,
+};
+use crate::{
+    http::HttpClient,
+    internal_events::EndpointBytesSent,
+    sinks::{
+        prelude::*,
+        util::{Compressor, uri},
+    },
+};
+
+const APPLICATION_X_PROTOBUF: &str = "application/x-protobuf";
+
+/// The OTLP payload of a request, holding either log records or spans.
+#[derive(Clone, Debug)]
+pub enum OtlpPayload {
+    Logs(ExportLogsServiceRequest),
+    Traces(ExportTraceServiceRequest),
+}
+
+impl OtlpPayload {
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Logs(request) => request.encoded_len(),
+            Self::Traces(request) => request.encoded_len(),
+        }
+    }
+
+    fn encode_to_vec(&self) -> Vec<u8> {
+        match self {
+            Self::Logs(request) => request.encode_to_vec(),
+            Self::Traces(request) => request.encode_to_vec(),
+        }
+    }
+
+    const fn signal(&self) -> OtlpSignal {
+        match self {
+            Self::Logs(_) => OtlpSignal::Logs,
+            Self::Traces(_) => OtlpSignal::Traces,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OtlpRequest {
+    pub finalizers: EventFinalizers,
+    pub metadata: RequestMetadata,
+    pub payload: OtlpPayload,
+}
+
+impl Finalizable for OtlpRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for OtlpRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub struct OtlpResponse {
+    events_byte_size: GroupedCountByteSize,
+}
+
+impl DriverResponse for OtlpResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        &self.events_byte_size
+    }
+}
+
+/// The transport-specific client used to export OTLP payloads.
+#[derive(Clone)]
+enum OtlpClient {
+    Grpc {
+        logs: LogsServiceClient<HyperSvc>,
+        traces: TraceServiceClient<HyperSvc>,
+        metadata: MetadataMap,
+    },
+    Http {
+        client: HttpClient,
+        logs_endpoint: Uri,
+        traces_endpoint: Uri,
+        compression: Compression,
+        headers: HeaderMap,
+    },
+}
+
+#[derive(Clone)]
+pub struct OtlpService {
+    client: OtlpClient,
+    protocol: String,
+    endpoint: String,
+}
+
+impl OtlpService {
+    pub fn grpc(
+        hyper_client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+        uri: Uri,
+        compression: bool,
+        headers: HeaderMap,
+    ) -> Self {
+        let (protocol, endpoint) = uri::protocol_endpoint(uri.clone());
+        let svc = HyperSvc {
+            uri,
+            client: hyper_client,
+        };
+        let mut logs = LogsServiceClient::new(svc.clone());
+        let mut traces = TraceServiceClient::new(svc);
+
+        if compression {
+            logs = logs.send_compressed(tonic::codec::CompressionEncoding::Gzip);
+            traces = traces.send_compressed(tonic::codec::CompressionEncoding::Gzip);
+        }
+
+        Self {
+            client: OtlpClient::Grpc {
+                logs,
+                traces,
+                metadata: MetadataMap::from_headers(headers),
+            },
+            protocol,
+            endpoint,
+        }
+    }
+
+    pub fn http(
+        client: HttpClient,
+        logs_endpoint: Uri,
+        traces_endpoint: Uri,
+        compression: Compression,
+        headers: HeaderMap,
+    ) -> Self {
+        let (protocol, endpoint) = uri::protocol_endpoint(logs_endpoint.clone());
+
+        Self {
+            client: OtlpClient::Http {
+                client,
+                logs_endpoint,
+                traces_endpoint,
+                compression,
+                headers,
+            },
+            protocol,
+            endpoint,
+        }
+    }
+
+    /// Exports the payload, returning the number of bytes sent.
+    pub(super) async fn export(
+        &mut self,
+        payload: OtlpPayload,
+    ) -> Result<usize, OpenTelemetrySinkError> {
+        match &mut self.client {
+            OtlpClient::Grpc {
+                logs,
+                traces,
+                metadata,
+            } => {
+                let byte_size = payload.encoded_len();
+                let (rejected, error_message) = match payload {
+                    OtlpPayload::Logs(request) => {
+                        let mut request = tonic::Request::new(request);
+                        *request.metadata_mut() = metadata.clone();
+                        let response = logs.export(request).await.context(GrpcRequestSnafu)?;
+                        response
+                            .into_inner()
+                            .partial_success
+                            .map(|partial| (partial.rejected_log_records, partial.error_message))
+                            .unwrap_or_default()
+                    }
+                    OtlpPayload::Traces(request) => {
+                        let mut request = tonic::Request::new(request);
+                        *request.metadata_mut() = metadata.clone();
+                        let response = traces.export(request).await.context(GrpcRequestSnafu)?;
+                        response
+                            .into_inner()
+                            .partial_success
+                            .map(|partial| (partial.rejected_spans, partial.error_message))
+                            .unwrap_or_default()
+                    }
+                };
+                warn_partial_success(rejected, &error_message);
+
+                Ok(byte_size)
+            }
+            OtlpClient::Http {
+                client,
+                logs_endpoint,
+                traces_endpoint,
+                compression,
+                headers,
+            } => {
+                let endpoint = match payload.signal() {
+                    OtlpSignal::Logs => logs_endpoint.clone(),
+                    OtlpSignal::Traces => traces_endpoint.clone(),
+                };
+
+                let body = if compression.is_compressed() {
+                    let mut compressor = Compressor::from(*compression);
+                    compressor
+                        .write_all(&payload.encode_to_vec())
+                        .context(CompressionSnafu)?;
+                    compressor.finish().context(CompressionSnafu)?.freeze()
+                } else {
+                    Bytes::from(payload.encode_to_vec())
+                };
+                let byte_size = body.len();
+
+                let mut builder = http::Request::post(endpoint)
+                    .header(header::CONTENT_TYPE, APPLICATION_X_PROTOBUF);
+                if let Some(content_encoding) = compression.content_encoding() {
+                    builder = builder.header(header::CONTENT_ENCODING, content_encoding);
+                }
+                let mut request = builder
+                    .body(hyper::Body::from(body))
+                    .expect("OTLP request should be valid");
+                request.headers_mut().extend(headers.clone());
+
+                let response = client.send(request).await.context(HttpRequestSnafu)?;
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body())
+                    .await
+                    .unwrap_or_default();
+
+                if !status.is_success() {
+                    return Err(OpenTelemetrySinkError::HttpResponse {
+                        status,
+                        body: String::from_utf8_lossy(&body).into_owned(),
+                    });
+                }
+
+                // An empty body means the payload was fully accepted.
+                let (rejected, error_message) = match payload.signal() {
+                    OtlpSignal::Logs => ExportLogsServiceResponse::decode(body)
+                        .ok()
+                        .and_then(|response| response.partial_success)
+                        .map(|partial| (partial.rejected_log_records, partial.error_message)),
+                    OtlpSignal::Traces => ExportTraceServiceResponse::decode(body)
+                        .ok()
+                        .and_then(|response| response.partial_success)
+                        .map(|partial| (partial.rejected_spans, partial.error_message)),
+                }
+                .unwrap_or_default();
+                warn_partial_success(rejected, &error_message);
+
+                Ok(byte_size)
+            }
+        }
+    }
+}
+
+/// Data rejected by the collector is not retried, as the collector won't accept it on a retry
+/// either, but is reported as it would otherwise be silently lost.
+fn warn_partial_success(rejected: i64, error_message: &str) {
+    if rejected > 0 || !error_message.is_empty() {
+        warn!(
+            message = "OTLP endpoint partially accepted the request.",
+            rejected, error_message,
+        );
+    }
+}
+
+impl Service<OtlpRequest> for OtlpService {
+    type Response = OtlpResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Emission of an internal event in case of errors is handled upstream by the caller.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Readiness of the gRPC clients is checked as part of the `export()` calls.
+        Poll::Ready(Ok(()))
+    }
+
+    // Emission of internal events for errors and dropped events is handled upstream by the caller.
+    fn call(&mut self, mut request: OtlpRequest) -> Self::Future {
+        let mut service = self.clone();
+        let metadata = std::mem::take(request.metadata_mut());
+        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
+
+        Box::pin(async move {
+            let byte_size = service.export(request.payload).await?;
+
+            emit!(EndpointBytesSent {
+                byte_size,
+                protocol: &service.protocol,
+                endpoint: &service.endpoint,
+            });
+
+            Ok(OtlpResponse { events_byte_size })
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HyperSvc {
+    uri: Uri,
+    client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+}
+
+impl Service<hyper::Request<BoxBody>> for HyperSvc {
+    type Response = hyper::Response<hyper::Body>;
+    type Error = hyper::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: hyper::Request<BoxBody>) -> Self::Future {
+        let uri = Uri::builder()
+            .scheme(self.uri.scheme().unwrap().clone())
+            .authority(self.uri.authority().unwrap().clone())
+            .path_and_query(req.uri().path_and_query().unwrap().clone())
+            .build()
+            .unwrap();
+
+        *req.uri_mut() = uri;
+
+        Box::pin(self.client.request(req))
+    }
+}
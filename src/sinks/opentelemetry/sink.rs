@@ -0,0 +1,48 @@
+use std::fmt;
+
+use super::{
+    request_builder::{OtlpRequestBuilder, OtlpSignalPartitioner},
+    service::OtlpRequest,
+};
+use crate::sinks::prelude::*;
+
+pub struct OpenTelemetrySink<S> {
+    pub batch_settings: BatcherSettings,
+    pub request_builder: OtlpRequestBuilder,
+    pub service: S,
+}
+
+impl<S> OpenTelemetrySink<S>
+where
+    S: Service<OtlpRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+        let request_builder = self.request_builder;
+
+        input
+            .batched_partitioned(OtlpSignalPartitioner, || {
+                batch_settings.as_byte_size_config()
+            })
+            .map(|(signal, events)| request_builder.build(signal, events))
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for OpenTelemetrySink<S>
+where
+    S: Service<OtlpRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
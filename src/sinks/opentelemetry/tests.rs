@@ -0,0 +1,299 @@
+use std::io::Read;
+
+use bytes::{Buf, Bytes};
+use flate2::read::MultiGzDecoder;
+use futures::{StreamExt, channel::mpsc, stream};
+use http::{StatusCode, request::Parts};
+use prost::Message;
+use vector_lib::{
+    event::{BatchNotifier, BatchStatus, Event, LogEvent, TraceEvent},
+    opentelemetry::{
+        common::to_hex,
+        proto::{
+            collector::{logs::v1::ExportLogsServiceRequest, trace::v1::ExportTraceServiceRequest},
+            common::v1::{AnyValue, any_value::Value as PBValue},
+        },
+    },
+};
+
+use super::*;
+use crate::{
+    config::{SinkConfig as _, SinkContext},
+    sinks::util::test::build_test_server_generic,
+    test_util::{
+        components::{HTTP_SINK_TAGS, run_and_assert_sink_compliance},
+        next_addr,
+    },
+};
+
+// one byte for the compression flag plus four bytes for the length
+const GRPC_HEADER_SIZE: usize = 5;
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<OpenTelemetryConfig>();
+}
+
+#[test]
+fn parse_otlp_protocols() {
+    for protocol in ["grpc", "http_protobuf"] {
+        let config: OpenTelemetryConfig = toml::from_str(&format!(
+            r#"
+            [protocol]
+            type = "{protocol}"
+            endpoint = "http://localhost:4317"
+            compression = "gzip"
+            headers.authorization = "Bearer token"
+            resource_attributes."service.name" = "{{{{ service }}}}"
+            "#
+        ))
+        .unwrap();
+
+        match config.protocol {
+            Protocol::Grpc(config) | Protocol::HttpProtobuf(config) => {
+                assert_eq!(config.resource_attributes.len(), 1);
+                assert_eq!(config.headers.len(), 1);
+            }
+            Protocol::Http(_) => panic!("unexpected protocol"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn unsupported_compression() {
+    let config: OpenTelemetryConfig = toml::from_str(indoc! {r#"
+        [protocol]
+        type = "grpc"
+        endpoint = "http://localhost:4317"
+        compression = "zstd"
+    "#})
+    .unwrap();
+
+    assert!(config.build(SinkContext::default()).await.is_err());
+}
+
+fn log_events(count: usize, batch: &BatchNotifier) -> Vec<Event> {
+    (0..count)
+        .map(|i| {
+            let mut log = LogEvent::from_str_legacy(format!("line {i}")).with_batch_notifier(batch);
+            log.insert("service", "checkout");
+            log.insert("level", "info");
+            Event::Log(log)
+        })
+        .collect()
+}
+
+fn string_value(s: &str) -> Option<AnyValue> {
+    Some(AnyValue {
+        value: Some(PBValue::StringValue(s.to_string())),
+    })
+}
+
+#[tokio::test]
+async fn http_protobuf_delivers_logs() {
+    let in_addr = next_addr();
+    let config: OpenTelemetryConfig = toml::from_str(&format!(
+        r#"
+        [protocol]
+        type = "http_protobuf"
+        endpoint = "http://{in_addr}"
+        compression = "gzip"
+        headers.authorization = "Bearer token"
+        resource_attributes."service.name" = "{{{{ service }}}}"
+        resource_attributes."deployment.environment" = "test"
+        resource_attributes."host.name" = "{{{{ host }}}}"
+        "#
+    ))
+    .unwrap();
+
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server_generic(in_addr, || {
+        hyper::Response::builder()
+            .header("content-type", "application/x-protobuf")
+            .body(hyper::Body::empty())
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = log_events(10, &batch);
+    drop(batch);
+
+    run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+    drop(trigger);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = get_received(rx, |parts| {
+        assert_eq!(parts.method, http::Method::POST);
+        assert_eq!(parts.uri.path(), "/v1/logs");
+        assert_eq!(parts.headers["content-type"], "application/x-protobuf");
+        assert_eq!(parts.headers["content-encoding"], "gzip");
+        assert_eq!(parts.headers["authorization"], "Bearer token");
+    })
+    .await;
+
+    let mut bodies = Vec::new();
+    for body in requests {
+        let mut decompressed = Vec::new();
+        MultiGzDecoder::new(body.reader())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        bodies.push(ExportLogsServiceRequest::decode(decompressed.as_slice()).unwrap());
+    }
+
+    let resource_logs = bodies
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .collect::<Vec<_>>();
+    assert_eq!(resource_logs.len(), 1);
+
+    // The `host.name` attribute is left out, as the events don't have a `host` field.
+    let resource = resource_logs[0].resource.as_ref().unwrap();
+    assert_eq!(resource.attributes.len(), 2);
+    assert_eq!(resource.attributes[0].key, "deployment.environment");
+    assert_eq!(resource.attributes[0].value, string_value("test"));
+    assert_eq!(resource.attributes[1].key, "service.name");
+    assert_eq!(resource.attributes[1].value, string_value("checkout"));
+
+    let records = resource_logs[0]
+        .scope_logs
+        .iter()
+        .flat_map(|scope_logs| &scope_logs.log_records)
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 10);
+    for (i, record) in records.into_iter().enumerate() {
+        assert_eq!(record.body, string_value(&format!("line {i}")));
+        assert!(record.time_unix_nano > 0);
+        assert!(
+            record
+                .attributes
+                .iter()
+                .any(|attribute| attribute.key == "level")
+        );
+    }
+}
+
+#[tokio::test]
+async fn grpc_delivers_logs_and_traces() {
+    let in_addr = next_addr();
+    let config: OpenTelemetryConfig = toml::from_str(&format!(
+        r#"
+        [protocol]
+        type = "grpc"
+        endpoint = "http://{in_addr}"
+        "#
+    ))
+    .unwrap();
+
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (rx, trigger, server) = build_test_server_generic(in_addr, || {
+        hyper::Response::builder()
+            .header("grpc-status", "0") // OK
+            .header("content-type", "application/grpc")
+            .body(hyper::Body::from(Bytes::from_static(
+                &[0; GRPC_HEADER_SIZE],
+            )))
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let mut events = log_events(3, &batch);
+    let mut trace = TraceEvent::default().with_batch_notifier(&batch);
+    trace.insert("trace_id", "4ac52aadf321c2e531db005df08792f5");
+    trace.insert("span_id", "0b9e4bda2a55530d");
+    trace.insert("name", "checkout");
+    events.push(Event::Trace(trace));
+    drop(batch);
+
+    sink.run(stream::iter(events).boxed())
+        .await
+        .expect("Running sink failed");
+    drop(trigger);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let mut logs = 0;
+    let mut spans = Vec::new();
+    let requests = rx.collect::<Vec<_>>().await;
+    for (parts, body) in requests {
+        let body = body.slice(GRPC_HEADER_SIZE..);
+        match parts.uri.path() {
+            "/opentelemetry.proto.collector.logs.v1.LogsService/Export" => {
+                let request = ExportLogsServiceRequest::decode(body).unwrap();
+                logs += request
+                    .resource_logs
+                    .iter()
+                    .flat_map(|resource_logs| &resource_logs.scope_logs)
+                    .map(|scope_logs| scope_logs.log_records.len())
+                    .sum::<usize>();
+            }
+            "/opentelemetry.proto.collector.trace.v1.TraceService/Export" => {
+                let request = ExportTraceServiceRequest::decode(body).unwrap();
+                spans.extend(
+                    request
+                        .resource_spans
+                        .into_iter()
+                        .flat_map(|resource_spans| resource_spans.scope_spans)
+                        .flat_map(|scope_spans| scope_spans.spans),
+                );
+            }
+            path => panic!("unexpected path: {path}"),
+        }
+    }
+
+    assert_eq!(logs, 3);
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].name, "checkout");
+    assert_eq!(
+        to_hex(&spans[0].trace_id),
+        "4ac52aadf321c2e531db005df08792f5"
+    );
+    assert_eq!(to_hex(&spans[0].span_id), "0b9e4bda2a55530d");
+}
+
+#[tokio::test]
+async fn http_protobuf_rejects_on_client_error() {
+    let in_addr = next_addr();
+    let config: OpenTelemetryConfig = toml::from_str(&format!(
+        r#"
+        [protocol]
+        type = "http_protobuf"
+        endpoint = "http://{in_addr}"
+        "#
+    ))
+    .unwrap();
+
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+    let (_rx, trigger, server) = build_test_server_generic(in_addr, || {
+        hyper::Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(hyper::Body::empty())
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = log_events(3, &batch);
+    drop(batch);
+
+    sink.run(stream::iter(events).boxed())
+        .await
+        .expect("Running sink failed");
+    drop(trigger);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+async fn get_received(
+    rx: mpsc::Receiver<(Parts, Bytes)>,
+    assert_parts: impl Fn(Parts),
+) -> Vec<Bytes> {
+    rx.map(|(parts, body)| {
+        assert_parts(parts);
+        body
+    })
+    .collect()
+    .await
+}
@@ -0,0 +1,397 @@
+//! Mapping of the fields of the events to the columns of the table.
+
+use std::collections::BTreeMap;
+
+use bytes::{BufMut, BytesMut};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use snafu::Snafu;
+use vector_lib::{
+    configurable::configurable_component,
+    lookup::{OwnedValuePath, lookup_v2::ConfigValuePath, owned_value_path},
+};
+use vrl::value::Value;
+
+/// The header of the binary `COPY` format: the signature, the flags, and the length of the
+/// header extension.
+const COPY_HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+/// The trailer of the binary `COPY` format.
+const COPY_TRAILER: i16 = -1;
+
+/// The number of days between the Unix epoch and the PostgreSQL epoch, 2000-01-01.
+const POSTGRES_EPOCH_DAYS: i64 = 10_957;
+
+/// The type of a column.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    /// `text`, or any other character type.
+    ///
+    /// The values that aren't strings are written as JSON.
+    Text,
+
+    /// `boolean`.
+    Boolean,
+
+    /// `smallint`.
+    Smallint,
+
+    /// `integer`.
+    Integer,
+
+    /// `bigint`.
+    Bigint,
+
+    /// `real`.
+    Real,
+
+    /// `double precision`.
+    DoublePrecision,
+
+    /// `timestamp with time zone`.
+    Timestamptz,
+
+    /// `timestamp without time zone`, in UTC.
+    Timestamp,
+
+    /// `date`.
+    Date,
+
+    /// `json`.
+    Json,
+
+    /// `jsonb`.
+    Jsonb,
+
+    /// `uuid`.
+    Uuid,
+
+    /// `bytea`.
+    Bytea,
+}
+
+impl ColumnType {
+    const fn sql_name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Boolean => "boolean",
+            Self::Smallint => "smallint",
+            Self::Integer => "integer",
+            Self::Bigint => "bigint",
+            Self::Real => "real",
+            Self::DoublePrecision => "double precision",
+            Self::Timestamptz => "timestamptz",
+            Self::Timestamp => "timestamp",
+            Self::Date => "date",
+            Self::Json => "json",
+            Self::Jsonb => "jsonb",
+            Self::Uuid => "uuid",
+            Self::Bytea => "bytea",
+        }
+    }
+}
+
+/// A column of the table.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// The field of the events written to the column.
+    ///
+    /// Defaults to the top-level field named after the column.
+    #[configurable(metadata(docs::examples = "message"))]
+    #[configurable(metadata(docs::examples = "http.status_code"))]
+    pub field: Option<ConfigValuePath>,
+
+    /// The type of the column.
+    pub r#type: ColumnType,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ColumnError {
+    #[snafu(display("Invalid {} value for the {} column `{}`.", kind, r#type, column))]
+    InvalidValue {
+        column: String,
+        r#type: &'static str,
+        kind: &'static str,
+    },
+}
+
+struct Column {
+    name: String,
+    field: OwnedValuePath,
+    r#type: ColumnType,
+}
+
+/// The columns the events are written to.
+pub(super) struct Columns {
+    columns: Vec<Column>,
+}
+
+impl Columns {
+    pub(super) fn new(columns: &BTreeMap<String, ColumnConfig>) -> Self {
+        let columns = columns
+            .iter()
+            .map(|(name, config)| Column {
+                name: name.clone(),
+                field: config
+                    .field
+                    .as_ref()
+                    .map_or_else(|| owned_value_path!(name.as_str()), |field| field.0.clone()),
+                r#type: config.r#type,
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// The quoted names of the columns, separated by commas.
+    pub(super) fn names(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The definition of the rows of `jsonb_to_recordset`.
+    pub(super) fn record_definition(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{} {}",
+                    quote_identifier(&column.name),
+                    column.r#type.sql_name()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The row of an event, as a JSON object keyed by the names of the columns.
+    pub(super) fn json_row(&self, event: &Value) -> serde_json::Value {
+        let row = self
+            .columns
+            .iter()
+            .map(|column| {
+                let value = event
+                    .get(&column.field)
+                    .and_then(|value| serde_json::to_value(value).ok())
+                    .unwrap_or_default();
+                (column.name.clone(), value)
+            })
+            .collect();
+        serde_json::Value::Object(row)
+    }
+
+    /// Writes the rows of the events in the binary `COPY` format.
+    pub(super) fn copy_rows<'a>(
+        &self,
+        events: impl IntoIterator<Item = &'a Value>,
+    ) -> Result<BytesMut, ColumnError> {
+        let mut buffer = BytesMut::new();
+        buffer.put_slice(COPY_HEADER);
+        for event in events {
+            buffer.put_i16(self.columns.len() as i16);
+            for column in &self.columns {
+                match event.get(&column.field) {
+                    None | Some(Value::Null) => buffer.put_i32(-1),
+                    Some(value) => {
+                        let start = buffer.len();
+                        buffer.put_i32(0);
+                        column.write(value, &mut buffer)?;
+                        let length = (buffer.len() - start - 4) as i32;
+                        buffer[start..start + 4].copy_from_slice(&length.to_be_bytes());
+                    }
+                }
+            }
+        }
+        buffer.put_i16(COPY_TRAILER);
+        Ok(buffer)
+    }
+}
+
+impl Column {
+    fn write(&self, value: &Value, buffer: &mut BytesMut) -> Result<(), ColumnError> {
+        match (self.r#type, value) {
+            (ColumnType::Text, Value::Bytes(bytes)) | (ColumnType::Bytea, Value::Bytes(bytes)) => {
+                buffer.put_slice(bytes)
+            }
+            (ColumnType::Text, value) => write_json(value, buffer),
+            (ColumnType::Json, value) => write_json(value, buffer),
+            (ColumnType::Jsonb, value) => {
+                // The version of the binary format of `jsonb`.
+                buffer.put_u8(1);
+                write_json(value, buffer);
+            }
+            (ColumnType::Boolean, Value::Boolean(boolean)) => buffer.put_u8(u8::from(*boolean)),
+            (ColumnType::Smallint, value) => buffer.put_i16(self.integer(value)?),
+            (ColumnType::Integer, value) => buffer.put_i32(self.integer(value)?),
+            (ColumnType::Bigint, value) => buffer.put_i64(self.integer(value)?),
+            (ColumnType::Real, value) => buffer.put_f32(self.float(value)? as f32),
+            (ColumnType::DoublePrecision, value) => buffer.put_f64(self.float(value)?),
+            (ColumnType::Timestamptz | ColumnType::Timestamp, value) => {
+                let timestamp = self.timestamp(value)?;
+                let micros =
+                    timestamp.timestamp_micros() - POSTGRES_EPOCH_DAYS * 24 * 60 * 60 * 1_000_000;
+                buffer.put_i64(micros);
+            }
+            (ColumnType::Date, value) => {
+                let date = match value {
+                    Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                        .ok()
+                        .and_then(|date| date.parse::<NaiveDate>().ok()),
+                    _ => None,
+                }
+                .or_else(|| {
+                    self.timestamp(value)
+                        .ok()
+                        .map(|timestamp| timestamp.date_naive())
+                })
+                .ok_or_else(|| self.invalid(value))?;
+                let days = date.signed_duration_since(NaiveDate::default()).num_days()
+                    - POSTGRES_EPOCH_DAYS;
+                buffer.put_i32(days as i32);
+            }
+            (ColumnType::Uuid, Value::Bytes(bytes)) => {
+                let uuid = std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|uuid| uuid::Uuid::parse_str(uuid).ok())
+                    .ok_or_else(|| self.invalid(value))?;
+                buffer.put_slice(uuid.as_bytes());
+            }
+            (ColumnType::Boolean | ColumnType::Uuid | ColumnType::Bytea, value) => {
+                return Err(self.invalid(value));
+            }
+        }
+        Ok(())
+    }
+
+    fn integer<T: TryFrom<i64>>(&self, value: &Value) -> Result<T, ColumnError> {
+        match value {
+            Value::Integer(integer) => Some(*integer),
+            Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|integer| integer.parse().ok()),
+            _ => None,
+        }
+        .and_then(|integer| T::try_from(integer).ok())
+        .ok_or_else(|| self.invalid(value))
+    }
+
+    fn float(&self, value: &Value) -> Result<f64, ColumnError> {
+        match value {
+            Value::Float(float) => Some(float.into_inner()),
+            Value::Integer(integer) => Some(*integer as f64),
+            Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|float| float.parse().ok()),
+            _ => None,
+        }
+        .ok_or_else(|| self.invalid(value))
+    }
+
+    fn timestamp(&self, value: &Value) -> Result<DateTime<Utc>, ColumnError> {
+        match value {
+            Value::Timestamp(timestamp) => Some(*timestamp),
+            Value::Bytes(bytes) => std::str::from_utf8(bytes).ok().and_then(|timestamp| {
+                DateTime::parse_from_rfc3339(timestamp)
+                    .map(|timestamp| timestamp.with_timezone(&Utc))
+                    .ok()
+                    .or_else(|| {
+                        timestamp
+                            .parse::<NaiveDateTime>()
+                            .ok()
+                            .map(|timestamp| timestamp.and_utc())
+                    })
+            }),
+            _ => None,
+        }
+        .ok_or_else(|| self.invalid(value))
+    }
+
+    fn invalid(&self, value: &Value) -> ColumnError {
+        ColumnError::InvalidValue {
+            column: self.name.clone(),
+            r#type: self.r#type.sql_name(),
+            kind: value.kind_str(),
+        }
+    }
+}
+
+fn write_json(value: &Value, buffer: &mut BytesMut) {
+    serde_json::to_writer(buffer.writer(), value).expect("values are serializable");
+}
+
+/// Quotes an identifier, so that it can't be mistaken for SQL.
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::{btreemap, value};
+
+    use super::*;
+
+    fn columns() -> Columns {
+        Columns::new(&btreemap! {
+            "id" => ColumnConfig { field: None, r#type: ColumnType::Bigint },
+            "message" => ColumnConfig { field: None, r#type: ColumnType::Text },
+            "status" => ColumnConfig {
+                field: Some(ConfigValuePath::try_from("http.status".to_owned()).unwrap()),
+                r#type: ColumnType::Smallint,
+            },
+            "timestamp" => ColumnConfig { field: None, r#type: ColumnType::Timestamptz },
+        })
+    }
+
+    #[test]
+    fn maps_json_rows() {
+        let columns = columns();
+        assert_eq!(columns.names(), r#""id", "message", "status", "timestamp""#);
+        assert_eq!(
+            columns.record_definition(),
+            r#""id" bigint, "message" text, "status" smallint, "timestamp" timestamptz"#
+        );
+        assert_eq!(
+            columns.json_row(&value!({"id": 1, "http": {"status": 200}, "extra": true})),
+            serde_json::json!({"id": 1, "message": null, "status": 200, "timestamp": null})
+        );
+    }
+
+    #[test]
+    fn writes_copy_rows() {
+        let rows = columns()
+            .copy_rows([&value!({
+                "id": 1,
+                "message": "hello",
+                "http": {"status": "200"},
+                "timestamp": "2000-01-01T00:00:01Z",
+            })])
+            .unwrap();
+
+        let mut expected = COPY_HEADER.to_vec();
+        expected.extend(4i16.to_be_bytes());
+        expected.extend(8i32.to_be_bytes());
+        expected.extend(1i64.to_be_bytes());
+        expected.extend(5i32.to_be_bytes());
+        expected.extend(b"hello");
+        expected.extend(2i32.to_be_bytes());
+        expected.extend(200i16.to_be_bytes());
+        expected.extend(8i32.to_be_bytes());
+        expected.extend(1_000_000i64.to_be_bytes());
+        expected.extend((-1i16).to_be_bytes());
+        assert_eq!(rows.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let error = columns().copy_rows([&value!({"id": "one"})]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid string value for the bigint column `id`."
+        );
+    }
+}
@@ -15,6 +15,7 @@ use crate::{
     config::{SinkConfig, SinkContext},
     event::{ObjectMap, TraceEvent, Value},
     sinks::{postgres::PostgresConfig, util::test::load_sink},
+    template::Template,
     test_util::{
         components::{
             COMPONENT_ERROR_TAGS, run_and_assert_sink_compliance, run_and_assert_sink_error,
@@ -148,6 +149,10 @@ struct TestTraceSpan {
 }
 
 async fn prepare_config() -> (PostgresConfig, String, PgConnection) {
+    prepare_config_with("").await
+}
+
+async fn prepare_config_with(extra_config: &str) -> (PostgresConfig, String, PgConnection) {
     let table = random_table_name();
     let endpoint = pg_url();
     let config_str = format!(
@@ -155,6 +160,7 @@ async fn prepare_config() -> (PostgresConfig, String, PgConnection) {
             endpoint = "{endpoint}"
             table = "{table}"
             batch.max_events = 1
+            {extra_config}
         "#,
     );
     let (config, _) = load_sink::<PostgresConfig>(&config_str).unwrap();
@@ -294,6 +300,125 @@ async fn insert_multiple_events() {
     assert_eq!(expected_values, actual_values);
 }
 
+#[tokio::test]
+async fn copy_multiple_events() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config_with(
+        r#"
+            mode = "copy"
+            columns.id.type = "bigint"
+            columns.host.type = "text"
+            columns.timestamp.type = "timestamptz"
+            columns.message.type = "text"
+            columns.payload.type = "jsonb"
+        "#,
+    )
+    .await;
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let create_table_sql = format!(
+        "CREATE TABLE {table} (id BIGINT, host TEXT, timestamp TIMESTAMPTZ, message TEXT, payload JSONB)"
+    );
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let (input_events, mut receiver) = create_events(100);
+    let input_log_events = input_events
+        .clone()
+        .into_iter()
+        .map(Event::into_log)
+        .collect::<Vec<_>>();
+    let expected_values = input_log_events
+        .iter()
+        .map(|event| serde_json::to_value(event).unwrap())
+        .collect::<Vec<_>>();
+    run_and_assert_sink_compliance(sink, stream::iter(input_events), &POSTGRES_SINK_TAGS).await;
+    // We drop the event to notify the receiver that the batch was delivered.
+    std::mem::drop(input_log_events);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let select_all_sql = format!("SELECT * FROM {table} ORDER BY id");
+    let actual_events: Vec<TestEvent> = sqlx::query_as(&select_all_sql)
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    let actual_values = actual_events
+        .iter()
+        .map(|event| serde_json::to_value(event).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(expected_values, actual_values);
+}
+
+#[tokio::test]
+async fn copy_fails_invalid_value() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config_with(
+        r#"
+            mode = "copy"
+            columns.host.type = "bigint"
+        "#,
+    )
+    .await;
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let create_table_sql = format!("CREATE TABLE {table} (host BIGINT)");
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let (input_event, mut receiver) = create_event_with_notifier(0);
+    run_and_assert_sink_error(
+        sink,
+        stream::once(ready(input_event)),
+        &COMPONENT_ERROR_TAGS,
+    )
+    .await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn insert_into_templated_tables() {
+    trace_init();
+
+    let (mut config, table, mut connection) = prepare_config_with(
+        r#"
+            columns.id.type = "bigint"
+            columns.message.type = "text"
+        "#,
+    )
+    .await;
+    config.table = Template::try_from(format!("{table}_{{{{ partition }}}}").as_str()).unwrap();
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    for partition in ["even", "odd"] {
+        let create_table_sql =
+            format!("CREATE TABLE {table}_{partition} (id BIGINT, message TEXT)");
+        sqlx::query(&create_table_sql)
+            .execute(&mut connection)
+            .await
+            .unwrap();
+    }
+
+    let (mut input_events, mut receiver) = create_events(10);
+    for (id, event) in input_events.iter_mut().enumerate() {
+        let partition = if id % 2 == 0 { "even" } else { "odd" };
+        event.as_mut_log().insert("partition", partition);
+    }
+    run_and_assert_sink_compliance(sink, stream::iter(input_events), &POSTGRES_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    for (partition, expected_ids) in [("even", [0, 2, 4, 6, 8]), ("odd", [1, 3, 5, 7, 9])] {
+        let select_ids_sql = format!("SELECT id FROM {table}_{partition} ORDER BY id");
+        let ids: Vec<i64> = sqlx::query_scalar(&select_ids_sql)
+            .fetch_all(&mut connection)
+            .await
+            .unwrap();
+        assert_eq!(ids, expected_ids);
+    }
+}
+
 #[tokio::test]
 async fn insert_metric() {
     trace_init();
@@ -476,7 +601,7 @@ async fn insertion_fails_missing_table() {
 
     let table = "missing_table".to_string();
     let (mut config, _, _) = prepare_config().await;
-    config.table = table.clone();
+    config.table = Template::try_from(table.as_str()).unwrap();
 
     let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
     let (input_event, mut receiver) = create_event_with_notifier(0);
@@ -1,3 +1,4 @@
+mod columns;
 mod config;
 #[cfg(all(test, feature = "postgres_sink-integration-tests"))]
 mod integration_tests;
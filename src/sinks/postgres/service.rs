@@ -1,5 +1,6 @@
 use std::{
     num::NonZeroUsize,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -14,7 +15,12 @@ use vector_lib::{
     request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata},
     stream::DriverResponse,
 };
+use vrl::value::Value;
 
+use super::{
+    columns::{ColumnError, Columns},
+    config::PostgresMode,
+};
 use crate::{
     internal_events::EndpointBytesSent,
     sinks::prelude::{RequestMetadataBuilder, RetryLogic},
@@ -38,47 +44,78 @@ impl RetryLogic for PostgresRetryLogic {
             return false;
         };
 
-        matches!(
-            postgres_error,
-            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut
-        )
+        match postgres_error {
+            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+            sqlx::Error::Database(error) => {
+                error.code().is_some_and(|code| is_retriable_code(&code))
+            }
+            _ => false,
+        }
     }
 }
 
+/// Whether the errors with the given [SQLSTATE code][codes] are transient.
+///
+/// Serialization failures and deadlocks, lost connections, lack of resources and restarting
+/// servers are retried, while the other errors, such as constraint violations, fail the same way
+/// again.
+///
+/// [codes]: https://www.postgresql.org/docs/current/errcodes-appendix.html
+fn is_retriable_code(code: &str) -> bool {
+    matches!(
+        code.get(..2),
+        // Transaction Rollback, Connection Exception and Insufficient Resources.
+        Some("40" | "08" | "53")
+    ) || matches!(
+        code,
+        // `admin_shutdown`, `crash_shutdown` and `cannot_connect_now`.
+        "57P01" | "57P02" | "57P03"
+    )
+}
+
 #[derive(Clone)]
 pub struct PostgresService {
     connection_pool: Pool<Postgres>,
-    table: String,
     endpoint: String,
+    mode: PostgresMode,
+    columns: Option<Arc<Columns>>,
 }
 
 impl PostgresService {
-    pub const fn new(connection_pool: Pool<Postgres>, table: String, endpoint: String) -> Self {
+    pub(super) const fn new(
+        connection_pool: Pool<Postgres>,
+        endpoint: String,
+        mode: PostgresMode,
+        columns: Option<Arc<Columns>>,
+    ) -> Self {
         Self {
             connection_pool,
-            table,
             endpoint,
+            mode,
+            columns,
         }
     }
 }
 
 #[derive(Clone)]
 pub struct PostgresRequest {
+    pub table: String,
     pub events: Vec<Event>,
     pub finalizers: EventFinalizers,
     pub metadata: RequestMetadata,
 }
 
-impl TryFrom<Vec<Event>> for PostgresRequest {
+impl TryFrom<(String, Vec<Event>)> for PostgresRequest {
     type Error = String;
 
-    fn try_from(mut events: Vec<Event>) -> Result<Self, Self::Error> {
+    fn try_from((table, mut events): (String, Vec<Event>)) -> Result<Self, Self::Error> {
         let finalizers = events.take_finalizers();
         let metadata_builder = RequestMetadataBuilder::from_events(&events);
         let events_size = NonZeroUsize::new(events.estimated_json_encoded_size_of().get())
             .ok_or("payload should never be zero length")?;
         let metadata = metadata_builder.with_request_size(events_size);
         Ok(PostgresRequest {
+            table,
             events,
             finalizers,
             metadata,
@@ -127,6 +164,9 @@ pub enum PostgresServiceError {
 
     #[snafu(display("Serialization error: {source}"))]
     VectorCommon { source: vector_common::Error },
+
+    #[snafu(display("Invalid row: {source}"))]
+    Row { source: ColumnError },
 }
 
 impl Service<PostgresRequest> for PostgresService {
@@ -141,7 +181,7 @@ impl Service<PostgresRequest> for PostgresService {
     fn call(&mut self, request: PostgresRequest) -> Self::Future {
         let service = self.clone();
         let future = async move {
-            let table = service.table;
+            let table = request.table;
             let metadata = request.metadata;
             let json_serializer = JsonSerializerConfig::default().build();
             let serialized_values = request
@@ -151,13 +191,57 @@ impl Service<PostgresRequest> for PostgresService {
                 .collect::<Result<Vec<_>, _>>()
                 .context(VectorCommonSnafu)?;
 
-            sqlx::query(&format!(
-                "INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1)"
-            ))
-            .bind(Json(serialized_values))
-            .execute(&service.connection_pool)
-            .await
-            .context(PostgresSnafu)?;
+            match (service.mode, &service.columns) {
+                (PostgresMode::Insert, None) => {
+                    sqlx::query(&format!(
+                        "INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1)"
+                    ))
+                    .bind(Json(serialized_values))
+                    .execute(&service.connection_pool)
+                    .await
+                    .context(PostgresSnafu)?;
+                }
+                (PostgresMode::Insert, Some(columns)) => {
+                    let rows = serialized_values
+                        .into_iter()
+                        .map(|value| columns.json_row(&Value::from(value)))
+                        .collect::<Vec<_>>();
+                    let names = columns.names();
+                    sqlx::query(&format!(
+                        "INSERT INTO {table} ({names}) SELECT {names} FROM jsonb_to_recordset($1) AS x({})",
+                        columns.record_definition()
+                    ))
+                    .bind(Json(rows))
+                    .execute(&service.connection_pool)
+                    .await
+                    .context(PostgresSnafu)?;
+                }
+                (PostgresMode::Copy, columns) => {
+                    let columns = columns
+                        .as_ref()
+                        .expect("columns are required by the copy mode");
+                    let events = serialized_values
+                        .into_iter()
+                        .map(Value::from)
+                        .collect::<Vec<_>>();
+                    let rows = columns.copy_rows(&events).context(RowSnafu)?;
+
+                    let mut copy = service
+                        .connection_pool
+                        .copy_in_raw(&format!(
+                            "COPY {table} ({}) FROM STDIN (FORMAT binary)",
+                            columns.names()
+                        ))
+                        .await
+                        .context(PostgresSnafu)?;
+                    if let Err(error) = copy.send(rows).await {
+                        // Aborting leaves the connection usable for the next requests.
+                        _ = copy.abort(error.to_string()).await;
+                        return Err(error).context(PostgresSnafu);
+                    }
+                    copy.finish().await.context(PostgresSnafu)?;
+                }
+            }
 
             emit!(EndpointBytesSent {
                 byte_size: metadata.request_encoded_size(),
@@ -171,3 +255,26 @@ impl Service<PostgresRequest> for PostgresService {
         Box::pin(future)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_retriable_codes() {
+        // `serialization_failure` and `deadlock_detected`.
+        assert!(is_retriable_code("40001"));
+        assert!(is_retriable_code("40P01"));
+        // `connection_failure` and `too_many_connections`.
+        assert!(is_retriable_code("08006"));
+        assert!(is_retriable_code("53300"));
+        assert!(is_retriable_code("57P01"));
+
+        // `unique_violation`, `not_null_violation` and `undefined_table`.
+        assert!(!is_retriable_code("23505"));
+        assert!(!is_retriable_code("23502"));
+        assert!(!is_retriable_code("42P01"));
+        // `query_canceled`.
+        assert!(!is_retriable_code("57014"));
+    }
+}
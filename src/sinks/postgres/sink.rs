@@ -4,24 +4,31 @@ use crate::sinks::prelude::*;
 pub struct PostgresSink {
     service: Svc<PostgresService, PostgresRetryLogic>,
     batch_settings: BatcherSettings,
+    table: Template,
 }
 
 impl PostgresSink {
     pub const fn new(
         service: Svc<PostgresService, PostgresRetryLogic>,
         batch_settings: BatcherSettings,
+        table: Template,
     ) -> Self {
         Self {
             service,
             batch_settings,
+            table,
         }
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+
         input
-            .batched(self.batch_settings.as_byte_size_config())
-            .filter_map(|events| async move {
-                match PostgresRequest::try_from(events) {
+            .batched_partitioned(TablePartitioner { table: self.table }, || {
+                batch_settings.as_byte_size_config()
+            })
+            .filter_map(|(table, events)| async move {
+                match PostgresRequest::try_from((table?, events)) {
                     Ok(request) => Some(request),
                     Err(e) => {
                         warn!(
@@ -44,3 +51,26 @@ impl StreamSink<Event> for PostgresSink {
         self.run_inner(input).await
     }
 }
+
+/// Partitions the events by the table they are written to.
+struct TablePartitioner {
+    table: Template,
+}
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.table
+            .render_string(item)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
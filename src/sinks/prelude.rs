@@ -10,7 +10,7 @@ pub use vector_lib::{
     config::{AcknowledgementsConfig, Input, telemetry},
     configurable::configurable_component,
     event::Value,
-    finalization::{EventFinalizers, EventStatus, Finalizable},
+    finalization::{DeliveryFailure, EventFinalizers, EventStatus, Finalizable},
     internal_event::{CountByteSize, TaggedEventsSent},
     json_size::JsonSize,
     partition::Partitioner,
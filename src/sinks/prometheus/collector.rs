@@ -2,11 +2,16 @@ use std::{collections::BTreeMap, fmt::Write as _};
 
 use chrono::Utc;
 use indexmap::map::IndexMap;
+use prost::Message;
 use vector_lib::{
-    event::metric::{MetricSketch, MetricTags, Quantile, samples_to_buckets},
-    prometheus::parser::{METRIC_NAME_LABEL, proto},
+    event::metric::{Bucket, MetricSketch, MetricTags, Quantile, samples_to_buckets},
+    prometheus::parser::{METRIC_NAME_LABEL, client_proto, proto},
 };
 
+use super::{
+    native_histogram::NativeHistogram,
+    remote_write::metadata::{received_exemplars, received_metadata},
+};
 use crate::{
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
     sinks::util::{encode_namespace, statistic::DistributionStatistic},
@@ -29,6 +34,20 @@ pub(super) trait MetricCollector {
         extra: Option<(&str, String)>,
     );
 
+    /// Whether the sketches are emitted as native histograms, rather than as summaries.
+    fn native_histograms(&self) -> bool {
+        false
+    }
+
+    fn emit_native_histogram(
+        &mut self,
+        _timestamp_millis: Option<i64>,
+        _name: &str,
+        _histogram: NativeHistogram,
+        _tags: Option<&MetricTags>,
+    ) {
+    }
+
     fn finish(self) -> Self::Output;
 
     fn encode_metric(
@@ -182,6 +201,10 @@ pub(super) trait MetricCollector {
                     self.emit_value(timestamp, name, "_count", *count as f64, tags, None);
                 }
                 MetricValue::Sketch { sketch } => match sketch {
+                    MetricSketch::AgentDDSketch(ddsketch) if self.native_histograms() => {
+                        let histogram = NativeHistogram::from_ddsketch(ddsketch);
+                        self.emit_native_histogram(timestamp, name, histogram, tags);
+                    }
                     MetricSketch::AgentDDSketch(ddsketch) => {
                         for q in quantiles {
                             let quantile = Quantile {
@@ -316,9 +339,10 @@ impl StringCollector {
 type Labels = Vec<proto::Label>;
 
 pub(super) struct TimeSeries {
-    buffer: IndexMap<Labels, Vec<proto::Sample>>,
+    buffer: IndexMap<Labels, proto::TimeSeries>,
     metadata: IndexMap<String, proto::MetricMetadata>,
     timestamp: Option<i64>,
+    native_histograms: bool,
 }
 
 impl TimeSeries {
@@ -353,6 +377,97 @@ impl TimeSeries {
             .timestamp
             .get_or_insert_with(|| Utc::now().timestamp_millis())
     }
+
+    /// Sends the sketches as native histograms, rather than as summaries.
+    pub(super) const fn with_native_histograms(mut self, native_histograms: bool) -> Self {
+        self.native_histograms = native_histograms;
+        self
+    }
+
+    /// Encodes the metric along with the metadata of its family and the exemplars of its series
+    /// received by the `prometheus_remote_write` source.
+    pub(super) fn encode_metric_with_metadata(
+        &mut self,
+        default_namespace: Option<&str>,
+        buckets: &[f64],
+        quantiles: &[f64],
+        metric: &Metric,
+    ) {
+        self.encode_metric(default_namespace, buckets, quantiles, metric);
+        if metric.kind() != MetricKind::Absolute {
+            return;
+        }
+
+        if let Some(received) = received_metadata(metric)
+            && let Some(metadata) = self.metadata.get_mut(metric.name())
+        {
+            // The type is only kept if the metric is still encoded as one of that type.
+            if let (Ok(received_type), Ok(encoded_type)) = (
+                proto::MetricType::try_from(received.r#type),
+                proto::MetricType::try_from(metadata.r#type),
+            ) && is_encoded_as(received_type, encoded_type)
+            {
+                metadata.r#type = received.r#type;
+            }
+            if !received.help.is_empty() {
+                metadata.help = received.help;
+            }
+            metadata.unit = received.unit;
+        }
+
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let tags = metric.tags();
+        for exemplar in received_exemplars(metric) {
+            let labels = match metric.value() {
+                MetricValue::Counter { .. }
+                | MetricValue::Gauge { .. }
+                | MetricValue::Set { .. } => Self::make_labels(tags, &name, "", None),
+                MetricValue::Sketch { .. } if self.native_histograms => {
+                    Self::make_labels(tags, &name, "", None)
+                }
+                MetricValue::AggregatedHistogram { buckets, .. } => {
+                    let le = bucket_label(buckets.iter().map(|b| b.upper_limit), exemplar.value);
+                    Self::make_labels(tags, &name, "_bucket", Some(("le", le)))
+                }
+                MetricValue::Distribution {
+                    statistic: StatisticKind::Histogram,
+                    ..
+                } => {
+                    let le = bucket_label(buckets.iter().copied(), exemplar.value);
+                    Self::make_labels(tags, &name, "_bucket", Some(("le", le)))
+                }
+                // The summaries have no exemplars.
+                _ => continue,
+            };
+            if let Some(series) = self.buffer.get_mut(&labels) {
+                series.exemplars.push(exemplar);
+            }
+        }
+    }
+}
+
+/// Whether the metrics of a type received by the source are still encoded as the given type, the
+/// gauge histograms being encoded as histograms, and the info, state set and untyped metrics as
+/// gauges.
+fn is_encoded_as(received: proto::MetricType, encoded: proto::MetricType) -> bool {
+    use proto::MetricType;
+    received == encoded
+        || matches!(
+            (received, encoded),
+            (MetricType::Gaugehistogram, MetricType::Histogram)
+                | (
+                    MetricType::Info | MetricType::Stateset | MetricType::Unknown,
+                    MetricType::Gauge
+                )
+        )
+}
+
+/// The `le` label of the bucket holding the value, among the buckets with the given upper limits.
+fn bucket_label(upper_limits: impl Iterator<Item = f64>, value: f64) -> String {
+    upper_limits
+        .filter(|upper_limit| upper_limit.is_finite() && value <= *upper_limit)
+        .min_by(f64::total_cmp)
+        .map_or_else(|| "+Inf".to_string(), |upper_limit| upper_limit.to_string())
 }
 
 impl MetricCollector for TimeSeries {
@@ -363,12 +478,18 @@ impl MetricCollector for TimeSeries {
             buffer: Default::default(),
             metadata: Default::default(),
             timestamp: None,
+            native_histograms: false,
         }
     }
 
     fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
         if !self.metadata.contains_key(name) {
-            let r#type = prometheus_metric_type(value);
+            let r#type = match value {
+                MetricValue::Sketch { .. } if self.native_histograms => {
+                    proto::MetricType::Histogram
+                }
+                value => prometheus_metric_type(value),
+            };
             let metadata = proto::MetricMetadata {
                 r#type: r#type as i32,
                 metric_family_name: fullname.into(),
@@ -392,14 +513,34 @@ impl MetricCollector for TimeSeries {
         self.buffer
             .entry(Self::make_labels(tags, name, suffix, extra))
             .or_default()
+            .samples
             .push(proto::Sample { value, timestamp });
     }
 
+    fn native_histograms(&self) -> bool {
+        self.native_histograms
+    }
+
+    fn emit_native_histogram(
+        &mut self,
+        timestamp_millis: Option<i64>,
+        name: &str,
+        histogram: NativeHistogram,
+        tags: Option<&MetricTags>,
+    ) {
+        let timestamp = timestamp_millis.unwrap_or_else(|| self.default_timestamp());
+        self.buffer
+            .entry(Self::make_labels(tags, name, "", None))
+            .or_default()
+            .histograms
+            .push(histogram.to_remote_write(timestamp));
+    }
+
     fn finish(self) -> proto::WriteRequest {
         let timeseries = self
             .buffer
             .into_iter()
-            .map(|(labels, samples)| proto::TimeSeries { labels, samples })
+            .map(|(labels, series)| proto::TimeSeries { labels, ..series })
             .collect::<Vec<_>>();
         let metadata = self
             .metadata
@@ -432,6 +573,164 @@ const fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType
     }
 }
 
+/// The content type of the protobuf exposition format.
+pub(super) const PROTOBUF_CONTENT_TYPE: &str =
+    "application/vnd.google.protobuf; proto=io.prometheus.client.MetricFamily; encoding=delimited";
+
+/// Encodes the metrics as the length-delimited metric families of the protobuf exposition format,
+/// the only one supporting the native histograms, which the sketches are encoded as.
+pub(super) fn encode_metric_families<'a>(
+    default_namespace: Option<&str>,
+    buckets: &[f64],
+    quantiles: &[f64],
+    metrics: impl IntoIterator<Item = &'a Metric>,
+) -> Vec<u8> {
+    // BTreeMap ensures we get sorted output, as in the text format
+    let mut families = BTreeMap::<String, client_proto::MetricFamily>::new();
+    for metric in metrics {
+        if metric.kind() != MetricKind::Absolute {
+            continue;
+        }
+
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let (r#type, mut encoded) = exposition_metric(metric.value(), buckets, quantiles);
+        encoded.label = metric
+            .tags()
+            .into_iter()
+            .flat_map(|tags| tags.iter_single())
+            .map(|(name, value)| client_proto::LabelPair {
+                name: Some(name.into()),
+                value: Some(value.into()),
+            })
+            .collect();
+        encoded.timestamp_ms = metric.timestamp().map(|t| t.timestamp_millis());
+
+        families
+            .entry(name)
+            .or_insert_with_key(|name| client_proto::MetricFamily {
+                name: Some(name.clone()),
+                help: Some(metric.name().into()),
+                r#type: Some(r#type as i32),
+                metric: Vec::new(),
+            })
+            .metric
+            .push(encoded);
+    }
+
+    let mut buffer = Vec::new();
+    for family in families.into_values() {
+        family
+            .encode_length_delimited(&mut buffer)
+            .expect("Out of memory");
+    }
+    buffer
+}
+
+fn exposition_metric(
+    value: &MetricValue,
+    buckets: &[f64],
+    quantiles: &[f64],
+) -> (client_proto::MetricType, client_proto::Metric) {
+    use client_proto::MetricType;
+
+    let counter = |value| client_proto::Metric {
+        counter: Some(client_proto::Counter { value: Some(value) }),
+        ..Default::default()
+    };
+    let gauge = |value| client_proto::Metric {
+        gauge: Some(client_proto::Gauge { value: Some(value) }),
+        ..Default::default()
+    };
+    let histogram = |histogram| client_proto::Metric {
+        histogram: Some(histogram),
+        ..Default::default()
+    };
+    let summary = |quantiles: Vec<(f64, f64)>, count, sum| client_proto::Metric {
+        summary: Some(client_proto::Summary {
+            sample_count: Some(count),
+            sample_sum: Some(sum),
+            quantile: quantiles
+                .into_iter()
+                .map(|(quantile, value)| client_proto::Quantile {
+                    quantile: Some(quantile),
+                    value: Some(value),
+                })
+                .collect(),
+        }),
+        ..Default::default()
+    };
+
+    match value {
+        MetricValue::Counter { value } => (MetricType::Counter, counter(*value)),
+        MetricValue::Gauge { value } => (MetricType::Gauge, gauge(*value)),
+        MetricValue::Set { values } => (MetricType::Gauge, gauge(values.len() as f64)),
+        MetricValue::Distribution {
+            samples,
+            statistic: StatisticKind::Histogram,
+        } => {
+            let (buckets, count, sum) = samples_to_buckets(samples, buckets);
+            (
+                MetricType::Histogram,
+                histogram(classic_histogram(&buckets, count, sum)),
+            )
+        }
+        MetricValue::Distribution {
+            samples,
+            statistic: StatisticKind::Summary,
+        } => {
+            let metric = match DistributionStatistic::from_samples(samples, quantiles) {
+                Some(statistic) => summary(statistic.quantiles, statistic.count, statistic.sum),
+                None => summary(Vec::new(), 0, 0.0),
+            };
+            (MetricType::Summary, metric)
+        }
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => (
+            MetricType::Histogram,
+            histogram(classic_histogram(buckets, *count, *sum)),
+        ),
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => {
+            let quantiles = quantiles.iter().map(|q| (q.quantile, q.value)).collect();
+            (MetricType::Summary, summary(quantiles, *count, *sum))
+        }
+        MetricValue::Sketch { sketch } => match sketch {
+            MetricSketch::AgentDDSketch(ddsketch) => (
+                MetricType::Histogram,
+                histogram(NativeHistogram::from_ddsketch(ddsketch).to_exposition()),
+            ),
+        },
+    }
+}
+
+/// A histogram of cumulative buckets, without the infinite one, which is implied by the count.
+fn classic_histogram(buckets: &[Bucket], count: u64, sum: f64) -> client_proto::Histogram {
+    let mut cumulative_count = 0;
+    client_proto::Histogram {
+        sample_count: Some(count),
+        sample_sum: Some(sum),
+        bucket: buckets
+            .iter()
+            .filter(|bucket| !bucket.upper_limit.is_infinite())
+            .map(|bucket| {
+                cumulative_count += bucket.count;
+                client_proto::Bucket {
+                    cumulative_count: Some(cumulative_count),
+                    upper_bound: Some(bucket.upper_limit),
+                    ..Default::default()
+                }
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -489,6 +788,7 @@ mod tests {
                                 value: $svalue,
                                 timestamp: $timestamp,
                             }],
+                            ..Default::default()
                         },
                     )*
                 ],
@@ -969,4 +1269,176 @@ mod tests {
             "#}
         );
     }
+
+    fn sketch() -> Metric {
+        let mut ddsketch = vector_lib::metrics::AgentDDSketch::with_agent_defaults();
+        ddsketch.insert_many(&[1.0, 2.0, 2.0, 4.0]);
+        Metric::new(
+            "latency".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Sketch {
+                sketch: MetricSketch::AgentDDSketch(ddsketch),
+            },
+        )
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(timestamp()))
+    }
+
+    #[test]
+    fn encodes_sketch_native_histogram_request() {
+        let mut collector = TimeSeries::new().with_native_histograms(true);
+        collector.encode_metric(Some("ns"), &[], &[], &sketch());
+        let request = collector.finish();
+
+        assert_eq!(
+            request.metadata[0].r#type,
+            proto::MetricType::Histogram as i32
+        );
+        assert_eq!(request.timeseries.len(), 1);
+        let series = &request.timeseries[0];
+        assert_eq!(series.labels[0].value, "ns_latency");
+        assert!(series.samples.is_empty());
+        let histogram = &series.histograms[0];
+        assert_eq!(histogram.count, Some(proto::histogram::Count::CountInt(4)));
+        assert_eq!(histogram.sum, 9.0);
+        assert_eq!(histogram.timestamp, 1612325106789);
+        // The buckets of 1.0, 2.0 and 4.0.
+        assert_eq!(histogram.positive_deltas, [1, 1, -1]);
+    }
+
+    #[test]
+    fn encodes_sketch_summary_request_by_default() {
+        let request = encode_one::<TimeSeries>(Some("ns"), &[], &[0.5], &sketch());
+        assert_eq!(
+            request.metadata[0].r#type,
+            proto::MetricType::Summary as i32
+        );
+        assert!(
+            request
+                .timeseries
+                .iter()
+                .all(|series| series.histograms.is_empty())
+        );
+    }
+
+    #[test]
+    fn encodes_metric_families() {
+        let counter = Metric::new(
+            "hits".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 10.0 },
+        )
+        .with_tags(Some(tags()));
+        let histogram = Metric::new(
+            "sizes".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_lib::buckets![1.0 => 1, 2.0 => 2, f64::INFINITY => 1],
+                count: 4,
+                sum: 7.0,
+            },
+        );
+        let encoded =
+            encode_metric_families(Some("ns"), &[], &[], [&counter, &histogram, &sketch()]);
+
+        let mut buffer = encoded.as_slice();
+        let mut families = Vec::new();
+        while !buffer.is_empty() {
+            families
+                .push(client_proto::MetricFamily::decode_length_delimited(&mut buffer).unwrap());
+        }
+        let names = families
+            .iter()
+            .map(|family| family.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ns_hits", "ns_latency", "ns_sizes"]);
+
+        let counter = &families[0].metric[0];
+        assert_eq!(counter.counter.as_ref().unwrap().value, Some(10.0));
+        assert_eq!(counter.label[0].name(), "code");
+
+        let native = families[1].metric[0].histogram.as_ref().unwrap();
+        assert_eq!(families[1].r#type(), client_proto::MetricType::Histogram);
+        assert_eq!(native.schema, Some(6));
+        assert_eq!(native.sample_count, Some(4));
+        assert!(native.bucket.is_empty());
+        assert_eq!(families[1].metric[0].timestamp_ms, Some(1612325106789));
+
+        let classic = families[2].metric[0].histogram.as_ref().unwrap();
+        let cumulative_counts = classic
+            .bucket
+            .iter()
+            .map(|bucket| bucket.cumulative_count())
+            .collect::<Vec<_>>();
+        assert_eq!(cumulative_counts, [1, 3]);
+        assert_eq!(classic.schema, None);
+    }
+
+    #[test]
+    fn encodes_received_metadata_and_exemplars() {
+        let exemplar = |value| proto::Exemplar {
+            labels: vec![proto::Label {
+                name: "trace_id".into(),
+                value: "abc".into(),
+            }],
+            value,
+            timestamp: 1612325106000,
+        };
+        let mut metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_lib::buckets![1.0 => 1, 2.0 => 2],
+                count: 4,
+                sum: 7.0,
+            },
+        )
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(timestamp()));
+        let metadata = proto::MetricMetadata {
+            r#type: proto::MetricType::Gaugehistogram as i32,
+            metric_family_name: "requests".into(),
+            help: "The requests.".into(),
+            unit: "seconds".into(),
+        };
+        super::super::remote_write::metadata::insert_metadata(
+            &mut metric,
+            Some(&metadata),
+            vec![exemplar(1.5), exemplar(3.0)],
+        );
+
+        let mut collector = TimeSeries::new();
+        collector.encode_metric_with_metadata(Some("ns"), &[], &[], &metric);
+        let request = collector.finish();
+
+        assert_eq!(
+            request.metadata,
+            [proto::MetricMetadata {
+                metric_family_name: "ns_requests".into(),
+                ..metadata
+            }]
+        );
+        let exemplars = request
+            .timeseries
+            .iter()
+            .filter(|series| !series.exemplars.is_empty())
+            .map(|series| {
+                let le = series.labels.iter().find(|label| label.name == "le");
+                (le.unwrap().value.as_str(), series.exemplars[0].value)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(exemplars, [("2", 1.5), ("+Inf", 3.0)]);
+
+        // The type isn't kept once the metric is of another type.
+        let metric = metric.with_value(MetricValue::Counter { value: 4.0 });
+        let mut collector = TimeSeries::new();
+        collector.encode_metric_with_metadata(None, &[], &[], &metric);
+        let request = collector.finish();
+        assert_eq!(
+            request.metadata[0].r#type,
+            proto::MetricType::Counter as i32
+        );
+        assert_eq!(request.metadata[0].help, "The requests.");
+        assert_eq!(request.timeseries[0].exemplars.len(), 2);
+    }
 }
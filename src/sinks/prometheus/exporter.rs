@@ -1,4 +1,6 @@
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     convert::Infallible,
     hash::Hash,
     mem::{Discriminant, discriminant},
@@ -18,7 +20,7 @@ use hyper::{
 };
 use indexmap::{IndexMap, map::Entry};
 use serde_with::serde_as;
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
 use stream_cancel::{Trigger, Tripwire};
 use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
@@ -32,7 +34,10 @@ use vector_lib::{
     },
 };
 
-use super::collector::{MetricCollector, StringCollector};
+use super::{
+    collector::{MetricCollector, PROTOBUF_CONTENT_TYPE, StringCollector, encode_metric_families},
+    relabel::{RelabelConfig, RelabelError, Selector, View},
+};
 use crate::{
     config::{AcknowledgementsConfig, GenerateConfig, Input, Resource, SinkConfig, SinkContext},
     event::{
@@ -52,10 +57,18 @@ const MIN_FLUSH_PERIOD_SECS: u64 = 1;
 
 const LOCK_FAILED: &str = "Prometheus exporter data lock is poisoned";
 
+const METRICS_PATH: &str = "/metrics";
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Flush period for sets must be greater or equal to {} secs", min))]
     FlushPeriodTooShort { min: u64 },
+    #[snafu(display("Scrape path {:?} must start with a slash", path))]
+    InvalidPath { path: String },
+    #[snafu(display("Scrape path {:?} is exposed more than once", path))]
+    DuplicatePath { path: String },
+    #[snafu(display("Invalid relabeling of scrape path {:?}: {}", path, source))]
+    Relabel { path: String, source: RelabelError },
 }
 
 /// Configuration for the `prometheus_exporter` sink.
@@ -81,7 +94,8 @@ pub struct PrometheusExporterConfig {
 
     /// The address to expose for scraping.
     ///
-    /// The metrics are exposed at the typical Prometheus exporter path, `/metrics`.
+    /// The metrics are exposed at the typical Prometheus exporter path, `/metrics`, and at the
+    /// additional scrape paths, if any.
     #[serde(default = "default_address")]
     #[configurable(metadata(docs::examples = "192.160.0.10:9598"))]
     pub address: SocketAddr,
@@ -119,6 +133,18 @@ pub struct PrometheusExporterConfig {
     #[configurable(metadata(docs::advanced))]
     pub distributions_as_summaries: bool,
 
+    /// Whether or not to expose the sketches, such as the distributions of the `datadog_agent`
+    /// source, as [native histograms][prom_native_hist_docs].
+    ///
+    /// The native histograms are only supported by the protobuf format, which is used when the
+    /// client accepts it, like Prometheus with the native histograms enabled. Otherwise, the
+    /// sketches are exposed as aggregated summaries.
+    ///
+    /// [prom_native_hist_docs]: https://prometheus.io/docs/specs/native_histograms/
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub native_histograms: bool,
+
     /// The interval, in seconds, on which metrics are flushed.
     ///
     /// On the flush interval, if a metric has not been seen since the last flush interval, it is
@@ -140,6 +166,25 @@ pub struct PrometheusExporterConfig {
     #[configurable(metadata(docs::advanced))]
     pub suppress_timestamp: bool,
 
+    /// The rules relabeling the metrics, applied in order before they're exposed on any path.
+    ///
+    /// As with the relabeling of Prometheus, the metrics can be dropped or kept by the values of
+    /// their labels, and their labels replaced, dropped or kept. The name of the metric, with its
+    /// namespace, is the `__name__` label.
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub relabel_configs: Vec<RelabelConfig>,
+
+    /// Additional scrape paths, each exposing the metrics selected and relabeled for it.
+    ///
+    /// The metrics exposed on any path can be further selected by the scraper, with the `match[]`
+    /// query parameters of the [federation endpoint][federation] of Prometheus.
+    ///
+    /// [federation]: https://prometheus.io/docs/prometheus/latest/federation/
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub paths: Vec<ScrapePathConfig>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -159,13 +204,39 @@ impl Default for PrometheusExporterConfig {
             buckets: super::default_histogram_buckets(),
             quantiles: super::default_summary_quantiles(),
             distributions_as_summaries: default_distributions_as_summaries(),
+            native_histograms: false,
             flush_period_secs: default_flush_period_secs(),
             suppress_timestamp: default_suppress_timestamp(),
+            relabel_configs: Vec::new(),
+            paths: Vec::new(),
             acknowledgements: Default::default(),
         }
     }
 }
 
+/// An additional scrape path of the `prometheus_exporter` sink.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ScrapePathConfig {
+    /// The path on which the metrics are exposed.
+    #[configurable(metadata(docs::examples = "/federate"))]
+    pub path: String,
+
+    /// The series selectors of the metrics exposed on the path, after their relabeling.
+    ///
+    /// A metric is exposed if it matches any of them, or if there are none. The selectors are
+    /// written as in PromQL, with the name of the metric followed by matchers of its labels.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "http_requests_total{code=~\"5..\"}"))]
+    #[configurable(metadata(docs::examples = "{job=\"vector\"}"))]
+    pub selectors: Vec<String>,
+
+    /// The rules relabeling the metrics exposed on the path, applied after those of the sink.
+    #[serde(default)]
+    pub relabel_configs: Vec<RelabelConfig>,
+}
+
 const fn default_address() -> SocketAddr {
     SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 9598)
 }
@@ -182,6 +253,35 @@ const fn default_suppress_timestamp() -> bool {
     false
 }
 
+impl PrometheusExporterConfig {
+    /// The views of the metrics exposed on each path.
+    fn views(&self) -> Result<HashMap<String, View>, BuildError> {
+        let mut views = HashMap::new();
+        let view =
+            View::new(&self.relabel_configs, &[]).context(RelabelSnafu { path: METRICS_PATH })?;
+        views.insert(METRICS_PATH.to_string(), view);
+
+        for path in &self.paths {
+            if !path.path.starts_with('/') {
+                return Err(BuildError::InvalidPath {
+                    path: path.path.clone(),
+                });
+            }
+            let view = View::new(
+                self.relabel_configs.iter().chain(&path.relabel_configs),
+                &path.selectors,
+            )
+            .context(RelabelSnafu { path: &path.path })?;
+            if views.insert(path.path.clone(), view).is_some() {
+                return Err(BuildError::DuplicatePath {
+                    path: path.path.clone(),
+                });
+            }
+        }
+        Ok(views)
+    }
+}
+
 impl GenerateConfig for PrometheusExporterConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self::default()).unwrap()
@@ -199,6 +299,7 @@ impl SinkConfig for PrometheusExporterConfig {
         }
 
         validate_quantiles(&self.quantiles)?;
+        self.views()?;
 
         let sink = PrometheusExporter::new(self.clone());
         let healthcheck = future::ok(()).boxed();
@@ -340,12 +441,33 @@ fn authorized<T: HttpBody>(req: &Request<T>, auth: &Option<Auth>) -> bool {
     false
 }
 
+/// The series selectors of the `match[]` query parameters of the request, as on the federation
+/// endpoint of Prometheus.
+fn request_selectors<T>(req: &Request<T>) -> Result<Vec<Selector>, RelabelError> {
+    url::form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+        .filter(|(name, _)| name == "match[]")
+        .map(|(_, selector)| selector.parse())
+        .collect()
+}
+
+/// Whether the client accepts the protobuf format, as Prometheus does when the native histograms
+/// are enabled.
+fn accepts_protobuf<T>(req: &Request<T>) -> bool {
+    req.headers()
+        .get_all(hyper::header::ACCEPT)
+        .iter()
+        .filter_map(|accept| accept.to_str().ok())
+        .any(|accept| accept.contains("proto=io.prometheus.client.MetricFamily"))
+}
+
 #[derive(Clone)]
 struct Handler {
     auth: Option<Auth>,
     default_namespace: Option<String>,
     buckets: Box<[f64]>,
     quantiles: Box<[f64]>,
+    native_histograms: bool,
+    views: Arc<HashMap<String, View>>,
     bytes_sent: Registered<BytesSent>,
     events_sent: Registered<EventsSent>,
 }
@@ -358,7 +480,8 @@ impl Handler {
     ) -> Response<Body> {
         let mut response = Response::new(Body::empty());
 
-        match (authorized(&req, &self.auth), req.method(), req.uri().path()) {
+        let view = self.views.get(req.uri().path());
+        match (authorized(&req, &self.auth), req.method(), view) {
             (false, _, _) => {
                 *response.status_mut() = StatusCode::UNAUTHORIZED;
                 response.headers_mut().insert(
@@ -367,37 +490,76 @@ impl Handler {
                 );
             }
 
-            (true, &Method::GET, "/metrics") => {
+            (true, &Method::GET, Some(view)) => {
+                let selectors = match request_selectors(&req) {
+                    Ok(selectors) => selectors,
+                    Err(error) => {
+                        *response.status_mut() = StatusCode::BAD_REQUEST;
+                        *response.body_mut() = error.to_string().into();
+                        return response;
+                    }
+                };
+
                 let metrics = metrics.read().expect(LOCK_FAILED);
 
-                let count = metrics.len();
-                let byte_size = metrics
+                // The relabeled metrics are named with their namespace already.
+                let passthrough = view.is_passthrough() && selectors.is_empty();
+                let (exposed, default_namespace) = if passthrough {
+                    let exposed = metrics
+                        .values()
+                        .map(|(metric, _)| Cow::Borrowed(metric))
+                        .collect::<Vec<_>>();
+                    (exposed, self.default_namespace.as_deref())
+                } else {
+                    let exposed = metrics
+                        .values()
+                        .filter_map(|(metric, _)| {
+                            view.expose(metric, self.default_namespace.as_deref(), &selectors)
+                        })
+                        .map(Cow::Owned)
+                        .collect();
+                    (exposed, None)
+                };
+
+                let count = exposed.len();
+                let byte_size = exposed
                     .iter()
-                    .map(|(_, (metric, _))| metric.estimated_json_encoded_size_of())
+                    .map(|metric| metric.estimated_json_encoded_size_of())
                     .sum();
 
-                let mut collector = StringCollector::new();
-
-                for (_, (metric, _)) in metrics.iter() {
-                    collector.encode_metric(
-                        self.default_namespace.as_deref(),
+                let (body, content_type) = if self.native_histograms && accepts_protobuf(&req) {
+                    let body = encode_metric_families(
+                        default_namespace,
                         &self.buckets,
                         &self.quantiles,
-                        metric,
+                        exposed.iter().map(|metric| &**metric),
                     );
-                }
+                    (body, PROTOBUF_CONTENT_TYPE)
+                } else {
+                    let mut collector = StringCollector::new();
+
+                    for metric in &exposed {
+                        collector.encode_metric(
+                            default_namespace,
+                            &self.buckets,
+                            &self.quantiles,
+                            metric,
+                        );
+                    }
+
+                    (collector.finish().into_bytes(), "text/plain; version=0.0.4")
+                };
 
+                drop(exposed);
                 drop(metrics);
 
-                let body = collector.finish();
                 let body_size = body.size_of();
 
                 *response.body_mut() = body.into();
 
-                response.headers_mut().insert(
-                    "Content-Type",
-                    HeaderValue::from_static("text/plain; version=0.0.4"),
-                );
+                response
+                    .headers_mut()
+                    .insert("Content-Type", HeaderValue::from_static(content_type));
 
                 self.events_sent.emit(CountByteSize(count, byte_size));
                 self.bytes_sent.emit(ByteSize(body_size));
@@ -432,6 +594,8 @@ impl PrometheusExporter {
             default_namespace: self.config.default_namespace.clone(),
             buckets: self.config.buckets.clone().into(),
             quantiles: self.config.quantiles.clone().into(),
+            native_histograms: self.config.native_histograms,
+            views: Arc::new(self.config.views()?),
             auth: self.config.auth.clone(),
         };
 
@@ -606,12 +770,15 @@ mod tests {
     use flate2::read::GzDecoder;
     use futures::stream;
     use indoc::indoc;
+    use prost::Message;
     use similar_asserts::assert_eq;
     use tokio::{sync::oneshot::error::TryRecvError, time};
     use vector_lib::{
         event::{MetricTags, StatisticKind},
         finalization::{BatchNotifier, BatchStatus},
-        metric_tags, samples,
+        metric_tags,
+        metrics::AgentDDSketch,
+        samples,
         sensitive_string::SensitiveString,
     };
 
@@ -1446,6 +1613,149 @@ mod tests {
             .expect("gauge metric should exist");
         assert_eq!(actual_gauge.0.value(), expected_gauge.value());
     }
+
+    #[tokio::test]
+    async fn exposes_sketches_as_native_histograms() {
+        let handler = Handler {
+            auth: None,
+            default_namespace: None,
+            buckets: super::super::default_histogram_buckets().into(),
+            quantiles: super::super::default_summary_quantiles().into(),
+            native_histograms: true,
+            views: Arc::new(PrometheusExporterConfig::default().views().unwrap()),
+            bytes_sent: register!(BytesSent::from(Protocol::HTTP)),
+            events_sent: register!(EventsSent::from(Output(None))),
+        };
+        let mut ddsketch = AgentDDSketch::with_agent_defaults();
+        ddsketch.insert_many(&[1.0, 2.0, 4.0]);
+        let metric = Metric::new("latency", MetricKind::Absolute, ddsketch.into());
+        let metrics = RwLock::new(IndexMap::from([(
+            MetricRef::from_metric(&metric),
+            (metric, MetricMetadata::new(time::Duration::from_secs(60))),
+        )]));
+
+        let request = Request::get("/metrics")
+            .header(
+                hyper::header::ACCEPT,
+                "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;\
+                 encoding=delimited;q=0.7,text/plain;version=0.0.4;q=0.3",
+            )
+            .body(Body::empty())
+            .unwrap();
+        let response = handler.handle(request, &metrics);
+        assert_eq!(response.headers()["Content-Type"], PROTOBUF_CONTENT_TYPE);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let family =
+            vector_lib::prometheus::parser::client_proto::MetricFamily::decode_length_delimited(
+                body,
+            )
+            .unwrap();
+        let histogram = family.metric[0].histogram.as_ref().unwrap();
+        assert_eq!(histogram.sample_count, Some(3));
+        assert_eq!(histogram.positive_delta, [1, 0, 0]);
+
+        // The text format can't hold native histograms.
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = handler.handle(request, &metrics);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("# TYPE latency summary"));
+    }
+
+    #[tokio::test]
+    async fn exposes_relabeled_metrics_on_scrape_paths() {
+        let config: PrometheusExporterConfig = toml::from_str(indoc! {r#"
+            default_namespace = "vector"
+
+            [[relabel_configs]]
+            action = "labeldrop"
+            regex = "host"
+
+            [[paths]]
+            path = "/federate"
+            selectors = ['{__name__=~"vector_http_.*"}']
+
+            [[paths.relabel_configs]]
+            source_labels = ["code"]
+            regex = "(.)xx"
+            target_label = "class"
+            replacement = "${1}xx"
+        "#})
+        .unwrap();
+        let handler = Handler {
+            auth: None,
+            default_namespace: config.default_namespace.clone(),
+            buckets: config.buckets.clone().into(),
+            quantiles: config.quantiles.clone().into(),
+            native_histograms: false,
+            views: Arc::new(config.views().unwrap()),
+            bytes_sent: register!(BytesSent::from(Protocol::HTTP)),
+            events_sent: register!(EventsSent::from(Output(None))),
+        };
+        let metrics = [
+            Metric::new(
+                "http_requests_total",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(metric_tags!("code" => "5xx", "host" => "a"))),
+            Metric::new(
+                "uptime_seconds",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 2.0 },
+            ),
+        ];
+        let metrics = RwLock::new(
+            metrics
+                .into_iter()
+                .map(|metric| {
+                    (
+                        MetricRef::from_metric(&metric),
+                        (metric, MetricMetadata::new(time::Duration::from_secs(60))),
+                    )
+                })
+                .collect(),
+        );
+        let scrape = |uri: &str| {
+            let response = handler.handle(Request::get(uri).body(Body::empty()).unwrap(), &metrics);
+            let status = response.status();
+            async move {
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        let (_, body) = scrape("/metrics").await;
+        assert!(body.contains(r#"vector_http_requests_total{code="5xx"} 1"#));
+        assert!(body.contains("vector_uptime_seconds 2"));
+
+        let (_, body) = scrape("/federate").await;
+        assert!(body.contains(r#"vector_http_requests_total{class="5xx",code="5xx"} 1"#));
+        assert!(!body.contains("vector_uptime_seconds"));
+
+        let (_, body) = scrape("/metrics?match[]=vector_uptime_seconds").await;
+        assert!(!body.contains("vector_http_requests_total"));
+        assert!(body.contains("vector_uptime_seconds 2"));
+
+        let (status, _) = scrape("/metrics?match[]=%7Bcode%3D%7D").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = scrape("/other").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn rejects_invalid_scrape_paths() {
+        let views = |paths: &str| {
+            toml::from_str::<PrometheusExporterConfig>(paths)
+                .unwrap()
+                .views()
+                .err()
+                .map(|error| error.to_string())
+        };
+        assert_eq!(views(r#"paths = [{ path = "/federate" }]"#), None);
+        assert!(views(r#"paths = [{ path = "federate" }]"#).is_some());
+        assert!(views(r#"paths = [{ path = "/metrics" }]"#).is_some());
+        assert!(views(r#"paths = [{ path = "/federate", selectors = ["{code=}"] }]"#).is_some());
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
@@ -4,6 +4,8 @@ use vector_lib::sensitive_string::SensitiveString;
 
 mod collector;
 pub mod exporter;
+mod native_histogram;
+mod relabel;
 pub mod remote_write;
 
 use vector_lib::configurable::configurable_component;
@@ -0,0 +1,191 @@
+//! The conversion of the sketches into Prometheus [native histograms][native_histograms], whose
+//! buckets are exponential and sparse.
+//!
+//! [native_histograms]: https://prometheus.io/docs/specs/native_histograms/
+
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use vector_lib::{
+    metrics::AgentDDSketch,
+    prometheus::parser::{client_proto, proto},
+};
+
+/// The narrowest and widest schemas of the buckets supported by Prometheus.
+const MIN_SCHEMA: i32 = -4;
+const MAX_SCHEMA: i32 = 8;
+
+/// A native histogram, whose bucket of index `i` covers the values in `(base^(i-1), base^i]`,
+/// `base` being `2^(2^-schema)`, and whose zero bucket covers the values in
+/// `[-zero_threshold, zero_threshold]`.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct NativeHistogram {
+    schema: i32,
+    zero_threshold: f64,
+    zero_count: u64,
+    count: u64,
+    sum: f64,
+    positive: Buckets,
+    negative: Buckets,
+}
+
+/// The sparse buckets of one side of a native histogram, as the spans of consecutive buckets and
+/// the difference between the count of each bucket and the previous one.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Buckets {
+    /// The gap since the previous span, or the index of the first bucket, and the length of the
+    /// spans.
+    spans: Vec<(i32, u32)>,
+    deltas: Vec<i64>,
+}
+
+impl Buckets {
+    fn from_counts(counts: BTreeMap<i32, u64>) -> Self {
+        let mut buckets = Self::default();
+        let mut previous: Option<(i32, u64)> = None;
+        for (index, count) in counts {
+            match previous {
+                Some((previous, _)) if index == previous + 1 => {
+                    buckets
+                        .spans
+                        .last_mut()
+                        .expect("span of the previous bucket")
+                        .1 += 1;
+                }
+                Some((previous, _)) => buckets.spans.push((index - previous - 1, 1)),
+                None => buckets.spans.push((index, 1)),
+            }
+            let previous_count = previous.map_or(0, |(_, count)| count);
+            buckets.deltas.push(count as i64 - previous_count as i64);
+            previous = Some((index, count));
+        }
+        buckets
+    }
+}
+
+impl NativeHistogram {
+    /// Converts a sketch, each of its bins going into the bucket holding the value of the bin.
+    ///
+    /// The buckets are no wider than the bins, so that the accuracy of the sketch is kept.
+    pub(super) fn from_ddsketch(sketch: &AgentDDSketch) -> Self {
+        let config = sketch.config();
+        let schema = schema(sketch.gamma());
+
+        let mut zero_count = 0;
+        let mut positive = BTreeMap::<i32, u64>::new();
+        let mut negative = BTreeMap::<i32, u64>::new();
+        let (keys, counts) = sketch.bin_map().into_parts();
+        for (key, count) in keys.into_iter().zip(counts) {
+            let value = config.bin_lower_bound(key);
+            let count = u64::from(count);
+            match key.cmp(&0) {
+                Ordering::Equal => zero_count += count,
+                Ordering::Greater => *positive.entry(index(value, schema)).or_default() += count,
+                Ordering::Less => *negative.entry(index(-value, schema)).or_default() += count,
+            }
+        }
+
+        Self {
+            schema,
+            // The bins of the smallest values go into the zero bucket.
+            zero_threshold: config.bin_lower_bound(1),
+            zero_count,
+            count: u64::from(sketch.count()),
+            sum: sketch.sum().unwrap_or(0.0),
+            positive: Buckets::from_counts(positive),
+            negative: Buckets::from_counts(negative),
+        }
+    }
+
+    /// The histogram of a time series of the remote write protocol.
+    pub(super) fn to_remote_write(&self, timestamp: i64) -> proto::Histogram {
+        let spans = |buckets: &Buckets| {
+            buckets
+                .spans
+                .iter()
+                .map(|&(offset, length)| proto::BucketSpan { offset, length })
+                .collect()
+        };
+        proto::Histogram {
+            count: Some(proto::histogram::Count::CountInt(self.count)),
+            sum: self.sum,
+            schema: self.schema,
+            zero_threshold: self.zero_threshold,
+            zero_count: Some(proto::histogram::ZeroCount::ZeroCountInt(self.zero_count)),
+            negative_spans: spans(&self.negative),
+            negative_deltas: self.negative.deltas.clone(),
+            positive_spans: spans(&self.positive),
+            positive_deltas: self.positive.deltas.clone(),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    /// The histogram of a metric of the protobuf exposition format.
+    pub(super) fn to_exposition(&self) -> client_proto::Histogram {
+        let spans = |buckets: &Buckets| {
+            buckets
+                .spans
+                .iter()
+                .map(|&(offset, length)| client_proto::BucketSpan {
+                    offset: Some(offset),
+                    length: Some(length),
+                })
+                .collect()
+        };
+        client_proto::Histogram {
+            sample_count: Some(self.count),
+            sample_sum: Some(self.sum),
+            schema: Some(self.schema),
+            zero_threshold: Some(self.zero_threshold),
+            zero_count: Some(self.zero_count),
+            negative_span: spans(&self.negative),
+            negative_delta: self.negative.deltas.clone(),
+            positive_span: spans(&self.positive),
+            positive_delta: self.positive.deltas.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The schema with the widest buckets that are still no wider than the bins of a sketch whose
+/// consecutive bins grow by a factor of `gamma`.
+fn schema(gamma: f64) -> i32 {
+    (-gamma.log2().log2())
+        .ceil()
+        .clamp(f64::from(MIN_SCHEMA), f64::from(MAX_SCHEMA)) as i32
+}
+
+/// The index of the bucket holding a positive value.
+fn index(value: f64, schema: i32) -> i32 {
+    (value.min(f64::MAX).log2() * 2f64.powi(schema)).ceil() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_spans_and_deltas() {
+        let buckets = Buckets::from_counts(BTreeMap::from([(-2, 3), (-1, 5), (2, 1), (3, 1)]));
+        assert_eq!(buckets.spans, [(-2, 2), (2, 2)]);
+        assert_eq!(buckets.deltas, [3, 2, -4, 0]);
+    }
+
+    #[test]
+    fn buckets_are_no_wider_than_the_bins() {
+        let mut sketch = AgentDDSketch::with_agent_defaults();
+        sketch.insert_many(&[-2.0, 0.0, 1.0, 1.0, 2.0, 2.05, 1000.0]);
+        let histogram = NativeHistogram::from_ddsketch(&sketch);
+
+        // The bins grow by 1.5625%, and the buckets by 2^(2^-6) - 1 = 1.089%.
+        assert_eq!(histogram.schema, 6);
+        assert_eq!(histogram.count, 7);
+        assert_eq!(histogram.zero_count, 1);
+        // The bin of 2.0 holds the values around 2.009, in the bucket (2^(64/64), 2^(65/64)].
+        assert_eq!(histogram.negative.spans, [(65, 1)]);
+        assert_eq!(histogram.negative.deltas, [1]);
+        // 2.0 and 2.05 are in consecutive bins and buckets.
+        assert_eq!(histogram.positive.spans, [(0, 1), (64, 2), (572, 1)]);
+        assert_eq!(histogram.positive.deltas, [2, -1, 0, 0]);
+    }
+}
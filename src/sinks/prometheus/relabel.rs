@@ -0,0 +1,454 @@
+//! The relabeling and filtering of the metrics exposed by the `prometheus_exporter` sink, following
+//! the [relabeling][relabel_config] of Prometheus and the [series selectors][selectors] of its
+//! federation endpoint.
+//!
+//! [relabel_config]: https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config
+//! [selectors]: https://prometheus.io/docs/prometheus/latest/querying/basics/#time-series-selectors
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use regex::Regex;
+use snafu::{ResultExt, Snafu};
+use vector_lib::{configurable::configurable_component, prometheus::parser::METRIC_NAME_LABEL};
+
+use crate::{event::Metric, sinks::util::encode_namespace};
+
+#[derive(Debug, Snafu)]
+pub(super) enum RelabelError {
+    #[snafu(display("Invalid regex {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+
+    #[snafu(display("The replace action requires a target label"))]
+    MissingTargetLabel,
+
+    #[snafu(display("Invalid series selector {:?}: {}", selector, reason))]
+    InvalidSelector {
+        selector: String,
+        reason: &'static str,
+    },
+}
+
+/// The action of a relabeling rule.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// Sets the target label to the replacement when the regex matches the values of the source
+    /// labels, removing it if the replacement is empty.
+    #[default]
+    Replace,
+
+    /// Keeps only the metrics whose values of the source labels match the regex.
+    Keep,
+
+    /// Drops the metrics whose values of the source labels match the regex.
+    Drop,
+
+    /// Removes the labels whose name matches the regex.
+    LabelDrop,
+
+    /// Removes the labels whose name doesn't match the regex.
+    LabelKeep,
+}
+
+/// A rule relabeling the metrics, as the relabeling rules of Prometheus.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelConfig {
+    /// The labels whose values, joined by the separator, are matched against the regex.
+    ///
+    /// The name of the metric, with its namespace, is the `__name__` label. A missing label has an
+    /// empty value.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "__name__"))]
+    #[configurable(metadata(docs::examples = "code"))]
+    pub source_labels: Vec<String>,
+
+    /// The separator between the values of the source labels.
+    #[serde(default = "default_separator")]
+    pub separator: String,
+
+    /// The regular expression matched against the values of the source labels, or against the
+    /// names of the labels for the `labeldrop` and `labelkeep` actions.
+    ///
+    /// The regular expression is anchored at both ends.
+    #[serde(default = "default_regex")]
+    #[configurable(metadata(docs::examples = "5.."))]
+    pub regex: String,
+
+    /// The label set by the `replace` action, which can refer to the capture groups of the regex,
+    /// as `$1` or `${name}`.
+    #[configurable(metadata(docs::examples = "status"))]
+    pub target_label: Option<String>,
+
+    /// The value of the target label set by the `replace` action, which can refer to the capture
+    /// groups of the regex, as `$1` or `${name}`.
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub action: RelabelAction,
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+struct Rule {
+    action: RelabelAction,
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: String,
+    replacement: String,
+}
+
+impl Rule {
+    fn new(config: &RelabelConfig) -> Result<Self, RelabelError> {
+        let target_label = match (config.action, &config.target_label) {
+            (RelabelAction::Replace, None) => return Err(RelabelError::MissingTargetLabel),
+            (_, target_label) => target_label.clone().unwrap_or_default(),
+        };
+        Ok(Self {
+            action: config.action,
+            source_labels: config.source_labels.clone(),
+            separator: config.separator.clone(),
+            regex: anchored_regex(&config.regex)?,
+            target_label,
+            replacement: config.replacement.clone(),
+        })
+    }
+
+    fn source_value(&self, labels: &BTreeMap<String, String>) -> String {
+        self.source_labels
+            .iter()
+            .map(|label| labels.get(label).map_or("", String::as_str))
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    /// Applies the rule to the labels, returning whether the metric is kept.
+    fn apply(&self, labels: &mut BTreeMap<String, String>) -> bool {
+        match self.action {
+            RelabelAction::Replace => {
+                let value = self.source_value(labels);
+                if let Some(captures) = self.regex.captures(&value) {
+                    let mut target_label = String::new();
+                    captures.expand(&self.target_label, &mut target_label);
+                    let mut replacement = String::new();
+                    captures.expand(&self.replacement, &mut replacement);
+                    if replacement.is_empty() {
+                        labels.remove(&target_label);
+                    } else {
+                        labels.insert(target_label, replacement);
+                    }
+                }
+                true
+            }
+            RelabelAction::Keep => self.regex.is_match(&self.source_value(labels)),
+            RelabelAction::Drop => !self.regex.is_match(&self.source_value(labels)),
+            // The name of the metric is always kept.
+            RelabelAction::LabelDrop => {
+                labels.retain(|name, _| name == METRIC_NAME_LABEL || !self.regex.is_match(name));
+                true
+            }
+            RelabelAction::LabelKeep => {
+                labels.retain(|name, _| name == METRIC_NAME_LABEL || self.regex.is_match(name));
+                true
+            }
+        }
+    }
+}
+
+fn anchored_regex(regex: &str) -> Result<Regex, RelabelError> {
+    Regex::new(&format!("^(?:{regex})$")).context(InvalidRegexSnafu { regex })
+}
+
+enum MatchOp {
+    Equal(String),
+    NotEqual(String),
+    Regex(Regex),
+    NotRegex(Regex),
+}
+
+struct Matcher {
+    label: String,
+    op: MatchOp,
+}
+
+impl Matcher {
+    fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        let value = labels.get(&self.label).map_or("", String::as_str);
+        match &self.op {
+            MatchOp::Equal(expected) => value == expected,
+            MatchOp::NotEqual(expected) => value != expected,
+            MatchOp::Regex(regex) => regex.is_match(value),
+            MatchOp::NotRegex(regex) => !regex.is_match(value),
+        }
+    }
+}
+
+/// A series selector, such as `http_requests_total{code=~"5.."}`, matching the metrics whose labels
+/// match all of its matchers.
+pub(super) struct Selector(Vec<Matcher>);
+
+impl Selector {
+    fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.0.iter().all(|matcher| matcher.matches(labels))
+    }
+}
+
+impl FromStr for Selector {
+    type Err = RelabelError;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason| RelabelError::InvalidSelector {
+            selector: selector.to_owned(),
+            reason,
+        };
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == ':';
+
+        let mut matchers = Vec::new();
+        let mut rest = selector.trim();
+        let name_len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        if name_len > 0 {
+            matchers.push(Matcher {
+                label: METRIC_NAME_LABEL.into(),
+                op: MatchOp::Equal(rest[..name_len].into()),
+            });
+            rest = rest[name_len..].trim_start();
+        }
+
+        if let Some(inner) = rest.strip_prefix('{') {
+            rest = inner.trim_start();
+            while let Some(label_len) = rest.find(|c: char| !is_name_char(c)).filter(|len| *len > 0)
+            {
+                let label = rest[..label_len].to_owned();
+                rest = rest[label_len..].trim_start();
+                let (op, after) = ["=~", "!~", "!=", "="]
+                    .into_iter()
+                    .find_map(|op| rest.strip_prefix(op).map(|after| (op, after)))
+                    .ok_or_else(|| invalid("expected a matching operator"))?;
+                let (value, after) = parse_quoted(after.trim_start())
+                    .ok_or_else(|| invalid("expected a quoted label value"))?;
+                let op = match op {
+                    "=~" => MatchOp::Regex(anchored_regex(&value)?),
+                    "!~" => MatchOp::NotRegex(anchored_regex(&value)?),
+                    "!=" => MatchOp::NotEqual(value),
+                    _ => MatchOp::Equal(value),
+                };
+                matchers.push(Matcher { label, op });
+
+                rest = after.trim_start();
+                match rest.strip_prefix(',') {
+                    Some(after) => rest = after.trim_start(),
+                    None => break,
+                }
+            }
+            rest = rest
+                .strip_prefix('}')
+                .ok_or_else(|| invalid("expected a label matcher or `}`"))?;
+        }
+
+        if !rest.trim().is_empty() {
+            Err(invalid("unexpected characters after the selector"))
+        } else if matchers.is_empty() {
+            Err(invalid("expected a metric name or label matchers"))
+        } else {
+            Ok(Self(matchers))
+        }
+    }
+}
+
+/// Parses a string quoted by double or single quotes, returning it unescaped and the rest of the
+/// input.
+fn parse_quoted(input: &str) -> Option<(String, &str)> {
+    let mut chars = input.char_indices();
+    let (_, quote) = chars.next().filter(|(_, c)| matches!(c, '"' | '\''))?;
+    let mut value = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => value.push(match chars.next()?.1 {
+                'n' => '\n',
+                't' => '\t',
+                c => c,
+            }),
+            c if c == quote => return Some((value, &input[index + 1..])),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// The metrics exposed on a path, relabeled and then filtered by series selectors.
+#[derive(Default)]
+pub(super) struct View {
+    rules: Vec<Rule>,
+    selectors: Vec<Selector>,
+}
+
+impl View {
+    pub(super) fn new<'a>(
+        relabel_configs: impl IntoIterator<Item = &'a RelabelConfig>,
+        selectors: &[String],
+    ) -> Result<Self, RelabelError> {
+        Ok(Self {
+            rules: relabel_configs
+                .into_iter()
+                .map(Rule::new)
+                .collect::<Result<_, _>>()?,
+            selectors: selectors
+                .iter()
+                .map(|selector| selector.parse())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Whether the metrics are exposed as they are, unless the request selects some of them.
+    pub(super) fn is_passthrough(&self) -> bool {
+        self.rules.is_empty() && self.selectors.is_empty()
+    }
+
+    /// Relabels the metric, then named with its namespace, if it's kept by the rules and matches
+    /// any of the selectors of the view, and any of the selectors of the request.
+    pub(super) fn expose(
+        &self,
+        metric: &Metric,
+        default_namespace: Option<&str>,
+        selectors: &[Selector],
+    ) -> Option<Metric> {
+        let mut labels = metric
+            .tags()
+            .into_iter()
+            .flat_map(|tags| tags.iter_single())
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect::<BTreeMap<_, _>>();
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        labels.insert(METRIC_NAME_LABEL.into(), name);
+
+        if !self.rules.iter().all(|rule| rule.apply(&mut labels)) {
+            return None;
+        }
+        let matches = |selectors: &[Selector]| {
+            selectors.is_empty() || selectors.iter().any(|selector| selector.matches(&labels))
+        };
+        if !matches(&self.selectors) || !matches(selectors) {
+            return None;
+        }
+
+        let name = labels
+            .remove(METRIC_NAME_LABEL)
+            .filter(|name| !name.is_empty())?;
+        let tags = (!labels.is_empty()).then(|| labels.into());
+        Some(
+            metric
+                .clone()
+                .with_name(name)
+                .with_namespace(None::<String>)
+                .with_tags(tags),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::metric_tags;
+
+    use super::*;
+    use crate::event::{MetricKind, MetricValue};
+
+    fn metric(name: &str, code: &str) -> Metric {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_namespace(Some("app"))
+        .with_tags(Some(metric_tags!("code" => code, "host" => "a")))
+    }
+
+    fn relabel_config(action: RelabelAction, source_labels: &[&str], regex: &str) -> RelabelConfig {
+        RelabelConfig {
+            source_labels: source_labels
+                .iter()
+                .map(|label| label.to_string())
+                .collect(),
+            separator: default_separator(),
+            regex: regex.into(),
+            target_label: None,
+            replacement: default_replacement(),
+            action,
+        }
+    }
+
+    #[test]
+    fn relabels_metrics() {
+        let view = View::new(
+            &[
+                relabel_config(
+                    RelabelAction::Drop,
+                    &["__name__", "code"],
+                    "app_requests;4..",
+                ),
+                RelabelConfig {
+                    target_label: Some("status".into()),
+                    replacement: "${class}xx".into(),
+                    ..relabel_config(RelabelAction::Replace, &["code"], "(?P<class>[0-9]).*")
+                },
+                relabel_config(RelabelAction::LabelDrop, &[], "code|host"),
+            ],
+            &[],
+        )
+        .unwrap();
+        assert!(!view.is_passthrough());
+
+        assert!(view.expose(&metric("requests", "404"), None, &[]).is_none());
+        let exposed = view.expose(&metric("requests", "503"), None, &[]).unwrap();
+        assert_eq!(exposed.name(), "app_requests");
+        assert_eq!(exposed.namespace(), None);
+        assert_eq!(exposed.tags(), Some(&metric_tags!("status" => "5xx")));
+    }
+
+    #[test]
+    fn filters_metrics_by_selectors() {
+        let view = View::new(&[], &[r#"app_requests{code=~"5..", host!='b'}"#.into()]).unwrap();
+        assert!(view.expose(&metric("requests", "200"), None, &[]).is_none());
+        assert!(view.expose(&metric("errors", "500"), None, &[]).is_none());
+        assert!(view.expose(&metric("requests", "500"), None, &[]).is_some());
+
+        let selectors = [r#"{code="503"}"#.parse().unwrap()];
+        assert!(
+            view.expose(&metric("requests", "500"), None, &selectors)
+                .is_none()
+        );
+        assert!(
+            view.expose(&metric("requests", "503"), None, &selectors)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_selectors() {
+        for selector in [
+            "",
+            "{}",
+            "up{code}",
+            r#"up{code="5"#,
+            r#"up{code=~"("}"#,
+            "up}",
+        ] {
+            assert!(selector.parse::<Selector>().is_err(), "{selector}");
+        }
+        assert!(r#"up{code = "5\"", }"#.parse::<Selector>().is_ok());
+    }
+}
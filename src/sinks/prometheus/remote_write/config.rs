@@ -73,6 +73,17 @@ pub struct RemoteWriteConfig {
     #[configurable(metadata(docs::advanced))]
     pub quantiles: Vec<f64>,
 
+    /// Whether or not to send the sketches, such as the distributions of the `datadog_agent`
+    /// source, as [native histograms][prom_native_hist_docs].
+    ///
+    /// The endpoint must accept the native histograms, like Prometheus with the native histograms
+    /// enabled. Otherwise, the sketches are sent as aggregated summaries.
+    ///
+    /// [prom_native_hist_docs]: https://prometheus.io/docs/specs/native_histograms/
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub native_histograms: bool,
+
     #[configurable(derived)]
     #[serde(default)]
     pub batch: RemoteWriteBatchConfig,
@@ -207,6 +218,7 @@ impl SinkConfig for RemoteWriteConfig {
                 .into_batcher_settings()?,
             buckets,
             quantiles,
+            native_histograms: self.native_histograms,
             default_namespace,
             expire_metrics_secs: self.expire_metrics_secs,
             service,
@@ -0,0 +1,169 @@
+//! The metadata of the metric families and the exemplars of the series of the remote write
+//! protocol, which the `prometheus_remote_write` source keeps in the metadata of the metrics so
+//! that this sink sends them along.
+//!
+//! They're kept under `%prometheus_remote_write`, as the `type`, `help` and `unit` of the family
+//! and the `exemplars` of the series, each with its `labels`, `value` and `timestamp`.
+
+use chrono::DateTime;
+use vector_lib::{
+    event::{Metric, ObjectMap, Value},
+    lookup::path,
+    prometheus::parser::proto,
+};
+
+/// The root of the fields of the metadata of the metrics.
+const ROOT: &str = "prometheus_remote_write";
+
+/// Keeps the metadata of the family of the metric and the exemplars of its series in the metadata
+/// of the metric.
+pub(crate) fn insert_metadata(
+    metric: &mut Metric,
+    metadata: Option<&proto::MetricMetadata>,
+    exemplars: Vec<proto::Exemplar>,
+) {
+    let value = metric.metadata_mut().value_mut();
+    if let Some(metadata) = metadata {
+        let r#type =
+            proto::MetricType::try_from(metadata.r#type).unwrap_or(proto::MetricType::Unknown);
+        value.insert(path!(ROOT, "type"), r#type.as_str());
+        if !metadata.help.is_empty() {
+            value.insert(path!(ROOT, "help"), metadata.help.clone());
+        }
+        if !metadata.unit.is_empty() {
+            value.insert(path!(ROOT, "unit"), metadata.unit.clone());
+        }
+    }
+
+    if !exemplars.is_empty() {
+        let exemplars = exemplars
+            .into_iter()
+            .map(|exemplar| {
+                let labels = exemplar
+                    .labels
+                    .into_iter()
+                    .map(|label| (label.name.into(), Value::from(label.value)))
+                    .collect::<ObjectMap>();
+                let timestamp = DateTime::from_timestamp_millis(exemplar.timestamp)
+                    .map_or(Value::Null, Value::Timestamp);
+                Value::from(ObjectMap::from([
+                    ("labels".into(), Value::from(labels)),
+                    ("value".into(), Value::from_f64_or_zero(exemplar.value)),
+                    ("timestamp".into(), timestamp),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        value.insert(path!(ROOT, "exemplars"), exemplars);
+    }
+}
+
+/// The metadata of the family of the metric received by the source, if any.
+pub(in crate::sinks::prometheus) fn received_metadata(
+    metric: &Metric,
+) -> Option<proto::MetricMetadata> {
+    let received = metric.metadata().value().get(path!(ROOT))?.as_object()?;
+    let field = |name: &str| {
+        received
+            .get(name)
+            .and_then(Value::as_str)
+            .map(|value| value.into_owned())
+    };
+    let r#type = field("type")
+        .and_then(|r#type| proto::MetricType::from_str_name(&r#type.to_uppercase()))
+        .unwrap_or(proto::MetricType::Unknown);
+    let help = field("help").unwrap_or_default();
+    let unit = field("unit").unwrap_or_default();
+    (r#type != proto::MetricType::Unknown || !help.is_empty() || !unit.is_empty()).then(|| {
+        proto::MetricMetadata {
+            r#type: r#type as i32,
+            metric_family_name: String::new(),
+            help,
+            unit,
+        }
+    })
+}
+
+/// The exemplars of the series of the metric received by the source.
+pub(in crate::sinks::prometheus) fn received_exemplars(metric: &Metric) -> Vec<proto::Exemplar> {
+    let Some(exemplars) = metric
+        .metadata()
+        .value()
+        .get(path!(ROOT, "exemplars"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+    exemplars
+        .iter()
+        .filter_map(Value::as_object)
+        .filter_map(|exemplar| {
+            let value = match exemplar.get("value")? {
+                Value::Float(value) => value.into_inner(),
+                Value::Integer(value) => *value as f64,
+                _ => return None,
+            };
+            let timestamp = exemplar
+                .get("timestamp")
+                .and_then(Value::as_timestamp)
+                .map_or(0, |timestamp| timestamp.timestamp_millis());
+            let labels = exemplar
+                .get("labels")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .map(|(name, value)| proto::Label {
+                    name: name.to_string(),
+                    value: value.to_string_lossy().into_owned(),
+                })
+                .collect();
+            Some(proto::Exemplar {
+                labels,
+                value,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::{MetricKind, MetricValue};
+
+    use super::*;
+
+    #[test]
+    fn round_trips_metadata_and_exemplars() {
+        let metadata = proto::MetricMetadata {
+            r#type: proto::MetricType::Gaugehistogram as i32,
+            metric_family_name: "queue_size".into(),
+            help: "The size of the queue.".into(),
+            unit: String::new(),
+        };
+        let exemplar = proto::Exemplar {
+            labels: vec![proto::Label {
+                name: "trace_id".into(),
+                value: "abc".into(),
+            }],
+            value: 0.5,
+            timestamp: 1395066367600,
+        };
+
+        let mut metric = Metric::new(
+            "queue_size",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        );
+        assert_eq!(received_metadata(&metric), None);
+        assert!(received_exemplars(&metric).is_empty());
+
+        insert_metadata(&mut metric, Some(&metadata), vec![exemplar.clone()]);
+        assert_eq!(
+            received_metadata(&metric),
+            Some(proto::MetricMetadata {
+                metric_family_name: String::new(),
+                ..metadata
+            })
+        );
+        assert_eq!(received_exemplars(&metric), [exemplar]);
+    }
+}
@@ -13,6 +13,7 @@ use crate::sinks::{
 };
 
 mod config;
+pub(crate) mod metadata;
 mod request_builder;
 mod service;
 mod sink;
@@ -14,6 +14,7 @@ pub(crate) struct RemoteWriteEncoder {
     pub(super) default_namespace: Option<String>,
     pub(super) buckets: Vec<f64>,
     pub(super) quantiles: Vec<f64>,
+    pub(super) native_histograms: bool,
 }
 
 impl encoding::Encoder<Vec<Metric>> for RemoteWriteEncoder {
@@ -24,12 +25,13 @@ impl encoding::Encoder<Vec<Metric>> for RemoteWriteEncoder {
     ) -> io::Result<(usize, GroupedCountByteSize)> {
         let mut byte_size = telemetry().create_request_count_byte_size();
 
-        let mut time_series = collector::TimeSeries::new();
+        let mut time_series =
+            collector::TimeSeries::new().with_native_histograms(self.native_histograms);
         let len = input.len();
         for metric in input {
             byte_size.add_event(&metric, metric.estimated_json_encoded_size_of());
 
-            time_series.encode_metric(
+            time_series.encode_metric_with_metadata(
                 self.default_namespace.as_deref(),
                 &self.buckets,
                 &self.quantiles,
@@ -150,6 +150,7 @@ pub(super) struct RemoteWriteSink<S> {
     pub(super) default_namespace: Option<String>,
     pub(super) buckets: Vec<f64>,
     pub(super) quantiles: Vec<f64>,
+    pub(super) native_histograms: bool,
     pub(super) expire_metrics_secs: Option<f64>,
     pub(super) service: S,
 }
@@ -168,6 +169,7 @@ where
                 default_namespace: self.default_namespace.clone(),
                 buckets: self.buckets.clone(),
                 quantiles: self.quantiles.clone(),
+                native_histograms: self.native_histograms,
             },
         };
 
@@ -1,4 +1,8 @@
-use std::{path::Path, time::Duration};
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use futures_util::{FutureExt, TryFutureExt};
 use pulsar::{
@@ -14,6 +18,7 @@ use vector_lib::{
     config::DataType,
     lookup::lookup_v2::OptionalTargetPath,
     sensitive_string::SensitiveString,
+    tls::{MaybeTlsSettings, TlsConfig},
 };
 use vrl::value::Kind;
 
@@ -21,7 +26,10 @@ use crate::{
     schema,
     sinks::{
         prelude::*,
-        pulsar::sink::{PulsarSink, healthcheck},
+        pulsar::{
+            sink::{PulsarSink, healthcheck},
+            transaction::{TransactionAuth, TransactionClient},
+        },
     },
 };
 
@@ -50,11 +58,16 @@ pub struct PulsarSinkConfig {
     ///
     /// If omitted, the key is not sent.
     ///
-    /// Pulsar uses a hash of the key to choose the topic-partition or uses round-robin if the record has no key.
+    /// The key is used to choose the partition of partitioned topics when `partition_routing` is
+    /// set to `key_hash`.
     #[configurable(metadata(docs::examples = "message"))]
     #[configurable(metadata(docs::examples = "my_field"))]
     pub(crate) partition_key_field: Option<OptionalTargetPath>,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) partition_routing: PulsarPartitionRouting,
+
     /// The log field name to use for the Pulsar properties key.
     ///
     /// If omitted, no properties will be written.
@@ -71,6 +84,9 @@ pub struct PulsarSinkConfig {
     #[configurable(derived)]
     pub encoding: EncodingConfig,
 
+    #[configurable(derived)]
+    pub(crate) schema: Option<PulsarSchemaConfig>,
+
     #[configurable(derived)]
     pub(crate) auth: Option<PulsarAuthConfig>,
 
@@ -89,9 +105,65 @@ pub struct PulsarSinkConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub(crate) tls: Option<PulsarTlsOptions>,
+
+    #[configurable(derived)]
+    #[configurable(metadata(docs::advanced))]
+    #[serde(default)]
+    pub(crate) transactional: Option<PulsarTransactionalConfig>,
+}
+
+/// Transactional delivery configuration.
+///
+/// When set, events are produced within Pulsar transactions. Each batch of events, as bounded by
+/// the `batch` settings, is committed atomically once all its messages are persisted, and the
+/// transaction is aborted if any of them fails. Consumers only receive the messages of committed
+/// transactions.
+///
+/// Transactions must be enabled on the brokers with the `transactionCoordinatorEnabled` setting.
+/// Messages for partitioned topics are always sent to a single partition, chosen as configured by
+/// `partition_routing`.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PulsarTransactionalConfig {
+    /// Maximum time a transaction may remain open, in seconds.
+    ///
+    /// Transactions that aren't committed in time are aborted by the transaction coordinator.
+    #[serde(default = "default_transaction_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub(crate) transaction_timeout_secs: NonZeroU64,
+
+    /// Maximum time a batch of events waits for more events before it is produced, in seconds.
+    #[serde(default = "default_transaction_batch_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub(crate) batch_timeout_secs: f64,
+}
+
+impl Default for PulsarTransactionalConfig {
+    fn default() -> Self {
+        Self {
+            transaction_timeout_secs: default_transaction_timeout_secs(),
+            batch_timeout_secs: default_transaction_batch_timeout_secs(),
+        }
+    }
+}
+
+const fn default_transaction_timeout_secs() -> NonZeroU64 {
+    NonZeroU64::new(60).unwrap()
+}
+
+const fn default_transaction_batch_timeout_secs() -> f64 {
+    1.0
 }
 
+/// The size of the batches produced within transactions when they aren't set by `batch`.
+const DEFAULT_TRANSACTION_MAX_EVENTS: NonZeroUsize = NonZeroUsize::new(1000).unwrap();
+const DEFAULT_TRANSACTION_MAX_BYTES: NonZeroUsize = NonZeroUsize::new(1_048_576).unwrap();
+
 /// Event batching behavior.
+///
+/// With transactional delivery, each batch is produced within a transaction, and defaults to at
+/// most 1000 events and 1 MiB.
 #[configurable_component]
 #[derive(Clone, Copy, Debug, Default)]
 pub(crate) struct PulsarBatchConfig {
@@ -108,6 +180,47 @@ pub(crate) struct PulsarBatchConfig {
     pub max_bytes: Option<usize>,
 }
 
+/// How messages are distributed between the partitions of partitioned topics.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PulsarPartitionRouting {
+    /// Sends messages to each partition in turn.
+    #[derivative(Default)]
+    RoundRobin,
+
+    /// Sends messages with the same partition key to the same partition.
+    ///
+    /// The partition is chosen with the same hash of the key as the default hashing scheme of the
+    /// Java client, so that messages are routed consistently with Java producers. Messages without
+    /// a partition key are sent to each partition in turn.
+    KeyHash,
+}
+
+/// Schema registry configuration for events encoded with the `json` codec.
+///
+/// The schema is sent to the broker when the producer is created, which either registers it for
+/// the topic or checks that it's compatible with the schema already registered, depending on the
+/// schema policies of the namespace. Events encoded with the `avro` codec always use the schema of
+/// the codec.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PulsarSchemaConfig {
+    /// The schema of the events, as an Avro schema definition in JSON.
+    #[configurable(metadata(
+        docs::examples = r#"{ "type": "record", "name": "log", "fields": [{ "name": "message", "type": "string" }] }"#
+    ))]
+    pub(crate) definition: String,
+
+    /// Whether to check that events match the schema before sending them.
+    ///
+    /// Events that don't match the schema are dropped, and an error is logged.
+    #[serde(default = "crate::serde::default_true")]
+    pub(crate) validate: bool,
+}
+
 /// Authentication configuration.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -241,13 +354,16 @@ impl Default for PulsarSinkConfig {
             producer_name: None,
             properties_key: None,
             partition_key_field: None,
+            partition_routing: Default::default(),
             batch: Default::default(),
             compression: Default::default(),
             encoding: TextSerializerConfig::default().into(),
+            schema: None,
             auth: None,
             acknowledgements: Default::default(),
             connection_retry_options: None,
             tls: None,
+            transactional: None,
         }
     }
 }
@@ -329,6 +445,85 @@ impl PulsarSinkConfig {
         builder.build().map_err(|e| e.into()).await
     }
 
+    /// Creates the client producing batches of events within transactions.
+    pub(crate) fn create_transaction_client(
+        &self,
+        transactional: &PulsarTransactionalConfig,
+    ) -> crate::Result<TransactionClient> {
+        let auth = match &self.auth {
+            None => None,
+            Some(auth) => match (
+                auth.name.as_ref(),
+                auth.token.as_ref(),
+                auth.oauth2.as_ref(),
+            ) {
+                (Some(name), Some(token), None) => Some(TransactionAuth::basic(
+                    name.clone(),
+                    token.inner().as_bytes().to_vec(),
+                )),
+                (None, None, Some(oauth2)) => Some(TransactionAuth::provider(
+                    OAuth2Authentication::client_credentials(OAuth2Params {
+                        issuer_url: oauth2.issuer_url.clone(),
+                        credentials_url: oauth2.credentials_url.clone(),
+                        audience: oauth2.audience.clone(),
+                        scope: oauth2.scope.clone(),
+                    }),
+                )),
+                _ => {
+                    return Err(
+                        "Invalid auth config: can only specify name and token or oauth2 configuration"
+                            .into(),
+                    );
+                }
+            },
+        };
+
+        let tls = if self.endpoint.starts_with("pulsar+ssl://") {
+            let config = self.tls.as_ref().map(|options| TlsConfig {
+                ca_file: Some(PathBuf::from(&options.ca_file)),
+                verify_certificate: options.verify_certificate,
+                verify_hostname: options.verify_hostname,
+                ..Default::default()
+            });
+            MaybeTlsSettings::tls_client(config.as_ref())?
+        } else {
+            MaybeTlsSettings::Raw(())
+        };
+
+        Ok(TransactionClient::new(
+            &self.endpoint,
+            tls,
+            auth,
+            self.producer_name.clone(),
+            self.build_producer_options().schema,
+            self.compression,
+            self.partition_routing,
+            Duration::from_secs(transactional.transaction_timeout_secs.get()),
+        )?)
+    }
+
+    /// Returns how events are batched into transactions.
+    pub(crate) fn transaction_batch_settings(
+        &self,
+        transactional: &PulsarTransactionalConfig,
+    ) -> crate::Result<BatcherSettings> {
+        let timeout = Duration::try_from_secs_f64(transactional.batch_timeout_secs)
+            .ok()
+            .filter(|timeout| !timeout.is_zero())
+            .ok_or("The `batch_timeout_secs` option must be a positive number of seconds")?;
+        let max_events = self
+            .batch
+            .max_events
+            .and_then(|max_events| NonZeroUsize::new(max_events as usize))
+            .unwrap_or(DEFAULT_TRANSACTION_MAX_EVENTS);
+        let max_bytes = self
+            .batch
+            .max_bytes
+            .and_then(NonZeroUsize::new)
+            .unwrap_or(DEFAULT_TRANSACTION_MAX_BYTES);
+        Ok(BatcherSettings::new(timeout, max_bytes, max_events))
+    }
+
     pub(crate) fn build_producer_options(&self) -> ProducerOptions {
         let mut opts = ProducerOptions {
             encrypted: None,
@@ -364,15 +559,40 @@ impl PulsarSinkConfig {
             }
         }
 
-        if let SerializerConfig::Avro { avro } = self.encoding.config() {
-            opts.schema = Some(proto::Schema {
-                schema_data: avro.schema.as_bytes().into(),
-                r#type: proto::schema::Type::Avro as i32,
-                ..Default::default()
-            });
+        match (self.encoding.config(), &self.schema) {
+            (SerializerConfig::Avro { avro }, _) => {
+                opts.schema = Some(proto::Schema {
+                    schema_data: avro.schema.as_bytes().into(),
+                    r#type: proto::schema::Type::Avro as i32,
+                    ..Default::default()
+                });
+            }
+            (SerializerConfig::Json(_), Some(schema)) => {
+                opts.schema = Some(proto::Schema {
+                    schema_data: schema.definition.as_bytes().into(),
+                    r#type: proto::schema::Type::Json as i32,
+                    ..Default::default()
+                });
+            }
+            _ => {}
         }
         opts
     }
+
+    /// Parses the schema of the `schema` option, if it's set and events are to be validated
+    /// against it.
+    pub(crate) fn build_validation_schema(&self) -> crate::Result<Option<apache_avro::Schema>> {
+        let Some(schema) = &self.schema else {
+            return Ok(None);
+        };
+        if !matches!(self.encoding.config(), SerializerConfig::Json(_)) {
+            return Err("The `schema` option can only be used with the `json` codec".into());
+        }
+
+        let parsed = apache_avro::Schema::parse_str(&schema.definition)
+            .map_err(|error| format!("Invalid schema definition: {error}"))?;
+        Ok(schema.validate.then_some(parsed))
+    }
 }
 
 impl GenerateConfig for PulsarSinkConfig {
@@ -385,12 +605,21 @@ impl GenerateConfig for PulsarSinkConfig {
 #[typetag::serde(name = "pulsar")]
 impl SinkConfig for PulsarSinkConfig {
     async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let client = self
-            .create_pulsar_client()
-            .await
-            .map_err(|e| super::sink::BuildError::CreatePulsarSink { source: e })?;
-
-        let sink = PulsarSink::new(client, self.clone())?;
+        let sink = match &self.transactional {
+            None => {
+                let client = self
+                    .create_pulsar_client()
+                    .await
+                    .map_err(|e| super::sink::BuildError::CreatePulsarSink { source: e })?;
+                PulsarSink::new(client, self.clone())?
+            }
+            Some(transactional) => {
+                let client = self
+                    .create_transaction_client(transactional)
+                    .map_err(|e| super::sink::BuildError::CreatePulsarSink { source: e })?;
+                PulsarSink::new_transactional(client, self.clone())?
+            }
+        };
         let hc = healthcheck(self.clone()).boxed();
 
         Ok((VectorSink::from_event_streamsink(sink), hc))
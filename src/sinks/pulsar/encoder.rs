@@ -1,5 +1,5 @@
 //! Encoding for the `Pulsar` sink.
-use std::io;
+use std::{io, sync::Arc};
 
 use bytes::BytesMut;
 use tokio_util::codec::Encoder as _;
@@ -16,6 +16,8 @@ use crate::{
 pub(super) struct PulsarEncoder {
     pub(super) encoder: crate::codecs::Encoder<()>,
     pub(super) transformer: crate::codecs::Transformer,
+    /// The schema that encoded events are checked against, if any.
+    pub(super) schema: Option<Arc<apache_avro::Schema>>,
 }
 
 impl Encoder<Event> for PulsarEncoder {
@@ -36,8 +38,20 @@ impl Encoder<Event> for PulsarEncoder {
             .map_err(|_| io::Error::other("unable to encode"))?;
 
         let body = body.freeze();
+        if let Some(schema) = &self.schema {
+            validate(&body, schema)?;
+        }
         write_all(writer, 1, body.as_ref())?;
 
         Ok((body.len(), byte_size))
     }
 }
+
+/// Checks that an event encoded as JSON matches an Avro schema.
+pub(super) fn validate(body: &[u8], schema: &apache_avro::Schema) -> io::Result<()> {
+    let json: serde_json::Value = serde_json::from_slice(body).map_err(io::Error::other)?;
+    apache_avro::types::Value::from(json)
+        .resolve(schema)
+        .map(|_| ())
+        .map_err(|error| io::Error::other(format!("event does not match the schema: {error}")))
+}
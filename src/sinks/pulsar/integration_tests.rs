@@ -7,7 +7,7 @@ use crate::{
     sinks::{
         VectorSink,
         pulsar::{
-            config::{PulsarSinkConfig, PulsarTlsOptions},
+            config::{PulsarSinkConfig, PulsarTlsOptions, PulsarTransactionalConfig},
             sink::PulsarSink,
         },
     },
@@ -72,7 +72,13 @@ async fn pulsar_happy_reuse(mut cnf: PulsarSinkConfig) {
         .unwrap();
 
     assert_sink_compliance(&SINK_TAGS, async move {
-        let sink = PulsarSink::new(pulsar, cnf).unwrap();
+        let sink = match &cnf.transactional {
+            None => PulsarSink::new(pulsar, cnf).unwrap(),
+            Some(transactional) => {
+                let client = cnf.create_transaction_client(transactional).unwrap();
+                PulsarSink::new_transactional(client, cnf).unwrap()
+            }
+        };
         let sink = VectorSink::from_event_streamsink(sink);
         sink.run(input_events).await
     })
@@ -115,3 +121,32 @@ async fn pulsar_happy_tls() {
 
     pulsar_happy_reuse(cnf).await
 }
+
+#[tokio::test]
+async fn pulsar_happy_transactional() {
+    let cnf = PulsarSinkConfig {
+        endpoint: pulsar_address("pulsar", 6650),
+        transactional: Some(PulsarTransactionalConfig::default()),
+        // overriden by test
+        ..Default::default()
+    };
+
+    pulsar_happy_reuse(cnf).await
+}
+
+#[tokio::test]
+async fn pulsar_happy_transactional_tls() {
+    let cnf = PulsarSinkConfig {
+        endpoint: pulsar_address("pulsar+ssl", 6651),
+        tls: Some(PulsarTlsOptions {
+            ca_file: TEST_PEM_INTERMEDIATE_CA_PATH.into(),
+            verify_certificate: None,
+            verify_hostname: None,
+        }),
+        transactional: Some(PulsarTransactionalConfig::default()),
+        // overriden by test
+        ..Default::default()
+    };
+
+    pulsar_happy_reuse(cnf).await
+}
@@ -3,6 +3,7 @@ mod encoder;
 mod request_builder;
 mod service;
 mod sink;
+mod transaction;
 pub(super) mod util;
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    sync::Arc,
+    sync::{Arc, Mutex as StdMutex},
     task::{Context, Poll},
 };
 
@@ -12,7 +12,10 @@ use tokio::sync::Mutex;
 
 use crate::{
     internal_events::PulsarSendingError,
-    sinks::{prelude::*, pulsar::request_builder::PulsarMetadata},
+    sinks::{
+        prelude::*,
+        pulsar::{config::PulsarPartitionRouting, request_builder::PulsarMetadata},
+    },
 };
 
 #[derive(Clone)]
@@ -25,11 +28,26 @@ pub(super) struct PulsarRequest {
 pub struct PulsarResponse {
     byte_size: usize,
     event_byte_size: GroupedCountByteSize,
+    event_status: EventStatus,
+}
+
+impl PulsarResponse {
+    pub(super) const fn new(
+        byte_size: usize,
+        event_byte_size: GroupedCountByteSize,
+        event_status: EventStatus,
+    ) -> Self {
+        Self {
+            byte_size,
+            event_byte_size,
+            event_status,
+        }
+    }
 }
 
 impl DriverResponse for PulsarResponse {
     fn event_status(&self) -> EventStatus {
-        EventStatus::Delivered
+        self.event_status
     }
 
     fn events_sent(&self) -> &GroupedCountByteSize {
@@ -61,6 +79,7 @@ pub struct PulsarService<Exe: Executor> {
     // NOTE: the reason for the Mutex here is because the `Producer` from the pulsar crate
     // needs to be `mut`, and the `Service::call()` returns a Future.
     producer: Arc<Mutex<MultiTopicProducer<Exe>>>,
+    router: Option<Arc<PartitionRouter<Exe>>>,
 }
 
 impl<Exe: Executor> PulsarService<Exe> {
@@ -68,7 +87,15 @@ impl<Exe: Executor> PulsarService<Exe> {
         pulsar_client: Pulsar<Exe>,
         producer_options: ProducerOptions,
         producer_name: Option<String>,
+        partition_routing: PulsarPartitionRouting,
     ) -> PulsarService<Exe> {
+        let router = (partition_routing == PulsarPartitionRouting::KeyHash).then(|| {
+            Arc::new(PartitionRouter {
+                client: pulsar_client.clone(),
+                partitions: StdMutex::new(HashMap::new()),
+            })
+        });
+
         let mut builder = pulsar_client.producer().with_options(producer_options);
 
         if let Some(name) = producer_name {
@@ -79,10 +106,66 @@ impl<Exe: Executor> PulsarService<Exe> {
 
         PulsarService {
             producer: Arc::new(Mutex::new(producer)),
+            router,
         }
     }
 }
 
+/// Chooses the partition of partitioned topics from the partition key of messages.
+///
+/// The producer of the pulsar crate sends messages to the partitions of a topic in turn, so
+/// messages are instead sent directly to the partition chosen for their key.
+struct PartitionRouter<Exe: Executor> {
+    client: Pulsar<Exe>,
+    /// The number of partitions of each topic, which is zero for non-partitioned topics.
+    partitions: StdMutex<HashMap<String, u32>>,
+}
+
+impl<Exe: Executor> PartitionRouter<Exe> {
+    async fn route(&self, topic: String, key: &str) -> Result<String, PulsarError> {
+        let cached = self
+            .partitions
+            .lock()
+            .expect("mutex should not be poisoned")
+            .get(&topic)
+            .copied();
+        let partitions = match cached {
+            Some(partitions) => partitions,
+            None => {
+                let partitions = self
+                    .client
+                    .lookup_partitioned_topic_number(topic.clone())
+                    .await?;
+                self.partitions
+                    .lock()
+                    .expect("mutex should not be poisoned")
+                    .insert(topic.clone(), partitions);
+                partitions
+            }
+        };
+
+        Ok(partition_topic(topic, key, partitions))
+    }
+}
+
+/// Returns the name of the partition of a topic to send a message with the given key to.
+pub(super) fn partition_topic(topic: String, key: &str, partitions: u32) -> String {
+    if partitions == 0 {
+        return topic;
+    }
+    let partition = java_string_hash(key) % partitions;
+    format!("{topic}-partition-{partition}")
+}
+
+/// Hashes a key like the `JavaStringHash` hashing scheme of the Java client, which is the hash code
+/// of the key as a Java string with its sign bit cleared.
+pub(super) fn java_string_hash(key: &str) -> u32 {
+    let hash = key.encode_utf16().fold(0i32, |hash, c| {
+        hash.wrapping_mul(31).wrapping_add(i32::from(c))
+    });
+    (hash & i32::MAX) as u32
+}
+
 impl<Exe: Executor> Service<PulsarRequest> for PulsarService<Exe> {
     type Response = PulsarResponse;
     type Error = PulsarError;
@@ -97,6 +180,7 @@ impl<Exe: Executor> Service<PulsarRequest> for PulsarService<Exe> {
 
     fn call(&mut self, request: PulsarRequest) -> Self::Future {
         let producer = Arc::clone(&self.producer);
+        let router = self.router.clone();
         let topic = request.metadata.topic.clone();
         let event_time = request
             .metadata
@@ -120,6 +204,22 @@ impl<Exe: Executor> Service<PulsarRequest> for PulsarService<Exe> {
                 .key
                 .map(|key| String::from_utf8_lossy(&key).to_string());
 
+            let topic = match (&router, &partition_key) {
+                (Some(router), Some(key)) => match router.route(topic, key).await {
+                    Ok(topic) => topic,
+                    Err(e) => {
+                        emit!(PulsarSendingError {
+                            error: Box::new(PulsarError::Custom(
+                                "failed to look up topic partitions".to_string()
+                            )),
+                            count: 1,
+                        });
+                        return Err(e);
+                    }
+                },
+                _ => topic,
+            };
+
             let message = Message {
                 payload: body.as_ref().to_vec(),
                 properties,
@@ -142,12 +242,13 @@ impl<Exe: Executor> Service<PulsarRequest> for PulsarService<Exe> {
 
             match fut {
                 Ok(resp) => match resp.await {
-                    Ok(_) => Ok(PulsarResponse {
+                    Ok(_) => Ok(PulsarResponse::new(
                         byte_size,
-                        event_byte_size: request
+                        request
                             .request_metadata
                             .into_events_estimated_json_encoded_byte_size(),
-                    }),
+                        EventStatus::Delivered,
+                    )),
                     Err(e) => {
                         emit!(PulsarSendingError {
                             error: Box::new(PulsarError::Custom("failed to send".to_string())),
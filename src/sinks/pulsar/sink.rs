@@ -1,15 +1,22 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use pulsar::{Pulsar, TokioExecutor};
 use serde::Serialize;
 use snafu::Snafu;
+use tower::limit::ConcurrencyLimit;
 use vrl::value::KeyString;
 
 use super::{
-    config::PulsarSinkConfig, encoder::PulsarEncoder, request_builder::PulsarRequestBuilder,
-    service::PulsarService, util,
+    config::PulsarSinkConfig,
+    encoder::PulsarEncoder,
+    request_builder::PulsarRequestBuilder,
+    service::PulsarService,
+    transaction::{
+        PulsarRequestSizer, PulsarTransactionRequest, PulsarTransactionalService, TransactionClient,
+    },
+    util,
 };
 use crate::sinks::prelude::*;
 
@@ -25,11 +32,23 @@ pub(crate) enum BuildError {
 pub(crate) struct PulsarSink {
     transformer: Transformer,
     encoder: Encoder<()>,
-    service: PulsarService<TokioExecutor>,
+    schema: Option<Arc<apache_avro::Schema>>,
+    service: PulsarSinkService,
     config: PulsarSinkConfig,
     topic_template: Template,
 }
 
+enum PulsarSinkService {
+    /// Each message is produced on its own.
+    Message(PulsarService<TokioExecutor>),
+
+    /// Batches of messages are produced within transactions, one at a time.
+    Transactional {
+        batch_settings: BatcherSettings,
+        service: ConcurrencyLimit<PulsarTransactionalService>,
+    },
+}
+
 /// Stores the event together with the extracted keys, topics, etc.
 /// This is passed into the `RequestBuilder` which then splits it out into the event
 /// and metadata containing the keys, and metadata.
@@ -83,31 +102,59 @@ impl PulsarSink {
         client: Pulsar<TokioExecutor>,
         config: PulsarSinkConfig,
     ) -> crate::Result<Self> {
-        let producer_opts = config.build_producer_options();
+        let service = PulsarService::new(
+            client,
+            config.build_producer_options(),
+            config.producer_name.clone(),
+            config.partition_routing,
+        );
+        Self::with_service(PulsarSinkService::Message(service), config)
+    }
+
+    /// Creates a sink producing batches of events within transactions.
+    pub(crate) fn new_transactional(
+        client: TransactionClient,
+        config: PulsarSinkConfig,
+    ) -> crate::Result<Self> {
+        let transactional = config
+            .transactional
+            .as_ref()
+            .ok_or("transactional delivery isn't configured")?;
+        let service = PulsarSinkService::Transactional {
+            batch_settings: config.transaction_batch_settings(transactional)?,
+            service: ServiceBuilder::new()
+                .concurrency_limit(1)
+                .service(PulsarTransactionalService::new(client)),
+        };
+        Self::with_service(service, config)
+    }
+
+    fn with_service(service: PulsarSinkService, config: PulsarSinkConfig) -> crate::Result<Self> {
         let transformer = config.encoding.transformer();
         let serializer = config.encoding.build()?;
         let encoder = Encoder::<()>::new(serializer);
-        let service = PulsarService::new(client, producer_opts, config.producer_name.clone());
+        let schema = config.build_validation_schema()?.map(Arc::new);
         let topic_template = config.topic.clone();
 
         Ok(PulsarSink {
             config,
             transformer,
             encoder,
+            schema,
             service,
             topic_template,
         })
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        let service = ServiceBuilder::new().service(self.service);
         let request_builder = PulsarRequestBuilder {
             encoder: PulsarEncoder {
                 transformer: self.transformer.clone(),
                 encoder: self.encoder.clone(),
+                schema: self.schema.clone(),
             },
         };
-        let sink = input
+        let requests = input
             .filter_map(|event| {
                 std::future::ready(util::make_pulsar_event(
                     &self.topic_template,
@@ -120,11 +167,25 @@ impl PulsarSink {
                 request
                     .map_err(|e| error!("Failed to build Pulsar request: {:?}.", e))
                     .ok()
-            })
-            .into_driver(service)
-            .protocol("tcp");
+            });
 
-        sink.run().await
+        match self.service {
+            PulsarSinkService::Message(service) => {
+                requests.into_driver(service).protocol("tcp").run().await
+            }
+            PulsarSinkService::Transactional {
+                batch_settings,
+                service,
+            } => {
+                requests
+                    .batched(batch_settings.as_item_size_config(PulsarRequestSizer))
+                    .map(PulsarTransactionRequest::new)
+                    .into_driver(service)
+                    .protocol("tcp")
+                    .run()
+                    .await
+            }
+        }
     }
 }
 
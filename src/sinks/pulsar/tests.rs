@@ -1,10 +1,15 @@
+use std::time::Duration;
+
 use bytes::Bytes;
-use vector_lib::{configurable::component::GenerateConfig, lookup::lookup_v2::OptionalTargetPath};
+use vector_lib::{
+    codecs::JsonSerializerConfig, configurable::component::GenerateConfig,
+    lookup::lookup_v2::OptionalTargetPath,
+};
 use vrl::value::{ObjectMap, Value};
 
 use crate::{
     event::{Event, LogEvent},
-    sinks::pulsar::config::PulsarSinkConfig,
+    sinks::pulsar::config::{PulsarSchemaConfig, PulsarSinkConfig, PulsarTransactionalConfig},
 };
 
 #[test]
@@ -29,3 +34,75 @@ fn pulsar_get_headers() {
     assert_eq!(properties.get("a-key").unwrap(), "a-value".as_bytes());
     assert_eq!(properties.get("b-key").unwrap(), "b-value".as_bytes());
 }
+
+#[test]
+fn java_string_hash_matches_java() {
+    // Values of `"...".hashCode() & Integer.MAX_VALUE` in Java.
+    assert_eq!(super::service::java_string_hash(""), 0);
+    assert_eq!(super::service::java_string_hash("key"), 106_079);
+    assert_eq!(super::service::java_string_hash("héllo"), 103_094_734);
+    // The hash codes of these keys are negative.
+    assert_eq!(super::service::java_string_hash("polygenelubricants"), 0);
+    assert_eq!(super::service::java_string_hash("user-42"), 2_000_300_992);
+}
+
+#[test]
+fn pulsar_partition_topic() {
+    assert_eq!(
+        super::service::partition_topic("topic".to_string(), "key", 0),
+        "topic"
+    );
+    assert_eq!(
+        super::service::partition_topic("topic".to_string(), "key", 4),
+        "topic-partition-3"
+    );
+}
+
+#[test]
+fn pulsar_validate_schema() {
+    let schema = apache_avro::Schema::parse_str(
+        r#"{ "type": "record", "name": "log", "fields": [{ "name": "message", "type": "string" }] }"#,
+    )
+    .unwrap();
+
+    assert!(super::encoder::validate(br#"{"message":"hello"}"#, &schema).is_ok());
+    assert!(super::encoder::validate(br#"{"message":1}"#, &schema).is_err());
+    assert!(super::encoder::validate(br#"{"other":"hello"}"#, &schema).is_err());
+}
+
+#[test]
+fn pulsar_schema_requires_json_codec() {
+    let mut config = PulsarSinkConfig {
+        schema: Some(PulsarSchemaConfig {
+            definition: r#"{ "type": "string" }"#.to_string(),
+            validate: true,
+        }),
+        ..Default::default()
+    };
+    assert!(config.build_validation_schema().is_err());
+
+    config.encoding = JsonSerializerConfig::default().into();
+    assert!(config.build_validation_schema().unwrap().is_some());
+}
+
+#[test]
+fn pulsar_transaction_batch_settings() {
+    let mut transactional = PulsarTransactionalConfig::default();
+    let mut config = PulsarSinkConfig::default();
+
+    let settings = config.transaction_batch_settings(&transactional).unwrap();
+    assert_eq!(Duration::from_secs(1), settings.timeout);
+    assert_eq!(1000, settings.item_limit);
+    assert_eq!(1_048_576, settings.size_limit);
+
+    config.batch.max_events = Some(10);
+    config.batch.max_bytes = Some(4096);
+    transactional.batch_timeout_secs = 0.5;
+    let settings = config.transaction_batch_settings(&transactional).unwrap();
+    assert_eq!(Duration::from_millis(500), settings.timeout);
+    assert_eq!(10, settings.item_limit);
+    assert_eq!(4096, settings.size_limit);
+
+    transactional.batch_timeout_secs = 0.0;
+    assert!(config.transaction_batch_settings(&transactional).is_err());
+}
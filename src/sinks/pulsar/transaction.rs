@@ -0,0 +1,1364 @@
+//! Produces batches of events within Pulsar transactions.
+//!
+//! The pulsar crate doesn't support transactions, so the parts of the binary protocol they need
+//! are implemented here on top of its framing codec: looking up the brokers owning topics,
+//! connecting to the transaction coordinators, registering the partitions produced to, sending
+//! messages as part of a transaction and ending it.
+//!
+//! Reference: <https://pulsar.apache.org/docs/next/developing-binary-protocol/>
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Write},
+    net::SocketAddr,
+    sync::{Arc, Mutex as StdMutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{SinkExt, future::join_all};
+use pulsar::{
+    Authentication,
+    error::{AuthenticationError, ConnectionError},
+    message::{
+        Codec, Message, Payload,
+        proto::{self, BaseCommand, ServerError, TxnAction, base_command::Type},
+    },
+};
+use snafu::{OptionExt, ResultExt, Snafu};
+use tokio::{
+    net::TcpStream,
+    sync::{Mutex, mpsc, oneshot},
+};
+use tokio_util::codec::Framed;
+use vector_lib::{
+    stream::batcher::limiter::ItemBatchSize,
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError},
+};
+
+use crate::{
+    dns,
+    internal_events::PulsarSendingError,
+    sinks::{
+        prelude::*,
+        pulsar::{
+            config::{PulsarCompression, PulsarPartitionRouting},
+            service::{PulsarRequest, PulsarResponse, partition_topic},
+        },
+    },
+};
+
+/// The protocol version announced to brokers, which is the first one supporting
+/// `TcClientConnectRequest`.
+const PROTOCOL_VERSION: i32 = 19;
+
+/// How long to wait for the response to a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The number of times a lookup can be redirected to another broker.
+const MAX_LOOKUP_REDIRECTS: usize = 20;
+
+/// The topic whose partitions are owned by the transaction coordinators of the cluster.
+const TRANSACTION_COORDINATOR_ASSIGN: &str =
+    "persistent://pulsar/system/transaction_coordinator_assign";
+
+/// Errors that can occur while producing within transactions.
+#[derive(Debug, Snafu)]
+pub(crate) enum TransactionError {
+    #[snafu(display("Invalid Pulsar URL {:?}", url))]
+    InvalidUrl { url: String },
+
+    #[snafu(display("Failed to resolve {}: {}", host, source))]
+    Resolve { host: String, source: dns::DnsError },
+
+    #[snafu(display("No addresses found for {}", host))]
+    NoAddresses { host: String },
+
+    #[snafu(display("Failed to connect to {}: {}", address, source))]
+    Connect {
+        address: SocketAddr,
+        source: TlsError,
+    },
+
+    #[snafu(display("Connection error: {}", source))]
+    Connection { source: ConnectionError },
+
+    #[snafu(display("Authentication failed: {}", source))]
+    Authentication { source: AuthenticationError },
+
+    #[snafu(display("Connection closed"))]
+    Disconnected,
+
+    #[snafu(display("Timed out waiting for the broker"))]
+    Timeout,
+
+    #[snafu(display("Broker error {:?}: {}", error, message))]
+    Server {
+        error: Option<ServerError>,
+        message: String,
+    },
+
+    #[snafu(display("Unexpected response {} received from the broker", response))]
+    UnexpectedResponse { response: String },
+
+    #[snafu(display("Transactions are not enabled on the Pulsar cluster"))]
+    TransactionsDisabled,
+
+    #[snafu(display("Too many redirects looking up topic {}", topic))]
+    TooManyRedirects { topic: String },
+
+    #[snafu(display("Failed to compress message: {}", source))]
+    Compress { source: io::Error },
+}
+
+type Result<T> = std::result::Result<T, TransactionError>;
+
+/// Sizes requests by their payload and key.
+#[derive(Clone, Copy, Default)]
+pub(super) struct PulsarRequestSizer;
+
+impl ItemBatchSize<PulsarRequest> for PulsarRequestSizer {
+    fn size(&self, item: &PulsarRequest) -> usize {
+        item.body.len() + item.metadata.key.as_ref().map_or(0, |key| key.len())
+    }
+}
+
+/// A batch of messages produced within a single transaction.
+pub(super) struct PulsarTransactionRequest {
+    requests: Vec<PulsarRequest>,
+    finalizers: EventFinalizers,
+    request_metadata: RequestMetadata,
+}
+
+impl PulsarTransactionRequest {
+    pub(super) fn new(mut requests: Vec<PulsarRequest>) -> Self {
+        let finalizers =
+            requests
+                .iter_mut()
+                .fold(EventFinalizers::default(), |mut finalizers, request| {
+                    finalizers.merge(request.take_finalizers());
+                    finalizers
+                });
+        let request_metadata = RequestMetadata::from_batch(
+            requests
+                .iter()
+                .map(|request| request.get_metadata().clone()),
+        );
+
+        Self {
+            requests,
+            finalizers,
+            request_metadata,
+        }
+    }
+}
+
+impl Finalizable for PulsarTransactionRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl MetaDescriptive for PulsarTransactionRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.request_metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.request_metadata
+    }
+}
+
+/// Produces each batch of messages within a transaction, which is only committed once all of its
+/// messages have been persisted and is aborted otherwise.
+#[derive(Clone)]
+pub(super) struct PulsarTransactionalService {
+    client: Arc<Mutex<TransactionClient>>,
+}
+
+impl PulsarTransactionalService {
+    pub(super) fn new(client: TransactionClient) -> Self {
+        Self {
+            client: Arc::new(Mutex::new(client)),
+        }
+    }
+}
+
+impl Service<PulsarTransactionRequest> for PulsarTransactionalService {
+    type Response = PulsarResponse;
+    type Error = TransactionError;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PulsarTransactionRequest) -> Self::Future {
+        let client = Arc::clone(&self.client);
+
+        Box::pin(async move {
+            let count = request.requests.len();
+            let byte_size = request
+                .requests
+                .iter()
+                .map(|request| request.body.len())
+                .sum();
+
+            match client.lock().await.produce(&request.requests).await {
+                Ok(()) => Ok(PulsarResponse::new(
+                    byte_size,
+                    request
+                        .request_metadata
+                        .into_events_estimated_json_encoded_byte_size(),
+                    EventStatus::Delivered,
+                )),
+                Err(error) => {
+                    emit!(PulsarSendingError {
+                        error: Box::new(error),
+                        count,
+                    });
+                    // The events of transactions that aren't committed are reported as errored, so
+                    // that sources with end-to-end acknowledgements enabled can deliver them again.
+                    Ok(PulsarResponse::new(
+                        0,
+                        telemetry().create_request_count_byte_size(),
+                        EventStatus::Errored,
+                    ))
+                }
+            }
+        })
+    }
+}
+
+/// The authentication of the connections to the brokers.
+pub(crate) enum TransactionAuth {
+    Basic {
+        name: String,
+        data: Vec<u8>,
+    },
+    Provider {
+        provider: Box<dyn Authentication>,
+        initialized: bool,
+    },
+}
+
+impl TransactionAuth {
+    pub(crate) fn basic(name: String, data: Vec<u8>) -> Self {
+        Self::Basic { name, data }
+    }
+
+    pub(crate) fn provider(provider: Box<dyn Authentication>) -> Self {
+        Self::Provider {
+            provider,
+            initialized: false,
+        }
+    }
+
+    /// Returns the name of the authentication method and the credentials to send.
+    async fn credentials(&mut self) -> Result<(String, Vec<u8>)> {
+        match self {
+            Self::Basic { name, data } => Ok((name.clone(), data.clone())),
+            Self::Provider {
+                provider,
+                initialized,
+            } => {
+                if !*initialized {
+                    provider.initialize().await.context(AuthenticationSnafu)?;
+                    *initialized = true;
+                }
+                let data = provider.auth_data().await.context(AuthenticationSnafu)?;
+                Ok((provider.auth_method_name(), data))
+            }
+        }
+    }
+}
+
+/// The address of a broker, from a `pulsar://` or `pulsar+ssl://` URL.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct BrokerUrl {
+    host: String,
+    port: u16,
+}
+
+impl BrokerUrl {
+    /// Parses a URL, returning the address and whether it uses TLS.
+    fn parse(url: &str) -> Result<(Self, bool)> {
+        let (tls, rest, default_port) = if let Some(rest) = url.strip_prefix("pulsar+ssl://") {
+            (true, rest, 6651)
+        } else if let Some(rest) = url.strip_prefix("pulsar://") {
+            (false, rest, 6650)
+        } else {
+            return InvalidUrlSnafu { url }.fail();
+        };
+
+        let authority = rest.split('/').next().unwrap_or_default();
+        let (host, port) = match authority.rsplit_once(':') {
+            // The colons of IPv6 addresses are enclosed in brackets.
+            Some((host, port)) if !port.ends_with(']') => {
+                (host, port.parse().ok().context(InvalidUrlSnafu { url })?)
+            }
+            _ => (authority, default_port),
+        };
+        if host.is_empty() {
+            return InvalidUrlSnafu { url }.fail();
+        }
+
+        Ok((
+            Self {
+                host: host.to_owned(),
+                port,
+            },
+            tls,
+        ))
+    }
+}
+
+impl std::fmt::Display for BrokerUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+/// Identifies the response to a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum RequestKey {
+    Request(u64),
+    Send { producer_id: u64, sequence_id: u64 },
+}
+
+impl RequestKey {
+    fn of_response(command: &BaseCommand) -> Option<Self> {
+        let request_id = command
+            .success
+            .as_ref()
+            .map(|response| response.request_id)
+            .or(command.error.as_ref().map(|response| response.request_id))
+            .or(command
+                .producer_success
+                .as_ref()
+                .map(|response| response.request_id))
+            .or(command
+                .lookup_topic_response
+                .as_ref()
+                .map(|response| response.request_id))
+            .or(command
+                .partition_metadata_response
+                .as_ref()
+                .map(|response| response.request_id))
+            .or(command
+                .tc_client_connect_response
+                .as_ref()
+                .map(|response| response.request_id))
+            .or(command
+                .new_txn_response
+                .as_ref()
+                .map(|response| response.request_id))
+            .or(command
+                .add_partition_to_txn_response
+                .as_ref()
+                .map(|response| response.request_id))
+            .or(command
+                .end_txn_response
+                .as_ref()
+                .map(|response| response.request_id));
+        if let Some(request_id) = request_id {
+            return Some(Self::Request(request_id));
+        }
+
+        command
+            .send_receipt
+            .as_ref()
+            .map(|receipt| (receipt.producer_id, receipt.sequence_id))
+            .or(command
+                .send_error
+                .as_ref()
+                .map(|error| (error.producer_id, error.sequence_id)))
+            .map(|(producer_id, sequence_id)| Self::Send {
+                producer_id,
+                sequence_id,
+            })
+    }
+}
+
+/// The state of a connection shared with the task reading from it.
+#[derive(Default)]
+struct Shared {
+    pending: StdMutex<HashMap<RequestKey, oneshot::Sender<BaseCommand>>>,
+    /// The producers closed by the broker, which must be created again.
+    closed_producers: StdMutex<HashSet<u64>>,
+}
+
+impl Shared {
+    fn close_producer(&self, producer_id: u64) {
+        self.closed_producers
+            .lock()
+            .expect("mutex should not be poisoned")
+            .insert(producer_id);
+        // Dropping the senders fails the sends waiting for a receipt.
+        self.pending
+            .lock()
+            .expect("mutex should not be poisoned")
+            .retain(|key, _| {
+                !matches!(key, RequestKey::Send { producer_id: id, .. } if *id == producer_id)
+            });
+    }
+}
+
+/// An established connection to a broker, whose responses are read by a background task.
+struct Connection {
+    outgoing: mpsc::UnboundedSender<Message>,
+    shared: Arc<Shared>,
+}
+
+impl Connection {
+    /// Connects to a broker, or to the proxy at `url` forwarding to the broker `proxy_to`.
+    async fn open(
+        url: &BrokerUrl,
+        proxy_to: Option<&BrokerUrl>,
+        tls: &MaybeTlsSettings,
+        auth: Option<&mut TransactionAuth>,
+    ) -> Result<Self> {
+        let ip = dns::Resolver
+            .lookup_ip(url.host.clone())
+            .await
+            .context(ResolveSnafu {
+                host: url.host.clone(),
+            })?
+            .next()
+            .context(NoAddressesSnafu {
+                host: url.host.clone(),
+            })?;
+        let address = SocketAddr::new(ip, url.port);
+        let stream = tls
+            .connect(&url.host, &address)
+            .await
+            .context(ConnectSnafu { address })?;
+        let mut framed = Framed::new(stream, Codec);
+
+        let credentials = match auth {
+            Some(auth) => Some(auth.credentials().await?),
+            None => None,
+        };
+        let (auth_method_name, auth_data) = credentials.clone().unzip();
+        send(
+            &mut framed,
+            BaseCommand {
+                r#type: Type::Connect as i32,
+                connect: Some(proto::CommandConnect {
+                    client_version: proto::client_version(),
+                    auth_method_name,
+                    auth_data,
+                    protocol_version: Some(PROTOCOL_VERSION),
+                    proxy_to_broker_url: proxy_to.map(ToString::to_string),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        loop {
+            let command = tokio::time::timeout(REQUEST_TIMEOUT, framed.next())
+                .await
+                .map_err(|_| TransactionError::Timeout)?
+                .ok_or(TransactionError::Disconnected)?
+                .context(ConnectionSnafu)?
+                .command;
+            if command.connected.is_some() {
+                break;
+            } else if command.auth_challenge.is_some() {
+                send(&mut framed, auth_response(credentials.as_ref())).await?;
+            } else if let Some(error) = command.error {
+                return Err(server_error(Some(error.error), Some(error.message)));
+            } else {
+                return Err(unexpected(&command));
+            }
+        }
+
+        let (outgoing, receiver) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared::default());
+        tokio::spawn(run_connection(
+            framed,
+            receiver,
+            Arc::clone(&shared),
+            credentials,
+        ));
+        Ok(Self { outgoing, shared })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.outgoing.is_closed()
+    }
+
+    fn is_producer_closed(&self, producer_id: u64) -> bool {
+        self.is_closed()
+            || self
+                .shared
+                .closed_producers
+                .lock()
+                .expect("mutex should not be poisoned")
+                .contains(&producer_id)
+    }
+
+    /// Sends a message, returning the response with the given key.
+    async fn request(&self, key: RequestKey, message: Message) -> Result<BaseCommand> {
+        let (sender, receiver) = oneshot::channel();
+        self.shared
+            .pending
+            .lock()
+            .expect("mutex should not be poisoned")
+            .insert(key, sender);
+        // Once the connection fails, the task reading from it stops receiving messages before
+        // dropping the pending senders, so either the message is refused or its sender dropped.
+        if self.outgoing.send(message).is_err() {
+            self.remove_pending(key);
+            return Err(TransactionError::Disconnected);
+        }
+
+        let response = match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(response) => response.map_err(|_| TransactionError::Disconnected)?,
+            Err(_) => {
+                self.remove_pending(key);
+                return Err(TransactionError::Timeout);
+            }
+        };
+        if let Some(error) = response.error {
+            return Err(server_error(Some(error.error), Some(error.message)));
+        }
+        if let Some(error) = response.send_error {
+            return Err(server_error(Some(error.error), Some(error.message)));
+        }
+        Ok(response)
+    }
+
+    fn remove_pending(&self, key: RequestKey) {
+        self.shared
+            .pending
+            .lock()
+            .expect("mutex should not be poisoned")
+            .remove(&key);
+    }
+
+    /// Sends a message without waiting for its response.
+    fn send(&self, command: BaseCommand) {
+        _ = self.outgoing.send(Message {
+            command,
+            payload: None,
+        });
+    }
+}
+
+async fn send(
+    framed: &mut Framed<MaybeTlsStream<TcpStream>, Codec>,
+    command: BaseCommand,
+) -> Result<()> {
+    framed
+        .send(Message {
+            command,
+            payload: None,
+        })
+        .await
+        .context(ConnectionSnafu)
+}
+
+fn auth_response(credentials: Option<&(String, Vec<u8>)>) -> BaseCommand {
+    BaseCommand {
+        r#type: Type::AuthResponse as i32,
+        auth_response: Some(proto::CommandAuthResponse {
+            client_version: Some(proto::client_version()),
+            response: Some(proto::AuthData {
+                auth_method_name: credentials.map(|(name, _)| name.clone()),
+                auth_data: credentials.map(|(_, data)| data.clone()),
+            }),
+            protocol_version: Some(PROTOCOL_VERSION),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Writes the outgoing messages to a connection and dispatches the responses read from it, until
+/// it fails or the [`Connection`] is dropped.
+async fn run_connection(
+    mut framed: Framed<MaybeTlsStream<TcpStream>, Codec>,
+    mut outgoing: mpsc::UnboundedReceiver<Message>,
+    shared: Arc<Shared>,
+    credentials: Option<(String, Vec<u8>)>,
+) {
+    let result = loop {
+        tokio::select! {
+            message = outgoing.recv() => match message {
+                Some(message) => {
+                    if let Err(error) = framed.send(message).await {
+                        break Err(error);
+                    }
+                }
+                None => break Ok(()),
+            },
+            message = framed.next() => {
+                let command = match message {
+                    Some(Ok(message)) => message.command,
+                    Some(Err(error)) => break Err(error),
+                    None => break Err(ConnectionError::Disconnected),
+                };
+                let reply = if command.ping.is_some() {
+                    Some(BaseCommand {
+                        r#type: Type::Pong as i32,
+                        pong: Some(proto::CommandPong {}),
+                        ..Default::default()
+                    })
+                } else if command.auth_challenge.is_some() {
+                    // The credentials are refreshed when reconnecting, once the broker closed the
+                    // connection because they expired.
+                    Some(auth_response(credentials.as_ref()))
+                } else {
+                    if let Some(close) = &command.close_producer {
+                        shared.close_producer(close.producer_id);
+                    } else if let Some(key) = RequestKey::of_response(&command) {
+                        let sender = shared
+                            .pending
+                            .lock()
+                            .expect("mutex should not be poisoned")
+                            .remove(&key);
+                        if let Some(sender) = sender {
+                            _ = sender.send(command);
+                        }
+                    }
+                    None
+                };
+                if let Some(reply) = reply
+                    && let Err(error) = send(&mut framed, reply).await
+                {
+                    debug!(message = "Failed to reply to Pulsar broker.", %error);
+                    break Ok(());
+                }
+            }
+        }
+    };
+
+    if let Err(error) = result {
+        debug!(message = "Pulsar connection closed.", %error);
+    }
+    outgoing.close();
+    shared
+        .pending
+        .lock()
+        .expect("mutex should not be poisoned")
+        .clear();
+}
+
+fn server_error(error: Option<i32>, message: Option<String>) -> TransactionError {
+    TransactionError::Server {
+        error: error.and_then(|error| ServerError::try_from(error).ok()),
+        message: message.unwrap_or_default(),
+    }
+}
+
+fn unexpected(command: &BaseCommand) -> TransactionError {
+    TransactionError::UnexpectedResponse {
+        response: Type::try_from(command.r#type)
+            .map_or_else(|_| command.r#type.to_string(), |kind| format!("{kind:?}")),
+    }
+}
+
+/// Takes a field of a response, failing if the broker sent another kind of response.
+fn take<T>(mut command: BaseCommand, field: fn(&mut BaseCommand) -> Option<T>) -> Result<T> {
+    field(&mut command).ok_or_else(|| unexpected(&command))
+}
+
+/// The ID of a transaction, whose most significant bits are the ID of its coordinator.
+#[derive(Clone, Copy, Debug)]
+struct TxnId {
+    most_bits: u64,
+    least_bits: u64,
+}
+
+/// A producer of a topic partition, with transactions enabled.
+struct Producer {
+    id: u64,
+    name: String,
+    connection: Arc<Connection>,
+    next_sequence_id: u64,
+}
+
+/// A client producing batches of messages within transactions.
+///
+/// Batches are produced one at a time, in a single transaction assigned to one of the
+/// coordinators in turn.
+pub(crate) struct TransactionClient {
+    service_url: BrokerUrl,
+    use_tls: bool,
+    tls: MaybeTlsSettings,
+    auth: Option<TransactionAuth>,
+    producer_name: Option<String>,
+    schema: Option<proto::Schema>,
+    compression: PulsarCompression,
+    partition_routing: PulsarPartitionRouting,
+    transaction_timeout: Duration,
+
+    /// The connections to each broker, directly or through a proxy.
+    connections: HashMap<(BrokerUrl, Option<BrokerUrl>), Arc<Connection>>,
+    /// The connections registered with each transaction coordinator.
+    coordinators: HashMap<u64, Arc<Connection>>,
+    /// The producer of each topic partition.
+    producers: HashMap<String, Producer>,
+    /// The number of partitions of each topic, which is zero for non-partitioned topics.
+    partitions: HashMap<String, u32>,
+    next_request_id: u64,
+    next_producer_id: u64,
+    next_coordinator: u64,
+    next_partition: u32,
+}
+
+impl TransactionClient {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        service_url: &str,
+        tls: MaybeTlsSettings,
+        auth: Option<TransactionAuth>,
+        producer_name: Option<String>,
+        schema: Option<proto::Schema>,
+        compression: PulsarCompression,
+        partition_routing: PulsarPartitionRouting,
+        transaction_timeout: Duration,
+    ) -> Result<Self> {
+        let (service_url, use_tls) = BrokerUrl::parse(service_url)?;
+        Ok(Self {
+            service_url,
+            use_tls,
+            tls,
+            auth,
+            producer_name,
+            schema,
+            compression,
+            partition_routing,
+            transaction_timeout,
+            connections: HashMap::new(),
+            coordinators: HashMap::new(),
+            producers: HashMap::new(),
+            partitions: HashMap::new(),
+            next_request_id: 0,
+            next_producer_id: 0,
+            next_coordinator: 0,
+            next_partition: 0,
+        })
+    }
+
+    fn request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        request_id
+    }
+
+    async fn connection(
+        &mut self,
+        url: &BrokerUrl,
+        proxy_to: Option<&BrokerUrl>,
+    ) -> Result<Arc<Connection>> {
+        let key = (url.clone(), proxy_to.cloned());
+        if let Some(connection) = self.connections.get(&key)
+            && !connection.is_closed()
+        {
+            return Ok(Arc::clone(connection));
+        }
+
+        let connection =
+            Arc::new(Connection::open(url, proxy_to, &self.tls, self.auth.as_mut()).await?);
+        self.connections.insert(key, Arc::clone(&connection));
+        Ok(connection)
+    }
+
+    /// Returns a connection to the broker owning a topic.
+    async fn lookup(&mut self, topic: &str) -> Result<Arc<Connection>> {
+        use proto::command_lookup_topic_response::LookupType;
+
+        let mut url = self.service_url.clone();
+        let mut proxy_to = None;
+        let mut authoritative = false;
+        for _ in 0..MAX_LOOKUP_REDIRECTS {
+            let connection = self.connection(&url, proxy_to.as_ref()).await?;
+            let request_id = self.request_id();
+            let response = connection
+                .request(
+                    RequestKey::Request(request_id),
+                    command(BaseCommand {
+                        r#type: Type::Lookup as i32,
+                        lookup_topic: Some(proto::CommandLookupTopic {
+                            topic: topic.to_owned(),
+                            request_id,
+                            authoritative: Some(authoritative),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                )
+                .await?;
+            let response = take(response, |command| command.lookup_topic_response.take())?;
+
+            let kind = LookupType::try_from(response.response.unwrap_or_default());
+            if !matches!(kind, Ok(LookupType::Redirect | LookupType::Connect)) {
+                return Err(server_error(response.error, response.message));
+            }
+            let broker_url = if self.use_tls {
+                response.broker_service_url_tls
+            } else {
+                response.broker_service_url
+            };
+            let (broker_url, _) = BrokerUrl::parse(broker_url.as_deref().unwrap_or_default())?;
+            if response.proxy_through_service_url == Some(true) {
+                url = self.service_url.clone();
+                proxy_to = Some(broker_url);
+            } else {
+                url = broker_url;
+                proxy_to = None;
+            }
+            authoritative = response.authoritative.unwrap_or(false);
+
+            if matches!(kind, Ok(LookupType::Connect)) {
+                return self.connection(&url, proxy_to.as_ref()).await;
+            }
+        }
+
+        TooManyRedirectsSnafu { topic }.fail()
+    }
+
+    /// Returns the number of partitions of a topic.
+    async fn partitions(&mut self, topic: &str) -> Result<u32> {
+        use proto::command_partitioned_topic_metadata_response::LookupType;
+
+        if let Some(partitions) = self.partitions.get(topic) {
+            return Ok(*partitions);
+        }
+
+        let url = self.service_url.clone();
+        let connection = self.connection(&url, None).await?;
+        let request_id = self.request_id();
+        let response = connection
+            .request(
+                RequestKey::Request(request_id),
+                command(BaseCommand {
+                    r#type: Type::PartitionedMetadata as i32,
+                    partition_metadata: Some(proto::CommandPartitionedTopicMetadata {
+                        topic: topic.to_owned(),
+                        request_id,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let response = take(response, |command| {
+            command.partition_metadata_response.take()
+        })?;
+        if response.response == Some(LookupType::Failed as i32) {
+            return Err(server_error(response.error, response.message));
+        }
+
+        let partitions = response.partitions.unwrap_or_default();
+        self.partitions.insert(topic.to_owned(), partitions);
+        Ok(partitions)
+    }
+
+    /// Returns the ID of the next transaction coordinator in turn, and a connection registered
+    /// with it.
+    async fn coordinator(&mut self) -> Result<(u64, Arc<Connection>)> {
+        let coordinators = self.partitions(TRANSACTION_COORDINATOR_ASSIGN).await?;
+        if coordinators == 0 {
+            return Err(TransactionError::TransactionsDisabled);
+        }
+        let tc_id = self.next_coordinator % u64::from(coordinators);
+        self.next_coordinator = self.next_coordinator.wrapping_add(1);
+
+        if let Some(connection) = self.coordinators.get(&tc_id)
+            && !connection.is_closed()
+        {
+            return Ok((tc_id, Arc::clone(connection)));
+        }
+
+        let connection = self
+            .lookup(&format!(
+                "{TRANSACTION_COORDINATOR_ASSIGN}-partition-{tc_id}"
+            ))
+            .await?;
+        let request_id = self.request_id();
+        let response = connection
+            .request(
+                RequestKey::Request(request_id),
+                command(BaseCommand {
+                    r#type: Type::TcClientConnectRequest as i32,
+                    tc_client_connect_request: Some(proto::CommandTcClientConnectRequest {
+                        request_id,
+                        tc_id,
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let response = take(response, |command| {
+            command.tc_client_connect_response.take()
+        })?;
+        if response.error.is_some() {
+            return Err(server_error(response.error, response.message));
+        }
+
+        self.coordinators.insert(tc_id, Arc::clone(&connection));
+        Ok((tc_id, connection))
+    }
+
+    async fn begin(&mut self) -> Result<(TxnId, Arc<Connection>)> {
+        let (tc_id, connection) = self.coordinator().await?;
+        let request_id = self.request_id();
+        let response = connection
+            .request(
+                RequestKey::Request(request_id),
+                command(BaseCommand {
+                    r#type: Type::NewTxn as i32,
+                    new_txn: Some(proto::CommandNewTxn {
+                        request_id,
+                        // Despite its name, the coordinators read the timeout in milliseconds,
+                        // which is what the Java client sends.
+                        txn_ttl_seconds: Some(self.transaction_timeout.as_millis() as u64),
+                        tc_id: Some(tc_id),
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let response = take(response, |command| command.new_txn_response.take())?;
+        if response.error.is_some() {
+            return Err(server_error(response.error, response.message));
+        }
+
+        let txn_id = TxnId {
+            most_bits: response.txnid_most_bits.unwrap_or_default(),
+            least_bits: response.txnid_least_bits.unwrap_or_default(),
+        };
+        Ok((txn_id, connection))
+    }
+
+    async fn add_partitions(
+        &mut self,
+        coordinator: &Connection,
+        txn_id: TxnId,
+        partitions: Vec<String>,
+    ) -> Result<()> {
+        let request_id = self.request_id();
+        let response = coordinator
+            .request(
+                RequestKey::Request(request_id),
+                command(BaseCommand {
+                    r#type: Type::AddPartitionToTxn as i32,
+                    add_partition_to_txn: Some(proto::CommandAddPartitionToTxn {
+                        request_id,
+                        txnid_least_bits: Some(txn_id.least_bits),
+                        txnid_most_bits: Some(txn_id.most_bits),
+                        partitions,
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let response = take(response, |command| {
+            command.add_partition_to_txn_response.take()
+        })?;
+        if response.error.is_some() {
+            return Err(server_error(response.error, response.message));
+        }
+        Ok(())
+    }
+
+    async fn end(
+        &mut self,
+        coordinator: &Connection,
+        txn_id: TxnId,
+        action: TxnAction,
+    ) -> Result<()> {
+        let request_id = self.request_id();
+        let response = coordinator
+            .request(
+                RequestKey::Request(request_id),
+                command(BaseCommand {
+                    r#type: Type::EndTxn as i32,
+                    end_txn: Some(proto::CommandEndTxn {
+                        request_id,
+                        txnid_least_bits: Some(txn_id.least_bits),
+                        txnid_most_bits: Some(txn_id.most_bits),
+                        txn_action: Some(action as i32),
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let response = take(response, |command| command.end_txn_response.take())?;
+        if response.error.is_some() {
+            return Err(server_error(response.error, response.message));
+        }
+        Ok(())
+    }
+
+    /// Creates the producer of a topic partition, unless it already exists.
+    async fn create_producer(&mut self, topic: &str) -> Result<()> {
+        if let Some(producer) = self.producers.get(topic)
+            && !producer.connection.is_producer_closed(producer.id)
+        {
+            return Ok(());
+        }
+
+        let connection = self.lookup(topic).await?;
+        let producer_id = self.next_producer_id;
+        self.next_producer_id = self.next_producer_id.wrapping_add(1);
+        let request_id = self.request_id();
+        let response = connection
+            .request(
+                RequestKey::Request(request_id),
+                command(BaseCommand {
+                    r#type: Type::Producer as i32,
+                    producer: Some(proto::CommandProducer {
+                        topic: topic.to_owned(),
+                        producer_id,
+                        request_id,
+                        producer_name: self.producer_name.clone(),
+                        user_provided_producer_name: Some(self.producer_name.is_some()),
+                        schema: self.schema.clone(),
+                        txn_enabled: Some(true),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let response = take(response, |command| command.producer_success.take())?;
+
+        self.producers.insert(
+            topic.to_owned(),
+            Producer {
+                id: producer_id,
+                name: response.producer_name,
+                connection,
+                next_sequence_id: response
+                    .last_sequence_id
+                    .map_or(0, |id| (id + 1).max(0) as u64),
+            },
+        );
+        Ok(())
+    }
+
+    /// Closes all producers, so that they are created again with the next batch.
+    fn close_producers(&mut self) {
+        for (_, producer) in std::mem::take(&mut self.producers) {
+            let request_id = self.request_id();
+            producer.connection.send(BaseCommand {
+                r#type: Type::CloseProducer as i32,
+                close_producer: Some(proto::CommandCloseProducer {
+                    producer_id: producer.id,
+                    request_id,
+                }),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Returns the topic partition to send a message to.
+    async fn route(&mut self, request: &PulsarRequest) -> Result<String> {
+        let topic = &request.metadata.topic;
+        let partitions = self.partitions(topic).await?;
+        if partitions == 0 {
+            return Ok(topic.clone());
+        }
+
+        match (&self.partition_routing, &request.metadata.key) {
+            (PulsarPartitionRouting::KeyHash, Some(key)) => Ok(partition_topic(
+                topic.clone(),
+                &String::from_utf8_lossy(key),
+                partitions,
+            )),
+            _ => {
+                let partition = self.next_partition % partitions;
+                self.next_partition = self.next_partition.wrapping_add(1);
+                Ok(format!("{topic}-partition-{partition}"))
+            }
+        }
+    }
+
+    /// Builds the message sending a request to a topic partition within a transaction.
+    fn message(
+        &mut self,
+        topic: &str,
+        request: &PulsarRequest,
+        txn_id: TxnId,
+    ) -> Result<(Arc<Connection>, RequestKey, Message)> {
+        let (compression, data) = compress(self.compression, &request.body)?;
+        let producer = self
+            .producers
+            .get_mut(topic)
+            .expect("producer should have been created");
+        let sequence_id = producer.next_sequence_id;
+        producer.next_sequence_id += 1;
+
+        let metadata = proto::MessageMetadata {
+            producer_name: producer.name.clone(),
+            sequence_id,
+            publish_time: chrono::Utc::now().timestamp_millis() as u64,
+            properties: request
+                .metadata
+                .properties
+                .iter()
+                .flatten()
+                .map(|(key, value)| proto::KeyValue {
+                    key: key.to_string(),
+                    value: String::from_utf8_lossy(value).into_owned(),
+                })
+                .collect(),
+            partition_key: request
+                .metadata
+                .key
+                .as_ref()
+                .map(|key| String::from_utf8_lossy(key).into_owned()),
+            event_time: request
+                .metadata
+                .timestamp_millis
+                .map(|timestamp| timestamp as u64),
+            compression: Some(compression as i32),
+            uncompressed_size: Some(request.body.len() as u32),
+            txnid_least_bits: Some(txn_id.least_bits),
+            txnid_most_bits: Some(txn_id.most_bits),
+            ..Default::default()
+        };
+        let message = Message {
+            command: BaseCommand {
+                r#type: Type::Send as i32,
+                send: Some(proto::CommandSend {
+                    producer_id: producer.id,
+                    sequence_id,
+                    num_messages: Some(1),
+                    txnid_least_bits: Some(txn_id.least_bits),
+                    txnid_most_bits: Some(txn_id.most_bits),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            payload: Some(Payload { metadata, data }),
+        };
+        let key = RequestKey::Send {
+            producer_id: producer.id,
+            sequence_id,
+        };
+        Ok((Arc::clone(&producer.connection), key, message))
+    }
+
+    /// Sends the messages of a batch within a transaction, which is committed once all of them
+    /// are persisted.
+    async fn send_all(
+        &mut self,
+        coordinator: &Connection,
+        txn_id: TxnId,
+        requests: &[PulsarRequest],
+        topics: &[String],
+        partitions: Vec<String>,
+    ) -> Result<()> {
+        self.add_partitions(coordinator, txn_id, partitions).await?;
+
+        let messages = requests
+            .iter()
+            .zip(topics)
+            .map(|(request, topic)| self.message(topic, request, txn_id))
+            .collect::<Result<Vec<_>>>()?;
+        // The messages are pipelined, the broker persisting them in order for each producer.
+        join_all(
+            messages
+                .into_iter()
+                .map(|(connection, key, message)| async move {
+                    connection.request(key, message).await
+                }),
+        )
+        .await
+        .into_iter()
+        .try_for_each(|response| response.map(|_| ()))?;
+
+        self.end(coordinator, txn_id, TxnAction::Commit).await
+    }
+
+    /// Produces a batch of messages within a transaction.
+    pub(super) async fn produce(&mut self, requests: &[PulsarRequest]) -> Result<()> {
+        let mut topics = Vec::with_capacity(requests.len());
+        for request in requests {
+            topics.push(self.route(request).await?);
+        }
+        let mut partitions = topics.clone();
+        partitions.sort_unstable();
+        partitions.dedup();
+        // The producers are created beforehand, so that it doesn't count toward the timeout of
+        // the transaction.
+        for topic in &partitions {
+            if let Err(error) = self.create_producer(topic).await {
+                self.close_producers();
+                return Err(error);
+            }
+        }
+
+        let (txn_id, coordinator) = self.begin().await?;
+        let result = self
+            .send_all(&coordinator, txn_id, requests, &topics, partitions)
+            .await;
+        if result.is_err() {
+            // The sequence of the producers no longer matches what the brokers persisted.
+            self.close_producers();
+            // None of the messages of an aborted transaction are visible to consumers. Aborting
+            // fails if the transaction was already committed or aborted by its coordinator.
+            warn!(message = "Aborting Pulsar transaction.");
+            if let Err(error) = self.end(&coordinator, txn_id, TxnAction::Abort).await {
+                debug!(message = "Failed to abort Pulsar transaction.", %error);
+            }
+        }
+        result
+    }
+}
+
+fn command(command: BaseCommand) -> Message {
+    Message {
+        command,
+        payload: None,
+    }
+}
+
+/// Compresses the payload of a message as the brokers and the other Pulsar clients expect it.
+fn compress(
+    compression: PulsarCompression,
+    data: &[u8],
+) -> Result<(proto::CompressionType, Vec<u8>)> {
+    Ok(match compression {
+        PulsarCompression::None => (proto::CompressionType::None, data.to_vec()),
+        PulsarCompression::Lz4 => (proto::CompressionType::Lz4, lz4_flex::block::compress(data)),
+        PulsarCompression::Zlib => {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data).context(CompressSnafu)?;
+            (
+                proto::CompressionType::Zlib,
+                encoder.finish().context(CompressSnafu)?,
+            )
+        }
+        PulsarCompression::Zstd => (
+            proto::CompressionType::Zstd,
+            zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL).context(CompressSnafu)?,
+        ),
+        PulsarCompression::Snappy => (
+            proto::CompressionType::Snappy,
+            snap::raw::Encoder::new()
+                .compress_vec(data)
+                .map_err(io::Error::other)
+                .context(CompressSnafu)?,
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn parses_broker_urls() {
+        let parse = |url| BrokerUrl::parse(url).map(|(url, tls)| (url.to_string(), tls));
+
+        assert_eq!(
+            ("broker-1:6650".to_owned(), false),
+            parse("pulsar://broker-1:6650").unwrap()
+        );
+        assert_eq!(
+            ("broker-1:6651".to_owned(), true),
+            parse("pulsar+ssl://broker-1").unwrap()
+        );
+        assert_eq!(
+            ("[::1]:6650".to_owned(), false),
+            parse("pulsar://[::1]/").unwrap()
+        );
+        assert_eq!(
+            ("[::1]:7000".to_owned(), false),
+            parse("pulsar://[::1]:7000").unwrap()
+        );
+        assert!(parse("http://broker-1:6650").is_err());
+        assert!(parse("pulsar://broker-1:port").is_err());
+        assert!(parse("pulsar://").is_err());
+    }
+
+    #[test]
+    fn compresses_payloads() {
+        let data = b"a message compressed for Pulsar, a message compressed for Pulsar".repeat(10);
+
+        let (kind, compressed) = compress(PulsarCompression::None, &data).unwrap();
+        assert_eq!(proto::CompressionType::None, kind);
+        assert_eq!(data, compressed);
+
+        let (kind, compressed) = compress(PulsarCompression::Lz4, &data).unwrap();
+        assert_eq!(proto::CompressionType::Lz4, kind);
+        assert_eq!(
+            data,
+            lz4_flex::block::decompress(&compressed, data.len()).unwrap()
+        );
+
+        let (kind, compressed) = compress(PulsarCompression::Zlib, &data).unwrap();
+        assert_eq!(proto::CompressionType::Zlib, kind);
+        let mut decompressed = Vec::new();
+        flate2::read::ZlibDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(data, decompressed);
+
+        let (kind, compressed) = compress(PulsarCompression::Zstd, &data).unwrap();
+        assert_eq!(proto::CompressionType::Zstd, kind);
+        assert_eq!(
+            data,
+            zstd::bulk::decompress(&compressed, data.len()).unwrap()
+        );
+
+        let (kind, compressed) = compress(PulsarCompression::Snappy, &data).unwrap();
+        assert_eq!(proto::CompressionType::Snappy, kind);
+        assert_eq!(
+            data,
+            snap::raw::Decoder::new()
+                .decompress_vec(&compressed)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn dispatches_responses() {
+        let receipt = BaseCommand {
+            r#type: Type::SendReceipt as i32,
+            send_receipt: Some(proto::CommandSendReceipt {
+                producer_id: 3,
+                sequence_id: 7,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(RequestKey::Send {
+                producer_id: 3,
+                sequence_id: 7
+            }),
+            RequestKey::of_response(&receipt)
+        );
+
+        let response = BaseCommand {
+            r#type: Type::NewTxnResponse as i32,
+            new_txn_response: Some(proto::CommandNewTxnResponse {
+                request_id: 12,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(RequestKey::Request(12)),
+            RequestKey::of_response(&response)
+        );
+
+        let ping = BaseCommand {
+            r#type: Type::Ping as i32,
+            ping: Some(proto::CommandPing {}),
+            ..Default::default()
+        };
+        assert_eq!(None, RequestKey::of_response(&ping));
+    }
+}
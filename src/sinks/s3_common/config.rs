@@ -123,6 +123,7 @@ pub struct S3Options {
     ///
     /// If not specified, the compression scheme used dictates this value.
     /// When `compression` is set to `none`, the value `text/x-log` is used.
+    /// When `batch_encoding` is set, the MIME type of the batch encoding is used.
     #[configurable(metadata(docs::examples = "application/gzip"))]
     pub content_type: Option<String>,
 }
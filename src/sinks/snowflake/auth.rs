@@ -0,0 +1,168 @@
+//! Key-pair authentication of the requests to Snowflake.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD, Engine as _};
+use http::HeaderValue;
+use openssl::{
+    error::ErrorStack,
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    sha::sha256,
+    sign::Signer,
+};
+use serde_json::json;
+use snafu::{ResultExt, Snafu};
+
+/// How long the tokens are valid, which Snowflake caps at one hour.
+const TOKEN_LIFETIME: Duration = Duration::from_secs(3600);
+
+/// How long before their expiry the tokens are renewed.
+const TOKEN_RENEWAL_MARGIN: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Snafu)]
+pub(super) enum AuthError {
+    #[snafu(display("Failed to read the private key from {:?}: {}", path, source))]
+    ReadKey {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[snafu(display("Invalid private key: {}", source))]
+    ParseKey { source: ErrorStack },
+    #[snafu(display("Failed to sign the token: {}", source))]
+    Sign { source: ErrorStack },
+}
+
+/// Authenticates the requests with JSON Web Tokens signed by the private key of the user.
+pub(super) struct SnowflakeAuth {
+    key: PKey<Private>,
+    /// The account and the user, as `ACCOUNT.USER`.
+    subject: String,
+    /// The fingerprint of the public key, as `SHA256:<base64 digest>`.
+    fingerprint: String,
+    token: Mutex<Option<(HeaderValue, SystemTime)>>,
+}
+
+impl SnowflakeAuth {
+    pub(super) fn new(
+        account: &str,
+        user: &str,
+        private_key_path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<Self, AuthError> {
+        let pem = std::fs::read(private_key_path).context(ReadKeySnafu {
+            path: private_key_path,
+        })?;
+        let key = match passphrase {
+            Some(passphrase) => PKey::private_key_from_pem_passphrase(&pem, passphrase.as_bytes()),
+            None => PKey::private_key_from_pem(&pem),
+        }
+        .context(ParseKeySnafu)?;
+        Self::from_key(account, user, key)
+    }
+
+    fn from_key(account: &str, user: &str, key: PKey<Private>) -> Result<Self, AuthError> {
+        let public_key = key.public_key_to_der().context(ParseKeySnafu)?;
+        let fingerprint = format!("SHA256:{}", BASE64_STANDARD.encode(sha256(&public_key)));
+
+        // The region and cloud of the legacy account locators aren't part of the account
+        // identifier of the tokens.
+        let account = account.split('.').next().unwrap_or(account);
+        let subject = format!("{}.{}", account.to_uppercase(), user.to_uppercase());
+
+        Ok(Self {
+            key,
+            subject,
+            fingerprint,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// The value of the `Authorization` header of the requests, renewed before it expires.
+    pub(super) fn authorization(&self) -> Result<HeaderValue, AuthError> {
+        let now = SystemTime::now();
+        let mut token = self
+            .token
+            .lock()
+            .expect("Snowflake token mutex is poisoned");
+        if let Some((header, renew_at)) = &*token
+            && now < *renew_at
+        {
+            return Ok(header.clone());
+        }
+
+        let mut header = HeaderValue::from_str(&format!("Bearer {}", self.make_token(now)?))
+            .expect("tokens are valid header values");
+        header.set_sensitive(true);
+        *token = Some((header.clone(), now + TOKEN_LIFETIME - TOKEN_RENEWAL_MARGIN));
+        Ok(header)
+    }
+
+    fn make_token(&self, now: SystemTime) -> Result<String, AuthError> {
+        let issued_at = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let header = json!({ "alg": "RS256", "typ": "JWT" });
+        let claims = json!({
+            "iss": format!("{}.{}", self.subject, self.fingerprint),
+            "sub": self.subject,
+            "iat": issued_at,
+            "exp": issued_at + TOKEN_LIFETIME.as_secs(),
+        });
+        let message = format!(
+            "{}.{}",
+            BASE64_URL_SAFE_NO_PAD.encode(header.to_string()),
+            BASE64_URL_SAFE_NO_PAD.encode(claims.to_string()),
+        );
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key).context(SignSnafu)?;
+        signer.update(message.as_bytes()).context(SignSnafu)?;
+        let signature = signer.sign_to_vec().context(SignSnafu)?;
+
+        Ok(format!(
+            "{message}.{}",
+            BASE64_URL_SAFE_NO_PAD.encode(signature)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{rsa::Rsa, sign::Verifier};
+
+    use super::*;
+
+    #[test]
+    fn signs_tokens() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let auth =
+            SnowflakeAuth::from_key("myorg-account.us-east-1", "vector", key.clone()).unwrap();
+        let token = auth
+            .make_token(UNIX_EPOCH + Duration::from_secs(1000))
+            .unwrap();
+
+        let (message, signature) = token.rsplit_once('.').unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &key).unwrap();
+        verifier.update(message.as_bytes()).unwrap();
+        assert!(
+            verifier
+                .verify(&BASE64_URL_SAFE_NO_PAD.decode(signature).unwrap())
+                .unwrap()
+        );
+
+        let (_, claims) = message.split_once('.').unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_slice(&BASE64_URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+        assert_eq!(
+            claims,
+            json!({
+                "iss": format!("MYORG-ACCOUNT.VECTOR.{}", auth.fingerprint),
+                "sub": "MYORG-ACCOUNT.VECTOR",
+                "iat": 1000,
+                "exp": 4600,
+            })
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Configuration for the `snowflake` sink.
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, atomic::AtomicU64},
+};
+
+use indoc::indoc;
+use tokio::sync::OnceCell;
+use vector_lib::{
+    codecs::{JsonSerializerConfig, NewlineDelimitedEncoderConfig, encoding::Framer},
+    sensitive_string::SensitiveString,
+};
+
+use super::{
+    auth::SnowflakeAuth,
+    request_builder::SnowflakeRequestBuilder,
+    service::{SnowflakeEndpoint, SnowflakeRetryLogic, SnowflakeService},
+    sink::SnowflakeSink,
+};
+use crate::{http::HttpClient, sinks::prelude::*};
+
+// 16MB maximum request size: https://docs.snowflake.com/en/user-guide/snowpipe-streaming/snowpipe-streaming-high-performance-limitations
+const MAX_BATCH_PAYLOAD_SIZE: usize = 16_000_000;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SnowflakeDefaultBatchSettings;
+
+impl SinkBatchSettings for SnowflakeDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = None;
+    const MAX_BYTES: Option<usize> = Some(10_000_000);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+/// Configuration for the `snowflake` sink.
+#[configurable_component(sink(
+    "snowflake",
+    "Stream log events to Snowflake tables with Snowpipe Streaming."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SnowflakeConfig {
+    /// The [account identifier][account_identifier] of the Snowflake account.
+    ///
+    /// [account_identifier]: https://docs.snowflake.com/en/user-guide/admin-account-identifier
+    #[configurable(metadata(docs::examples = "myorg-myaccount"))]
+    pub account: String,
+
+    /// The URL of the Snowflake account.
+    ///
+    /// Defaults to `https://<account>.snowflakecomputing.com`.
+    #[configurable(metadata(
+        docs::examples = "https://myorg-myaccount.privatelink.snowflakecomputing.com"
+    ))]
+    pub endpoint: Option<String>,
+
+    /// The user to authenticate as.
+    ///
+    /// The public key matching `private_key_path` must be assigned to the user.
+    #[configurable(metadata(docs::examples = "VECTOR"))]
+    pub user: String,
+
+    /// The path of the private key of the user, in the PEM format.
+    ///
+    /// The requests are authenticated with JSON Web Tokens signed with this key.
+    #[configurable(metadata(docs::examples = "/etc/vector/snowflake_key.p8"))]
+    pub private_key_path: PathBuf,
+
+    /// The passphrase of the private key, if it is encrypted.
+    #[configurable(metadata(docs::examples = "${SNOWFLAKE_PRIVATE_KEY_PASSPHRASE}"))]
+    pub private_key_passphrase: Option<SensitiveString>,
+
+    /// The database of the tables.
+    #[configurable(metadata(docs::examples = "OBSERVABILITY"))]
+    pub database: String,
+
+    /// The schema of the tables.
+    #[configurable(metadata(docs::examples = "PUBLIC"))]
+    pub schema: String,
+
+    /// The table to write the events to.
+    ///
+    /// The events are written through the default pipe of the table, which matches the fields
+    /// of the events to the columns by name.
+    #[configurable(metadata(docs::examples = "LOGS"))]
+    #[configurable(metadata(docs::examples = "{{ application }}_LOGS"))]
+    pub table: Template,
+
+    /// The name of the channels the events are written with, one per table.
+    ///
+    /// The channels must not be shared with other writers, including other Vector instances.
+    /// Defaults to `vector_<hostname>`.
+    #[configurable(metadata(docs::examples = "vector_aggregator_0"))]
+    pub channel: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    pub encoding: Transformer,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<SnowflakeDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for SnowflakeConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(indoc! {r#"
+            account = "myorg-myaccount"
+            user = "VECTOR"
+            private_key_path = "/etc/vector/snowflake_key.p8"
+            database = "OBSERVABILITY"
+            schema = "PUBLIC"
+            table = "LOGS"
+        "#})
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snowflake")]
+impl SinkConfig for SnowflakeConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let auth = SnowflakeAuth::new(
+            &self.account,
+            &self.user,
+            &self.private_key_path,
+            self.private_key_passphrase
+                .as_ref()
+                .map(|passphrase| passphrase.inner()),
+        )?;
+        let tls_settings = TlsSettings::from_options(self.tls.as_ref())?;
+        let client = HttpClient::new(tls_settings, &cx.proxy)?;
+
+        let account_url = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://{}.snowflakecomputing.com", self.account));
+        let channel = match &self.channel {
+            Some(channel) => channel.clone(),
+            None => format!("vector_{}", crate::get_hostname()?),
+        };
+        let endpoint = Arc::new(SnowflakeEndpoint {
+            client,
+            auth,
+            account_url: account_url.trim_end_matches('/').to_owned(),
+            database: self.database.clone(),
+            schema: self.schema.clone(),
+            channel,
+            ingest_host: OnceCell::new(),
+        });
+
+        let healthcheck = healthcheck(Arc::clone(&endpoint)).boxed();
+
+        let service = ServiceBuilder::new()
+            .settings(self.request.into_settings(), SnowflakeRetryLogic)
+            .service(SnowflakeService::new(endpoint));
+
+        let batch_settings = self
+            .batch
+            .validate()?
+            .limit_max_bytes(MAX_BATCH_PAYLOAD_SIZE)?
+            .into_batcher_settings()?;
+
+        let request_builder = SnowflakeRequestBuilder {
+            encoder: (
+                self.encoding.clone(),
+                Encoder::<Framer>::new(
+                    NewlineDelimitedEncoderConfig.build().into(),
+                    JsonSerializerConfig::default().build().into(),
+                ),
+            ),
+            next_id: AtomicU64::new(0),
+        };
+
+        let sink = SnowflakeSink {
+            batch_settings,
+            service,
+            table: self.table.clone(),
+            request_builder,
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+/// Checks that the requests are authenticated, by looking up the host of the Snowpipe Streaming
+/// API of the account.
+async fn healthcheck(endpoint: Arc<SnowflakeEndpoint>) -> crate::Result<()> {
+    endpoint.ingest_host().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnowflakeConfig>();
+    }
+}
@@ -0,0 +1,16 @@
+//! The `snowflake` sink.
+//!
+//! Streams log events to Snowflake tables with the [Snowpipe Streaming REST API][snowpipe_streaming].
+//! The events of each table are encoded as newline-delimited JSON rows and appended to a channel of
+//! the default pipe of the table. The events are acknowledged once the offset token of their rows
+//! is committed.
+//!
+//! [snowpipe_streaming]: https://docs.snowflake.com/en/user-guide/snowpipe-streaming/snowpipe-streaming-high-performance-overview
+
+mod auth;
+mod config;
+mod request_builder;
+mod service;
+mod sink;
+
+pub use config::SnowflakeConfig;
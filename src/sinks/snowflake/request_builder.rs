@@ -0,0 +1,66 @@
+//! `RequestBuilder` implementation for the `snowflake` sink.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bytes::Bytes;
+use vector_lib::codecs::encoding::Framer;
+
+use super::service::SnowflakeRequest;
+use crate::sinks::prelude::*;
+
+pub(super) struct SnowflakeRequestBuilder {
+    pub(super) encoder: (Transformer, Encoder<Framer>),
+    pub(super) next_id: AtomicU64,
+}
+
+impl RequestBuilder<(String, Vec<Event>)> for SnowflakeRequestBuilder {
+    type Metadata = (String, EventFinalizers);
+    type Events = Vec<Event>;
+    type Encoder = (Transformer, Encoder<Framer>);
+    type Payload = Bytes;
+    type Request = SnowflakeRequest;
+    type Error = std::io::Error;
+
+    fn compression(&self) -> Compression {
+        Compression::None
+    }
+
+    fn encoder(&self) -> &Self::Encoder {
+        &self.encoder
+    }
+
+    fn split_input(
+        &self,
+        input: (String, Vec<Event>),
+    ) -> (Self::Metadata, RequestMetadataBuilder, Self::Events) {
+        let (table, mut events) = input;
+
+        // The requests of a table are sent one at a time, as they append to the same channel.
+        let mut hasher = DefaultHasher::new();
+        table.hash(&mut hasher);
+        let builder =
+            RequestMetadataBuilder::from_events(&events).with_ordering_keys(vec![hasher.finish()]);
+
+        let finalizers = events.take_finalizers();
+        ((table, finalizers), builder, events)
+    }
+
+    fn build_request(
+        &self,
+        metadata: Self::Metadata,
+        request_metadata: RequestMetadata,
+        payload: EncodeResult<Self::Payload>,
+    ) -> Self::Request {
+        let (table, finalizers) = metadata;
+        SnowflakeRequest {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            table,
+            payload: payload.into_payload(),
+            finalizers,
+            metadata: request_metadata,
+        }
+    }
+}
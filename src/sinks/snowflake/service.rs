@@ -0,0 +1,420 @@
+//! Appends the rows to the channels of the Snowpipe Streaming API.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http::{
+    HeaderValue, Method, Request, StatusCode, Uri,
+    header::{AUTHORIZATION, CONTENT_TYPE},
+};
+use hyper::Body;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::json;
+use snafu::{ResultExt, Snafu};
+use tokio::sync::OnceCell;
+
+use super::auth::{AuthError, SnowflakeAuth};
+use crate::{
+    http::{HttpClient, HttpError},
+    internal_events::EndpointBytesSent,
+    sinks::prelude::*,
+};
+
+/// How often the status of a channel is checked while waiting for its rows to be committed.
+const COMMIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The characters escaped in the identifiers of the paths of the requests.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+
+#[derive(Debug, Snafu)]
+pub(super) enum SnowflakeError {
+    #[snafu(display("Failed to authenticate: {}", source))]
+    Auth { source: AuthError },
+    #[snafu(display("Invalid request URI: {}", source))]
+    Uri { source: http::uri::InvalidUri },
+    #[snafu(display("Request failed: {}", source))]
+    Http { source: HttpError },
+    #[snafu(display("Failed to read the response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Snowflake responded with {}: {}", status, body))]
+    Status { status: StatusCode, body: String },
+    #[snafu(display("Invalid response: {}", source))]
+    InvalidResponse { source: serde_json::Error },
+}
+
+impl SnowflakeError {
+    fn is_retriable(&self) -> bool {
+        match self {
+            Self::Http { source } => source.is_retriable(),
+            Self::ReadResponse { .. } => true,
+            Self::Status { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            Self::Auth { .. } | Self::Uri { .. } | Self::InvalidResponse { .. } => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct SnowflakeRequest {
+    /// Identifies the request across its retries.
+    pub(super) id: u64,
+    pub(super) table: String,
+    pub(super) payload: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) metadata: RequestMetadata,
+}
+
+impl Finalizable for SnowflakeRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for SnowflakeRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub(super) struct SnowflakeResponse {
+    events_byte_size: GroupedCountByteSize,
+}
+
+impl DriverResponse for SnowflakeResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        &self.events_byte_size
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct SnowflakeRetryLogic;
+
+impl RetryLogic for SnowflakeRetryLogic {
+    type Error = SnowflakeError;
+    type Request = SnowflakeRequest;
+    type Response = SnowflakeResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_retriable()
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenChannelResponse {
+    next_continuation_token: String,
+    channel_status: ChannelStatus,
+}
+
+#[derive(Deserialize)]
+struct AppendRowsResponse {
+    next_continuation_token: String,
+}
+
+#[derive(Deserialize)]
+struct BulkChannelStatusResponse {
+    channel_statuses: HashMap<String, ChannelStatus>,
+}
+
+#[derive(Deserialize)]
+struct ChannelStatus {
+    last_committed_offset_token: Option<String>,
+}
+
+impl ChannelStatus {
+    /// The offset token of the last committed rows, if they were appended by this sink.
+    fn last_committed_offset(&self) -> Option<u64> {
+        self.last_committed_offset_token
+            .as_deref()
+            .and_then(|token| token.parse().ok())
+    }
+}
+
+/// An open channel of the pipe of a table.
+struct Channel {
+    continuation_token: String,
+    /// The offset token of the last rows appended to the channel.
+    last_offset: u64,
+    /// The request whose rows were appended with the offset token, and aren't known to be
+    /// committed yet.
+    pending: Option<(u64, u64)>,
+}
+
+/// The location of the channels, shared by the clones of the service.
+pub(super) struct SnowflakeEndpoint {
+    pub(super) client: HttpClient,
+    pub(super) auth: SnowflakeAuth,
+    /// The URL of the account, which tells the host of the Snowpipe Streaming API.
+    pub(super) account_url: String,
+    pub(super) database: String,
+    pub(super) schema: String,
+    pub(super) channel: String,
+    pub(super) ingest_host: OnceCell<String>,
+}
+
+impl SnowflakeEndpoint {
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        content_type: &'static str,
+        body: Bytes,
+    ) -> Result<Bytes, SnowflakeError> {
+        let uri: Uri = uri.parse().context(UriSnafu)?;
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, self.auth.authorization().context(AuthSnafu)?)
+            .header(
+                "X-Snowflake-Authorization-Token-Type",
+                HeaderValue::from_static("KEYPAIR_JWT"),
+            )
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .expect("error creating request");
+
+        let response = self.client.send(request).await.context(HttpSnafu)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(ReadResponseSnafu)?;
+        if !parts.status.is_success() {
+            return StatusSnafu {
+                status: parts.status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+            .fail();
+        }
+        Ok(body)
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> Result<T, SnowflakeError> {
+        let body = self
+            .send(method, uri, "application/json", body.to_string().into())
+            .await?;
+        serde_json::from_slice(&body).context(InvalidResponseSnafu)
+    }
+
+    /// The host of the Snowpipe Streaming API for the account.
+    pub(super) async fn ingest_host(&self) -> Result<&str, SnowflakeError> {
+        self.ingest_host
+            .get_or_try_init(|| async {
+                let uri = format!("{}/v2/streaming/hostname", self.account_url);
+                let host = self
+                    .send(Method::GET, &uri, "text/plain", Bytes::new())
+                    .await?;
+                Ok(String::from_utf8_lossy(&host).trim().to_owned())
+            })
+            .await
+            .map(String::as_str)
+    }
+
+    /// The URL of the default pipe of the table under `api`, as Snowflake creates a pipe named
+    /// after every table.
+    async fn pipe_url(&self, api: &str, table: &str) -> Result<String, SnowflakeError> {
+        let pipe = format!("{}-STREAMING", table.to_uppercase());
+        Ok(format!(
+            "https://{}/{api}/databases/{}/schemas/{}/pipes/{}",
+            self.ingest_host().await?,
+            utf8_percent_encode(&self.database, PATH_SEGMENT),
+            utf8_percent_encode(&self.schema, PATH_SEGMENT),
+            utf8_percent_encode(&pipe, PATH_SEGMENT),
+        ))
+    }
+
+    /// Opens the channel of the table, which invalidates the rows appended to it that aren't
+    /// committed yet.
+    async fn open_channel(&self, table: &str) -> Result<OpenChannelResponse, SnowflakeError> {
+        let uri = format!(
+            "{}/channels/{}",
+            self.pipe_url("v2/streaming", table).await?,
+            utf8_percent_encode(&self.channel, PATH_SEGMENT)
+        );
+        let response: OpenChannelResponse = self.send_json(Method::PUT, &uri, json!({})).await?;
+        debug!(
+            message = "Opened a Snowpipe Streaming channel.",
+            table,
+            channel = %self.channel,
+            last_committed_offset_token = ?response.channel_status.last_committed_offset_token,
+        );
+        Ok(response)
+    }
+
+    async fn append_rows(
+        &self,
+        table: &str,
+        continuation_token: &str,
+        offset: u64,
+        rows: Bytes,
+    ) -> Result<AppendRowsResponse, SnowflakeError> {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("continuationToken", continuation_token)
+            .append_pair("offsetToken", &offset.to_string())
+            .finish();
+        let uri = format!(
+            "{}/channels/{}/rows?{query}",
+            self.pipe_url("v2/streaming/data", table).await?,
+            utf8_percent_encode(&self.channel, PATH_SEGMENT),
+        );
+        let body = self
+            .send(Method::POST, &uri, "application/x-ndjson", rows)
+            .await?;
+        serde_json::from_slice(&body).context(InvalidResponseSnafu)
+    }
+
+    async fn last_committed_offset(&self, table: &str) -> Result<Option<u64>, SnowflakeError> {
+        let uri = format!(
+            "{}:bulk-channel-status",
+            self.pipe_url("v2/streaming", table).await?
+        );
+        let mut response: BulkChannelStatusResponse = self
+            .send_json(
+                Method::POST,
+                &uri,
+                json!({ "channel_names": [self.channel] }),
+            )
+            .await?;
+        Ok(response
+            .channel_statuses
+            .remove(&self.channel)
+            .and_then(|status| status.last_committed_offset()))
+    }
+}
+
+/// Appends the rows of the requests to the channel of their table, and waits for them to be
+/// committed before acknowledging their events.
+///
+/// The rows are appended with increasing offset tokens. When a request fails after its rows were
+/// appended, the channel is reopened before the next request: the last committed offset token then
+/// tells whether the rows were committed, and the rows that weren't are discarded, so that the
+/// retries don't write them twice. The requests of a table must be sent one at a time.
+#[derive(Clone)]
+pub(super) struct SnowflakeService {
+    endpoint: Arc<SnowflakeEndpoint>,
+    channels: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<Option<Channel>>>>>>,
+}
+
+impl SnowflakeService {
+    pub(super) fn new(endpoint: Arc<SnowflakeEndpoint>) -> Self {
+        Self {
+            endpoint,
+            channels: Default::default(),
+        }
+    }
+
+    async fn append(&self, request: SnowflakeRequest) -> Result<(), SnowflakeError> {
+        let channel_lock = Arc::clone(
+            self.channels
+                .lock()
+                .expect("Snowflake channels mutex is poisoned")
+                .entry(request.table.clone())
+                .or_default(),
+        );
+        let mut channel = channel_lock.lock().await;
+
+        if channel
+            .as_ref()
+            .is_none_or(|channel| channel.pending.is_some())
+        {
+            let previous = channel.take();
+            let opened = self.endpoint.open_channel(&request.table).await?;
+            let committed = opened.channel_status.last_committed_offset();
+            let last_offset = previous
+                .as_ref()
+                .map_or(0, |previous| previous.last_offset)
+                .max(committed.unwrap_or(0));
+            *channel = Some(Channel {
+                continuation_token: opened.next_continuation_token,
+                last_offset,
+                pending: None,
+            });
+
+            // The rows of a previous attempt of this request were committed after all.
+            if let Some(Channel {
+                pending: Some((id, offset)),
+                ..
+            }) = previous
+                && id == request.id
+                && committed.is_some_and(|committed| committed >= offset)
+            {
+                return Ok(());
+            }
+        }
+        let channel = channel.as_mut().expect("channel should have been opened");
+
+        let offset = channel.last_offset + 1;
+        channel.last_offset = offset;
+        channel.pending = Some((request.id, offset));
+        let byte_size = request.payload.len();
+        let response = self
+            .endpoint
+            .append_rows(
+                &request.table,
+                &channel.continuation_token,
+                offset,
+                request.payload,
+            )
+            .await?;
+        channel.continuation_token = response.next_continuation_token;
+        emit!(EndpointBytesSent {
+            byte_size,
+            protocol: "https",
+            endpoint: self.endpoint.ingest_host().await?,
+        });
+
+        while self
+            .endpoint
+            .last_committed_offset(&request.table)
+            .await?
+            .is_none_or(|committed| committed < offset)
+        {
+            tokio::time::sleep(COMMIT_POLL_INTERVAL).await;
+        }
+        channel.pending = None;
+        Ok(())
+    }
+}
+
+impl Service<SnowflakeRequest> for SnowflakeService {
+    type Response = SnowflakeResponse;
+    type Error = SnowflakeError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Emission of an internal event in case of errors is handled upstream by the caller.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    // Emission of internal events for errors and dropped events is handled upstream by the caller.
+    fn call(&mut self, mut request: SnowflakeRequest) -> Self::Future {
+        let service = self.clone();
+        let metadata = std::mem::take(request.metadata_mut());
+        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
+
+        Box::pin(async move {
+            service.append(request).await?;
+            Ok(SnowflakeResponse { events_byte_size })
+        })
+    }
+}
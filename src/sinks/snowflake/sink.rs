@@ -0,0 +1,81 @@
+//! Implementation of the `snowflake` sink.
+
+use super::{request_builder::SnowflakeRequestBuilder, service::SnowflakeRequest};
+use crate::sinks::prelude::*;
+
+pub(super) struct SnowflakeSink<S> {
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) service: S,
+    pub(super) table: Template,
+    pub(super) request_builder: SnowflakeRequestBuilder,
+}
+
+impl<S> SnowflakeSink<S>
+where
+    S: Service<SnowflakeRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: std::fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+
+        input
+            .batched_partitioned(TablePartitioner { table: self.table }, || {
+                batch_settings.as_byte_size_config()
+            })
+            .filter_map(|(key, batch)| async move { key.map(move |k| (k, batch)) })
+            .request_builder(
+                default_request_builder_concurrency_limit(),
+                self.request_builder,
+            )
+            .filter_map(|request| async {
+                match request {
+                    Err(error) => {
+                        emit!(SinkRequestBuildError { error });
+                        None
+                    }
+                    Ok(req) => Some(req),
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> StreamSink<Event> for SnowflakeSink<S>
+where
+    S: Service<SnowflakeRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: std::fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+/// Partitions the events by the table they are written to.
+struct TablePartitioner {
+    table: Template,
+}
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.table
+            .render_string(item)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
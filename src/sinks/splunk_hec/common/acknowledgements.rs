@@ -3,24 +3,29 @@ use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::{
     collections::HashMap,
-    num::{NonZeroU8, NonZeroU64},
-    sync::Arc,
-    time::Duration,
+    num::{NonZeroU8, NonZeroU16, NonZeroU64},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc::Receiver, oneshot::Sender};
 use vector_lib::{configurable::configurable_component, event::EventStatus};
 
-use super::service::{HttpRequestBuilder, MetadataFields};
+use super::service::{HttpRequestBuilder, MetadataFields, new_channel};
 use crate::sinks::util::Compressor;
 use crate::{
     config::AcknowledgementsConfig,
     http::HttpClient,
     internal_events::{
         SplunkIndexerAcknowledgementAPIError, SplunkIndexerAcknowledgementAckAdded,
-        SplunkIndexerAcknowledgementAcksRemoved,
+        SplunkIndexerAcknowledgementAckDelivered, SplunkIndexerAcknowledgementAcksRemoved,
+        SplunkIndexerAcknowledgementChannelLost, SplunkIndexerAcknowledgementChannelRotated,
     },
 };
 
+/// The code of the error returned by Splunk HEC for channels it doesn't know about, such as
+/// channels removed after being idle or lost by a restarted indexer.
+const INVALID_DATA_CHANNEL_CODE: u64 = 11;
+
 /// Splunk HEC acknowledgement configuration.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -33,6 +38,9 @@ pub struct HecClientAcknowledgementsConfig {
     pub indexer_acknowledgements_enabled: bool,
 
     /// The amount of time to wait between queries to the Splunk HEC indexer acknowledgement endpoint.
+    ///
+    /// While queries don't resolve any acknowledgement ID, the amount of time between them is
+    /// doubled, up to half of `channel_idle_timeout`.
     #[configurable(metadata(docs::type_unit = "seconds"))]
     pub query_interval: NonZeroU8,
 
@@ -44,6 +52,19 @@ pub struct HecClientAcknowledgementsConfig {
     /// Once reached, the sink begins applying backpressure.
     pub max_pending_acks: NonZeroU64,
 
+    /// The maximum number of pending acknowledgements on a single Splunk HEC channel.
+    ///
+    /// Once reached, the following requests are sent on a new channel. This should not exceed the
+    /// `max_number_of_acked_requests_pending_query_per_ack_channel` limit of Splunk.
+    pub max_pending_acks_per_channel: NonZeroU64,
+
+    /// The amount of time after which Splunk HEC removes idle channels and their pending acknowledgements.
+    ///
+    /// This should match the `maxIdleTime` setting of Splunk, used when `ackIdleCleanup` is enabled,
+    /// so that channels with pending acknowledgements are always queried before they are removed.
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub channel_idle_timeout: NonZeroU16,
+
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -60,6 +81,8 @@ impl Default for HecClientAcknowledgementsConfig {
             query_interval: NonZeroU8::new(10).unwrap(),
             retry_limit: NonZeroU8::new(30).unwrap(),
             max_pending_acks: NonZeroU64::new(1_000_000).unwrap(),
+            max_pending_acks_per_channel: NonZeroU64::new(1_000_000).unwrap(),
+            channel_idle_timeout: NonZeroU16::new(600).unwrap(),
             inner: Default::default(),
         }
     }
@@ -75,19 +98,146 @@ pub struct HecAckStatusResponse {
     pub acks: HashMap<u64, bool>,
 }
 
+#[derive(Deserialize, Debug)]
+struct HecErrorResponse {
+    code: u64,
+}
+
 #[derive(Debug)]
 pub enum HecAckApiError {
     ClientBuildRequest,
     ClientParseResponse,
     ClientSendQuery,
+    InvalidChannel,
     ServerSendQuery,
 }
 
+/// The Splunk HEC channels that requests are sent on when indexer acknowledgements are enabled.
+///
+/// Requests are sent on the current channel until it has `max_pending_acks_per_channel` pending
+/// acknowledgements, or until Splunk loses it, for example when an indexer restarts. A new channel
+/// is then used for the following requests, while the previous one is still queried for the
+/// acknowledgements pending on it.
+pub(super) struct HecChannels {
+    max_pending_acks: u64,
+    state: Mutex<ChannelsState>,
+}
+
+struct ChannelsState {
+    current: Arc<str>,
+    pending_acks: HashMap<Arc<str>, u64>,
+}
+
+impl ChannelsState {
+    fn rotate(&mut self, reason: &'static str) {
+        self.current = new_channel().into();
+        emit!(SplunkIndexerAcknowledgementChannelRotated { reason });
+    }
+}
+
+impl HecChannels {
+    pub(super) fn new(channel: &str, max_pending_acks: u64) -> Self {
+        Self {
+            max_pending_acks,
+            state: Mutex::new(ChannelsState {
+                current: channel.into(),
+                pending_acks: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Reserves a pending acknowledgement on the current channel, first rotating it if it is full.
+    pub(super) fn acquire(self: &Arc<Self>) -> ChannelSlot {
+        let mut state = self.state.lock().expect("Poisoned channels lock");
+        let pending_acks = state.pending_acks.get(&state.current).copied();
+        if pending_acks.is_some_and(|pending_acks| pending_acks >= self.max_pending_acks) {
+            state.rotate("full");
+        }
+        let channel = Arc::clone(&state.current);
+        *state.pending_acks.entry(Arc::clone(&channel)).or_default() += 1;
+        ChannelSlot {
+            channels: Arc::clone(self),
+            channel,
+        }
+    }
+
+    /// Stops sending requests on a channel that Splunk lost.
+    fn retire(&self, channel: &str) {
+        let mut state = self.state.lock().expect("Poisoned channels lock");
+        if *state.current == *channel {
+            state.rotate("lost");
+        }
+    }
+
+    fn release(&self, channel: &Arc<str>) {
+        let mut state = self.state.lock().expect("Poisoned channels lock");
+        if let Some(pending_acks) = state.pending_acks.get_mut(channel) {
+            *pending_acks -= 1;
+            if *pending_acks == 0 {
+                state.pending_acks.remove(channel);
+            }
+        }
+    }
+}
+
+/// A pending acknowledgement on a channel, released when dropped.
+pub(super) struct ChannelSlot {
+    channels: Arc<HecChannels>,
+    channel: Arc<str>,
+}
+
+impl ChannelSlot {
+    pub(super) const fn channel(&self) -> &Arc<str> {
+        &self.channel
+    }
+}
+
+impl Drop for ChannelSlot {
+    fn drop(&mut self) {
+        self.channels.release(&self.channel);
+    }
+}
+
+/// The amount of time between ack queries, backing off while queries don't resolve any ack id.
+struct QueryInterval {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl QueryInterval {
+    fn new(config: &HecClientAcknowledgementsConfig) -> Self {
+        let min = Duration::from_secs(config.query_interval.get().into());
+        // Channels must be queried at least twice before Splunk considers them idle.
+        let max = (Duration::from_secs(config.channel_idle_timeout.get().into()) / 2).max(min);
+        Self {
+            min,
+            max,
+            current: min,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.current = self.min;
+    }
+
+    fn back_off(&mut self) {
+        self.current = (self.current * 2).min(self.max);
+    }
+}
+
+struct PendingAck {
+    retries: u8,
+    added_at: Instant,
+    sender: Sender<EventStatus>,
+}
+
 struct HecAckClient {
-    acks: HashMap<u64, (u8, Sender<EventStatus>)>,
+    acks: HashMap<Arc<str>, HashMap<u64, PendingAck>>,
     retry_limit: u8,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
+    channels: Arc<HecChannels>,
 }
 
 impl HecAckClient {
@@ -95,110 +245,165 @@ impl HecAckClient {
         retry_limit: u8,
         client: HttpClient,
         http_request_builder: Arc<HttpRequestBuilder>,
+        channels: Arc<HecChannels>,
     ) -> Self {
         Self {
             acks: HashMap::new(),
             retry_limit,
             client,
             http_request_builder,
+            channels,
         }
     }
 
     /// Adds an ack id to be queried
-    fn add(&mut self, ack_id: u64, ack_event_status_sender: Sender<EventStatus>) {
-        self.acks
-            .insert(ack_id, (self.retry_limit, ack_event_status_sender));
+    fn add(
+        &mut self,
+        channel: Arc<str>,
+        ack_id: u64,
+        ack_event_status_sender: Sender<EventStatus>,
+    ) {
+        let channel_acks = self.acks.entry(Arc::clone(&channel)).or_default();
+        if channel_acks.contains_key(&ack_id) {
+            // Splunk only reuses the ack ids of a channel once it lost it, in which case the
+            // pending ack ids can't be resolved anymore.
+            self.expire_channel(&channel);
+        }
+        self.acks.entry(channel).or_default().insert(
+            ack_id,
+            PendingAck {
+                retries: self.retry_limit,
+                added_at: Instant::now(),
+                sender: ack_event_status_sender,
+            },
+        );
         emit!(SplunkIndexerAcknowledgementAckAdded);
     }
 
-    /// Queries Splunk HEC with stored ack ids and finalizes events that are successfully acked
-    async fn run(&mut self) {
-        let ack_query_body = self.get_ack_query_body();
-        if !ack_query_body.acks.is_empty() {
-            let ack_query_response = self.send_ack_query_request(&ack_query_body).await;
-
-            match ack_query_response {
-                Ok(ack_query_response) => {
-                    debug!(message = "Received ack statuses.", ?ack_query_response);
-                    let acked_ack_ids = ack_query_response
-                        .acks
-                        .iter()
-                        .filter(|&(_ack_id, ack_status)| *ack_status)
-                        .map(|(ack_id, _ack_status)| *ack_id)
-                        .collect::<Vec<u64>>();
-                    self.finalize_delivered_ack_ids(acked_ack_ids.as_slice());
-                    self.expire_ack_ids_with_status(EventStatus::Rejected);
-                }
-                Err(error) => {
-                    match error {
-                        HecAckApiError::ClientParseResponse | HecAckApiError::ClientSendQuery => {
-                            // If we are permanently unable to interact with
-                            // Splunk HEC indexer acknowledgements (e.g. due to
-                            // request/response format changes in future
-                            // versions), log an error and fall back to default
-                            // behavior.
-                            emit!(SplunkIndexerAcknowledgementAPIError {
-                                message: "Unable to use indexer acknowledgements. Acknowledging based on initial 200 OK.",
-                                error,
-                            });
-                            self.finalize_delivered_ack_ids(
-                                self.acks.keys().copied().collect::<Vec<_>>().as_slice(),
-                            );
-                        }
-                        _ => {
-                            emit!(SplunkIndexerAcknowledgementAPIError {
-                                message: "Unable to send acknowledgement query request. Will retry.",
-                                error,
-                            });
-                            self.expire_ack_ids_with_status(EventStatus::Errored);
-                        }
+    /// Whether any ack id is waiting to be queried
+    fn has_pending_acks(&self) -> bool {
+        !self.acks.is_empty()
+    }
+
+    /// Queries Splunk HEC with the stored ack ids of each channel and finalizes events that are
+    /// successfully acked. Returns the number of delivered ack ids.
+    async fn run(&mut self) -> usize {
+        let channels = self.acks.keys().cloned().collect::<Vec<_>>();
+        let mut delivered_count = 0;
+        for channel in channels {
+            delivered_count += self.run_channel(&channel).await;
+        }
+        self.acks.retain(|_, channel_acks| !channel_acks.is_empty());
+        delivered_count
+    }
+
+    async fn run_channel(&mut self, channel: &Arc<str>) -> usize {
+        let ack_query_body = self.get_ack_query_body(channel);
+        if ack_query_body.acks.is_empty() {
+            return 0;
+        }
+
+        let ack_query_response = self.send_ack_query_request(channel, &ack_query_body).await;
+        match ack_query_response {
+            Ok(ack_query_response) => {
+                debug!(message = "Received ack statuses.", ?ack_query_response);
+                let acked_ack_ids = ack_query_response
+                    .acks
+                    .iter()
+                    .filter(|&(_ack_id, ack_status)| *ack_status)
+                    .map(|(ack_id, _ack_status)| *ack_id)
+                    .collect::<Vec<u64>>();
+                let delivered_count =
+                    self.finalize_delivered_ack_ids(channel, acked_ack_ids.as_slice());
+                self.expire_ack_ids_with_status(channel, EventStatus::Rejected);
+                delivered_count
+            }
+            Err(HecAckApiError::InvalidChannel) => {
+                self.expire_channel(channel);
+                0
+            }
+            Err(error) => {
+                match error {
+                    HecAckApiError::ClientParseResponse | HecAckApiError::ClientSendQuery => {
+                        // If we are permanently unable to interact with
+                        // Splunk HEC indexer acknowledgements (e.g. due to
+                        // request/response format changes in future
+                        // versions), log an error and fall back to default
+                        // behavior.
+                        emit!(SplunkIndexerAcknowledgementAPIError {
+                            message: "Unable to use indexer acknowledgements. Acknowledging based on initial 200 OK.",
+                            error,
+                        });
+                        self.finalize_delivered_ack_ids(channel, ack_query_body.acks.as_slice());
+                    }
+                    _ => {
+                        emit!(SplunkIndexerAcknowledgementAPIError {
+                            message: "Unable to send acknowledgement query request. Will retry.",
+                            error,
+                        });
+                        self.expire_ack_ids_with_status(channel, EventStatus::Errored);
                     }
                 }
-            };
+                0
+            }
         }
     }
 
     /// Removes successfully acked ack ids and finalizes associated events
-    fn finalize_delivered_ack_ids(&mut self, ack_ids: &[u64]) {
-        let mut removed_count = 0.0;
-        for ack_id in ack_ids {
-            if let Some((_, ack_event_status_sender)) = self.acks.remove(ack_id) {
-                _ = ack_event_status_sender.send(EventStatus::Delivered);
-                removed_count += 1.0;
-                debug!(message = "Finalized ack id.", ?ack_id);
+    fn finalize_delivered_ack_ids(&mut self, channel: &str, ack_ids: &[u64]) -> usize {
+        let mut removed_count = 0;
+        if let Some(channel_acks) = self.acks.get_mut(channel) {
+            for ack_id in ack_ids {
+                if let Some(pending_ack) = channel_acks.remove(ack_id) {
+                    _ = pending_ack.sender.send(EventStatus::Delivered);
+                    removed_count += 1;
+                    emit!(SplunkIndexerAcknowledgementAckDelivered {
+                        latency: pending_ack.added_at.elapsed(),
+                    });
+                    debug!(message = "Finalized ack id.", ?ack_id);
+                }
             }
         }
         emit!(SplunkIndexerAcknowledgementAcksRemoved {
-            count: removed_count
+            count: removed_count as f64
         });
+        removed_count
     }
 
-    /// Builds an ack query body with stored ack ids
-    fn get_ack_query_body(&mut self) -> HecAckStatusRequest {
+    /// Builds an ack query body with the stored ack ids of a channel
+    fn get_ack_query_body(&mut self, channel: &str) -> HecAckStatusRequest {
         HecAckStatusRequest {
-            acks: self.acks.keys().copied().collect::<Vec<u64>>(),
+            acks: self
+                .acks
+                .get(channel)
+                .map(|channel_acks| channel_acks.keys().copied().collect::<Vec<u64>>())
+                .unwrap_or_default(),
         }
     }
 
-    /// Decrements retry count on all stored ack ids by 1
-    fn decrement_retries(&mut self) {
-        for (retries, _) in self.acks.values_mut() {
-            *retries = retries.checked_sub(1).unwrap_or(0);
+    /// Decrements retry count on the stored ack ids of a channel by 1
+    fn decrement_retries(&mut self, channel: &str) {
+        if let Some(channel_acks) = self.acks.get_mut(channel) {
+            for pending_ack in channel_acks.values_mut() {
+                pending_ack.retries = pending_ack.retries.saturating_sub(1);
+            }
         }
     }
 
-    /// Removes all expired ack ids (those with a retry count of 0) and
-    /// finalizes associated events with the given status
-    fn expire_ack_ids_with_status(&mut self, status: EventStatus) {
-        let expired_ack_ids = self
-            .acks
+    /// Removes all expired ack ids (those with a retry count of 0) of a
+    /// channel and finalizes associated events with the given status
+    fn expire_ack_ids_with_status(&mut self, channel: &str, status: EventStatus) {
+        let Some(channel_acks) = self.acks.get_mut(channel) else {
+            return;
+        };
+        let expired_ack_ids = channel_acks
             .iter()
-            .filter_map(|(ack_id, (retries, _))| (*retries == 0).then_some(*ack_id))
+            .filter_map(|(ack_id, pending_ack)| (pending_ack.retries == 0).then_some(*ack_id))
             .collect::<Vec<_>>();
         let mut removed_count = 0.0;
         for ack_id in expired_ack_ids {
-            if let Some((_, ack_event_status_sender)) = self.acks.remove(&ack_id) {
-                _ = ack_event_status_sender.send(status);
+            if let Some(pending_ack) = channel_acks.remove(&ack_id) {
+                _ = pending_ack.sender.send(status);
                 removed_count += 1.0;
             }
         }
@@ -207,12 +412,28 @@ impl HecAckClient {
         });
     }
 
+    /// Finalizes all the events pending on a channel that Splunk lost with an error status, and
+    /// stops sending requests on it
+    fn expire_channel(&mut self, channel: &str) {
+        let lost_acks = self.acks.remove(channel).unwrap_or_default();
+        let count = lost_acks.len();
+        for pending_ack in lost_acks.into_values() {
+            _ = pending_ack.sender.send(EventStatus::Errored);
+        }
+        emit!(SplunkIndexerAcknowledgementChannelLost { count });
+        emit!(SplunkIndexerAcknowledgementAcksRemoved {
+            count: count as f64
+        });
+        self.channels.retire(channel);
+    }
+
     // Sends an ack status query request to Splunk HEC
     async fn send_ack_query_request(
         &mut self,
+        channel: &str,
         request_body: &HecAckStatusRequest,
     ) -> Result<HecAckStatusResponse, HecAckApiError> {
-        self.decrement_retries();
+        self.decrement_retries(channel);
         let request_body_bytes = crate::serde::json::to_bytes(request_body)
             .map_err(|_| HecAckApiError::ClientBuildRequest)?
             .freeze();
@@ -230,6 +451,7 @@ impl HecAckClient {
                 payload,
                 "/services/collector/ack",
                 None,
+                Some(channel),
                 MetadataFields::default(),
                 false,
             )
@@ -249,37 +471,52 @@ impl HecAckClient {
             serde_json::from_slice::<HecAckStatusResponse>(&response_body)
                 .map_err(|_| HecAckApiError::ClientParseResponse)
         } else if status.is_client_error() {
-            Err(HecAckApiError::ClientSendQuery)
+            let response_body = hyper::body::to_bytes(response.into_body())
+                .await
+                .unwrap_or_default();
+            match serde_json::from_slice::<HecErrorResponse>(&response_body) {
+                Ok(error) if error.code == INVALID_DATA_CHANNEL_CODE => {
+                    Err(HecAckApiError::InvalidChannel)
+                }
+                _ => Err(HecAckApiError::ClientSendQuery),
+            }
         } else {
             Err(HecAckApiError::ServerSendQuery)
         }
     }
 }
 
-pub async fn run_acknowledgements(
-    mut receiver: Receiver<(u64, Sender<EventStatus>)>,
+pub(super) async fn run_acknowledgements(
+    mut receiver: Receiver<(Arc<str>, u64, Sender<EventStatus>)>,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
+    channels: Arc<HecChannels>,
     indexer_acknowledgements: HecClientAcknowledgementsConfig,
 ) {
-    let mut interval = tokio::time::interval(Duration::from_secs(
-        indexer_acknowledgements.query_interval.get() as u64,
-    ));
+    let mut query_interval = QueryInterval::new(&indexer_acknowledgements);
+    let mut next_query = tokio::time::Instant::now();
     let mut ack_client = HecAckClient::new(
         indexer_acknowledgements.retry_limit.get(),
         client,
         http_request_builder,
+        channels,
     );
 
     loop {
         tokio::select! {
-            _ = interval.tick() => {
-                ack_client.run().await;
+            _ = tokio::time::sleep_until(next_query) => {
+                let queried = ack_client.has_pending_acks();
+                if ack_client.run().await > 0 || !queried {
+                    query_interval.reset();
+                } else {
+                    query_interval.back_off();
+                }
+                next_query = tokio::time::Instant::now() + query_interval.current;
             },
             ack_info = receiver.recv() => {
                 match ack_info {
-                    Some((ack_id, tx)) => {
-                        ack_client.add(ack_id, tx);
+                    Some((channel, ack_id, tx)) => {
+                        ack_client.add(channel, ack_id, tx);
                         debug!(message = "Stored ack id.", ?ack_id);
                     },
                     None => break,
@@ -291,13 +528,17 @@ pub async fn run_acknowledgements(
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{
+        num::{NonZeroU8, NonZeroU16},
+        sync::Arc,
+        time::Duration,
+    };
 
     use futures_util::{StreamExt, stream::FuturesUnordered};
     use tokio::sync::oneshot::{self, Receiver};
     use vector_lib::{config::proxy::ProxyConfig, event::EventStatus};
 
-    use super::HecAckClient;
+    use super::{HecAckClient, HecChannels, HecClientAcknowledgementsConfig, QueryInterval};
     use crate::{
         http::HttpClient,
         sinks::{
@@ -308,6 +549,8 @@ mod tests {
         },
     };
 
+    const CHANNEL: &str = "3ed5b2e0-6f1f-4a5e-9f4d-1c8a2f9b7d10";
+
     fn get_ack_client(retry_limit: u8) -> HecAckClient {
         let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
         let http_request_builder = HttpRequestBuilder::new(
@@ -316,7 +559,13 @@ mod tests {
             String::from(""),
             Compression::default(),
         );
-        HecAckClient::new(retry_limit, client, Arc::new(http_request_builder))
+        let channels = Arc::new(HecChannels::new(CHANNEL, 1_000_000));
+        HecAckClient::new(
+            retry_limit,
+            client,
+            Arc::new(http_request_builder),
+            channels,
+        )
     }
 
     fn populate_ack_client(
@@ -326,7 +575,7 @@ mod tests {
         let mut ack_status_rxs = Vec::new();
         for ack_id in ack_ids {
             let (tx, rx) = oneshot::channel();
-            ack_client.add(*ack_id, tx);
+            ack_client.add(CHANNEL.into(), *ack_id, tx);
             ack_status_rxs.push(rx);
         }
         ack_status_rxs
@@ -339,9 +588,10 @@ mod tests {
         _ = populate_ack_client(&mut ack_client, &ack_ids);
         let expected_ack_body = HecAckStatusRequest { acks: ack_ids };
 
-        let mut ack_request_body = ack_client.get_ack_query_body();
+        let mut ack_request_body = ack_client.get_ack_query_body(CHANNEL);
         ack_request_body.acks.sort_unstable();
         assert_eq!(expected_ack_body, ack_request_body);
+        assert!(ack_client.get_ack_query_body("other").acks.is_empty());
     }
 
     #[test]
@@ -350,13 +600,13 @@ mod tests {
         let ack_ids = (0..100).collect::<Vec<u64>>();
         _ = populate_ack_client(&mut ack_client, &ack_ids);
 
-        let mut ack_request_body = ack_client.get_ack_query_body();
+        let mut ack_request_body = ack_client.get_ack_query_body(CHANNEL);
         ack_request_body.acks.sort_unstable();
         assert_eq!(ack_ids, ack_request_body.acks);
-        ack_client.decrement_retries();
-        ack_client.expire_ack_ids_with_status(EventStatus::Rejected);
+        ack_client.decrement_retries(CHANNEL);
+        ack_client.expire_ack_ids_with_status(CHANNEL, EventStatus::Rejected);
 
-        let ack_request_body = ack_client.get_ack_query_body();
+        let ack_request_body = ack_client.get_ack_query_body(CHANNEL);
         assert!(ack_request_body.acks.is_empty())
     }
 
@@ -366,7 +616,8 @@ mod tests {
         let ack_ids = (0..100).collect::<Vec<u64>>();
         let ack_status_rxs = populate_ack_client(&mut ack_client, &ack_ids);
 
-        ack_client.finalize_delivered_ack_ids(ack_ids.as_slice());
+        let delivered_count = ack_client.finalize_delivered_ack_ids(CHANNEL, ack_ids.as_slice());
+        assert_eq!(ack_ids.len(), delivered_count);
         let mut statuses = ack_status_rxs.into_iter().collect::<FuturesUnordered<_>>();
         while let Some(status) = statuses.next().await {
             assert_eq!(EventStatus::Delivered, status.unwrap());
@@ -379,11 +630,62 @@ mod tests {
         let ack_ids = (0..100).collect::<Vec<u64>>();
         let ack_status_rxs = populate_ack_client(&mut ack_client, &ack_ids);
 
-        ack_client.decrement_retries();
-        ack_client.expire_ack_ids_with_status(EventStatus::Rejected);
+        ack_client.decrement_retries(CHANNEL);
+        ack_client.expire_ack_ids_with_status(CHANNEL, EventStatus::Rejected);
         let mut statuses = ack_status_rxs.into_iter().collect::<FuturesUnordered<_>>();
         while let Some(status) = statuses.next().await {
             assert_eq!(EventStatus::Rejected, status.unwrap());
         }
     }
+
+    #[tokio::test]
+    async fn test_reused_ack_id_expires_channel() {
+        let mut ack_client = get_ack_client(1);
+        let lost_rxs = populate_ack_client(&mut ack_client, &[0, 1]);
+        _ = populate_ack_client(&mut ack_client, &[0]);
+
+        for status in lost_rxs {
+            assert_eq!(EventStatus::Errored, status.await.unwrap());
+        }
+        assert_eq!(vec![0], ack_client.get_ack_query_body(CHANNEL).acks);
+        // Requests are no longer sent on the lost channel.
+        assert_ne!(CHANNEL, &**ack_client.channels.acquire().channel());
+    }
+
+    #[test]
+    fn test_channel_rotated_when_full() {
+        let channels = Arc::new(HecChannels::new(CHANNEL, 2));
+
+        let first = channels.acquire();
+        let second = channels.acquire();
+        assert_eq!(CHANNEL, &**first.channel());
+        assert_eq!(CHANNEL, &**second.channel());
+
+        let third = channels.acquire();
+        assert_ne!(CHANNEL, &**third.channel());
+        // Releasing acks of a previous channel doesn't switch back to it.
+        drop(first);
+        drop(second);
+        assert_eq!(third.channel(), channels.acquire().channel());
+    }
+
+    #[test]
+    fn test_query_interval_backoff() {
+        let config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(10).unwrap(),
+            channel_idle_timeout: NonZeroU16::new(60).unwrap(),
+            ..Default::default()
+        };
+        let mut query_interval = QueryInterval::new(&config);
+
+        let mut intervals = Vec::new();
+        for _ in 0..3 {
+            query_interval.back_off();
+            intervals.push(query_interval.current.as_secs());
+        }
+        assert_eq!(vec![20, 30, 30], intervals);
+
+        query_interval.reset();
+        assert_eq!(Duration::from_secs(10), query_interval.current);
+    }
 }
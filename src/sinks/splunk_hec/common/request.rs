@@ -15,6 +15,9 @@ pub struct HecRequest {
     pub metadata: RequestMetadata,
     pub finalizers: EventFinalizers,
     pub passthrough_token: Option<Arc<str>>,
+    /// The Splunk channel the request is sent on, set by the service when indexer
+    /// acknowledgements are enabled.
+    pub channel: Option<Arc<str>>,
     pub index: Option<String>,
     pub source: Option<String>,
     pub sourcetype: Option<String>,
@@ -17,7 +17,7 @@ use vector_lib::{event::EventStatus, request_metadata::MetaDescriptive};
 
 use super::{
     EndpointTarget,
-    acknowledgements::{HecClientAcknowledgementsConfig, run_acknowledgements},
+    acknowledgements::{HecChannels, HecClientAcknowledgementsConfig, run_acknowledgements},
 };
 use crate::{
     http::HttpClient,
@@ -31,7 +31,8 @@ use crate::{
 
 pub struct HecService<S> {
     pub inner: S,
-    ack_finalizer_tx: Option<mpsc::Sender<(u64, oneshot::Sender<EventStatus>)>>,
+    ack_finalizer_tx: Option<mpsc::Sender<(Arc<str>, u64, oneshot::Sender<EventStatus>)>>,
+    channels: Option<Arc<HecChannels>>,
     ack_slots: PollSemaphore,
     current_ack_slot: Option<OwnedSemaphorePermit>,
 }
@@ -56,23 +57,29 @@ where
         indexer_acknowledgements: HecClientAcknowledgementsConfig,
     ) -> Self {
         let max_pending_acks = indexer_acknowledgements.max_pending_acks.get();
-        let tx = if let Some(ack_client) = ack_client {
+        let (tx, channels) = if let Some(ack_client) = ack_client {
             let (tx, rx) = mpsc::channel(128);
+            let channels = Arc::new(HecChannels::new(
+                &http_request_builder.channel,
+                indexer_acknowledgements.max_pending_acks_per_channel.get(),
+            ));
             tokio::spawn(run_acknowledgements(
                 rx,
                 ack_client,
                 Arc::clone(&http_request_builder),
+                Arc::clone(&channels),
                 indexer_acknowledgements,
             ));
-            Some(tx)
+            (Some(tx), Some(channels))
         } else {
-            None
+            (None, None)
         };
 
         let ack_slots = PollSemaphore::new(Arc::new(Semaphore::new(max_pending_acks as usize)));
         Self {
             inner,
             ack_finalizer_tx: tx,
+            channels,
             ack_slots,
             current_ack_slot: None,
         }
@@ -111,6 +118,11 @@ where
     fn call(&mut self, mut req: HecRequest) -> Self::Future {
         let ack_finalizer_tx = self.ack_finalizer_tx.clone();
         let ack_slot = self.current_ack_slot.take();
+        // The channel is kept reserved until the acknowledgement of the request is resolved.
+        let channel_slot = self.channels.as_ref().map(HecChannels::acquire);
+        req.channel = channel_slot
+            .as_ref()
+            .map(|channel_slot| Arc::clone(channel_slot.channel()));
 
         let metadata = std::mem::take(req.metadata_mut());
         let events_count = metadata.event_count();
@@ -125,9 +137,10 @@ where
                     let body = serde_json::from_slice::<HecAckResponseBody>(response.body());
                     match body {
                         Ok(body) => {
-                            if let Some(ack_id) = body.ack_id {
+                            if let Some((ack_id, channel_slot)) = body.ack_id.zip(channel_slot) {
                                 let (tx, rx) = oneshot::channel();
-                                match ack_finalizer_tx.send((ack_id, tx)).await {
+                                let channel = Arc::clone(channel_slot.channel());
+                                match ack_finalizer_tx.send((channel, ack_id, tx)).await {
                                     Ok(_) => rx.await.unwrap_or(EventStatus::Rejected),
                                     // If we cannot send ack ids to the ack client, fall back to default behavior
                                     Err(error) => {
@@ -188,6 +201,11 @@ pub struct HttpRequestBuilder {
     pub channel: String,
 }
 
+/// Generates the identifier of a new Splunk channel.
+pub(super) fn new_channel() -> String {
+    Uuid::new_v4().hyphenated().to_string()
+}
+
 #[derive(Default)]
 pub(super) struct MetadataFields {
     pub(super) source: Option<String>,
@@ -203,7 +221,7 @@ impl HttpRequestBuilder {
         default_token: String,
         compression: Compression,
     ) -> Self {
-        let channel = Uuid::new_v4().hyphenated().to_string();
+        let channel = new_channel();
         Self {
             endpoint,
             endpoint_target,
@@ -218,6 +236,7 @@ impl HttpRequestBuilder {
         body: Bytes,
         path: &str,
         passthrough_token: Option<Arc<str>>,
+        channel: Option<&str>,
         metadata_fields: MetadataFields,
         auto_extract_timestamp: bool,
     ) -> Result<Request<Bytes>, crate::Error> {
@@ -256,7 +275,10 @@ impl HttpRequestBuilder {
                     passthrough_token.unwrap_or_else(|| self.default_token.as_str().into())
                 ),
             )
-            .header("X-Splunk-Request-Channel", self.channel.as_str());
+            .header(
+                "X-Splunk-Request-Channel",
+                channel.unwrap_or(self.channel.as_str()),
+            );
 
         if let Some(ce) = self.compression.content_encoding() {
             builder = builder.header("Content-Encoding", ce);
@@ -269,11 +291,11 @@ impl HttpRequestBuilder {
 #[cfg(test)]
 mod tests {
     use std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         future::poll_fn,
-        num::{NonZeroU8, NonZeroU64, NonZeroUsize},
+        num::{NonZeroU8, NonZeroU16, NonZeroU64, NonZeroUsize},
         sync::{
-            Arc,
+            Arc, Mutex,
             atomic::{AtomicU64, Ordering},
         },
         task::Poll,
@@ -354,6 +376,7 @@ mod tests {
             metadata,
             finalizers: EventFinalizers::default(),
             passthrough_token: None,
+            channel: None,
             index: None,
             source: None,
             sourcetype: None,
@@ -507,6 +530,27 @@ mod tests {
         let acknowledgements_config = HecClientAcknowledgementsConfig {
             query_interval: NonZeroU8::new(1).unwrap(),
             retry_limit: NonZeroU8::new(3).unwrap(),
+            // Query every second without backing off
+            channel_idle_timeout: NonZeroU16::new(2).unwrap(),
+            ..Default::default()
+        };
+        let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
+
+        let request = get_hec_request();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(EventStatus::Errored, response.event_status)
+    }
+
+    #[tokio::test]
+    async fn acknowledgements_channel_lost() {
+        let ack_response = |_: &Request| {
+            ResponseTemplate::new(400)
+                .set_body_json(serde_json::json!({ "text": "Invalid data channel", "code": 11 }))
+        };
+        let mock_server = get_hec_mock_server(true, ack_response).await;
+
+        let acknowledgements_config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(1).unwrap(),
             ..Default::default()
         };
         let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
@@ -516,6 +560,43 @@ mod tests {
         assert_eq!(EventStatus::Errored, response.event_status)
     }
 
+    #[tokio::test]
+    async fn acknowledgements_max_pending_acks_per_channel_reached() {
+        let channels = Arc::new(Mutex::new(HashSet::new()));
+        let mock_server = get_hec_mock_server(true, ack_response_always_succeed).await;
+        let seen_channels = Arc::clone(&channels);
+        Mock::given(method("POST"))
+            .and(path("/services/collector/event"))
+            .respond_with(move |req: &Request| {
+                let channel = req.headers.get("X-Splunk-Request-Channel").unwrap();
+                seen_channels
+                    .lock()
+                    .unwrap()
+                    .insert(channel.to_str().unwrap().to_owned());
+                let ack_id = Some(ACK_ID.fetch_add(1, Ordering::Relaxed));
+                ResponseTemplate::new(200).set_body_json(HecAckResponseBody { ack_id })
+            })
+            // Takes precedence over the usual event endpoint
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+
+        let acknowledgements_config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(1).unwrap(),
+            max_pending_acks_per_channel: NonZeroU64::new(1).unwrap(),
+            ..Default::default()
+        };
+        let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
+
+        let mut responses = FuturesUnordered::new();
+        responses.push(service.ready().await.unwrap().call(get_hec_request()));
+        responses.push(service.ready().await.unwrap().call(get_hec_request()));
+        while let Some(response) = responses.next().await {
+            assert_eq!(EventStatus::Delivered, response.unwrap().event_status)
+        }
+        assert_eq!(2, channels.lock().unwrap().len());
+    }
+
     #[tokio::test]
     async fn acknowledgements_server_changed_event_response_format() {
         let mock_server = get_hec_mock_server(true, ack_response_always_succeed).await;
@@ -569,6 +650,8 @@ mod tests {
         let acknowledgements_config = HecClientAcknowledgementsConfig {
             query_interval: NonZeroU8::new(1).unwrap(),
             retry_limit: NonZeroU8::new(5).unwrap(),
+            // Query every second without backing off
+            channel_idle_timeout: NonZeroU16::new(2).unwrap(),
             // Allow a single pending ack
             max_pending_acks: NonZeroU64::new(1).unwrap(),
             ..Default::default()
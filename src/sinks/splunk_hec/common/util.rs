@@ -72,6 +72,7 @@ pub fn build_http_batch_service(
                         EndpointTarget::Raw => "/services/collector/raw",
                     },
                     req.passthrough_token,
+                    req.channel.as_deref(),
                     MetadataFields {
                         source: req.source,
                         sourcetype: req.sourcetype,
@@ -281,6 +282,7 @@ mod tests {
                 events.clone(),
                 "/services/collector/event",
                 None,
+                None,
                 MetadataFields::default(),
                 false,
             )
@@ -324,6 +326,7 @@ mod tests {
                 events.clone(),
                 "/services/collector/event",
                 None,
+                None,
                 MetadataFields::default(),
                 false,
             )
@@ -370,6 +373,7 @@ mod tests {
                 events,
                 "/services/collector/event",
                 None,
+                None,
                 MetadataFields::default(),
                 false,
             )
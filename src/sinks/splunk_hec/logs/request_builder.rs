@@ -83,6 +83,7 @@ impl RequestBuilder<(Option<Partitioned>, Vec<HecProcessedEvent>)> for HecLogsRe
             body: payload.into_payload(),
             finalizers: hec_metadata.finalizers,
             passthrough_token: hec_metadata.partition,
+            channel: None,
             source: hec_metadata.source,
             sourcetype: hec_metadata.sourcetype,
             index: hec_metadata.index,
@@ -58,6 +58,7 @@ impl RequestBuilder<(Option<Arc<str>>, Vec<HecProcessedEvent>)> for HecMetricsRe
             body: payload.into_payload(),
             finalizers,
             passthrough_token,
+            channel: None,
             index: None,
             source: None,
             sourcetype: None,
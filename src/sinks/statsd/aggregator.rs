@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use async_stream::stream;
+use futures_util::{Stream, StreamExt};
+use tokio::time::{Instant, interval_at};
+use vector_lib::event::Metric;
+
+use crate::sinks::util::buffer::metrics::MetricSet;
+
+/// Aggregates the metrics of each series over the flush period, and then emits them.
+///
+/// The incremental metrics are added to the previous ones of the series: the counters are summed,
+/// the samples of the distributions are merged along with their sample rates, and the values of the
+/// sets are merged. The absolute metrics replace the previous ones.
+pub(super) fn aggregated<'a>(
+    input: impl Stream<Item = Metric> + Send + 'a,
+    flush_period: Duration,
+) -> impl Stream<Item = Metric> + Send + 'a {
+    stream! {
+        let mut input = Box::pin(input);
+        let mut flush = interval_at(Instant::now() + flush_period, flush_period);
+        let mut metrics = MetricSet::default();
+        loop {
+            tokio::select! {
+                _ = flush.tick() => {
+                    for metric in std::mem::take(&mut metrics).into_metrics() {
+                        yield metric;
+                    }
+                }
+                metric = input.next() => match metric {
+                    Some(metric) => metrics.insert_update(metric),
+                    None => break,
+                },
+            }
+        }
+        for metric in metrics.into_metrics() {
+            yield metric;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+    use vector_lib::{
+        event::{MetricKind, MetricValue, StatisticKind},
+        samples,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn aggregates_metrics_of_each_series() {
+        let timer = |samples| MetricValue::Distribution {
+            samples,
+            statistic: StatisticKind::Histogram,
+        };
+        let input = vec![
+            Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            ),
+            Metric::new(
+                "latency",
+                MetricKind::Incremental,
+                timer(samples![2.0 => 10]),
+            ),
+            Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 2.0 },
+            ),
+            Metric::new(
+                "queue",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 5.0 },
+            ),
+            Metric::new(
+                "latency",
+                MetricKind::Incremental,
+                timer(samples![2.0 => 1, 3.0 => 1]),
+            ),
+            Metric::new(
+                "queue",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 3.0 },
+            ),
+        ];
+
+        let mut output = aggregated(stream::iter(input), Duration::from_secs(3600))
+            .collect::<Vec<_>>()
+            .await;
+        output.sort_by(|a, b| a.name().cmp(b.name()));
+        let values = output.iter().map(Metric::value).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                &timer(samples![2.0 => 10, 2.0 => 1, 3.0 => 1]),
+                &MetricValue::Gauge { value: 3.0 },
+                &MetricValue::Counter { value: 3.0 },
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn flushes_on_the_flush_period() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut output = Box::pin(aggregated(
+            UnboundedReceiverStream::new(rx),
+            Duration::from_secs(10),
+        ));
+
+        for value in [1.0, 2.0] {
+            tx.send(Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            ))
+            .unwrap();
+        }
+        let flushed = output.next().await.unwrap();
+        assert_eq!(flushed.value(), &MetricValue::Counter { value: 3.0 });
+
+        drop(tx);
+        assert!(output.next().await.is_none());
+    }
+}
@@ -1,6 +1,10 @@
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use serde_with::serde_as;
 use vector_lib::{
     config::{AcknowledgementsConfig, Input},
     configurable::{component::GenerateConfig, configurable_component},
@@ -51,6 +55,10 @@ pub struct StatsdSinkConfig {
     #[serde(default)]
     pub batch: BatchConfig<StatsdDefaultBatchSettings>,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub aggregation: Option<AggregationConfig>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -60,6 +68,29 @@ pub struct StatsdSinkConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Client-side aggregation of the metrics.
+///
+/// The metrics of each series are aggregated over the flush period before they're sent, which
+/// reduces the number of packets sent for high-volume metrics. The counters are summed, the samples
+/// of the timers, histograms and distributions are merged along with their sample rates, the
+/// values of the sets are merged, and the gauges keep their last value, or the sum of their
+/// changes.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationConfig {
+    /// The interval, in seconds, over which the metrics are aggregated before they're sent.
+    #[serde(default = "default_flush_period_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Flush Interval"))]
+    pub flush_period_secs: Duration,
+}
+
+const fn default_flush_period_secs() -> Duration {
+    Duration::from_secs(10)
+}
+
 /// Socket mode.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -112,6 +143,7 @@ impl GenerateConfig for StatsdSinkConfig {
                 address.port(),
             )),
             batch: Default::default(),
+            aggregation: None,
             acknowledgements: Default::default(),
         })
         .unwrap()
@@ -136,6 +168,8 @@ impl SinkConfig for StatsdSinkConfig {
         let sink = StatsdSink::new(
             StatsdService::from_transport(service),
             batcher_settings,
+            self.aggregation
+                .map(|aggregation| aggregation.flush_period_secs),
             request_builder,
             protocol,
         );
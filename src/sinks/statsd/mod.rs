@@ -1,3 +1,4 @@
+mod aggregator;
 mod batch;
 mod config;
 mod encoder;
@@ -1,4 +1,4 @@
-use std::{fmt, future::ready};
+use std::{fmt, future::ready, time::Duration};
 
 use async_trait::async_trait;
 use futures_util::{
@@ -14,14 +14,15 @@ use vector_lib::{
 };
 
 use super::{
-    batch::StatsdBatchSizer, normalizer::StatsdNormalizer, request_builder::StatsdRequestBuilder,
-    service::StatsdRequest,
+    aggregator::aggregated, batch::StatsdBatchSizer, normalizer::StatsdNormalizer,
+    request_builder::StatsdRequestBuilder, service::StatsdRequest,
 };
 use crate::sinks::util::SinkBuilderExt;
 
 pub(crate) struct StatsdSink<S> {
     service: S,
     batch_settings: BatcherSettings,
+    flush_period: Option<Duration>,
     request_builder: StatsdRequestBuilder,
     protocol: Protocol,
 }
@@ -37,26 +38,37 @@ where
     pub const fn new(
         service: S,
         batch_settings: BatcherSettings,
+        flush_period: Option<Duration>,
         request_builder: StatsdRequestBuilder,
         protocol: Protocol,
     ) -> Self {
         Self {
             service,
             batch_settings,
+            flush_period,
             request_builder,
             protocol,
         }
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        input
+        let metrics = input
             // Convert `Event` to `Metric` so we don't have to deal with constant conversions.
             .filter_map(|event| ready(event.try_into_metric()))
             // Converts absolute counters into incremental counters, but otherwise leaves everything
             // else alone. The encoder will handle the difference in absolute vs incremental for
             // other metric types in type-specific ways i.e. incremental gauge updates use a
             // different syntax, etc.
-            .normalized_with_default::<StatsdNormalizer>()
+            .normalized_with_default::<StatsdNormalizer>();
+
+        // With client-side aggregation, the metrics of each series are only sent once per flush
+        // period.
+        let metrics = match self.flush_period {
+            Some(flush_period) => aggregated(metrics, flush_period).boxed(),
+            None => metrics.boxed(),
+        };
+
+        metrics
             .batched(self.batch_settings.as_item_size_config(StatsdBatchSizer))
             // We build our requests "incrementally", which means that for a single batch of
             // metrics, we might generate N requests to represent all of the metrics in the batch.
@@ -43,6 +43,7 @@ async fn test_send_to_statsd() {
             addr.port(),
         )),
         batch: Default::default(),
+        aggregation: None,
         acknowledgements: Default::default(),
     };
 
@@ -14,13 +14,20 @@ use crate::test_util::stats::{TimeHistogram, TimeWeightedSum};
 use crate::{
     http::HttpError,
     internal_events::{
-        AdaptiveConcurrencyAveragedRtt, AdaptiveConcurrencyInFlight, AdaptiveConcurrencyLimit,
-        AdaptiveConcurrencyLimitData, AdaptiveConcurrencyObservedRtt,
+        AdaptiveConcurrencyAveragedRtt, AdaptiveConcurrencyHealth, AdaptiveConcurrencyInFlight,
+        AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitData, AdaptiveConcurrencyObservedRtt,
     },
     sinks::util::retries::{RetryAction, RetryLogic},
-    stats::{EwmaVar, Mean, MeanVariance},
+    stats::{EwmaDefault, EwmaVar, Mean, MeanVariance},
 };
 
+/// The weighting of each response in the health score of the service.
+const HEALTH_EWMA_ALPHA: f64 = 0.1;
+
+/// The lowest health score, so that the load of an unhealthy service stays finite and it is still
+/// picked when all the others are busy.
+const MIN_HEALTH: f64 = 0.05;
+
 /// Shared class for `tokio::sync::Semaphore` that manages adjusting the
 /// semaphore size and other associated data.
 #[derive(Clone)]
@@ -37,6 +44,7 @@ pub(super) struct Controller<L> {
     in_flight: Registered<AdaptiveConcurrencyInFlight>,
     observed_rtt: Registered<AdaptiveConcurrencyObservedRtt>,
     averaged_rtt: Registered<AdaptiveConcurrencyAveragedRtt>,
+    health: Registered<AdaptiveConcurrencyHealth>,
 }
 
 #[derive(Debug)]
@@ -48,6 +56,9 @@ pub(super) struct Inner {
     current_rtt: Mean,
     had_back_pressure: bool,
     reached_limit: bool,
+    /// The fraction of the recent responses that were processed successfully and in a time
+    /// within the expected deviation of the past RTT.
+    health: EwmaDefault,
 }
 
 #[cfg(test)]
@@ -64,6 +75,7 @@ impl<L> Controller<L> {
         concurrency: Option<usize>,
         settings: AdaptiveConcurrencySettings,
         logic: L,
+        endpoint: Option<String>,
     ) -> Self {
         // If a `concurrency` is specified, it becomes both the
         // current limit and the maximum, effectively bypassing all the
@@ -83,26 +95,49 @@ impl<L> Controller<L> {
                 current_rtt: Default::default(),
                 had_back_pressure: false,
                 reached_limit: false,
+                health: EwmaDefault::new(HEALTH_EWMA_ALPHA, 1.0),
             })),
             #[cfg(test)]
             stats: Arc::new(Mutex::new(ControllerStatistics::default())),
-            limit: register!(AdaptiveConcurrencyLimit),
-            in_flight: register!(AdaptiveConcurrencyInFlight),
-            observed_rtt: register!(AdaptiveConcurrencyObservedRtt),
-            averaged_rtt: register!(AdaptiveConcurrencyAveragedRtt),
+            limit: register!(AdaptiveConcurrencyLimit {
+                endpoint: endpoint.clone()
+            }),
+            in_flight: register!(AdaptiveConcurrencyInFlight {
+                endpoint: endpoint.clone()
+            }),
+            observed_rtt: register!(AdaptiveConcurrencyObservedRtt {
+                endpoint: endpoint.clone()
+            }),
+            averaged_rtt: register!(AdaptiveConcurrencyAveragedRtt {
+                endpoint: endpoint.clone()
+            }),
+            health: register!(AdaptiveConcurrencyHealth { endpoint }),
         }
     }
 
     /// An estimate of current load on service managed by this controller.
     ///
-    /// 0.0 is no load, while 1.0 is max load.
+    /// 0.0 is no load, while 1.0 is max load for a healthy service. The load of a service whose
+    /// responses are slow or failing is scaled up by its health score, so that balancers prefer
+    /// healthier services.
     pub(super) fn load(&self) -> f64 {
         let inner = self.inner.lock().expect("Controller mutex is poisoned");
-        if inner.current_limit > 0 {
+        let load = if inner.current_limit > 0 {
             inner.in_flight as f64 / inner.current_limit as f64
         } else {
             1.0
-        }
+        };
+        load / inner.health.average().max(MIN_HEALTH)
+    }
+
+    /// The health score of the service managed by this controller.
+    ///
+    /// 1.0 is fully healthy, while 0.0 is every recent response being back pressure or slower
+    /// than expected.
+    #[cfg(test)]
+    pub(super) fn health(&self) -> f64 {
+        let inner = self.inner.lock().expect("Controller mutex is poisoned");
+        inner.health.average()
     }
 
     pub(super) fn acquire(
@@ -162,6 +197,17 @@ impl<L> Controller<L> {
         if use_rtt {
             inner.current_rtt.update(rtt);
         }
+
+        // A response is healthy when it isn't back pressure and, if its RTT is measured, that RTT
+        // is within the deviation expected from the past ones.
+        let healthy = !is_back_pressure
+            && (!use_rtt
+                || inner.past_rtt.state().is_none_or(|past_rtt| {
+                    rtt <= past_rtt.mean
+                        + past_rtt.variance.sqrt() * self.settings.rtt_deviation_scale
+                }));
+        let health = inner.health.update(if healthy { 1.0 } else { 0.0 });
+        self.health.emit(health);
         let current_rtt = inner.current_rtt.average();
 
         // When the RTT values are all exactly the same, as for the
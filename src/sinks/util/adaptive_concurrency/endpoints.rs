@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    mem,
+    sync::Arc,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use futures::{FutureExt, future::BoxFuture};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+use tower::{Service, ServiceExt};
+
+use super::{AdaptiveConcurrencyLimit, AdaptiveConcurrencySettings};
+use crate::sinks::util::retries::RetryLogic;
+
+/// How long the limit of an endpoint is kept after its last request, so that the limits of the
+/// endpoints of a templated URI don't accumulate.
+const ENDPOINT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Enforces a separate adaptive concurrency limit on the requests sent to each endpoint of the
+/// underlying service, so that a slow endpoint doesn't hold back the requests to the other ones.
+///
+/// The limit of an endpoint is created when the first request is sent to it, and its metrics are
+/// tagged with that endpoint. It is dropped once no request has been sent to the endpoint for
+/// `ENDPOINT_IDLE_TIMEOUT`. A request is sent once both its endpoint and the maximum concurrency
+/// limit over all the endpoints allow it, and this service is ready as long as fewer requests than
+/// the maximum concurrency limit are waiting for their endpoint.
+pub struct PerEndpointConcurrencyLimit<S, L, Request> {
+    inner: S,
+    logic: L,
+    concurrency: Option<usize>,
+    options: AdaptiveConcurrencySettings,
+    endpoint: fn(&Request) -> String,
+    limits: HashMap<String, Endpoint<S, L>>,
+    last_eviction: Instant,
+    waiting: Arc<Semaphore>,
+    in_flight: Arc<Semaphore>,
+    state: State,
+}
+
+struct Endpoint<S, L> {
+    limit: AdaptiveConcurrencyLimit<S, L>,
+    last_request: Instant,
+}
+
+enum State {
+    Waiting(BoxFuture<'static, OwnedSemaphorePermit>),
+    Ready(OwnedSemaphorePermit),
+    Empty,
+}
+
+impl<S, L, Request> PerEndpointConcurrencyLimit<S, L, Request> {
+    /// Create a new automated concurrency limiter for each endpoint, the endpoint of a request
+    /// being given by `endpoint`.
+    pub(crate) fn new(
+        inner: S,
+        logic: L,
+        concurrency: Option<usize>,
+        options: AdaptiveConcurrencySettings,
+        endpoint: fn(&Request) -> String,
+    ) -> Self {
+        Self {
+            inner,
+            logic,
+            concurrency,
+            options,
+            endpoint,
+            limits: HashMap::new(),
+            last_eviction: Instant::now(),
+            waiting: Arc::new(Semaphore::new(options.max_concurrency_limit)),
+            in_flight: Arc::new(Semaphore::new(options.max_concurrency_limit)),
+            state: State::Empty,
+        }
+    }
+
+    /// Drops the limits of the endpoints that have no request in flight or waiting, and haven't
+    /// been sent a request for `ENDPOINT_IDLE_TIMEOUT`.
+    fn evict_idle_endpoints(&mut self, now: Instant) {
+        if now.duration_since(self.last_eviction) < ENDPOINT_IDLE_TIMEOUT {
+            return;
+        }
+        self.last_eviction = now;
+        self.limits.retain(|_, endpoint| {
+            Arc::strong_count(&endpoint.limit.controller) > 1
+                || now.duration_since(endpoint.last_request) < ENDPOINT_IDLE_TIMEOUT
+        });
+    }
+}
+
+impl<S, L, Request> Service<Request> for PerEndpointConcurrencyLimit<S, L, Request>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Error: Into<crate::Error>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    L: RetryLogic<Response = S::Response>,
+    Request: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        loop {
+            self.state = match self.state {
+                State::Ready(_) => return Poll::Ready(Ok(())),
+                State::Waiting(ref mut fut) => State::Ready(ready!(fut.as_mut().poll(cx))),
+                State::Empty => State::Waiting(
+                    Arc::clone(&self.waiting)
+                        .acquire_owned()
+                        .map(|permit| permit.expect("Semaphore is never closed"))
+                        .boxed(),
+                ),
+            };
+        }
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // Make sure a permit has been acquired
+        let waiting = match mem::replace(&mut self.state, State::Empty) {
+            State::Ready(permit) => permit,
+            _ => panic!("Maximum requests in-flight; poll_ready must be called first"),
+        };
+
+        let now = Instant::now();
+        self.evict_idle_endpoints(now);
+        let endpoint = self
+            .limits
+            .entry((self.endpoint)(&request))
+            .or_insert_with_key(|endpoint| Endpoint {
+                limit: AdaptiveConcurrencyLimit::new(
+                    self.inner.clone(),
+                    self.logic.clone(),
+                    self.concurrency,
+                    self.options,
+                    Some(endpoint.clone()),
+                ),
+                last_request: now,
+            });
+        endpoint.last_request = now;
+        let mut limit = endpoint.limit.clone();
+        let in_flight = Arc::clone(&self.in_flight);
+
+        // The request waits for the limit of its endpoint in its own future, as the endpoint of
+        // the next request isn't known when the readiness of this service is polled. The permit
+        // over all the endpoints is only taken once the endpoint allows the request, so that the
+        // requests waiting for a slow endpoint don't hold the permits of the other endpoints.
+        async move {
+            limit.ready().await?;
+            let in_flight = in_flight
+                .acquire_owned()
+                .await
+                .expect("Semaphore is never closed");
+            drop(waiting);
+            let response = limit.call(request).await;
+            drop(in_flight);
+            response
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_test::{assert_pending, assert_ready_ok, task};
+    use tower_test::mock;
+
+    use super::*;
+
+    #[derive(Clone, Copy, Debug)]
+    struct TestRetryLogic;
+    impl RetryLogic for TestRetryLogic {
+        type Error = std::io::Error;
+        type Request = String;
+        type Response = String;
+        fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn isolates_endpoints() {
+        let (mock, mut handle) = mock::pair::<String, String>();
+        let mut service = PerEndpointConcurrencyLimit::new(
+            mock,
+            TestRetryLogic,
+            None,
+            AdaptiveConcurrencySettings::default(),
+            |request: &String| request.split('/').next().unwrap().to_owned(),
+        );
+
+        // The concurrency limit of each endpoint starts at 1.
+        service.ready().await.unwrap();
+        let mut slow_1 = task::spawn(service.call("slow/1".to_owned()));
+        service.ready().await.unwrap();
+        let mut slow_2 = task::spawn(service.call("slow/2".to_owned()));
+        assert_pending!(slow_1.poll());
+        assert_pending!(slow_2.poll());
+        let (request, _slow_response) = handle.next_request().await.unwrap();
+        assert_eq!(request, "slow/1");
+
+        // A request to another endpoint isn't held back by the slow one.
+        service.ready().await.unwrap();
+        let mut fast = task::spawn(service.call("fast/1".to_owned()));
+        assert_pending!(fast.poll());
+        let (request, response) = handle.next_request().await.unwrap();
+        assert_eq!(request, "fast/1");
+        response.send_response("done".to_owned());
+        assert_eq!(assert_ready_ok!(fast.poll()), "done");
+
+        assert_pending!(slow_2.poll());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn evicts_idle_endpoints() {
+        let (mock, mut handle) = mock::pair::<String, String>();
+        let mut service = PerEndpointConcurrencyLimit::new(
+            mock,
+            TestRetryLogic,
+            None,
+            AdaptiveConcurrencySettings::default(),
+            |request: &String| request.split('/').next().unwrap().to_owned(),
+        );
+
+        service.ready().await.unwrap();
+        let mut first = task::spawn(service.call("first/1".to_owned()));
+        assert_pending!(first.poll());
+        let (_, response) = handle.next_request().await.unwrap();
+        response.send_response("done".to_owned());
+        assert_ready_ok!(first.poll());
+
+        tokio::time::advance(ENDPOINT_IDLE_TIMEOUT).await;
+        service.ready().await.unwrap();
+        let mut second = task::spawn(service.call("second/1".to_owned()));
+        assert_pending!(second.poll());
+
+        assert_eq!(service.limits.len(), 1);
+        assert!(service.limits.contains_key("second"));
+    }
+}
@@ -10,6 +10,7 @@ pub struct AdaptiveConcurrencyLimitLayer<L> {
     concurrency: Option<usize>,
     options: AdaptiveConcurrencySettings,
     logic: L,
+    endpoint: Option<String>,
 }
 
 impl<L> AdaptiveConcurrencyLimitLayer<L> {
@@ -23,14 +24,27 @@ impl<L> AdaptiveConcurrencyLimitLayer<L> {
             concurrency,
             options,
             logic,
+            endpoint: None,
         }
     }
+
+    /// Tags the metrics of the limited services with the endpoint they send requests to.
+    pub fn with_endpoint(mut self, endpoint: String) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
 }
 
 impl<S, L: RetryLogic> Layer<S> for AdaptiveConcurrencyLimitLayer<L> {
     type Service = AdaptiveConcurrencyLimit<S, L>;
 
     fn layer(&self, service: S) -> Self::Service {
-        AdaptiveConcurrencyLimit::new(service, self.logic.clone(), self.concurrency, self.options)
+        AdaptiveConcurrencyLimit::new(
+            service,
+            self.logic.clone(),
+            self.concurrency,
+            self.options,
+            self.endpoint.clone(),
+        )
     }
 }
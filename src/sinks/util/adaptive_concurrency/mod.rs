@@ -1,6 +1,7 @@
 //! Limit the max number of requests being concurrently processed.
 
 mod controller;
+mod endpoints;
 mod future;
 mod layer;
 mod semaphore;
@@ -9,6 +10,7 @@ mod service;
 #[cfg(test)]
 pub mod tests;
 
+pub(crate) use endpoints::PerEndpointConcurrencyLimit;
 pub(crate) use layer::AdaptiveConcurrencyLimitLayer;
 pub(crate) use service::AdaptiveConcurrencyLimit;
 use vector_lib::configurable::configurable_component;
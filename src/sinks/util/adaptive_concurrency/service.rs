@@ -35,10 +35,11 @@ impl<S, L> AdaptiveConcurrencyLimit<S, L> {
         logic: L,
         concurrency: Option<usize>,
         options: AdaptiveConcurrencySettings,
+        endpoint: Option<String>,
     ) -> Self {
         AdaptiveConcurrencyLimit {
             inner,
-            controller: Arc::new(Controller::new(concurrency, options, logic)),
+            controller: Arc::new(Controller::new(concurrency, options, logic, endpoint)),
             state: State::Empty,
         }
     }
@@ -310,8 +311,11 @@ mod tests {
 
             let req = svc.send(true).await;
             advance(Duration::from_secs(1)).await;
+            assert_eq!(svc.service.get_ref().controller.health(), 1.0);
             req.defer().await;
             assert_eq!(svc.inner().current_limit, 1);
+            // Back pressure lowers the health score.
+            assert!(svc.service.get_ref().controller.health() < 1.0);
         })
         .await;
     }
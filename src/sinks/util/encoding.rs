@@ -8,7 +8,13 @@ use vector_lib::{
     request_metadata::GroupedCountByteSize,
 };
 
-use crate::{codecs::Transformer, event::Event, internal_events::EncoderWriteError};
+#[cfg(feature = "codecs-parquet")]
+use crate::internal_events::EncoderBatchSerializeError;
+use crate::{
+    codecs::{BatchEncoder, Transformer},
+    event::Event,
+    internal_events::EncoderWriteError,
+};
 
 pub trait Encoder<T> {
     /// Encodes the input into the provided writer.
@@ -29,51 +35,94 @@ impl Encoder<Vec<Event>> for (Transformer, crate::codecs::Encoder<Framer>) {
         events: Vec<Event>,
         writer: &mut dyn io::Write,
     ) -> io::Result<(usize, GroupedCountByteSize)> {
-        let mut encoder = self.1.clone();
-        let mut bytes_written = 0;
-        let mut n_events_pending = events.len();
-        let is_empty = events.is_empty();
-        let batch_prefix = encoder.batch_prefix();
-        write_all(writer, n_events_pending, batch_prefix)?;
-        bytes_written += batch_prefix.len();
+        encode_framed(&self.0, &self.1, events, writer)
+    }
+}
 
-        let mut byte_size = telemetry().create_request_count_byte_size();
+impl Encoder<Vec<Event>> for (Transformer, BatchEncoder) {
+    fn encode_input(
+        &self,
+        events: Vec<Event>,
+        writer: &mut dyn io::Write,
+    ) -> io::Result<(usize, GroupedCountByteSize)> {
+        match &self.1 {
+            BatchEncoder::Framed(encoder) => encode_framed(&self.0, encoder, events, writer),
+            #[cfg(feature = "codecs-parquet")]
+            BatchEncoder::Batch(serializer) => {
+                let mut events = events;
+                let mut byte_size = telemetry().create_request_count_byte_size();
+                for event in &mut events {
+                    self.0.transform(event);
+                    byte_size.add_event(event, event.estimated_json_encoded_size_of());
+                }
 
-        for (position, mut event) in events.into_iter().with_position() {
-            self.0.transform(&mut event);
+                let count = events.len();
+                let bytes = serializer.encode(events).map_err(|error| {
+                    emit!(EncoderBatchSerializeError {
+                        error: &error,
+                        count,
+                    });
+                    io::Error::new(io::ErrorKind::InvalidData, error)
+                })?;
+                write_all(writer, count, &bytes)?;
+                Ok((bytes.len(), byte_size))
+            }
+        }
+    }
+}
 
-            // Ensure the json size is calculated after any fields have been removed
-            // by the transformer.
-            byte_size.add_event(&event, event.estimated_json_encoded_size_of());
+/// Encodes the events one by one with the framed encoder, enclosing them in the batch prefix and
+/// suffix of the encoder.
+fn encode_framed(
+    transformer: &Transformer,
+    encoder: &crate::codecs::Encoder<Framer>,
+    events: Vec<Event>,
+    writer: &mut dyn io::Write,
+) -> io::Result<(usize, GroupedCountByteSize)> {
+    let mut encoder = encoder.clone();
+    let mut bytes_written = 0;
+    let mut n_events_pending = events.len();
+    let is_empty = events.is_empty();
+    let batch_prefix = encoder.batch_prefix();
+    write_all(writer, n_events_pending, batch_prefix)?;
+    bytes_written += batch_prefix.len();
+
+    let mut byte_size = telemetry().create_request_count_byte_size();
+
+    for (position, mut event) in events.into_iter().with_position() {
+        transformer.transform(&mut event);
+
+        // Ensure the json size is calculated after any fields have been removed
+        // by the transformer.
+        byte_size.add_event(&event, event.estimated_json_encoded_size_of());
 
-            let mut bytes = BytesMut::new();
-            match (position, encoder.framer()) {
-                (
-                    Position::Last | Position::Only,
-                    Framer::CharacterDelimited(_) | Framer::NewlineDelimited(_),
-                ) => {
-                    encoder
-                        .serialize(event, &mut bytes)
-                        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
-                }
-                _ => {
-                    encoder
-                        .encode(event, &mut bytes)
-                        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
-                }
+        let mut bytes = BytesMut::new();
+        match (position, encoder.framer()) {
+            (
+                Position::Last | Position::Only,
+                Framer::CharacterDelimited(_) | Framer::NewlineDelimited(_),
+            ) => {
+                encoder
+                    .serialize(event, &mut bytes)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            }
+            _ => {
+                encoder
+                    .encode(event, &mut bytes)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
             }
-            write_all(writer, n_events_pending, &bytes)?;
-            bytes_written += bytes.len();
-            n_events_pending -= 1;
         }
+        write_all(writer, n_events_pending, &bytes)?;
+        bytes_written += bytes.len();
+        n_events_pending -= 1;
+    }
 
-        let batch_suffix = encoder.batch_suffix(is_empty);
-        assert!(n_events_pending == 0);
-        write_all(writer, 0, batch_suffix)?;
-        bytes_written += batch_suffix.len();
+    let batch_suffix = encoder.batch_suffix(is_empty);
+    assert!(n_events_pending == 0);
+    write_all(writer, 0, batch_suffix)?;
+    bytes_written += batch_suffix.len();
 
-        Ok((bytes_written, byte_size))
-    }
+    Ok((bytes_written, byte_size))
 }
 
 impl Encoder<Event> for (Transformer, crate::codecs::Encoder<()>) {
@@ -823,6 +823,17 @@ impl DriverResponse for HttpResponse {
     fn bytes_sent(&self) -> Option<usize> {
         Some(self.raw_byte_size)
     }
+
+    fn failure(&self) -> Option<DeliveryFailure> {
+        let status = self.http_response.status();
+        (!self.http_response.is_successful()).then(|| DeliveryFailure {
+            message: format!(
+                "{status}: {}",
+                String::from_utf8_lossy(self.http_response.body())
+            ),
+            status_code: Some(status.as_u16()),
+        })
+    }
 }
 
 /// Creates a `RetryLogic` for use with `HttpResponse`.
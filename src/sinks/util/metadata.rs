@@ -12,6 +12,7 @@ pub struct RequestMetadataBuilder {
     event_count: usize,
     events_byte_size: usize,
     grouped_events_byte_size: GroupedCountByteSize,
+    ordering_keys: Vec<u64>,
 }
 
 impl RequestMetadataBuilder {
@@ -32,6 +33,7 @@ impl RequestMetadataBuilder {
             event_count: events.len(),
             events_byte_size,
             grouped_events_byte_size: size,
+            ordering_keys: Vec::new(),
         }
     }
 
@@ -46,6 +48,7 @@ impl RequestMetadataBuilder {
             event_count: 1,
             events_byte_size: event.size_of(),
             grouped_events_byte_size: size,
+            ordering_keys: Vec::new(),
         }
     }
 
@@ -58,9 +61,17 @@ impl RequestMetadataBuilder {
             event_count,
             events_byte_size,
             grouped_events_byte_size,
+            ordering_keys: Vec::new(),
         }
     }
 
+    /// Sets the ordering keys of the request, see [`RequestMetadata::with_ordering_keys`].
+    #[must_use]
+    pub fn with_ordering_keys(mut self, ordering_keys: Vec<u64>) -> Self {
+        self.ordering_keys = ordering_keys;
+        self
+    }
+
     pub fn track_event<E>(&mut self, event: E)
     where
         E: ByteSizeOf + GetEventCountTags + EstimatedJsonEncodedSizeOf,
@@ -83,6 +94,7 @@ impl RequestMetadataBuilder {
             size,
             self.grouped_events_byte_size.clone(),
         )
+        .with_ordering_keys(self.ordering_keys.clone())
     }
 
     /// Builds the [`RequestMetadata`] from the results of encoding.
@@ -101,5 +113,6 @@ impl RequestMetadataBuilder {
             // after transforming the event.
             result.transformed_json_size.clone(),
         )
+        .with_ordering_keys(self.ordering_keys.clone())
     }
 }
@@ -11,6 +11,7 @@ pub mod encoding;
 pub mod http;
 pub mod metadata;
 pub mod normalizer;
+pub mod ordering;
 pub mod partitioner;
 pub mod processed_event;
 pub mod request_builder;
@@ -0,0 +1,109 @@
+//! The order in which sinks send their requests.
+//!
+//! The driver of a sink sends the requests holding ordering keys one at a time for each key, see
+//! [`RequestMetadata::with_ordering_keys`](vector_lib::request_metadata::RequestMetadata::with_ordering_keys).
+//! The keys are computed by the request builders from the events of the requests with
+//! [`OrderingKeys`].
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use snafu::Snafu;
+use vector_lib::{
+    configurable::configurable_component,
+    lookup::{OwnedTargetPath, lookup_v2::ConfigTargetPath},
+};
+
+use crate::event::Event;
+
+/// The order in which the requests of the sink are sent.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkOrdering {
+    /// The requests are sent concurrently, so the events can be delivered out of order.
+    #[default]
+    Unordered,
+
+    /// The requests holding events with the same value at `ordering_key_field` are sent one at a
+    /// time, in the order the events were received.
+    ///
+    /// The requests of different keys are still sent concurrently.
+    PerKey,
+}
+
+#[derive(Debug, Snafu)]
+pub enum OrderingError {
+    #[snafu(display("`ordering_key_field` must be set when `ordering` is `per_key`"))]
+    MissingKeyField,
+}
+
+/// Computes the ordering keys of the requests of a sink from the events they hold.
+#[derive(Clone, Debug, Default)]
+pub struct OrderingKeys {
+    key_field: Option<OwnedTargetPath>,
+}
+
+impl OrderingKeys {
+    pub fn new(
+        ordering: SinkOrdering,
+        key_field: Option<&ConfigTargetPath>,
+    ) -> Result<Self, OrderingError> {
+        match (ordering, key_field) {
+            (SinkOrdering::Unordered, _) => Ok(Self::default()),
+            (SinkOrdering::PerKey, Some(key_field)) => Ok(Self {
+                key_field: Some(key_field.0.clone()),
+            }),
+            (SinkOrdering::PerKey, None) => Err(OrderingError::MissingKeyField),
+        }
+    }
+
+    /// Returns the hashes of the values of the key field of the events, or nothing if the requests
+    /// aren't ordered.
+    ///
+    /// The events without the field, or that aren't logs, share the same key.
+    pub fn keys<'a>(&self, events: impl IntoIterator<Item = &'a Event>) -> Vec<u64> {
+        let Some(key_field) = &self.key_field else {
+            return Vec::new();
+        };
+        events
+            .into_iter()
+            .map(|event| {
+                let value = event
+                    .maybe_as_log()
+                    .and_then(|log| log.get(key_field))
+                    .map(|value| value.coerce_to_bytes());
+                let mut hasher = DefaultHasher::new();
+                value.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn hashes_the_key_field() {
+        let key_field = ConfigTargetPath::try_from("user_id".to_owned()).unwrap();
+        let keys = OrderingKeys::new(SinkOrdering::PerKey, Some(&key_field)).unwrap();
+
+        let events: [Event; 4] = [
+            LogEvent::from_iter([("user_id", "a")]).into(),
+            LogEvent::from_iter([("user_id", "b")]).into(),
+            LogEvent::from_iter([("user_id", "a")]).into(),
+            LogEvent::from("no user").into(),
+        ];
+        let hashes = keys.keys(&events);
+        assert_eq!(hashes.len(), 4);
+        assert_eq!(hashes[0], hashes[2]);
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[3]);
+
+        let unordered = OrderingKeys::new(SinkOrdering::Unordered, Some(&key_field)).unwrap();
+        assert!(unordered.keys(&events).is_empty());
+        assert!(OrderingKeys::new(SinkOrdering::PerKey, None).is_err());
+    }
+}
@@ -47,6 +47,13 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
 
     /// Optional hook run when an error is determined to be retriable.
     fn on_retriable_error(&self, _error: &Self::Error) {}
+
+    /// When a response is retried, this function allows implementors to specify the delay
+    /// before the retry instead of the backoff, such as the one requested by the downstream
+    /// service. The delay is capped at the maximum backoff.
+    fn retry_delay(&self, _response: &Self::Response) -> Option<Duration> {
+        None
+    }
 }
 
 /// The jitter mode to use for retry backoff behavior.
@@ -126,12 +133,14 @@ impl<L: RetryLogic> FibonacciRetryPolicy<L> {
         self.current_jitter_duration = Self::add_full_jitter(next_duration);
     }
 
-    fn build_retry(&mut self) -> RetryPolicyFuture {
+    fn build_retry(&mut self, delay: Option<Duration>) -> RetryPolicyFuture {
         self.advance();
-        let delay = Box::pin(sleep(self.backoff()));
+        let delay = delay.map_or_else(|| self.backoff(), |delay| delay.min(self.max_duration));
 
-        debug!(message = "Retrying request.", delay_ms = %self.backoff().as_millis());
-        RetryPolicyFuture { delay }
+        debug!(message = "Retrying request.", delay_ms = %delay.as_millis());
+        RetryPolicyFuture {
+            delay: Box::pin(sleep(delay)),
+        }
     }
 }
 
@@ -157,7 +166,8 @@ where
                     }
 
                     warn!(message = "Retrying after response.", reason = %reason);
-                    Some(self.build_retry())
+                    let delay = self.logic.retry_delay(response);
+                    Some(self.build_retry(delay))
                 }
                 RetryAction::RetryPartial(modify_request) => {
                     if self.remaining_attempts == 0 {
@@ -169,7 +179,7 @@ where
                     }
                     *req = modify_request(req.clone());
                     warn!("OK/retrying partial after response.");
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
                 }
                 RetryAction::DontRetry(reason) => {
                     error!(message = "Not retriable; dropping the request.", ?reason);
@@ -188,7 +198,7 @@ where
                     if self.logic.is_retriable_error(expected) {
                         self.logic.on_retriable_error(expected);
                         warn!(message = "Retrying after error.", error = %expected);
-                        Some(self.build_retry())
+                        Some(self.build_retry(None))
                     } else {
                         error!(
                             message = "Non-retriable error; dropping the request.",
@@ -200,7 +210,7 @@ where
                     warn!(
                         "Request timed out. If this happens often while the events are actually reaching their destination, try decreasing `batch.max_bytes` and/or using `compression` if applicable. Alternatively `request.timeout_secs` can be increased."
                     );
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
                 } else {
                     error!(
                         message = "Unexpected error type; dropping the request.",
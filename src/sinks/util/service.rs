@@ -16,7 +16,7 @@ use vector_lib::configurable::configurable_component;
 
 pub use crate::sinks::util::service::{
     concurrency::Concurrency,
-    health::{HealthConfig, HealthLogic, HealthService},
+    health::{EndpointHealth, HealthConfig, HealthLogic, HealthService},
     map::Map,
 };
 use crate::{
@@ -25,6 +25,7 @@ use crate::{
         Batch, BatchSink, Partition, PartitionBatchSink,
         adaptive_concurrency::{
             AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
+            PerEndpointConcurrencyLimit,
         },
         retries::{FibonacciRetryPolicy, JitterMode, RetryLogic},
         service::map::MapLayer,
@@ -41,6 +42,8 @@ pub type Svc<S, L> =
     RateLimit<AdaptiveConcurrencyLimit<Retry<FibonacciRetryPolicy<L>, Timeout<S>>, L>>;
 pub type TowerBatchedSink<S, B, RL> = BatchSink<Svc<S, RL>, B>;
 pub type TowerPartitionSink<S, B, RL, K> = PartitionBatchSink<Svc<S, RL>, B, K>;
+pub type PerEndpointSvc<S, L, Request> =
+    RateLimit<PerEndpointConcurrencyLimit<Retry<FibonacciRetryPolicy<L>, Timeout<S>>, L, Request>>;
 
 // Distributed service types
 pub type DistributedService<S, RL, HL, K, Req> = RateLimit<
@@ -53,6 +56,8 @@ pub type DiscoveryService<S, RL, HL, K> =
     BoxStream<'static, Result<Change<K, SingleDistributedService<S, RL, HL>>, crate::Error>>;
 pub type SingleDistributedService<S, RL, HL> =
     AdaptiveConcurrencyLimit<HealthService<Timeout<S>, HL>, RL>;
+pub type BalancedService<B, RL, Req> =
+    RateLimit<Retry<FibonacciRetryPolicy<RL>, Buffer<Req, <B as Service<Req>>::Future>>>;
 
 pub trait ServiceBuilderExt<L> {
     fn map<R1, R2, F>(self, f: F) -> ServiceBuilder<Stack<MapLayer<R1, R2>, L>>
@@ -308,6 +313,36 @@ impl TowerRequestSettings {
         BatchSink::new(service, batch, batch_timeout)
     }
 
+    /// Limits the concurrency of the requests to each endpoint of the service separately, the
+    /// endpoint of a request being given by `endpoint`.
+    pub fn per_endpoint_service<RL, S, Request>(
+        &self,
+        retry_logic: RL,
+        service: S,
+        endpoint: fn(&Request) -> String,
+    ) -> PerEndpointSvc<S, RL, Request>
+    where
+        RL: RetryLogic<Request = Request, Response = S::Response>,
+        S: Service<Request> + Clone,
+        Request: Clone,
+    {
+        let policy = self.retry_policy(retry_logic.clone());
+        ServiceBuilder::new()
+            .rate_limit(self.rate_limit_num, self.rate_limit_duration)
+            .layer_fn(|inner| {
+                PerEndpointConcurrencyLimit::new(
+                    inner,
+                    retry_logic.clone(),
+                    self.concurrency,
+                    self.adaptive_concurrency,
+                    endpoint,
+                )
+            })
+            .retry(policy)
+            .timeout(self.timeout)
+            .service(service)
+    }
+
     /// Distributes requests to services [(Endpoint, service, healthcheck)]
     ///
     /// [BufferLayer] suggests that the `buffer_bound` should be at least equal to
@@ -332,28 +367,10 @@ impl TowerRequestSettings {
         let policy = self.retry_policy(retry_logic.clone());
 
         // Build services
-        let open = OpenGauge::new();
-        let services = services
+        let services = self
+            .endpoint_services::<Req, _, _, _>(retry_logic, services, health_config, health_logic)
             .into_iter()
-            .map(|(endpoint, inner)| {
-                // Build individual service
-                ServiceBuilder::new()
-                    .layer(AdaptiveConcurrencyLimitLayer::new(
-                        self.concurrency,
-                        self.adaptive_concurrency,
-                        retry_logic.clone(),
-                    ))
-                    .service(
-                        health_config.build(
-                            health_logic.clone(),
-                            ServiceBuilder::new().timeout(self.timeout).service(inner),
-                            open.clone(),
-                            endpoint,
-                        ), // NOTE: there is a version conflict for crate `tracing` between `tracing_tower` crate
-                           // and Vector. Once that is resolved, this can be used instead of passing endpoint everywhere.
-                           // .trace_service(|_| info_span!("endpoint", %endpoint)),
-                    )
-            })
+            .map(|(service, _)| service)
             .enumerate()
             .map(|(i, service)| Ok::<_, S::Error>(Change::Insert(i, service)))
             .collect::<Vec<_>>();
@@ -366,6 +383,93 @@ impl TowerRequestSettings {
             .layer(BufferLayer::new(buffer_bound))
             .service(Balance::new(Box::pin(stream::iter(services)) as Pin<Box<_>>))
     }
+
+    /// Distributes requests to services [(Endpoint, service)] with the balancer built by `balance`
+    /// from the services of the endpoints and their health.
+    ///
+    /// [BufferLayer] suggests that the `buffer_bound` should be at least equal to
+    /// the number of the callers of the service. For sinks, this should typically be 1.
+    pub fn balanced_service<Req, RL, HL, S, B>(
+        self,
+        retry_logic: RL,
+        services: Vec<(String, S)>,
+        health_config: HealthConfig,
+        health_logic: HL,
+        buffer_bound: usize,
+        balance: impl FnOnce(Vec<(SingleDistributedService<S, RL, HL>, EndpointHealth)>) -> B,
+    ) -> BalancedService<B, RL, Req>
+    where
+        Req: Clone + Send + 'static,
+        RL: RetryLogic<Response = S::Response>,
+        HL: HealthLogic<Response = S::Response, Error = crate::Error>,
+        S: Service<Req> + Clone + Send + 'static,
+        S::Error: Into<crate::Error> + Send + Sync + 'static,
+        S::Response: Send,
+        S::Future: Send + 'static,
+        B: Service<Req, Response = S::Response> + Send + 'static,
+        B::Error: Into<crate::Error> + Send + Sync,
+        B::Future: Send + 'static,
+    {
+        let policy = self.retry_policy(retry_logic.clone());
+        let services = self.endpoint_services::<Req, _, _, _>(
+            retry_logic,
+            services,
+            health_config,
+            health_logic,
+        );
+
+        ServiceBuilder::new()
+            .rate_limit(self.rate_limit_num, self.rate_limit_duration)
+            .retry(policy)
+            // The balancer must be wrapped with a [BufferLayer] so that the overall service implements Clone.
+            .layer(BufferLayer::new(buffer_bound))
+            .service(balance(services))
+    }
+
+    /// Builds the services of the endpoints, each limiting its own concurrency and monitoring its
+    /// own health.
+    fn endpoint_services<Req, RL, HL, S>(
+        &self,
+        retry_logic: RL,
+        services: Vec<(String, S)>,
+        health_config: HealthConfig,
+        health_logic: HL,
+    ) -> Vec<(SingleDistributedService<S, RL, HL>, EndpointHealth)>
+    where
+        RL: RetryLogic<Response = S::Response>,
+        HL: HealthLogic<Response = S::Response, Error = crate::Error>,
+        S: Service<Req>,
+    {
+        let open = OpenGauge::new();
+        services
+            .into_iter()
+            .map(|(endpoint, inner)| {
+                let service = health_config.build(
+                    health_logic.clone(),
+                    ServiceBuilder::new().timeout(self.timeout).service(inner),
+                    open.clone(),
+                    endpoint.clone(),
+                );
+                let health = service.health();
+
+                // Build individual service
+                let service = ServiceBuilder::new()
+                    .layer(
+                        AdaptiveConcurrencyLimitLayer::new(
+                            self.concurrency,
+                            self.adaptive_concurrency,
+                            retry_logic.clone(),
+                        )
+                        .with_endpoint(endpoint),
+                    )
+                    // NOTE: there is a version conflict for crate `tracing` between `tracing_tower` crate
+                    // and Vector. Once that is resolved, this can be used instead of passing endpoint everywhere.
+                    // .trace_service(|_| info_span!("endpoint", %endpoint)),
+                    .service(service);
+                (service, health)
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -3,7 +3,7 @@ use std::{
     pin::Pin,
     sync::{
         Arc,
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
     },
     task::{Context, Poll, ready},
 };
@@ -73,6 +73,7 @@ impl HealthConfig {
             snapshot,
             endpoint,
             state: CircuitState::Closed,
+            health: EndpointHealth::default(),
             open,
             // An exponential backoff starting from retry_initial_backoff_sec and doubling every time
             // up to retry_max_duration_secs.
@@ -118,6 +119,29 @@ pub struct HealthService<S, L> {
     state: CircuitState,
     open: OpenGauge,
     endpoint: String,
+    health: EndpointHealth,
+}
+
+impl<S, L> HealthService<S, L> {
+    /// The health of the endpoint, shared with the balancers choosing among endpoints.
+    pub fn health(&self) -> EndpointHealth {
+        self.health.clone()
+    }
+}
+
+/// Whether an endpoint is ejected, which it is from the moment it's found unhealthy until a
+/// request on probation succeeds.
+#[derive(Clone, Debug, Default)]
+pub struct EndpointHealth(Arc<AtomicBool>);
+
+impl EndpointHealth {
+    pub fn is_ejected(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    fn set_ejected(&self, ejected: bool) {
+        self.0.store(ejected, Ordering::Release);
+    }
 }
 
 impl<S, L, Req> Service<Req> for HealthService<S, L>
@@ -165,6 +189,7 @@ where
 
                         self.backoff.reset();
                         self.open.clone().open(emit_active_endpoints);
+                        self.health.set_ejected(false);
                         CircuitState::Closed
                     } else {
                         debug!(message = "Endpoint failed probation.", endpoint = %&self.endpoint);
@@ -185,6 +210,7 @@ where
                         Err(errors) if errors >= UNHEALTHY_AMOUNT_OF_ERRORS => {
                             // Unhealthy
                             warn!(message = "Endpoint is unhealthy.", endpoint = %&self.endpoint);
+                            self.health.set_ejected(true);
                             CircuitState::Open(
                                 sleep(self.backoff.next().expect("Should never end")).boxed(),
                             )
@@ -0,0 +1,325 @@
+//! The balancing of the requests across the endpoints of the sink.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use tower::Service;
+use vector_lib::{
+    configurable::configurable_component,
+    lookup::lookup_v2::{ConfigTargetPath, OwnedTargetPath},
+};
+
+use super::service::VectorRequest;
+use crate::{event::Event, sinks::util::service::EndpointHealth};
+
+/// The number of points of each endpoint on the hash ring, which spread the keys evenly across the
+/// endpoints.
+const POINTS_PER_ENDPOINT: usize = 128;
+
+/// The balancing of the requests across the endpoints.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+#[configurable(metadata(
+    docs::enum_tag_description = "The policy choosing the endpoint of each request."
+))]
+pub enum BalanceConfig {
+    /// The requests are sent to each of the endpoints in turn.
+    #[default]
+    RoundRobin,
+
+    /// The requests are sent to the endpoint with the fewest requests in flight.
+    LeastInFlight,
+
+    /// The events with the same value of a key are sent to the same endpoint.
+    ///
+    /// The endpoints own the ranges of a hash ring, so that adding or removing an endpoint only
+    /// moves the keys of its ranges. While an endpoint is ejected, the events of its keys are sent
+    /// to the next endpoint of the list. The events without the key are sent to each of the
+    /// endpoints in turn.
+    ConsistentHash {
+        /// The field of the logs and traces, or the tag of the metrics, whose value is hashed.
+        #[configurable(metadata(docs::examples = "host"))]
+        #[configurable(metadata(docs::examples = ".tenant.id"))]
+        key: ConfigTargetPath,
+    },
+}
+
+/// The ring of the hashes of the keys, each endpoint owning the ranges ending at its points.
+///
+/// The hashes are stable, so that all of the sinks with the same endpoints send the events of a key
+/// to the same endpoint.
+pub(super) struct HashRing {
+    key: OwnedTargetPath,
+    points: Vec<(u64, usize)>,
+}
+
+impl HashRing {
+    pub(super) fn new(key: OwnedTargetPath, endpoints: &[String]) -> Self {
+        let mut points = endpoints
+            .iter()
+            .enumerate()
+            .flat_map(|(index, endpoint)| {
+                (0..POINTS_PER_ENDPOINT).map(move |point| {
+                    (
+                        seahash::hash(format!("{endpoint}-{point}").as_bytes()),
+                        index,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        points.sort_unstable();
+        Self { key, points }
+    }
+
+    /// The index of the endpoint owning the key of the event, if it has one.
+    pub(super) fn endpoint(&self, event: &Event) -> Option<usize> {
+        let key = get_key(event, &self.key)?;
+        let hash = seahash::hash(&key);
+        let point = self.points.partition_point(|&(point, _)| point < hash);
+        Some(self.points[point % self.points.len()].1)
+    }
+}
+
+fn get_key(event: &Event, key: &OwnedTargetPath) -> Option<Bytes> {
+    match event {
+        Event::Log(log) => log.get(key).map(|value| value.coerce_to_bytes()),
+        Event::Metric(metric) => metric
+            .tags()
+            .and_then(|tags| tags.get(key.path.to_string().as_str()))
+            .map(|value| Bytes::copy_from_slice(value.as_bytes())),
+        Event::Trace(trace) => trace.get(key).map(|value| value.coerce_to_bytes()),
+    }
+}
+
+struct Endpoint<S> {
+    service: S,
+    health: EndpointHealth,
+    ready: bool,
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// Sends each request to one of the endpoints ready to send it, skipping the ejected ones.
+pub(super) struct Balancer<S> {
+    endpoints: Vec<Endpoint<S>>,
+    by_key: bool,
+    least_in_flight: bool,
+    next: usize,
+}
+
+impl<S> Balancer<S> {
+    pub(super) fn new(config: &BalanceConfig, services: Vec<(S, EndpointHealth)>) -> Self {
+        Self {
+            endpoints: services
+                .into_iter()
+                .map(|(service, health)| Endpoint {
+                    service,
+                    health,
+                    ready: false,
+                    in_flight: Default::default(),
+                })
+                .collect(),
+            by_key: matches!(config, BalanceConfig::ConsistentHash { .. }),
+            least_in_flight: matches!(config, BalanceConfig::LeastInFlight),
+            next: 0,
+        }
+    }
+
+    fn choose(&mut self, owner: Option<usize>) -> Option<usize> {
+        let endpoints = &self.endpoints;
+        let len = endpoints.len();
+        let index = match owner {
+            // The endpoint owning the key, which is ready while it's either healthy or on probation,
+            // or else the next one that isn't ejected, or any ready one if all of them are.
+            Some(owner) if endpoints[owner].ready => Some(owner),
+            Some(owner) => rotation(owner, len)
+                .find(|&index| endpoints[index].ready && !endpoints[index].health.is_ejected())
+                .or_else(|| rotation(owner, len).find(|&index| endpoints[index].ready)),
+            None if self.least_in_flight => rotation(self.next, len)
+                .filter(|&index| endpoints[index].ready)
+                .min_by_key(|&index| endpoints[index].in_flight.load(Ordering::Acquire)),
+            None => rotation(self.next, len).find(|&index| endpoints[index].ready),
+        }?;
+        if owner.is_none() {
+            self.next = index + 1;
+        }
+        Some(index)
+    }
+}
+
+impl<S> Service<VectorRequest> for Balancer<S>
+where
+    S: Service<VectorRequest>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        for endpoint in &mut self.endpoints {
+            if !endpoint.ready {
+                endpoint.ready = endpoint.service.poll_ready(cx)?.is_ready();
+            }
+        }
+
+        let mut active = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| !endpoint.health.is_ejected())
+            .peekable();
+        let ready = if self.by_key && active.peek().is_some() {
+            // The requests of a key wait for the endpoint owning it, so all of the endpoints that
+            // aren't ejected must be ready.
+            active.all(|endpoint| endpoint.ready)
+        } else {
+            self.endpoints.iter().any(|endpoint| endpoint.ready)
+        };
+
+        if ready {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn call(&mut self, request: VectorRequest) -> Self::Future {
+        let index = self
+            .choose(request.endpoint)
+            .expect("Balancer must be ready before being called");
+        let endpoint = &mut self.endpoints[index];
+        endpoint.ready = false;
+
+        let in_flight = InFlight::new(Arc::clone(&endpoint.in_flight));
+        let future = endpoint.service.call(request);
+        Box::pin(async move {
+            let _in_flight = in_flight;
+            future.await
+        })
+    }
+}
+
+/// The indices of the endpoints, starting from the given one.
+fn rotation(start: usize, len: usize) -> impl Iterator<Item = usize> {
+    (0..len).map(move |offset| (start + offset) % len)
+}
+
+/// Counts a request in flight until it's dropped.
+struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    fn new(count: Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::AcqRel);
+        Self(count)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::{Ready, ready};
+
+    use futures::task::noop_waker_ref;
+    use vector_lib::{event::LogEvent, lookup::owned_value_path};
+
+    use super::*;
+
+    /// A service answering with the index of its endpoint.
+    struct Index(usize);
+
+    impl Service<VectorRequest> for Index {
+        type Response = usize;
+        type Error = crate::Error;
+        type Future = Ready<Result<usize, crate::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: VectorRequest) -> Self::Future {
+            ready(Ok(self.0))
+        }
+    }
+
+    fn balancer(config: BalanceConfig) -> Balancer<Index> {
+        Balancer::new(
+            &config,
+            (0..3)
+                .map(|index| (Index(index), EndpointHealth::default()))
+                .collect(),
+        )
+    }
+
+    async fn send(balancer: &mut Balancer<Index>, endpoint: Option<usize>) -> usize {
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(balancer.poll_ready(&mut cx).is_ready());
+        balancer
+            .call(VectorRequest {
+                endpoint,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_robin() {
+        let mut balancer = balancer(BalanceConfig::RoundRobin);
+        let mut sent = Vec::new();
+        for _ in 0..4 {
+            sent.push(send(&mut balancer, None).await);
+        }
+        assert_eq!(sent, [0, 1, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn sends_keys_to_their_owner() {
+        let mut balancer = balancer(BalanceConfig::ConsistentHash {
+            key: ConfigTargetPath::try_from("host".to_owned()).unwrap(),
+        });
+        assert_eq!(send(&mut balancer, Some(2)).await, 2);
+        assert_eq!(send(&mut balancer, Some(2)).await, 2);
+        assert_eq!(send(&mut balancer, Some(1)).await, 1);
+    }
+
+    #[test]
+    fn hash_ring_is_stable() {
+        let endpoints = ["a:6000", "b:6000", "c:6000"].map(str::to_owned);
+        let ring = HashRing::new(
+            OwnedTargetPath::event(owned_value_path!("host")),
+            &endpoints,
+        );
+        let fewer = HashRing::new(
+            OwnedTargetPath::event(owned_value_path!("host")),
+            &endpoints[..2],
+        );
+
+        let mut moved = 0;
+        for host in 0..1000 {
+            let event = Event::Log(LogEvent::from_iter([("host", format!("host-{host}"))]));
+            let endpoint = ring.endpoint(&event).unwrap();
+            assert_eq!(ring.endpoint(&event), Some(endpoint));
+            // Only the keys of the removed endpoint move.
+            if endpoint != 2 {
+                assert_eq!(fewer.endpoint(&event), Some(endpoint));
+            } else {
+                moved += 1;
+            }
+        }
+        assert!((200..500).contains(&moved), "{moved} keys moved");
+
+        assert_eq!(ring.endpoint(&Event::Log(LogEvent::default())), None);
+    }
+}
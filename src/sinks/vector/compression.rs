@@ -0,0 +1,107 @@
+//! The compression of the messages sent to the `vector` source, negotiated with it through the
+//! `grpc-encoding` and `grpc-accept-encoding` headers.
+
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Deserializer, de};
+use vector_lib::configurable::configurable_component;
+
+/// The compression of the requests.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorCompression {
+    /// The requests aren't compressed.
+    #[default]
+    None,
+
+    /// The requests are compressed with [gzip][gzip_docs].
+    ///
+    /// [gzip_docs]: https://www.gzip.org/
+    Gzip,
+
+    /// The requests are compressed with [Zstandard][zstd].
+    ///
+    /// Zstandard compresses faster than gzip, at a similar ratio.
+    ///
+    /// [zstd]: https://facebook.github.io/zstd/
+    Zstd,
+
+    /// The requests are compressed with the block format of [LZ4][lz4].
+    ///
+    /// LZ4 is the fastest of the schemes, at the expense of the ratio.
+    ///
+    /// [lz4]: https://lz4.org/
+    Lz4,
+}
+
+impl VectorCompression {
+    /// The schemes fallen back to when the source doesn't accept the configured one, in order of
+    /// preference.
+    pub(super) const FALLBACKS: [Self; 3] = [Self::Zstd, Self::Lz4, Self::Gzip];
+
+    /// The value of the `grpc-encoding` header of the scheme.
+    pub(super) const fn encoding(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gzip"),
+            Self::Zstd => Some("zstd"),
+            Self::Lz4 => Some("lz4"),
+        }
+    }
+
+    /// Compresses a single message.
+    pub(super) fn compress(self, message: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(message.to_vec()),
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(message)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::bulk::compress(message, zstd::DEFAULT_COMPRESSION_LEVEL),
+            Self::Lz4 => Ok(lz4_flex::compress_prepend_size(message)),
+        }
+    }
+}
+
+/// Deserializes the compression from its name or, as it was previously a boolean, from `true` for
+/// gzip and `false` for none.
+pub(super) fn bool_or_compression<'de, D>(deserializer: D) -> Result<VectorCompression, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct BoolOrCompression;
+
+    impl de::Visitor<'de> for BoolOrCompression {
+        type Value = VectorCompression;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("bool or string")
+        }
+
+        fn visit_bool<E>(self, value: bool) -> Result<VectorCompression, E>
+        where
+            E: de::Error,
+        {
+            Ok(if value {
+                VectorCompression::Gzip
+            } else {
+                VectorCompression::None
+            })
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<VectorCompression, E>
+        where
+            E: de::Error,
+        {
+            VectorCompression::deserialize(de::value::StrDeserializer::new(value))
+        }
+    }
+
+    deserializer.deserialize_any(BoolOrCompression)
+}
@@ -1,3 +1,4 @@
+use futures::{FutureExt, TryFutureExt, future};
 use http::Uri;
 use hyper::client::HttpConnector;
 use hyper_openssl::HttpsConnector;
@@ -8,10 +9,13 @@ use vector_lib::configurable::configurable_component;
 
 use super::{
     VectorSinkError,
+    balance::{BalanceConfig, Balancer, HashRing},
+    compression::{VectorCompression, bool_or_compression},
     service::{VectorRequest, VectorResponse, VectorService},
     sink::VectorSink,
 };
 use crate::{
+    common::grpc::Http2Config,
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext,
         SinkHealthcheckOptions,
@@ -22,7 +26,9 @@ use crate::{
         Healthcheck, VectorSink as VectorSinkType,
         util::{
             BatchConfig, RealtimeEventBasedDefaultBatchSettings, ServiceBuilderExt,
-            TowerRequestConfig, retries::RetryLogic,
+            TowerRequestConfig,
+            retries::RetryLogic,
+            service::{HealthConfig, HealthLogic},
         },
     },
     tls::{MaybeTlsSettings, TlsEnableableConfig},
@@ -43,20 +49,41 @@ pub struct VectorConfig {
     ///
     /// Both IP address and hostname are accepted formats.
     ///
-    /// The address _must_ include a port.
+    /// The address _must_ include a port. Either `address` or `endpoints` must be set.
     #[configurable(validation(format = "uri"))]
     #[configurable(metadata(docs::examples = "92.12.333.224:6000"))]
     #[configurable(metadata(docs::examples = "https://somehost:6000"))]
-    address: String,
+    #[serde(default)]
+    address: Option<String>,
 
-    /// Whether or not to compress requests.
+    /// The addresses of the downstream Vector instances to balance the requests across.
     ///
-    /// If set to `true`, requests are compressed with [`gzip`][gzip_docs].
+    /// The addresses have the same format as `address`. The endpoints that fail are ejected until
+    /// a request on probation succeeds, with the backoff set in `distribution`.
+    #[configurable(metadata(docs::examples = "aggregator-0:6000"))]
+    #[configurable(metadata(docs::examples = "aggregator-1:6000"))]
+    #[serde(default)]
+    endpoints: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    balance: BalanceConfig,
+
+    #[configurable(derived)]
+    #[serde(default, rename = "distribution")]
+    endpoint_health: Option<HealthConfig>,
+
+    /// The compression of the requests.
     ///
-    /// [gzip_docs]: https://www.gzip.org/
+    /// If the source doesn't accept the configured scheme, the requests fall back to the preferred
+    /// scheme it accepts. For compatibility, `true` is accepted for `gzip`, and `false` for `none`.
     #[configurable(metadata(docs::advanced))]
+    #[serde(default, deserialize_with = "bool_or_compression")]
+    compression: VectorCompression,
+
+    #[configurable(derived)]
     #[serde(default)]
-    compression: bool,
+    http2: Http2Config,
 
     #[configurable(derived)]
     #[serde(default)]
@@ -96,8 +123,12 @@ impl GenerateConfig for VectorConfig {
 fn default_config(address: &str) -> VectorConfig {
     VectorConfig {
         version: None,
-        address: address.to_owned(),
-        compression: false,
+        address: Some(address.to_owned()),
+        endpoints: Vec::new(),
+        balance: BalanceConfig::default(),
+        endpoint_health: None,
+        compression: VectorCompression::None,
+        http2: Http2Config::default(),
         batch: BatchConfig::default(),
         request: TowerRequestConfig::default(),
         tls: None,
@@ -110,35 +141,80 @@ fn default_config(address: &str) -> VectorConfig {
 impl SinkConfig for VectorConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSinkType, Healthcheck)> {
         let tls = MaybeTlsSettings::from_config(self.tls.as_ref(), false)?;
-        let uri = with_default_scheme(&self.address, tls.is_tls())?;
-
-        let client = new_client(&tls, cx.proxy())?;
-
-        let healthcheck_uri = cx
-            .healthcheck
-            .uri
-            .clone()
-            .map(|uri| uri.uri)
-            .unwrap_or_else(|| uri.clone());
-        let healthcheck_client = VectorService::new(client.clone(), healthcheck_uri, false);
-        let healthcheck = healthcheck(healthcheck_client, cx.healthcheck);
-        let service = VectorService::new(client, uri, self.compression);
+        let endpoints = match (&self.address, self.endpoints.as_slice()) {
+            (Some(address), []) => std::slice::from_ref(address),
+            (None, endpoints) if !endpoints.is_empty() => endpoints,
+            _ => return Err(Box::new(VectorSinkError::AddressOrEndpoints)),
+        };
+        let uris = endpoints
+            .iter()
+            .map(|endpoint| with_default_scheme(endpoint, tls.is_tls()))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let client = new_client(&tls, cx.proxy(), &self.http2)?;
+
+        let healthcheck_uris = match cx.healthcheck.uri.clone() {
+            Some(uri) => vec![uri.uri],
+            None => uris.clone(),
+        };
+        // The sink is healthy if any of its endpoints is.
+        let options = cx.healthcheck.clone();
+        let healthcheck = future::select_ok(healthcheck_uris.into_iter().map(|uri| {
+            let client = VectorService::new(client.clone(), uri, VectorCompression::None);
+            healthcheck(client, options.clone()).boxed()
+        }))
+        .map_ok(|((), _)| ())
+        .boxed();
+
         let request_settings = self.request.into_settings();
         let batch_settings = self.batch.into_batcher_settings()?;
 
-        let service = ServiceBuilder::new()
-            .settings(request_settings, VectorGrpcRetryLogic)
-            .service(service);
+        let mut services = uris
+            .into_iter()
+            .map(|uri| {
+                let endpoint = uri.to_string();
+                (
+                    endpoint,
+                    VectorService::new(client.clone(), uri, self.compression),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let sink = if services.len() == 1 {
+            let (_, service) = services.remove(0);
+            let service = ServiceBuilder::new()
+                .settings(request_settings, VectorGrpcRetryLogic)
+                .service(service);
 
-        let sink = VectorSink {
-            batch_settings,
-            service,
+            VectorSinkType::from_event_streamsink(VectorSink {
+                batch_settings,
+                service,
+                hash_ring: None,
+            })
+        } else {
+            let service = request_settings.balanced_service(
+                VectorGrpcRetryLogic,
+                services,
+                self.endpoint_health.clone().unwrap_or_default(),
+                VectorHealthLogic,
+                1,
+                |services| Balancer::new(&self.balance, services),
+            );
+            let hash_ring = match &self.balance {
+                BalanceConfig::ConsistentHash { key } => {
+                    Some(HashRing::new(key.0.clone(), endpoints))
+                }
+                BalanceConfig::RoundRobin | BalanceConfig::LeastInFlight => None,
+            };
+
+            VectorSinkType::from_event_streamsink(VectorSink {
+                batch_settings,
+                service,
+                hash_ring,
+            })
         };
 
-        Ok((
-            VectorSinkType::from_event_streamsink(sink),
-            Box::pin(healthcheck),
-        ))
+        Ok((sink, healthcheck))
     }
 
     fn input(&self) -> Input {
@@ -209,10 +285,18 @@ pub fn with_default_scheme(address: &str, tls: bool) -> crate::Result<Uri> {
 fn new_client(
     tls_settings: &MaybeTlsSettings,
     proxy_config: &ProxyConfig,
+    http2: &Http2Config,
 ) -> crate::Result<hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>> {
     let proxy = build_proxy_connector(tls_settings.clone(), proxy_config)?;
 
-    Ok(hyper::Client::builder().http2_only(true).build(proxy))
+    // The concurrent requests are multiplexed over the streams of the same connection.
+    Ok(hyper::Client::builder()
+        .http2_only(true)
+        .http2_keep_alive_interval(http2.keepalive_interval())
+        .http2_keep_alive_timeout(http2.keepalive_timeout())
+        .http2_keep_alive_while_idle(true)
+        .http2_adaptive_window(http2.adaptive_window)
+        .build(proxy))
 }
 
 #[derive(Debug, Clone)]
@@ -245,3 +329,29 @@ impl RetryLogic for VectorGrpcRetryLogic {
         }
     }
 }
+
+/// Ejects the endpoints that can't be reached or don't answer in time.
+#[derive(Clone)]
+struct VectorHealthLogic;
+
+impl HealthLogic for VectorHealthLogic {
+    type Error = crate::Error;
+    type Response = VectorResponse;
+
+    fn is_healthy(&self, response: &Result<Self::Response, Self::Error>) -> Option<bool> {
+        use tonic::Code::*;
+
+        match response {
+            Ok(_) => Some(true),
+            Err(error) => match error.downcast_ref::<VectorSinkError>() {
+                Some(VectorSinkError::Request { source }) => {
+                    matches!(source.code(), Unavailable | Unknown | DeadlineExceeded)
+                        .then_some(false)
+                }
+                _ => error
+                    .is::<tower::timeout::error::Elapsed>()
+                    .then_some(false),
+            },
+        }
+    }
+}
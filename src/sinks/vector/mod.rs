@@ -1,6 +1,8 @@
 use snafu::Snafu;
 use vector_lib::configurable::configurable_component;
 
+mod balance;
+mod compression;
 mod config;
 mod service;
 mod sink;
@@ -27,6 +29,9 @@ pub enum VectorSinkError {
 
     #[snafu(display("URL has no host."))]
     NoHost,
+
+    #[snafu(display("Either `address` or `endpoints` must be set, but not both."))]
+    AddressOrEndpoints,
 }
 
 #[cfg(test)]
@@ -179,6 +184,67 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
     }
 
+    #[tokio::test]
+    async fn balances_across_endpoints() {
+        let num_lines = 10;
+
+        let addrs = [next_addr(), next_addr()];
+        let config = format!(
+            r#"
+            endpoints = ["http://{}/", "http://{}/"]
+            batch.max_events = 1
+            "#,
+            addrs[0], addrs[1]
+        );
+        let config: VectorConfig = toml::from_str(&config).unwrap();
+
+        let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+        let mut receivers = Vec::new();
+        let mut triggers = Vec::new();
+        for addr in addrs {
+            let (rx, trigger, server) = build_test_server_generic(addr, move || {
+                hyper::Response::builder()
+                    .header("grpc-status", "0") // OK
+                    .header("content-type", "application/grpc")
+                    .body(hyper::Body::from(encode_body(proto::PushEventsResponse {})))
+                    .unwrap()
+            });
+            tokio::spawn(server);
+            receivers.push(rx);
+            triggers.push(trigger);
+        }
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input_lines, events) = random_lines_with_stream(8, num_lines, Some(batch));
+
+        sink.run(events).await.expect("Running sink failed");
+        drop(triggers);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let mut output_lines = Vec::new();
+        for rx in receivers {
+            let lines = get_received(rx, |_| {}).await;
+            assert!(!lines.is_empty());
+            output_lines.extend(lines);
+        }
+        let mut input_lines = input_lines;
+        input_lines.sort();
+        output_lines.sort();
+        assert_eq!(input_lines, output_lines);
+    }
+
+    #[tokio::test]
+    async fn rejects_address_and_endpoints() {
+        let config: VectorConfig = toml::from_str(
+            r#"
+            address = "127.0.0.1:6000"
+            endpoints = ["127.0.0.1:6001"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.build(SinkContext::default()).await.is_err());
+    }
+
     #[test]
     fn test_with_default_scheme() {
         assert_eq!(
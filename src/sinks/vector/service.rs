@@ -1,7 +1,12 @@
-use std::task::{Context, Poll};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
 
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use futures::{TryFutureExt, future::BoxFuture};
-use http::Uri;
+use http::{HeaderValue, Uri, request::Parts};
 use hyper::client::HttpConnector;
 use hyper_openssl::HttpsConnector;
 use hyper_proxy::ProxyConnector;
@@ -13,7 +18,7 @@ use vector_lib::{
     stream::DriverResponse,
 };
 
-use super::VectorSinkError;
+use super::{VectorSinkError, compression::VectorCompression};
 use crate::{
     Error,
     event::{EventFinalizers, EventStatus, Finalizable},
@@ -48,6 +53,8 @@ pub struct VectorRequest {
     pub finalizers: EventFinalizers,
     pub metadata: RequestMetadata,
     pub request: proto_vector::PushEventsRequest,
+    /// The endpoint owning the key of the events, if they're balanced by key.
+    pub endpoint: Option<usize>,
 }
 
 impl Finalizable for VectorRequest {
@@ -70,17 +77,16 @@ impl VectorService {
     pub fn new(
         hyper_client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
         uri: Uri,
-        compression: bool,
+        compression: VectorCompression,
     ) -> Self {
         let (protocol, endpoint) = uri::protocol_endpoint(uri.clone());
-        let mut proto_client = proto_vector::Client::new(HyperSvc {
+        // The messages are compressed by `HyperSvc` rather than by `tonic`, which only supports gzip.
+        let proto_client = proto_vector::Client::new(HyperSvc {
             uri,
             client: hyper_client,
+            compression: Arc::new(Mutex::new(compression)),
         });
 
-        if compression {
-            proto_client = proto_client.send_compressed(tonic::codec::CompressionEncoding::Gzip);
-        }
         Self {
             client: proto_client,
             protocol,
@@ -132,15 +138,23 @@ impl Service<VectorRequest> for VectorService {
     }
 }
 
+// one byte for the compression flag plus four bytes for the length
+const GRPC_HEADER_SIZE: usize = 5;
+const GRPC_ENCODING_HEADER: &str = "grpc-encoding";
+const GRPC_ACCEPT_ENCODING_HEADER: &str = "grpc-accept-encoding";
+
 #[derive(Clone, Debug)]
 pub struct HyperSvc {
     uri: Uri,
     client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+    /// The compression of the requests, shared by the clones of the service so that all of them
+    /// fall back to the same scheme.
+    compression: Arc<Mutex<VectorCompression>>,
 }
 
 impl Service<hyper::Request<BoxBody>> for HyperSvc {
     type Response = hyper::Response<hyper::Body>;
-    type Error = hyper::Error;
+    type Error = Error;
     type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     // Emission of an internal event in case of errors is handled upstream by the caller.
@@ -159,6 +173,173 @@ impl Service<hyper::Request<BoxBody>> for HyperSvc {
 
         *req.uri_mut() = uri;
 
-        Box::pin(self.client.request(req))
+        let client = self.client.clone();
+        let shared_compression = Arc::clone(&self.compression);
+        let compression = *shared_compression.lock().unwrap();
+        if compression == VectorCompression::None {
+            return Box::pin(client.request(req).err_into());
+        }
+
+        Box::pin(async move {
+            // The body is kept to be sent again if the source doesn't accept the compression.
+            let (parts, body) = req.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            let response = client
+                .request(build_request(&parts, &body, compression)?)
+                .await?;
+            let Some(fallback) = negotiate(&response, compression) else {
+                return Ok(response);
+            };
+
+            warn!(
+                message = "The source doesn't accept the compression of the requests, falling back to another one.",
+                compression = ?compression,
+                fallback = ?fallback,
+            );
+            *shared_compression.lock().unwrap() = fallback;
+            Ok(client
+                .request(build_request(&parts, &body, fallback)?)
+                .await?)
+        })
+    }
+}
+
+/// Builds the request with the given body, compressing its messages.
+fn build_request(
+    parts: &Parts,
+    body: &Bytes,
+    compression: VectorCompression,
+) -> io::Result<hyper::Request<BoxBody>> {
+    let mut request = hyper::Request::new(tonic::body::boxed(http_body::Full::new(
+        compress_messages(body.clone(), compression)?,
+    )));
+    *request.method_mut() = parts.method.clone();
+    *request.uri_mut() = parts.uri.clone();
+    *request.version_mut() = parts.version;
+    *request.headers_mut() = parts.headers.clone();
+    if let Some(encoding) = compression.encoding() {
+        request
+            .headers_mut()
+            .insert(GRPC_ENCODING_HEADER, HeaderValue::from_static(encoding));
+    }
+    Ok(request)
+}
+
+/// Compresses each of the length-prefixed messages of the body, setting their compression flag.
+fn compress_messages(mut body: Bytes, compression: VectorCompression) -> io::Result<Bytes> {
+    if compression == VectorCompression::None {
+        return Ok(body);
+    }
+
+    let mut compressed = BytesMut::with_capacity(body.len());
+    while body.has_remaining() {
+        if body.len() < GRPC_HEADER_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated message header",
+            ));
+        }
+        let len = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+        body.advance(GRPC_HEADER_SIZE);
+        if body.len() < len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated message",
+            ));
+        }
+
+        let message = compression.compress(&body.split_to(len))?;
+        compressed.put_u8(1);
+        compressed.put_u32(message.len() as u32);
+        compressed.extend_from_slice(&message);
+    }
+    Ok(compressed.freeze())
+}
+
+/// The compression to fall back to, if the source rejected the request because it doesn't accept
+/// its compression.
+fn negotiate(
+    response: &hyper::Response<hyper::Body>,
+    compression: VectorCompression,
+) -> Option<VectorCompression> {
+    let headers = response.headers();
+    // The sources reject the unknown compression schemes with `UNIMPLEMENTED`.
+    if headers
+        .get("grpc-status")
+        .is_none_or(|status| status != "12")
+    {
+        return None;
+    }
+
+    let accepted = headers.get(GRPC_ACCEPT_ENCODING_HEADER)?.to_str().ok()?;
+    let accepted = accepted.split(',').map(str::trim).collect::<Vec<_>>();
+    if accepted.contains(&compression.encoding()?) {
+        return None;
+    }
+
+    Some(
+        VectorCompression::FALLBACKS
+            .into_iter()
+            .find(|fallback| {
+                fallback
+                    .encoding()
+                    .is_some_and(|encoding| accepted.contains(&encoding))
+            })
+            .unwrap_or(VectorCompression::None),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejection(accepted: &'static str) -> hyper::Response<hyper::Body> {
+        hyper::Response::builder()
+            .header("grpc-status", "12")
+            .header(GRPC_ACCEPT_ENCODING_HEADER, accepted)
+            .body(hyper::Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn negotiates_accepted_compression() {
+        assert_eq!(
+            negotiate(&rejection("gzip,identity"), VectorCompression::Zstd),
+            Some(VectorCompression::Gzip)
+        );
+        assert_eq!(
+            negotiate(&rejection("identity, lz4, gzip"), VectorCompression::Zstd),
+            Some(VectorCompression::Lz4)
+        );
+        assert_eq!(
+            negotiate(&rejection("identity"), VectorCompression::Gzip),
+            Some(VectorCompression::None)
+        );
+        // The request was rejected for another reason.
+        assert_eq!(
+            negotiate(&rejection("gzip,zstd,identity"), VectorCompression::Zstd),
+            None
+        );
+    }
+
+    #[test]
+    fn compresses_messages() {
+        let mut body = BytesMut::new();
+        for message in [&b"hello"[..], b"world"] {
+            body.put_u8(0);
+            body.put_u32(message.len() as u32);
+            body.extend_from_slice(message);
+        }
+
+        let mut compressed = compress_messages(body.freeze(), VectorCompression::Lz4).unwrap();
+        for message in [&b"hello"[..], b"world"] {
+            assert_eq!(compressed.get_u8(), 1);
+            let len = compressed.get_u32() as usize;
+            let decompressed =
+                lz4_flex::decompress_size_prepended(&compressed.split_to(len)).unwrap();
+            assert_eq!(decompressed, message);
+        }
+        assert!(compressed.is_empty());
     }
 }
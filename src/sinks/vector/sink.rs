@@ -7,11 +7,12 @@ use tower::Service;
 use vector_lib::{
     ByteSizeOf, EstimatedJsonEncodedSizeOf,
     config::telemetry,
+    partition::Partitioner,
     request_metadata::GroupedCountByteSize,
     stream::{BatcherSettings, DriverResponse, batcher::data::BatchReduce},
 };
 
-use super::service::VectorRequest;
+use super::{balance::HashRing, service::VectorRequest};
 use crate::{
     event::{Event, EventFinalizers, Finalizable, proto::EventWrapper},
     proto::vector as proto_vector,
@@ -24,6 +25,25 @@ struct EventData {
     json_byte_size: GroupedCountByteSize,
     finalizers: EventFinalizers,
     wrapper: EventWrapper,
+    endpoint: Option<usize>,
+}
+
+impl ByteSizeOf for EventData {
+    fn allocated_bytes(&self) -> usize {
+        self.byte_size
+    }
+}
+
+/// Batches the events of each endpoint separately, when they're balanced by key.
+struct EndpointPartitioner;
+
+impl Partitioner for EndpointPartitioner {
+    type Item = EventData;
+    type Key = Option<usize>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        item.endpoint
+    }
 }
 
 /// Temporary struct to collect events during batching.
@@ -49,6 +69,7 @@ impl Default for EventCollection {
 pub struct VectorSink<S> {
     pub batch_settings: BatcherSettings,
     pub service: S,
+    pub hash_ring: Option<HashRing>,
 }
 
 impl<S> VectorSink<S>
@@ -59,8 +80,10 @@ where
     S::Error: fmt::Debug + Into<crate::Error> + Send,
 {
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let hash_ring = self.hash_ring;
+        let batch_settings = self.batch_settings;
         input
-            .map(|mut event| {
+            .map(move |mut event| {
                 let mut byte_size = telemetry().create_request_count_byte_size();
                 byte_size.add_event(&event, event.estimated_json_encoded_size_of());
 
@@ -68,19 +91,22 @@ where
                     byte_size: event.size_of(),
                     json_byte_size: byte_size,
                     finalizers: event.take_finalizers(),
+                    endpoint: hash_ring.as_ref().and_then(|ring| ring.endpoint(&event)),
                     wrapper: EventWrapper::from(event),
                 }
             })
-            .batched(self.batch_settings.as_reducer_config(
-                |data: &EventData| data.wrapper.encoded_len(),
-                BatchReduce::new(|event_collection: &mut EventCollection, item: EventData| {
-                    event_collection.finalizers.merge(item.finalizers);
-                    event_collection.events.push(item.wrapper);
-                    event_collection.events_byte_size += item.byte_size;
-                    event_collection.events_json_byte_size += item.json_byte_size;
-                }),
-            ))
-            .map(|event_collection| {
+            .batched_partitioned(EndpointPartitioner, || {
+                batch_settings.as_reducer_config(
+                    |data: &EventData| data.wrapper.encoded_len(),
+                    BatchReduce::new(|event_collection: &mut EventCollection, item: EventData| {
+                        event_collection.finalizers.merge(item.finalizers);
+                        event_collection.events.push(item.wrapper);
+                        event_collection.events_byte_size += item.byte_size;
+                        event_collection.events_json_byte_size += item.json_byte_size;
+                    }),
+                )
+            })
+            .map(|(endpoint, event_collection)| {
                 let builder = RequestMetadataBuilder::new(
                     event_collection.events.len(),
                     event_collection.events_byte_size,
@@ -99,6 +125,7 @@ where
                     finalizers: event_collection.finalizers,
                     metadata: builder.with_request_size(bytes_len),
                     request: encoded_events,
+                    endpoint,
                 }
             })
             .into_driver(self.service)
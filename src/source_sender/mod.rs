@@ -15,7 +15,10 @@ use vector_lib::{
         topology::channel::{self, LimitedReceiver, LimitedSender},
     },
     config::{SourceOutput, log_schema},
-    event::{Event, EventArray, EventContainer, EventRef, array, array::EventArrayIntoIter},
+    event::{
+        Event, EventArray, EventContainer, EventRef, array, array::EventArrayIntoIter,
+        trace_sampling,
+    },
     finalization::{AddBatchNotifier, BatchNotifier},
     internal_event::{
         self, ComponentEventsDropped, CountByteSize, DEFAULT_OUTPUT, EventsSent,
@@ -277,6 +280,14 @@ impl SourceSender {
         self.default_output.as_mut().expect("no default output")
     }
 
+    /// Returns the number of events waiting in the buffer of the default output, along with the
+    /// capacity of that buffer, or `None` if there is no default output.
+    ///
+    /// The buffer fills up when the components downstream of the source can't keep up with it.
+    pub fn default_output_buffer_usage(&self) -> Option<(usize, usize)> {
+        self.default_output.as_ref().map(Output::buffer_usage)
+    }
+
     /// Send an event to the default output.
     ///
     /// This internally handles emitting [EventsSent] and [ComponentEventsDropped] events.
@@ -410,6 +421,12 @@ impl Output {
         )
     }
 
+    fn buffer_usage(&self) -> (usize, usize) {
+        let capacity = self.sender.capacity();
+        let buffered = capacity.saturating_sub(self.sender.available_capacity());
+        (buffered, capacity)
+    }
+
     async fn send(
         &mut self,
         mut events: EventArray,
@@ -429,6 +446,7 @@ impl Output {
             event
                 .metadata_mut()
                 .set_upstream_id(Arc::clone(&self.output_id));
+            trace_sampling::sample(event.metadata_mut());
         });
 
         let byte_size = events.estimated_json_encoded_size_of();
@@ -703,4 +721,20 @@ mod tests {
         };
         assert_eq!(*value, expected_drop as f64);
     }
+
+    #[tokio::test]
+    async fn reports_default_output_buffer_usage() {
+        let (mut sender, mut recv) = SourceSender::new_test_sender_with_buffer(10);
+        assert_eq!(sender.default_output_buffer_usage(), Some((0, 10)));
+
+        let events = (0..3).map(|i| Event::Log(LogEvent::from(format!("event {i}"))));
+        sender
+            .send_batch(events.collect::<Vec<_>>())
+            .await
+            .expect("Send should not fail");
+        assert_eq!(sender.default_output_buffer_usage(), Some((3, 10)));
+
+        recv.next().await.expect("Receive should not fail");
+        assert_eq!(sender.default_output_buffer_usage(), Some((0, 10)));
+    }
 }
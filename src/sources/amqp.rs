@@ -1,13 +1,19 @@
 //! `AMQP` source.
 //! Handles version AMQP 0.9.1 which is used by RabbitMQ.
-use std::{io::Cursor, pin::Pin};
+use std::{io::Cursor, pin::Pin, time::Duration};
 
 use async_stream::stream;
 use bytes::Bytes;
 use chrono::{TimeZone, Utc};
 use futures::{FutureExt, StreamExt};
 use futures_util::Stream;
-use lapin::{Channel, acker::Acker, message::Delivery};
+use lapin::{
+    Channel, Consumer,
+    acker::Acker,
+    message::Delivery,
+    options::{BasicQosOptions, QueueBindOptions, QueueDeclareOptions},
+    types::{AMQPValue, FieldTable},
+};
 use snafu::Snafu;
 use tokio_util::codec::FramedRead;
 use vector_lib::{
@@ -26,13 +32,16 @@ use crate::{
     SourceSender,
     amqp::AmqpConfig,
     codecs::{Decoder, DecodingConfig},
+    common::backoff::ExponentialBackoff,
     config::{SourceConfig, SourceContext, SourceOutput},
     event::{BatchNotifier, BatchStatus},
     internal_events::{
         StreamClosedError,
-        source::{AmqpAckError, AmqpBytesReceived, AmqpEventError, AmqpRejectError},
+        source::{
+            AmqpAckError, AmqpBytesReceived, AmqpConnectionError, AmqpEventError, AmqpRejectError,
+        },
     },
-    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based, default_true},
     shutdown::ShutdownSignal,
 };
 
@@ -42,6 +51,119 @@ enum BuildError {
     AmqpCreateError {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    #[snafu(display("Could not declare AMQP queue: {}", source))]
+    AmqpDeclareError { source: lapin::Error },
+}
+
+/// The type of a declared queue.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AmqpQueueType {
+    /// A classic queue.
+    #[default]
+    Classic,
+
+    /// A [quorum queue][quorum_queues], replicated across the nodes of the cluster.
+    ///
+    /// [quorum_queues]: https://www.rabbitmq.com/docs/quorum-queues
+    Quorum,
+}
+
+/// A binding of the declared queue to an exchange.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpQueueBinding {
+    /// The exchange the queue is bound to.
+    #[configurable(metadata(docs::examples = "logs"))]
+    pub(crate) exchange: String,
+
+    /// The routing key of the binding.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "app.*"))]
+    pub(crate) routing_key: String,
+}
+
+/// Declares the queue, and its bindings, whenever the source connects to the broker.
+///
+/// This recreates the queue if it was deleted while the source was disconnected. The
+/// declaration fails if the queue already exists with different settings.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpQueueDeclareConfig {
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) queue_type: AmqpQueueType,
+
+    /// Whether the queue survives the restarts of the broker.
+    ///
+    /// Quorum queues are always durable.
+    #[serde(default = "default_true")]
+    pub(crate) durable: bool,
+
+    /// The number of times a message is delivered before it's dropped, or dead-lettered.
+    ///
+    /// This only applies to quorum queues, and bounds the redeliveries of the messages whose events
+    /// failed to be delivered.
+    #[configurable(metadata(docs::examples = 10))]
+    pub(crate) delivery_limit: Option<u32>,
+
+    /// The exchange the rejected messages are dead-lettered to.
+    #[configurable(metadata(docs::examples = "logs.dead"))]
+    pub(crate) dead_letter_exchange: Option<String>,
+
+    /// The bindings of the queue to exchanges.
+    #[serde(default)]
+    pub(crate) bindings: Vec<AmqpQueueBinding>,
+}
+
+impl AmqpQueueDeclareConfig {
+    fn arguments(&self) -> FieldTable {
+        let mut arguments = FieldTable::default();
+        if self.queue_type == AmqpQueueType::Quorum {
+            arguments.insert(
+                "x-queue-type".into(),
+                AMQPValue::LongString("quorum".into()),
+            );
+            if let Some(delivery_limit) = self.delivery_limit {
+                arguments.insert(
+                    "x-delivery-limit".into(),
+                    AMQPValue::LongUInt(delivery_limit),
+                );
+            }
+        }
+        if let Some(exchange) = &self.dead_letter_exchange {
+            arguments.insert(
+                "x-dead-letter-exchange".into(),
+                AMQPValue::LongString(exchange.as_str().into()),
+            );
+        }
+        arguments
+    }
+
+    async fn declare(&self, channel: &Channel, queue: &str) -> Result<(), lapin::Error> {
+        let options = QueueDeclareOptions {
+            durable: self.durable || self.queue_type == AmqpQueueType::Quorum,
+            ..Default::default()
+        };
+        channel
+            .queue_declare(queue, options, self.arguments())
+            .await?;
+        for binding in &self.bindings {
+            channel
+                .queue_bind(
+                    queue,
+                    &binding.exchange,
+                    &binding.routing_key,
+                    QueueBindOptions::default(),
+                    FieldTable::default(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 /// Configuration for the `amqp` source.
@@ -100,6 +222,16 @@ pub struct AmqpSourceConfig {
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     pub(crate) acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The maximum number of messages delivered to the source and not acknowledged yet.
+    ///
+    /// By default, this is unlimited. Setting it bounds the events in flight, and lets the other
+    /// consumers of the queue receive the messages that this source can't keep up with.
+    #[configurable(metadata(docs::examples = 500))]
+    pub(crate) prefetch_count: Option<u16>,
+
+    #[configurable(derived)]
+    pub(crate) declare: Option<AmqpQueueDeclareConfig>,
 }
 
 fn default_queue() -> String {
@@ -215,6 +347,12 @@ pub(crate) async fn amqp_source(
         .connect()
         .await
         .map_err(|source| BuildError::AmqpCreateError { source })?;
+    if let Some(declare) = &config.declare {
+        declare
+            .declare(&channel, &config.queue)
+            .await
+            .map_err(|source| BuildError::AmqpDeclareError { source })?;
+    }
 
     Ok(Box::pin(run_amqp_source(
         config,
@@ -411,6 +549,9 @@ async fn finalize_event_stream(
 }
 
 /// Runs the `AMQP` source involving the main loop pulling data from the server.
+///
+/// The source reconnects to the server, and declares the queue again, whenever the channel is
+/// closed.
 async fn run_amqp_source(
     config: AmqpSourceConfig,
     shutdown: ShutdownSignal,
@@ -421,48 +562,83 @@ async fn run_amqp_source(
 ) -> Result<(), ()> {
     let (finalizer, mut ack_stream) =
         UnorderedFinalizer::<FinalizerEntry>::maybe_new(acknowledgements, Some(shutdown.clone()));
-
-    debug!("Starting amqp source, listening to queue {}.", config.queue);
-    let mut consumer = channel
-        .basic_consume(
-            &config.queue,
-            &config.consumer,
-            lapin::options::BasicConsumeOptions::default(),
-            lapin::types::FieldTable::default(),
-        )
-        .await
-        .map_err(|error| {
-            error!(message = "Failed to consume.", ?error);
-        })?
-        .fuse();
     let mut shutdown = shutdown.fuse();
+    let mut channel = Some(channel);
+    let mut backoff = fresh_backoff();
+
     loop {
-        tokio::select! {
-            _ = &mut shutdown => break,
-            entry = ack_stream.next() => {
-                if let Some((status, entry)) = entry {
-                    handle_ack(status, entry).await;
-                }
-            },
-            opt_m = consumer.next() => {
-                if let Some(try_m) = opt_m {
-                    match try_m {
-                        Err(error) => {
-                            emit!(AmqpEventError { error });
-                            return Err(());
-                        }
-                        Ok(msg) => {
-                            receive_event(&config, &mut out, log_namespace, finalizer.as_ref(), msg).await?
+        let consumer = match channel.take() {
+            // The queue was declared when building the source.
+            Some(channel) => consume(&config, &channel).await,
+            None => reconnect(&config).await,
+        };
+        match consumer {
+            Ok(mut consumer) => {
+                debug!("Starting amqp source, listening to queue {}.", config.queue);
+                loop {
+                    tokio::select! {
+                        _ = &mut shutdown => return Ok(()),
+                        entry = ack_stream.next() => {
+                            if let Some((status, entry)) = entry {
+                                handle_ack(status, entry).await;
+                            }
+                        },
+                        opt_m = consumer.next() => {
+                            match opt_m {
+                                Some(Ok(msg)) => {
+                                    backoff.reset();
+                                    receive_event(&config, &mut out, log_namespace, finalizer.as_ref(), msg).await?
+                                }
+                                Some(Err(error)) => {
+                                    emit!(AmqpEventError { error });
+                                    break;
+                                }
+                                // The consumer was cancelled by the server.
+                                None => break,
+                            }
                         }
-                    }
-                } else {
-                    break
+                    };
                 }
             }
-        };
+            Err(error) => emit!(AmqpConnectionError { error }),
+        }
+
+        tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            _ = tokio::time::sleep(backoff.next().unwrap()) => {},
+        }
     }
+}
 
-    Ok(())
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+async fn reconnect(config: &AmqpSourceConfig) -> crate::Result<Consumer> {
+    let (_conn, channel) = config.connection.connect().await?;
+    if let Some(declare) = &config.declare {
+        declare.declare(&channel, &config.queue).await?;
+    }
+    consume(config, &channel).await
+}
+
+async fn consume(config: &AmqpSourceConfig, channel: &Channel) -> crate::Result<Consumer> {
+    if let Some(prefetch_count) = config.prefetch_count {
+        channel
+            .basic_qos(prefetch_count, BasicQosOptions::default())
+            .await?;
+    }
+    let consumer = channel
+        .basic_consume(
+            &config.queue,
+            &config.consumer,
+            lapin::options::BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+    Ok(consumer)
 }
 
 async fn handle_ack(status: BatchStatus, entry: FinalizerEntry) {
@@ -474,12 +650,14 @@ async fn handle_ack(status: BatchStatus, entry: FinalizerEntry) {
             }
         }
         BatchStatus::Errored => {
-            let ack_options = lapin::options::BasicRejectOptions::default();
+            // The message is redelivered, to this consumer or another one.
+            let ack_options = lapin::options::BasicRejectOptions { requeue: true };
             if let Err(error) = entry.acker.reject(ack_options).await {
                 emit!(AmqpRejectError { error });
             }
         }
         BatchStatus::Rejected => {
+            // The message is dropped, or dead-lettered.
             let ack_options = lapin::options::BasicRejectOptions::default();
             if let Err(error) = entry.acker.reject(ack_options).await {
                 emit!(AmqpRejectError { error });
@@ -490,6 +668,7 @@ async fn handle_ack(status: BatchStatus, entry: FinalizerEntry) {
 
 #[cfg(test)]
 pub mod test {
+    use lapin::types::ShortString;
     use vector_lib::{lookup::OwnedTargetPath, schema::Definition, tls::TlsConfig};
     use vrl::value::kind::Collection;
 
@@ -500,6 +679,41 @@ pub mod test {
         crate::test_util::test_generate_config::<AmqpSourceConfig>();
     }
 
+    #[test]
+    fn quorum_queue_arguments() {
+        let config: AmqpQueueDeclareConfig = toml::from_str(
+            r#"
+            queue_type = "quorum"
+            delivery_limit = 10
+            dead_letter_exchange = "logs.dead"
+            "#,
+        )
+        .unwrap();
+        let arguments = config.arguments();
+        let arguments = arguments.inner();
+
+        assert_eq!(arguments.len(), 3);
+        assert_eq!(
+            arguments.get(&ShortString::from("x-queue-type")),
+            Some(&AMQPValue::LongString("quorum".into()))
+        );
+        assert_eq!(
+            arguments.get(&ShortString::from("x-delivery-limit")),
+            Some(&AMQPValue::LongUInt(10))
+        );
+        assert_eq!(
+            arguments.get(&ShortString::from("x-dead-letter-exchange")),
+            Some(&AMQPValue::LongString("logs.dead".into()))
+        );
+    }
+
+    #[test]
+    fn classic_queue_arguments() {
+        let config: AmqpQueueDeclareConfig = toml::from_str("delivery_limit = 10").unwrap();
+        assert!(config.arguments().inner().is_empty());
+        assert!(config.durable);
+    }
+
     pub fn make_config() -> AmqpSourceConfig {
         let mut config = AmqpSourceConfig {
             queue: "it".to_string(),
@@ -764,6 +978,62 @@ mod integration_test {
         source_consume_event(config).await;
     }
 
+    #[tokio::test]
+    async fn amqp_source_declares_quorum_queue() {
+        let mut config = make_config();
+        await_connection(&config.connection).await;
+
+        let exchange = format!("test-{}-exchange", random_string(10));
+        config.queue = format!("test-{}-queue", random_string(10));
+        config.consumer = format!("test-consumer-{}", random_string(10));
+        config.prefetch_count = Some(10);
+        config.declare = Some(AmqpQueueDeclareConfig {
+            queue_type: AmqpQueueType::Quorum,
+            durable: true,
+            delivery_limit: Some(5),
+            dead_letter_exchange: None,
+            bindings: vec![AmqpQueueBinding {
+                exchange: exchange.clone(),
+                routing_key: "my_key".to_owned(),
+            }],
+        });
+
+        let (_conn, channel) = config.connection.connect().await.unwrap();
+        channel
+            .exchange_declare(
+                &exchange,
+                lapin::ExchangeKind::Direct,
+                ExchangeDeclareOptions {
+                    auto_delete: true,
+                    ..Default::default()
+                },
+                lapin::types::FieldTable::default(),
+            )
+            .await
+            .unwrap();
+
+        // The queue is declared, and bound, when the source is built.
+        let (tx, rx) = SourceSender::new_test();
+        let source = amqp_source(
+            &config,
+            ShutdownSignal::noop(),
+            tx,
+            LogNamespace::Legacy,
+            false,
+        )
+        .await
+        .unwrap();
+        tokio::spawn(source);
+
+        send_event(&channel, &exchange, "my_key", "my message", 0).await;
+
+        let events = crate::test_util::collect_n(rx, 1).await;
+        assert_eq!(
+            *events[0].as_log().get_message().unwrap(),
+            "my message".into()
+        );
+    }
+
     #[tokio::test]
     async fn amqp_tls_source_consume_event() {
         let config = make_tls_config();
@@ -11,7 +11,7 @@ use vector_lib::{
 use warp::{Filter, http::StatusCode};
 
 use super::{
-    Compression,
+    Compression, RecordFormat,
     errors::{ParseSnafu, RequestError},
     handlers,
     models::{FirehoseRequest, FirehoseResponse},
@@ -26,6 +26,7 @@ pub fn firehose(
     access_keys: Vec<String>,
     store_access_key: bool,
     record_compression: Compression,
+    record_format: RecordFormat,
     decoder: codecs::Decoder,
     acknowledgements: bool,
     out: SourceSender,
@@ -34,6 +35,7 @@ pub fn firehose(
     let bytes_received = register!(BytesReceived::from(Protocol::HTTP));
     let context = handlers::Context {
         compression: record_compression,
+        record_format,
         store_access_key,
         decoder,
         acknowledgements,
@@ -2,7 +2,7 @@ use std::io::Read;
 
 use base64::prelude::{BASE64_STANDARD, Engine as _};
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use flate2::read::MultiGzDecoder;
 use futures::StreamExt;
 use snafu::{ResultExt, Snafu};
@@ -23,15 +23,18 @@ use vrl::compiler::SecretTarget;
 use warp::reject;
 
 use super::{
-    Compression,
+    Compression, RecordFormat,
     errors::{ParseRecordsSnafu, RequestError},
-    models::{EncodedFirehoseRecord, FirehoseRequest, FirehoseResponse},
+    models::{
+        CloudwatchLogsMessageType, CloudwatchLogsSubscriptionMessage, EncodedFirehoseRecord,
+        FirehoseRequest, FirehoseResponse,
+    },
 };
 use crate::{
     SourceSender,
     codecs::Decoder,
     config::log_schema,
-    event::{BatchStatus, Event},
+    event::{BatchStatus, Event, LogEvent},
     internal_events::{
         AwsKinesisFirehoseAutomaticRecordDecodeError, EventsReceived, StreamClosedError,
     },
@@ -41,6 +44,7 @@ use crate::{
 #[derive(Clone)]
 pub(super) struct Context {
     pub(super) compression: Compression,
+    pub(super) record_format: RecordFormat,
     pub(super) store_access_key: bool,
     pub(super) decoder: Decoder,
     pub(super) acknowledgements: bool,
@@ -67,116 +71,134 @@ pub(super) async fn firehose(
             .map_err(reject::custom)?;
         context.bytes_received.emit(ByteSize(bytes.len()));
 
-        let mut stream = FramedRead::new(bytes.as_ref(), context.decoder.clone());
-        loop {
-            match stream.next().await {
-                Some(Ok((mut events, _byte_size))) => {
-                    events_received.emit(CountByteSize(
-                        events.len(),
-                        events.estimated_json_encoded_size_of(),
-                    ));
-
-                    let (batch, receiver) = if context.acknowledgements {
-                        {
-                            let (batch, receiver) = BatchNotifier::new_with_receiver();
-                            (Some(batch), Some(receiver))
-                        }
-                    } else {
-                        (None, None)
-                    };
-
-                    let now = Utc::now();
-                    for event in &mut events {
-                        if let Some(batch) = &batch {
-                            event.add_batch_notifier(batch.clone());
-                        }
-                        if let Event::Log(log) = event {
-                            log_namespace.insert_vector_metadata(
-                                log,
-                                log_schema().source_type_key(),
-                                path!("source_type"),
-                                Bytes::from_static(AwsKinesisFirehoseConfig::NAME.as_bytes()),
-                            );
-                            // This handles the transition from the original timestamp logic. Originally the
-                            // `timestamp_key` was always populated by the `request.timestamp` time.
-                            match log_namespace {
-                                LogNamespace::Vector => {
-                                    log.insert(metadata_path!("vector", "ingest_timestamp"), now);
-                                    log.insert(
-                                        metadata_path!(AwsKinesisFirehoseConfig::NAME, "timestamp"),
-                                        request.timestamp,
-                                    );
-                                }
-                                LogNamespace::Legacy => {
-                                    if let Some(timestamp_key) = log_schema().timestamp_key() {
-                                        log.try_insert(
-                                            (PathPrefix::Event, timestamp_key),
-                                            request.timestamp,
+        let payloads = split_record(bytes, context.record_format, request.timestamp)
+            .with_context(|_| ParseRecordsSnafu {
+                request_id: request_id.clone(),
+            })
+            .map_err(reject::custom)?;
+
+        for payload in payloads {
+            let mut stream = FramedRead::new(payload.bytes.as_ref(), context.decoder.clone());
+            loop {
+                match stream.next().await {
+                    Some(Ok((mut events, _byte_size))) => {
+                        events_received.emit(CountByteSize(
+                            events.len(),
+                            events.estimated_json_encoded_size_of(),
+                        ));
+
+                        let (batch, receiver) = if context.acknowledgements {
+                            {
+                                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                (Some(batch), Some(receiver))
+                            }
+                        } else {
+                            (None, None)
+                        };
+
+                        let now = Utc::now();
+                        for event in &mut events {
+                            if let Some(batch) = &batch {
+                                event.add_batch_notifier(batch.clone());
+                            }
+                            if let Event::Log(log) = event {
+                                log_namespace.insert_vector_metadata(
+                                    log,
+                                    log_schema().source_type_key(),
+                                    path!("source_type"),
+                                    Bytes::from_static(AwsKinesisFirehoseConfig::NAME.as_bytes()),
+                                );
+                                // This handles the transition from the original timestamp logic. Originally the
+                                // `timestamp_key` was always populated by the `request.timestamp` time.
+                                match log_namespace {
+                                    LogNamespace::Vector => {
+                                        log.insert(
+                                            metadata_path!("vector", "ingest_timestamp"),
+                                            now,
                                         );
+                                        log.insert(
+                                            metadata_path!(
+                                                AwsKinesisFirehoseConfig::NAME,
+                                                "timestamp"
+                                            ),
+                                            payload.timestamp,
+                                        );
+                                    }
+                                    LogNamespace::Legacy => {
+                                        if let Some(timestamp_key) = log_schema().timestamp_key() {
+                                            log.try_insert(
+                                                (PathPrefix::Event, timestamp_key),
+                                                payload.timestamp,
+                                            );
+                                        }
                                     }
+                                };
+
+                                log_namespace.insert_source_metadata(
+                                    AwsKinesisFirehoseConfig::NAME,
+                                    log,
+                                    Some(LegacyKey::InsertIfEmpty(path!("request_id"))),
+                                    path!("request_id"),
+                                    request_id.to_owned(),
+                                );
+                                log_namespace.insert_source_metadata(
+                                    AwsKinesisFirehoseConfig::NAME,
+                                    log,
+                                    Some(LegacyKey::InsertIfEmpty(path!("source_arn"))),
+                                    path!("source_arn"),
+                                    source_arn.to_owned(),
+                                );
+                                if let Some(subscription) = &payload.subscription {
+                                    subscription.insert(log, log_namespace);
                                 }
-                            };
 
-                            log_namespace.insert_source_metadata(
-                                AwsKinesisFirehoseConfig::NAME,
-                                log,
-                                Some(LegacyKey::InsertIfEmpty(path!("request_id"))),
-                                path!("request_id"),
-                                request_id.to_owned(),
-                            );
-                            log_namespace.insert_source_metadata(
-                                AwsKinesisFirehoseConfig::NAME,
-                                log,
-                                Some(LegacyKey::InsertIfEmpty(path!("source_arn"))),
-                                path!("source_arn"),
-                                source_arn.to_owned(),
-                            );
-
-                            if context.store_access_key
-                                && let Some(access_key) = &request.access_key
-                            {
-                                log.metadata_mut()
-                                    .secrets_mut()
-                                    .insert_secret("aws_kinesis_firehose_access_key", access_key);
+                                if context.store_access_key
+                                    && let Some(access_key) = &request.access_key
+                                {
+                                    log.metadata_mut().secrets_mut().insert_secret(
+                                        "aws_kinesis_firehose_access_key",
+                                        access_key,
+                                    );
+                                }
                             }
                         }
-                    }
 
-                    let count = events.len();
-                    if let Err(error) = context.out.send_batch(events).await {
-                        emit!(StreamClosedError { count });
-                        let error = RequestError::ShuttingDown {
-                            request_id: request_id.clone(),
-                            source: error,
-                        };
-                        warp::reject::custom(error);
-                    }
+                        let count = events.len();
+                        if let Err(error) = context.out.send_batch(events).await {
+                            emit!(StreamClosedError { count });
+                            let error = RequestError::ShuttingDown {
+                                request_id: request_id.clone(),
+                                source: error,
+                            };
+                            warp::reject::custom(error);
+                        }
 
-                    drop(batch);
-                    if let Some(receiver) = receiver {
-                        match receiver.await {
-                            BatchStatus::Delivered => Ok(()),
-                            BatchStatus::Rejected => {
-                                Err(warp::reject::custom(RequestError::DeliveryFailed {
-                                    request_id: request_id.clone(),
-                                }))
-                            }
-                            BatchStatus::Errored => {
-                                Err(warp::reject::custom(RequestError::DeliveryErrored {
-                                    request_id: request_id.clone(),
-                                }))
-                            }
-                        }?;
+                        drop(batch);
+                        if let Some(receiver) = receiver {
+                            match receiver.await {
+                                BatchStatus::Delivered => Ok(()),
+                                BatchStatus::Rejected => {
+                                    Err(warp::reject::custom(RequestError::DeliveryFailed {
+                                        request_id: request_id.clone(),
+                                    }))
+                                }
+                                BatchStatus::Errored => {
+                                    Err(warp::reject::custom(RequestError::DeliveryErrored {
+                                        request_id: request_id.clone(),
+                                    }))
+                                }
+                            }?;
+                        }
                     }
-                }
-                Some(Err(error)) => {
-                    // Error is logged by `crate::codecs::Decoder`, no further
-                    // handling is needed here.
-                    if !error.can_continue() {
-                        break;
+                    Some(Err(error)) => {
+                        // Error is logged by `crate::codecs::Decoder`, no further
+                        // handling is needed here.
+                        if !error.can_continue() {
+                            break;
+                        }
                     }
+                    None => break,
                 }
-                None => break,
             }
         }
     }
@@ -188,10 +210,93 @@ pub(super) async fn firehose(
     }))
 }
 
+/// A payload to decode into events, along with the metadata of the record it was read from.
+struct Payload {
+    bytes: Bytes,
+    timestamp: DateTime<Utc>,
+    subscription: Option<SubscriptionMetadata>,
+}
+
+/// The metadata of a log event read from a CloudWatch Logs subscription message.
+struct SubscriptionMetadata {
+    id: String,
+    log_group: String,
+    log_stream: String,
+    owner: String,
+    subscription_filters: Vec<String>,
+}
+
+impl SubscriptionMetadata {
+    fn insert(&self, log: &mut LogEvent, log_namespace: LogNamespace) {
+        for (key, value) in [
+            ("id", &self.id),
+            ("log_group", &self.log_group),
+            ("log_stream", &self.log_stream),
+            ("owner", &self.owner),
+        ] {
+            log_namespace.insert_source_metadata(
+                AwsKinesisFirehoseConfig::NAME,
+                log,
+                Some(LegacyKey::InsertIfEmpty(path!(key))),
+                path!(key),
+                value.to_owned(),
+            );
+        }
+        log_namespace.insert_source_metadata(
+            AwsKinesisFirehoseConfig::NAME,
+            log,
+            Some(LegacyKey::InsertIfEmpty(path!("subscription_filters"))),
+            path!("subscription_filters"),
+            self.subscription_filters.clone(),
+        );
+    }
+}
+
+/// Splits a decoded record into the payloads to decode into events, according to the record format.
+fn split_record(
+    bytes: Bytes,
+    record_format: RecordFormat,
+    timestamp: DateTime<Utc>,
+) -> Result<Vec<Payload>, RecordDecodeError> {
+    match record_format {
+        RecordFormat::Raw => Ok(vec![Payload {
+            bytes,
+            timestamp,
+            subscription: None,
+        }]),
+        RecordFormat::CloudwatchLogsSubscription => {
+            let mut payloads = Vec::new();
+            for message in serde_json::Deserializer::from_slice(&bytes)
+                .into_iter::<CloudwatchLogsSubscriptionMessage>()
+            {
+                let message = message.context(CloudwatchLogsSubscriptionSnafu {})?;
+                if message.message_type != CloudwatchLogsMessageType::DataMessage {
+                    continue;
+                }
+
+                payloads.extend(message.log_events.into_iter().map(|log_event| Payload {
+                    bytes: Bytes::from(log_event.message),
+                    timestamp: log_event.timestamp,
+                    subscription: Some(SubscriptionMetadata {
+                        id: log_event.id,
+                        log_group: message.log_group.clone(),
+                        log_stream: message.log_stream.clone(),
+                        owner: message.owner.clone(),
+                        subscription_filters: message.subscription_filters.clone(),
+                    }),
+                }));
+            }
+            Ok(payloads)
+        }
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum RecordDecodeError {
     #[snafu(display("Could not base64 decode request data: {}", source))]
     Base64 { source: base64::DecodeError },
+    #[snafu(display("Could not parse CloudWatch Logs subscription message: {}", source))]
+    CloudwatchLogsSubscription { source: serde_json::Error },
     #[snafu(display("Could not decompress request data as {}: {}", compression, source))]
     Decompression {
         source: std::io::Error,
@@ -271,4 +376,54 @@ mod tests {
         let compressed = encoder.finish().unwrap();
         assert!(is_gzip(&compressed));
     }
+
+    #[test]
+    fn splits_cloudwatch_logs_subscription_messages() {
+        let record = br#"{"messageType":"CONTROL_MESSAGE","owner":"CloudwatchLogs","logGroup":"","logStream":"","subscriptionFilters":[],"logEvents":[{"id":"","timestamp":1600110003794,"message":"CWL CONTROL MESSAGE: Checking health of destination Firehose."}]}
+{"messageType":"DATA_MESSAGE","owner":"111111111111","logGroup":"/test/group","logStream":"stream","subscriptionFilters":["Destination"],"logEvents":[{"id":"1","timestamp":1600110569039,"message":"first"},{"id":"2","timestamp":1600110569041,"message":"second"}]}"#;
+
+        let payloads = split_record(
+            Bytes::from_static(record),
+            RecordFormat::CloudwatchLogsSubscription,
+            Utc::now(),
+        )
+        .unwrap();
+
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].bytes, "first");
+        assert_eq!(payloads[1].bytes, "second");
+        assert_eq!(payloads[1].timestamp.timestamp_millis(), 1600110569041);
+
+        let subscription = payloads[0].subscription.as_ref().unwrap();
+        assert_eq!(subscription.id, "1");
+        assert_eq!(subscription.log_group, "/test/group");
+        assert_eq!(subscription.log_stream, "stream");
+        assert_eq!(subscription.owner, "111111111111");
+        assert_eq!(subscription.subscription_filters, vec!["Destination"]);
+    }
+
+    #[test]
+    fn rejects_invalid_cloudwatch_logs_subscription_messages() {
+        let result = split_record(
+            Bytes::from_static(CONTENT),
+            RecordFormat::CloudwatchLogsSubscription,
+            Utc::now(),
+        );
+        assert!(matches!(
+            result,
+            Err(RecordDecodeError::CloudwatchLogsSubscription { .. })
+        ));
+    }
+
+    #[test]
+    fn raw_records_are_not_split() {
+        let timestamp = Utc::now();
+        let payloads =
+            split_record(Bytes::from_static(CONTENT), RecordFormat::Raw, timestamp).unwrap();
+
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].bytes, CONTENT);
+        assert_eq!(payloads[0].timestamp, timestamp);
+        assert!(payloads[0].subscription.is_none());
+    }
 }
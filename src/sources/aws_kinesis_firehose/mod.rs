@@ -13,7 +13,7 @@ use vector_lib::{
     sensitive_string::SensitiveString,
     tls::MaybeTlsIncomingStream,
 };
-use vrl::value::Kind;
+use vrl::value::{Kind, kind::Collection};
 
 use crate::{
     codecs::DecodingConfig,
@@ -79,6 +79,10 @@ pub struct AwsKinesisFirehoseConfig {
     #[serde(default)]
     record_compression: Compression,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    record_format: RecordFormat,
+
     #[configurable(derived)]
     tls: Option<TlsEnableableConfig>,
 
@@ -147,6 +151,26 @@ impl fmt::Display for Compression {
     }
 }
 
+/// The format of the records within the Firehose message, once decompressed.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// Each record is decoded with the configured `framing` and `decoding`.
+    #[default]
+    Raw,
+
+    /// Each record is a [CloudWatch Logs subscription message][subscription_message].
+    ///
+    /// Every log event of the message is decoded with the configured `framing` and `decoding`,
+    /// and annotated with the log group, log stream, owner, and subscription filters of the
+    /// message. The timestamp of the log event is used as the event timestamp. Control messages
+    /// are discarded.
+    ///
+    /// [subscription_message]: https://docs.aws.amazon.com/AmazonCloudWatch/latest/logs/SubscriptionFilters.html#FirehoseExample
+    CloudwatchLogsSubscription,
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "aws_kinesis_firehose")]
 impl SourceConfig for AwsKinesisFirehoseConfig {
@@ -173,6 +197,7 @@ impl SourceConfig for AwsKinesisFirehoseConfig {
             access_keys.map(|key| key.inner().to_string()).collect(),
             self.store_access_key,
             self.record_compression,
+            self.record_format,
             decoder,
             acknowledgements,
             cx.out,
@@ -213,7 +238,7 @@ impl SourceConfig for AwsKinesisFirehoseConfig {
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
-        let schema_definition = self
+        let mut schema_definition = self
             .decoding
             .schema_definition(global_log_namespace.merge(self.log_namespace))
             .with_standard_vector_source_metadata()
@@ -232,6 +257,27 @@ impl SourceConfig for AwsKinesisFirehoseConfig {
                 None,
             );
 
+        if self.record_format == RecordFormat::CloudwatchLogsSubscription {
+            for key in ["id", "log_group", "log_stream", "owner"] {
+                schema_definition = schema_definition.with_source_metadata(
+                    Self::NAME,
+                    Some(LegacyKey::InsertIfEmpty(owned_value_path!(key))),
+                    &owned_value_path!(key),
+                    Kind::bytes(),
+                    None,
+                );
+            }
+            schema_definition = schema_definition.with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!(
+                    "subscription_filters"
+                ))),
+                &owned_value_path!("subscription_filters"),
+                Kind::array(Collection::empty().with_unknown(Kind::bytes())),
+                None,
+            );
+        }
+
         vec![SourceOutput::new_maybe_logs(
             self.decoding.output_type(),
             schema_definition,
@@ -256,6 +302,7 @@ impl GenerateConfig for AwsKinesisFirehoseConfig {
             store_access_key: false,
             tls: None,
             record_compression: Default::default(),
+            record_format: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             acknowledgements: Default::default(),
@@ -348,6 +395,7 @@ mod tests {
                 access_keys,
                 store_access_key,
                 record_compression,
+                record_format: Default::default(),
                 framing: default_framing_message_based(),
                 decoding: default_decoding(),
                 acknowledgements: true.into(),
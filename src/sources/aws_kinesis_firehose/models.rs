@@ -39,3 +39,35 @@ pub struct FirehoseResponse {
 
     pub error_message: Option<String>,
 }
+
+/// Represents a CloudWatch Logs subscription message, as delivered in a Firehose record
+///
+/// <https://docs.aws.amazon.com/AmazonCloudWatch/latest/logs/SubscriptionFilters.html#FirehoseExample>
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudwatchLogsSubscriptionMessage {
+    pub message_type: CloudwatchLogsMessageType,
+    pub owner: String,
+    pub log_group: String,
+    pub log_stream: String,
+    pub subscription_filters: Vec<String>,
+    pub log_events: Vec<CloudwatchLogsEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CloudwatchLogsMessageType {
+    DataMessage,
+    /// Sent by CloudWatch Logs to check that the destination is reachable.
+    ControlMessage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloudwatchLogsEvent {
+    pub id: String,
+
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+
+    pub message: String,
+}
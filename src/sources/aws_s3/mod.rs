@@ -1,10 +1,9 @@
 use std::convert::TryInto;
 
-use async_compression::tokio::bufread;
 use aws_smithy_types::byte_stream::ByteStream;
-use futures::{TryStreamExt, stream, stream::StreamExt};
+use bytes::Bytes;
+use futures::stream::{BoxStream, StreamExt};
 use snafu::Snafu;
-use tokio_util::io::StreamReader;
 use vector_lib::{
     codecs::{
         NewlineDelimitedDecoderConfig,
@@ -17,6 +16,7 @@ use vector_lib::{
 use vrl::value::{Kind, kind::Collection};
 
 use super::util::MultilineConfig;
+pub use super::util::object_storage::Compression;
 use crate::{
     aws::{RegionOrEndpoint, auth::AwsAuthentication, create_client, create_client_and_region},
     codecs::DecodingConfig,
@@ -29,34 +29,9 @@ use crate::{
     tls::TlsConfig,
 };
 
+mod poll;
 pub mod sqs;
 
-/// Compression scheme for objects retrieved from S3.
-#[configurable_component]
-#[configurable(metadata(docs::advanced))]
-#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-#[derivative(Default)]
-pub enum Compression {
-    /// Automatically attempt to determine the compression scheme.
-    ///
-    /// The compression scheme of the object is determined from its `Content-Encoding` and
-    /// `Content-Type` metadata, as well as the key suffix (for example, `.gz`).
-    ///
-    /// It is set to `none` if the compression scheme cannot be determined.
-    #[derivative(Default)]
-    Auto,
-
-    /// Uncompressed.
-    None,
-
-    /// GZIP.
-    Gzip,
-
-    /// ZSTD.
-    Zstd,
-}
-
 /// Strategies for consuming objects from AWS S3.
 #[configurable_component]
 #[derive(Clone, Copy, Debug, Derivative)]
@@ -68,6 +43,14 @@ enum Strategy {
     /// [aws_sqs]: https://aws.amazon.com/sqs/
     #[derivative(Default)]
     Sqs,
+
+    /// Consumes objects by periodically listing the bucket, or by reading its [S3 Inventory][s3_inventory] reports.
+    ///
+    /// This can be used for buckets where bucket notifications can't be configured. Processed
+    /// objects are tracked in checkpoints persisted in the data directory.
+    ///
+    /// [s3_inventory]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-inventory.html
+    Poll,
 }
 
 /// Configuration for the `aws_s3` source.
@@ -88,12 +71,14 @@ pub struct AwsS3Config {
     compression: Compression,
 
     /// The strategy to use to consume objects from S3.
-    #[configurable(metadata(docs::hidden))]
     strategy: Strategy,
 
     /// Configuration options for SQS.
     sqs: Option<sqs::Config>,
 
+    /// Configuration options for polling the bucket.
+    poll: Option<poll::Config>,
+
     /// The ARN of an [IAM role][iam_role] to assume at startup.
     ///
     /// [iam_role]: https://docs.aws.amazon.com/IAM/latest/UserGuide/id_roles.html
@@ -172,6 +157,16 @@ impl SourceConfig for AwsS3Config {
                     .await?
                     .run(cx, self.acknowledgements, log_namespace),
             )),
+            Strategy::Poll => {
+                let poller = self
+                    .create_poller(multiline_config, &cx, log_namespace)
+                    .await?;
+                Ok(Box::pin(poller.run(
+                    cx,
+                    self.acknowledgements,
+                    log_namespace,
+                )))
+            }
         }
     }
 
@@ -290,104 +285,72 @@ impl AwsS3Config {
             None => Err(CreateSqsIngestorError::ConfigMissing {}.into()),
         }
     }
-}
-
-#[derive(Debug, Snafu)]
-enum CreateSqsIngestorError {
-    #[snafu(display("Configuration for `sqs` required when strategy=sqs"))]
-    ConfigMissing,
-}
 
-/// None if body is empty
-async fn s3_object_decoder(
-    compression: Compression,
-    key: &str,
-    content_encoding: Option<&str>,
-    content_type: Option<&str>,
-    mut body: ByteStream,
-) -> Box<dyn tokio::io::AsyncRead + Send + Unpin> {
-    let first = match body.next().await {
-        Some(first) => first,
-        _ => {
-            return Box::new(tokio::io::empty());
-        }
-    };
+    async fn create_poller(
+        &self,
+        multiline: Option<line_agg::Config>,
+        cx: &SourceContext,
+        log_namespace: LogNamespace,
+    ) -> crate::Result<poll::Poller> {
+        let Some(ref poll) = self.poll else {
+            return Err(CreatePollerError::ConfigMissing {}.into());
+        };
 
-    let r = tokio::io::BufReader::new(StreamReader::new(
-        stream::iter(Some(first))
-            .chain(Box::pin(async_stream::stream! {
-                while let Some(next) = body.next().await {
-                    yield next;
-                }
-            }))
-            .map_err(std::io::Error::other),
-    ));
+        let region = self.region.region();
+        let (s3_client, region) = create_client_and_region::<S3ClientBuilder>(
+            &S3ClientBuilder {
+                force_path_style: Some(self.force_path_style),
+            },
+            &self.auth,
+            region,
+            self.region.endpoint(),
+            &cx.proxy,
+            self.tls_options.as_ref(),
+            None,
+        )
+        .await?;
 
-    let compression = match compression {
-        Auto => determine_compression(content_encoding, content_type, key).unwrap_or(None),
-        _ => compression,
-    };
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
 
-    use Compression::*;
-    match compression {
-        Auto => unreachable!(), // is mapped above
-        None => Box::new(r),
-        Gzip => Box::new({
-            let mut decoder = bufread::GzipDecoder::new(r);
-            decoder.multiple_members(true);
-            decoder
-        }),
-        Zstd => Box::new({
-            let mut decoder = bufread::ZstdDecoder::new(r);
-            decoder.multiple_members(true);
-            decoder
-        }),
+        // Each source gets its own subdirectory, so that two sources polling the same bucket
+        // track the objects they processed separately.
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(poll.data_dir.as_ref(), cx.key.id())?;
+
+        Ok(poll::Poller::new(
+            region,
+            s3_client,
+            poll.clone(),
+            data_dir,
+            self.compression,
+            multiline,
+            decoder,
+        )
+        .await?)
     }
 }
 
-// try to determine the compression given the:
-// * content-encoding
-// * content-type
-// * key name (for file extension)
-//
-// It will use this information in this order
-fn determine_compression(
-    content_encoding: Option<&str>,
-    content_type: Option<&str>,
-    key: &str,
-) -> Option<Compression> {
-    content_encoding
-        .and_then(content_encoding_to_compression)
-        .or_else(|| content_type.and_then(content_type_to_compression))
-        .or_else(|| object_key_to_compression(key))
-}
-
-fn content_encoding_to_compression(content_encoding: &str) -> Option<Compression> {
-    match content_encoding {
-        "gzip" => Some(Compression::Gzip),
-        "zstd" => Some(Compression::Zstd),
-        _ => None,
-    }
+#[derive(Debug, Snafu)]
+enum CreateSqsIngestorError {
+    #[snafu(display("Configuration for `sqs` required when strategy=sqs"))]
+    ConfigMissing,
 }
 
-fn content_type_to_compression(content_type: &str) -> Option<Compression> {
-    match content_type {
-        "application/gzip" | "application/x-gzip" => Some(Compression::Gzip),
-        "application/zstd" => Some(Compression::Zstd),
-        _ => None,
-    }
+#[derive(Debug, Snafu)]
+enum CreatePollerError {
+    #[snafu(display("Configuration for `poll` required when strategy=poll"))]
+    ConfigMissing,
 }
 
-fn object_key_to_compression(key: &str) -> Option<Compression> {
-    let extension = std::path::Path::new(key)
-        .extension()
-        .and_then(std::ffi::OsStr::to_str);
-
-    use Compression::*;
-    extension.and_then(|extension| match extension {
-        "gz" => Some(Gzip),
-        "zst" => Some(Zstd),
-        _ => Option::None,
+/// Streams the chunks of the body of an object.
+fn object_body_stream(mut body: ByteStream) -> BoxStream<'static, std::io::Result<Bytes>> {
+    Box::pin(async_stream::stream! {
+        while let Some(next) = body.next().await {
+            yield next.map_err(std::io::Error::other);
+        }
     })
 }
 
@@ -396,43 +359,19 @@ mod test {
     use tokio::io::AsyncReadExt;
 
     use super::*;
-
-    #[test]
-    fn determine_compression() {
-        use super::Compression;
-
-        let cases = vec![
-            ("out.log", Some("gzip"), None, Some(Compression::Gzip)),
-            (
-                "out.log",
-                None,
-                Some("application/gzip"),
-                Some(Compression::Gzip),
-            ),
-            ("out.log.gz", None, None, Some(Compression::Gzip)),
-            ("out.txt", None, None, None),
-        ];
-        for case in cases {
-            let (key, content_encoding, content_type, expected) = case;
-            assert_eq!(
-                super::determine_compression(content_encoding, content_type, key),
-                expected,
-                "key={key:?} content_encoding={content_encoding:?} content_type={content_type:?}",
-            );
-        }
-    }
+    use crate::sources::util::object_storage::object_decoder;
 
     #[tokio::test]
     async fn decode_empty_message_gzip() {
         let key = uuid::Uuid::new_v4().to_string();
 
         let mut data = Vec::new();
-        s3_object_decoder(
+        object_decoder(
             Compression::Auto,
             &key,
             Some("gzip"),
             None,
-            ByteStream::default(),
+            object_body_stream(ByteStream::default()),
         )
         .await
         .read_to_end(&mut data)
@@ -451,6 +390,7 @@ mod integration_tests {
         collections::HashMap,
         fs::File,
         io::{self, BufRead},
+        num::NonZeroU64,
         path::Path,
         time::Duration,
     };
@@ -793,6 +733,68 @@ mod integration_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn s3_poll_process_objects() {
+        trace_init();
+
+        let s3 = s3_client().await;
+        let bucket = create_bucket(&s3).await;
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let small: Vec<String> = random_lines(100).take(10).collect();
+        // Large enough to be read in several ranges.
+        let large: Vec<String> = random_lines(100).take(100).collect();
+        for (key, lines) in [
+            ("logs/small.log", &small),
+            ("logs/large.log", &large),
+            ("other/ignored.log", &small),
+        ] {
+            s3.put_object()
+                .bucket(bucket.clone())
+                .key(key)
+                .body(ByteStream::from(lines.join("\n").into_bytes()))
+                .send()
+                .await
+                .expect("Could not put object");
+        }
+
+        let config = AwsS3Config {
+            region: RegionOrEndpoint::with_both("us-east-1", s3_address()),
+            strategy: Strategy::Poll,
+            poll: Some(poll::Config {
+                bucket: bucket.clone(),
+                prefix: Some("logs/".to_owned()),
+                interval_secs: Duration::from_secs(1),
+                range_reads: Some(poll::RangeReadsConfig {
+                    part_size_bytes: NonZeroU64::new(1024).unwrap(),
+                    ..Default::default()
+                }),
+                data_dir: Some(data_dir.path().to_path_buf()),
+                ..Default::default()
+            }),
+            acknowledgements: true.into(),
+            decoding: DeserializerConfig::Bytes,
+            ..Default::default()
+        };
+
+        let (tx, rx) = SourceSender::new_test_finalize(Delivered);
+        let cx = SourceContext::new_test(tx, None);
+        let source = config.build(cx).await.unwrap();
+        tokio::spawn(async move { source.await.unwrap() });
+
+        let events = collect_n(rx, small.len() + large.len()).await;
+
+        // Objects are processed concurrently, so their events are interleaved.
+        let mut messages = events
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let mut expected = small.iter().chain(&large).cloned().collect::<Vec<_>>();
+        messages.sort();
+        expected.sort();
+        assert_eq!(messages, expected);
+    }
+
     fn s3_address() -> String {
         std::env::var("S3_ADDRESS").unwrap_or_else(|_| "http://localhost:4566".into())
     }
@@ -0,0 +1,822 @@
+use std::{
+    collections::HashSet,
+    io,
+    num::{NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use aws_sdk_s3::{Client as S3Client, operation::list_objects_v2::ListObjectsV2Error};
+use aws_smithy_runtime_api::client::{orchestrator::HttpResponse, result::SdkError};
+use aws_types::region::Region;
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{StreamExt, stream, stream::BoxStream};
+use serde::Deserialize;
+use serde_with::serde_as;
+use snafu::{OptionExt, ResultExt, Snafu};
+use tokio::{io::AsyncReadExt, select, time::MissedTickBehavior};
+use vector_lib::{
+    config::LogNamespace,
+    configurable::configurable_component,
+    internal_event::{BytesReceived, Protocol, Registered},
+};
+
+use super::sqs::{ObjectMetadata, ProcessingError, send_object_events};
+use crate::{
+    SourceSender,
+    codecs::Decoder,
+    config::{SourceAcknowledgementsConfig, SourceContext},
+    event::BatchStatus,
+    internal_events::{EventsReceived, ObjectStoragePollError},
+    line_agg,
+    shutdown::ShutdownSignal,
+    sources::util::object_storage::{
+        CheckpointsError, ListedObject, ObjectPoller, object_decoder, paginate,
+    },
+};
+
+/// The number of objects of an inventory report processed between checkpoint writes.
+const INVENTORY_CHUNK_SIZE: usize = 1000;
+
+/// Polling configuration options.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The name of the bucket to consume objects from.
+    #[configurable(metadata(docs::examples = "my-bucket"))]
+    pub(super) bucket: String,
+
+    /// Only objects whose key starts with this prefix are consumed.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "logs/"))]
+    pub(super) prefix: Option<String>,
+
+    /// How often to look for new objects, in seconds.
+    #[serde(default = "default_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_interval_secs()"))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Polling Interval"))]
+    pub(super) interval_secs: Duration,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) inventory: Option<InventoryConfig>,
+
+    /// Number of objects to process concurrently.
+    ///
+    /// Defaults to the number of available CPUs on the system.
+    #[configurable(metadata(docs::type_unit = "tasks"))]
+    #[configurable(metadata(docs::examples = 5))]
+    pub(super) client_concurrency: Option<NonZeroUsize>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) range_reads: Option<RangeReadsConfig>,
+
+    /// The directory used to persist the checkpoints of the processed objects.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub(super) data_dir: Option<PathBuf>,
+}
+
+/// Configuration for finding objects in [S3 Inventory][s3_inventory] reports instead of listing the bucket.
+///
+/// Only reports in the CSV format are supported. The latest report is processed once it is
+/// delivered, which is more efficient than listing buckets holding many objects.
+///
+/// [s3_inventory]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-inventory.html
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct InventoryConfig {
+    /// The name of the bucket the inventory reports are delivered to.
+    ///
+    /// Defaults to the bucket objects are consumed from.
+    #[configurable(metadata(docs::examples = "my-inventory-bucket"))]
+    pub(super) bucket: Option<String>,
+
+    /// The prefix of the inventory reports of the inventory configuration.
+    ///
+    /// This is `<destination prefix>/<source bucket>/<configuration ID>/`, under which S3 delivers
+    /// the `manifest.json` file of each report.
+    #[configurable(metadata(docs::examples = "inventory/my-bucket/daily/"))]
+    pub(super) manifest_prefix: String,
+}
+
+/// Configuration for reading large objects with concurrent range requests.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RangeReadsConfig {
+    /// The size of each range, in bytes.
+    ///
+    /// Objects larger than this are fetched in ranges of this size. Ranges fetched ahead of the
+    /// one being decoded are buffered in memory.
+    #[serde(default = "default_part_size_bytes")]
+    #[derivative(Default(value = "default_part_size_bytes()"))]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub(super) part_size_bytes: NonZeroU64,
+
+    /// The number of ranges of an object fetched concurrently.
+    #[serde(default = "default_range_concurrency")]
+    #[derivative(Default(value = "default_range_concurrency()"))]
+    #[configurable(metadata(docs::type_unit = "requests"))]
+    pub(super) concurrency: NonZeroUsize,
+}
+
+const fn default_interval_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+const fn default_part_size_bytes() -> NonZeroU64 {
+    NonZeroU64::new(8 * 1024 * 1024).unwrap()
+}
+
+const fn default_range_concurrency() -> NonZeroUsize {
+    NonZeroUsize::new(4).unwrap()
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum PollError {
+    #[snafu(transparent)]
+    Checkpoints { source: CheckpointsError },
+    #[snafu(display("Failed to list s3://{}/{}: {}", bucket, prefix, source))]
+    ListObjects {
+        source: SdkError<ListObjectsV2Error, HttpResponse>,
+        bucket: String,
+        prefix: String,
+    },
+    #[snafu(display("Failed to read inventory s3://{}/{}: {}", bucket, key, source))]
+    ReadInventory {
+        source: crate::Error,
+        bucket: String,
+        key: String,
+    },
+    #[snafu(display(
+        "Unsupported format {} of inventory s3://{}/{}, only CSV is supported",
+        format,
+        bucket,
+        key
+    ))]
+    UnsupportedInventoryFormat {
+        format: String,
+        bucket: String,
+        key: String,
+    },
+    #[snafu(display("Inventory s3://{}/{} doesn't list object keys", bucket, key))]
+    InventoryKeyMissing { bucket: String, key: String },
+}
+
+/// ETags are quoted in listings, but not in inventory reports.
+fn normalize_etag(etag: &str) -> String {
+    etag.trim_matches('"').to_owned()
+}
+
+// https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-inventory-location.html
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InventoryManifest {
+    file_format: String,
+    file_schema: String,
+    files: Vec<InventoryFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InventoryFile {
+    key: String,
+}
+
+/// The positions of the fields of inventory report rows, as listed in the manifest schema.
+#[derive(Debug, PartialEq, Eq)]
+struct InventoryColumns {
+    bucket: Option<usize>,
+    key: usize,
+    size: Option<usize>,
+    etag: Option<usize>,
+    is_latest: Option<usize>,
+    is_delete_marker: Option<usize>,
+}
+
+impl InventoryColumns {
+    fn new(file_schema: &str) -> Option<Self> {
+        let fields = file_schema.split(',').map(str::trim).collect::<Vec<_>>();
+        let position = |name| fields.iter().position(|field| *field == name);
+        Some(Self {
+            bucket: position("Bucket"),
+            key: position("Key")?,
+            size: position("Size"),
+            etag: position("ETag"),
+            is_latest: position("IsLatest"),
+            is_delete_marker: position("IsDeleteMarker"),
+        })
+    }
+
+    /// Parses a row, skipping noncurrent versions, delete markers, and objects of other buckets
+    /// or outside of the prefix.
+    fn parse(
+        &self,
+        record: &csv::StringRecord,
+        bucket: &str,
+        prefix: &str,
+    ) -> Option<ListedObject> {
+        let field = |position: Option<usize>| position.and_then(|position| record.get(position));
+
+        if field(self.bucket).is_some_and(|record_bucket| record_bucket != bucket)
+            || field(self.is_latest) == Some("false")
+            || field(self.is_delete_marker) == Some("true")
+        {
+            return None;
+        }
+
+        let key = super::sqs::decode_object_key(record.get(self.key)?.as_bytes()).ok()?;
+        key.starts_with(prefix).then(|| ListedObject {
+            key,
+            etag: field(self.etag)
+                .filter(|etag| !etag.is_empty())
+                .map(normalize_etag),
+            size: field(self.size).and_then(|size| size.parse().ok()),
+        })
+    }
+}
+
+struct State {
+    region: Region,
+    s3_client: S3Client,
+
+    bucket: String,
+    prefix: Option<String>,
+    range_reads: Option<RangeReadsConfig>,
+
+    multiline: Option<line_agg::Config>,
+    compression: super::Compression,
+    decoder: Decoder,
+}
+
+/// Lists the objects of the bucket, under the prefix.
+struct BucketLister {
+    s3_client: S3Client,
+    bucket: String,
+    prefix: Option<String>,
+}
+
+impl BucketLister {
+    fn list(&self) -> BoxStream<'_, crate::Result<Vec<ListedObject>>> {
+        paginate(|page_token| self.list_page(page_token))
+    }
+
+    async fn list_page(
+        &self,
+        page_token: Option<String>,
+    ) -> crate::Result<(Vec<ListedObject>, Option<String>)> {
+        let output = self
+            .s3_client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .set_prefix(self.prefix.clone())
+            .set_continuation_token(page_token)
+            .send()
+            .await
+            .context(ListObjectsSnafu {
+                bucket: self.bucket.clone(),
+                prefix: self.prefix.clone().unwrap_or_default(),
+            })?;
+
+        let objects = output
+            .contents()
+            .iter()
+            .filter_map(|object| {
+                Some(ListedObject {
+                    key: object.key()?.to_owned(),
+                    etag: object.e_tag().map(normalize_etag),
+                    size: object.size().and_then(|size| size.try_into().ok()),
+                })
+            })
+            .collect();
+        Ok((
+            objects,
+            output.next_continuation_token().map(ToOwned::to_owned),
+        ))
+    }
+}
+
+pub(super) struct Poller {
+    state: Arc<State>,
+    interval: Duration,
+    inventory: Option<InventoryConfig>,
+    lister: BucketLister,
+    poller: ObjectPoller,
+}
+
+impl Poller {
+    pub(super) async fn new(
+        region: Region,
+        s3_client: S3Client,
+        config: Config,
+        data_dir: PathBuf,
+        compression: super::Compression,
+        multiline: Option<line_agg::Config>,
+        decoder: Decoder,
+    ) -> Result<Poller, PollError> {
+        let poller = ObjectPoller::new(
+            data_dir,
+            config
+                .client_concurrency
+                .map(|n| n.get())
+                .unwrap_or_else(crate::num_threads),
+        )
+        .await?;
+        let lister = BucketLister {
+            s3_client: s3_client.clone(),
+            bucket: config.bucket.clone(),
+            prefix: config.prefix.clone(),
+        };
+
+        let state = Arc::new(State {
+            region,
+            s3_client,
+
+            bucket: config.bucket,
+            prefix: config.prefix,
+            range_reads: config.range_reads,
+
+            multiline,
+            compression,
+            decoder,
+        });
+
+        Ok(Poller {
+            state,
+            interval: config.interval_secs,
+            inventory: config.inventory,
+            lister,
+            poller,
+        })
+    }
+
+    pub(super) async fn run(
+        mut self,
+        cx: SourceContext,
+        acknowledgements: SourceAcknowledgementsConfig,
+        log_namespace: LogNamespace,
+    ) -> Result<(), ()> {
+        let acknowledgements = cx.do_acknowledgements(acknowledgements);
+        let process = ObjectProcess {
+            state: Arc::clone(&self.state),
+            out: cx.out,
+            acknowledgements,
+            log_namespace,
+            bytes_received: register!(BytesReceived::from(Protocol::HTTP)),
+            events_received: register!(EventsReceived),
+        };
+        let mut shutdown = cx.shutdown;
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            select! {
+                biased;
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {}
+            }
+
+            match self.inventory.clone() {
+                Some(inventory) => {
+                    if let Err(error) = self.poll_inventory(&inventory, &process, &shutdown).await {
+                        emit!(ObjectStoragePollError { error: &error });
+                    }
+                }
+                None => {
+                    let process = |object| process.clone().run(object);
+                    if let Err(error) = self
+                        .poller
+                        .poll_listing(self.lister.list(), process, &shutdown)
+                        .await
+                    {
+                        emit!(ObjectStoragePollError { error: &error });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Processes the objects of the latest inventory report, unless it was already.
+    async fn poll_inventory(
+        &mut self,
+        inventory: &InventoryConfig,
+        process: &ObjectProcess,
+        shutdown: &ShutdownSignal,
+    ) -> Result<(), PollError> {
+        let bucket = inventory
+            .bucket
+            .clone()
+            .unwrap_or_else(|| self.state.bucket.clone());
+        let Some(manifest_key) = self
+            .latest_manifest(&bucket, &inventory.manifest_prefix)
+            .await?
+        else {
+            debug!(
+                message = "No inventory report found.",
+                bucket = bucket,
+                prefix = inventory.manifest_prefix,
+            );
+            return Ok(());
+        };
+        if self.poller.checkpoints.manifest.as_ref() == Some(&manifest_key) {
+            return Ok(());
+        }
+
+        let manifest = self.get_object_bytes(&bucket, &manifest_key).await?;
+        let manifest: InventoryManifest =
+            serde_json::from_slice(&manifest).map_err(|error| PollError::ReadInventory {
+                source: error.into(),
+                bucket: bucket.clone(),
+                key: manifest_key.clone(),
+            })?;
+        if manifest.file_format != "CSV" {
+            return Err(PollError::UnsupportedInventoryFormat {
+                format: manifest.file_format,
+                bucket,
+                key: manifest_key,
+            });
+        }
+        let columns =
+            InventoryColumns::new(&manifest.file_schema).context(InventoryKeyMissingSnafu {
+                bucket: bucket.clone(),
+                key: manifest_key.clone(),
+            })?;
+
+        let prefix = self.state.prefix.clone().unwrap_or_default();
+        let process = |object| process.clone().run(object);
+        let mut listed = HashSet::new();
+        let mut complete = true;
+        for file in &manifest.files {
+            let data = self.get_object_bytes(&bucket, &file.key).await?;
+            let objects = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(data.as_ref())
+                .into_records()
+                .filter_map(|record| {
+                    record
+                        .map(|record| columns.parse(&record, &self.state.bucket, &prefix))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| PollError::ReadInventory {
+                    source: error.into(),
+                    bucket: bucket.clone(),
+                    key: file.key.clone(),
+                })?;
+            listed.extend(objects.iter().map(|object| object.key.clone()));
+
+            for chunk in objects.chunks(INVENTORY_CHUNK_SIZE) {
+                match self
+                    .poller
+                    .process_objects(chunk.to_vec(), &process, shutdown)
+                    .await
+                {
+                    Some(succeeded) => complete &= succeeded,
+                    None => return Ok(()),
+                }
+            }
+        }
+
+        // The report is processed again on the next poll if any of its objects failed, which only
+        // retries the failed objects.
+        if complete {
+            self.poller.forget_unlisted(&listed);
+            self.poller.checkpoints.manifest = Some(manifest_key);
+            self.poller.write_checkpoints().await;
+        }
+        Ok(())
+    }
+
+    /// Finds the manifest of the latest inventory report.
+    async fn latest_manifest(
+        &self,
+        bucket: &str,
+        manifest_prefix: &str,
+    ) -> Result<Option<String>, PollError> {
+        let mut latest: Option<String> = None;
+        let mut continuation_token = None;
+        loop {
+            let output = self
+                .state
+                .s3_client
+                .list_objects_v2()
+                .bucket(bucket)
+                .prefix(manifest_prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .context(ListObjectsSnafu {
+                    bucket,
+                    prefix: manifest_prefix,
+                })?;
+
+            // Reports are delivered under a `YYYY-MM-DDTHH-MMZ` directory, so the latest one sorts
+            // last.
+            let manifests = output
+                .contents()
+                .iter()
+                .filter_map(|object| object.key())
+                .filter(|key| key.ends_with("/manifest.json"));
+            if let Some(key) = manifests.max()
+                && latest.as_deref().is_none_or(|latest| key > latest)
+            {
+                latest = Some(key.to_owned());
+            }
+
+            continuation_token = output.next_continuation_token().map(ToOwned::to_owned);
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Fetches a whole inventory file, decompressing it if needed.
+    async fn get_object_bytes(&self, bucket: &str, key: &str) -> Result<Vec<u8>, PollError> {
+        let object = self
+            .state
+            .s3_client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|error| PollError::ReadInventory {
+                source: error.into(),
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+            })?;
+
+        let mut reader = object_decoder(
+            super::Compression::Auto,
+            key,
+            object.content_encoding.as_deref(),
+            object.content_type.as_deref(),
+            super::object_body_stream(object.body),
+        )
+        .await;
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .await
+            .map_err(|error| PollError::ReadInventory {
+                source: error.into(),
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+            })?;
+        Ok(data)
+    }
+}
+
+#[derive(Clone)]
+struct ObjectProcess {
+    state: Arc<State>,
+    out: SourceSender,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+    bytes_received: Registered<BytesReceived>,
+    events_received: Registered<EventsReceived>,
+}
+
+impl ObjectProcess {
+    async fn run(mut self, object: ListedObject) -> Result<(), ProcessingError> {
+        let bucket = self.state.bucket.clone();
+
+        // Large objects are read in ranges, which must all come from the same version of the
+        // object.
+        let part_size = self
+            .state
+            .range_reads
+            .as_ref()
+            .map(|range_reads| range_reads.part_size_bytes.get())
+            .filter(|part_size| object.size.is_some_and(|size| size > *part_size));
+        let if_match = part_size
+            .and(object.etag.as_ref())
+            .map(|etag| format!("\"{etag}\""));
+
+        let output = self
+            .state
+            .s3_client
+            .get_object()
+            .bucket(&bucket)
+            .key(&object.key)
+            .set_range(part_size.map(|part_size| format!("bytes=0-{}", part_size - 1)))
+            .set_if_match(if_match.clone())
+            .send()
+            .await
+            .map_err(|error| ProcessingError::GetObject {
+                source: error,
+                bucket: bucket.clone(),
+                key: object.key.clone(),
+            })?;
+
+        debug!(
+            message = "Got S3 object from bucket listing.",
+            bucket = bucket,
+            key = object.key,
+        );
+
+        let timestamp = output.last_modified.map(|ts| {
+            Utc.timestamp_opt(ts.secs(), ts.subsec_nanos())
+                .single()
+                .expect("invalid timestamp")
+        });
+
+        let body = super::object_body_stream(output.body);
+        let body = match (part_size, object.size) {
+            (Some(part_size), Some(size)) => {
+                let concurrency = self
+                    .state
+                    .range_reads
+                    .as_ref()
+                    .map_or(1, |range_reads| range_reads.concurrency.get());
+                let s3_client = self.state.s3_client.clone();
+                let (bucket, key) = (bucket.clone(), object.key.clone());
+                let ranges = stream::iter(1..size.div_ceil(part_size))
+                    .map(move |part| {
+                        let start = part * part_size;
+                        let end = (start + part_size).min(size) - 1;
+                        get_range(
+                            s3_client.clone(),
+                            bucket.clone(),
+                            key.clone(),
+                            if_match.clone(),
+                            start,
+                            end,
+                        )
+                    })
+                    .buffered(concurrency);
+                body.chain(ranges).boxed()
+            }
+            _ => body,
+        };
+
+        let object_reader = object_decoder(
+            self.state.compression,
+            &object.key,
+            output.content_encoding.as_deref(),
+            output.content_type.as_deref(),
+            body,
+        )
+        .await;
+
+        let receiver = send_object_events(
+            &mut self.out,
+            &self.state.decoder,
+            self.state.multiline.as_ref(),
+            object_reader,
+            &ObjectMetadata {
+                bucket: &bucket,
+                key: &object.key,
+                region: self.state.region.as_ref(),
+                metadata: &output.metadata,
+                timestamp,
+            },
+            self.acknowledgements,
+            self.log_namespace,
+            &self.bytes_received,
+            &self.events_received,
+        )
+        .await?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => {
+                    debug!(
+                        message = "S3 object from bucket listing delivered.",
+                        bucket = bucket,
+                        key = object.key,
+                    );
+                    Ok(())
+                }
+                BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement {
+                    bucket,
+                    key: object.key.clone(),
+                    region: self.state.region.to_string(),
+                }),
+                BatchStatus::Rejected => {
+                    warn!(
+                        message = "S3 object from bucket listing was rejected. It won't be processed again.",
+                        bucket = bucket,
+                        key = object.key,
+                    );
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+/// Fetches a range of an object, from `start` to `end` inclusive.
+async fn get_range(
+    s3_client: S3Client,
+    bucket: String,
+    key: String,
+    if_match: Option<String>,
+    start: u64,
+    end: u64,
+) -> io::Result<Bytes> {
+    let output = s3_client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .range(format!("bytes={start}-{end}"))
+        .set_if_match(if_match)
+        .send()
+        .await
+        .map_err(io::Error::other)?;
+    output
+        .body
+        .collect()
+        .await
+        .map(|data| data.into_bytes())
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_poll_config() {
+        let config: super::super::AwsS3Config = toml::from_str(
+            r#"
+                strategy = "poll"
+
+                [poll]
+                bucket = "my-bucket"
+                prefix = "logs/"
+                interval_secs = 300
+                range_reads.part_size_bytes = 1048576
+            "#,
+        )
+        .unwrap();
+        let poll = config.poll.unwrap();
+
+        assert_eq!(poll.bucket, "my-bucket");
+        assert_eq!(poll.interval_secs, Duration::from_secs(300));
+        assert!(poll.inventory.is_none());
+        let range_reads = poll.range_reads.unwrap();
+        assert_eq!(range_reads.part_size_bytes.get(), 1048576);
+        assert_eq!(range_reads.concurrency, default_range_concurrency());
+    }
+
+    #[test]
+    fn parse_inventory_rows() {
+        let columns = InventoryColumns::new(
+            "Bucket, Key, VersionId, IsLatest, IsDeleteMarker, Size, LastModifiedDate, ETag",
+        )
+        .unwrap();
+        let data = [
+            r#""my-bucket","logs/a+b%2B.log","v1","true","false","42","2024-01-01T00:00:00.000Z","0cc175b9c0f1b6a831c399e269772661""#,
+            r#""my-bucket","logs/old.log","v0","false","false","42","2023-01-01T00:00:00.000Z","92eb5ffee6ae2fec3ad71c777531578f""#,
+            r#""my-bucket","logs/deleted.log","v2","true","true","","2024-01-01T00:00:00.000Z","""#,
+            r#""my-bucket","other/c.log","v1","true","false","1","2024-01-01T00:00:00.000Z","4a8a08f09d37b73795649038408b5f33""#,
+            r#""other-bucket","logs/d.log","v1","true","false","1","2024-01-01T00:00:00.000Z","8277e0910d750195b448797616e091ad""#,
+        ]
+        .join("\n");
+
+        let objects = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(data.as_bytes())
+            .into_records()
+            .filter_map(|record| columns.parse(&record.unwrap(), "my-bucket", "logs/"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            objects,
+            vec![ListedObject {
+                key: "logs/a b+.log".to_owned(),
+                etag: Some("0cc175b9c0f1b6a831c399e269772661".to_owned()),
+                size: Some(42),
+            }]
+        );
+    }
+
+    #[test]
+    fn inventory_schema_requires_key() {
+        assert!(InventoryColumns::new("Bucket, Size").is_none());
+    }
+}
@@ -1,6 +1,5 @@
 use std::{
     collections::HashMap,
-    future::ready,
     num::NonZeroUsize,
     panic,
     sync::{Arc, LazyLock},
@@ -21,23 +20,18 @@ use aws_smithy_runtime_api::client::{orchestrator::HttpResponse, result::SdkErro
 use aws_types::region::Region;
 use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
-use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
+use futures::{FutureExt, TryFutureExt};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::serde_as;
-use smallvec::SmallVec;
 use snafu::{ResultExt, Snafu};
 use tokio::{pin, select};
-use tokio_util::codec::FramedRead;
 use tracing::Instrument;
 use vector_lib::{
     codecs::decoding::FramingError,
-    config::{LegacyKey, LogNamespace, log_schema},
+    config::{LegacyKey, LogNamespace},
     configurable::configurable_component,
-    event::MaybeAsLogMut,
-    internal_event::{
-        ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Protocol, Registered,
-    },
-    lookup::{PathPrefix, metadata_path, path},
+    internal_event::{BytesReceived, Protocol, Registered},
+    lookup::path,
 };
 
 use crate::{
@@ -46,17 +40,22 @@ use crate::{
     codecs::Decoder,
     common::backoff::ExponentialBackoff,
     config::{SourceAcknowledgementsConfig, SourceContext},
-    event::{BatchNotifier, BatchStatus, EstimatedJsonEncodedSizeOf, Event, LogEvent},
+    event::{BatchStatus, BatchStatusReceiver, LogEvent},
     internal_events::{
         EventsReceived, SqsMessageDeleteBatchError, SqsMessageDeletePartialError,
         SqsMessageDeleteSucceeded, SqsMessageProcessingError, SqsMessageProcessingSucceeded,
         SqsMessageReceiveError, SqsMessageReceiveSucceeded, SqsMessageSendBatchError,
         SqsMessageSentPartialError, SqsMessageSentSucceeded, SqsS3EventRecordInvalidEventIgnored,
-        StreamClosedError,
     },
-    line_agg::{self, LineAgg},
+    line_agg,
     shutdown::ShutdownSignal,
-    sources::aws_s3::AwsS3Config,
+    sources::{
+        aws_s3::AwsS3Config,
+        util::object_storage::{
+            SendObjectError, insert_object_timestamp, object_decoder,
+            send_object_events as send_events,
+        },
+    },
     tls::TlsConfig,
 };
 
@@ -700,151 +699,67 @@ impl IngestorProcess {
                 .expect("invalid timestamp")
         });
 
-        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
-        let object_reader = super::s3_object_decoder(
+        let object_reader = object_decoder(
             self.state.compression,
             &s3_event.s3.object.key,
             object.content_encoding.as_deref(),
             object.content_type.as_deref(),
-            object.body,
+            super::object_body_stream(object.body),
         )
         .await;
 
-        // Record the read error seen to propagate up later so we avoid ack'ing the SQS
-        // message
-        //
-        // String is used as we cannot clone std::io::Error to take ownership in closure
-        //
-        // FramedRead likely stops when it gets an i/o error but I found it more clear to
-        // show that we `take_while` there hasn't been an error
-        //
-        // This can result in objects being partially processed before an error, but we
-        // prefer duplicate lines over message loss. Future work could include recording
-        // the offset of the object that has been read, but this would only be relevant in
-        // the case that the same vector instance processes the same message.
-        let mut read_error = None;
-        let bytes_received = self.bytes_received.clone();
-        let events_received = self.events_received.clone();
-        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
-            FramedRead::new(object_reader, self.state.decoder.framer.clone())
-                .map(|res| {
-                    res.inspect(|bytes| {
-                        bytes_received.emit(ByteSize(bytes.len()));
-                    })
-                    .map_err(|err| {
-                        read_error = Some(err);
-                    })
-                    .ok()
-                })
-                .take_while(|res| ready(res.is_some()))
-                .map(|r| r.expect("validated by take_while")),
-        );
-
-        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = match &self.state.multiline {
-            Some(config) => Box::new(
-                LineAgg::new(
-                    lines.map(|line| ((), line, ())),
-                    line_agg::Logic::new(config.clone()),
-                )
-                .map(|(_src, line, _context, _lastline_context)| line),
-            ),
-            None => lines,
-        };
-
-        let mut stream = lines.flat_map(|line| {
-            let events = match self.state.decoder.deserializer_parse(line) {
-                Ok((events, _events_size)) => events,
-                Err(_error) => {
-                    // Error is handled by `codecs::Decoder`, no further handling
-                    // is needed here.
-                    SmallVec::new()
-                }
-            };
-
-            let events = events
-                .into_iter()
-                .map(|mut event: Event| {
-                    event = event.with_batch_notifier_option(&batch);
-                    if let Some(log_event) = event.maybe_as_log_mut() {
-                        handle_single_log(
-                            log_event,
-                            log_namespace,
-                            &s3_event,
-                            &metadata,
-                            timestamp,
+        let receiver = send_object_events(
+            &mut self.out,
+            &self.state.decoder,
+            self.state.multiline.as_ref(),
+            object_reader,
+            &ObjectMetadata {
+                bucket: &s3_event.s3.bucket.name,
+                key: &s3_event.s3.object.key,
+                region: &s3_event.aws_region,
+                metadata: &metadata,
+                timestamp,
+            },
+            self.acknowledgements,
+            log_namespace,
+            &self.bytes_received,
+            &self.events_received,
+        )
+        .await?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => {
+                let result = receiver.await;
+                match result {
+                    BatchStatus::Delivered => {
+                        debug!(
+                            message = "S3 object from SQS delivered.",
+                            bucket = s3_event.s3.bucket.name,
+                            key = s3_event.s3.object.key,
                         );
+                        Ok(())
                     }
-                    events_received.emit(CountByteSize(1, event.estimated_json_encoded_size_of()));
-                    event
-                })
-                .collect::<Vec<Event>>();
-            futures::stream::iter(events)
-        });
-
-        let send_error = match self.out.send_event_stream(&mut stream).await {
-            Ok(_) => None,
-            Err(_) => {
-                let (count, _) = stream.size_hint();
-                emit!(StreamClosedError { count });
-                Some(crate::source_sender::ClosedError)
-            }
-        };
-
-        // Up above, `lines` captures `read_error`, and eventually is captured by `stream`,
-        // so we explicitly drop it so that we can again utilize `read_error` below.
-        drop(stream);
-
-        // The BatchNotifier is cloned for each LogEvent in the batch stream, but the last
-        // reference must be dropped before the status of the batch is sent to the channel.
-        drop(batch);
-
-        if let Some(error) = read_error {
-            Err(ProcessingError::ReadObject {
-                source: error,
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
-            })
-        } else if let Some(error) = send_error {
-            Err(ProcessingError::PipelineSend {
-                source: error,
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
-            })
-        } else {
-            match receiver {
-                None => Ok(()),
-                Some(receiver) => {
-                    let result = receiver.await;
-                    match result {
-                        BatchStatus::Delivered => {
-                            debug!(
-                                message = "S3 object from SQS delivered.",
+                    BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement {
+                        bucket: s3_event.s3.bucket.name,
+                        key: s3_event.s3.object.key,
+                        region: s3_event.aws_region,
+                    }),
+                    BatchStatus::Rejected => {
+                        if self.state.delete_failed_message {
+                            warn!(
+                                message =
+                                    "S3 object from SQS was rejected. Deleting failed message.",
                                 bucket = s3_event.s3.bucket.name,
                                 key = s3_event.s3.object.key,
                             );
                             Ok(())
-                        }
-                        BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement {
-                            bucket: s3_event.s3.bucket.name,
-                            key: s3_event.s3.object.key,
-                            region: s3_event.aws_region,
-                        }),
-                        BatchStatus::Rejected => {
-                            if self.state.delete_failed_message {
-                                warn!(
-                                    message =
-                                        "S3 object from SQS was rejected. Deleting failed message.",
-                                    bucket = s3_event.s3.bucket.name,
-                                    key = s3_event.s3.object.key,
-                                );
-                                Ok(())
-                            } else {
-                                Err(ProcessingError::ErrorAcknowledgement {
-                                    bucket: s3_event.s3.bucket.name,
-                                    key: s3_event.s3.object.key,
-                                    region: s3_event.aws_region,
-                                })
-                            }
+                        } else {
+                            Err(ProcessingError::ErrorAcknowledgement {
+                                bucket: s3_event.s3.bucket.name,
+                                key: s3_event.s3.object.key,
+                                region: s3_event.aws_region,
+                            })
                         }
                     }
                 }
@@ -895,19 +810,62 @@ impl IngestorProcess {
     }
 }
 
-fn handle_single_log(
-    log: &mut LogEvent,
+/// The S3 object that events were decoded from, attached to each event as metadata.
+pub(super) struct ObjectMetadata<'a> {
+    pub(super) bucket: &'a str,
+    pub(super) key: &'a str,
+    pub(super) region: &'a str,
+    pub(super) metadata: &'a Option<HashMap<String, String>>,
+    pub(super) timestamp: Option<DateTime<Utc>>,
+}
+
+/// Decodes the events of an S3 object and sends them downstream.
+///
+/// If acknowledgements are enabled, the receiver of the status of the sent events is returned.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn send_object_events(
+    out: &mut SourceSender,
+    decoder: &Decoder,
+    multiline: Option<&line_agg::Config>,
+    object_reader: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
+    object: &ObjectMetadata<'_>,
+    acknowledgements: bool,
     log_namespace: LogNamespace,
-    s3_event: &S3EventRecord,
-    metadata: &Option<HashMap<String, String>>,
-    timestamp: Option<DateTime<Utc>>,
-) {
+    bytes_received: &Registered<BytesReceived>,
+    events_received: &Registered<EventsReceived>,
+) -> Result<Option<BatchStatusReceiver>, ProcessingError> {
+    send_events(
+        out,
+        decoder,
+        multiline,
+        object_reader,
+        acknowledgements,
+        bytes_received,
+        events_received,
+        |log| handle_single_log(log, log_namespace, object),
+    )
+    .await
+    .map_err(|error| match error {
+        SendObjectError::ReadObject { source } => ProcessingError::ReadObject {
+            source,
+            bucket: object.bucket.to_owned(),
+            key: object.key.to_owned(),
+        },
+        SendObjectError::PipelineSend { source } => ProcessingError::PipelineSend {
+            source,
+            bucket: object.bucket.to_owned(),
+            key: object.key.to_owned(),
+        },
+    })
+}
+
+fn handle_single_log(log: &mut LogEvent, log_namespace: LogNamespace, object: &ObjectMetadata<'_>) {
     log_namespace.insert_source_metadata(
         AwsS3Config::NAME,
         log,
         Some(LegacyKey::Overwrite(path!("bucket"))),
         path!("bucket"),
-        Bytes::from(s3_event.s3.bucket.name.as_bytes().to_vec()),
+        Bytes::from(object.bucket.as_bytes().to_vec()),
     );
 
     log_namespace.insert_source_metadata(
@@ -915,17 +873,17 @@ fn handle_single_log(
         log,
         Some(LegacyKey::Overwrite(path!("object"))),
         path!("object"),
-        Bytes::from(s3_event.s3.object.key.as_bytes().to_vec()),
+        Bytes::from(object.key.as_bytes().to_vec()),
     );
     log_namespace.insert_source_metadata(
         AwsS3Config::NAME,
         log,
         Some(LegacyKey::Overwrite(path!("region"))),
         path!("region"),
-        Bytes::from(s3_event.aws_region.as_bytes().to_vec()),
+        Bytes::from(object.region.as_bytes().to_vec()),
     );
 
-    if let Some(metadata) = metadata {
+    if let Some(metadata) = object.metadata {
         for (key, value) in metadata {
             log_namespace.insert_source_metadata(
                 AwsS3Config::NAME,
@@ -937,33 +895,7 @@ fn handle_single_log(
         }
     }
 
-    log_namespace.insert_vector_metadata(
-        log,
-        log_schema().source_type_key(),
-        path!("source_type"),
-        Bytes::from_static(AwsS3Config::NAME.as_bytes()),
-    );
-
-    // This handles the transition from the original timestamp logic. Originally the
-    // `timestamp_key` was populated by the `last_modified` time on the object, falling
-    // back to calling `now()`.
-    match log_namespace {
-        LogNamespace::Vector => {
-            if let Some(timestamp) = timestamp {
-                log.insert(metadata_path!(AwsS3Config::NAME, "timestamp"), timestamp);
-            }
-
-            log.insert(metadata_path!("vector", "ingest_timestamp"), Utc::now());
-        }
-        LogNamespace::Legacy => {
-            if let Some(timestamp_key) = log_schema().timestamp_key() {
-                log.try_insert(
-                    (PathPrefix::Event, timestamp_key),
-                    timestamp.unwrap_or_else(Utc::now),
-                );
-            }
-        }
-    };
+    insert_object_timestamp(log, log_namespace, AwsS3Config::NAME, object.timestamp);
 }
 
 // https://docs.aws.amazon.com/sns/latest/dg/sns-sqs-as-subscriber.html
@@ -1127,8 +1059,26 @@ pub struct S3Object {
     pub key: String,
 }
 
+/// Decodes an URL-encoded object key, as found in bucket notifications and inventory reports.
+pub(super) fn decode_object_key(s: &[u8]) -> Result<String, std::str::Utf8Error> {
+    if s.contains(&b'+') {
+        // AWS encodes spaces as `+` rather than `%20`, so we first need to handle this.
+        let s = s
+            .iter()
+            .map(|c| if *c == b'+' { b' ' } else { *c })
+            .collect::<Vec<_>>();
+        percent_encoding::percent_decode(&s)
+            .decode_utf8()
+            .map(Into::into)
+    } else {
+        percent_encoding::percent_decode(s)
+            .decode_utf8()
+            .map(Into::into)
+    }
+}
+
 mod urlencoded_string {
-    use percent_encoding::{percent_decode, utf8_percent_encode};
+    use percent_encoding::utf8_percent_encode;
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
     where
@@ -1137,18 +1087,7 @@ mod urlencoded_string {
         use serde::de::Error;
 
         serde::de::Deserialize::deserialize(deserializer).and_then(|s: &[u8]| {
-            let decoded = if s.contains(&b'+') {
-                // AWS encodes spaces as `+` rather than `%20`, so we first need to handle this.
-                let s = s
-                    .iter()
-                    .map(|c| if *c == b'+' { b' ' } else { *c })
-                    .collect::<Vec<_>>();
-                percent_decode(&s).decode_utf8().map(Into::into)
-            } else {
-                percent_decode(s).decode_utf8().map(Into::into)
-            };
-
-            decoded
+            super::decode_object_key(s)
                 .map_err(|err| D::Error::custom(format!("error url decoding S3 object key: {err}")))
         })
     }
@@ -0,0 +1,252 @@
+use std::{convert::Infallible, net::SocketAddr, num::NonZeroUsize};
+
+use bytes::Bytes;
+use futures::{FutureExt, StreamExt, stream};
+use hyper::{Server, service::make_service_fn};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tower::ServiceBuilder;
+use tracing::Span;
+use vector_lib::{configurable::configurable_component, tls::MaybeTlsIncomingStream};
+use warp::{
+    Filter, Reply,
+    http::StatusCode,
+    reply::{Response, json},
+};
+
+use super::ObjectProcess;
+use crate::{
+    http::build_http_trace_layer,
+    internal_events::{ObjectNotificationReceiveError, ObjectProcessingError},
+    shutdown::ShutdownSignal,
+    sources::Source,
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+/// The type of the event sent by Event Grid to validate the ownership of the webhook.
+const SUBSCRIPTION_VALIDATION_EVENT: &str = "Microsoft.EventGrid.SubscriptionValidationEvent";
+
+/// The type of the events of blobs being created or overwritten.
+const BLOB_CREATED_EVENT: &str = "Microsoft.Storage.BlobCreated";
+
+/// Event Grid configuration options.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The socket address to listen for Event Grid deliveries on.
+    ///
+    /// The webhook endpoint of the Event Grid subscription must be routed to this address. Event
+    /// Grid only delivers events to HTTPS endpoints, so either `tls` must be enabled, or Vector
+    /// must be behind a proxy terminating TLS.
+    #[configurable(metadata(docs::examples = "0.0.0.0:443"))]
+    #[configurable(metadata(docs::examples = "localhost:443"))]
+    pub(super) address: SocketAddr,
+
+    #[configurable(derived)]
+    pub(super) tls: Option<TlsEnableableConfig>,
+
+    /// Number of blobs to process concurrently.
+    ///
+    /// Defaults to the number of available CPUs on the system.
+    #[configurable(metadata(docs::type_unit = "tasks"))]
+    #[configurable(metadata(docs::examples = 5))]
+    pub(super) client_concurrency: Option<NonZeroUsize>,
+}
+
+// https://learn.microsoft.com/en-us/azure/event-grid/event-schema
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventGridEvent {
+    event_type: String,
+    #[serde(default)]
+    subject: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+impl EventGridEvent {
+    /// The validation code of a subscription validation event.
+    fn validation_code(&self) -> Option<&str> {
+        if self.event_type != SUBSCRIPTION_VALIDATION_EVENT {
+            return None;
+        }
+        self.data.get("validationCode")?.as_str()
+    }
+
+    /// The name of the blob created by the event, if it belongs to the container.
+    fn created_blob(&self, container_name: &str) -> Option<&str> {
+        if self.event_type != BLOB_CREATED_EVENT {
+            return None;
+        }
+        // The subject has the form `/blobServices/default/containers/<container>/blobs/<blob>`.
+        let (container, blob) = self
+            .subject
+            .strip_prefix("/blobServices/default/containers/")?
+            .split_once("/blobs/")?;
+        (container == container_name).then_some(blob)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidationResponse<'a> {
+    validation_response: &'a str,
+}
+
+pub(super) async fn build_server(
+    config: &Config,
+    process: ObjectProcess,
+    shutdown: ShutdownSignal,
+) -> crate::Result<Source> {
+    let client_concurrency = config
+        .client_concurrency
+        .map(|n| n.get())
+        .unwrap_or_else(crate::num_threads);
+    let filter =
+        warp::post()
+            .and(warp::body::bytes())
+            .and_then(move |body: Bytes| {
+                let process = process.clone();
+                async move {
+                    Ok::<_, Infallible>(handle_events(body, process, client_concurrency).await)
+                }
+            });
+
+    let tls = MaybeTlsSettings::from_config(config.tls.as_ref(), true)?;
+    let listener = tls.bind(&config.address).await?;
+
+    Ok(Box::pin(async move {
+        let span = Span::current();
+        let make_svc = make_service_fn(move |_conn: &MaybeTlsIncomingStream<TcpStream>| {
+            let svc = ServiceBuilder::new()
+                .layer(build_http_trace_layer(span.clone()))
+                .service(warp::service(filter.clone()));
+            futures_util::future::ok::<_, Infallible>(svc)
+        });
+
+        Server::builder(hyper::server::accept::from_stream(listener.accept_stream()))
+            .serve(make_svc)
+            .with_graceful_shutdown(shutdown.map(|_| ()))
+            .await
+            .map_err(|err| {
+                error!("An error occurred: {:?}.", err);
+            })?;
+
+        Ok(())
+    }))
+}
+
+/// Handles a delivery of Event Grid events.
+///
+/// The delivery succeeds only once all the created blobs have been processed, otherwise Event
+/// Grid delivers the events again.
+async fn handle_events(body: Bytes, process: ObjectProcess, client_concurrency: usize) -> Response {
+    let events: Vec<EventGridEvent> = match serde_json::from_slice(&body) {
+        Ok(events) => events,
+        Err(error) => {
+            emit!(ObjectNotificationReceiveError { error: &error });
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    if let Some(validation_response) = events.iter().find_map(EventGridEvent::validation_code) {
+        return json(&ValidationResponse {
+            validation_response,
+        })
+        .into_response();
+    }
+
+    let container_name = process.container_name.clone();
+    let results: Vec<_> = stream::iter(
+        events
+            .iter()
+            .filter_map(|event| event.created_blob(&container_name)),
+    )
+    .map(|blob| process.clone().run(blob.to_owned()))
+    .buffer_unordered(client_concurrency)
+    .collect()
+    .await;
+
+    let mut succeeded = true;
+    for result in results {
+        if let Err(error) = result {
+            emit!(ObjectProcessingError { error: &error });
+            succeeded = false;
+        }
+    }
+
+    if succeeded {
+        StatusCode::OK.into_response()
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_events() {
+        let events: Vec<EventGridEvent> = serde_json::from_str(
+            r#"[
+                {
+                    "topic": "/subscriptions/id/resourceGroups/group/providers/Microsoft.Storage/storageAccounts/account",
+                    "subject": "/blobServices/default/containers/logs/blobs/2024/01/a.log",
+                    "eventType": "Microsoft.Storage.BlobCreated",
+                    "id": "1",
+                    "data": {"api": "PutBlob", "contentType": "text/plain"},
+                    "dataVersion": "",
+                    "metadataVersion": "1",
+                    "eventTime": "2024-01-01T00:00:00Z"
+                },
+                {
+                    "subject": "/blobServices/default/containers/other/blobs/b.log",
+                    "eventType": "Microsoft.Storage.BlobCreated",
+                    "id": "2"
+                },
+                {
+                    "subject": "/blobServices/default/containers/logs/blobs/c.log",
+                    "eventType": "Microsoft.Storage.BlobDeleted",
+                    "id": "3"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(events[0].created_blob("logs"), Some("2024/01/a.log"));
+        assert_eq!(events[1].created_blob("logs"), None);
+        assert_eq!(events[2].created_blob("logs"), None);
+        assert!(events.iter().all(|event| event.validation_code().is_none()));
+    }
+
+    #[test]
+    fn parse_validation_event() {
+        let events: Vec<EventGridEvent> = serde_json::from_str(
+            r#"[
+                {
+                    "id": "1",
+                    "topic": "/subscriptions/id",
+                    "subject": "",
+                    "data": {
+                        "validationCode": "512d38b6-c7b8-40c8-89fe-f46f9e9622b6",
+                        "validationUrl": "https://rp-eastus2.eventgrid.azure.net/validate"
+                    },
+                    "eventType": "Microsoft.EventGrid.SubscriptionValidationEvent",
+                    "eventTime": "2024-01-01T00:00:00Z"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let validation_response = events[0].validation_code().unwrap();
+        assert_eq!(
+            serde_json::to_value(ValidationResponse {
+                validation_response
+            })
+            .unwrap(),
+            serde_json::json!({"validationResponse": "512d38b6-c7b8-40c8-89fe-f46f9e9622b6"})
+        );
+    }
+}
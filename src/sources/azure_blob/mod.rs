@@ -0,0 +1,455 @@
+use std::{collections::HashMap, io, sync::Arc};
+
+use azure_storage_blobs::prelude::ContainerClient;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt, stream};
+use snafu::Snafu;
+use vector_lib::{
+    codecs::{
+        NewlineDelimitedDecoderConfig,
+        decoding::{DeserializerConfig, FramingConfig, NewlineDelimitedDecoderOptions},
+    },
+    config::{LegacyKey, LogNamespace},
+    configurable::configurable_component,
+    internal_event::{BytesReceived, Protocol, Registered},
+    lookup::{owned_value_path, path},
+    sensitive_string::SensitiveString,
+};
+use vrl::value::{Kind, kind::Collection};
+
+use super::util::{
+    MultilineConfig,
+    object_storage::{
+        Compression, SendObjectError, insert_object_timestamp, object_decoder, send_object_events,
+    },
+};
+use crate::{
+    SourceSender,
+    codecs::{Decoder, DecodingConfig},
+    config::{Resource, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput},
+    event::{BatchStatus, LogEvent},
+    internal_events::EventsReceived,
+    line_agg,
+    serde::{bool_or_struct, default_decoding},
+    sinks::azure_common,
+};
+
+mod event_grid;
+mod poll;
+
+/// Strategies for consuming blobs from Azure Blob Storage.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+enum Strategy {
+    /// Consumes blobs by receiving the `BlobCreated` events of the container from an [Event Grid][event_grid] subscription.
+    ///
+    /// Vector serves the webhook the events are delivered to.
+    ///
+    /// [event_grid]: https://learn.microsoft.com/en-us/azure/event-grid/overview
+    #[derivative(Default)]
+    EventGrid,
+
+    /// Consumes blobs by periodically listing the container.
+    ///
+    /// This can be used for containers where Event Grid can't deliver events to Vector. Processed
+    /// blobs are tracked in checkpoints persisted in the data directory.
+    Poll,
+}
+
+/// Configuration for the `azure_blob` source.
+#[configurable_component(source("azure_blob", "Collect logs from Azure Blob Storage."))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AzureBlobSourceConfig {
+    /// The Azure Blob Storage Account connection string.
+    ///
+    /// Authentication with an access key or shared access signature (SAS) are supported
+    /// authentication methods. The credentials must allow listing and reading the blobs of the
+    /// container.
+    #[configurable(metadata(
+        docs::examples = "DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"
+    ))]
+    #[configurable(metadata(
+        docs::examples = "BlobEndpoint=https://mylogstorage.blob.core.windows.net/;SharedAccessSignature=generatedsastoken"
+    ))]
+    connection_string: SensitiveString,
+
+    /// The Azure Blob Storage Account container name.
+    #[configurable(metadata(docs::examples = "my-logs"))]
+    container_name: String,
+
+    /// The compression scheme used for decompressing blobs retrieved from Azure Blob Storage.
+    #[serde(default)]
+    compression: Compression,
+
+    /// The strategy to use to consume blobs from Azure Blob Storage.
+    #[serde(default)]
+    strategy: Strategy,
+
+    /// Configuration options for Event Grid events.
+    #[serde(default)]
+    event_grid: Option<event_grid::Config>,
+
+    /// Configuration options for polling the container.
+    #[serde(default)]
+    poll: Option<poll::Config>,
+
+    /// Multiline aggregation configuration.
+    ///
+    /// If not specified, multiline aggregation is disabled.
+    #[configurable(derived)]
+    #[serde(default)]
+    multiline: Option<MultilineConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing")]
+    #[derivative(Default(value = "default_framing()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+}
+
+const fn default_framing() -> FramingConfig {
+    FramingConfig::NewlineDelimited(NewlineDelimitedDecoderConfig {
+        newline_delimited: NewlineDelimitedDecoderOptions { max_length: None },
+    })
+}
+
+impl_generate_config_from_default!(AzureBlobSourceConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_blob")]
+impl SourceConfig for AzureBlobSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        let multiline: Option<line_agg::Config> = self
+            .multiline
+            .as_ref()
+            .map(|config| config.try_into())
+            .transpose()?;
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+        let client = azure_common::config::build_client(
+            self.connection_string.inner().to_string(),
+            self.container_name.clone(),
+        )?;
+
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+        let process = ObjectProcess {
+            client,
+            container_name: self.container_name.clone(),
+            compression: self.compression,
+            multiline,
+            decoder,
+            out: cx.out.clone(),
+            acknowledgements,
+            log_namespace,
+            bytes_received: register!(BytesReceived::from(Protocol::HTTP)),
+            events_received: register!(EventsReceived),
+        };
+
+        match self.strategy {
+            Strategy::EventGrid => {
+                let Some(ref event_grid) = self.event_grid else {
+                    return Err(BuildError::EventGridConfigMissing.into());
+                };
+                event_grid::build_server(event_grid, process, cx.shutdown).await
+            }
+            Strategy::Poll => {
+                let Some(ref poll) = self.poll else {
+                    return Err(BuildError::PollConfigMissing.into());
+                };
+                // Each source gets its own subdirectory, so that two sources polling the same
+                // container track the blobs they processed separately.
+                let data_dir = cx
+                    .globals
+                    .resolve_and_make_data_subdir(poll.data_dir.as_ref(), cx.key.id())?;
+                let poller = poll::Poller::new(poll.clone(), data_dir, process).await?;
+                Ok(Box::pin(poller.run(cx.shutdown)))
+            }
+        }
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let mut schema_definition = self
+            .decoding
+            .schema_definition(log_namespace)
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!("container"))),
+                &owned_value_path!("container"),
+                Kind::bytes(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!("blob"))),
+                &owned_value_path!("blob"),
+                Kind::bytes(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                None,
+                &owned_value_path!("timestamp"),
+                Kind::timestamp(),
+                Some("timestamp"),
+            )
+            .with_standard_vector_source_metadata()
+            // for metadata that is added to the events dynamically from the metadata
+            .with_source_metadata(
+                Self::NAME,
+                None,
+                &owned_value_path!("metadata"),
+                Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                None,
+            );
+
+        // for metadata that is added to the events dynamically from the metadata
+        if log_namespace == LogNamespace::Legacy {
+            schema_definition = schema_definition.unknown_fields(Kind::bytes());
+        }
+
+        vec![SourceOutput::new_maybe_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        match (self.strategy, &self.event_grid) {
+            (Strategy::EventGrid, Some(event_grid)) => vec![Resource::tcp(event_grid.address)],
+            _ => Vec::new(),
+        }
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Configuration for `event_grid` required when strategy=event_grid"))]
+    EventGridConfigMissing,
+    #[snafu(display("Configuration for `poll` required when strategy=poll"))]
+    PollConfigMissing,
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum ProcessingError {
+    #[snafu(display("Failed to fetch blob {} of container {}: {}", blob, container, source))]
+    GetBlob {
+        source: crate::Error,
+        container: String,
+        blob: String,
+    },
+    #[snafu(display(
+        "Failed to process blob {} of container {}: {}",
+        blob,
+        container,
+        source
+    ))]
+    SendEvents {
+        source: SendObjectError,
+        container: String,
+        blob: String,
+    },
+    #[snafu(display(
+        "Sink reported an error sending events for blob {} of container {}",
+        blob,
+        container
+    ))]
+    ErrorAcknowledgement { container: String, blob: String },
+}
+
+/// Fetches blobs, and sends their events downstream.
+#[derive(Clone)]
+struct ObjectProcess {
+    client: Arc<ContainerClient>,
+    container_name: String,
+    compression: Compression,
+    multiline: Option<line_agg::Config>,
+    decoder: Decoder,
+
+    out: SourceSender,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+    bytes_received: Registered<BytesReceived>,
+    events_received: Registered<EventsReceived>,
+}
+
+impl ObjectProcess {
+    async fn run(mut self, blob: String) -> Result<(), ProcessingError> {
+        let container = self.container_name.clone();
+        let get_blob_error = |source: azure_core_for_storage::Error| ProcessingError::GetBlob {
+            source: source.into(),
+            container: container.clone(),
+            blob: blob.clone(),
+        };
+
+        // Large blobs are fetched in chunks, the first one holding the properties of the blob.
+        let mut chunks = self.client.blob_client(&blob).get().into_stream();
+        let first = match chunks.next().await {
+            Some(first) => first.map_err(get_blob_error)?,
+            None => return Ok(()),
+        };
+
+        debug!(
+            message = "Got Azure blob.",
+            container = container,
+            blob = blob,
+        );
+
+        let properties = &first.blob.properties;
+        let content_encoding = properties.content_encoding.clone();
+        let content_type = properties.content_type.clone();
+        let timestamp = DateTime::<Utc>::from_timestamp(
+            properties.last_modified.unix_timestamp(),
+            properties.last_modified.nanosecond(),
+        );
+        let metadata = first.blob.metadata.clone().unwrap_or_default();
+
+        let body = stream::once(first.data.collect())
+            .chain(chunks.and_then(|chunk| chunk.data.collect()))
+            .map_err(io::Error::other)
+            .boxed();
+        let object_reader = object_decoder(
+            self.compression,
+            &blob,
+            content_encoding.as_deref(),
+            Some(content_type.as_str()),
+            body,
+        )
+        .await;
+
+        let log_namespace = self.log_namespace;
+        let receiver = send_object_events(
+            &mut self.out,
+            &self.decoder,
+            self.multiline.as_ref(),
+            object_reader,
+            self.acknowledgements,
+            &self.bytes_received,
+            &self.events_received,
+            |log| handle_single_log(log, log_namespace, &container, &blob, &metadata, timestamp),
+        )
+        .await
+        .map_err(|source| ProcessingError::SendEvents {
+            source,
+            container: container.clone(),
+            blob: blob.clone(),
+        })?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => {
+                    debug!(
+                        message = "Azure blob delivered.",
+                        container = container,
+                        blob = blob,
+                    );
+                    Ok(())
+                }
+                BatchStatus::Errored => {
+                    Err(ProcessingError::ErrorAcknowledgement { container, blob })
+                }
+                BatchStatus::Rejected => {
+                    warn!(
+                        message = "Azure blob was rejected. It won't be processed again.",
+                        container = container,
+                        blob = blob,
+                    );
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+fn handle_single_log(
+    log: &mut LogEvent,
+    log_namespace: LogNamespace,
+    container: &str,
+    blob: &str,
+    metadata: &HashMap<String, String>,
+    timestamp: Option<DateTime<Utc>>,
+) {
+    log_namespace.insert_source_metadata(
+        AzureBlobSourceConfig::NAME,
+        log,
+        Some(LegacyKey::Overwrite(path!("container"))),
+        path!("container"),
+        Bytes::from(container.as_bytes().to_vec()),
+    );
+    log_namespace.insert_source_metadata(
+        AzureBlobSourceConfig::NAME,
+        log,
+        Some(LegacyKey::Overwrite(path!("blob"))),
+        path!("blob"),
+        Bytes::from(blob.as_bytes().to_vec()),
+    );
+
+    for (key, value) in metadata {
+        log_namespace.insert_source_metadata(
+            AzureBlobSourceConfig::NAME,
+            log,
+            Some(LegacyKey::Overwrite(path!(key))),
+            path!("metadata", key.as_str()),
+            value.clone(),
+        );
+    }
+
+    insert_object_timestamp(log, log_namespace, AzureBlobSourceConfig::NAME, timestamp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AzureBlobSourceConfig>();
+    }
+
+    #[test]
+    fn parse_config() {
+        let config: AzureBlobSourceConfig = toml::from_str(
+            r#"
+                connection_string = "UseDevelopmentStorage=true"
+                container_name = "logs"
+                strategy = "event_grid"
+                event_grid.address = "0.0.0.0:8080"
+                compression = "gzip"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(config.strategy, Strategy::EventGrid));
+        assert_eq!(config.compression, Compression::Gzip);
+        assert_eq!(
+            config.resources(),
+            vec![Resource::tcp("0.0.0.0:8080".parse().unwrap())]
+        );
+    }
+}
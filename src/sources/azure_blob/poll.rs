@@ -0,0 +1,134 @@
+use std::{num::NonZeroUsize, path::PathBuf, time::Duration};
+
+use futures::{StreamExt, stream::BoxStream};
+use serde_with::serde_as;
+use tokio::{select, time::MissedTickBehavior};
+use vector_lib::configurable::configurable_component;
+
+use super::ObjectProcess;
+use crate::{
+    internal_events::ObjectStoragePollError,
+    shutdown::ShutdownSignal,
+    sources::util::object_storage::{CheckpointsError, ListedObject, ObjectPoller},
+};
+
+/// Polling configuration options.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// Only blobs whose name starts with this prefix are consumed.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "logs/"))]
+    pub(super) prefix: Option<String>,
+
+    /// How often to look for new blobs, in seconds.
+    #[serde(default = "default_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_interval_secs()"))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Polling Interval"))]
+    pub(super) interval_secs: Duration,
+
+    /// Number of blobs to process concurrently.
+    ///
+    /// Defaults to the number of available CPUs on the system.
+    #[configurable(metadata(docs::type_unit = "tasks"))]
+    #[configurable(metadata(docs::examples = 5))]
+    pub(super) client_concurrency: Option<NonZeroUsize>,
+
+    /// The directory used to persist the checkpoints of the processed blobs.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub(super) data_dir: Option<PathBuf>,
+}
+
+const fn default_interval_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+pub(super) struct Poller {
+    interval: Duration,
+    prefix: Option<String>,
+    poller: ObjectPoller,
+    process: ObjectProcess,
+}
+
+impl Poller {
+    pub(super) async fn new(
+        config: Config,
+        data_dir: PathBuf,
+        process: ObjectProcess,
+    ) -> Result<Self, CheckpointsError> {
+        let poller = ObjectPoller::new(
+            data_dir,
+            config
+                .client_concurrency
+                .map(|n| n.get())
+                .unwrap_or_else(crate::num_threads),
+        )
+        .await?;
+
+        Ok(Self {
+            interval: config.interval_secs,
+            prefix: config.prefix,
+            poller,
+            process,
+        })
+    }
+
+    /// Lists the blobs of the container, under the prefix.
+    fn list(&self) -> BoxStream<'static, crate::Result<Vec<ListedObject>>> {
+        let mut list_blobs = self.process.client.list_blobs();
+        if let Some(prefix) = &self.prefix {
+            list_blobs = list_blobs.prefix(prefix.clone());
+        }
+
+        // The ETag of a blob changes when it is overwritten.
+        list_blobs
+            .into_stream()
+            .map(|page| {
+                Ok(page?
+                    .blobs
+                    .blobs()
+                    .map(|blob| ListedObject {
+                        key: blob.name.clone(),
+                        etag: Some(blob.properties.etag.to_string()),
+                        size: Some(blob.properties.content_length),
+                    })
+                    .collect())
+            })
+            .boxed()
+    }
+
+    pub(super) async fn run(mut self, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            select! {
+                biased;
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {}
+            }
+
+            let pages = self.list();
+            let process = |object: ListedObject| self.process.clone().run(object.key);
+            if let Err(error) = self.poller.poll_listing(pages, process, &shutdown).await {
+                emit!(ObjectStoragePollError { error: &error });
+            }
+        }
+
+        Ok(())
+    }
+}
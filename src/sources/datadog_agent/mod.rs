@@ -5,6 +5,7 @@ mod tests;
 
 pub mod logs;
 pub mod metrics;
+mod obfuscation;
 pub mod traces;
 
 #[allow(warnings, clippy::pedantic, clippy::nursery)]
@@ -60,7 +61,7 @@ use crate::{
     internal_events::{HttpBytesReceived, HttpDecompressError, StreamClosedError},
     schema,
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
-    sources::{self},
+    sources::{self, datadog_agent::obfuscation::TraceObfuscationConfig},
     tls::{MaybeTlsSettings, TlsEnableableConfig},
 };
 
@@ -127,6 +128,11 @@ pub struct DatadogAgentConfig {
     #[serde(default = "crate::serde::default_true")]
     split_metric_namespace: bool,
 
+    #[configurable(derived)]
+    #[configurable(metadata(docs::advanced))]
+    #[serde(default)]
+    trace_obfuscation: TraceObfuscationConfig,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[serde(default)]
     #[configurable(metadata(docs::hidden))]
@@ -167,6 +173,7 @@ impl GenerateConfig for DatadogAgentConfig {
             multiple_outputs: false,
             parse_ddtags: false,
             split_metric_namespace: true,
+            trace_obfuscation: TraceObfuscationConfig::default(),
             log_namespace: Some(false),
             keepalive: KeepaliveConfig::default(),
         })
@@ -199,6 +206,7 @@ impl SourceConfig for DatadogAgentConfig {
             log_namespace,
             self.parse_ddtags,
             self.split_metric_namespace,
+            self.trace_obfuscation.clone(),
         );
         let listener = tls.bind(&self.address).await?;
         let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
@@ -356,6 +364,7 @@ pub(crate) struct DatadogAgentSource {
     events_received: Registered<EventsReceived>,
     parse_ddtags: bool,
     split_metric_namespace: bool,
+    trace_obfuscation: TraceObfuscationConfig,
 }
 
 #[derive(Clone)]
@@ -394,6 +403,7 @@ impl DatadogAgentSource {
         log_namespace: LogNamespace,
         parse_ddtags: bool,
         split_metric_namespace: bool,
+        trace_obfuscation: TraceObfuscationConfig,
     ) -> Self {
         Self {
             api_key_extractor: ApiKeyExtractor {
@@ -416,6 +426,7 @@ impl DatadogAgentSource {
             events_received: register!(EventsReceived),
             parse_ddtags,
             split_metric_namespace,
+            trace_obfuscation,
         }
     }
 
@@ -0,0 +1,231 @@
+//! The obfuscation of the spans of the traces, as done by the Datadog Agent before sending them to
+//! the intake, so that the sensitive data of the traces can be scrubbed before they re-enter the
+//! pipeline.
+
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use itertools::Itertools;
+use regex::Regex;
+use vector_lib::configurable::configurable_component;
+
+use super::ddtrace_proto;
+
+/// A list of obfuscated literals, such as the values of an `IN` clause.
+static LITERAL_LIST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\(\s*\?(?:\s*,\s*\?)*\s*\)").expect("static regex always compiles")
+});
+
+/// Configuration for the obfuscation of the traces.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TraceObfuscationConfig {
+    /// Whether or not to obfuscate the SQL queries of the spans of the `sql` and `cassandra` types.
+    ///
+    /// The literals of the queries, in the resource and the `sql.query` tag of the spans, are
+    /// replaced with `?`, their lists are collapsed, and their comments are removed.
+    #[serde(default)]
+    pub sql: bool,
+
+    /// Whether or not to obfuscate the Redis commands of the spans of the `redis` type.
+    ///
+    /// The arguments of the commands in the `redis.raw_command` tag of the spans are replaced with
+    /// `?`, except for the key. All of the arguments of the `AUTH` command are replaced.
+    #[serde(default)]
+    pub redis: bool,
+
+    /// The tags removed from the spans and the traces.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "http.url"))]
+    #[configurable(metadata(docs::examples = "usr.email"))]
+    pub remove_tags: Vec<String>,
+}
+
+impl TraceObfuscationConfig {
+    /// Obfuscates the queries or the commands of the span, depending on its type, and removes its
+    /// tags to strip.
+    pub(super) fn obfuscate_span(&self, span: &mut ddtrace_proto::Span) {
+        match span.r#type.as_str() {
+            "sql" | "cassandra" if self.sql => {
+                span.resource = obfuscate_sql(&span.resource);
+                if let Some(query) = span.meta.get_mut("sql.query") {
+                    *query = obfuscate_sql(query);
+                }
+            }
+            "redis" if self.redis => {
+                if let Some(command) = span.meta.get_mut("redis.raw_command") {
+                    *command = obfuscate_redis(command);
+                }
+            }
+            _ => {}
+        }
+        self.strip_tags(&mut span.meta);
+    }
+
+    /// Removes the tags to strip.
+    pub(super) fn strip_tags(&self, tags: &mut BTreeMap<String, String>) {
+        if !self.remove_tags.is_empty() {
+            tags.retain(|name, _| !self.remove_tags.contains(name));
+        }
+    }
+}
+
+/// Replaces the string and numeric literals of the query with `?`, collapses the lists of literals
+/// and removes the comments, normalizing the whitespace.
+fn obfuscate_sql(query: &str) -> String {
+    let mut obfuscated = String::with_capacity(query.len());
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // Quotes are escaped by doubling them.
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                obfuscated.push('?');
+            }
+            // Quoted identifiers are kept.
+            '"' | '`' => {
+                obfuscated.push(c);
+                for quoted in chars.by_ref() {
+                    obfuscated.push(quoted);
+                    if quoted == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.by_ref().find(|&c| c == '\n');
+                obfuscated.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                obfuscated.push(' ');
+            }
+            // The negative numbers are told apart from the subtractions by what precedes them.
+            c if c.is_ascii_digit()
+                || (c == '-'
+                    && chars.peek().is_some_and(char::is_ascii_digit)
+                    && obfuscated
+                        .trim_end()
+                        .chars()
+                        .next_back()
+                        .is_none_or(|previous| "=<>(,+-*/".contains(previous))) =>
+            {
+                chars
+                    .peeking_take_while(|c| c.is_ascii_alphanumeric() || *c == '.')
+                    .for_each(drop);
+                obfuscated.push('?');
+            }
+            // The digits of the identifiers are kept.
+            c if c.is_alphabetic() || c == '_' => {
+                obfuscated.push(c);
+                obfuscated.extend(
+                    chars.peeking_take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$'),
+                );
+            }
+            c => obfuscated.push(c),
+        }
+    }
+
+    let obfuscated = obfuscated.split_whitespace().collect::<Vec<_>>().join(" ");
+    LITERAL_LIST.replace_all(&obfuscated, "( ? )").into_owned()
+}
+
+/// Replaces the arguments of each of the commands with `?`, except for the key.
+fn obfuscate_redis(raw_command: &str) -> String {
+    raw_command
+        .lines()
+        .map(|command| {
+            let mut args = command.split_whitespace();
+            let Some(name) = args.next() else {
+                return String::new();
+            };
+            let key = args.next();
+            let has_values = args.next().is_some();
+            match key {
+                Some(_) if name.eq_ignore_ascii_case("AUTH") => format!("{name} ?"),
+                Some(key) if has_values => format!("{name} {key} ?"),
+                Some(key) => format!("{name} {key}"),
+                None => name.to_owned(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn obfuscates_sql_queries() {
+        for (query, obfuscated) in [
+            (
+                "SELECT * FROM users WHERE name = 'O''Brien' AND age > 42",
+                "SELECT * FROM users WHERE name = ? AND age > ?",
+            ),
+            (
+                "select id from t1 where id in (1, 2, -3) -- the first ones\nlimit 10",
+                "select id from t1 where id in ( ? ) limit ?",
+            ),
+            (
+                r#"UPDATE "table2" /* bump */ SET price = price - 1.5e3 WHERE sku = 0xFF"#,
+                r#"UPDATE "table2" SET price = price - ? WHERE sku = ?"#,
+            ),
+        ] {
+            assert_eq!(obfuscate_sql(query), obfuscated);
+        }
+    }
+
+    #[test]
+    fn obfuscates_redis_commands() {
+        assert_eq!(
+            obfuscate_redis("SET session:42 secret EX 60\nGET session:42\nauth hunter2\nPING"),
+            "SET session:42 ?\nGET session:42\nauth ?\nPING"
+        );
+    }
+
+    #[test]
+    fn obfuscates_spans() {
+        let config = TraceObfuscationConfig {
+            sql: true,
+            redis: false,
+            remove_tags: vec!["usr.email".into()],
+        };
+        let span = |r#type: &str, resource: &str, tag: &str| ddtrace_proto::Span {
+            resource: resource.into(),
+            r#type: r#type.into(),
+            meta: BTreeMap::from([
+                (tag.into(), resource.into()),
+                ("usr.email".into(), "jane@example.com".into()),
+            ]),
+            ..Default::default()
+        };
+
+        let mut sql = span("sql", "SELECT 1", "sql.query");
+        config.obfuscate_span(&mut sql);
+        assert_eq!(sql.resource, "SELECT ?");
+        assert_eq!(
+            sql.meta,
+            BTreeMap::from([("sql.query".into(), "SELECT ?".into())])
+        );
+
+        let mut redis = span("redis", "SET", "redis.raw_command");
+        redis
+            .meta
+            .insert("redis.raw_command".into(), "SET k v".into());
+        config.obfuscate_span(&mut redis);
+        assert_eq!(redis.meta["redis.raw_command"], "SET k v");
+        assert!(!redis.meta.contains_key("usr.email"));
+    }
+}
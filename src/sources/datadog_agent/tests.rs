@@ -110,6 +110,7 @@ fn test_decode_log_body() {
             LogNamespace::Legacy,
             false,
             true,
+            Default::default(),
         );
 
         let events = decode_log_body(body, api_key, &source).unwrap();
@@ -166,6 +167,7 @@ fn test_decode_log_body_parse_ddtags() {
         LogNamespace::Legacy,
         true,
         true,
+        Default::default(),
     );
 
     let events = decode_log_body(body, api_key, &source).unwrap();
@@ -203,6 +205,7 @@ fn test_decode_log_body_empty_object() {
         LogNamespace::Legacy,
         false,
         true,
+        Default::default(),
     );
 
     let events = decode_log_body(body, api_key, &source).unwrap();
@@ -1483,6 +1486,7 @@ fn test_config_outputs_with_disabled_data_types() {
             disable_traces,
             parse_ddtags: false,
             split_metric_namespace: true,
+            trace_obfuscation: Default::default(),
             log_namespace: Some(false),
             keepalive: Default::default(),
         };
@@ -1926,6 +1930,7 @@ fn test_config_outputs() {
             disable_traces: false,
             parse_ddtags: false,
             split_metric_namespace: true,
+            trace_obfuscation: Default::default(),
             log_namespace: Some(false),
             keepalive: Default::default(),
         };
@@ -2597,6 +2602,7 @@ impl ValidatableComponent for DatadogAgentConfig {
             disable_traces: false,
             parse_ddtags: false,
             split_metric_namespace: true,
+            trace_obfuscation: Default::default(),
             log_namespace: Some(false),
             keepalive: Default::default(),
         };
@@ -18,7 +18,8 @@ use crate::{
     common::http::ErrorMessage,
     event::{Event, ObjectMap, TraceEvent, Value},
     sources::datadog_agent::{
-        ApiKeyQueryParams, DatadogAgentSource, ddtrace_proto, handle_request,
+        ApiKeyQueryParams, DatadogAgentSource, TraceObfuscationConfig, ddtrace_proto,
+        handle_request,
     },
 };
 
@@ -123,12 +124,14 @@ fn handle_dd_trace_payload_v1(
     let agent_version = decoded_payload.agent_version;
     let target_tps = decoded_payload.target_tps;
     let error_tps = decoded_payload.error_tps;
-    let tags = convert_tags(decoded_payload.tags);
+    let mut tags = decoded_payload.tags;
+    source.trace_obfuscation.strip_tags(&mut tags);
+    let tags = convert_tags(tags);
 
     let trace_events: Vec<TraceEvent> = decoded_payload
         .tracer_payloads
         .into_iter()
-        .flat_map(convert_dd_tracer_payload)
+        .flat_map(|payload| convert_dd_tracer_payload(payload, &source.trace_obfuscation))
         .collect();
 
     source.events_received.emit(CountByteSize(
@@ -171,16 +174,21 @@ fn handle_dd_trace_payload_v1(
     Ok(enriched_events)
 }
 
-fn convert_dd_tracer_payload(payload: ddtrace_proto::TracerPayload) -> Vec<TraceEvent> {
+fn convert_dd_tracer_payload(
+    mut payload: ddtrace_proto::TracerPayload,
+    obfuscation: &TraceObfuscationConfig,
+) -> Vec<TraceEvent> {
+    obfuscation.strip_tags(&mut payload.tags);
     let tags = convert_tags(payload.tags);
     payload
         .chunks
         .into_iter()
-        .map(|trace| {
+        .map(|mut trace| {
             let mut trace_event = TraceEvent::default();
             trace_event.insert(event_path!("priority"), trace.priority as i64);
             trace_event.insert(event_path!("origin"), trace.origin);
             trace_event.insert(event_path!("dropped"), trace.dropped_trace);
+            obfuscation.strip_tags(&mut trace.tags);
             let mut trace_tags = convert_tags(trace.tags);
             trace_tags.extend(tags.clone());
             trace_event.insert(event_path!("tags"), Value::from(trace_tags));
@@ -190,7 +198,7 @@ fn convert_dd_tracer_payload(payload: ddtrace_proto::TracerPayload) -> Vec<Trace
                 trace
                     .spans
                     .into_iter()
-                    .map(|s| Value::from(convert_span(s)))
+                    .map(|s| Value::from(convert_span(s, obfuscation)))
                     .collect::<Vec<Value>>(),
             );
 
@@ -240,7 +248,7 @@ fn handle_dd_trace_payload_v0(
                 dd_trace
                     .spans
                     .into_iter()
-                    .map(|s| Value::from(convert_span(s)))
+                    .map(|s| Value::from(convert_span(s, &source.trace_obfuscation)))
                     .collect::<Vec<Value>>(),
             );
             trace_event
@@ -248,7 +256,8 @@ fn handle_dd_trace_payload_v0(
         //... and each APM event is also mapped into its own event
         .chain(decoded_payload.transactions.into_iter().map(|s| {
             let mut trace_event = TraceEvent::default();
-            trace_event.insert(event_path!("spans"), vec![Value::from(convert_span(s))]);
+            let span = convert_span(s, &source.trace_obfuscation);
+            trace_event.insert(event_path!("spans"), vec![Value::from(span)]);
             trace_event.insert(event_path!("dropped"), true);
             trace_event
         })).collect();
@@ -283,7 +292,12 @@ fn handle_dd_trace_payload_v0(
     Ok(enriched_events)
 }
 
-fn convert_span(dd_span: ddtrace_proto::Span) -> ObjectMap {
+fn convert_span(
+    mut dd_span: ddtrace_proto::Span,
+    obfuscation: &TraceObfuscationConfig,
+) -> ObjectMap {
+    obfuscation.obfuscate_span(&mut dd_span);
+
     let mut span = ObjectMap::new();
     span.insert("service".into(), Value::from(dd_span.service));
     span.insert("name".into(), Value::from(dd_span.name));
@@ -0,0 +1,171 @@
+//! Parsing of the formats of tracepoints, which give the offsets of their fields on the running
+//! kernel.
+use snafu::{OptionExt, Snafu};
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub(super) enum FormatError {
+    #[snafu(display("missing tracepoint ID"))]
+    MissingId,
+
+    #[snafu(display("invalid field description `{line}`"))]
+    InvalidField { line: String },
+
+    #[snafu(display("tracepoint has no field `{name}`"))]
+    MissingField { name: &'static str },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Field {
+    pub(super) name: String,
+    pub(super) offset: usize,
+    pub(super) size: usize,
+    /// Whether the field is a `__data_loc` reference to data after the fixed fields, such as a
+    /// string.
+    pub(super) dynamic: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Format {
+    pub(super) id: u64,
+    pub(super) fields: Vec<Field>,
+}
+
+impl Format {
+    pub(super) fn field(&self, name: &'static str) -> Result<&Field, FormatError> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .context(MissingFieldSnafu { name })
+    }
+
+    pub(super) fn has_field(&self, name: &str) -> bool {
+        self.fields.iter().any(|field| field.name == name)
+    }
+
+    /// The size of the fixed fields of records.
+    pub(super) fn fixed_size(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| field.offset + field.size)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Parses the content of the `format` file of a tracepoint.
+pub(super) fn parse(content: &str) -> Result<Format, FormatError> {
+    let mut id = None;
+    let mut fields = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if let Some(value) = line.strip_prefix("ID:") {
+            id = value.trim().parse().ok();
+        } else if line.starts_with("field:") {
+            fields.push(parse_field(line).context(InvalidFieldSnafu { line })?);
+        }
+    }
+    Ok(Format {
+        id: id.context(MissingIdSnafu)?,
+        fields,
+    })
+}
+
+/// Parses a field description, such as `field:pid_t pid;	offset:12;	size:4;	signed:1;`.
+fn parse_field(line: &str) -> Option<Field> {
+    let mut declaration = None;
+    let mut offset = None;
+    let mut size = None;
+    for part in line.split(';').map(str::trim) {
+        if let Some(value) = part.strip_prefix("field:") {
+            declaration = Some(value);
+        } else if let Some(value) = part.strip_prefix("offset:") {
+            offset = value.parse().ok();
+        } else if let Some(value) = part.strip_prefix("size:") {
+            size = value.parse().ok();
+        }
+    }
+
+    let declaration = declaration?;
+    // The name is the last word of the declaration, without the length of arrays.
+    let name = declaration.rsplit(' ').next()?;
+    let name = name.split_once('[').map_or(name, |(name, _)| name);
+    Some(Field {
+        name: name.to_owned(),
+        offset: offset?,
+        size: size?,
+        dynamic: declaration.starts_with("__data_loc "),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHED_PROCESS_EXEC: &str = "name: sched_process_exec
+ID: 365
+format:
+\tfield:unsigned short common_type;\toffset:0;\tsize:2;\tsigned:0;
+\tfield:unsigned char common_flags;\toffset:2;\tsize:1;\tsigned:0;
+\tfield:unsigned char common_preempt_count;\toffset:3;\tsize:1;\tsigned:0;
+\tfield:int common_pid;\toffset:4;\tsize:4;\tsigned:1;
+
+\tfield:__data_loc char[] filename;\toffset:8;\tsize:4;\tsigned:0;
+\tfield:pid_t pid;\toffset:12;\tsize:4;\tsigned:1;
+\tfield:pid_t old_pid;\toffset:16;\tsize:4;\tsigned:1;
+
+print fmt: \"filename=%s pid=%d old_pid=%d\", __get_str(filename), REC->pid, REC->old_pid
+";
+
+    #[test]
+    fn parses_formats() {
+        let format = parse(SCHED_PROCESS_EXEC).unwrap();
+        assert_eq!(365, format.id);
+        assert_eq!(7, format.fields.len());
+        assert_eq!(
+            Ok(&Field {
+                name: "filename".to_owned(),
+                offset: 8,
+                size: 4,
+                dynamic: true,
+            }),
+            format.field("filename")
+        );
+        assert_eq!(
+            Ok(&Field {
+                name: "old_pid".to_owned(),
+                offset: 16,
+                size: 4,
+                dynamic: false,
+            }),
+            format.field("old_pid")
+        );
+        assert_eq!(20, format.fixed_size());
+        assert!(!format.has_field("group_dead"));
+        assert_eq!(
+            Err(FormatError::MissingField { name: "comm" }),
+            format.field("comm")
+        );
+    }
+
+    #[test]
+    fn parses_array_fields() {
+        let field =
+            parse_field("field:__u8 saddr_v6[16];\toffset:40;\tsize:16;\tsigned:0;").unwrap();
+        assert_eq!("saddr_v6", field.name);
+        assert_eq!(16, field.size);
+        assert!(!field.dynamic);
+    }
+
+    #[test]
+    fn rejects_invalid_formats() {
+        assert_eq!(
+            Err(FormatError::MissingId),
+            parse("name: sched_process_exec\nformat:\n")
+        );
+        assert_eq!(
+            Err(FormatError::InvalidField {
+                line: "field:pid_t pid;\toffset:12;".to_owned()
+            }),
+            parse("ID: 1\nformat:\n\tfield:pid_t pid;\toffset:12;\n")
+        );
+    }
+}
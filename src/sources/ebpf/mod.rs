@@ -0,0 +1,529 @@
+//! The `ebpf` source attaches BPF programs to tracepoints of the Linux kernel, to collect the
+//! processes which start and exit and the TCP connections which open and close on the host.
+//!
+//! The programs are assembled when the source starts, from the formats of the tracepoints on the
+//! running kernel, so that the same binary works across kernel versions without BTF or a
+//! compiler. They write samples to a perf ring buffer per CPU, which are turned into events.
+use std::{
+    io,
+    os::fd::{AsRawFd, OwnedFd},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use futures::future::select_all;
+use snafu::{ResultExt, Snafu};
+use tokio::io::unix::AsyncFd;
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LegacyKey, LogNamespace, log_schema},
+    configurable::configurable_component,
+    internal_event::{ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Protocol},
+    lookup::{owned_value_path, path},
+    schema::Definition,
+};
+use vrl::value::{Kind, ObjectMap, kind::Collection};
+
+use self::{
+    format::FormatError,
+    probe::{LoadedProbe, Probe},
+    sys::{PerfBuffer, Record},
+};
+use crate::{
+    SourceSender,
+    config::{DataType, SourceConfig, SourceContext, SourceOutput},
+    event::LogEvent,
+    internal_events::{
+        EbpfInvalidSample, EbpfReadError, EbpfSamplesLost, EventsReceived, StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+};
+
+mod format;
+mod probe;
+mod program;
+mod sys;
+
+/// The paths tracefs is mounted at, in order of preference.
+const TRACEFS_PATHS: [&str; 2] = ["/sys/kernel/tracing", "/sys/kernel/debug/tracing"];
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one probe must be enabled"))]
+    NoProbes,
+
+    #[snafu(display("`buffer_pages` must be a power of two, got {pages}"))]
+    InvalidBufferPages { pages: usize },
+
+    #[snafu(display("tracefs is not mounted, set `tracefs_path` to where it is mounted"))]
+    MissingTracefs,
+
+    #[snafu(display("Could not list the online CPUs: {source}"))]
+    ListCpus { source: io::Error },
+
+    #[snafu(display("Could not create the perf event array: {source}"))]
+    CreateMap { source: io::Error },
+
+    #[snafu(display("Could not open the perf buffer of CPU {cpu}: {source}"))]
+    OpenBuffer { cpu: u32, source: io::Error },
+
+    #[snafu(display("Could not read the format of tracepoint {tracepoint}: {source}"))]
+    ReadFormat {
+        tracepoint: String,
+        source: io::Error,
+    },
+
+    #[snafu(display("Invalid format of tracepoint {tracepoint}: {source}"))]
+    ParseFormat {
+        tracepoint: String,
+        source: FormatError,
+    },
+
+    #[snafu(display("Could not load the program of probe {probe}: {source}\n{log}"))]
+    LoadProgram {
+        probe: &'static str,
+        source: io::Error,
+        log: String,
+    },
+
+    #[snafu(display("Could not attach the program of probe {probe}: {source}"))]
+    Attach {
+        probe: &'static str,
+        source: io::Error,
+    },
+}
+
+/// Configuration for the `ebpf` source.
+#[configurable_component(source(
+    "ebpf",
+    "Collect process and TCP connection events from the Linux kernel with eBPF."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EbpfConfig {
+    #[configurable(derived)]
+    #[serde(default)]
+    pub probes: ProbesConfig,
+
+    /// The path where [tracefs][tracefs] is mounted, which has the formats of the tracepoints.
+    ///
+    /// By default, `/sys/kernel/tracing` is used, or `/sys/kernel/debug/tracing` on systems
+    /// where tracefs is only mounted in debugfs.
+    ///
+    /// [tracefs]: https://docs.kernel.org/trace/ftrace.html
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/sys/kernel/tracing"))]
+    pub tracefs_path: Option<PathBuf>,
+
+    /// The number of memory pages of the buffer each CPU writes events to, which must be a
+    /// power of two.
+    ///
+    /// Events are dropped when the buffer of a CPU is full, which can happen during bursts of
+    /// activity if Vector can't keep up.
+    #[serde(default = "default_buffer_pages")]
+    #[configurable(metadata(docs::type_unit = "pages"))]
+    pub buffer_pages: usize,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    pub log_namespace: Option<bool>,
+}
+
+/// The probes to attach, each emitting a kind of event.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProbesConfig {
+    /// Emit an event when a process executes a program.
+    #[serde(default = "crate::serde::default_true")]
+    pub process_exec: bool,
+
+    /// Emit an event when a process exits.
+    #[serde(default = "crate::serde::default_true")]
+    pub process_exit: bool,
+
+    /// Emit an event when a TCP connection is established, either by connecting to or by
+    /// accepting a connection from another host.
+    #[serde(default = "crate::serde::default_true")]
+    pub tcp_connect: bool,
+
+    /// Emit an event when a TCP connection is closed.
+    #[serde(default = "crate::serde::default_true")]
+    pub tcp_close: bool,
+}
+
+impl Default for ProbesConfig {
+    fn default() -> Self {
+        Self {
+            process_exec: true,
+            process_exit: true,
+            tcp_connect: true,
+            tcp_close: true,
+        }
+    }
+}
+
+impl ProbesConfig {
+    const fn is_enabled(&self, probe: Probe) -> bool {
+        match probe {
+            Probe::ProcessExec => self.process_exec,
+            Probe::ProcessExit => self.process_exit,
+            Probe::TcpConnect => self.tcp_connect,
+            Probe::TcpClose => self.tcp_close,
+        }
+    }
+}
+
+const fn default_buffer_pages() -> usize {
+    64
+}
+
+impl Default for EbpfConfig {
+    fn default() -> Self {
+        Self {
+            probes: ProbesConfig::default(),
+            tracefs_path: None,
+            buffer_pages: default_buffer_pages(),
+            log_namespace: None,
+        }
+    }
+}
+
+impl_generate_config_from_default!(EbpfConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ebpf")]
+impl SourceConfig for EbpfConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let probes = Probe::ALL
+            .into_iter()
+            .filter(|probe| self.probes.is_enabled(*probe))
+            .collect::<Vec<_>>();
+        if probes.is_empty() {
+            return Err(BuildError::NoProbes.into());
+        }
+        if !self.buffer_pages.is_power_of_two() {
+            return Err(BuildError::InvalidBufferPages {
+                pages: self.buffer_pages,
+            }
+            .into());
+        }
+        let tracefs = match &self.tracefs_path {
+            Some(path) => path.clone(),
+            None => TRACEFS_PATHS
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.join("events").is_dir())
+                .ok_or(BuildError::MissingTracefs)?,
+        };
+
+        let loaded = load(&probes, &tracefs, self.buffer_pages)?;
+        let reader = SampleReader {
+            probes: loaded.probes,
+            host: crate::get_hostname().ok(),
+            log_namespace: cx.log_namespace(self.log_namespace),
+        };
+        let buffers = loaded
+            .buffers
+            .into_iter()
+            .map(AsyncFd::new)
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Box::pin(run(
+            reader,
+            buffers,
+            loaded.attachments,
+            cx.out,
+            cx.shutdown,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let schema_definition = Definition::new_with_default_metadata(
+            Kind::object(Collection::from_unknown(Kind::any())),
+            [log_namespace],
+        )
+        .with_standard_vector_source_metadata()
+        .with_source_metadata(
+            Self::NAME,
+            log_schema()
+                .timestamp_key()
+                .cloned()
+                .map(LegacyKey::Overwrite),
+            &owned_value_path!("timestamp"),
+            Kind::timestamp(),
+            Some("timestamp"),
+        )
+        .with_source_metadata(
+            Self::NAME,
+            log_schema().host_key().cloned().map(LegacyKey::Overwrite),
+            &owned_value_path!("host"),
+            Kind::bytes().or_undefined(),
+            Some("host"),
+        );
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// The perf buffers and the attached programs, which are detached once dropped.
+struct Loaded {
+    probes: Vec<LoadedProbe>,
+    buffers: Vec<PerfBuffer>,
+    attachments: Vec<OwnedFd>,
+}
+
+fn load(probes: &[Probe], tracefs: &Path, pages: usize) -> Result<Loaded, BuildError> {
+    let cpus = sys::online_cpus().context(ListCpusSnafu)?;
+    let entries = cpus.iter().max().map_or(1, |cpu| cpu + 1);
+    let map = sys::create_perf_event_array(entries).context(CreateMapSnafu)?;
+
+    let mut buffers = Vec::with_capacity(cpus.len());
+    for cpu in cpus {
+        let buffer = PerfBuffer::open(cpu, pages).context(OpenBufferSnafu { cpu })?;
+        sys::set_perf_event(&map, cpu, buffer.event()).context(OpenBufferSnafu { cpu })?;
+        buffers.push(buffer);
+    }
+
+    let mut loaded = Vec::with_capacity(probes.len());
+    // The programs and the map are kept open by the perf events the programs are attached to.
+    let mut attachments = Vec::with_capacity(probes.len());
+    for &probe in probes {
+        let (system, name) = probe.tracepoint();
+        let tracepoint = format!("{system}:{name}");
+        let path = tracefs
+            .join("events")
+            .join(system)
+            .join(name)
+            .join("format");
+        let content = std::fs::read_to_string(path).context(ReadFormatSnafu {
+            tracepoint: &tracepoint,
+        })?;
+        let format = format::parse(&content).context(ParseFormatSnafu {
+            tracepoint: &tracepoint,
+        })?;
+        let program = probe.program(&format).context(ParseFormatSnafu {
+            tracepoint: &tracepoint,
+        })?;
+
+        let fd =
+            sys::load_program(&program.assemble(map.as_raw_fd())).map_err(|(source, log)| {
+                BuildError::LoadProgram {
+                    probe: probe.name(),
+                    source,
+                    log,
+                }
+            })?;
+        attachments.push(sys::attach_tracepoint(format.id, &fd).context(AttachSnafu {
+            probe: probe.name(),
+        })?);
+        debug!(message = "Attached probe.", probe = probe.name(), %tracepoint);
+        loaded.push(LoadedProbe { probe, format });
+    }
+
+    Ok(Loaded {
+        probes: loaded,
+        buffers,
+        attachments,
+    })
+}
+
+/// Turns the samples written by the programs into events.
+struct SampleReader {
+    probes: Vec<LoadedProbe>,
+    host: Option<String>,
+    log_namespace: LogNamespace,
+}
+
+impl SampleReader {
+    /// Parses a sample, returning `None` if the probe doesn't report it.
+    fn read(&self, sample: &[u8]) -> Result<Option<LogEvent>, ()> {
+        let probe = probe::sample_probe(sample)
+            .and_then(|probe| self.probes.iter().find(|loaded| loaded.probe == probe))
+            .ok_or(())?;
+        Ok(probe.parse(sample).map(|fields| self.event(fields)))
+    }
+
+    fn event(&self, fields: ObjectMap) -> LogEvent {
+        let mut log = LogEvent::from(fields);
+        let now = Utc::now();
+        self.log_namespace
+            .insert_standard_vector_source_metadata(&mut log, EbpfConfig::NAME, now);
+        self.log_namespace.insert_source_metadata(
+            EbpfConfig::NAME,
+            &mut log,
+            log_schema().timestamp_key().map(LegacyKey::Overwrite),
+            path!("timestamp"),
+            now,
+        );
+        if let Some(host) = &self.host {
+            self.log_namespace.insert_source_metadata(
+                EbpfConfig::NAME,
+                &mut log,
+                log_schema().host_key().map(LegacyKey::Overwrite),
+                path!("host"),
+                host.clone(),
+            );
+        }
+        log
+    }
+}
+
+async fn run(
+    reader: SampleReader,
+    mut buffers: Vec<AsyncFd<PerfBuffer>>,
+    _attachments: Vec<OwnedFd>,
+    mut out: SourceSender,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let bytes_received = register!(BytesReceived::from(Protocol::NONE));
+    let events_received = register!(EventsReceived);
+
+    loop {
+        let readable = select_all(buffers.iter().map(|buffer| Box::pin(buffer.readable())));
+        let ready = tokio::select! {
+            (result, index, _) = readable => {
+                // The readiness is cleared before reading, so that samples written meanwhile wake
+                // the source up again.
+                result.map(|mut guard| {
+                    guard.clear_ready();
+                    index
+                })
+            },
+            _ = &mut shutdown => break,
+        };
+        let index = match ready {
+            Ok(index) => index,
+            Err(error) => {
+                emit!(EbpfReadError { error });
+                return Err(());
+            }
+        };
+
+        let mut events = Vec::new();
+        let mut byte_size = 0;
+        buffers[index].get_mut().read(|record| match record {
+            Record::Sample(sample) => {
+                byte_size += sample.len();
+                match reader.read(sample) {
+                    Ok(Some(event)) => events.push(event),
+                    Ok(None) => (),
+                    Err(()) => emit!(EbpfInvalidSample {
+                        length: sample.len()
+                    }),
+                }
+            }
+            Record::Lost(count) => emit!(EbpfSamplesLost { count }),
+        });
+
+        bytes_received.emit(ByteSize(byte_size));
+        if events.is_empty() {
+            continue;
+        }
+        let count = events.len();
+        events_received.emit(CountByteSize(
+            count,
+            events.estimated_json_encoded_size_of(),
+        ));
+        if out.send_batch(events).await.is_err() {
+            emit!(StreamClosedError { count });
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::value::Value;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EbpfConfig>();
+    }
+
+    #[test]
+    fn parses_probes_config() {
+        let config: EbpfConfig = toml::from_str(
+            r#"
+            buffer_pages = 16
+
+            [probes]
+            process_exit = false
+            tcp_close = false
+            "#,
+        )
+        .unwrap();
+
+        let enabled = Probe::ALL
+            .into_iter()
+            .filter(|probe| config.probes.is_enabled(*probe))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Probe::ProcessExec, Probe::TcpConnect], enabled);
+        assert_eq!(16, config.buffer_pages);
+    }
+
+    fn reader(log_namespace: LogNamespace) -> SampleReader {
+        SampleReader {
+            probes: Vec::new(),
+            host: Some("host.example.com".to_owned()),
+            log_namespace,
+        }
+    }
+
+    fn fields() -> ObjectMap {
+        let mut fields = ObjectMap::new();
+        fields.insert("probe".into(), "process_exit".into());
+        fields.insert("pid".into(), 1234.into());
+        fields
+    }
+
+    #[test]
+    fn creates_events() {
+        let log = reader(LogNamespace::Legacy).event(fields());
+
+        assert_eq!(log["probe"], "process_exit".into());
+        assert_eq!(log["pid"], 1234.into());
+        assert_eq!(log["host"], "host.example.com".into());
+        assert!(log.get("timestamp").is_some());
+        assert_eq!(log["source_type"], "ebpf".into());
+    }
+
+    #[test]
+    fn creates_events_with_vector_namespace() {
+        let log = reader(LogNamespace::Vector).event(fields());
+
+        assert_eq!(log["probe"], "process_exit".into());
+        assert!(log.get("host").is_none());
+        assert_eq!(
+            log.metadata().value().get(path!("ebpf", "host")),
+            Some(&Value::from("host.example.com"))
+        );
+        assert!(
+            log.metadata()
+                .value()
+                .get(path!("ebpf", "timestamp"))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn rejects_samples_of_unloaded_probes() {
+        assert_eq!(
+            Err(()),
+            reader(LogNamespace::Legacy).read(&[0; 64]).map(|_| ())
+        );
+    }
+}
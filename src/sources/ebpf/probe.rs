@@ -0,0 +1,421 @@
+//! The probes of the source, which tell which tracepoint each program is attached to and how the
+//! samples it writes are turned into events.
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    format::{Format, FormatError},
+    program::{COMM_LENGTH, COMM_OFFSET, Condition, HEADER_SIZE, MAX_SAMPLE_SIZE, Program},
+};
+
+const IPPROTO_TCP: u32 = 6;
+const AF_INET: u16 = 2;
+const AF_INET6: u16 = 10;
+
+const TCP_ESTABLISHED: u32 = 1;
+const TCP_SYN_SENT: u32 = 2;
+const TCP_CLOSE: u32 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Probe {
+    ProcessExec,
+    ProcessExit,
+    TcpConnect,
+    TcpClose,
+}
+
+impl Probe {
+    pub(super) const ALL: [Self; 4] = [
+        Self::ProcessExec,
+        Self::ProcessExit,
+        Self::TcpConnect,
+        Self::TcpClose,
+    ];
+
+    pub(super) const fn name(self) -> &'static str {
+        match self {
+            Self::ProcessExec => "process_exec",
+            Self::ProcessExit => "process_exit",
+            Self::TcpConnect => "tcp_connect",
+            Self::TcpClose => "tcp_close",
+        }
+    }
+
+    /// The system and the name of the tracepoint the probe is attached to.
+    pub(super) const fn tracepoint(self) -> (&'static str, &'static str) {
+        match self {
+            Self::ProcessExec => ("sched", "sched_process_exec"),
+            Self::ProcessExit => ("sched", "sched_process_exit"),
+            Self::TcpConnect | Self::TcpClose => ("sock", "inet_sock_set_state"),
+        }
+    }
+
+    const fn id(self) -> u32 {
+        self as u32
+    }
+
+    fn from_id(id: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|probe| probe.id() == id)
+    }
+
+    /// Describes the program of the probe, given the format of its tracepoint on the running
+    /// kernel.
+    pub(super) fn program(self, format: &Format) -> Result<Program, FormatError> {
+        let (copy_length, conditions) = match self {
+            // The filename is stored after the fixed fields, so as much of the record as fits in
+            // a sample is copied.
+            Self::ProcessExec => {
+                format.field("filename")?;
+                format.field("pid")?;
+                (MAX_SAMPLE_SIZE - HEADER_SIZE, Vec::new())
+            }
+            Self::ProcessExit => (format.fixed_size(), Vec::new()),
+            Self::TcpConnect | Self::TcpClose => {
+                for name in [
+                    "oldstate", "sport", "dport", "family", "saddr", "daddr", "saddr_v6",
+                    "daddr_v6",
+                ] {
+                    format.field(name)?;
+                }
+                let new_state = if self == Self::TcpConnect {
+                    TCP_ESTABLISHED
+                } else {
+                    TCP_CLOSE
+                };
+                let conditions = vec![
+                    condition(format, "protocol", IPPROTO_TCP)?,
+                    condition(format, "newstate", new_state)?,
+                ];
+                (format.fixed_size(), conditions)
+            }
+        };
+        Ok(Program {
+            probe_id: self.id(),
+            copy_length,
+            conditions,
+        })
+    }
+}
+
+fn condition(format: &Format, name: &'static str, value: u32) -> Result<Condition, FormatError> {
+    let field = format.field(name)?;
+    Ok(Condition {
+        offset: field.offset,
+        size: field.size,
+        values: vec![value],
+    })
+}
+
+/// Returns the probe which wrote a sample.
+pub(super) fn sample_probe(sample: &[u8]) -> Option<Probe> {
+    Probe::from_id(read_u32(sample, 0)?)
+}
+
+/// A probe loaded for the running kernel, which knows where the fields of its samples are.
+#[derive(Clone, Debug)]
+pub(super) struct LoadedProbe {
+    pub(super) probe: Probe,
+    pub(super) format: Format,
+}
+
+impl LoadedProbe {
+    /// Parses a sample written by the program of the probe, returning `None` if it's truncated or
+    /// if it isn't reported, such as the exits of threads other than the main thread of a
+    /// process.
+    pub(super) fn parse(&self, sample: &[u8]) -> Option<ObjectMap> {
+        let pid_tgid = read_u64(sample, 8)?;
+        let uid_gid = read_u64(sample, 16)?;
+        let comm = sample.get(COMM_OFFSET..COMM_OFFSET + COMM_LENGTH)?;
+        let (pid, tid) = ((pid_tgid >> 32) as u32, pid_tgid as u32);
+
+        let mut fields = ObjectMap::new();
+        fields.insert("probe".into(), self.probe.name().into());
+        fields.insert("pid".into(), integer(pid));
+        fields.insert("tid".into(), integer(tid));
+        fields.insert("uid".into(), integer(uid_gid as u32));
+        fields.insert("gid".into(), integer((uid_gid >> 32) as u32));
+        fields.insert("command".into(), c_string(comm).into());
+
+        let record = sample.get(HEADER_SIZE..)?;
+        match self.probe {
+            Probe::ProcessExec => {
+                fields.insert("filename".into(), self.data_loc(record, "filename")?.into());
+            }
+            // The threads of a process exit one by one, and the process exits with its main
+            // thread.
+            Probe::ProcessExit if tid != pid => return None,
+            Probe::ProcessExit => (),
+            Probe::TcpConnect | Probe::TcpClose => {
+                let old_state = self.read(record, "oldstate")?;
+                let (source, destination) = match self.read(record, "family")? as u16 {
+                    AF_INET => (
+                        IpAddr::from(Ipv4Addr::from(self.array::<4>(record, "saddr")?)),
+                        IpAddr::from(Ipv4Addr::from(self.array::<4>(record, "daddr")?)),
+                    ),
+                    AF_INET6 => (
+                        IpAddr::from(Ipv6Addr::from(self.array::<16>(record, "saddr_v6")?)),
+                        IpAddr::from(Ipv6Addr::from(self.array::<16>(record, "daddr_v6")?)),
+                    ),
+                    _ => return None,
+                };
+                fields.insert(
+                    "family".into(),
+                    if source.is_ipv4() { "ipv4" } else { "ipv6" }.into(),
+                );
+                fields.insert("source_address".into(), source.to_string().into());
+                fields.insert("source_port".into(), integer(self.read(record, "sport")?));
+                fields.insert("destination_address".into(), destination.to_string().into());
+                fields.insert(
+                    "destination_port".into(),
+                    integer(self.read(record, "dport")?),
+                );
+                if self.probe == Probe::TcpConnect {
+                    // Connections opened by the host are established after sending a SYN, while
+                    // accepted connections are established after receiving one.
+                    let direction = if old_state == TCP_SYN_SENT {
+                        "outbound"
+                    } else {
+                        "inbound"
+                    };
+                    fields.insert("direction".into(), direction.into());
+                } else {
+                    fields.insert("previous_state".into(), tcp_state(old_state).into());
+                }
+            }
+        }
+        Some(fields)
+    }
+
+    /// Reads an integer field, of up to four bytes.
+    fn read(&self, record: &[u8], name: &'static str) -> Option<u32> {
+        let field = self.format.field(name).ok()?;
+        let bytes = record.get(field.offset..field.offset + field.size)?;
+        match *bytes {
+            [a] => Some(a.into()),
+            [a, b] => Some(u16::from_ne_bytes([a, b]).into()),
+            [a, b, c, d] => Some(u32::from_ne_bytes([a, b, c, d])),
+            _ => None,
+        }
+    }
+
+    fn array<const N: usize>(&self, record: &[u8], name: &'static str) -> Option<[u8; N]> {
+        let field = self.format.field(name).ok()?;
+        record.get(field.offset..field.offset + N)?.try_into().ok()
+    }
+
+    /// Reads a `__data_loc` string, whose field has the length of the string in its high half
+    /// and its offset in the record in its low half. Strings longer than the sample are
+    /// truncated.
+    fn data_loc(&self, record: &[u8], name: &'static str) -> Option<String> {
+        let location = self.read(record, name)?;
+        let start = (location & 0xffff) as usize;
+        let end = (start + (location >> 16) as usize).min(record.len());
+        Some(c_string(record.get(start..end)?))
+    }
+}
+
+fn integer(value: u32) -> Value {
+    Value::from(i64::from(value))
+}
+
+/// Decodes a string up to its null terminator, if any.
+fn c_string(bytes: &[u8]) -> String {
+    let length = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..length]).into_owned()
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+const fn tcp_state(state: u32) -> &'static str {
+    match state {
+        1 => "established",
+        2 => "syn_sent",
+        3 => "syn_recv",
+        4 => "fin_wait1",
+        5 => "fin_wait2",
+        6 => "time_wait",
+        7 => "close",
+        8 => "close_wait",
+        9 => "last_ack",
+        10 => "listen",
+        11 => "closing",
+        12 => "new_syn_recv",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::ebpf::format;
+
+    const SCHED_PROCESS_EXEC: &str = "ID: 365
+format:
+\tfield:unsigned short common_type;\toffset:0;\tsize:2;\tsigned:0;
+\tfield:int common_pid;\toffset:4;\tsize:4;\tsigned:1;
+
+\tfield:__data_loc char[] filename;\toffset:8;\tsize:4;\tsigned:0;
+\tfield:pid_t pid;\toffset:12;\tsize:4;\tsigned:1;
+\tfield:pid_t old_pid;\toffset:16;\tsize:4;\tsigned:1;
+";
+
+    const INET_SOCK_SET_STATE: &str = "ID: 1512
+format:
+\tfield:unsigned short common_type;\toffset:0;\tsize:2;\tsigned:0;
+\tfield:int common_pid;\toffset:4;\tsize:4;\tsigned:1;
+
+\tfield:const void * skaddr;\toffset:8;\tsize:8;\tsigned:0;
+\tfield:int oldstate;\toffset:16;\tsize:4;\tsigned:1;
+\tfield:int newstate;\toffset:20;\tsize:4;\tsigned:1;
+\tfield:__u16 sport;\toffset:24;\tsize:2;\tsigned:0;
+\tfield:__u16 dport;\toffset:26;\tsize:2;\tsigned:0;
+\tfield:__u16 family;\toffset:28;\tsize:2;\tsigned:0;
+\tfield:__u16 protocol;\toffset:30;\tsize:2;\tsigned:0;
+\tfield:__u8 saddr[4];\toffset:32;\tsize:4;\tsigned:0;
+\tfield:__u8 daddr[4];\toffset:36;\tsize:4;\tsigned:0;
+\tfield:__u8 saddr_v6[16];\toffset:40;\tsize:16;\tsigned:0;
+\tfield:__u8 daddr_v6[16];\toffset:56;\tsize:16;\tsigned:0;
+";
+
+    /// Builds a sample as written by the program of the probe.
+    fn sample(
+        probe: Probe,
+        program: &Program,
+        pid: u32,
+        tid: u32,
+        record: &[(usize, &[u8])],
+    ) -> Vec<u8> {
+        let mut sample = vec![0; program.sample_size()];
+        sample[..4].copy_from_slice(&probe.id().to_ne_bytes());
+        sample[8..16].copy_from_slice(&((u64::from(pid) << 32) | u64::from(tid)).to_ne_bytes());
+        sample[16..24].copy_from_slice(&((1000_u64 << 32) | 1001).to_ne_bytes());
+        sample[COMM_OFFSET..COMM_OFFSET + 5].copy_from_slice(b"bash\0");
+        for (offset, bytes) in record {
+            let start = HEADER_SIZE + offset;
+            sample[start..start + bytes.len()].copy_from_slice(bytes);
+        }
+        sample
+    }
+
+    #[test]
+    fn parses_process_samples() {
+        let format = format::parse(SCHED_PROCESS_EXEC).unwrap();
+        let program = Probe::ProcessExec.program(&format).unwrap();
+        assert_eq!(MAX_SAMPLE_SIZE, program.sample_size());
+        assert!(program.conditions.is_empty());
+
+        let exec = LoadedProbe {
+            probe: Probe::ProcessExec,
+            format: format.clone(),
+        };
+        let location = (10_u32 << 16) | 20;
+        let sample = sample(
+            Probe::ProcessExec,
+            &program,
+            4321,
+            4321,
+            &[(8, &location.to_ne_bytes()), (20, b"/bin/true\0")],
+        );
+        assert_eq!(Some(Probe::ProcessExec), sample_probe(&sample));
+        let fields = exec.parse(&sample).unwrap();
+        assert_eq!(fields["probe"], "process_exec".into());
+        assert_eq!(fields["pid"], 4321.into());
+        assert_eq!(fields["uid"], 1001.into());
+        assert_eq!(fields["gid"], 1000.into());
+        assert_eq!(fields["command"], "bash".into());
+        assert_eq!(fields["filename"], "/bin/true".into());
+
+        // Only the exit of the main thread of a process is reported.
+        let exit = LoadedProbe {
+            probe: Probe::ProcessExit,
+            format,
+        };
+        let program = Probe::ProcessExit.program(&exit.format).unwrap();
+        let sample = self::sample(Probe::ProcessExit, &program, 4321, 4322, &[]);
+        assert_eq!(None, exit.parse(&sample));
+        let sample = self::sample(Probe::ProcessExit, &program, 4321, 4321, &[]);
+        assert_eq!(exit.parse(&sample).unwrap()["tid"], 4321.into());
+    }
+
+    #[test]
+    fn parses_tcp_samples() {
+        let format = format::parse(INET_SOCK_SET_STATE).unwrap();
+        let program = Probe::TcpConnect.program(&format).unwrap();
+        assert_eq!(
+            vec![
+                Condition {
+                    offset: 30,
+                    size: 2,
+                    values: vec![IPPROTO_TCP],
+                },
+                Condition {
+                    offset: 20,
+                    size: 4,
+                    values: vec![TCP_ESTABLISHED],
+                },
+            ],
+            program.conditions
+        );
+
+        let connect = LoadedProbe {
+            probe: Probe::TcpConnect,
+            format: format.clone(),
+        };
+        let sample = sample(
+            Probe::TcpConnect,
+            &program,
+            4321,
+            4321,
+            &[
+                (16, &TCP_SYN_SENT.to_ne_bytes()),
+                (24, &51234_u16.to_ne_bytes()),
+                (26, &443_u16.to_ne_bytes()),
+                (28, &AF_INET.to_ne_bytes()),
+                (32, &[10, 0, 0, 1]),
+                (36, &[93, 184, 216, 34]),
+            ],
+        );
+        let fields = connect.parse(&sample).unwrap();
+        assert_eq!(fields["family"], "ipv4".into());
+        assert_eq!(fields["source_address"], "10.0.0.1".into());
+        assert_eq!(fields["source_port"], 51234.into());
+        assert_eq!(fields["destination_address"], "93.184.216.34".into());
+        assert_eq!(fields["destination_port"], 443.into());
+        assert_eq!(fields["direction"], "outbound".into());
+
+        let close = LoadedProbe {
+            probe: Probe::TcpClose,
+            format,
+        };
+        let program = Probe::TcpClose.program(&close.format).unwrap();
+        let mut destination = [0; 16];
+        destination[15] = 1;
+        let sample = self::sample(
+            Probe::TcpClose,
+            &program,
+            4321,
+            4321,
+            &[
+                (16, &9_u32.to_ne_bytes()),
+                (28, &AF_INET6.to_ne_bytes()),
+                (56, &destination),
+            ],
+        );
+        let fields = close.parse(&sample).unwrap();
+        assert_eq!(fields["family"], "ipv6".into());
+        assert_eq!(fields["destination_address"], "::1".into());
+        assert_eq!(fields["previous_state"], "last_ack".into());
+        assert!(!fields.contains_key("direction"));
+    }
+}
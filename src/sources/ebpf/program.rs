@@ -0,0 +1,251 @@
+//! Assembly of the BPF programs attached to tracepoints.
+//!
+//! Every program copies the record of its tracepoint, along with the process, user and command of
+//! the current task, to a perf event array, so the programs only differ by the offsets of the
+//! fields they read, which are taken from the format of the tracepoint when they are loaded.
+
+/// The size of the header written by programs before the record of the tracepoint.
+pub(super) const HEADER_SIZE: usize = 40;
+
+/// The offset of the command of the current task in the header.
+pub(super) const COMM_OFFSET: usize = 24;
+
+/// The length of the command of a task, including its null terminator.
+pub(super) const COMM_LENGTH: usize = 16;
+
+/// The maximum size of a sample, which must fit in the stack of the program.
+pub(super) const MAX_SAMPLE_SIZE: usize = 512;
+
+/// The offset of the first field of a record that programs can read, after the common fields,
+/// which the verifier doesn't let tracepoint programs access.
+pub(super) const FIRST_FIELD_OFFSET: usize = 8;
+
+const BPF_FUNC_GET_CURRENT_PID_TGID: i32 = 14;
+const BPF_FUNC_GET_CURRENT_UID_GID: i32 = 15;
+const BPF_FUNC_GET_CURRENT_COMM: i32 = 16;
+const BPF_FUNC_PERF_EVENT_OUTPUT: i32 = 25;
+
+/// Writes the sample to the buffer of the CPU the program runs on.
+const BPF_F_CURRENT_CPU: i64 = 0xffff_ffff;
+
+/// Marks the immediate of a 64 bit load as the file descriptor of a map.
+const BPF_PSEUDO_MAP_FD: u8 = 1;
+
+const R0: u8 = 0;
+const R1: u8 = 1;
+const R2: u8 = 2;
+const R3: u8 = 3;
+const R4: u8 = 4;
+const R5: u8 = 5;
+const R6: u8 = 6;
+const R10: u8 = 10;
+
+// The opcodes used by the programs, which combine an instruction class, an operation and a size
+// or source.
+const MOV64_IMM: u8 = 0xb7;
+const MOV64_REG: u8 = 0xbf;
+const ADD64_IMM: u8 = 0x07;
+const LDX_H: u8 = 0x69;
+const LDX_W: u8 = 0x61;
+const LDX_DW: u8 = 0x79;
+const STX_DW: u8 = 0x7b;
+const ST_DW: u8 = 0x7a;
+const ST_W: u8 = 0x62;
+const LD_IMM64: u8 = 0x18;
+const JEQ_IMM: u8 = 0x15;
+const CALL: u8 = 0x85;
+const EXIT: u8 = 0x95;
+
+/// An instruction of a BPF program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Instruction {
+    code: u8,
+    dst: u8,
+    src: u8,
+    offset: i16,
+    imm: i32,
+}
+
+impl Instruction {
+    const fn new(code: u8, dst: u8, src: u8, offset: i16, imm: i32) -> Self {
+        Self {
+            code,
+            dst,
+            src,
+            offset,
+            imm,
+        }
+    }
+
+    /// Encodes the instruction in the byte order of the host, which is expected by the kernel.
+    pub(super) fn encode(self) -> [u8; 8] {
+        let mut bytes = [0; 8];
+        bytes[0] = self.code;
+        bytes[1] = if cfg!(target_endian = "little") {
+            self.src << 4 | self.dst
+        } else {
+            self.dst << 4 | self.src
+        };
+        bytes[2..4].copy_from_slice(&self.offset.to_ne_bytes());
+        bytes[4..8].copy_from_slice(&self.imm.to_ne_bytes());
+        bytes
+    }
+}
+
+/// A field of a tracepoint which must have one of the given values for a sample to be written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Condition {
+    pub(super) offset: usize,
+    /// The size of the field, which is either 2 or 4 bytes.
+    pub(super) size: usize,
+    pub(super) values: Vec<u32>,
+}
+
+/// The description of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Program {
+    /// The identifier written at the start of each sample, telling which probe wrote it.
+    pub(super) probe_id: u32,
+    /// The number of bytes copied from the start of the record of the tracepoint, which is
+    /// rounded up to a multiple of eight. The common fields at the start of the record are
+    /// written as zeros, so that the fields keep their offsets in the sample.
+    pub(super) copy_length: usize,
+    pub(super) conditions: Vec<Condition>,
+}
+
+impl Program {
+    /// The size of the samples written by the program.
+    pub(super) const fn sample_size(&self) -> usize {
+        HEADER_SIZE + self.copy_length.next_multiple_of(8)
+    }
+
+    /// Assembles the program, which writes to the perf event array with the given file
+    /// descriptor.
+    pub(super) fn assemble(&self, map_fd: i32) -> Vec<Instruction> {
+        let size = self.sample_size();
+        assert!(
+            size <= MAX_SAMPLE_SIZE,
+            "sample of {size} bytes is too large"
+        );
+        // The sample is written at the bottom of the stack.
+        let sample = -(size as i16);
+
+        let mut program = vec![Instruction::new(MOV64_REG, R6, R1, 0, 0)];
+
+        for condition in &self.conditions {
+            let load = if condition.size == 2 { LDX_H } else { LDX_W };
+            program.push(Instruction::new(load, R0, R6, condition.offset as i16, 0));
+            // Each comparison jumps over the remaining comparisons and the exit, to the next
+            // condition.
+            let count = condition.values.len();
+            for (index, value) in condition.values.iter().enumerate() {
+                let skip = (count - index - 1) + 2;
+                program.push(Instruction::new(JEQ_IMM, R0, 0, skip as i16, *value as i32));
+            }
+            program.push(Instruction::new(MOV64_IMM, R0, 0, 0, 0));
+            program.push(Instruction::new(EXIT, 0, 0, 0, 0));
+        }
+
+        program.extend([
+            Instruction::new(ST_W, R10, 0, sample, self.probe_id as i32),
+            Instruction::new(ST_W, R10, 0, sample + 4, 0),
+            Instruction::new(CALL, 0, 0, 0, BPF_FUNC_GET_CURRENT_PID_TGID),
+            Instruction::new(STX_DW, R10, R0, sample + 8, 0),
+            Instruction::new(CALL, 0, 0, 0, BPF_FUNC_GET_CURRENT_UID_GID),
+            Instruction::new(STX_DW, R10, R0, sample + 16, 0),
+            Instruction::new(MOV64_REG, R1, R10, 0, 0),
+            Instruction::new(ADD64_IMM, R1, 0, 0, (sample + COMM_OFFSET as i16).into()),
+            Instruction::new(MOV64_IMM, R2, 0, 0, COMM_LENGTH as i32),
+            Instruction::new(CALL, 0, 0, 0, BPF_FUNC_GET_CURRENT_COMM),
+        ]);
+
+        program.push(Instruction::new(
+            ST_DW,
+            R10,
+            0,
+            sample + HEADER_SIZE as i16,
+            0,
+        ));
+        for offset in (FIRST_FIELD_OFFSET..self.copy_length.next_multiple_of(8)).step_by(8) {
+            program.push(Instruction::new(LDX_DW, R0, R6, offset as i16, 0));
+            program.push(Instruction::new(
+                STX_DW,
+                R10,
+                R0,
+                sample + (HEADER_SIZE + offset) as i16,
+                0,
+            ));
+        }
+
+        program.extend([
+            Instruction::new(MOV64_REG, R1, R6, 0, 0),
+            Instruction::new(LD_IMM64, R2, BPF_PSEUDO_MAP_FD, 0, map_fd),
+            Instruction::new(0, 0, 0, 0, 0),
+            Instruction::new(LD_IMM64, R3, 0, 0, BPF_F_CURRENT_CPU as i32),
+            Instruction::new(0, 0, 0, 0, (BPF_F_CURRENT_CPU >> 32) as i32),
+            Instruction::new(MOV64_REG, R4, R10, 0, 0),
+            Instruction::new(ADD64_IMM, R4, 0, 0, sample.into()),
+            Instruction::new(MOV64_IMM, R5, 0, 0, size as i32),
+            Instruction::new(CALL, 0, 0, 0, BPF_FUNC_PERF_EVENT_OUTPUT),
+            Instruction::new(MOV64_IMM, R0, 0, 0, 0),
+            Instruction::new(EXIT, 0, 0, 0, 0),
+        ]);
+        program
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_instructions() {
+        let instruction = Instruction::new(STX_DW, R10, R0, -8, 0);
+        let mut expected = [0x7b, 0x0a, 0, 0, 0, 0, 0, 0];
+        expected[2..4].copy_from_slice(&(-8_i16).to_ne_bytes());
+        if cfg!(target_endian = "big") {
+            expected[1] = 0xa0;
+        }
+        assert_eq!(expected, instruction.encode());
+    }
+
+    #[test]
+    fn assembles_programs() {
+        let program = Program {
+            probe_id: 2,
+            copy_length: 20,
+            conditions: vec![Condition {
+                offset: 20,
+                size: 4,
+                values: vec![2, 7],
+            }],
+        };
+        assert_eq!(64, program.sample_size());
+
+        let instructions = program.assemble(3);
+        // The comparisons skip to the instruction after the exit.
+        assert_eq!(Instruction::new(LDX_W, R0, R6, 20, 0), instructions[1]);
+        assert_eq!(Instruction::new(JEQ_IMM, R0, 0, 3, 2), instructions[2]);
+        assert_eq!(Instruction::new(JEQ_IMM, R0, 0, 2, 7), instructions[3]);
+        assert_eq!(Instruction::new(EXIT, 0, 0, 0, 0), instructions[5]);
+        assert_eq!(Instruction::new(ST_W, R10, 0, -64, 2), instructions[6]);
+        // The common fields are zeroed, and the two other words of the record are copied after
+        // the header.
+        assert!(instructions.contains(&Instruction::new(ST_DW, R10, 0, -24, 0)));
+        let copies = instructions
+            .iter()
+            .filter(|instruction| instruction.code == LDX_DW)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                &Instruction::new(LDX_DW, R0, R6, 8, 0),
+                &Instruction::new(LDX_DW, R0, R6, 16, 0)
+            ],
+            copies
+        );
+        assert_eq!(
+            Some(&Instruction::new(EXIT, 0, 0, 0, 0)),
+            instructions.last()
+        );
+    }
+}
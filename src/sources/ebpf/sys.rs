@@ -0,0 +1,375 @@
+//! Wrappers of the `bpf` and `perf_event_open` system calls, and of the perf ring buffers the
+//! programs write samples to.
+use std::{
+    ffi::CString,
+    io, mem,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    ptr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::program::Instruction;
+
+const BPF_MAP_CREATE: libc::c_long = 0;
+const BPF_MAP_UPDATE_ELEM: libc::c_long = 2;
+const BPF_PROG_LOAD: libc::c_long = 5;
+const BPF_MAP_TYPE_PERF_EVENT_ARRAY: u32 = 4;
+const BPF_PROG_TYPE_TRACEPOINT: u32 = 5;
+
+const PERF_TYPE_SOFTWARE: u32 = 1;
+const PERF_TYPE_TRACEPOINT: u32 = 2;
+const PERF_COUNT_SW_BPF_OUTPUT: u64 = 10;
+const PERF_SAMPLE_RAW: u64 = 1 << 10;
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 8;
+const PERF_RECORD_LOST: u32 = 2;
+const PERF_RECORD_SAMPLE: u32 = 9;
+/// The size of `struct perf_event_header`, which has the type and size of a record.
+const RECORD_HEADER_SIZE: usize = 8;
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+const PERF_EVENT_IOC_SET_BPF: libc::c_ulong = 0x4004_2408;
+
+/// The size of the verifier log kept when a program is rejected.
+const LOG_SIZE: usize = 64 * 1024;
+
+#[repr(C)]
+#[derive(Default)]
+struct MapCreateAttr {
+    map_type: u32,
+    key_size: u32,
+    value_size: u32,
+    max_entries: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct MapUpdateAttr {
+    map_fd: u32,
+    _padding: u32,
+    key: u64,
+    value: u64,
+    flags: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct ProgLoadAttr {
+    prog_type: u32,
+    insn_cnt: u32,
+    insns: u64,
+    license: u64,
+    log_level: u32,
+    log_size: u32,
+    log_buf: u64,
+}
+
+/// The first version of `struct perf_event_attr`, which is accepted by every kernel.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+/// The header of the first page of a perf ring buffer, up to the positions of its data.
+#[repr(C)]
+struct PerfEventMmapPage {
+    _reserved: [u8; 1024],
+    data_head: AtomicU64,
+    data_tail: AtomicU64,
+}
+
+fn bpf<T>(command: libc::c_long, attr: &mut T) -> io::Result<libc::c_long> {
+    // SAFETY: the attributes are a `union bpf_attr` member of the given size, which the kernel
+    // zero-extends.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            command,
+            attr as *mut T,
+            mem::size_of::<T>() as libc::c_uint,
+        )
+    };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Creates a perf event array, with an entry for each CPU.
+pub(super) fn create_perf_event_array(max_entries: u32) -> io::Result<OwnedFd> {
+    let mut attr = MapCreateAttr {
+        map_type: BPF_MAP_TYPE_PERF_EVENT_ARRAY,
+        key_size: 4,
+        value_size: 4,
+        max_entries,
+    };
+    let fd = bpf(BPF_MAP_CREATE, &mut attr)?;
+    // SAFETY: the command returns a new file descriptor.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
+
+/// Sets the perf event that programs write to on the given CPU.
+pub(super) fn set_perf_event(map: &OwnedFd, cpu: u32, event: &OwnedFd) -> io::Result<()> {
+    let value = event.as_raw_fd() as u32;
+    let mut attr = MapUpdateAttr {
+        map_fd: map.as_raw_fd() as u32,
+        key: ptr::from_ref(&cpu) as u64,
+        value: ptr::from_ref(&value) as u64,
+        ..Default::default()
+    };
+    bpf(BPF_MAP_UPDATE_ELEM, &mut attr).map(|_| ())
+}
+
+/// Loads a tracepoint program, returning the log of the verifier if it is rejected.
+pub(super) fn load_program(instructions: &[Instruction]) -> Result<OwnedFd, (io::Error, String)> {
+    let code = instructions
+        .iter()
+        .flat_map(|instruction| instruction.encode())
+        .collect::<Vec<_>>();
+    // Programs which write to perf event arrays must be GPL compatible.
+    let license = CString::new("GPL").expect("license has no null byte");
+    let mut log = vec![0_u8; LOG_SIZE];
+    let mut attr = ProgLoadAttr {
+        prog_type: BPF_PROG_TYPE_TRACEPOINT,
+        insn_cnt: instructions.len() as u32,
+        insns: code.as_ptr() as u64,
+        license: license.as_ptr() as u64,
+        log_level: 1,
+        log_size: LOG_SIZE as u32,
+        log_buf: log.as_mut_ptr() as u64,
+    };
+    match bpf(BPF_PROG_LOAD, &mut attr) {
+        // SAFETY: the command returns a new file descriptor.
+        Ok(fd) => Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) }),
+        Err(error) => {
+            let length = log.iter().position(|&c| c == 0).unwrap_or(log.len());
+            Err((error, String::from_utf8_lossy(&log[..length]).into_owned()))
+        }
+    }
+}
+
+fn perf_event_open(attr: &mut PerfEventAttr, pid: i32, cpu: i32) -> io::Result<OwnedFd> {
+    attr.size = mem::size_of::<PerfEventAttr>() as u32;
+    // SAFETY: the attributes have the size they are declared with.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            attr as *mut PerfEventAttr,
+            pid,
+            cpu,
+            -1,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: the system call returned a new file descriptor.
+    Ok(unsafe { OwnedFd::from_raw_fd(result as RawFd) })
+}
+
+fn ioctl(fd: &OwnedFd, request: libc::c_ulong, argument: libc::c_int) -> io::Result<()> {
+    // SAFETY: the requests used take an integer argument.
+    if unsafe { libc::ioctl(fd.as_raw_fd(), request as _, argument) } < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Attaches a program to the tracepoint with the given ID, returning the perf event which keeps it
+/// attached until it is closed.
+pub(super) fn attach_tracepoint(id: u64, program: &OwnedFd) -> io::Result<OwnedFd> {
+    let mut attr = PerfEventAttr {
+        type_: PERF_TYPE_TRACEPOINT,
+        config: id,
+        ..Default::default()
+    };
+    // The program runs on every CPU, even though the event is opened on the first one.
+    let event = perf_event_open(&mut attr, -1, 0)?;
+    ioctl(&event, PERF_EVENT_IOC_SET_BPF, program.as_raw_fd())?;
+    ioctl(&event, PERF_EVENT_IOC_ENABLE, 0)?;
+    Ok(event)
+}
+
+/// Returns the CPUs which are online, from a list of ranges such as `0-3,6`.
+pub(super) fn online_cpus() -> io::Result<Vec<u32>> {
+    let content = std::fs::read_to_string("/sys/devices/system/cpu/online")?;
+    parse_cpu_list(content.trim())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid list of online CPUs"))
+}
+
+fn parse_cpu_list(list: &str) -> Option<Vec<u32>> {
+    let mut cpus = Vec::new();
+    for range in list.split(',') {
+        match range.split_once('-') {
+            Some((start, end)) => cpus.extend(start.parse::<u32>().ok()?..=end.parse().ok()?),
+            None => cpus.push(range.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+/// A record read from a perf ring buffer.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Record<'a> {
+    /// A sample written by a program.
+    Sample(&'a [u8]),
+    /// The number of samples which were lost because the buffer was full.
+    Lost(u64),
+}
+
+/// The ring buffer a CPU writes samples to.
+pub(super) struct PerfBuffer {
+    event: OwnedFd,
+    page: *mut PerfEventMmapPage,
+    data: *const u8,
+    data_size: usize,
+    mmap_size: usize,
+    /// A copy of the records which wrap around the end of the buffer.
+    scratch: Vec<u8>,
+}
+
+// SAFETY: the mapping is only accessed through `&mut self`.
+unsafe impl Send for PerfBuffer {}
+
+impl PerfBuffer {
+    /// Opens the buffer of a CPU, whose size is `pages`, a power of two, memory pages.
+    pub(super) fn open(cpu: u32, pages: usize) -> io::Result<Self> {
+        let mut attr = PerfEventAttr {
+            type_: PERF_TYPE_SOFTWARE,
+            config: PERF_COUNT_SW_BPF_OUTPUT,
+            sample_period: 1,
+            sample_type: PERF_SAMPLE_RAW,
+            wakeup_events: 1,
+            ..Default::default()
+        };
+        let event = perf_event_open(&mut attr, -1, cpu as i32)?;
+
+        // SAFETY: the page size is always available.
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let data_size = pages * page_size;
+        let mmap_size = data_size + page_size;
+        // SAFETY: the mapping of a perf event is a header page followed by the data pages.
+        let page = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                mmap_size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                event.as_raw_fd(),
+                0,
+            )
+        };
+        if page == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        ioctl(&event, PERF_EVENT_IOC_ENABLE, 0)?;
+
+        Ok(Self {
+            event,
+            page: page.cast(),
+            // SAFETY: the data pages follow the header page.
+            data: unsafe { page.cast::<u8>().add(page_size) },
+            data_size,
+            mmap_size,
+            scratch: Vec::new(),
+        })
+    }
+
+    pub(super) const fn event(&self) -> &OwnedFd {
+        &self.event
+    }
+
+    /// Reads the records written since the last read.
+    pub(super) fn read(&mut self, mut handle: impl FnMut(Record<'_>)) {
+        // SAFETY: the header page is mapped until the buffer is dropped.
+        let page = unsafe { &*self.page };
+        let head = page.data_head.load(Ordering::Acquire);
+        let mut tail = page.data_tail.load(Ordering::Relaxed);
+
+        while tail < head {
+            let mut header = [0; RECORD_HEADER_SIZE];
+            self.copy(tail, &mut header);
+            let type_ = u32::from_ne_bytes(header[..4].try_into().expect("length is checked"));
+            let size = u16::from_ne_bytes(header[6..8].try_into().expect("length is checked"));
+            let size = usize::from(size);
+            if size < RECORD_HEADER_SIZE {
+                // The buffer is corrupted, so the remaining records are skipped.
+                break;
+            }
+
+            let mut body = mem::take(&mut self.scratch);
+            body.resize(size - RECORD_HEADER_SIZE, 0);
+            self.copy(tail + RECORD_HEADER_SIZE as u64, &mut body);
+            match type_ {
+                // The sample is the size of the raw data, followed by the data.
+                PERF_RECORD_SAMPLE if body.len() >= 4 => {
+                    let length =
+                        u32::from_ne_bytes(body[..4].try_into().expect("length is checked"));
+                    let end = (4 + length as usize).min(body.len());
+                    handle(Record::Sample(&body[4..end]));
+                }
+                // The record has the ID of the event, followed by the number of lost samples.
+                PERF_RECORD_LOST if body.len() >= 16 => {
+                    let lost =
+                        u64::from_ne_bytes(body[8..16].try_into().expect("length is checked"));
+                    handle(Record::Lost(lost));
+                }
+                _ => (),
+            }
+            self.scratch = body;
+            tail += size as u64;
+        }
+        page.data_tail.store(tail, Ordering::Release);
+    }
+
+    /// Copies the data at the given position, which may wrap around the end of the buffer.
+    fn copy(&self, position: u64, output: &mut [u8]) {
+        let start = (position % self.data_size as u64) as usize;
+        let first = output.len().min(self.data_size - start);
+        // SAFETY: both parts are within the data pages.
+        unsafe {
+            ptr::copy_nonoverlapping(self.data.add(start), output.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(
+                self.data,
+                output[first..].as_mut_ptr(),
+                output.len() - first,
+            );
+        }
+    }
+}
+
+impl AsRawFd for PerfBuffer {
+    fn as_raw_fd(&self) -> RawFd {
+        self.event.as_raw_fd()
+    }
+}
+
+impl Drop for PerfBuffer {
+    fn drop(&mut self) {
+        // SAFETY: the mapping is only unmapped once.
+        unsafe { libc::munmap(self.page.cast(), self.mmap_size) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cpu_lists() {
+        assert_eq!(Some(vec![0]), parse_cpu_list("0"));
+        assert_eq!(Some(vec![0, 1, 2, 3, 6]), parse_cpu_list("0-3,6"));
+        assert_eq!(None, parse_cpu_list("0-"));
+    }
+}
@@ -1,7 +1,7 @@
 use std::{convert::TryInto, future, path::PathBuf, time::Duration};
 
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
 use regex::bytes::Regex;
 use serde_with::serde_as;
@@ -29,8 +29,8 @@ use super::util::{EncodingConfig, MultilineConfig};
 use crate::{
     SourceSender,
     config::{
-        DataType, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput,
-        log_schema,
+        ComponentKey, DataType, GlobalOptions, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext, SourceOutput, log_schema,
     },
     encoding_transcode::{Decoder, Encoder},
     event::{BatchNotifier, BatchStatus, LogEvent},
@@ -109,6 +109,18 @@ pub struct FileConfig {
     #[configurable(metadata(docs::human_name = "Ignore Older Files"))]
     pub ignore_older_secs: Option<u64>,
 
+    /// Consider files without a checkpoint that were last modified before this time as already
+    /// read.
+    ///
+    /// When Vector starts, such files are read from their end rather than as set by `read_from`.
+    /// This is useful to migrate Vector to another host or data directory without importing the
+    /// checkpoints of the previous instance: set it to the time the previous instance stopped, so
+    /// that files it had finished reading are not read again, and files written since are not
+    /// skipped.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "2025-01-01T00:00:00Z"))]
+    pub seed_checkpoints_before: Option<DateTime<Utc>>,
+
     /// The maximum size of a line before it is discarded.
     ///
     /// This protects against malformed lines or tailing incorrect files.
@@ -364,6 +376,7 @@ impl Default for FileConfig {
             ignore_checkpoints: None,
             read_from: default_read_from(),
             ignore_older_secs: None,
+            seed_checkpoints_before: None,
             max_line_bytes: default_max_line_bytes(),
             fingerprint: FingerprintConfig::default(),
             ignore_not_found: false,
@@ -478,6 +491,18 @@ impl SourceConfig for FileConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn checkpoints_dir(
+        &self,
+        globals: &GlobalOptions,
+        key: &ComponentKey,
+    ) -> Option<crate::Result<PathBuf>> {
+        Some(
+            globals
+                .resolve_and_validate_data_dir(self.data_dir.as_ref())
+                .map(|data_dir| data_dir.join(key.id())),
+        )
+    }
 }
 
 pub fn file_source(
@@ -540,6 +565,7 @@ pub fn file_source(
         ignore_checkpoints,
         read_from,
         ignore_before,
+        seed_checkpoints_before: config.seed_checkpoints_before,
         max_line_bytes: config.max_line_bytes,
         line_delimiter: line_delimiter_as_bytes,
         data_dir,
@@ -1758,6 +1784,50 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn file_start_position_seed_checkpoints() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            seed_checkpoints_before: Some(Utc::now() - chrono::Duration::seconds(5)),
+            ..test_default_file_config(&dir)
+        };
+
+        let before_path = dir.path().join("before");
+        let mut before_file = File::create(&before_path).unwrap();
+        let after_path = dir.path().join("after");
+        let mut after_file = File::create(&after_path).unwrap();
+
+        writeln!(&mut before_file, "first line").unwrap();
+        writeln!(&mut after_file, "_first line").unwrap();
+        before_file
+            .set_modified(SystemTime::now() - Duration::from_secs(10))
+            .unwrap();
+
+        let received = run_file_source(&config, false, NoAcks, LogNamespace::Legacy, async {
+            sleep_500_millis().await;
+            writeln!(&mut before_file, "second line").unwrap();
+            writeln!(&mut after_file, "_second line").unwrap();
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let lines_of = |name: &str| {
+            received
+                .iter()
+                .filter(|event| event.as_log()["file"].to_string_lossy().ends_with(name))
+                .map(|event| {
+                    event.as_log()[log_schema().message_key().unwrap().to_string()]
+                        .to_string_lossy()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines_of("before"), vec!["second line"]);
+        assert_eq!(lines_of("after"), vec!["_first line", "_second line"]);
+    }
+
     #[cfg(unix)] // this test uses unix-specific function `futimes` during test time
     #[tokio::test]
     async fn file_start_position_ignore_old_files() {
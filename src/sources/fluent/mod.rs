@@ -182,6 +182,16 @@ pub struct FluentTcpConfig {
     #[configurable(derived)]
     pub permit_origin: Option<IpAllowlistConfig>,
 
+    /// Whether the connections start with a [PROXY protocol][proxy_protocol] header.
+    ///
+    /// When the forwarders connect through a load balancer sending the version 1 or 2 of the
+    /// header, such as HAProxy, the address of each forwarder is read from it and used as the
+    /// `host` of the events. The connections without a valid header are closed.
+    ///
+    /// [proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+    #[serde(default)]
+    proxy_protocol: bool,
+
     /// The size of the receive buffer used for each connection.
     ///
     /// This generally should not need to be changed.
@@ -224,6 +234,7 @@ impl FluentTcpConfig {
             self.acknowledgements,
             self.connection_limit,
             self.permit_origin.clone().map(Into::into),
+            self.proxy_protocol,
             FluentConfig::NAME,
             log_namespace,
         )
@@ -279,6 +290,7 @@ impl GenerateConfig for FluentConfig {
                 address: SocketListenAddr::SocketAddr("0.0.0.0:24224".parse().unwrap()),
                 keepalive: None,
                 permit_origin: None,
+                proxy_protocol: false,
                 tls: None,
                 receive_buffer_bytes: None,
                 acknowledgements: Default::default(),
@@ -1094,6 +1106,7 @@ mod tests {
                 tls: None,
                 keepalive: None,
                 permit_origin: None,
+                proxy_protocol: false,
                 receive_buffer_bytes: None,
                 acknowledgements: true.into(),
                 connection_limit: None,
@@ -1161,6 +1174,7 @@ mod tests {
                 tls: None,
                 keepalive: None,
                 permit_origin: None,
+                proxy_protocol: false,
                 receive_buffer_bytes: None,
                 acknowledgements: false.into(),
                 connection_limit: None,
@@ -1219,6 +1233,7 @@ mod tests {
                 tls: None,
                 keepalive: None,
                 permit_origin: None,
+                proxy_protocol: false,
                 receive_buffer_bytes: None,
                 acknowledgements: false.into(),
                 connection_limit: None,
@@ -1443,6 +1458,7 @@ mod integration_tests {
                     tls: None,
                     keepalive: None,
                     permit_origin: None,
+                    proxy_protocol: false,
                     receive_buffer_bytes: None,
                     acknowledgements: false.into(),
                     connection_limit: None,
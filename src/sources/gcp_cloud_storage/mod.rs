@@ -0,0 +1,504 @@
+use std::{collections::HashMap, io};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use http::{Request, Response, StatusCode, header};
+use hyper::Body;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use snafu::Snafu;
+use vector_lib::{
+    codecs::{
+        NewlineDelimitedDecoderConfig,
+        decoding::{DeserializerConfig, FramingConfig, NewlineDelimitedDecoderOptions},
+    },
+    config::{LegacyKey, LogNamespace},
+    configurable::configurable_component,
+    internal_event::{BytesReceived, Protocol, Registered},
+    lookup::{owned_value_path, path},
+};
+use vrl::value::{Kind, kind::Collection};
+
+use super::util::{
+    MultilineConfig,
+    object_storage::{
+        Compression, SendObjectError, insert_object_timestamp, object_decoder, send_object_events,
+    },
+};
+use crate::{
+    SourceSender,
+    codecs::{Decoder, DecodingConfig},
+    config::{SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput},
+    event::{BatchStatus, LogEvent},
+    gcp::{GcpAuthConfig, GcpAuthenticator, Scope},
+    http::HttpClient,
+    internal_events::EventsReceived,
+    line_agg,
+    serde::{bool_or_struct, default_decoding},
+    tls::{TlsConfig, TlsSettings},
+};
+
+mod poll;
+mod pubsub;
+
+/// The characters of object names escaped in request paths, `/` is kept as is.
+const OBJECT_NAME_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+
+const METADATA_HEADER_PREFIX: &str = "x-goog-meta-";
+
+/// Strategies for consuming objects from GCP Cloud Storage.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+enum Strategy {
+    /// Consumes objects by processing [Pub/Sub notifications][pubsub_notifications] of the bucket.
+    ///
+    /// [pubsub_notifications]: https://cloud.google.com/storage/docs/pubsub-notifications
+    #[derivative(Default)]
+    Pubsub,
+
+    /// Consumes objects by periodically listing the bucket.
+    ///
+    /// This can be used for buckets where notifications can't be configured. Processed objects
+    /// are tracked in checkpoints persisted in the data directory.
+    Poll,
+}
+
+/// Configuration for the `gcp_cloud_storage` source.
+#[configurable_component(source("gcp_cloud_storage", "Collect logs from GCP Cloud Storage."))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GcsSourceConfig {
+    /// API endpoint for Google Cloud Storage.
+    #[configurable(metadata(docs::examples = "http://localhost:9000"))]
+    #[serde(default = "default_endpoint")]
+    #[derivative(Default(value = "default_endpoint()"))]
+    endpoint: String,
+
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+
+    /// The compression scheme used for decompressing objects retrieved from Cloud Storage.
+    compression: Compression,
+
+    /// The strategy to use to consume objects from Cloud Storage.
+    strategy: Strategy,
+
+    /// Configuration options for Pub/Sub notifications.
+    pubsub: Option<pubsub::Config>,
+
+    /// Configuration options for polling the bucket.
+    poll: Option<poll::Config>,
+
+    /// Multiline aggregation configuration.
+    ///
+    /// If not specified, multiline aggregation is disabled.
+    #[configurable(derived)]
+    multiline: Option<MultilineConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    #[configurable(derived)]
+    tls: Option<TlsConfig>,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing")]
+    #[derivative(Default(value = "default_framing()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+}
+
+fn default_endpoint() -> String {
+    "https://storage.googleapis.com".to_string()
+}
+
+const fn default_framing() -> FramingConfig {
+    FramingConfig::NewlineDelimited(NewlineDelimitedDecoderConfig {
+        newline_delimited: NewlineDelimitedDecoderOptions { max_length: None },
+    })
+}
+
+impl_generate_config_from_default!(GcsSourceConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_cloud_storage")]
+impl SourceConfig for GcsSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        let multiline: Option<line_agg::Config> = self
+            .multiline
+            .as_ref()
+            .map(|config| config.try_into())
+            .transpose()?;
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+
+        // Pub/Sub notifications are pulled with the same credentials as objects.
+        let scope = match self.strategy {
+            Strategy::Pubsub => Scope::CloudPlatform,
+            Strategy::Poll => Scope::DevStorageReadOnly,
+        };
+        let auth = self.auth.build(scope).await?;
+        auth.spawn_regenerate_token();
+        let tls = TlsSettings::from_options(self.tls.as_ref())?;
+        let client = GcsClient {
+            http: HttpClient::new(tls, &cx.proxy)?,
+            auth,
+            endpoint: self.endpoint.clone(),
+        };
+
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+        let process = ObjectProcess {
+            client,
+            compression: self.compression,
+            multiline,
+            decoder,
+            out: cx.out.clone(),
+            acknowledgements,
+            log_namespace,
+            bytes_received: register!(BytesReceived::from(Protocol::HTTP)),
+            events_received: register!(EventsReceived),
+        };
+
+        match self.strategy {
+            Strategy::Pubsub => {
+                let Some(ref pubsub) = self.pubsub else {
+                    return Err(BuildError::PubsubConfigMissing.into());
+                };
+                let subscriber = pubsub::Subscriber::new(pubsub.clone(), process);
+                Ok(Box::pin(subscriber.run(cx.shutdown)))
+            }
+            Strategy::Poll => {
+                let Some(ref poll) = self.poll else {
+                    return Err(BuildError::PollConfigMissing.into());
+                };
+                // Each source gets its own subdirectory, so that two sources polling the same
+                // bucket track the objects they processed separately.
+                let data_dir = cx
+                    .globals
+                    .resolve_and_make_data_subdir(poll.data_dir.as_ref(), cx.key.id())?;
+                let poller = poll::Poller::new(poll.clone(), data_dir, process).await?;
+                Ok(Box::pin(poller.run(cx.shutdown)))
+            }
+        }
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let mut schema_definition = self
+            .decoding
+            .schema_definition(log_namespace)
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!("bucket"))),
+                &owned_value_path!("bucket"),
+                Kind::bytes(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!("object"))),
+                &owned_value_path!("object"),
+                Kind::bytes(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                None,
+                &owned_value_path!("timestamp"),
+                Kind::timestamp(),
+                Some("timestamp"),
+            )
+            .with_standard_vector_source_metadata()
+            // for metadata that is added to the events dynamically from the metadata
+            .with_source_metadata(
+                Self::NAME,
+                None,
+                &owned_value_path!("metadata"),
+                Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                None,
+            );
+
+        // for metadata that is added to the events dynamically from the metadata
+        if log_namespace == LogNamespace::Legacy {
+            schema_definition = schema_definition.unknown_fields(Kind::bytes());
+        }
+
+        vec![SourceOutput::new_maybe_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Configuration for `pubsub` required when strategy=pubsub"))]
+    PubsubConfigMissing,
+    #[snafu(display("Configuration for `poll` required when strategy=poll"))]
+    PollConfigMissing,
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum ProcessingError {
+    #[snafu(display("Failed to fetch gs://{}/{}: {}", bucket, object, source))]
+    GetObject {
+        source: crate::Error,
+        bucket: String,
+        object: String,
+    },
+    #[snafu(display("Failed to process gs://{}/{}: {}", bucket, object, source))]
+    SendEvents {
+        source: SendObjectError,
+        bucket: String,
+        object: String,
+    },
+    #[snafu(display("Sink reported an error sending events for gs://{}/{}", bucket, object))]
+    ErrorAcknowledgement { bucket: String, object: String },
+}
+
+#[derive(Debug, Snafu)]
+enum GcsError {
+    #[snafu(display("Unexpected status: {}", status))]
+    UnexpectedStatus { status: StatusCode },
+}
+
+/// A client of the Cloud Storage JSON and XML APIs.
+#[derive(Clone)]
+struct GcsClient {
+    http: HttpClient,
+    auth: GcpAuthenticator,
+    endpoint: String,
+}
+
+impl GcsClient {
+    async fn send(&self, mut request: Request<Body>) -> crate::Result<Response<Body>> {
+        self.auth.apply(&mut request);
+        let response = self.http.send(request).await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(GcsError::UnexpectedStatus {
+                status: response.status(),
+            }
+            .into())
+        }
+    }
+
+    /// Fetches an object, in the given generation if any.
+    async fn get_object(
+        &self,
+        bucket: &str,
+        object: &str,
+        generation: Option<&str>,
+    ) -> crate::Result<Response<Body>> {
+        let mut uri = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            bucket,
+            utf8_percent_encode(object, OBJECT_NAME_ENCODE_SET)
+        );
+        if let Some(generation) = generation {
+            uri.push_str("?generation=");
+            uri.push_str(generation);
+        }
+
+        // Objects stored with `Content-Encoding: gzip` are served as is, and decompressed by the
+        // source, instead of being decompressed by Cloud Storage.
+        let request = Request::get(uri)
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())?;
+        self.send(request).await
+    }
+}
+
+/// Fetches objects, and sends their events downstream.
+#[derive(Clone)]
+struct ObjectProcess {
+    client: GcsClient,
+    compression: Compression,
+    multiline: Option<line_agg::Config>,
+    decoder: Decoder,
+
+    out: SourceSender,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+    bytes_received: Registered<BytesReceived>,
+    events_received: Registered<EventsReceived>,
+}
+
+impl ObjectProcess {
+    async fn run(
+        mut self,
+        bucket: String,
+        object: String,
+        generation: Option<String>,
+    ) -> Result<(), ProcessingError> {
+        let response = self
+            .client
+            .get_object(&bucket, &object, generation.as_deref())
+            .await
+            .map_err(|source| ProcessingError::GetObject {
+                source,
+                bucket: bucket.clone(),
+                object: object.clone(),
+            })?;
+
+        debug!(
+            message = "Got Cloud Storage object.",
+            bucket = bucket,
+            object = object,
+        );
+
+        let headers = response.headers();
+        let header_value = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        let content_encoding = header_value(header::CONTENT_ENCODING);
+        let content_type = header_value(header::CONTENT_TYPE);
+        let timestamp = header_value(header::LAST_MODIFIED)
+            .and_then(|value| DateTime::parse_from_rfc2822(&value).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc));
+        let metadata = headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let key = name.as_str().strip_prefix(METADATA_HEADER_PREFIX)?;
+                Some((key.to_owned(), value.to_str().ok()?.to_owned()))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let body = response.into_body().map_err(io::Error::other).boxed();
+        let object_reader = object_decoder(
+            self.compression,
+            &object,
+            content_encoding.as_deref(),
+            content_type.as_deref(),
+            body,
+        )
+        .await;
+
+        let log_namespace = self.log_namespace;
+        let receiver = send_object_events(
+            &mut self.out,
+            &self.decoder,
+            self.multiline.as_ref(),
+            object_reader,
+            self.acknowledgements,
+            &self.bytes_received,
+            &self.events_received,
+            |log| handle_single_log(log, log_namespace, &bucket, &object, &metadata, timestamp),
+        )
+        .await
+        .map_err(|source| ProcessingError::SendEvents {
+            source,
+            bucket: bucket.clone(),
+            object: object.clone(),
+        })?;
+
+        match receiver {
+            None => Ok(()),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => {
+                    debug!(
+                        message = "Cloud Storage object delivered.",
+                        bucket = bucket,
+                        object = object,
+                    );
+                    Ok(())
+                }
+                BatchStatus::Errored => {
+                    Err(ProcessingError::ErrorAcknowledgement { bucket, object })
+                }
+                BatchStatus::Rejected => {
+                    warn!(
+                        message = "Cloud Storage object was rejected. It won't be processed again.",
+                        bucket = bucket,
+                        object = object,
+                    );
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+fn handle_single_log(
+    log: &mut LogEvent,
+    log_namespace: LogNamespace,
+    bucket: &str,
+    object: &str,
+    metadata: &HashMap<String, String>,
+    timestamp: Option<DateTime<Utc>>,
+) {
+    log_namespace.insert_source_metadata(
+        GcsSourceConfig::NAME,
+        log,
+        Some(LegacyKey::Overwrite(path!("bucket"))),
+        path!("bucket"),
+        Bytes::from(bucket.as_bytes().to_vec()),
+    );
+    log_namespace.insert_source_metadata(
+        GcsSourceConfig::NAME,
+        log,
+        Some(LegacyKey::Overwrite(path!("object"))),
+        path!("object"),
+        Bytes::from(object.as_bytes().to_vec()),
+    );
+
+    for (key, value) in metadata {
+        log_namespace.insert_source_metadata(
+            GcsSourceConfig::NAME,
+            log,
+            Some(LegacyKey::Overwrite(path!(key))),
+            path!("metadata", key.as_str()),
+            value.clone(),
+        );
+    }
+
+    insert_object_timestamp(log, log_namespace, GcsSourceConfig::NAME, timestamp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GcsSourceConfig>();
+    }
+
+    #[test]
+    fn encodes_object_names() {
+        assert_eq!(
+            utf8_percent_encode("logs/2024 01/a+b?.log", OBJECT_NAME_ENCODE_SET).to_string(),
+            "logs/2024%2001/a%2Bb%3F.log"
+        );
+    }
+}
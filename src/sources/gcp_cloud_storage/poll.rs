@@ -0,0 +1,227 @@
+use std::{num::NonZeroUsize, path::PathBuf, time::Duration};
+
+use futures::stream::BoxStream;
+use http::Request;
+use hyper::Body;
+use serde::Deserialize;
+use serde_with::serde_as;
+use tokio::{select, time::MissedTickBehavior};
+use vector_lib::configurable::configurable_component;
+
+use super::{GcsClient, ObjectProcess};
+use crate::{
+    internal_events::ObjectStoragePollError,
+    shutdown::ShutdownSignal,
+    sources::util::object_storage::{CheckpointsError, ListedObject, ObjectPoller, paginate},
+};
+
+/// Polling configuration options.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The name of the bucket to consume objects from.
+    #[configurable(metadata(docs::examples = "my-bucket"))]
+    pub(super) bucket: String,
+
+    /// Only objects whose name starts with this prefix are consumed.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "logs/"))]
+    pub(super) prefix: Option<String>,
+
+    /// How often to look for new objects, in seconds.
+    #[serde(default = "default_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_interval_secs()"))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Polling Interval"))]
+    pub(super) interval_secs: Duration,
+
+    /// Number of objects to process concurrently.
+    ///
+    /// Defaults to the number of available CPUs on the system.
+    #[configurable(metadata(docs::type_unit = "tasks"))]
+    #[configurable(metadata(docs::examples = 5))]
+    pub(super) client_concurrency: Option<NonZeroUsize>,
+
+    /// The directory used to persist the checkpoints of the processed objects.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub(super) data_dir: Option<PathBuf>,
+}
+
+const fn default_interval_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+// https://cloud.google.com/storage/docs/json_api/v1/objects/list
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectList {
+    #[serde(default)]
+    items: Vec<ObjectResource>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjectResource {
+    name: String,
+    generation: Option<String>,
+    size: Option<String>,
+}
+
+/// Lists the objects of the bucket, under the prefix.
+struct BucketLister {
+    client: GcsClient,
+    bucket: String,
+    prefix: Option<String>,
+}
+
+impl BucketLister {
+    fn list(&self) -> BoxStream<'_, crate::Result<Vec<ListedObject>>> {
+        paginate(|page_token| self.list_page(page_token))
+    }
+
+    async fn list_page(
+        &self,
+        page_token: Option<String>,
+    ) -> crate::Result<(Vec<ListedObject>, Option<String>)> {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        query.append_pair("fields", "items(name,generation,size),nextPageToken");
+        if let Some(prefix) = &self.prefix {
+            query.append_pair("prefix", prefix);
+        }
+        if let Some(page_token) = &page_token {
+            query.append_pair("pageToken", page_token);
+        }
+        let uri = format!(
+            "{}/storage/v1/b/{}/o?{}",
+            self.client.endpoint,
+            self.bucket,
+            query.finish()
+        );
+
+        let response = self
+            .client
+            .send(Request::get(uri).body(Body::empty())?)
+            .await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let list: ObjectList = serde_json::from_slice(&body)?;
+
+        // The generation of an object changes when it is overwritten.
+        let objects = list
+            .items
+            .into_iter()
+            .map(|object| ListedObject {
+                key: object.name,
+                etag: object.generation,
+                size: object.size.and_then(|size| size.parse().ok()),
+            })
+            .collect();
+        Ok((objects, list.next_page_token))
+    }
+}
+
+pub(super) struct Poller {
+    interval: Duration,
+    lister: BucketLister,
+    poller: ObjectPoller,
+    process: ObjectProcess,
+}
+
+impl Poller {
+    pub(super) async fn new(
+        config: Config,
+        data_dir: PathBuf,
+        process: ObjectProcess,
+    ) -> Result<Self, CheckpointsError> {
+        let poller = ObjectPoller::new(
+            data_dir,
+            config
+                .client_concurrency
+                .map(|n| n.get())
+                .unwrap_or_else(crate::num_threads),
+        )
+        .await?;
+
+        Ok(Self {
+            interval: config.interval_secs,
+            lister: BucketLister {
+                client: process.client.clone(),
+                bucket: config.bucket,
+                prefix: config.prefix,
+            },
+            poller,
+            process,
+        })
+    }
+
+    pub(super) async fn run(mut self, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            select! {
+                biased;
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {}
+            }
+
+            let bucket = &self.lister.bucket;
+            let process = |object: ListedObject| {
+                self.process
+                    .clone()
+                    .run(bucket.clone(), object.key, object.etag)
+            };
+            if let Err(error) = self
+                .poller
+                .poll_listing(self.lister.list(), process, &shutdown)
+                .await
+            {
+                emit!(ObjectStoragePollError { error: &error });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_object_list() {
+        let list: ObjectList = serde_json::from_str(
+            r#"{
+                "kind": "storage#objects",
+                "items": [
+                    {"name": "logs/a.log", "generation": "1700000000000001", "size": "42"},
+                    {"name": "logs/b.log", "generation": "1700000000000002"}
+                ],
+                "nextPageToken": "token"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(list.next_page_token.as_deref(), Some("token"));
+        assert_eq!(list.items.len(), 2);
+        assert_eq!(list.items[0].name, "logs/a.log");
+        assert_eq!(list.items[0].size.as_deref(), Some("42"));
+        assert!(list.items[1].size.is_none());
+
+        let empty: ObjectList = serde_json::from_str(r#"{"kind": "storage#objects"}"#).unwrap();
+        assert!(empty.items.is_empty());
+        assert!(empty.next_page_token.is_none());
+    }
+}
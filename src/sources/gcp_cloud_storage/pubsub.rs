@@ -0,0 +1,296 @@
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
+
+use futures::{StreamExt, stream};
+use http::{Request, header};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tokio::select;
+use vector_lib::configurable::configurable_component;
+
+use super::{GcsClient, ObjectProcess};
+use crate::{
+    gcp::PUBSUB_URL,
+    internal_events::{
+        ObjectNotificationAcknowledgeError, ObjectNotificationReceiveError, ObjectProcessingError,
+    },
+    shutdown::ShutdownSignal,
+};
+
+/// The type of the notifications of objects being created or overwritten.
+const OBJECT_FINALIZE: &str = "OBJECT_FINALIZE";
+
+/// Pub/Sub notifications configuration options.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The project of the subscription.
+    #[configurable(metadata(docs::examples = "my-project"))]
+    pub(super) project: String,
+
+    /// The subscription receiving the notifications of the bucket.
+    ///
+    /// The acknowledgement deadline of the subscription must be longer than the time needed to
+    /// process an object, or notifications are delivered again while their object is processed.
+    #[configurable(metadata(docs::examples = "my-bucket-notifications"))]
+    pub(super) subscription: String,
+
+    /// The endpoint from which to pull notifications.
+    #[configurable(metadata(docs::examples = "https://us-central1-pubsub.googleapis.com"))]
+    #[serde(default = "default_endpoint")]
+    #[derivative(Default(value = "default_endpoint()"))]
+    pub(super) endpoint: String,
+
+    /// The maximum number of notifications to pull at once.
+    #[serde(default = "default_max_messages")]
+    #[derivative(Default(value = "default_max_messages()"))]
+    #[configurable(metadata(docs::type_unit = "messages"))]
+    pub(super) max_messages: NonZeroUsize,
+
+    /// Number of objects to process concurrently.
+    ///
+    /// Defaults to the number of available CPUs on the system.
+    #[configurable(metadata(docs::type_unit = "tasks"))]
+    #[configurable(metadata(docs::examples = 5))]
+    pub(super) client_concurrency: Option<NonZeroUsize>,
+
+    /// The amount of time, in seconds, to wait between retry attempts after an error.
+    #[serde(default = "default_retry_delay")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[derivative(Default(value = "default_retry_delay()"))]
+    #[configurable(metadata(docs::human_name = "Retry Delay"))]
+    pub(super) retry_delay_secs: Duration,
+}
+
+fn default_endpoint() -> String {
+    PUBSUB_URL.to_string()
+}
+
+const fn default_max_messages() -> NonZeroUsize {
+    NonZeroUsize::new(10).unwrap()
+}
+
+const fn default_retry_delay() -> Duration {
+    Duration::from_secs(1)
+}
+
+// https://cloud.google.com/pubsub/docs/reference/rest/v1/projects.subscriptions/pull
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequest {
+    max_messages: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullResponse {
+    #[serde(default)]
+    received_messages: Vec<ReceivedMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReceivedMessage {
+    ack_id: String,
+    message: PubsubMessage,
+}
+
+// https://cloud.google.com/storage/docs/pubsub-notifications#attributes
+#[derive(Debug, Deserialize)]
+struct PubsubMessage {
+    #[serde(default)]
+    attributes: HashMap<String, String>,
+}
+
+impl PubsubMessage {
+    /// The bucket, name and generation of the object created by the notified event, if any.
+    fn finalized_object(&self) -> Option<(&str, &str, Option<&str>)> {
+        if self.attributes.get("eventType").map(String::as_str) != Some(OBJECT_FINALIZE) {
+            return None;
+        }
+        Some((
+            self.attributes.get("bucketId").map(String::as_str)?,
+            self.attributes.get("objectId").map(String::as_str)?,
+            self.attributes.get("objectGeneration").map(String::as_str),
+        ))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AcknowledgeRequest<'a> {
+    ack_ids: Vec<&'a str>,
+}
+
+pub(super) struct Subscriber {
+    client: GcsClient,
+    subscription_url: String,
+    max_messages: usize,
+    client_concurrency: usize,
+    retry_delay: Duration,
+    process: ObjectProcess,
+}
+
+impl Subscriber {
+    pub(super) fn new(config: Config, process: ObjectProcess) -> Self {
+        Self {
+            client: process.client.clone(),
+            subscription_url: format!(
+                "{}/v1/projects/{}/subscriptions/{}",
+                config.endpoint, config.project, config.subscription
+            ),
+            max_messages: config.max_messages.get(),
+            client_concurrency: config
+                .client_concurrency
+                .map(|n| n.get())
+                .unwrap_or_else(crate::num_threads),
+            retry_delay: config.retry_delay_secs,
+            process,
+        }
+    }
+
+    pub(super) async fn run(self, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        loop {
+            let messages = select! {
+                biased;
+                _ = &mut shutdown => break,
+                result = self.pull() => result,
+            };
+
+            match messages {
+                Ok(messages) => {
+                    let ack_ids = self.process_messages(&messages).await;
+                    if !ack_ids.is_empty()
+                        && let Err(error) = self.acknowledge(ack_ids).await
+                    {
+                        emit!(ObjectNotificationAcknowledgeError { error: &error });
+                    }
+                }
+                Err(error) => {
+                    emit!(ObjectNotificationReceiveError { error: &error });
+                    select! {
+                        _ = &mut shutdown => break,
+                        _ = tokio::time::sleep(self.retry_delay) => {}
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn pull(&self) -> crate::Result<Vec<ReceivedMessage>> {
+        let body = serde_json::to_vec(&PullRequest {
+            max_messages: self.max_messages,
+        })?;
+        let request = Request::post(format!("{}:pull", self.subscription_url))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))?;
+
+        let response = self.client.send(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let response: PullResponse = serde_json::from_slice(&body)?;
+        Ok(response.received_messages)
+    }
+
+    /// Processes the objects of the notifications, returning the acknowledgement IDs of the
+    /// notifications that don't need to be delivered again.
+    ///
+    /// Notifications of other events than object creations are acknowledged and ignored.
+    async fn process_messages<'a>(&self, messages: &'a [ReceivedMessage]) -> Vec<&'a str> {
+        stream::iter(messages)
+            .map(|received| async move {
+                let Some((bucket, object, generation)) = received.message.finalized_object() else {
+                    return Some(received.ack_id.as_str());
+                };
+
+                let result = self
+                    .process
+                    .clone()
+                    .run(
+                        bucket.to_owned(),
+                        object.to_owned(),
+                        generation.map(ToOwned::to_owned),
+                    )
+                    .await;
+                match result {
+                    Ok(()) => Some(received.ack_id.as_str()),
+                    Err(error) => {
+                        // The notification is delivered again once its acknowledgement deadline
+                        // expires.
+                        emit!(ObjectProcessingError { error: &error });
+                        None
+                    }
+                }
+            })
+            .buffer_unordered(self.client_concurrency)
+            .filter_map(futures::future::ready)
+            .collect()
+            .await
+    }
+
+    async fn acknowledge(&self, ack_ids: Vec<&str>) -> crate::Result<()> {
+        let body = serde_json::to_vec(&AcknowledgeRequest { ack_ids })?;
+        let request = Request::post(format!("{}:acknowledge", self.subscription_url))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))?;
+        self.client.send(request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_notifications() {
+        let response: PullResponse = serde_json::from_str(
+            r#"{
+                "receivedMessages": [
+                    {
+                        "ackId": "ack-1",
+                        "message": {
+                            "data": "e30=",
+                            "attributes": {
+                                "eventType": "OBJECT_FINALIZE",
+                                "bucketId": "my-bucket",
+                                "objectId": "logs/a.log",
+                                "objectGeneration": "1700000000000001",
+                                "payloadFormat": "JSON_API_V1"
+                            },
+                            "messageId": "1"
+                        }
+                    },
+                    {
+                        "ackId": "ack-2",
+                        "message": {
+                            "attributes": {
+                                "eventType": "OBJECT_DELETE",
+                                "bucketId": "my-bucket",
+                                "objectId": "logs/b.log"
+                            },
+                            "messageId": "2"
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let messages = response.received_messages;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].ack_id, "ack-1");
+        assert_eq!(
+            messages[0].message.finalized_object(),
+            Some(("my-bucket", "logs/a.log", Some("1700000000000001")))
+        );
+        assert_eq!(messages[1].message.finalized_object(), None);
+
+        let empty: PullResponse = serde_json::from_str("{}").unwrap();
+        assert!(empty.received_messages.is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! The `grpc_server` source. See [GrpcServerConfig].
+use std::{fs, io, net::SocketAddr, path::PathBuf};
+
+use futures::TryFutureExt;
+use prost_reflect::{DescriptorError, DescriptorPool, DynamicMessage, MethodDescriptor};
+use snafu::{ResultExt, Snafu};
+use tonic::transport::server::Routes;
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    configurable::configurable_component,
+    lookup::owned_value_path,
+    schema::Definition,
+};
+use vrl::value::Kind;
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext, SourceOutput,
+    },
+    serde::bool_or_struct,
+    sources::{Source, util::grpc::run_grpc_server_with_routes},
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+mod service;
+
+use self::service::MethodService;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to read the descriptor set file {}: {}", path.display(), source))]
+    ReadDescriptorSet { path: PathBuf, source: io::Error },
+    #[snafu(display("Failed to decode the descriptor set file {}: {}", path.display(), source))]
+    DecodeDescriptorSet {
+        path: PathBuf,
+        source: DescriptorError,
+    },
+    #[snafu(display("The service {:?} isn't in the descriptor set", service))]
+    UnknownService { service: String },
+    #[snafu(display("The service {:?} has no method {:?}", service, method))]
+    UnknownMethod { service: String, method: String },
+    #[snafu(display("The method {:?} is server streaming, which isn't supported", method))]
+    ServerStreamingMethod { method: String },
+    #[snafu(display("The response isn't a valid {:?} message: {}", message, source))]
+    InvalidResponse {
+        message: String,
+        source: serde_json::Error,
+    },
+}
+
+/// Configuration for the `grpc_server` source.
+#[configurable_component(source(
+    "grpc_server",
+    "Receive the messages of the methods of any gRPC service, as described by its descriptor set."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcServerConfig {
+    /// The socket address to listen for connections on.
+    ///
+    /// It _must_ include a port.
+    #[configurable(metadata(docs::examples = "0.0.0.0:50051"))]
+    address: SocketAddr,
+
+    /// The path to the protobuf descriptor set file of the service.
+    ///
+    /// This file is the output of `protoc -I <include path> -o <desc output path> <proto>`. It must
+    /// include the imported files, with `--include_imports`, if the messages of the methods are
+    /// defined in other files.
+    #[configurable(metadata(docs::examples = "/etc/vector/protos/ingest.desc"))]
+    desc_file: PathBuf,
+
+    /// The fully qualified name of the service.
+    #[configurable(metadata(docs::examples = "package.Service"))]
+    service: String,
+
+    /// The names of the methods of the service to receive the messages of.
+    ///
+    /// The unary and client streaming methods are supported. If empty, all of the methods of the
+    /// service are served, and must be supported.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "Push"))]
+    methods: Vec<String>,
+
+    /// The response to each request, as the JSON mapping of the response message of the methods.
+    ///
+    /// The response is sent once the events of the request are delivered, if acknowledgements are
+    /// enabled. If not set, the response is the default message, with no fields set.
+    #[configurable(metadata(docs::examples = r#"{"accepted": true}"#))]
+    response: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+impl GenerateConfig for GrpcServerConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "0.0.0.0:50051"
+desc_file = "/etc/vector/protos/ingest.desc"
+service = "package.Service""#,
+        )
+        .unwrap()
+    }
+}
+
+impl GrpcServerConfig {
+    /// The descriptors of the methods to serve.
+    fn methods(&self) -> crate::Result<Vec<MethodDescriptor>> {
+        let bytes = fs::read(&self.desc_file).context(ReadDescriptorSetSnafu {
+            path: &self.desc_file,
+        })?;
+        let pool = DescriptorPool::decode(bytes.as_slice()).context(DecodeDescriptorSetSnafu {
+            path: &self.desc_file,
+        })?;
+        let service =
+            pool.get_service_by_name(&self.service)
+                .ok_or_else(|| BuildError::UnknownService {
+                    service: self.service.clone(),
+                })?;
+
+        let methods = if self.methods.is_empty() {
+            service.methods().collect()
+        } else {
+            self.methods
+                .iter()
+                .map(|name| {
+                    service
+                        .methods()
+                        .find(|method| method.name() == name)
+                        .ok_or_else(|| BuildError::UnknownMethod {
+                            service: self.service.clone(),
+                            method: name.clone(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        if let Some(method) = methods.iter().find(|method| method.is_server_streaming()) {
+            return Err(BuildError::ServerStreamingMethod {
+                method: method.full_name().to_owned(),
+            }
+            .into());
+        }
+        Ok(methods)
+    }
+
+    fn response(&self, method: &MethodDescriptor) -> crate::Result<DynamicMessage> {
+        let Some(response) = &self.response else {
+            return Ok(DynamicMessage::new(method.output()));
+        };
+        let mut deserializer = serde_json::Deserializer::from_str(response);
+        let message = DynamicMessage::deserialize(method.output(), &mut deserializer)
+            .and_then(|message| deserializer.end().map(|()| message))
+            .context(InvalidResponseSnafu {
+                message: method.output().full_name(),
+            })?;
+        Ok(message)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "grpc_server")]
+impl SourceConfig for GrpcServerConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        let tls_settings = MaybeTlsSettings::from_config(self.tls.as_ref(), true)?;
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        // The routes of the methods are only known once the descriptors are loaded, so they're
+        // added to the router directly, rather than as named services.
+        let mut router = axum::Router::new();
+        for method in self.methods()? {
+            let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+            let service = MethodService {
+                response: self.response(&method)?,
+                method,
+                out: cx.out.clone(),
+                acknowledgements,
+                log_namespace,
+            };
+            router = router.route_service(&path, service);
+        }
+
+        let source = run_grpc_server_with_routes(
+            self.address,
+            tls_settings,
+            Routes::from(router),
+            cx.shutdown,
+        )
+        .map_err(|error| {
+            error!(message = "Source future failed.", %error);
+        });
+
+        Ok(Box::pin(source))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+
+        let schema_definition = Definition::default_for_namespace(&[log_namespace].into())
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!("grpc_method"))),
+                &owned_value_path!("method"),
+                Kind::bytes(),
+                None,
+            )
+            .with_standard_vector_source_metadata();
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, path::PathBuf};
+
+    use futures::{Stream, stream};
+    use http::uri::PathAndQuery;
+    use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+    use tonic::{Code, transport::Channel};
+    use vector_lib::event::EventStatus;
+
+    use super::{GrpcServerConfig, service::DynamicCodec};
+    use crate::{
+        SourceSender,
+        config::{SourceConfig, SourceContext},
+        event::Event,
+        test_util::{
+            collect_n,
+            components::{SOURCE_TAGS, assert_source_compliance},
+            next_addr, wait_for_tcp,
+        },
+    };
+
+    fn desc_file() -> PathBuf {
+        PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/protobuf/test_service.desc")
+    }
+
+    fn message(name: &str) -> MessageDescriptor {
+        let bytes = std::fs::read(desc_file()).unwrap();
+        DescriptorPool::decode(bytes.as_slice())
+            .unwrap()
+            .get_message_by_name(name)
+            .unwrap()
+    }
+
+    fn reading(sensor: &str) -> DynamicMessage {
+        let mut reading = DynamicMessage::new(message("test_service.Reading"));
+        reading.set_field_by_name("sensor", prost_reflect::Value::String(sensor.to_owned()));
+        reading.set_field_by_name("value", prost_reflect::Value::F64(21.5));
+        reading
+    }
+
+    fn config(address: SocketAddr, methods: &[&str]) -> GrpcServerConfig {
+        toml::from_str(&format!(
+            r#"
+            address = "{address}"
+            desc_file = "{}"
+            service = "test_service.Ingest"
+            methods = {methods:?}
+            response = '{{"ok": true}}'
+            "#,
+            desc_file().display(),
+        ))
+        .unwrap()
+    }
+
+    async fn source(
+        methods: &[&str],
+        status: EventStatus,
+    ) -> (impl Stream<Item = Event> + Unpin, Channel) {
+        let (sender, recv) = SourceSender::new_test_finalize(status);
+        let address = next_addr();
+        let source = config(address, methods)
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+
+        let channel = Channel::from_shared(format!("http://{address}"))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        (recv, channel)
+    }
+
+    fn codec() -> DynamicCodec {
+        DynamicCodec::new(message("test_service.Ack"))
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GrpcServerConfig>();
+    }
+
+    #[tokio::test]
+    async fn receives_unary_requests() {
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let (rx, channel) = source(&["Push"], EventStatus::Delivered).await;
+            let mut client = tonic::client::Grpc::new(channel);
+            client.ready().await.unwrap();
+
+            let response = client
+                .unary(
+                    tonic::Request::new(reading("kitchen")),
+                    PathAndQuery::from_static("/test_service.Ingest/Push"),
+                    codec(),
+                )
+                .await
+                .unwrap()
+                .into_inner();
+            assert_eq!(
+                response.get_field_by_name("ok").unwrap().as_bool(),
+                Some(true)
+            );
+
+            let events = collect_n(rx, 1).await;
+            let log = events[0].as_log();
+            assert_eq!(log["sensor"], "kitchen".into());
+            assert_eq!(log["value"], 21.5.into());
+            assert_eq!(log["grpc_method"], "test_service.Ingest.Push".into());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn receives_client_streaming_requests() {
+        let (rx, channel) = source(&[], EventStatus::Delivered).await;
+        let mut client = tonic::client::Grpc::new(channel);
+        client.ready().await.unwrap();
+
+        client
+            .client_streaming(
+                tonic::Request::new(stream::iter([reading("kitchen"), reading("garage")])),
+                PathAndQuery::from_static("/test_service.Ingest/PushStream"),
+                codec(),
+            )
+            .await
+            .unwrap();
+
+        let events = collect_n(rx, 2).await;
+        assert_eq!(events[0].as_log()["sensor"], "kitchen".into());
+        assert_eq!(events[1].as_log()["sensor"], "garage".into());
+    }
+
+    #[tokio::test]
+    async fn responds_with_errors_on_rejected_events() {
+        let (_rx, channel) = source(&["Push"], EventStatus::Rejected).await;
+        let mut client = tonic::client::Grpc::new(channel);
+        client.ready().await.unwrap();
+
+        let status = client
+            .unary(
+                tonic::Request::new(reading("kitchen")),
+                PathAndQuery::from_static("/test_service.Ingest/Push"),
+                codec(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::DataLoss);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_configurations() {
+        let address = next_addr();
+        for (methods, response) in [
+            (&["Watch"][..], None),
+            (&["Missing"][..], None),
+            (&["Push"][..], Some(r#"{"missing": true}"#)),
+        ] {
+            let mut config = config(address, methods);
+            config.response = response.map(ToOwned::to_owned);
+            assert!(
+                config
+                    .build(SourceContext::new_test(SourceSender::new_test().0, None))
+                    .await
+                    .is_err(),
+                "{methods:?} was accepted"
+            );
+        }
+    }
+}
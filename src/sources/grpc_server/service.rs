@@ -0,0 +1,213 @@
+//! The services of the methods, which decode their messages with the descriptors of the method
+//! rather than with generated code.
+
+use std::{
+    convert::Infallible,
+    task::{Context, Poll},
+};
+
+use chrono::Utc;
+use futures::{StreamExt, future::BoxFuture, stream, stream::BoxStream};
+use http::{Request, Response};
+use hyper::Body;
+use prost_reflect::{DynamicMessage, MessageDescriptor, MethodDescriptor, prost::Message as _};
+use tonic::{
+    Status,
+    body::BoxBody,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    server::{ClientStreamingService, Grpc, UnaryService},
+};
+use tower::Service;
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LegacyKey, LogNamespace},
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, LogEvent},
+    finalization::AddBatchNotifier,
+    internal_event::{CountByteSize, InternalEventHandle as _},
+    lookup::path,
+};
+use vrl::protobuf::parse::{Options, proto_to_value};
+
+use super::GrpcServerConfig;
+use crate::{
+    SourceSender,
+    internal_events::{EventsReceived, StreamClosedError},
+};
+
+/// The codec of the messages of a method, decoding them with their descriptor.
+#[derive(Clone, Debug)]
+pub(super) struct DynamicCodec {
+    decoded: MessageDescriptor,
+}
+
+impl DynamicCodec {
+    /// Creates a codec decoding the messages of the given type. The messages of any type can be
+    /// encoded.
+    pub(super) const fn new(decoded: MessageDescriptor) -> Self {
+        Self { decoded }
+    }
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder(self.decoded.clone())
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct DynamicEncoder;
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|error| Status::internal(format!("Failed encoding message: {error}")))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct DynamicDecoder(MessageDescriptor);
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.0.clone(), src)
+            .map(Some)
+            .map_err(|error| Status::invalid_argument(format!("Failed decoding message: {error}")))
+    }
+}
+
+/// Serves a unary or client streaming method, sending an event for each of the messages of the
+/// requests, and responding with the same message to all of them.
+#[derive(Clone)]
+pub(super) struct MethodService {
+    pub(super) method: MethodDescriptor,
+    pub(super) response: DynamicMessage,
+    pub(super) out: SourceSender,
+    pub(super) acknowledgements: bool,
+    pub(super) log_namespace: LogNamespace,
+}
+
+impl MethodService {
+    async fn handle(
+        self,
+        mut messages: BoxStream<'static, Result<DynamicMessage, Status>>,
+    ) -> Result<tonic::Response<DynamicMessage>, Status> {
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+        let events_received = register!(EventsReceived);
+        let mut out = self.out.clone();
+
+        // The events of client streaming requests are sent as their messages are received.
+        while let Some(message) = messages.next().await {
+            let mut event = self.build_event(message?)?;
+            if let Some(batch) = &batch {
+                event.add_batch_notifier(batch.clone());
+            }
+            events_received.emit(CountByteSize(1, event.estimated_json_encoded_size_of()));
+
+            out.send_event(event).await.map_err(|error| {
+                emit!(StreamClosedError { count: 1 });
+                Status::unavailable(error.to_string())
+            })?;
+        }
+        drop(batch);
+
+        handle_batch_status(receiver).await?;
+        Ok(tonic::Response::new(self.response.clone()))
+    }
+
+    fn build_event(&self, message: DynamicMessage) -> Result<Event, Status> {
+        let value = proto_to_value(
+            &prost_reflect::Value::Message(message),
+            None,
+            &Options::default(),
+        )
+        .map_err(|error| Status::invalid_argument(format!("Failed decoding message: {error}")))?;
+
+        let mut log = LogEvent::from(value);
+        self.log_namespace.insert_source_metadata(
+            GrpcServerConfig::NAME,
+            &mut log,
+            Some(LegacyKey::InsertIfEmpty(path!("grpc_method"))),
+            path!("method"),
+            self.method.full_name(),
+        );
+        self.log_namespace.insert_standard_vector_source_metadata(
+            &mut log,
+            GrpcServerConfig::NAME,
+            Utc::now(),
+        );
+        Ok(Event::Log(log))
+    }
+}
+
+async fn handle_batch_status(receiver: Option<BatchStatusReceiver>) -> Result<(), Status> {
+    let status = match receiver {
+        Some(receiver) => receiver.await,
+        None => BatchStatus::Delivered,
+    };
+
+    match status {
+        BatchStatus::Errored => Err(Status::internal("Delivery error")),
+        BatchStatus::Rejected => Err(Status::data_loss("Delivery failed")),
+        BatchStatus::Delivered => Ok(()),
+    }
+}
+
+impl UnaryService<DynamicMessage> for MethodService {
+    type Response = DynamicMessage;
+    type Future = BoxFuture<'static, Result<tonic::Response<DynamicMessage>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
+        let messages = stream::iter([Ok(request.into_inner())]).boxed();
+        Box::pin(self.clone().handle(messages))
+    }
+}
+
+impl ClientStreamingService<DynamicMessage> for MethodService {
+    type Response = DynamicMessage;
+    type Future = BoxFuture<'static, Result<tonic::Response<DynamicMessage>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<tonic::Streaming<DynamicMessage>>) -> Self::Future {
+        let messages = request.into_inner().boxed();
+        Box::pin(self.clone().handle(messages))
+    }
+}
+
+impl Service<Request<Body>> for MethodService {
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            let mut grpc = Grpc::new(DynamicCodec::new(service.method.input()))
+                // Tonic added a default of 4MB in 0.9. This replaces the old behavior.
+                .max_decoding_message_size(usize::MAX);
+            let response = if service.method.is_client_streaming() {
+                grpc.client_streaming(service, request).await
+            } else {
+                grpc.unary(service, request).await
+            };
+            Ok(response)
+        })
+    }
+}
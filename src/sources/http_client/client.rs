@@ -1,7 +1,7 @@
 //! Generalized HTTP client source.
 //! Calls an endpoint at an interval, decoding the HTTP responses into events.
 
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use bytes::{Bytes, BytesMut};
 use chrono::Utc;
@@ -26,19 +26,23 @@ use vrl::{
     prelude::TypeState,
 };
 
+use super::{
+    OAuth2Config,
+    pagination::{PaginationConfig, Paginator},
+};
 use crate::{
     codecs::{Decoder, DecodingConfig},
     config::{SourceConfig, SourceContext},
     format_vrl_diagnostics,
-    http::{Auth, ParamType, ParameterValue, QueryParameterValue, QueryParameters},
+    http::{Auth, HttpClient, ParamType, ParameterValue, QueryParameterValue, QueryParameters},
     serde::{default_decoding, default_framing_message_based},
     sources,
     sources::util::{
         http::HttpMethod,
         http_client,
         http_client::{
-            GenericHttpClientInputs, HttpClientBuilder, build_url, call, default_interval,
-            default_timeout, warn_if_interval_too_low,
+            GenericHttpClientInputs, HttpClientBuilder, RequestAuthorizer, build_url, call,
+            default_interval, default_timeout, warn_if_interval_too_low,
         },
     },
     tls::{TlsConfig, TlsSettings},
@@ -122,6 +126,30 @@ pub struct HttpClientConfig {
     #[configurable(derived)]
     pub auth: Option<Auth>,
 
+    /// OAuth2 client credentials authentication.
+    ///
+    /// This can't be used together with `auth`.
+    #[configurable(derived)]
+    pub oauth2: Option<OAuth2Config>,
+
+    #[configurable(derived)]
+    pub pagination: Option<PaginationConfig>,
+
+    /// The directory used to persist the pagination cursor.
+    ///
+    /// This is only used when `pagination` is set.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/lib/vector"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -187,6 +215,9 @@ impl Default for HttpClientConfig {
             method: default_http_method(),
             tls: None,
             auth: None,
+            oauth2: None,
+            pagination: None,
+            data_dir: None,
             log_namespace: None,
         }
     }
@@ -314,6 +345,27 @@ impl SourceConfig for HttpClientConfig {
 
         let tls = TlsSettings::from_options(self.tls.as_ref())?;
 
+        let authorizer: Option<Arc<dyn RequestAuthorizer>> = match &self.oauth2 {
+            Some(_) if self.auth.is_some() => {
+                return Err("Only one of `auth` and `oauth2` can be set.".into());
+            }
+            Some(oauth2) => {
+                let client = HttpClient::new(tls.clone(), &cx.proxy)?;
+                Some(Arc::new(oauth2.build(client)?))
+            }
+            None => None,
+        };
+
+        let paginator = match &self.pagination {
+            Some(pagination) => {
+                let data_dir = cx
+                    .globals
+                    .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+                Some(Arc::new(Paginator::new(pagination.clone(), &data_dir)?))
+            }
+            None => None,
+        };
+
         let log_namespace = cx.log_namespace(self.log_namespace);
 
         // build the decoder
@@ -326,6 +378,7 @@ impl SourceConfig for HttpClientConfig {
             decoder,
             log_namespace,
             query,
+            paginator,
         };
 
         warn_if_interval_too_low(self.timeout, self.interval);
@@ -337,6 +390,7 @@ impl SourceConfig for HttpClientConfig {
             headers: self.headers.clone(),
             content_type,
             auth: self.auth.clone(),
+            authorizer,
             tls,
             proxy: cx.proxy.clone(),
             shutdown: cx.shutdown,
@@ -383,9 +437,71 @@ pub struct HttpClientContext {
     pub decoder: Decoder,
     pub log_namespace: LogNamespace,
     query: Query,
+    paginator: Option<Arc<Paginator>>,
 }
 
 impl HttpClientContext {
+    /// Resolve the VRL query parameters of the URL, if there are any.
+    fn resolve_query(&self, url: &Uri) -> Option<Uri> {
+        // Early exit if there is no VRL to process
+        let query: &Query = &self.query;
+        if !query.has_vrl {
+            return None;
+        }
+
+        let mut processed_query = HashMap::new();
+
+        for (param_name, compiled_value) in &query.compiled {
+            match compiled_value {
+                CompiledQueryParameterValue::SingleParam(compiled_param) => {
+                    let result = match &compiled_param.program {
+                        Some(prog) => resolve_vrl(&compiled_param.value, prog)?,
+                        None => compiled_param.value.clone(),
+                    };
+
+                    processed_query.insert(
+                        param_name.clone(),
+                        QueryParameterValue::SingleParam(ParameterValue::String(result)),
+                    );
+                }
+                CompiledQueryParameterValue::MultiParams(compiled_params) => {
+                    let mut results = Vec::new();
+
+                    for param in compiled_params {
+                        let result = match &param.program {
+                            Some(p) => resolve_vrl(&param.value, p)?,
+                            None => param.value.clone(),
+                        };
+                        results.push(ParameterValue::String(result));
+                    }
+
+                    processed_query.insert(
+                        param_name.clone(),
+                        QueryParameterValue::MultiParams(results),
+                    );
+                }
+            };
+        }
+
+        // Extract the base URI without query parameters to avoid parameter duplication
+        let base_uri = Uri::builder()
+            .scheme(
+                url.scheme()
+                    .cloned()
+                    .unwrap_or_else(|| http::uri::Scheme::try_from("http").unwrap()),
+            )
+            .authority(
+                url.authority()
+                    .cloned()
+                    .unwrap_or_else(|| http::uri::Authority::try_from("localhost").unwrap()),
+            )
+            .path_and_query(url.path().to_string())
+            .build()
+            .ok()?;
+
+        Some(build_url(&base_uri, &processed_query))
+    }
+
     /// Decode the events from the byte buffer
     fn decode_events(&mut self, buf: &mut BytesMut) -> Vec<Event> {
         let mut events = Vec::new();
@@ -457,63 +573,19 @@ impl http_client::HttpClientContext for HttpClientContext {
 
     /// Process the URL dynamically before each request
     fn process_url(&self, url: &Uri) -> Option<Uri> {
-        // Early exit if there is no VRL to process
-        let query: &Query = &self.query;
-        if !query.has_vrl {
-            return None;
+        let resolved = self.resolve_query(url);
+        match &self.paginator {
+            // Continue from the last page of the previous scrapes.
+            Some(paginator) => paginator
+                .first_page(resolved.as_ref().unwrap_or(url))
+                .or(resolved),
+            None => resolved,
         }
+    }
 
-        let mut processed_query = HashMap::new();
-
-        for (param_name, compiled_value) in &query.compiled {
-            match compiled_value {
-                CompiledQueryParameterValue::SingleParam(compiled_param) => {
-                    let result = match &compiled_param.program {
-                        Some(prog) => resolve_vrl(&compiled_param.value, prog)?,
-                        None => compiled_param.value.clone(),
-                    };
-
-                    processed_query.insert(
-                        param_name.clone(),
-                        QueryParameterValue::SingleParam(ParameterValue::String(result)),
-                    );
-                }
-                CompiledQueryParameterValue::MultiParams(compiled_params) => {
-                    let mut results = Vec::new();
-
-                    for param in compiled_params {
-                        let result = match &param.program {
-                            Some(p) => resolve_vrl(&param.value, p)?,
-                            None => param.value.clone(),
-                        };
-                        results.push(ParameterValue::String(result));
-                    }
-
-                    processed_query.insert(
-                        param_name.clone(),
-                        QueryParameterValue::MultiParams(results),
-                    );
-                }
-            };
-        }
-
-        // Extract the base URI without query parameters to avoid parameter duplication
-        let base_uri = Uri::builder()
-            .scheme(
-                url.scheme()
-                    .cloned()
-                    .unwrap_or_else(|| http::uri::Scheme::try_from("http").unwrap()),
-            )
-            .authority(
-                url.authority()
-                    .cloned()
-                    .unwrap_or_else(|| http::uri::Authority::try_from("localhost").unwrap()),
-            )
-            .path_and_query(url.path().to_string())
-            .build()
-            .ok()?;
-
-        Some(build_url(&base_uri, &processed_query))
+    /// Follows the pagination of the responses, if enabled.
+    fn next_page(&mut self, url: &Uri, header: &Parts, body: &Bytes) -> Option<Uri> {
+        self.paginator.as_ref()?.next_page(url, header, body)
     }
 
     /// Enriches events with source_type, timestamp
@@ -58,6 +58,9 @@ async fn invalid_endpoint() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -77,6 +80,9 @@ async fn collected_logs_bytes() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -102,6 +108,9 @@ async fn collected_logs_json() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -127,6 +136,9 @@ async fn collected_metrics_native_json() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -157,6 +169,9 @@ async fn collected_trace_native_json() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -182,6 +197,9 @@ async fn unauthorized_no_auth() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -204,6 +222,9 @@ async fn unauthorized_wrong_auth() {
             user: "white_rabbit".to_string(),
             password: "morpheus".to_string().into(),
         }),
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -226,6 +247,9 @@ async fn authorized() {
             user: "user".to_string(),
             password: "pass".to_string().into(),
         }),
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -248,6 +272,9 @@ async fn tls_invalid_ca() {
             ..Default::default()
         }),
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -270,6 +297,9 @@ async fn tls_valid() {
             ..Default::default()
         }),
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -290,6 +320,9 @@ async fn shutdown() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     };
 
@@ -1,5 +1,9 @@
 #[cfg(feature = "sources-http_client")]
 pub mod client;
+#[cfg(feature = "sources-http_client")]
+mod oauth2;
+#[cfg(feature = "sources-http_client")]
+mod pagination;
 
 #[cfg(test)]
 mod tests;
@@ -8,3 +12,5 @@ mod tests;
 mod integration_tests;
 
 pub use client::HttpClientConfig;
+pub use oauth2::OAuth2Config;
+pub use pagination::PaginationConfig;
@@ -0,0 +1,204 @@
+//! OAuth2 client credentials authentication of the requests of the `http_client` source.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use http::{
+    HeaderValue, Request, StatusCode, Uri,
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+};
+use hyper::Body;
+use serde::Deserialize;
+use serde_with::serde_as;
+use snafu::{ResultExt, Snafu};
+use tokio::time::Instant;
+use vector_lib::{configurable::configurable_component, sensitive_string::SensitiveString};
+
+use crate::{
+    http::{HttpClient, HttpError},
+    sources::util::http_client::RequestAuthorizer,
+};
+
+/// OAuth2 client credentials authentication.
+///
+/// An access token is requested from the token endpoint of the authorization server with the client
+/// credentials, and sent as a bearer token with the requests. A new access token is requested before
+/// the current one expires, or when a request is rejected as unauthorized.
+///
+/// The `tls` settings of the source, including the client certificate, also apply to the requests
+/// to the token endpoint.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OAuth2Config {
+    /// The URL of the token endpoint of the authorization server.
+    #[configurable(metadata(
+        docs::examples = "https://login.salesforce.com/services/oauth2/token"
+    ))]
+    pub token_endpoint: String,
+
+    /// The client identifier.
+    #[configurable(metadata(docs::examples = "${OAUTH2_CLIENT_ID}"))]
+    pub client_id: String,
+
+    /// The client secret.
+    ///
+    /// This can be omitted when the authorization server authenticates the client with the
+    /// certificate set in `tls.crt_file` instead.
+    #[configurable(metadata(docs::examples = "${OAUTH2_CLIENT_SECRET}"))]
+    pub client_secret: Option<SensitiveString>,
+
+    /// The scopes to request for the access token.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "api"))]
+    pub scopes: Vec<String>,
+
+    /// How long before the expiry of the access token a new one is requested.
+    #[serde(default = "default_refresh_before_expiry_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Refresh Before Expiry"))]
+    pub refresh_before_expiry_secs: Duration,
+}
+
+const fn default_refresh_before_expiry_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+#[derive(Debug, Snafu)]
+enum OAuth2Error {
+    #[snafu(display("Invalid token endpoint: {}", source))]
+    InvalidTokenEndpoint { source: http::uri::InvalidUri },
+    #[snafu(display("Failed to request an access token: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("Failed to read the token response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("The token endpoint responded with {}.", status))]
+    Status { status: StatusCode },
+    #[snafu(display("Invalid token response: {}", source))]
+    ParseResponse { source: serde_json::Error },
+    #[snafu(display("Invalid access token: {}", source))]
+    InvalidToken {
+        source: http::header::InvalidHeaderValue,
+    },
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct AccessToken {
+    header: HeaderValue,
+    /// When a new access token is requested, unless the authorization server didn't tell when
+    /// this one expires.
+    refresh_at: Option<Instant>,
+}
+
+/// Authorizes the requests with the access tokens requested from the token endpoint.
+pub(super) struct OAuth2Authorizer {
+    client: HttpClient,
+    token_endpoint: Uri,
+    /// The form sent to the token endpoint.
+    form: String,
+    refresh_before_expiry: Duration,
+    token: tokio::sync::Mutex<Option<AccessToken>>,
+    invalidated: AtomicBool,
+}
+
+impl OAuth2Config {
+    pub(super) fn build(&self, client: HttpClient) -> crate::Result<OAuth2Authorizer> {
+        let token_endpoint = self
+            .token_endpoint
+            .parse::<Uri>()
+            .context(InvalidTokenEndpointSnafu)?;
+
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        form.append_pair("grant_type", "client_credentials");
+        form.append_pair("client_id", &self.client_id);
+        if let Some(client_secret) = &self.client_secret {
+            form.append_pair("client_secret", client_secret.inner());
+        }
+        if !self.scopes.is_empty() {
+            form.append_pair("scope", &self.scopes.join(" "));
+        }
+
+        Ok(OAuth2Authorizer {
+            client,
+            token_endpoint,
+            form: form.finish(),
+            refresh_before_expiry: self.refresh_before_expiry_secs,
+            token: Default::default(),
+            invalidated: AtomicBool::new(false),
+        })
+    }
+}
+
+impl OAuth2Authorizer {
+    async fn request_token(&self) -> Result<AccessToken, OAuth2Error> {
+        let request = Request::post(&self.token_endpoint)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(Body::from(self.form.clone()))
+            .expect("error creating request");
+
+        let response = self.client.send(request).await.context(RequestSnafu)?;
+        let (parts, body) = response.into_parts();
+        if !parts.status.is_success() {
+            return StatusSnafu {
+                status: parts.status,
+            }
+            .fail();
+        }
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(ReadResponseSnafu)?;
+        let response: TokenResponse = serde_json::from_slice(&body).context(ParseResponseSnafu)?;
+
+        let mut header = HeaderValue::from_str(&format!("Bearer {}", response.access_token))
+            .context(InvalidTokenSnafu)?;
+        header.set_sensitive(true);
+        let refresh_at = response.expires_in.map(|expires_in| {
+            Instant::now()
+                + Duration::from_secs(expires_in).saturating_sub(self.refresh_before_expiry)
+        });
+        debug!(message = "Received an OAuth2 access token.", expires_in = ?response.expires_in);
+
+        Ok(AccessToken { header, refresh_at })
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestAuthorizer for OAuth2Authorizer {
+    async fn authorize(&self, request: &mut Request<Body>) -> crate::Result<()> {
+        // The lock is held while requesting a new access token so that concurrent requests wait
+        // for it, instead of each requesting its own.
+        let mut token = self.token.lock().await;
+        let invalidated = self.invalidated.swap(false, Ordering::Relaxed);
+        let header = match &*token {
+            Some(current)
+                if !invalidated
+                    && current
+                        .refresh_at
+                        .is_none_or(|refresh_at| Instant::now() < refresh_at) =>
+            {
+                current.header.clone()
+            }
+            _ => {
+                let new_token = self.request_token().await?;
+                let header = new_token.header.clone();
+                *token = Some(new_token);
+                header
+            }
+        };
+        request.headers_mut().insert(AUTHORIZATION, header);
+        Ok(())
+    }
+
+    fn invalidate(&self) {
+        self.invalidated.store(true, Ordering::Relaxed);
+    }
+}
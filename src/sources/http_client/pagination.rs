@@ -0,0 +1,219 @@
+//! Pagination of the responses of the `http_client` source.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use bytes::Bytes;
+use http::{Uri, header::LINK, response::Parts};
+use vector_lib::{configurable::configurable_component, lookup::lookup_v2::ConfigValuePath};
+use vrl::value::Value;
+
+use crate::internal_events::HttpClientCursorWriteError;
+
+const CURSOR_FILENAME: &str = "cursor.txt";
+
+/// Pagination of the responses.
+///
+/// The pages are requested one after the other until a page has no events or no next page. The
+/// position of the next page is persisted in the data directory, so that the next scrapes, and
+/// Vector after a restart, continue from the last page.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+#[configurable(metadata(docs::enum_tag_description = "The pagination strategy to use."))]
+pub enum PaginationConfig {
+    /// Follow the `next` link of the `Link` header of the responses.
+    LinkHeader,
+
+    /// Send the cursor read from the body of the previous response as a query parameter.
+    Cursor {
+        /// The path of the cursor in the JSON body of the responses.
+        ///
+        /// There is no next page when the cursor is missing, null, or empty.
+        #[configurable(metadata(docs::examples = "next_cursor"))]
+        #[configurable(metadata(docs::examples = "meta.cursor"))]
+        cursor_path: ConfigValuePath,
+
+        /// The query parameter to send the cursor with.
+        #[configurable(metadata(docs::examples = "cursor"))]
+        query_parameter: String,
+    },
+}
+
+/// Follows the pages of the responses, persisting the position of the next page.
+#[derive(Debug)]
+pub(super) struct Paginator {
+    config: PaginationConfig,
+    path: PathBuf,
+    /// The url of the next page with the `link_header` strategy, or its cursor with the `cursor`
+    /// strategy.
+    cursor: Mutex<Option<String>>,
+}
+
+impl Paginator {
+    pub(super) fn new(config: PaginationConfig, data_dir: &Path) -> io::Result<Self> {
+        let path = data_dir.join(CURSOR_FILENAME);
+        let cursor = match fs::read_to_string(&path) {
+            Ok(cursor) => Some(cursor.trim_end().to_owned()).filter(|cursor| !cursor.is_empty()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error),
+        };
+        Ok(Self {
+            config,
+            path,
+            cursor: Mutex::new(cursor),
+        })
+    }
+
+    /// The url of the first page of a scrape, continuing from the last page of the previous
+    /// scrapes if there is one.
+    pub(super) fn first_page(&self, url: &Uri) -> Option<Uri> {
+        let cursor = self
+            .cursor
+            .lock()
+            .expect("Pagination mutex is poisoned")
+            .clone()?;
+        match &self.config {
+            PaginationConfig::LinkHeader => cursor.parse().ok(),
+            PaginationConfig::Cursor {
+                query_parameter, ..
+            } => Some(with_query_parameter(url, query_parameter, &cursor)),
+        }
+    }
+
+    /// The url of the page following the response to `url`, if there is one.
+    pub(super) fn next_page(&self, url: &Uri, header: &Parts, body: &Bytes) -> Option<Uri> {
+        let (cursor, next_page) = match &self.config {
+            PaginationConfig::LinkHeader => {
+                let next_page = next_link(url, header)?;
+                (next_page.to_string(), next_page)
+            }
+            PaginationConfig::Cursor {
+                cursor_path,
+                query_parameter,
+            } => {
+                let cursor = body_cursor(body, cursor_path)?;
+                let next_page = with_query_parameter(url, query_parameter, &cursor);
+                (cursor, next_page)
+            }
+        };
+
+        // The cursor is small enough to be written synchronously.
+        if let Err(error) = fs::write(&self.path, format!("{cursor}\n")) {
+            emit!(HttpClientCursorWriteError {
+                error,
+                path: &self.path,
+            });
+        }
+        *self.cursor.lock().expect("Pagination mutex is poisoned") = Some(cursor);
+
+        Some(next_page)
+    }
+}
+
+/// Finds the `next` link of the `Link` headers, resolved against the url of the response.
+fn next_link(url: &Uri, header: &Parts) -> Option<Uri> {
+    let link = header
+        .headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let (target, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .filter_map(|param| param.trim().strip_prefix("rel="))
+                .any(|rel| {
+                    rel.trim_matches('"')
+                        .split_whitespace()
+                        .any(|rel| rel == "next")
+                })
+                .then(|| target.trim().trim_start_matches('<').trim_end_matches('>'))
+        })?;
+
+    url::Url::parse(&url.to_string())
+        .and_then(|url| url.join(link))
+        .ok()?
+        .as_str()
+        .parse()
+        .ok()
+}
+
+/// Reads the cursor at `path` in the JSON body of a response.
+fn body_cursor(body: &Bytes, path: &ConfigValuePath) -> Option<String> {
+    let body: serde_json::Value = serde_json::from_slice(body).ok()?;
+    match Value::from(body).get(&path.0)? {
+        Value::Bytes(cursor) if !cursor.is_empty() => {
+            Some(String::from_utf8_lossy(cursor).into_owned())
+        }
+        Value::Integer(cursor) => Some(cursor.to_string()),
+        _ => None,
+    }
+}
+
+/// Sets the query parameter of the url, replacing its existing values.
+fn with_query_parameter(url: &Uri, name: &str, value: &str) -> Uri {
+    let query = url::form_urlencoded::parse(url.query().unwrap_or_default().as_bytes())
+        .filter(|(key, _)| key != name)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .chain([(name.to_owned(), value.to_owned())]);
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    serializer.extend_pairs(query);
+
+    let mut parts = url.clone().into_parts();
+    parts.path_and_query = format!("{}?{}", url.path(), serializer.finish())
+        .parse()
+        .ok();
+    Uri::from_parts(parts).unwrap_or_else(|_| url.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_next_link() {
+        let url = Uri::from_static("https://example.com/logs?since=1");
+        let (mut header, _) = http::Response::new(()).into_parts();
+        header.headers.insert(
+            LINK,
+            r#"<https://example.com/logs?page=1>; rel="prev", </logs?page=3>; rel="next""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            next_link(&url, &header),
+            Some(Uri::from_static("https://example.com/logs?page=3"))
+        );
+
+        header.headers.remove(LINK);
+        assert_eq!(next_link(&url, &header), None);
+    }
+
+    #[test]
+    fn reads_the_cursor() {
+        let path = ConfigValuePath::from("meta.cursor");
+        let cursor = |body: &'static str| body_cursor(&Bytes::from_static(body.as_bytes()), &path);
+        assert_eq!(
+            cursor(r#"{"meta": {"cursor": "abc"}}"#),
+            Some("abc".to_owned())
+        );
+        assert_eq!(cursor(r#"{"meta": {"cursor": 42}}"#), Some("42".to_owned()));
+        assert_eq!(cursor(r#"{"meta": {"cursor": ""}}"#), None);
+        assert_eq!(cursor(r#"{"meta": {"cursor": null}}"#), None);
+        assert_eq!(cursor("[]"), None);
+
+        assert_eq!(
+            with_query_parameter(
+                &Uri::from_static("https://example.com/logs?cursor=abc&limit=10"),
+                "cursor",
+                "def"
+            ),
+            Uri::from_static("https://example.com/logs?limit=10&cursor=def")
+        );
+    }
+}
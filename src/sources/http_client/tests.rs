@@ -12,7 +12,7 @@ use vector_lib::{
 };
 use warp::{Filter, http::HeaderMap};
 
-use super::HttpClientConfig;
+use super::{HttpClientConfig, OAuth2Config, PaginationConfig};
 use crate::{
     components::validation::prelude::*,
     http::{ParamType, ParameterValue, QueryParameterValue},
@@ -20,7 +20,7 @@ use crate::{
     sources::util::http::HttpMethod,
     test_util::{
         components::{HTTP_PULL_SOURCE_TAGS, run_and_assert_source_compliance},
-        next_addr, test_generate_config, wait_for_tcp,
+        next_addr, temp_dir, test_generate_config, wait_for_tcp,
     },
 };
 
@@ -100,6 +100,9 @@ async fn bytes_decoding() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -129,6 +132,9 @@ async fn json_decoding_newline_delimited() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -163,6 +169,9 @@ async fn json_decoding_character_delimited() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -203,6 +212,9 @@ async fn request_query_applied() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -314,6 +326,9 @@ async fn request_query_vrl_applied() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -395,6 +410,9 @@ async fn request_query_vrl_dynamic_updates() {
         method: HttpMethod::Get,
         tls: None,
         auth: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -462,6 +480,9 @@ async fn headers_applied() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
@@ -491,7 +512,112 @@ async fn accept_header_override() {
         method: HttpMethod::Get,
         auth: None,
         tls: None,
+        oauth2: None,
+        pagination: None,
+        data_dir: None,
         log_namespace: None,
     })
     .await;
 }
+
+/// An OAuth2 access token should be requested with the client credentials and sent as a bearer token.
+#[tokio::test]
+async fn oauth2_client_credentials() {
+    let in_addr = next_addr();
+
+    let token_endpoint = warp::path!("token")
+        .and(warp::post())
+        .and(warp::body::form())
+        .map(|form: HashMap<String, String>| {
+            assert_eq!(form["grant_type"], "client_credentials");
+            assert_eq!(form["client_id"], "vector");
+            assert_eq!(form["client_secret"], "secret");
+            assert_eq!(form["scope"], "api refresh_token");
+            warp::reply::json(&serde_json::json!({
+                "access_token": "token",
+                "token_type": "Bearer",
+                "expires_in": 3600,
+            }))
+        });
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::header::exact("Authorization", "Bearer token"))
+        .map(|| r#"{"data" : "foo"}"#);
+
+    tokio::spawn(warp::serve(token_endpoint.or(dummy_endpoint)).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    run_compliance(HttpClientConfig {
+        endpoint: format!("http://{in_addr}/endpoint"),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        query: HashMap::new(),
+        decoding: DeserializerConfig::Json(Default::default()),
+        framing: default_framing_message_based(),
+        headers: HashMap::new(),
+        method: HttpMethod::Get,
+        auth: None,
+        tls: None,
+        oauth2: Some(OAuth2Config {
+            token_endpoint: format!("http://{in_addr}/token"),
+            client_id: "vector".to_string(),
+            client_secret: Some("secret".to_string().into()),
+            scopes: vec!["api".to_string(), "refresh_token".to_string()],
+            refresh_before_expiry_secs: Duration::from_secs(60),
+        }),
+        log_namespace: None,
+    })
+    .await;
+}
+
+/// Pages are followed with the cursor of the previous response until a page has no events.
+#[tokio::test]
+async fn cursor_pagination() {
+    let in_addr = next_addr();
+
+    let dummy_endpoint = warp::path!("endpoint")
+        .and(warp::query::<HashMap<String, String>>())
+        .map(
+            |query: HashMap<String, String>| match query.get("cursor").map(String::as_str) {
+                None => r#"{"data": "a", "next_cursor": "2"}"#,
+                Some("2") => r#"{"data": "b", "next_cursor": "3"}"#,
+                Some(_) => "[]",
+            },
+        );
+
+    tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+    wait_for_tcp(in_addr).await;
+
+    let data_dir = temp_dir();
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let events = run_compliance(HttpClientConfig {
+        endpoint: format!("http://{in_addr}/endpoint"),
+        interval: INTERVAL,
+        timeout: TIMEOUT,
+        query: HashMap::new(),
+        decoding: DeserializerConfig::Json(Default::default()),
+        framing: default_framing_message_based(),
+        headers: HashMap::new(),
+        method: HttpMethod::Get,
+        auth: None,
+        tls: None,
+        oauth2: None,
+        pagination: Some(PaginationConfig::Cursor {
+            cursor_path: "next_cursor".into(),
+            query_parameter: "cursor".to_string(),
+        }),
+        data_dir: Some(data_dir.clone()),
+        log_namespace: None,
+    })
+    .await;
+
+    // The later scrapes continue from the persisted cursor, which has no new events.
+    let data = events
+        .iter()
+        .map(|event| event.as_log()["data"].to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(data, ["a", "b"]);
+
+    let cursor = std::fs::read_to_string(data_dir.join("cursor.txt")).unwrap();
+    assert_eq!(cursor, "3\n");
+}
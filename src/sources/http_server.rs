@@ -1,10 +1,17 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{collections::HashMap, io, net::SocketAddr};
 
 use bytes::{Bytes, BytesMut};
 use chrono::Utc;
-use http::StatusCode;
+use futures::{Stream, StreamExt, TryStreamExt, future, stream, stream::BoxStream};
+use http::{
+    StatusCode,
+    header::{CONTENT_TYPE, TRANSFER_ENCODING},
+};
 use http_serde;
-use tokio_util::codec::Decoder as _;
+use tokio_util::{
+    codec::{Decoder as _, FramedRead},
+    io::StreamReader,
+};
 use vector_lib::{
     codecs::{
         BytesDecoderConfig, BytesDeserializerConfig, JsonDeserializerConfig,
@@ -20,7 +27,7 @@ use vrl::value::{Kind, kind::Collection};
 use warp::http::HeaderMap;
 
 use crate::{
-    codecs::{Decoder, DecodingConfig},
+    codecs::{Decoder, DecodingConfig, ReadyFrames},
     common::http::{ErrorMessage, server_auth::HttpServerAuthConfig},
     config::{
         GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig, SourceContext,
@@ -31,7 +38,7 @@ use crate::{
     serde::{bool_or_struct, default_decoding},
     sources::util::{
         Encoding, HttpSource,
-        http::{HttpMethod, add_headers, add_query_parameters},
+        http::{BodyStream, HttpMethod, OverloadConfig, add_headers, add_query_parameters},
     },
     tls::TlsEnableableConfig,
 };
@@ -175,6 +182,27 @@ pub struct SimpleHttpConfig {
     #[configurable(derived)]
     #[serde(default)]
     keepalive: KeepaliveConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    overload: OverloadConfig,
+
+    /// The maximum size of the body of each request, before any decompression.
+    ///
+    /// The requests whose body is larger are rejected with a `413` status code. The events already
+    /// decoded from a streamed body are kept.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    #[configurable(metadata(docs::examples = 10485760))]
+    max_body_size: Option<usize>,
+
+    /// Whether to decode the bodies sent with `Transfer-Encoding: chunked` while they're received.
+    ///
+    /// The events are then sent as the chunks arrive, such as the lines of an NDJSON body,
+    /// rather than once the whole body is received, which suits large uploads and long-lived
+    /// streaming clients. The response is sent once the body ends, or fails to be decoded, in
+    /// which case the events decoded until then are still sent.
+    #[serde(default)]
+    stream_chunked_bodies: bool,
 }
 
 impl SimpleHttpConfig {
@@ -215,6 +243,23 @@ impl SimpleHttpConfig {
                 Kind::bytes().or_undefined(),
                 None,
             )
+            // for the events decoded from the file parts of `multipart/form-data` bodies
+            .with_source_metadata(
+                SimpleHttpConfig::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!(
+                    "multipart_field"
+                ))),
+                &owned_value_path!("multipart_field"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                SimpleHttpConfig::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!("file_name"))),
+                &owned_value_path!("file_name"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
             .with_standard_vector_source_metadata();
 
         // for metadata that is added to the events dynamically from config options
@@ -286,6 +331,9 @@ impl Default for SimpleHttpConfig {
             acknowledgements: SourceAcknowledgementsConfig::default(),
             log_namespace: None,
             keepalive: KeepaliveConfig::default(),
+            overload: OverloadConfig::default(),
+            max_body_size: None,
+            stream_chunked_bodies: false,
         }
     }
 }
@@ -373,6 +421,9 @@ impl SourceConfig for SimpleHttpConfig {
             host_key: self.host_key.clone(),
             decoder,
             log_namespace,
+            overload: self.overload.clone(),
+            max_body_size: self.max_body_size,
+            stream_chunked_bodies: self.stream_chunked_bodies,
         };
         source.run(
             self.address,
@@ -421,6 +472,116 @@ struct SimpleHttpSource {
     host_key: OptionalValuePath,
     decoder: Decoder,
     log_namespace: LogNamespace,
+    overload: OverloadConfig,
+    max_body_size: Option<usize>,
+    stream_chunked_bodies: bool,
+}
+
+impl SimpleHttpSource {
+    /// Decodes the file parts of a `multipart/form-data` body, while it's received. The other
+    /// parts, which hold the values of the form, are skipped.
+    fn decode_multipart(
+        &self,
+        body: BodyStream,
+        boundary: String,
+    ) -> BoxStream<'static, Result<Vec<Event>, ErrorMessage>> {
+        let source = self.clone();
+        // The next part can only be read once the previous one is dropped, which happens once
+        // its events are decoded.
+        stream::try_unfold(
+            multer::Multipart::new(body, boundary),
+            |mut multipart| async move {
+                let field = multipart.next_field().await.map_err(|error| {
+                    ErrorMessage::new(
+                        StatusCode::BAD_REQUEST,
+                        format!("Failed parsing multipart body: {error}"),
+                    )
+                })?;
+                Ok(field.map(|field| (field, multipart)))
+            },
+        )
+        .try_filter(|field| future::ready(field.file_name().is_some()))
+        .map_ok(move |field| {
+            let source = source.clone();
+            let name = field.name().map(ToOwned::to_owned);
+            let file_name = field.file_name().map(ToOwned::to_owned);
+            decode_body(
+                source.decoder.clone(),
+                field.map_err(io::Error::other).boxed(),
+            )
+            .map_ok(move |mut events| {
+                source.insert_part_metadata(&mut events, name.as_deref(), file_name.as_deref());
+                events
+            })
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    fn insert_part_metadata(
+        &self,
+        events: &mut [Event],
+        name: Option<&str>,
+        file_name: Option<&str>,
+    ) {
+        for event in events {
+            if let Event::Log(log) = event {
+                if let Some(name) = name {
+                    self.log_namespace.insert_source_metadata(
+                        SimpleHttpConfig::NAME,
+                        log,
+                        Some(LegacyKey::InsertIfEmpty(path!("multipart_field"))),
+                        path!("multipart_field"),
+                        name,
+                    );
+                }
+                if let Some(file_name) = file_name {
+                    self.log_namespace.insert_source_metadata(
+                        SimpleHttpConfig::NAME,
+                        log,
+                        Some(LegacyKey::InsertIfEmpty(path!("file_name"))),
+                        path!("file_name"),
+                        file_name,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Decodes the events of a body while it's received, in batches of the frames received so far.
+fn decode_body(
+    decoder: Decoder,
+    body: BodyStream,
+) -> impl Stream<Item = Result<Vec<Event>, ErrorMessage>> + Send + 'static {
+    ReadyFrames::new(FramedRead::new(StreamReader::new(body), decoder)).map(|result| {
+        result
+            .map(|(frames, _)| frames.into_iter().flatten().collect())
+            .map_err(|error| {
+                // Error is logged / emitted by `crate::codecs::Decoder`, no further
+                // handling is needed here
+                ErrorMessage::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed decoding body: {error}"),
+                )
+            })
+    })
+}
+
+/// The boundary of the parts of `multipart/form-data` bodies.
+fn multipart_boundary(header_map: &HeaderMap) -> Option<String> {
+    header_map
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| multer::parse_boundary(value).ok())
+}
+
+fn is_chunked(header_map: &HeaderMap) -> bool {
+    header_map
+        .get_all(TRANSFER_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.to_ascii_lowercase().contains("chunked"))
 }
 
 impl HttpSource for SimpleHttpSource {
@@ -518,9 +679,33 @@ impl HttpSource for SimpleHttpSource {
         Ok(events)
     }
 
+    fn stream_body(&self, header_map: &HeaderMap) -> bool {
+        multipart_boundary(header_map).is_some()
+            || (self.stream_chunked_bodies && is_chunked(header_map))
+    }
+
+    fn build_event_stream(
+        &self,
+        body: BodyStream,
+        header_map: &HeaderMap,
+    ) -> BoxStream<'static, Result<Vec<Event>, ErrorMessage>> {
+        match multipart_boundary(header_map) {
+            Some(boundary) => self.decode_multipart(body, boundary),
+            None => decode_body(self.decoder.clone(), body).boxed(),
+        }
+    }
+
     fn enable_source_ip(&self) -> bool {
         self.host_key.path.is_some()
     }
+
+    fn overload(&self) -> Option<&OverloadConfig> {
+        Some(&self.overload)
+    }
+
+    fn max_body_size(&self) -> Option<usize> {
+        self.max_body_size
+    }
 }
 
 #[cfg(test)]
@@ -531,7 +716,7 @@ mod tests {
         Compression,
         write::{GzEncoder, ZlibEncoder},
     };
-    use futures::Stream;
+    use futures::{Stream, StreamExt};
     use headers::{Authorization, authorization::Credentials};
     use http::{HeaderMap, Method, StatusCode, Uri, header::AUTHORIZATION};
     use similar_asserts::assert_eq;
@@ -549,7 +734,7 @@ mod tests {
     };
     use vrl::value::{Kind, ObjectMap, kind::Collection};
 
-    use super::{SimpleHttpConfig, remove_duplicates};
+    use super::{OverloadConfig, SimpleHttpConfig, remove_duplicates};
     use crate::{
         SourceSender,
         common::http::server_auth::HttpServerAuthConfig,
@@ -615,6 +800,9 @@ mod tests {
                 acknowledgements: acknowledgements.into(),
                 log_namespace: None,
                 keepalive: Default::default(),
+                overload: Default::default(),
+                max_body_size: None,
+                stream_chunked_bodies: false,
             }
             .build(context)
             .await
@@ -1611,6 +1799,124 @@ mod tests {
         assert_eq!(200, send_with_headers(addr, "", headers).await);
     }
 
+    #[tokio::test]
+    async fn http_overload_rejects_with_retry_after() {
+        // The events are never read from the buffer, so it stays full once filled.
+        let (sender, _recv) = SourceSender::new_test_sender_with_buffer(2);
+        let address = next_addr();
+        let context = SourceContext::new_test(sender, None);
+        let config = SimpleHttpConfig {
+            address,
+            overload: OverloadConfig {
+                enabled: true,
+                threshold: 0.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        tokio::spawn(async move { config.build(context).await.unwrap().await.unwrap() });
+        wait_for_tcp(address).await;
+
+        assert_eq!(200, send(address, "first").await);
+        assert_eq!(200, send(address, "second").await);
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{address}/"))
+            .body("third")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 429);
+        assert_eq!(response.headers()["retry-after"], "30");
+    }
+
+    async fn ndjson_source(
+        max_body_size: Option<usize>,
+        stream_chunked_bodies: bool,
+    ) -> (impl Stream<Item = Event> + Unpin, SocketAddr) {
+        let (sender, recv) = SourceSender::new_test();
+        let address = next_addr();
+        let context = SourceContext::new_test(sender, None);
+        let config = SimpleHttpConfig {
+            address,
+            framing: Some(FramingConfig::NewlineDelimited(Default::default())),
+            decoding: Some(DeserializerConfig::Json(Default::default())),
+            max_body_size,
+            stream_chunked_bodies,
+            ..Default::default()
+        };
+        tokio::spawn(async move { config.build(context).await.unwrap().await.unwrap() });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    #[tokio::test]
+    async fn http_streams_chunked_body() {
+        let (mut rx, address) = ndjson_source(None, true).await;
+
+        let (mut body, request_body) = hyper::Body::channel();
+        let request = hyper::Request::post(format!("http://{address}/"))
+            .body(request_body)
+            .unwrap();
+        let response = tokio::spawn(hyper::Client::new().request(request));
+
+        // The events of the first chunk are sent before the rest of the body is received.
+        body.send_data(r#"{"key":"a"}"#.into()).await.unwrap();
+        body.send_data("\n".into()).await.unwrap();
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()["key"], "a".into());
+
+        body.send_data("{\"key\":\"b\"}\n".into()).await.unwrap();
+        drop(body);
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()["key"], "b".into());
+
+        let response = response.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn http_decodes_multipart_files() {
+        let (rx, address) = ndjson_source(None, false).await;
+
+        let body = [
+            "--boundary",
+            "Content-Disposition: form-data; name=\"comment\"",
+            "",
+            "not a file",
+            "--boundary",
+            "Content-Disposition: form-data; name=\"logs\"; filename=\"app.ndjson\"",
+            "Content-Type: application/x-ndjson",
+            "",
+            "{\"key\":\"a\"}\n{\"key\":\"b\"}",
+            "--boundary--",
+            "",
+        ]
+        .join("\r\n");
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Content-Type",
+            "multipart/form-data; boundary=boundary".parse().unwrap(),
+        );
+
+        let events = spawn_ok_collect_n(send_with_headers(address, &body, headers), rx, 2).await;
+        assert_eq!(events.len(), 2);
+        for (event, key) in events.iter().zip(["a", "b"]) {
+            let log = event.as_log();
+            assert_eq!(log["key"], key.into());
+            assert_eq!(log["multipart_field"], "logs".into());
+            assert_eq!(log["file_name"], "app.ndjson".into());
+        }
+    }
+
+    #[tokio::test]
+    async fn http_rejects_large_bodies() {
+        let (_rx, address) = ndjson_source(Some(16), false).await;
+
+        assert_eq!(200, send(address, r#"{"key":"a"}"#).await);
+        assert_eq!(413, send(address, &"{\"key\":\"a\"}\n".repeat(3)).await);
+    }
+
     #[test]
     fn output_schema_definition_vector_namespace() {
         let config = SimpleHttpConfig {
@@ -1651,6 +1957,16 @@ mod tests {
                     Kind::bytes().or_undefined(),
                     None,
                 )
+                .with_metadata_field(
+                    &owned_value_path!(SimpleHttpConfig::NAME, "multipart_field"),
+                    Kind::bytes().or_undefined(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!(SimpleHttpConfig::NAME, "file_name"),
+                    Kind::bytes().or_undefined(),
+                    None,
+                )
                 .with_metadata_field(
                     &owned_value_path!("vector", "ingest_timestamp"),
                     Kind::timestamp(),
@@ -1686,6 +2002,16 @@ mod tests {
             Kind::bytes().or_undefined(),
             None,
         )
+        .with_event_field(
+            &owned_value_path!("multipart_field"),
+            Kind::bytes().or_undefined(),
+            None,
+        )
+        .with_event_field(
+            &owned_value_path!("file_name"),
+            Kind::bytes().or_undefined(),
+            None,
+        )
         .unknown_fields(Kind::bytes());
 
         assert_eq!(definitions, Some(expected_definition))
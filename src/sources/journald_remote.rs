@@ -0,0 +1,642 @@
+use std::{collections::HashMap, net::SocketAddr};
+
+use bytes::{Buf, Bytes};
+use chrono::{TimeZone, Utc};
+use snafu::Snafu;
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    configurable::configurable_component,
+    lookup::{metadata_path, owned_value_path, path},
+    schema::Definition,
+};
+use vrl::value::{Kind, ObjectMap, Value, kind::Collection};
+use warp::http::{HeaderMap, StatusCode};
+
+use crate::{
+    common::http::{ErrorMessage, server_auth::HttpServerAuthConfig},
+    config::{
+        DataType, GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext, SourceOutput, log_schema,
+    },
+    event::{Event, LogEvent},
+    http::KeepaliveConfig,
+    serde::bool_or_struct,
+    sources::util::{HttpSource, http::HttpMethod},
+    tls::TlsEnableableConfig,
+};
+
+/// The media type of the journal export format, as sent by `systemd-journal-upload`.
+const JOURNAL_EXPORT_CONTENT_TYPE: &str = "application/vnd.fdo.journal";
+
+const HOSTNAME: &str = "_HOSTNAME";
+const MESSAGE: &str = "MESSAGE";
+const SOURCE_TIMESTAMP: &str = "_SOURCE_REALTIME_TIMESTAMP";
+const RECEIVED_TIMESTAMP: &str = "__REALTIME_TIMESTAMP";
+
+/// Configuration for the `journald_remote` source.
+#[configurable_component(source(
+    "journald_remote",
+    "Receive journal entries from `systemd-journal-upload` over the systemd journal remote protocol."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JournaldRemoteConfig {
+    /// The socket address to listen for connections on.
+    ///
+    /// `systemd-journal-upload` sends journal entries to port 19532 by default.
+    #[configurable(metadata(docs::examples = "0.0.0.0:19532"))]
+    #[configurable(metadata(docs::examples = "localhost:19532"))]
+    address: SocketAddr,
+
+    /// The URL path journal entries are uploaded to.
+    ///
+    /// `systemd-journal-upload` always uploads to `/upload`.
+    #[serde(default = "default_path")]
+    #[configurable(metadata(docs::examples = "/upload"))]
+    path: String,
+
+    #[configurable(derived)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    auth: Option<HttpServerAuthConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    keepalive: KeepaliveConfig,
+}
+
+fn default_path() -> String {
+    "/upload".to_string()
+}
+
+impl JournaldRemoteConfig {
+    /// Builds the `schema::Definition` for this source using the provided `LogNamespace`.
+    fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        let schema_definition = match log_namespace {
+            LogNamespace::Vector => Definition::new_with_default_metadata(
+                Kind::bytes().or_null(),
+                [LogNamespace::Vector],
+            ),
+            LogNamespace::Legacy => Definition::new_with_default_metadata(
+                Kind::object(Collection::empty()),
+                [LogNamespace::Legacy],
+            ),
+        };
+
+        let mut schema_definition = schema_definition
+            .with_standard_vector_source_metadata()
+            // for metadata that is added to the events dynamically from the journal fields
+            .with_source_metadata(
+                JournaldRemoteConfig::NAME,
+                None,
+                &owned_value_path!("metadata"),
+                Kind::object(Collection::empty().with_unknown(
+                    Kind::bytes().or_array(Collection::empty().with_unknown(Kind::bytes())),
+                ))
+                .or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                JournaldRemoteConfig::NAME,
+                None,
+                &owned_value_path!("timestamp"),
+                Kind::timestamp().or_undefined(),
+                Some("timestamp"),
+            )
+            .with_source_metadata(
+                JournaldRemoteConfig::NAME,
+                log_schema().host_key().cloned().map(LegacyKey::Overwrite),
+                &owned_value_path!("host"),
+                Kind::bytes().or_undefined(),
+                Some("host"),
+            );
+
+        // for metadata that is added to the events dynamically from the journal fields
+        if log_namespace == LogNamespace::Legacy {
+            schema_definition = schema_definition.unknown_fields(
+                Kind::bytes().or_array(Collection::empty().with_unknown(Kind::bytes())),
+            );
+        }
+
+        schema_definition
+    }
+}
+
+impl Default for JournaldRemoteConfig {
+    fn default() -> Self {
+        Self {
+            address: "0.0.0.0:19532".parse().unwrap(),
+            path: default_path(),
+            tls: None,
+            auth: None,
+            acknowledgements: SourceAcknowledgementsConfig::default(),
+            log_namespace: None,
+            keepalive: KeepaliveConfig::default(),
+        }
+    }
+}
+
+impl GenerateConfig for JournaldRemoteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(JournaldRemoteConfig::default()).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "journald_remote")]
+impl SourceConfig for JournaldRemoteConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let source = JournaldRemoteSource {
+            log_namespace: cx.log_namespace(self.log_namespace),
+        };
+
+        // `systemd-journal-remote` replies with `202 Accepted` to successful uploads.
+        source.run(
+            self.address,
+            &self.path,
+            HttpMethod::Post,
+            StatusCode::ACCEPTED,
+            true,
+            self.tls.as_ref(),
+            self.auth.as_ref(),
+            cx,
+            self.acknowledgements,
+            self.keepalive.clone(),
+        )
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let schema_definition =
+            self.schema_definition(global_log_namespace.merge(self.log_namespace));
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone)]
+struct JournaldRemoteSource {
+    log_namespace: LogNamespace,
+}
+
+impl HttpSource for JournaldRemoteSource {
+    fn build_events(
+        &self,
+        body: Bytes,
+        header_map: &HeaderMap,
+        _query_parameters: &HashMap<String, String>,
+        _full_path: &str,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        let content_type = header_map
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default()
+            .trim();
+        if content_type != JOURNAL_EXPORT_CONTENT_TYPE {
+            return Err(ErrorMessage::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!(
+                    "Unsupported content type {content_type:?}, expected {JOURNAL_EXPORT_CONTENT_TYPE:?}"
+                ),
+            ));
+        }
+
+        let entries = parse_export_format(body).map_err(|error| {
+            ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid journal export format: {error}"),
+            )
+        })?;
+
+        let now = Utc::now();
+        Ok(entries
+            .into_iter()
+            .map(|entry| create_log_event(entry, self.log_namespace, now).into())
+            .collect())
+    }
+}
+
+/// A journal entry, as a list of fields in the order they were received.
+type Entry = Vec<(String, Bytes)>;
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+enum ExportFormatError {
+    #[snafu(display("invalid field name {name:?}"))]
+    InvalidFieldName { name: String },
+
+    #[snafu(display("binary field {name:?} is truncated"))]
+    TruncatedField { name: String },
+
+    #[snafu(display("binary field {name:?} is not terminated by a newline"))]
+    MissingNewline { name: String },
+}
+
+/// Parses entries serialized in the [journal export format][export_format].
+///
+/// Entries are separated by an empty line. Each field is either a `NAME=value` line, or, for
+/// values that contain newlines or binary data, the field name on its own line, followed by the
+/// length of the value as a little-endian 64-bit integer, the value, and a newline.
+///
+/// [export_format]: https://systemd.io/JOURNAL_EXPORT_FORMATS/#journal-export-format
+fn parse_export_format(mut body: Bytes) -> Result<Vec<Entry>, ExportFormatError> {
+    let mut entries = Vec::new();
+    let mut entry = Entry::new();
+
+    while !body.is_empty() {
+        let line_end = body.iter().position(|&b| b == b'\n');
+
+        // An empty line terminates the entry.
+        if line_end == Some(0) {
+            body.advance(1);
+            if !entry.is_empty() {
+                entries.push(std::mem::take(&mut entry));
+            }
+            continue;
+        }
+
+        let line = body.slice(..line_end.unwrap_or(body.len()));
+        match line.iter().position(|&b| b == b'=') {
+            Some(separator) => {
+                let name = field_name(&line[..separator])?;
+                entry.push((name, line.slice(separator + 1..)));
+                body.advance(line_end.map_or(body.len(), |end| end + 1));
+            }
+            None => {
+                let name = field_name(&line)?;
+                let Some(line_end) = line_end else {
+                    return TruncatedFieldSnafu { name }.fail();
+                };
+                body.advance(line_end + 1);
+
+                if body.len() < 8 {
+                    return TruncatedFieldSnafu { name }.fail();
+                }
+                let size = body.get_u64_le();
+                let size = match usize::try_from(size) {
+                    Ok(size) if size < body.len() => size,
+                    _ => return TruncatedFieldSnafu { name }.fail(),
+                };
+                let value = body.split_to(size);
+                if body.get_u8() != b'\n' {
+                    return MissingNewlineSnafu { name }.fail();
+                }
+                entry.push((name, value));
+            }
+        }
+    }
+
+    // The last entry is not necessarily followed by an empty line.
+    if !entry.is_empty() {
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Validates a field name. Journal field names only consist of uppercase letters, digits and
+/// underscores, while address fields added on export, such as `__CURSOR`, start with two
+/// underscores.
+fn field_name(name: &[u8]) -> Result<String, ExportFormatError> {
+    if !name.is_empty()
+        && name
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || *b == b'_')
+    {
+        Ok(String::from_utf8_lossy(name).into_owned())
+    } else {
+        InvalidFieldNameSnafu {
+            name: String::from_utf8_lossy(name).into_owned(),
+        }
+        .fail()
+    }
+}
+
+fn create_log_event(
+    entry: Entry,
+    log_namespace: LogNamespace,
+    now: chrono::DateTime<Utc>,
+) -> LogEvent {
+    // A field may occur multiple times in an entry, in which case its values are collected
+    // into an array, as `journalctl` does.
+    let mut fields = ObjectMap::new();
+    for (name, value) in entry {
+        let value = Value::Bytes(value);
+        match fields.get_mut(name.as_str()) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => {
+                let first = std::mem::replace(existing, Value::Null);
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                fields.insert(name.into(), value);
+            }
+        }
+    }
+
+    let timestamp = fields
+        .get(SOURCE_TIMESTAMP)
+        .or_else(|| fields.get(RECEIVED_TIMESTAMP))
+        .and_then(|value| value.as_str()?.parse::<i64>().ok())
+        .and_then(|micros| Utc.timestamp_micros(micros).single());
+    let host = fields.get(HOSTNAME).cloned();
+
+    let mut log = match log_namespace {
+        LogNamespace::Vector => {
+            let message = fields.remove(MESSAGE).unwrap_or(Value::Null);
+            let mut log = LogEvent::from(message);
+            log.insert(
+                metadata_path!(JournaldRemoteConfig::NAME, "metadata"),
+                fields,
+            );
+            log
+        }
+        LogNamespace::Legacy => {
+            let message = fields.remove(MESSAGE);
+            fields.remove(HOSTNAME);
+            let mut log = LogEvent::from(fields);
+            if let Some(message) = message {
+                log.maybe_insert(log_schema().message_key_target_path(), message);
+            }
+            log
+        }
+    };
+
+    if let Some(host) = host {
+        log_namespace.insert_source_metadata(
+            JournaldRemoteConfig::NAME,
+            &mut log,
+            log_schema().host_key().map(LegacyKey::Overwrite),
+            path!("host"),
+            host,
+        );
+    }
+
+    if let Some(timestamp) = timestamp {
+        log_namespace.insert_source_metadata(
+            JournaldRemoteConfig::NAME,
+            &mut log,
+            log_schema().timestamp_key().map(LegacyKey::Overwrite),
+            path!("timestamp"),
+            timestamp,
+        );
+    }
+
+    log_namespace.insert_standard_vector_source_metadata(&mut log, JournaldRemoteConfig::NAME, now);
+
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use futures::Stream;
+    use similar_asserts::assert_eq;
+    use vector_lib::event::EventStatus;
+    use vrl::value;
+
+    use super::*;
+    use crate::{
+        SourceSender,
+        test_util::{
+            components::{HTTP_PUSH_SOURCE_TAGS, assert_source_compliance},
+            next_addr, spawn_collect_n, wait_for_tcp,
+        },
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JournaldRemoteConfig>();
+    }
+
+    fn binary_field(name: &str, value: &[u8]) -> Vec<u8> {
+        let mut field = format!("{name}\n").into_bytes();
+        field.extend((value.len() as u64).to_le_bytes());
+        field.extend(value);
+        field.push(b'\n');
+        field
+    }
+
+    fn sample_body() -> Vec<u8> {
+        let mut body = b"__CURSOR=s=1;i=1\n\
+            __REALTIME_TIMESTAMP=1700000000000000\n\
+            _HOSTNAME=web-1\n\
+            PRIORITY=6\n\
+            _SYSTEMD_UNIT=nginx.service\n\
+            MESSAGE=started\n\
+            \n\
+            __CURSOR=s=1;i=2\n\
+            __REALTIME_TIMESTAMP=1700000001000000\n\
+            _SOURCE_REALTIME_TIMESTAMP=1700000000500000\n\
+            _HOSTNAME=web-1\n"
+            .to_vec();
+        body.extend(binary_field("MESSAGE", b"multi\nline"));
+        body.extend(b"TAG=a\nTAG=b\n\n");
+        body
+    }
+
+    #[test]
+    fn parses_export_format() {
+        let entries = parse_export_format(Bytes::from(sample_body())).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].len(), 6);
+        assert_eq!(
+            entries[0][5],
+            ("MESSAGE".to_string(), Bytes::from_static(b"started"))
+        );
+        assert_eq!(
+            entries[1][4],
+            ("MESSAGE".to_string(), Bytes::from_static(b"multi\nline"))
+        );
+        assert_eq!(entries[1][6], ("TAG".to_string(), Bytes::from_static(b"b")));
+    }
+
+    #[test]
+    fn parses_last_entry_without_empty_line() {
+        let entries =
+            parse_export_format(Bytes::from_static(b"MESSAGE=one\n\nMESSAGE=two")).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[1],
+            vec![("MESSAGE".to_string(), Bytes::from_static(b"two"))]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_export_format() {
+        assert_eq!(
+            parse_export_format(Bytes::from_static(b"message=lowercase\n")),
+            Err(ExportFormatError::InvalidFieldName {
+                name: "message".to_string()
+            })
+        );
+
+        let mut truncated = binary_field("MESSAGE", b"value");
+        truncated.truncate(truncated.len() - 3);
+        assert_eq!(
+            parse_export_format(Bytes::from(truncated)),
+            Err(ExportFormatError::TruncatedField {
+                name: "MESSAGE".to_string()
+            })
+        );
+
+        let mut unterminated = binary_field("MESSAGE", b"value");
+        *unterminated.last_mut().unwrap() = b'x';
+        assert_eq!(
+            parse_export_format(Bytes::from(unterminated)),
+            Err(ExportFormatError::MissingNewline {
+                name: "MESSAGE".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn creates_legacy_log_event() {
+        let entries = parse_export_format(Bytes::from(sample_body())).unwrap();
+        let now = Utc::now();
+        let mut events = entries
+            .into_iter()
+            .map(|entry| create_log_event(entry, LogNamespace::Legacy, now))
+            .collect::<Vec<_>>();
+
+        let log = events.remove(0);
+        assert_eq!(*log.get_message().unwrap(), "started".into());
+        assert_eq!(*log.get_host().unwrap(), "web-1".into());
+        assert_eq!(
+            *log.get_timestamp().unwrap(),
+            Utc.timestamp_opt(1_700_000_000, 0).unwrap().into()
+        );
+        assert_eq!(log["PRIORITY"], "6".into());
+        assert_eq!(log["_SYSTEMD_UNIT"], "nginx.service".into());
+        assert_eq!(*log.get_source_type().unwrap(), "journald_remote".into());
+
+        let log = events.remove(0);
+        assert_eq!(*log.get_message().unwrap(), "multi\nline".into());
+        assert_eq!(
+            *log.get_timestamp().unwrap(),
+            Utc.timestamp_opt(1_700_000_000, 500_000_000)
+                .unwrap()
+                .into()
+        );
+        assert_eq!(log["TAG"], value!(["a", "b"]));
+    }
+
+    #[test]
+    fn creates_vector_namespace_log_event() {
+        let mut entries = parse_export_format(Bytes::from(sample_body())).unwrap();
+        let now = Utc::now();
+        let log = create_log_event(entries.remove(0), LogNamespace::Vector, now);
+
+        assert_eq!(*log.value(), "started".into());
+        assert_eq!(
+            log.get(metadata_path!("journald_remote", "host")).unwrap(),
+            &value!("web-1")
+        );
+        assert_eq!(
+            log.get(metadata_path!("journald_remote", "timestamp"))
+                .unwrap(),
+            &Value::from(Utc.timestamp_opt(1_700_000_000, 0).unwrap())
+        );
+        assert_eq!(
+            log.get(metadata_path!("journald_remote", "metadata", "PRIORITY"))
+                .unwrap(),
+            &value!("6")
+        );
+        assert_eq!(
+            log.get(metadata_path!("vector", "source_type")).unwrap(),
+            &value!("journald_remote")
+        );
+
+        JournaldRemoteConfig::default()
+            .schema_definition(LogNamespace::Vector)
+            .assert_valid_for_event(&log.into());
+    }
+
+    async fn source(status: EventStatus) -> (impl Stream<Item = Event> + Unpin, SocketAddr) {
+        let (sender, recv) = SourceSender::new_test_finalize(status);
+        let address = next_addr();
+        let context = SourceContext::new_test(sender, None);
+        tokio::spawn(async move {
+            JournaldRemoteConfig {
+                address,
+                acknowledgements: true.into(),
+                ..Default::default()
+            }
+            .build(context)
+            .await
+            .unwrap()
+            .await
+            .unwrap()
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    async fn send(address: SocketAddr, content_type: &str, body: Vec<u8>) -> u16 {
+        reqwest::Client::new()
+            .post(format!("http://{address}/upload"))
+            .header("content-type", content_type)
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn receives_uploaded_entries() {
+        assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let (rx, address) = source(EventStatus::Delivered).await;
+
+            let events = spawn_collect_n(
+                async move {
+                    assert_eq!(
+                        202,
+                        send(address, JOURNAL_EXPORT_CONTENT_TYPE, sample_body()).await
+                    );
+                },
+                rx,
+                2,
+            )
+            .await;
+
+            assert_eq!(events.len(), 2);
+            assert_eq!(*events[0].as_log().get_message().unwrap(), "started".into());
+            assert_eq!(
+                *events[1].as_log().get_message().unwrap(),
+                "multi\nline".into()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_content_type() {
+        let (_rx, address) = source(EventStatus::Delivered).await;
+
+        assert_eq!(415, send(address, "application/json", b"{}".to_vec()).await);
+    }
+}
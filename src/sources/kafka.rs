@@ -78,6 +78,10 @@ enum BuildError {
     CreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     SubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Exactly one of `topics` and `partitions` must be set"))]
+    TopicsOrPartitions,
+    #[snafu(display("Could not assign Kafka partitions: {}", source))]
+    AssignError { source: rdkafka::error::KafkaError },
 }
 
 /// Metrics (beta) configuration.
@@ -88,6 +92,52 @@ struct Metrics {
     pub topic_lag_metric: bool,
 }
 
+/// The strategy used by the consumer group to assign partitions to its members.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum PartitionAssignmentStrategy {
+    /// Use the eager `range` and `roundrobin` assignors.
+    ///
+    /// Every rebalance revokes all partitions from all members of the group, which stop
+    /// consuming until the new assignment is received.
+    #[default]
+    Eager,
+
+    /// Use the incremental `cooperative-sticky` assignor.
+    ///
+    /// A rebalance only revokes the partitions that move to another member of the group, while
+    /// the other partitions are consumed without interruption.
+    CooperativeSticky,
+}
+
+impl PartitionAssignmentStrategy {
+    const fn as_librdkafka_value(self) -> &'static str {
+        match self {
+            Self::Eager => "range,roundrobin",
+            Self::CooperativeSticky => "cooperative-sticky",
+        }
+    }
+}
+
+/// A partition consumed without joining the consumer group.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct StaticPartitionConfig {
+    /// The topic of the partition.
+    #[configurable(metadata(docs::examples = "topic-1"))]
+    topic: String,
+
+    /// The number of the partition.
+    #[configurable(metadata(docs::examples = 0))]
+    partition: i32,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    start_offset: kafka::KafkaStartOffset,
+}
+
 /// Configuration for the `kafka` source.
 #[serde_as]
 #[configurable_component(source("kafka", "Collect logs from Apache Kafka."))]
@@ -107,6 +157,9 @@ pub struct KafkaSourceConfig {
     /// The Kafka topics names to read events from.
     ///
     /// Regular expression syntax is supported if the topic begins with `^`.
+    ///
+    /// Either `topics` or `partitions` must be set.
+    #[serde(default)]
     #[configurable(metadata(
         docs::examples = "^(prefix1|prefix2)-.+",
         docs::examples = "topic-1",
@@ -114,6 +167,19 @@ pub struct KafkaSourceConfig {
     ))]
     topics: Vec<String>,
 
+    /// A static list of partitions to read events from, instead of subscribing to `topics`.
+    ///
+    /// The consumer doesn't join the consumer group, so the partitions are never rebalanced, and
+    /// each one is consumed from its `start_offset`. This is useful to replay or backfill events
+    /// from given offsets or timestamps.
+    ///
+    /// Offsets are still committed for `group_id`, which shouldn't be used by any other consumer.
+    ///
+    /// Either `topics` or `partitions` must be set.
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    partitions: Vec<StaticPartitionConfig>,
+
     /// The consumer group name to be used to consume events from Kafka.
     #[configurable(metadata(docs::examples = "consumer-group-name"))]
     group_id: String,
@@ -148,6 +214,17 @@ pub struct KafkaSourceConfig {
     #[configurable(metadata(docs::human_name = "Drain Timeout"))]
     drain_timeout_ms: Option<u64>,
 
+    /// The partition assignment strategy of the consumer group.
+    ///
+    /// All members of a consumer group must use the same rebalance protocol, so switching an
+    /// existing group from `eager` to `cooperative_sticky` requires restarting all its consumers.
+    ///
+    /// Setting `partition.assignment.strategy` in `librdkafka_options` overrides this option.
+    #[configurable(derived)]
+    #[configurable(metadata(docs::advanced))]
+    #[serde(default)]
+    partition_assignment_strategy: PartitionAssignmentStrategy,
+
     /// Timeout for network requests.
     #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
     #[configurable(metadata(docs::examples = 30000, docs::examples = 60000))]
@@ -333,6 +410,11 @@ impl SourceConfig for KafkaSourceConfig {
                 .build()?;
         let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
 
+        snafu::ensure!(
+            self.topics.is_empty() != self.partitions.is_empty(),
+            TopicsOrPartitionsSnafu
+        );
+
         if let Some(d) = self.drain_timeout_ms {
             snafu::ensure!(
                 Duration::from_millis(d) <= self.session_timeout_ms,
@@ -442,11 +524,30 @@ async fn kafka_source(
     // EOF signal allowing the coordination task to tell the kafka client task when all partitions have reached EOF
     let (eof_tx, eof_rx) = eof.then(oneshot::channel::<()>).unzip();
 
-    let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
-    if let Err(e) = consumer.subscribe(&topics).context(SubscribeSnafu) {
-        error!("{}", e);
-        return Err(());
-    }
+    let assignment = if config.partitions.is_empty() {
+        let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        if let Err(e) = consumer.subscribe(&topics).context(SubscribeSnafu) {
+            error!("{}", e);
+            return Err(());
+        }
+        None
+    } else {
+        let consumer = Arc::clone(&consumer);
+        let partitions = config.partitions.clone();
+        let timeout = config.socket_timeout_ms;
+        match tokio::task::spawn_blocking(move || {
+            assign_partitions(&consumer, &partitions, timeout)
+        })
+        .await
+        .expect("Partition assignment task panicked.")
+        {
+            Ok(tpl) => Some(tpl),
+            Err(e) => {
+                error!("{}", e);
+                return Err(());
+            }
+        }
+    };
 
     let coordination_task = {
         let span = span.clone();
@@ -468,6 +569,13 @@ async fn kafka_source(
         })
     };
 
+    // Statically assigned partitions don't trigger a rebalance, so their consumer tasks are set
+    // up here, before the kafka client task starts polling the consumer.
+    if let Some(tpl) = assignment {
+        let consumer = Arc::clone(&consumer);
+        _ = tokio::task::spawn_blocking(move || consumer.context().consume_partitions(&tpl)).await;
+    }
+
     let client_task = {
         let consumer = Arc::clone(&consumer);
         tokio::task::spawn_blocking(move || {
@@ -813,6 +921,13 @@ async fn coordinate_kafka_callbacks(
                     ConsumerState::Consuming(ref consumer_state) => {
                         let acks = consumer.context().acknowledgements;
                         for tp in assigned_partitions.drain(0..) {
+                            // Incremental assignments only contain newly assigned partitions, but
+                            // never spawn a second consumer task for a partition, since both would
+                            // store offsets for it.
+                            if end_signals.contains_key(&tp) {
+                                debug!("Partition {}:{} is already being consumed.", &tp.0, tp.1);
+                                continue;
+                            }
                             let topic = tp.0.as_str();
                             let partition = tp.1;
                             match consumer.split_partition_queue(topic, partition) { Some(pq) => {
@@ -849,7 +964,14 @@ async fn coordinate_kafka_callbacks(
                             }}
                         }
 
-                        state.keep_draining(deadline)
+                        // With incremental rebalancing, none of the revoked partitions may have a
+                        // running consumer task. Waiting for the drain deadline in that case would
+                        // only delay the rebalance for the remaining partitions.
+                        if state.is_drain_complete() {
+                            state.finish_drain(deadline)
+                        } else {
+                            state.keep_draining(deadline)
+                        }
                     }
                 },
                 KafkaCallback::ShuttingDown(drain) => (drain_deadline, consumer_state) = match consumer_state {
@@ -1217,6 +1339,10 @@ fn create_consumer(
             config.commit_interval_ms.as_millis().to_string(),
         )
         .set("enable.auto.offset.store", "false")
+        .set(
+            "partition.assignment.strategy",
+            config.partition_assignment_strategy.as_librdkafka_value(),
+        )
         .set("statistics.interval.ms", "1000")
         .set("client.id", "vector");
 
@@ -1241,6 +1367,25 @@ fn create_consumer(
     Ok((consumer, callback_rx))
 }
 
+/// Assigns the statically configured partitions to the consumer, starting from their configured
+/// offsets.
+fn assign_partitions(
+    consumer: &StreamConsumer<KafkaSourceContext>,
+    partitions: &[StaticPartitionConfig],
+    timeout: Duration,
+) -> Result<TopicPartitionList, BuildError> {
+    let tpl = kafka::start_offsets(
+        consumer,
+        partitions
+            .iter()
+            .map(|p| (p.topic.as_str(), p.partition, p.start_offset)),
+        timeout,
+    )
+    .context(AssignSnafu)?;
+    consumer.assign(&tpl).context(AssignSnafu)?;
+    Ok(tpl)
+}
+
 type TopicPartition = (String, i32);
 
 /// Status returned by partition consumer tasks, allowing the coordination task
@@ -1329,6 +1474,10 @@ impl KafkaSourceContext {
     /// signal individual partitions completing. This function blocks until the
     /// sender is dropped by the callback handler.
     fn revoke_partitions(&self, tpl: &TopicPartitionList) {
+        // Incremental rebalances may revoke no partitions at all from this consumer
+        if tpl.count() == 0 {
+            return;
+        }
         let (send, rendezvous) = sync_channel(0);
         let _ = self.callbacks.send(KafkaCallback::PartitionsRevoked(
             tpl.elements()
@@ -1530,6 +1679,90 @@ mod test {
         };
         assert!(create_consumer(&config, true).is_err());
     }
+
+    #[tokio::test]
+    async fn consumer_create_cooperative_sticky() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topics = ["topic"]
+            group_id = "group"
+            partition_assignment_strategy = "cooperative_sticky"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.partition_assignment_strategy,
+            PartitionAssignmentStrategy::CooperativeSticky
+        );
+        assert!(create_consumer(&config, true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_static_partitions() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            group_id = "group"
+
+            [[partitions]]
+            topic = "topic"
+            partition = 0
+
+            [[partitions]]
+            topic = "topic"
+            partition = 1
+            start_offset.type = "timestamp"
+            start_offset.timestamp_ms = 1735689600000
+
+            [[partitions]]
+            topic = "other"
+            partition = 0
+            start_offset.type = "offset"
+            start_offset.offset = 42
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config
+                .partitions
+                .iter()
+                .map(|p| p.start_offset)
+                .collect::<Vec<_>>(),
+            vec![
+                kafka::KafkaStartOffset::Committed,
+                kafka::KafkaStartOffset::Timestamp {
+                    timestamp_ms: 1735689600000
+                },
+                kafka::KafkaStartOffset::Offset { offset: 42 },
+            ]
+        );
+        assert!(create_consumer(&config, true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn requires_topics_or_partitions() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            group_id = "group"
+            "#,
+        )
+        .unwrap();
+        let cx = SourceContext::new_test(SourceSender::new_test().0, None);
+        assert!(config.build(cx).await.is_err());
+
+        let config = KafkaSourceConfig {
+            partitions: vec![StaticPartitionConfig {
+                topic: "topic".into(),
+                partition: 0,
+                start_offset: kafka::KafkaStartOffset::Earliest,
+            }],
+            ..make_config("topic", "group", LogNamespace::Legacy, None)
+        };
+        let cx = SourceContext::new_test(SourceSender::new_test().0, None);
+        assert!(config.build(cx).await.is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -1766,6 +1999,36 @@ mod integration_test {
         }
     }
 
+    #[tokio::test]
+    async fn consumes_static_partition_from_offset() {
+        let topic = format!("test-topic-{}", random_string(10));
+        let group_id = format!("test-group-{}", random_string(10));
+        send_events(topic.clone(), 1, 10).await;
+
+        let mut opts = HashMap::new();
+        opts.insert("enable.partition.eof".into(), "true".into());
+        let config = KafkaSourceConfig {
+            topics: vec![],
+            partitions: vec![StaticPartitionConfig {
+                topic: topic.clone(),
+                partition: 0,
+                start_offset: kafka::KafkaStartOffset::Offset { offset: 6 },
+            }],
+            ..make_config(&topic, &group_id, LogNamespace::Legacy, Some(opts))
+        };
+
+        let (tx, rx) = SourceSender::new_test_errors(|_| false);
+        let (trigger_shutdown, shutdown_done) =
+            spawn_kafka(tx, config, true, true, LogNamespace::Legacy);
+        let events = rx.collect::<Vec<Event>>().await;
+        drop(trigger_shutdown);
+        shutdown_done.await;
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].as_log()["offset"], 6.into());
+        assert_eq!(fetch_tpl_offset(&group_id, &topic, 0), Offset::from_raw(10));
+    }
+
     fn make_rand_config() -> (String, String, KafkaSourceConfig) {
         let topic = format!("test-topic-{}", random_string(10));
         let group_id = format!("test-group-{}", random_string(10));
@@ -2029,7 +2292,7 @@ mod integration_test {
         assert_eq!(total, expect_count);
     }
 
-    async fn consume_with_rebalance(rebalance_strategy: String) {
+    async fn consume_with_rebalance(partition_assignment_strategy: PartitionAssignmentStrategy) {
         // 1. Send N events (if running against a pre-populated kafka topic, use send_count=0 and expect_count=expected number of messages; otherwise just set send_count)
         let send_count: usize = std::env::var("KAFKA_SEND_COUNT")
             .unwrap_or_else(|_| "125000".into())
@@ -2053,13 +2316,10 @@ mod integration_test {
         let mut kafka_options = HashMap::new();
         kafka_options.insert("enable.partition.eof".into(), "true".into());
         kafka_options.insert("fetch.message.max.bytes".into(), kafka_max_bytes());
-        kafka_options.insert("partition.assignment.strategy".into(), rebalance_strategy);
-        let config1 = make_config(
-            &topic,
-            &group_id,
-            LogNamespace::Legacy,
-            Some(kafka_options.clone()),
-        );
+        let config1 = KafkaSourceConfig {
+            partition_assignment_strategy,
+            ..make_config(&topic, &group_id, LogNamespace::Legacy, Some(kafka_options))
+        };
         let config2 = config1.clone();
         let config3 = config1.clone();
         let config4 = config1.clone();
@@ -2144,13 +2404,13 @@ mod integration_test {
     #[tokio::test]
     async fn drains_acknowledgements_during_rebalance_default_assignments() {
         // the default, eager rebalance strategies generally result in more revocations
-        consume_with_rebalance("range,roundrobin".into()).await;
+        consume_with_rebalance(PartitionAssignmentStrategy::Eager).await;
     }
     #[tokio::test]
     async fn drains_acknowledgements_during_rebalance_sticky_assignments() {
         // Cooperative rebalance strategies generally result in fewer revokes,
         // as only reassigned partitions are revoked
-        consume_with_rebalance("cooperative-sticky".into()).await;
+        consume_with_rebalance(PartitionAssignmentStrategy::CooperativeSticky).await;
     }
 
     fn map_logs(events: EventArray) -> impl Iterator<Item = String> {
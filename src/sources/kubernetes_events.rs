@@ -0,0 +1,484 @@
+//! This mod implements `kubernetes_events` source.
+//! The scope of this source is to watch the Kubernetes Events API
+//! (`events.k8s.io/v1`) and emit each event as a structured log.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use http_1::{HeaderName, HeaderValue};
+use k8s_openapi::api::{core::v1::ObjectReference, events::v1::Event as K8sEvent};
+use kube::{
+    Client, Config as ClientConfig,
+    api::Api,
+    config::{self, KubeConfigOptions},
+    runtime::{WatchStreamExt, watcher},
+};
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LogNamespace, log_schema},
+    configurable::configurable_component,
+    internal_event::{CountByteSize, InternalEventHandle as _},
+    lookup::event_path,
+    schema::Definition,
+};
+use vrl::value::{Kind, ObjectMap, Value, kind::Collection};
+
+use crate::{
+    SourceSender,
+    built_info::{PKG_NAME, PKG_VERSION},
+    config::{DataType, SourceConfig, SourceContext, SourceOutput},
+    event::{Event, LogEvent},
+    internal_events::{EventsReceived, KubernetesEventsWatchError, StreamClosedError},
+    shutdown::ShutdownSignal,
+};
+
+/// Configuration for the `kubernetes_events` source.
+#[configurable_component(source(
+    "kubernetes_events",
+    "Collect events from the Kubernetes Events API."
+))]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct KubernetesEventsConfig {
+    /// Specifies the [field selector][field_selector] to filter Events with.
+    ///
+    /// [field_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/field-selectors/
+    #[configurable(metadata(docs::examples = "type=Warning"))]
+    #[configurable(metadata(docs::examples = "metadata.namespace=default,regarding.kind=Pod"))]
+    field_selector: String,
+
+    /// Specifies the [label selector][label_selector] to filter Events with.
+    ///
+    /// [label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+    #[configurable(metadata(docs::examples = "my_custom_label!=my_value"))]
+    label_selector: String,
+
+    /// Whether or not to emit the Events that already exist when the source starts.
+    ///
+    /// By default, only Events that are observed after the source started are emitted.
+    include_existing: bool,
+
+    /// Optional path to a readable [kubeconfig][kubeconfig] file.
+    ///
+    /// If not set, a connection to Kubernetes is made using the in-cluster configuration.
+    ///
+    /// [kubeconfig]: https://kubernetes.io/docs/concepts/configuration/organize-cluster-access-kubeconfig/
+    #[configurable(metadata(docs::examples = "/path/to/.kube/config"))]
+    kube_config_file: Option<PathBuf>,
+
+    /// Determines if requests to the kube-apiserver can be served by a cache.
+    use_apiserver_cache: bool,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+impl_generate_config_from_default!(KubernetesEventsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kubernetes_events")]
+impl SourceConfig for KubernetesEventsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        // If the user passed a custom Kubeconfig use it, otherwise
+        // we attempt to load the local kubeconfig, followed by the
+        // in-cluster environment variables
+        let mut client_config = match &self.kube_config_file {
+            Some(kc) => {
+                ClientConfig::from_custom_kubeconfig(
+                    config::Kubeconfig::read_from(kc)?,
+                    &KubeConfigOptions::default(),
+                )
+                .await?
+            }
+            None => ClientConfig::infer().await?,
+        };
+        if let Ok(user_agent) = HeaderValue::from_str(&format!("{PKG_NAME}/{PKG_VERSION}")) {
+            client_config
+                .headers
+                .push((HeaderName::from_static("user-agent"), user_agent));
+        }
+        let client = Client::try_from(client_config)?;
+
+        let watcher_config = watcher::Config {
+            field_selector: (!self.field_selector.is_empty()).then(|| self.field_selector.clone()),
+            label_selector: (!self.label_selector.is_empty()).then(|| self.label_selector.clone()),
+            list_semantic: if self.use_apiserver_cache {
+                watcher::ListSemantic::Any
+            } else {
+                watcher::ListSemantic::MostRecent
+            },
+            page_size: if self.use_apiserver_cache {
+                None
+            } else {
+                watcher::Config::default().page_size
+            },
+            ..Default::default()
+        };
+
+        Ok(Box::pin(run(
+            Api::<K8sEvent>::all(client),
+            watcher_config,
+            self.include_existing,
+            cx.out,
+            cx.shutdown,
+            log_namespace,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let schema_definition = Definition::new_with_default_metadata(
+            Kind::object(Collection::from_unknown(Kind::any())),
+            [log_namespace],
+        )
+        .with_standard_vector_source_metadata();
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn run(
+    api: Api<K8sEvent>,
+    watcher_config: watcher::Config,
+    include_existing: bool,
+    mut out: SourceSender,
+    shutdown: ShutdownSignal,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    let events_received = register!(EventsReceived);
+    let mut watermark = Watermark::new(include_existing, Utc::now());
+
+    let mut stream = watcher(api, watcher_config)
+        .backoff(watcher::DefaultBackoff::default())
+        .take_until(shutdown);
+
+    while let Some(result) = stream.next().await {
+        let event = match result {
+            Ok(watcher::Event::Apply(event)) => {
+                watermark.observe(last_timestamp(&event));
+                event
+            }
+            Ok(watcher::Event::InitApply(event)) => {
+                if !watermark.observe_init(last_timestamp(&event)) {
+                    continue;
+                }
+                event
+            }
+            Ok(watcher::Event::InitDone) => {
+                watermark.init_done();
+                continue;
+            }
+            Ok(watcher::Event::Init | watcher::Event::Delete(_)) => continue,
+            Err(error) => {
+                emit!(KubernetesEventsWatchError { error });
+                continue;
+            }
+        };
+
+        let log = create_log(event, log_namespace);
+        events_received.emit(CountByteSize(1, log.estimated_json_encoded_size_of()));
+
+        if out.send_event(Event::from(log)).await.is_err() {
+            emit!(StreamClosedError { count: 1 });
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Tracks the most recent Event timestamp that was emitted.
+///
+/// The watcher re-lists all Events when it starts and whenever it has to resync with the
+/// kube-apiserver, so this is used to avoid emitting the same Events over and over again.
+#[derive(Debug)]
+struct Watermark {
+    current: Option<DateTime<Utc>>,
+    pending: Option<DateTime<Utc>>,
+}
+
+impl Watermark {
+    fn new(include_existing: bool, now: DateTime<Utc>) -> Self {
+        Self {
+            current: (!include_existing).then_some(now),
+            pending: None,
+        }
+    }
+
+    /// Records an Event received through the watch, which is always emitted.
+    fn observe(&mut self, timestamp: Option<DateTime<Utc>>) {
+        self.current = self.current.max(timestamp);
+    }
+
+    /// Records an Event received while (re-)listing, returning whether it should be emitted.
+    fn observe_init(&mut self, timestamp: Option<DateTime<Utc>>) -> bool {
+        let emit = match (self.current, timestamp) {
+            (None, _) => true,
+            (Some(current), Some(timestamp)) => timestamp > current,
+            (Some(_), None) => false,
+        };
+        if emit {
+            self.pending = self.pending.max(timestamp);
+        }
+        emit
+    }
+
+    /// Applies the timestamps observed while (re-)listing once the listing is complete.
+    ///
+    /// This is deferred as listed Events are not ordered by time.
+    fn init_done(&mut self) {
+        self.current = self.current.max(self.pending.take());
+    }
+}
+
+fn first_timestamp(event: &K8sEvent) -> Option<DateTime<Utc>> {
+    event
+        .event_time
+        .as_ref()
+        .map(|time| time.0)
+        .or_else(|| event.deprecated_first_timestamp.as_ref().map(|time| time.0))
+        .or_else(|| {
+            event
+                .metadata
+                .creation_timestamp
+                .as_ref()
+                .map(|time| time.0)
+        })
+}
+
+fn last_timestamp(event: &K8sEvent) -> Option<DateTime<Utc>> {
+    event
+        .series
+        .as_ref()
+        .map(|series| series.last_observed_time.0)
+        .or_else(|| event.deprecated_last_timestamp.as_ref().map(|time| time.0))
+        .or_else(|| first_timestamp(event))
+}
+
+fn object_reference(reference: ObjectReference) -> Value {
+    let mut object = ObjectMap::new();
+    let fields = [
+        ("api_version", reference.api_version),
+        ("kind", reference.kind),
+        ("name", reference.name),
+        ("namespace", reference.namespace),
+        ("uid", reference.uid),
+        ("field_path", reference.field_path),
+        ("resource_version", reference.resource_version),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            object.insert(key.into(), value.into());
+        }
+    }
+    Value::Object(object)
+}
+
+fn insert_optional(log: &mut LogEvent, path: &str, value: Option<impl Into<Value>>) {
+    if let Some(value) = value {
+        log.insert(event_path!(path), value);
+    }
+}
+
+fn create_log(event: K8sEvent, log_namespace: LogNamespace) -> LogEvent {
+    let first_timestamp = first_timestamp(&event);
+    let last_timestamp = last_timestamp(&event);
+    let count = event
+        .series
+        .as_ref()
+        .map(|series| series.count)
+        .or(event.deprecated_count)
+        .unwrap_or(1);
+
+    let mut log = LogEvent::default();
+
+    match log_namespace {
+        LogNamespace::Vector => insert_optional(&mut log, "message", event.note),
+        LogNamespace::Legacy => {
+            if let Some(note) = event.note {
+                log.maybe_insert(log_schema().message_key_target_path(), note);
+            }
+            if let Some(timestamp) = last_timestamp {
+                log.maybe_insert(log_schema().timestamp_key_target_path(), timestamp);
+            }
+        }
+    }
+
+    insert_optional(&mut log, "reason", event.reason);
+    insert_optional(&mut log, "action", event.action);
+    insert_optional(&mut log, "type", event.type_);
+    insert_optional(&mut log, "reporting_controller", event.reporting_controller);
+    insert_optional(&mut log, "reporting_instance", event.reporting_instance);
+    insert_optional(
+        &mut log,
+        "involved_object",
+        event.regarding.map(object_reference),
+    );
+    insert_optional(&mut log, "related", event.related.map(object_reference));
+    log.insert(event_path!("count"), count);
+    insert_optional(&mut log, "first_timestamp", first_timestamp);
+    insert_optional(&mut log, "last_timestamp", last_timestamp);
+
+    let mut metadata = ObjectMap::new();
+    let fields = [
+        ("name", event.metadata.name),
+        ("namespace", event.metadata.namespace),
+        ("uid", event.metadata.uid),
+        ("resource_version", event.metadata.resource_version),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            metadata.insert(key.into(), value.into());
+        }
+    }
+    log.insert(event_path!("metadata"), metadata);
+
+    log_namespace.insert_standard_vector_source_metadata(
+        &mut log,
+        KubernetesEventsConfig::NAME,
+        Utc::now(),
+    );
+
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use k8s_openapi::{
+        api::events::v1::EventSeries,
+        apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta, Time},
+    };
+    use vrl::value;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KubernetesEventsConfig>();
+    }
+
+    fn timestamp(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).single().unwrap()
+    }
+
+    fn test_event() -> K8sEvent {
+        K8sEvent {
+            metadata: ObjectMeta {
+                name: Some("my-pod.17a6f3bd".to_owned()),
+                namespace: Some("default".to_owned()),
+                uid: Some("event-uid".to_owned()),
+                resource_version: Some("42".to_owned()),
+                ..Default::default()
+            },
+            note: Some("Back-off restarting failed container".to_owned()),
+            reason: Some("BackOff".to_owned()),
+            type_: Some("Warning".to_owned()),
+            reporting_controller: Some("kubelet".to_owned()),
+            regarding: Some(ObjectReference {
+                kind: Some("Pod".to_owned()),
+                name: Some("my-pod".to_owned()),
+                namespace: Some("default".to_owned()),
+                ..Default::default()
+            }),
+            event_time: Some(MicroTime(timestamp(10))),
+            series: Some(EventSeries {
+                count: 5,
+                last_observed_time: MicroTime(timestamp(20)),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn create_log_legacy() {
+        let log = create_log(test_event(), LogNamespace::Legacy);
+
+        assert_eq!(
+            log.get(".message"),
+            Some(&value!("Back-off restarting failed container"))
+        );
+        assert_eq!(log.get(".reason"), Some(&value!("BackOff")));
+        assert_eq!(log.get(".type"), Some(&value!("Warning")));
+        assert_eq!(log.get(".involved_object.kind"), Some(&value!("Pod")));
+        assert_eq!(log.get(".involved_object.name"), Some(&value!("my-pod")));
+        assert_eq!(log.get(".count"), Some(&value!(5)));
+        assert_eq!(
+            log.get(".first_timestamp"),
+            Some(&Value::from(timestamp(10)))
+        );
+        assert_eq!(
+            log.get(".last_timestamp"),
+            Some(&Value::from(timestamp(20)))
+        );
+        assert_eq!(log.get(".timestamp"), Some(&Value::from(timestamp(20))));
+        assert_eq!(log.get(".metadata.uid"), Some(&value!("event-uid")));
+        assert_eq!(log.get(".source_type"), Some(&value!("kubernetes_events")));
+    }
+
+    #[test]
+    fn create_log_vector_namespace() {
+        let mut event = test_event();
+        event.series = None;
+        event.deprecated_last_timestamp = Some(Time(timestamp(30)));
+
+        let log = create_log(event, LogNamespace::Vector);
+
+        assert_eq!(
+            log.get(".message"),
+            Some(&value!("Back-off restarting failed container"))
+        );
+        assert_eq!(log.get(".count"), Some(&value!(1)));
+        assert_eq!(
+            log.get(".last_timestamp"),
+            Some(&Value::from(timestamp(30)))
+        );
+        assert_eq!(log.get(".metadata.resource_version"), Some(&value!("42")));
+        assert_eq!(
+            log.get("%vector.source_type"),
+            Some(&value!("kubernetes_events"))
+        );
+    }
+
+    #[test]
+    fn watermark_skips_existing_events() {
+        let mut watermark = Watermark::new(false, timestamp(100));
+
+        assert!(!watermark.observe_init(Some(timestamp(50))));
+        assert!(watermark.observe_init(Some(timestamp(150))));
+        assert!(!watermark.observe_init(None));
+        watermark.init_done();
+
+        // A resync only emits the Events that were not seen yet.
+        assert!(!watermark.observe_init(Some(timestamp(150))));
+        assert!(watermark.observe_init(Some(timestamp(160))));
+        watermark.init_done();
+
+        watermark.observe(Some(timestamp(200)));
+        assert!(!watermark.observe_init(Some(timestamp(190))));
+    }
+
+    #[test]
+    fn watermark_includes_existing_events() {
+        let mut watermark = Watermark::new(true, timestamp(100));
+
+        assert!(watermark.observe_init(Some(timestamp(50))));
+        assert!(watermark.observe_init(Some(timestamp(20))));
+        assert!(watermark.observe_init(None));
+        watermark.init_done();
+
+        assert!(!watermark.observe_init(Some(timestamp(40))));
+        assert!(watermark.observe_init(Some(timestamp(60))));
+    }
+}
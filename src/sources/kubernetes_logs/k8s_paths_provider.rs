@@ -2,13 +2,16 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use k8s_openapi::api::core::v1::{Namespace, Pod};
 use kube::runtime::reflector::{ObjectRef, store::Store};
-use vector_lib::file_source::paths_provider::PathsProvider;
+use vector_lib::file_source::paths_provider::{FilePriority, PathsProvider};
 
-use super::path_helpers::build_pod_logs_directory;
+use super::{
+    path_helpers::{build_pod_logs_directory, parse_log_file_path},
+    pod_filter::PodFilter,
+};
 use crate::kubernetes::pod_manager_logic::extract_static_pod_config_hashsum;
 
 /// A paths provider implementation that uses the state obtained from the
@@ -19,6 +22,7 @@ pub struct K8sPathsProvider {
     include_paths: Vec<glob::Pattern>,
     exclude_paths: Vec<glob::Pattern>,
     insert_namespace_fields: bool,
+    pod_filter: PodFilter,
 }
 
 impl K8sPathsProvider {
@@ -29,6 +33,7 @@ impl K8sPathsProvider {
         include_paths: Vec<glob::Pattern>,
         exclude_paths: Vec<glob::Pattern>,
         insert_namespace_fields: bool,
+        pod_filter: PodFilter,
     ) -> Self {
         Self {
             pod_state,
@@ -36,6 +41,7 @@ impl K8sPathsProvider {
             include_paths,
             exclude_paths,
             insert_namespace_fields,
+            pod_filter,
         }
     }
 }
@@ -48,6 +54,7 @@ impl PathsProvider for K8sPathsProvider {
 
         state
             .into_iter()
+            .filter(|pod| self.pod_filter.matches_pod(pod))
             // filter out pods where we haven't fetched the namespace metadata yet
             // they will be picked up on a later run
             // Only check namespace metadata if insert_namespace_fields is enabled
@@ -73,10 +80,26 @@ impl PathsProvider for K8sPathsProvider {
                     &self.exclude_paths,
                     false,
                 )
+                .filter(|path| self.pod_filter.matches_container_path(path))
                 .collect::<Vec<_>>()
             })
             .collect()
     }
+
+    fn priority(&self, path: &Path) -> FilePriority {
+        let Some(file_info) = path.to_str().and_then(parse_log_file_path) else {
+            return FilePriority::default();
+        };
+        let obj = ObjectRef::<Pod>::new(file_info.pod_name).within(file_info.pod_namespace);
+        self.pod_state
+            .get(&obj)
+            .map(|pod| extract_priority_for_pod(&pod))
+            .unwrap_or_default()
+    }
+
+    fn has_priorities(&self) -> bool {
+        true
+    }
 }
 
 /// This function takes a `Pod` resource and returns the path to where the logs
@@ -132,6 +155,21 @@ fn extract_excluded_containers_for_pod(pod: &Pod) -> impl Iterator<Item = &str>
     })
 }
 
+const PRIORITY_ANNOTATION_KEY: &str = "vector.dev/priority";
+
+fn extract_priority_for_pod(pod: &Pod) -> FilePriority {
+    let priority = pod
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(PRIORITY_ANNOTATION_KEY));
+    match priority.map(|priority| priority.trim()) {
+        Some("high") => FilePriority::High,
+        Some("low") => FilePriority::Low,
+        _ => FilePriority::Normal,
+    }
+}
+
 fn build_container_exclusion_patterns<'a>(
     pod_logs_dir: &'a str,
     containers: impl Iterator<Item = &'a str> + 'a,
@@ -216,9 +254,11 @@ mod tests {
 
     use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::ObjectMeta};
 
+    use vector_lib::file_source::paths_provider::FilePriority;
+
     use super::{
         build_container_exclusion_patterns, extract_excluded_containers_for_pod,
-        extract_pod_logs_directory, filter_paths, list_pod_log_paths,
+        extract_pod_logs_directory, extract_priority_for_pod, filter_paths, list_pod_log_paths,
     };
 
     #[test]
@@ -629,4 +669,30 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn test_extract_priority_for_pod() {
+        let cases = vec![
+            (None, FilePriority::Normal),
+            (Some("high"), FilePriority::High),
+            (Some(" low "), FilePriority::Low),
+            (Some("normal"), FilePriority::Normal),
+            (Some("urgent"), FilePriority::Normal),
+        ];
+
+        for (annotation, expected) in cases {
+            let pod = Pod {
+                metadata: ObjectMeta {
+                    annotations: annotation.map(|annotation| {
+                        vec![("vector.dev/priority".to_owned(), annotation.to_owned())]
+                            .into_iter()
+                            .collect()
+                    }),
+                    ..ObjectMeta::default()
+                },
+                ..Pod::default()
+            };
+            assert_eq!(extract_priority_for_pod(&pod), expected, "{annotation:?}");
+        }
+    }
 }
@@ -0,0 +1,231 @@
+//! Parsing and matching of Kubernetes label selectors.
+//!
+//! Supports the syntax described at
+//! <https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors>.
+
+#![deny(missing_docs)]
+
+use std::collections::BTreeMap;
+
+use snafu::Snafu;
+
+/// Errors that can occur while parsing a label selector.
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum LabelSelectorError {
+    /// A requirement of the selector could not be parsed.
+    #[snafu(display("invalid label selector requirement {:?}", requirement))]
+    InvalidRequirement {
+        /// The offending requirement.
+        requirement: String,
+    },
+}
+
+/// A single requirement of a label selector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Requirement {
+    Equals(String, String),
+    NotEquals(String, String),
+    In(String, Vec<String>),
+    NotIn(String, Vec<String>),
+    Exists(String),
+    DoesNotExist(String),
+}
+
+impl Requirement {
+    fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        match self {
+            Self::Equals(key, value) => labels.get(key) == Some(value),
+            Self::NotEquals(key, value) => labels.get(key) != Some(value),
+            Self::In(key, values) => labels.get(key).is_some_and(|v| values.contains(v)),
+            Self::NotIn(key, values) => labels.get(key).is_none_or(|v| !values.contains(v)),
+            Self::Exists(key) => labels.contains_key(key),
+            Self::DoesNotExist(key) => !labels.contains_key(key),
+        }
+    }
+
+    fn parse(requirement: &str) -> Result<Self, LabelSelectorError> {
+        let invalid = || LabelSelectorError::InvalidRequirement {
+            requirement: requirement.to_owned(),
+        };
+        let valid_key = |key: &str| {
+            let key = key.trim();
+            (!key.is_empty() && !key.contains(char::is_whitespace))
+                .then(|| key.to_owned())
+                .ok_or_else(invalid)
+        };
+
+        if let Some(key) = requirement.strip_prefix('!') {
+            return Ok(Self::DoesNotExist(valid_key(key)?));
+        }
+
+        if let Some((head, tail)) = requirement.split_once('(') {
+            let values = tail
+                .strip_suffix(')')
+                .ok_or_else(invalid)?
+                .split(',')
+                .map(|value| value.trim().to_owned())
+                .collect();
+            let mut head = head.split_whitespace();
+            let (key, operator) = (head.next(), head.next());
+            if head.next().is_some() {
+                return Err(invalid());
+            }
+            return match (key, operator) {
+                (Some(key), Some("in")) => Ok(Self::In(valid_key(key)?, values)),
+                (Some(key), Some("notin")) => Ok(Self::NotIn(valid_key(key)?, values)),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some((key, value)) = requirement.split_once("!=") {
+            return Ok(Self::NotEquals(valid_key(key)?, value.trim().to_owned()));
+        }
+
+        if let Some((key, value)) = requirement
+            .split_once("==")
+            .or_else(|| requirement.split_once('='))
+        {
+            return Ok(Self::Equals(valid_key(key)?, value.trim().to_owned()));
+        }
+
+        Ok(Self::Exists(valid_key(requirement)?))
+    }
+}
+
+/// A parsed label selector, matching a set of labels if all of its requirements are satisfied.
+///
+/// An empty selector matches everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelSelector {
+    requirements: Vec<Requirement>,
+}
+
+impl LabelSelector {
+    /// Parses a comma separated list of label selector requirements.
+    pub fn parse(selector: &str) -> Result<Self, LabelSelectorError> {
+        let mut requirements = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+
+        for (index, c) in selector.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    requirements.push(&selector[start..index]);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        requirements.push(&selector[start..]);
+
+        let requirements = requirements
+            .into_iter()
+            .map(str::trim)
+            .filter(|requirement| !requirement.is_empty())
+            .map(Requirement::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { requirements })
+    }
+
+    /// Returns whether the given labels satisfy all the requirements of this selector.
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.requirements
+            .iter()
+            .all(|requirement| requirement.matches(labels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse() {
+        let cases = vec![
+            ("", Ok(vec![])),
+            (
+                "app=foo, tier==backend",
+                Ok(vec![
+                    Requirement::Equals("app".to_owned(), "foo".to_owned()),
+                    Requirement::Equals("tier".to_owned(), "backend".to_owned()),
+                ]),
+            ),
+            (
+                "app!=foo",
+                Ok(vec![Requirement::NotEquals(
+                    "app".to_owned(),
+                    "foo".to_owned(),
+                )]),
+            ),
+            (
+                "env in (prod, staging),tier notin (frontend)",
+                Ok(vec![
+                    Requirement::In(
+                        "env".to_owned(),
+                        vec!["prod".to_owned(), "staging".to_owned()],
+                    ),
+                    Requirement::NotIn("tier".to_owned(), vec!["frontend".to_owned()]),
+                ]),
+            ),
+            (
+                "partition,!canary",
+                Ok(vec![
+                    Requirement::Exists("partition".to_owned()),
+                    Requirement::DoesNotExist("canary".to_owned()),
+                ]),
+            ),
+            (
+                "env within (prod)",
+                Err(LabelSelectorError::InvalidRequirement {
+                    requirement: "env within (prod)".to_owned(),
+                }),
+            ),
+            (
+                "=foo",
+                Err(LabelSelectorError::InvalidRequirement {
+                    requirement: "=foo".to_owned(),
+                }),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let actual = LabelSelector::parse(input).map(|selector| selector.requirements);
+            assert_eq!(actual, expected, "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let pod_labels = labels(&[("app", "foo"), ("tier", "backend"), ("env", "prod")]);
+
+        let cases = vec![
+            ("", true),
+            ("app=foo", true),
+            ("app=foo,tier=backend", true),
+            ("app=foo,tier=frontend", false),
+            ("app!=bar", true),
+            ("missing!=bar", true),
+            ("env in (prod,staging)", true),
+            ("env notin (prod)", false),
+            ("missing notin (prod)", true),
+            ("tier", true),
+            ("!tier", false),
+            ("!canary", true),
+        ];
+
+        for (selector, expected) in cases {
+            let selector = LabelSelector::parse(selector).unwrap();
+            assert_eq!(selector.matches(&pod_labels), expected, "{selector:?}");
+        }
+    }
+}
@@ -4,13 +4,20 @@
 //! running inside the cluster as a DaemonSet.
 
 #![deny(missing_docs)]
-use std::{cmp::min, path::PathBuf, time::Duration};
+use std::{
+    cmp::min,
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use bytes::Bytes;
 use chrono::Utc;
 use futures::{future::FutureExt, stream::StreamExt};
 use futures_util::Stream;
 use http_1::{HeaderName, HeaderValue};
+use indexmap::IndexMap;
 use k8s_openapi::api::core::v1::{Namespace, Node, Pod};
 use k8s_paths_provider::K8sPathsProvider;
 use kube::{
@@ -51,28 +58,47 @@ use crate::{
         KubernetesLogsEventNodeAnnotationError, KubernetesLogsEventsReceived,
         KubernetesLogsPodInfo, StreamClosedError,
     },
-    kubernetes::{custom_reflector, meta_cache::MetaCache},
+    kubernetes::{
+        custom_reflector,
+        meta_cache::MetaCache,
+        shared_state::{KubernetesState, SharedStateSlot},
+    },
+    line_agg,
     shutdown::ShutdownSignal,
     sources,
-    sources::kubernetes_logs::partial_events_merger::merge_partial_events,
+    sources::{
+        kubernetes_logs::partial_events_merger::{
+            OversizeLineBehavior, PodIdentityFields, TRUNCATED_KEY, aggregate_multiline_events,
+            merge_partial_events,
+        },
+        util::MultilineConfig,
+    },
     transforms::{FunctionTransform, OutputBuffer},
 };
 
 mod k8s_paths_provider;
+mod label_selector;
 mod lifecycle;
 mod namespace_metadata_annotator;
 mod node_metadata_annotator;
 mod parser;
 mod partial_events_merger;
 mod path_helpers;
+mod pod_filter;
 mod pod_metadata_annotator;
+mod rate_limiter;
+mod route_annotator;
 mod transform_utils;
 mod util;
 
 use self::{
     namespace_metadata_annotator::NamespaceMetadataAnnotator,
-    node_metadata_annotator::NodeMetadataAnnotator, parser::Parser,
+    node_metadata_annotator::NodeMetadataAnnotator,
+    parser::{Parser, ParserMode},
+    pod_filter::{PodFilter, PodFilterConfig},
     pod_metadata_annotator::PodMetadataAnnotator,
+    rate_limiter::RateLimiter,
+    route_annotator::{ROUTES_KEY, Route, RouteAnnotator, RouteSelector},
 };
 
 /// The `self_node_name` value env var key.
@@ -144,6 +170,19 @@ pub struct Config {
     /// log driver.
     auto_partial_merge: bool,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    parser_mode: ParserMode,
+
+    /// Multiline aggregation configuration.
+    ///
+    /// Lines are aggregated per container, after partial events are merged. This allows, for
+    /// example, merging Java stack traces without an additional `reduce` transform.
+    ///
+    /// If not specified, multiline aggregation is disabled.
+    #[configurable(derived)]
+    multiline: Option<MultilineConfig>,
+
     /// The directory used to persist file checkpoint positions.
     ///
     /// By default, the [global `data_dir` option][global_data_dir] is used.
@@ -166,6 +205,19 @@ pub struct Config {
     #[configurable(derived)]
     node_annotation_fields: node_metadata_annotator::FieldsSpec,
 
+    /// A map of route names to label selectors matched against the Pod (and optionally the
+    /// Namespace) each event originates from.
+    ///
+    /// The names of all matching routes are added to the event under `kubernetes.routes` (or the
+    /// `routes` source metadata field when using the Vector log namespace), in the order they are
+    /// configured, so downstream components can route events without re-implementing label
+    /// selector matching.
+    #[configurable(metadata(
+        docs::additional_props_description = "Label selectors an event has to match for the route."
+    ))]
+    #[configurable(metadata(docs::examples = "example_routes()"))]
+    routes: IndexMap<String, RouteSelector>,
+
     /// A list of glob patterns to include while reading the files.
     #[configurable(metadata(docs::examples = "**/include/**"))]
     include_paths_glob_patterns: Vec<PathBuf>,
@@ -174,6 +226,9 @@ pub struct Config {
     #[configurable(metadata(docs::examples = "**/exclude/**"))]
     exclude_paths_glob_patterns: Vec<PathBuf>,
 
+    #[configurable(derived)]
+    pod_filter: PodFilterConfig,
+
     #[configurable(derived)]
     #[serde(default = "default_read_from")]
     read_from: ReadFromConfig,
@@ -210,9 +265,30 @@ pub struct Config {
     /// Note that, if auto_partial_merge is false, this config will be ignored. Also, if max_line_bytes is too small to reach the continuation character, then this
     /// config will have no practical impact (the same is true of `auto_partial_merge`). Finally, the smaller of `max_merged_line_bytes` and `max_line_bytes` will apply
     /// if auto_partial_merge is true, so if this is set to be 1 MiB, for example, but `max_line_bytes` is set to ~2.5 MiB, then every line greater than 1 MiB will be dropped.
+    ///
+    /// How lines exceeding this limit are handled can be changed with `oversize_line_behavior`.
     #[configurable(metadata(docs::type_unit = "bytes"))]
     max_merged_line_bytes: Option<usize>,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    oversize_line_behavior: OversizeLineBehavior,
+
+    /// The maximum number of lines read per second from the logs of each container.
+    ///
+    /// The lines exceeding the rate limit are discarded, so that a single container writing
+    /// excessive logs can't starve the others. The rate limit of the containers of a Pod can be
+    /// overridden with the `vector.dev/rate-limit-lines-per-sec` annotation of the Pod, where `0`
+    /// disables the rate limit.
+    ///
+    /// The lines are counted once partial lines are merged and multiline events are aggregated, so
+    /// the parts of a line are kept or discarded together.
+    ///
+    /// By default, the lines aren't rate limited.
+    #[configurable(metadata(docs::examples = 1000))]
+    #[configurable(metadata(docs::type_unit = "lines"))]
+    rate_limit_lines_per_sec: Option<NonZeroU32>,
+
     /// The number of lines to read for generating the checksum.
     ///
     /// If your files share a common header that is not always a fixed size,
@@ -280,6 +356,10 @@ pub struct Config {
     rotate_wait: Duration,
 }
 
+fn example_routes() -> IndexMap<String, String> {
+    IndexMap::from([("app-a".to_owned(), "app=foo,tier=backend".to_owned())])
+}
+
 const fn default_read_from() -> ReadFromConfig {
     ReadFromConfig::Beginning
 }
@@ -304,18 +384,24 @@ impl Default for Config {
             self_node_name: default_self_node_name_env_template(),
             extra_field_selector: "".to_string(),
             auto_partial_merge: true,
+            parser_mode: ParserMode::default(),
+            multiline: None,
             data_dir: None,
             pod_annotation_fields: pod_metadata_annotator::FieldsSpec::default(),
             namespace_annotation_fields: namespace_metadata_annotator::FieldsSpec::default(),
             node_annotation_fields: node_metadata_annotator::FieldsSpec::default(),
+            routes: IndexMap::new(),
             include_paths_glob_patterns: default_path_inclusion(),
             exclude_paths_glob_patterns: default_path_exclusion(),
+            pod_filter: PodFilterConfig::default(),
             read_from: default_read_from(),
             ignore_older_secs: None,
             max_read_bytes: default_max_read_bytes(),
             oldest_first: default_oldest_first(),
             max_line_bytes: default_max_line_bytes(),
             max_merged_line_bytes: None,
+            oversize_line_behavior: OversizeLineBehavior::default(),
+            rate_limit_lines_per_sec: None,
             fingerprint_lines: default_fingerprint_lines(),
             glob_minimum_cooldown_ms: default_glob_minimum_cooldown_ms(),
             ingestion_timestamp_field: None,
@@ -350,7 +436,7 @@ impl SourceConfig for Config {
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
         let log_namespace = global_log_namespace.merge(self.log_namespace);
-        let schema_definition = BytesDeserializerConfig
+        let mut schema_definition = BytesDeserializerConfig
             .schema_definition(log_namespace)
             .with_source_metadata(
                 Self::NAME,
@@ -544,6 +630,30 @@ impl SourceConfig for Config {
             )
             .with_standard_vector_source_metadata();
 
+        if self.auto_partial_merge && self.oversize_line_behavior == OversizeLineBehavior::Truncate
+        {
+            schema_definition = schema_definition.with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!(TRUNCATED_KEY))),
+                &owned_value_path!(TRUNCATED_KEY),
+                Kind::boolean().or_undefined(),
+                None,
+            );
+        }
+
+        if !self.routes.is_empty() {
+            schema_definition = schema_definition.with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(
+                    "kubernetes",
+                    ROUTES_KEY
+                ))),
+                &owned_value_path!(ROUTES_KEY),
+                Kind::array(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                None,
+            );
+        }
+
         vec![SourceOutput::new_maybe_logs(
             DataType::Log,
             schema_definition,
@@ -553,16 +663,32 @@ impl SourceConfig for Config {
     fn can_acknowledge(&self) -> bool {
         false
     }
+
+    fn checkpoints_dir(
+        &self,
+        globals: &GlobalOptions,
+        key: &ComponentKey,
+    ) -> Option<crate::Result<PathBuf>> {
+        Some(
+            globals
+                .resolve_and_validate_data_dir(self.data_dir.as_ref())
+                .map(|data_dir| data_dir.join(key.id())),
+        )
+    }
 }
 
 #[derive(Clone)]
 struct Source {
+    component_id: String,
     client: Client,
     data_dir: PathBuf,
     auto_partial_merge: bool,
+    parser_mode: ParserMode,
+    multiline: Option<line_agg::Config>,
     pod_fields_spec: pod_metadata_annotator::FieldsSpec,
     namespace_fields_spec: namespace_metadata_annotator::FieldsSpec,
     node_field_spec: node_metadata_annotator::FieldsSpec,
+    routes: Vec<Route>,
     field_selector: String,
     label_selector: String,
     namespace_label_selector: String,
@@ -571,12 +697,15 @@ struct Source {
     self_node_name: String,
     include_paths: Vec<glob::Pattern>,
     exclude_paths: Vec<glob::Pattern>,
+    pod_filter: PodFilter,
     read_from: ReadFrom,
     ignore_older_secs: Option<u64>,
     max_read_bytes: usize,
     oldest_first: bool,
     max_line_bytes: usize,
     max_merged_line_bytes: Option<usize>,
+    oversize_line_behavior: OversizeLineBehavior,
+    rate_limit_lines_per_sec: Option<NonZeroU32>,
     fingerprint_lines: usize,
     glob_minimum_cooldown: Duration,
     use_apiserver_cache: bool,
@@ -609,6 +738,12 @@ impl Source {
         let namespace_label_selector =
             prepare_label_selector(config.extra_namespace_label_selector.as_ref());
         let node_selector = prepare_node_selector(self_node_name.as_str())?;
+        let routes = Route::build_all(&config.routes)?;
+        let multiline = config
+            .multiline
+            .as_ref()
+            .map(line_agg::Config::try_from)
+            .transpose()?;
 
         // If the user passed a custom Kubeconfig use it, otherwise
         // we attempt to load the local kubeconfig, followed by the
@@ -636,6 +771,8 @@ impl Source {
 
         let exclude_paths = prepare_exclude_paths(config)?;
 
+        let pod_filter = config.pod_filter.build()?;
+
         let glob_minimum_cooldown = config.glob_minimum_cooldown_ms;
 
         let delay_deletion = config.delay_deletion_ms;
@@ -646,12 +783,16 @@ impl Source {
             .and_then(|k| k.path);
 
         Ok(Self {
+            component_id: key.id().to_owned(),
             client,
             data_dir,
             auto_partial_merge: config.auto_partial_merge,
+            parser_mode: config.parser_mode,
+            multiline,
             pod_fields_spec: config.pod_annotation_fields.clone(),
             namespace_fields_spec: config.namespace_annotation_fields.clone(),
             node_field_spec: config.node_annotation_fields.clone(),
+            routes,
             field_selector,
             label_selector,
             namespace_label_selector,
@@ -660,12 +801,15 @@ impl Source {
             self_node_name,
             include_paths,
             exclude_paths,
+            pod_filter,
             read_from: ReadFrom::from(config.read_from),
             ignore_older_secs: config.ignore_older_secs,
             max_read_bytes: config.max_read_bytes,
             oldest_first: config.oldest_first,
             max_line_bytes: config.max_line_bytes,
             max_merged_line_bytes: config.max_merged_line_bytes,
+            oversize_line_behavior: config.oversize_line_behavior,
+            rate_limit_lines_per_sec: config.rate_limit_lines_per_sec,
             fingerprint_lines: config.fingerprint_lines,
             glob_minimum_cooldown,
             use_apiserver_cache: config.use_apiserver_cache,
@@ -683,12 +827,16 @@ impl Source {
         log_namespace: LogNamespace,
     ) -> crate::Result<()> {
         let Self {
+            component_id,
             client,
             data_dir,
             auto_partial_merge,
+            parser_mode,
+            multiline,
             pod_fields_spec,
             namespace_fields_spec,
             node_field_spec,
+            routes,
             field_selector,
             label_selector,
             namespace_label_selector,
@@ -697,12 +845,15 @@ impl Source {
             self_node_name,
             include_paths,
             exclude_paths,
+            pod_filter,
             read_from,
             ignore_older_secs,
             max_read_bytes,
             oldest_first,
             max_line_bytes,
             max_merged_line_bytes,
+            oversize_line_behavior,
+            rate_limit_lines_per_sec,
             fingerprint_lines,
             glob_minimum_cooldown,
             use_apiserver_cache,
@@ -794,13 +945,31 @@ impl Source {
             delay_deletion,
         )));
 
+        // Share the state of the watchers with the `kubernetes_metadata` transforms.
+        let _shared_state = SharedStateSlot::share(
+            &component_id,
+            KubernetesState {
+                pods: pod_state.clone(),
+                namespaces: ns_state.clone(),
+            },
+        );
+
         let paths_provider = K8sPathsProvider::new(
             pod_state.clone(),
             ns_state.clone(),
             include_paths,
             exclude_paths,
             insert_namespace_fields,
+            pod_filter,
         );
+        let route_annotator =
+            RouteAnnotator::new(pod_state.clone(), ns_state.clone(), routes, log_namespace);
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new(
+            pod_state.clone(),
+            rate_limit_lines_per_sec,
+            log_namespace,
+        )));
+        let pod_identity_fields = PodIdentityFields::new(&pod_fields_spec, log_namespace);
         let annotator = PodMetadataAnnotator::new(pod_state, pod_fields_spec, log_namespace);
         let ns_annotator =
             NamespaceMetadataAnnotator::new(ns_state, namespace_fields_spec, log_namespace);
@@ -809,7 +978,8 @@ impl Source {
         let ignore_before = calculate_ignore_before(ignore_older_secs);
 
         let mut resolved_max_line_bytes = max_line_bytes;
-        if auto_partial_merge {
+        // Oversized lines only need to be discarded early on when they would be dropped anyways.
+        if auto_partial_merge && oversize_line_behavior == OversizeLineBehavior::Drop {
             resolved_max_line_bytes = min(
                 max_line_bytes,
                 max_merged_line_bytes.unwrap_or(max_line_bytes),
@@ -838,6 +1008,8 @@ impl Source {
             // be other, more sound ways for users considering the use of this
             // option to solve their use case, so take consideration.
             ignore_before,
+            // Seeding checkpoints is only exposed by the `file` source.
+            seed_checkpoints_before: None,
             // The maximum number of bytes a line can contain before being discarded. This
             // protects against malformed lines or tailing incorrect files.
             max_line_bytes: resolved_max_line_bytes,
@@ -877,7 +1049,7 @@ impl Source {
         let checkpoints = checkpointer.view();
         let events = file_source_rx.flat_map(futures::stream::iter);
         let bytes_received = register!(BytesReceived::from(Protocol::HTTP));
-        let events = events.map(move |line| {
+        let events = events.filter_map(move |line| {
             let byte_size = line.text.len();
             bytes_received.emit(ByteSize(byte_size));
 
@@ -899,6 +1071,10 @@ impl Source {
                 }),
             });
 
+            if let Some(file_info) = file_info.as_ref() {
+                route_annotator.annotate(&mut event, file_info);
+            }
+
             if file_info.is_none() {
                 emit!(KubernetesLogsEventAnnotationError { event: &event });
             } else {
@@ -919,10 +1095,10 @@ impl Source {
             }
 
             checkpoints.update(line.file_id, line.end_offset);
-            event
+            futures::future::ready(Some(event))
         });
 
-        let mut parser = Parser::new(log_namespace);
+        let mut parser = Parser::new(log_namespace, parser_mode);
         let events = events.flat_map(move |event| {
             let mut buf = OutputBuffer::with_capacity(1);
             parser.transform(&mut buf, event);
@@ -931,12 +1107,36 @@ impl Source {
 
         let (events_count, _) = events.size_hint();
 
-        let mut stream = if auto_partial_merge {
-            merge_partial_events(events, log_namespace, max_merged_line_bytes).left_stream()
+        let events = if auto_partial_merge {
+            merge_partial_events(
+                events,
+                log_namespace,
+                pod_identity_fields.clone(),
+                max_merged_line_bytes,
+                oversize_line_behavior,
+            )
+            .left_stream()
         } else {
             events.right_stream()
         };
 
+        let events = match multiline {
+            Some(multiline) => {
+                aggregate_multiline_events(events, log_namespace, pod_identity_fields, multiline)
+                    .left_stream()
+            }
+            None => events.right_stream(),
+        };
+
+        // The lines are rate limited once merged, so that the parts of a line are kept or dropped
+        // together.
+        tokio::spawn(rate_limiter::flush_periodically(Arc::downgrade(
+            &rate_limiter,
+        )));
+        let mut stream = events.filter(move |event| {
+            futures::future::ready(rate_limiter.lock().expect("lock poisoned").allow(event))
+        });
+
         let event_processing_loop = out.send_event_stream(&mut stream);
 
         let mut lifecycle = Lifecycle::new();
@@ -2,15 +2,83 @@ mod cri;
 mod docker;
 mod test_util;
 
-use vector_lib::config::LogNamespace;
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+use vector_lib::{config::LogNamespace, configurable::configurable_component};
+use vrl::path::OwnedTargetPath;
 
 use crate::{
     event::{Event, Value},
     internal_events::KubernetesLogsFormatPickerEdgeCase,
-    sources::kubernetes_logs::transform_utils::get_message_path,
+    sources::kubernetes_logs::transform_utils::{get_file_path, get_message_path},
     transforms::{FunctionTransform, OutputBuffer},
 };
 
+/// The maximum number of files to remember the detected log format of in the `auto` mode.
+const PER_FILE_FORMATS_CAPACITY: NonZeroUsize = NonZeroUsize::new(4096).unwrap();
+
+/// How the format of the log files is determined.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParserMode {
+    /// Detect the log format from the first line read by the source, and use it for all files.
+    #[default]
+    DetectOnce,
+
+    /// Detect the log format of each file separately, from the first line read from it.
+    ///
+    /// This is useful in clusters running a mix of container runtimes, for example while
+    /// migrating from Docker to containerd or CRI-O.
+    Auto,
+
+    /// Parse all files as written by the Docker `json-file` log driver.
+    Docker,
+
+    /// Parse all files using the CRI log format, as written by containerd or CRI-O.
+    Cri,
+}
+
+/// A detected log format.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    Docker,
+    Cri,
+}
+
+impl Format {
+    /// Sniffs the log format from the first bytes of a line.
+    fn detect(event: &Event, log_namespace: LogNamespace) -> Option<Self> {
+        let message_field = get_message_path(log_namespace);
+        let message = match event.as_log().get(&message_field) {
+            Some(message) => message,
+            None => {
+                emit!(KubernetesLogsFormatPickerEdgeCase {
+                    what: "got an event without a message"
+                });
+                return None;
+            }
+        };
+
+        let bytes = match message {
+            Value::Bytes(bytes) => bytes,
+            _ => {
+                emit!(KubernetesLogsFormatPickerEdgeCase {
+                    what: "got an event with non-bytes message"
+                });
+                return None;
+            }
+        };
+
+        Some(if bytes.len() > 1 && bytes[0] == b'{' {
+            Self::Docker
+        } else {
+            Self::Cri
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 enum ParserState {
     /// Runtime has not yet been detected.
@@ -21,19 +89,31 @@ enum ParserState {
 
     /// CRI is being used.
     Cri(cri::Cri),
+
+    /// Runtime is detected separately for each file.
+    PerFile(LruCache<String, Format>),
 }
 
 #[derive(Clone, Debug)]
 pub struct Parser {
     state: ParserState,
     log_namespace: LogNamespace,
+    file_path: OwnedTargetPath,
 }
 
 impl Parser {
-    pub const fn new(log_namespace: LogNamespace) -> Self {
+    pub fn new(log_namespace: LogNamespace, mode: ParserMode) -> Self {
+        let state = match mode {
+            ParserMode::DetectOnce => ParserState::Uninitialized,
+            ParserMode::Auto => ParserState::PerFile(LruCache::new(PER_FILE_FORMATS_CAPACITY)),
+            ParserMode::Docker => ParserState::Docker(docker::Docker::new(log_namespace)),
+            ParserMode::Cri => ParserState::Cri(cri::Cri::new(log_namespace)),
+        };
+
         Self {
-            state: ParserState::Uninitialized,
+            state,
             log_namespace,
+            file_path: get_file_path(log_namespace),
         }
     }
 }
@@ -42,36 +122,44 @@ impl FunctionTransform for Parser {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
         match &mut self.state {
             ParserState::Uninitialized => {
-                let message_field = get_message_path(self.log_namespace);
-                let message = match event.as_log().get(&message_field) {
-                    Some(message) => message,
-                    None => {
-                        emit!(KubernetesLogsFormatPickerEdgeCase {
-                            what: "got an event without a message"
-                        });
-                        return;
-                    }
+                let Some(format) = Format::detect(&event, self.log_namespace) else {
+                    return;
                 };
 
-                let bytes = match message {
-                    Value::Bytes(bytes) => bytes,
-                    _ => {
-                        emit!(KubernetesLogsFormatPickerEdgeCase {
-                            what: "got an event with non-bytes message"
-                        });
-                        return;
-                    }
-                };
-
-                self.state = if bytes.len() > 1 && bytes[0] == b'{' {
-                    ParserState::Docker(docker::Docker::new(self.log_namespace))
-                } else {
-                    ParserState::Cri(cri::Cri::new(self.log_namespace))
+                self.state = match format {
+                    Format::Docker => ParserState::Docker(docker::Docker::new(self.log_namespace)),
+                    Format::Cri => ParserState::Cri(cri::Cri::new(self.log_namespace)),
                 };
                 self.transform(output, event)
             }
             ParserState::Docker(t) => t.transform(output, event),
             ParserState::Cri(t) => t.transform(output, event),
+            ParserState::PerFile(formats) => {
+                let file = event
+                    .as_log()
+                    .get(&self.file_path)
+                    .and_then(Value::as_str)
+                    .map(|file| file.into_owned())
+                    .unwrap_or_default();
+
+                let format = match formats.get(&file) {
+                    Some(format) => *format,
+                    None => {
+                        let Some(format) = Format::detect(&event, self.log_namespace) else {
+                            return;
+                        };
+                        formats.put(file, format);
+                        format
+                    }
+                };
+
+                match format {
+                    Format::Docker => {
+                        docker::Docker::new(self.log_namespace).transform(output, event)
+                    }
+                    Format::Cri => cri::Cri::new(self.log_namespace).transform(output, event),
+                }
+            }
         }
     }
 }
@@ -106,7 +194,7 @@ mod tests {
     fn test_parsing_valid_vector_namespace() {
         trace_init();
         test_util::test_parser(
-            || Parser::new(LogNamespace::Vector),
+            || Parser::new(LogNamespace::Vector, ParserMode::DetectOnce),
             |bytes| Event::Log(LogEvent::from(value!(bytes))),
             valid_cases(LogNamespace::Vector),
         );
@@ -116,7 +204,7 @@ mod tests {
     fn test_parsing_valid_legacy_namespace() {
         trace_init();
         test_util::test_parser(
-            || Parser::new(LogNamespace::Legacy),
+            || Parser::new(LogNamespace::Legacy, ParserMode::DetectOnce),
             |bytes| Event::Log(LogEvent::from(bytes)),
             valid_cases(LogNamespace::Legacy),
         );
@@ -129,7 +217,7 @@ mod tests {
         let cases = invalid_cases();
 
         for bytes in cases {
-            let mut parser = Parser::new(LogNamespace::Legacy);
+            let mut parser = Parser::new(LogNamespace::Legacy, ParserMode::DetectOnce);
             let input = LogEvent::from(bytes);
             let mut output = OutputBuffer::default();
             parser.transform(&mut output, input.into());
@@ -158,11 +246,75 @@ mod tests {
         ];
 
         for (input, log_namespace) in cases {
-            let mut parser = Parser::new(log_namespace);
+            let mut parser = Parser::new(log_namespace, ParserMode::DetectOnce);
             let mut output = OutputBuffer::default();
             parser.transform(&mut output, input.into());
 
             assert!(output.is_empty(), "Expected no events: {output:?}");
         }
     }
+
+    #[test]
+    fn test_parsing_valid_auto_mode() {
+        trace_init();
+        for log_namespace in [LogNamespace::Legacy, LogNamespace::Vector] {
+            test_util::test_parser(
+                || Parser::new(log_namespace, ParserMode::Auto),
+                |bytes| match log_namespace {
+                    LogNamespace::Vector => Event::Log(LogEvent::from(value!(bytes))),
+                    LogNamespace::Legacy => Event::Log(LogEvent::from(bytes)),
+                },
+                valid_cases(log_namespace),
+            );
+        }
+    }
+
+    #[test]
+    fn test_auto_mode_detects_format_per_file() {
+        trace_init();
+
+        let docker_line = Bytes::from(
+            r#"{"log": "docker\n", "stream": "stdout", "time": "2019-01-01T00:00:00.000000000Z"}"#,
+        );
+        let cri_line = Bytes::from("2019-01-01T00:00:00.000000000+00:00 stdout F cri");
+
+        let event = |file: &str, line: &Bytes| {
+            let mut log = LogEvent::from(line.clone());
+            log.insert(event_path!("file"), file);
+            Event::Log(log)
+        };
+
+        let mut parser = Parser::new(LogNamespace::Legacy, ParserMode::Auto);
+        let mut output = OutputBuffer::default();
+        parser.transform(&mut output, event("docker.log", &docker_line));
+        parser.transform(&mut output, event("cri.log", &cri_line));
+        parser.transform(&mut output, event("docker.log", &docker_line));
+        // The format is cached per file, so CRI lines in a Docker file fail to parse.
+        parser.transform(&mut output, event("docker.log", &cri_line));
+
+        let messages = output
+            .into_events()
+            .map(|event| event.as_log()["message"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![value!("docker"), value!("cri"), value!("docker")]
+        );
+    }
+
+    #[test]
+    fn test_fixed_parser_modes() {
+        trace_init();
+
+        let cri_line = Bytes::from("2019-01-01T00:00:00.000000000+00:00 stdout F cri");
+
+        let mut output = OutputBuffer::default();
+        Parser::new(LogNamespace::Legacy, ParserMode::Docker)
+            .transform(&mut output, LogEvent::from(cri_line.clone()).into());
+        assert!(output.is_empty(), "Expected no events: {output:?}");
+
+        Parser::new(LogNamespace::Legacy, ParserMode::Cri)
+            .transform(&mut output, LogEvent::from(cri_line).into());
+        assert_eq!(output.len(), 1);
+    }
 }
@@ -1,15 +1,16 @@
 #![deny(missing_docs)]
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, hash_map::Entry},
     time::{Duration, Instant},
 };
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use futures::{Stream, StreamExt};
 use vector_lib::{
-    config::LogNamespace,
-    lookup::OwnedTargetPath,
+    config::{LegacyKey, LogNamespace},
+    configurable::configurable_component,
+    lookup::{OwnedTargetPath, path},
     stream::expiration_map::{Emitter, map_with_expiration},
 };
 use vrl::owned_value_path;
@@ -17,32 +18,151 @@ use vrl::owned_value_path;
 use crate::{
     event,
     event::{Event, LogEvent, Value},
-    internal_events::KubernetesMergedLineTooBigError,
-    sources::kubernetes_logs::transform_utils::get_message_path,
+    internal_events::{KubernetesMergedLineTooBigError, KubernetesMergedLineTooBigWarning},
+    line_agg,
+    sources::kubernetes_logs::{
+        path_helpers::parse_log_file_path, pod_metadata_annotator::FieldsSpec,
+        transform_utils::get_message_path,
+    },
 };
 
 /// The key we use for `file` field.
 const FILE_KEY: &str = "file";
 
+/// The key we use to mark events truncated to `max_merged_line_bytes`.
+pub(super) const TRUNCATED_KEY: &str = "truncated";
+
 const EXPIRATION_TIME: Duration = Duration::from_secs(30);
 
+/// The fields of events holding the pod UID and container name annotated from the pod metadata.
+#[derive(Clone, Debug)]
+pub struct PodIdentityFields {
+    pod_uid: Option<OwnedTargetPath>,
+    container_name: Option<OwnedTargetPath>,
+}
+
+impl PodIdentityFields {
+    /// Returns the fields the pod metadata annotator writes to with the given spec.
+    pub fn new(fields_spec: &FieldsSpec, log_namespace: LogNamespace) -> Self {
+        match log_namespace {
+            LogNamespace::Vector => Self {
+                pod_uid: Some(OwnedTargetPath::metadata(owned_value_path!(
+                    super::Config::NAME,
+                    "pod_uid"
+                ))),
+                container_name: Some(OwnedTargetPath::metadata(owned_value_path!(
+                    super::Config::NAME,
+                    "container_name"
+                ))),
+            },
+            LogNamespace::Legacy => Self {
+                pod_uid: fields_spec.pod_uid.path.clone(),
+                container_name: fields_spec.container_name.path.clone(),
+            },
+        }
+    }
+}
+
+/// Identifies the container instance a partial event originates from.
+///
+/// Read from the pod UID and container name annotated on the event, so that lines written by
+/// different pods or containers are never merged together, even if the file path itself ends up
+/// being reused. The pod log file path is only used for the parts that weren't annotated, such as
+/// when the pod is no longer known.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct PodIdentity {
+    pod_uid: String,
+    container_name: String,
+}
+
+impl PodIdentity {
+    fn from_event(event: &LogEvent, fields: &PodIdentityFields, file: &str) -> Self {
+        let annotated = |path: &Option<OwnedTargetPath>| {
+            path.as_ref()
+                .and_then(|path| event.get(path))
+                .and_then(Value::as_str)
+                .map(|value| value.into_owned())
+        };
+        let file_info = parse_log_file_path(file);
+        Self {
+            pod_uid: annotated(&fields.pod_uid)
+                .or_else(|| file_info.as_ref().map(|info| info.pod_uid.to_owned()))
+                .unwrap_or_default(),
+            container_name: annotated(&fields.container_name)
+                .or_else(|| {
+                    file_info
+                        .as_ref()
+                        .map(|info| info.container_name.to_owned())
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The key partial events are grouped by while they are being merged.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct BucketKey {
+    pod: PodIdentity,
+    file: String,
+}
+
+impl BucketKey {
+    fn new(event: &LogEvent, file_path: &OwnedTargetPath, pod_fields: &PodIdentityFields) -> Self {
+        let file = event
+            .get(file_path)
+            .and_then(|x| x.as_str())
+            .map(|x| x.to_string())
+            .unwrap_or_default();
+        Self {
+            pod: PodIdentity::from_event(event, pod_fields, &file),
+            file,
+        }
+    }
+}
+
+/// What to do with a line that exceeds `max_merged_line_bytes` once merged.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizeLineBehavior {
+    /// Drop the whole merged line.
+    #[default]
+    Drop,
+
+    /// Emit the merged line truncated to `max_merged_line_bytes`.
+    ///
+    /// The event is marked with a `truncated` metadata field set to `true`.
+    Truncate,
+
+    /// Emit the partial events making up the line individually, without merging them.
+    ///
+    /// Parts that were already merged before the limit was reached are emitted as a single event.
+    EmitRawParts,
+}
+
 struct PartialEventMergeState {
-    buckets: HashMap<String, Bucket>,
+    buckets: HashMap<BucketKey, Bucket>,
     maybe_max_merged_line_bytes: Option<usize>,
+    oversize_line_behavior: OversizeLineBehavior,
+    log_namespace: LogNamespace,
 }
 
 impl PartialEventMergeState {
     fn add_event(
         &mut self,
         event: LogEvent,
-        file: &str,
+        key: &BucketKey,
         message_path: &OwnedTargetPath,
         expiration_time: Duration,
+        emitter: &mut Emitter<LogEvent>,
     ) {
         let mut bytes_mut = BytesMut::new();
-        if let Some(bucket) = self.buckets.get_mut(file) {
-            // don't bother continuing to process new partial events that match existing ones that are already too big
+        if let Some(bucket) = self.buckets.get_mut(key) {
+            // don't bother continuing to merge new partial events that match existing ones that are already too big
             if bucket.exceeds_max_merged_line_limit {
+                if self.oversize_line_behavior == OversizeLineBehavior::EmitRawParts {
+                    emitter.emit(event);
+                }
                 return;
             }
 
@@ -54,17 +174,38 @@ impl PartialEventMergeState {
                 bytes_mut.extend_from_slice(prev_value);
                 bytes_mut.extend_from_slice(new_value);
 
-                // drop event if it's bigger than max allowed
                 if let Some(max_merged_line_bytes) = self.maybe_max_merged_line_bytes
                     && bytes_mut.len() > max_merged_line_bytes
                 {
                     bucket.exceeds_max_merged_line_limit = true;
-                    // perf impact of clone should be minimal since being here means no further processing of this event will occur
-                    emit!(KubernetesMergedLineTooBigError {
-                        event: &Value::Bytes(new_value.clone()),
-                        configured_limit: max_merged_line_bytes,
-                        encountered_size_so_far: bytes_mut.len()
-                    });
+                    // perf impact of clone should be minimal since being here means no further merging of this event will occur
+                    emit_merged_line_too_big(
+                        self.oversize_line_behavior,
+                        &Value::Bytes(new_value.clone()),
+                        &bucket.pod,
+                        max_merged_line_bytes,
+                        bytes_mut.len(),
+                    );
+
+                    match self.oversize_line_behavior {
+                        OversizeLineBehavior::Drop => {}
+                        OversizeLineBehavior::Truncate => {
+                            bytes_mut.truncate(max_merged_line_bytes);
+                            *prev_value = bytes_mut.freeze();
+                            self.log_namespace.insert_source_metadata(
+                                super::Config::NAME,
+                                &mut bucket.event,
+                                Some(LegacyKey::InsertIfEmpty(path!(TRUNCATED_KEY))),
+                                path!(TRUNCATED_KEY),
+                                true,
+                            );
+                        }
+                        OversizeLineBehavior::EmitRawParts => {
+                            emitter.emit(bucket.event.clone());
+                            emitter.emit(event);
+                        }
+                    }
+                    return;
                 }
 
                 *prev_value = bytes_mut.freeze();
@@ -72,28 +213,48 @@ impl PartialEventMergeState {
         } else {
             // new event
 
+            let mut event = event;
             let mut exceeds_max_merged_line_limit = false;
 
-            if let Some(Value::Bytes(event_bytes)) = event.get(message_path) {
+            if let Some(Value::Bytes(event_bytes)) = event.get_mut(message_path) {
                 bytes_mut.extend_from_slice(event_bytes);
                 if let Some(max_merged_line_bytes) = self.maybe_max_merged_line_bytes {
                     exceeds_max_merged_line_limit = bytes_mut.len() > max_merged_line_bytes;
 
                     if exceeds_max_merged_line_limit {
-                        // perf impact of clone should be minimal since being here means no further processing of this event will occur
-                        emit!(KubernetesMergedLineTooBigError {
-                            event: &Value::Bytes(event_bytes.clone()),
-                            configured_limit: max_merged_line_bytes,
-                            encountered_size_so_far: bytes_mut.len()
-                        });
+                        // perf impact of clone should be minimal since being here means no further merging of this event will occur
+                        emit_merged_line_too_big(
+                            self.oversize_line_behavior,
+                            &Value::Bytes(event_bytes.clone()),
+                            &key.pod,
+                            max_merged_line_bytes,
+                            bytes_mut.len(),
+                        );
+
+                        match self.oversize_line_behavior {
+                            OversizeLineBehavior::Drop => {}
+                            OversizeLineBehavior::Truncate => {
+                                bytes_mut.truncate(max_merged_line_bytes);
+                                *event_bytes = bytes_mut.freeze();
+                                self.log_namespace.insert_source_metadata(
+                                    super::Config::NAME,
+                                    &mut event,
+                                    Some(LegacyKey::InsertIfEmpty(path!(TRUNCATED_KEY))),
+                                    path!(TRUNCATED_KEY),
+                                    true,
+                                );
+                            }
+                            OversizeLineBehavior::EmitRawParts => emitter.emit(event.clone()),
+                        }
                     }
                 }
             }
 
             self.buckets.insert(
-                file.to_owned(),
+                key.clone(),
                 Bucket {
                     event,
+                    pod: key.pod.clone(),
                     expiration: Instant::now() + expiration_time,
                     exceeds_max_merged_line_limit,
                 },
@@ -101,18 +262,31 @@ impl PartialEventMergeState {
         }
     }
 
-    fn remove_event(&mut self, file: &str) -> Option<LogEvent> {
+    /// Whether the event held by the bucket still has to be emitted once the line is complete.
+    ///
+    /// Oversized lines are only kept around when they are being truncated, as dropped lines are
+    /// discarded and raw parts are emitted as soon as they are received.
+    fn should_emit(&self, bucket: &Bucket) -> bool {
+        !bucket.exceeds_max_merged_line_limit
+            || self.oversize_line_behavior == OversizeLineBehavior::Truncate
+    }
+
+    fn remove_event(&mut self, key: &BucketKey) -> Option<LogEvent> {
         self.buckets
-            .remove(file)
-            .filter(|bucket| !bucket.exceeds_max_merged_line_limit)
+            .remove(key)
+            .filter(|bucket| self.should_emit(bucket))
             .map(|bucket| bucket.event)
     }
 
     fn emit_expired_events(&mut self, emitter: &mut Emitter<LogEvent>) {
         let now = Instant::now();
+        let oversize_line_behavior = self.oversize_line_behavior;
         self.buckets.retain(|_key, bucket| {
             let expired = now >= bucket.expiration;
-            if expired && !bucket.exceeds_max_merged_line_limit {
+            if expired
+                && (!bucket.exceeds_max_merged_line_limit
+                    || oversize_line_behavior == OversizeLineBehavior::Truncate)
+            {
                 emitter.emit(bucket.event.clone());
             }
             !expired
@@ -120,16 +294,47 @@ impl PartialEventMergeState {
     }
 
     fn flush_events(&mut self, emitter: &mut Emitter<LogEvent>) {
-        for (_, bucket) in self.buckets.drain() {
-            if !bucket.exceeds_max_merged_line_limit {
+        let buckets = std::mem::take(&mut self.buckets);
+        for (_, bucket) in buckets {
+            if self.should_emit(&bucket) {
                 emitter.emit(bucket.event);
             }
         }
     }
 }
 
+fn emit_merged_line_too_big(
+    oversize_line_behavior: OversizeLineBehavior,
+    event: &Value,
+    pod: &PodIdentity,
+    configured_limit: usize,
+    encountered_size_so_far: usize,
+) {
+    match oversize_line_behavior {
+        OversizeLineBehavior::Drop => emit!(KubernetesMergedLineTooBigError {
+            event,
+            pod_uid: &pod.pod_uid,
+            container_name: &pod.container_name,
+            configured_limit,
+            encountered_size_so_far,
+        }),
+        OversizeLineBehavior::Truncate | OversizeLineBehavior::EmitRawParts => {
+            emit!(KubernetesMergedLineTooBigWarning {
+                event,
+                pod_uid: &pod.pod_uid,
+                container_name: &pod.container_name,
+                configured_limit,
+                encountered_size_so_far,
+                truncated: oversize_line_behavior == OversizeLineBehavior::Truncate,
+            })
+        }
+    }
+}
+
 struct Bucket {
     event: LogEvent,
+    /// The container instance all events merged into this bucket belong to.
+    pod: PodIdentity,
     expiration: Instant,
     exceeds_max_merged_line_limit: bool,
 }
@@ -137,13 +342,17 @@ struct Bucket {
 pub fn merge_partial_events(
     stream: impl Stream<Item = Event> + 'static,
     log_namespace: LogNamespace,
+    pod_fields: PodIdentityFields,
     maybe_max_merged_line_bytes: Option<usize>,
+    oversize_line_behavior: OversizeLineBehavior,
 ) -> impl Stream<Item = Event> {
     merge_partial_events_with_custom_expiration(
         stream,
         log_namespace,
+        pod_fields,
         EXPIRATION_TIME,
         maybe_max_merged_line_bytes,
+        oversize_line_behavior,
     )
 }
 
@@ -151,8 +360,10 @@ pub fn merge_partial_events(
 fn merge_partial_events_with_custom_expiration(
     stream: impl Stream<Item = Event> + 'static,
     log_namespace: LogNamespace,
+    pod_fields: PodIdentityFields,
     expiration_time: Duration,
     maybe_max_merged_line_bytes: Option<usize>,
+    oversize_line_behavior: OversizeLineBehavior,
 ) -> impl Stream<Item = Event> {
     let partial_flag_path = match log_namespace {
         LogNamespace::Vector => {
@@ -161,16 +372,13 @@ fn merge_partial_events_with_custom_expiration(
         LogNamespace::Legacy => OwnedTargetPath::event(owned_value_path!(event::PARTIAL)),
     };
 
-    let file_path = match log_namespace {
-        LogNamespace::Vector => {
-            OwnedTargetPath::metadata(owned_value_path!(super::Config::NAME, FILE_KEY))
-        }
-        LogNamespace::Legacy => OwnedTargetPath::event(owned_value_path!(FILE_KEY)),
-    };
+    let file_path = file_path(log_namespace);
 
     let state = PartialEventMergeState {
         buckets: HashMap::new(),
         maybe_max_merged_line_bytes,
+        oversize_line_behavior,
+        log_namespace,
     };
 
     let message_path = get_message_path(log_namespace);
@@ -188,14 +396,10 @@ fn merge_partial_events_with_custom_expiration(
                 .and_then(|x| x.as_boolean())
                 .unwrap_or(false);
 
-            let file = event
-                .get(&file_path)
-                .and_then(|x| x.as_str())
-                .map(|x| x.to_string())
-                .unwrap_or_default();
+            let key = BucketKey::new(&event, &file_path, &pod_fields);
 
-            state.add_event(event, &file, &message_path, expiration_time);
-            if !is_partial && let Some(log_event) = state.remove_event(&file) {
+            state.add_event(event, &key, &message_path, expiration_time, emitter);
+            if !is_partial && let Some(log_event) = state.remove_event(&key) {
                 emitter.emit(log_event);
             }
         },
@@ -212,6 +416,143 @@ fn merge_partial_events_with_custom_expiration(
     .map(|e| e.into())
 }
 
+struct MultilineAggregationState {
+    buckets: HashMap<BucketKey, Bucket>,
+    config: line_agg::Config,
+}
+
+impl MultilineAggregationState {
+    fn add_event(
+        &mut self,
+        event: LogEvent,
+        key: BucketKey,
+        message_path: &OwnedTargetPath,
+        emitter: &mut Emitter<LogEvent>,
+    ) {
+        let Some(line) = event.get(message_path).and_then(Value::as_bytes).cloned() else {
+            // Not a line we can aggregate, make sure it isn't reordered with the pending one.
+            if let Some(bucket) = self.buckets.remove(&key) {
+                emitter.emit(bucket.event);
+            }
+            emitter.emit(event);
+            return;
+        };
+
+        if let Entry::Occupied(mut entry) = self.buckets.entry(key.clone()) {
+            match self.config.decide(&line) {
+                line_agg::Decision::Continue => {
+                    let bucket = entry.get_mut();
+                    append_line(&mut bucket.event, message_path, &line);
+                    bucket.expiration = Instant::now() + self.config.timeout;
+                    return;
+                }
+                line_agg::Decision::EndInclude => {
+                    let mut bucket = entry.remove();
+                    append_line(&mut bucket.event, message_path, &line);
+                    emitter.emit(bucket.event);
+                    return;
+                }
+                line_agg::Decision::EndExclude => {
+                    emitter.emit(entry.remove().event);
+                    // The line may start the next group, handle it as a new one.
+                }
+            }
+        }
+
+        if self.config.start_pattern.is_match(&line) {
+            let pod = key.pod.clone();
+            self.buckets.insert(
+                key,
+                Bucket {
+                    event,
+                    pod,
+                    expiration: Instant::now() + self.config.timeout,
+                    exceeds_max_merged_line_limit: false,
+                },
+            );
+        } else {
+            emitter.emit(event);
+        }
+    }
+
+    fn emit_expired_events(&mut self, emitter: &mut Emitter<LogEvent>) {
+        let now = Instant::now();
+        self.buckets.retain(|_key, bucket| {
+            let expired = now >= bucket.expiration;
+            if expired {
+                emitter.emit(bucket.event.clone());
+            }
+            !expired
+        });
+    }
+
+    fn flush_events(&mut self, emitter: &mut Emitter<LogEvent>) {
+        for (_, bucket) in self.buckets.drain() {
+            emitter.emit(bucket.event);
+        }
+    }
+}
+
+fn append_line(event: &mut LogEvent, message_path: &OwnedTargetPath, line: &Bytes) {
+    if let Some(Value::Bytes(message)) = event.get_mut(message_path) {
+        let mut bytes_mut = BytesMut::with_capacity(message.len() + 1 + line.len());
+        bytes_mut.extend_from_slice(message);
+        bytes_mut.extend_from_slice(b"\n");
+        bytes_mut.extend_from_slice(line);
+        *message = bytes_mut.freeze();
+    }
+}
+
+/// Aggregates consecutive lines of the same container into a single event, as configured by
+/// `multiline`.
+///
+/// The aggregated event keeps the fields of the event the first line came from.
+pub fn aggregate_multiline_events(
+    stream: impl Stream<Item = Event> + 'static,
+    log_namespace: LogNamespace,
+    pod_fields: PodIdentityFields,
+    config: line_agg::Config,
+) -> impl Stream<Item = Event> {
+    let file_path = file_path(log_namespace);
+    let message_path = get_message_path(log_namespace);
+    let expiration_interval = config
+        .timeout
+        .clamp(Duration::from_millis(100), Duration::from_secs(1));
+
+    let state = MultilineAggregationState {
+        buckets: HashMap::new(),
+        config,
+    };
+
+    map_with_expiration(
+        state,
+        stream.map(|e| e.into_log()),
+        expiration_interval,
+        move |state: &mut MultilineAggregationState,
+              event: LogEvent,
+              emitter: &mut Emitter<LogEvent>| {
+            let key = BucketKey::new(&event, &file_path, &pod_fields);
+            state.add_event(event, key, &message_path, emitter);
+        },
+        |state: &mut MultilineAggregationState, emitter: &mut Emitter<LogEvent>| {
+            state.emit_expired_events(emitter)
+        },
+        |state: &mut MultilineAggregationState, emitter: &mut Emitter<LogEvent>| {
+            state.flush_events(emitter);
+        },
+    )
+    .map(|e| e.into())
+}
+
+pub(super) fn file_path(log_namespace: LogNamespace) -> OwnedTargetPath {
+    match log_namespace {
+        LogNamespace::Vector => {
+            OwnedTargetPath::metadata(owned_value_path!(super::Config::NAME, FILE_KEY))
+        }
+        LogNamespace::Legacy => OwnedTargetPath::event(owned_value_path!(FILE_KEY)),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use vector_lib::event::LogEvent;
@@ -219,13 +560,23 @@ mod test {
 
     use super::*;
 
+    fn pod_fields(log_namespace: LogNamespace) -> PodIdentityFields {
+        PodIdentityFields::new(&FieldsSpec::default(), log_namespace)
+    }
+
     #[tokio::test]
     async fn merge_single_event_legacy() {
         let mut e_1 = LogEvent::from("test message 1");
         e_1.insert("foo", 1);
 
         let input_stream = futures::stream::iter([e_1.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            None,
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -241,7 +592,13 @@ mod test {
         e_1.insert("foo", 1);
 
         let input_stream = futures::stream::iter([e_1.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, Some(1));
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            Some(1),
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 0);
@@ -257,7 +614,13 @@ mod test {
         e_2.insert("foo2", 1);
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            None,
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -278,7 +641,13 @@ mod test {
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
         // 24 > length of first message but less than the two combined
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, Some(24));
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            Some(24),
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 0);
@@ -295,7 +664,13 @@ mod test {
         e_1.insert("_partial", true);
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            None,
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -317,12 +692,105 @@ mod test {
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
         // 24 > length of first message but less than the two combined
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, Some(24));
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            Some(24),
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 0);
     }
 
+    #[tokio::test]
+    async fn merge_multiple_events_legacy_exceeds_max_merged_line_limit_truncate() {
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("foo", 1);
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("foo2", 1);
+
+        let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            Some(24),
+            OversizeLineBehavior::Truncate,
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            output[0].as_log().get(".message"),
+            Some(&value!("test message 1test messa"))
+        );
+        assert_eq!(output[0].as_log().get(".truncated"), Some(&value!(true)));
+    }
+
+    #[tokio::test]
+    async fn merge_single_event_vector_namespace_exceeds_max_merged_line_limit_truncate() {
+        let e_1 = LogEvent::from(value!("test message 1"));
+
+        let input_stream = futures::stream::iter([e_1.into()]);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Vector,
+            pod_fields(LogNamespace::Vector),
+            Some(4),
+            OversizeLineBehavior::Truncate,
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log().get("."), Some(&value!("test")));
+        assert_eq!(
+            output[0].as_log().get("%kubernetes_logs.truncated"),
+            Some(&value!(true))
+        );
+    }
+
+    #[tokio::test]
+    async fn merge_multiple_events_legacy_exceeds_max_merged_line_limit_emit_raw_parts() {
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("_partial", true);
+
+        let mut e_3 = LogEvent::from("test message 3");
+        e_3.insert("_partial", true);
+
+        let e_4 = LogEvent::from("test message 4");
+
+        let input_stream = futures::stream::iter([e_1.into(), e_2.into(), e_3.into(), e_4.into()]);
+        // 30 > length of the first two messages but less than the first three combined
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            Some(30),
+            OversizeLineBehavior::EmitRawParts,
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        let messages = output
+            .iter()
+            .map(|event| event.as_log().get(".message").cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                Some(value!("test message 1test message 2")),
+                Some(value!("test message 3")),
+                Some(value!("test message 4")),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn multiple_events_expire_legacy() {
         let mut e_1 = LogEvent::from("test message");
@@ -340,8 +808,10 @@ mod test {
         let output_stream = merge_partial_events_with_custom_expiration(
             input_stream,
             LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
             Duration::from_secs(1),
             None,
+            OversizeLineBehavior::Drop,
         );
 
         let output: Vec<Event> = output_stream.take(2).collect().await;
@@ -356,6 +826,117 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn merge_does_not_cross_pods_legacy() {
+        let mut e_1 = LogEvent::from("pod 1 message");
+        e_1.insert(FILE_KEY, "/var/log/pods/ns_name_uid1/container/0.log");
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("pod 2 message");
+        e_2.insert(FILE_KEY, "/var/log/pods/ns_name_uid2/container/0.log");
+
+        let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            None,
+            OversizeLineBehavior::Drop,
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get(".message"),
+            Some(&value!("pod 2 message"))
+        );
+        assert_eq!(
+            output[1].as_log().get(".message"),
+            Some(&value!("pod 1 message"))
+        );
+    }
+
+    #[tokio::test]
+    async fn merge_does_not_cross_annotated_pods_legacy() {
+        // A pod recreated with the same name reuses the log file path of the previous one.
+        let mut e_1 = LogEvent::from("pod 1 message");
+        e_1.insert(FILE_KEY, "foo1");
+        e_1.insert("kubernetes.pod_uid", "uid1");
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("pod 2 message");
+        e_2.insert(FILE_KEY, "foo1");
+        e_2.insert("kubernetes.pod_uid", "uid2");
+
+        let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            None,
+            OversizeLineBehavior::Drop,
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get(".message"),
+            Some(&value!("pod 2 message"))
+        );
+        assert_eq!(
+            output[1].as_log().get(".message"),
+            Some(&value!("pod 1 message"))
+        );
+    }
+
+    #[test]
+    fn bucket_key_pod_identity() {
+        let file_path = file_path(LogNamespace::Legacy);
+        let fields = pod_fields(LogNamespace::Legacy);
+
+        let mut event = LogEvent::from("message");
+        event.insert(FILE_KEY, "/var/log/pods/ns_name_uid/container/1.log");
+        let key = BucketKey::new(&event, &file_path, &fields);
+        assert_eq!(key.pod.pod_uid, "uid");
+        assert_eq!(key.pod.container_name, "container");
+
+        event.insert("kubernetes.pod_uid", "annotated-uid");
+        event.insert("kubernetes.container_name", "annotated-container");
+        let key = BucketKey::new(&event, &file_path, &fields);
+        assert_eq!(key.pod.pod_uid, "annotated-uid");
+        assert_eq!(key.pod.container_name, "annotated-container");
+
+        let mut event = LogEvent::from("message");
+        event.insert(FILE_KEY, "foo1");
+        let key = BucketKey::new(&event, &file_path, &fields);
+        assert_eq!(key.pod, PodIdentity::default());
+    }
+
+    #[test]
+    fn bucket_key_pod_identity_vector_namespace() {
+        let mut event = LogEvent::from(value!("message"));
+        event.insert(
+            vrl::metadata_path!(super::super::Config::NAME, FILE_KEY),
+            "foo1",
+        );
+        event.insert(
+            vrl::metadata_path!(super::super::Config::NAME, "pod_uid"),
+            "uid",
+        );
+        event.insert(
+            vrl::metadata_path!(super::super::Config::NAME, "container_name"),
+            "container",
+        );
+
+        let key = BucketKey::new(
+            &event,
+            &file_path(LogNamespace::Vector),
+            &pod_fields(LogNamespace::Vector),
+        );
+        assert_eq!(key.pod.pod_uid, "uid");
+        assert_eq!(key.pod.container_name, "container");
+    }
+
     #[tokio::test]
     async fn merge_single_event_vector_namespace() {
         let mut e_1 = LogEvent::from(value!("test message 1"));
@@ -365,7 +946,13 @@ mod test {
         );
 
         let input_stream = futures::stream::iter([e_1.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Vector, None);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Vector,
+            pod_fields(LogNamespace::Vector),
+            None,
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -395,7 +982,13 @@ mod test {
         );
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Vector, None);
+        let output_stream = merge_partial_events(
+            input_stream,
+            LogNamespace::Vector,
+            pod_fields(LogNamespace::Vector),
+            None,
+            OversizeLineBehavior::Drop,
+        );
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -408,4 +1001,115 @@ mod test {
             Some(&value!("foo1"))
         );
     }
+
+    fn multiline_config(mode: line_agg::Mode, timeout: Duration) -> line_agg::Config {
+        line_agg::Config {
+            start_pattern: regex::bytes::Regex::new(r"^[^\s]").unwrap(),
+            condition_pattern: regex::bytes::Regex::new(r"^[\s]+").unwrap(),
+            mode,
+            timeout,
+        }
+    }
+
+    fn multiline_events(lines: &[(&str, &str)]) -> Vec<Event> {
+        lines
+            .iter()
+            .map(|(file, line)| {
+                let mut event = LogEvent::from(*line);
+                event.insert(FILE_KEY, *file);
+                event.into()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn aggregate_multiline_continue_through_legacy() {
+        let input_stream = futures::stream::iter(multiline_events(&[
+            ("a.log", "Exception in thread main"),
+            ("b.log", "unrelated"),
+            ("a.log", "    at com.foo.Bar.baz(Bar.java:1)"),
+            ("a.log", "    at com.foo.Main.main(Main.java:2)"),
+            ("a.log", "next message"),
+        ]));
+        let output_stream = aggregate_multiline_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            multiline_config(line_agg::Mode::ContinueThrough, Duration::from_secs(30)),
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        let messages = output
+            .iter()
+            .map(|event| event.as_log().get(".message").cloned().unwrap())
+            .collect::<Vec<_>>();
+        // `b.log` and the last line of `a.log` only start a group, which is flushed at the end.
+        assert_eq!(
+            messages[0],
+            value!(
+                "Exception in thread main\n    at com.foo.Bar.baz(Bar.java:1)\n    at com.foo.Main.main(Main.java:2)"
+            )
+        );
+        assert_eq!(messages.len(), 3);
+        assert!(messages.contains(&value!("unrelated")));
+        assert!(messages.contains(&value!("next message")));
+    }
+
+    #[tokio::test]
+    async fn aggregate_multiline_passes_through_unmatched_lines_legacy() {
+        let input_stream = futures::stream::iter(multiline_events(&[
+            ("a.log", "  orphan continuation"),
+            ("a.log", "first"),
+            ("a.log", "second"),
+        ]));
+        let output_stream = aggregate_multiline_events(
+            input_stream,
+            LogNamespace::Legacy,
+            pod_fields(LogNamespace::Legacy),
+            multiline_config(line_agg::Mode::ContinueThrough, Duration::from_secs(30)),
+        );
+
+        let output: Vec<Event> = output_stream.collect().await;
+        let messages = output
+            .iter()
+            .map(|event| event.as_log().get(".message").cloned().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                value!("  orphan continuation"),
+                value!("first"),
+                value!("second")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregate_multiline_flushes_on_timeout_vector_namespace() {
+        let mut e_1 = LogEvent::from(value!("start"));
+        e_1.insert(
+            vrl::metadata_path!(super::super::Config::NAME, FILE_KEY),
+            "a.log",
+        );
+        let mut e_2 = LogEvent::from(value!("  continued"));
+        e_2.insert(
+            vrl::metadata_path!(super::super::Config::NAME, FILE_KEY),
+            "a.log",
+        );
+
+        let input_stream =
+            futures::stream::iter([e_1.into(), e_2.into()]).chain(futures::stream::pending());
+        let mut output_stream = Box::pin(aggregate_multiline_events(
+            input_stream,
+            LogNamespace::Vector,
+            pod_fields(LogNamespace::Vector),
+            multiline_config(line_agg::Mode::ContinueThrough, Duration::from_millis(10)),
+        ));
+
+        let event = tokio::time::timeout(Duration::from_secs(5), output_stream.next())
+            .await
+            .expect("aggregated event was not flushed after the timeout")
+            .unwrap();
+        assert_eq!(event.as_log().value(), &value!("start\n  continued"));
+    }
 }
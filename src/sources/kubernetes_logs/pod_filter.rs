@@ -0,0 +1,275 @@
+//! Filters the Pods and the containers whose logs are collected by their metadata.
+
+#![deny(missing_docs)]
+
+use std::path::Path;
+
+use k8s_openapi::api::core::v1::Pod;
+use regex::Regex;
+use vector_lib::configurable::configurable_component;
+
+/// The label set on the Pods of the `ReplicaSet`s managed by a `Deployment`.
+const POD_TEMPLATE_HASH_LABEL: &str = "pod-template-hash";
+
+/// The kind of the workload of the Pods without a controller.
+const BARE_POD_KIND: &str = "Pod";
+
+/// Configuration for filtering the Pods and the containers whose logs are collected.
+///
+/// The rules are evaluated against the metadata of the Pods before their log files are discovered,
+/// so the logs of the filtered out Pods and containers are never read. An exclusion rule always
+/// takes precedence over an inclusion one, and an empty list of inclusion rules includes
+/// everything.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct PodFilterConfig {
+    /// Regular expressions matched against the Namespace of the Pods whose logs are collected.
+    #[configurable(metadata(docs::examples = "^team-"))]
+    pub include_namespaces: Vec<String>,
+
+    /// Regular expressions matched against the Namespace of the Pods whose logs aren't collected.
+    #[configurable(metadata(docs::examples = "^kube-"))]
+    #[configurable(metadata(docs::examples = "-staging$"))]
+    pub exclude_namespaces: Vec<String>,
+
+    /// The kinds of the workloads whose logs are collected.
+    ///
+    /// The kind of the workload of a Pod is the kind of its controller, as found in its owner
+    /// references, where the Pods of the `ReplicaSet`s managed by a `Deployment` have the
+    /// `Deployment` kind, and the Pods without a controller have the `Pod` kind. Kinds are matched
+    /// case-insensitively.
+    #[configurable(metadata(docs::examples = "Deployment"))]
+    #[configurable(metadata(docs::examples = "StatefulSet"))]
+    pub include_workload_kinds: Vec<String>,
+
+    /// The kinds of the workloads whose logs aren't collected.
+    ///
+    /// See `include_workload_kinds` for how the kind of the workload of a Pod is derived.
+    #[configurable(metadata(docs::examples = "DaemonSet"))]
+    #[configurable(metadata(docs::examples = "Job"))]
+    pub exclude_workload_kinds: Vec<String>,
+
+    /// Glob patterns matched against the name of the containers whose logs are collected.
+    #[configurable(metadata(docs::examples = "app-*"))]
+    pub include_containers: Vec<String>,
+
+    /// Glob patterns matched against the name of the containers whose logs aren't collected.
+    #[configurable(metadata(docs::examples = "istio-*"))]
+    #[configurable(metadata(docs::examples = "linkerd-proxy"))]
+    pub exclude_containers: Vec<String>,
+}
+
+impl PodFilterConfig {
+    /// Compiles the rules of the filter.
+    pub fn build(&self) -> crate::Result<PodFilter> {
+        let regexes = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|error| {
+                        format!("Invalid namespace pattern `{pattern}` in `pod_filter`: {error}")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let globs = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(pattern).map_err(|error| {
+                        format!("Invalid container pattern `{pattern}` in `pod_filter`: {error}")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(PodFilter {
+            include_namespaces: regexes(&self.include_namespaces)?,
+            exclude_namespaces: regexes(&self.exclude_namespaces)?,
+            include_workload_kinds: self.include_workload_kinds.clone(),
+            exclude_workload_kinds: self.exclude_workload_kinds.clone(),
+            include_containers: globs(&self.include_containers)?,
+            exclude_containers: globs(&self.exclude_containers)?,
+        })
+    }
+}
+
+/// The compiled rules filtering the Pods and the containers whose logs are collected.
+#[derive(Clone, Debug, Default)]
+pub struct PodFilter {
+    include_namespaces: Vec<Regex>,
+    exclude_namespaces: Vec<Regex>,
+    include_workload_kinds: Vec<String>,
+    exclude_workload_kinds: Vec<String>,
+    include_containers: Vec<glob::Pattern>,
+    exclude_containers: Vec<glob::Pattern>,
+}
+
+impl PodFilter {
+    /// Checks whether the logs of the Pod are collected, given its Namespace and workload kind.
+    pub fn matches_pod(&self, pod: &Pod) -> bool {
+        let namespace = pod.metadata.namespace.as_deref().unwrap_or_default();
+        if !filter(
+            &self.include_namespaces,
+            &self.exclude_namespaces,
+            |regex| regex.is_match(namespace),
+        ) {
+            return false;
+        }
+
+        if self.include_workload_kinds.is_empty() && self.exclude_workload_kinds.is_empty() {
+            return true;
+        }
+        let kind = extract_workload_kind(pod);
+        filter(
+            &self.include_workload_kinds,
+            &self.exclude_workload_kinds,
+            |rule| rule.eq_ignore_ascii_case(kind),
+        )
+    }
+
+    /// Checks whether the logs of the container of a log file are collected, given its name.
+    ///
+    /// The log files are expected at `<pod_logs_dir>/<container_name>/<n>.log`.
+    pub fn matches_container_path(&self, path: &Path) -> bool {
+        if self.include_containers.is_empty() && self.exclude_containers.is_empty() {
+            return true;
+        }
+        let Some(container_name) = path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+        else {
+            return false;
+        };
+        filter(
+            &self.include_containers,
+            &self.exclude_containers,
+            |pattern| pattern.matches(container_name),
+        )
+    }
+}
+
+fn filter<T>(include: &[T], exclude: &[T], matches: impl Fn(&T) -> bool) -> bool {
+    (include.is_empty() || include.iter().any(&matches)) && !exclude.iter().any(&matches)
+}
+
+/// Derives the kind of the workload of a Pod from its controller.
+fn extract_workload_kind(pod: &Pod) -> &str {
+    let controller = pod
+        .metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .find(|owner| owner.controller == Some(true));
+    match controller {
+        Some(owner)
+            if owner.kind == "ReplicaSet"
+                && pod
+                    .metadata
+                    .labels
+                    .as_ref()
+                    .is_some_and(|labels| labels.contains_key(POD_TEMPLATE_HASH_LABEL)) =>
+        {
+            "Deployment"
+        }
+        Some(owner) => &owner.kind,
+        None => BARE_POD_KIND,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+
+    use super::*;
+
+    fn pod(namespace: &str, owner_kind: Option<&str>, labels: &[&str]) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                namespace: Some(namespace.to_owned()),
+                owner_references: owner_kind.map(|kind| {
+                    vec![OwnerReference {
+                        kind: kind.to_owned(),
+                        controller: Some(true),
+                        ..Default::default()
+                    }]
+                }),
+                labels: Some(
+                    labels
+                        .iter()
+                        .map(|label| (label.to_string(), "value".to_owned()))
+                        .collect::<BTreeMap<_, _>>(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_workload_kind() {
+        let cases = [
+            (pod("ns", None, &[]), "Pod"),
+            (pod("ns", Some("DaemonSet"), &[]), "DaemonSet"),
+            (pod("ns", Some("ReplicaSet"), &[]), "ReplicaSet"),
+            (
+                pod("ns", Some("ReplicaSet"), &[POD_TEMPLATE_HASH_LABEL]),
+                "Deployment",
+            ),
+        ];
+        for (pod, expected) in cases {
+            assert_eq!(extract_workload_kind(&pod), expected);
+        }
+    }
+
+    #[test]
+    fn filters_pods_and_containers() {
+        let filter = PodFilterConfig {
+            include_namespaces: vec!["^team-".to_owned()],
+            exclude_namespaces: vec!["-staging$".to_owned()],
+            exclude_workload_kinds: vec!["job".to_owned()],
+            exclude_containers: vec!["istio-*".to_owned()],
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        for (pod, expected) in [
+            (pod("team-a", Some("StatefulSet"), &[]), true),
+            (pod("team-a", None, &[]), true),
+            (pod("team-a", Some("Job"), &[]), false),
+            (pod("team-a-staging", Some("StatefulSet"), &[]), false),
+            (pod("kube-system", Some("DaemonSet"), &[]), false),
+        ] {
+            assert_eq!(filter.matches_pod(&pod), expected, "{pod:?}");
+        }
+
+        let dir = "/var/log/pods/team-a_app-0_uid";
+        for (path, expected) in [
+            (format!("{dir}/app/0.log"), true),
+            (format!("{dir}/istio-proxy/0.log"), false),
+        ] {
+            assert_eq!(filter.matches_container_path(Path::new(&path)), expected);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        for config in [
+            PodFilterConfig {
+                include_namespaces: vec!["(".to_owned()],
+                ..Default::default()
+            },
+            PodFilterConfig {
+                exclude_containers: vec!["[".to_owned()],
+                ..Default::default()
+            },
+        ] {
+            assert!(config.build().is_err());
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Limits the rate of the lines read from the log files of each container.
+
+#![deny(missing_docs)]
+
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::{Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use k8s_openapi::api::core::v1::Pod;
+use kube::runtime::reflector::{ObjectRef, store::Store};
+use vector_lib::{config::LogNamespace, lookup::OwnedTargetPath};
+
+use super::{
+    partial_events_merger::file_path,
+    path_helpers::{LogFileInfo, parse_log_file_path},
+};
+use crate::{event::Event, internal_events::KubernetesLogsLinesRateLimited};
+
+/// The annotation overriding the rate limit of the containers of a Pod.
+const RATE_LIMIT_ANNOTATION_KEY: &str = "vector.dev/rate-limit-lines-per-sec";
+
+/// The window over which the lines of a container are counted.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// How long the state of a container is kept after its last line.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The lines counted for a container in the current window.
+struct ContainerState {
+    window_start: Instant,
+    lines: u32,
+    dropped: u64,
+}
+
+/// The rate limit of a Pod, and the lines counted for its containers.
+struct PodState {
+    namespace: String,
+    name: String,
+    limit: Option<NonZeroU32>,
+    limit_resolved: Instant,
+    containers: HashMap<String, ContainerState>,
+}
+
+/// Limits the rate of the lines of each container, dropping the lines exceeding the rate limit of
+/// their Pod.
+///
+/// The rate limit of a Pod defaults to the configured one, and can be overridden with the
+/// `vector.dev/rate-limit-lines-per-sec` annotation, where `0` disables the rate limiting of the
+/// Pod. The lines are counted once partial lines have been merged, so that the parts of a line
+/// are kept or dropped together.
+pub struct RateLimiter {
+    pods_state_reader: Store<Pod>,
+    default_limit: Option<NonZeroU32>,
+    file_path: OwnedTargetPath,
+    /// The state of the Pods, by UID.
+    pods: HashMap<String, PodState>,
+}
+
+impl RateLimiter {
+    /// Create a new [`RateLimiter`].
+    pub fn new(
+        pods_state_reader: Store<Pod>,
+        default_limit: Option<NonZeroU32>,
+        log_namespace: LogNamespace,
+    ) -> Self {
+        Self {
+            pods_state_reader,
+            default_limit,
+            file_path: file_path(log_namespace),
+            pods: HashMap::new(),
+        }
+    }
+
+    /// Checks whether an event is within the rate limit of the Pod it was read from, counting it
+    /// if so. The events that weren't read from the log file of a Pod are always allowed.
+    pub fn allow(&mut self, event: &Event) -> bool {
+        let file = event
+            .as_log()
+            .get(&self.file_path)
+            .and_then(|file| file.as_str());
+        match file.as_deref().and_then(parse_log_file_path) {
+            Some(file_info) => self.allow_at(&file_info, Instant::now()),
+            None => true,
+        }
+    }
+
+    fn allow_at(&mut self, file_info: &LogFileInfo<'_>, now: Instant) -> bool {
+        if !self.pods.contains_key(file_info.pod_uid) {
+            let pod = PodState {
+                namespace: file_info.pod_namespace.to_owned(),
+                name: file_info.pod_name.to_owned(),
+                limit: resolve_limit(
+                    &self.pods_state_reader,
+                    self.default_limit,
+                    file_info.pod_namespace,
+                    file_info.pod_name,
+                ),
+                limit_resolved: now,
+                containers: HashMap::new(),
+            };
+            self.pods.insert(file_info.pod_uid.to_owned(), pod);
+        }
+        let pod = self
+            .pods
+            .get_mut(file_info.pod_uid)
+            .expect("pod state was just inserted");
+
+        // The annotations of the Pod can change while it's running.
+        if now.duration_since(pod.limit_resolved) >= WINDOW {
+            pod.limit = resolve_limit(
+                &self.pods_state_reader,
+                self.default_limit,
+                &pod.namespace,
+                &pod.name,
+            );
+            pod.limit_resolved = now;
+        }
+        let Some(limit) = pod.limit else {
+            return true;
+        };
+
+        if !pod.containers.contains_key(file_info.container_name) {
+            pod.containers.insert(
+                file_info.container_name.to_owned(),
+                ContainerState {
+                    window_start: now,
+                    lines: 0,
+                    dropped: 0,
+                },
+            );
+        }
+        let container = pod
+            .containers
+            .get_mut(file_info.container_name)
+            .expect("container state was just inserted");
+        if now.duration_since(container.window_start) >= WINDOW {
+            container.window_start = now;
+            container.lines = 0;
+        }
+
+        if container.lines < limit.get() {
+            container.lines += 1;
+            true
+        } else {
+            container.dropped += 1;
+            false
+        }
+    }
+
+    /// Reports the lines dropped since the last flush, and forgets the idle containers.
+    pub fn flush(&mut self) {
+        self.flush_at(Instant::now());
+    }
+
+    fn flush_at(&mut self, now: Instant) {
+        for pod in self.pods.values_mut() {
+            pod.containers.retain(|container_name, container| {
+                if container.dropped > 0 {
+                    emit!(KubernetesLogsLinesRateLimited {
+                        pod_namespace: &pod.namespace,
+                        pod_name: &pod.name,
+                        container_name,
+                        count: container.dropped,
+                    });
+                    container.dropped = 0;
+                }
+                now.duration_since(container.window_start) < IDLE_TIMEOUT
+            });
+        }
+        self.pods.retain(|_, pod| {
+            !pod.containers.is_empty() || now.duration_since(pod.limit_resolved) < IDLE_TIMEOUT
+        });
+    }
+}
+
+impl Drop for RateLimiter {
+    fn drop(&mut self) {
+        // Report the lines dropped since the last flush.
+        self.flush();
+    }
+}
+
+/// Flushes a rate limiter every window, so that the dropped lines are reported even when no more
+/// lines are read, until the rate limiter is dropped.
+pub async fn flush_periodically(rate_limiter: Weak<Mutex<RateLimiter>>) {
+    let mut interval = tokio::time::interval(WINDOW);
+    loop {
+        interval.tick().await;
+        let Some(rate_limiter) = rate_limiter.upgrade() else {
+            break;
+        };
+        rate_limiter.lock().expect("lock poisoned").flush();
+    }
+}
+
+/// Resolves the rate limit of a Pod from its annotation, falling back to the default one.
+fn resolve_limit(
+    pods_state_reader: &Store<Pod>,
+    default_limit: Option<NonZeroU32>,
+    namespace: &str,
+    name: &str,
+) -> Option<NonZeroU32> {
+    let pod = pods_state_reader.get(&ObjectRef::<Pod>::new(name).within(namespace));
+    let annotation = pod.as_ref().and_then(|pod| {
+        pod.metadata
+            .annotations
+            .as_ref()?
+            .get(RATE_LIMIT_ANNOTATION_KEY)?
+            .trim()
+            .parse::<u32>()
+            .ok()
+    });
+    match annotation {
+        Some(limit) => NonZeroU32::new(limit),
+        None => default_limit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kube::runtime::{reflector::store::Writer, watcher};
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn pod(name: &str, limit: Option<&str>) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                namespace: Some("ns".to_owned()),
+                name: Some(name.to_owned()),
+                annotations: limit.map(|limit| {
+                    BTreeMap::from([(RATE_LIMIT_ANNOTATION_KEY.to_owned(), limit.to_owned())])
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn file_info<'a>(pod_name: &'a str, container_name: &'a str) -> LogFileInfo<'a> {
+        LogFileInfo {
+            pod_namespace: "ns",
+            pod_name,
+            pod_uid: pod_name,
+            container_name,
+        }
+    }
+
+    fn allowed(limiter: &mut RateLimiter, file_info: &LogFileInfo<'_>, now: Instant) -> usize {
+        (0..10).filter(|_| limiter.allow_at(file_info, now)).count()
+    }
+
+    #[test]
+    fn limits_lines_per_container() {
+        let mut writer = Writer::<Pod>::default();
+        for pod in [
+            pod("default", None),
+            pod("annotated", Some("2")),
+            pod("unlimited", Some("0")),
+            pod("invalid", Some("lots")),
+        ] {
+            writer.apply_watcher_event(&watcher::Event::Apply(pod));
+        }
+        let mut limiter =
+            RateLimiter::new(writer.as_reader(), NonZeroU32::new(5), LogNamespace::Legacy);
+        let now = Instant::now();
+
+        assert_eq!(allowed(&mut limiter, &file_info("default", "app"), now), 5);
+        assert_eq!(
+            allowed(&mut limiter, &file_info("default", "sidecar"), now),
+            5
+        );
+        assert_eq!(
+            allowed(&mut limiter, &file_info("annotated", "app"), now),
+            2
+        );
+        assert_eq!(
+            allowed(&mut limiter, &file_info("unlimited", "app"), now),
+            10
+        );
+        assert_eq!(allowed(&mut limiter, &file_info("invalid", "app"), now), 5);
+        assert_eq!(allowed(&mut limiter, &file_info("unknown", "app"), now), 5);
+
+        // The lines are counted again in the next window.
+        let next = now + WINDOW;
+        assert_eq!(
+            allowed(&mut limiter, &file_info("annotated", "app"), next),
+            2
+        );
+        assert_eq!(limiter.pods["default"].containers.len(), 2);
+
+        // The idle containers are forgotten.
+        let later = next + IDLE_TIMEOUT;
+        limiter.allow_at(&file_info("annotated", "app"), later);
+        limiter.flush_at(later);
+        assert!(!limiter.pods.contains_key("default"));
+        assert!(limiter.pods.contains_key("annotated"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn limits_events_by_their_file() {
+        let writer = Writer::<Pod>::default();
+        let mut limiter =
+            RateLimiter::new(writer.as_reader(), NonZeroU32::new(1), LogNamespace::Legacy);
+
+        let mut event = LogEvent::from("merged line");
+        event.insert("file", "/var/log/pods/ns_name_uid/app/0.log");
+        let event = Event::from(event);
+        assert!(limiter.allow(&event));
+        assert!(!limiter.allow(&event));
+
+        // Events that can't be attributed to a container aren't rate limited.
+        let event = Event::from(LogEvent::from("unknown"));
+        assert!(limiter.allow(&event));
+        assert!(limiter.allow(&event));
+    }
+}
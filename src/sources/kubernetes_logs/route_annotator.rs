@@ -0,0 +1,237 @@
+//! Annotates events with the routes matched by their Pod and Namespace labels.
+
+#![deny(missing_docs)]
+
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+use k8s_openapi::api::core::v1::{Namespace, Pod};
+use kube::runtime::reflector::{ObjectRef, store::Store};
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    configurable::configurable_component,
+    lookup::path,
+};
+
+use super::{
+    Config,
+    label_selector::{LabelSelector, LabelSelectorError},
+    path_helpers::LogFileInfo,
+};
+use crate::event::Event;
+
+/// The key we use for the `routes` field.
+pub(super) const ROUTES_KEY: &str = "routes";
+
+/// Label selectors deciding whether an event matches a route.
+///
+/// A label selector can be passed as a string, in which case it is matched against the labels of
+/// the Pod the event originates from:
+///
+/// ```toml
+/// routes.app-a = "app=foo,tier=backend"
+/// ```
+///
+/// The labels of the Pod's Namespace can be matched as well by specifying the selectors
+/// separately:
+///
+/// ```toml
+/// routes.team-a.pod_label_selector = "tier=backend"
+/// routes.team-a.namespace_label_selector = "team=a"
+/// ```
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(untagged)]
+pub enum RouteSelector {
+    /// A [label selector][label_selector] matched against the labels of the Pod.
+    ///
+    /// [label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+    PodLabelSelector(String),
+
+    /// Label selectors matched against the labels of the Pod and its Namespace.
+    Selectors(RouteSelectorsConfig),
+}
+
+/// Label selectors matched against the labels of the Pod and its Namespace.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct RouteSelectorsConfig {
+    /// The [label selector][label_selector] matched against the labels of the Pod.
+    ///
+    /// [label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+    #[configurable(metadata(docs::examples = "app=foo,tier=backend"))]
+    pub pod_label_selector: String,
+
+    /// The [label selector][label_selector] matched against the labels of the Pod's Namespace.
+    ///
+    /// Namespace labels are only available when `insert_namespace_fields` is enabled.
+    ///
+    /// [label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+    #[configurable(metadata(docs::examples = "team=a"))]
+    pub namespace_label_selector: String,
+}
+
+/// A compiled route.
+#[derive(Clone, Debug)]
+pub struct Route {
+    name: String,
+    pod_label_selector: LabelSelector,
+    namespace_label_selector: LabelSelector,
+}
+
+impl Route {
+    /// Compiles the configured routes, in order.
+    pub fn build_all(
+        routes: &IndexMap<String, RouteSelector>,
+    ) -> Result<Vec<Self>, LabelSelectorError> {
+        routes
+            .iter()
+            .map(|(name, selector)| Self::new(name, selector))
+            .collect()
+    }
+
+    fn new(name: &str, selector: &RouteSelector) -> Result<Self, LabelSelectorError> {
+        let (pod_label_selector, namespace_label_selector) = match selector {
+            RouteSelector::PodLabelSelector(selector) => (selector.as_str(), ""),
+            RouteSelector::Selectors(config) => (
+                config.pod_label_selector.as_str(),
+                config.namespace_label_selector.as_str(),
+            ),
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            pod_label_selector: LabelSelector::parse(pod_label_selector)?,
+            namespace_label_selector: LabelSelector::parse(namespace_label_selector)?,
+        })
+    }
+
+    fn matches(
+        &self,
+        pod_labels: &BTreeMap<String, String>,
+        namespace_labels: &BTreeMap<String, String>,
+    ) -> bool {
+        self.pod_label_selector.matches(pod_labels)
+            && self.namespace_label_selector.matches(namespace_labels)
+    }
+}
+
+/// Annotate the event with the names of the routes it matches.
+pub struct RouteAnnotator {
+    pods_state_reader: Store<Pod>,
+    namespace_state_reader: Store<Namespace>,
+    routes: Vec<Route>,
+    log_namespace: LogNamespace,
+}
+
+impl RouteAnnotator {
+    /// Create a new [`RouteAnnotator`].
+    pub const fn new(
+        pods_state_reader: Store<Pod>,
+        namespace_state_reader: Store<Namespace>,
+        routes: Vec<Route>,
+        log_namespace: LogNamespace,
+    ) -> Self {
+        Self {
+            pods_state_reader,
+            namespace_state_reader,
+            routes,
+            log_namespace,
+        }
+    }
+
+    /// Annotates an event with the names of the routes matched by its Pod and Namespace labels.
+    pub fn annotate(&self, event: &mut Event, file_info: &LogFileInfo<'_>) -> Option<()> {
+        if self.routes.is_empty() {
+            return Some(());
+        }
+
+        let obj = ObjectRef::<Pod>::new(file_info.pod_name).within(file_info.pod_namespace);
+        let pod = self.pods_state_reader.get(&obj)?;
+        let namespace = self
+            .namespace_state_reader
+            .get(&ObjectRef::<Namespace>::new(file_info.pod_namespace));
+
+        let no_labels = BTreeMap::new();
+        let pod_labels = pod.metadata.labels.as_ref().unwrap_or(&no_labels);
+        let namespace_labels = namespace
+            .as_ref()
+            .and_then(|namespace| namespace.metadata.labels.as_ref())
+            .unwrap_or(&no_labels);
+
+        let matched = matching_routes(&self.routes, pod_labels, namespace_labels);
+
+        self.log_namespace.insert_source_metadata(
+            Config::NAME,
+            event.as_mut_log(),
+            Some(LegacyKey::Overwrite(path!("kubernetes", ROUTES_KEY))),
+            path!(ROUTES_KEY),
+            matched,
+        );
+        Some(())
+    }
+}
+
+fn matching_routes(
+    routes: &[Route],
+    pod_labels: &BTreeMap<String, String>,
+    namespace_labels: &BTreeMap<String, String>,
+) -> Vec<String> {
+    routes
+        .iter()
+        .filter(|route| route.matches(pod_labels, namespace_labels))
+        .map(|route| route.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Test {
+        routes: IndexMap<String, RouteSelector>,
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn matching_routes_in_order() {
+        let config: Test = toml::from_str(
+            r#"
+            routes.app-a = "app=foo,tier=backend"
+            routes.backend = "tier=backend"
+            routes.frontend = "tier=frontend"
+            routes.team-a.pod_label_selector = "tier"
+            routes.team-a.namespace_label_selector = "team=a"
+            "#,
+        )
+        .unwrap();
+        let routes = Route::build_all(&config.routes).unwrap();
+
+        let pod_labels = labels(&[("app", "foo"), ("tier", "backend")]);
+        assert_eq!(
+            matching_routes(&routes, &pod_labels, &labels(&[("team", "a")])),
+            vec!["app-a", "backend", "team-a"]
+        );
+        assert_eq!(
+            matching_routes(&routes, &pod_labels, &BTreeMap::new()),
+            vec!["app-a", "backend"]
+        );
+        assert!(matching_routes(&routes, &BTreeMap::new(), &BTreeMap::new()).is_empty());
+    }
+
+    #[test]
+    fn invalid_route_selector() {
+        let config: Test = toml::from_str(r#"routes.broken = "env notin prod""#).unwrap();
+        assert!(Route::build_all(&config.routes).is_err());
+    }
+}
@@ -12,3 +12,12 @@ pub(crate) fn get_message_path(log_namespace: LogNamespace) -> OwnedTargetPath {
         ),
     }
 }
+
+pub(crate) fn get_file_path(log_namespace: LogNamespace) -> OwnedTargetPath {
+    match log_namespace {
+        LogNamespace::Vector => {
+            OwnedTargetPath::metadata(owned_value_path!(super::Config::NAME, "file"))
+        }
+        LogNamespace::Legacy => OwnedTargetPath::event(owned_value_path!("file")),
+    }
+}
@@ -167,6 +167,7 @@ impl SourceConfig for LogstashConfig {
             self.acknowledgements,
             self.connection_limit,
             self.permit_origin.clone().map(Into::into),
+            false,
             LogstashConfig::NAME,
             log_namespace,
         )
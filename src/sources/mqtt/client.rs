@@ -0,0 +1,179 @@
+//! The clients of the versions 3.1.1 and 5 of the MQTT protocol, which `rumqttc` implements
+//! separately.
+
+use std::borrow::Cow;
+
+use bytes::Bytes;
+use rumqttc::{
+    AsyncClient, ClientError, ConnectionError, Event, EventLoop, Incoming, Publish, QoS, v5,
+};
+
+use crate::common::mqtt::MqttConnector;
+
+/// The capacity of the channel of the requests to the event loop.
+const REQUEST_CAPACITY: usize = 1024;
+
+#[derive(Clone)]
+pub(super) enum MqttSourceConnector {
+    V3(MqttConnector),
+    V5(v5::MqttOptions),
+}
+
+impl MqttSourceConnector {
+    pub(super) fn connect(&self) -> (MqttSourceClient, MqttSourceEventLoop) {
+        match self {
+            Self::V3(connector) => {
+                let (client, eventloop) = connector.connect();
+                (
+                    MqttSourceClient::V3(client),
+                    MqttSourceEventLoop::V3(eventloop),
+                )
+            }
+            Self::V5(options) => {
+                let (client, eventloop) = v5::AsyncClient::new(options.clone(), REQUEST_CAPACITY);
+                (
+                    MqttSourceClient::V5(client),
+                    MqttSourceEventLoop::V5(eventloop),
+                )
+            }
+        }
+    }
+
+    pub(super) fn broker_address(&self) -> String {
+        match self {
+            Self::V3(connector) => connector.options.broker_address().0,
+            Self::V5(options) => options.broker_address().0,
+        }
+    }
+}
+
+pub(super) enum MqttSourceClient {
+    V3(AsyncClient),
+    V5(v5::AsyncClient),
+}
+
+impl MqttSourceClient {
+    /// Subscribes to the topic filter with the QoS 1, so that the messages are acknowledged.
+    pub(super) async fn subscribe(&self, topic: &str) -> Result<(), MqttRequestError> {
+        match self {
+            Self::V3(client) => client.subscribe(topic, QoS::AtLeastOnce).await?,
+            Self::V5(client) => {
+                client
+                    .subscribe(topic, v5::mqttbytes::QoS::AtLeastOnce)
+                    .await?
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) async fn ack(&self, publish: &MqttPublish) -> Result<(), MqttRequestError> {
+        match (self, publish) {
+            (Self::V3(client), MqttPublish::V3(publish)) => client.ack(publish).await?,
+            (Self::V5(client), MqttPublish::V5(publish)) => client.ack(publish).await?,
+            // The messages are always received by the event loop of the client.
+            _ => unreachable!("MQTT message acknowledged with another protocol version"),
+        }
+        Ok(())
+    }
+}
+
+pub(super) enum MqttSourceEventLoop {
+    V3(EventLoop),
+    V5(v5::EventLoop),
+}
+
+impl MqttSourceEventLoop {
+    /// Polls the event loop until the next event, returning the published messages.
+    ///
+    /// The connection is established again by the next poll after an error.
+    pub(super) async fn poll(&mut self) -> Result<Option<MqttPublish>, MqttPollError> {
+        match self {
+            Self::V3(eventloop) => match eventloop.poll().await? {
+                Event::Incoming(Incoming::Publish(publish)) => Ok(Some(MqttPublish::V3(publish))),
+                _ => Ok(None),
+            },
+            Self::V5(eventloop) => match eventloop.poll().await? {
+                v5::Event::Incoming(v5::Incoming::Publish(publish)) => {
+                    Ok(Some(MqttPublish::V5(publish)))
+                }
+                _ => Ok(None),
+            },
+        }
+    }
+}
+
+/// A message published to one of the subscribed topics.
+pub(super) enum MqttPublish {
+    V3(Publish),
+    V5(v5::mqttbytes::v5::Publish),
+}
+
+impl MqttPublish {
+    pub(super) fn topic(&self) -> Cow<'_, str> {
+        match self {
+            Self::V3(publish) => Cow::Borrowed(&publish.topic),
+            Self::V5(publish) => String::from_utf8_lossy(&publish.topic),
+        }
+    }
+
+    pub(super) fn payload(&self) -> &Bytes {
+        match self {
+            Self::V3(publish) => &publish.payload,
+            Self::V5(publish) => &publish.payload,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum MqttRequestError {
+    V3(ClientError),
+    V5(v5::ClientError),
+}
+
+impl From<ClientError> for MqttRequestError {
+    fn from(error: ClientError) -> Self {
+        Self::V3(error)
+    }
+}
+
+impl From<v5::ClientError> for MqttRequestError {
+    fn from(error: v5::ClientError) -> Self {
+        Self::V5(error)
+    }
+}
+
+impl std::fmt::Display for MqttRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::V3(error) => error.fmt(f),
+            Self::V5(error) => error.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum MqttPollError {
+    V3(ConnectionError),
+    V5(v5::ConnectionError),
+}
+
+impl From<ConnectionError> for MqttPollError {
+    fn from(error: ConnectionError) -> Self {
+        Self::V3(error)
+    }
+}
+
+impl From<v5::ConnectionError> for MqttPollError {
+    fn from(error: v5::ConnectionError) -> Self {
+        Self::V5(error)
+    }
+}
+
+impl std::fmt::Display for MqttPollError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::V3(error) => error.fmt(f),
+            Self::V5(error) => error.fmt(f),
+        }
+    }
+}
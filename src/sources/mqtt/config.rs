@@ -1,18 +1,18 @@
 use std::time::Duration;
 
 use rand::Rng;
-use rumqttc::{MqttOptions, TlsConfiguration, Transport};
+use rumqttc::{MqttOptions, TlsConfiguration, Transport, v5};
 use snafu::ResultExt;
 use vector_lib::{
     codecs::decoding::{DeserializerConfig, FramingConfig},
-    config::{LegacyKey, LogNamespace},
+    config::{LegacyKey, LogNamespace, SourceAcknowledgementsConfig},
     configurable::configurable_component,
     lookup::{lookup_v2::OptionalValuePath, owned_value_path},
     tls::MaybeTlsSettings,
 };
 use vrl::value::Kind;
 
-use super::source::MqttSource;
+use super::{client::MqttSourceConnector, source::MqttSource, topic::TopicTemplate};
 use crate::{
     codecs::DecodingConfig,
     common::mqtt::{
@@ -20,9 +20,27 @@ use crate::{
         TlsSnafu,
     },
     config::{SourceConfig, SourceContext, SourceOutput},
-    serde::{default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
 };
 
+/// How long the broker keeps the session of the source, with the messages that weren't
+/// acknowledged yet, once it's disconnected with MQTT 5.
+const SESSION_EXPIRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The version of the MQTT protocol used to connect to the broker.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MqttProtocolVersion {
+    /// MQTT 3.1.1.
+    #[default]
+    #[serde(rename = "v3.1.1")]
+    V311,
+
+    /// MQTT 5.
+    #[serde(rename = "v5")]
+    V5,
+}
+
 /// Configuration for the `mqtt` source.
 #[configurable_component(source("mqtt", "Collect logs from MQTT."))]
 #[derive(Clone, Debug, Derivative)]
@@ -38,6 +56,31 @@ pub struct MqttSourceConfig {
     #[derivative(Default(value = "default_topic()"))]
     pub topic: String,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub protocol_version: MqttProtocolVersion,
+
+    /// The group of the [shared subscription][shared_subscriptions] to the topic.
+    ///
+    /// The messages of the topic are distributed between the members of the group, instead of
+    /// being delivered to each of them, so that several instances of Vector share the load. The
+    /// broker must support shared subscriptions, which are part of MQTT 5 and supported by most
+    /// brokers with MQTT 3.1.1.
+    ///
+    /// [shared_subscriptions]: https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901250
+    #[configurable(metadata(docs::examples = "vector"))]
+    pub shared_group: Option<String>,
+
+    /// A template the topics of the messages are matched against, to extract their levels into
+    /// fields.
+    ///
+    /// Each level of the template is either `{name}`, which extracts the level into the `name`
+    /// field, `+`, which matches any level, or a level the topic must be equal to. The template
+    /// may end with `#`, which matches the remaining levels. The fields aren't extracted from the
+    /// topics that don't match the template.
+    #[configurable(metadata(docs::examples = "sensors/{site}/{device}/#"))]
+    pub topic_template: Option<String>,
+
     #[configurable(derived)]
     #[serde(default = "default_framing_message_based")]
     #[derivative(Default(value = "default_framing_message_based()"))]
@@ -61,6 +104,10 @@ pub struct MqttSourceConfig {
     #[serde(default = "default_topic_key")]
     #[configurable(metadata(docs::examples = "topic"))]
     pub topic_key: OptionalValuePath,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    pub acknowledgements: SourceAcknowledgementsConfig,
 }
 
 fn default_topic() -> String {
@@ -77,14 +124,30 @@ impl SourceConfig for MqttSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<crate::sources::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
 
-        let connector = self.build_connector()?;
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+        let connector = self.build_connector(acknowledgements)?;
+        let topic = self.subscription_topic()?;
+        let topic_template = self
+            .topic_template
+            .as_deref()
+            .map(TopicTemplate::parse)
+            .transpose()
+            .context(ConfigurationSnafu)?;
 
         let decoder =
             DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
                 .build()?;
 
-        let sink = MqttSource::new(connector.clone(), decoder, log_namespace, self.clone())?;
-        Ok(Box::pin(sink.run(cx.out, cx.shutdown)))
+        let source = MqttSource::new(
+            connector,
+            decoder,
+            log_namespace,
+            topic,
+            topic_template,
+            acknowledgements,
+            self.clone(),
+        )?;
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
@@ -107,12 +170,23 @@ impl SourceConfig for MqttSourceConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        true
     }
 }
 
 impl MqttSourceConfig {
-    fn build_connector(&self) -> Result<MqttConnector, MqttError> {
+    /// The topic filter subscribed to, which is shared by the members of the `shared_group`.
+    fn subscription_topic(&self) -> Result<String, MqttError> {
+        match &self.shared_group {
+            Some(group) if group.is_empty() || group.contains(['/', '+', '#']) => {
+                Err(ConfigurationError::InvalidSharedGroup).context(ConfigurationSnafu)
+            }
+            Some(group) => Ok(format!("$share/{group}/{}", self.topic)),
+            None => Ok(self.topic.clone()),
+        }
+    }
+
+    fn build_connector(&self, acknowledgements: bool) -> Result<MqttSourceConnector, MqttError> {
         let client_id = self.common.client_id.clone().unwrap_or_else(|| {
             let hash = rand::rng()
                 .sample_iter(&rand_distr::Alphanumeric)
@@ -128,36 +202,64 @@ impl MqttSourceConfig {
 
         let tls =
             MaybeTlsSettings::from_config(self.common.tls.as_ref(), false).context(TlsSnafu)?;
-        let mut options = MqttOptions::new(client_id, &self.common.host, self.common.port);
-        options.set_keep_alive(Duration::from_secs(self.common.keep_alive.into()));
-        options.set_max_packet_size(self.common.max_packet_size, self.common.max_packet_size);
-
-        options.set_clean_session(false);
-        match (&self.common.user, &self.common.password) {
-            (Some(user), Some(password)) => {
-                options.set_credentials(user, password);
-            }
+        let credentials = match (&self.common.user, &self.common.password) {
+            (Some(user), Some(password)) => Some((user, password)),
             (None, None) => {
                 // Credentials were not provided
+                None
             }
             _ => {
                 // We need either both username and password, or neither. MQTT also allows for providing only password, but rumqttc does not allow that so we cannot either.
                 return Err(ConfigurationError::IncompleteCredentials).context(ConfigurationSnafu);
             }
-        }
-
-        if let Some(tls) = tls.tls() {
+        };
+        let transport = tls.tls().map(|tls| {
             let ca = tls.authorities_pem().flatten().collect();
-            let client_auth = None;
+            let client_auth = tls.identity_pem();
             let alpn = Some(vec!["mqtt".into()]);
-            options.set_transport(Transport::Tls(TlsConfiguration::Simple {
+            Transport::Tls(TlsConfiguration::Simple {
                 ca,
                 client_auth,
                 alpn,
-            }));
-        }
+            })
+        });
+        let keep_alive = Duration::from_secs(self.common.keep_alive.into());
 
-        Ok(MqttConnector::new(options))
+        // The messages are acknowledged once their events are delivered, instead of as soon as
+        // they're received.
+        match self.protocol_version {
+            MqttProtocolVersion::V311 => {
+                let mut options = MqttOptions::new(client_id, &self.common.host, self.common.port);
+                options.set_keep_alive(keep_alive);
+                options
+                    .set_max_packet_size(self.common.max_packet_size, self.common.max_packet_size);
+                options.set_clean_session(false);
+                options.set_manual_acks(acknowledgements);
+                if let Some((user, password)) = credentials {
+                    options.set_credentials(user, password);
+                }
+                if let Some(transport) = transport {
+                    options.set_transport(transport);
+                }
+                Ok(MqttSourceConnector::V3(MqttConnector::new(options)))
+            }
+            MqttProtocolVersion::V5 => {
+                let mut options =
+                    v5::MqttOptions::new(client_id, &self.common.host, self.common.port);
+                options.set_keep_alive(keep_alive);
+                options.set_max_packet_size(u32::try_from(self.common.max_packet_size).ok());
+                options.set_clean_start(false);
+                options.set_session_expiry_interval(Some(SESSION_EXPIRY_INTERVAL.as_secs() as u32));
+                options.set_manual_acks(acknowledgements);
+                if let Some((user, password)) = credentials {
+                    options.set_credentials(user, password);
+                }
+                if let Some(transport) = transport {
+                    options.set_transport(transport);
+                }
+                Ok(MqttSourceConnector::V5(options))
+            }
+        }
     }
 }
 
@@ -171,4 +273,20 @@ mod test {
     fn generate_config() {
         crate::test_util::test_generate_config::<MqttSourceConfig>();
     }
+
+    #[test]
+    fn shared_subscription_topic() {
+        let mut config = MqttSourceConfig {
+            topic: "sensors/#".to_owned(),
+            shared_group: Some("vector".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.subscription_topic().unwrap(),
+            "$share/vector/sensors/#"
+        );
+
+        config.shared_group = Some("vector/a".to_owned());
+        assert!(config.subscription_topic().is_err());
+    }
 }
@@ -7,12 +7,12 @@ use futures::StreamExt;
 use rumqttc::{AsyncClient, MqttOptions, QoS};
 use tokio::time::timeout;
 
-use super::MqttSourceConfig;
+use super::{MqttProtocolVersion, MqttSourceConfig};
 use crate::{
     SourceSender,
     common::mqtt::MqttCommonConfig,
     config::{SourceConfig, SourceContext, log_schema},
-    event::Event,
+    event::{Event, EventStatus},
     test_util::{
         components::{SOURCE_TAGS, assert_source_compliance},
         random_lines_with_stream, random_string, trace_init,
@@ -116,3 +116,66 @@ async fn mqtt_happy() {
     })
     .await;
 }
+
+#[tokio::test]
+async fn mqtt_shared_subscription_v5() {
+    trace_init();
+    let topic = format!("sensors/{}/temperature", random_string(6));
+    let client_id = format!("sourceTest{}", random_string(6));
+    let num_events = 10;
+    let (input, _events) = random_lines_with_stream(100, num_events, None);
+
+    assert_source_compliance(&SOURCE_TAGS, async {
+        let common = MqttCommonConfig {
+            host: mqtt_broker_address(),
+            port: mqtt_broker_port(),
+            client_id: Some(client_id),
+            ..Default::default()
+        };
+
+        let config = MqttSourceConfig {
+            common,
+            topic: topic.clone(),
+            protocol_version: MqttProtocolVersion::V5,
+            shared_group: Some("vector".to_owned()),
+            topic_template: Some("sensors/{site}/+".to_owned()),
+            acknowledgements: true.into(),
+            ..MqttSourceConfig::default()
+        };
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        tokio::spawn(async move {
+            config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap()
+                .await
+                .unwrap()
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let client = get_mqtt_client().await;
+        send_test_events(&client, &topic, &input).await;
+
+        let events: Vec<Event> = timeout(Duration::from_secs(2), rx.take(num_events).collect())
+            .await
+            .unwrap();
+
+        let site = topic.split('/').nth(1).unwrap();
+        let mut expected_messages: HashSet<_> = input.into_iter().collect();
+        for event in events {
+            let log = event.as_log();
+            let message = log
+                .get(log_schema().message_key_target_path().unwrap())
+                .unwrap()
+                .to_string_lossy();
+            if !expected_messages.remove(message.as_ref()) {
+                panic!("Received unexpected message: {message:?}");
+            }
+            assert_eq!(log["site"], site.into());
+        }
+        assert!(expected_messages.is_empty());
+    })
+    .await;
+}
@@ -1,5 +1,7 @@
+mod client;
 mod config;
 mod integration_tests;
 mod source;
+mod topic;
 
-pub use config::MqttSourceConfig;
+pub use config::{MqttProtocolVersion, MqttSourceConfig};
@@ -1,91 +1,124 @@
+use std::time::Duration;
+
+use futures::StreamExt;
 use itertools::Itertools;
-use rumqttc::{Event as MqttEvent, Incoming, Publish, QoS};
 use vector_lib::{
     config::{LegacyKey, LogNamespace},
+    finalizer::OrderedFinalizer,
     internal_event::EventsReceived,
     lookup::path,
 };
 
+use super::{
+    client::{MqttPublish, MqttSourceClient, MqttSourceConnector},
+    topic::TopicTemplate,
+};
 use crate::{
     SourceSender,
     codecs::Decoder,
-    common::mqtt::MqttConnector,
-    event::{BatchNotifier, Event},
-    internal_events::{EndpointBytesReceived, StreamClosedError},
+    common::backoff::ExponentialBackoff,
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        EndpointBytesReceived, MqttAckError, MqttReceiveError, MqttSubscribeError,
+        StreamClosedError,
+    },
     shutdown::ShutdownSignal,
     sources::{mqtt::MqttSourceConfig, util},
 };
 
 pub struct MqttSource {
-    connector: MqttConnector,
+    connector: MqttSourceConnector,
     decoder: Decoder,
     log_namespace: LogNamespace,
+    topic: String,
+    topic_template: Option<TopicTemplate>,
+    acknowledgements: bool,
     config: MqttSourceConfig,
 }
 
 impl MqttSource {
-    pub fn new(
-        connector: MqttConnector,
+    pub(super) fn new(
+        connector: MqttSourceConnector,
         decoder: Decoder,
         log_namespace: LogNamespace,
+        topic: String,
+        topic_template: Option<TopicTemplate>,
+        acknowledgements: bool,
         config: MqttSourceConfig,
     ) -> crate::Result<Self> {
         Ok(Self {
             connector,
             decoder,
             log_namespace,
+            topic,
+            topic_template,
+            acknowledgements,
             config,
         })
     }
 
-    pub async fn run(self, mut out: SourceSender, shutdown: ShutdownSignal) -> Result<(), ()> {
+    pub async fn run(self, mut out: SourceSender, mut shutdown: ShutdownSignal) -> Result<(), ()> {
         let (client, mut connection) = self.connector.connect();
 
-        client
-            .subscribe(&self.config.topic, QoS::AtLeastOnce)
-            .await
-            .map_err(|_| ())?;
+        // The subscription is kept by the session of the client when it reconnects.
+        if let Err(error) = client.subscribe(&self.topic).await {
+            emit!(MqttSubscribeError { error });
+            return Err(());
+        }
+
+        // The messages must be acknowledged in the order they were received.
+        let (finalizer, mut ack_stream) = OrderedFinalizer::<MqttPublish>::maybe_new(
+            self.acknowledgements,
+            Some(shutdown.clone()),
+        );
+        let mut backoff = fresh_backoff();
 
         loop {
             tokio::select! {
-                _ = shutdown.clone() => return Ok(()),
+                _ = &mut shutdown => return Ok(()),
+                entry = ack_stream.next() => {
+                    if let Some((status, publish)) = entry {
+                        acknowledge(&client, status, publish).await;
+                    }
+                }
                 mqtt_event = connection.poll() => {
-                    // If an error is returned here there is currently no way to tie this back
-                    // to the event that was posted which means we can't accurately provide
-                    // delivery guarantees.
-                    // We need this issue resolved first:
-                    // https://github.com/bytebeamio/rumqtt/issues/349
                     match mqtt_event {
-                        Ok(MqttEvent::Incoming(Incoming::Publish(publish))) => {
-                            self.process_message(publish, &mut out).await;
+                        Ok(Some(publish)) => {
+                            backoff = fresh_backoff();
+                            self.process_message(publish, &finalizer, &mut out).await;
                         }
-                        Ok(MqttEvent::Incoming(
-                            Incoming::PubAck(_) | Incoming::PubRec(_) | Incoming::PubComp(_),
-                        )) => {
-                            // TODO Handle acknowledgement - https://github.com/vectordotdev/vector/issues/21967
+                        Ok(None) => backoff = fresh_backoff(),
+                        Err(error) => {
+                            emit!(MqttReceiveError { error });
+                            // The connection is established again by the next poll.
+                            tokio::time::sleep(backoff.next().unwrap()).await;
                         }
-                        _ => {}
                     }
                 }
             }
         }
     }
 
-    async fn process_message(&self, publish: Publish, out: &mut SourceSender) {
+    async fn process_message(
+        &self,
+        publish: MqttPublish,
+        finalizer: &Option<OrderedFinalizer<MqttPublish>>,
+        out: &mut SourceSender,
+    ) {
         emit!(EndpointBytesReceived {
-            byte_size: publish.payload.len(),
+            byte_size: publish.payload().len(),
             protocol: "mqtt",
-            endpoint: &self.connector.options.broker_address().0,
+            endpoint: &self.connector.broker_address(),
         });
         let events_received = register!(EventsReceived);
 
-        let (batch, _batch_receiver) = BatchNotifier::maybe_new_with_receiver(false);
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
         // Error is logged by `crate::codecs::Decoder`, no further handling
         // is needed here.
         let decoded = util::decode_message(
             self.decoder.clone(),
             "mqtt",
-            &publish.payload,
+            publish.payload(),
             None,
             &batch,
             self.log_namespace,
@@ -96,17 +129,23 @@ impl MqttSource {
             event
         })
         .collect_vec();
+        drop(batch);
 
         let count = decoded.len();
 
         match out.send_batch(decoded).await {
-            Ok(()) => {}
+            Ok(()) => {
+                if let (Some(finalizer), Some(receiver)) = (finalizer, receiver) {
+                    finalizer.add(publish, receiver);
+                }
+            }
             Err(_) => emit!(StreamClosedError { count }),
         }
     }
 
-    fn apply_metadata(&self, publish: &Publish, event: &mut Event) {
+    fn apply_metadata(&self, publish: &MqttPublish, event: &mut Event) {
         if let Event::Log(log) = event {
+            let topic = publish.topic();
             self.log_namespace.insert_source_metadata(
                 MqttSourceConfig::NAME,
                 log,
@@ -116,8 +155,43 @@ impl MqttSource {
                     .as_ref()
                     .map(LegacyKey::Overwrite),
                 path!("topic"),
-                publish.topic.clone(),
+                topic.as_ref(),
             );
+
+            let fields = self
+                .topic_template
+                .as_ref()
+                .and_then(|template| template.extract(&topic))
+                .unwrap_or_default();
+            for (name, value) in fields {
+                self.log_namespace.insert_source_metadata(
+                    MqttSourceConfig::NAME,
+                    log,
+                    Some(LegacyKey::Overwrite(path!(name))),
+                    path!("topic_fields", name),
+                    value,
+                );
+            }
+        }
+    }
+}
+
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+/// Acknowledges the message once its events are delivered, or rejected. The messages whose events
+/// failed to be delivered aren't acknowledged, so that the broker delivers them again when the
+/// source reconnects.
+async fn acknowledge(client: &MqttSourceClient, status: BatchStatus, publish: MqttPublish) {
+    match status {
+        BatchStatus::Delivered | BatchStatus::Rejected => {
+            if let Err(error) = client.ack(&publish).await {
+                emit!(MqttAckError { error });
+            }
         }
+        BatchStatus::Errored => {}
     }
 }
@@ -0,0 +1,119 @@
+//! The templates the topics of the messages are matched against, to extract their levels into
+//! fields.
+
+use crate::common::mqtt::ConfigurationError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Level {
+    /// A level that must be equal to this one.
+    Literal(String),
+    /// `+`, any single level.
+    Any,
+    /// `{name}`, any single level, extracted into the `name` field.
+    Field(String),
+}
+
+/// A topic template, such as `sensors/{site}/{device}/#`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct TopicTemplate {
+    levels: Vec<Level>,
+    /// Whether the template ends with `#`, which matches any number of levels.
+    rest: bool,
+}
+
+impl TopicTemplate {
+    pub(super) fn parse(template: &str) -> Result<Self, ConfigurationError> {
+        let invalid = |reason| ConfigurationError::InvalidTopicTemplate {
+            template: template.to_owned(),
+            reason,
+        };
+
+        let mut levels = Vec::new();
+        let mut rest = false;
+        for level in template.split('/') {
+            if rest {
+                return Err(invalid("`#` must be the last level"));
+            }
+            let level = match level {
+                "#" => {
+                    rest = true;
+                    continue;
+                }
+                "+" => Level::Any,
+                _ => match level.strip_prefix('{').and_then(|l| l.strip_suffix('}')) {
+                    Some("") => return Err(invalid("the field names must not be empty")),
+                    Some(name) => Level::Field(name.to_owned()),
+                    None if level.contains(['+', '#', '{', '}']) => {
+                        return Err(invalid(
+                            "the wildcards and the fields must take whole levels",
+                        ));
+                    }
+                    None => Level::Literal(level.to_owned()),
+                },
+            };
+            levels.push(level);
+        }
+
+        Ok(Self { levels, rest })
+    }
+
+    /// The names and values of the fields of the topic, if it matches the template.
+    pub(super) fn extract<'a>(&'a self, topic: &'a str) -> Option<Vec<(&'a str, &'a str)>> {
+        let mut topic_levels = topic.split('/');
+        let mut fields = Vec::new();
+        for level in &self.levels {
+            let value = topic_levels.next()?;
+            match level {
+                Level::Literal(literal) if literal != value => return None,
+                Level::Field(name) => fields.push((name.as_str(), value)),
+                Level::Literal(_) | Level::Any => {}
+            }
+        }
+        if !self.rest && topic_levels.next().is_some() {
+            return None;
+        }
+        Some(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_fields() {
+        let template = TopicTemplate::parse("sensors/{site}/+/{device}").unwrap();
+        assert_eq!(
+            template.extract("sensors/paris/temperature/d42"),
+            Some(vec![("site", "paris"), ("device", "d42")])
+        );
+        assert_eq!(template.extract("sensors/paris/temperature"), None);
+        assert_eq!(template.extract("sensors/paris/temperature/d42/raw"), None);
+        assert_eq!(template.extract("actuators/paris/valve/d42"), None);
+    }
+
+    #[test]
+    fn matches_remaining_levels() {
+        let template = TopicTemplate::parse("{tenant}/#").unwrap();
+        assert_eq!(
+            template.extract("acme/sensors/d42"),
+            Some(vec![("tenant", "acme")])
+        );
+        assert_eq!(template.extract("acme"), Some(vec![("tenant", "acme")]));
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        for template in [
+            "sensors/#/{device}",
+            "sensors/{}",
+            "sensors/d{id}",
+            "sensors/a+",
+        ] {
+            assert!(
+                TopicTemplate::parse(template).is_err(),
+                "{template} was accepted"
+            );
+        }
+    }
+}
@@ -1,11 +1,13 @@
+use std::time::Duration;
+
 use async_nats::jetstream::{
-    consumer::{PullConsumer, StreamError as ConsumerStreamError},
+    consumer::{AckPolicy, PullConsumer, StreamError as ConsumerStreamError, pull},
     context::GetStreamError,
 };
 use snafu::{ResultExt, Snafu};
 use vector_lib::{
     codecs::decoding::{DeserializerConfig, FramingConfig},
-    config::{LegacyKey, LogNamespace},
+    config::{LegacyKey, LogNamespace, SourceAcknowledgementsConfig},
     configurable::configurable_component,
     lookup::{lookup_v2::OptionalValuePath, owned_value_path},
 };
@@ -14,8 +16,8 @@ use vrl::value::Kind;
 use crate::{
     codecs::DecodingConfig,
     config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput},
-    nats::{NatsAuthConfig, NatsConfigError, from_tls_auth_config},
-    serde::{default_decoding, default_framing_message_based},
+    nats::{NatsAuthConfig, NatsConfigError, NatsStreamConfig, from_tls_auth_config},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
     sources::{
         Source,
         nats::source::{create_subscription, run_nats_core, run_nats_jetstream},
@@ -34,6 +36,8 @@ pub enum BuildError {
     Subscribe { source: async_nats::SubscribeError },
     #[snafu(display("NATS stream not found: {}", source))]
     Stream { source: GetStreamError },
+    #[snafu(display("Failed to create NATS stream: {}", source))]
+    CreateStream { source: async_nats::Error },
     #[snafu(display("Failed to get NATS consumer: {}", source))]
     Consumer { source: async_nats::Error },
     #[snafu(display("Failed to retrieve messages from NATS consumer: {}", source))]
@@ -43,7 +47,8 @@ pub enum BuildError {
 /// Batch settings for a JetStream pull consumer.
 ///
 /// By default, messages are pulled in batches of up to 200.
-/// Each pull request expires after 30 seconds if not fulfilled.
+/// Each pull request expires after 30 seconds if not fulfilled, and
+/// the server sends heartbeats every 15 seconds while it is pending.
 /// There is no explicit maximum byte size per batch unless specified.
 ///
 /// **Note:** These defaults follow the `async-nats` crate’s `StreamBuilder`.
@@ -58,6 +63,20 @@ pub struct BatchConfig {
     /// fulfilled when either `size` or `max_bytes` is reached.
     #[serde(default = "default_max_bytes")]
     max_bytes: usize,
+
+    /// The time after which a pull request expires if it isn't fulfilled, in seconds.
+    #[serde(default = "default_expires_secs")]
+    #[configurable(metadata(docs::human_name = "Expiration"))]
+    expires_secs: u64,
+
+    /// The interval at which the server sends heartbeats while a pull request is pending, in
+    /// seconds.
+    ///
+    /// Missing heartbeats are reported, and the pull request is sent again. This must be at
+    /// most half of `expires_secs`.
+    #[serde(default = "default_idle_heartbeat_secs")]
+    #[configurable(metadata(docs::human_name = "Idle Heartbeat"))]
+    idle_heartbeat_secs: u64,
 }
 
 const fn default_batch() -> usize {
@@ -68,11 +87,21 @@ const fn default_max_bytes() -> usize {
     0
 }
 
+const fn default_expires_secs() -> u64 {
+    30
+}
+
+const fn default_idle_heartbeat_secs() -> u64 {
+    15
+}
+
 impl Default for BatchConfig {
     fn default() -> Self {
         Self {
             batch: default_batch(),
             max_bytes: default_max_bytes(),
+            expires_secs: default_expires_secs(),
+            idle_heartbeat_secs: default_idle_heartbeat_secs(),
         }
     }
 }
@@ -89,6 +118,67 @@ pub struct JetStreamConfig {
     #[serde(default)]
     #[configurable(derived)]
     pub batch_config: BatchConfig,
+
+    /// Creates the stream if it doesn't exist.
+    ///
+    /// By default, the stream must already exist.
+    #[configurable(derived)]
+    pub create_stream: Option<NatsStreamConfig>,
+
+    /// Creates the durable consumer if it doesn't exist.
+    ///
+    /// By default, the consumer must already exist.
+    #[configurable(derived)]
+    pub create_consumer: Option<JetStreamConsumerConfig>,
+}
+
+/// Settings of the durable pull consumer, created if it doesn't exist.
+///
+/// The consumer is created with the explicit ack policy, so that each message is acknowledged
+/// once its events are processed.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JetStreamConsumerConfig {
+    /// The time after which a message that wasn't acknowledged is redelivered, in seconds.
+    ///
+    /// With end-to-end acknowledgements, this must be longer than the time the sinks take to
+    /// deliver the events.
+    #[serde(default = "default_ack_wait_secs")]
+    #[configurable(metadata(docs::human_name = "Acknowledgement Wait"))]
+    pub ack_wait_secs: u64,
+
+    /// The maximum number of messages delivered and not acknowledged yet.
+    ///
+    /// The server stops delivering messages once it is reached, which bounds the events in flight.
+    #[serde(default = "default_max_ack_pending")]
+    pub max_ack_pending: i64,
+
+    /// The maximum number of times a message is delivered.
+    ///
+    /// By default, the messages are redelivered until they are acknowledged.
+    pub max_deliver: Option<i64>,
+}
+
+const fn default_ack_wait_secs() -> u64 {
+    30
+}
+
+const fn default_max_ack_pending() -> i64 {
+    1000
+}
+
+impl JetStreamConsumerConfig {
+    fn to_consumer_config(&self, name: &str) -> pull::Config {
+        pull::Config {
+            durable_name: Some(name.to_owned()),
+            ack_policy: AckPolicy::Explicit,
+            ack_wait: Duration::from_secs(self.ack_wait_secs),
+            max_ack_pending: self.max_ack_pending,
+            max_deliver: self.max_deliver.unwrap_or(-1),
+            ..Default::default()
+        }
+    }
 }
 
 /// Configuration for the `nats` source.
@@ -171,6 +261,10 @@ pub struct NatsSourceConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub jetstream: Option<JetStreamConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    pub acknowledgements: SourceAcknowledgementsConfig,
 }
 
 pub fn default_subject_key_field() -> OptionalValuePath {
@@ -198,6 +292,7 @@ impl GenerateConfig for NatsSourceConfig {
 impl SourceConfig for NatsSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
         let decoder =
             DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
                 .build()?;
@@ -206,14 +301,35 @@ impl SourceConfig for NatsSourceConfig {
             NatsMode::JetStream(js_config) => {
                 let connection = self.connect().await?;
                 let js = async_nats::jetstream::new(connection.clone());
-                let stream = js
-                    .get_stream(&js_config.stream)
-                    .await
-                    .context(StreamSnafu)?;
-                let consumer: PullConsumer = stream
-                    .get_consumer(&js_config.consumer)
-                    .await
-                    .context(ConsumerSnafu)?;
+                let stream = match &js_config.create_stream {
+                    Some(stream_config) => js
+                        .get_or_create_stream(
+                            stream_config.to_stream_config(&js_config.stream, &self.subject),
+                        )
+                        .await
+                        .map_err(|error| BuildError::CreateStream {
+                            source: error.into(),
+                        })?,
+                    None => js
+                        .get_stream(&js_config.stream)
+                        .await
+                        .context(StreamSnafu)?,
+                };
+                let consumer: PullConsumer = match &js_config.create_consumer {
+                    Some(consumer_config) => stream
+                        .get_or_create_consumer(
+                            &js_config.consumer,
+                            consumer_config.to_consumer_config(&js_config.consumer),
+                        )
+                        .await
+                        .map_err(|error| BuildError::Consumer {
+                            source: error.into(),
+                        })?,
+                    None => stream
+                        .get_consumer(&js_config.consumer)
+                        .await
+                        .context(ConsumerSnafu)?,
+                };
 
                 let batch_config = js_config.batch_config.clone();
 
@@ -221,6 +337,8 @@ impl SourceConfig for NatsSourceConfig {
                     .stream()
                     .max_messages_per_batch(batch_config.batch)
                     .max_bytes_per_batch(batch_config.max_bytes)
+                    .expires(Duration::from_secs(batch_config.expires_secs))
+                    .heartbeat(Duration::from_secs(batch_config.idle_heartbeat_secs))
                     .messages()
                     .await
                     .context(MessagesSnafu)?;
@@ -233,6 +351,7 @@ impl SourceConfig for NatsSourceConfig {
                     log_namespace,
                     cx.shutdown,
                     cx.out,
+                    acknowledgements,
                 )))
             }
             NatsMode::Core => {
@@ -399,4 +518,31 @@ mod tests {
 
         assert_eq!(definitions, Some(expected_definition));
     }
+
+    #[test]
+    fn consumer_config_acknowledges_explicitly() {
+        let config: NatsSourceConfig = toml::from_str(
+            r#"
+            url = "nats://127.0.0.1:4222"
+            connection_name = "vector"
+            subject = "events.>"
+            jetstream.stream = "EVENTS"
+            jetstream.consumer = "vector"
+            jetstream.create_stream.storage = "memory"
+            jetstream.create_consumer.max_ack_pending = 100
+            "#,
+        )
+        .unwrap();
+        let jetstream = config.jetstream.unwrap();
+        let consumer = jetstream
+            .create_consumer
+            .unwrap()
+            .to_consumer_config(&jetstream.consumer);
+
+        assert_eq!(consumer.durable_name.as_deref(), Some("vector"));
+        assert_eq!(consumer.ack_policy, AckPolicy::Explicit);
+        assert_eq!(consumer.ack_wait, Duration::from_secs(30));
+        assert_eq!(consumer.max_ack_pending, 100);
+        assert_eq!(consumer.max_deliver, -1);
+    }
 }
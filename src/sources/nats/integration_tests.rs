@@ -1,5 +1,5 @@
 #![allow(clippy::print_stdout)]
-use async_nats::jetstream::stream::StorageType;
+use async_nats::jetstream::{consumer::PullConsumer, stream::StorageType};
 use bytes::Bytes;
 use vector_lib::config::log_schema;
 
@@ -7,8 +7,10 @@ use crate::{
     SourceSender,
     codecs::DecodingConfig,
     config::{LogNamespace, SourceConfig, SourceContext},
+    event::EventStatus,
     nats::{
         NatsAuthConfig, NatsAuthCredentialsFile, NatsAuthNKey, NatsAuthToken, NatsAuthUserPassword,
+        NatsStreamConfig, NatsStreamStorage,
     },
     serde::{default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
@@ -452,6 +454,63 @@ async fn nats_jetstream_valid() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn nats_jetstream_create_stream_and_consumer() {
+    let subject = format!("test-js-create-{}", random_string(10));
+    let url = std::env::var("NATS_JETSTREAM_ADDRESS")
+        .unwrap_or_else(|_| "nats://localhost:4222".to_string());
+    let stream_name = format!("S_{}", subject.replace('.', "_"));
+    let consumer_name = format!("C_{}", subject.replace('.', "_"));
+    let msg = "my acknowledged message";
+
+    let mut conf = generate_source_config(&url, &subject);
+    conf.jetstream = Some(JetStreamConfig {
+        stream: stream_name.clone(),
+        consumer: consumer_name.clone(),
+        create_stream: Some(NatsStreamConfig {
+            storage: NatsStreamStorage::Memory,
+            ..Default::default()
+        }),
+        create_consumer: Some(toml::from_str("").unwrap()),
+        ..Default::default()
+    });
+
+    let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+    let mut cx = SourceContext::new_test(tx, None);
+    cx.acknowledgements = true;
+    let source = conf.build(cx).await.unwrap();
+    tokio::spawn(source);
+
+    // The stream was created by the source.
+    let client = async_nats::connect(&url).await.unwrap();
+    let js = async_nats::jetstream::new(client);
+    js.publish(subject, msg.as_bytes().into())
+        .await
+        .unwrap()
+        .await
+        .unwrap();
+
+    let events = collect_n(rx, 1).await;
+    assert_eq!(
+        events[0].as_log()[log_schema().message_key().unwrap().to_string()],
+        msg.into()
+    );
+
+    // The message is acknowledged once its event is delivered.
+    let stream = js.get_stream(&stream_name).await.unwrap();
+    let mut consumer: PullConsumer = stream.get_consumer(&consumer_name).await.unwrap();
+    let mut pending = usize::MAX;
+    for _ in 0..50 {
+        let info = consumer.info().await.unwrap();
+        pending = info.num_ack_pending;
+        if pending == 0 && info.num_pending == 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(pending, 0);
+}
+
 #[tokio::test]
 async fn nats_jetstream_stream_not_found() {
     let subject = format!("test-js-no-stream-{}", random_string(10));
@@ -1,4 +1,4 @@
-use async_nats::jetstream::consumer::pull::Stream as PullConsumerStream;
+use async_nats::jetstream::{AckKind, consumer::pull::Stream as PullConsumerStream};
 use chrono::Utc;
 use futures::StreamExt;
 use snafu::ResultExt;
@@ -7,6 +7,8 @@ use vector_lib::{
     EstimatedJsonEncodedSizeOf,
     codecs::decoding::StreamDecodingError,
     config::{LegacyKey, LogNamespace},
+    finalization::{BatchNotifier, BatchStatus},
+    finalizer::UnorderedFinalizer,
     internal_event::{
         ByteSize, BytesReceived, CountByteSize, EventsReceived, EventsReceivedHandle,
         InternalEventHandle as _, Protocol,
@@ -18,7 +20,7 @@ use crate::{
     SourceSender,
     codecs::Decoder,
     event::Event,
-    internal_events::StreamClosedError,
+    internal_events::{NatsAckError, NatsReceiveError, StreamClosedError},
     shutdown::ShutdownSignal,
     sources::nats::config::{BuildError, NatsSourceConfig, SubscribeSnafu},
 };
@@ -36,6 +38,8 @@ pub enum ProcessingStatus {
 /// Processes a single NATS message, sending decoded events downstream.
 ///
 /// This function contains the common logic for both Core and JetStream NATS.
+/// The events of JetStream messages are attached to the notifier of their message's batch,
+/// if any.
 pub async fn process_message(
     msg: &async_nats::Message,
     config: &NatsSourceConfig,
@@ -43,6 +47,7 @@ pub async fn process_message(
     log_namespace: LogNamespace,
     out: &mut SourceSender,
     events_received: &EventsReceivedHandle,
+    batch: &Option<BatchNotifier>,
 ) -> ProcessingStatus {
     let mut framed = FramedRead::new(msg.payload.as_ref(), decoder.clone());
     let mut success = true;
@@ -78,7 +83,7 @@ pub async fn process_message(
                             msg.subject.as_str(),
                         );
                     }
-                    event
+                    event.with_batch_notifier_option(batch)
                 });
 
                 if out.send_batch(events).await.is_err() {
@@ -104,6 +109,7 @@ pub async fn process_message(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_nats_jetstream(
     config: NatsSourceConfig,
     _connection: async_nats::Client,
@@ -112,43 +118,79 @@ pub async fn run_nats_jetstream(
     log_namespace: LogNamespace,
     shutdown: ShutdownSignal,
     mut out: SourceSender,
+    acknowledgements: bool,
 ) -> Result<(), ()> {
     let events_received = register!(EventsReceived);
     let bytes_received = register!(BytesReceived::from(Protocol::TCP));
+    let (finalizer, mut ack_stream) =
+        UnorderedFinalizer::<async_nats::jetstream::Message>::maybe_new(
+            acknowledgements,
+            Some(shutdown.clone()),
+        );
     let mut message_stream = stream.take_until(shutdown);
 
-    while let Some(Ok(msg)) = message_stream.next().await {
-        bytes_received.emit(ByteSize(msg.payload.len()));
-
-        let status = process_message(
-            &msg,
-            &config,
-            &decoder,
-            log_namespace,
-            &mut out,
-            &events_received,
-        )
-        .await;
-
-        match status {
-            ProcessingStatus::Success => {
-                // Message processed successfully, acknowledge it.
-                if let Err(err) = msg.ack().await {
-                    error!(message = "Failed to acknowledge JetStream message.", %err);
+    loop {
+        tokio::select! {
+            Some((status, msg)) = ack_stream.next() => acknowledge(&msg, status).await,
+            next = message_stream.next() => {
+                let msg = match next {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(error)) => {
+                        // Missed heartbeats and failed pull requests are retried by the stream.
+                        emit!(NatsReceiveError { error });
+                        continue;
+                    }
+                    None => break,
+                };
+                bytes_received.emit(ByteSize(msg.payload.len()));
+
+                let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
+                let status = process_message(
+                    &msg,
+                    &config,
+                    &decoder,
+                    log_namespace,
+                    &mut out,
+                    &events_received,
+                    &batch,
+                )
+                .await;
+                drop(batch);
+
+                match status {
+                    ProcessingStatus::Success => match (&finalizer, receiver) {
+                        // Acknowledge the message once its events are processed.
+                        (Some(finalizer), Some(receiver)) => finalizer.add(msg, receiver),
+                        _ => acknowledge(&msg, BatchStatus::Delivered).await,
+                    },
+                    ProcessingStatus::Failed => {
+                        // Do not acknowledge on failure; the message will be redelivered.
+                    }
+                    ProcessingStatus::ChannelClosed => {
+                        // Downstream channel is closed, shut down the source.
+                        return Err(());
+                    }
                 }
             }
-            ProcessingStatus::Failed => {
-                // Do not acknowledge on failure; the message will be redelivered.
-            }
-            ProcessingStatus::ChannelClosed => {
-                // Downstream channel is closed, shut down the source.
-                return Err(());
-            }
         }
     }
     Ok(())
 }
 
+/// Acknowledges a JetStream message according to the status of its events: the messages whose
+/// events failed to be delivered are redelivered, and the messages whose events were rejected
+/// are terminated.
+async fn acknowledge(msg: &async_nats::jetstream::Message, status: BatchStatus) {
+    let kind = match status {
+        BatchStatus::Delivered => AckKind::Ack,
+        BatchStatus::Errored => AckKind::Nak(None),
+        BatchStatus::Rejected => AckKind::Term,
+    };
+    if let Err(error) = msg.ack_with(kind).await {
+        emit!(NatsAckError { error });
+    }
+}
+
 pub async fn run_nats_core(
     config: NatsSourceConfig,
     _connection: async_nats::Client,
@@ -183,6 +225,7 @@ pub async fn run_nats_core(
                             log_namespace,
                             &mut out,
                             &events_received,
+                            &None,
                         )
                         .await;
 
@@ -0,0 +1,268 @@
+//! The information elements of NetFlow v9 and IPFIX templates, named after the fields of the
+//! events.
+//!
+//! The element identifiers are assigned by [IANA][iana], and the identifiers below 128 are shared
+//! with the field types of NetFlow v9.
+//!
+//! [iana]: https://www.iana.org/assignments/ipfix/ipfix.xhtml
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use vrl::value::Value;
+
+/// How the content of an element is decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum FieldType {
+    Unsigned,
+    Ipv4Address,
+    Ipv6Address,
+    MacAddress,
+    String,
+    /// The system uptime, in milliseconds, when the event occurred.
+    SysUptime,
+    Seconds,
+    Milliseconds,
+}
+
+/// The information elements which are decoded, by identifier.
+const FIELDS: &[(u16, &str, FieldType)] = &[
+    (1, "bytes", FieldType::Unsigned),
+    (2, "packets", FieldType::Unsigned),
+    (3, "flows", FieldType::Unsigned),
+    (4, "protocol", FieldType::Unsigned),
+    (5, "tos", FieldType::Unsigned),
+    (6, "tcp_flags", FieldType::Unsigned),
+    (7, "src_port", FieldType::Unsigned),
+    (8, "src_addr", FieldType::Ipv4Address),
+    (9, "src_mask", FieldType::Unsigned),
+    (10, "input_interface", FieldType::Unsigned),
+    (11, "dst_port", FieldType::Unsigned),
+    (12, "dst_addr", FieldType::Ipv4Address),
+    (13, "dst_mask", FieldType::Unsigned),
+    (14, "output_interface", FieldType::Unsigned),
+    (15, "next_hop", FieldType::Ipv4Address),
+    (16, "src_as", FieldType::Unsigned),
+    (17, "dst_as", FieldType::Unsigned),
+    (18, "bgp_next_hop", FieldType::Ipv4Address),
+    (21, "flow_end", FieldType::SysUptime),
+    (22, "flow_start", FieldType::SysUptime),
+    (23, "out_bytes", FieldType::Unsigned),
+    (24, "out_packets", FieldType::Unsigned),
+    (27, "src_addr", FieldType::Ipv6Address),
+    (28, "dst_addr", FieldType::Ipv6Address),
+    (29, "src_mask", FieldType::Unsigned),
+    (30, "dst_mask", FieldType::Unsigned),
+    (31, "ipv6_flow_label", FieldType::Unsigned),
+    (32, "icmp_type_code", FieldType::Unsigned),
+    (34, "sampling_interval", FieldType::Unsigned),
+    (35, "sampling_algorithm", FieldType::Unsigned),
+    (56, "src_mac", FieldType::MacAddress),
+    (58, "vlan_id", FieldType::Unsigned),
+    (60, "ip_version", FieldType::Unsigned),
+    (61, "direction", FieldType::Unsigned),
+    (62, "next_hop", FieldType::Ipv6Address),
+    (63, "bgp_next_hop", FieldType::Ipv6Address),
+    (80, "dst_mac", FieldType::MacAddress),
+    (82, "interface_name", FieldType::String),
+    (83, "interface_description", FieldType::String),
+    (136, "flow_end_reason", FieldType::Unsigned),
+    (139, "icmp_type_code", FieldType::Unsigned),
+    (148, "flow_id", FieldType::Unsigned),
+    (150, "flow_start", FieldType::Seconds),
+    (151, "flow_end", FieldType::Seconds),
+    (152, "flow_start", FieldType::Milliseconds),
+    (153, "flow_end", FieldType::Milliseconds),
+    (225, "post_nat_src_addr", FieldType::Ipv4Address),
+    (226, "post_nat_dst_addr", FieldType::Ipv4Address),
+    (227, "post_nat_src_port", FieldType::Unsigned),
+    (228, "post_nat_dst_port", FieldType::Unsigned),
+];
+
+/// Returns the name and type of an information element, if it is decoded.
+pub(super) fn lookup(id: u16) -> Option<(&'static str, FieldType)> {
+    FIELDS
+        .binary_search_by_key(&id, |(field_id, _, _)| *field_id)
+        .ok()
+        .map(|index| (FIELDS[index].1, FIELDS[index].2))
+}
+
+/// The clock of the exporter, used to turn system uptimes into timestamps.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ExportClock {
+    /// The time the packet was exported.
+    pub(super) export_time: DateTime<Utc>,
+    /// The system uptime, in milliseconds, when the packet was exported.
+    pub(super) sys_uptime: u32,
+}
+
+impl ExportClock {
+    /// Returns the time at which the system had the given uptime. The uptime wraps around after
+    /// about 49 days, so it is assumed to be in the past.
+    pub(super) fn timestamp(&self, uptime: u32) -> DateTime<Utc> {
+        self.export_time - TimeDelta::milliseconds(self.sys_uptime.wrapping_sub(uptime).into())
+    }
+}
+
+/// Decodes the content of an element, returning `None` if its length doesn't fit its type.
+pub(super) fn decode(
+    field_type: FieldType,
+    bytes: &[u8],
+    clock: Option<ExportClock>,
+) -> Option<Value> {
+    let value = match field_type {
+        FieldType::Unsigned => decode_unsigned(bytes)?,
+        FieldType::Ipv4Address => Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)
+            .to_string()
+            .into(),
+        FieldType::Ipv6Address => Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)
+            .to_string()
+            .into(),
+        FieldType::MacAddress => format_mac(bytes)?.into(),
+        FieldType::String => String::from_utf8_lossy(bytes)
+            .trim_end_matches('\0')
+            .to_owned()
+            .into(),
+        FieldType::SysUptime => {
+            let uptime = u32::try_from(unsigned(bytes)?).ok()?;
+            match clock {
+                Some(clock) => clock.timestamp(uptime).into(),
+                // Without the clock of the exporter, the uptime is kept as is.
+                None => i64::from(uptime).into(),
+            }
+        }
+        FieldType::Seconds => {
+            DateTime::from_timestamp(i64::try_from(unsigned(bytes)?).ok()?, 0)?.into()
+        }
+        FieldType::Milliseconds => {
+            DateTime::from_timestamp_millis(i64::try_from(unsigned(bytes)?).ok()?)?.into()
+        }
+    };
+    Some(value)
+}
+
+/// Decodes an unsigned integer of up to eight bytes. Exporters may send integers in fewer bytes
+/// than their type, which is known as reduced-size encoding.
+fn unsigned(bytes: &[u8]) -> Option<u64> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+    )
+}
+
+/// Decodes an unsigned integer, keeping values which don't fit in a signed integer exact, as
+/// strings.
+fn decode_unsigned(bytes: &[u8]) -> Option<Value> {
+    let value = unsigned(bytes)?;
+    Some(i64::try_from(value).map_or_else(|_| value.to_string().into(), Value::from))
+}
+
+/// Formats a MAC address as colon separated hexadecimal, such as `00:1a:2b:3c:4d:5e`.
+fn format_mac(bytes: &[u8]) -> Option<String> {
+    (bytes.len() == 6).then(|| format_hex(bytes))
+}
+
+/// Formats bytes as colon separated hexadecimal.
+pub(super) fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_sorted() {
+        assert!(FIELDS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn looks_up_fields() {
+        assert_eq!(Some(("src_addr", FieldType::Ipv4Address)), lookup(8));
+        assert_eq!(Some(("src_addr", FieldType::Ipv6Address)), lookup(27));
+        assert_eq!(None, lookup(999));
+    }
+
+    #[test]
+    fn decodes_fields() {
+        assert_eq!(
+            Some(Value::from(80)),
+            decode(FieldType::Unsigned, &[0x00, 0x50], None)
+        );
+        assert_eq!(
+            Some(Value::from(u64::MAX.to_string())),
+            decode(FieldType::Unsigned, &[0xff; 8], None)
+        );
+        assert_eq!(None, decode(FieldType::Unsigned, &[0xff; 9], None));
+        assert_eq!(
+            Some(Value::from("10.0.0.1")),
+            decode(FieldType::Ipv4Address, &[10, 0, 0, 1], None)
+        );
+        assert_eq!(None, decode(FieldType::Ipv4Address, &[10, 0, 0], None));
+        assert_eq!(
+            Some(Value::from("2001:db8::1")),
+            decode(
+                FieldType::Ipv6Address,
+                &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+                None
+            )
+        );
+        assert_eq!(
+            Some(Value::from("00:1a:2b:3c:4d:5e")),
+            decode(
+                FieldType::MacAddress,
+                &[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e],
+                None
+            )
+        );
+        assert_eq!(
+            Some(Value::from("eth0")),
+            decode(FieldType::String, b"eth0\0\0", None)
+        );
+        assert_eq!(
+            Some(Value::from(
+                DateTime::from_timestamp_millis(1_700_000_000_123).unwrap()
+            )),
+            decode(
+                FieldType::Milliseconds,
+                &1_700_000_000_123_u64.to_be_bytes(),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn converts_uptimes() {
+        let clock = ExportClock {
+            export_time: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            sys_uptime: 10_000,
+        };
+        assert_eq!(
+            Some(Value::from(
+                DateTime::from_timestamp_millis(1_699_999_998_500).unwrap()
+            )),
+            decode(FieldType::SysUptime, &8_500_u32.to_be_bytes(), Some(clock))
+        );
+        assert_eq!(
+            Some(Value::from(8_500)),
+            decode(FieldType::SysUptime, &8_500_u32.to_be_bytes(), None)
+        );
+
+        // The uptime wrapped around since the flow ended.
+        let clock = ExportClock {
+            sys_uptime: 1_000,
+            ..clock
+        };
+        assert_eq!(
+            DateTime::from_timestamp_millis(1_699_999_998_999).unwrap(),
+            clock.timestamp(u32::MAX)
+        );
+    }
+}
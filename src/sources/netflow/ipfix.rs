@@ -0,0 +1,271 @@
+//! Decoding of [IPFIX][ipfix] messages, whose records are described by templates.
+//!
+//! [ipfix]: https://www.rfc-editor.org/rfc/rfc7011
+use std::net::IpAddr;
+
+use super::{
+    DecodeError, DecodedPacket,
+    reader::{Reader, Truncated},
+    templates::{Template, TemplateCache, TemplateField, TemplateKey},
+};
+
+pub(super) const VERSION: u16 = 10;
+
+const HEADER_LENGTH: u16 = 16;
+const TEMPLATE_SET_ID: u16 = 2;
+const OPTIONS_TEMPLATE_SET_ID: u16 = 3;
+const MIN_DATA_SET_ID: u16 = 256;
+
+/// The bit of field identifiers which is set for enterprise-specific fields.
+const ENTERPRISE_BIT: u16 = 0x8000;
+
+pub(super) fn decode(
+    mut message: Reader<'_>,
+    exporter: IpAddr,
+    templates: &mut TemplateCache,
+) -> Result<DecodedPacket, DecodeError> {
+    let _version = message.u16()?;
+    let length = message.u16()?;
+    let _export_time = message.u32()?;
+    let sequence_number = message.u32()?;
+    let domain_id = message.u32()?;
+    let mut sets = message.sub_reader(
+        length
+            .checked_sub(HEADER_LENGTH)
+            .ok_or(DecodeError::InvalidMessageLength { length })?
+            .into(),
+    )?;
+
+    let key = |template_id| TemplateKey {
+        exporter,
+        version: VERSION,
+        domain_id,
+        template_id,
+    };
+
+    let mut decoded = DecodedPacket::default();
+    while !sets.is_empty() {
+        let set_id = sets.u16()?;
+        let length = sets.u16()?;
+        let mut set = sets.sub_reader(
+            usize::from(length)
+                .checked_sub(4)
+                .ok_or(DecodeError::InvalidSetLength { length })?,
+        )?;
+
+        match set_id {
+            TEMPLATE_SET_ID | OPTIONS_TEMPLATE_SET_ID => {
+                let options = set_id == OPTIONS_TEMPLATE_SET_ID;
+                // The set may be padded, with fewer bytes than a template record.
+                while set.len() >= 4 {
+                    let template_id = set.u16()?;
+                    let field_count = set.u16()?;
+                    if field_count == 0 {
+                        // A withdrawal of the template, or of every template if its ID is the
+                        // ID of the set.
+                        if template_id == set_id {
+                            templates.remove_domain(exporter, VERSION, domain_id);
+                        } else {
+                            templates.remove(&key(template_id));
+                        }
+                        continue;
+                    }
+                    if options {
+                        let _scope_field_count = set.u16()?;
+                    }
+                    let template = Template {
+                        fields: read_fields(&mut set, field_count)?,
+                        options,
+                    };
+                    templates.insert(key(template_id), template);
+                }
+            }
+            MIN_DATA_SET_ID.. => match templates.get(&key(set_id)) {
+                Some(template) if template.options => (),
+                Some(template) => {
+                    for mut flow in template.decode_records(set, None)? {
+                        flow.insert("flow_type".into(), "ipfix".into());
+                        flow.insert("sequence_number".into(), sequence_number.into());
+                        flow.insert("observation_domain_id".into(), domain_id.into());
+                        decoded.flows.push(flow);
+                    }
+                }
+                None => decoded.missing_templates.push(set_id),
+            },
+            // Reserved for future use.
+            _ => (),
+        }
+    }
+    Ok(decoded)
+}
+
+fn read_fields(set: &mut Reader<'_>, count: u16) -> Result<Vec<TemplateField>, Truncated> {
+    (0..count)
+        .map(|_| {
+            let id = set.u16()?;
+            let length = set.u16()?;
+            let enterprise = if id & ENTERPRISE_BIT != 0 {
+                Some(set.u32()?)
+            } else {
+                None
+            };
+            Ok(TemplateField {
+                id: id & !ENTERPRISE_BIT,
+                enterprise,
+                length,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use vrl::value::Value;
+
+    use super::*;
+    use crate::sources::netflow::templates::VARIABLE_LENGTH;
+
+    /// Wraps sets in a message of observation domain 1.
+    pub(in crate::sources::netflow) fn message(sets: &[Vec<u8>]) -> Vec<u8> {
+        let sets = sets.concat();
+        let mut data = Vec::new();
+        data.extend(VERSION.to_be_bytes());
+        data.extend((sets.len() as u16 + HEADER_LENGTH).to_be_bytes());
+        data.extend(1_700_000_000_u32.to_be_bytes());
+        data.extend(3_u32.to_be_bytes());
+        data.extend(1_u32.to_be_bytes());
+        data.extend(sets);
+        data
+    }
+
+    fn set(id: u16, content: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(id.to_be_bytes());
+        data.extend((content.len() as u16 + 4).to_be_bytes());
+        data.extend(content);
+        data
+    }
+
+    /// A template set of template 300, with the IPv6 source and destination addresses, the byte
+    /// count in reduced-size encoding, the start time, the interface name, and an
+    /// enterprise-specific field.
+    pub(in crate::sources::netflow) fn template_set() -> Vec<u8> {
+        let mut content = Vec::new();
+        content.extend(300_u16.to_be_bytes());
+        content.extend(6_u16.to_be_bytes());
+        for (id, length) in [
+            (27_u16, 16_u16),
+            (28, 16),
+            (1, 4),
+            (152, 8),
+            (82, VARIABLE_LENGTH),
+        ] {
+            content.extend(id.to_be_bytes());
+            content.extend(length.to_be_bytes());
+        }
+        content.extend((ENTERPRISE_BIT | 1).to_be_bytes());
+        content.extend(2_u16.to_be_bytes());
+        content.extend(29_305_u32.to_be_bytes());
+        set(TEMPLATE_SET_ID, &content)
+    }
+
+    /// A data set of template 300, with a single flow.
+    pub(in crate::sources::netflow) fn data_set() -> Vec<u8> {
+        let mut content = Vec::new();
+        content.extend(
+            "2001:db8::1"
+                .parse::<std::net::Ipv6Addr>()
+                .unwrap()
+                .octets(),
+        );
+        content.extend(
+            "2001:db8::2"
+                .parse::<std::net::Ipv6Addr>()
+                .unwrap()
+                .octets(),
+        );
+        content.extend(1_500_u32.to_be_bytes());
+        content.extend(1_700_000_000_123_u64.to_be_bytes());
+        content.push(4);
+        content.extend(b"eth0");
+        content.extend([0xab, 0xcd]);
+        set(300, &content)
+    }
+
+    fn exporter() -> IpAddr {
+        "192.0.2.1".parse().unwrap()
+    }
+
+    #[test]
+    fn decodes_messages() {
+        let mut templates = TemplateCache::default();
+        let data = message(&[template_set(), data_set()]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+
+        assert_eq!(1, decoded.flows.len());
+        let flow = &decoded.flows[0];
+        assert_eq!(Some(&Value::from("ipfix")), flow.get("flow_type"));
+        assert_eq!(Some(&Value::from(3)), flow.get("sequence_number"));
+        assert_eq!(Some(&Value::from(1)), flow.get("observation_domain_id"));
+        assert_eq!(Some(&Value::from("2001:db8::1")), flow.get("src_addr"));
+        assert_eq!(Some(&Value::from("2001:db8::2")), flow.get("dst_addr"));
+        assert_eq!(Some(&Value::from(1_500)), flow.get("bytes"));
+        assert_eq!(
+            Some(&Value::from(
+                chrono::DateTime::from_timestamp_millis(1_700_000_000_123).unwrap()
+            )),
+            flow.get("flow_start")
+        );
+        assert_eq!(Some(&Value::from("eth0")), flow.get("interface_name"));
+        assert_eq!(Some(&Value::from("ab:cd")), flow.get("field_29305_1"));
+    }
+
+    #[test]
+    fn withdraws_templates() {
+        let mut templates = TemplateCache::default();
+        let data = message(&[template_set()]);
+        decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+        assert!(
+            templates
+                .get(&TemplateKey {
+                    exporter: exporter(),
+                    version: VERSION,
+                    domain_id: 1,
+                    template_id: 300,
+                })
+                .is_some()
+        );
+
+        let withdrawal = set(TEMPLATE_SET_ID, &[0x01, 0x2c, 0, 0]);
+        let data = message(&[withdrawal, data_set()]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+        assert!(decoded.flows.is_empty());
+        assert_eq!(vec![300], decoded.missing_templates);
+        assert_eq!(0, templates.len());
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        let mut data = message(&[]);
+        data[2..4].copy_from_slice(&8_u16.to_be_bytes());
+        assert!(matches!(
+            decode(
+                Reader::new(&data),
+                exporter(),
+                &mut TemplateCache::default()
+            ),
+            Err(DecodeError::InvalidMessageLength { length: 8 })
+        ));
+
+        let mut data = message(&[template_set()]);
+        data.truncate(data.len() - 2);
+        assert!(matches!(
+            decode(
+                Reader::new(&data),
+                exporter(),
+                &mut TemplateCache::default()
+            ),
+            Err(DecodeError::Truncated { .. })
+        ));
+    }
+}
@@ -0,0 +1,448 @@
+//! The `netflow` source receives flow records from network devices, in the NetFlow v5, NetFlow v9,
+//! IPFIX and sFlow v5 formats, and emits one log per flow.
+use std::net::{IpAddr, SocketAddr};
+
+use chrono::Utc;
+use listenfd::ListenFd;
+use snafu::Snafu;
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LegacyKey, LogNamespace, log_schema},
+    configurable::configurable_component,
+    internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol},
+    lookup::{lookup_v2::OptionalValuePath, owned_value_path, path},
+    schema::Definition,
+};
+use vrl::value::{Kind, ObjectMap, kind::Collection};
+
+use self::{reader::Reader, templates::TemplateCache};
+use crate::{
+    SourceSender,
+    config::{DataType, Resource, SourceConfig, SourceContext, SourceOutput},
+    event::LogEvent,
+    internal_events::{
+        NetflowDecodeError, NetflowTemplateNotFound, SocketBindError, SocketEventsReceived,
+        SocketMode, SocketReceiveError, StreamClosedError,
+    },
+    net,
+    shutdown::ShutdownSignal,
+    sources::util::net::{SocketListenAddr, try_bind_udp_socket},
+};
+
+mod fields;
+mod ipfix;
+mod reader;
+mod sflow;
+mod templates;
+mod v5;
+mod v9;
+
+/// The maximum size of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Configuration for the `netflow` source.
+#[configurable_component(source(
+    "netflow",
+    "Receive NetFlow, IPFIX and sFlow flow records from network devices."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    /// The socket address to listen for flow packets on.
+    ///
+    /// The format of each packet is detected from its version, so exporters of every format can
+    /// send to the same address.
+    #[serde(default = "default_address")]
+    #[configurable(metadata(docs::examples = "0.0.0.0:2055"))]
+    #[configurable(metadata(docs::examples = "0.0.0.0:4739"))]
+    #[configurable(metadata(docs::examples = "0.0.0.0:6343"))]
+    #[configurable(metadata(docs::examples = "systemd"))]
+    address: SocketListenAddr,
+
+    /// The size of the receive buffer used for the listening socket.
+    ///
+    /// Flow exporters send bursts of packets, which may be dropped by the operating system if the
+    /// buffer is too small.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    receive_buffer_bytes: Option<usize>,
+
+    /// Overrides the name of the log field used to add the exporter host to each event.
+    ///
+    /// The value is the IP address of the host that sent the packet.
+    ///
+    /// By default, the [global `log_schema.host_key` option][global_host_key] is used.
+    ///
+    /// Set to `""` to suppress this key.
+    ///
+    /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+    host_key: Option<OptionalValuePath>,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+fn default_address() -> SocketListenAddr {
+    SocketListenAddr::SocketAddr("0.0.0.0:2055".parse().unwrap())
+}
+
+impl Default for NetflowConfig {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            receive_buffer_bytes: None,
+            host_key: None,
+            log_namespace: None,
+        }
+    }
+}
+
+impl_generate_config_from_default!(NetflowConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let decoder = Decoder {
+            templates: TemplateCache::default(),
+            host_key: self
+                .host_key
+                .clone()
+                .unwrap_or_else(|| log_schema().host_key().cloned().into()),
+            log_namespace: cx.log_namespace(self.log_namespace),
+        };
+
+        Ok(Box::pin(run(
+            self.address,
+            self.receive_buffer_bytes,
+            decoder,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().cloned().into())
+            .path
+            .map(LegacyKey::Overwrite);
+        let schema_definition = Definition::new_with_default_metadata(
+            Kind::object(Collection::from_unknown(Kind::any())),
+            [log_namespace],
+        )
+        .with_standard_vector_source_metadata()
+        .with_source_metadata(
+            Self::NAME,
+            host_key,
+            &owned_value_path!("host"),
+            Kind::bytes(),
+            Some("host"),
+        );
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.address.as_udp_resource()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum DecodeError {
+    #[snafu(context(false), display("{source}"))]
+    Truncated { source: reader::Truncated },
+
+    #[snafu(display("unsupported version {version}"))]
+    UnsupportedVersion { version: u32 },
+
+    #[snafu(display("invalid export time"))]
+    InvalidTimestamp,
+
+    #[snafu(display("invalid message length {length}"))]
+    InvalidMessageLength { length: u16 },
+
+    #[snafu(display("invalid set length {length}"))]
+    InvalidSetLength { length: u16 },
+
+    #[snafu(display("unsupported address type {address_type}"))]
+    UnsupportedAddressType { address_type: u32 },
+}
+
+/// The flows of a packet, and the IDs of the templates which were needed to decode the rest of
+/// it.
+#[derive(Debug, Default)]
+struct DecodedPacket {
+    flows: Vec<ObjectMap>,
+    missing_templates: Vec<u16>,
+}
+
+/// Turns the received packets into events, keeping the templates of each exporter.
+#[derive(Debug)]
+struct Decoder {
+    templates: TemplateCache,
+    host_key: OptionalValuePath,
+    log_namespace: LogNamespace,
+}
+
+impl Decoder {
+    fn decode(&mut self, data: &[u8], exporter: IpAddr) -> Result<DecodedPacket, DecodeError> {
+        let packet = Reader::new(data);
+        let flows = match Reader::new(data).u16()? {
+            v5::VERSION => v5::decode(packet)?,
+            v9::VERSION => return v9::decode(packet, exporter, &mut self.templates),
+            ipfix::VERSION => return ipfix::decode(packet, exporter, &mut self.templates),
+            // The version of sFlow is a 32 bit integer.
+            _ => match Reader::new(data).u32()? {
+                sflow::VERSION => sflow::decode(packet)?,
+                version => return UnsupportedVersionSnafu { version }.fail(),
+            },
+        };
+        Ok(DecodedPacket {
+            flows,
+            missing_templates: Vec::new(),
+        })
+    }
+
+    fn event(&self, flow: ObjectMap, peer_addr: SocketAddr) -> LogEvent {
+        let mut log = LogEvent::from(flow);
+        self.log_namespace.insert_standard_vector_source_metadata(
+            &mut log,
+            NetflowConfig::NAME,
+            Utc::now(),
+        );
+        self.log_namespace.insert_source_metadata(
+            NetflowConfig::NAME,
+            &mut log,
+            self.host_key.path.as_ref().map(LegacyKey::Overwrite),
+            path!("host"),
+            peer_addr.ip().to_string(),
+        );
+        log
+    }
+}
+
+async fn run(
+    address: SocketListenAddr,
+    receive_buffer_bytes: Option<usize>,
+    mut decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let listenfd = ListenFd::from_env();
+    let socket = try_bind_udp_socket(address, listenfd)
+        .await
+        .map_err(|error| {
+            emit!(SocketBindError {
+                mode: SocketMode::Udp,
+                error,
+            })
+        })?;
+
+    if let Some(receive_buffer_bytes) = receive_buffer_bytes
+        && let Err(error) = net::set_receive_buffer_size(&socket, receive_buffer_bytes)
+    {
+        warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+    }
+
+    let bytes_received = register!(BytesReceived::from(Protocol::UDP));
+
+    info!(message = "Listening.", address = %address);
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (byte_size, peer_addr) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => recv.map_err(|error| {
+                emit!(SocketReceiveError {
+                    mode: SocketMode::Udp,
+                    error,
+                })
+            })?,
+            _ = &mut shutdown => return Ok(()),
+        };
+        bytes_received.emit(ByteSize(byte_size));
+
+        let decoded = match decoder.decode(&buf[..byte_size], peer_addr.ip()) {
+            Ok(decoded) => decoded,
+            Err(error) => {
+                emit!(NetflowDecodeError { error, peer_addr });
+                continue;
+            }
+        };
+        for template_id in decoded.missing_templates {
+            emit!(NetflowTemplateNotFound {
+                template_id,
+                peer_addr
+            });
+        }
+        if decoded.flows.is_empty() {
+            continue;
+        }
+
+        let events = decoded
+            .flows
+            .into_iter()
+            .map(|flow| decoder.event(flow, peer_addr))
+            .collect::<Vec<_>>();
+        let count = events.len();
+        emit!(SocketEventsReceived {
+            mode: SocketMode::Udp,
+            byte_size: events.estimated_json_encoded_size_of(),
+            count,
+        });
+
+        tokio::select! {
+            result = out.send_batch(events) => {
+                if result.is_err() {
+                    emit!(StreamClosedError { count });
+                    return Err(());
+                }
+            }
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::{net::UdpSocket, time::timeout};
+    use vrl::value::Value;
+
+    use super::*;
+    use crate::{
+        config::ComponentKey,
+        test_util::{collect_n, next_addr},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    fn decoder(log_namespace: LogNamespace) -> Decoder {
+        Decoder {
+            templates: TemplateCache::default(),
+            host_key: log_schema().host_key().cloned().into(),
+            log_namespace,
+        }
+    }
+
+    fn exporter() -> IpAddr {
+        "192.0.2.1".parse().unwrap()
+    }
+
+    #[test]
+    fn detects_formats() {
+        let mut decoder = decoder(LogNamespace::Legacy);
+        let flow_type = |decoded: DecodedPacket| decoded.flows[0]["flow_type"].clone();
+
+        let decoded = decoder.decode(&v5::tests::packet(1), exporter()).unwrap();
+        assert_eq!(Value::from("netflow_v5"), flow_type(decoded));
+
+        let data = v9::tests::packet(&[v9::tests::template_set(), v9::tests::data_set()]);
+        let decoded = decoder.decode(&data, exporter()).unwrap();
+        assert_eq!(Value::from("netflow_v9"), flow_type(decoded));
+
+        let data = ipfix::tests::message(&[ipfix::tests::template_set(), ipfix::tests::data_set()]);
+        let decoded = decoder.decode(&data, exporter()).unwrap();
+        assert_eq!(Value::from("ipfix"), flow_type(decoded));
+
+        let decoded = decoder
+            .decode(&sflow::tests::datagram(), exporter())
+            .unwrap();
+        assert_eq!(Value::from("sflow_v5"), flow_type(decoded));
+
+        assert!(matches!(
+            decoder.decode(&[0, 7, 0, 0], exporter()),
+            Err(DecodeError::UnsupportedVersion {
+                version: 0x0007_0000
+            })
+        ));
+        assert!(matches!(
+            decoder.decode(&[0], exporter()),
+            Err(DecodeError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn creates_events() {
+        let decoder = decoder(LogNamespace::Legacy);
+        let mut flow = ObjectMap::new();
+        flow.insert("src_addr".into(), "10.0.0.1".into());
+        let log = decoder.event(flow, "192.0.2.1:2055".parse().unwrap());
+
+        assert_eq!(log["src_addr"], "10.0.0.1".into());
+        assert_eq!(log["host"], "192.0.2.1".into());
+        assert_eq!(log["source_type"], "netflow".into());
+    }
+
+    #[test]
+    fn creates_events_with_vector_namespace() {
+        let decoder = decoder(LogNamespace::Vector);
+        let mut flow = ObjectMap::new();
+        flow.insert("src_addr".into(), "10.0.0.1".into());
+        let log = decoder.event(flow, "192.0.2.1:2055".parse().unwrap());
+
+        assert_eq!(log["src_addr"], "10.0.0.1".into());
+        assert!(log.get("host").is_none());
+        assert_eq!(
+            log.metadata().value().get(path!("netflow", "host")),
+            Some(&Value::from("192.0.2.1"))
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_flows() {
+        let address = next_addr();
+        let config = NetflowConfig {
+            address: address.into(),
+            ..Default::default()
+        };
+
+        let (tx, rx) = SourceSender::new_test();
+        let (context, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("in"), tx);
+        let source = config.build(context).await.unwrap();
+        tokio::spawn(source);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        // The data of an unknown template is discarded.
+        socket
+            .send_to(&v9::tests::packet(&[v9::tests::data_set()]), address)
+            .await
+            .unwrap();
+        socket
+            .send_to(&v9::tests::packet(&[v9::tests::template_set()]), address)
+            .await
+            .unwrap();
+        socket
+            .send_to(&v9::tests::packet(&[v9::tests::data_set()]), address)
+            .await
+            .unwrap();
+        socket
+            .send_to(&v5::tests::packet(1), address)
+            .await
+            .unwrap();
+
+        let events = timeout(Duration::from_secs(5), collect_n(rx, 3))
+            .await
+            .unwrap();
+        assert_eq!(events[0].as_log()["flow_type"], "netflow_v9".into());
+        assert_eq!(events[1].as_log()["src_port"], 1025.into());
+        assert_eq!(events[2].as_log()["flow_type"], "netflow_v5".into());
+        assert_eq!(events[2].as_log()["host"], "127.0.0.1".into());
+
+        shutdown.shutdown_all(None).await;
+    }
+}
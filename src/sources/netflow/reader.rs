@@ -0,0 +1,75 @@
+use snafu::Snafu;
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+#[snafu(display("unexpected end of data"))]
+pub(super) struct Truncated;
+
+/// Reads the big-endian fields of a datagram, borrowing their content.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(super) const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub(super) const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub(super) fn bytes(&mut self, count: usize) -> Result<&'a [u8], Truncated> {
+        let (bytes, rest) = self.data.split_at_checked(count).ok_or(Truncated)?;
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Reads a fixed number of bytes, such as an address.
+    pub(super) fn array<const N: usize>(&mut self) -> Result<[u8; N], Truncated> {
+        self.bytes(N)
+            .map(|bytes| bytes.try_into().expect("length is checked"))
+    }
+
+    pub(super) fn skip(&mut self, count: usize) -> Result<(), Truncated> {
+        self.bytes(count).map(|_| ())
+    }
+
+    pub(super) fn u8(&mut self) -> Result<u8, Truncated> {
+        self.array().map(u8::from_be_bytes)
+    }
+
+    pub(super) fn u16(&mut self) -> Result<u16, Truncated> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, Truncated> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    /// Reads a length-prefixed element, returning a reader of its content.
+    pub(super) fn sub_reader(&mut self, length: usize) -> Result<Reader<'a>, Truncated> {
+        self.bytes(length).map(Reader::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_fields() {
+        let mut reader = Reader::new(&[0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0xff]);
+        assert_eq!(Ok(1), reader.u8());
+        assert_eq!(Ok(2), reader.u16());
+        assert_eq!(Ok(3), reader.u32());
+        assert_eq!(1, reader.len());
+        assert_eq!(Err(Truncated), reader.u16());
+        assert_eq!(Ok([0xff]), reader.array());
+        assert!(reader.is_empty());
+    }
+}
@@ -0,0 +1,439 @@
+//! Decoding of [sFlow v5][sflow] datagrams, whose flow samples are turned into flow records.
+//!
+//! Counter samples, and the records of other enterprises, are skipped.
+//!
+//! [sflow]: https://sflow.org/sflow_version_5.txt
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    DecodeError,
+    fields::format_hex,
+    reader::{Reader, Truncated},
+};
+
+pub(super) const VERSION: u32 = 5;
+
+const FLOW_SAMPLE: u32 = 1;
+const EXPANDED_FLOW_SAMPLE: u32 = 3;
+
+const RAW_PACKET_HEADER: u32 = 1;
+const IPV4_DATA: u32 = 3;
+const IPV6_DATA: u32 = 4;
+const EXTENDED_SWITCH: u32 = 1001;
+const EXTENDED_ROUTER: u32 = 1002;
+const EXTENDED_GATEWAY: u32 = 1003;
+
+const HEADER_PROTOCOL_ETHERNET: u32 = 1;
+const HEADER_PROTOCOL_IPV4: u32 = 11;
+const HEADER_PROTOCOL_IPV6: u32 = 12;
+
+const ETHER_TYPE_IPV4: u16 = 0x0800;
+const ETHER_TYPE_IPV6: u16 = 0x86dd;
+const ETHER_TYPE_VLAN: u16 = 0x8100;
+
+const PROTOCOL_TCP: u8 = 6;
+const PROTOCOL_UDP: u8 = 17;
+
+pub(super) fn decode(mut datagram: Reader<'_>) -> Result<Vec<ObjectMap>, DecodeError> {
+    let _version = datagram.u32()?;
+    let agent_address = address(&mut datagram)?;
+    let sub_agent_id = datagram.u32()?;
+    let sequence_number = datagram.u32()?;
+    let _uptime = datagram.u32()?;
+    let sample_count = datagram.u32()?;
+
+    let mut flows = Vec::new();
+    for _ in 0..sample_count {
+        let format = datagram.u32()?;
+        let length = datagram.u32()?;
+        let mut sample = datagram.sub_reader(length as usize)?;
+
+        let mut flow = match format {
+            FLOW_SAMPLE => decode_flow_sample(&mut sample)?,
+            EXPANDED_FLOW_SAMPLE => decode_expanded_flow_sample(&mut sample)?,
+            // Counter samples, and samples of other enterprises.
+            _ => continue,
+        };
+        flow.insert("flow_type".into(), "sflow_v5".into());
+        flow.insert("agent_address".into(), agent_address.clone());
+        flow.insert("sub_agent_id".into(), sub_agent_id.into());
+        flow.insert("sequence_number".into(), sequence_number.into());
+        flows.push(flow);
+    }
+    Ok(flows)
+}
+
+fn decode_flow_sample(sample: &mut Reader<'_>) -> Result<ObjectMap, DecodeError> {
+    let mut flow = ObjectMap::new();
+    let _sample_sequence_number = sample.u32()?;
+    let _source_id = sample.u32()?;
+    insert_sampling(sample, &mut flow)?;
+    // The two most significant bits give the format of the interface, which is an index if zero.
+    let input = sample.u32()?;
+    let output = sample.u32()?;
+    insert_interface(
+        &mut flow,
+        "input_interface",
+        input >> 30,
+        input & 0x3fff_ffff,
+    );
+    insert_interface(
+        &mut flow,
+        "output_interface",
+        output >> 30,
+        output & 0x3fff_ffff,
+    );
+    decode_flow_records(sample, &mut flow)?;
+    Ok(flow)
+}
+
+fn decode_expanded_flow_sample(sample: &mut Reader<'_>) -> Result<ObjectMap, DecodeError> {
+    let mut flow = ObjectMap::new();
+    let _sample_sequence_number = sample.u32()?;
+    let _source_id_type = sample.u32()?;
+    let _source_id_index = sample.u32()?;
+    insert_sampling(sample, &mut flow)?;
+    let input_format = sample.u32()?;
+    let input = sample.u32()?;
+    let output_format = sample.u32()?;
+    let output = sample.u32()?;
+    insert_interface(&mut flow, "input_interface", input_format, input);
+    insert_interface(&mut flow, "output_interface", output_format, output);
+    decode_flow_records(sample, &mut flow)?;
+    Ok(flow)
+}
+
+fn insert_sampling(sample: &mut Reader<'_>, flow: &mut ObjectMap) -> Result<(), Truncated> {
+    flow.insert("sampling_rate".into(), sample.u32()?.into());
+    flow.insert("sample_pool".into(), sample.u32()?.into());
+    flow.insert("drops".into(), sample.u32()?.into());
+    Ok(())
+}
+
+fn insert_interface(flow: &mut ObjectMap, name: &str, format: u32, value: u32) {
+    if format == 0 {
+        flow.insert(name.into(), value.into());
+    }
+}
+
+fn decode_flow_records(sample: &mut Reader<'_>, flow: &mut ObjectMap) -> Result<(), DecodeError> {
+    let record_count = sample.u32()?;
+    for _ in 0..record_count {
+        let format = sample.u32()?;
+        let length = sample.u32()?;
+        let mut record = sample.sub_reader(length as usize)?;
+        match format {
+            RAW_PACKET_HEADER => {
+                let protocol = record.u32()?;
+                let frame_length = record.u32()?;
+                let _stripped = record.u32()?;
+                let header_length = record.u32()?;
+                let header = record.bytes(header_length as usize)?;
+                flow.insert("bytes".into(), frame_length.into());
+                // Sampled headers are cut at a given length, so whatever could be decoded
+                // before the end of the header is kept.
+                let _ = match protocol {
+                    HEADER_PROTOCOL_ETHERNET => decode_ethernet(Reader::new(header), flow),
+                    HEADER_PROTOCOL_IPV4 => decode_ipv4(Reader::new(header), flow),
+                    HEADER_PROTOCOL_IPV6 => decode_ipv6(Reader::new(header), flow),
+                    _ => Ok(()),
+                };
+            }
+            IPV4_DATA | IPV6_DATA => {
+                flow.insert("bytes".into(), record.u32()?.into());
+                flow.insert("protocol".into(), record.u32()?.into());
+                let (src_addr, dst_addr) = if format == IPV4_DATA {
+                    (ipv4(&mut record)?, ipv4(&mut record)?)
+                } else {
+                    (ipv6(&mut record)?, ipv6(&mut record)?)
+                };
+                flow.insert("src_addr".into(), src_addr);
+                flow.insert("dst_addr".into(), dst_addr);
+                flow.insert("src_port".into(), record.u32()?.into());
+                flow.insert("dst_port".into(), record.u32()?.into());
+                flow.insert("tcp_flags".into(), record.u32()?.into());
+                flow.insert("tos".into(), record.u32()?.into());
+            }
+            EXTENDED_SWITCH => {
+                flow.insert("src_vlan".into(), record.u32()?.into());
+                let _src_priority = record.u32()?;
+                flow.insert("dst_vlan".into(), record.u32()?.into());
+            }
+            EXTENDED_ROUTER => {
+                flow.insert("next_hop".into(), address(&mut record)?);
+                flow.insert("src_mask".into(), record.u32()?.into());
+                flow.insert("dst_mask".into(), record.u32()?.into());
+            }
+            EXTENDED_GATEWAY => {
+                flow.insert("bgp_next_hop".into(), address(&mut record)?);
+                let _as = record.u32()?;
+                flow.insert("src_as".into(), record.u32()?.into());
+                let _src_peer_as = record.u32()?;
+                // The destination AS is the last one of the AS path.
+                let mut dst_as = None;
+                for _ in 0..record.u32()? {
+                    let _segment_type = record.u32()?;
+                    for _ in 0..record.u32()? {
+                        dst_as = Some(record.u32()?);
+                    }
+                }
+                if let Some(dst_as) = dst_as {
+                    flow.insert("dst_as".into(), dst_as.into());
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Reads an address, preceded by its type.
+fn address(reader: &mut Reader<'_>) -> Result<Value, DecodeError> {
+    match reader.u32()? {
+        1 => Ok(ipv4(reader)?),
+        2 => Ok(ipv6(reader)?),
+        address_type => Err(DecodeError::UnsupportedAddressType { address_type }),
+    }
+}
+
+fn ipv4(reader: &mut Reader<'_>) -> Result<Value, Truncated> {
+    Ok(Ipv4Addr::from(reader.array::<4>()?).to_string().into())
+}
+
+fn ipv6(reader: &mut Reader<'_>) -> Result<Value, Truncated> {
+    Ok(Ipv6Addr::from(reader.array::<16>()?).to_string().into())
+}
+
+fn decode_ethernet(mut header: Reader<'_>, flow: &mut ObjectMap) -> Result<(), Truncated> {
+    flow.insert("dst_mac".into(), format_hex(header.bytes(6)?).into());
+    flow.insert("src_mac".into(), format_hex(header.bytes(6)?).into());
+    let mut ether_type = header.u16()?;
+    if ether_type == ETHER_TYPE_VLAN {
+        flow.insert("vlan_id".into(), (header.u16()? & 0x0fff).into());
+        ether_type = header.u16()?;
+    }
+    flow.insert("ether_type".into(), ether_type.into());
+    match ether_type {
+        ETHER_TYPE_IPV4 => decode_ipv4(header, flow),
+        ETHER_TYPE_IPV6 => decode_ipv6(header, flow),
+        _ => Ok(()),
+    }
+}
+
+fn decode_ipv4(mut header: Reader<'_>, flow: &mut ObjectMap) -> Result<(), Truncated> {
+    let header_length = usize::from(header.u8()? & 0x0f) * 4;
+    flow.insert("tos".into(), i64::from(header.u8()?).into());
+    let _total_length = header.u16()?;
+    let _identification = header.u16()?;
+    let fragment_offset = header.u16()? & 0x1fff;
+    let _ttl = header.u8()?;
+    let protocol = header.u8()?;
+    flow.insert("protocol".into(), i64::from(protocol).into());
+    let _checksum = header.u16()?;
+    flow.insert("src_addr".into(), ipv4(&mut header)?);
+    flow.insert("dst_addr".into(), ipv4(&mut header)?);
+    // Only the first fragment has the transport header.
+    if fragment_offset == 0 {
+        header.skip(header_length.saturating_sub(20))?;
+        decode_transport(header, protocol, flow)?;
+    }
+    Ok(())
+}
+
+fn decode_ipv6(mut header: Reader<'_>, flow: &mut ObjectMap) -> Result<(), Truncated> {
+    let version_class_label = header.u32()?;
+    flow.insert("tos".into(), ((version_class_label >> 20) & 0xff).into());
+    let _payload_length = header.u16()?;
+    let next_header = header.u8()?;
+    flow.insert("protocol".into(), i64::from(next_header).into());
+    let _hop_limit = header.u8()?;
+    flow.insert("src_addr".into(), ipv6(&mut header)?);
+    flow.insert("dst_addr".into(), ipv6(&mut header)?);
+    decode_transport(header, next_header, flow)
+}
+
+fn decode_transport(
+    mut header: Reader<'_>,
+    protocol: u8,
+    flow: &mut ObjectMap,
+) -> Result<(), Truncated> {
+    if protocol != PROTOCOL_TCP && protocol != PROTOCOL_UDP {
+        return Ok(());
+    }
+    flow.insert("src_port".into(), header.u16()?.into());
+    flow.insert("dst_port".into(), header.u16()?.into());
+    if protocol == PROTOCOL_TCP {
+        header.skip(9)?;
+        flow.insert("tcp_flags".into(), i64::from(header.u8()?).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    fn record(format: u32, content: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(format.to_be_bytes());
+        data.extend((content.len() as u32).to_be_bytes());
+        data.extend(content);
+        data
+    }
+
+    /// A sampled TCP packet from `10.0.0.1:1024` to `10.0.0.2:443`, in a VLAN.
+    fn packet_header() -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+        header.extend([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5f]);
+        header.extend(ETHER_TYPE_VLAN.to_be_bytes());
+        header.extend(100_u16.to_be_bytes());
+        header.extend(ETHER_TYPE_IPV4.to_be_bytes());
+        header.extend([
+            0x45,
+            0x10,
+            0x05,
+            0xdc,
+            0,
+            0,
+            0x40,
+            0,
+            64,
+            PROTOCOL_TCP,
+            0,
+            0,
+        ]);
+        header.extend([10, 0, 0, 1, 10, 0, 0, 2]);
+        header.extend(1024_u16.to_be_bytes());
+        header.extend(443_u16.to_be_bytes());
+        header.extend([0, 0, 0, 0, 0, 0, 0, 0, 0x50, 0x18]);
+
+        let mut content = Vec::new();
+        content.extend(HEADER_PROTOCOL_ETHERNET.to_be_bytes());
+        content.extend(1_500_u32.to_be_bytes());
+        content.extend(4_u32.to_be_bytes());
+        content.extend((header.len() as u32).to_be_bytes());
+        content.extend(&header);
+        record(RAW_PACKET_HEADER, &content)
+    }
+
+    /// A datagram of an agent at `192.0.2.10`, with a flow sample and a counter sample.
+    pub(in crate::sources::netflow) fn datagram() -> Vec<u8> {
+        let mut router = Vec::new();
+        router.extend(1_u32.to_be_bytes());
+        router.extend([10, 0, 0, 254]);
+        router.extend(24_u32.to_be_bytes());
+        router.extend(16_u32.to_be_bytes());
+
+        let mut gateway = Vec::new();
+        gateway.extend(1_u32.to_be_bytes());
+        gateway.extend([10, 0, 0, 253]);
+        gateway.extend(64_512_u32.to_be_bytes());
+        gateway.extend(64_513_u32.to_be_bytes());
+        gateway.extend(64_514_u32.to_be_bytes());
+        gateway.extend(1_u32.to_be_bytes());
+        gateway.extend(2_u32.to_be_bytes());
+        gateway.extend(2_u32.to_be_bytes());
+        gateway.extend(64_514_u32.to_be_bytes());
+        gateway.extend(15_169_u32.to_be_bytes());
+
+        let mut sample = Vec::new();
+        sample.extend(1_u32.to_be_bytes());
+        sample.extend(3_u32.to_be_bytes());
+        sample.extend(512_u32.to_be_bytes());
+        sample.extend(1_024_u32.to_be_bytes());
+        sample.extend(0_u32.to_be_bytes());
+        sample.extend(3_u32.to_be_bytes());
+        sample.extend(4_u32.to_be_bytes());
+        sample.extend(3_u32.to_be_bytes());
+        sample.extend(packet_header());
+        sample.extend(record(EXTENDED_ROUTER, &router));
+        sample.extend(record(EXTENDED_GATEWAY, &gateway));
+
+        let mut data = Vec::new();
+        data.extend(VERSION.to_be_bytes());
+        data.extend(1_u32.to_be_bytes());
+        data.extend([192, 0, 2, 10]);
+        data.extend(0_u32.to_be_bytes());
+        data.extend(9_u32.to_be_bytes());
+        data.extend(60_000_u32.to_be_bytes());
+        data.extend(2_u32.to_be_bytes());
+        data.extend(record(FLOW_SAMPLE, &sample));
+        data.extend(record(2, &[0; 8]));
+        data
+    }
+
+    #[test]
+    fn decodes_datagrams() {
+        let flows = decode(Reader::new(&datagram())).unwrap();
+        assert_eq!(1, flows.len());
+
+        let flow = &flows[0];
+        assert_eq!(Some(&Value::from("sflow_v5")), flow.get("flow_type"));
+        assert_eq!(Some(&Value::from("192.0.2.10")), flow.get("agent_address"));
+        assert_eq!(Some(&Value::from(0)), flow.get("sub_agent_id"));
+        assert_eq!(Some(&Value::from(9)), flow.get("sequence_number"));
+        assert_eq!(Some(&Value::from(512)), flow.get("sampling_rate"));
+        assert_eq!(Some(&Value::from(1_024)), flow.get("sample_pool"));
+        assert_eq!(Some(&Value::from(0)), flow.get("drops"));
+        assert_eq!(Some(&Value::from(3)), flow.get("input_interface"));
+        assert_eq!(Some(&Value::from(4)), flow.get("output_interface"));
+        assert_eq!(Some(&Value::from(1_500)), flow.get("bytes"));
+        assert_eq!(Some(&Value::from("00:1a:2b:3c:4d:5e")), flow.get("dst_mac"));
+        assert_eq!(Some(&Value::from("00:1a:2b:3c:4d:5f")), flow.get("src_mac"));
+        assert_eq!(Some(&Value::from(100)), flow.get("vlan_id"));
+        assert_eq!(Some(&Value::from(0x0800)), flow.get("ether_type"));
+        assert_eq!(Some(&Value::from(0x10)), flow.get("tos"));
+        assert_eq!(Some(&Value::from(6)), flow.get("protocol"));
+        assert_eq!(Some(&Value::from("10.0.0.1")), flow.get("src_addr"));
+        assert_eq!(Some(&Value::from("10.0.0.2")), flow.get("dst_addr"));
+        assert_eq!(Some(&Value::from(1_024)), flow.get("src_port"));
+        assert_eq!(Some(&Value::from(443)), flow.get("dst_port"));
+        assert_eq!(Some(&Value::from(0x18)), flow.get("tcp_flags"));
+        assert_eq!(Some(&Value::from("10.0.0.254")), flow.get("next_hop"));
+        assert_eq!(Some(&Value::from(24)), flow.get("src_mask"));
+        assert_eq!(Some(&Value::from(16)), flow.get("dst_mask"));
+        assert_eq!(Some(&Value::from("10.0.0.253")), flow.get("bgp_next_hop"));
+        assert_eq!(Some(&Value::from(64_513)), flow.get("src_as"));
+        assert_eq!(Some(&Value::from(15_169)), flow.get("dst_as"));
+    }
+
+    #[test]
+    fn keeps_fields_of_truncated_headers() {
+        let mut flow = ObjectMap::new();
+        let header = [
+            0x45,
+            0x00,
+            0x00,
+            0x28,
+            0,
+            0,
+            0,
+            0,
+            64,
+            PROTOCOL_UDP,
+            0,
+            0,
+            10,
+            0,
+            0,
+            1,
+        ];
+        assert_eq!(Err(Truncated), decode_ipv4(Reader::new(&header), &mut flow));
+        assert_eq!(Some(&Value::from(17)), flow.get("protocol"));
+        assert_eq!(Some(&Value::from("10.0.0.1")), flow.get("src_addr"));
+        assert!(flow.get("dst_addr").is_none());
+    }
+
+    #[test]
+    fn rejects_unsupported_addresses() {
+        let mut data = datagram();
+        data[4..8].copy_from_slice(&3_u32.to_be_bytes());
+        assert!(matches!(
+            decode(Reader::new(&data)),
+            Err(DecodeError::UnsupportedAddressType { address_type: 3 })
+        ));
+    }
+}
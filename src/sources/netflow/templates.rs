@@ -0,0 +1,237 @@
+//! The templates of NetFlow v9 and IPFIX, which describe the fields of the data records sent by
+//! an exporter.
+use std::{collections::HashMap, net::IpAddr};
+
+use vrl::value::{KeyString, ObjectMap};
+
+use super::{
+    fields::{self, ExportClock},
+    reader::{Reader, Truncated},
+};
+
+/// The length of IPFIX fields whose length is given in each record.
+pub(super) const VARIABLE_LENGTH: u16 = 0xffff;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct TemplateField {
+    pub(super) id: u16,
+    /// The private enterprise number of enterprise-specific IPFIX fields.
+    pub(super) enterprise: Option<u32>,
+    pub(super) length: u16,
+}
+
+impl TemplateField {
+    fn decode(&self, bytes: &[u8], clock: Option<ExportClock>, record: &mut ObjectMap) {
+        let decoded = match self.enterprise {
+            None => fields::lookup(self.id).and_then(|(name, field_type)| {
+                fields::decode(field_type, bytes, clock).map(|value| (name, value))
+            }),
+            Some(_) => None,
+        };
+        // Fields which aren't known, or don't fit their type, are kept as hexadecimal.
+        match decoded {
+            Some((name, value)) => {
+                record.insert(name.into(), value);
+            }
+            None => {
+                let name = match self.enterprise {
+                    Some(enterprise) => format!("field_{enterprise}_{}", self.id),
+                    None => format!("field_{}", self.id),
+                };
+                record.insert(KeyString::from(name), fields::format_hex(bytes).into());
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Template {
+    pub(super) fields: Vec<TemplateField>,
+    /// Whether this is an options template, whose records describe the exporter rather than
+    /// flows.
+    pub(super) options: bool,
+}
+
+impl Template {
+    /// The minimum length of a record, which is used to tell records from the padding at the end
+    /// of a set.
+    fn min_record_length(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => usize::from(length),
+            })
+            .sum()
+    }
+
+    /// Decodes the records of a data set.
+    pub(super) fn decode_records(
+        &self,
+        mut set: Reader<'_>,
+        clock: Option<ExportClock>,
+    ) -> Result<Vec<ObjectMap>, Truncated> {
+        let min_record_length = self.min_record_length();
+        let mut records = Vec::new();
+        if min_record_length == 0 {
+            return Ok(records);
+        }
+
+        while set.len() >= min_record_length {
+            let mut record = ObjectMap::new();
+            for field in &self.fields {
+                let length = match field.length {
+                    VARIABLE_LENGTH => match set.u8()? {
+                        255 => usize::from(set.u16()?),
+                        length => usize::from(length),
+                    },
+                    length => usize::from(length),
+                };
+                field.decode(set.bytes(length)?, clock, &mut record);
+            }
+            records.push(record);
+        }
+        Ok(records)
+    }
+}
+
+/// Identifies a template. Template IDs are only unique within an observation domain of an
+/// exporter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) struct TemplateKey {
+    pub(super) exporter: IpAddr,
+    pub(super) version: u16,
+    /// The source ID of NetFlow v9, or the observation domain ID of IPFIX.
+    pub(super) domain_id: u32,
+    pub(super) template_id: u16,
+}
+
+/// The templates received from every exporter.
+#[derive(Debug, Default)]
+pub(super) struct TemplateCache {
+    templates: HashMap<TemplateKey, Template>,
+}
+
+impl TemplateCache {
+    pub(super) fn get(&self, key: &TemplateKey) -> Option<&Template> {
+        self.templates.get(key)
+    }
+
+    pub(super) fn insert(&mut self, key: TemplateKey, template: Template) {
+        self.templates.insert(key, template);
+    }
+
+    pub(super) fn remove(&mut self, key: &TemplateKey) {
+        self.templates.remove(key);
+    }
+
+    /// Removes every template of an observation domain.
+    pub(super) fn remove_domain(&mut self, exporter: IpAddr, version: u16, domain_id: u32) {
+        self.templates.retain(|key, _| {
+            key.exporter != exporter || key.version != version || key.domain_id != domain_id
+        });
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.templates.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::value::Value;
+
+    use super::*;
+
+    fn field(id: u16, length: u16) -> TemplateField {
+        TemplateField {
+            id,
+            enterprise: None,
+            length,
+        }
+    }
+
+    #[test]
+    fn decodes_records() {
+        let template = Template {
+            fields: vec![field(8, 4), field(7, 2), field(999, 2)],
+            options: false,
+        };
+        // Two records, followed by padding.
+        let data = [
+            10, 0, 0, 1, 0, 80, 0xab, 0xcd, 10, 0, 0, 2, 0, 81, 0, 0, 0, 0,
+        ];
+        let records = template.decode_records(Reader::new(&data), None).unwrap();
+
+        assert_eq!(2, records.len());
+        assert_eq!(Some(&Value::from("10.0.0.1")), records[0].get("src_addr"));
+        assert_eq!(Some(&Value::from(80)), records[0].get("src_port"));
+        assert_eq!(Some(&Value::from("ab:cd")), records[0].get("field_999"));
+        assert_eq!(Some(&Value::from(81)), records[1].get("src_port"));
+    }
+
+    #[test]
+    fn decodes_variable_length_fields() {
+        let template = Template {
+            fields: vec![
+                field(82, VARIABLE_LENGTH),
+                TemplateField {
+                    id: 1,
+                    enterprise: Some(29305),
+                    length: VARIABLE_LENGTH,
+                },
+            ],
+            options: false,
+        };
+        let mut data = vec![4];
+        data.extend(b"eth0");
+        data.extend([255, 0, 3, 1, 2, 3]);
+        let records = template.decode_records(Reader::new(&data), None).unwrap();
+
+        assert_eq!(1, records.len());
+        assert_eq!(Some(&Value::from("eth0")), records[0].get("interface_name"));
+        assert_eq!(
+            Some(&Value::from("01:02:03")),
+            records[0].get("field_29305_1")
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_fields_as_hex() {
+        let template = Template {
+            fields: vec![field(8, 3)],
+            options: false,
+        };
+        let records = template
+            .decode_records(Reader::new(&[10, 0, 0]), None)
+            .unwrap();
+        assert_eq!(Some(&Value::from("0a:00:00")), records[0].get("field_8"));
+    }
+
+    #[test]
+    fn removes_domains() {
+        let exporter = "192.0.2.1".parse().unwrap();
+        let key = |domain_id, template_id| TemplateKey {
+            exporter,
+            version: 10,
+            domain_id,
+            template_id,
+        };
+        let template = Template {
+            fields: vec![field(8, 4)],
+            options: false,
+        };
+
+        let mut cache = TemplateCache::default();
+        cache.insert(key(1, 256), template.clone());
+        cache.insert(key(1, 257), template.clone());
+        cache.insert(key(2, 256), template);
+        cache.remove(&key(1, 257));
+        assert_eq!(2, cache.len());
+
+        cache.remove_domain(exporter, 10, 1);
+        assert_eq!(1, cache.len());
+        assert!(cache.get(&key(2, 256)).is_some());
+    }
+}
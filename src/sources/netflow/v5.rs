@@ -0,0 +1,165 @@
+//! Decoding of [NetFlow v5][v5] packets, whose records have a fixed format.
+//!
+//! [v5]: https://www.cisco.com/c/en/us/td/docs/net_mgmt/netflow_collection_engine/3-6/user/guide/format.html
+use std::net::Ipv4Addr;
+
+use chrono::DateTime;
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    DecodeError,
+    fields::ExportClock,
+    reader::{Reader, Truncated},
+};
+
+pub(super) const VERSION: u16 = 5;
+
+/// The length of a flow record.
+const RECORD_LENGTH: usize = 48;
+
+pub(super) fn decode(mut packet: Reader<'_>) -> Result<Vec<ObjectMap>, DecodeError> {
+    let _version = packet.u16()?;
+    let count = packet.u16()?;
+    let sys_uptime = packet.u32()?;
+    let unix_secs = packet.u32()?;
+    let unix_nsecs = packet.u32()?;
+    let sequence_number = packet.u32()?;
+    let engine_type = packet.u8()?;
+    let engine_id = packet.u8()?;
+    // The two most significant bits give the sampling mode.
+    let sampling_interval = packet.u16()? & 0x3fff;
+
+    let clock = ExportClock {
+        export_time: DateTime::from_timestamp(unix_secs.into(), unix_nsecs)
+            .ok_or(DecodeError::InvalidTimestamp)?,
+        sys_uptime,
+    };
+
+    let mut records = packet.sub_reader(usize::from(count) * RECORD_LENGTH)?;
+    let mut flows = Vec::with_capacity(count.into());
+    while !records.is_empty() {
+        let mut flow = decode_record(&mut records, clock)?;
+        flow.insert("flow_type".into(), "netflow_v5".into());
+        flow.insert("sequence_number".into(), sequence_number.into());
+        flow.insert("engine_type".into(), i64::from(engine_type).into());
+        flow.insert("engine_id".into(), i64::from(engine_id).into());
+        flow.insert("sampling_interval".into(), sampling_interval.into());
+        flows.push(flow);
+    }
+    Ok(flows)
+}
+
+fn decode_record(record: &mut Reader<'_>, clock: ExportClock) -> Result<ObjectMap, Truncated> {
+    let mut flow = ObjectMap::new();
+    let mut insert = |name: &str, value: Value| {
+        flow.insert(name.into(), value);
+    };
+    insert("src_addr", address(record)?);
+    insert("dst_addr", address(record)?);
+    insert("next_hop", address(record)?);
+    insert("input_interface", record.u16()?.into());
+    insert("output_interface", record.u16()?.into());
+    insert("packets", record.u32()?.into());
+    insert("bytes", record.u32()?.into());
+    insert("flow_start", clock.timestamp(record.u32()?).into());
+    insert("flow_end", clock.timestamp(record.u32()?).into());
+    insert("src_port", record.u16()?.into());
+    insert("dst_port", record.u16()?.into());
+    record.skip(1)?;
+    insert("tcp_flags", i64::from(record.u8()?).into());
+    insert("protocol", i64::from(record.u8()?).into());
+    insert("tos", i64::from(record.u8()?).into());
+    insert("src_as", record.u16()?.into());
+    insert("dst_as", record.u16()?.into());
+    insert("src_mask", i64::from(record.u8()?).into());
+    insert("dst_mask", i64::from(record.u8()?).into());
+    record.skip(2)?;
+    Ok(flow)
+}
+
+fn address(record: &mut Reader<'_>) -> Result<Value, Truncated> {
+    Ok(Ipv4Addr::from(record.array::<4>()?).to_string().into())
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Builds a packet of flow records between `10.0.0.1:<1024 + index>` and `10.0.0.2:443`.
+    pub(in crate::sources::netflow) fn packet(count: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(VERSION.to_be_bytes());
+        data.extend(count.to_be_bytes());
+        data.extend(10_000_u32.to_be_bytes());
+        data.extend(1_700_000_000_u32.to_be_bytes());
+        data.extend(0_u32.to_be_bytes());
+        data.extend(42_u32.to_be_bytes());
+        data.extend([1, 2]);
+        data.extend((0x4000_u16 | 100).to_be_bytes());
+        for index in 0..count {
+            data.extend([10, 0, 0, 1, 10, 0, 0, 2, 10, 0, 0, 254]);
+            data.extend(3_u16.to_be_bytes());
+            data.extend(4_u16.to_be_bytes());
+            data.extend(5_u32.to_be_bytes());
+            data.extend(1_500_u32.to_be_bytes());
+            data.extend(8_000_u32.to_be_bytes());
+            data.extend(9_000_u32.to_be_bytes());
+            data.extend((1024 + index).to_be_bytes());
+            data.extend(443_u16.to_be_bytes());
+            data.extend([0, 0x1b, 6, 0]);
+            data.extend(64_512_u16.to_be_bytes());
+            data.extend(15_169_u16.to_be_bytes());
+            data.extend([24, 16, 0, 0]);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_packets() {
+        let flows = decode(Reader::new(&packet(2))).unwrap();
+        assert_eq!(2, flows.len());
+
+        let flow = &flows[1];
+        assert_eq!(Some(&Value::from("netflow_v5")), flow.get("flow_type"));
+        assert_eq!(Some(&Value::from(42)), flow.get("sequence_number"));
+        assert_eq!(Some(&Value::from(1)), flow.get("engine_type"));
+        assert_eq!(Some(&Value::from(2)), flow.get("engine_id"));
+        assert_eq!(Some(&Value::from(100)), flow.get("sampling_interval"));
+        assert_eq!(Some(&Value::from("10.0.0.1")), flow.get("src_addr"));
+        assert_eq!(Some(&Value::from("10.0.0.2")), flow.get("dst_addr"));
+        assert_eq!(Some(&Value::from("10.0.0.254")), flow.get("next_hop"));
+        assert_eq!(Some(&Value::from(3)), flow.get("input_interface"));
+        assert_eq!(Some(&Value::from(4)), flow.get("output_interface"));
+        assert_eq!(Some(&Value::from(5)), flow.get("packets"));
+        assert_eq!(Some(&Value::from(1_500)), flow.get("bytes"));
+        assert_eq!(
+            Some(&Value::from(
+                DateTime::from_timestamp(1_699_999_998, 0).unwrap()
+            )),
+            flow.get("flow_start")
+        );
+        assert_eq!(
+            Some(&Value::from(
+                DateTime::from_timestamp(1_699_999_999, 0).unwrap()
+            )),
+            flow.get("flow_end")
+        );
+        assert_eq!(Some(&Value::from(1_025)), flow.get("src_port"));
+        assert_eq!(Some(&Value::from(443)), flow.get("dst_port"));
+        assert_eq!(Some(&Value::from(0x1b)), flow.get("tcp_flags"));
+        assert_eq!(Some(&Value::from(6)), flow.get("protocol"));
+        assert_eq!(Some(&Value::from(64_512)), flow.get("src_as"));
+        assert_eq!(Some(&Value::from(15_169)), flow.get("dst_as"));
+        assert_eq!(Some(&Value::from(24)), flow.get("src_mask"));
+        assert_eq!(Some(&Value::from(16)), flow.get("dst_mask"));
+    }
+
+    #[test]
+    fn rejects_truncated_packets() {
+        let data = packet(2);
+        assert!(matches!(
+            decode(Reader::new(&data[..data.len() - 1])),
+            Err(DecodeError::Truncated { .. })
+        ));
+    }
+}
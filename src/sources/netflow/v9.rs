@@ -0,0 +1,269 @@
+//! Decoding of [NetFlow v9][v9] packets, whose records are described by templates.
+//!
+//! [v9]: https://www.rfc-editor.org/rfc/rfc3954
+use std::net::IpAddr;
+
+use chrono::DateTime;
+
+use super::{
+    DecodeError, DecodedPacket,
+    fields::ExportClock,
+    reader::{Reader, Truncated},
+    templates::{Template, TemplateCache, TemplateField, TemplateKey},
+};
+
+pub(super) const VERSION: u16 = 9;
+
+const TEMPLATE_SET_ID: u16 = 0;
+const OPTIONS_TEMPLATE_SET_ID: u16 = 1;
+const MIN_DATA_SET_ID: u16 = 256;
+
+pub(super) fn decode(
+    mut packet: Reader<'_>,
+    exporter: IpAddr,
+    templates: &mut TemplateCache,
+) -> Result<DecodedPacket, DecodeError> {
+    let _version = packet.u16()?;
+    let _count = packet.u16()?;
+    let sys_uptime = packet.u32()?;
+    let unix_secs = packet.u32()?;
+    let sequence_number = packet.u32()?;
+    let source_id = packet.u32()?;
+
+    let clock = ExportClock {
+        export_time: DateTime::from_timestamp(unix_secs.into(), 0)
+            .ok_or(DecodeError::InvalidTimestamp)?,
+        sys_uptime,
+    };
+    let key = |template_id| TemplateKey {
+        exporter,
+        version: VERSION,
+        domain_id: source_id,
+        template_id,
+    };
+
+    let mut decoded = DecodedPacket::default();
+    while !packet.is_empty() {
+        let set_id = packet.u16()?;
+        let length = packet.u16()?;
+        let mut set = packet.sub_reader(
+            usize::from(length)
+                .checked_sub(4)
+                .ok_or(DecodeError::InvalidSetLength { length })?,
+        )?;
+
+        match set_id {
+            TEMPLATE_SET_ID => {
+                // The set may be padded to a multiple of four bytes.
+                while set.len() >= 4 {
+                    let template_id = set.u16()?;
+                    let field_count = set.u16()?;
+                    let template = Template {
+                        fields: read_fields(&mut set, field_count.into())?,
+                        options: false,
+                    };
+                    templates.insert(key(template_id), template);
+                }
+            }
+            OPTIONS_TEMPLATE_SET_ID => {
+                while set.len() >= 6 {
+                    let template_id = set.u16()?;
+                    let scope_length = set.u16()?;
+                    let options_length = set.u16()?;
+                    let field_count = (usize::from(scope_length) + usize::from(options_length)) / 4;
+                    let template = Template {
+                        fields: read_fields(&mut set, field_count)?,
+                        options: true,
+                    };
+                    templates.insert(key(template_id), template);
+                }
+            }
+            MIN_DATA_SET_ID.. => match templates.get(&key(set_id)) {
+                Some(template) if template.options => (),
+                Some(template) => {
+                    for mut flow in template.decode_records(set, Some(clock))? {
+                        flow.insert("flow_type".into(), "netflow_v9".into());
+                        flow.insert("sequence_number".into(), sequence_number.into());
+                        flow.insert("source_id".into(), source_id.into());
+                        decoded.flows.push(flow);
+                    }
+                }
+                None => decoded.missing_templates.push(set_id),
+            },
+            // Reserved for future use.
+            _ => (),
+        }
+    }
+    Ok(decoded)
+}
+
+fn read_fields(set: &mut Reader<'_>, count: usize) -> Result<Vec<TemplateField>, Truncated> {
+    (0..count)
+        .map(|_| {
+            Ok(TemplateField {
+                id: set.u16()?,
+                enterprise: None,
+                length: set.u16()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use vrl::value::Value;
+
+    use super::*;
+
+    /// Wraps flow sets in a packet.
+    pub(in crate::sources::netflow) fn packet(sets: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(VERSION.to_be_bytes());
+        data.extend((sets.len() as u16).to_be_bytes());
+        data.extend(10_000_u32.to_be_bytes());
+        data.extend(1_700_000_000_u32.to_be_bytes());
+        data.extend(7_u32.to_be_bytes());
+        data.extend(1_u32.to_be_bytes());
+        data.extend(sets.concat());
+        data
+    }
+
+    fn set(id: u16, content: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(id.to_be_bytes());
+        data.extend((content.len() as u16 + 4).to_be_bytes());
+        data.extend(content);
+        data
+    }
+
+    /// A template set of template 256, with the source and destination addresses and ports, the
+    /// protocol and the times of a flow.
+    pub(in crate::sources::netflow) fn template_set() -> Vec<u8> {
+        let mut content = Vec::new();
+        content.extend(256_u16.to_be_bytes());
+        content.extend(7_u16.to_be_bytes());
+        for (id, length) in [
+            (8_u16, 4_u16),
+            (12, 4),
+            (7, 2),
+            (11, 2),
+            (4, 1),
+            (22, 4),
+            (21, 4),
+        ] {
+            content.extend(id.to_be_bytes());
+            content.extend(length.to_be_bytes());
+        }
+        set(TEMPLATE_SET_ID, &content)
+    }
+
+    /// A data set of template 256, with two flows and padding.
+    pub(in crate::sources::netflow) fn data_set() -> Vec<u8> {
+        let mut content = Vec::new();
+        for port in [1024_u16, 1025] {
+            content.extend([10, 0, 0, 1, 10, 0, 0, 2]);
+            content.extend(port.to_be_bytes());
+            content.extend(53_u16.to_be_bytes());
+            content.push(17);
+            content.extend(8_000_u32.to_be_bytes());
+            content.extend(9_000_u32.to_be_bytes());
+        }
+        content.extend([0, 0, 0]);
+        set(256, &content)
+    }
+
+    fn exporter() -> IpAddr {
+        "192.0.2.1".parse().unwrap()
+    }
+
+    #[test]
+    fn decodes_packets() {
+        let mut templates = TemplateCache::default();
+        let data = packet(&[template_set(), data_set()]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+
+        assert!(decoded.missing_templates.is_empty());
+        assert_eq!(2, decoded.flows.len());
+        let flow = &decoded.flows[1];
+        assert_eq!(Some(&Value::from("netflow_v9")), flow.get("flow_type"));
+        assert_eq!(Some(&Value::from(7)), flow.get("sequence_number"));
+        assert_eq!(Some(&Value::from(1)), flow.get("source_id"));
+        assert_eq!(Some(&Value::from("10.0.0.1")), flow.get("src_addr"));
+        assert_eq!(Some(&Value::from("10.0.0.2")), flow.get("dst_addr"));
+        assert_eq!(Some(&Value::from(1025)), flow.get("src_port"));
+        assert_eq!(Some(&Value::from(53)), flow.get("dst_port"));
+        assert_eq!(Some(&Value::from(17)), flow.get("protocol"));
+        assert_eq!(
+            Some(&Value::from(
+                DateTime::from_timestamp(1_699_999_998, 0).unwrap()
+            )),
+            flow.get("flow_start")
+        );
+        assert_eq!(
+            Some(&Value::from(
+                DateTime::from_timestamp(1_699_999_999, 0).unwrap()
+            )),
+            flow.get("flow_end")
+        );
+    }
+
+    #[test]
+    fn caches_templates() {
+        let mut templates = TemplateCache::default();
+
+        // Data received before its template can't be decoded.
+        let data = packet(&[data_set()]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+        assert!(decoded.flows.is_empty());
+        assert_eq!(vec![256], decoded.missing_templates);
+
+        let data = packet(&[template_set()]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+        assert!(decoded.flows.is_empty());
+
+        let data = packet(&[data_set()]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+        assert_eq!(2, decoded.flows.len());
+
+        // Templates are only used for the exporter that sent them.
+        let decoded = decode(
+            Reader::new(&data),
+            "192.0.2.2".parse().unwrap(),
+            &mut templates,
+        )
+        .unwrap();
+        assert!(decoded.flows.is_empty());
+    }
+
+    #[test]
+    fn skips_options_records() {
+        let mut content = Vec::new();
+        content.extend(257_u16.to_be_bytes());
+        content.extend(4_u16.to_be_bytes());
+        content.extend(4_u16.to_be_bytes());
+        // The scope is the system, and the option is the sampling interval.
+        content.extend([0, 1, 0, 4, 0, 34, 0, 4]);
+        let template_set = set(OPTIONS_TEMPLATE_SET_ID, &content);
+        let data_set = set(257, &[0, 0, 0, 1, 0, 0, 0, 100]);
+
+        let mut templates = TemplateCache::default();
+        let data = packet(&[template_set, data_set]);
+        let decoded = decode(Reader::new(&data), exporter(), &mut templates).unwrap();
+        assert!(decoded.flows.is_empty());
+        assert!(decoded.missing_templates.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_sets() {
+        let mut data = packet(&[]);
+        data.extend([1, 0, 0, 2]);
+        assert!(matches!(
+            decode(
+                Reader::new(&data),
+                exporter(),
+                &mut TemplateCache::default()
+            ),
+            Err(DecodeError::InvalidSetLength { length: 2 })
+        ));
+    }
+}
@@ -13,6 +13,7 @@ use crate::{
         opentelemetry::{
             grpc::Service,
             http::{build_warp_filter, run_http_server},
+            metrics::{MetricsConfig, MetricsConverter},
         },
         util::grpc::run_grpc_server_with_routes,
     },
@@ -75,6 +76,9 @@ pub struct OpentelemetryConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub use_otlp_decoding: bool,
+    #[configurable(derived)]
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 /// Configuration for the `opentelemetry` gRPC server.
@@ -153,6 +157,7 @@ impl GenerateConfig for OpentelemetryConfig {
             acknowledgements: Default::default(),
             log_namespace: None,
             use_otlp_decoding: false,
+            metrics: Default::default(),
         })
         .unwrap()
     }
@@ -186,6 +191,7 @@ impl SourceConfig for OpentelemetryConfig {
         let logs_deserializer = self.get_signal_deserializer(OtlpSignalType::Logs)?;
         let metrics_deserializer = self.get_signal_deserializer(OtlpSignalType::Metrics)?;
         let traces_deserializer = self.get_signal_deserializer(OtlpSignalType::Traces)?;
+        let metrics_converter = MetricsConverter::new(&self.metrics)?;
 
         let log_service = LogsServiceServer::new(Service {
             pipeline: cx.out.clone(),
@@ -193,6 +199,7 @@ impl SourceConfig for OpentelemetryConfig {
             log_namespace,
             events_received: events_received.clone(),
             deserializer: logs_deserializer.clone(),
+            metrics_converter: metrics_converter.clone(),
         })
         .accept_compressed(CompressionEncoding::Gzip)
         .max_decoding_message_size(usize::MAX);
@@ -203,6 +210,7 @@ impl SourceConfig for OpentelemetryConfig {
             log_namespace,
             events_received: events_received.clone(),
             deserializer: metrics_deserializer.clone(),
+            metrics_converter: metrics_converter.clone(),
         })
         .accept_compressed(CompressionEncoding::Gzip)
         .max_decoding_message_size(usize::MAX);
@@ -213,6 +221,7 @@ impl SourceConfig for OpentelemetryConfig {
             log_namespace,
             events_received: events_received.clone(),
             deserializer: traces_deserializer.clone(),
+            metrics_converter: metrics_converter.clone(),
         })
         .accept_compressed(CompressionEncoding::Gzip)
         .max_decoding_message_size(usize::MAX);
@@ -249,6 +258,7 @@ impl SourceConfig for OpentelemetryConfig {
             logs_deserializer,
             metrics_deserializer,
             traces_deserializer,
+            metrics_converter,
         );
 
         let http_source = run_http_server(
@@ -25,7 +25,10 @@ use vector_lib::{
 use crate::{
     SourceSender,
     internal_events::{EventsReceived, StreamClosedError},
-    sources::opentelemetry::config::{LOGS, METRICS, TRACES},
+    sources::opentelemetry::{
+        config::{LOGS, METRICS, TRACES},
+        metrics::MetricsConverter,
+    },
 };
 
 #[derive(Clone)]
@@ -35,6 +38,7 @@ pub(super) struct Service {
     pub events_received: Registered<EventsReceived>,
     pub log_namespace: LogNamespace,
     pub deserializer: Option<OtlpDeserializer>,
+    pub metrics_converter: MetricsConverter,
 }
 
 #[tonic::async_trait]
@@ -110,12 +114,8 @@ impl MetricsService for Service {
                 .map_err(|e| Status::invalid_argument(e.to_string()))
                 .map(|buf| buf.into_vec())?
         } else {
-            request
-                .into_inner()
-                .resource_metrics
-                .into_iter()
-                .flat_map(|v| v.into_event_iter())
-                .collect()
+            self.metrics_converter
+                .convert(request.into_inner().resource_metrics)
         };
 
         self.handle_events(events, METRICS).await?;
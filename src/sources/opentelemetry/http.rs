@@ -38,7 +38,10 @@ use crate::{
     shutdown::ShutdownSignal,
     sources::{
         http_server::HttpConfigParamKind,
-        opentelemetry::config::{LOGS, METRICS, OpentelemetryConfig, TRACES},
+        opentelemetry::{
+            config::{LOGS, METRICS, OpentelemetryConfig, TRACES},
+            metrics::MetricsConverter,
+        },
         util::{add_headers, decode},
     },
     tls::MaybeTlsSettings,
@@ -97,6 +100,7 @@ pub(crate) fn build_warp_filter(
     logs_deserializer: Option<OtlpDeserializer>,
     metrics_deserializer: Option<OtlpDeserializer>,
     traces_deserializer: Option<OtlpDeserializer>,
+    metrics_converter: MetricsConverter,
 ) -> BoxedFilter<(Response,)> {
     let log_filters = build_warp_log_filter(
         acknowledgements,
@@ -113,6 +117,7 @@ pub(crate) fn build_warp_filter(
         bytes_received.clone(),
         events_received.clone(),
         metrics_deserializer,
+        metrics_converter,
     );
     let trace_filters = build_warp_trace_filter(
         acknowledgements,
@@ -252,6 +257,7 @@ fn build_warp_metrics_filter(
     bytes_received: Registered<BytesReceived>,
     events_received: Registered<EventsReceived>,
     deserializer: Option<OtlpDeserializer>,
+    metrics_converter: MetricsConverter,
 ) -> BoxedFilter<(Response,)> {
     let make_events = move |encoding_header: Option<String>, _headers: HeaderMap, body: Bytes| {
         decode(encoding_header.as_deref(), body)
@@ -269,7 +275,7 @@ fn build_warp_metrics_filter(
                 if let Some(d) = deserializer.as_ref() {
                     parse_with_deserializer(d, decoded_body, LogNamespace::default())
                 } else {
-                    decode_metrics_body(decoded_body, &events_received)
+                    decode_metrics_body(decoded_body, &metrics_converter, &events_received)
                 }
             })
     };
@@ -361,15 +367,12 @@ fn decode_log_body(
 
 fn decode_metrics_body(
     body: Bytes,
+    metrics_converter: &MetricsConverter,
     events_received: &Registered<EventsReceived>,
 ) -> Result<Vec<Event>, ErrorMessage> {
     let request = ExportMetricsServiceRequest::decode(body).map_err(emit_decode_error)?;
 
-    let events: Vec<Event> = request
-        .resource_metrics
-        .into_iter()
-        .flat_map(|v| v.into_event_iter())
-        .collect();
+    let events = metrics_converter.convert(request.resource_metrics);
 
     events_received.emit(CountByteSize(
         events.len(),
@@ -63,6 +63,7 @@ async fn receive_logs_legacy_namespace() {
             acknowledgements: Default::default(),
             log_namespace: Default::default(),
             use_otlp_decoding: false,
+            metrics: Default::default(),
         };
 
         let (sender, logs_output, _) = new_source(EventStatus::Delivered, LOGS.to_string());
@@ -162,6 +163,7 @@ async fn receive_trace() {
             acknowledgements: Default::default(),
             log_namespace: Default::default(),
             use_otlp_decoding: false,
+            metrics: Default::default(),
         };
 
         let (sender, trace_output, _) = new_source(EventStatus::Delivered, TRACES.to_string());
@@ -267,6 +269,7 @@ async fn receive_metric() {
             acknowledgements: Default::default(),
             log_namespace: Default::default(),
             use_otlp_decoding: false,
+            metrics: Default::default(),
         };
 
         let (sender, metrics_output, _) = new_source(EventStatus::Delivered, METRICS.to_string());
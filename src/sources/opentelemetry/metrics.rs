@@ -0,0 +1,230 @@
+use std::sync::{Arc, Mutex};
+
+use vector_lib::{
+    configurable::configurable_component, opentelemetry::proto::metrics::v1::ResourceMetrics,
+};
+
+use crate::{
+    event::Event,
+    sinks::util::buffer::metrics::{MetricSet, NormalizerConfig, NormalizerSettings},
+};
+
+/// Configuration for the conversion of OTLP metrics into metric events.
+///
+/// This has no effect if `use_otlp_decoding` is enabled.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Whether to convert metrics with delta aggregation temporality into cumulative metrics.
+    ///
+    /// By default, delta sums and histograms are emitted as incremental metrics. When enabled,
+    /// the deltas of each series are accumulated, and emitted as absolute metrics instead, as
+    /// required by sinks that only accept cumulative metrics.
+    #[serde(default)]
+    pub delta_to_cumulative: bool,
+
+    /// Configuration for the cache holding the accumulated values of delta metrics.
+    ///
+    /// By default, series are evicted after 5 minutes of not being updated, after which their
+    /// accumulated value starts again from zero.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub cache: NormalizerConfig<DeltaToCumulativeNormalizerSettings>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeltaToCumulativeNormalizerSettings;
+
+impl NormalizerSettings for DeltaToCumulativeNormalizerSettings {
+    const MAX_EVENTS: Option<usize> = None;
+    const MAX_BYTES: Option<usize> = None;
+    const TIME_TO_LIVE: Option<u64> = Some(300);
+}
+
+/// Converts OTLP metrics into metric events.
+///
+/// The accumulated values of delta metrics are shared between the gRPC and HTTP servers, so that
+/// the same series can be received over both.
+#[derive(Clone, Debug, Default)]
+pub(super) struct MetricsConverter {
+    cumulative: Option<Arc<Mutex<MetricSet>>>,
+}
+
+impl MetricsConverter {
+    pub(super) fn new(config: &MetricsConfig) -> crate::Result<Self> {
+        let cumulative = if config.delta_to_cumulative {
+            let settings = config.cache.validate()?.into_settings();
+            Some(Arc::new(Mutex::new(MetricSet::new(settings))))
+        } else {
+            None
+        };
+
+        Ok(Self { cumulative })
+    }
+
+    pub(super) fn convert(&self, resource_metrics: Vec<ResourceMetrics>) -> Vec<Event> {
+        let events = resource_metrics
+            .into_iter()
+            .flat_map(ResourceMetrics::into_event_iter);
+
+        match &self.cumulative {
+            Some(cumulative) => {
+                let mut cumulative = cumulative.lock().expect("mutex poisoned");
+                events
+                    .filter_map(|event| cumulative.make_absolute(event.into_metric()))
+                    .map(Event::Metric)
+                    .collect()
+            }
+            None => events.collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::opentelemetry::proto::metrics::v1::{
+        AggregationTemporality, Histogram, HistogramDataPoint, Metric, NumberDataPoint,
+        ScopeMetrics, Sum, metric::Data, number_data_point::Value as NumberValue,
+    };
+
+    use super::*;
+    use crate::event::{MetricKind, MetricValue, metric::Bucket};
+
+    fn resource_metrics(data: Data) -> Vec<ResourceMetrics> {
+        vec![ResourceMetrics {
+            resource: None,
+            scope_metrics: vec![ScopeMetrics {
+                scope: None,
+                metrics: vec![Metric {
+                    name: "requests".to_string(),
+                    description: String::new(),
+                    unit: String::new(),
+                    data: Some(data),
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }]
+    }
+
+    fn delta_sum(value: f64, is_monotonic: bool) -> Vec<ResourceMetrics> {
+        resource_metrics(Data::Sum(Sum {
+            data_points: vec![NumberDataPoint {
+                value: Some(NumberValue::AsDouble(value)),
+                ..Default::default()
+            }],
+            aggregation_temporality: AggregationTemporality::Delta as i32,
+            is_monotonic,
+        }))
+    }
+
+    fn delta_histogram(bucket_counts: Vec<u64>, sum: f64) -> Vec<ResourceMetrics> {
+        resource_metrics(Data::Histogram(Histogram {
+            data_points: vec![HistogramDataPoint {
+                count: bucket_counts.iter().sum(),
+                sum: Some(sum),
+                bucket_counts,
+                explicit_bounds: vec![1.0],
+                ..Default::default()
+            }],
+            aggregation_temporality: AggregationTemporality::Delta as i32,
+        }))
+    }
+
+    fn convert_one(
+        converter: &MetricsConverter,
+        metrics: Vec<ResourceMetrics>,
+    ) -> (MetricKind, MetricValue) {
+        let mut events = converter.convert(metrics);
+        assert_eq!(events.len(), 1);
+        let metric = events.pop().unwrap().into_metric();
+        (metric.kind(), metric.value().clone())
+    }
+
+    #[test]
+    fn delta_metrics_are_incremental_by_default() {
+        let converter = MetricsConverter::new(&MetricsConfig::default()).unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                convert_one(&converter, delta_sum(2.0, true)),
+                (MetricKind::Incremental, MetricValue::Counter { value: 2.0 })
+            );
+        }
+    }
+
+    fn cumulative_converter() -> MetricsConverter {
+        MetricsConverter::new(&MetricsConfig {
+            delta_to_cumulative: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn delta_sums_to_cumulative() {
+        let converter = cumulative_converter();
+        assert_eq!(
+            convert_one(&converter, delta_sum(2.0, true)),
+            (MetricKind::Absolute, MetricValue::Counter { value: 2.0 })
+        );
+        assert_eq!(
+            convert_one(&converter, delta_sum(3.0, true)),
+            (MetricKind::Absolute, MetricValue::Counter { value: 5.0 })
+        );
+    }
+
+    #[test]
+    fn delta_non_monotonic_sums_to_cumulative() {
+        // Non-monotonic sums are converted to gauges, which can go down.
+        let converter = cumulative_converter();
+        assert_eq!(
+            convert_one(&converter, delta_sum(3.0, false)),
+            (MetricKind::Absolute, MetricValue::Gauge { value: 3.0 })
+        );
+        assert_eq!(
+            convert_one(&converter, delta_sum(-4.0, false)),
+            (MetricKind::Absolute, MetricValue::Gauge { value: -1.0 })
+        );
+    }
+
+    #[test]
+    fn delta_histograms_to_cumulative() {
+        let converter = cumulative_converter();
+        convert_one(&converter, delta_histogram(vec![1, 2], 5.0));
+        assert_eq!(
+            convert_one(&converter, delta_histogram(vec![3, 0], 1.5)),
+            (
+                MetricKind::Absolute,
+                MetricValue::AggregatedHistogram {
+                    buckets: vec![
+                        Bucket {
+                            upper_limit: 1.0,
+                            count: 4,
+                        },
+                        Bucket {
+                            upper_limit: f64::INFINITY,
+                            count: 2,
+                        },
+                    ],
+                    count: 6,
+                    sum: 6.5,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_cache_config() {
+        let config = MetricsConfig {
+            delta_to_cumulative: true,
+            cache: NormalizerConfig {
+                time_to_live: Some(0),
+                ..Default::default()
+            },
+        };
+
+        assert!(MetricsConverter::new(&config).is_err());
+    }
+}
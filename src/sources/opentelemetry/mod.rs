@@ -6,5 +6,6 @@ mod tests;
 pub mod config;
 mod grpc;
 mod http;
+pub mod metrics;
 mod reply;
 mod status;
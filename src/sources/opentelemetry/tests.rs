@@ -1096,6 +1096,7 @@ async fn http_headers() {
             acknowledgements: Default::default(),
             log_namespace: Default::default(),
             use_otlp_decoding: false,
+            metrics: Default::default(),
         };
         let schema_definitions = source
             .outputs(LogNamespace::Legacy)
@@ -1202,6 +1203,7 @@ pub async fn build_otlp_test_env(
         acknowledgements: Default::default(),
         log_namespace,
         use_otlp_decoding: false,
+        metrics: Default::default(),
     };
 
     let (sender, output, _) = new_source(EventStatus::Delivered, event_name.to_string());
@@ -0,0 +1,354 @@
+//! Decoding of the messages of the [`pgoutput`][pgoutput] logical decoding plugin, in the
+//! version 1 of its protocol.
+//!
+//! [pgoutput]: https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html
+
+use bytes::Bytes;
+use snafu::{ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+pub(super) enum DecodeError {
+    #[snafu(display("Truncated message."))]
+    Truncated,
+    #[snafu(display("Unknown message type {:?}.", char::from(*tag)))]
+    UnknownMessage { tag: u8 },
+    #[snafu(display("Unknown tuple data kind {:?}.", char::from(*kind)))]
+    UnknownTupleData { kind: u8 },
+    #[snafu(display("Invalid name: {}", source))]
+    InvalidName { source: std::str::Utf8Error },
+}
+
+/// A column of a relation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct Column {
+    pub(super) name: String,
+    pub(super) type_oid: u32,
+    /// Whether the column is part of the replica identity of the relation, usually its primary
+    /// key.
+    pub(super) key: bool,
+}
+
+/// A table, as described by the `Relation` messages sent before its first change.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct Relation {
+    pub(super) id: u32,
+    pub(super) namespace: String,
+    pub(super) name: String,
+    pub(super) columns: Vec<Column>,
+}
+
+/// The value of a column in a row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum TupleValue {
+    Null,
+    /// A TOASTed value that wasn't changed by an update, and isn't sent.
+    Unchanged,
+    /// The value, in the text format of its type.
+    Text(Bytes),
+}
+
+/// The old image of an updated or deleted row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum OldTuple {
+    /// The columns of the replica identity, the others being null.
+    Key(Vec<TupleValue>),
+    /// All the columns, with `REPLICA IDENTITY FULL`.
+    Full(Vec<TupleValue>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum Message {
+    Begin {
+        /// The microseconds since the PostgreSQL epoch, 2000-01-01.
+        commit_timestamp: i64,
+        xid: u32,
+    },
+    Commit {
+        /// The end of the transaction, which is confirmed once its changes are processed.
+        end_lsn: u64,
+    },
+    Relation(Relation),
+    Insert {
+        relation_id: u32,
+        new: Vec<TupleValue>,
+    },
+    Update {
+        relation_id: u32,
+        old: Option<OldTuple>,
+        new: Vec<TupleValue>,
+    },
+    Delete {
+        relation_id: u32,
+        old: OldTuple,
+    },
+    /// The messages without changes to emit: `Origin`, `Type`, `Truncate` and `Message`.
+    Other,
+}
+
+impl Message {
+    pub(super) fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader(data);
+        let message = match reader.u8()? {
+            b'B' => {
+                let _final_lsn = reader.u64()?;
+                Self::Begin {
+                    commit_timestamp: reader.i64()?,
+                    xid: reader.u32()?,
+                }
+            }
+            b'C' => {
+                let _flags = reader.u8()?;
+                let _commit_lsn = reader.u64()?;
+                Self::Commit {
+                    end_lsn: reader.u64()?,
+                }
+            }
+            b'R' => {
+                let id = reader.u32()?;
+                let namespace = reader.string()?;
+                let name = reader.string()?;
+                let _replica_identity = reader.u8()?;
+                let columns = (0..reader.u16()?)
+                    .map(|_| {
+                        let flags = reader.u8()?;
+                        let name = reader.string()?;
+                        let type_oid = reader.u32()?;
+                        let _type_modifier = reader.u32()?;
+                        Ok(Column {
+                            name,
+                            type_oid,
+                            key: flags & 1 == 1,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                Self::Relation(Relation {
+                    id,
+                    namespace,
+                    name,
+                    columns,
+                })
+            }
+            b'I' => {
+                let relation_id = reader.u32()?;
+                reader.expect(b'N')?;
+                Self::Insert {
+                    relation_id,
+                    new: reader.tuple()?,
+                }
+            }
+            b'U' => {
+                let relation_id = reader.u32()?;
+                let old = match reader.u8()? {
+                    b'K' => Some(OldTuple::Key(reader.tuple()?)),
+                    b'O' => Some(OldTuple::Full(reader.tuple()?)),
+                    b'N' => None,
+                    kind => return Err(DecodeError::UnknownTupleData { kind }),
+                };
+                if old.is_some() {
+                    reader.expect(b'N')?;
+                }
+                Self::Update {
+                    relation_id,
+                    old,
+                    new: reader.tuple()?,
+                }
+            }
+            b'D' => {
+                let relation_id = reader.u32()?;
+                let old = match reader.u8()? {
+                    b'K' => OldTuple::Key(reader.tuple()?),
+                    b'O' => OldTuple::Full(reader.tuple()?),
+                    kind => return Err(DecodeError::UnknownTupleData { kind }),
+                };
+                Self::Delete { relation_id, old }
+            }
+            b'O' | b'Y' | b'T' | b'M' => Self::Other,
+            tag => return Err(DecodeError::UnknownMessage { tag }),
+        };
+        Ok(message)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let (bytes, rest) = self
+            .0
+            .split_first_chunk::<N>()
+            .ok_or(DecodeError::Truncated)?;
+        self.0 = rest;
+        Ok(*bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        self.take().map(u16::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        self.take().map(u32::from_be_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        self.take().map(u64::from_be_bytes)
+    }
+
+    fn i64(&mut self) -> Result<i64, DecodeError> {
+        self.take().map(i64::from_be_bytes)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), DecodeError> {
+        match self.u8()? {
+            kind if kind == expected => Ok(()),
+            kind => Err(DecodeError::UnknownTupleData { kind }),
+        }
+    }
+
+    /// Reads a null-terminated string.
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let end = self
+            .0
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or(DecodeError::Truncated)?;
+        let string = std::str::from_utf8(&self.0[..end]).context(InvalidNameSnafu)?;
+        self.0 = &self.0[end + 1..];
+        Ok(string.to_owned())
+    }
+
+    fn tuple(&mut self) -> Result<Vec<TupleValue>, DecodeError> {
+        (0..self.u16()?)
+            .map(|_| match self.u8()? {
+                b'n' => Ok(TupleValue::Null),
+                b'u' => Ok(TupleValue::Unchanged),
+                b't' => {
+                    let length = self.u32()? as usize;
+                    if self.0.len() < length {
+                        return Err(DecodeError::Truncated);
+                    }
+                    let (value, rest) = self.0.split_at(length);
+                    self.0 = rest;
+                    Ok(TupleValue::Text(Bytes::copy_from_slice(value)))
+                }
+                kind => Err(DecodeError::UnknownTupleData { kind }),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use bytes::{BufMut, BytesMut};
+
+    use super::*;
+
+    pub(in super::super) fn relation_message() -> Vec<u8> {
+        let mut message = BytesMut::new();
+        message.put_u8(b'R');
+        message.put_u32(16384);
+        message.put_slice(b"public\0users\0");
+        message.put_u8(b'd');
+        message.put_u16(2);
+        message.put_u8(1);
+        message.put_slice(b"id\0");
+        message.put_u32(23);
+        message.put_i32(-1);
+        message.put_u8(0);
+        message.put_slice(b"name\0");
+        message.put_u32(25);
+        message.put_i32(-1);
+        message.to_vec()
+    }
+
+    pub(in super::super) fn update_message() -> Vec<u8> {
+        let mut message = BytesMut::new();
+        message.put_u8(b'U');
+        message.put_u32(16384);
+        message.put_u8(b'K');
+        message.put_u16(2);
+        message.put_u8(b't');
+        message.put_u32(1);
+        message.put_slice(b"1");
+        message.put_u8(b'n');
+        message.put_u8(b'N');
+        message.put_u16(2);
+        message.put_u8(b't');
+        message.put_u32(1);
+        message.put_slice(b"2");
+        message.put_u8(b'u');
+        message.to_vec()
+    }
+
+    #[test]
+    fn decodes_relations() {
+        assert_eq!(
+            Message::decode(&relation_message()).unwrap(),
+            Message::Relation(Relation {
+                id: 16384,
+                namespace: "public".to_owned(),
+                name: "users".to_owned(),
+                columns: vec![
+                    Column {
+                        name: "id".to_owned(),
+                        type_oid: 23,
+                        key: true,
+                    },
+                    Column {
+                        name: "name".to_owned(),
+                        type_oid: 25,
+                        key: false,
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_updates() {
+        assert_eq!(
+            Message::decode(&update_message()).unwrap(),
+            Message::Update {
+                relation_id: 16384,
+                old: Some(OldTuple::Key(vec![
+                    TupleValue::Text(Bytes::from_static(b"1")),
+                    TupleValue::Null,
+                ])),
+                new: vec![
+                    TupleValue::Text(Bytes::from_static(b"2")),
+                    TupleValue::Unchanged,
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_commits() {
+        let mut message = BytesMut::new();
+        message.put_u8(b'C');
+        message.put_u8(0);
+        message.put_u64(0x16B3748);
+        message.put_u64(0x16B3778);
+        message.put_i64(1_000_000);
+        assert_eq!(
+            Message::decode(&message).unwrap(),
+            Message::Commit { end_lsn: 0x16B3778 }
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let message = update_message();
+        assert!(matches!(
+            Message::decode(&message[..message.len() - 2]),
+            Err(DecodeError::Truncated)
+        ));
+        assert!(matches!(
+            Message::decode(b"Z"),
+            Err(DecodeError::UnknownMessage { tag: b'Z' })
+        ));
+    }
+}
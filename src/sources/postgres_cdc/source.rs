@@ -0,0 +1,531 @@
+use std::{collections::HashMap, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::FutureExt;
+use ordered_float::NotNan;
+use postgres_openssl::MakeTlsConnector;
+use snafu::{ResultExt, Snafu};
+use tokio::{pin, select, time::sleep};
+use tokio_postgres::{Client, Config, NoTls, Row, types::PgLsn};
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LogNamespace, log_schema},
+    finalization::{BatchNotifier, BatchStatus},
+    internal_event::{CountByteSize, InternalEventHandle as _, Registered},
+    lookup::event_path,
+};
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    PostgresCdcConfig,
+    pgoutput::{Message, OldTuple, Relation, TupleValue},
+};
+use crate::{
+    SourceSender,
+    common::backoff::ExponentialBackoff,
+    event::LogEvent,
+    internal_events::{
+        EventsReceived, PostgresCdcDecodeError, PostgresCdcReplicationError, StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+};
+
+/// Reads the changes of the slot without consuming them, as they are only confirmed once the
+/// events are acknowledged.
+const READ_CHANGES: &str = "SELECT lsn, data FROM pg_logical_slot_peek_binary_changes(\
+    $1, NULL, $2, 'proto_version', '1', 'publication_names', $3)";
+
+const CONFIRM_CHANGES: &str = "SELECT pg_replication_slot_advance($1, $2)";
+
+const CREATE_SLOT: &str = "SELECT pg_create_logical_replication_slot($1, 'pgoutput') \
+    WHERE NOT EXISTS (SELECT 1 FROM pg_replication_slots WHERE slot_name = $1)";
+
+/// The number of seconds between the Unix epoch and the PostgreSQL epoch, 2000-01-01.
+const POSTGRES_EPOCH_SECS: i64 = 946_684_800;
+
+// The OIDs of the types whose values aren't kept as strings.
+const BOOL_OID: u32 = 16;
+const INT8_OID: u32 = 20;
+const INT2_OID: u32 = 21;
+const INT4_OID: u32 = 23;
+const OID_OID: u32 = 26;
+const JSON_OID: u32 = 114;
+const FLOAT4_OID: u32 = 700;
+const FLOAT8_OID: u32 = 701;
+const TIMESTAMP_OID: u32 = 1114;
+const TIMESTAMPTZ_OID: u32 = 1184;
+const JSONB_OID: u32 = 3802;
+
+#[derive(Debug, Snafu)]
+enum ReplicationError {
+    #[snafu(display("failed to connect: {}", source))]
+    Connect { source: tokio_postgres::Error },
+    #[snafu(display("failed to create the replication slot: {}", source))]
+    CreateSlot { source: tokio_postgres::Error },
+    #[snafu(display("failed to read the changes: {}", source))]
+    ReadChanges { source: tokio_postgres::Error },
+    #[snafu(display("failed to confirm the changes: {}", source))]
+    ConfirmChanges { source: tokio_postgres::Error },
+    #[snafu(display("the output is closed"))]
+    StreamClosed,
+}
+
+pub(super) struct PostgresCdcSource {
+    config: Config,
+    tls: Option<MakeTlsConnector>,
+    publication: String,
+    slot: String,
+    max_changes: i32,
+    poll_interval: Duration,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+    out: SourceSender,
+    /// The relations of the changes, kept up to date by the `Relation` messages that precede the
+    /// first change of each relation and follow the changes to their schema.
+    relations: HashMap<u32, Relation>,
+    events_received: Registered<EventsReceived>,
+    backoff: ExponentialBackoff,
+}
+
+/// The transaction the changes being decoded belong to.
+struct Transaction {
+    xid: u32,
+    commit_timestamp: DateTime<Utc>,
+    events: Vec<LogEvent>,
+}
+
+impl PostgresCdcSource {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        config: Config,
+        tls: Option<MakeTlsConnector>,
+        publication: String,
+        slot: String,
+        max_changes: i32,
+        poll_interval: Duration,
+        acknowledgements: bool,
+        log_namespace: LogNamespace,
+        out: SourceSender,
+    ) -> Self {
+        Self {
+            config,
+            tls,
+            publication,
+            slot,
+            max_changes,
+            poll_interval,
+            acknowledgements,
+            log_namespace,
+            out,
+            relations: HashMap::new(),
+            events_received: register!(EventsReceived),
+            backoff: ExponentialBackoff::from_millis(2)
+                .factor(250)
+                .max_delay(Duration::from_secs(30)),
+        }
+    }
+
+    pub(super) async fn run(mut self, shutdown: ShutdownSignal) -> Result<(), ()> {
+        let shutdown = shutdown.fuse();
+        pin!(shutdown);
+
+        loop {
+            select! {
+                _ = &mut shutdown => break,
+                result = self.replicate() => match result {
+                    Err(ReplicationError::StreamClosed) => return Err(()),
+                    Err(error) => emit!(PostgresCdcReplicationError { error }),
+                    Ok(()) => {}
+                },
+            }
+
+            let delay = self.backoff.next().expect("backoff never ends");
+            select! {
+                _ = &mut shutdown => break,
+                _ = sleep(delay) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the changes until an error occurs.
+    async fn replicate(&mut self) -> Result<(), ReplicationError> {
+        let client = self.connect().await?;
+        client
+            .execute(CREATE_SLOT, &[&self.slot])
+            .await
+            .context(CreateSlotSnafu)?;
+        let read_changes = client
+            .prepare(READ_CHANGES)
+            .await
+            .context(ReadChangesSnafu)?;
+
+        loop {
+            let rows = client
+                .query(
+                    &read_changes,
+                    &[&self.slot, &self.max_changes, &self.publication],
+                )
+                .await
+                .context(ReadChangesSnafu)?;
+            self.backoff.reset();
+
+            let drained = rows.len() < self.max_changes as usize;
+            let (events, end_lsn) = self.decode(&rows);
+            let confirmed = self.send(events).await?;
+
+            if confirmed && let Some(end_lsn) = end_lsn {
+                client
+                    .execute(CONFIRM_CHANGES, &[&self.slot, &PgLsn::from(end_lsn)])
+                    .await
+                    .context(ConfirmChangesSnafu)?;
+            }
+            if drained || !confirmed {
+                sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    async fn connect(&self) -> Result<Client, ReplicationError> {
+        let client = match &self.tls {
+            Some(connector) => {
+                let (client, connection) = self
+                    .config
+                    .connect(connector.clone())
+                    .await
+                    .context(ConnectSnafu)?;
+                tokio::spawn(connection);
+                client
+            }
+            None => {
+                let (client, connection) =
+                    self.config.connect(NoTls).await.context(ConnectSnafu)?;
+                tokio::spawn(connection);
+                client
+            }
+        };
+        Ok(client)
+    }
+
+    /// Decodes the changes of the complete transactions, returning their events and the end of
+    /// the last one.
+    fn decode(&mut self, rows: &[Row]) -> (Vec<LogEvent>, Option<u64>) {
+        let mut events = Vec::new();
+        let mut end_lsn = None;
+        let mut transaction = None;
+
+        for row in rows {
+            let lsn: PgLsn = row.get(0);
+            let data: &[u8] = row.get(1);
+            let message = match Message::decode(data) {
+                Ok(message) => message,
+                Err(error) => {
+                    emit!(PostgresCdcDecodeError { error });
+                    continue;
+                }
+            };
+
+            let (operation, relation_id, before, after) = match message {
+                Message::Begin {
+                    commit_timestamp,
+                    xid,
+                } => {
+                    transaction = Some(Transaction {
+                        xid,
+                        commit_timestamp: DateTime::from_timestamp_micros(
+                            commit_timestamp + POSTGRES_EPOCH_SECS * 1_000_000,
+                        )
+                        .unwrap_or_default(),
+                        events: Vec::new(),
+                    });
+                    continue;
+                }
+                Message::Commit { end_lsn: lsn } => {
+                    if let Some(transaction) = transaction.take() {
+                        events.extend(transaction.events);
+                    }
+                    end_lsn = Some(lsn);
+                    continue;
+                }
+                Message::Relation(relation) => {
+                    self.relations.insert(relation.id, relation);
+                    continue;
+                }
+                Message::Insert { relation_id, new } => ("insert", relation_id, None, Some(new)),
+                Message::Update {
+                    relation_id,
+                    old,
+                    new,
+                } => ("update", relation_id, old, Some(new)),
+                Message::Delete { relation_id, old } => ("delete", relation_id, Some(old), None),
+                Message::Other => continue,
+            };
+
+            let Some(transaction) = transaction.as_mut() else {
+                emit!(PostgresCdcDecodeError {
+                    error: format!("Change outside of a transaction at {lsn}.")
+                });
+                continue;
+            };
+            let Some(relation) = self.relations.get(&relation_id) else {
+                emit!(PostgresCdcDecodeError {
+                    error: format!("Change to the unknown relation {relation_id} at {lsn}.")
+                });
+                continue;
+            };
+
+            let before = before.map(|old| match old {
+                OldTuple::Key(tuple) => create_row(relation, tuple, true),
+                OldTuple::Full(tuple) => create_row(relation, tuple, false),
+            });
+            let after = after.map(|new| create_row(relation, new, false));
+            let log = create_log(
+                operation,
+                relation,
+                before,
+                after,
+                lsn,
+                transaction,
+                self.log_namespace,
+            );
+            transaction.events.push(log);
+        }
+
+        (events, end_lsn)
+    }
+
+    /// Sends the events, returning whether they were acknowledged.
+    async fn send(&mut self, events: Vec<LogEvent>) -> Result<bool, ReplicationError> {
+        if events.is_empty() {
+            return Ok(true);
+        }
+
+        let count = events.len();
+        self.events_received.emit(CountByteSize(
+            count,
+            events.estimated_json_encoded_size_of(),
+        ));
+
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+        let events = events
+            .into_iter()
+            .map(|log| log.with_batch_notifier_option(&batch))
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        if self.out.send_batch(events).await.is_err() {
+            emit!(StreamClosedError { count });
+            return Err(ReplicationError::StreamClosed);
+        }
+
+        Ok(match receiver {
+            Some(receiver) => receiver.await == BatchStatus::Delivered,
+            None => true,
+        })
+    }
+}
+
+/// Creates the image of a row, without the TOASTed values that weren't changed.
+fn create_row(relation: &Relation, tuple: Vec<TupleValue>, key_only: bool) -> ObjectMap {
+    relation
+        .columns
+        .iter()
+        .zip(tuple)
+        .filter(|(column, _)| column.key || !key_only)
+        .filter_map(|(column, value)| {
+            let value = match value {
+                TupleValue::Null => Value::Null,
+                TupleValue::Unchanged => return None,
+                TupleValue::Text(text) => decode_value(column.type_oid, text),
+            };
+            Some((column.name.as_str().into(), value))
+        })
+        .collect()
+}
+
+/// Decodes a value from the text format of its type, keeping it as a string if its type isn't
+/// known.
+fn decode_value(type_oid: u32, bytes: Bytes) -> Value {
+    let Ok(text) = std::str::from_utf8(&bytes) else {
+        return Value::Bytes(bytes);
+    };
+    let value = match type_oid {
+        BOOL_OID => Some(Value::Boolean(text == "t")),
+        INT2_OID | INT4_OID | INT8_OID | OID_OID => text.parse().ok().map(Value::Integer),
+        FLOAT4_OID | FLOAT8_OID => text
+            .parse()
+            .ok()
+            .and_then(|float| NotNan::new(float).ok())
+            .map(Value::Float),
+        JSON_OID | JSONB_OID => serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .map(Value::from),
+        TIMESTAMPTZ_OID => DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%#z")
+            .ok()
+            .map(|timestamp| Value::Timestamp(timestamp.with_timezone(&Utc))),
+        TIMESTAMP_OID => NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .map(|timestamp| Value::Timestamp(timestamp.and_utc())),
+        _ => None,
+    };
+    value.unwrap_or(Value::Bytes(bytes))
+}
+
+fn create_log(
+    operation: &'static str,
+    relation: &Relation,
+    before: Option<ObjectMap>,
+    after: Option<ObjectMap>,
+    lsn: PgLsn,
+    transaction: &Transaction,
+    log_namespace: LogNamespace,
+) -> LogEvent {
+    let mut log = LogEvent::default();
+
+    if log_namespace == LogNamespace::Legacy {
+        log.maybe_insert(
+            log_schema().timestamp_key_target_path(),
+            transaction.commit_timestamp,
+        );
+    }
+
+    log.insert(event_path!("op"), operation);
+    log.insert(event_path!("schema"), relation.namespace.as_str());
+    log.insert(event_path!("table"), relation.name.as_str());
+    if let Some(before) = before {
+        log.insert(event_path!("before"), before);
+    }
+    if let Some(after) = after {
+        log.insert(event_path!("after"), after);
+    }
+    log.insert(event_path!("lsn"), lsn.to_string());
+    log.insert(event_path!("xid"), i64::from(transaction.xid));
+    log.insert(
+        event_path!("commit_timestamp"),
+        transaction.commit_timestamp,
+    );
+
+    log_namespace.insert_standard_vector_source_metadata(
+        &mut log,
+        PostgresCdcConfig::NAME,
+        Utc::now(),
+    );
+
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vrl::value;
+
+    use super::{super::pgoutput::tests::*, *};
+
+    fn relation() -> Relation {
+        match Message::decode(&relation_message()).unwrap() {
+            Message::Relation(relation) => relation,
+            message => panic!("unexpected message: {message:?}"),
+        }
+    }
+
+    fn transaction() -> Transaction {
+        Transaction {
+            xid: 735,
+            commit_timestamp: Utc.timestamp_opt(1_700_000_000, 0).single().unwrap(),
+            events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn decodes_values() {
+        assert_eq!(decode_value(BOOL_OID, Bytes::from("t")), value!(true));
+        assert_eq!(decode_value(INT8_OID, Bytes::from("-42")), value!(-42));
+        assert_eq!(decode_value(FLOAT8_OID, Bytes::from("1.5")), value!(1.5));
+        assert_eq!(decode_value(FLOAT8_OID, Bytes::from("NaN")), value!("NaN"));
+        assert_eq!(
+            decode_value(JSONB_OID, Bytes::from(r#"{"a": [1, "b"]}"#)),
+            value!({"a": [1, "b"]})
+        );
+        assert_eq!(
+            decode_value(TIMESTAMPTZ_OID, Bytes::from("2023-11-14 22:13:20.5+01")),
+            Value::Timestamp(Utc.timestamp_opt(1_699_996_400, 500_000_000).unwrap())
+        );
+        assert_eq!(
+            decode_value(TIMESTAMP_OID, Bytes::from("2023-11-14 22:13:20")),
+            Value::Timestamp(Utc.timestamp_opt(1_700_000_000, 0).unwrap())
+        );
+        assert_eq!(
+            decode_value(1700, Bytes::from("3.14159")),
+            value!("3.14159")
+        );
+    }
+
+    #[test]
+    fn create_log_update() {
+        let relation = relation();
+        let Message::Update {
+            old: Some(OldTuple::Key(old)),
+            new,
+            ..
+        } = Message::decode(&update_message()).unwrap()
+        else {
+            panic!("unexpected message");
+        };
+
+        let log = create_log(
+            "update",
+            &relation,
+            Some(create_row(&relation, old, true)),
+            Some(create_row(&relation, new, false)),
+            PgLsn::from(0x16B3748),
+            &transaction(),
+            LogNamespace::Legacy,
+        );
+
+        assert_eq!(log.get(".op"), Some(&value!("update")));
+        assert_eq!(log.get(".schema"), Some(&value!("public")));
+        assert_eq!(log.get(".table"), Some(&value!("users")));
+        assert_eq!(log.get(".before"), Some(&value!({"id": 1})));
+        // The unchanged TOASTed `name` isn't part of the new image.
+        assert_eq!(log.get(".after"), Some(&value!({"id": 2})));
+        assert_eq!(log.get(".lsn"), Some(&value!("0/16B3748")));
+        assert_eq!(log.get(".xid"), Some(&value!(735)));
+        assert_eq!(
+            log.get(".timestamp"),
+            Some(&Value::from(transaction().commit_timestamp))
+        );
+        assert_eq!(log.get(".source_type"), Some(&value!("postgres_cdc")));
+    }
+
+    #[test]
+    fn create_log_vector_namespace() {
+        let relation = relation();
+        let log = create_log(
+            "delete",
+            &relation,
+            Some(create_row(
+                &relation,
+                vec![TupleValue::Text(Bytes::from("1")), TupleValue::Null],
+                false,
+            )),
+            None,
+            PgLsn::from(1),
+            &transaction(),
+            LogNamespace::Vector,
+        );
+
+        assert_eq!(log.get(".op"), Some(&value!("delete")));
+        assert_eq!(log.get(".before"), Some(&value!({"id": 1, "name": null})));
+        assert_eq!(log.get(".after"), None);
+        assert_eq!(
+            log.get(".commit_timestamp"),
+            Some(&Value::from(transaction().commit_timestamp))
+        );
+        assert_eq!(
+            log.get("%vector.source_type"),
+            Some(&value!("postgres_cdc"))
+        );
+    }
+}
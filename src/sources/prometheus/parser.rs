@@ -1,15 +1,17 @@
 #[cfg(feature = "sources-prometheus-remote-write")]
 use super::remote_write::MetadataConflictStrategy;
 use chrono::{DateTime, TimeZone, Utc};
-#[cfg(feature = "sources-prometheus-remote-write")]
-use vector_lib::prometheus::parser::proto;
 use vector_lib::prometheus::parser::{
-    GroupKind, MetadataConflictStrategy as ParserMetadataConflictStrategy, MetricGroup, ParserError,
+    GroupKind, MetadataConflictStrategy as ParserMetadataConflictStrategy, MetricGroup,
+    ParserError, proto,
 };
 
-use crate::event::{
-    Event,
-    metric::{Bucket, Metric, MetricKind, MetricTags, MetricValue, Quantile},
+use crate::{
+    event::{
+        Event,
+        metric::{Bucket, Metric, MetricKind, MetricTags, MetricValue, Quantile},
+    },
+    sinks::prometheus::remote_write::metadata::insert_metadata,
 };
 
 fn utc_timestamp(timestamp: Option<i64>, default: DateTime<Utc>) -> DateTime<Utc> {
@@ -68,7 +70,7 @@ fn reparse_groups(
         MetricKind::Absolute
     };
 
-    for group in groups {
+    for mut group in groups {
         match group.metrics {
             GroupKind::Counter(metrics) => {
                 for (key, metric) in metrics {
@@ -76,6 +78,7 @@ fn reparse_groups(
                         continue;
                     }
 
+                    let exemplars = group.exemplars.swap_remove(&key).unwrap_or_default();
                     let tags = combine_tags(key.labels, tag_overrides.clone());
 
                     let counter = Metric::new(
@@ -88,7 +91,7 @@ fn reparse_groups(
                     .with_timestamp(Some(utc_timestamp(key.timestamp, start)))
                     .with_tags(tags.as_option());
 
-                    result.push(counter.into());
+                    result.push(with_metadata(counter, group.metadata.as_ref(), exemplars).into());
                 }
             }
             GroupKind::Gauge(metrics) | GroupKind::Untyped(metrics) => {
@@ -97,6 +100,7 @@ fn reparse_groups(
                         continue;
                     }
 
+                    let exemplars = group.exemplars.swap_remove(&key).unwrap_or_default();
                     let tags = combine_tags(key.labels, tag_overrides.clone());
 
                     let gauge = Metric::new(
@@ -110,7 +114,7 @@ fn reparse_groups(
                     .with_timestamp(Some(utc_timestamp(key.timestamp, start)))
                     .with_tags(tags.as_option());
 
-                    result.push(gauge.into());
+                    result.push(with_metadata(gauge, group.metadata.as_ref(), exemplars).into());
                 }
             }
             GroupKind::Histogram(metrics) => {
@@ -121,6 +125,7 @@ fn reparse_groups(
                         continue;
                     }
 
+                    let exemplars = group.exemplars.swap_remove(&key).unwrap_or_default();
                     let tags = combine_tags(key.labels, tag_overrides.clone());
 
                     let mut buckets = metric.buckets;
@@ -137,26 +142,25 @@ fn reparse_groups(
                         buckets.pop();
                     }
 
-                    result.push(
-                        Metric::new(
-                            group.name.clone(),
-                            metric_kind,
-                            MetricValue::AggregatedHistogram {
-                                buckets: buckets
-                                    .into_iter()
-                                    .map(|b| Bucket {
-                                        upper_limit: b.bucket,
-                                        count: b.count,
-                                    })
-                                    .collect(),
-                                count: metric.count,
-                                sum: metric.sum,
-                            },
-                        )
-                        .with_timestamp(Some(utc_timestamp(key.timestamp, start)))
-                        .with_tags(tags.as_option())
-                        .into(),
-                    );
+                    let histogram = Metric::new(
+                        group.name.clone(),
+                        metric_kind,
+                        MetricValue::AggregatedHistogram {
+                            buckets: buckets
+                                .into_iter()
+                                .map(|b| Bucket {
+                                    upper_limit: b.bucket,
+                                    count: b.count,
+                                })
+                                .collect(),
+                            count: metric.count,
+                            sum: metric.sum,
+                        },
+                    )
+                    .with_timestamp(Some(utc_timestamp(key.timestamp, start)))
+                    .with_tags(tags.as_option());
+                    result
+                        .push(with_metadata(histogram, group.metadata.as_ref(), exemplars).into());
                 }
             }
             GroupKind::Summary(metrics) => {
@@ -171,30 +175,29 @@ fn reparse_groups(
                         continue;
                     }
 
+                    let exemplars = group.exemplars.swap_remove(&key).unwrap_or_default();
                     let tags = combine_tags(key.labels, tag_overrides.clone());
 
-                    result.push(
-                        Metric::new(
-                            group.name.clone(),
-                            // Summaries are always absolute: aggregating them makes no sense
-                            MetricKind::Absolute,
-                            MetricValue::AggregatedSummary {
-                                quantiles: metric
-                                    .quantiles
-                                    .into_iter()
-                                    .map(|q| Quantile {
-                                        quantile: q.quantile,
-                                        value: q.value,
-                                    })
-                                    .collect(),
-                                count: metric.count,
-                                sum: metric.sum,
-                            },
-                        )
-                        .with_timestamp(Some(utc_timestamp(key.timestamp, start)))
-                        .with_tags(tags.as_option())
-                        .into(),
-                    );
+                    let summary = Metric::new(
+                        group.name.clone(),
+                        // Summaries are always absolute: aggregating them makes no sense
+                        MetricKind::Absolute,
+                        MetricValue::AggregatedSummary {
+                            quantiles: metric
+                                .quantiles
+                                .into_iter()
+                                .map(|q| Quantile {
+                                    quantile: q.quantile,
+                                    value: q.value,
+                                })
+                                .collect(),
+                            count: metric.count,
+                            sum: metric.sum,
+                        },
+                    )
+                    .with_timestamp(Some(utc_timestamp(key.timestamp, start)))
+                    .with_tags(tags.as_option());
+                    result.push(with_metadata(summary, group.metadata.as_ref(), exemplars).into());
                 }
             }
         }
@@ -213,6 +216,16 @@ impl From<MetadataConflictStrategy> for ParserMetadataConflictStrategy {
     }
 }
 
+/// Keeps the metadata and exemplars of a remote_write request along with the metric.
+fn with_metadata(
+    mut metric: Metric,
+    metadata: Option<&proto::MetricMetadata>,
+    exemplars: Vec<proto::Exemplar>,
+) -> Metric {
+    insert_metadata(&mut metric, metadata, exemplars);
+    metric
+}
+
 fn combine_tags(
     base_tags: impl Into<MetricTags>,
     tag_overrides: impl IntoIterator<Item = (String, String)>,
@@ -205,7 +205,8 @@ impl HttpSource for RemoteWriteSource {
 mod test {
     use chrono::{SubsecRound as _, Utc};
     use vector_lib::{
-        event::{EventStatus, Metric, MetricKind, MetricValue},
+        event::{EventStatus, Metric, MetricKind, MetricValue, Value},
+        lookup::path,
         metric_tags,
     };
 
@@ -367,6 +368,7 @@ mod test {
                     value: 42.0,
                     timestamp: chrono::Utc::now().timestamp_millis(),
                 }],
+                ..Default::default()
             }],
         };
 
@@ -405,6 +407,7 @@ mod test {
                     value: 42.0,
                     timestamp: chrono::Utc::now().timestamp_millis(),
                 }],
+                ..Default::default()
             }],
         };
 
@@ -542,6 +545,7 @@ mod test {
                             value: 42.0,
                             timestamp: chrono::Utc::now().timestamp_millis(),
                         }],
+                        ..Default::default()
                     },
                     proto::TimeSeries {
                         labels: vec![proto::Label {
@@ -552,6 +556,7 @@ mod test {
                             value: f64::NAN,
                             timestamp: chrono::Utc::now().timestamp_millis(),
                         }],
+                        ..Default::default()
                     },
                 ],
             };
@@ -613,6 +618,7 @@ mod test {
                             value: 42.0,
                             timestamp: chrono::Utc::now().timestamp_millis(),
                         }],
+                        ..Default::default()
                     },
                     proto::TimeSeries {
                         labels: vec![proto::Label {
@@ -623,6 +629,7 @@ mod test {
                             value: f64::NAN,
                             timestamp: chrono::Utc::now().timestamp_millis(),
                         }],
+                        ..Default::default()
                     },
                 ],
             };
@@ -822,6 +829,66 @@ mod test {
         );
     }
 
+    #[test]
+    fn keeps_metadata_and_exemplars() {
+        let request = proto::WriteRequest {
+            metadata: vec![proto::MetricMetadata {
+                r#type: proto::MetricType::Counter as i32,
+                metric_family_name: "requests_total".into(),
+                help: "The requests.".into(),
+                unit: String::default(),
+            }],
+            timeseries: vec![proto::TimeSeries {
+                labels: vec![proto::Label {
+                    name: "__name__".into(),
+                    value: "requests_total".into(),
+                }],
+                samples: vec![proto::Sample {
+                    value: 42.0,
+                    timestamp: 1395066367600,
+                }],
+                exemplars: vec![proto::Exemplar {
+                    labels: vec![proto::Label {
+                        name: "trace_id".into(),
+                        value: "abc".into(),
+                    }],
+                    value: 1.0,
+                    timestamp: 1395066367500,
+                }],
+                ..Default::default()
+            }],
+        };
+        let source = RemoteWriteSource {
+            metadata_conflict_strategy: Default::default(),
+            skip_nan_values: false,
+        };
+        let events = source.decode_body(request.encode_to_vec().into()).unwrap();
+
+        assert_eq!(events.len(), 1);
+        let metadata = events[0].as_metric().metadata().value();
+        assert_eq!(
+            metadata.get(path!("prometheus_remote_write", "type")),
+            Some(&Value::from("counter"))
+        );
+        assert_eq!(
+            metadata.get(path!("prometheus_remote_write", "help")),
+            Some(&Value::from("The requests."))
+        );
+        let exemplar = path!("prometheus_remote_write", "exemplars", 0);
+        assert_eq!(
+            metadata
+                .get(exemplar)
+                .and_then(|exemplar| exemplar.get(path!("labels", "trace_id"))),
+            Some(&Value::from("abc"))
+        );
+        assert_eq!(
+            metadata
+                .get(exemplar)
+                .and_then(|exemplar| exemplar.get(path!("value"))),
+            Some(&Value::from(1.0))
+        );
+    }
+
     #[tokio::test]
     async fn accepts_conflicting_metadata() {
         let address = test_util::next_addr();
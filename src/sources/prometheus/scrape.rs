@@ -156,6 +156,7 @@ impl SourceConfig for PrometheusScrapeConfig {
             headers: HashMap::new(),
             content_type: "text/plain".to_string(),
             auth: self.auth.clone(),
+            authorizer: None,
             tls,
             proxy: cx.proxy.clone(),
             shutdown: cx.shutdown,
@@ -701,8 +702,11 @@ mod test {
                 buckets: vec![1.0, 2.0, 4.0],
                 quantiles: vec![],
                 distributions_as_summaries: false,
+                native_histograms: false,
                 flush_period_secs: Duration::from_secs(3),
                 suppress_timestamp: false,
+                relabel_configs: vec![],
+                paths: vec![],
                 acknowledgements: Default::default(),
             },
         );
@@ -62,7 +62,7 @@ impl InputHandler {
     }
 }
 
-async fn backoff_exponential(exp: u32) {
+pub(super) async fn backoff_exponential(exp: u32) {
     let ms = if exp <= 4 { 2_u64.pow(exp + 5) } else { 1000 };
     tokio::time::sleep(Duration::from_millis(ms)).await;
 }
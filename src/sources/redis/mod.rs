@@ -9,8 +9,9 @@ use vector_lib::{
         StreamDecodingError,
         decoding::{DeserializerConfig, FramingConfig},
     },
-    config::{LegacyKey, LogNamespace},
+    config::{LegacyKey, LogNamespace, SourceAcknowledgementsConfig},
     configurable::configurable_component,
+    finalization::BatchNotifier,
     internal_event::{
         ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Protocol, Registered,
     },
@@ -23,16 +24,19 @@ use crate::{
     config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput, log_schema},
     event::Event,
     internal_events::{EventsReceived, StreamClosedError},
-    serde::{default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
 };
 
 mod channel;
 mod list;
+mod stream;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Failed to build redis client: {}", source))]
     Client { source: redis::RedisError },
+    #[snafu(display("The `stream` data type requires the `stream` options."))]
+    MissingStreamOptions,
 }
 
 /// Data type to use for reading messages from Redis.
@@ -49,6 +53,14 @@ pub enum DataTypeConfig {
     ///
     /// This is based on Redis' Pub/Sub capabilities.
     Channel,
+
+    /// The `stream` data type.
+    ///
+    /// The entries are read by a consumer of a [consumer group][consumer_groups], and
+    /// acknowledged once their events are processed.
+    ///
+    /// [consumer_groups]: https://redis.io/docs/latest/develop/data-types/streams/#consumer-groups
+    Stream,
 }
 
 /// Options for the Redis `list` data type.
@@ -74,6 +86,59 @@ pub enum Method {
     Rpop,
 }
 
+/// Options for the Redis `stream` data type.
+#[configurable_component]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    /// The consumer group the entries are read with.
+    ///
+    /// The group, and the stream, are created if they don't exist, in which case only the
+    /// entries added afterwards are read.
+    #[configurable(metadata(docs::examples = "vector"))]
+    group: String,
+
+    /// The name of the consumer in the group.
+    ///
+    /// It must be unique among the running instances of Vector reading from the group, and
+    /// stable across their restarts, so that the entries left pending are read again. By
+    /// default, the hostname is used.
+    #[configurable(metadata(docs::examples = "vector-0"))]
+    consumer: Option<String>,
+
+    /// The field of the entries that holds the message to decode.
+    ///
+    /// The entries without this field are acknowledged and skipped.
+    #[serde(default = "default_stream_field")]
+    #[configurable(metadata(docs::examples = "payload"))]
+    field: String,
+
+    /// The maximum number of entries read at once.
+    #[serde(default = "default_stream_batch_size")]
+    batch_size: usize,
+
+    /// The time after which the entries left pending by other consumers of the group are
+    /// claimed, in seconds.
+    ///
+    /// This is how the entries delivered to consumers that were removed, or died, are
+    /// eventually processed.
+    #[serde(default = "default_claim_min_idle_secs")]
+    #[configurable(metadata(docs::human_name = "Claim Minimum Idle Time"))]
+    claim_min_idle_secs: u64,
+}
+
+fn default_stream_field() -> String {
+    "message".to_owned()
+}
+
+const fn default_stream_batch_size() -> usize {
+    100
+}
+
+const fn default_claim_min_idle_secs() -> u64 {
+    60
+}
+
 pub struct ConnectionInfo {
     protocol: &'static str,
     endpoint: String,
@@ -96,13 +161,16 @@ impl From<&redis::ConnectionInfo> for ConnectionInfo {
 #[derive(Clone, Debug, Derivative)]
 #[serde(deny_unknown_fields)]
 pub struct RedisSourceConfig {
-    /// The Redis data type (`list` or `channel`) to use.
+    /// The Redis data type (`list`, `channel` or `stream`) to use.
     #[serde(default)]
     data_type: DataTypeConfig,
 
     #[configurable(derived)]
     list: Option<ListOption>,
 
+    #[configurable(derived)]
+    stream: Option<StreamOption>,
+
     /// The Redis URL to connect to.
     ///
     /// The URL must take the form of `protocol://server:port/db` where the `protocol` can either be `redis` or `rediss` for connections secured using TLS.
@@ -131,6 +199,10 @@ pub struct RedisSourceConfig {
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
 
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -157,6 +229,7 @@ impl GenerateConfig for RedisSourceConfig {
 impl SourceConfig for RedisSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
 
         // A key must be specified to actually query i.e. the list to pop from, or the channel to subscribe to.
         if self.key.is_empty() {
@@ -191,6 +264,13 @@ impl SourceConfig for RedisSourceConfig {
                 handler.watch(method).await
             }
             DataTypeConfig::Channel => handler.subscribe(connection_info).await,
+            DataTypeConfig::Stream => {
+                let options = self
+                    .stream
+                    .clone()
+                    .ok_or(BuildError::MissingStreamOptions)?;
+                handler.read_stream(options, acknowledgements).await
+            }
         }
     }
 
@@ -203,7 +283,7 @@ impl SourceConfig for RedisSourceConfig {
             .and_then(|k| k.path)
             .map(LegacyKey::InsertIfEmpty);
 
-        let schema_definition = self
+        let mut schema_definition = self
             .decoding
             .schema_definition(log_namespace)
             .with_source_metadata(
@@ -214,6 +294,15 @@ impl SourceConfig for RedisSourceConfig {
                 None,
             )
             .with_standard_vector_source_metadata();
+        if matches!(self.data_type, DataTypeConfig::Stream) {
+            schema_definition = schema_definition.with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!("id"))),
+                &owned_value_path!("id"),
+                Kind::bytes(),
+                None,
+            );
+        }
 
         vec![SourceOutput::new_maybe_logs(
             self.decoding.output_type(),
@@ -222,7 +311,7 @@ impl SourceConfig for RedisSourceConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        matches!(self.data_type, DataTypeConfig::Stream)
     }
 }
 
@@ -239,6 +328,17 @@ struct InputHandler {
 
 impl InputHandler {
     async fn handle_line(&mut self, line: String) -> Result<(), ()> {
+        self.handle_message(line, None, &None).await
+    }
+
+    /// Decodes a message, the stream entries also carrying their ID and the notifier of the batch
+    /// they were read in.
+    async fn handle_message(
+        &mut self,
+        line: String,
+        id: Option<&str>,
+        batch: &Option<BatchNotifier>,
+    ) -> Result<(), ()> {
         let now = Utc::now();
 
         self.bytes_received.emit(ByteSize(line.len()));
@@ -273,9 +373,18 @@ impl InputHandler {
                                 path!("key"),
                                 self.key.as_str(),
                             );
+                            if let Some(id) = id {
+                                self.log_namespace.insert_source_metadata(
+                                    RedisSourceConfig::NAME,
+                                    log,
+                                    Some(LegacyKey::InsertIfEmpty(path!("id"))),
+                                    path!("id"),
+                                    id,
+                                );
+                            }
                         };
 
-                        event
+                        event.with_batch_notifier_option(batch)
                     });
 
                     if (self.cx.out.send_batch(events).await).is_err() {
@@ -315,6 +424,7 @@ mod integration_test {
     use crate::{
         SourceSender,
         config::log_schema,
+        event::EventStatus,
         test_util::{
             collect_n,
             components::{SOURCE_TAGS, run_and_assert_source_compliance_n},
@@ -343,11 +453,13 @@ mod integration_test {
             list: Some(ListOption {
                 method: Method::Rpop,
             }),
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
             log_namespace: Some(false),
         };
 
@@ -384,11 +496,13 @@ mod integration_test {
             list: Some(ListOption {
                 method: Method::Rpop,
             }),
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: Some(OptionalValuePath::from(owned_value_path!("remapped_key"))),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
             log_namespace: Some(true),
         };
 
@@ -425,11 +539,13 @@ mod integration_test {
             list: Some(ListOption {
                 method: Method::Lpop,
             }),
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
             log_namespace: Some(false),
         };
 
@@ -458,11 +574,13 @@ mod integration_test {
         let config = RedisSourceConfig {
             data_type: DataTypeConfig::Channel,
             list: None,
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
             log_namespace: Some(false),
         };
 
@@ -507,4 +625,74 @@ mod integration_test {
             );
         }
     }
+
+    #[tokio::test]
+    async fn redis_source_stream_acknowledges_entries() {
+        let key = format!("test-stream-{}", random_string(10));
+
+        let config = RedisSourceConfig {
+            data_type: DataTypeConfig::Stream,
+            list: None,
+            stream: Some(StreamOption {
+                group: "vector".to_owned(),
+                consumer: Some("vector-0".to_owned()),
+                field: default_stream_field(),
+                batch_size: 2,
+                claim_min_idle_secs: default_claim_min_idle_secs(),
+            }),
+            url: REDIS_SERVER.to_owned(),
+            key: key.clone(),
+            redis_key: None,
+            framing: default_framing_message_based(),
+            decoding: default_decoding(),
+            acknowledgements: true.into(),
+            log_namespace: Some(false),
+        };
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let mut context = SourceContext::new_test(tx, None);
+        context.acknowledgements = true;
+        let source = config
+            .build(context)
+            .await
+            .expect("source should not fail to build");
+        tokio::spawn(source);
+
+        // The consumer group is created when the source is built, so the entries added from now on
+        // are read.
+        let client = redis::Client::open(REDIS_SERVER).unwrap();
+        let mut conn = client.get_connection_manager().await.unwrap();
+        let mut ids = Vec::new();
+        for message in ["1", "2", "3"] {
+            let id: String = conn.xadd(&key, "*", &[("message", message)]).await.unwrap();
+            ids.push(id);
+        }
+
+        let events = collect_n(rx, 3).await;
+        for (event, id) in events.iter().zip(&ids) {
+            assert_eq!(event.as_log()["id"], id.as_str().into());
+        }
+        assert_eq!(
+            events
+                .iter()
+                .map(
+                    |event| event.as_log()[log_schema().message_key().unwrap().to_string()].clone()
+                )
+                .collect::<Vec<_>>(),
+            vec!["1".into(), "2".into(), "3".into()]
+        );
+
+        // The entries are acknowledged once their events are delivered.
+        let mut pending = usize::MAX;
+        for _ in 0..50 {
+            let reply: redis::streams::StreamPendingReply =
+                conn.xpending(&key, "vector").await.unwrap();
+            pending = reply.count();
+            if pending == 0 {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(pending, 0);
+    }
 }
@@ -0,0 +1,243 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use futures::{FutureExt, StreamExt};
+use redis::{
+    AsyncCommands, ErrorKind, RedisError, RedisResult,
+    aio::ConnectionManager,
+    streams::{StreamId, StreamReadOptions, StreamReadReply},
+};
+use snafu::{ResultExt, Snafu};
+use vector_lib::{
+    finalization::{BatchNotifier, BatchStatus},
+    finalizer::UnorderedFinalizer,
+};
+
+use super::{InputHandler, StreamOption, list::backoff_exponential};
+use crate::{
+    internal_events::{RedisAckError, RedisReceiveEventError, RedisStreamEntryMissingField},
+    sources::Source,
+};
+
+/// How long the reads of new entries wait for entries to be added.
+const READ_BLOCK: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to create connection: {}", source))]
+    Connection { source: RedisError },
+    #[snafu(display("Failed to create the consumer group: {}", source))]
+    CreateGroup { source: RedisError },
+}
+
+/// Where the next entries are read from.
+struct StreamReader {
+    key: String,
+    group: String,
+    consumer: String,
+    count: usize,
+    claim_min_idle: Duration,
+    /// The entries that were delivered to this consumer before it restarted, and weren't
+    /// acknowledged, are read first, after this ID.
+    pending_cursor: Option<String>,
+    /// The entries pending for other consumers are claimed in passes, starting from this ID.
+    claim_cursor: String,
+    next_claim: Instant,
+}
+
+impl StreamReader {
+    async fn read(&mut self, conn: &mut ConnectionManager) -> RedisResult<Vec<StreamId>> {
+        if let Some(cursor) = &self.pending_cursor {
+            let options = StreamReadOptions::default()
+                .group(&self.group, &self.consumer)
+                .count(self.count);
+            let entries = read_group(conn, &self.key, cursor, &options).await?;
+            self.pending_cursor = entries.last().map(|entry| entry.id.clone());
+            return Ok(entries);
+        }
+
+        if Instant::now() >= self.next_claim {
+            let (cursor, entries) = self.claim(conn).await?;
+            // A pass is complete once the cursor wraps around.
+            if cursor == "0-0" {
+                self.next_claim = Instant::now() + self.claim_min_idle;
+            }
+            self.claim_cursor = cursor;
+            return Ok(entries);
+        }
+
+        let options = StreamReadOptions::default()
+            .group(&self.group, &self.consumer)
+            .count(self.count)
+            .block(READ_BLOCK.as_millis() as usize);
+        read_group(conn, &self.key, ">", &options).await
+    }
+
+    /// Claims the entries that were delivered to other consumers, presumably dead, and weren't
+    /// acknowledged for `claim_min_idle`.
+    async fn claim(&self, conn: &mut ConnectionManager) -> RedisResult<(String, Vec<StreamId>)> {
+        let reply: Vec<redis::Value> = redis::cmd("XAUTOCLAIM")
+            .arg(&self.key)
+            .arg(&self.group)
+            .arg(&self.consumer)
+            .arg(self.claim_min_idle.as_millis() as u64)
+            .arg(&self.claim_cursor)
+            .arg("COUNT")
+            .arg(self.count)
+            .query_async(conn)
+            .await?;
+        let mut reply = reply.into_iter();
+        let cursor = redis::from_owned_redis_value(reply.next().unwrap_or(redis::Value::Nil))?;
+        let entries = redis::from_owned_redis_value(reply.next().unwrap_or(redis::Value::Nil))?;
+        Ok((cursor, entries))
+    }
+}
+
+async fn read_group(
+    conn: &mut ConnectionManager,
+    key: &str,
+    id: &str,
+    options: &StreamReadOptions,
+) -> RedisResult<Vec<StreamId>> {
+    let reply: Option<StreamReadReply> = conn.xread_options(&[key], &[id], options).await?;
+    Ok(reply
+        .into_iter()
+        .flat_map(|reply| reply.keys)
+        .flat_map(|key| key.ids)
+        .collect())
+}
+
+impl InputHandler {
+    pub(super) async fn read_stream(
+        mut self,
+        options: StreamOption,
+        acknowledgements: bool,
+    ) -> crate::Result<Source> {
+        let mut conn = self
+            .client
+            .get_connection_manager()
+            .await
+            .context(ConnectionSnafu {})?;
+
+        let created: RedisResult<()> = conn
+            .xgroup_create_mkstream(&self.key, &options.group, "$")
+            .await;
+        // The group already exists when the source restarts.
+        match created {
+            Err(error) if error.code() != Some("BUSYGROUP") => {
+                Err(error).context(CreateGroupSnafu {})?
+            }
+            _ => {}
+        }
+
+        let consumer = match options.consumer {
+            Some(consumer) => consumer,
+            None => crate::get_hostname()?,
+        };
+        let mut reader = StreamReader {
+            key: self.key.clone(),
+            group: options.group,
+            consumer,
+            count: options.batch_size,
+            claim_min_idle: Duration::from_secs(options.claim_min_idle_secs),
+            pending_cursor: Some("0".to_owned()),
+            claim_cursor: "0-0".to_owned(),
+            next_claim: Instant::now(),
+        };
+        let field = options.field;
+
+        Ok(Box::pin(async move {
+            let mut shutdown = self.cx.shutdown.clone();
+            let (finalizer, mut ack_stream) = UnorderedFinalizer::<Vec<String>>::maybe_new(
+                acknowledgements,
+                Some(shutdown.clone()),
+            );
+            // The entries whose events weren't acknowledged yet, which aren't claimed again.
+            let mut in_flight = HashSet::new();
+            let mut retry: u32 = 0;
+
+            loop {
+                // Acknowledge the entries whose events were delivered since the last read.
+                while let Some(Some((status, ids))) = ack_stream.next().now_or_never() {
+                    for id in &ids {
+                        in_flight.remove(id);
+                    }
+                    // The entries whose events failed are left pending, to be claimed again.
+                    if status != BatchStatus::Errored {
+                        ack(&mut conn, &reader, ids).await;
+                    }
+                }
+
+                let result = tokio::select! {
+                    result = reader.read(&mut conn) => result,
+                    _ = &mut shutdown => break,
+                };
+                let entries = match result {
+                    Err(error) => {
+                        let kind = error.kind();
+
+                        emit!(RedisReceiveEventError::from(error));
+
+                        if kind == ErrorKind::IoError {
+                            retry += 1;
+                            backoff_exponential(retry).await
+                        }
+                        continue;
+                    }
+                    Ok(entries) => {
+                        if retry > 0 {
+                            retry = 0
+                        }
+                        entries
+                    }
+                };
+
+                let entries = entries
+                    .into_iter()
+                    .filter(|entry| !in_flight.contains(&entry.id))
+                    .collect::<Vec<_>>();
+                if entries.is_empty() {
+                    continue;
+                }
+
+                let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
+                let mut ids = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    match entry.get::<String>(&field) {
+                        Some(line) => {
+                            if let Err(()) =
+                                self.handle_message(line, Some(&entry.id), &batch).await
+                            {
+                                return Ok(());
+                            }
+                        }
+                        None => emit!(RedisStreamEntryMissingField {
+                            id: &entry.id,
+                            field: &field,
+                        }),
+                    }
+                    ids.push(entry.id);
+                }
+                drop(batch);
+
+                match (&finalizer, receiver) {
+                    (Some(finalizer), Some(receiver)) => {
+                        in_flight.extend(ids.iter().cloned());
+                        finalizer.add(ids, receiver);
+                    }
+                    _ => ack(&mut conn, &reader, ids).await,
+                }
+            }
+            Ok(())
+        }))
+    }
+}
+
+async fn ack(conn: &mut ConnectionManager, reader: &StreamReader, ids: Vec<String>) {
+    let acked: RedisResult<i64> = conn.xack(&reader.key, &reader.group, ids.as_slice()).await;
+    if let Err(error) = acked {
+        emit!(RedisAckError { error });
+    }
+}
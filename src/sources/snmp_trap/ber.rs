@@ -0,0 +1,311 @@
+//! A decoder for the subset of the ASN.1 [Basic Encoding Rules][ber] used by SNMP.
+//!
+//! [ber]: https://en.wikipedia.org/wiki/X.690#BER_encoding
+use snafu::Snafu;
+
+pub(super) const INTEGER: u8 = 0x02;
+pub(super) const OCTET_STRING: u8 = 0x04;
+pub(super) const NULL: u8 = 0x05;
+pub(super) const OBJECT_IDENTIFIER: u8 = 0x06;
+pub(super) const SEQUENCE: u8 = 0x30;
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub(super) enum BerError {
+    #[snafu(display("unexpected end of data"))]
+    Truncated,
+
+    #[snafu(display("unexpected tag 0x{actual:02x}, expected 0x{expected:02x}"))]
+    UnexpectedTag { expected: u8, actual: u8 },
+
+    #[snafu(display("unsupported length encoding"))]
+    UnsupportedLength,
+
+    #[snafu(display("integer is too large"))]
+    IntegerOverflow,
+
+    #[snafu(display("invalid object identifier"))]
+    InvalidObjectIdentifier,
+}
+
+/// Reads the type-length-value elements of BER encoded data, borrowing their content.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(super) const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the data that has not been read yet.
+    pub(super) const fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the tag of the next element, without reading it.
+    pub(super) fn peek_tag(&self) -> Result<u8, BerError> {
+        self.data.first().copied().ok_or(BerError::Truncated)
+    }
+
+    /// Reads the next element, returning its tag and content.
+    pub(super) fn read_any(&mut self) -> Result<(u8, &'a [u8]), BerError> {
+        let (&tag, rest) = self.data.split_first().ok_or(BerError::Truncated)?;
+        let (&first, mut rest) = rest.split_first().ok_or(BerError::Truncated)?;
+
+        let length = if first & 0x80 == 0 {
+            usize::from(first)
+        } else {
+            // The long form gives the number of bytes the length is encoded in. The indefinite
+            // form isn't allowed by SNMP.
+            let count = usize::from(first & 0x7f);
+            if count == 0 || count > 4 {
+                return Err(BerError::UnsupportedLength);
+            }
+            let (bytes, remaining) = rest.split_at_checked(count).ok_or(BerError::Truncated)?;
+            rest = remaining;
+            bytes
+                .iter()
+                .fold(0, |length, byte| (length << 8) | usize::from(*byte))
+        };
+
+        let (content, rest) = rest.split_at_checked(length).ok_or(BerError::Truncated)?;
+        self.data = rest;
+        Ok((tag, content))
+    }
+
+    /// Reads the next element, checking its tag.
+    pub(super) fn read(&mut self, expected: u8) -> Result<&'a [u8], BerError> {
+        let (actual, content) = self.read_any()?;
+        if actual != expected {
+            return Err(BerError::UnexpectedTag { expected, actual });
+        }
+        Ok(content)
+    }
+
+    /// Reads a constructed element, such as a sequence, returning a reader of its elements.
+    pub(super) fn read_constructed(&mut self, expected: u8) -> Result<Reader<'a>, BerError> {
+        self.read(expected).map(Reader::new)
+    }
+
+    pub(super) fn read_integer(&mut self) -> Result<i64, BerError> {
+        decode_integer(self.read(INTEGER)?)
+    }
+
+    pub(super) fn read_octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        self.read(OCTET_STRING)
+    }
+
+    pub(super) fn read_oid(&mut self) -> Result<Vec<u32>, BerError> {
+        decode_oid(self.read(OBJECT_IDENTIFIER)?)
+    }
+}
+
+/// Decodes a two's complement signed integer.
+pub(super) fn decode_integer(content: &[u8]) -> Result<i64, BerError> {
+    if content.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    let sign = content.first().is_some_and(|byte| byte & 0x80 != 0);
+    let initial = if sign { -1 } else { 0 };
+    Ok(content
+        .iter()
+        .fold(initial, |value, byte| (value << 8) | i64::from(*byte)))
+}
+
+/// Decodes an unsigned integer, such as the `Counter64` application type. A leading zero byte is
+/// allowed, to keep the most significant bit from being interpreted as a sign.
+pub(super) fn decode_unsigned(content: &[u8]) -> Result<u64, BerError> {
+    let content = match content {
+        [0, rest @ ..] => rest,
+        content => content,
+    };
+    if content.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    Ok(content
+        .iter()
+        .fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+}
+
+/// Decodes the arcs of an object identifier.
+pub(super) fn decode_oid(content: &[u8]) -> Result<Vec<u32>, BerError> {
+    let mut arcs = Vec::with_capacity(content.len() + 1);
+    let mut arc: u32 = 0;
+    for (index, byte) in content.iter().enumerate() {
+        arc = arc
+            .checked_mul(128)
+            .ok_or(BerError::InvalidObjectIdentifier)?
+            | u32::from(byte & 0x7f);
+        if byte & 0x80 != 0 {
+            if index == content.len() - 1 {
+                return Err(BerError::InvalidObjectIdentifier);
+            }
+            continue;
+        }
+
+        // The first two arcs are encoded together.
+        if arcs.is_empty() {
+            let first = (arc / 40).min(2);
+            arcs.push(first);
+            arcs.push(arc - first * 40);
+        } else {
+            arcs.push(arc);
+        }
+        arc = 0;
+    }
+
+    if arcs.is_empty() {
+        return Err(BerError::InvalidObjectIdentifier);
+    }
+    Ok(arcs)
+}
+
+/// Encodes an element, for building messages in tests.
+#[cfg(test)]
+pub(super) fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut data = vec![tag];
+    match content.len() {
+        length @ 0..=0x7f => data.push(length as u8),
+        length @ 0x80..=0xff => data.extend([0x81, length as u8]),
+        length => data.extend([0x82, (length >> 8) as u8, length as u8]),
+    }
+    data.extend(content);
+    data
+}
+
+#[cfg(test)]
+pub(super) fn encode_integer(tag: u8, value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    // Strip the redundant leading bytes, keeping the sign bit intact.
+    let start = (0..7)
+        .find(|&index| {
+            let redundant = (bytes[index] == 0x00 && bytes[index + 1] & 0x80 == 0)
+                || (bytes[index] == 0xff && bytes[index + 1] & 0x80 != 0);
+            !redundant
+        })
+        .unwrap_or(7);
+    encode(tag, &bytes[start..])
+}
+
+#[cfg(test)]
+pub(super) fn encode_oid(arcs: &[u32]) -> Vec<u8> {
+    let mut content = Vec::new();
+    let first = std::iter::once(arcs[0] * 40 + arcs[1]);
+    for arc in first.chain(arcs[2..].iter().copied()) {
+        let mut bytes = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            bytes.push((rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        content.extend(bytes.iter().rev());
+    }
+    encode(OBJECT_IDENTIFIER, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_elements() {
+        let data = [
+            0x30, 0x0b, 0x02, 0x01, 0x01, 0x04, 0x06, b'p', b'u', b'b', b'l', b'i', b'c',
+        ];
+        let mut reader = Reader::new(&data);
+        let mut sequence = reader.read_constructed(SEQUENCE).unwrap();
+        assert!(reader.is_empty());
+
+        assert_eq!(Ok(1), sequence.read_integer());
+        assert_eq!(Ok(b"public".as_slice()), sequence.read_octet_string());
+        assert!(sequence.is_empty());
+        assert_eq!(Err(BerError::Truncated), sequence.read_integer());
+    }
+
+    #[test]
+    fn reads_long_form_length() {
+        let mut data = vec![OCTET_STRING, 0x81, 0xc8];
+        data.extend([b'a'; 200]);
+
+        assert_eq!(Ok(&data[3..]), Reader::new(&data).read_octet_string());
+        assert_eq!(
+            Err(BerError::Truncated),
+            Reader::new(&data[..100]).read_octet_string()
+        );
+        assert_eq!(
+            Err(BerError::UnsupportedLength),
+            Reader::new(&[OCTET_STRING, 0x80]).read_octet_string()
+        );
+    }
+
+    #[test]
+    fn checks_tags() {
+        assert_eq!(
+            Err(BerError::UnexpectedTag {
+                expected: INTEGER,
+                actual: NULL
+            }),
+            Reader::new(&[NULL, 0x00]).read_integer()
+        );
+    }
+
+    #[test]
+    fn decodes_integers() {
+        assert_eq!(Ok(0), decode_integer(&[0x00]));
+        assert_eq!(Ok(127), decode_integer(&[0x7f]));
+        assert_eq!(Ok(128), decode_integer(&[0x00, 0x80]));
+        assert_eq!(Ok(-1), decode_integer(&[0xff]));
+        assert_eq!(Ok(-129), decode_integer(&[0xff, 0x7f]));
+        assert_eq!(
+            Ok(4_294_967_295),
+            decode_unsigned(&[0x00, 0xff, 0xff, 0xff, 0xff])
+        );
+        assert_eq!(
+            Ok(u64::MAX),
+            decode_unsigned(&[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+        );
+    }
+
+    #[test]
+    fn decodes_object_identifiers() {
+        assert_eq!(
+            Ok(vec![1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0]),
+            decode_oid(&[0x2b, 0x06, 0x01, 0x06, 0x03, 0x01, 0x01, 0x04, 0x01, 0x00])
+        );
+        assert_eq!(
+            Ok(vec![1, 3, 6, 1, 4, 1, 2636]),
+            decode_oid(&[0x2b, 0x06, 0x01, 0x04, 0x01, 0x94, 0x4c])
+        );
+        assert_eq!(
+            Err(BerError::InvalidObjectIdentifier),
+            decode_oid(&[0x2b, 0x86])
+        );
+    }
+
+    #[test]
+    fn encodes_round_trip() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            255,
+            256,
+            -1,
+            -128,
+            -129,
+            i64::from(u32::MAX),
+        ] {
+            let data = encode_integer(INTEGER, value);
+            assert_eq!(Ok(value), Reader::new(&data).read_integer());
+        }
+
+        let oid = [1, 3, 6, 1, 4, 1, 8072, 2, 3, 0, 1];
+        assert_eq!(Ok(oid.to_vec()), Reader::new(&encode_oid(&oid)).read_oid());
+    }
+}
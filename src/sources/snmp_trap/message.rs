@@ -0,0 +1,593 @@
+//! Decoding of SNMP messages and the notification PDUs they carry, as described in
+//! [RFC 1157][rfc1157], [RFC 3416][rfc3416] and [RFC 3412][rfc3412].
+//!
+//! [rfc1157]: https://datatracker.ietf.org/doc/html/rfc1157
+//! [rfc3416]: https://datatracker.ietf.org/doc/html/rfc3416
+//! [rfc3412]: https://datatracker.ietf.org/doc/html/rfc3412
+use std::net::Ipv4Addr;
+
+use snafu::Snafu;
+
+use super::ber::{self, BerError, OCTET_STRING, Reader, SEQUENCE};
+
+pub(super) const TRAP_V1: u8 = 0xa4;
+pub(super) const INFORM_REQUEST: u8 = 0xa6;
+pub(super) const TRAP_V2: u8 = 0xa7;
+pub(super) const RESPONSE: u8 = 0xa2;
+
+pub(super) const IP_ADDRESS: u8 = 0x40;
+pub(super) const COUNTER32: u8 = 0x41;
+pub(super) const GAUGE32: u8 = 0x42;
+pub(super) const TIME_TICKS: u8 = 0x43;
+pub(super) const OPAQUE: u8 = 0x44;
+pub(super) const COUNTER64: u8 = 0x46;
+const NO_SUCH_OBJECT: u8 = 0x80;
+const NO_SUCH_INSTANCE: u8 = 0x81;
+const END_OF_MIB_VIEW: u8 = 0x82;
+
+/// The User-based Security Model, the only one defined for SNMPv3.
+const USM_SECURITY_MODEL: i64 = 3;
+
+/// `SNMPv2-MIB::sysUpTime.0`
+pub(super) const SYS_UP_TIME: [u32; 9] = [1, 3, 6, 1, 2, 1, 1, 3, 0];
+/// `SNMPv2-MIB::snmpTrapOID.0`
+pub(super) const SNMP_TRAP_OID: [u32; 11] = [1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+/// `SNMPv2-MIB::snmpTraps`, the parent of the generic traps.
+const SNMP_TRAPS: [u32; 9] = [1, 3, 6, 1, 6, 3, 1, 1, 5];
+
+#[derive(Debug, Snafu)]
+pub(super) enum MessageError {
+    #[snafu(display("invalid encoding: {source}"))]
+    Encoding { source: BerError },
+
+    #[snafu(display("unsupported SNMP version {version}"))]
+    UnsupportedVersion { version: i64 },
+
+    #[snafu(display("unsupported PDU type 0x{tag:02x}"))]
+    UnsupportedPdu { tag: u8 },
+
+    #[snafu(display("unsupported security model {model}"))]
+    UnsupportedSecurityModel { model: i64 },
+
+    #[snafu(display("invalid IP address"))]
+    InvalidAddress,
+
+    #[snafu(display("invalid message flags"))]
+    InvalidFlags,
+}
+
+impl From<BerError> for MessageError {
+    fn from(source: BerError) -> Self {
+        Self::Encoding { source }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Version {
+    V1,
+    V2c,
+    V3,
+}
+
+impl Version {
+    pub(super) const fn as_str(self) -> &'static str {
+        match self {
+            Self::V1 => "1",
+            Self::V2c => "2c",
+            Self::V3 => "3",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum Message<'a> {
+    /// An SNMPv1 or SNMPv2c message, authenticated by its community string.
+    Community(CommunityMessage<'a>),
+
+    /// An SNMPv3 message, secured by the User-based Security Model.
+    V3(V3Message<'a>),
+}
+
+#[derive(Debug)]
+pub(super) struct CommunityMessage<'a> {
+    pub(super) version: Version,
+    pub(super) community: &'a [u8],
+    pub(super) pdu: Pdu,
+
+    /// The offset of the PDU in the message.
+    pdu_offset: usize,
+}
+
+impl CommunityMessage<'_> {
+    /// Builds the Response acknowledging an InformRequest.
+    ///
+    /// The Response carries the same request ID and variable bindings as the request, so it is
+    /// the received message with a different PDU type.
+    pub(super) fn inform_response(&self, data: &[u8]) -> Option<Vec<u8>> {
+        (self.pdu.pdu_type == PduType::InformRequest).then(|| {
+            let mut response = data.to_vec();
+            response[self.pdu_offset] = RESPONSE;
+            response
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct V3Message<'a> {
+    pub(super) flags: u8,
+    pub(super) security: SecurityParameters<'a>,
+
+    /// The offset of the authentication parameters in the message, which need to be zeroed to
+    /// verify the message digest.
+    pub(super) auth_offset: usize,
+    pub(super) scoped_pdu: ScopedPduData<'a>,
+}
+
+/// The USM security parameters, as defined in [RFC 3414][rfc3414].
+///
+/// [rfc3414]: https://datatracker.ietf.org/doc/html/rfc3414#section-2.4
+#[derive(Debug)]
+pub(super) struct SecurityParameters<'a> {
+    pub(super) engine_id: &'a [u8],
+    pub(super) engine_boots: i64,
+    pub(super) engine_time: i64,
+    pub(super) user_name: &'a [u8],
+    pub(super) auth_params: &'a [u8],
+    pub(super) priv_params: &'a [u8],
+}
+
+#[derive(Debug)]
+pub(super) enum ScopedPduData<'a> {
+    Plaintext(&'a [u8]),
+    Encrypted(&'a [u8]),
+}
+
+#[derive(Debug)]
+pub(super) struct ScopedPdu {
+    pub(super) context_name: Vec<u8>,
+    pub(super) pdu: Pdu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PduType {
+    Trap,
+    SnmpV2Trap,
+    InformRequest,
+}
+
+impl PduType {
+    pub(super) const fn as_str(self) -> &'static str {
+        match self {
+            Self::Trap => "trap",
+            Self::SnmpV2Trap => "snmpv2_trap",
+            Self::InformRequest => "inform_request",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Pdu {
+    pub(super) pdu_type: PduType,
+    pub(super) request_id: Option<i64>,
+    pub(super) v1: Option<TrapV1>,
+    pub(super) varbinds: Vec<VarBind>,
+}
+
+impl Pdu {
+    /// Returns the OID identifying the notification.
+    ///
+    /// The SNMPv1 trap fields are mapped to the OID following [RFC 3584][rfc3584].
+    ///
+    /// [rfc3584]: https://datatracker.ietf.org/doc/html/rfc3584#section-3.1
+    pub(super) fn trap_oid(&self) -> Option<Vec<u32>> {
+        match &self.v1 {
+            Some(trap) if (0..6).contains(&trap.generic_trap) => {
+                let mut oid = SNMP_TRAPS.to_vec();
+                oid.push(trap.generic_trap as u32 + 1);
+                Some(oid)
+            }
+            Some(trap) => {
+                let mut oid = trap.enterprise.clone();
+                oid.push(0);
+                oid.push(u32::try_from(trap.specific_trap).ok()?);
+                Some(oid)
+            }
+            None => self.find(&SNMP_TRAP_OID).and_then(|value| match value {
+                VarValue::ObjectIdentifier(oid) => Some(oid.clone()),
+                _ => None,
+            }),
+        }
+    }
+
+    /// Returns the time since the agent was (re-)initialized, in hundredths of a second.
+    pub(super) fn uptime(&self) -> Option<u64> {
+        match &self.v1 {
+            Some(trap) => Some(trap.time_stamp),
+            None => self.find(&SYS_UP_TIME).and_then(|value| match value {
+                VarValue::TimeTicks(ticks) => Some(*ticks),
+                _ => None,
+            }),
+        }
+    }
+
+    fn find(&self, oid: &[u32]) -> Option<&VarValue> {
+        self.varbinds
+            .iter()
+            .find(|varbind| varbind.oid == oid)
+            .map(|varbind| &varbind.value)
+    }
+}
+
+/// The fields specific to the SNMPv1 Trap-PDU.
+#[derive(Debug)]
+pub(super) struct TrapV1 {
+    pub(super) enterprise: Vec<u32>,
+    pub(super) agent_address: Ipv4Addr,
+    pub(super) generic_trap: i64,
+    pub(super) specific_trap: i64,
+    pub(super) time_stamp: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct VarBind {
+    pub(super) oid: Vec<u32>,
+    pub(super) value: VarValue,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum VarValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectIdentifier(Vec<u32>),
+    IpAddress(Ipv4Addr),
+    Counter32(u64),
+    Gauge32(u64),
+    TimeTicks(u64),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl VarValue {
+    pub(super) const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::OctetString(_) => "octet_string",
+            Self::Null => "null",
+            Self::ObjectIdentifier(_) => "object_identifier",
+            Self::IpAddress(_) => "ip_address",
+            Self::Counter32(_) => "counter32",
+            Self::Gauge32(_) => "gauge32",
+            Self::TimeTicks(_) => "timeticks",
+            Self::Opaque(_) => "opaque",
+            Self::Counter64(_) => "counter64",
+            Self::NoSuchObject => "no_such_object",
+            Self::NoSuchInstance => "no_such_instance",
+            Self::EndOfMibView => "end_of_mib_view",
+        }
+    }
+}
+
+/// Parses the outer layer of an SNMP message.
+pub(super) fn parse(data: &[u8]) -> Result<Message<'_>, MessageError> {
+    let mut message = Reader::new(data).read_constructed(SEQUENCE)?;
+    let version = match message.read_integer()? {
+        0 => Version::V1,
+        1 => Version::V2c,
+        3 => return parse_v3(data, message).map(Message::V3),
+        version => return UnsupportedVersionSnafu { version }.fail(),
+    };
+
+    let community = message.read_octet_string()?;
+    let pdu_offset = offset(data, message.remaining());
+    let pdu = parse_pdu(&mut message, version)?;
+    Ok(Message::Community(CommunityMessage {
+        version,
+        community,
+        pdu,
+        pdu_offset,
+    }))
+}
+
+/// Parses a plaintext, or decrypted, SNMPv3 scoped PDU.
+///
+/// Any data following the scoped PDU is ignored, as it is padding added by the encryption.
+pub(super) fn parse_scoped_pdu(data: &[u8]) -> Result<ScopedPdu, MessageError> {
+    let mut scoped_pdu = Reader::new(data).read_constructed(SEQUENCE)?;
+    let _context_engine_id = scoped_pdu.read_octet_string()?;
+    let context_name = scoped_pdu.read_octet_string()?.to_vec();
+    let pdu = parse_pdu(&mut scoped_pdu, Version::V3)?;
+    Ok(ScopedPdu { context_name, pdu })
+}
+
+fn parse_v3<'a>(data: &'a [u8], mut message: Reader<'a>) -> Result<V3Message<'a>, MessageError> {
+    let mut global_data = message.read_constructed(SEQUENCE)?;
+    let _msg_id = global_data.read_integer()?;
+    let _max_size = global_data.read_integer()?;
+    let flags = match global_data.read_octet_string()? {
+        [flags] => *flags,
+        _ => return Err(MessageError::InvalidFlags),
+    };
+    let model = global_data.read_integer()?;
+    if model != USM_SECURITY_MODEL {
+        return UnsupportedSecurityModelSnafu { model }.fail();
+    }
+
+    let mut security = Reader::new(message.read_octet_string()?).read_constructed(SEQUENCE)?;
+    let security = SecurityParameters {
+        engine_id: security.read_octet_string()?,
+        engine_boots: security.read_integer()?,
+        engine_time: security.read_integer()?,
+        user_name: security.read_octet_string()?,
+        auth_params: security.read_octet_string()?,
+        priv_params: security.read_octet_string()?,
+    };
+    let auth_offset = offset(data, security.auth_params);
+
+    let scoped_pdu = if message.peek_tag()? == OCTET_STRING {
+        ScopedPduData::Encrypted(message.read_octet_string()?)
+    } else {
+        ScopedPduData::Plaintext(message.remaining())
+    };
+
+    Ok(V3Message {
+        flags,
+        security,
+        auth_offset,
+        scoped_pdu,
+    })
+}
+
+fn parse_pdu(reader: &mut Reader<'_>, version: Version) -> Result<Pdu, MessageError> {
+    let (tag, content) = reader.read_any()?;
+    let mut pdu = Reader::new(content);
+    match (tag, version) {
+        (TRAP_V1, Version::V1) => {
+            let enterprise = pdu.read_oid()?;
+            let agent_address = parse_ip_address(pdu.read(IP_ADDRESS)?)?;
+            let generic_trap = pdu.read_integer()?;
+            let specific_trap = pdu.read_integer()?;
+            let time_stamp = ber::decode_unsigned(pdu.read(TIME_TICKS)?)?;
+            Ok(Pdu {
+                pdu_type: PduType::Trap,
+                request_id: None,
+                v1: Some(TrapV1 {
+                    enterprise,
+                    agent_address,
+                    generic_trap,
+                    specific_trap,
+                    time_stamp,
+                }),
+                varbinds: parse_varbinds(&mut pdu)?,
+            })
+        }
+        (TRAP_V2 | INFORM_REQUEST, Version::V2c | Version::V3) => {
+            let request_id = pdu.read_integer()?;
+            let _error_status = pdu.read_integer()?;
+            let _error_index = pdu.read_integer()?;
+            Ok(Pdu {
+                pdu_type: if tag == TRAP_V2 {
+                    PduType::SnmpV2Trap
+                } else {
+                    PduType::InformRequest
+                },
+                request_id: Some(request_id),
+                v1: None,
+                varbinds: parse_varbinds(&mut pdu)?,
+            })
+        }
+        (tag, _) => UnsupportedPduSnafu { tag }.fail(),
+    }
+}
+
+fn parse_varbinds(pdu: &mut Reader<'_>) -> Result<Vec<VarBind>, MessageError> {
+    let mut list = pdu.read_constructed(SEQUENCE)?;
+    let mut varbinds = Vec::new();
+    while !list.is_empty() {
+        let mut varbind = list.read_constructed(SEQUENCE)?;
+        let oid = varbind.read_oid()?;
+        let (tag, content) = varbind.read_any()?;
+        let value = match tag {
+            ber::INTEGER => VarValue::Integer(ber::decode_integer(content)?),
+            ber::OCTET_STRING => VarValue::OctetString(content.to_vec()),
+            ber::NULL => VarValue::Null,
+            ber::OBJECT_IDENTIFIER => VarValue::ObjectIdentifier(ber::decode_oid(content)?),
+            IP_ADDRESS => VarValue::IpAddress(parse_ip_address(content)?),
+            COUNTER32 => VarValue::Counter32(ber::decode_unsigned(content)?),
+            GAUGE32 => VarValue::Gauge32(ber::decode_unsigned(content)?),
+            TIME_TICKS => VarValue::TimeTicks(ber::decode_unsigned(content)?),
+            OPAQUE => VarValue::Opaque(content.to_vec()),
+            COUNTER64 => VarValue::Counter64(ber::decode_unsigned(content)?),
+            NO_SUCH_OBJECT => VarValue::NoSuchObject,
+            NO_SUCH_INSTANCE => VarValue::NoSuchInstance,
+            END_OF_MIB_VIEW => VarValue::EndOfMibView,
+            tag => {
+                return Err(BerError::UnexpectedTag {
+                    expected: ber::NULL,
+                    actual: tag,
+                }
+                .into());
+            }
+        };
+        varbinds.push(VarBind { oid, value });
+    }
+    Ok(varbinds)
+}
+
+fn parse_ip_address(content: &[u8]) -> Result<Ipv4Addr, MessageError> {
+    <[u8; 4]>::try_from(content)
+        .map(Ipv4Addr::from)
+        .map_err(|_| MessageError::InvalidAddress)
+}
+
+/// Returns the offset of a slice borrowed from the message.
+fn offset(data: &[u8], part: &[u8]) -> usize {
+    part.as_ptr() as usize - data.as_ptr() as usize
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::sources::snmp_trap::ber::{INTEGER, encode, encode_integer, encode_oid};
+
+    const LINK_DOWN: [u32; 10] = [1, 3, 6, 1, 6, 3, 1, 1, 5, 3];
+    const IF_INDEX: [u32; 11] = [1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 2];
+
+    pub(in crate::sources::snmp_trap) fn varbind(oid: &[u32], value: Vec<u8>) -> Vec<u8> {
+        encode(SEQUENCE, &[encode_oid(oid), value].concat())
+    }
+
+    /// Encodes a `linkDown` notification for interface 2.
+    pub(in crate::sources::snmp_trap) fn link_down_pdu(tag: u8, request_id: i64) -> Vec<u8> {
+        let varbinds = [
+            varbind(&SYS_UP_TIME, encode_integer(TIME_TICKS, 123_456)),
+            varbind(&SNMP_TRAP_OID, encode_oid(&LINK_DOWN)),
+            varbind(&IF_INDEX, encode_integer(INTEGER, 2)),
+        ];
+        let content = [
+            encode_integer(INTEGER, request_id),
+            encode_integer(INTEGER, 0),
+            encode_integer(INTEGER, 0),
+            encode(SEQUENCE, &varbinds.concat()),
+        ];
+        encode(tag, &content.concat())
+    }
+
+    pub(in crate::sources::snmp_trap) fn community_message(
+        version: i64,
+        community: &str,
+        pdu: Vec<u8>,
+    ) -> Vec<u8> {
+        let content = [
+            encode_integer(INTEGER, version),
+            encode(OCTET_STRING, community.as_bytes()),
+            pdu,
+        ];
+        encode(SEQUENCE, &content.concat())
+    }
+
+    fn parse_community(data: &[u8]) -> CommunityMessage<'_> {
+        match parse(data).unwrap() {
+            Message::Community(message) => message,
+            Message::V3(_) => panic!("expected a community message"),
+        }
+    }
+
+    #[test]
+    fn parses_v2c_trap() {
+        let data = community_message(1, "public", link_down_pdu(TRAP_V2, 42));
+        let message = parse_community(&data);
+
+        assert_eq!(Version::V2c, message.version);
+        assert_eq!(b"public", message.community);
+        assert_eq!(PduType::SnmpV2Trap, message.pdu.pdu_type);
+        assert_eq!(Some(42), message.pdu.request_id);
+        assert_eq!(Some(LINK_DOWN.to_vec()), message.pdu.trap_oid());
+        assert_eq!(Some(123_456), message.pdu.uptime());
+        assert_eq!(
+            VarBind {
+                oid: IF_INDEX.to_vec(),
+                value: VarValue::Integer(2)
+            },
+            message.pdu.varbinds[2]
+        );
+        assert_eq!(None, message.inform_response(&data));
+    }
+
+    #[test]
+    fn parses_v1_trap() {
+        let enterprise = [1, 3, 6, 1, 4, 1, 8072, 4];
+        let content = [
+            encode_oid(&enterprise),
+            encode(IP_ADDRESS, &[192, 0, 2, 1]),
+            encode_integer(INTEGER, 6),
+            encode_integer(INTEGER, 17),
+            encode_integer(TIME_TICKS, 500),
+            encode(
+                SEQUENCE,
+                &varbind(
+                    &[1, 3, 6, 1, 4, 1, 8072, 4, 1],
+                    encode(OCTET_STRING, b"hello"),
+                ),
+            ),
+        ];
+        let data = community_message(0, "public", encode(TRAP_V1, &content.concat()));
+        let message = parse_community(&data);
+
+        assert_eq!(Version::V1, message.version);
+        assert_eq!(PduType::Trap, message.pdu.pdu_type);
+        let trap = message.pdu.v1.as_ref().unwrap();
+        assert_eq!(Ipv4Addr::new(192, 0, 2, 1), trap.agent_address);
+        assert_eq!(
+            Some(vec![1, 3, 6, 1, 4, 1, 8072, 4, 0, 17]),
+            message.pdu.trap_oid()
+        );
+        assert_eq!(Some(500), message.pdu.uptime());
+        assert_eq!(
+            VarValue::OctetString(b"hello".to_vec()),
+            message.pdu.varbinds[0].value
+        );
+    }
+
+    #[test]
+    fn maps_v1_generic_traps() {
+        let pdu = Pdu {
+            pdu_type: PduType::Trap,
+            request_id: None,
+            v1: Some(TrapV1 {
+                enterprise: vec![1, 3, 6, 1, 4, 1, 8072],
+                agent_address: Ipv4Addr::LOCALHOST,
+                generic_trap: 2,
+                specific_trap: 0,
+                time_stamp: 0,
+            }),
+            varbinds: Vec::new(),
+        };
+        assert_eq!(Some(LINK_DOWN.to_vec()), pdu.trap_oid());
+    }
+
+    #[test]
+    fn builds_inform_response() {
+        let data = community_message(1, "public", link_down_pdu(INFORM_REQUEST, 7));
+        let message = parse_community(&data);
+        assert_eq!(PduType::InformRequest, message.pdu.pdu_type);
+
+        let response = message.inform_response(&data).unwrap();
+        assert_eq!(
+            community_message(1, "public", link_down_pdu(RESPONSE, 7)),
+            response
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_messages() {
+        let data = community_message(2, "public", link_down_pdu(TRAP_V2, 1));
+        assert!(matches!(
+            parse(&data),
+            Err(MessageError::UnsupportedVersion { version: 2 })
+        ));
+
+        // A GetRequest.
+        let data = community_message(1, "public", link_down_pdu(0xa0, 1));
+        assert!(matches!(
+            parse(&data),
+            Err(MessageError::UnsupportedPdu { tag: 0xa0 })
+        ));
+
+        // SNMPv2 notifications can't be sent using SNMPv1.
+        let data = community_message(0, "public", link_down_pdu(TRAP_V2, 1));
+        assert!(matches!(
+            parse(&data),
+            Err(MessageError::UnsupportedPdu { tag: TRAP_V2 })
+        ));
+
+        let data = community_message(1, "public", link_down_pdu(TRAP_V2, 1));
+        assert!(matches!(
+            parse(&data[..data.len() - 1]),
+            Err(MessageError::Encoding { .. })
+        ));
+    }
+}
@@ -0,0 +1,508 @@
+//! Loading of MIB modules, to resolve OIDs to the names of the objects they identify.
+//!
+//! Only the OID assignments of the modules are extracted, from `OBJECT IDENTIFIER` values and
+//! macros such as `OBJECT-TYPE` and `NOTIFICATION-TYPE`. The other definitions are skipped, so
+//! modules don't need to be valid, or to have their imports available, to be used.
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use snafu::{ResultExt, Snafu};
+
+/// The macros assigning an OID to their name, as `name MACRO ... ::= { parent number }`.
+const MACROS: &[&str] = &[
+    "AGENT-CAPABILITIES",
+    "MODULE-COMPLIANCE",
+    "MODULE-IDENTITY",
+    "NOTIFICATION-GROUP",
+    "NOTIFICATION-TYPE",
+    "OBJECT-GROUP",
+    "OBJECT-IDENTITY",
+    "OBJECT-TYPE",
+    "TRAP-TYPE",
+];
+
+/// The well-known objects, which are resolved without loading any module and which modules are
+/// commonly defined under.
+const BUILTIN: &[(&str, &str, &[u32])] = &[
+    ("SNMPv2-SMI", "zeroDotZero", &[0, 0]),
+    ("SNMPv2-SMI", "iso", &[1]),
+    ("SNMPv2-SMI", "org", &[1, 3]),
+    ("SNMPv2-SMI", "dod", &[1, 3, 6]),
+    ("SNMPv2-SMI", "internet", &[1, 3, 6, 1]),
+    ("SNMPv2-SMI", "directory", &[1, 3, 6, 1, 1]),
+    ("SNMPv2-SMI", "mgmt", &[1, 3, 6, 1, 2]),
+    ("SNMPv2-SMI", "mib-2", &[1, 3, 6, 1, 2, 1]),
+    ("SNMPv2-SMI", "transmission", &[1, 3, 6, 1, 2, 1, 10]),
+    ("SNMPv2-SMI", "experimental", &[1, 3, 6, 1, 3]),
+    ("SNMPv2-SMI", "private", &[1, 3, 6, 1, 4]),
+    ("SNMPv2-SMI", "enterprises", &[1, 3, 6, 1, 4, 1]),
+    ("SNMPv2-SMI", "security", &[1, 3, 6, 1, 5]),
+    ("SNMPv2-SMI", "snmpV2", &[1, 3, 6, 1, 6]),
+    ("SNMPv2-SMI", "snmpDomains", &[1, 3, 6, 1, 6, 1]),
+    ("SNMPv2-SMI", "snmpProxys", &[1, 3, 6, 1, 6, 2]),
+    ("SNMPv2-SMI", "snmpModules", &[1, 3, 6, 1, 6, 3]),
+    ("SNMPv2-MIB", "system", &[1, 3, 6, 1, 2, 1, 1]),
+    ("SNMPv2-MIB", "sysDescr", &[1, 3, 6, 1, 2, 1, 1, 1]),
+    ("SNMPv2-MIB", "sysObjectID", &[1, 3, 6, 1, 2, 1, 1, 2]),
+    ("SNMPv2-MIB", "sysUpTime", &[1, 3, 6, 1, 2, 1, 1, 3]),
+    ("SNMPv2-MIB", "sysContact", &[1, 3, 6, 1, 2, 1, 1, 4]),
+    ("SNMPv2-MIB", "sysName", &[1, 3, 6, 1, 2, 1, 1, 5]),
+    ("SNMPv2-MIB", "sysLocation", &[1, 3, 6, 1, 2, 1, 1, 6]),
+    ("SNMPv2-MIB", "snmpMIB", &[1, 3, 6, 1, 6, 3, 1]),
+    ("SNMPv2-MIB", "snmpMIBObjects", &[1, 3, 6, 1, 6, 3, 1, 1]),
+    ("SNMPv2-MIB", "snmpTrap", &[1, 3, 6, 1, 6, 3, 1, 1, 4]),
+    ("SNMPv2-MIB", "snmpTrapOID", &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1]),
+    (
+        "SNMPv2-MIB",
+        "snmpTrapEnterprise",
+        &[1, 3, 6, 1, 6, 3, 1, 1, 4, 3],
+    ),
+    ("SNMPv2-MIB", "snmpTraps", &[1, 3, 6, 1, 6, 3, 1, 1, 5]),
+    ("SNMPv2-MIB", "coldStart", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 1]),
+    ("SNMPv2-MIB", "warmStart", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 2]),
+    ("IF-MIB", "linkDown", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 3]),
+    ("IF-MIB", "linkUp", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 4]),
+    (
+        "SNMPv2-MIB",
+        "authenticationFailure",
+        &[1, 3, 6, 1, 6, 3, 1, 1, 5, 5],
+    ),
+    (
+        "RFC1213-MIB",
+        "egpNeighborLoss",
+        &[1, 3, 6, 1, 6, 3, 1, 1, 5, 6],
+    ),
+    ("IF-MIB", "ifIndex", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 1]),
+    ("IF-MIB", "ifDescr", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2]),
+    ("IF-MIB", "ifAdminStatus", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 7]),
+    ("IF-MIB", "ifOperStatus", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8]),
+    (
+        "SNMP-COMMUNITY-MIB",
+        "snmpTrapAddress",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 3],
+    ),
+    (
+        "SNMP-COMMUNITY-MIB",
+        "snmpTrapCommunity",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 4],
+    ),
+];
+
+#[derive(Debug, Snafu)]
+pub(super) enum MibError {
+    #[snafu(display("could not read MIB path {}: {source}", path.display()))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// An OID assignment, relative to its parent.
+#[derive(Debug)]
+struct Definition<'a> {
+    module: &'a str,
+    name: &'a str,
+
+    /// The name of the parent, or `None` if the arcs are the complete OID.
+    parent: Option<&'a str>,
+    arcs: Vec<u32>,
+}
+
+/// The names of the OIDs, from the built-in objects and the loaded modules.
+#[derive(Debug)]
+pub(super) struct Mibs {
+    names: BTreeMap<Vec<u32>, String>,
+}
+
+impl Mibs {
+    /// Loads the modules from the files, or all the files in the directories, at the paths.
+    pub(super) fn load(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let mut entries = std::fs::read_dir(path)
+                    .context(ReadSnafu { path })?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ReadSnafu { path })?;
+                entries.retain(|entry| entry.is_file());
+                entries.sort();
+                files.extend(entries);
+            } else {
+                files.push(path.clone());
+            }
+        }
+
+        let sources = files
+            .iter()
+            .map(|path| read(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (mibs, unresolved) = Self::parse(sources.iter().map(String::as_str));
+        if unresolved > 0 {
+            warn!(
+                message = "Some MIB objects could not be resolved, as their parent is not defined.",
+                count = unresolved,
+            );
+        }
+        Ok(mibs)
+    }
+
+    /// Resolves the OIDs defined by the modules, returning the number of definitions which
+    /// couldn't be resolved.
+    fn parse<'a>(sources: impl Iterator<Item = &'a str>) -> (Self, usize) {
+        let mut oids = HashMap::new();
+        let mut names = BTreeMap::new();
+        for (module, name, oid) in BUILTIN {
+            oids.insert(*name, oid.to_vec());
+            names.insert(oid.to_vec(), format!("{module}::{name}"));
+        }
+
+        let mut pending = sources
+            .flat_map(|source| definitions(&tokenize(source)))
+            .collect::<Vec<_>>();
+
+        // Definitions may come before their parent, possibly in another module, so they are
+        // resolved until no more progress is made.
+        loop {
+            let count = pending.len();
+            pending.retain(|definition| {
+                let mut oid = match definition.parent {
+                    None => Vec::new(),
+                    Some(parent) => match oids.get(parent) {
+                        Some(oid) => Vec::clone(oid),
+                        None => return true,
+                    },
+                };
+                oid.extend(&definition.arcs);
+                names
+                    .entry(oid.clone())
+                    .or_insert_with(|| format!("{}::{}", definition.module, definition.name));
+                oids.entry(definition.name).or_insert(oid);
+                false
+            });
+            if pending.len() == count {
+                break;
+            }
+        }
+
+        (Self { names }, pending.len())
+    }
+
+    /// Returns the name of an OID, as the name of its closest known ancestor followed by the
+    /// remaining arcs, such as `IF-MIB::ifIndex.2`.
+    pub(super) fn resolve(&self, oid: &[u32]) -> Option<String> {
+        (1..=oid.len()).rev().find_map(|length| {
+            self.names.get(&oid[..length]).map(|name| {
+                oid[length..]
+                    .iter()
+                    .fold(name.clone(), |name, arc| format!("{name}.{arc}"))
+            })
+        })
+    }
+}
+
+impl Default for Mibs {
+    fn default() -> Self {
+        Self::parse(std::iter::empty()).0
+    }
+}
+
+fn read(path: &Path) -> Result<String, MibError> {
+    std::fs::read(path)
+        .map(|data| String::from_utf8_lossy(&data).into_owned())
+        .context(ReadSnafu { path })
+}
+
+/// Splits a module into its words and symbols, skipping comments and strings.
+fn tokenize(source: &str) -> Vec<&str> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while let Some(&byte) = bytes.get(index) {
+        let rest = &bytes[index..];
+        if rest.starts_with(b"--") {
+            // Comments end at the end of the line, or at the next `--`.
+            index += 2;
+            while index < bytes.len() && bytes[index] != b'\n' {
+                if bytes[index..].starts_with(b"--") {
+                    index += 2;
+                    break;
+                }
+                index += 1;
+            }
+        } else if byte == b'"' || byte == b'\'' {
+            // Strings, and binary or hexadecimal values such as `'00'H`.
+            index += 1;
+            while index < bytes.len() && bytes[index] != byte {
+                index += 1;
+            }
+            index += 1;
+            if byte == b'\'' && bytes.get(index).is_some_and(u8::is_ascii_alphabetic) {
+                index += 1;
+            }
+        } else if rest.starts_with(b"::=") {
+            tokens.push("::=");
+            index += 3;
+        } else if byte.is_ascii_alphanumeric() {
+            let start = index;
+            while let Some(&byte) = bytes.get(index) {
+                let word = byte.is_ascii_alphanumeric()
+                    || byte == b'_'
+                    || (byte == b'-' && !bytes[index..].starts_with(b"--"));
+                if !word {
+                    break;
+                }
+                index += 1;
+            }
+            tokens.push(&source[start..index]);
+        } else {
+            if byte.is_ascii_punctuation() {
+                tokens.push(&source[index..index + 1]);
+            }
+            index += 1;
+        }
+    }
+    tokens
+}
+
+/// Extracts the OID assignments from the tokens of one or more modules.
+fn definitions<'a>(tokens: &[&'a str]) -> Vec<Definition<'a>> {
+    let mut definitions = Vec::new();
+    let mut module = "";
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        index += 1;
+        match token {
+            // `MODULE-NAME DEFINITIONS ::= BEGIN`
+            "DEFINITIONS" if index > 1 => module = tokens[index - 2],
+            "IMPORTS" => {
+                while index < tokens.len() && tokens[index] != ";" {
+                    index += 1;
+                }
+            }
+            name if name.starts_with(|c: char| c.is_ascii_lowercase()) => {
+                let rest = &tokens[index..];
+                let value = match rest {
+                    ["OBJECT", "IDENTIFIER", "::=", value @ ..] => parse_value(value),
+                    [kind, rest @ ..] if MACROS.contains(kind) => {
+                        // Skip the clauses of the macro, up to its value.
+                        let Some(end) = rest.iter().position(|token| *token == "::=") else {
+                            continue;
+                        };
+                        if *kind == "TRAP-TYPE" {
+                            parse_trap_type(&rest[..end], &rest[end + 1..])
+                        } else {
+                            parse_value(&rest[end + 1..])
+                        }
+                    }
+                    _ => None,
+                };
+                if let Some((parent, arcs)) = value {
+                    definitions.push(Definition {
+                        module,
+                        name,
+                        parent,
+                        arcs,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    definitions
+}
+
+/// Parses an OID value, such as `{ mib-2 1 }` or `{ iso(1) org(3) dod(6) }`, returning the
+/// parent name and the arcs relative to it.
+fn parse_value<'a>(tokens: &[&'a str]) -> Option<(Option<&'a str>, Vec<u32>)> {
+    let ["{", tokens @ ..] = tokens else {
+        return None;
+    };
+    let end = tokens.iter().position(|token| *token == "}")?;
+    let mut components = &tokens[..end];
+
+    let mut parent = None;
+    let mut arcs = Vec::new();
+    if let [name, rest @ ..] = components
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && rest.first() != Some(&"(")
+    {
+        parent = Some(*name);
+        components = rest;
+    }
+    while !components.is_empty() {
+        // Components are either a number, or a name followed by a number, such as `org(3)`.
+        let number = match components {
+            [_, "(", number, ")", rest @ ..] => {
+                components = rest;
+                number
+            }
+            [number, rest @ ..] => {
+                components = rest;
+                number
+            }
+            [] => unreachable!(),
+        };
+        arcs.push(number.parse().ok()?);
+    }
+
+    (parent.is_some() || !arcs.is_empty()).then_some((parent, arcs))
+}
+
+/// Parses the value of an SNMPv1 `TRAP-TYPE` macro, which is the specific trap number relative to
+/// the `ENTERPRISE` clause.
+fn parse_trap_type<'a>(
+    clauses: &[&'a str],
+    value: &[&'a str],
+) -> Option<(Option<&'a str>, Vec<u32>)> {
+    let enterprise = clauses
+        .iter()
+        .position(|token| *token == "ENTERPRISE")
+        .and_then(|index| clauses.get(index + 1))?;
+    let specific_trap = value.first()?.parse().ok()?;
+    Some((Some(*enterprise), vec![0, specific_trap]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"
+NET-SNMP-EXAMPLES-MIB DEFINITIONS ::= BEGIN
+
+-- A trimmed down version of the Net-SNMP example notifications.
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, NOTIFICATION-TYPE, Integer32 FROM SNMPv2-SMI
+    netSnmp FROM NET-SNMP-MIB;
+
+netSnmpExamples MODULE-IDENTITY
+    LAST-UPDATED "200406150000Z"
+    ORGANIZATION "www.net-snmp.org"
+    CONTACT-INFO "postal: Wes Hardaker -- ::= { iso 99 }"
+    DESCRIPTION  "Example MIB objects for agent module example implementations"
+    ::= { netSnmp 2 }
+
+netSnmpExampleNotifications OBJECT IDENTIFIER ::= { netSnmpExamples 3 }
+netSnmpExampleNotificationPrefix  OBJECT IDENTIFIER
+                                  ::= { netSnmpExampleNotifications 0 }
+
+netSnmpExampleHeartbeatRate OBJECT-TYPE
+    SYNTAX      Integer32
+    MAX-ACCESS  accessible-for-notify
+    STATUS      current
+    DESCRIPTION "A simple integer object."
+::= { netSnmpExampleNotifications 2 }
+
+netSnmpExampleHeartbeatNotification NOTIFICATION-TYPE
+    OBJECTS     { netSnmpExampleHeartbeatRate }
+    STATUS      current
+    DESCRIPTION "An example notification."
+::= { netSnmpExampleNotificationPrefix 1 }
+
+exampleTrap TRAP-TYPE
+    ENTERPRISE  netSnmpExamples -- the enterprise
+    VARIABLES   { netSnmpExampleHeartbeatRate }
+    ::= 17
+
+END
+"#;
+
+    const NET_SNMP_MIB: &str = "
+NET-SNMP-MIB DEFINITIONS ::= BEGIN
+netSnmp OBJECT IDENTIFIER ::= { enterprises 8072 }
+END
+";
+
+    #[test]
+    fn resolves_builtin_objects() {
+        let mibs = Mibs::default();
+        assert_eq!(
+            Some("IF-MIB::linkDown".to_owned()),
+            mibs.resolve(&[1, 3, 6, 1, 6, 3, 1, 1, 5, 3])
+        );
+        assert_eq!(
+            Some("SNMPv2-MIB::sysUpTime.0".to_owned()),
+            mibs.resolve(&[1, 3, 6, 1, 2, 1, 1, 3, 0])
+        );
+        assert_eq!(
+            Some("SNMPv2-SMI::enterprises.8072.2.3".to_owned()),
+            mibs.resolve(&[1, 3, 6, 1, 4, 1, 8072, 2, 3])
+        );
+        assert_eq!(None, mibs.resolve(&[2, 999]));
+    }
+
+    #[test]
+    fn resolves_module_objects() {
+        // The definitions are resolved regardless of the order of the modules.
+        let (mibs, unresolved) = Mibs::parse([MODULE, NET_SNMP_MIB].into_iter());
+        assert_eq!(0, unresolved);
+
+        let resolve = |oid: &[u32]| mibs.resolve(oid).unwrap();
+        assert_eq!("NET-SNMP-MIB::netSnmp", resolve(&[1, 3, 6, 1, 4, 1, 8072]));
+        assert_eq!(
+            "NET-SNMP-EXAMPLES-MIB::netSnmpExampleHeartbeatNotification",
+            resolve(&[1, 3, 6, 1, 4, 1, 8072, 2, 3, 0, 1])
+        );
+        assert_eq!(
+            "NET-SNMP-EXAMPLES-MIB::netSnmpExampleHeartbeatRate.0",
+            resolve(&[1, 3, 6, 1, 4, 1, 8072, 2, 3, 2, 0])
+        );
+        assert_eq!(
+            "NET-SNMP-EXAMPLES-MIB::exampleTrap",
+            resolve(&[1, 3, 6, 1, 4, 1, 8072, 2, 0, 17])
+        );
+    }
+
+    #[test]
+    fn reports_unresolved_objects() {
+        let (mibs, unresolved) = Mibs::parse([MODULE].into_iter());
+        assert_eq!(6, unresolved);
+        assert_eq!(
+            Some("SNMPv2-SMI::enterprises.8072".to_owned()),
+            mibs.resolve(&[1, 3, 6, 1, 4, 1, 8072])
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_strings() {
+        assert_eq!(
+            vec!["a", "::=", "{", "b-c", "}", "e", "g"],
+            tokenize("a ::= {b-c} -- comment\n\"string -- ::=\" 'ff'H e -- f -- g")
+        );
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(
+            Some((None, vec![1, 3, 6, 1])),
+            parse_value(&[
+                "{", "iso", "(", "1", ")", "org", "(", "3", ")", "6", "1", "}"
+            ])
+        );
+        assert_eq!(
+            Some((Some("mib-2"), vec![31])),
+            parse_value(&["{", "mib-2", "31", "}"])
+        );
+        assert_eq!(None, parse_value(&["{", "mib-2", "foo", "}"]));
+        assert_eq!(None, parse_value(&["mib-2"]));
+    }
+
+    #[test]
+    fn loads_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("NET-SNMP-MIB.txt"), NET_SNMP_MIB).unwrap();
+        std::fs::write(dir.path().join("NET-SNMP-EXAMPLES-MIB.txt"), MODULE).unwrap();
+
+        let mibs = Mibs::load(&[dir.path().to_owned()]).unwrap();
+        assert_eq!(
+            Some("NET-SNMP-EXAMPLES-MIB::exampleTrap".to_owned()),
+            mibs.resolve(&[1, 3, 6, 1, 4, 1, 8072, 2, 0, 17])
+        );
+
+        assert!(Mibs::load(&[dir.path().join("missing")]).is_err());
+    }
+}
@@ -0,0 +1,675 @@
+//! The `snmp_trap` source receives SNMP notifications, that is SNMPv1 traps, SNMPv2c and SNMPv3
+//! traps, and inform requests, and emits them as structured logs.
+use std::{net::SocketAddr, path::PathBuf};
+
+use chrono::Utc;
+use listenfd::ListenFd;
+use snafu::{ResultExt, Snafu};
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LegacyKey, LogNamespace, log_schema},
+    configurable::configurable_component,
+    internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol},
+    lookup::{event_path, lookup_v2::OptionalValuePath, owned_value_path, path},
+    schema::Definition,
+    sensitive_string::SensitiveString,
+};
+use vrl::value::{Kind, ObjectMap, Value, kind::Collection};
+
+use self::{
+    message::{Message, MessageError, Pdu, VarValue, Version},
+    mib::Mibs,
+    usm::{Usm, UsmError},
+};
+use crate::{
+    SourceSender,
+    config::{DataType, Resource, SourceConfig, SourceContext, SourceOutput},
+    event::{Event, LogEvent},
+    internal_events::{
+        SnmpTrapMessageError, SocketBindError, SocketEventsReceived, SocketMode,
+        SocketReceiveError, StreamClosedError,
+    },
+    net,
+    shutdown::ShutdownSignal,
+    sources::util::net::{SocketListenAddr, try_bind_udp_socket},
+};
+
+mod ber;
+mod message;
+mod mib;
+mod usm;
+
+pub use self::usm::{AuthProtocol, PrivacyProtocol, UserAuthConfig, UserConfig, UserPrivacyConfig};
+
+/// The maximum size of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Configuration for the `snmp_trap` source.
+#[configurable_component(source(
+    "snmp_trap",
+    "Receive SNMP traps and inform requests from network devices."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpTrapConfig {
+    /// The socket address to listen for notifications on.
+    ///
+    /// The standard port for SNMP notifications is `162`, which is a privileged port on most
+    /// systems.
+    #[serde(default = "default_address")]
+    #[configurable(metadata(docs::examples = "0.0.0.0:162"))]
+    #[configurable(metadata(docs::examples = "systemd"))]
+    #[configurable(metadata(docs::examples = "systemd#3"))]
+    address: SocketListenAddr,
+
+    /// The size of the receive buffer used for the listening socket.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    receive_buffer_bytes: Option<usize>,
+
+    /// The communities accepted in SNMPv1 and SNMPv2c notifications.
+    ///
+    /// If empty, SNMPv1 and SNMPv2c notifications are accepted regardless of their community.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "public"))]
+    communities: Vec<SensitiveString>,
+
+    /// The users accepted in SNMPv3 notifications.
+    ///
+    /// SNMPv3 notifications are rejected unless they are sent by one of the users, with the
+    /// security level of the user.
+    #[serde(default)]
+    users: Vec<UserConfig>,
+
+    /// Paths to MIB modules, or to directories of MIB modules, used to resolve OIDs to names.
+    ///
+    /// OIDs are named after their closest ancestor defined in the modules, such as
+    /// `IF-MIB::ifIndex.2`. The common objects of `SNMPv2-SMI`, `SNMPv2-MIB` and `IF-MIB` are
+    /// resolved without loading any module.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/usr/share/snmp/mibs"))]
+    mib_paths: Vec<PathBuf>,
+
+    /// Overrides the name of the log field used to add the peer host to each event.
+    ///
+    /// The value is the IP address of the host that sent the notification, which is not
+    /// necessarily the agent, if the notification was forwarded.
+    ///
+    /// By default, the [global `log_schema.host_key` option][global_host_key] is used.
+    ///
+    /// Set to `""` to suppress this key.
+    ///
+    /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+    host_key: Option<OptionalValuePath>,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+fn default_address() -> SocketListenAddr {
+    SocketListenAddr::SocketAddr("0.0.0.0:162".parse().unwrap())
+}
+
+impl Default for SnmpTrapConfig {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            receive_buffer_bytes: None,
+            communities: Vec::new(),
+            users: Vec::new(),
+            mib_paths: Vec::new(),
+            host_key: None,
+            log_namespace: None,
+        }
+    }
+}
+
+impl_generate_config_from_default!(SnmpTrapConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_trap")]
+impl SourceConfig for SnmpTrapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+        let processor = Processor {
+            communities: self.communities.clone(),
+            usm: Usm::new(&self.users)?,
+            mibs: Mibs::load(&self.mib_paths)?,
+            host_key: self
+                .host_key
+                .clone()
+                .unwrap_or_else(|| log_schema().host_key().cloned().into()),
+            log_namespace,
+        };
+
+        Ok(Box::pin(run(
+            self.address,
+            self.receive_buffer_bytes,
+            processor,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().cloned().into())
+            .path
+            .map(LegacyKey::Overwrite);
+        let schema_definition = Definition::new_with_default_metadata(
+            Kind::object(Collection::from_unknown(Kind::any())),
+            [log_namespace],
+        )
+        .with_standard_vector_source_metadata()
+        .with_source_metadata(
+            Self::NAME,
+            host_key,
+            &owned_value_path!("host"),
+            Kind::bytes(),
+            Some("host"),
+        );
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.address.as_udp_resource()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum ProcessError {
+    #[snafu(display("invalid message: {source}"))]
+    InvalidMessage { source: MessageError },
+
+    #[snafu(display("unknown community"))]
+    UnknownCommunity,
+
+    #[snafu(display("{source}"))]
+    Security { source: UsmError },
+}
+
+/// Turns the received messages into events.
+#[derive(Debug)]
+struct Processor {
+    communities: Vec<SensitiveString>,
+    usm: Usm,
+    mibs: Mibs,
+    host_key: OptionalValuePath,
+    log_namespace: LogNamespace,
+}
+
+impl Processor {
+    /// Processes a message, returning its event and the response to send back, if any.
+    fn process(
+        &self,
+        data: &[u8],
+        peer_addr: SocketAddr,
+    ) -> Result<(LogEvent, Option<Vec<u8>>), ProcessError> {
+        let mut log = LogEvent::default();
+        let (pdu, response) = match message::parse(data).context(InvalidMessageSnafu)? {
+            Message::Community(message) => {
+                if !self.communities.is_empty()
+                    && !self
+                        .communities
+                        .iter()
+                        .any(|community| community.inner().as_bytes() == message.community)
+                {
+                    return UnknownCommunitySnafu.fail();
+                }
+                log.insert(event_path!("version"), message.version.as_str());
+                let response = message.inform_response(data);
+                (message.pdu, response)
+            }
+            Message::V3(message) => {
+                let scoped_pdu = self.usm.process(data, &message).context(SecuritySnafu)?;
+                log.insert(event_path!("version"), Version::V3.as_str());
+                log.insert(
+                    event_path!("user"),
+                    String::from_utf8_lossy(message.security.user_name).into_owned(),
+                );
+                log.insert(
+                    event_path!("context_name"),
+                    String::from_utf8_lossy(&scoped_pdu.context_name).into_owned(),
+                );
+                // Acknowledging SNMPv3 inform requests requires the receiver to act as the
+                // authoritative engine, which isn't supported.
+                (scoped_pdu.pdu, None)
+            }
+        };
+
+        self.insert_pdu(&mut log, pdu);
+
+        self.log_namespace.insert_standard_vector_source_metadata(
+            &mut log,
+            SnmpTrapConfig::NAME,
+            Utc::now(),
+        );
+        self.log_namespace.insert_source_metadata(
+            SnmpTrapConfig::NAME,
+            &mut log,
+            self.host_key.path.as_ref().map(LegacyKey::Overwrite),
+            path!("host"),
+            peer_addr.ip().to_string(),
+        );
+
+        Ok((log, response))
+    }
+
+    fn insert_pdu(&self, log: &mut LogEvent, pdu: Pdu) {
+        log.insert(event_path!("pdu_type"), pdu.pdu_type.as_str());
+        if let Some(request_id) = pdu.request_id {
+            log.insert(event_path!("request_id"), request_id);
+        }
+        if let Some(trap) = &pdu.v1 {
+            log.insert(event_path!("enterprise"), format_oid(&trap.enterprise));
+            log.insert(event_path!("agent_address"), trap.agent_address.to_string());
+            log.insert(event_path!("generic_trap"), trap.generic_trap);
+            log.insert(event_path!("specific_trap"), trap.specific_trap);
+        }
+        if let Some(uptime) = pdu.uptime() {
+            log.insert(event_path!("uptime"), uptime as i64);
+        }
+        if let Some(trap_oid) = pdu.trap_oid() {
+            log.insert(event_path!("trap_oid"), format_oid(&trap_oid));
+            if let Some(name) = self.mibs.resolve(&trap_oid) {
+                log.insert(event_path!("trap_name"), name);
+            }
+        }
+
+        let varbinds = pdu
+            .varbinds
+            .into_iter()
+            .map(|varbind| {
+                let mut object = ObjectMap::new();
+                object.insert("oid".into(), format_oid(&varbind.oid).into());
+                if let Some(name) = self.mibs.resolve(&varbind.oid) {
+                    object.insert("name".into(), name.into());
+                }
+                object.insert("type".into(), varbind.value.type_name().into());
+                if let VarValue::ObjectIdentifier(oid) = &varbind.value
+                    && let Some(name) = self.mibs.resolve(oid)
+                {
+                    object.insert("value_name".into(), name.into());
+                }
+                object.insert("value".into(), varbind_value(varbind.value));
+                Value::Object(object)
+            })
+            .collect::<Vec<_>>();
+        log.insert(event_path!("varbinds"), varbinds);
+    }
+}
+
+fn format_oid(oid: &[u32]) -> String {
+    oid.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Formats bytes as colon separated hexadecimal, such as `00:1a:2b`.
+fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn varbind_value(value: VarValue) -> Value {
+    match value {
+        VarValue::Integer(value) => value.into(),
+        // Octet strings are either text, or binary data such as MAC addresses.
+        VarValue::OctetString(bytes) => match String::from_utf8(bytes) {
+            Ok(text)
+                if !text
+                    .chars()
+                    .any(|c| c.is_control() && !c.is_ascii_whitespace()) =>
+            {
+                text.into()
+            }
+            Ok(text) => format_hex(text.as_bytes()).into(),
+            Err(error) => format_hex(error.as_bytes()).into(),
+        },
+        VarValue::Opaque(bytes) => format_hex(&bytes).into(),
+        VarValue::ObjectIdentifier(oid) => format_oid(&oid).into(),
+        VarValue::IpAddress(address) => address.to_string().into(),
+        VarValue::Counter32(value) | VarValue::Gauge32(value) | VarValue::TimeTicks(value) => {
+            (value as i64).into()
+        }
+        // Values which don't fit in a signed integer are kept exact, as strings.
+        VarValue::Counter64(value) => {
+            i64::try_from(value).map_or_else(|_| value.to_string().into(), Value::from)
+        }
+        VarValue::Null
+        | VarValue::NoSuchObject
+        | VarValue::NoSuchInstance
+        | VarValue::EndOfMibView => Value::Null,
+    }
+}
+
+async fn run(
+    address: SocketListenAddr,
+    receive_buffer_bytes: Option<usize>,
+    processor: Processor,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let listenfd = ListenFd::from_env();
+    let socket = try_bind_udp_socket(address, listenfd)
+        .await
+        .map_err(|error| {
+            emit!(SocketBindError {
+                mode: SocketMode::Udp,
+                error,
+            })
+        })?;
+
+    if let Some(receive_buffer_bytes) = receive_buffer_bytes
+        && let Err(error) = net::set_receive_buffer_size(&socket, receive_buffer_bytes)
+    {
+        warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+    }
+
+    let bytes_received = register!(BytesReceived::from(Protocol::UDP));
+
+    info!(message = "Listening.", address = %address);
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (byte_size, peer_addr) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => recv.map_err(|error| {
+                emit!(SocketReceiveError {
+                    mode: SocketMode::Udp,
+                    error,
+                })
+            })?,
+            _ = &mut shutdown => return Ok(()),
+        };
+        bytes_received.emit(ByteSize(byte_size));
+
+        let (log, response) = match processor.process(&buf[..byte_size], peer_addr) {
+            Ok(result) => result,
+            Err(error) => {
+                emit!(SnmpTrapMessageError { error, peer_addr });
+                continue;
+            }
+        };
+
+        if let Some(response) = response
+            && let Err(error) = socket.send_to(&response, peer_addr).await
+        {
+            warn!(message = "Failed to acknowledge inform request.", %error, %peer_addr);
+        }
+
+        emit!(SocketEventsReceived {
+            mode: SocketMode::Udp,
+            byte_size: log.estimated_json_encoded_size_of(),
+            count: 1,
+        });
+
+        tokio::select! {
+            result = out.send_event(Event::from(log)) => {
+                if result.is_err() {
+                    emit!(StreamClosedError { count: 1 });
+                    return Err(());
+                }
+            }
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::{net::UdpSocket, time::timeout};
+    use vrl::value;
+
+    use super::*;
+    use crate::{
+        config::ComponentKey,
+        sources::snmp_trap::{
+            ber::{INTEGER, OCTET_STRING, SEQUENCE, encode, encode_integer, encode_oid},
+            message::{
+                INFORM_REQUEST, RESPONSE, TIME_TICKS, TRAP_V2,
+                tests::{community_message, link_down_pdu, varbind},
+            },
+        },
+        test_util::{collect_n, next_addr},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpTrapConfig>();
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: SnmpTrapConfig = toml::from_str(
+            r#"
+            address = "0.0.0.0:1162"
+            communities = ["public"]
+            mib_paths = ["/usr/share/snmp/mibs"]
+
+            [[users]]
+            name = "vector"
+            auth.protocol = "sha256"
+            auth.passphrase = "maplesyrup"
+            privacy.protocol = "aes"
+            privacy.passphrase = "pancakes!"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(1, config.communities.len());
+        let user = &config.users[0];
+        assert_eq!(AuthProtocol::Sha256, user.auth.as_ref().unwrap().protocol);
+        assert_eq!(
+            PrivacyProtocol::Aes,
+            user.privacy.as_ref().unwrap().protocol
+        );
+    }
+
+    fn processor(communities: &[&str]) -> Processor {
+        Processor {
+            communities: communities
+                .iter()
+                .map(|community| community.to_string().into())
+                .collect(),
+            usm: Usm::new(&[]).unwrap(),
+            mibs: Mibs::default(),
+            host_key: log_schema().host_key().cloned().into(),
+            log_namespace: LogNamespace::Legacy,
+        }
+    }
+
+    #[test]
+    fn creates_events() {
+        let data = community_message(1, "public", link_down_pdu(TRAP_V2, 42));
+        let (log, response) = processor(&[])
+            .process(&data, "192.0.2.1:1234".parse().unwrap())
+            .unwrap();
+        assert_eq!(None, response);
+
+        assert_eq!(log["version"], "2c".into());
+        assert_eq!(log["pdu_type"], "snmpv2_trap".into());
+        assert_eq!(log["request_id"], 42.into());
+        assert_eq!(log["uptime"], 123_456.into());
+        assert_eq!(log["trap_oid"], "1.3.6.1.6.3.1.1.5.3".into());
+        assert_eq!(log["trap_name"], "IF-MIB::linkDown".into());
+        assert_eq!(log["host"], "192.0.2.1".into());
+        assert_eq!(log["source_type"], "snmp_trap".into());
+        assert_eq!(
+            log["varbinds[2]"],
+            value!({
+                "oid": "1.3.6.1.2.1.2.2.1.1.2",
+                "name": "IF-MIB::ifIndex.2",
+                "type": "integer",
+                "value": 2,
+            })
+        );
+        assert_eq!(
+            log["varbinds[1]"],
+            value!({
+                "oid": "1.3.6.1.6.3.1.1.4.1.0",
+                "name": "SNMPv2-MIB::snmpTrapOID.0",
+                "type": "object_identifier",
+                "value_name": "IF-MIB::linkDown",
+                "value": "1.3.6.1.6.3.1.1.5.3",
+            })
+        );
+    }
+
+    #[test]
+    fn creates_events_with_vector_namespace() {
+        let data = community_message(1, "public", link_down_pdu(TRAP_V2, 42));
+        let processor = Processor {
+            log_namespace: LogNamespace::Vector,
+            ..processor(&[])
+        };
+        let (log, _) = processor
+            .process(&data, "192.0.2.1:1234".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(log["trap_name"], "IF-MIB::linkDown".into());
+        assert!(log.get("host").is_none());
+        assert_eq!(
+            log.metadata().value().get(path!("snmp_trap", "host")),
+            Some(&value!("192.0.2.1"))
+        );
+    }
+
+    #[test]
+    fn formats_values() {
+        let varbinds = [
+            varbind(&[1, 3, 6, 1, 4, 1, 1, 1], encode(OCTET_STRING, b"eth0")),
+            varbind(
+                &[1, 3, 6, 1, 4, 1, 1, 2],
+                encode(OCTET_STRING, &[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]),
+            ),
+            varbind(
+                &[1, 3, 6, 1, 4, 1, 1, 3],
+                encode(
+                    message::COUNTER64,
+                    &[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                ),
+            ),
+            varbind(
+                &[1, 3, 6, 1, 4, 1, 1, 4],
+                encode(message::IP_ADDRESS, &[10, 0, 0, 1]),
+            ),
+        ];
+        let pdu = encode(
+            TRAP_V2,
+            &[
+                encode_integer(INTEGER, 1),
+                encode_integer(INTEGER, 0),
+                encode_integer(INTEGER, 0),
+                encode(SEQUENCE, &varbinds.concat()),
+            ]
+            .concat(),
+        );
+        let data = community_message(1, "public", pdu);
+        let (log, _) = processor(&[])
+            .process(&data, "192.0.2.1:1234".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(log["varbinds[0].value"], "eth0".into());
+        assert_eq!(log["varbinds[1].value"], "00:1a:2b:3c:4d:5e".into());
+        assert_eq!(log["varbinds[2].value"], u64::MAX.to_string().into());
+        assert_eq!(log["varbinds[3].value"], "10.0.0.1".into());
+        assert!(log.get("trap_oid").is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_communities() {
+        let data = community_message(1, "private", link_down_pdu(TRAP_V2, 42));
+        assert!(matches!(
+            processor(&["public"]).process(&data, "192.0.2.1:1234".parse().unwrap()),
+            Err(ProcessError::UnknownCommunity)
+        ));
+
+        let data = community_message(1, "public", link_down_pdu(TRAP_V2, 42));
+        assert!(
+            processor(&["public"])
+                .process(&data, "192.0.2.1:1234".parse().unwrap())
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_notifications() {
+        let address = next_addr();
+        let config = SnmpTrapConfig {
+            address: address.into(),
+            ..Default::default()
+        };
+
+        let (tx, rx) = SourceSender::new_test();
+        let (context, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("in"), tx);
+        let source = config.build(context).await.unwrap();
+        tokio::spawn(source);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let trap = community_message(1, "public", link_down_pdu(TRAP_V2, 1));
+        socket.send_to(&trap, address).await.unwrap();
+
+        // Inform requests are acknowledged with a Response.
+        let inform = community_message(1, "public", link_down_pdu(INFORM_REQUEST, 2));
+        socket.send_to(&inform, address).await.unwrap();
+        let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+        let size = timeout(Duration::from_secs(5), socket.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            community_message(1, "public", link_down_pdu(RESPONSE, 2)),
+            buf[..size]
+        );
+
+        let events = timeout(Duration::from_secs(5), collect_n(rx, 2))
+            .await
+            .unwrap();
+        assert_eq!(events[0].as_log()["pdu_type"], "snmpv2_trap".into());
+        assert_eq!(events[1].as_log()["pdu_type"], "inform_request".into());
+        assert_eq!(events[1].as_log()["request_id"], 2.into());
+
+        shutdown.shutdown_all(None).await;
+    }
+
+    #[test]
+    fn creates_v1_events() {
+        let content = [
+            encode_oid(&[1, 3, 6, 1, 4, 1, 8072]),
+            encode(message::IP_ADDRESS, &[192, 0, 2, 5]),
+            encode_integer(INTEGER, 0),
+            encode_integer(INTEGER, 0),
+            encode_integer(TIME_TICKS, 42),
+            encode(SEQUENCE, &[]),
+        ];
+        let data = community_message(0, "public", encode(message::TRAP_V1, &content.concat()));
+        let (log, _) = processor(&[])
+            .process(&data, "192.0.2.1:1234".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(log["version"], "1".into());
+        assert_eq!(log["agent_address"], "192.0.2.5".into());
+        assert_eq!(log["enterprise"], "1.3.6.1.4.1.8072".into());
+        assert_eq!(log["uptime"], 42.into());
+        assert_eq!(log["trap_name"], "SNMPv2-MIB::coldStart".into());
+        assert_eq!(log["varbinds"], Value::Array(Vec::new()));
+    }
+}
@@ -0,0 +1,674 @@
+//! The User-based Security Model (USM) of SNMPv3, as described in [RFC 3414][rfc3414], with the
+//! SHA-2 authentication protocols of [RFC 7860][rfc7860] and the AES privacy protocol of
+//! [RFC 3826][rfc3826].
+//!
+//! Notifications are sent by the authoritative engine, so the keys of the users are localized
+//! with the engine ID of each message.
+//!
+//! [rfc3414]: https://datatracker.ietf.org/doc/html/rfc3414
+//! [rfc7860]: https://datatracker.ietf.org/doc/html/rfc7860
+//! [rfc3826]: https://datatracker.ietf.org/doc/html/rfc3826
+use std::collections::HashMap;
+
+use openssl::{
+    error::ErrorStack,
+    hash::{Hasher, MessageDigest},
+    memcmp,
+    pkey::PKey,
+    provider::Provider,
+    sign::Signer,
+    symm::{Cipher, Crypter, Mode},
+};
+use snafu::{OptionExt, ResultExt, Snafu};
+use vector_lib::{configurable::configurable_component, sensitive_string::SensitiveString};
+
+use super::message::{MessageError, ScopedPdu, ScopedPduData, V3Message, parse_scoped_pdu};
+
+const FLAG_AUTH: u8 = 0x01;
+const FLAG_PRIV: u8 = 0x02;
+
+/// The minimum length of passphrases, as required by RFC 3414.
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+/// An SNMPv3 user allowed to send notifications.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    /// The name of the user, also known as its security name.
+    #[configurable(metadata(docs::examples = "vector"))]
+    pub name: String,
+
+    /// The authentication settings of the user.
+    ///
+    /// Messages from users without authentication settings must not be authenticated.
+    #[serde(default)]
+    pub auth: Option<UserAuthConfig>,
+
+    /// The privacy settings of the user.
+    ///
+    /// Messages from users without privacy settings must not be encrypted. Privacy requires the
+    /// user to have authentication settings.
+    #[serde(default)]
+    pub privacy: Option<UserPrivacyConfig>,
+}
+
+/// Authentication settings of an SNMPv3 user.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UserAuthConfig {
+    #[configurable(derived)]
+    pub protocol: AuthProtocol,
+
+    /// The authentication passphrase of the user.
+    ///
+    /// It must be at least 8 characters long.
+    #[configurable(metadata(docs::examples = "${SNMP_AUTH_PASSPHRASE}"))]
+    pub passphrase: SensitiveString,
+}
+
+/// Privacy settings of an SNMPv3 user.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UserPrivacyConfig {
+    #[configurable(derived)]
+    pub protocol: PrivacyProtocol,
+
+    /// The privacy passphrase of the user.
+    ///
+    /// It must be at least 8 characters long.
+    #[configurable(metadata(docs::examples = "${SNMP_PRIVACY_PASSPHRASE}"))]
+    pub passphrase: SensitiveString,
+}
+
+/// The protocol used to authenticate messages.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProtocol {
+    /// HMAC-MD5-96.
+    Md5,
+
+    /// HMAC-SHA-96.
+    Sha,
+
+    /// HMAC-SHA-224-128.
+    Sha224,
+
+    /// HMAC-SHA-256-192.
+    Sha256,
+
+    /// HMAC-SHA-384-256.
+    Sha384,
+
+    /// HMAC-SHA-512-384.
+    Sha512,
+}
+
+impl AuthProtocol {
+    fn digest(self) -> MessageDigest {
+        match self {
+            Self::Md5 => MessageDigest::md5(),
+            Self::Sha => MessageDigest::sha1(),
+            Self::Sha224 => MessageDigest::sha224(),
+            Self::Sha256 => MessageDigest::sha256(),
+            Self::Sha384 => MessageDigest::sha384(),
+            Self::Sha512 => MessageDigest::sha512(),
+        }
+    }
+
+    /// The length of the authentication parameters, which are the truncated HMAC of the message.
+    const fn mac_length(self) -> usize {
+        match self {
+            Self::Md5 | Self::Sha => 12,
+            Self::Sha224 => 16,
+            Self::Sha256 => 24,
+            Self::Sha384 => 32,
+            Self::Sha512 => 48,
+        }
+    }
+}
+
+/// The protocol used to encrypt messages.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyProtocol {
+    /// CBC-DES.
+    ///
+    /// DES is considered insecure, and is only supported for compatibility with older agents.
+    Des,
+
+    /// CFB128-AES-128.
+    Aes,
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum UsmBuildError {
+    #[snafu(display("user {user:?} is configured with privacy but without authentication"))]
+    PrivacyWithoutAuth { user: String },
+
+    #[snafu(display(
+        "the passphrases of user {user:?} must be at least {MIN_PASSPHRASE_LENGTH} characters long"
+    ))]
+    PassphraseTooShort { user: String },
+
+    #[snafu(display("user {user:?} is configured more than once"))]
+    DuplicateUser { user: String },
+
+    #[snafu(display("failed to derive the keys of user {user:?}: {source}"))]
+    DeriveKey { user: String, source: ErrorStack },
+
+    #[snafu(display("failed to load the OpenSSL legacy provider required by DES: {source}"))]
+    LoadLegacyProvider { source: ErrorStack },
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum UsmError {
+    #[snafu(display("unknown user {user:?}"))]
+    UnknownUser { user: String },
+
+    #[snafu(display("unsupported security level for user {user:?}"))]
+    UnsupportedSecurityLevel { user: String },
+
+    #[snafu(display("wrong digest for user {user:?}"))]
+    WrongDigest { user: String },
+
+    #[snafu(display("decryption failed for user {user:?}"))]
+    DecryptionFailed { user: String },
+
+    #[snafu(display("cryptographic operation failed: {source}"))]
+    Crypto { source: ErrorStack },
+
+    #[snafu(display("invalid scoped PDU: {source}"))]
+    ScopedPdu { source: MessageError },
+}
+
+impl From<ErrorStack> for UsmError {
+    fn from(source: ErrorStack) -> Self {
+        Self::Crypto { source }
+    }
+}
+
+#[derive(Debug)]
+struct User {
+    auth: Option<UserKey<AuthProtocol>>,
+    privacy: Option<UserKey<PrivacyProtocol>>,
+}
+
+/// A protocol and the key derived from the passphrase, before it is localized.
+#[derive(Debug)]
+struct UserKey<P> {
+    protocol: P,
+    key: Vec<u8>,
+}
+
+/// Authenticates and decrypts SNMPv3 messages of the configured users.
+pub(super) struct Usm {
+    users: HashMap<Vec<u8>, User>,
+
+    /// DES is only available from the legacy OpenSSL provider, which is loaded as long as this is
+    /// held.
+    _legacy_provider: Option<Provider>,
+}
+
+impl std::fmt::Debug for Usm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Usm")
+            .field("users", &self.users.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Usm {
+    pub(super) fn new(configs: &[UserConfig]) -> Result<Self, UsmBuildError> {
+        let mut users = HashMap::new();
+        let mut legacy_provider = None;
+        for config in configs {
+            let user = config.name.clone();
+            let passphrases = config
+                .auth
+                .iter()
+                .map(|auth| auth.passphrase.inner())
+                .chain(
+                    config
+                        .privacy
+                        .iter()
+                        .map(|privacy| privacy.passphrase.inner()),
+                );
+            for passphrase in passphrases {
+                if passphrase.len() < MIN_PASSPHRASE_LENGTH {
+                    return PassphraseTooShortSnafu { user }.fail();
+                }
+            }
+
+            let auth = config
+                .auth
+                .as_ref()
+                .map(|auth| {
+                    password_to_key(auth.protocol, auth.passphrase.inner().as_bytes()).map(|key| {
+                        UserKey {
+                            protocol: auth.protocol,
+                            key,
+                        }
+                    })
+                })
+                .transpose()
+                .context(DeriveKeySnafu { user: &user })?;
+
+            // The privacy key is derived using the hash function of the authentication protocol.
+            let privacy = match (&config.privacy, &auth) {
+                (None, _) => None,
+                (Some(_), None) => return PrivacyWithoutAuthSnafu { user }.fail(),
+                (Some(privacy), Some(auth)) => Some(UserKey {
+                    protocol: privacy.protocol,
+                    key: password_to_key(auth.protocol, privacy.passphrase.inner().as_bytes())
+                        .context(DeriveKeySnafu { user: &user })?,
+                }),
+            };
+
+            if privacy
+                .as_ref()
+                .is_some_and(|privacy| privacy.protocol == PrivacyProtocol::Des)
+                && legacy_provider.is_none()
+            {
+                legacy_provider = Some(
+                    Provider::try_load(None, "legacy", true).context(LoadLegacyProviderSnafu)?,
+                );
+            }
+
+            if users
+                .insert(user.clone().into_bytes(), User { auth, privacy })
+                .is_some()
+            {
+                return DuplicateUserSnafu { user }.fail();
+            }
+        }
+
+        Ok(Self {
+            users,
+            _legacy_provider: legacy_provider,
+        })
+    }
+
+    /// Authenticates and decrypts a message, returning its scoped PDU.
+    ///
+    /// The engine time of the message isn't checked against the time window, as notifications
+    /// are only received and not answered, so replays aren't detected.
+    pub(super) fn process(
+        &self,
+        data: &[u8],
+        message: &V3Message<'_>,
+    ) -> Result<ScopedPdu, UsmError> {
+        let security = &message.security;
+        let user_name = || String::from_utf8_lossy(security.user_name).into_owned();
+        let user = self
+            .users
+            .get(security.user_name)
+            .with_context(|| UnknownUserSnafu { user: user_name() })?;
+
+        let authenticated = message.flags & FLAG_AUTH != 0;
+        let encrypted = message.flags & FLAG_PRIV != 0;
+        if authenticated != user.auth.is_some() || encrypted != user.privacy.is_some() {
+            return UnsupportedSecurityLevelSnafu { user: user_name() }.fail();
+        }
+
+        let Some(auth) = &user.auth else {
+            return match message.scoped_pdu {
+                ScopedPduData::Plaintext(data) => parse_scoped_pdu(data).context(ScopedPduSnafu),
+                ScopedPduData::Encrypted(_) => {
+                    UnsupportedSecurityLevelSnafu { user: user_name() }.fail()
+                }
+            };
+        };
+
+        let auth_key = localize_key(auth.protocol, &auth.key, security.engine_id)?;
+        if !verify(auth.protocol, &auth_key, data, message)? {
+            return WrongDigestSnafu { user: user_name() }.fail();
+        }
+
+        match (&message.scoped_pdu, &user.privacy) {
+            (ScopedPduData::Plaintext(data), None) => {
+                parse_scoped_pdu(data).context(ScopedPduSnafu)
+            }
+            (ScopedPduData::Encrypted(data), Some(privacy)) => {
+                let privacy_key = localize_key(auth.protocol, &privacy.key, security.engine_id)?;
+                let plaintext = decrypt(privacy.protocol, &privacy_key, message, data)?
+                    .with_context(|| DecryptionFailedSnafu { user: user_name() })?;
+                parse_scoped_pdu(&plaintext).context(ScopedPduSnafu)
+            }
+            _ => UnsupportedSecurityLevelSnafu { user: user_name() }.fail(),
+        }
+    }
+}
+
+/// Derives a key from a passphrase, by hashing a megabyte of the repeated passphrase.
+///
+/// See [RFC 3414 Appendix A.2](https://datatracker.ietf.org/doc/html/rfc3414#appendix-A.2).
+fn password_to_key(protocol: AuthProtocol, passphrase: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let data = passphrase
+        .iter()
+        .cycle()
+        .take(1024 * 1024)
+        .copied()
+        .collect::<Vec<_>>();
+    let mut hasher = Hasher::new(protocol.digest())?;
+    hasher.update(&data)?;
+    Ok(hasher.finish()?.to_vec())
+}
+
+/// Localizes a key to an SNMP engine, so that the same passphrase yields different keys for each
+/// engine.
+fn localize_key(
+    protocol: AuthProtocol,
+    key: &[u8],
+    engine_id: &[u8],
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut hasher = Hasher::new(protocol.digest())?;
+    hasher.update(key)?;
+    hasher.update(engine_id)?;
+    hasher.update(key)?;
+    Ok(hasher.finish()?.to_vec())
+}
+
+/// Computes the authentication parameters of a message, with its own authentication parameters
+/// replaced by zeros.
+fn sign(
+    protocol: AuthProtocol,
+    key: &[u8],
+    data: &[u8],
+    auth_offset: usize,
+) -> Result<Vec<u8>, ErrorStack> {
+    let length = protocol.mac_length();
+    let mut data = data.to_vec();
+    data[auth_offset..auth_offset + length].fill(0);
+
+    let key = PKey::hmac(key)?;
+    let mut signer = Signer::new(protocol.digest(), &key)?;
+    signer.update(&data)?;
+    let mut mac = signer.sign_to_vec()?;
+    mac.truncate(length);
+    Ok(mac)
+}
+
+fn verify(
+    protocol: AuthProtocol,
+    key: &[u8],
+    data: &[u8],
+    message: &V3Message<'_>,
+) -> Result<bool, ErrorStack> {
+    let expected = message.security.auth_params;
+    if expected.len() != protocol.mac_length() {
+        return Ok(false);
+    }
+    let actual = sign(protocol, key, data, message.auth_offset)?;
+    Ok(memcmp::eq(&actual, expected))
+}
+
+/// Returns the cipher, key and IV used to encrypt the scoped PDU of a message.
+fn cipher_parameters<'a>(
+    protocol: PrivacyProtocol,
+    key: &'a [u8],
+    message: &V3Message<'_>,
+) -> Option<(Cipher, &'a [u8], Vec<u8>)> {
+    let security = &message.security;
+    let salt = security.priv_params;
+    if salt.len() != 8 || key.len() < 16 {
+        return None;
+    }
+
+    Some(match protocol {
+        // The second half of the key is the pre-IV, combined with the salt.
+        PrivacyProtocol::Des => {
+            let iv = key[8..16]
+                .iter()
+                .zip(salt)
+                .map(|(pre_iv, salt)| pre_iv ^ salt)
+                .collect();
+            (Cipher::des_cbc(), &key[..8], iv)
+        }
+        PrivacyProtocol::Aes => {
+            let mut iv = Vec::with_capacity(16);
+            iv.extend((security.engine_boots as u32).to_be_bytes());
+            iv.extend((security.engine_time as u32).to_be_bytes());
+            iv.extend(salt);
+            (Cipher::aes_128_cfb128(), &key[..16], iv)
+        }
+    })
+}
+
+/// Decrypts the scoped PDU of a message, returning `None` if the privacy parameters are invalid.
+fn decrypt(
+    protocol: PrivacyProtocol,
+    key: &[u8],
+    message: &V3Message<'_>,
+    ciphertext: &[u8],
+) -> Result<Option<Vec<u8>>, ErrorStack> {
+    let Some((cipher, key, iv)) = cipher_parameters(protocol, key, message) else {
+        return Ok(None);
+    };
+    if ciphertext.len() % cipher.block_size() != 0 {
+        return Ok(None);
+    }
+
+    let mut crypter = Crypter::new(cipher, Mode::Decrypt, key, Some(&iv))?;
+    crypter.pad(false);
+    let mut plaintext = vec![0; ciphertext.len() + cipher.block_size()];
+    let mut length = crypter.update(ciphertext, &mut plaintext)?;
+    length += crypter.finalize(&mut plaintext[length..])?;
+    plaintext.truncate(length);
+    Ok(Some(plaintext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::snmp_trap::{
+        ber::{INTEGER, OCTET_STRING, SEQUENCE, encode, encode_integer},
+        message::{self, Message, PduType, TRAP_V2, tests::link_down_pdu},
+    };
+
+    const ENGINE_ID: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+    fn user(auth: Option<AuthProtocol>, privacy: Option<PrivacyProtocol>) -> UserConfig {
+        UserConfig {
+            name: "vector".to_owned(),
+            auth: auth.map(|protocol| UserAuthConfig {
+                protocol,
+                passphrase: "maplesyrup".to_owned().into(),
+            }),
+            privacy: privacy.map(|protocol| UserPrivacyConfig {
+                protocol,
+                passphrase: "pancakes!".to_owned().into(),
+            }),
+        }
+    }
+
+    fn encode_message(flags: u8, auth_params: &[u8], priv_params: &[u8], data: &[u8]) -> Vec<u8> {
+        let global_data = [
+            encode_integer(INTEGER, 1),
+            encode_integer(INTEGER, 65507),
+            encode(OCTET_STRING, &[flags]),
+            encode_integer(INTEGER, 3),
+        ];
+        let security = [
+            encode(OCTET_STRING, &ENGINE_ID),
+            encode_integer(INTEGER, 3),
+            encode_integer(INTEGER, 1234),
+            encode(OCTET_STRING, b"vector"),
+            encode(OCTET_STRING, auth_params),
+            encode(OCTET_STRING, priv_params),
+        ];
+        let content = [
+            encode_integer(INTEGER, 3),
+            encode(SEQUENCE, &global_data.concat()),
+            encode(OCTET_STRING, &encode(SEQUENCE, &security.concat())),
+            data.to_vec(),
+        ];
+        encode(SEQUENCE, &content.concat())
+    }
+
+    /// Encodes a notification from the user, the way an agent would.
+    fn build_message(
+        auth: Option<AuthProtocol>,
+        privacy: Option<PrivacyProtocol>,
+        context_name: &[u8],
+    ) -> Vec<u8> {
+        let scoped_pdu = encode(
+            SEQUENCE,
+            &[
+                encode(OCTET_STRING, &ENGINE_ID),
+                encode(OCTET_STRING, context_name),
+                link_down_pdu(TRAP_V2, 1),
+            ]
+            .concat(),
+        );
+        let Some(auth) = auth else {
+            return encode_message(0, &[], &[], &scoped_pdu);
+        };
+
+        let auth_params = vec![0; auth.mac_length()];
+        let (flags, priv_params, data) = match privacy {
+            None => (FLAG_AUTH, Vec::new(), scoped_pdu),
+            Some(privacy) => {
+                let salt = vec![0, 0, 0, 1, 2, 3, 4, 5];
+                let placeholder = encode(OCTET_STRING, &[]);
+                let message =
+                    encode_message(FLAG_AUTH | FLAG_PRIV, &auth_params, &salt, &placeholder);
+                let Ok(Message::V3(message)) = message::parse(&message) else {
+                    panic!("invalid message");
+                };
+                let key = password_to_key(auth, b"pancakes!").unwrap();
+                let key = localize_key(auth, &key, &ENGINE_ID).unwrap();
+                let (cipher, key, iv) = cipher_parameters(privacy, &key, &message).unwrap();
+
+                let mut plaintext = scoped_pdu;
+                plaintext.resize(plaintext.len().next_multiple_of(cipher.block_size()), 0);
+                let mut crypter = Crypter::new(cipher, Mode::Encrypt, key, Some(&iv)).unwrap();
+                crypter.pad(false);
+                let mut ciphertext = vec![0; plaintext.len() + cipher.block_size()];
+                let length = crypter.update(&plaintext, &mut ciphertext).unwrap();
+                ciphertext.truncate(length);
+                (
+                    FLAG_AUTH | FLAG_PRIV,
+                    salt,
+                    encode(OCTET_STRING, &ciphertext),
+                )
+            }
+        };
+
+        let mut data = encode_message(flags, &auth_params, &priv_params, &data);
+        let Ok(Message::V3(message)) = message::parse(&data) else {
+            panic!("invalid message");
+        };
+        let auth_offset = message.auth_offset;
+        let key = password_to_key(auth, b"maplesyrup").unwrap();
+        let key = localize_key(auth, &key, &ENGINE_ID).unwrap();
+        let mac = sign(auth, &key, &data, auth_offset).unwrap();
+        data[auth_offset..auth_offset + mac.len()].copy_from_slice(&mac);
+        data
+    }
+
+    fn process(usm: &Usm, data: &[u8]) -> Result<ScopedPdu, UsmError> {
+        let Ok(Message::V3(message)) = message::parse(data) else {
+            panic!("invalid message");
+        };
+        usm.process(data, &message)
+    }
+
+    #[test]
+    fn localizes_keys() {
+        // The test vectors from RFC 3414 Appendix A.3.
+        let key = password_to_key(AuthProtocol::Md5, b"maplesyrup").unwrap();
+        assert_eq!(
+            [
+                0x52, 0x6f, 0x5e, 0xed, 0x9f, 0xcc, 0xe2, 0x6f, 0x89, 0x64, 0xc2, 0x93, 0x07, 0x87,
+                0xd8, 0x2b
+            ]
+            .as_slice(),
+            localize_key(AuthProtocol::Md5, &key, &ENGINE_ID).unwrap()
+        );
+
+        let key = password_to_key(AuthProtocol::Sha, b"maplesyrup").unwrap();
+        assert_eq!(
+            [
+                0x66, 0x95, 0xfe, 0xbc, 0x92, 0x88, 0xe3, 0x62, 0x82, 0x23, 0x5f, 0xc7, 0x15, 0x1f,
+                0x12, 0x84, 0x97, 0xb3, 0x8f, 0x3f
+            ]
+            .as_slice(),
+            localize_key(AuthProtocol::Sha, &key, &ENGINE_ID).unwrap()
+        );
+    }
+
+    #[test]
+    fn processes_all_security_levels() {
+        let levels = [
+            (None, None),
+            (Some(AuthProtocol::Md5), None),
+            (Some(AuthProtocol::Sha512), None),
+            (Some(AuthProtocol::Sha), Some(PrivacyProtocol::Aes)),
+            (Some(AuthProtocol::Sha256), Some(PrivacyProtocol::Aes)),
+            (Some(AuthProtocol::Md5), Some(PrivacyProtocol::Des)),
+        ];
+        for (auth, privacy) in levels {
+            let usm = Usm::new(&[user(auth, privacy)]).unwrap();
+            let data = build_message(auth, privacy, b"context");
+            let scoped_pdu = process(&usm, &data).unwrap();
+            assert_eq!(b"context", scoped_pdu.context_name.as_slice());
+            assert_eq!(PduType::SnmpV2Trap, scoped_pdu.pdu.pdu_type);
+            assert_eq!(3, scoped_pdu.pdu.varbinds.len());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        let usm = Usm::new(&[user(Some(AuthProtocol::Sha), Some(PrivacyProtocol::Aes))]).unwrap();
+
+        let mut data = build_message(Some(AuthProtocol::Sha), Some(PrivacyProtocol::Aes), b"");
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        assert!(matches!(
+            process(&usm, &data),
+            Err(UsmError::WrongDigest { .. })
+        ));
+
+        let data = build_message(Some(AuthProtocol::Sha), None, b"");
+        assert!(matches!(
+            process(&usm, &data),
+            Err(UsmError::UnsupportedSecurityLevel { .. })
+        ));
+
+        let usm = Usm::new(&[UserConfig {
+            name: "other".to_owned(),
+            ..user(None, None)
+        }])
+        .unwrap();
+        let data = build_message(None, None, b"");
+        assert!(matches!(
+            process(&usm, &data),
+            Err(UsmError::UnknownUser { user }) if user == "vector"
+        ));
+    }
+
+    #[test]
+    fn validates_users() {
+        assert!(matches!(
+            Usm::new(&[user(None, Some(PrivacyProtocol::Aes))]),
+            Err(UsmBuildError::PrivacyWithoutAuth { .. })
+        ));
+
+        let mut config = user(Some(AuthProtocol::Sha), None);
+        config.auth.as_mut().unwrap().passphrase = "short".to_owned().into();
+        assert!(matches!(
+            Usm::new(&[config]),
+            Err(UsmBuildError::PassphraseTooShort { .. })
+        ));
+
+        assert!(matches!(
+            Usm::new(&[user(None, None), user(None, None)]),
+            Err(UsmBuildError::DuplicateUser { .. })
+        ));
+    }
+}
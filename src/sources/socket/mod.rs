@@ -146,6 +146,7 @@ impl SourceConfig for SocketConfig {
                     false.into(),
                     config.connection_limit,
                     config.permit_origin.map(Into::into),
+                    config.proxy_protocol,
                     SocketConfig::NAME,
                     log_namespace,
                 )
@@ -496,6 +497,38 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn tcp_it_includes_proxied_host() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let (tx, mut rx) = SourceSender::new_test();
+            let (guard, addr) = next_addr().await;
+
+            let mut config = TcpConfig::from_address(addr.into());
+            config.proxy_protocol = true;
+            let server = SocketConfig::from(config)
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(server);
+
+            wait_for_tcp_and_release(guard, addr).await;
+
+            // The lines are terminated by `\n`, which completes the `\r\n` ending the header.
+            let lines = vec![
+                "PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r".to_owned(),
+                "test".to_owned(),
+            ];
+            send_lines(addr, lines.into_iter()).await.unwrap();
+
+            let event = rx.next().await.unwrap();
+
+            assert_eq!(event.as_log()["host"], "192.0.2.1".into());
+            assert_eq!(event.as_log()["port"], 56324.into());
+            assert_eq!(event.as_log()["message"], "test".into());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn tcp_it_includes_vector_namespaced_fields() {
         assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
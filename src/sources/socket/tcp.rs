@@ -62,6 +62,21 @@ pub struct TcpConfig {
     #[configurable(derived)]
     pub permit_origin: Option<IpAllowlistConfig>,
 
+    /// Whether the connections start with a [PROXY protocol][proxy_protocol] header.
+    ///
+    /// Enable this when Vector is behind a load balancer, such as HAProxy or an AWS Network Load
+    /// Balancer, that sends the version 1 or 2 of the header. The address and port of the original
+    /// client are then read from the header, and used as the `host` and `port` of the events,
+    /// instead of those of the load balancer. The connections that don't start with a valid
+    /// header are closed.
+    ///
+    /// The header is read before the TLS handshake, and `permit_origin` still applies to the
+    /// address of the load balancer.
+    ///
+    /// [proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+    #[serde(default)]
+    pub proxy_protocol: bool,
+
     #[configurable(derived)]
     tls: Option<TlsSourceConfig>,
 
@@ -109,6 +124,7 @@ impl TcpConfig {
             host_key: None,
             port_key: default_port_key(),
             permit_origin: None,
+            proxy_protocol: false,
             tls: None,
             receive_buffer_bytes: None,
             max_connection_duration_secs: None,
@@ -226,6 +226,7 @@ impl SourceConfig for StatsdConfig {
                     false.into(),
                     config.connection_limit,
                     config.permit_origin.clone().map(Into::into),
+                    false,
                     StatsdConfig::NAME,
                     LogNamespace::Legacy,
                 )
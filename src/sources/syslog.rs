@@ -6,19 +6,24 @@ use bytes::Bytes;
 use chrono::Utc;
 use futures::StreamExt;
 use listenfd::ListenFd;
-use smallvec::SmallVec;
+use smallvec::{SmallVec, smallvec};
 use tokio_util::udp::UdpFramed;
 use vector_lib::{
     codecs::{
-        BytesDecoder, OctetCountingDecoder, SyslogDeserializerConfig,
-        decoding::{Deserializer, Framer},
+        BytesDecoder, BytesDeserializerConfig, OctetCountingDecoder, SyslogDeserializer,
+        SyslogDeserializerConfig,
+        decoding::{Deserializer, Framer, format::Deserializer as _},
     },
     config::{LegacyKey, LogNamespace},
     configurable::configurable_component,
     ipallowlist::IpAllowlistConfig,
-    lookup::{OwnedValuePath, lookup_v2::OptionalValuePath, path},
+    lookup::{OwnedValuePath, lookup_v2::OptionalValuePath, owned_value_path, path},
+    schema::Definition,
+};
+use vrl::{
+    event_path,
+    value::{Kind, Value, kind::Collection},
 };
-use vrl::event_path;
 
 #[cfg(unix)]
 use crate::sources::util::build_unix_stream_source;
@@ -28,8 +33,10 @@ use crate::{
     config::{
         DataType, GenerateConfig, Resource, SourceConfig, SourceContext, SourceOutput, log_schema,
     },
-    event::Event,
-    internal_events::{SocketBindError, SocketMode, SocketReceiveError, StreamClosedError},
+    event::{Event, LogEvent},
+    internal_events::{
+        DecoderDeserializeError, SocketBindError, SocketMode, SocketReceiveError, StreamClosedError,
+    },
     net,
     shutdown::ShutdownSignal,
     sources::util::net::{SocketListenAddr, TcpNullAcker, TcpSource, try_bind_udp_socket},
@@ -37,6 +44,9 @@ use crate::{
     tls::{MaybeTlsSettings, TlsSourceConfig},
 };
 
+/// The name of the output the messages that fail to be parsed are sent to.
+const DECODE_ERRORS: &str = "decode_errors";
+
 /// Configuration for the `syslog` source.
 #[configurable_component(source("syslog", "Collect logs sent via Syslog."))]
 #[derive(Clone, Debug)]
@@ -85,6 +95,17 @@ pub enum Mode {
         #[configurable(derived)]
         permit_origin: Option<IpAllowlistConfig>,
 
+        /// Whether the connections start with a [PROXY protocol][proxy_protocol] header, as sent
+        /// by load balancers such as HAProxy.
+        ///
+        /// The address of the original client is read from the header of the versions 1 or 2 of
+        /// the protocol, and used as the `source_ip` of the events instead of the address of the
+        /// load balancer. The connections without a valid header are closed.
+        ///
+        /// [proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+        #[serde(default)]
+        proxy_protocol: bool,
+
         #[configurable(derived)]
         tls: Option<TlsSourceConfig>,
 
@@ -148,6 +169,7 @@ impl Default for SyslogConfig {
                 address: SocketListenAddr::SocketAddr("0.0.0.0:514".parse().unwrap()),
                 keepalive: None,
                 permit_origin: None,
+                proxy_protocol: false,
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
@@ -181,6 +203,7 @@ impl SourceConfig for SyslogConfig {
                 address,
                 keepalive,
                 permit_origin,
+                proxy_protocol,
                 tls,
                 receive_buffer_bytes,
                 connection_limit,
@@ -209,6 +232,7 @@ impl SourceConfig for SyslogConfig {
                     false.into(),
                     connection_limit,
                     permit_origin.map(Into::into),
+                    proxy_protocol,
                     SyslogConfig::NAME,
                     log_namespace,
                 )
@@ -231,9 +255,10 @@ impl SourceConfig for SyslogConfig {
                 socket_file_mode,
             } => {
                 let decoder = Decoder::new(
-                    Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(
-                        self.max_length,
-                    )),
+                    Framer::OctetCounting(
+                        OctetCountingDecoder::new_with_max_length(self.max_length)
+                            .detect_per_stream(),
+                    ),
                     Deserializer::Syslog(
                         SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build(),
                     ),
@@ -257,10 +282,21 @@ impl SourceConfig for SyslogConfig {
             .schema_definition(log_namespace)
             .with_standard_vector_source_metadata();
 
-        vec![SourceOutput::new_maybe_logs(
+        let mut outputs = vec![SourceOutput::new_maybe_logs(
             DataType::Log,
             schema_definition,
-        )]
+        )];
+        // The messages received on Unix sockets that fail to be parsed are dropped.
+        if matches!(self.mode, Mode::Tcp { .. } | Mode::Udp { .. }) {
+            outputs.push(
+                SourceOutput::new_maybe_logs(
+                    DataType::Log,
+                    decode_errors_schema_definition(log_namespace),
+                )
+                .with_port(DECODE_ERRORS),
+            );
+        }
+        outputs
     }
 
     fn resources(&self) -> Vec<Resource> {
@@ -292,8 +328,10 @@ impl TcpSource for SyslogTcpSource {
 
     fn decoder(&self) -> Self::Decoder {
         Decoder::new(
-            Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(self.max_length)),
-            Deserializer::Syslog(SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build()),
+            Framer::OctetCounting(
+                OctetCountingDecoder::new_with_max_length(self.max_length).detect_per_stream(),
+            ),
+            decode_errors_deserializer(),
         )
     }
 
@@ -309,6 +347,105 @@ impl TcpSource for SyslogTcpSource {
     fn build_acker(&self, _: &[Self::Item]) -> Self::Acker {
         TcpNullAcker
     }
+
+    fn event_output(&self, event: &Event) -> Option<&'static str> {
+        is_decode_error(event).then_some(DECODE_ERRORS)
+    }
+}
+
+/// Parses the syslog messages, and turns the ones that fail to be parsed, such as those with a
+/// malformed priority, into events for the `decode_errors` output instead of dropping them.
+#[derive(Clone)]
+struct DecodeErrorsDeserializer {
+    syslog: SyslogDeserializer,
+}
+
+impl vector_lib::codecs::decoding::format::Deserializer for DecodeErrorsDeserializer {
+    fn parse(
+        &self,
+        bytes: Bytes,
+        log_namespace: LogNamespace,
+    ) -> crate::Result<SmallVec<[Event; 1]>> {
+        self.syslog
+            .parse(bytes.clone(), log_namespace)
+            .or_else(|error| {
+                emit!(DecoderDeserializeError { error: &error });
+                Ok(smallvec![decode_error_event(bytes, &error, log_namespace)])
+            })
+    }
+}
+
+fn decode_errors_deserializer() -> Deserializer {
+    Deserializer::Boxed(Box::new(DecodeErrorsDeserializer {
+        syslog: SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build(),
+    }))
+}
+
+/// An event holding the raw message, and why it failed to be parsed.
+fn decode_error_event(bytes: Bytes, error: &crate::Error, log_namespace: LogNamespace) -> Event {
+    let mut log = match log_namespace {
+        LogNamespace::Vector => LogEvent::from(Value::Bytes(bytes)),
+        LogNamespace::Legacy => LogEvent::from_bytes_legacy(&bytes),
+    };
+    let error = error.to_string();
+    log_namespace.insert_source_metadata(
+        SyslogConfig::NAME,
+        &mut log,
+        Some(LegacyKey::Overwrite(path!("decode_error"))),
+        path!("decode_error"),
+        error.clone(),
+    );
+    if log_namespace == LogNamespace::Legacy {
+        // The event is routed to the `decode_errors` output by this metadata, as with the
+        // `vector` namespace, so that the fields of the messages can't be mistaken for it.
+        log.metadata_mut()
+            .value_mut()
+            .insert(path!(SyslogConfig::NAME, "decode_error"), error);
+    }
+    Event::from(log)
+}
+
+fn is_decode_error(event: &Event) -> bool {
+    event.maybe_as_log().is_some_and(|log| {
+        log.metadata()
+            .value()
+            .get(path!(SyslogConfig::NAME, "decode_error"))
+            .is_some()
+    })
+}
+
+fn decode_errors_schema_definition(log_namespace: LogNamespace) -> Definition {
+    BytesDeserializerConfig::new()
+        .schema_definition(log_namespace)
+        .with_source_metadata(
+            SyslogConfig::NAME,
+            Some(LegacyKey::Overwrite(owned_value_path!("decode_error"))),
+            &owned_value_path!("decode_error"),
+            Kind::bytes(),
+            None,
+        )
+        .with_source_metadata(
+            SyslogConfig::NAME,
+            Some(LegacyKey::Overwrite(owned_value_path!("source_ip"))),
+            &owned_value_path!("source_ip"),
+            Kind::bytes().or_undefined(),
+            None,
+        )
+        .with_source_metadata(
+            SyslogConfig::NAME,
+            None,
+            &owned_value_path!("host"),
+            Kind::bytes().or_undefined(),
+            Some("host"),
+        )
+        .with_source_metadata(
+            SyslogConfig::NAME,
+            None,
+            &owned_value_path!("tls_client_metadata"),
+            Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+            None,
+        )
+        .with_standard_vector_source_metadata()
 }
 
 pub fn udp(
@@ -345,9 +482,7 @@ pub fn udp(
             socket,
             Decoder::new(
                 Framer::Bytes(BytesDecoder::new()),
-                Deserializer::Syslog(
-                    SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build(),
-                ),
+                decode_errors_deserializer(),
             ),
         )
         .take_until(shutdown)
@@ -372,17 +507,20 @@ pub fn udp(
         })
         .boxed();
 
-        match out.send_event_stream(&mut stream).await {
-            Ok(()) => {
-                debug!("Finished sending.");
-                Ok(())
-            }
-            Err(_) => {
+        while let Some(event) = stream.next().await {
+            let sent = if is_decode_error(&event) {
+                out.send_batch_named(DECODE_ERRORS, [event]).await
+            } else {
+                out.send_event(event).await
+            };
+            if sent.is_err() {
                 let (count, _) = stream.size_hint();
-                emit!(StreamClosedError { count });
-                Err(())
+                emit!(StreamClosedError { count: count + 1 });
+                return Err(());
             }
         }
+        debug!("Finished sending.");
+        Ok(())
     })
 }
 
@@ -460,7 +598,7 @@ mod test {
         assert_event_data_eq,
         codecs::decoding::format::Deserializer,
         config::ComponentKey,
-        lookup::{OwnedTargetPath, PathPrefix, event_path, owned_value_path},
+        lookup::{OwnedTargetPath, PathPrefix, event_path, metadata_path, owned_value_path},
         schema::Definition,
     };
     use vrl::value::{Kind, ObjectMap, Value, kind::Collection};
@@ -653,6 +791,70 @@ mod test {
         assert_eq!(definitions, Some(expected_definition));
     }
 
+    #[test]
+    fn output_decode_errors_only_for_network_modes() {
+        let ports = |mode| {
+            SyslogConfig::from_mode(mode)
+                .outputs(LogNamespace::Legacy)
+                .into_iter()
+                .map(|output| output.port)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ports(Mode::Udp {
+                address: SocketListenAddr::SocketAddr("127.0.0.1:514".parse().unwrap()),
+                receive_buffer_bytes: None,
+            }),
+            vec![None, Some(DECODE_ERRORS.to_owned())]
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            ports(Mode::Unix {
+                path: "/tmp/syslog.sock".into(),
+                socket_file_mode: None,
+            }),
+            vec![None]
+        );
+    }
+
+    #[test]
+    fn decode_errors_keep_raw_message() {
+        let raw = Bytes::from("<13>1 2019-02-13T19:48:34+00:00");
+        let error: crate::Error = "unexpected end of message".into();
+
+        for log_namespace in [LogNamespace::Legacy, LogNamespace::Vector] {
+            let event = decode_error_event(raw.clone(), &error, log_namespace);
+            assert!(is_decode_error(&event));
+
+            let log = event.as_log();
+            let (message, decode_error) = match log_namespace {
+                LogNamespace::Legacy => (
+                    log.get(log_schema().message_key_target_path().unwrap()),
+                    log.get(event_path!("decode_error")),
+                ),
+                LogNamespace::Vector => (
+                    log.get(event_path!()),
+                    log.get(metadata_path!(SyslogConfig::NAME, "decode_error")),
+                ),
+            };
+            assert_eq!(message, Some(&Value::from(raw.clone())));
+            assert_eq!(
+                decode_error,
+                Some(&Value::from("unexpected end of message"))
+            );
+        }
+
+        let parsed = event_from_bytes(
+            "host",
+            None,
+            Bytes::from("<13>1 2019-02-13T19:48:34+00:00 host app 1 - - hello"),
+            LogNamespace::Legacy,
+        )
+        .unwrap();
+        assert!(!is_decode_error(&parsed));
+    }
+
     #[test]
     fn config_tcp() {
         let config: SyslogConfig = toml::from_str(
@@ -1127,6 +1329,7 @@ mod test {
             let config = SyslogConfig::from_mode(Mode::Tcp {
                 address: in_addr.into(),
                 permit_origin: None,
+                proxy_protocol: false,
                 keepalive: None,
                 tls: None,
                 receive_buffer_bytes: None,
@@ -1272,6 +1475,7 @@ mod test {
             let config = SyslogConfig::from_mode(Mode::Tcp {
                 address: in_addr.into(),
                 permit_origin: None,
+                proxy_protocol: false,
                 keepalive: None,
                 tls: None,
                 receive_buffer_bytes: None,
@@ -1,7 +1,7 @@
 use std::{
     cmp,
     future::Future,
-    io::Write,
+    io::{self, Write},
     mem,
     pin::Pin,
     task::{Context, Poll},
@@ -10,7 +10,7 @@ use std::{
 use bytes::{Buf, BufMut, BytesMut};
 use flate2::write::GzDecoder;
 use futures_util::FutureExt;
-use http::{Request, Response};
+use http::{HeaderValue, Request, Response};
 use hyper::{
     Body,
     body::{HttpBody, Sender},
@@ -30,9 +30,15 @@ use crate::internal_events::{GrpcError, GrpcInvalidCompressionSchemeError};
 const GRPC_MESSAGE_HEADER_LEN: usize = mem::size_of::<u8>() + mem::size_of::<u32>();
 const GRPC_ENCODING_HEADER: &str = "grpc-encoding";
 const GRPC_ACCEPT_ENCODING_HEADER: &str = "grpc-accept-encoding";
+/// The compression schemes of the requests that are decompressed, advertised to the clients.
+const ACCEPTED_ENCODINGS: &str = "gzip,zstd,lz4,identity";
 
+#[derive(Clone, Copy)]
 enum CompressionScheme {
     Gzip,
+    Zstd,
+    /// The LZ4 block format, prefixed with the size of the decompressed message.
+    Lz4,
 }
 
 impl CompressionScheme {
@@ -51,6 +57,8 @@ impl CompressionScheme {
                 None => Ok(None),
                 Some(scheme) => match scheme.as_str() {
                     "gzip" => Ok(Some(CompressionScheme::Gzip)),
+                    "zstd" => Ok(Some(CompressionScheme::Zstd)),
+                    "lz4" => Ok(Some(CompressionScheme::Lz4)),
                     other => Err(Status::unimplemented(format!(
                         "compression scheme `{other}` is not supported"
                     ))),
@@ -59,7 +67,7 @@ impl CompressionScheme {
             .map_err(|mut status| {
                 status.metadata_mut().insert(
                     GRPC_ACCEPT_ENCODING_HEADER,
-                    AsciiMetadataValue::from_static("gzip,identity"),
+                    AsciiMetadataValue::from_static(ACCEPTED_ENCODINGS),
                 );
                 status
             })
@@ -78,17 +86,59 @@ impl Default for State {
     }
 }
 
-fn new_decompressor() -> GzDecoder<Vec<u8>> {
-    // Create the backing buffer for the decompressor and set the compression flag to false (0) and pre-allocate
-    // the space for the length prefix, which we'll fill out once we've finalized the decompressor.
-    let buf = vec![0; GRPC_MESSAGE_HEADER_LEN];
+enum Decompressor {
+    Gzip(GzDecoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Decoder<'static, Vec<u8>>),
+    /// The blocks can only be decompressed whole, so the compressed message is buffered.
+    Lz4(Vec<u8>),
+}
+
+impl Decompressor {
+    fn new(scheme: CompressionScheme) -> io::Result<Self> {
+        // Create the backing buffer for the decompressor and set the compression flag to false (0) and pre-allocate
+        // the space for the length prefix, which we'll fill out once we've finalized the decompressor.
+        let buf = vec![0; GRPC_MESSAGE_HEADER_LEN];
+
+        Ok(match scheme {
+            CompressionScheme::Gzip => Self::Gzip(GzDecoder::new(buf)),
+            CompressionScheme::Zstd => Self::Zstd(zstd::stream::write::Decoder::new(buf)?),
+            CompressionScheme::Lz4 => Self::Lz4(Vec::new()),
+        })
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Gzip(decoder) => decoder.write_all(data),
+            Self::Zstd(decoder) => decoder.write_all(data),
+            Self::Lz4(compressed) => {
+                compressed.extend_from_slice(data);
+                Ok(())
+            }
+        }
+    }
 
-    GzDecoder::new(buf)
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip(decoder) => decoder.finish(),
+            Self::Zstd(mut decoder) => {
+                decoder.flush()?;
+                Ok(decoder.into_inner())
+            }
+            Self::Lz4(compressed) => {
+                let message = lz4_flex::decompress_size_prepended(&compressed)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+                let mut buf = vec![0; GRPC_MESSAGE_HEADER_LEN];
+                buf.extend_from_slice(&message);
+                Ok(buf)
+            }
+        }
+    }
 }
 
 async fn drive_body_decompression(
     mut source: Body,
     mut destination: Sender,
+    scheme: Option<CompressionScheme>,
 ) -> Result<usize, Status> {
     let mut state = State::default();
     let mut buf = BytesMut::new();
@@ -170,7 +220,17 @@ async fn drive_body_decompression(
                             // the decompressor. This is _technically_ synchronous but there's really no way to do it
                             // asynchronously since we already have the data, and that's the only asynchronous part.
                             let to_take = cmp::min(available, *remaining);
-                            let decompressor = decompressor.get_or_insert_with(new_decompressor);
+                            if decompressor.is_none() {
+                                // The messages compressed without a `grpc-encoding` header were always
+                                // decompressed as gzip.
+                                let scheme = scheme.unwrap_or(CompressionScheme::Gzip);
+                                decompressor = Some(Decompressor::new(scheme).map_err(|_| {
+                                    Status::internal("failed to create decompressor")
+                                })?);
+                            }
+                            let decompressor = decompressor
+                                .as_mut()
+                                .expect("decompressor was just created");
                             if decompressor.write_all(&buf[..to_take]).is_err() {
                                 return Err(Status::internal("failed to write to decompressor"));
                             }
@@ -188,14 +248,10 @@ async fn drive_body_decompression(
                             .expect("consumed decompressor when no decompressor was present")
                             .finish();
 
-                        // The only I/O errors that occur during `finish` should be I/O errors from writing to the internal
-                        // buffer, but `Vec<T>` is infallible in this regard, so this should be impossible without having
-                        // first panicked due to memory exhaustion.
-                        let mut buf = result.map_err(|_| {
-                            Status::internal(
-                                "reached impossible error during decompressor finalization",
-                            )
-                        })?;
+                        // The I/O errors that occur during `finish` come from invalid compressed data, as writing to
+                        // the internal buffer is infallible.
+                        let mut buf =
+                            result.map_err(|_| Status::internal("failed to decompress message"))?;
                         bytes_received += buf.len();
 
                         // Write the length of our decompressed message in the pre-allocated slot for the message's length prefix.
@@ -241,6 +297,7 @@ async fn drive_body_decompression(
 async fn drive_request<F, E>(
     source: Body,
     destination: Sender,
+    scheme: Option<CompressionScheme>,
     inner: F,
     bytes_received: Registered<BytesReceived>,
 ) -> Result<Response<BoxBody>, E>
@@ -248,7 +305,7 @@ where
     F: Future<Output = Result<Response<BoxBody>, E>>,
     E: std::fmt::Display,
 {
-    let body_decompression = drive_body_decompression(source, destination);
+    let body_decompression = drive_body_decompression(source, destination, scheme);
 
     pin!(inner);
     pin!(body_decompression);
@@ -275,6 +332,16 @@ where
         }
     };
 
+    // The compression schemes supported by `tonic` are replaced by the ones decompressed here, so
+    // that the clients can fall back to one of them.
+    let mut result = result;
+    if let Ok(res) = &mut result {
+        res.headers_mut().insert(
+            GRPC_ACCEPT_ENCODING_HEADER,
+            HeaderValue::from_static(ACCEPTED_ENCODINGS),
+        );
+    }
+
     // If the response indicates success, then emit the necessary metrics
     // otherwise emit the error.
     match &result {
@@ -325,16 +392,24 @@ where
             // The request either isn't using compression, or it has indicated compression may be used and we know we
             // can support decompression based on the indicated compression scheme... so wrap the body to decompress, if
             // need be, and then track the bytes that flowed through.
-            //
-            // TODO: Actually use the scheme given back to us to support other compression schemes.
-            Ok(_) => {
+            Ok(scheme) => {
                 let (destination, decompressed_body) = Body::channel();
-                let (req_parts, req_body) = req.into_parts();
+                let (mut req_parts, req_body) = req.into_parts();
+                // The messages are no longer compressed once they reach the service, which would otherwise reject
+                // the schemes `tonic` doesn't support.
+                req_parts.headers.remove(GRPC_ENCODING_HEADER);
                 let mapped_req = Request::from_parts(req_parts, decompressed_body);
 
                 let inner = self.inner.call(mapped_req);
 
-                drive_request(req_body, destination, inner, self.bytes_received.clone()).boxed()
+                drive_request(
+                    req_body,
+                    destination,
+                    scheme,
+                    inner,
+                    self.bytes_received.clone(),
+                )
+                .boxed()
             }
         }
     }
@@ -359,7 +434,8 @@ where
 /// request was valid, and was processed -- we can now report the number of bytes (after decompression) that were
 /// received _and_ processed correctly.
 ///
-/// The only supported compression scheme is gzip, which is also the only supported compression scheme in `tonic` itself.
+/// The supported compression schemes are gzip, zstd, and LZ4, which are advertised to the clients in the
+/// `grpc-accept-encoding` header of the responses.
 #[derive(Clone, Default)]
 pub struct DecompressionAndMetricsLayer;
 
@@ -24,9 +24,30 @@ use crate::{
 mod decompression;
 pub use self::decompression::{DecompressionAndMetrics, DecompressionAndMetricsLayer};
 
+/// The settings of the HTTP/2 connections of a gRPC server, left to the defaults of `tonic` when
+/// not set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Http2Settings {
+    pub keepalive_interval: Option<Duration>,
+    pub keepalive_timeout: Option<Duration>,
+    pub adaptive_window: Option<bool>,
+    pub max_concurrent_streams: Option<u32>,
+}
+
+impl Http2Settings {
+    fn apply(&self, server: Server) -> Server {
+        server
+            .http2_keepalive_interval(self.keepalive_interval)
+            .http2_keepalive_timeout(self.keepalive_timeout)
+            .http2_adaptive_window(self.adaptive_window)
+            .max_concurrent_streams(self.max_concurrent_streams)
+    }
+}
+
 pub async fn run_grpc_server<S>(
     address: SocketAddr,
     tls_settings: MaybeTlsSettings,
+    http2: Http2Settings,
     service: S,
     shutdown: ShutdownSignal,
 ) -> crate::Result<()>
@@ -45,7 +66,8 @@ where
 
     info!(%address, "Building gRPC server.");
 
-    Server::builder()
+    http2
+        .apply(Server::builder())
         .layer(build_grpc_trace_layer(span.clone()))
         // This layer explicitly decompresses payloads, if compressed, and reports the number of message bytes we've
         // received if the message is processed successfully, aka `BytesReceived`. We do this because otherwise the only
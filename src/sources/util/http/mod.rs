@@ -8,6 +8,8 @@ mod encoding;
 mod headers;
 mod method;
 #[cfg(feature = "sources-utils-http-prelude")]
+mod overload;
+#[cfg(feature = "sources-utils-http-prelude")]
 mod prelude;
 #[cfg(any(
     feature = "sources-http_server",
@@ -22,6 +24,8 @@ pub use encoding::decode;
 pub use headers::add_headers;
 pub use method::HttpMethod;
 #[cfg(feature = "sources-utils-http-prelude")]
+pub use overload::OverloadConfig;
+#[cfg(feature = "sources-utils-http-prelude")]
 pub use prelude::HttpSource;
 #[cfg(feature = "sources-utils-http-query")]
 pub use query::add_query_parameters;
@@ -0,0 +1,273 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use vector_lib::configurable::configurable_component;
+
+use crate::SourceSender;
+
+/// The minimum amount of time between two measurements of the buffer drain rate.
+const DRAIN_RATE_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The weighting of new drain rate measurements compared to older measurements.
+const DRAIN_RATE_EWMA_ALPHA: f64 = 0.3;
+
+/// Configuration for rejecting requests while the source is overloaded.
+///
+/// The source is overloaded when the components downstream of it can't keep up, and events
+/// accumulate in its buffer. While overloaded, requests are rejected with a `429 Too Many Requests`
+/// response, with a `Retry-After` header estimating how long it takes for the buffer to drain, so
+/// that clients back off instead of having their requests time out.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OverloadConfig {
+    /// Whether to reject requests while the source is overloaded.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// The fraction of the buffer above which requests are rejected.
+    ///
+    /// Valid values are between `0` and `1`.
+    #[configurable(validation(range(min = 0.0, max = 1.0)))]
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+
+    /// The minimum delay, in seconds, sent in the `Retry-After` header.
+    #[serde(default = "default_min_retry_after_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Minimum Retry After"))]
+    pub min_retry_after_secs: u64,
+
+    /// The maximum delay, in seconds, sent in the `Retry-After` header.
+    ///
+    /// This is also the delay sent before the drain rate of the buffer is known, or while the
+    /// buffer isn't draining at all.
+    #[serde(default = "default_max_retry_after_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Maximum Retry After"))]
+    pub max_retry_after_secs: u64,
+}
+
+const fn default_threshold() -> f64 {
+    0.9
+}
+
+const fn default_min_retry_after_secs() -> u64 {
+    1
+}
+
+const fn default_max_retry_after_secs() -> u64 {
+    30
+}
+
+impl Default for OverloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: default_threshold(),
+            min_retry_after_secs: default_min_retry_after_secs(),
+            max_retry_after_secs: default_max_retry_after_secs(),
+        }
+    }
+}
+
+impl OverloadConfig {
+    /// Builds the detector for the given source output, or `None` if not enabled.
+    pub(super) fn build(&self, out: SourceSender) -> crate::Result<Option<OverloadDetector>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err("`overload.threshold` must be between 0 and 1".into());
+        }
+        if self.min_retry_after_secs > self.max_retry_after_secs {
+            return Err(
+                "`overload.min_retry_after_secs` must not be greater than `overload.max_retry_after_secs`"
+                    .into(),
+            );
+        }
+
+        Ok(Some(OverloadDetector {
+            threshold: self.threshold,
+            min_retry_after: Duration::from_secs(self.min_retry_after_secs),
+            max_retry_after: Duration::from_secs(self.max_retry_after_secs),
+            out,
+            drain_rate: Arc::new(Mutex::new(DrainRate::new(Instant::now()))),
+        }))
+    }
+}
+
+/// Detects when the buffer of a source output fills past the configured threshold, and estimates
+/// how long it takes to drain.
+#[derive(Clone, Debug)]
+pub(super) struct OverloadDetector {
+    threshold: f64,
+    min_retry_after: Duration,
+    max_retry_after: Duration,
+    out: SourceSender,
+    drain_rate: Arc<Mutex<DrainRate>>,
+}
+
+impl OverloadDetector {
+    /// Returns the delay after which the client should retry if the source is overloaded, or
+    /// `None` if the request should be accepted.
+    pub(super) fn check(&self) -> Option<Duration> {
+        let (buffered, capacity) = self.out.default_output_buffer_usage()?;
+        self.check_at(Instant::now(), buffered, capacity)
+    }
+
+    /// Records events sent to the buffer, which is required to measure its drain rate.
+    pub(super) fn record_sent(&self, count: usize) {
+        self.drain_rate.lock().expect("mutex poisoned").sent += count;
+    }
+
+    fn check_at(&self, now: Instant, buffered: usize, capacity: usize) -> Option<Duration> {
+        let rate = {
+            let mut drain_rate = self.drain_rate.lock().expect("mutex poisoned");
+            drain_rate.update(now, buffered);
+            drain_rate.rate
+        };
+
+        if capacity == 0 || (buffered as f64 / capacity as f64) <= self.threshold {
+            return None;
+        }
+
+        let retry_after = rate
+            .filter(|rate| *rate > 0.0)
+            .map_or(self.max_retry_after, |rate| {
+                Duration::from_secs_f64((buffered as f64 / rate).ceil())
+            });
+        Some(retry_after.clamp(self.min_retry_after, self.max_retry_after))
+    }
+}
+
+/// Measures how fast events are drained from a buffer, from the events buffered and the events
+/// sent to it since the previous measurement.
+#[derive(Debug)]
+struct DrainRate {
+    sampled_at: Instant,
+    buffered: usize,
+    sent: usize,
+    /// The exponentially weighted moving average of the drain rate, in events per second.
+    rate: Option<f64>,
+}
+
+impl DrainRate {
+    const fn new(now: Instant) -> Self {
+        Self {
+            sampled_at: now,
+            buffered: 0,
+            sent: 0,
+            rate: None,
+        }
+    }
+
+    fn update(&mut self, now: Instant, buffered: usize) {
+        let elapsed = now.saturating_duration_since(self.sampled_at);
+        if elapsed < DRAIN_RATE_SAMPLE_INTERVAL {
+            return;
+        }
+
+        let drained = (self.buffered + self.sent).saturating_sub(buffered);
+        let rate = drained as f64 / elapsed.as_secs_f64();
+        self.rate = Some(match self.rate {
+            Some(previous) => previous + DRAIN_RATE_EWMA_ALPHA * (rate - previous),
+            None => rate,
+        });
+
+        self.sampled_at = now;
+        self.buffered = buffered;
+        self.sent = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(threshold: f64) -> (OverloadDetector, Instant) {
+        let (out, _recv) = SourceSender::new_test_sender_with_buffer(1);
+        let detector = OverloadConfig {
+            enabled: true,
+            threshold,
+            min_retry_after_secs: 2,
+            max_retry_after_secs: 30,
+        }
+        .build(out)
+        .unwrap()
+        .unwrap();
+        let start = detector.drain_rate.lock().unwrap().sampled_at;
+        (detector, start)
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let (out, _recv) = SourceSender::new_test_sender_with_buffer(1);
+        assert!(OverloadConfig::default().build(out).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_config() {
+        let (out, _recv) = SourceSender::new_test_sender_with_buffer(1);
+        let config = OverloadConfig {
+            enabled: true,
+            min_retry_after_secs: 60,
+            ..Default::default()
+        };
+        assert!(config.build(out).is_err());
+    }
+
+    #[test]
+    fn accepts_below_threshold() {
+        let (detector, now) = detector(0.5);
+        assert_eq!(detector.check_at(now, 0, 100), None);
+        assert_eq!(detector.check_at(now, 50, 100), None);
+    }
+
+    #[test]
+    fn max_retry_after_until_drain_rate_is_known() {
+        let (detector, now) = detector(0.5);
+        assert_eq!(
+            detector.check_at(now, 80, 100),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn retry_after_from_drain_rate() {
+        let (detector, start) = detector(0.5);
+
+        // 100 events are sent to the buffer, of which 20 are drained within two seconds.
+        detector.record_sent(100);
+        assert_eq!(
+            detector.check_at(start + Duration::from_secs(2), 80, 100),
+            Some(Duration::from_secs(8))
+        );
+
+        // The buffer stops draining, so the estimated drain rate decreases.
+        assert_eq!(
+            detector.check_at(start + Duration::from_secs(4), 80, 100),
+            Some(Duration::from_secs(12))
+        );
+    }
+
+    #[test]
+    fn retry_after_is_clamped() {
+        let (detector, start) = detector(0.5);
+
+        // The buffer drains within a fraction of a second.
+        detector.record_sent(10000);
+        assert_eq!(
+            detector.check_at(start + Duration::from_secs(1), 600, 1000),
+            Some(Duration::from_secs(2))
+        );
+
+        // The buffer isn't draining at all.
+        assert_eq!(
+            detector.check_at(start + Duration::from_secs(60), 600, 1000),
+            Some(Duration::from_secs(30))
+        );
+    }
+}
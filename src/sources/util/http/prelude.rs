@@ -1,7 +1,17 @@
-use std::{collections::HashMap, convert::Infallible, fmt, net::SocketAddr, time::Duration};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt, io,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
-use bytes::Bytes;
-use futures::{FutureExt, TryFutureExt};
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{FutureExt, Stream, StreamExt, TryFutureExt, TryStreamExt, stream::BoxStream};
 use hyper::{Server, service::make_service_fn};
 use tokio::net::TcpStream;
 use tower::ServiceBuilder;
@@ -10,6 +20,7 @@ use vector_lib::{
     EstimatedJsonEncodedSizeOf,
     config::SourceAcknowledgementsConfig,
     event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event},
+    finalization::AddBatchNotifier,
 };
 use warp::{
     Filter,
@@ -19,9 +30,13 @@ use warp::{
     },
     http::{HeaderMap, StatusCode},
     reject::Rejection,
+    reply::Reply,
 };
 
-use super::encoding::decode;
+use super::{
+    encoding::decode,
+    overload::{OverloadConfig, OverloadDetector},
+};
 use crate::{
     SourceSender,
     common::http::{ErrorMessage, server_auth::HttpServerAuthConfig},
@@ -34,6 +49,9 @@ use crate::{
     tls::{MaybeTlsIncomingStream, MaybeTlsSettings, TlsEnableableConfig},
 };
 
+/// The body of a request, as it is received.
+pub type BodyStream = BoxStream<'static, io::Result<Bytes>>;
+
 pub trait HttpSource: Clone + Send + Sync + 'static {
     // This function can be defined to enrich events with additional HTTP
     // metadata. This function should be used rather than internal enrichment so
@@ -56,6 +74,29 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         path: &str,
     ) -> Result<Vec<Event>, ErrorMessage>;
 
+    // This function can be defined to build the events of the requests it returns `true` for
+    // while their body is received, with `build_event_stream`, rather than once it is buffered.
+    // The events are sent downstream as they are built.
+    fn stream_body(&self, _header_map: &HeaderMap) -> bool {
+        false
+    }
+
+    // This function must be defined along with `stream_body`. Otherwise, the requests whose body
+    // is streamed get an error response.
+    fn build_event_stream(
+        &self,
+        _body: BodyStream,
+        _header_map: &HeaderMap,
+    ) -> BoxStream<'static, Result<Vec<Event>, ErrorMessage>> {
+        futures::stream::once(async {
+            Err(ErrorMessage::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "The source can't stream the body of requests.".to_owned(),
+            ))
+        })
+        .boxed()
+    }
+
     fn decode(&self, encoding_header: Option<&str>, body: Bytes) -> Result<Bytes, ErrorMessage> {
         decode(encoding_header, body)
     }
@@ -80,6 +121,11 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         let path = path.to_owned();
         let acknowledgements = cx.do_acknowledgements(acknowledgements);
         let enable_source_ip = self.enable_source_ip();
+        let overload = self
+            .overload()
+            .map(|config| config.build(cx.out.clone()))
+            .transpose()?
+            .flatten();
 
         Ok(Box::pin(async move {
             let mut filter: BoxedFilter<()> = match method {
@@ -113,69 +159,178 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                     }
                 })
                 .untuple_one()
+                .and_then({
+                    let overload = overload.clone();
+                    move || {
+                        let retry_after = overload.as_ref().and_then(OverloadDetector::check);
+                        async move {
+                            match retry_after {
+                                Some(retry_after) => {
+                                    debug!(
+                                        message = "Rejecting request, source is overloaded.",
+                                        retry_after_secs = retry_after.as_secs(),
+                                    );
+                                    Err(warp::reject::custom(RejectOverloaded { retry_after }))
+                                }
+                                None => Ok(()),
+                            }
+                        }
+                    }
+                })
+                .untuple_one()
                 .and(warp::path::full())
                 .and(warp::header::optional::<String>("content-encoding"))
                 .and(warp::header::headers_cloned())
-                .and(warp::body::bytes())
+                .and(warp::body::stream())
                 .and(warp::query::<HashMap<String, String>>())
                 .and(warp::filters::ext::optional())
                 .and_then(
                     move |path: FullPath,
                           encoding_header: Option<String>,
                           headers: HeaderMap,
-                          body: Bytes,
+                          body,
                           query_parameters: HashMap<String, String>,
                           addr: Option<PeerAddr>| {
                         debug!(message = "Handling HTTP request.", headers = ?headers);
-                        let http_path = path.as_str();
-                        let events = auth_matcher
-                            .as_ref()
-                            .map_or(Ok(()), |a| {
-                                a.handle_auth(
-                                    addr.as_ref().map(|a| a.0).as_ref(),
-                                    &headers,
-                                    path.as_str(),
-                                )
-                            })
-                            .and_then(|()| self.decode(encoding_header.as_deref(), body))
-                            .and_then(|body| {
-                                emit!(HttpBytesReceived {
-                                    byte_size: body.len(),
-                                    http_path,
-                                    protocol,
-                                });
-                                self.build_events(body, &headers, &query_parameters, path.as_str())
-                            })
-                            .map(|mut events| {
-                                emit!(HttpEventsReceived {
-                                    count: events.len(),
-                                    byte_size: events.estimated_json_encoded_size_of(),
-                                    http_path,
-                                    protocol,
-                                });
+                        let auth = auth_matcher.as_ref().map_or(Ok(()), |a| {
+                            a.handle_auth(
+                                addr.as_ref().map(|a| a.0).as_ref(),
+                                &headers,
+                                path.as_str(),
+                            )
+                        });
+                        let source_ip = addr
+                            .and_then(|a| enable_source_ip.then_some(a))
+                            .map(|PeerAddr(inner_addr)| inner_addr);
+                        let too_large = Arc::new(AtomicBool::new(false));
+                        let body = limit_body(body, self.max_body_size(), Arc::clone(&too_large));
+                        let source = self.clone();
+                        let out = cx.out.clone();
+                        let overload = overload.clone();
+
+                        async move {
+                            let http_path = path.as_str();
 
-                                self.enrich_events(
-                                    &mut events,
-                                    path.as_str(),
+                            if auth.is_ok() && source.stream_body(&headers) {
+                                // The compressed bodies are decompressed once they're received.
+                                let body = match encoding_header {
+                                    None => {
+                                        let http_path = http_path.to_owned();
+                                        body.inspect_ok(move |chunk| {
+                                            emit!(HttpBytesReceived {
+                                                byte_size: chunk.len(),
+                                                http_path: &http_path,
+                                                protocol,
+                                            });
+                                        })
+                                        .boxed()
+                                    }
+                                    Some(encoding_header) => {
+                                        let body = collect_body(body, &too_large)
+                                            .await
+                                            .and_then(|body| {
+                                                source.decode(Some(&encoding_header), body)
+                                            })
+                                            .inspect(|body| {
+                                                emit!(HttpBytesReceived {
+                                                    byte_size: body.len(),
+                                                    http_path,
+                                                    protocol,
+                                                });
+                                            });
+                                        match body {
+                                            Ok(body) => futures::stream::iter([Ok(body)]).boxed(),
+                                            Err(error) => {
+                                                return handle_request(
+                                                    Err(error),
+                                                    acknowledgements,
+                                                    response_code,
+                                                    out,
+                                                    overload,
+                                                )
+                                                .await;
+                                            }
+                                        }
+                                    }
+                                };
+                                let events = source.build_event_stream(body, &headers);
+                                return handle_streaming_request(
+                                    &source,
+                                    events,
+                                    &too_large,
+                                    http_path,
                                     &headers,
                                     &query_parameters,
-                                    addr.and_then(|a| enable_source_ip.then_some(a))
-                                        .map(|PeerAddr(inner_addr)| inner_addr)
-                                        .as_ref(),
-                                );
+                                    source_ip.as_ref(),
+                                    protocol,
+                                    acknowledgements,
+                                    response_code,
+                                    out,
+                                    overload,
+                                )
+                                .await;
+                            }
+
+                            let body = match auth {
+                                Ok(()) => collect_body(body, &too_large).await,
+                                Err(error) => Err(error),
+                            };
+                            let events = body
+                                .and_then(|body| source.decode(encoding_header.as_deref(), body))
+                                .and_then(|body| {
+                                    emit!(HttpBytesReceived {
+                                        byte_size: body.len(),
+                                        http_path,
+                                        protocol,
+                                    });
+                                    source.build_events(
+                                        body,
+                                        &headers,
+                                        &query_parameters,
+                                        http_path,
+                                    )
+                                })
+                                .map(|mut events| {
+                                    emit!(HttpEventsReceived {
+                                        count: events.len(),
+                                        byte_size: events.estimated_json_encoded_size_of(),
+                                        http_path,
+                                        protocol,
+                                    });
 
-                                events
-                            });
+                                    source.enrich_events(
+                                        &mut events,
+                                        http_path,
+                                        &headers,
+                                        &query_parameters,
+                                        source_ip.as_ref(),
+                                    );
 
-                        handle_request(events, acknowledgements, response_code, cx.out.clone())
+                                    events
+                                });
+
+                            handle_request(events, acknowledgements, response_code, out, overload)
+                                .await
+                        }
                     },
                 );
 
             let ping = warp::get().and(warp::path("ping")).map(|| "pong");
             let routes = svc.or(ping).recover(|r: Rejection| async move {
-                if let Some(e_msg) = r.find::<ErrorMessage>() {
+                if let Some(overloaded) = r.find::<RejectOverloaded>() {
+                    let json = warp::reply::json(&ErrorMessage::new(
+                        StatusCode::TOO_MANY_REQUESTS,
+                        "Source is overloaded".to_string(),
+                    ));
+                    Ok(warp::reply::with_header(
+                        warp::reply::with_status(json, StatusCode::TOO_MANY_REQUESTS),
+                        "retry-after",
+                        overloaded.retry_after.as_secs(),
+                    )
+                    .into_response())
+                } else if let Some(e_msg) = r.find::<ErrorMessage>() {
                     let json = warp::reply::json(e_msg);
-                    Ok(warp::reply::with_status(json, e_msg.status_code()))
+                    Ok(warp::reply::with_status(json, e_msg.status_code()).into_response())
                 } else {
                     //other internal error - will return 500 internal server error
                     emit!(HttpInternalError {
@@ -227,6 +382,18 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
     fn enable_source_ip(&self) -> bool {
         false
     }
+
+    // This function can be defined to reject requests while the components downstream of the
+    // source can't keep up with it.
+    fn overload(&self) -> Option<&OverloadConfig> {
+        None
+    }
+
+    // This function can be defined to reject the requests whose body is larger than this size,
+    // before any decompression.
+    fn max_body_size(&self) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Clone)]
@@ -249,12 +416,126 @@ impl fmt::Debug for RejectShuttingDown {
 
 impl warp::reject::Reject for RejectShuttingDown {}
 
+#[derive(Debug)]
+struct RejectOverloaded {
+    retry_after: Duration,
+}
+
+impl warp::reject::Reject for RejectOverloaded {}
+
+/// Fails the body once it's larger than the maximum size, setting `too_large`.
+fn limit_body(
+    body: impl Stream<Item = Result<impl Buf, warp::Error>> + Send + 'static,
+    max_size: Option<usize>,
+    too_large: Arc<AtomicBool>,
+) -> BodyStream {
+    let mut size = 0;
+    body.map(move |chunk| {
+        let mut chunk = chunk.map_err(io::Error::other)?;
+        let chunk = chunk.copy_to_bytes(chunk.remaining());
+        size += chunk.len();
+        match max_size {
+            Some(max_size) if size > max_size => {
+                too_large.store(true, Ordering::Relaxed);
+                Err(io::Error::other(format!(
+                    "Body is larger than {max_size} bytes"
+                )))
+            }
+            _ => Ok(chunk),
+        }
+    })
+    .boxed()
+}
+
+async fn collect_body(body: BodyStream, too_large: &AtomicBool) -> Result<Bytes, ErrorMessage> {
+    body.try_fold(BytesMut::new(), |mut bytes, chunk| async move {
+        bytes.extend_from_slice(&chunk);
+        Ok(bytes)
+    })
+    .await
+    .map(BytesMut::freeze)
+    .map_err(|error| body_error(error.to_string(), too_large))
+}
+
+fn body_error(message: String, too_large: &AtomicBool) -> ErrorMessage {
+    if too_large.load(Ordering::Relaxed) {
+        ErrorMessage::new(StatusCode::PAYLOAD_TOO_LARGE, message)
+    } else {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Failed reading body: {message}"),
+        )
+    }
+}
+
+/// Sends the events of the request as they are built, and responds once the body is entirely
+/// received, and its events are delivered if acknowledgements are enabled.
+#[allow(clippy::too_many_arguments)]
+async fn handle_streaming_request(
+    source: &impl HttpSource,
+    mut events: BoxStream<'static, Result<Vec<Event>, ErrorMessage>>,
+    too_large: &AtomicBool,
+    http_path: &str,
+    headers: &HeaderMap,
+    query_parameters: &HashMap<String, String>,
+    source_ip: Option<&SocketAddr>,
+    protocol: &'static str,
+    acknowledgements: bool,
+    response_code: StatusCode,
+    mut out: SourceSender,
+    overload: Option<OverloadDetector>,
+) -> Result<StatusCode, Rejection> {
+    let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
+
+    while let Some(result) = events.next().await {
+        let mut events = match result {
+            Ok(events) => events,
+            Err(error) => {
+                // The errors of the body are reported by the decoders as decoding errors.
+                let error = if too_large.load(Ordering::Relaxed) {
+                    body_error(error.message().to_owned(), too_large)
+                } else {
+                    error
+                };
+                emit!(HttpBadRequest::new(error.code(), error.message()));
+                return Err(warp::reject::custom(error));
+            }
+        };
+
+        emit!(HttpEventsReceived {
+            count: events.len(),
+            byte_size: events.estimated_json_encoded_size_of(),
+            http_path,
+            protocol,
+        });
+        source.enrich_events(&mut events, http_path, headers, query_parameters, source_ip);
+        if let Some(batch) = &batch {
+            for event in &mut events {
+                event.add_batch_notifier(batch.clone());
+            }
+        }
+
+        let count = events.len();
+        out.send_batch(events).await.map_err(|_| {
+            emit!(StreamClosedError { count });
+            warp::reject::custom(RejectShuttingDown)
+        })?;
+        if let Some(overload) = &overload {
+            overload.record_sent(count);
+        }
+    }
+    drop(batch);
+
+    handle_batch_status(response_code, receiver).await
+}
+
 async fn handle_request(
     events: Result<Vec<Event>, ErrorMessage>,
     acknowledgements: bool,
     response_code: StatusCode,
     mut out: SourceSender,
-) -> Result<impl warp::Reply, Rejection> {
+    overload: Option<OverloadDetector>,
+) -> Result<StatusCode, Rejection> {
     match events {
         Ok(mut events) => {
             let receiver = BatchNotifier::maybe_apply_to(acknowledgements, &mut events);
@@ -267,6 +548,11 @@ async fn handle_request(
                     emit!(StreamClosedError { count });
                     warp::reject::custom(RejectShuttingDown)
                 })
+                .inspect_ok(|()| {
+                    if let Some(overload) = &overload {
+                        overload.record_sent(count);
+                    }
+                })
                 .and_then(|_| handle_batch_status(response_code, receiver))
                 .await
         }
@@ -280,7 +566,7 @@ async fn handle_request(
 async fn handle_batch_status(
     success_response_code: StatusCode,
     receiver: Option<BatchStatusReceiver>,
-) -> Result<impl warp::Reply, Rejection> {
+) -> Result<StatusCode, Rejection> {
     match receiver {
         None => Ok(success_response_code),
         Some(receiver) => match receiver.await {
@@ -11,10 +11,10 @@
 // Okta source only imports defaults but doesn't use the rest of the client
 #![cfg_attr(feature = "sources-okta", allow(dead_code))]
 
-use std::{collections::HashMap, future::ready, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use bytes::Bytes;
-use futures_util::{FutureExt, StreamExt, TryFutureExt, stream};
+use futures_util::{StreamExt, stream};
 use http::{Uri, response::Parts};
 use hyper::{Body, Request};
 use tokio_stream::wrappers::IntervalStream;
@@ -47,6 +47,8 @@ pub(crate) struct GenericHttpClientInputs {
     /// Content type of the HTTP request, determined by the source.
     pub content_type: String,
     pub auth: Option<Auth>,
+    /// Authorizes the HTTP requests with credentials obtained at runtime.
+    pub authorizer: Option<Arc<dyn RequestAuthorizer>>,
     pub tls: TlsSettings,
     pub proxy: ProxyConfig,
     pub shutdown: ShutdownSignal,
@@ -79,6 +81,12 @@ pub(crate) trait HttpClientContext {
     /// (Optional) Called if the HTTP response is not 200 ('OK').
     fn on_http_response_error(&self, _uri: &Uri, _header: &Parts) {}
 
+    /// (Optional) Called after a successful HTTP response with events to get the URL of the next
+    /// page, which is then requested right away. Returns None if this is the last page.
+    fn next_page(&mut self, _url: &Uri, _header: &Parts, _body: &Bytes) -> Option<Uri> {
+        None
+    }
+
     /// (Optional) Process the base URL before each request.
     /// Allows for dynamic query parameters that update at runtime.
     /// Returns a new URL if parameters need to be updated, or None to use the original URL.
@@ -92,6 +100,18 @@ pub(crate) trait HttpClientContext {
     fn enrich_events(&mut self, _events: &mut Vec<Event>) {}
 }
 
+/// Authorizes the HTTP requests with credentials obtained at runtime, such as OAuth2 access
+/// tokens, rather than with static credentials.
+#[async_trait::async_trait]
+pub(crate) trait RequestAuthorizer: Send + Sync {
+    /// Adds the credentials to the HTTP request, obtaining them first if needed.
+    async fn authorize(&self, request: &mut Request<Body>) -> crate::Result<()>;
+
+    /// (Optional) Called if the HTTP request was rejected as unauthorized, so that the
+    /// credentials are obtained again for the next request.
+    fn invalidate(&self) {}
+}
+
 /// Builds a url for the HTTP requests.
 pub(crate) fn build_url(uri: &Uri, query: &QueryParameters) -> Uri {
     let mut serializer = url::form_urlencoded::Serializer::new(String::new());
@@ -137,10 +157,82 @@ pub(crate) fn warn_if_interval_too_low(timeout: Duration, interval: Duration) {
     }
 }
 
+/// The inputs needed to send each HTTP request.
+struct RequestInputs {
+    timeout: Duration,
+    headers: HashMap<String, Vec<String>>,
+    content_type: String,
+    auth: Option<Auth>,
+    authorizer: Option<Arc<dyn RequestAuthorizer>>,
+    http_method: HttpMethod,
+}
+
+/// Sends an HTTP request to the url, returning the response once its body is read.
+async fn fetch(
+    client: &HttpClient,
+    inputs: &RequestInputs,
+    url: &Uri,
+    endpoint: &str,
+) -> crate::Result<(Parts, Bytes)> {
+    let mut builder = match inputs.http_method {
+        HttpMethod::Head => Request::head(url),
+        HttpMethod::Get => Request::get(url),
+        HttpMethod::Post => Request::post(url),
+        HttpMethod::Put => Request::put(url),
+        HttpMethod::Patch => Request::patch(url),
+        HttpMethod::Delete => Request::delete(url),
+        HttpMethod::Options => Request::options(url),
+    };
+
+    // add user specified headers
+    for (header, values) in &inputs.headers {
+        for value in values {
+            builder = builder.header(header, value);
+        }
+    }
+
+    // set ACCEPT header if not user specified
+    if !inputs.headers.contains_key(http::header::ACCEPT.as_str()) {
+        builder = builder.header(http::header::ACCEPT, &inputs.content_type);
+    }
+
+    // building an empty request should be infallible
+    let mut request = builder.body(Body::empty()).expect("error creating request");
+
+    if let Some(auth) = &inputs.auth {
+        auth.apply(&mut request);
+    }
+
+    let send = async {
+        if let Some(authorizer) = &inputs.authorizer {
+            authorizer.authorize(&mut request).await?;
+        }
+        Ok::<_, crate::Error>(client.send(request).await?)
+    };
+    let response = tokio::time::timeout(inputs.timeout, send)
+        .await
+        .map_err(|_| {
+            format!(
+                "Timeout error: request exceeded {}s",
+                inputs.timeout.as_secs_f64()
+            )
+        })??;
+
+    let (header, body) = response.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    emit!(EndpointBytesReceived {
+        byte_size: body.len(),
+        protocol: "http",
+        endpoint,
+    });
+    Ok((header, body))
+}
+
 /// Calls one or more urls at an interval.
 ///   - The HTTP request is built per the options in provided generic inputs.
 ///   - The HTTP response is decoded/parsed into events by the specific context.
 ///   - The events are then sent to the output stream.
+///   - If the context finds a next page in a response with events, it is requested right away.
 pub(crate) async fn call<
     B: HttpClientBuilder<Context = C> + Send + Clone,
     C: HttpClientContext + Send,
@@ -154,122 +246,105 @@ pub(crate) async fn call<
     // proxy and tls settings.
     let client =
         HttpClient::new(inputs.tls.clone(), &inputs.proxy).expect("Building HTTP client failed");
+    let request_inputs = Arc::new(RequestInputs {
+        timeout: inputs.timeout,
+        headers: inputs.headers,
+        content_type: inputs.content_type,
+        auth: inputs.auth,
+        authorizer: inputs.authorizer,
+        http_method,
+    });
+    let urls = inputs.urls;
     let mut stream = IntervalStream::new(tokio::time::interval(inputs.interval))
         .take_until(inputs.shutdown)
-        .map(move |_| stream::iter(inputs.urls.clone()))
+        .map(move |_| stream::iter(urls.clone()))
         .flatten()
         .map(move |base_url| {
             let client = client.clone();
+            let request_inputs = Arc::clone(&request_inputs);
             let endpoint = base_url.to_string();
 
             let context_builder = context_builder.clone();
-            let mut context = context_builder.build(&base_url);
+            let context = context_builder.build(&base_url);
 
             // Check if we need to process the URL dynamically (for updating VRL expressions)
             let url = context.process_url(&base_url).unwrap_or(base_url);
 
-            let mut builder = match http_method {
-                HttpMethod::Head => Request::head(&url),
-                HttpMethod::Get => Request::get(&url),
-                HttpMethod::Post => Request::post(&url),
-                HttpMethod::Put => Request::put(&url),
-                HttpMethod::Patch => Request::patch(&url),
-                HttpMethod::Delete => Request::delete(&url),
-                HttpMethod::Options => Request::options(&url),
-            };
-
-            // add user specified headers
-            for (header, values) in &inputs.headers {
-                for value in values {
-                    builder = builder.header(header, value);
-                }
-            }
-
-            // set ACCEPT header if not user specified
-            if !inputs.headers.contains_key(http::header::ACCEPT.as_str()) {
-                builder = builder.header(http::header::ACCEPT, &inputs.content_type);
-            }
+            // The pages are requested one after the other, as each page gives the url of the
+            // next one.
+            stream::unfold(Some((context, url)), move |state| {
+                let client = client.clone();
+                let request_inputs = Arc::clone(&request_inputs);
+                let endpoint = endpoint.clone();
+                async move {
+                    let (mut context, url) = state?;
+                    let (events, next_page) =
+                        match fetch(&client, &request_inputs, &url, &endpoint).await {
+                            Ok((header, body)) if header.status == hyper::StatusCode::OK => {
+                                match context.on_response(&url, &header, &body) {
+                                    Some(mut events) => {
+                                        let byte_size = if events.is_empty() {
+                                            // We need to explicitly set the byte size to 0
+                                            // since `estimated_json_encoded_size_of` returns
+                                            // at least 1 for an empty collection. For the
+                                            // purposes of the HttpClientEventsReceived event,
+                                            // we should emit 0 when there aren't any usable
+                                            // metrics.
+                                            JsonSize::zero()
+                                        } else {
+                                            events.estimated_json_encoded_size_of()
+                                        };
 
-            // building an empty request should be infallible
-            let mut request = builder.body(Body::empty()).expect("error creating request");
+                                        emit!(HttpClientEventsReceived {
+                                            byte_size,
+                                            count: events.len(),
+                                            url: url.to_string()
+                                        });
 
-            if let Some(auth) = &inputs.auth {
-                auth.apply(&mut request);
-            }
+                                        // We'll enrich after receiving the events so
+                                        // that the byte sizes are accurate.
+                                        context.enrich_events(&mut events);
 
-            tokio::time::timeout(inputs.timeout, client.send(request))
-                .then(move |result| async move {
-                    match result {
-                        Ok(Ok(response)) => Ok(response),
-                        Ok(Err(error)) => Err(error.into()),
-                        Err(_) => Err(format!(
-                            "Timeout error: request exceeded {}s",
-                            inputs.timeout.as_secs_f64()
-                        )
-                        .into()),
-                    }
-                })
-                .and_then(|response| async move {
-                    let (header, body) = response.into_parts();
-                    let body = hyper::body::to_bytes(body).await?;
-                    emit!(EndpointBytesReceived {
-                        byte_size: body.len(),
-                        protocol: "http",
-                        endpoint: endpoint.as_str(),
-                    });
-                    Ok((header, body))
-                })
-                .into_stream()
-                .filter_map(move |response| {
-                    ready(match response {
-                        Ok((header, body)) if header.status == hyper::StatusCode::OK => {
-                            context.on_response(&url, &header, &body).map(|mut events| {
-                                let byte_size = if events.is_empty() {
-                                    // We need to explicitly set the byte size
-                                    // to 0 since
-                                    // `estimated_json_encoded_size_of` returns
-                                    // at least 1 for an empty collection. For
-                                    // the purposes of the
-                                    // HttpClientEventsReceived event, we should
-                                    // emit 0 when there aren't any usable
-                                    // metrics.
-                                    JsonSize::zero()
-                                } else {
-                                    events.estimated_json_encoded_size_of()
-                                };
-
-                                emit!(HttpClientEventsReceived {
-                                    byte_size,
-                                    count: events.len(),
+                                        // An empty page is the last one.
+                                        let next_page = if events.is_empty() {
+                                            None
+                                        } else {
+                                            context.next_page(&url, &header, &body)
+                                        };
+                                        (events, next_page)
+                                    }
+                                    None => (Vec::new(), None),
+                                }
+                            }
+                            Ok((header, _)) => {
+                                if header.status == hyper::StatusCode::UNAUTHORIZED
+                                    && let Some(authorizer) = &request_inputs.authorizer
+                                {
+                                    authorizer.invalidate();
+                                }
+                                context.on_http_response_error(&url, &header);
+                                emit!(HttpClientHttpResponseError {
+                                    code: header.status,
+                                    url: url.to_string(),
+                                });
+                                (Vec::new(), None)
+                            }
+                            Err(error) => {
+                                emit!(HttpClientHttpError {
+                                    error,
                                     url: url.to_string()
                                 });
-
-                                // We'll enrich after receiving the events so
-                                // that the byte sizes are accurate.
-                                context.enrich_events(&mut events);
-
-                                stream::iter(events)
-                            })
-                        }
-                        Ok((header, _)) => {
-                            context.on_http_response_error(&url, &header);
-                            emit!(HttpClientHttpResponseError {
-                                code: header.status,
-                                url: url.to_string(),
-                            });
-                            None
-                        }
-                        Err(error) => {
-                            emit!(HttpClientHttpError {
-                                error,
-                                url: url.to_string()
-                            });
-                            None
-                        }
-                    })
-                })
-                .flatten()
-                .boxed()
+                                (Vec::new(), None)
+                            }
+                        };
+                    Some((
+                        stream::iter(events),
+                        next_page.map(|next_url| (context, next_url)),
+                    ))
+                }
+            })
+            .flatten()
+            .boxed()
         })
         .flatten_unordered(None)
         .boxed();
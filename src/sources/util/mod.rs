@@ -5,7 +5,11 @@ mod body_decoding;
 mod encoding_config;
 #[cfg(all(unix, feature = "sources-dnstap"))]
 pub mod framestream;
-#[cfg(any(feature = "sources-vector", feature = "sources-opentelemetry"))]
+#[cfg(any(
+    feature = "sources-grpc_server",
+    feature = "sources-opentelemetry",
+    feature = "sources-vector"
+))]
 pub mod grpc;
 #[cfg(any(
     feature = "sources-utils-http-auth",
@@ -30,6 +34,12 @@ mod message_decoding;
 pub mod multiline_config;
 #[cfg(any(feature = "sources-utils-net-tcp", feature = "sources-utils-net-udp"))]
 pub mod net;
+#[cfg(any(
+    feature = "sources-aws_s3",
+    feature = "sources-azure_blob",
+    feature = "sources-gcp_cloud_storage"
+))]
+pub mod object_storage;
 #[cfg(all(
     unix,
     any(feature = "sources-socket", feature = "sources-utils-net-unix",)
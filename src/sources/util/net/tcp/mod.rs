@@ -1,11 +1,12 @@
 pub mod request_limiter;
 
-use std::{io, mem::drop, net::SocketAddr, time::Duration};
+use std::{io, mem::drop, time::Duration};
 
 use bytes::Bytes;
 use futures::{FutureExt, StreamExt, future::BoxFuture};
 use futures_util::future::OptionFuture;
 use ipnet::IpNet;
+use itertools::Itertools;
 use listenfd::ListenFd;
 use smallvec::SmallVec;
 use socket2::SockRef;
@@ -38,6 +39,7 @@ use crate::{
         TcpSocketTlsConnectionError,
     },
     shutdown::ShutdownSignal,
+    source_sender::ClosedError,
     sources::util::AfterReadExt,
     tcp::TcpKeepaliveConfig,
     tls::{CertificateMetadata, MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
@@ -106,6 +108,11 @@ where
 
     fn handle_events(&self, _events: &mut [Event], _host: std::net::SocketAddr) {}
 
+    /// The named output the event is sent to, instead of the default output.
+    fn event_output(&self, _event: &Event) -> Option<&'static str> {
+        None
+    }
+
     fn build_acker(&self, item: &[Self::Item]) -> Self::Acker;
 
     #[allow(clippy::too_many_arguments)]
@@ -122,6 +129,7 @@ where
         acknowledgements: SourceAcknowledgementsConfig,
         max_connections: Option<u32>,
         allowlist: Option<Vec<IpNet>>,
+        proxy_protocol: bool,
         source_name: &'static str,
         log_namespace: LogNamespace,
     ) -> crate::Result<crate::sources::Source> {
@@ -136,7 +144,8 @@ where
                         mode: SocketMode::Tcp,
                         error: &error,
                     })
-                })?;
+                })?
+                .with_proxy_protocol(proxy_protocol);
 
             info!(
                 message = "Listening.",
@@ -209,7 +218,6 @@ where
                                 max_connection_duration_secs,
                                 source,
                                 tripwire,
-                                peer_addr,
                                 out,
                                 acknowledgements,
                                 request_limiter,
@@ -243,7 +251,6 @@ async fn handle_stream<T>(
     max_connection_duration_secs: Option<u64>,
     source: T,
     mut tripwire: BoxFuture<'static, ()>,
-    peer_addr: SocketAddr,
     mut out: SourceSender,
     acknowledgements: bool,
     request_limiter: RequestLimiter,
@@ -266,6 +273,9 @@ async fn handle_stream<T>(
         }
     };
 
+    // The address of the client, once the PROXY protocol header, if any, is read.
+    let peer_addr = socket.peer_addr();
+
     if let Some(keepalive) = keepalive
         && let Err(error) = socket.set_keepalive(keepalive)
     {
@@ -384,7 +394,7 @@ async fn handle_stream<T>(
                         }
 
                         source.handle_events(&mut events, peer_addr);
-                        match out.send_batch(events).await {
+                        match send_events(&source, &mut out, events).await {
                             Ok(_) => {
                                 let ack = match receiver {
                                     None => TcpSourceAck::Ack,
@@ -434,6 +444,35 @@ async fn handle_stream<T>(
     }
 }
 
+/// Sends the events to the default output, or to the named output given by the source.
+async fn send_events<T: TcpSource>(
+    source: &T,
+    out: &mut SourceSender,
+    events: Vec<Event>,
+) -> Result<(), ClosedError>
+where
+    <<T as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
+{
+    if !events
+        .iter()
+        .any(|event| source.event_output(event).is_some())
+    {
+        return out.send_batch(events).await;
+    }
+
+    let (named, events): (Vec<_>, Vec<_>) = events
+        .into_iter()
+        .partition(|event| source.event_output(event).is_some());
+    out.send_batch(events).await?;
+    for (output, events) in named
+        .into_iter()
+        .into_group_map_by(|event| source.event_output(event).unwrap_or_default())
+    {
+        out.send_batch_named(output, events).await?;
+    }
+    Ok(())
+}
+
 fn close_socket(socket: &MaybeTlsIncomingStream<TcpStream>) -> bool {
     debug!("Start graceful shutdown.");
     // Close our write part of TCP socket to signal the other side
@@ -0,0 +1,612 @@
+//! Building blocks shared by the sources consuming objects from object storage services.
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use async_compression::tokio::bufread;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Future, Stream, StreamExt, future::ready, stream, stream::BoxStream};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use snafu::{ResultExt, Snafu};
+use tokio_util::{codec::FramedRead, io::StreamReader};
+use vector_lib::{
+    codecs::decoding::FramingError,
+    config::{LogNamespace, log_schema},
+    configurable::configurable_component,
+    event::MaybeAsLogMut,
+    internal_event::{
+        ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Registered,
+    },
+    lookup::{PathPrefix, metadata_path, path},
+};
+
+use crate::{
+    SourceSender,
+    codecs::Decoder,
+    event::{BatchNotifier, BatchStatusReceiver, EstimatedJsonEncodedSizeOf, Event, LogEvent},
+    internal_events::{
+        EventsReceived, ObjectCheckpointWriteError, ObjectProcessingError, StreamClosedError,
+    },
+    line_agg::{self, LineAgg},
+    shutdown::ShutdownSignal,
+};
+
+const CHECKPOINTS_FILE_NAME: &str = "checkpoints.json";
+const TMP_CHECKPOINTS_FILE_NAME: &str = "checkpoints.new.json";
+
+/// Compression scheme for objects retrieved from object storage.
+#[configurable_component]
+#[configurable(metadata(docs::advanced))]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+pub enum Compression {
+    /// Automatically attempt to determine the compression scheme.
+    ///
+    /// The compression scheme of the object is determined from its `Content-Encoding` and
+    /// `Content-Type` metadata, as well as the key suffix (for example, `.gz`).
+    ///
+    /// It is set to `none` if the compression scheme cannot be determined.
+    #[derivative(Default)]
+    Auto,
+
+    /// Uncompressed.
+    None,
+
+    /// GZIP.
+    Gzip,
+
+    /// ZSTD.
+    Zstd,
+}
+
+/// Decompresses the body of an object.
+///
+/// The reader is empty if the body is.
+pub async fn object_decoder(
+    compression: Compression,
+    key: &str,
+    content_encoding: Option<&str>,
+    content_type: Option<&str>,
+    mut body: BoxStream<'static, io::Result<Bytes>>,
+) -> Box<dyn tokio::io::AsyncRead + Send + Unpin> {
+    let first = match body.next().await {
+        Some(first) => first,
+        _ => {
+            return Box::new(tokio::io::empty());
+        }
+    };
+
+    let r = tokio::io::BufReader::new(StreamReader::new(stream::iter(Some(first)).chain(body)));
+
+    let compression = match compression {
+        Auto => determine_compression(content_encoding, content_type, key).unwrap_or(None),
+        _ => compression,
+    };
+
+    use Compression::*;
+    match compression {
+        Auto => unreachable!(), // is mapped above
+        None => Box::new(r),
+        Gzip => Box::new({
+            let mut decoder = bufread::GzipDecoder::new(r);
+            decoder.multiple_members(true);
+            decoder
+        }),
+        Zstd => Box::new({
+            let mut decoder = bufread::ZstdDecoder::new(r);
+            decoder.multiple_members(true);
+            decoder
+        }),
+    }
+}
+
+// try to determine the compression given the:
+// * content-encoding
+// * content-type
+// * key name (for file extension)
+//
+// It will use this information in this order
+fn determine_compression(
+    content_encoding: Option<&str>,
+    content_type: Option<&str>,
+    key: &str,
+) -> Option<Compression> {
+    content_encoding
+        .and_then(content_encoding_to_compression)
+        .or_else(|| content_type.and_then(content_type_to_compression))
+        .or_else(|| object_key_to_compression(key))
+}
+
+fn content_encoding_to_compression(content_encoding: &str) -> Option<Compression> {
+    match content_encoding {
+        "gzip" => Some(Compression::Gzip),
+        "zstd" => Some(Compression::Zstd),
+        _ => None,
+    }
+}
+
+fn content_type_to_compression(content_type: &str) -> Option<Compression> {
+    match content_type {
+        "application/gzip" | "application/x-gzip" => Some(Compression::Gzip),
+        "application/zstd" => Some(Compression::Zstd),
+        _ => None,
+    }
+}
+
+fn object_key_to_compression(key: &str) -> Option<Compression> {
+    let extension = std::path::Path::new(key)
+        .extension()
+        .and_then(std::ffi::OsStr::to_str);
+
+    use Compression::*;
+    extension.and_then(|extension| match extension {
+        "gz" => Some(Gzip),
+        "zst" => Some(Zstd),
+        _ => Option::None,
+    })
+}
+
+#[derive(Debug, Snafu)]
+pub enum SendObjectError {
+    #[snafu(display("Failed to read the object: {}", source))]
+    ReadObject { source: Box<dyn FramingError> },
+    #[snafu(display("Failed to flush the events of the object: {}", source))]
+    PipelineSend {
+        source: crate::source_sender::ClosedError,
+    },
+}
+
+/// Decodes the events of an object and sends them downstream.
+///
+/// Each log event is passed to `enrich` to add the metadata of the object. If acknowledgements
+/// are enabled, the receiver of the status of the sent events is returned.
+#[allow(clippy::too_many_arguments)]
+pub async fn send_object_events<F>(
+    out: &mut SourceSender,
+    decoder: &Decoder,
+    multiline: Option<&line_agg::Config>,
+    object_reader: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
+    acknowledgements: bool,
+    bytes_received: &Registered<BytesReceived>,
+    events_received: &Registered<EventsReceived>,
+    enrich: F,
+) -> Result<Option<BatchStatusReceiver>, SendObjectError>
+where
+    F: Fn(&mut LogEvent) + Send + Sync,
+{
+    let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
+
+    // Record the read error seen to propagate up later so we avoid ack'ing the object
+    //
+    // String is used as we cannot clone std::io::Error to take ownership in closure
+    //
+    // FramedRead likely stops when it gets an i/o error but I found it more clear to
+    // show that we `take_while` there hasn't been an error
+    //
+    // This can result in objects being partially processed before an error, but we
+    // prefer duplicate lines over message loss. Future work could include recording
+    // the offset of the object that has been read, but this would only be relevant in
+    // the case that the same vector instance processes the same message.
+    let mut read_error = None;
+    let bytes_received = bytes_received.clone();
+    let events_received = events_received.clone();
+    let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
+        FramedRead::new(object_reader, decoder.framer.clone())
+            .map(|res| {
+                res.inspect(|bytes| {
+                    bytes_received.emit(ByteSize(bytes.len()));
+                })
+                .map_err(|err| {
+                    read_error = Some(err);
+                })
+                .ok()
+            })
+            .take_while(|res| ready(res.is_some()))
+            .map(|r| r.expect("validated by take_while")),
+    );
+
+    let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = match multiline {
+        Some(config) => Box::new(
+            LineAgg::new(
+                lines.map(|line| ((), line, ())),
+                line_agg::Logic::new(config.clone()),
+            )
+            .map(|(_src, line, _context, _lastline_context)| line),
+        ),
+        None => lines,
+    };
+
+    let mut stream = lines.flat_map(|line| {
+        let events = match decoder.deserializer_parse(line) {
+            Ok((events, _events_size)) => events,
+            Err(_error) => {
+                // Error is handled by `codecs::Decoder`, no further handling
+                // is needed here.
+                SmallVec::new()
+            }
+        };
+
+        let events = events
+            .into_iter()
+            .map(|mut event: Event| {
+                event = event.with_batch_notifier_option(&batch);
+                if let Some(log_event) = event.maybe_as_log_mut() {
+                    enrich(log_event);
+                }
+                events_received.emit(CountByteSize(1, event.estimated_json_encoded_size_of()));
+                event
+            })
+            .collect::<Vec<Event>>();
+        futures::stream::iter(events)
+    });
+
+    let send_error = match out.send_event_stream(&mut stream).await {
+        Ok(_) => None,
+        Err(_) => {
+            let (count, _) = stream.size_hint();
+            emit!(StreamClosedError { count });
+            Some(crate::source_sender::ClosedError)
+        }
+    };
+
+    // Up above, `lines` captures `read_error`, and eventually is captured by `stream`,
+    // so we explicitly drop it so that we can again utilize `read_error` below.
+    drop(stream);
+
+    // The BatchNotifier is cloned for each LogEvent in the batch stream, but the last
+    // reference must be dropped before the status of the batch is sent to the channel.
+    drop(batch);
+
+    if let Some(error) = read_error {
+        Err(SendObjectError::ReadObject { source: error })
+    } else if let Some(error) = send_error {
+        Err(SendObjectError::PipelineSend { source: error })
+    } else {
+        Ok(receiver)
+    }
+}
+
+/// Inserts the source type, and the timestamp of the object the event was decoded from.
+pub fn insert_object_timestamp(
+    log: &mut LogEvent,
+    log_namespace: LogNamespace,
+    source_name: &'static str,
+    timestamp: Option<DateTime<Utc>>,
+) {
+    log_namespace.insert_vector_metadata(
+        log,
+        log_schema().source_type_key(),
+        path!("source_type"),
+        Bytes::from_static(source_name.as_bytes()),
+    );
+
+    // This handles the transition from the original timestamp logic. Originally the
+    // `timestamp_key` was populated by the `last_modified` time on the object, falling
+    // back to calling `now()`.
+    match log_namespace {
+        LogNamespace::Vector => {
+            if let Some(timestamp) = timestamp {
+                log.insert(metadata_path!(source_name, "timestamp"), timestamp);
+            }
+
+            log.insert(metadata_path!("vector", "ingest_timestamp"), Utc::now());
+        }
+        LogNamespace::Legacy => {
+            if let Some(timestamp_key) = log_schema().timestamp_key() {
+                log.try_insert(
+                    (PathPrefix::Event, timestamp_key),
+                    timestamp.unwrap_or_else(Utc::now),
+                );
+            }
+        }
+    };
+}
+
+/// An object to consume, as listed in a bucket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListedObject {
+    pub key: String,
+    /// Identifies the content of the object, which changes when it is overwritten.
+    pub etag: Option<String>,
+    pub size: Option<u64>,
+}
+
+/// Streams the pages of a listing paginated with tokens.
+///
+/// `list_page` lists the page of the given token, or the first page, returning the token of the
+/// next page if there is one.
+pub fn paginate<'a, F, Fut>(list_page: F) -> BoxStream<'a, crate::Result<Vec<ListedObject>>>
+where
+    F: Fn(Option<String>) -> Fut + Send + 'a,
+    Fut: Future<Output = crate::Result<(Vec<ListedObject>, Option<String>)>> + Send + 'a,
+{
+    stream::try_unfold(Some(None), move |page_token: Option<Option<String>>| {
+        let page = page_token.map(&list_page);
+        async move {
+            match page {
+                Some(page) => {
+                    let (objects, next_page_token) = page.await?;
+                    Ok(Some((objects, next_page_token.map(Some))))
+                }
+                None => Ok(None),
+            }
+        }
+    })
+    .boxed()
+}
+
+/// The objects processed by a source, persisted in its data directory.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Checkpoints {
+    /// The ETag of each processed object, by key.
+    #[serde(default)]
+    pub objects: BTreeMap<String, Option<String>>,
+
+    /// The key of the manifest of the last fully processed inventory report, for sources reading
+    /// them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<String>,
+}
+
+impl Checkpoints {
+    /// Whether the object was processed, and wasn't overwritten since.
+    pub fn is_processed(&self, object: &ListedObject) -> bool {
+        self.objects
+            .get(&object.key)
+            .is_some_and(|etag| *etag == object.etag)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum CheckpointsError {
+    #[snafu(display("Failed to read checkpoints from {}: {}", path.display(), source))]
+    ReadCheckpoints { source: io::Error, path: PathBuf },
+    #[snafu(display("Failed to parse checkpoints in {}: {}", path.display(), source))]
+    ParseCheckpoints {
+        source: serde_json::Error,
+        path: PathBuf,
+    },
+}
+
+async fn read_checkpoints(data_dir: &Path) -> Result<Checkpoints, CheckpointsError> {
+    let path = data_dir.join(CHECKPOINTS_FILE_NAME);
+    match tokio::fs::read(&path).await {
+        Ok(data) => serde_json::from_slice(&data).context(ParseCheckpointsSnafu { path }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Checkpoints::default()),
+        Err(error) => Err(CheckpointsError::ReadCheckpoints {
+            source: error,
+            path,
+        }),
+    }
+}
+
+async fn write_checkpoints(data_dir: &Path, checkpoints: &Checkpoints) -> io::Result<()> {
+    // Checkpoints are written to a temporary file first and renamed over the previous ones, so
+    // they are never left partially written.
+    let tmp_path = data_dir.join(TMP_CHECKPOINTS_FILE_NAME);
+    tokio::fs::write(&tmp_path, serde_json::to_vec(checkpoints)?).await?;
+    tokio::fs::rename(tmp_path, data_dir.join(CHECKPOINTS_FILE_NAME)).await
+}
+
+/// Processes the objects listed in a bucket, checkpointing the ones that were processed so they
+/// aren't processed again.
+pub struct ObjectPoller {
+    data_dir: PathBuf,
+    concurrency: usize,
+    pub checkpoints: Checkpoints,
+}
+
+impl ObjectPoller {
+    pub async fn new(data_dir: PathBuf, concurrency: usize) -> Result<Self, CheckpointsError> {
+        let checkpoints = read_checkpoints(&data_dir).await?;
+        Ok(Self {
+            data_dir,
+            concurrency,
+            checkpoints,
+        })
+    }
+
+    /// Processes the listed objects that weren't already, page by page.
+    pub async fn poll_listing<S, F, Fut, E>(
+        &mut self,
+        pages: S,
+        process: F,
+        shutdown: &ShutdownSignal,
+    ) -> crate::Result<()>
+    where
+        S: Stream<Item = crate::Result<Vec<ListedObject>>>,
+        F: Fn(ListedObject) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: fmt::Display,
+    {
+        let mut pages = std::pin::pin!(pages);
+        let mut listed = HashSet::new();
+        while let Some(objects) = pages.next().await {
+            let objects = objects?;
+            listed.extend(objects.iter().map(|object| object.key.clone()));
+            if self
+                .process_objects(objects, &process, shutdown)
+                .await
+                .is_none()
+            {
+                return Ok(());
+            }
+        }
+
+        self.forget_unlisted(&listed);
+        self.write_checkpoints().await;
+        Ok(())
+    }
+
+    /// Processes the objects that weren't already, and checkpoints the ones that succeeded.
+    ///
+    /// Returns whether all the objects were processed, or `None` if interrupted by shutdown.
+    pub async fn process_objects<F, Fut, E>(
+        &mut self,
+        objects: Vec<ListedObject>,
+        process: &F,
+        shutdown: &ShutdownSignal,
+    ) -> Option<bool>
+    where
+        F: Fn(ListedObject) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: fmt::Display,
+    {
+        let pending = objects
+            .into_iter()
+            .filter(|object| !self.checkpoints.is_processed(object))
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return Some(true);
+        }
+
+        let count = pending.len();
+        let results = stream::iter(pending)
+            .map(|object| {
+                let result = process(object.clone());
+                async move { (object, result.await) }
+            })
+            .buffer_unordered(self.concurrency)
+            .take_until(shutdown.clone())
+            .collect::<Vec<_>>()
+            .await;
+
+        let interrupted = results.len() < count;
+        let mut succeeded = true;
+        for (object, result) in results {
+            match result {
+                Ok(()) => {
+                    self.checkpoints.objects.insert(object.key, object.etag);
+                }
+                Err(error) => {
+                    emit!(ObjectProcessingError { error: &error });
+                    succeeded = false;
+                }
+            }
+        }
+        self.write_checkpoints().await;
+
+        (!interrupted).then_some(succeeded)
+    }
+
+    /// Forgets the objects deleted from the bucket, so the checkpoints don't grow unbounded.
+    pub fn forget_unlisted(&mut self, listed: &HashSet<String>) {
+        self.checkpoints
+            .objects
+            .retain(|key, _| listed.contains(key));
+    }
+
+    pub async fn write_checkpoints(&self) {
+        if let Err(error) = write_checkpoints(&self.data_dir, &self.checkpoints).await {
+            emit!(ObjectCheckpointWriteError { error });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(key: &str, etag: Option<&str>) -> ListedObject {
+        ListedObject {
+            key: key.to_owned(),
+            etag: etag.map(ToOwned::to_owned),
+            size: None,
+        }
+    }
+
+    #[test]
+    fn determine_compression() {
+        let cases = vec![
+            ("out.log", Some("gzip"), None, Some(Compression::Gzip)),
+            (
+                "out.log",
+                None,
+                Some("application/gzip"),
+                Some(Compression::Gzip),
+            ),
+            ("out.log.gz", None, None, Some(Compression::Gzip)),
+            ("out.txt", None, None, None),
+        ];
+        for case in cases {
+            let (key, content_encoding, content_type, expected) = case;
+            assert_eq!(
+                super::determine_compression(content_encoding, content_type, key),
+                expected,
+                "key={key:?} content_encoding={content_encoding:?} content_type={content_type:?}",
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn checkpoints_roundtrip() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut poller = ObjectPoller::new(data_dir.path().to_path_buf(), 1)
+            .await
+            .unwrap();
+        assert_eq!(poller.checkpoints, Checkpoints::default());
+
+        poller
+            .checkpoints
+            .objects
+            .insert("a.log".to_owned(), Some("etag-a".to_owned()));
+        poller.checkpoints.manifest = Some("inventory/manifest.json".to_owned());
+        poller.write_checkpoints().await;
+
+        let checkpoints = read_checkpoints(data_dir.path()).await.unwrap();
+        assert!(checkpoints.is_processed(&listed("a.log", Some("etag-a"))));
+        // Overwritten objects are processed again.
+        assert!(!checkpoints.is_processed(&listed("a.log", Some("etag-b"))));
+        assert!(!checkpoints.is_processed(&listed("b.log", Some("etag-a"))));
+        assert_eq!(
+            checkpoints.manifest.as_deref(),
+            Some("inventory/manifest.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn processes_objects_once() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut poller = ObjectPoller::new(data_dir.path().to_path_buf(), 2)
+            .await
+            .unwrap();
+        let shutdown = ShutdownSignal::noop();
+        let process = |object: ListedObject| async move {
+            if object.key == "failed.log" {
+                Err("failed")
+            } else {
+                Ok(())
+            }
+        };
+
+        let objects = vec![
+            listed("a.log", Some("etag-a")),
+            listed("failed.log", Some("etag-b")),
+        ];
+        assert_eq!(
+            poller
+                .process_objects(objects.clone(), &process, &shutdown)
+                .await,
+            Some(false)
+        );
+        assert!(poller.checkpoints.is_processed(&objects[0]));
+        assert!(!poller.checkpoints.is_processed(&objects[1]));
+
+        // Only the failed object is processed again.
+        let processed = std::sync::Mutex::new(Vec::new());
+        let record = |object: ListedObject| {
+            processed.lock().unwrap().push(object.key);
+            ready(Ok::<_, &str>(()))
+        };
+        assert_eq!(
+            poller.process_objects(objects, &record, &shutdown).await,
+            Some(true)
+        );
+        assert_eq!(*processed.lock().unwrap(), vec!["failed.log".to_owned()]);
+    }
+}
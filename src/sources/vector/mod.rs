@@ -15,6 +15,7 @@ use vector_lib::{
 
 use crate::{
     SourceSender,
+    common::grpc::Http2Config,
     config::{
         DataType, GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig,
         SourceContext, SourceOutput,
@@ -22,7 +23,10 @@ use crate::{
     internal_events::{EventsReceived, StreamClosedError},
     proto::vector as proto,
     serde::bool_or_struct,
-    sources::{Source, util::grpc::run_grpc_server},
+    sources::{
+        Source,
+        util::grpc::{Http2Settings, run_grpc_server},
+    },
     tls::{MaybeTlsSettings, TlsEnableableConfig},
 };
 
@@ -130,6 +134,18 @@ pub struct VectorConfig {
     #[serde(default)]
     tls: Option<TlsEnableableConfig>,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    http2: Http2Config,
+
+    /// The maximum number of concurrent streams on each connection.
+    ///
+    /// The sinks send their requests over concurrent streams of the same connection, so that a slow
+    /// request doesn't hold back the others. If not set, the number of streams isn't limited.
+    #[configurable(metadata(docs::examples = 100))]
+    #[serde(default)]
+    max_concurrent_streams: Option<u32>,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
@@ -156,6 +172,8 @@ impl Default for VectorConfig {
             version: None,
             address: "0.0.0.0:6000".parse().unwrap(),
             tls: None,
+            http2: Default::default(),
+            max_concurrent_streams: None,
             acknowledgements: Default::default(),
             log_namespace: None,
         }
@@ -185,8 +203,15 @@ impl SourceConfig for VectorConfig {
         // Tonic added a default of 4MB in 0.9. This replaces the old behavior.
         .max_decoding_message_size(usize::MAX);
 
-        let source =
-            run_grpc_server(self.address, tls_settings, service, cx.shutdown).map_err(|error| {
+        let http2 = Http2Settings {
+            keepalive_interval: self.http2.keepalive_interval(),
+            keepalive_timeout: Some(self.http2.keepalive_timeout()),
+            adaptive_window: Some(self.http2.adaptive_window),
+            max_concurrent_streams: self.max_concurrent_streams,
+        };
+
+        let source = run_grpc_server(self.address, tls_settings, http2, service, cx.shutdown)
+            .map_err(|error| {
                 error!(message = "Source future failed.", %error);
             });
 
@@ -337,4 +362,26 @@ mod tests {
         );
         run_test(&config, addr).await;
     }
+
+    #[tokio::test]
+    async fn receive_zstd_compressed_message() {
+        let addr = test_util::next_addr();
+
+        let config = format!(
+            r#"address = "{addr}"
+            compression = "zstd""#
+        );
+        run_test(&config, addr).await;
+    }
+
+    #[tokio::test]
+    async fn receive_lz4_compressed_message() {
+        let addr = test_util::next_addr();
+
+        let config = format!(
+            r#"address = "{addr}"
+            compression = "lz4""#
+        );
+        run_test(&config, addr).await;
+    }
 }
@@ -0,0 +1,331 @@
+//! Safe wrappers of the [Windows Event Log API][api], which is only used through handles.
+//!
+//! [api]: https://learn.microsoft.com/en-us/windows/win32/wes/windows-event-log
+use std::{collections::HashMap, ffi::c_void, io, iter, ptr, time::Duration};
+
+const ERROR_INSUFFICIENT_BUFFER: i32 = 122;
+const ERROR_NO_MORE_ITEMS: i32 = 259;
+const WAIT_OBJECT_0: u32 = 0;
+const WAIT_TIMEOUT: u32 = 258;
+
+const EVT_SUBSCRIBE_TO_FUTURE_EVENTS: u32 = 1;
+const EVT_SUBSCRIBE_START_AT_OLDEST_RECORD: u32 = 2;
+const EVT_SUBSCRIBE_START_AFTER_BOOKMARK: u32 = 3;
+const EVT_RENDER_EVENT_XML: u32 = 1;
+const EVT_RENDER_BOOKMARK: u32 = 2;
+const EVT_FORMAT_MESSAGE_EVENT: u32 = 1;
+
+#[link(name = "wevtapi")]
+unsafe extern "system" {
+    fn EvtSubscribe(
+        session: isize,
+        signal_event: isize,
+        channel_path: *const u16,
+        query: *const u16,
+        bookmark: isize,
+        context: *mut c_void,
+        callback: *const c_void,
+        flags: u32,
+    ) -> isize;
+    fn EvtNext(
+        result_set: isize,
+        events_size: u32,
+        events: *mut isize,
+        timeout: u32,
+        flags: u32,
+        returned: *mut u32,
+    ) -> i32;
+    fn EvtRender(
+        context: isize,
+        fragment: isize,
+        flags: u32,
+        buffer_size: u32,
+        buffer: *mut c_void,
+        buffer_used: *mut u32,
+        property_count: *mut u32,
+    ) -> i32;
+    fn EvtClose(object: isize) -> i32;
+    fn EvtCreateBookmark(bookmark_xml: *const u16) -> isize;
+    fn EvtUpdateBookmark(bookmark: isize, event: isize) -> i32;
+    fn EvtOpenPublisherMetadata(
+        session: isize,
+        publisher_id: *const u16,
+        log_file_path: *const u16,
+        locale: u32,
+        flags: u32,
+    ) -> isize;
+    fn EvtFormatMessage(
+        publisher_metadata: isize,
+        event: isize,
+        message_id: u32,
+        value_count: u32,
+        values: *const c_void,
+        flags: u32,
+        buffer_size: u32,
+        buffer: *mut u16,
+        buffer_used: *mut u32,
+    ) -> i32;
+}
+
+#[link(name = "kernel32")]
+unsafe extern "system" {
+    fn CreateEventW(
+        attributes: *const c_void,
+        manual_reset: i32,
+        initial_state: i32,
+        name: *const u16,
+    ) -> isize;
+    fn WaitForSingleObject(handle: isize, milliseconds: u32) -> u32;
+    fn ResetEvent(handle: isize) -> i32;
+    fn CloseHandle(handle: isize) -> i32;
+}
+
+/// A handle of the Event Log API, such as an event or a bookmark, closed when dropped.
+#[derive(Debug)]
+pub(super) struct Handle(isize);
+
+impl Handle {
+    fn new(handle: isize) -> io::Result<Self> {
+        if handle == 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Self(handle))
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        // SAFETY: the handle was returned by the API, and is only closed once.
+        unsafe { EvtClose(self.0) };
+    }
+}
+
+/// The position to start reading from when subscribing.
+pub(super) enum Start<'a> {
+    Future,
+    Oldest,
+    AfterBookmark(&'a Bookmark),
+}
+
+/// A pull subscription to the events matching a query, signalling an event object when new events
+/// are available.
+pub(super) struct Subscription {
+    handle: Handle,
+    signal: isize,
+}
+
+impl Subscription {
+    pub(super) fn new(query: &str, start: Start<'_>) -> io::Result<Self> {
+        // SAFETY: the name and attributes are optional.
+        let signal = unsafe { CreateEventW(ptr::null(), 1, 1, ptr::null()) };
+        if signal == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let (flags, bookmark) = match start {
+            Start::Future => (EVT_SUBSCRIBE_TO_FUTURE_EVENTS, 0),
+            Start::Oldest => (EVT_SUBSCRIBE_START_AT_OLDEST_RECORD, 0),
+            Start::AfterBookmark(bookmark) => (EVT_SUBSCRIBE_START_AFTER_BOOKMARK, bookmark.0.0),
+        };
+        let query = wide(query);
+        // SAFETY: the query is a null-terminated string which outlives the call, and the channel
+        // path is in the query.
+        let handle = Handle::new(unsafe {
+            EvtSubscribe(
+                0,
+                signal,
+                ptr::null(),
+                query.as_ptr(),
+                bookmark,
+                ptr::null_mut(),
+                ptr::null(),
+                flags,
+            )
+        });
+        match handle {
+            Ok(handle) => Ok(Self { handle, signal }),
+            Err(error) => {
+                // SAFETY: the event object isn't used by anything else.
+                unsafe { CloseHandle(signal) };
+                Err(error)
+            }
+        }
+    }
+
+    /// Waits until new events are available, returning `false` on timeout.
+    pub(super) fn wait(&self, timeout: Duration) -> io::Result<bool> {
+        let milliseconds = timeout.as_millis().try_into().unwrap_or(u32::MAX);
+        // SAFETY: the event object is open until the subscription is dropped.
+        match unsafe { WaitForSingleObject(self.signal, milliseconds) } {
+            WAIT_OBJECT_0 => {
+                // The event is signalled again if events are added while reading.
+                // SAFETY: as above.
+                unsafe { ResetEvent(self.signal) };
+                Ok(true)
+            }
+            WAIT_TIMEOUT => Ok(false),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Returns up to `count` events, or none if there aren't any more.
+    pub(super) fn next(&self, count: usize) -> io::Result<Vec<Handle>> {
+        let mut events = vec![0; count];
+        let mut returned = 0;
+        // SAFETY: the array has room for `count` handles.
+        let ok = unsafe {
+            EvtNext(
+                self.handle.0,
+                events.len().try_into().unwrap_or(u32::MAX),
+                events.as_mut_ptr(),
+                0,
+                0,
+                &mut returned,
+            )
+        };
+        if ok == 0 {
+            let error = io::Error::last_os_error();
+            return if error.raw_os_error() == Some(ERROR_NO_MORE_ITEMS) {
+                Ok(Vec::new())
+            } else {
+                Err(error)
+            };
+        }
+        events.truncate(returned as usize);
+        Ok(events.into_iter().map(Handle).collect())
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // SAFETY: the event object is only closed once.
+        unsafe { CloseHandle(self.signal) };
+    }
+}
+
+/// The position of the last event read from each channel of a query.
+pub(super) struct Bookmark(Handle);
+
+impl Bookmark {
+    /// Creates an empty bookmark, or restores one from its XML rendering.
+    pub(super) fn new(xml: Option<&str>) -> io::Result<Self> {
+        let xml = xml.map(wide);
+        let xml = xml.as_ref().map_or(ptr::null(), |xml| xml.as_ptr());
+        // SAFETY: the XML is either null or a null-terminated string which outlives the call.
+        Handle::new(unsafe { EvtCreateBookmark(xml) }).map(Self)
+    }
+
+    pub(super) fn update(&mut self, event: &Handle) -> io::Result<()> {
+        // SAFETY: both handles are open.
+        if unsafe { EvtUpdateBookmark(self.0.0, event.0) } == 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(super) fn render(&self) -> io::Result<String> {
+        render(&self.0, EVT_RENDER_BOOKMARK)
+    }
+}
+
+/// Renders an event as XML.
+pub(super) fn render_event(event: &Handle) -> io::Result<String> {
+    render(event, EVT_RENDER_EVENT_XML)
+}
+
+fn render(fragment: &Handle, flags: u32) -> io::Result<String> {
+    let mut buffer = Vec::<u16>::new();
+    loop {
+        let mut used = 0;
+        let mut property_count = 0;
+        // SAFETY: the size of the buffer is given in bytes.
+        let ok = unsafe {
+            EvtRender(
+                0,
+                fragment.0,
+                flags,
+                (buffer.len() * 2).try_into().unwrap_or(u32::MAX),
+                buffer.as_mut_ptr().cast(),
+                &mut used,
+                &mut property_count,
+            )
+        };
+        if ok != 0 {
+            return Ok(from_wide(&buffer[..used as usize / 2]));
+        }
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER) {
+            return Err(error);
+        }
+        buffer.resize((used as usize).div_ceil(2), 0);
+    }
+}
+
+/// The metadata of the providers of events, used to format their messages.
+#[derive(Default)]
+pub(super) struct Publishers {
+    /// The metadata of each provider, or `None` if it can't be opened, such as when the provider
+    /// isn't installed on this computer.
+    metadata: HashMap<String, Option<Handle>>,
+}
+
+impl Publishers {
+    /// Formats the message of an event, using the message table of its provider.
+    pub(super) fn format_message(&mut self, provider: &str, event: &Handle) -> Option<String> {
+        let metadata = self
+            .metadata
+            .entry(provider.to_owned())
+            .or_insert_with(|| {
+                let provider = wide(provider);
+                // SAFETY: the provider is a null-terminated string which outlives the call, and
+                // the log file path is optional.
+                let handle =
+                    unsafe { EvtOpenPublisherMetadata(0, provider.as_ptr(), ptr::null(), 0, 0) };
+                Handle::new(handle)
+                    .inspect_err(|error| {
+                        debug!(message = "Failed opening provider metadata.", provider, %error);
+                    })
+                    .ok()
+            })
+            .as_ref()?;
+
+        let mut buffer = Vec::<u16>::new();
+        loop {
+            let mut used = 0;
+            // SAFETY: the size of the buffer is given in characters.
+            let ok = unsafe {
+                EvtFormatMessage(
+                    metadata.0,
+                    event.0,
+                    0,
+                    0,
+                    ptr::null(),
+                    EVT_FORMAT_MESSAGE_EVENT,
+                    buffer.len().try_into().unwrap_or(u32::MAX),
+                    buffer.as_mut_ptr(),
+                    &mut used,
+                )
+            };
+            if ok != 0 {
+                return Some(from_wide(&buffer[..used as usize]));
+            }
+            if io::Error::last_os_error().raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER) {
+                return None;
+            }
+            buffer.resize(used as usize, 0);
+        }
+    }
+}
+
+/// Converts a string to a null-terminated UTF-16 string.
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(iter::once(0)).collect()
+}
+
+/// Converts a UTF-16 string, which may be null-terminated.
+fn from_wide(s: &[u16]) -> String {
+    let length = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+    String::from_utf16_lossy(&s[..length])
+}
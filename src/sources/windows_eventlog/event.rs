@@ -0,0 +1,308 @@
+//! Extraction of the fields of an event from its XML rendering, which follows the
+//! [event schema][schema].
+//!
+//! [schema]: https://learn.microsoft.com/en-us/windows/win32/wes/eventschema-schema
+use chrono::{DateTime, Utc};
+use vrl::value::{ObjectMap, Value};
+
+use super::xml::Element;
+
+/// The fields of an event.
+#[derive(Debug)]
+pub(super) struct ParsedEvent {
+    pub(super) fields: ObjectMap,
+    /// The name of the provider, which is needed to format the message of the event.
+    pub(super) provider: Option<String>,
+    pub(super) timestamp: Option<DateTime<Utc>>,
+}
+
+pub(super) fn parse(event: &Element) -> ParsedEvent {
+    let mut fields = ObjectMap::new();
+    let mut provider = None;
+    let mut timestamp = None;
+
+    if let Some(system) = event.child("System") {
+        if let Some(element) = system.child("Provider") {
+            provider = element.attribute("Name").map(str::to_owned);
+            insert_attribute(&mut fields, "provider_name", element, "Name");
+            insert_attribute(&mut fields, "provider_guid", element, "Guid");
+        }
+        if let Some(element) = system.child("EventID") {
+            insert_number(&mut fields, "event_id", Some(&element.text));
+            insert_number(&mut fields, "qualifiers", element.attribute("Qualifiers"));
+        }
+        insert_number(&mut fields, "version", system.child_text("Version"));
+        if let Some(level) = system.child_text("Level") {
+            insert_number(&mut fields, "level", Some(level));
+            if let Some(name) = level_name(level) {
+                fields.insert("level_name".into(), name.into());
+            }
+        }
+        insert_number(&mut fields, "task", system.child_text("Task"));
+        insert_number(&mut fields, "opcode", system.child_text("Opcode"));
+        if let Some(keywords) = system.child_text("Keywords") {
+            fields.insert("keywords".into(), keywords.into());
+        }
+        timestamp = system
+            .child("TimeCreated")
+            .and_then(|element| element.attribute("SystemTime"))
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc));
+        insert_number(&mut fields, "record_id", system.child_text("EventRecordID"));
+        if let Some(element) = system.child("Correlation") {
+            insert_attribute(&mut fields, "activity_id", element, "ActivityID");
+            insert_attribute(
+                &mut fields,
+                "related_activity_id",
+                element,
+                "RelatedActivityID",
+            );
+        }
+        if let Some(element) = system.child("Execution") {
+            insert_number(&mut fields, "process_id", element.attribute("ProcessID"));
+            insert_number(&mut fields, "thread_id", element.attribute("ThreadID"));
+        }
+        if let Some(channel) = system.child_text("Channel") {
+            fields.insert("channel".into(), channel.into());
+        }
+        if let Some(computer) = system.child_text("Computer") {
+            fields.insert("computer".into(), computer.into());
+        }
+        if let Some(element) = system.child("Security") {
+            insert_attribute(&mut fields, "user_id", element, "UserID");
+        }
+    }
+
+    if let Some(event_data) = event.child("EventData") {
+        let data = event_data_fields(event_data);
+        if !data.is_empty() {
+            fields.insert("event_data".into(), Value::Object(data));
+        }
+    }
+    if let Some(user_data) = event
+        .child("UserData")
+        .and_then(|element| element.children.first())
+    {
+        fields.insert("user_data".into(), element_value(user_data));
+    }
+
+    ParsedEvent {
+        fields,
+        provider,
+        timestamp,
+    }
+}
+
+/// The names of the standard levels.
+fn level_name(level: &str) -> Option<&'static str> {
+    match level {
+        // Classic event logs, such as the application log, use 0 for information.
+        "0" | "4" => Some("Information"),
+        "1" => Some("Critical"),
+        "2" => Some("Error"),
+        "3" => Some("Warning"),
+        "5" => Some("Verbose"),
+        _ => None,
+    }
+}
+
+fn insert_attribute(fields: &mut ObjectMap, key: &str, element: &Element, name: &str) {
+    if let Some(value) = element.attribute(name).filter(|value| !value.is_empty()) {
+        fields.insert(key.into(), value.into());
+    }
+}
+
+fn insert_number(fields: &mut ObjectMap, key: &str, text: Option<&str>) {
+    if let Some(number) = text.and_then(|text| text.parse::<i64>().ok()) {
+        fields.insert(key.into(), number.into());
+    }
+}
+
+/// The `Data` elements of events are named by their manifest, but the events of classic providers
+/// only have their values, which are named `param1`, `param2` and so on.
+fn event_data_fields(event_data: &Element) -> ObjectMap {
+    let mut fields = ObjectMap::new();
+    let mut index = 0;
+    for element in &event_data.children {
+        match element.name.as_str() {
+            "Data" => {
+                index += 1;
+                let key = element
+                    .attribute("Name")
+                    .map_or_else(|| format!("param{index}"), str::to_owned);
+                fields.insert(key.into(), element.text.as_str().into());
+            }
+            "Binary" => {
+                fields.insert("binary".into(), element.text.as_str().into());
+            }
+            _ => (),
+        }
+    }
+    fields
+}
+
+/// Converts an element of user data, whose structure is defined by its provider, to an object of
+/// its attributes and children, or to its text if it has neither.
+fn element_value(element: &Element) -> Value {
+    let mut object = ObjectMap::new();
+    for (name, value) in &element.attributes {
+        if name != "xmlns" && !name.starts_with("xmlns:") {
+            object.insert(name.as_str().into(), value.as_str().into());
+        }
+    }
+    if object.is_empty() && element.children.is_empty() {
+        return element.text.as_str().into();
+    }
+    for child in &element.children {
+        let value = element_value(child);
+        match object.get_mut(child.name.as_str()) {
+            // Repeated elements are collected in an array.
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.clone(), value]),
+            None => {
+                object.insert(child.name.as_str().into(), value);
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::windows_eventlog::xml;
+
+    #[test]
+    fn parses_events() {
+        let event = xml::parse(
+            r#"<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'>
+                <System>
+                    <Provider Name='Microsoft-Windows-Security-Auditing' Guid='{54849625-5478-4994-a5ba-3e3b0328c30d}'/>
+                    <EventID>4624</EventID>
+                    <Version>2</Version>
+                    <Level>0</Level>
+                    <Task>12544</Task>
+                    <Opcode>0</Opcode>
+                    <Keywords>0x8020000000000000</Keywords>
+                    <TimeCreated SystemTime='2024-03-01T12:30:45.1234567Z'/>
+                    <EventRecordID>42</EventRecordID>
+                    <Correlation ActivityID='{0b8d3c2a-0f6e-0001-7e3c-8d0b6e0fda01}'/>
+                    <Execution ProcessID='636' ThreadID='7452'/>
+                    <Channel>Security</Channel>
+                    <Computer>host.example.com</Computer>
+                    <Security/>
+                </System>
+                <EventData>
+                    <Data Name='TargetUserName'>alice</Data>
+                    <Data Name='LogonType'>2</Data>
+                </EventData>
+            </Event>"#,
+        )
+        .unwrap();
+        let parsed = parse(&event);
+
+        assert_eq!(
+            Some("Microsoft-Windows-Security-Auditing"),
+            parsed.provider.as_deref()
+        );
+        assert_eq!(
+            Some(
+                DateTime::parse_from_rfc3339("2024-03-01T12:30:45.1234567Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            ),
+            parsed.timestamp
+        );
+
+        let fields = parsed.fields;
+        assert_eq!(
+            Some(&Value::from("{54849625-5478-4994-a5ba-3e3b0328c30d}")),
+            fields.get("provider_guid")
+        );
+        assert_eq!(Some(&Value::from(4624)), fields.get("event_id"));
+        assert_eq!(None, fields.get("qualifiers"));
+        assert_eq!(Some(&Value::from(2)), fields.get("version"));
+        assert_eq!(Some(&Value::from(0)), fields.get("level"));
+        assert_eq!(Some(&Value::from("Information")), fields.get("level_name"));
+        assert_eq!(Some(&Value::from(12544)), fields.get("task"));
+        assert_eq!(
+            Some(&Value::from("0x8020000000000000")),
+            fields.get("keywords")
+        );
+        assert_eq!(Some(&Value::from(42)), fields.get("record_id"));
+        assert_eq!(Some(&Value::from(636)), fields.get("process_id"));
+        assert_eq!(Some(&Value::from(7452)), fields.get("thread_id"));
+        assert_eq!(Some(&Value::from("Security")), fields.get("channel"));
+        assert_eq!(
+            Some(&Value::from("host.example.com")),
+            fields.get("computer")
+        );
+        assert_eq!(None, fields.get("user_id"));
+
+        let mut event_data = ObjectMap::new();
+        event_data.insert("TargetUserName".into(), "alice".into());
+        event_data.insert("LogonType".into(), "2".into());
+        assert_eq!(Some(&Value::Object(event_data)), fields.get("event_data"));
+    }
+
+    #[test]
+    fn names_classic_event_data() {
+        let event = xml::parse(
+            r#"<Event>
+                <System>
+                    <Provider Name='Application Error'/>
+                    <EventID Qualifiers='0'>1000</EventID>
+                    <Level>2</Level>
+                    <Security UserID='S-1-5-18'/>
+                </System>
+                <EventData><Data>app.exe</Data><Data>1.0.0.0</Data><Binary>00FF</Binary></EventData>
+            </Event>"#,
+        )
+        .unwrap();
+        let fields = parse(&event).fields;
+
+        assert_eq!(Some(&Value::from(0)), fields.get("qualifiers"));
+        assert_eq!(Some(&Value::from("Error")), fields.get("level_name"));
+        assert_eq!(Some(&Value::from("S-1-5-18")), fields.get("user_id"));
+        let mut event_data = ObjectMap::new();
+        event_data.insert("param1".into(), "app.exe".into());
+        event_data.insert("param2".into(), "1.0.0.0".into());
+        event_data.insert("binary".into(), "00FF".into());
+        assert_eq!(Some(&Value::Object(event_data)), fields.get("event_data"));
+    }
+
+    #[test]
+    fn converts_user_data() {
+        let event = xml::parse(
+            r#"<Event>
+                <System><Provider Name='Microsoft-Windows-Eventlog'/></System>
+                <UserData>
+                    <LogFileCleared xmlns='http://manifests.microsoft.com/win/2004/08/windows/eventlog'>
+                        <SubjectUserName>alice</SubjectUserName>
+                        <Privilege Name='SeSecurityPrivilege'/>
+                        <Privilege Name='SeBackupPrivilege'/>
+                    </LogFileCleared>
+                </UserData>
+            </Event>"#,
+        )
+        .unwrap();
+        let fields = parse(&event).fields;
+
+        let privilege = |name: &str| {
+            let mut object = ObjectMap::new();
+            object.insert("Name".into(), name.into());
+            Value::Object(object)
+        };
+        let mut user_data = ObjectMap::new();
+        user_data.insert("SubjectUserName".into(), "alice".into());
+        user_data.insert(
+            "Privilege".into(),
+            Value::Array(vec![
+                privilege("SeSecurityPrivilege"),
+                privilege("SeBackupPrivilege"),
+            ]),
+        );
+        assert_eq!(Some(&Value::Object(user_data)), fields.get("user_data"));
+        assert_eq!(None, fields.get("event_data"));
+    }
+}
@@ -0,0 +1,520 @@
+//! The `windows_eventlog` source reads events from the channels of the Windows Event Log, keeping
+//! a bookmark of the last event read so that it resumes from there after a restart.
+use std::{fmt::Write as _, io, path::PathBuf, time::Duration};
+
+use chrono::Utc;
+use snafu::{ResultExt, Snafu};
+use tokio::sync::mpsc;
+use vector_lib::{
+    EstimatedJsonEncodedSizeOf,
+    config::{LegacyKey, LogNamespace, log_schema},
+    configurable::configurable_component,
+    internal_event::{ByteSize, BytesReceived, CountByteSize, InternalEventHandle as _, Protocol},
+    lookup::{owned_value_path, path},
+    schema::Definition,
+};
+use vrl::value::{Kind, kind::Collection};
+
+use self::{
+    api::{Bookmark, Handle, Publishers, Start, Subscription},
+    event::ParsedEvent,
+};
+use crate::{
+    SourceSender,
+    config::{DataType, SourceConfig, SourceContext, SourceOutput},
+    event::LogEvent,
+    internal_events::{
+        EventsReceived, StreamClosedError, WindowsEventLogBookmarkError, WindowsEventLogParseError,
+        WindowsEventLogReadError,
+    },
+    shutdown::ShutdownSignal,
+};
+
+mod api;
+mod event;
+mod xml;
+
+const BOOKMARK_FILENAME: &str = "bookmark.xml";
+
+/// How often the reader checks whether the source is shutting down while no events arrive.
+const WAIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one channel must be configured"))]
+    NoChannels,
+}
+
+#[derive(Debug, Snafu)]
+enum EventError {
+    #[snafu(display("failed rendering event: {source}"))]
+    Render { source: io::Error },
+
+    #[snafu(display("failed parsing event XML: {source}"))]
+    Parse { source: xml::XmlError },
+}
+
+/// Configuration for the `windows_eventlog` source.
+#[configurable_component(source("windows_eventlog", "Collect events from the Windows Event Log."))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WindowsEventLogConfig {
+    /// The channels to read events from.
+    ///
+    /// Use `wevtutil enum-logs` to list the channels of a computer.
+    #[serde(default = "default_channels")]
+    #[configurable(metadata(docs::examples = "System"))]
+    #[configurable(metadata(docs::examples = "Application"))]
+    #[configurable(metadata(docs::examples = "Security"))]
+    #[configurable(metadata(docs::examples = "Microsoft-Windows-Sysmon/Operational"))]
+    pub channels: Vec<String>,
+
+    /// An [XPath query][xpath] selecting the events to read from each channel.
+    ///
+    /// By default, all events are read.
+    ///
+    /// [xpath]: https://learn.microsoft.com/en-us/windows/win32/wes/consuming-events#xpath-10-limitations
+    #[serde(default = "default_query")]
+    #[configurable(metadata(docs::examples = "*[System[Level<=3]]"))]
+    #[configurable(metadata(docs::examples = "*[System[(EventID=4624 or EventID=4625)]]"))]
+    pub query: String,
+
+    /// Read the events that were logged before the source started.
+    ///
+    /// Only used the first time the source runs: once a bookmark has been saved, reading
+    /// resumes after the last event that was sent.
+    #[serde(default)]
+    pub read_existing_events: bool,
+
+    /// Format the message of each event with the message table of its provider.
+    ///
+    /// Formatting is skipped for providers which are not installed on the computer.
+    #[serde(default = "crate::serde::default_true")]
+    pub render_message: bool,
+
+    /// The maximum number of events to read from the event log at once.
+    #[serde(default = "default_batch_size")]
+    #[configurable(metadata(docs::type_unit = "events"))]
+    pub batch_size: usize,
+
+    /// The directory used to persist the bookmark of the last event read.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "C:\\ProgramData\\vector\\windows_eventlog"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    pub log_namespace: Option<bool>,
+}
+
+fn default_channels() -> Vec<String> {
+    vec!["Application".to_owned(), "System".to_owned()]
+}
+
+fn default_query() -> String {
+    "*".to_owned()
+}
+
+const fn default_batch_size() -> usize {
+    100
+}
+
+impl Default for WindowsEventLogConfig {
+    fn default() -> Self {
+        Self {
+            channels: default_channels(),
+            query: default_query(),
+            read_existing_events: false,
+            render_message: true,
+            batch_size: default_batch_size(),
+            data_dir: None,
+            log_namespace: None,
+        }
+    }
+}
+
+impl_generate_config_from_default!(WindowsEventLogConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "windows_eventlog")]
+impl SourceConfig for WindowsEventLogConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.channels.is_empty() {
+            return Err(BuildError::NoChannels.into());
+        }
+
+        let mut bookmark_path = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        bookmark_path.push(BOOKMARK_FILENAME);
+
+        let reader = EventReader {
+            query: query_list(&self.channels, &self.query),
+            read_existing_events: self.read_existing_events,
+            render_message: self.render_message,
+            batch_size: self.batch_size.max(1),
+            log_namespace: cx.log_namespace(self.log_namespace),
+        };
+
+        Ok(Box::pin(run(reader, bookmark_path, cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let schema_definition = Definition::new_with_default_metadata(
+            Kind::object(Collection::from_unknown(Kind::any())),
+            [log_namespace],
+        )
+        .with_standard_vector_source_metadata()
+        .with_source_metadata(
+            Self::NAME,
+            log_schema()
+                .timestamp_key()
+                .cloned()
+                .map(LegacyKey::Overwrite),
+            &owned_value_path!("timestamp"),
+            Kind::timestamp().or_undefined(),
+            Some("timestamp"),
+        )
+        .with_source_metadata(
+            Self::NAME,
+            log_schema().host_key().cloned().map(LegacyKey::Overwrite),
+            &owned_value_path!("host"),
+            Kind::bytes().or_undefined(),
+            Some("host"),
+        );
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Builds a [structured query][query] selecting the events matching `query` in each channel.
+///
+/// [query]: https://learn.microsoft.com/en-us/windows/win32/wes/queryschema-schema
+fn query_list(channels: &[String], query: &str) -> String {
+    let mut xml = String::from("<QueryList><Query Id='0'>");
+    for channel in channels {
+        write!(
+            xml,
+            "<Select Path='{}'>{}</Select>",
+            escape(channel),
+            escape(query)
+        )
+        .expect("writing to a string can't fail");
+    }
+    xml.push_str("</Query></QueryList>");
+    xml
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+/// The events read at once, and the bookmark to save once they have been sent.
+struct Batch {
+    events: Vec<LogEvent>,
+    byte_size: usize,
+    bookmark: String,
+}
+
+/// Reads events from a subscription, which blocks, on a thread of its own.
+struct EventReader {
+    query: String,
+    read_existing_events: bool,
+    render_message: bool,
+    batch_size: usize,
+    log_namespace: LogNamespace,
+}
+
+impl EventReader {
+    fn run(self, bookmark: Option<String>, sender: mpsc::Sender<Batch>) -> Result<(), ()> {
+        let saved = bookmark.and_then(|xml| {
+            Bookmark::new(Some(&xml))
+                .map_err(|error| emit!(WindowsEventLogReadError { error }))
+                .ok()
+        });
+        let start = match &saved {
+            Some(bookmark) => Start::AfterBookmark(bookmark),
+            None if self.read_existing_events => Start::Oldest,
+            None => Start::Future,
+        };
+        let subscription = Subscription::new(&self.query, start).map_err(read_error)?;
+        let mut bookmark = match saved {
+            Some(bookmark) => bookmark,
+            None => Bookmark::new(None).map_err(read_error)?,
+        };
+        info!(message = "Subscribed to event log.", query = %self.query);
+
+        let mut publishers = Publishers::default();
+        while !sender.is_closed() {
+            if !subscription.wait(WAIT_TIMEOUT).map_err(read_error)? {
+                continue;
+            }
+            loop {
+                let handles = subscription.next(self.batch_size).map_err(read_error)?;
+                if handles.is_empty() {
+                    break;
+                }
+
+                let mut events = Vec::with_capacity(handles.len());
+                let mut byte_size = 0;
+                for handle in &handles {
+                    match self.read_event(handle, &mut publishers) {
+                        Ok((event, size)) => {
+                            events.push(event);
+                            byte_size += size;
+                        }
+                        Err(error) => emit!(WindowsEventLogParseError { error }),
+                    }
+                    bookmark.update(handle).map_err(read_error)?;
+                }
+
+                let batch = Batch {
+                    events,
+                    byte_size,
+                    bookmark: bookmark.render().map_err(read_error)?,
+                };
+                if sender.blocking_send(batch).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the event, and the size of its XML rendering.
+    fn read_event(
+        &self,
+        handle: &Handle,
+        publishers: &mut Publishers,
+    ) -> Result<(LogEvent, usize), EventError> {
+        let rendered = api::render_event(handle).context(RenderSnafu)?;
+        let element = xml::parse(&rendered).context(ParseSnafu)?;
+        let parsed = event::parse(&element);
+        let message = self
+            .render_message
+            .then(|| {
+                let provider = parsed.provider.as_deref()?;
+                publishers.format_message(provider, handle)
+            })
+            .flatten();
+        Ok((self.event(parsed, message), rendered.len()))
+    }
+
+    fn event(&self, parsed: ParsedEvent, message: Option<String>) -> LogEvent {
+        let ParsedEvent {
+            mut fields,
+            timestamp,
+            ..
+        } = parsed;
+        if let Some(message) = message {
+            fields.insert("message".into(), message.trim_end().into());
+        }
+        let host = fields.get("computer").cloned();
+
+        let mut log = LogEvent::from(fields);
+        self.log_namespace.insert_standard_vector_source_metadata(
+            &mut log,
+            WindowsEventLogConfig::NAME,
+            Utc::now(),
+        );
+        if let Some(timestamp) = timestamp {
+            self.log_namespace.insert_source_metadata(
+                WindowsEventLogConfig::NAME,
+                &mut log,
+                log_schema().timestamp_key().map(LegacyKey::Overwrite),
+                path!("timestamp"),
+                timestamp,
+            );
+        }
+        if let Some(host) = host {
+            self.log_namespace.insert_source_metadata(
+                WindowsEventLogConfig::NAME,
+                &mut log,
+                log_schema().host_key().map(LegacyKey::Overwrite),
+                path!("host"),
+                host,
+            );
+        }
+        log
+    }
+}
+
+fn read_error(error: io::Error) {
+    emit!(WindowsEventLogReadError { error });
+}
+
+async fn run(
+    reader: EventReader,
+    bookmark_path: PathBuf,
+    mut out: SourceSender,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let bookmark = match tokio::fs::read_to_string(&bookmark_path).await {
+        Ok(xml) => Some(xml),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => {
+            emit!(WindowsEventLogReadError { error });
+            None
+        }
+    };
+
+    let (sender, mut receiver) = mpsc::channel(1);
+    let reader = tokio::task::spawn_blocking(move || reader.run(bookmark, sender));
+
+    let bytes_received = register!(BytesReceived::from(Protocol::NONE));
+    let events_received = register!(EventsReceived);
+    let temporary_path = bookmark_path.with_extension("xml.tmp");
+    loop {
+        let batch = tokio::select! {
+            batch = receiver.recv() => match batch {
+                Some(batch) => batch,
+                // The reader stopped because of an error.
+                None => break,
+            },
+            _ = &mut shutdown => break,
+        };
+
+        if !batch.events.is_empty() {
+            let count = batch.events.len();
+            bytes_received.emit(ByteSize(batch.byte_size));
+            events_received.emit(CountByteSize(
+                count,
+                batch.events.estimated_json_encoded_size_of(),
+            ));
+            if out.send_batch(batch.events).await.is_err() {
+                emit!(StreamClosedError { count });
+                return Err(());
+            }
+        }
+
+        // The bookmark is replaced at once, so that it is never partially written.
+        let result = match tokio::fs::write(&temporary_path, batch.bookmark).await {
+            Ok(()) => tokio::fs::rename(&temporary_path, &bookmark_path).await,
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            emit!(WindowsEventLogBookmarkError {
+                error,
+                path: &bookmark_path,
+            });
+        }
+    }
+
+    // The reader stops once it notices that the receiver is closed.
+    drop(receiver);
+    reader.await.unwrap_or(Err(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::value::{ObjectMap, Value};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowsEventLogConfig>();
+    }
+
+    #[test]
+    fn builds_query_lists() {
+        assert_eq!(
+            "<QueryList><Query Id='0'>\
+                <Select Path='System'>*[System[Level&lt;=3]]</Select>\
+                <Select Path='Microsoft-Windows-Sysmon/Operational'>*[System[Level&lt;=3]]</Select>\
+            </Query></QueryList>",
+            query_list(
+                &[
+                    "System".to_owned(),
+                    "Microsoft-Windows-Sysmon/Operational".to_owned()
+                ],
+                "*[System[Level<=3]]"
+            )
+        );
+    }
+
+    fn reader(log_namespace: LogNamespace) -> EventReader {
+        EventReader {
+            query: query_list(&default_channels(), &default_query()),
+            read_existing_events: false,
+            render_message: true,
+            batch_size: default_batch_size(),
+            log_namespace,
+        }
+    }
+
+    fn parsed_event() -> ParsedEvent {
+        let mut fields = ObjectMap::new();
+        fields.insert("event_id".into(), 7036.into());
+        fields.insert("computer".into(), "host.example.com".into());
+        ParsedEvent {
+            fields,
+            provider: Some("Service Control Manager".to_owned()),
+            timestamp: chrono::DateTime::from_timestamp(1_700_000_000, 0),
+        }
+    }
+
+    #[test]
+    fn creates_events() {
+        let log = reader(LogNamespace::Legacy).event(
+            parsed_event(),
+            Some("The Print Spooler service entered the running state.\r\n".to_owned()),
+        );
+
+        assert_eq!(log["event_id"], 7036.into());
+        assert_eq!(
+            log["message"],
+            "The Print Spooler service entered the running state.".into()
+        );
+        assert_eq!(log["host"], "host.example.com".into());
+        assert_eq!(
+            log["timestamp"],
+            Value::from(chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+        );
+        assert_eq!(log["source_type"], "windows_eventlog".into());
+    }
+
+    #[test]
+    fn creates_events_with_vector_namespace() {
+        let log = reader(LogNamespace::Vector).event(parsed_event(), None);
+
+        assert_eq!(log["event_id"], 7036.into());
+        assert_eq!(log["computer"], "host.example.com".into());
+        assert!(log.get("message").is_none());
+        assert_eq!(
+            log.metadata()
+                .value()
+                .get(path!("windows_eventlog", "host")),
+            Some(&Value::from("host.example.com"))
+        );
+        assert_eq!(
+            log.metadata()
+                .value()
+                .get(path!("windows_eventlog", "timestamp")),
+            Some(&Value::from(
+                chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+            ))
+        );
+    }
+}
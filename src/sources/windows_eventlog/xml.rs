@@ -0,0 +1,308 @@
+//! A parser for the XML rendering of events, which is machine generated and only uses elements,
+//! attributes, text and character references.
+use snafu::Snafu;
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub(super) enum XmlError {
+    #[snafu(display("unexpected end of document"))]
+    UnexpectedEnd,
+
+    #[snafu(display("expected {expected} at position {position}"))]
+    Unexpected {
+        expected: &'static str,
+        position: usize,
+    },
+
+    #[snafu(display("closing tag `{actual}` does not match `{expected}`"))]
+    MismatchedTag { expected: String, actual: String },
+
+    #[snafu(display("invalid entity `&{entity};`"))]
+    InvalidEntity { entity: String },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct Element {
+    pub(super) name: String,
+    pub(super) attributes: Vec<(String, String)>,
+    pub(super) children: Vec<Element>,
+    /// The text content of the element, excluding the text of its children.
+    pub(super) text: String,
+}
+
+impl Element {
+    pub(super) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(super) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns the text of the child with the given name, if it isn't empty.
+    pub(super) fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|child| child.text.as_str())
+            .filter(|text| !text.is_empty())
+    }
+}
+
+/// Parses a document, returning its root element.
+pub(super) fn parse(xml: &str) -> Result<Element, XmlError> {
+    let mut parser = Parser { xml, position: 0 };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.position < xml.len() {
+        return parser.unexpected("end of document");
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    xml: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.xml[self.position..]
+    }
+
+    fn unexpected<T>(&self, expected: &'static str) -> Result<T, XmlError> {
+        if self.position >= self.xml.len() {
+            Err(XmlError::UnexpectedEnd)
+        } else {
+            Err(XmlError::Unexpected {
+                expected,
+                position: self.position,
+            })
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips everything up to and including `end`.
+    fn skip_past(&mut self, end: &str) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let index = rest.find(end).ok_or(XmlError::UnexpectedEnd)?;
+        self.position += index + end.len();
+        Ok(&rest[..index])
+    }
+
+    /// Skips whitespace, comments and processing instructions, such as the XML declaration.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return self.unexpected("a name");
+        }
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), XmlError> {
+        if !self.rest().starts_with(token) {
+            return self.unexpected(token);
+        }
+        self.position += token.len();
+        Ok(())
+    }
+
+    fn element(&mut self) -> Result<Element, XmlError> {
+        self.expect("<")?;
+        let mut element = Element {
+            name: self.name()?.to_owned(),
+            ..Default::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            let name = self.name()?.to_owned();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let value = self.attribute_value()?;
+            element.attributes.push((name, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.position += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return MismatchedTagSnafu {
+                        expected: element.name,
+                        actual: name,
+                    }
+                    .fail();
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                // Whitespace between children is only formatting.
+                if !element.children.is_empty() && element.text.trim().is_empty() {
+                    element.text.clear();
+                }
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.position += "<![CDATA[".len();
+                element.text.push_str(self.skip_past("]]>")?);
+            } else if rest.starts_with('<') {
+                element.children.push(self.element()?);
+            } else if rest.is_empty() {
+                return Err(XmlError::UnexpectedEnd);
+            } else {
+                let length = rest.find('<').unwrap_or(rest.len());
+                self.position += length;
+                unescape(&rest[..length], &mut element.text)?;
+            }
+        }
+    }
+
+    fn attribute_value(&mut self) -> Result<String, XmlError> {
+        let quote = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return self.unexpected("a quoted value"),
+        };
+        self.position += 1;
+        let rest = self.rest();
+        let length = rest.find(quote).ok_or(XmlError::UnexpectedEnd)?;
+        self.position += length + 1;
+        let mut value = String::with_capacity(length);
+        unescape(&rest[..length], &mut value)?;
+        Ok(value)
+    }
+}
+
+/// Appends text to `output`, replacing its entity and character references.
+fn unescape(mut text: &str, output: &mut String) -> Result<(), XmlError> {
+    while let Some(start) = text.find('&') {
+        output.push_str(&text[..start]);
+        let rest = &text[start + 1..];
+        let end = rest.find(';').ok_or_else(|| XmlError::InvalidEntity {
+            entity: rest.to_owned(),
+        })?;
+        let entity = &rest[..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        output.push(c.ok_or_else(|| XmlError::InvalidEntity {
+            entity: entity.to_owned(),
+        })?);
+        text = &rest[end + 1..];
+    }
+    output.push_str(text);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_documents() {
+        let root = parse(
+            r#"<?xml version="1.0"?>
+            <!-- A comment -->
+            <Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'>
+                <System>
+                    <Provider Name="Service Control Manager" Guid='{555908d1-a6d7-4695-8e1e-26931d2012f4}'/>
+                    <EventID Qualifiers='16384'>7036</EventID>
+                </System>
+                <EventData><Data Name='param1'>a &lt;b&gt; &amp; &#x43;&#68;</Data><Data><![CDATA[<raw>]]></Data></EventData>
+            </Event>
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!("Event", root.name);
+        assert_eq!("", root.text);
+        let system = root.child("System").unwrap();
+        assert_eq!(
+            Some("Service Control Manager"),
+            system.child("Provider").unwrap().attribute("Name")
+        );
+        let event_id = system.child("EventID").unwrap();
+        assert_eq!("7036", event_id.text);
+        assert_eq!(Some("16384"), event_id.attribute("Qualifiers"));
+        assert_eq!(Some("7036"), system.child_text("EventID"));
+        assert_eq!(None, system.child_text("Provider"));
+
+        let data = &root.child("EventData").unwrap().children;
+        assert_eq!("a <b> & CD", data[0].text);
+        assert_eq!("<raw>", data[1].text);
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        assert_eq!(Err(XmlError::UnexpectedEnd), parse("<Event><System>"));
+        assert_eq!(
+            Err(XmlError::MismatchedTag {
+                expected: "System".to_owned(),
+                actual: "Event".to_owned()
+            }),
+            parse("<Event><System></Event>")
+        );
+        assert_eq!(
+            Err(XmlError::InvalidEntity {
+                entity: "nbsp".to_owned()
+            }),
+            parse("<Event>&nbsp;</Event>")
+        );
+        assert_eq!(
+            Err(XmlError::Unexpected {
+                expected: "end of document",
+                position: 9
+            }),
+            parse("<Event/> <Event/>")
+        );
+        assert_eq!(
+            Err(XmlError::Unexpected {
+                expected: "a quoted value",
+                position: 12
+            }),
+            parse("<Event Name=value/>")
+        );
+    }
+}
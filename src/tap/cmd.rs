@@ -40,7 +40,11 @@ pub(crate) async fn cmd(opts: &super::Opts, signal_rx: SignalRx) -> exitcode::Ex
 /// Observe event flow from specified components
 pub async fn tap(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode::ExitCode {
     let subscription_url = opts.web_socket_url();
-    let output_channel = OutputChannel::Stdout(EventFormatter::new(opts.meta, opts.format));
+    // The trace IDs of the traced events are part of their metadata.
+    let output_channel = OutputChannel::Stdout(EventFormatter::new(
+        opts.meta || opts.trace_sample_rate.is_some(),
+        opts.format,
+    ));
     let tap_runner = TapRunner::new(
         &subscription_url,
         opts.inputs_of.clone(),
@@ -56,6 +60,7 @@ pub async fn tap(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode::ExitC
             exec_result = tap_runner.run_tap(
                 opts.interval as i64,
                 opts.limit as i64,
+                opts.trace_sample_rate.map(i64::from),
                 opts.duration_ms,
                 opts.quiet,
             ) => {
@@ -49,6 +49,12 @@ pub struct Opts {
     #[arg(short, long)]
     meta: bool,
 
+    /// Trace one in every N of the events sent by the sources, and print only the traced events
+    /// with their metadata, grouped by trace. The events derived from a traced event keep its
+    /// trace ID, showing the transforms it went through and what each of them emitted.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    trace_sample_rate: Option<u32>,
+
     /// Whether to reconnect if the underlying API connection drops. By default, tap will attempt to reconnect if the connection drops.
     #[arg(short, long)]
     no_reconnect: bool,
@@ -0,0 +1,155 @@
+//! The detection of the events that sinks hold for too long without acknowledging them.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use metrics::Gauge;
+use tokio::time::{Instant, MissedTickBehavior, interval};
+use tracing::Instrument;
+use vector_lib::{
+    event::{BatchNotifier, EventArray, EventContainer},
+    finalization::AddBatchNotifier,
+};
+
+use crate::internal_events::SinkAcknowledgementTimeout;
+
+/// The longest interval between two checks of the events held by a sink.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A batch of events held by a sink.
+struct HeldBatch {
+    received: Instant,
+    count: usize,
+    reported: bool,
+}
+
+/// The batches of events held by a sink, by their sequence number, so from the oldest one.
+#[derive(Default)]
+struct HeldBatches {
+    next: u64,
+    batches: BTreeMap<u64, HeldBatch>,
+}
+
+/// Tracks the events held by a sink until they're acknowledged, reporting the batches of events
+/// held for longer than the timeout and the age of the oldest event held.
+pub(super) struct AckTimeoutTracker {
+    held: Arc<Mutex<HeldBatches>>,
+}
+
+impl AckTimeoutTracker {
+    /// Creates a tracker, which checks the events held by the sink in the background until it's
+    /// dropped.
+    pub(super) fn new(timeout: Duration, oldest_age: Gauge) -> Self {
+        let held = Arc::new(Mutex::new(HeldBatches::default()));
+        tokio::spawn(watch(Arc::downgrade(&held), timeout, oldest_age).in_current_span());
+        Self { held }
+    }
+
+    /// Tracks the events of an array until all of them are finalized.
+    pub(super) fn track(&self, mut events: EventArray) -> EventArray {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        events.add_batch_notifier(batch);
+
+        let id = {
+            let mut held = self.held.lock().expect("mutex should not be poisoned");
+            let id = held.next;
+            held.next += 1;
+            held.batches.insert(
+                id,
+                HeldBatch {
+                    received: Instant::now(),
+                    count: events.len(),
+                    reported: false,
+                },
+            );
+            id
+        };
+
+        let held = Arc::downgrade(&self.held);
+        tokio::spawn(async move {
+            receiver.await;
+            if let Some(held) = held.upgrade() {
+                held.lock()
+                    .expect("mutex should not be poisoned")
+                    .batches
+                    .remove(&id);
+            }
+        });
+
+        events
+    }
+}
+
+async fn watch(held: Weak<Mutex<HeldBatches>>, timeout: Duration, oldest_age: Gauge) {
+    let mut checks = interval(CHECK_INTERVAL.min(timeout));
+    checks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        checks.tick().await;
+        let Some(held) = held.upgrade() else {
+            break;
+        };
+        let mut held = held.lock().expect("mutex should not be poisoned");
+        let now = Instant::now();
+
+        let oldest = held
+            .batches
+            .values()
+            .next()
+            .map_or(Duration::ZERO, |batch| now - batch.received);
+        oldest_age.set(oldest.as_secs_f64());
+
+        for (&id, batch) in &mut held.batches {
+            let age = now - batch.received;
+            if age < timeout {
+                break;
+            }
+            if !batch.reported {
+                batch.reported = true;
+                emit!(SinkAcknowledgementTimeout {
+                    batch: id,
+                    count: batch.count,
+                    held: age,
+                });
+            }
+        }
+    }
+    oldest_age.set(0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::LogEvent;
+
+    use super::*;
+
+    fn held(tracker: &AckTimeoutTracker) -> Vec<(u64, bool)> {
+        let held = tracker.held.lock().unwrap();
+        held.batches
+            .iter()
+            .map(|(&batch, held)| (batch, held.reported))
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_events_held_past_the_timeout() {
+        let tracker = AckTimeoutTracker::new(Duration::from_secs(10), Gauge::noop());
+        let first = tracker.track(EventArray::from(vec![LogEvent::from("first")]));
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        let second = tracker.track(EventArray::from(vec![LogEvent::from("second")]));
+        assert_eq!(held(&tracker), [(0, false), (1, false)]);
+
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        assert_eq!(held(&tracker), [(0, true), (1, false)]);
+
+        drop(first);
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        assert_eq!(held(&tracker), [(1, false)]);
+
+        drop(second);
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        assert!(held(&tracker).is_empty());
+    }
+}
@@ -3,10 +3,10 @@ use std::{
     future::ready,
     num::NonZeroUsize,
     sync::{Arc, LazyLock, Mutex},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use futures::{FutureExt, StreamExt, TryStreamExt, stream::FuturesOrdered};
+use futures::{FutureExt, Stream, StreamExt, TryStreamExt, stream::FuturesOrdered};
 use futures_util::stream::FuturesUnordered;
 use metrics::gauge;
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
@@ -22,7 +22,7 @@ use vector_lib::{
         BufferType, WhenFull,
         topology::{
             builder::TopologyBuilder,
-            channel::{BufferReceiver, BufferSender},
+            channel::{BufferReceiver, BufferSender, LimitedReceiver},
         },
     },
     config::LogNamespace,
@@ -33,6 +33,7 @@ use vector_lib::{
 
 use super::{
     BuiltBuffer, ConfigDiff,
+    ack_timeout::AckTimeoutTracker,
     fanout::{self, Fanout},
     schema,
     task::{Task, TaskOutput, TaskResult},
@@ -42,10 +43,11 @@ use crate::{
     config::{
         ComponentKey, Config, DataType, EnrichmentTableConfig, Input, Inputs, OutputId,
         ProxyConfig, SinkContext, SourceContext, TransformContext, TransformOuter, TransformOutput,
+        dead_letter::{DROPPED_OUTPUT, DeadLetterSender, DeadLetterTracker},
     },
-    event::{EventArray, EventContainer},
+    event::{EventArray, EventContainer, EventStatus, Finalizable},
     extra_context::ExtraContext,
-    internal_events::EventsReceived,
+    internal_events::{EventsReceived, config::ComponentBuilt},
     shutdown::SourceShutdownCoordinator,
     source_sender::{CHUNK_SIZE, SourceSenderItem},
     spawn_named,
@@ -69,7 +71,7 @@ static TRANSFORM_CONCURRENCY_LIMIT: LazyLock<usize> = LazyLock::new(|| {
         .unwrap_or_else(crate::num_threads)
 });
 
-const INTERNAL_SOURCES: [&str; 2] = ["internal_logs", "internal_metrics"];
+const INTERNAL_SOURCES: [&str; 3] = ["dead_letter", "internal_logs", "internal_metrics"];
 
 struct Builder<'a> {
     config: &'a super::Config,
@@ -80,6 +82,7 @@ struct Builder<'a> {
     tasks: HashMap<ComponentKey, Task>,
     buffers: HashMap<ComponentKey, BuiltBuffer>,
     inputs: HashMap<ComponentKey, (BufferSender<EventArray>, Inputs<OutputId>)>,
+    rewired_inputs: HashMap<ComponentKey, Inputs<OutputId>>,
     healthchecks: HashMap<ComponentKey, Task>,
     detach_triggers: HashMap<ComponentKey, Trigger>,
     extra_context: ExtraContext,
@@ -102,6 +105,7 @@ impl<'a> Builder<'a> {
             outputs: HashMap::new(),
             tasks: HashMap::new(),
             inputs: HashMap::new(),
+            rewired_inputs: HashMap::new(),
             healthchecks: HashMap::new(),
             detach_triggers: HashMap::new(),
             extra_context,
@@ -123,6 +127,7 @@ impl<'a> Builder<'a> {
         if self.errors.is_empty() {
             Ok(TopologyPieces {
                 inputs: self.inputs,
+                rewired_inputs: self.rewired_inputs,
                 outputs: Self::finalize_outputs(self.outputs),
                 tasks: self.tasks,
                 source_tasks,
@@ -251,9 +256,32 @@ impl<'a> Builder<'a> {
             let mut pumps = Vec::new();
             let mut controls = HashMap::new();
             let mut schema_definitions = HashMap::with_capacity(source_outputs.len());
+            let mut wal_failed = false;
 
             for output in source_outputs.into_iter() {
-                let mut rx = builder.add_source_output(output.clone(), key.clone());
+                let rx = builder.add_source_output(output.clone(), key.clone());
+
+                let wal = match &source.wal {
+                    Some(wal) => {
+                        let output_id = OutputId {
+                            component: key.clone(),
+                            port: output.port.clone(),
+                        };
+                        let wal_span = error_span!("source", buffer_type = "disk");
+                        match wal
+                            .build(self.config.global.data_dir.clone(), &output_id, wal_span)
+                            .await
+                        {
+                            Err(error) => {
+                                self.errors.push(format!("Source \"{key}\": {error}"));
+                                wal_failed = true;
+                                break;
+                            }
+                            Ok(wal) => Some(wal),
+                        }
+                    }
+                    None => None,
+                };
 
                 let (mut fanout, control) = Fanout::new();
                 let source_type = source.inner.get_component_name();
@@ -262,20 +290,22 @@ impl<'a> Builder<'a> {
                 let pump = async move {
                     debug!("Source pump starting.");
 
-                    while let Some(SourceSenderItem {
-                        events: mut array,
-                        send_reference,
-                    }) = rx.next().await
-                    {
-                        array.set_output_id(&source);
-                        array.set_source_type(source_type);
-                        fanout
-                            .send(array, Some(send_reference))
-                            .await
-                            .map_err(|e| {
-                                debug!("Source pump finished with an error.");
-                                TaskError::wrapped(e)
-                            })?;
+                    match wal {
+                        None => {
+                            let events = rx
+                                .into_stream()
+                                .map(|item| (item.events, Some(item.send_reference)));
+                            pump_source_output(events, &mut fanout, &source, source_type).await?;
+                        }
+                        // The events are forwarded from the write-ahead log, which only hands them
+                        // out once they're persisted.
+                        Some((wal_tx, wal_rx)) => {
+                            let events = wal_rx.into_stream().map(|array| (array, None));
+                            tokio::try_join!(
+                                persist_source_output(rx, wal_tx),
+                                pump_source_output(events, &mut fanout, &source, source_type),
+                            )?;
+                        }
                     }
 
                     debug!("Source pump finished normally.");
@@ -296,6 +326,9 @@ impl<'a> Builder<'a> {
                     schema_definitions.insert(port, definition);
                 }
             }
+            if wal_failed {
+                continue;
+            }
 
             let (pump_error_tx, mut pump_error_rx) = oneshot::channel();
             let pump = async move {
@@ -343,12 +376,17 @@ impl<'a> Builder<'a> {
                 shutdown: shutdown_signal,
                 out: pipeline,
                 proxy: ProxyConfig::merge_with_env(&self.config.global.proxy, &source.proxy),
-                acknowledgements: source.sink_acknowledgements,
+                // The sources with a write-ahead log acknowledge the events once they're persisted.
+                acknowledgements: source.sink_acknowledgements || source.wal.is_some(),
                 schema_definitions,
                 schema: self.config.schema,
                 extra_context: self.extra_context.clone(),
             };
+            let started = Instant::now();
             let source = source.inner.build(context).await;
+            emit!(ComponentBuilt {
+                duration: started.elapsed()
+            });
             let server = match source {
                 Err(error) => {
                     self.errors.push(format!("Source \"{key}\": {error}"));
@@ -422,6 +460,7 @@ impl<'a> Builder<'a> {
         enrichment_tables: &vector_lib::enrichment::TableRegistry,
     ) {
         let mut definition_cache = HashMap::default();
+        let mut builds = Vec::new();
 
         for (key, transform) in self
             .config
@@ -441,7 +480,7 @@ impl<'a> Builder<'a> {
                     // We have received an error whilst retrieving the definitions,
                     // there is no point in continuing.
 
-                    return;
+                    break;
                 }
             };
 
@@ -492,12 +531,33 @@ impl<'a> Builder<'a> {
                 self.config.schema.log_namespace(),
             );
 
-            let transform = match transform
-                .inner
-                .build(&context)
-                .instrument(span.clone())
-                .await
-            {
+            // The transforms are built in their own tasks, so that the ones that are expensive to
+            // build, such as those compiling VRL programs, are built in parallel.
+            let inner = transform.inner.clone();
+            let build = async move {
+                let started = Instant::now();
+                let transform = inner.build(&context).await;
+                emit!(ComponentBuilt {
+                    duration: started.elapsed()
+                });
+                transform
+            }
+            .instrument(span.clone());
+            builds.push((key, span, node, build));
+        }
+
+        let mut built = futures::stream::iter(builds.into_iter().map(
+            |(key, span, node, build)| async move {
+                let transform = tokio::spawn(build)
+                    .await
+                    .unwrap_or_else(|error| std::panic::resume_unwind(error.into_panic()));
+                (key, span, node, transform)
+            },
+        ))
+        .buffered(*TRANSFORM_CONCURRENCY_LIMIT);
+
+        while let Some((key, span, node, transform)) = built.next().await {
+            let transform = match transform {
                 Err(error) => {
                     self.errors.push(format!("Transform \"{key}\": {error}"));
                     continue;
@@ -523,6 +583,26 @@ impl<'a> Builder<'a> {
     }
 
     async fn build_sinks(&mut self, enrichment_tables: &vector_lib::enrichment::TableRegistry) {
+        // The sinks whose inputs are their only change keep running, but the schemas of their new
+        // inputs must still be validated.
+        for (key, sink) in self
+            .config
+            .sinks()
+            .filter(|(key, _)| self.diff.sinks.to_rewire.contains(*key))
+        {
+            debug!(component_id = %key, "Validating rewired sink.");
+
+            if let Err(mut err) = schema::validate_sink_expectations(
+                key,
+                sink,
+                self.config,
+                enrichment_tables.clone(),
+            ) {
+                self.errors.append(&mut err);
+            };
+            self.rewired_inputs.insert(key.clone(), sink.inputs.clone());
+        }
+
         let table_sinks = self
             .config
             .enrichment_tables
@@ -598,6 +678,7 @@ impl<'a> Builder<'a> {
             };
 
             let cx = SinkContext {
+                key: Some(key.clone()),
                 healthcheck,
                 globals: self.config.global.clone(),
                 enrichment_tables: enrichment_tables.clone(),
@@ -608,7 +689,12 @@ impl<'a> Builder<'a> {
                 extra_context: self.extra_context.clone(),
             };
 
-            let (sink, healthcheck) = match sink.inner.build(cx).await {
+            let started = Instant::now();
+            let built = sink.inner.build(cx).await;
+            emit!(ComponentBuilt {
+                duration: started.elapsed()
+            });
+            let (sink, healthcheck) = match built {
                 Err(error) => {
                     self.errors.push(format!("Sink \"{key}\": {error}"));
                     continue;
@@ -618,6 +704,16 @@ impl<'a> Builder<'a> {
 
             let (trigger, tripwire) = Tripwire::new();
 
+            let dead_letter = sink.dead_letter.then(|| {
+                DeadLetterTracker::new(DeadLetterSender::new(key, typetag, DROPPED_OUTPUT))
+            });
+            let ack_timeout = sink.acknowledgement_timeout_secs.map(|timeout| {
+                AckTimeoutTracker::new(
+                    Duration::from_secs(timeout.get()),
+                    gauge!("oldest_unacknowledged_event_age_seconds"),
+                )
+            });
+
             let utilization_sender = self
                 .utilization_emitter
                 .add_component(key.clone(), gauge!("utilization"));
@@ -649,6 +745,14 @@ impl<'a> Builder<'a> {
                                 events.estimated_json_encoded_size_of(),
                             ))
                         })
+                        .map(|events| match &dead_letter {
+                            Some(dead_letter) => dead_letter.track(events),
+                            None => events,
+                        })
+                        .map(|events| match &ack_timeout {
+                            Some(ack_timeout) => ack_timeout.track(events),
+                            None => events,
+                        })
                         .take_until_if(tripwire),
                 )
                 .await
@@ -757,6 +861,8 @@ pub async fn reload_enrichment_tables(config: &Config) {
 
 pub struct TopologyPieces {
     pub(super) inputs: HashMap<ComponentKey, (BufferSender<EventArray>, Inputs<OutputId>)>,
+    /// The new inputs of the running sinks which are only reconnected.
+    pub(super) rewired_inputs: HashMap<ComponentKey, Inputs<OutputId>>,
     pub(crate) outputs: HashMap<ComponentKey, HashMap<Option<String>, fanout::ControlChannel>>,
     pub(super) tasks: HashMap<ComponentKey, Task>,
     pub(crate) source_tasks: HashMap<ComponentKey, Task>,
@@ -838,6 +944,50 @@ impl TransformNode {
     }
 }
 
+/// Sends the events of a source output to its fanout.
+async fn pump_source_output(
+    events: impl Stream<Item = (EventArray, Option<Instant>)>,
+    fanout: &mut Fanout,
+    source: &Arc<ComponentKey>,
+    source_type: &'static str,
+) -> Result<(), TaskError> {
+    tokio::pin!(events);
+    while let Some((mut array, send_reference)) = events.next().await {
+        array.set_output_id(source);
+        array.set_source_type(source_type);
+        fanout.send(array, send_reference).await.map_err(|e| {
+            debug!("Source pump finished with an error.");
+            TaskError::wrapped(e)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Persists the events of a source output to its write-ahead log.
+///
+/// The events are only finalized once they're written to the log, so that the source acknowledges
+/// them to its clients once they're persisted.
+async fn persist_source_output(
+    mut rx: LimitedReceiver<SourceSenderItem>,
+    mut wal: BufferSender<EventArray>,
+) -> Result<(), TaskError> {
+    while let Some(SourceSenderItem {
+        events: mut array,
+        send_reference,
+    }) = rx.next().await
+    {
+        let finalizers = array.take_finalizers();
+        wal.send(array, Some(send_reference))
+            .await
+            .map_err(TaskError::wrapped)?;
+        wal.flush().await.map_err(TaskError::wrapped)?;
+        finalizers.update_status(EventStatus::Delivered);
+    }
+
+    Ok(())
+}
+
 fn build_transform(
     transform: Transform,
     node: TransformNode,
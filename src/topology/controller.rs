@@ -4,7 +4,7 @@ use futures_util::FutureExt as _;
 use tokio::sync::{Mutex, MutexGuard};
 
 #[cfg(feature = "api")]
-use crate::api;
+use crate::{api, signal::SignalTx};
 use crate::{
     config,
     extra_context::ExtraContext,
@@ -37,9 +37,14 @@ impl SharedTopologyController {
 pub struct TopologyController {
     pub topology: RunningTopology,
     pub config_paths: Vec<config::ConfigPath>,
+    /// The input of the running config, if it was received as a string rather than loaded from
+    /// the config paths, so that it can be loaded again when its secrets change.
+    pub config_input: Option<(String, config::Format)>,
     pub require_healthy: Option<bool>,
     #[cfg(feature = "api")]
     pub api_server: Option<api::Server>,
+    #[cfg(feature = "api")]
+    pub signal_tx: SignalTx,
     pub extra_context: ExtraContext,
 }
 
@@ -86,6 +91,7 @@ impl TopologyController {
                 self.topology.config(),
                 self.topology.watch(),
                 Arc::<AtomicBool>::clone(&self.topology.running),
+                self.signal_tx.clone(),
                 &Handle::current(),
             ) {
                 Ok(api_server) => {
@@ -10,6 +10,7 @@
 pub(super) use vector_lib::fanout;
 pub mod schema;
 
+mod ack_timeout;
 pub mod builder;
 mod controller;
 mod ready_arrays;
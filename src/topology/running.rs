@@ -24,7 +24,7 @@ use super::{
     BuiltBuffer, TaskHandle,
     builder::{self, TopologyPieces, reload_enrichment_tables},
     fanout::{ControlChannel, ControlMessage},
-    handle_errors, retain, take_healthchecks,
+    handle_errors, retain, schema, take_healthchecks,
     task::{Task, TaskOutput},
 };
 use crate::{
@@ -268,7 +268,13 @@ impl RunningTopology {
     ) -> Result<bool, ()> {
         info!("Reloading running topology with new configuration.");
 
-        if self.config.global != new_config.global {
+        // The rate limits of the internal logs are the only global options applied without a
+        // restart.
+        let mut new_global = new_config.global.clone();
+        new_global
+            .internal_log_rate_limit
+            .clone_from(&self.config.global.internal_log_rate_limit);
+        if self.config.global != new_global {
             match self.config.global.diff(&new_config.global) {
                 Ok(changed) => {
                     emit!(ConfigReloadRejected::global_options_changed(changed));
@@ -285,11 +291,18 @@ impl RunningTopology {
         // spawning the new version of the component.
         //
         // We also shutdown any component that is simply being removed entirely.
-        let diff = if let Some(components) = &self.pending_reload {
+        let mut diff = if let Some(components) = &self.pending_reload {
             ConfigDiff::new(&self.config, &new_config, components.clone())
         } else {
             ConfigDiff::new(&self.config, &new_config, HashSet::new())
         };
+        // The unchanged transforms whose input definitions are changed by an upstream component
+        // must be rebuilt too.
+        let input_schema_changed =
+            schema::transforms_with_changed_input_definitions(&self.config, &new_config, &diff);
+        diff.transforms
+            .to_change
+            .extend(input_schema_changed.iter().cloned());
         let buffers = self.shutdown_diff(&diff, &new_config).await;
 
         // Gives windows some time to make available any port
@@ -320,8 +333,16 @@ impl RunningTopology {
             {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
+                if self.config.global.internal_log_rate_limit
+                    != new_config.global.internal_log_rate_limit
+                {
+                    crate::trace::set_internal_log_rate_limit(
+                        &new_config.global.internal_log_rate_limit,
+                    );
+                }
                 self.config = new_config;
 
+                log_reload_summary(&diff, &input_schema_changed);
                 emit!(ConfigReloaded);
 
                 return Ok(true);
@@ -443,6 +464,19 @@ impl RunningTopology {
                     task.await.unwrap().unwrap();
                 }
             }
+
+            // The pumps of the changed sources with a write-ahead log drain it before it's reopened
+            // by the new sources.
+            for key in &diff.sources.to_change {
+                if self
+                    .config
+                    .source(key)
+                    .is_some_and(|source| source.wal.is_some())
+                    && let Some(pump) = self.tasks.remove(key)
+                {
+                    _ = pump.await;
+                }
+            }
         }
 
         // Next, we shutdown any changed/removed transforms.  Same as before: we want allow
@@ -753,6 +787,10 @@ impl RunningTopology {
                 self.inputs_tap_metadata
                     .insert(key.clone(), input.1.clone());
             }
+
+            for (key, inputs) in &new_pieces.rewired_inputs {
+                self.inputs_tap_metadata.insert(key.clone(), inputs.clone());
+            }
         }
 
         // We configure the outputs of any changed/added sources first, so they're available to any
@@ -791,6 +829,10 @@ impl RunningTopology {
             debug!(component_id = %key, "Connecting inputs for sink.");
             self.setup_inputs(key, diff, new_pieces).await;
         }
+        for (key, inputs) in &new_pieces.rewired_inputs {
+            debug!(component_id = %key, "Rewiring inputs for sink.");
+            self.rewire_inputs(key, diff, inputs);
+        }
         let added_changed_tables: Vec<&ComponentKey> = diff
             .enrichment_tables
             .changed_and_added()
@@ -931,6 +973,45 @@ impl RunningTopology {
             .map(|trigger| self.detach_triggers.insert(key.clone(), trigger.into()));
     }
 
+    /// Reconnects a running component, which isn't rebuilt, to its new inputs.
+    fn rewire_inputs(&mut self, key: &ComponentKey, diff: &ConfigDiff, inputs: &[OutputId]) {
+        let tx = self
+            .inputs
+            .get(key)
+            .cloned()
+            .expect("rewired component should be running");
+
+        let old_inputs = self
+            .config
+            .inputs_for_node(key)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        for input in old_inputs.iter().filter(|input| !inputs.contains(input)) {
+            // The outputs of the changed components were recreated without this component, and
+            // the ones of the removed components are gone.
+            if !diff.contains(&input.component)
+                && let Some(output) = self.outputs.get_mut(input)
+            {
+                debug!(component_id = %key, fanout_id = %input, "Removing component input from fanout.");
+
+                _ = output.send(ControlMessage::Remove(key.clone()));
+            }
+        }
+
+        for input in inputs {
+            if diff.contains(&input.component) || !old_inputs.contains(input) {
+                let output = self.outputs.get_mut(input).expect("unknown output");
+
+                debug!(component_id = %key, fanout_id = %input, "Adding component input to fanout.");
+
+                _ = output.send(ControlMessage::Add(key.clone(), tx.clone()));
+            }
+        }
+    }
+
     fn remove_outputs(&mut self, key: &ComponentKey) {
         self.outputs.retain(|id, _output| &id.component != key);
     }
@@ -1297,6 +1378,8 @@ impl RunningTopology {
             return None;
         }
 
+        crate::trace::set_internal_log_rate_limit(&config.global.internal_log_rate_limit);
+
         let mut utilization_emitter = pieces
             .utilization_emitter
             .take()
@@ -1333,6 +1416,39 @@ impl RunningTopology {
     }
 }
 
+/// Logs how each of the components affected by a reload was reloaded, the other ones being left
+/// running untouched.
+fn log_reload_summary(diff: &ConfigDiff, input_schema_changed: &HashSet<ComponentKey>) {
+    let differences = [
+        ("source", &diff.sources),
+        ("transform", &diff.transforms),
+        ("sink", &diff.sinks),
+    ];
+    for (component_kind, difference) in differences {
+        let actions = [
+            ("added", &difference.to_add),
+            ("changed", &difference.to_change),
+            ("removed", &difference.to_remove),
+            ("rewired", &difference.to_rewire),
+        ];
+        for (action, keys) in actions {
+            for key in keys {
+                let action = if input_schema_changed.contains(key) {
+                    "input_schema_changed"
+                } else {
+                    action
+                };
+                info!(
+                    message = "Component reloaded.",
+                    component_kind,
+                    component_id = %key,
+                    action,
+                );
+            }
+        }
+    }
+}
+
 fn get_changed_outputs(diff: &ConfigDiff, output_ids: Inputs<OutputId>) -> Vec<OutputId> {
     let mut changed_outputs = Vec::new();
 
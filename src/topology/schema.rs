@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use snafu::Snafu;
 use vector_lib::config::SourceOutput;
 
 pub(super) use crate::schema::Definition;
 use crate::{
-    config::{ComponentKey, Config, OutputId, SinkOuter, TransformOutput},
+    config::{ComponentKey, Config, ConfigDiff, OutputId, SinkOuter, TransformOutput},
     topology,
 };
 
@@ -296,6 +296,69 @@ fn contains_never(transform_definitions: &[(OutputId, Definition)]) -> bool {
         .any(|(_, definition)| definition.event_kind().is_never())
 }
 
+/// Returns the transforms left unchanged by a reload whose input definitions are changed anyway,
+/// by a change of one of the components upstream of them, such as a `remap` transform assigning
+/// new fields. These transforms must be rebuilt, as their outputs are built from the definitions of
+/// their inputs.
+///
+/// The transforms whose input definitions can't be computed, such as the ones downstream of a
+/// `remap` transform using an enrichment table, are also rebuilt.
+pub(super) fn transforms_with_changed_input_definitions(
+    old: &Config,
+    new: &Config,
+    diff: &ConfigDiff,
+) -> HashSet<ComponentKey> {
+    let is_upstream_changed = |key: &ComponentKey| {
+        let mut visited = HashSet::new();
+        let mut pending = vec![key];
+        while let Some(key) = pending.pop() {
+            for input in new.transform_inputs(key).unwrap_or_default() {
+                let upstream = &input.component;
+                if diff.sources.is_changed(upstream) || diff.transforms.is_changed(upstream) {
+                    return true;
+                }
+                if visited.insert(upstream) {
+                    pending.push(upstream);
+                }
+            }
+        }
+        false
+    };
+    let candidates = new
+        .transforms()
+        .filter(|(key, _)| !diff.transforms.contains(key) && is_upstream_changed(key))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return HashSet::new();
+    }
+
+    let (old_copy, new_copy) = (old.schema_copy(), new.schema_copy());
+    let enrichment_tables = vector_lib::enrichment::TableRegistry::default();
+    let (mut old_cache, mut new_cache) = (Cache::default(), Cache::default());
+    candidates
+        .into_iter()
+        .filter(|(_, transform)| {
+            let old_definitions = input_definitions(
+                &transform.inputs,
+                &old_copy,
+                enrichment_tables.clone(),
+                &mut old_cache,
+            );
+            let new_definitions = input_definitions(
+                &transform.inputs,
+                &new_copy,
+                enrichment_tables.clone(),
+                &mut new_cache,
+            );
+            match (old_definitions, new_definitions) {
+                (Ok(old_definitions), Ok(new_definitions)) => old_definitions != new_definitions,
+                _ => true,
+            }
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
 pub(super) fn validate_sink_expectations(
     key: &ComponentKey,
     sink: &SinkOuter<OutputId>,
@@ -18,8 +18,10 @@ use vector_lib::{
 };
 
 use crate::{
-    config::{Config, ConfigDiff, SinkOuter},
-    event::{Event, EventArray, EventContainer, LogEvent, into_event_stream},
+    config::{Config, ConfigDiff, SinkOuter, SourceWalConfig},
+    event::{
+        BatchNotifier, BatchStatus, Event, EventArray, EventContainer, LogEvent, into_event_stream,
+    },
     schema::Definition,
     source_sender::SourceSenderItem,
     test_util::{
@@ -288,7 +290,7 @@ async fn topology_remove_one_source() {
 
     let (mut in1, source1) = basic_source();
     let (mut in2, source2) = basic_source();
-    let (_out1, sink1) = basic_sink(10);
+    let (out1, sink1) = basic_sink(10);
 
     let mut config = Config::builder();
     config.add_source("in1", source1);
@@ -297,7 +299,8 @@ async fn topology_remove_one_source() {
 
     let (mut topology, _) = start_topology(config.build().unwrap(), false).await;
 
-    let (out1, sink1) = basic_sink(10);
+    // The inputs of the sink are its only change, so it keeps running and is only rewired:
+    let (unused_out1, sink1) = basic_sink(10);
 
     let mut config = Config::builder();
     config.add_source("in1", basic_source().1);
@@ -314,6 +317,7 @@ async fn topology_remove_one_source() {
     let mut event1 = Event::Log(LogEvent::from("this"));
     let event2 = Event::Log(LogEvent::from("that"));
     let h_out1 = tokio::spawn(out1.flat_map(into_event_stream).collect::<Vec<_>>());
+    let h_unused_out1 = tokio::spawn(unused_out1.flat_map(into_event_stream).collect::<Vec<_>>());
 
     in1.send_event(event1.clone()).await.unwrap();
     in2.send_event(event2.clone()).await.unwrap_err();
@@ -333,6 +337,7 @@ async fn topology_remove_one_source() {
 
     let res = h_out1.await.unwrap();
     assert_eq!(vec![event1], res);
+    assert_eq!(Vec::<Event>::new(), h_unused_out1.await.unwrap());
 }
 
 #[tokio::test]
@@ -444,7 +449,7 @@ async fn topology_swap_source() {
 
     // Add source #1 as `in1`, and sink #1 as `out1`, with sink #1 attached to `in1`:
     let (mut in1, source1) = basic_source();
-    let (out1, sink1) = basic_sink(10);
+    let (out1, sink1) = basic_sink_with_data(10, "v1");
 
     let mut config = Config::builder();
     config.add_source("in1", source1);
@@ -454,7 +459,7 @@ async fn topology_swap_source() {
 
     // Now, create sink #2 and replace `out2` with it, and add source #2 as `in2`, attached to `out1`:
     let (mut in2, source2) = basic_source();
-    let (out2, sink2) = basic_sink(10);
+    let (out2, sink2) = basic_sink_with_data(10, "v2");
 
     let mut config = Config::builder();
     config.add_source("in2", source2);
@@ -901,6 +906,7 @@ async fn topology_disk_buffer_flushes_on_idle() {
     sink1_outer.buffer = BufferConfig::Single(BufferType::DiskV2 {
         max_size: std::num::NonZeroU64::new(268435488).unwrap(),
         when_full: WhenFull::DropNewest,
+        encryption: None,
     });
     config.add_sink_outer("out1", sink1_outer);
 
@@ -934,6 +940,47 @@ async fn topology_disk_buffer_flushes_on_idle() {
     assert!(rest.is_empty());
 }
 
+#[tokio::test]
+async fn topology_source_wal_acknowledges_persisted_events() {
+    trace_init();
+
+    let tmpdir = tempfile::tempdir().expect("no tmpdir");
+    let (mut in1, source1) = basic_source();
+    let (mut out1, sink1) = basic_sink(10);
+
+    let mut config = Config::builder();
+    config.set_data_dir(tmpdir.path());
+    config.add_source("in1", source1);
+    config.sources[&ComponentKey::from("in1")].wal = Some(SourceWalConfig {
+        max_size: std::num::NonZeroU64::new(268435488).unwrap(),
+    });
+    config.add_sink("out1", &["in1"], sink1);
+
+    let (topology, _) = start_topology(config.build().unwrap(), false).await;
+
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    in1.send_event(LogEvent::from("foo").with_batch_notifier(&batch))
+        .await
+        .unwrap();
+    drop(batch);
+
+    // the event is acknowledged once it's persisted, while the sink still holds it
+    let status = tokio::time::timeout(Duration::from_secs(1), receiver)
+        .await
+        .expect("timeout 1");
+    assert_eq!(status, BatchStatus::Delivered);
+    assert!(tmpdir.path().join("wal/buffer/v2/in1").is_dir());
+
+    let res = tokio::time::timeout(Duration::from_secs(1), out1.next())
+        .await
+        .expect("timeout 2")
+        .map(|array| into_message(array.into_events().next().unwrap()))
+        .expect("no output");
+    assert_eq!("foo", res);
+
+    topology.stop().await;
+}
+
 #[tokio::test]
 async fn topology_transform_error_definition() {
     trace_init();
@@ -4,7 +4,7 @@ use std::{
     marker::PhantomData,
     str::FromStr,
     sync::{
-        Mutex, MutexGuard, OnceLock,
+        LazyLock, Mutex, MutexGuard, OnceLock,
         atomic::{AtomicBool, Ordering},
     },
 };
@@ -17,7 +17,7 @@ use tokio::sync::{
 };
 use tokio_stream::wrappers::BroadcastStream;
 use tracing::{Event, Subscriber};
-use tracing_limit::RateLimitedLayer;
+use tracing_limit::{RateLimitHandle, RateLimitOverrides, RateLimitedLayer};
 use tracing_subscriber::{
     Layer,
     filter::LevelFilter,
@@ -26,7 +26,7 @@ use tracing_subscriber::{
     util::SubscriberInitExt,
 };
 pub use tracing_tower::{InstrumentableService, InstrumentedService};
-use vector_lib::lookup::event_path;
+use vector_lib::{config::InternalLogRateLimit, lookup::event_path};
 use vrl::value::Value;
 
 use crate::event::LogEvent;
@@ -52,6 +52,10 @@ static SUBSCRIBERS: Mutex<Option<Vec<oneshot::Sender<Vec<LogEvent>>>>> =
 /// has been initialized.
 static SENDER: OnceLock<Sender<LogEvent>> = OnceLock::new();
 
+/// RATE_LIMITS holds the windows of the rate limits of the internal log events set by the config or the API, which
+/// override the one set by the `--internal-log-rate-limit` option in all of the rate limited layers.
+static RATE_LIMITS: LazyLock<RateLimitHandle> = LazyLock::new(RateLimitHandle::default);
+
 fn metrics_layer_enabled() -> bool {
     !matches!(std::env::var("DISABLE_INTERNAL_METRICS_TRACING_INTEGRATION"), Ok(x) if x == "true")
 }
@@ -66,6 +70,7 @@ pub fn init(color: bool, json: bool, levels: &str, internal_log_rate_limit: u64)
 
     let broadcast_layer = RateLimitedLayer::new(BroadcastLayer::new())
         .with_default_limit(internal_log_rate_limit)
+        .with_overrides(RATE_LIMITS.clone())
         .with_filter(fmt_filter.clone());
 
     let subscriber = tracing_subscriber::registry()
@@ -95,8 +100,9 @@ pub fn init(color: bool, json: bool, levels: &str, internal_log_rate_limit: u64)
         #[cfg(test)]
         let formatter = formatter.with_test_writer();
 
-        let rate_limited =
-            RateLimitedLayer::new(formatter).with_default_limit(internal_log_rate_limit);
+        let rate_limited = RateLimitedLayer::new(formatter)
+            .with_default_limit(internal_log_rate_limit)
+            .with_overrides(RATE_LIMITS.clone());
         let subscriber = subscriber.with(rate_limited.with_filter(fmt_filter));
 
         _ = subscriber.try_init();
@@ -108,14 +114,36 @@ pub fn init(color: bool, json: bool, levels: &str, internal_log_rate_limit: u64)
         #[cfg(test)]
         let formatter = formatter.with_test_writer();
 
-        let rate_limited =
-            RateLimitedLayer::new(formatter).with_default_limit(internal_log_rate_limit);
+        let rate_limited = RateLimitedLayer::new(formatter)
+            .with_default_limit(internal_log_rate_limit)
+            .with_overrides(RATE_LIMITS.clone());
         let subscriber = subscriber.with(rate_limited.with_filter(fmt_filter));
 
         _ = subscriber.try_init();
     }
 }
 
+/// Sets the windows of the rate limits of the internal log events, replacing the ones previously set.
+pub fn set_internal_log_rate_limit(rate_limit: &InternalLogRateLimit) {
+    RATE_LIMITS.set(RateLimitOverrides {
+        window_secs: rate_limit.window_secs,
+        components: rate_limit
+            .components
+            .iter()
+            .map(|(component, window_secs)| (component.clone(), *window_secs))
+            .collect(),
+    });
+}
+
+/// Gets the windows of the rate limits of the internal log events currently in effect.
+pub fn internal_log_rate_limit() -> InternalLogRateLimit {
+    let overrides = RATE_LIMITS.get();
+    InternalLogRateLimit {
+        window_secs: overrides.window_secs,
+        components: overrides.components.into_iter().collect(),
+    }
+}
+
 #[cfg(test)]
 pub fn reset_early_buffer() -> Option<Vec<LogEvent>> {
     get_early_buffer().replace(Vec::new())
@@ -0,0 +1,235 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use serde_with::serde_as;
+use vector_lib::{
+    config::LogNamespace,
+    configurable::configurable_component,
+    lookup::{lookup_v2::ConfigTargetPath, owned_value_path},
+};
+use vrl::value::Kind;
+
+use crate::{
+    config::{DataType, Input, OutputId, TransformConfig, TransformContext, TransformOutput},
+    schema::Definition,
+    transforms::{Transform, aggregate_v2::transform::AggregateV2},
+};
+
+/// Configuration for the `aggregate_v2` transform.
+#[serde_as]
+#[configurable_component(transform(
+    "aggregate_v2",
+    "Aggregate log and metric events over tumbling, hopping, or sliding time windows."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AggregateV2Config {
+    #[configurable(derived)]
+    pub window: WindowConfig,
+
+    /// An ordered list of event paths by which to group events.
+    ///
+    /// Each group with matching values at the specified paths is aggregated independently, and
+    /// the values are copied to the aggregated events. For metric events, the `.name`,
+    /// `.namespace`, and `.tags.<tag>` paths are supported.
+    ///
+    /// When not specified, all events of a window are aggregated in a single group.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = ".service", docs::examples = ".tags.host"))]
+    pub group_by: Vec<ConfigTargetPath>,
+
+    /// The aggregates to compute for each group and window.
+    pub aggregations: Vec<AggregationConfig>,
+
+    /// How long to wait for events arriving out of order, in milliseconds.
+    ///
+    /// The watermark of the transform trails the most recent event timestamp seen by this
+    /// amount, and a window is closed once the watermark passes its end. Events belonging only
+    /// to closed windows are dropped.
+    ///
+    /// When no newer events are received, the watermark advances with the wall clock, so that
+    /// windows are closed even if the input goes idle.
+    #[serde(default)]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[configurable(metadata(docs::examples = 5000))]
+    #[configurable(metadata(docs::human_name = "Allowed Lateness"))]
+    pub allowed_lateness_ms: Duration,
+
+    /// The interval to check for and close expired windows, in milliseconds.
+    #[serde(default = "default_flush_period_ms")]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Flush Period"))]
+    pub flush_period_ms: Duration,
+}
+
+/// The time windows events are aggregated over.
+///
+/// Windows are aligned to the Unix epoch, and events are assigned to windows based on their
+/// timestamp, or on the time they are received if they have none.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+#[configurable(metadata(docs::enum_tag_description = "The type of time window."))]
+pub enum WindowConfig {
+    /// Fixed-size, non-overlapping windows.
+    ///
+    /// Each event belongs to exactly one window.
+    Tumbling(TumblingWindowConfig),
+
+    /// Fixed-size windows starting every `hop_ms`.
+    ///
+    /// Windows overlap when `hop_ms` is smaller than `size_ms`, in which case each event
+    /// belongs to several windows.
+    Hopping(HoppingWindowConfig),
+
+    /// Windows ending at the timestamp of each event, covering the preceding `size_ms`.
+    ///
+    /// An aggregated event is emitted for each distinct event timestamp of a group.
+    Sliding(SlidingWindowConfig),
+}
+
+/// Tumbling window configuration.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct TumblingWindowConfig {
+    /// The size of each window, in milliseconds.
+    #[configurable(metadata(docs::examples = 60000))]
+    pub size_ms: u64,
+}
+
+/// Hopping window configuration.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct HoppingWindowConfig {
+    /// The size of each window, in milliseconds.
+    #[configurable(metadata(docs::examples = 60000))]
+    pub size_ms: u64,
+
+    /// The interval between the start of consecutive windows, in milliseconds.
+    ///
+    /// Must not be larger than `size_ms`.
+    #[configurable(metadata(docs::examples = 10000))]
+    pub hop_ms: u64,
+}
+
+/// Sliding window configuration.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct SlidingWindowConfig {
+    /// The size of each window, in milliseconds.
+    #[configurable(metadata(docs::examples = 60000))]
+    pub size_ms: u64,
+}
+
+/// An aggregate computed for each group and window.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationConfig {
+    /// The path the aggregate is written to in the aggregated events.
+    #[configurable(metadata(docs::examples = ".request_count", docs::examples = ".latency_p99"))]
+    pub target: ConfigTargetPath,
+
+    #[configurable(derived)]
+    pub function: AggregateFunction,
+
+    /// The path of the numeric value to aggregate in log events.
+    ///
+    /// Metric events are aggregated by their counter or gauge value instead. This option is not
+    /// used by the `count` function.
+    #[configurable(metadata(docs::examples = ".duration_ms"))]
+    pub field: Option<ConfigTargetPath>,
+}
+
+/// The function used to aggregate values.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateFunction {
+    /// The number of events.
+    Count,
+
+    /// The sum of the values.
+    Sum,
+
+    /// The smallest value.
+    Min,
+
+    /// The largest value.
+    Max,
+
+    /// The arithmetic mean of the values.
+    Mean,
+
+    /// The median of the values, estimated with a sketch.
+    P50,
+
+    /// The 95th percentile of the values, estimated with a sketch.
+    P95,
+
+    /// The 99th percentile of the values, estimated with a sketch.
+    P99,
+}
+
+const fn default_flush_period_ms() -> Duration {
+    Duration::from_millis(1000)
+}
+
+impl Default for AggregateV2Config {
+    fn default() -> Self {
+        Self {
+            window: WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 60000 }),
+            group_by: Vec::new(),
+            aggregations: vec![AggregationConfig {
+                target: ConfigTargetPath::try_from(".count".to_string()).expect("valid path"),
+                function: AggregateFunction::Count,
+                field: None,
+            }],
+            allowed_lateness_ms: Duration::ZERO,
+            flush_period_ms: default_flush_period_ms(),
+        }
+    }
+}
+
+impl_generate_config_from_default!(AggregateV2Config);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aggregate_v2")]
+impl TransformConfig for AggregateV2Config {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        AggregateV2::new(self).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::new(DataType::Log | DataType::Metric)
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, Definition)],
+        global_log_namespace: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        let schema_definition =
+            Definition::default_for_namespace(&BTreeSet::from([global_log_namespace]))
+                .with_event_field(&owned_value_path!("window_start"), Kind::timestamp(), None)
+                .with_event_field(&owned_value_path!("window_end"), Kind::timestamp(), None);
+
+        vec![TransformOutput::new(
+            DataType::Log,
+            input_definitions
+                .iter()
+                .map(|(output, _)| (output.clone(), schema_definition.clone()))
+                .collect(),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AggregateV2Config>();
+    }
+}
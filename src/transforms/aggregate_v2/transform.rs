@@ -0,0 +1,836 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    time::Duration,
+};
+
+use async_stream::stream;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use ordered_float::NotNan;
+use tokio::time::Instant;
+use vector_lib::{
+    event::{MetricValue, metric::Metric},
+    internal_event::{Count, InternalEventHandle as _, Registered},
+    lookup::{OwnedTargetPath, PathPrefix, event_path, lookup_v2::OwnedSegment},
+    metrics::AgentDDSketch,
+};
+
+use super::config::{AggregateFunction, AggregateV2Config, WindowConfig};
+use crate::{
+    event::{Event, LogEvent, Value},
+    internal_events::AggregateV2LateEventsDropped,
+    transforms::TaskTransform,
+};
+
+/// The time windows events are assigned to, with durations in milliseconds.
+#[derive(Clone, Copy, Debug)]
+enum WindowKind {
+    Tumbling { size: i64 },
+    Hopping { size: i64, hop: i64 },
+    Sliding { size: i64 },
+}
+
+impl WindowKind {
+    fn from_config(config: &WindowConfig) -> crate::Result<Self> {
+        let millis = |value: u64, option: &str| match i64::try_from(value) {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(format!(
+                "`window.{option}` must be a positive number of milliseconds."
+            )),
+        };
+
+        Ok(match config {
+            WindowConfig::Tumbling(config) => Self::Tumbling {
+                size: millis(config.size_ms, "size_ms")?,
+            },
+            WindowConfig::Hopping(config) => {
+                let size = millis(config.size_ms, "size_ms")?;
+                let hop = millis(config.hop_ms, "hop_ms")?;
+                if hop > size {
+                    return Err("`window.hop_ms` must not be larger than `window.size_ms`.".into());
+                }
+                Self::Hopping { size, hop }
+            }
+            WindowConfig::Sliding(config) => Self::Sliding {
+                size: millis(config.size_ms, "size_ms")?,
+            },
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Aggregation {
+    target: OwnedTargetPath,
+    function: AggregateFunction,
+    field: Option<OwnedTargetPath>,
+}
+
+/// The state of the aggregations of a group within a window.
+#[derive(Debug)]
+struct Accumulator {
+    count: u64,
+    /// A sketch for each aggregation, except for `count` which has none.
+    sketches: Vec<Option<AgentDDSketch>>,
+}
+
+impl Accumulator {
+    fn new(aggregations: &[Aggregation]) -> Self {
+        Self {
+            count: 0,
+            sketches: aggregations
+                .iter()
+                .map(|aggregation| {
+                    (aggregation.function != AggregateFunction::Count)
+                        .then(AgentDDSketch::with_agent_defaults)
+                })
+                .collect(),
+        }
+    }
+
+    fn record(&mut self, values: &[Option<f64>]) {
+        self.count += 1;
+        for (sketch, value) in self.sketches.iter_mut().zip(values) {
+            if let (Some(sketch), Some(value)) = (sketch, value) {
+                sketch.insert(*value);
+            }
+        }
+    }
+
+    fn write_into(&self, aggregations: &[Aggregation], log: &mut LogEvent) {
+        for (aggregation, sketch) in aggregations.iter().zip(&self.sketches) {
+            let value = match (aggregation.function, sketch) {
+                (AggregateFunction::Count, _) => {
+                    log.insert(&aggregation.target, self.count as i64);
+                    continue;
+                }
+                (_, None) => None,
+                (AggregateFunction::Sum, Some(sketch)) => Some(sketch.sum().unwrap_or(0.0)),
+                (AggregateFunction::Min, Some(sketch)) => sketch.min(),
+                (AggregateFunction::Max, Some(sketch)) => sketch.max(),
+                (AggregateFunction::Mean, Some(sketch)) => sketch.avg(),
+                (AggregateFunction::P50, Some(sketch)) => sketch.quantile(0.5),
+                (AggregateFunction::P95, Some(sketch)) => sketch.quantile(0.95),
+                (AggregateFunction::P99, Some(sketch)) => sketch.quantile(0.99),
+            };
+            // Aggregates of groups without any value are left out.
+            if let Some(value) = value.and_then(|value| NotNan::new(value).ok()) {
+                log.insert(&aggregation.target, Value::Float(value));
+            }
+        }
+    }
+}
+
+/// Groups are keyed by the JSON encoding of their values, which keeps values of different
+/// types apart.
+type GroupKey = Vec<Option<String>>;
+
+#[derive(Debug)]
+struct Group {
+    values: Vec<Option<Value>>,
+    accumulator: Accumulator,
+}
+
+/// An event recorded in a sliding window group.
+#[derive(Debug)]
+struct SlidingEntry {
+    timestamp: i64,
+    values: Vec<Option<f64>>,
+}
+
+#[derive(Debug)]
+struct SlidingGroup {
+    values: Vec<Option<Value>>,
+    /// Sorted by timestamp.
+    entries: Vec<SlidingEntry>,
+}
+
+pub struct AggregateV2 {
+    window: WindowKind,
+    group_by: Vec<OwnedTargetPath>,
+    aggregations: Vec<Aggregation>,
+    allowed_lateness: i64,
+    flush_period: Duration,
+
+    /// Open tumbling or hopping windows, by end time.
+    windows: BTreeMap<i64, HashMap<GroupKey, Group>>,
+    /// Groups of recorded events, for sliding windows.
+    sliding: HashMap<GroupKey, SlidingGroup>,
+    /// The latest event timestamp seen, and when it was first seen.
+    latest: Option<(i64, Instant)>,
+    /// The watermark up to which windows have been closed.
+    closed_until: Option<i64>,
+
+    late_events_dropped: Registered<AggregateV2LateEventsDropped>,
+}
+
+impl AggregateV2 {
+    pub fn new(config: &AggregateV2Config) -> crate::Result<Self> {
+        if config.aggregations.is_empty() {
+            return Err("At least one aggregation must be configured.".into());
+        }
+
+        Ok(Self {
+            window: WindowKind::from_config(&config.window)?,
+            group_by: config.group_by.iter().map(|path| path.0.clone()).collect(),
+            aggregations: config
+                .aggregations
+                .iter()
+                .map(|aggregation| Aggregation {
+                    target: aggregation.target.0.clone(),
+                    function: aggregation.function,
+                    field: aggregation.field.as_ref().map(|field| field.0.clone()),
+                })
+                .collect(),
+            allowed_lateness: i64::try_from(config.allowed_lateness_ms.as_millis())
+                .unwrap_or(i64::MAX),
+            flush_period: config.flush_period_ms,
+            windows: BTreeMap::new(),
+            sliding: HashMap::new(),
+            latest: None,
+            closed_until: None,
+            late_events_dropped: register!(AggregateV2LateEventsDropped),
+        })
+    }
+
+    fn is_closed(&self, end: i64) -> bool {
+        self.closed_until.is_some_and(|watermark| end <= watermark)
+    }
+
+    fn record(&mut self, event: Event, now: Instant) {
+        let timestamp = event_timestamp(&event)
+            .unwrap_or_else(Utc::now)
+            .timestamp_millis();
+        if self.latest.is_none_or(|(latest, _)| timestamp > latest) {
+            self.latest = Some((timestamp, now));
+        }
+
+        let group_values: Vec<_> = self
+            .group_by
+            .iter()
+            .map(|path| group_value(&event, path))
+            .collect();
+        let key: GroupKey = group_values
+            .iter()
+            .map(|value| {
+                value
+                    .as_ref()
+                    .map(|value| serde_json::to_string(value).unwrap_or_default())
+            })
+            .collect();
+        let values: Vec<_> = self
+            .aggregations
+            .iter()
+            .map(|aggregation| aggregation_value(&event, aggregation.field.as_ref()))
+            .collect();
+
+        let recorded = match self.window {
+            WindowKind::Tumbling { size } => {
+                let end = timestamp.div_euclid(size) * size + size;
+                self.record_window(end, key, group_values, &values)
+            }
+            WindowKind::Hopping { size, hop } => {
+                let mut recorded = false;
+                let mut start = timestamp.div_euclid(hop) * hop;
+                while start > timestamp - size {
+                    recorded |= self.record_window(
+                        start + size,
+                        key.clone(),
+                        group_values.clone(),
+                        &values,
+                    );
+                    start -= hop;
+                }
+                recorded
+            }
+            WindowKind::Sliding { .. } => {
+                if self.is_closed(timestamp) {
+                    false
+                } else {
+                    let group = self.sliding.entry(key).or_insert_with(|| SlidingGroup {
+                        values: group_values,
+                        entries: Vec::new(),
+                    });
+                    let index = group
+                        .entries
+                        .partition_point(|entry| entry.timestamp <= timestamp);
+                    group
+                        .entries
+                        .insert(index, SlidingEntry { timestamp, values });
+                    true
+                }
+            }
+        };
+
+        if !recorded {
+            self.late_events_dropped.emit(Count(1));
+        }
+    }
+
+    /// Records values in the window ending at `end`, unless it is already closed.
+    fn record_window(
+        &mut self,
+        end: i64,
+        key: GroupKey,
+        group_values: Vec<Option<Value>>,
+        values: &[Option<f64>],
+    ) -> bool {
+        if self.is_closed(end) {
+            return false;
+        }
+        let aggregations = &self.aggregations;
+        self.windows
+            .entry(end)
+            .or_default()
+            .entry(key)
+            .or_insert_with(|| Group {
+                values: group_values,
+                accumulator: Accumulator::new(aggregations),
+            })
+            .accumulator
+            .record(values);
+        true
+    }
+
+    /// The watermark trails the latest event timestamp by the allowed lateness, and advances
+    /// with the wall clock until a newer event is seen.
+    fn watermark(&self, now: Instant) -> Option<i64> {
+        self.latest.map(|(latest, seen)| {
+            let idle = i64::try_from((now - seen).as_millis()).unwrap_or(i64::MAX);
+            latest
+                .saturating_add(idle)
+                .saturating_sub(self.allowed_lateness)
+        })
+    }
+
+    fn flush_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        if let Some(watermark) = self.watermark(now) {
+            self.close_until(watermark, output);
+        }
+    }
+
+    fn flush_all_into(&mut self, output: &mut Vec<Event>) {
+        self.close_until(i64::MAX, output);
+    }
+
+    /// Emits the aggregates of all windows ending at or before the watermark.
+    fn close_until(&mut self, watermark: i64, output: &mut Vec<Event>) {
+        let previous = self.closed_until;
+        if previous.is_some_and(|previous| watermark <= previous) {
+            return;
+        }
+        self.closed_until = Some(watermark);
+
+        match self.window {
+            WindowKind::Tumbling { size } | WindowKind::Hopping { size, .. } => {
+                while let Some(entry) = self.windows.first_entry() {
+                    let end = *entry.key();
+                    if end > watermark {
+                        break;
+                    }
+                    for group in entry.remove().into_values() {
+                        output.push(self.aggregated_event(
+                            end - size,
+                            end,
+                            &group.values,
+                            &group.accumulator,
+                        ));
+                    }
+                }
+            }
+            WindowKind::Sliding { size } => {
+                for group in self.sliding.values_mut() {
+                    let entries = &group.entries;
+                    let mut emitted = None;
+                    for entry in entries {
+                        let end = entry.timestamp;
+                        if previous.is_some_and(|previous| end <= previous) || emitted == Some(end)
+                        {
+                            continue;
+                        }
+                        if end > watermark {
+                            break;
+                        }
+                        let first = entries.partition_point(|entry| entry.timestamp <= end - size);
+                        let last = entries.partition_point(|entry| entry.timestamp <= end);
+                        let mut accumulator = Accumulator::new(&self.aggregations);
+                        for entry in &entries[first..last] {
+                            accumulator.record(&entry.values);
+                        }
+                        output.push(aggregated_event(
+                            &self.group_by,
+                            &self.aggregations,
+                            end - size,
+                            end,
+                            &group.values,
+                            &accumulator,
+                        ));
+                        emitted = Some(end);
+                    }
+
+                    // Entries are only needed by the windows that are still open.
+                    let expired = group
+                        .entries
+                        .partition_point(|entry| entry.timestamp <= watermark.saturating_sub(size));
+                    group.entries.drain(..expired);
+                }
+                self.sliding.retain(|_, group| !group.entries.is_empty());
+            }
+        }
+    }
+
+    fn aggregated_event(
+        &self,
+        start: i64,
+        end: i64,
+        values: &[Option<Value>],
+        accumulator: &Accumulator,
+    ) -> Event {
+        aggregated_event(
+            &self.group_by,
+            &self.aggregations,
+            start,
+            end,
+            values,
+            accumulator,
+        )
+    }
+}
+
+fn aggregated_event(
+    group_by: &[OwnedTargetPath],
+    aggregations: &[Aggregation],
+    start: i64,
+    end: i64,
+    values: &[Option<Value>],
+    accumulator: &Accumulator,
+) -> Event {
+    let mut log = LogEvent::default();
+    for (path, value) in group_by.iter().zip(values) {
+        if let Some(value) = value {
+            log.insert(path, value.clone());
+        }
+    }
+    if let Some(start) = Utc.timestamp_millis_opt(start).single() {
+        log.insert(event_path!("window_start"), start);
+    }
+    if let Some(end) = Utc.timestamp_millis_opt(end).single() {
+        log.insert(event_path!("window_end"), end);
+    }
+    accumulator.write_into(aggregations, &mut log);
+    Event::Log(log)
+}
+
+fn event_timestamp(event: &Event) -> Option<DateTime<Utc>> {
+    match event {
+        Event::Log(log) => log.get_timestamp().and_then(|v| v.as_timestamp()).copied(),
+        Event::Metric(metric) => metric.timestamp(),
+        Event::Trace(_) => None,
+    }
+}
+
+fn group_value(event: &Event, path: &OwnedTargetPath) -> Option<Value> {
+    match event {
+        Event::Log(log) => log.get(path).cloned(),
+        Event::Metric(metric) => metric_group_value(metric, path),
+        Event::Trace(_) => None,
+    }
+}
+
+/// Resolves the paths of metric events that can be used to group them.
+fn metric_group_value(metric: &Metric, path: &OwnedTargetPath) -> Option<Value> {
+    if path.prefix != PathPrefix::Event {
+        return None;
+    }
+    match &path.path.segments[..] {
+        [OwnedSegment::Field(field)] if field.as_str() == "name" => Some(metric.name().into()),
+        [OwnedSegment::Field(field)] if field.as_str() == "namespace" => {
+            metric.namespace().map(Into::into)
+        }
+        [OwnedSegment::Field(field), OwnedSegment::Field(tag)] if field.as_str() == "tags" => {
+            metric.tag_value(tag.as_str()).map(Into::into)
+        }
+        _ => None,
+    }
+}
+
+fn aggregation_value(event: &Event, field: Option<&OwnedTargetPath>) -> Option<f64> {
+    match event {
+        Event::Log(log) => match log.get(field?)? {
+            Value::Integer(value) => Some(*value as f64),
+            Value::Float(value) => Some(value.into_inner()),
+            _ => None,
+        },
+        Event::Metric(metric) => match metric.value() {
+            MetricValue::Counter { value } | MetricValue::Gauge { value } => Some(*value),
+            _ => None,
+        },
+        Event::Trace(_) => None,
+    }
+}
+
+impl TaskTransform<Event> for AggregateV2 {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(self.flush_period);
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(Instant::now(), &mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_all_into(&mut output);
+                                done = true;
+                            }
+                            Some(event) => {
+                                let now = Instant::now();
+                                self.record(event, now);
+                                self.flush_into(now, &mut output);
+                            }
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use futures::stream;
+    use vector_lib::event::{MetricKind, metric::MetricTags};
+
+    use super::*;
+    use crate::transforms::aggregate_v2::config::{
+        AggregationConfig, HoppingWindowConfig, SlidingWindowConfig, TumblingWindowConfig,
+    };
+
+    fn aggregation(
+        target: &str,
+        function: AggregateFunction,
+        field: Option<&str>,
+    ) -> AggregationConfig {
+        AggregationConfig {
+            target: target.to_string().try_into().unwrap(),
+            function,
+            field: field.map(|field| field.to_string().try_into().unwrap()),
+        }
+    }
+
+    fn config(window: WindowConfig, aggregations: Vec<AggregationConfig>) -> AggregateV2Config {
+        AggregateV2Config {
+            window,
+            group_by: vec![".service".to_string().try_into().unwrap()],
+            aggregations,
+            ..Default::default()
+        }
+    }
+
+    fn log(timestamp_ms: i64, service: &str, duration: i64) -> Event {
+        let mut log = LogEvent::default();
+        log.insert(
+            event_path!("timestamp"),
+            Utc.timestamp_millis_opt(timestamp_ms).unwrap(),
+        );
+        log.insert(event_path!("service"), service);
+        log.insert(event_path!("duration"), duration);
+        Event::Log(log)
+    }
+
+    /// Returns the aggregated events as `(window_start, service, field)` sorted tuples.
+    fn collect(output: Vec<Event>, field: &str) -> Vec<(i64, String, Value)> {
+        let mut results: Vec<_> = output
+            .into_iter()
+            .map(|event| {
+                let log = event.into_log();
+                (
+                    log.get(event_path!("window_start"))
+                        .and_then(Value::as_timestamp)
+                        .unwrap()
+                        .timestamp_millis(),
+                    log.get(event_path!("service"))
+                        .map(|value| value.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    log.get(event_path!(field)).cloned().unwrap_or(Value::Null),
+                )
+            })
+            .collect();
+        results.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        results
+    }
+
+    #[test]
+    fn tumbling_windows_by_group() {
+        let mut aggregate = AggregateV2::new(&config(
+            WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 1000 }),
+            vec![
+                aggregation(".count", AggregateFunction::Count, None),
+                aggregation(".total", AggregateFunction::Sum, Some(".duration")),
+            ],
+        ))
+        .unwrap();
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        for event in [
+            log(0, "api", 10),
+            log(500, "api", 20),
+            log(900, "web", 5),
+            log(1200, "api", 7),
+        ] {
+            aggregate.record(event, now);
+            aggregate.flush_into(now, &mut output);
+        }
+        // Only the first window has closed.
+        assert_eq!(
+            collect(output.clone(), "count"),
+            vec![
+                (0, "api".into(), Value::from(2)),
+                (0, "web".into(), Value::from(1)),
+            ]
+        );
+        assert_eq!(
+            collect(std::mem::take(&mut output), "total"),
+            vec![
+                (0, "api".into(), Value::from(30.0)),
+                (0, "web".into(), Value::from(5.0)),
+            ]
+        );
+
+        aggregate.flush_all_into(&mut output);
+        assert_eq!(
+            collect(output, "count"),
+            vec![(1000, "api".into(), Value::from(1))]
+        );
+    }
+
+    #[test]
+    fn hopping_windows_overlap() {
+        let mut aggregate = AggregateV2::new(&config(
+            WindowConfig::Hopping(HoppingWindowConfig {
+                size_ms: 1000,
+                hop_ms: 500,
+            }),
+            vec![aggregation(".count", AggregateFunction::Count, None)],
+        ))
+        .unwrap();
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        for event in [log(100, "api", 1), log(600, "api", 1), log(1100, "api", 1)] {
+            aggregate.record(event, now);
+        }
+        aggregate.flush_all_into(&mut output);
+
+        assert_eq!(
+            collect(output, "count"),
+            vec![
+                (-500, "api".into(), Value::from(1)),
+                (0, "api".into(), Value::from(2)),
+                (500, "api".into(), Value::from(2)),
+                (1000, "api".into(), Value::from(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn sliding_windows_end_at_events() {
+        let mut aggregate = AggregateV2::new(&config(
+            WindowConfig::Sliding(SlidingWindowConfig { size_ms: 1000 }),
+            vec![aggregation(
+                ".max",
+                AggregateFunction::Max,
+                Some(".duration"),
+            )],
+        ))
+        .unwrap();
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        for event in [log(0, "api", 30), log(600, "api", 10), log(1500, "api", 20)] {
+            aggregate.record(event, now);
+            aggregate.flush_into(now, &mut output);
+        }
+        aggregate.flush_all_into(&mut output);
+
+        let results = collect(output, "max");
+        let maxima: Vec<_> = results
+            .iter()
+            .map(|(start, _, max)| (*start + 1000, max.as_float().unwrap().into_inner().round()))
+            .collect();
+        assert_eq!(maxima, vec![(0, 30.0), (600, 30.0), (1500, 20.0)]);
+    }
+
+    #[test]
+    fn late_events_are_dropped() {
+        let mut aggregate = AggregateV2::new(&AggregateV2Config {
+            allowed_lateness_ms: Duration::from_millis(500),
+            ..config(
+                WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 1000 }),
+                vec![aggregation(".count", AggregateFunction::Count, None)],
+            )
+        })
+        .unwrap();
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        // Within the allowed lateness, the first window is still open.
+        for event in [log(900, "api", 1), log(1400, "api", 1), log(800, "api", 1)] {
+            aggregate.record(event, now);
+            aggregate.flush_into(now, &mut output);
+        }
+        assert!(output.is_empty());
+
+        // The watermark passes the end of the first window, closing it.
+        for event in [log(1600, "api", 1), log(700, "api", 1)] {
+            aggregate.record(event, now);
+            aggregate.flush_into(now, &mut output);
+        }
+        assert_eq!(
+            collect(std::mem::take(&mut output), "count"),
+            vec![(0, "api".into(), Value::from(2))]
+        );
+
+        aggregate.flush_all_into(&mut output);
+        assert_eq!(
+            collect(output, "count"),
+            vec![(1000, "api".into(), Value::from(2))]
+        );
+    }
+
+    #[test]
+    fn idle_windows_close_with_wall_clock() {
+        let mut aggregate = AggregateV2::new(&config(
+            WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 1000 }),
+            vec![aggregation(".count", AggregateFunction::Count, None)],
+        ))
+        .unwrap();
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        aggregate.record(log(200, "api", 1), now);
+        aggregate.flush_into(now + Duration::from_millis(500), &mut output);
+        assert!(output.is_empty());
+
+        aggregate.flush_into(now + Duration::from_millis(800), &mut output);
+        assert_eq!(
+            collect(output, "count"),
+            vec![(0, "api".into(), Value::from(1))]
+        );
+    }
+
+    #[test]
+    fn percentiles_of_metrics() {
+        let mut aggregate = AggregateV2::new(&AggregateV2Config {
+            window: WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 60000 }),
+            group_by: vec![".tags.host".to_string().try_into().unwrap()],
+            aggregations: vec![
+                aggregation(".p50", AggregateFunction::P50, None),
+                aggregation(".p99", AggregateFunction::P99, None),
+                aggregation(".mean", AggregateFunction::Mean, None),
+            ],
+            ..Default::default()
+        })
+        .unwrap();
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        for value in 1..=100 {
+            let metric = Metric::new(
+                "latency",
+                MetricKind::Absolute,
+                MetricValue::Gauge {
+                    value: value as f64,
+                },
+            )
+            .with_tags(Some(MetricTags::from(BTreeMap::from([(
+                "host".to_string(),
+                "a".to_string(),
+            )]))))
+            .with_timestamp(Utc.timestamp_millis_opt(1000).single());
+            aggregate.record(Event::Metric(metric), now);
+        }
+        aggregate.flush_all_into(&mut output);
+
+        assert_eq!(output.len(), 1);
+        let log = output.remove(0).into_log();
+        assert_eq!(
+            log.get(event_path!("tags", "host")),
+            Some(&Value::from("a"))
+        );
+        let value = |field| {
+            log.get(event_path!(field))
+                .unwrap()
+                .as_float()
+                .unwrap()
+                .into_inner()
+        };
+        assert!((value("p50") - 50.0).abs() <= 1.0);
+        assert!((value("p99") - 99.0).abs() <= 2.0);
+        assert_eq!(value("mean"), 50.5);
+    }
+
+    #[test]
+    fn invalid_windows() {
+        assert!(
+            AggregateV2::new(&config(
+                WindowConfig::Hopping(HoppingWindowConfig {
+                    size_ms: 1000,
+                    hop_ms: 2000,
+                }),
+                vec![aggregation(".count", AggregateFunction::Count, None)],
+            ))
+            .is_err()
+        );
+        assert!(
+            AggregateV2::new(&config(
+                WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 0 }),
+                vec![aggregation(".count", AggregateFunction::Count, None)],
+            ))
+            .is_err()
+        );
+        assert!(
+            AggregateV2::new(&config(
+                WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 1000 }),
+                Vec::new(),
+            ))
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn flushes_open_windows_at_shutdown() {
+        let aggregate = AggregateV2::new(&config(
+            WindowConfig::Tumbling(TumblingWindowConfig { size_ms: 60000 }),
+            vec![aggregation(".count", AggregateFunction::Count, None)],
+        ))
+        .unwrap();
+        let input = stream::iter([log(0, "api", 1), log(1, "api", 1)]);
+
+        let output: Vec<_> = Box::new(aggregate)
+            .transform(Box::pin(input))
+            .collect()
+            .await;
+
+        assert_eq!(
+            collect(output, "count"),
+            vec![(0, "api".into(), Value::from(2))]
+        );
+    }
+}
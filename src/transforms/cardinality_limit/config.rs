@@ -0,0 +1,165 @@
+use std::{
+    collections::HashMap,
+    num::{NonZeroU64, NonZeroUsize},
+};
+
+use vector_lib::{config::LogNamespace, configurable::configurable_component};
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    schema,
+    transforms::{Transform, cardinality_limit::CardinalityLimit},
+};
+
+/// Configuration for the `cardinality_limit` transform.
+#[configurable_component(transform(
+    "cardinality_limit",
+    "Limit the number of active metric series as a safeguard for downstream metrics stores."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CardinalityLimitConfig {
+    /// The limits to enforce.
+    ///
+    /// Limits are applied in order, and each one sees the metrics as changed by the previous ones.
+    pub limits: Vec<LimitConfig>,
+
+    /// How long a series is considered active after its last event, in seconds.
+    ///
+    /// Series that have been inactive for longer no longer count towards the limits.
+    #[serde(default = "default_series_ttl_secs")]
+    #[configurable(metadata(docs::human_name = "Series TTL"))]
+    pub series_ttl_secs: NonZeroU64,
+}
+
+/// A limit on the number of active series of a group of metrics.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct LimitConfig {
+    /// The name of the limit.
+    ///
+    /// This is added as the `limit` tag to the internal metrics of the limit.
+    #[configurable(metadata(docs::examples = "per_host"))]
+    pub name: String,
+
+    /// The maximum number of active series for each group of metrics.
+    #[configurable(metadata(docs::examples = 10000))]
+    pub max_series: NonZeroUsize,
+
+    /// The tags that metrics are grouped by.
+    ///
+    /// Each distinct combination of the values of these tags is limited separately. Metrics
+    /// without a tag are grouped with the other metrics without it.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "host"))]
+    pub group_by: Vec<String>,
+
+    /// Whether the series of each metric are limited separately.
+    ///
+    /// If false, the series of all metrics in a group count towards the same limit.
+    #[serde(default = "crate::serde::default_true")]
+    pub per_metric: bool,
+
+    #[serde(flatten)]
+    pub action: LimitExceededAction,
+}
+
+/// Possible actions to take when an event arrives for a new series that would exceed a limit.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "limit_exceeded_action", rename_all = "snake_case")]
+#[configurable(metadata(
+    docs::enum_tag_description = "The action to take when a new series would exceed the limit."
+))]
+pub enum LimitExceededAction {
+    /// Drop the event.
+    DropEvent,
+
+    /// Fold the event into a single overflow series for its group.
+    ///
+    /// All the tags of the event are removed, except those in `group_by`, and the `overflow_tag`
+    /// tag is added with a value of `true`. Absolute metrics are first converted to incremental
+    /// ones, tracking the previous value of each overflowed series for `series_ttl_secs`, so that
+    /// the overflow series can be aggregated by downstream metrics stores. The first value of an
+    /// absolute series is dropped, as there is nothing to compare it to.
+    Overflow {
+        /// The name of the tag marking overflow series.
+        #[serde(default = "default_overflow_tag")]
+        #[configurable(metadata(docs::examples = "cardinality_overflow"))]
+        overflow_tag: String,
+    },
+
+    /// Drop the given tags from the event.
+    ///
+    /// The event is then sent on without counting towards the limit.
+    DropTag {
+        /// The tags to drop.
+        #[configurable(metadata(docs::examples = "user_id"))]
+        drop_tags: Vec<String>,
+    },
+}
+
+const fn default_series_ttl_secs() -> NonZeroU64 {
+    NonZeroU64::new(300).unwrap()
+}
+
+fn default_overflow_tag() -> String {
+    "cardinality_overflow".to_string()
+}
+
+impl GenerateConfig for CardinalityLimitConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            limits: vec![LimitConfig {
+                name: "per_host".to_string(),
+                max_series: NonZeroUsize::new(10_000).unwrap(),
+                group_by: vec!["host".to_string()],
+                per_metric: true,
+                action: LimitExceededAction::Overflow {
+                    overflow_tag: default_overflow_tag(),
+                },
+            }],
+            series_ttl_secs: default_series_ttl_secs(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "cardinality_limit")]
+impl TransformConfig for CardinalityLimitConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.limits.is_empty() {
+            return Err("At least one limit must be configured in `limits`".into());
+        }
+        for limit in &self.limits {
+            if let LimitExceededAction::DropTag { drop_tags } = &limit.action
+                && drop_tags.is_empty()
+            {
+                return Err(format!(
+                    "`drop_tags` must not be empty for limit {:?} using the `drop_tag` action",
+                    limit.name
+                )
+                .into());
+            }
+        }
+
+        Ok(Transform::event_task(CardinalityLimit::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        _: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        vec![TransformOutput::new(DataType::Metric, HashMap::new())]
+    }
+}
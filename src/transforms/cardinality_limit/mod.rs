@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    future::ready,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt};
+use seahash::SeaHasher;
+
+use crate::{
+    event::{Event, Metric},
+    internal_events::{
+        CardinalityLimitActiveSeries, CardinalityLimitRejectingEvent,
+        CardinalityLimitRewritingSeries,
+    },
+    sinks::util::buffer::metrics::{MetricSet, MetricSetSettings},
+    transforms::TaskTransform,
+};
+
+mod config;
+
+#[cfg(test)]
+mod tests;
+
+pub use config::{CardinalityLimitConfig, LimitConfig, LimitExceededAction};
+
+/// The longest time between two checks for inactive series.
+const MAX_EXPIRATION_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Identifies a group of metrics limited together: the metric, if limited separately, and the
+/// values of the `group_by` tags.
+type GroupKey = (Option<(Option<String>, String)>, Vec<Option<String>>);
+
+/// The active series of a limit, by group, along with when they were last seen.
+struct Limit {
+    config: LimitConfig,
+    groups: HashMap<GroupKey, HashMap<u64, Instant>>,
+    /// The last values of the absolute series sent to the overflow series, used to turn them into
+    /// incremental values that can be merged.
+    overflowed: MetricSet,
+}
+
+impl Limit {
+    fn group_key(&self, metric: &Metric) -> GroupKey {
+        let metric_id = self.config.per_metric.then(|| {
+            (
+                metric.namespace().map(ToOwned::to_owned),
+                metric.name().to_owned(),
+            )
+        });
+        let tags = self
+            .config
+            .group_by
+            .iter()
+            .map(|tag| metric.tag_value(tag))
+            .collect();
+        (metric_id, tags)
+    }
+
+    /// Records the series of the metric as active, returning false if it's a new series that would
+    /// exceed the limit.
+    fn try_accept(&mut self, metric: &Metric, now: Instant) -> bool {
+        let max_series = self.config.max_series.get();
+        let key = self.group_key(metric);
+        let series = self.groups.entry(key).or_default();
+        let hash = hash_series(metric);
+
+        if let Some(last_seen) = series.get_mut(&hash) {
+            *last_seen = now;
+            true
+        } else if series.len() < max_series {
+            series.insert(hash, now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Applies the configured action to a metric exceeding the limit, returning the metric if it's
+    /// sent on.
+    fn reject(&mut self, mut metric: Metric) -> Option<Metric> {
+        match &self.config.action {
+            LimitExceededAction::DropEvent => {
+                emit!(CardinalityLimitRejectingEvent {
+                    limit: &self.config.name,
+                });
+                return None;
+            }
+            LimitExceededAction::Overflow { overflow_tag } => {
+                // Values of different series can only be merged as increments, so the first
+                // absolute value of a series is only kept as the reference for the next one.
+                metric = self.overflowed.make_incremental(metric)?;
+                if let Some(tags) = metric.tags_mut() {
+                    tags.retain(|key, _| self.config.group_by.iter().any(|tag| tag == key));
+                }
+                metric.replace_tag(overflow_tag.clone(), "true".to_string());
+            }
+            LimitExceededAction::DropTag { drop_tags } => {
+                for tag in drop_tags {
+                    metric.remove_tag(tag);
+                }
+            }
+        }
+
+        emit!(CardinalityLimitRewritingSeries {
+            limit: &self.config.name,
+            action: self.config.action.as_str(),
+        });
+        Some(metric)
+    }
+
+    fn expire(&mut self, now: Instant, ttl: Duration) {
+        self.groups.retain(|_, series| {
+            series.retain(|_, last_seen| now.saturating_duration_since(*last_seen) < ttl);
+            !series.is_empty()
+        });
+
+        emit!(CardinalityLimitActiveSeries {
+            limit: &self.config.name,
+            count: self.groups.values().map(HashMap::len).sum(),
+        });
+    }
+}
+
+impl LimitExceededAction {
+    const fn as_str(&self) -> &'static str {
+        match self {
+            Self::DropEvent => "drop_event",
+            Self::Overflow { .. } => "overflow",
+            Self::DropTag { .. } => "drop_tag",
+        }
+    }
+}
+
+fn hash_series(metric: &Metric) -> u64 {
+    let mut hasher = SeaHasher::default();
+    metric.series().hash(&mut hasher);
+    hasher.finish()
+}
+
+pub struct CardinalityLimit {
+    limits: Vec<Limit>,
+    series_ttl: Duration,
+    expiration_interval: Duration,
+    expired_at: Instant,
+}
+
+impl CardinalityLimit {
+    fn new(config: &CardinalityLimitConfig) -> Self {
+        let series_ttl = Duration::from_secs(config.series_ttl_secs.get());
+        Self {
+            limits: config
+                .limits
+                .iter()
+                .map(|limit| Limit {
+                    config: limit.clone(),
+                    groups: HashMap::new(),
+                    overflowed: MetricSet::new(MetricSetSettings {
+                        time_to_live: Some(config.series_ttl_secs.get()),
+                        ..Default::default()
+                    }),
+                })
+                .collect(),
+            series_ttl,
+            expiration_interval: series_ttl.min(MAX_EXPIRATION_INTERVAL),
+            expired_at: Instant::now(),
+        }
+    }
+
+    fn transform_one(&mut self, event: Event) -> Option<Event> {
+        self.transform_one_at(event, Instant::now())
+    }
+
+    fn transform_one_at(&mut self, event: Event, now: Instant) -> Option<Event> {
+        if now.saturating_duration_since(self.expired_at) >= self.expiration_interval {
+            for limit in &mut self.limits {
+                limit.expire(now, self.series_ttl);
+            }
+            self.expired_at = now;
+        }
+
+        let mut metric = event.into_metric();
+        for limit in &mut self.limits {
+            if !limit.try_accept(&metric, now) {
+                metric = limit.reject(metric)?;
+            }
+        }
+        Some(Event::Metric(metric))
+    }
+}
+
+impl TaskTransform<Event> for CardinalityLimit {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut inner = self;
+        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+    }
+}
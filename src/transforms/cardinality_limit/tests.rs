@@ -0,0 +1,208 @@
+use std::num::{NonZeroU64, NonZeroUsize};
+
+use vector_lib::metric_tags;
+
+use super::*;
+use crate::event::{MetricTags, metric};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<CardinalityLimitConfig>();
+}
+
+fn make_metric(name: &str, tags: MetricTags) -> Event {
+    Event::Metric(
+        Metric::new(
+            name,
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(tags)),
+    )
+}
+
+fn make_transform(
+    max_series: usize,
+    group_by: &[&str],
+    per_metric: bool,
+    action: LimitExceededAction,
+) -> CardinalityLimit {
+    CardinalityLimit::new(&CardinalityLimitConfig {
+        limits: vec![LimitConfig {
+            name: "test".to_string(),
+            max_series: NonZeroUsize::new(max_series).unwrap(),
+            group_by: group_by.iter().map(ToString::to_string).collect(),
+            per_metric,
+            action,
+        }],
+        series_ttl_secs: NonZeroU64::new(60).unwrap(),
+    })
+}
+
+fn transform_tags(
+    transform: &mut CardinalityLimit,
+    event: Event,
+    now: Instant,
+) -> Option<MetricTags> {
+    transform
+        .transform_one_at(event, now)
+        .map(|event| event.into_metric().tags().cloned().unwrap_or_default())
+}
+
+#[test]
+fn drop_event() {
+    let mut transform = make_transform(2, &[], true, LimitExceededAction::DropEvent);
+    let now = Instant::now();
+
+    let a = make_metric("requests", metric_tags!("user" => "a"));
+    let b = make_metric("requests", metric_tags!("user" => "b"));
+    let c = make_metric("requests", metric_tags!("user" => "c"));
+    let other = make_metric("errors", metric_tags!("user" => "c"));
+
+    assert!(transform.transform_one_at(a.clone(), now).is_some());
+    assert!(transform.transform_one_at(b, now).is_some());
+    assert!(transform.transform_one_at(c, now).is_none());
+    // Known series and series of other metrics are still accepted.
+    assert!(transform.transform_one_at(a, now).is_some());
+    assert!(transform.transform_one_at(other, now).is_some());
+}
+
+#[test]
+fn limits_all_metrics_together() {
+    let mut transform = make_transform(1, &[], false, LimitExceededAction::DropEvent);
+    let now = Instant::now();
+
+    let a = make_metric("requests", metric_tags!("user" => "a"));
+    let other = make_metric("errors", metric_tags!("user" => "a"));
+
+    assert!(transform.transform_one_at(a, now).is_some());
+    assert!(transform.transform_one_at(other, now).is_none());
+}
+
+#[test]
+fn limits_groups_separately() {
+    let mut transform = make_transform(1, &["host"], true, LimitExceededAction::DropEvent);
+    let now = Instant::now();
+
+    let a1 = make_metric("requests", metric_tags!("host" => "1", "user" => "a"));
+    let b1 = make_metric("requests", metric_tags!("host" => "1", "user" => "b"));
+    let b2 = make_metric("requests", metric_tags!("host" => "2", "user" => "b"));
+
+    assert!(transform.transform_one_at(a1, now).is_some());
+    assert!(transform.transform_one_at(b1, now).is_none());
+    assert!(transform.transform_one_at(b2, now).is_some());
+}
+
+#[test]
+fn overflow() {
+    let mut transform = make_transform(
+        1,
+        &["host"],
+        true,
+        LimitExceededAction::Overflow {
+            overflow_tag: "overflow".to_string(),
+        },
+    );
+    let now = Instant::now();
+
+    let a = make_metric("requests", metric_tags!("host" => "1", "user" => "a"));
+    let b = make_metric("requests", metric_tags!("host" => "1", "user" => "b"));
+
+    assert_eq!(
+        transform_tags(&mut transform, a, now),
+        Some(metric_tags!("host" => "1", "user" => "a"))
+    );
+    assert_eq!(
+        transform_tags(&mut transform, b, now),
+        Some(metric_tags!("host" => "1", "overflow" => "true"))
+    );
+}
+
+#[test]
+fn overflow_makes_absolute_metrics_incremental() {
+    let mut transform = make_transform(
+        1,
+        &[],
+        true,
+        LimitExceededAction::Overflow {
+            overflow_tag: "overflow".to_string(),
+        },
+    );
+    let now = Instant::now();
+
+    let gauge = |user: &str, value: f64| {
+        Event::Metric(
+            Metric::new(
+                "connections",
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Gauge { value },
+            )
+            .with_tags(Some(metric_tags!("user" => user))),
+        )
+    };
+    let mut transform_value = |event| {
+        transform.transform_one_at(event, now).map(|event| {
+            let metric = event.into_metric();
+            (metric.kind(), metric.value().clone())
+        })
+    };
+
+    assert!(transform_value(gauge("a", 1.0)).is_some());
+    // The first values of overflowed series are only kept as references.
+    assert!(transform_value(gauge("b", 5.0)).is_none());
+    assert!(transform_value(gauge("c", 3.0)).is_none());
+    assert_eq!(
+        transform_value(gauge("b", 7.0)),
+        Some((
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Gauge { value: 2.0 }
+        ))
+    );
+    assert_eq!(
+        transform_value(gauge("c", 2.0)),
+        Some((
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Gauge { value: -1.0 }
+        ))
+    );
+}
+
+#[test]
+fn drop_tag() {
+    let mut transform = make_transform(
+        1,
+        &[],
+        true,
+        LimitExceededAction::DropTag {
+            drop_tags: vec!["user".to_string()],
+        },
+    );
+    let now = Instant::now();
+
+    let a = make_metric("requests", metric_tags!("host" => "1", "user" => "a"));
+    let b = make_metric("requests", metric_tags!("host" => "1", "user" => "b"));
+
+    assert_eq!(
+        transform_tags(&mut transform, a, now),
+        Some(metric_tags!("host" => "1", "user" => "a"))
+    );
+    assert_eq!(
+        transform_tags(&mut transform, b, now),
+        Some(metric_tags!("host" => "1"))
+    );
+}
+
+#[test]
+fn inactive_series_expire() {
+    let mut transform = make_transform(1, &[], true, LimitExceededAction::DropEvent);
+    let now = Instant::now();
+
+    let a = make_metric("requests", metric_tags!("user" => "a"));
+    let b = make_metric("requests", metric_tags!("user" => "b"));
+
+    assert!(transform.transform_one_at(a, now).is_some());
+    assert!(transform.transform_one_at(b.clone(), now).is_none());
+
+    let later = now + Duration::from_secs(60);
+    assert!(transform.transform_one_at(b, later).is_some());
+}
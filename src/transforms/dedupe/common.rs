@@ -1,4 +1,7 @@
-use std::{num::NonZeroUsize, time::Duration};
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    time::Duration,
+};
 
 use serde_with::serde_as;
 use vector_lib::{configurable::configurable_component, lookup::lookup_v2::ConfigTargetPath};
@@ -29,6 +32,40 @@ pub struct TimedCacheConfig {
     pub refresh_on_drop: bool,
 }
 
+/// Configuration for deduplicating with a probabilistic cache.
+///
+/// Instead of remembering the last `cache.num_events` events exactly, seen events are remembered
+/// in Bloom filters holding up to `cache.num_events` distinct events each. This bounds the memory
+/// used to deduplicate streams of very high cardinality, at the cost of occasionally dropping an
+/// event that isn't a duplicate.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProbabilisticCacheConfig {
+    /// The target probability of dropping an event that isn't a duplicate.
+    #[serde(default = "default_false_positive_rate")]
+    #[configurable(validation(range(min = 0.0, max = 1.0)))]
+    pub false_positive_rate: f64,
+
+    /// How often to rotate the cache, in seconds.
+    ///
+    /// Two Bloom filters are kept, and rotating the cache discards the oldest one. The cache is also
+    /// rotated as soon as `cache.num_events` distinct events have been seen since the last rotation.
+    /// Events are therefore remembered for at most two rotation intervals after they were last
+    /// seen.
+    #[serde(default = "default_rotation_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub rotation_interval_secs: NonZeroU64,
+}
+
+const fn default_false_positive_rate() -> f64 {
+    0.001
+}
+
+const fn default_rotation_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(300).expect("static non-zero number")
+}
+
 pub const fn default_cache_config() -> CacheConfig {
     CacheConfig {
         num_events: NonZeroUsize::new(5000).expect("static non-zero number"),
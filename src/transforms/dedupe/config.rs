@@ -5,9 +5,10 @@ use vector_lib::{
 
 use super::{
     common::{
-        CacheConfig, FieldMatchConfig, TimedCacheConfig, default_cache_config,
-        fill_default_fields_match,
+        CacheConfig, FieldMatchConfig, ProbabilisticCacheConfig, TimedCacheConfig,
+        default_cache_config, fill_default_fields_match,
     },
+    probabilistic_transform::ProbabilisticDedupe,
     timed_transform::TimedDedupe,
     transform::Dedupe,
 };
@@ -36,6 +37,10 @@ pub struct DedupeConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub time_settings: Option<TimedCacheConfig>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub probabilistic: Option<ProbabilisticCacheConfig>,
 }
 
 impl GenerateConfig for DedupeConfig {
@@ -44,6 +49,7 @@ impl GenerateConfig for DedupeConfig {
             fields: None,
             cache: default_cache_config(),
             time_settings: None,
+            probabilistic: None,
         })
         .unwrap()
     }
@@ -53,7 +59,16 @@ impl GenerateConfig for DedupeConfig {
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        if let Some(time_config) = &self.time_settings {
+        if let Some(probabilistic_config) = &self.probabilistic {
+            if self.time_settings.is_some() {
+                return Err("`time_settings` can't be used along with `probabilistic`".into());
+            }
+            Ok(Transform::event_task(ProbabilisticDedupe::new(
+                self.cache.num_events,
+                fill_default_fields_match(self.fields.as_ref()),
+                probabilistic_config,
+            )?))
+        } else if let Some(time_config) = &self.time_settings {
             Ok(Transform::event_task(TimedDedupe::new(
                 self.cache.num_events,
                 fill_default_fields_match(self.fields.as_ref()),
@@ -96,12 +111,12 @@ mod tests {
     };
 
     use crate::{
-        config::schema::Definition,
+        config::{TransformConfig, TransformContext, schema::Definition},
         event::{Event, LogEvent, ObjectMap, Value},
         test_util::components::assert_transform_compliance,
         transforms::{
             dedupe::{
-                common::TimedCacheConfig,
+                common::{ProbabilisticCacheConfig, TimedCacheConfig},
                 config::{CacheConfig, DedupeConfig, FieldMatchConfig},
             },
             test::create_topology,
@@ -123,6 +138,7 @@ mod tests {
             },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
             time_settings: None,
+            probabilistic: None,
         }
     }
 
@@ -140,6 +156,7 @@ mod tests {
             },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
             time_settings: None,
+            probabilistic: None,
         }
     }
 
@@ -422,6 +439,39 @@ mod tests {
         timed_age_out(transform_config).await;
     }
 
+    #[tokio::test]
+    async fn dedupe_match_probabilistic_basic() {
+        let transform_config = DedupeConfig {
+            probabilistic: Some(ProbabilisticCacheConfig {
+                false_positive_rate: 0.001,
+                rotation_interval_secs: std::num::NonZeroU64::new(60).unwrap(),
+            }),
+            ..make_match_transform_config(5, vec!["matched".into()])
+        };
+        basic(transform_config, "matched", "unmatched").await;
+    }
+
+    #[tokio::test]
+    async fn dedupe_probabilistic_with_time_settings() {
+        let transform_config = DedupeConfig {
+            time_settings: Some(TimedCacheConfig {
+                max_age_ms: Duration::from_millis(100),
+                refresh_on_drop: false,
+            }),
+            probabilistic: Some(ProbabilisticCacheConfig {
+                false_positive_rate: 0.001,
+                rotation_interval_secs: std::num::NonZeroU64::new(60).unwrap(),
+            }),
+            ..make_match_transform_config(5, vec!["matched".into()])
+        };
+        assert!(
+            transform_config
+                .build(&TransformContext::default())
+                .await
+                .is_err()
+        );
+    }
+
     /// Test the eviction behavior of the underlying LruCache
     async fn timed_age_out(transform_config: DedupeConfig) {
         assert_transform_compliance(async {
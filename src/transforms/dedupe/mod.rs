@@ -9,3 +9,6 @@ pub mod transform;
 
 #[cfg(feature = "transforms-impl-dedupe")]
 pub mod timed_transform;
+
+#[cfg(feature = "transforms-impl-dedupe")]
+pub mod probabilistic_transform;
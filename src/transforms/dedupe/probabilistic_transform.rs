@@ -0,0 +1,268 @@
+use std::{
+    future::ready,
+    num::NonZeroUsize,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use bloomy::BloomFilter;
+use futures::{Stream, StreamExt};
+
+use super::{
+    common::{FieldMatchConfig, ProbabilisticCacheConfig},
+    transform::{CacheEntry, build_cache_entry},
+};
+use crate::{
+    event::Event,
+    internal_events::{DedupeCacheCardinality, DedupeEventsDropped},
+    transforms::TaskTransform,
+};
+
+/// How often the estimated cardinality of the events is reported.
+const CARDINALITY_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub struct ProbabilisticDedupe {
+    fields: FieldMatchConfig,
+    current: BloomFilterStorage,
+    previous: BloomFilterStorage,
+    /// The number of events the current filter is rotated after, so that its false positive rate
+    /// stays within the configured one.
+    num_entries: usize,
+    rotation_interval: Duration,
+    rotated_at: Instant,
+    reported_at: Instant,
+}
+
+impl ProbabilisticDedupe {
+    pub fn new(
+        num_entries: NonZeroUsize,
+        fields: FieldMatchConfig,
+        config: &ProbabilisticCacheConfig,
+    ) -> crate::Result<Self> {
+        if !(config.false_positive_rate > 0.0 && config.false_positive_rate < 1.0) {
+            return Err("`probabilistic.false_positive_rate` must be between 0 and 1".into());
+        }
+
+        let filter = BloomFilterStorage::new(num_entries.get(), config.false_positive_rate);
+        let now = Instant::now();
+        Ok(Self {
+            fields,
+            current: filter.clone(),
+            previous: filter,
+            num_entries: num_entries.get(),
+            rotation_interval: Duration::from_secs(config.rotation_interval_secs.get()),
+            rotated_at: now,
+            reported_at: now,
+        })
+    }
+
+    pub fn transform_one(&mut self, event: Event) -> Option<Event> {
+        self.transform_one_at(event, Instant::now())
+    }
+
+    fn transform_one_at(&mut self, event: Event, now: Instant) -> Option<Event> {
+        if now.saturating_duration_since(self.rotated_at) >= self.rotation_interval
+            || self.current.len() >= self.num_entries
+        {
+            std::mem::swap(&mut self.current, &mut self.previous);
+            self.current.clear();
+            self.rotated_at = now;
+        }
+
+        let entry = build_cache_entry(&event, &self.fields);
+        let in_current = self.current.contains(&entry);
+        if !in_current {
+            // Duplicates of events seen before the last rotation are also added to the current
+            // filter, so that they're remembered for as long as they keep being seen.
+            self.current.insert(&entry);
+        }
+
+        if now.saturating_duration_since(self.reported_at) >= CARDINALITY_REPORT_INTERVAL {
+            emit!(DedupeCacheCardinality {
+                estimated_cardinality: self.current.len(),
+            });
+            self.reported_at = now;
+        }
+
+        if in_current || self.previous.contains(&entry) {
+            emit!(DedupeEventsDropped { count: 1 });
+            None
+        } else {
+            Some(event)
+        }
+    }
+}
+
+impl TaskTransform<Event> for ProbabilisticDedupe {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut inner = self;
+        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+    }
+}
+
+/// A Bloom filter that tracks the number of entries inserted into it.
+#[derive(Clone)]
+struct BloomFilterStorage {
+    inner: BloomFilter<CacheEntry>,
+
+    /// Count of entries inserted into the Bloom filter. Entries are only inserted if the filter
+    /// doesn't already contain them, so this estimates the number of distinct entries, minus false
+    /// positives.
+    count: usize,
+}
+
+impl BloomFilterStorage {
+    fn new(num_entries: usize, false_positive_rate: f64) -> Self {
+        Self {
+            inner: BloomFilter::with_rate(num_entries, false_positive_rate),
+            count: 0,
+        }
+    }
+
+    fn contains(&self, entry: &CacheEntry) -> bool {
+        self.inner.contains(entry)
+    }
+
+    fn insert(&mut self, entry: &CacheEntry) {
+        self.inner.insert(entry);
+        self.count += 1;
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+        self.count = 0;
+    }
+
+    const fn len(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn dedupe(num_entries: usize, rotation_interval_secs: u64) -> ProbabilisticDedupe {
+        ProbabilisticDedupe::new(
+            NonZeroUsize::new(num_entries).unwrap(),
+            FieldMatchConfig::MatchFields(vec!["message".into()]),
+            &ProbabilisticCacheConfig {
+                false_positive_rate: 0.01,
+                rotation_interval_secs: NonZeroU64::new(rotation_interval_secs).unwrap(),
+            },
+        )
+        .unwrap()
+    }
+
+    fn event(message: &str) -> Event {
+        Event::Log(LogEvent::from(message))
+    }
+
+    #[test]
+    fn bloom_filter_false_positive_rate() {
+        let entry = |prefix: &str, i: usize| {
+            CacheEntry::Match(vec![Some((0, format!("{prefix}{i}").into()))])
+        };
+
+        let mut filter = BloomFilterStorage::new(10_000, 0.01);
+        for i in 0..10_000 {
+            filter.insert(&entry("inserted", i));
+        }
+        assert_eq!(filter.len(), 10_000);
+        assert!((0..10_000).all(|i| filter.contains(&entry("inserted", i))));
+
+        let false_positives = (0..10_000)
+            .filter(|i| filter.contains(&entry("other", *i)))
+            .count();
+        assert!(
+            false_positives < 300,
+            "{false_positives} false positives out of 10000"
+        );
+    }
+
+    #[test]
+    fn drops_duplicates() {
+        let mut dedupe = dedupe(100, 60);
+        let now = Instant::now();
+
+        assert!(dedupe.transform_one_at(event("a"), now).is_some());
+        assert!(dedupe.transform_one_at(event("b"), now).is_some());
+        assert!(dedupe.transform_one_at(event("a"), now).is_none());
+        assert_eq!(dedupe.current.len(), 2);
+    }
+
+    #[test]
+    fn forgets_events_after_two_rotations() {
+        let mut dedupe = dedupe(100, 60);
+        let start = Instant::now();
+
+        assert!(dedupe.transform_one_at(event("a"), start).is_some());
+        assert!(dedupe.transform_one_at(event("b"), start).is_some());
+
+        // Both events are remembered by the previous filter after the first rotation, and `a` is
+        // added to the current one again.
+        let rotated = start + Duration::from_secs(60);
+        assert!(dedupe.transform_one_at(event("a"), rotated).is_none());
+
+        // Only `a` is still remembered after the second rotation.
+        let rotated = rotated + Duration::from_secs(60);
+        assert!(dedupe.transform_one_at(event("a"), rotated).is_none());
+        assert!(dedupe.transform_one_at(event("b"), rotated).is_some());
+    }
+
+    #[test]
+    fn rotates_when_full() {
+        // A low false positive rate keeps the tiny filters from matching events they don't contain.
+        let mut dedupe = ProbabilisticDedupe::new(
+            NonZeroUsize::new(2).unwrap(),
+            FieldMatchConfig::MatchFields(vec!["message".into()]),
+            &ProbabilisticCacheConfig {
+                false_positive_rate: 0.000_001,
+                rotation_interval_secs: NonZeroU64::new(60).unwrap(),
+            },
+        )
+        .unwrap();
+        let now = Instant::now();
+
+        assert!(dedupe.transform_one_at(event("a"), now).is_some());
+        assert!(dedupe.transform_one_at(event("b"), now).is_some());
+        assert_eq!(dedupe.current.len(), 2);
+
+        // The current filter is full, so it's rotated before the interval elapses, and the events
+        // are still remembered by the previous one.
+        assert!(dedupe.transform_one_at(event("c"), now).is_some());
+        assert_eq!(dedupe.current.len(), 1);
+        assert_eq!(dedupe.previous.len(), 2);
+        assert!(dedupe.transform_one_at(event("a"), now).is_none());
+
+        // `b` is forgotten once the filter that remembered it is rotated out.
+        assert!(dedupe.transform_one_at(event("d"), now).is_some());
+        assert!(dedupe.transform_one_at(event("b"), now).is_some());
+    }
+
+    #[test]
+    fn invalid_false_positive_rate() {
+        let config = ProbabilisticCacheConfig {
+            false_positive_rate: 1.0,
+            rotation_interval_secs: NonZeroU64::new(60).unwrap(),
+        };
+        assert!(
+            ProbabilisticDedupe::new(
+                NonZeroUsize::new(100).unwrap(),
+                FieldMatchConfig::MatchFields(vec![]),
+                &config
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf, time::Duration};
+
+use serde_with::serde_as;
+use vector_lib::{config::LogNamespace, configurable::configurable_component};
+
+use super::transform::Exec;
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    schema::{self, Definition},
+    transforms::Transform,
+};
+
+/// Configuration for the `exec` transform.
+#[serde_as]
+#[configurable_component(transform(
+    "exec",
+    "Modify event data with a process running on the host."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ExecConfig {
+    /// The command to run, plus any arguments required.
+    ///
+    /// Each event is written to the standard input of the process as a line of JSON, and the
+    /// process must write one line to its standard output for each line it reads: either a JSON
+    /// object, which replaces the event, or a JSON array of objects, which is empty to drop the
+    /// event.
+    #[configurable(metadata(docs::examples = "./enrich", docs::examples = "--format=json"))]
+    pub command: Vec<String>,
+
+    /// Custom environment variables to set or update when running the command.
+    /// If a variable name already exists in the environment, its value is replaced.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "An environment variable."))]
+    #[configurable(metadata(docs::examples = "environment_examples()"))]
+    pub environment: Option<HashMap<String, String>>,
+
+    /// Whether or not to clear the environment before setting custom environment variables.
+    #[serde(default)]
+    pub clear_environment: bool,
+
+    /// The directory in which to run the command.
+    pub working_directory: Option<PathBuf>,
+
+    /// The number of processes to run.
+    ///
+    /// Each process is sent one event at a time, and the events are sent downstream in the order
+    /// they were received.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: NonZeroUsize,
+
+    /// How long to wait for the process to answer an event, in seconds.
+    ///
+    /// A process that doesn't answer in time is restarted, as its answer would be mistaken for the
+    /// answer to the next event.
+    #[serde(default = "default_timeout_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Timeout"))]
+    pub timeout_secs: Duration,
+
+    /// The maximum delay before restarting a process that exited or failed, in seconds.
+    ///
+    /// The first restart is delayed by half a second, and the delay doubles each time the process
+    /// fails again without having answered an event, up to this delay.
+    #[serde(default = "default_max_respawn_backoff_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Maximum Respawn Backoff"))]
+    pub max_respawn_backoff_secs: Duration,
+
+    /// Drops the events that the process failed to transform.
+    ///
+    /// Otherwise, those events are sent downstream unmodified.
+    #[serde(default)]
+    pub drop_on_error: bool,
+}
+
+const fn default_concurrency() -> NonZeroUsize {
+    NonZeroUsize::new(1).unwrap()
+}
+
+const fn default_timeout_secs() -> Duration {
+    Duration::from_secs(10)
+}
+
+const fn default_max_respawn_backoff_secs() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn environment_examples() -> HashMap<String, String> {
+    HashMap::from([("LANG".to_owned(), "en_US.UTF-8".to_owned())])
+}
+
+impl GenerateConfig for ExecConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"command = ["./enrich", "--format=json"]"#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "exec")]
+impl TransformConfig for ExecConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.command.is_empty() {
+            return Err("A non-empty list for command must be provided".into());
+        }
+
+        Ok(Transform::event_task(Exec::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        // The process can return any events, which resets the type definition.
+        let namespaces = input_definitions
+            .iter()
+            .flat_map(|(_output, definition)| definition.log_namespaces().clone())
+            .collect();
+
+        let definition = input_definitions
+            .iter()
+            .map(|(output, _definition)| {
+                (
+                    output.clone(),
+                    Definition::default_for_namespace(&namespaces),
+                )
+            })
+            .collect();
+
+        vec![TransformOutput::new(DataType::Log, definition)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExecConfig;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ExecConfig>();
+    }
+}
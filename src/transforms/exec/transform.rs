@@ -0,0 +1,343 @@
+use std::{io, pin::Pin, process::Stdio, sync::Arc, time::Duration};
+
+use futures::{Stream, StreamExt, stream};
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::Mutex,
+    time::{sleep, timeout},
+};
+use vector_lib::event::{LogEvent, Value};
+
+use super::config::ExecConfig;
+use crate::{
+    common::backoff::ExponentialBackoff, event::Event, internal_events::ExecTransformError,
+    transforms::TaskTransform,
+};
+
+#[derive(Debug, Snafu)]
+pub enum ExecError {
+    #[snafu(display("Failed to start the process: {}", source))]
+    SpawnFailed { source: io::Error },
+    #[snafu(display("Failed to write the event to the process: {}", source))]
+    WriteFailed { source: io::Error },
+    #[snafu(display("Failed to read the output of the process: {}", source))]
+    ReadFailed { source: io::Error },
+    #[snafu(display("The process exited"))]
+    Exited,
+    #[snafu(display("The process didn't answer in time"))]
+    TimedOut,
+    #[snafu(display("The process returned invalid events: {}", reason))]
+    InvalidOutput { reason: String },
+}
+
+impl ExecError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::SpawnFailed { .. } => "spawn_failed",
+            Self::WriteFailed { .. } => "write_failed",
+            Self::ReadFailed { .. } => "read_failed",
+            Self::Exited => "exited",
+            Self::TimedOut => "timed_out",
+            Self::InvalidOutput { .. } => "invalid_output",
+        }
+    }
+}
+
+/// The command run by each process, from the configuration.
+struct ExecCommand {
+    command: Vec<String>,
+    environment: Vec<(String, String)>,
+    clear_environment: bool,
+    working_directory: Option<std::path::PathBuf>,
+}
+
+impl ExecCommand {
+    fn spawn(&self) -> Result<Process, ExecError> {
+        let mut command = Command::new(&self.command[0]);
+        command
+            .args(&self.command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true);
+        if self.clear_environment {
+            command.env_clear();
+        }
+        command.envs(self.environment.iter().cloned());
+        if let Some(working_directory) = &self.working_directory {
+            command.current_dir(working_directory);
+        }
+
+        let mut child = command.spawn().context(SpawnFailedSnafu)?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped")).lines();
+        Ok(Process {
+            _child: child,
+            stdin,
+            stdout,
+        })
+    }
+}
+
+/// A running process, killed when dropped.
+struct Process {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl Process {
+    /// Writes an event to the process, and reads its answer.
+    async fn exchange(&mut self, line: &[u8]) -> Result<String, ExecError> {
+        self.stdin.write_all(line).await.context(WriteFailedSnafu)?;
+        self.stdin.flush().await.context(WriteFailedSnafu)?;
+        self.stdout
+            .next_line()
+            .await
+            .context(ReadFailedSnafu)?
+            .ok_or(ExecError::Exited)
+    }
+}
+
+/// A process slot, restarting its process after it exits or fails.
+struct Worker {
+    command: Arc<ExecCommand>,
+    timeout: Duration,
+    process: Option<Process>,
+    backoff: ExponentialBackoff,
+    respawn_delay: Option<Duration>,
+}
+
+impl Worker {
+    async fn process(&mut self, line: &[u8]) -> Result<Vec<Value>, ExecError> {
+        let mut process = match self.process.take() {
+            Some(process) => process,
+            None => {
+                if let Some(delay) = self.respawn_delay.take() {
+                    sleep(delay).await;
+                }
+                self.command.spawn().inspect_err(|_| {
+                    self.respawn_delay = self.backoff.next();
+                })?
+            }
+        };
+
+        let answer = match timeout(self.timeout, process.exchange(line)).await {
+            Ok(Ok(answer)) => answer,
+            Ok(Err(error)) => {
+                self.respawn_delay = self.backoff.next();
+                return Err(error);
+            }
+            Err(_) => {
+                self.respawn_delay = self.backoff.next();
+                return Err(ExecError::TimedOut);
+            }
+        };
+        // The process answered, so it is kept even if the answer is invalid.
+        self.process = Some(process);
+        self.backoff.reset();
+        parse_answer(&answer)
+    }
+}
+
+/// Parses the answer of the process, which is either an event or an array of events.
+fn parse_answer(answer: &str) -> Result<Vec<Value>, ExecError> {
+    let value =
+        serde_json::from_str::<Value>(answer).map_err(|error| ExecError::InvalidOutput {
+            reason: error.to_string(),
+        })?;
+    let events = match value {
+        Value::Array(events) => events,
+        event => vec![event],
+    };
+    if !events.iter().all(Value::is_object) {
+        return Err(ExecError::InvalidOutput {
+            reason: "events must be objects".to_owned(),
+        });
+    }
+    Ok(events)
+}
+
+pub struct Exec {
+    workers: Vec<Mutex<Worker>>,
+    drop_on_error: bool,
+}
+
+impl Exec {
+    pub fn new(config: &ExecConfig) -> crate::Result<Self> {
+        let command = Arc::new(ExecCommand {
+            command: config.command.clone(),
+            environment: config
+                .environment
+                .iter()
+                .flatten()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            clear_environment: config.clear_environment,
+            working_directory: config.working_directory.clone(),
+        });
+
+        let mut workers = Vec::with_capacity(config.concurrency.get());
+        for _ in 0..config.concurrency.get() {
+            // The processes are started right away, so that a command that can't be run is
+            // reported when the transform is built.
+            workers.push(Mutex::new(Worker {
+                process: Some(command.spawn()?),
+                command: Arc::clone(&command),
+                timeout: config.timeout_secs,
+                backoff: ExponentialBackoff::from_millis(2)
+                    .factor(250)
+                    .max_delay(config.max_respawn_backoff_secs),
+                respawn_delay: None,
+            }));
+        }
+
+        Ok(Self {
+            workers,
+            drop_on_error: config.drop_on_error,
+        })
+    }
+}
+
+impl TaskTransform<Event> for Exec {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>> {
+        let concurrency = self.workers.len();
+        let exec = Arc::new(*self);
+        Box::pin(
+            task.map(move |event| Arc::clone(&exec).transform_one(event))
+                .buffered(concurrency)
+                .flat_map(stream::iter),
+        )
+    }
+}
+
+impl Exec {
+    async fn transform_one(self: Arc<Self>, event: Event) -> Vec<Event> {
+        // At most `concurrency` events are processed at once, so a worker is always available.
+        let mut worker = match self
+            .workers
+            .iter()
+            .find_map(|worker| worker.try_lock().ok())
+        {
+            Some(worker) => worker,
+            None => self.workers[0].lock().await,
+        };
+
+        let (value, metadata) = event.into_log().into_parts();
+        let mut line = serde_json::to_vec(&value).expect("events are serializable");
+        line.push(b'\n');
+
+        match worker.process(&line).await {
+            Ok(events) => events
+                .into_iter()
+                .map(|value| Event::from(LogEvent::from_parts(value, metadata.clone())))
+                .collect(),
+            Err(error) => {
+                emit!(ExecTransformError {
+                    error,
+                    event_dropped: self.drop_on_error,
+                });
+                if self.drop_on_error {
+                    Vec::new()
+                } else {
+                    vec![Event::from(LogEvent::from_parts(value, metadata))]
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+
+    fn config(script: &str) -> ExecConfig {
+        ExecConfig {
+            command: vec!["sh".to_owned(), "-c".to_owned(), script.to_owned()],
+            environment: None,
+            clear_environment: false,
+            working_directory: None,
+            concurrency: NonZeroUsize::new(2).unwrap(),
+            timeout_secs: Duration::from_secs(5),
+            max_respawn_backoff_secs: Duration::from_secs(1),
+            drop_on_error: false,
+        }
+    }
+
+    async fn transform(config: &ExecConfig, messages: &[&str]) -> Vec<LogEvent> {
+        let events = messages
+            .iter()
+            .map(|message| Event::from(LogEvent::from(*message)))
+            .collect::<Vec<_>>();
+        Box::new(Exec::new(config).unwrap())
+            .transform_events(stream::iter(events).boxed())
+            .map(Event::into_log)
+            .collect()
+            .await
+    }
+
+    fn messages(events: &[LogEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event["message"].to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn exec_returns_events_of_process_in_order() {
+        let config =
+            config(r#"while IFS= read -r line; do printf '[%s,%s]\n' "$line" "$line"; done"#);
+
+        let events = transform(&config, &["a", "b", "c"]).await;
+        assert_eq!(messages(&events), ["a", "a", "b", "b", "c", "c"]);
+    }
+
+    #[tokio::test]
+    async fn exec_restarts_process_that_exited() {
+        let mut config = config(r#"read -r line; echo '{"message":"answered"}'"#);
+        config.concurrency = NonZeroUsize::new(1).unwrap();
+
+        // The process exits after its first answer, so the second event is sent downstream
+        // unmodified, and the third one is answered by a new process.
+        let events = transform(&config, &["a", "b", "c"]).await;
+        assert_eq!(messages(&events), ["answered", "b", "answered"]);
+    }
+
+    #[tokio::test]
+    async fn exec_restarts_process_that_timed_out() {
+        let mut config = config(
+            r#"while IFS= read -r line; do
+                case "$line" in *slow*) sleep 10;; esac
+                echo "$line"
+            done"#,
+        );
+        config.concurrency = NonZeroUsize::new(1).unwrap();
+        config.timeout_secs = Duration::from_millis(200);
+        config.drop_on_error = true;
+
+        let events = transform(&config, &["slow", "fast"]).await;
+        assert_eq!(messages(&events), ["fast"]);
+    }
+
+    #[tokio::test]
+    async fn exec_handles_invalid_output() {
+        let config = config(r#"while IFS= read -r line; do echo '"not an event"'; done"#);
+
+        let events = transform(&config, &["a", "b"]).await;
+        assert_eq!(messages(&events), ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn exec_rejects_commands_that_cant_run() {
+        let mut config = config("");
+        config.command = vec!["/nonexistent/enrich".to_owned()];
+        assert!(Exec::new(&config).is_err());
+    }
+}
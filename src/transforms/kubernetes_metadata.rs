@@ -0,0 +1,359 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::{Namespace, Pod};
+use kube::runtime::reflector::ObjectRef;
+use vector_lib::{
+    config::LogNamespace,
+    configurable::configurable_component,
+    lookup::{OwnedTargetPath, lookup_v2::ConfigTargetPath, owned_value_path},
+};
+use vrl::value::{Kind, ObjectMap, Value, kind::Collection};
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::{Event, LogEvent},
+    internal_events::KubernetesMetadataStateUnavailable,
+    kubernetes::shared_state::{KubernetesState, SharedStateSlot},
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// Configuration for the `kubernetes_metadata` transform.
+#[configurable_component(transform(
+    "kubernetes_metadata",
+    "Enrich events with the metadata of the Kubernetes Pods they originate from."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KubernetesMetadataConfig {
+    /// The ID of the `kubernetes_logs` source whose cached Pod and Namespace metadata are used.
+    ///
+    /// The source must run in the same Vector instance, and the metadata of the Pods is the one it
+    /// watches, so only the Pods of its Node can be looked up. While the source isn't running, the
+    /// events are passed through without being enriched.
+    #[configurable(metadata(docs::examples = "kubernetes_logs"))]
+    pub source: String,
+
+    /// The field of the events holding the IP address of the Pod they originate from.
+    ///
+    /// The Pods using the network of their Node are never looked up by their IP address, as it's
+    /// shared with the Node.
+    #[configurable(metadata(docs::examples = ".host", docs::examples = ".source_ip"))]
+    pub pod_ip_field: Option<ConfigTargetPath>,
+
+    /// The field of the events holding the name of the Pod they originate from.
+    ///
+    /// The Pod is only looked up by its name if it isn't found by its IP address.
+    #[configurable(metadata(docs::examples = ".resource.attributes.\"k8s.pod.name\""))]
+    pub pod_name_field: Option<ConfigTargetPath>,
+
+    /// The field of the events holding the Namespace of the Pod they originate from.
+    ///
+    /// If not set, or if the field is missing, the Pod is looked up by its name in all of the
+    /// Namespaces.
+    #[configurable(metadata(docs::examples = ".resource.attributes.\"k8s.namespace.name\""))]
+    pub pod_namespace_field: Option<ConfigTargetPath>,
+
+    /// The field the metadata of the Pod is written to.
+    #[serde(default = "default_target")]
+    #[configurable(metadata(docs::examples = ".k8s"))]
+    pub target: ConfigTargetPath,
+}
+
+fn default_target() -> ConfigTargetPath {
+    ConfigTargetPath(OwnedTargetPath::event(owned_value_path!("kubernetes")))
+}
+
+impl GenerateConfig for KubernetesMetadataConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"source = "kubernetes_logs"
+            pod_ip_field = ".host""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kubernetes_metadata")]
+impl TransformConfig for KubernetesMetadataConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.pod_ip_field.is_none() && self.pod_name_field.is_none() {
+            return Err("At least one of `pod_ip_field` or `pod_name_field` must be set.".into());
+        }
+
+        Ok(Transform::function(KubernetesMetadata {
+            source: self.source.clone(),
+            state: SharedStateSlot::get(&self.source),
+            pod_ip_field: self.pod_ip_field.clone(),
+            pod_name_field: self.pod_name_field.clone(),
+            pod_namespace_field: self.pod_namespace_field.clone(),
+            target: self.target.clone(),
+        }))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        let schema_definitions = input_definitions
+            .iter()
+            .map(|(output, definition)| {
+                let definition = definition.clone().with_field(
+                    &self.target.0,
+                    Kind::object(Collection::any()).or_undefined(),
+                    None,
+                );
+                (output.clone(), definition)
+            })
+            .collect();
+
+        vec![TransformOutput::new(DataType::Log, schema_definitions)]
+    }
+}
+
+#[derive(Clone)]
+struct KubernetesMetadata {
+    source: String,
+    state: Arc<SharedStateSlot>,
+    pod_ip_field: Option<ConfigTargetPath>,
+    pod_name_field: Option<ConfigTargetPath>,
+    pod_namespace_field: Option<ConfigTargetPath>,
+    target: ConfigTargetPath,
+}
+
+impl FunctionTransform for KubernetesMetadata {
+    fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
+        match self.state.load() {
+            Some(state) => self.enrich(&state, event.as_mut_log()),
+            None => emit!(KubernetesMetadataStateUnavailable {
+                source: &self.source
+            }),
+        }
+        output.push(event);
+    }
+}
+
+impl KubernetesMetadata {
+    fn enrich(&self, state: &KubernetesState, log: &mut LogEvent) {
+        let Some(pod) = self.find_pod(state, log) else {
+            return;
+        };
+        let namespace = pod
+            .metadata
+            .namespace
+            .as_ref()
+            .and_then(|namespace| state.namespaces.get(&ObjectRef::new(namespace)));
+        log.insert(&self.target.0, pod_metadata(&pod, namespace.as_deref()));
+    }
+
+    fn find_pod(&self, state: &KubernetesState, log: &LogEvent) -> Option<Arc<Pod>> {
+        let field = |field: &Option<ConfigTargetPath>| {
+            field
+                .as_ref()
+                .and_then(|field| log.get(&field.0))
+                .map(|value| value.to_string_lossy().into_owned())
+        };
+
+        if let Some(ip) = field(&self.pod_ip_field) {
+            let pod = state.pods.find(|pod| {
+                let host_network = pod
+                    .spec
+                    .as_ref()
+                    .and_then(|spec| spec.host_network)
+                    .unwrap_or(false);
+                !host_network
+                    && pod
+                        .status
+                        .as_ref()
+                        .and_then(|status| status.pod_ip.as_deref())
+                        == Some(ip.as_str())
+            });
+            if pod.is_some() {
+                return pod;
+            }
+        }
+
+        let name = field(&self.pod_name_field)?;
+        match field(&self.pod_namespace_field) {
+            Some(namespace) => state.pods.get(&ObjectRef::new(&name).within(&namespace)),
+            None => state
+                .pods
+                .find(|pod| pod.metadata.name.as_deref() == Some(name.as_str())),
+        }
+    }
+}
+
+/// Builds the metadata of a Pod written to the events.
+fn pod_metadata(pod: &Pod, namespace: Option<&Namespace>) -> Value {
+    let mut metadata = ObjectMap::new();
+    let mut insert = |key: &str, value: Option<&String>| {
+        if let Some(value) = value {
+            metadata.insert(key.into(), Value::from(value.as_str()));
+        }
+    };
+    insert("pod_name", pod.metadata.name.as_ref());
+    insert("pod_namespace", pod.metadata.namespace.as_ref());
+    insert("pod_uid", pod.metadata.uid.as_ref());
+    insert(
+        "pod_ip",
+        pod.status
+            .as_ref()
+            .and_then(|status| status.pod_ip.as_ref()),
+    );
+    insert(
+        "pod_node_name",
+        pod.spec.as_ref().and_then(|spec| spec.node_name.as_ref()),
+    );
+
+    if let Some(owner) = pod.metadata.owner_references.iter().flatten().next() {
+        metadata.insert(
+            "pod_owner".into(),
+            Value::from(format!("{}/{}", owner.kind, owner.name)),
+        );
+    }
+
+    let labels = [
+        ("pod_labels", pod.metadata.labels.as_ref()),
+        ("pod_annotations", pod.metadata.annotations.as_ref()),
+        (
+            "namespace_labels",
+            namespace.and_then(|namespace| namespace.metadata.labels.as_ref()),
+        ),
+    ];
+    for (key, labels) in labels {
+        if let Some(labels) = labels {
+            let labels = labels
+                .iter()
+                .map(|(key, value)| (key.as_str().into(), Value::from(value.as_str())))
+                .collect::<ObjectMap>();
+            metadata.insert(key.into(), Value::Object(labels));
+        }
+    }
+
+    Value::Object(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::{
+        api::core::v1::{PodSpec, PodStatus},
+        apimachinery::pkg::apis::meta::v1::ObjectMeta,
+    };
+    use kube::runtime::{reflector::store::Writer, watcher};
+    use vrl::{event_path, value};
+
+    use super::*;
+    use crate::transforms::test::transform_one;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KubernetesMetadataConfig>();
+    }
+
+    fn pod(name: &str, ip: &str, host_network: bool) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                namespace: Some("team-a".to_owned()),
+                name: Some(name.to_owned()),
+                labels: Some(BTreeMap::from([("app".to_owned(), name.to_owned())])),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                host_network: Some(host_network),
+                node_name: Some("node-1".to_owned()),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                pod_ip: Some(ip.to_owned()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn transform(config: &str, component_id: &str) -> KubernetesMetadata {
+        let config: KubernetesMetadataConfig = toml::from_str(config).unwrap();
+        KubernetesMetadata {
+            source: component_id.to_owned(),
+            state: SharedStateSlot::get(component_id),
+            pod_ip_field: config.pod_ip_field,
+            pod_name_field: config.pod_name_field,
+            pod_namespace_field: config.pod_namespace_field,
+            target: config.target,
+        }
+    }
+
+    #[test]
+    fn enriches_events_by_pod_ip_and_name() {
+        let mut pods = Writer::<Pod>::default();
+        for pod in [
+            pod("web", "10.0.0.1", false),
+            pod("node-exporter", "10.0.0.2", true),
+        ] {
+            pods.apply_watcher_event(&watcher::Event::Apply(pod));
+        }
+        let mut namespaces = Writer::<Namespace>::default();
+        namespaces.apply_watcher_event(&watcher::Event::Apply(Namespace {
+            metadata: ObjectMeta {
+                name: Some("team-a".to_owned()),
+                labels: Some(BTreeMap::from([("team".to_owned(), "a".to_owned())])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+
+        let mut transform = transform(
+            r#"source = "enriches_events"
+            pod_ip_field = ".host"
+            pod_name_field = ".pod""#,
+            "enriches_events",
+        );
+        let mut event = Event::Log(LogEvent::from(value!({"host": "10.0.0.1"})));
+
+        // The events are passed through while the source isn't running.
+        let enriched = transform_one(&mut transform, event.clone()).unwrap();
+        assert!(enriched.as_log().get(event_path!("kubernetes")).is_none());
+
+        let _state = SharedStateSlot::share(
+            "enriches_events",
+            KubernetesState {
+                pods: pods.as_reader(),
+                namespaces: namespaces.as_reader(),
+            },
+        );
+        let enriched = transform_one(&mut transform, event.clone()).unwrap();
+        assert_eq!(
+            enriched.as_log().get(event_path!("kubernetes")),
+            Some(&value!({
+                "pod_name": "web",
+                "pod_namespace": "team-a",
+                "pod_ip": "10.0.0.1",
+                "pod_node_name": "node-1",
+                "pod_labels": {"app": "web"},
+                "namespace_labels": {"team": "a"},
+            }))
+        );
+
+        // The Pods using the network of their Node are only looked up by their name.
+        event.as_mut_log().insert("host", "10.0.0.2");
+        let enriched = transform_one(&mut transform, event.clone()).unwrap();
+        assert!(enriched.as_log().get(event_path!("kubernetes")).is_none());
+        event.as_mut_log().insert("pod", "node-exporter");
+        let enriched = transform_one(&mut transform, event).unwrap();
+        assert_eq!(
+            enriched.as_log().get(event_path!("kubernetes", "pod_name")),
+            Some(&value!("node-exporter"))
+        );
+    }
+}
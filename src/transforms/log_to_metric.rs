@@ -1,13 +1,17 @@
-use std::{collections::HashMap, num::ParseFloatError, sync::Arc};
+use std::{
+    collections::HashMap,
+    num::{NonZeroU32, ParseFloatError},
+    sync::Arc,
+};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
 use vector_lib::{
     config::LogNamespace,
     configurable::configurable_component,
     event::{
         DatadogMetricOriginMetadata, LogEvent,
-        metric::{Bucket, Quantile, Sample},
+        metric::{Bucket, MetricSketch, Quantile, Sample},
     },
 };
 use vrl::{
@@ -90,6 +94,31 @@ pub struct CounterConfig {
     pub kind: MetricKind,
 }
 
+/// Specification of a histogram derived from a log event.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct HistogramConfig {
+    /// The upper limits of the buckets of the histogram, in increasing order.
+    ///
+    /// If set, an aggregated histogram is created, counting the value in the first bucket whose
+    /// upper limit it doesn't exceed. Otherwise, a distribution with the value as its sample is
+    /// created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buckets: Option<Vec<f64>>,
+}
+
+/// Specification of a summary derived from a log event.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct SummaryConfig {
+    /// The quantiles of the summary, between `0` and `1`.
+    ///
+    /// If set, an aggregated summary of the value is created, every quantile of a single value
+    /// being the value itself. Otherwise, a distribution with the value as its sample is created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantiles: Option<Vec<f64>>,
+}
+
 /// Specification of a metric derived from a log event.
 // TODO: While we're resolving the schema for this enum somewhat reasonably (in
 // `generate-components-docs.rb`), we have a problem where an overlapping field (overlap between two
@@ -146,7 +175,7 @@ pub enum MetricTypeConfig {
     Counter(CounterConfig),
 
     /// A histogram.
-    Histogram,
+    Histogram(HistogramConfig),
 
     /// A gauge.
     Gauge,
@@ -155,13 +184,49 @@ pub enum MetricTypeConfig {
     Set,
 
     /// A summary.
-    Summary,
+    Summary(SummaryConfig),
 }
 
 impl MetricConfig {
     fn field(&self) -> &str {
         self.field.get_ref()
     }
+
+    fn validate(&self) -> crate::Result<()> {
+        match &self.metric {
+            MetricTypeConfig::Histogram(HistogramConfig {
+                buckets: Some(buckets),
+            }) => {
+                if buckets.is_empty()
+                    || buckets.iter().any(|bucket| !bucket.is_finite())
+                    || buckets.windows(2).any(|pair| pair[0] >= pair[1])
+                {
+                    return Err(format!(
+                        "The buckets of the histogram of `{}` must be finite and in increasing order.",
+                        self.field()
+                    )
+                    .into());
+                }
+            }
+            MetricTypeConfig::Summary(SummaryConfig {
+                quantiles: Some(quantiles),
+            }) => {
+                if quantiles.is_empty()
+                    || quantiles
+                        .iter()
+                        .any(|quantile| !(0.0..=1.0).contains(quantile))
+                {
+                    return Err(format!(
+                        "The quantiles of the summary of `{}` must be between 0 and 1.",
+                        self.field()
+                    )
+                    .into());
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 const fn default_increment_by_value() -> bool {
@@ -201,8 +266,13 @@ impl GenerateConfig for LogToMetricConfig {
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        let metrics = self.metrics.clone().unwrap_or_default();
+        for metric in &metrics {
+            metric.validate()?;
+        }
+
         Ok(Transform::function(LogToMetric {
-            metrics: self.metrics.clone().unwrap_or_default(),
+            metrics,
             all_metrics: self.all_metrics.unwrap_or_default(),
         }))
     }
@@ -236,6 +306,8 @@ pub enum TransformParseErrorKind {
     IntError,
     /// Errors when Parsing Arrays
     ArrayError,
+    /// Errors when Parsing Sketches
+    SketchError,
 }
 
 impl std::fmt::Display for TransformParseErrorKind {
@@ -431,7 +503,7 @@ fn to_metric_with_config(config: &MetricConfig, event: &Event) -> Result<Metric,
 
             (counter.kind, MetricValue::Counter { value })
         }
-        MetricTypeConfig::Histogram => {
+        MetricTypeConfig::Histogram(histogram) => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
                     path: field.to_string(),
@@ -439,15 +511,20 @@ fn to_metric_with_config(config: &MetricConfig, event: &Event) -> Result<Metric,
                 }
             })?;
 
-            (
-                MetricKind::Incremental,
-                MetricValue::Distribution {
-                    samples: vector_lib::samples![value => 1],
-                    statistic: StatisticKind::Histogram,
-                },
-            )
+            let distribution = MetricValue::Distribution {
+                samples: vector_lib::samples![value => 1],
+                statistic: StatisticKind::Histogram,
+            };
+            let value = match &histogram.buckets {
+                Some(buckets) => distribution
+                    .distribution_to_agg_histogram(buckets)
+                    .expect("value is a distribution"),
+                None => distribution,
+            };
+
+            (MetricKind::Incremental, value)
         }
-        MetricTypeConfig::Summary => {
+        MetricTypeConfig::Summary(summary) => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
                     path: field.to_string(),
@@ -455,13 +532,22 @@ fn to_metric_with_config(config: &MetricConfig, event: &Event) -> Result<Metric,
                 }
             })?;
 
-            (
-                MetricKind::Incremental,
-                MetricValue::Distribution {
+            let value = match &summary.quantiles {
+                Some(quantiles) => MetricValue::AggregatedSummary {
+                    quantiles: quantiles
+                        .iter()
+                        .map(|&quantile| Quantile { quantile, value })
+                        .collect(),
+                    count: 1,
+                    sum: value,
+                },
+                None => MetricValue::Distribution {
                     samples: vector_lib::samples![value => 1],
                     statistic: StatisticKind::Summary,
                 },
-            )
+            };
+
+            (MetricKind::Incremental, value)
         }
         MetricTypeConfig::Gauge => {
             let value = value.to_string_lossy().parse().map_err(|error| {
@@ -638,67 +724,69 @@ fn get_distribution_value(log: &LogEvent) -> Result<MetricValue, TransformError>
     })
 }
 
-fn get_histogram_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
+fn get_histogram_value(log: &LogEvent, key: &str) -> Result<MetricValue, TransformError> {
     let event_buckets = log
-        .get(event_path!("histogram", "buckets"))
+        .get(event_path!(key, "buckets"))
         .ok_or_else(|| TransformError::PathNotFound {
-            path: "histogram.buckets".to_string(),
+            path: format!("{key}.buckets"),
         })?
         .as_array()
         .ok_or_else(|| TransformError::ParseError {
-            path: "histogram.buckets".to_string(),
+            path: format!("{key}.buckets"),
             kind: TransformParseErrorKind::ArrayError,
         })?;
 
     let mut buckets: Vec<Bucket> = Vec::new();
     for e_bucket in event_buckets {
-        let upper_limit = e_bucket
-            .get(path!("upper_limit"))
-            .ok_or_else(|| TransformError::PathNotFound {
-                path: "histogram.buckets.upper_limit".to_string(),
-            })?
-            .as_float()
-            .ok_or_else(|| TransformError::ParseError {
-                path: "histogram.buckets.upper_limit".to_string(),
+        // The infinite upper limits are serialized as null, as JSON has no infinity.
+        let upper_limit = match e_bucket.get(path!("upper_limit")).ok_or_else(|| {
+            TransformError::PathNotFound {
+                path: format!("{key}.buckets.upper_limit"),
+            }
+        })? {
+            Value::Null => f64::INFINITY,
+            value => *value.as_float().ok_or_else(|| TransformError::ParseError {
+                path: format!("{key}.buckets.upper_limit"),
                 kind: TransformParseErrorKind::FloatError,
-            })?;
+            })?,
+        };
 
         let count = e_bucket
             .get(path!("count"))
             .ok_or_else(|| TransformError::PathNotFound {
-                path: "histogram.buckets.count".to_string(),
+                path: format!("{key}.buckets.count"),
             })?
             .as_integer()
             .ok_or_else(|| TransformError::ParseError {
-                path: "histogram.buckets.count".to_string(),
+                path: format!("{key}.buckets.count"),
                 kind: TransformParseErrorKind::IntError,
             })?;
 
         buckets.push(Bucket {
-            upper_limit: *upper_limit,
+            upper_limit,
             count: count as u64,
         });
     }
 
     let count = log
-        .get(event_path!("histogram", "count"))
+        .get(event_path!(key, "count"))
         .ok_or_else(|| TransformError::PathNotFound {
-            path: "histogram.count".to_string(),
+            path: format!("{key}.count"),
         })?
         .as_integer()
         .ok_or_else(|| TransformError::ParseError {
-            path: "histogram.count".to_string(),
+            path: format!("{key}.count"),
             kind: TransformParseErrorKind::IntError,
         })?;
 
     let sum = log
-        .get(event_path!("histogram", "sum"))
+        .get(event_path!(key, "sum"))
         .ok_or_else(|| TransformError::PathNotFound {
-            path: "histogram.sum".to_string(),
+            path: format!("{key}.sum"),
         })?
         .as_float()
         .ok_or_else(|| TransformError::ParseError {
-            path: "histogram.sum".to_string(),
+            path: format!("{key}.sum"),
             kind: TransformParseErrorKind::FloatError,
         })?;
 
@@ -709,15 +797,15 @@ fn get_histogram_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
     })
 }
 
-fn get_summary_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
+fn get_summary_value(log: &LogEvent, key: &str) -> Result<MetricValue, TransformError> {
     let event_quantiles = log
-        .get(event_path!("summary", "quantiles"))
+        .get(event_path!(key, "quantiles"))
         .ok_or_else(|| TransformError::PathNotFound {
-            path: "summary.quantiles".to_string(),
+            path: format!("{key}.quantiles"),
         })?
         .as_array()
         .ok_or_else(|| TransformError::ParseError {
-            path: "summary.quantiles".to_string(),
+            path: format!("{key}.quantiles"),
             kind: TransformParseErrorKind::ArrayError,
         })?;
 
@@ -726,22 +814,22 @@ fn get_summary_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
         let quantile = e_quantile
             .get(path!("quantile"))
             .ok_or_else(|| TransformError::PathNotFound {
-                path: "summary.quantiles.quantile".to_string(),
+                path: format!("{key}.quantiles.quantile"),
             })?
             .as_float()
             .ok_or_else(|| TransformError::ParseError {
-                path: "summary.quantiles.quantile".to_string(),
+                path: format!("{key}.quantiles.quantile"),
                 kind: TransformParseErrorKind::FloatError,
             })?;
 
         let value = e_quantile
             .get(path!("value"))
             .ok_or_else(|| TransformError::PathNotFound {
-                path: "summary.quantiles.value".to_string(),
+                path: format!("{key}.quantiles.value"),
             })?
             .as_float()
             .ok_or_else(|| TransformError::ParseError {
-                path: "summary.quantiles.value".to_string(),
+                path: format!("{key}.quantiles.value"),
                 kind: TransformParseErrorKind::FloatError,
             })?;
 
@@ -752,24 +840,24 @@ fn get_summary_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
     }
 
     let count = log
-        .get(event_path!("summary", "count"))
+        .get(event_path!(key, "count"))
         .ok_or_else(|| TransformError::PathNotFound {
-            path: "summary.count".to_string(),
+            path: format!("{key}.count"),
         })?
         .as_integer()
         .ok_or_else(|| TransformError::ParseError {
-            path: "summary.count".to_string(),
+            path: format!("{key}.count"),
             kind: TransformParseErrorKind::IntError,
         })?;
 
     let sum = log
-        .get(event_path!("summary", "sum"))
+        .get(event_path!(key, "sum"))
         .ok_or_else(|| TransformError::PathNotFound {
-            path: "summary.sum".to_string(),
+            path: format!("{key}.sum"),
         })?
         .as_float()
         .ok_or_else(|| TransformError::ParseError {
-            path: "summary.sum".to_string(),
+            path: format!("{key}.sum"),
             kind: TransformParseErrorKind::FloatError,
         })?;
 
@@ -780,13 +868,41 @@ fn get_summary_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
     })
 }
 
+fn get_sketch_value(log: &LogEvent) -> Result<MetricValue, TransformError> {
+    let sketch = log
+        .get(event_path!("sketch"))
+        .ok_or_else(|| TransformError::PathNotFound {
+            path: "sketch".to_string(),
+        })?;
+
+    serde_json::to_value(sketch)
+        .and_then(serde_json::from_value::<MetricSketch>)
+        .map(|sketch| MetricValue::Sketch { sketch })
+        .map_err(|_| TransformError::ParseError {
+            path: "sketch".to_string(),
+            kind: TransformParseErrorKind::SketchError,
+        })
+}
+
+/// Returns the timestamp of the log, which is a string once the log went through a codec, or
+/// kept in the `timestamp` field by `metric_to_log` in the Vector namespace.
+fn get_timestamp(log: &LogEvent) -> Option<DateTime<Utc>> {
+    match log
+        .get_timestamp()
+        .or_else(|| log.get(event_path!("timestamp")))?
+    {
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
 fn to_metrics(event: &Event) -> Result<Metric, TransformError> {
     let log = event.as_log();
-    let timestamp = log
-        .get_timestamp()
-        .and_then(Value::as_timestamp)
-        .cloned()
-        .or_else(|| Some(Utc::now()));
+    let timestamp = get_timestamp(log).or_else(|| Some(Utc::now()));
 
     let name = match try_get_string_from_log(log, "name")? {
         Some(n) => n,
@@ -803,7 +919,16 @@ fn to_metrics(event: &Event) -> Result<Metric, TransformError> {
         && let Some(el) = els.as_object()
     {
         for (key, value) in el {
-            tags.insert(key.to_string(), bytes_to_str(value));
+            // The tags with several values are arrays, and the bare ones are null.
+            match value {
+                Value::Array(values) => tags.set_multi_value(
+                    key.to_string(),
+                    values
+                        .iter()
+                        .map(|value| TagValue::from(bytes_to_str(value))),
+                ),
+                value => tags.insert(key.to_string(), bytes_to_str(value)),
+            }
         }
     }
     let tags_result = Some(tags);
@@ -832,8 +957,11 @@ fn to_metrics(event: &Event) -> Result<Metric, TransformError> {
             value = match key.as_str() {
                 "gauge" => Some(get_gauge_value(log)?),
                 "distribution" => Some(get_distribution_value(log)?),
-                "histogram" => Some(get_histogram_value(log)?),
-                "summary" => Some(get_summary_value(log)?),
+                "histogram" | "aggregated_histogram" => {
+                    Some(get_histogram_value(log, key.as_str())?)
+                }
+                "summary" | "aggregated_summary" => Some(get_summary_value(log, key.as_str())?),
+                "sketch" => Some(get_sketch_value(log)?),
                 "counter" => Some(get_counter_value(log)?),
                 "set" => Some(get_set_value(log)?),
                 _ => None,
@@ -847,9 +975,16 @@ fn to_metrics(event: &Event) -> Result<Metric, TransformError> {
 
     let value = value.ok_or(TransformError::MetricDetailsNotFound)?;
 
+    let interval_ms = log
+        .get(event_path!("interval_ms"))
+        .and_then(Value::as_integer)
+        .and_then(|interval_ms| u32::try_from(interval_ms).ok())
+        .and_then(NonZeroU32::new);
+
     let mut metric = Metric::new_with_metadata(name, kind, value, log.metadata().clone())
         .with_tags(tags_result)
-        .with_timestamp(timestamp);
+        .with_timestamp(timestamp)
+        .with_interval_ms(interval_ms);
 
     if let Ok(namespace) = try_get_string_from_log(log, "namespace") {
         metric = metric.with_namespace(namespace);
@@ -1813,6 +1948,82 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn response_time_histogram_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            name = "{{ service }}_response_time"
+            namespace = "{{ app }}"
+            buckets = [1.0, 2.5, 5.0]
+            "#,
+        );
+
+        let mut event = create_event("response_time", "2.5");
+        event.as_mut_log().insert("service", "api");
+        event.as_mut_log().insert("app", "shop");
+        let metric = do_transform(config, event).await.unwrap().into_metric();
+
+        assert_eq!(metric.name(), "api_response_time");
+        assert_eq!(metric.namespace(), Some("shop"));
+        assert_eq!(metric.kind(), MetricKind::Incremental);
+        assert_eq!(
+            metric.value(),
+            &MetricValue::AggregatedHistogram {
+                buckets: vector_lib::buckets![1.0 => 0, 2.5 => 1, 5.0 => 0],
+                count: 1,
+                sum: 2.5,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn response_time_summary_quantiles() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "summary"
+            field = "response_time"
+            quantiles = [0.5, 0.99]
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metric = do_transform(config, event).await.unwrap().into_metric();
+
+        assert_eq!(
+            metric.value(),
+            &MetricValue::AggregatedSummary {
+                quantiles: vector_lib::quantiles![0.5 => 2.5, 0.99 => 2.5],
+                count: 1,
+                sum: 2.5,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_buckets_and_quantiles() {
+        for config in [
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [5.0, 1.0]
+            "#,
+            r#"
+            [[metrics]]
+            type = "summary"
+            field = "response_time"
+            quantiles = [1.5]
+            "#,
+        ] {
+            let config = parse_config(config);
+            assert!(config.build(&TransformContext::default()).await.is_err());
+        }
+    }
+
     //  Metric Metadata Tests
     //
     fn create_log_event(json_str: &str) -> Event {
@@ -2239,4 +2450,81 @@ mod tests {
             .with_timestamp(Some(ts()))
         );
     }
+
+    #[test]
+    fn round_trips_metric_to_log() {
+        use vector_lib::{TimeZone, codecs::MetricTagValues, metrics::AgentDDSketch};
+
+        use crate::{
+            event::metric::{Bucket, Quantile, Sample},
+            transforms::metric_to_log::MetricToLog,
+        };
+
+        let mut sketch = AgentDDSketch::with_agent_defaults();
+        sketch.insert_many(&[1.0, 2.5, 3.0, 100.0]);
+        let values = [
+            MetricValue::Counter { value: 10.5 },
+            MetricValue::Gauge { value: -2.0 },
+            MetricValue::Set {
+                values: ["a".into(), "b".into()].into_iter().collect(),
+            },
+            MetricValue::Distribution {
+                samples: vec![Sample {
+                    value: 1.5,
+                    rate: 3,
+                }],
+                statistic: StatisticKind::Summary,
+            },
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2,
+                    },
+                    Bucket {
+                        upper_limit: f64::INFINITY,
+                        count: 1,
+                    },
+                ],
+                count: 3,
+                sum: 12.0,
+            },
+            MetricValue::AggregatedSummary {
+                quantiles: vec![Quantile {
+                    quantile: 0.99,
+                    value: 4.0,
+                }],
+                count: 7,
+                sum: 20.0,
+            },
+            MetricValue::Sketch {
+                sketch: MetricSketch::AgentDDSketch(sketch),
+            },
+        ];
+
+        for log_namespace in [LogNamespace::Legacy, LogNamespace::Vector] {
+            let metric_to_log = MetricToLog::new(
+                None,
+                TimeZone::default(),
+                log_namespace,
+                MetricTagValues::Full,
+            );
+            for value in values.clone() {
+                let mut tags = metric_tags!("env" => "test", "region" => "a", "region" => "b");
+                tags.insert("bare".to_string(), TagValue::Bare);
+                let metric = Metric::new("requests", MetricKind::Incremental, value)
+                    .with_namespace(Some("app"))
+                    .with_tags(Some(tags))
+                    .with_timestamp(Some(ts()))
+                    .with_interval_ms(NonZeroU32::new(10_000));
+
+                let log = metric_to_log.transform_one(metric.clone()).unwrap();
+                let Ok(reconstructed) = to_metrics(&Event::Log(log)) else {
+                    panic!("{metric:?} wasn't reconstructed");
+                };
+                assert_eq!(reconstructed.series(), metric.series());
+                assert_eq!(reconstructed.data(), metric.data());
+            }
+        }
+    }
 }
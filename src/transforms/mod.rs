@@ -9,14 +9,22 @@ pub mod sample;
 
 #[cfg(feature = "transforms-aggregate")]
 pub mod aggregate;
+#[cfg(feature = "transforms-aggregate_v2")]
+pub mod aggregate_v2;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
 pub mod aws_ec2_metadata;
+#[cfg(feature = "transforms-cardinality_limit")]
+pub mod cardinality_limit;
+#[cfg(feature = "transforms-exec")]
+pub mod exec;
 #[cfg(feature = "transforms-exclusive-route")]
 mod exclusive_route;
 #[cfg(feature = "transforms-filter")]
 pub mod filter;
 #[cfg(feature = "transforms-incremental_to_absolute")]
 pub mod incremental_to_absolute;
+#[cfg(feature = "transforms-kubernetes_metadata")]
+pub mod kubernetes_metadata;
 #[cfg(feature = "transforms-log_to_metric")]
 pub mod log_to_metric;
 #[cfg(feature = "transforms-lua")]
@@ -29,8 +37,12 @@ pub mod remap;
 pub mod route;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sample")]
+pub mod tail_sample;
 #[cfg(feature = "transforms-throttle")]
 pub mod throttle;
+#[cfg(feature = "transforms-wasm")]
+pub mod wasm;
 #[cfg(feature = "transforms-window")]
 pub mod window;
 
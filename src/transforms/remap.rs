@@ -1,12 +1,26 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     fs::File,
     io::{self, Read},
-    path::PathBuf,
-    sync::Mutex,
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex, Weak},
+    time::{Duration, Instant, SystemTime},
 };
 
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+use ordered_float::NotNan;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
+use tokio::sync::Semaphore;
+use trust_dns_resolver::{
+    TokioAsyncResolver,
+    error::{ResolveError, ResolveErrorKind},
+};
 use vector_lib::{
     TimeZone,
     codecs::MetricTagValues,
@@ -14,13 +28,22 @@ use vector_lib::{
     config::LogNamespace,
     configurable::configurable_component,
     enrichment::TableRegistry,
+    internal_event::{InternalEventHandle as _, Registered},
     lookup::{PathPrefix, metadata_path, owned_value_path},
     schema::Definition,
+    sensitive_string::SensitiveString,
+};
+use vector_vrl_functions::{
+    cache::{DEFAULT_PATTERN_CACHE_SIZE, PatternCache, PatternCacheObserver, PatternKind},
+    dns::{self, DnsBackend, DnsError, DnsQuery, DnsResolver},
+    encryption::EncryptionKeys,
+    set_semantic_meaning::MeaningList,
+    state::{StateBackend, StateEntry, StateError, StateStore},
+    user_function::{UserFunction, parse_library},
 };
-use vector_vrl_functions::set_semantic_meaning::MeaningList;
 use vrl::{
     compiler::{
-        CompileConfig, ExpressionError, Program, TypeState, VrlRuntime,
+        CompileConfig, ExpressionError, Function, Program, TypeState, VrlRuntime,
         runtime::{Runtime, Terminate},
         state::ExternalEnv,
     },
@@ -38,14 +61,43 @@ use crate::{
     },
     event::{Event, TargetEvents, VrlTarget},
     format_vrl_diagnostics,
-    internal_events::{RemapMappingAbort, RemapMappingError},
+    internal_events::{
+        RemapMappingAbort, RemapMappingError, RemapPatternCacheLookup, RemapStateWriteError,
+    },
     schema,
     transforms::{SyncTransform, Transform, TransformOutputsBuf},
 };
 
 const DROPPED: &str = "dropped";
+const STATE_DIR_NAME: &str = "state";
+/// The memory used to cache the entries of the state.
+const STATE_CACHE_CAPACITY: u64 = 64 * 1024 * 1024;
+/// How often the expired entries of the state are removed.
+const STATE_PURGE_INTERVAL: Duration = Duration::from_secs(60);
 type CacheKey = (TableRegistry, schema::Definition);
-type CacheValue = (Program, String, MeaningList);
+type CacheValue = (Arc<Program>, String, MeaningList, Option<StateStore>);
+
+/// The programs compiled by the transforms without a state, by source, shared by the transforms
+/// with the same source and inputs so that the programs repeated across a configuration are only
+/// compiled, and held in memory, once.
+static SHARED_PROGRAMS: LazyLock<Mutex<HashMap<String, Vec<SharedProgram>>>> =
+    LazyLock::new(Default::default);
+
+/// The functions of the libraries included by the programs, by source, so that each library is
+/// only compiled once.
+static COMPILED_LIBRARIES: LazyLock<Mutex<HashMap<String, Vec<UserFunction>>>> =
+    LazyLock::new(Default::default);
+
+/// A program shared by the transforms with the same source, as long as one of them is running.
+struct SharedProgram {
+    libraries: Vec<String>,
+    enrichment_tables: TableRegistry,
+    merged_schema_definition: schema::Definition,
+    pattern_cache_size: NonZeroUsize,
+    program: Weak<Program>,
+    warnings: String,
+    meaning_list: MeaningList,
+}
 
 /// Configuration for the `remap` transform.
 #[configurable_component(transform(
@@ -87,6 +139,20 @@ pub struct RemapConfig {
     #[configurable(metadata(docs::examples = "['./my/program.vrl', './my/program2.vrl']"))]
     pub files: Option<Vec<PathBuf>>,
 
+    /// File paths to VRL libraries defining functions that the program can call.
+    ///
+    /// A library defines functions with `fn <name>(<parameters>) { <body> }`. The body of a
+    /// function is a VRL program of its own, run with the arguments bound to the variables named
+    /// after the parameters, and returning the value of its last expression. It doesn't have access
+    /// to the event, and can only call the functions defined before it in the same library, and the
+    /// functions that don't depend on the options of the transform. Each library is only compiled
+    /// once, and shared by the programs including it.
+    ///
+    /// If a relative path is provided, its root is the current working directory.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "lib/parsers.vrl"))]
+    pub includes: Vec<PathBuf>,
+
     /// When set to `single`, metric tag values are exposed as single strings, the
     /// same as they were before this config option. Tags with multiple values show the last assigned value, and null values
     /// are ignored.
@@ -149,6 +215,52 @@ pub struct RemapConfig {
     #[configurable(metadata(docs::human_name = "Reroute Dropped Events"))]
     pub reroute_dropped: bool,
 
+    /// Persistent state shared across the events processed by the program.
+    ///
+    /// When set, the program can read and write key-value state with the `get_state` and
+    /// `set_state` functions, for example to track sessions, count events, or detect the first
+    /// occurrence of a value. The state is persisted in the data directory, and restored when
+    /// Vector restarts.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub state: Option<RemapStateConfig>,
+
+    /// The keys available to the `encrypt_field` and `decrypt_field` functions, by name.
+    ///
+    /// Each key must be a base64-encoded, 32-byte key, and can be sourced from a secrets backend
+    /// with the `SECRET[<backend>.<secret_key>]` syntax. The program only refers to the keys by
+    /// name, and the name of the key is stored along with each encrypted value, so that the values
+    /// encrypted before a key is rotated remain readable as long as the previous key is still
+    /// listed under its name.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "A base64-encoded, 32-byte encryption key."
+    ))]
+    #[configurable(metadata(docs::examples = "example_encryption_keys()"))]
+    pub encryption_keys: BTreeMap<String, SensitiveString>,
+
+    /// Cached, non-blocking DNS lookups for the `dns_lookup` and `reverse_dns` functions.
+    ///
+    /// When set, the functions resolve their lookups with the name servers of the system in the
+    /// background, and cache the answers for the TTL of their records, so that enriching the events
+    /// with host names never stalls the transform. The functions fail for the events whose lookup
+    /// isn't cached yet, so the first events with a new host name or address aren't enriched.
+    /// Otherwise, the functions block until each lookup completes.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub dns: Option<RemapDnsConfig>,
+
+    /// The number of patterns of each kind cached by the functions parsing patterns only known at
+    /// runtime.
+    ///
+    /// The regexes of `match_dynamic`, the grok patterns of `parse_grok_dynamic` and the formats of
+    /// `parse_timestamp_dynamic` are each kept in a cache of this size, evicting the least recently
+    /// used pattern once full, so that they aren't parsed again for each event.
+    #[serde(default = "default_pattern_cache_size")]
+    #[derivative(Default(value = "default_pattern_cache_size()"))]
+    #[configurable(metadata(docs::advanced))]
+    pub pattern_cache_size: NonZeroUsize,
+
     #[configurable(derived, metadata(docs::hidden))]
     #[serde(default)]
     pub runtime: VrlRuntime,
@@ -168,23 +280,152 @@ impl Clone for RemapConfig {
             source: self.source.clone(),
             file: self.file.clone(),
             files: self.files.clone(),
+            includes: self.includes.clone(),
             metric_tag_values: self.metric_tag_values,
             timezone: self.timezone,
             drop_on_error: self.drop_on_error,
             drop_on_abort: self.drop_on_abort,
             reroute_dropped: self.reroute_dropped,
+            state: self.state.clone(),
+            encryption_keys: self.encryption_keys.clone(),
+            dns: self.dns.clone(),
+            pattern_cache_size: self.pattern_cache_size,
             runtime: self.runtime,
             cache: Mutex::new(Default::default()),
         }
     }
 }
 
+/// Configuration of the persistent state of the VRL program.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct RemapStateConfig {
+    /// The directory used to persist the state.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used.
+    /// Make sure the running user has write permissions to this directory.
+    ///
+    /// If this directory is specified, then Vector will attempt to create it.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/local/lib/vector/"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+
+    /// How often the writes to the state are flushed to disk, in seconds.
+    ///
+    /// Each value is written as it is set, and the writes are also flushed when the transform
+    /// stops.
+    #[serde(default = "default_state_flush_interval_secs")]
+    #[derivative(Default(value = "default_state_flush_interval_secs()"))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub flush_interval_secs: NonZeroU64,
+}
+
+const fn default_state_flush_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(10).unwrap()
+}
+
+/// Configuration of the DNS lookups of the VRL program.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct RemapDnsConfig {
+    /// The number of lookups cached.
+    ///
+    /// The answers are cached for the TTL of their records, and the lookups that found no records
+    /// for their negative TTL. The least recently used lookup is evicted once the cache is full.
+    #[serde(default = "default_dns_cache_size")]
+    #[derivative(Default(value = "default_dns_cache_size()"))]
+    pub cache_size: NonZeroUsize,
+
+    /// The maximum number of lookups in progress at once.
+    ///
+    /// The functions fail right away when this many lookups are in progress, instead of waiting
+    /// for a slow name server.
+    #[serde(default = "default_dns_max_concurrent_lookups")]
+    #[derivative(Default(value = "default_dns_max_concurrent_lookups()"))]
+    pub max_concurrent_lookups: NonZeroUsize,
+
+    /// How long a lookup can take, in milliseconds.
+    ///
+    /// A lookup that times out isn't cached, and is started again for the next events needing it.
+    #[serde(default = "default_dns_timeout_ms")]
+    #[derivative(Default(value = "default_dns_timeout_ms()"))]
+    #[configurable(metadata(docs::type_unit = "milliseconds"))]
+    pub timeout_ms: NonZeroU64,
+}
+
+const fn default_dns_cache_size() -> NonZeroUsize {
+    NonZeroUsize::new(10_000).unwrap()
+}
+
+const fn default_dns_max_concurrent_lookups() -> NonZeroUsize {
+    NonZeroUsize::new(32).unwrap()
+}
+
+const fn default_dns_timeout_ms() -> NonZeroU64 {
+    NonZeroU64::new(500).unwrap()
+}
+
+fn example_encryption_keys() -> BTreeMap<String, String> {
+    BTreeMap::from([("pii_v1".to_owned(), "SECRET[vault.pii_v1]".to_owned())])
+}
+
+const fn default_pattern_cache_size() -> NonZeroUsize {
+    DEFAULT_PATTERN_CACHE_SIZE
+}
+
+/// Reports the lookups of the pattern caches as internal metrics.
+struct PatternCacheMetrics {
+    regex: Registered<RemapPatternCacheLookup>,
+    grok: Registered<RemapPatternCacheLookup>,
+    date_format: Registered<RemapPatternCacheLookup>,
+}
+
+impl PatternCacheMetrics {
+    fn new() -> Self {
+        let register = |kind: PatternKind| {
+            register!(RemapPatternCacheLookup {
+                cache: kind.as_str(),
+            })
+        };
+        Self {
+            regex: register(PatternKind::Regex),
+            grok: register(PatternKind::Grok),
+            date_format: register(PatternKind::DateFormat),
+        }
+    }
+}
+
+impl fmt::Debug for PatternCacheMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatternCacheMetrics")
+            .finish_non_exhaustive()
+    }
+}
+
+impl PatternCacheObserver for PatternCacheMetrics {
+    fn lookup(&self, kind: PatternKind, hit: bool) {
+        match kind {
+            PatternKind::Regex => &self.regex,
+            PatternKind::Grok => &self.grok,
+            PatternKind::DateFormat => &self.date_format,
+        }
+        .emit(hit);
+    }
+}
+
 impl RemapConfig {
     fn compile_vrl_program(
         &self,
         enrichment_tables: TableRegistry,
         merged_schema_definition: schema::Definition,
-    ) -> Result<(Program, String, MeaningList)> {
+    ) -> Result<CacheValue> {
         if let Some((_, res)) = self
             .cache
             .lock()
@@ -209,12 +450,57 @@ impl RemapConfig {
             }
             _ => return Err(Box::new(BuildError::SourceAndOrFileOrFiles)),
         };
+        let libraries = self
+            .includes
+            .iter()
+            .map(Self::read_file)
+            .collect::<Result<Vec<_>>>()?;
+
+        // The programs with a state are bound to the state of their transform, the keys of the
+        // programs encrypting fields aren't kept around after the transform is dropped, and the
+        // DNS lookups are bounded for each transform.
+        let shareable =
+            self.state.is_none() && self.encryption_keys.is_empty() && self.dns.is_none();
+        if shareable
+            && let Some(shared) = self.find_shared_program(
+                &source,
+                &libraries,
+                &enrichment_tables,
+                &merged_schema_definition,
+            )
+        {
+            self.cache.lock().expect("Data poisoned").push((
+                (enrichment_tables, merged_schema_definition),
+                Ok(shared.clone()),
+            ));
+            return Ok(shared);
+        }
 
-        let mut functions = vrl::stdlib::all();
-        functions.append(&mut vector_lib::enrichment::vrl_functions());
-        #[cfg(feature = "sources-dnstap")]
-        functions.append(&mut dnstap_parser::vrl_functions());
-        functions.append(&mut vector_vrl_functions::all());
+        let mut functions = vrl_functions();
+        for (path, library) in self.includes.iter().zip(&libraries) {
+            for function in compile_library(path, library)? {
+                if functions
+                    .iter()
+                    .any(|defined| defined.identifier() == function.identifier())
+                {
+                    return Err(Box::new(BuildError::LibraryFunctionAlreadyDefined {
+                        path: path.clone(),
+                        function: function.identifier().to_owned(),
+                    }));
+                }
+                functions.push(Box::new(function));
+            }
+        }
+        if self.dns.is_some() {
+            // The cached lookups replace the blocking ones of the standard library.
+            let cached = dns::functions();
+            functions.retain(|function| {
+                !cached
+                    .iter()
+                    .any(|cached| cached.identifier() == function.identifier())
+            });
+            functions.extend(cached);
+        }
 
         let state = TypeState {
             local: Default::default(),
@@ -227,17 +513,58 @@ impl RemapConfig {
 
         config.set_custom(enrichment_tables.clone());
         config.set_custom(MeaningList::default());
+        config.set_custom(PatternCache::new(
+            self.pattern_cache_size,
+            Some(Arc::new(PatternCacheMetrics::new())),
+        ));
+        if self.state.is_some() {
+            config.set_custom(StateStore::default());
+        }
+        if !self.encryption_keys.is_empty() {
+            let keys = EncryptionKeys::new(
+                self.encryption_keys
+                    .iter()
+                    .map(|(name, key)| (name.as_str(), key.inner())),
+            )
+            .map_err(|reason| BuildError::InvalidEncryptionKeys { reason })?;
+            config.set_custom(keys);
+        }
+        if let Some(dns_config) = &self.dns {
+            config.set_custom(DnsResolver::new(Arc::new(RemapDns::new(dns_config)?)));
+        }
 
         let res = compile_vrl(&source, &functions, &state, config)
             .map_err(|diagnostics| format_vrl_diagnostics(&source, diagnostics))
             .map(|result| {
                 (
-                    result.program,
+                    Arc::new(result.program),
                     format_vrl_diagnostics(&source, result.warnings),
                     result.config.get_custom::<MeaningList>().unwrap().clone(),
+                    result.config.get_custom::<StateStore>().cloned(),
                 )
             });
 
+        if shareable && let Ok((program, warnings, meaning_list, _)) = &res {
+            let mut shared_programs = SHARED_PROGRAMS.lock().expect("Data poisoned");
+            // Forget the programs of the transforms that were dropped.
+            shared_programs.retain(|_, programs| {
+                programs.retain(|shared| shared.program.strong_count() > 0);
+                !programs.is_empty()
+            });
+            shared_programs
+                .entry(source)
+                .or_default()
+                .push(SharedProgram {
+                    libraries,
+                    enrichment_tables: enrichment_tables.clone(),
+                    merged_schema_definition: merged_schema_definition.clone(),
+                    pattern_cache_size: self.pattern_cache_size,
+                    program: Arc::downgrade(program),
+                    warnings: warnings.clone(),
+                    meaning_list: meaning_list.clone(),
+                });
+        }
+
         self.cache
             .lock()
             .expect("Data poisoned")
@@ -246,6 +573,32 @@ impl RemapConfig {
         res.map_err(Into::into)
     }
 
+    fn find_shared_program(
+        &self,
+        source: &str,
+        libraries: &[String],
+        enrichment_tables: &TableRegistry,
+        merged_schema_definition: &schema::Definition,
+    ) -> Option<CacheValue> {
+        let shared_programs = SHARED_PROGRAMS.lock().expect("Data poisoned");
+        shared_programs.get(source)?.iter().find_map(|shared| {
+            if shared.libraries != libraries
+                || shared.enrichment_tables != *enrichment_tables
+                || shared.merged_schema_definition != *merged_schema_definition
+                || shared.pattern_cache_size != self.pattern_cache_size
+            {
+                return None;
+            }
+            let program = shared.program.upgrade()?;
+            Some((
+                program,
+                shared.warnings.clone(),
+                shared.meaning_list.clone(),
+                None,
+            ))
+        })
+    }
+
     fn read_file(path: &PathBuf) -> Result<String> {
         let mut buffer = String::new();
         File::open(path)
@@ -256,6 +609,67 @@ impl RemapConfig {
     }
 }
 
+/// The functions available to the programs, before the ones of their libraries.
+fn vrl_functions() -> Vec<Box<dyn Function>> {
+    let mut functions = vrl::stdlib::all();
+    functions.append(&mut vector_lib::enrichment::vrl_functions());
+    #[cfg(feature = "sources-dnstap")]
+    functions.append(&mut dnstap_parser::vrl_functions());
+    functions.append(&mut vector_vrl_functions::all());
+    functions
+}
+
+/// Compiles the functions of a library, or returns the ones compiled for the same source.
+fn compile_library(path: &Path, library: &str) -> Result<Vec<UserFunction>> {
+    if let Some(compiled) = COMPILED_LIBRARIES
+        .lock()
+        .expect("Data poisoned")
+        .get(library)
+    {
+        return Ok(compiled.clone());
+    }
+
+    let definitions = parse_library(library).map_err(|reason| BuildError::LibraryParseFailed {
+        path: path.to_owned(),
+        reason,
+    })?;
+    let mut functions = vrl_functions();
+    let mut compiled = Vec::with_capacity(definitions.len());
+    for definition in &definitions {
+        if functions
+            .iter()
+            .any(|function| function.identifier() == definition.name)
+        {
+            return Err(Box::new(BuildError::LibraryFunctionAlreadyDefined {
+                path: path.to_owned(),
+                function: definition.name.clone(),
+            }));
+        }
+        let program = compile_vrl(
+            &definition.source,
+            &functions,
+            &TypeState::default(),
+            CompileConfig::default(),
+        )
+        .map_err(|diagnostics| BuildError::LibraryCompileFailed {
+            path: path.to_owned(),
+            function: definition.name.clone(),
+            line: definition.line,
+            diagnostics: format_vrl_diagnostics(&definition.source, diagnostics),
+        })?
+        .program;
+        let function = UserFunction::new(definition, program);
+        functions.push(Box::new(function.clone()));
+        compiled.push(function);
+    }
+
+    COMPILED_LIBRARIES
+        .lock()
+        .expect("Data poisoned")
+        .insert(library.to_owned(), compiled.clone());
+    Ok(compiled)
+}
+
 impl_generate_config_from_default!(RemapConfig);
 
 #[async_trait::async_trait]
@@ -301,7 +715,7 @@ impl TransformConfig for RemapConfig {
         // step.
         let compiled = self
             .compile_vrl_program(enrichment_tables, merged_definition)
-            .map(|(program, _, meaning_list)| (program.final_type_info().state, meaning_list.0))
+            .map(|(program, _, meaning_list, _)| (program.final_type_info().state, meaning_list.0))
             .map_err(|_| ());
 
         let mut dropped_definitions = HashMap::new();
@@ -393,6 +807,7 @@ impl TransformConfig for RemapConfig {
         self.file
             .iter()
             .chain(self.files.iter().flatten())
+            .chain(&self.includes)
             .collect()
     }
 }
@@ -403,7 +818,7 @@ where
     Runner: VrlRunner,
 {
     component_key: Option<ComponentKey>,
-    program: Program,
+    program: Arc<Program>,
     timezone: TimeZone,
     drop_on_error: bool,
     drop_on_abort: bool,
@@ -412,6 +827,315 @@ where
     metric_tag_values: MetricTagValues,
 }
 
+/// The state of the VRL program, persisted in the data directory.
+///
+/// Each entry is written to an embedded key-value store as it is set. The store only caches the
+/// most recently used entries in memory, and flushes the writes to disk periodically and when it's
+/// dropped.
+#[derive(Debug)]
+pub struct RemapState {
+    db: sled::Db,
+}
+
+/// The states opened by the running transforms, by directory, so that a transform rebuilt on
+/// reload shares the state of the one it replaces instead of failing to lock it.
+static OPENED_STATES: LazyLock<Mutex<HashMap<PathBuf, Weak<RemapState>>>> =
+    LazyLock::new(Default::default);
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PersistedStateEntry {
+    value: PersistedValue,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// A value of the state, keeping its type.
+#[derive(Debug, Deserialize, Serialize)]
+enum PersistedValue {
+    Bytes(Bytes),
+    Regex(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Timestamp(DateTime<Utc>),
+    Object(BTreeMap<String, PersistedValue>),
+    Array(Vec<PersistedValue>),
+    Null,
+}
+
+impl From<Value> for PersistedValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Bytes(bytes) => Self::Bytes(bytes),
+            Value::Regex(regex) => Self::Regex(regex.as_str().to_owned()),
+            Value::Integer(value) => Self::Integer(value),
+            Value::Float(value) => Self::Float(value.into_inner()),
+            Value::Boolean(value) => Self::Boolean(value),
+            Value::Timestamp(timestamp) => Self::Timestamp(timestamp),
+            Value::Object(fields) => Self::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+            Value::Array(items) => Self::Array(items.into_iter().map(Into::into).collect()),
+            Value::Null => Self::Null,
+        }
+    }
+}
+
+impl TryFrom<PersistedValue> for Value {
+    type Error = StateError;
+
+    fn try_from(value: PersistedValue) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            PersistedValue::Bytes(bytes) => Self::Bytes(bytes),
+            PersistedValue::Regex(regex) => Self::from(Regex::new(&regex)?),
+            PersistedValue::Integer(value) => Self::Integer(value),
+            PersistedValue::Float(value) => Self::Float(NotNan::new(value)?),
+            PersistedValue::Boolean(value) => Self::Boolean(value),
+            PersistedValue::Timestamp(timestamp) => Self::Timestamp(timestamp),
+            PersistedValue::Object(fields) => Self::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| Ok((key.into(), value.try_into()?)))
+                    .collect::<std::result::Result<_, StateError>>()?,
+            ),
+            PersistedValue::Array(items) => Self::Array(
+                items
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+            PersistedValue::Null => Self::Null,
+        })
+    }
+}
+
+impl RemapState {
+    /// Opens the state persisted in the directory, or the one already opened by another transform.
+    fn open(data_dir: &Path, flush_interval: Duration) -> Result<Arc<Self>> {
+        let path = data_dir.join(STATE_DIR_NAME);
+        let mut opened = OPENED_STATES.lock().expect("Data poisoned");
+        opened.retain(|_, state| state.strong_count() > 0);
+        if let Some(state) = opened.get(&path).and_then(Weak::upgrade) {
+            return Ok(state);
+        }
+
+        let db = sled::Config::new()
+            .path(&path)
+            .cache_capacity(STATE_CACHE_CAPACITY)
+            .flush_every_ms(Some(flush_interval.as_millis() as u64))
+            .open()
+            .with_context(|_| StateOpenFailedSnafu { path: path.clone() })?;
+        let state = Arc::new(Self { db });
+        opened.insert(path, Arc::downgrade(&state));
+        Self::spawn_purge(Arc::downgrade(&state));
+        Ok(state)
+    }
+
+    /// Periodically removes the expired entries, until the state is dropped.
+    fn spawn_purge(state: Weak<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STATE_PURGE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+                if let Ok(Err(error)) = tokio::task::spawn_blocking(move || state.purge()).await {
+                    emit!(RemapStateWriteError { error });
+                }
+            }
+        });
+    }
+
+    fn purge(&self) -> sled::Result<()> {
+        let now = SystemTime::now();
+        for item in self.db.iter() {
+            let (key, data) = item?;
+            // Entries that can't be decoded are reported when they are read.
+            if decode_state_entry(&data).is_ok_and(|entry| entry.expired(now)) {
+                self.db.remove(key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StateBackend for RemapState {
+    fn get(&self, key: &str) -> std::result::Result<Option<StateEntry>, StateError> {
+        self.db
+            .get(key)?
+            .map(|data| decode_state_entry(&data))
+            .transpose()
+    }
+
+    fn insert(&self, key: &str, entry: StateEntry) -> std::result::Result<(), StateError> {
+        let data = rmp_serde::to_vec(&PersistedStateEntry {
+            value: entry.value.into(),
+            expires_at: entry.expires_at.map(Into::into),
+        })?;
+        self.db.insert(key, data)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> std::result::Result<(), StateError> {
+        self.db.remove(key)?;
+        Ok(())
+    }
+}
+
+fn decode_state_entry(data: &[u8]) -> std::result::Result<StateEntry, StateError> {
+    let entry: PersistedStateEntry = rmp_serde::from_slice(data)?;
+    Ok(StateEntry {
+        value: entry.value.try_into()?,
+        expires_at: entry.expires_at.map(Into::into),
+    })
+}
+
+/// The runtime resolving the DNS lookups of the programs, apart from the runtime running the
+/// transforms, which never wait for the lookups.
+static DNS_RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("vector-remap-dns")
+        .enable_all()
+        .build()
+        .expect("failed to build the DNS lookups runtime")
+});
+
+/// How long a lookup that found no records is cached when the name server doesn't tell.
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(60);
+
+type DnsAnswer = std::result::Result<Vec<String>, String>;
+
+#[derive(Debug)]
+struct CachedDnsAnswer {
+    answer: DnsAnswer,
+    expires_at: Instant,
+}
+
+/// The DNS lookups of the program, cached for the TTL of their records.
+///
+/// The lookups that aren't cached are resolved in the background, up to `max_concurrent_lookups` at
+/// once, and fail until their answer is cached.
+struct RemapDns {
+    resolver: TokioAsyncResolver,
+    cache: Arc<Mutex<LruCache<DnsQuery, CachedDnsAnswer>>>,
+    /// The queries being resolved in the background.
+    pending: Arc<Mutex<HashSet<DnsQuery>>>,
+    lookups: Arc<Semaphore>,
+    timeout: Duration,
+}
+
+impl RemapDns {
+    fn new(config: &RemapDnsConfig) -> std::result::Result<Self, BuildError> {
+        Ok(Self {
+            resolver: TokioAsyncResolver::tokio_from_system_conf().context(DnsConfigFailedSnafu)?,
+            cache: Arc::new(Mutex::new(LruCache::new(config.cache_size))),
+            pending: Default::default(),
+            lookups: Arc::new(Semaphore::new(config.max_concurrent_lookups.get())),
+            timeout: Duration::from_millis(config.timeout_ms.get()),
+        })
+    }
+
+    fn cached(&self, query: &DnsQuery) -> Option<DnsAnswer> {
+        let mut cache = self.cache.lock().expect("Data poisoned");
+        match cache.get(query) {
+            Some(cached) if cached.expires_at > Instant::now() => Some(cached.answer.clone()),
+            Some(_) => {
+                cache.pop(query);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+/// Resolves a query, returning its answer and how long it can be cached, unless the lookup failed
+/// for a reason that may not last, such as a timeout.
+async fn lookup_dns(
+    resolver: &TokioAsyncResolver,
+    query: &DnsQuery,
+) -> std::result::Result<(DnsAnswer, Instant), ResolveError> {
+    let lookup = match query {
+        DnsQuery::Lookup(host) => resolver.lookup_ip(host.as_str()).await.map(|lookup| {
+            let answer = lookup.iter().map(|address| address.to_string()).collect();
+            (answer, lookup.as_lookup().valid_until())
+        }),
+        DnsQuery::Reverse(address) => resolver.reverse_lookup(*address).await.map(|lookup| {
+            let answer = lookup
+                .iter()
+                .map(|name| name.to_utf8().trim_end_matches('.').to_owned())
+                .collect();
+            (answer, lookup.as_lookup().valid_until())
+        }),
+    };
+    match lookup {
+        Ok((answer, valid_until)) => Ok((Ok(answer), valid_until)),
+        Err(error) => match error.kind() {
+            ResolveErrorKind::NoRecordsFound { negative_ttl, .. } => {
+                let ttl = negative_ttl.map_or(DEFAULT_NEGATIVE_TTL, |ttl| {
+                    Duration::from_secs(u64::from(ttl))
+                });
+                Ok((Err(error.to_string()), Instant::now() + ttl))
+            }
+            _ => Err(error),
+        },
+    }
+}
+
+impl fmt::Debug for RemapDns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemapDns")
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DnsBackend for RemapDns {
+    fn resolve(&self, query: &DnsQuery) -> std::result::Result<Vec<String>, DnsError> {
+        if let Some(answer) = self.cached(query) {
+            return answer.map_err(Into::into);
+        }
+
+        if !self
+            .pending
+            .lock()
+            .expect("Data poisoned")
+            .insert(query.clone())
+        {
+            return Err("DNS lookup in progress".into());
+        }
+        let Ok(permit) = Arc::clone(&self.lookups).try_acquire_owned() else {
+            self.pending.lock().expect("Data poisoned").remove(query);
+            return Err("too many DNS lookups in progress".into());
+        };
+
+        let resolver = self.resolver.clone();
+        let cache = Arc::clone(&self.cache);
+        let pending = Arc::clone(&self.pending);
+        let timeout = self.timeout;
+        let query = query.clone();
+        DNS_RUNTIME.spawn(async move {
+            // The lookups that failed for a reason that may not last, such as a timeout, aren't
+            // cached, so that they're tried again for the next events.
+            if let Ok(Ok((answer, expires_at))) =
+                tokio::time::timeout(timeout, lookup_dns(&resolver, &query)).await
+            {
+                cache
+                    .lock()
+                    .expect("Data poisoned")
+                    .put(query.clone(), CachedDnsAnswer { answer, expires_at });
+            }
+            pending.lock().expect("Data poisoned").remove(&query);
+            drop(permit);
+        });
+
+        Err("DNS lookup in progress".into())
+    }
+}
+
 pub trait VrlRunner {
     fn run(
         &mut self,
@@ -452,11 +1176,23 @@ impl Remap<AstRunner> {
         config: RemapConfig,
         context: &TransformContext,
     ) -> crate::Result<(Self, String)> {
-        let (program, warnings, _) = config.compile_vrl_program(
+        let (program, warnings, _, store) = config.compile_vrl_program(
             context.enrichment_tables.clone(),
             context.merged_schema_definition.clone(),
         )?;
 
+        if let (Some(state_config), Some(store)) = (&config.state, store) {
+            let data_dir = context.globals.resolve_and_make_data_subdir(
+                state_config.data_dir.as_ref(),
+                context.key.as_ref().map_or("remap", ComponentKey::id),
+            )?;
+            let state = RemapState::open(
+                &data_dir,
+                Duration::from_secs(state_config.flush_interval_secs.get()),
+            )?;
+            store.attach(state);
+        }
+
         let runtime = Runtime::default();
         let runner = AstRunner { runtime };
 
@@ -471,7 +1207,7 @@ where
     fn new(
         config: RemapConfig,
         context: &TransformContext,
-        program: Program,
+        program: Arc<Program>,
         runner: Runner,
     ) -> crate::Result<Self> {
         Ok(Remap {
@@ -664,6 +1400,33 @@ pub enum BuildError {
     FileOpenFailed { path: PathBuf, source: io::Error },
     #[snafu(display("Could not read vrl program {:?}: {}", path, source))]
     FileReadFailed { path: PathBuf, source: io::Error },
+
+    #[snafu(display("Could not parse VRL library {:?}: {}", path, reason))]
+    LibraryParseFailed { path: PathBuf, reason: String },
+    #[snafu(display(
+        "Could not compile function {:?} of VRL library {:?}, defined at line {}:\n{}",
+        function,
+        path,
+        line,
+        diagnostics
+    ))]
+    LibraryCompileFailed {
+        path: PathBuf,
+        function: String,
+        line: usize,
+        diagnostics: String,
+    },
+    #[snafu(display("Function {:?} of VRL library {:?} is already defined", function, path))]
+    LibraryFunctionAlreadyDefined { path: PathBuf, function: String },
+
+    #[snafu(display("Could not open remap state {:?}: {}", path, source))]
+    StateOpenFailed { path: PathBuf, source: sled::Error },
+
+    #[snafu(display("Invalid encryption keys: {}", reason))]
+    InvalidEncryptionKeys { reason: String },
+
+    #[snafu(display("Could not read the DNS configuration of the system: {}", source))]
+    DnsConfigFailed { source: ResolveError },
 }
 
 #[cfg(test)]
@@ -764,6 +1527,25 @@ mod tests {
         )
     }
 
+    #[test]
+    fn shares_programs_with_the_same_source() {
+        let config = || RemapConfig {
+            source: Some(".shares_programs_with_the_same_source = true".to_owned()),
+            ..Default::default()
+        };
+
+        let first = remap(config()).unwrap();
+        let second = remap(config()).unwrap();
+        assert!(Arc::ptr_eq(&first.program, &second.program));
+
+        let other = remap(RemapConfig {
+            source: Some(".shares_programs_with_the_same_source = false".to_owned()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!Arc::ptr_eq(&first.program, &other.program));
+    }
+
     fn get_field_string(event: &Event, field: &str) -> String {
         event
             .as_log()
@@ -805,6 +1587,152 @@ mod tests {
         assert!(tform.runner().runtime.is_empty());
     }
 
+    #[tokio::test]
+    async fn check_remap_state_persists_across_events_and_restarts() {
+        let data_dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let conf = || RemapConfig {
+            source: Some(
+                indoc! {r#"
+                    key = string!(.host)
+                    count = int(get_state!(key)) ?? 0
+                    count = count + 1
+                    set_state!(key, count)
+                    .count = count
+                    .first_seen_at = get_state!(key + ":first_seen_at")
+                    if .first_seen_at == null {
+                        set_state!(key + ":first_seen_at", .timestamp)
+                    }
+                "#}
+                .to_owned(),
+            ),
+            state: Some(RemapStateConfig {
+                data_dir: Some(data_dir.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let event = |host: &str| {
+            let mut log = LogEvent::from("message");
+            log.insert("host", host);
+            log.insert("timestamp", chrono::Utc::now());
+            Event::from(log)
+        };
+
+        let mut tform = remap(conf()).unwrap();
+        // Opening the state again while it's in use shares it.
+        let shared = remap(conf()).unwrap();
+        for expected in 1_i64..=2 {
+            let result = transform_one(&mut tform, event("a")).unwrap();
+            assert_eq!(result.as_log()["count"], Value::from(expected));
+        }
+        let result = transform_one(&mut tform, event("b")).unwrap();
+        assert_eq!(result.as_log()["count"], Value::from(1_i64));
+
+        // Dropping the transforms flushes the state, which is restored by the next one, keeping
+        // the types of its values.
+        let first_seen_at = result.as_log()["timestamp"].clone();
+        drop((tform, shared));
+        let mut tform = remap(conf()).unwrap();
+        let result = transform_one(&mut tform, event("b")).unwrap();
+        assert_eq!(result.as_log()["count"], Value::from(2_i64));
+        assert_eq!(result.as_log()["first_seen_at"], first_seen_at);
+        assert!(first_seen_at.is_timestamp());
+    }
+
+    #[test]
+    fn check_remap_state_functions_require_state() {
+        let conf = RemapConfig {
+            source: Some(r#"set_state("key", 1)"#.to_owned()),
+            ..Default::default()
+        };
+
+        let err = remap(conf).unwrap_err().to_string();
+        assert!(err.contains("state is not enabled"), "{err}");
+    }
+
+    #[test]
+    fn check_remap_includes_libraries() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let library = dir.join("parsers.vrl");
+        std::fs::write(
+            &library,
+            indoc! {r#"
+                fn status_class(status) {
+                    string!(slice!(status, 0, 1)) + "xx"
+                }
+
+                fn parse_status(message) {
+                    status_class!(parse_regex!(message, r'status=(?P<status>\d+)').status)
+                }
+            "#},
+        )
+        .unwrap();
+        let conf = |source: &str| RemapConfig {
+            source: Some(source.to_owned()),
+            includes: vec![library.clone()],
+            ..Default::default()
+        };
+
+        let mut tform = remap(conf(".class = parse_status!(.message)")).unwrap();
+        let event = Event::from(LogEvent::from("GET / status=404"));
+        let result = transform_one(&mut tform, event).unwrap();
+        assert_eq!(get_field_string(&result, "class"), "4xx");
+
+        let err = remap(conf("parse_status(.message)"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unhandled"), "{err}");
+
+        let err = remap(RemapConfig {
+            includes: vec![library.clone(), library.clone()],
+            ..conf(".")
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains(r#"Function "status_class""#), "{err}");
+    }
+
+    #[test]
+    fn check_remap_dns_lookups_are_cached() {
+        let dns = RemapDns::new(&RemapDnsConfig::default()).unwrap();
+        let query = DnsQuery::Reverse([192, 0, 2, 1].into());
+        let cache = |answer: DnsAnswer, expires_at| {
+            dns.cache
+                .lock()
+                .unwrap()
+                .put(query.clone(), CachedDnsAnswer { answer, expires_at });
+        };
+
+        cache(
+            Ok(vec!["host.example".to_owned()]),
+            Instant::now() + Duration::from_secs(60),
+        );
+        assert_eq!(dns.resolve(&query).unwrap(), ["host.example"]);
+
+        // The lookups that found no records are cached as well.
+        cache(
+            Err("no records found".to_owned()),
+            Instant::now() + Duration::from_secs(60),
+        );
+        let error = dns.resolve(&query).unwrap_err().to_string();
+        assert_eq!(error, "no records found");
+
+        // Expired answers are evicted instead of being returned.
+        cache(
+            Ok(vec!["host.example".to_owned()]),
+            Instant::now() - Duration::from_secs(1),
+        );
+        assert_eq!(dns.cached(&query), None);
+        assert!(dns.cache.lock().unwrap().is_empty());
+
+        // The functions don't wait for the lookups that aren't cached yet.
+        dns.pending.lock().unwrap().insert(query.clone());
+        let error = dns.resolve(&query).unwrap_err().to_string();
+        assert_eq!(error, "DNS lookup in progress");
+    }
+
     #[test]
     fn remap_return_raw_string_vector_namespace() {
         let initial_definition = Definition::default_for_namespace(&[LogNamespace::Vector].into());
@@ -913,6 +1841,42 @@ mod tests {
         assert_eq!(get_field_string(&r, "message"), "bar");
     }
 
+    #[test]
+    fn check_remap_parse_proto() {
+        let event = {
+            let mut event = LogEvent::from("decode me");
+            event.insert(
+                "payload",
+                "CgMxMjMSBUFsaWNlGB4iEWFsaWNlQGV4YW1wbGUuY29tIg5hbGljZUB3b3JrLmNvbQ==",
+            );
+            Event::from(event)
+        };
+        let desc_file = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/protobuf/test_proto.desc");
+
+        let conf = RemapConfig {
+            source: Some(formatdoc! {r#"
+                .user = parse_proto!(decode_base64!(.payload), "{}", "test_proto.User")
+                del(.payload)
+            "#, desc_file.display()}),
+            drop_on_error: true,
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+        let result = transform_one(&mut tform, event).unwrap();
+        assert_eq!(get_field_string(&result, "message"), "decode me");
+        assert!(result.as_log().get("payload").is_none());
+        assert_eq!(
+            result.as_log().get("user"),
+            Some(&Value::from(btreemap! {
+                "id" => "123",
+                "name" => "Alice",
+                "age" => 30,
+                "emails" => vec!["alice@example.com", "alice@work.com"],
+            }))
+        );
+    }
+
     #[test]
     fn check_remap_error() {
         let event = {
@@ -2,6 +2,7 @@ use indexmap::IndexMap;
 use vector_lib::{
     config::{LogNamespace, clone_input_definitions},
     configurable::configurable_component,
+    internal_event::InternalEventHandle as _,
     transform::SyncTransform,
 };
 
@@ -12,6 +13,7 @@ use crate::{
         TransformOutput,
     },
     event::Event,
+    internal_events::{RouteEvents, RouteEventsHandle},
     schema,
     transforms::Transform,
 };
@@ -20,19 +22,62 @@ pub(crate) const UNMATCHED_ROUTE: &str = "_unmatched";
 
 #[derive(Clone)]
 pub struct Route {
-    conditions: Vec<(String, Condition)>,
+    routes: Vec<RouteOutput>,
     reroute_unmatched: bool,
 }
 
+#[derive(Clone)]
+struct RouteOutput {
+    name: String,
+    condition: Condition,
+    sampler: Option<Sampler>,
+    events: Option<RouteEventsHandle>,
+}
+
+/// Forwards a ratio of the events by accumulating the ratio for each of them, like the `sample`
+/// transform does when it isn't given a key.
+#[derive(Clone, Debug)]
+struct Sampler {
+    ratio: f64,
+    value: f64,
+}
+
+impl Sampler {
+    const fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            value: 1.0 - ratio,
+        }
+    }
+
+    fn sample(&mut self) -> bool {
+        self.value += self.ratio;
+        if self.value >= 1.0 {
+            self.value -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 impl Route {
     pub fn new(config: &RouteConfig, context: &TransformContext) -> crate::Result<Self> {
-        let mut conditions = Vec::with_capacity(config.route.len());
+        let mut routes = Vec::with_capacity(config.route.len());
         for (output_name, condition) in config.route.iter() {
-            let condition = condition.build(&context.enrichment_tables)?;
-            conditions.push((output_name.clone(), condition));
+            routes.push(RouteOutput {
+                name: output_name.clone(),
+                condition: condition.build(&context.enrichment_tables)?,
+                sampler: config.sample.get(output_name).copied().map(Sampler::new),
+                events: config.route_metrics.then(|| {
+                    register!(RouteEvents {
+                        route: output_name.clone(),
+                    })
+                }),
+            });
         }
         Ok(Self {
-            conditions,
+            routes,
             reroute_unmatched: config.reroute_unmatched,
         })
     }
@@ -41,15 +86,20 @@ impl Route {
 impl SyncTransform for Route {
     fn transform(&mut self, event: Event, output: &mut vector_lib::transform::TransformOutputsBuf) {
         let mut check_failed: usize = 0;
-        for (output_name, condition) in &self.conditions {
-            let (result, event) = condition.check(event.clone());
+        for route in &mut self.routes {
+            let (result, event) = route.condition.check(event.clone());
+            if let Some(events) = &route.events {
+                events.emit(result);
+            }
             if result {
-                output.push(Some(output_name), event);
+                if route.sampler.as_mut().is_none_or(Sampler::sample) {
+                    output.push(Some(&route.name), event);
+                }
             } else {
                 check_failed += 1;
             }
         }
-        if self.reroute_unmatched && check_failed == self.conditions.len() {
+        if self.reroute_unmatched && check_failed == self.routes.len() {
             output.push(Some(UNMATCHED_ROUTE), event);
         }
     }
@@ -89,6 +139,27 @@ pub struct RouteConfig {
     #[configurable(metadata(docs::additional_props_description = "An individual route."))]
     #[configurable(metadata(docs::examples = "route_examples()"))]
     route: IndexMap<String, AnyCondition>,
+
+    /// Whether or not to emit the number of events matching and not matching the condition of
+    /// each route.
+    ///
+    /// The counts are emitted as the `route_matched_events_total` and
+    /// `route_unmatched_events_total` internal metrics, tagged with the `route` identifier.
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    route_metrics: bool,
+
+    /// A map from route identifiers to the ratio of their matching events that are forwarded.
+    ///
+    /// For example, `sample.canary = 0.05` forwards 5% of the events matching the `canary` route to
+    /// the `<transform_name>.canary` output. The other events matching the route aren't forwarded
+    /// to it, but they aren't considered as unmatched either. The routes without a ratio forward
+    /// all of their matching events.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    #[configurable(metadata(
+        docs::additional_props_description = "The ratio of the events forwarded by a route."
+    ))]
+    #[configurable(metadata(docs::examples = "sample_examples()"))]
+    sample: IndexMap<String, f64>,
 }
 
 fn route_examples() -> IndexMap<String, AnyCondition> {
@@ -110,11 +181,17 @@ fn route_examples() -> IndexMap<String, AnyCondition> {
     ])
 }
 
+fn sample_examples() -> IndexMap<String, f64> {
+    IndexMap::from([("foo-exists".to_owned(), 0.1)])
+}
+
 impl GenerateConfig for RouteConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             reroute_unmatched: true,
             route: route_examples(),
+            route_metrics: false,
+            sample: IndexMap::new(),
         })
         .unwrap()
     }
@@ -133,12 +210,25 @@ impl TransformConfig for RouteConfig {
     }
 
     fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
         if self.route.contains_key(UNMATCHED_ROUTE) {
-            Err(vec![format!(
+            errors.push(format!(
                 "cannot have a named output with reserved name: `{UNMATCHED_ROUTE}`"
-            )])
-        } else {
+            ));
+        }
+        for (output_name, ratio) in &self.sample {
+            if !self.route.contains_key(output_name) {
+                errors.push(format!("cannot sample the undefined route `{output_name}`"));
+            } else if !(*ratio > 0.0 && *ratio <= 1.0) {
+                errors.push(format!(
+                    "the sample ratio of the route `{output_name}` must be greater than 0 and at most 1, value: {ratio}"
+                ));
+            }
+        }
+        if errors.is_empty() {
             Ok(())
+        } else {
+            Err(errors)
         }
     }
 
@@ -387,6 +477,69 @@ mod test {
         }
     }
 
+    #[test]
+    fn route_samples_matching_events() {
+        let output_names = vec!["first", "second", UNMATCHED_ROUTE];
+        let event = Event::from_json_value(
+            serde_json::json!({"message": "hello world"}),
+            LogNamespace::Legacy,
+        )
+        .unwrap();
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.first.type = "vrl"
+            route.first.source = '.message == "hello world"'
+
+            route.second.type = "vrl"
+            route.second.source = '.message == "hello world"'
+
+            sample.first = 0.25
+        "#,
+        )
+        .unwrap();
+
+        let mut transform = Route::new(&config, &Default::default()).unwrap();
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            output_names
+                .iter()
+                .map(|output_name| {
+                    TransformOutput::new(DataType::all_bits(), HashMap::new())
+                        .with_port(output_name.to_owned())
+                })
+                .collect(),
+            8,
+        );
+
+        for _ in 0..8 {
+            transform.transform(event.clone(), &mut outputs);
+        }
+        assert_eq!(outputs.drain_named("first").count(), 2);
+        assert_eq!(outputs.drain_named("second").count(), 8);
+        assert_eq!(outputs.drain_named(UNMATCHED_ROUTE).count(), 0);
+    }
+
+    #[test]
+    fn route_validates_sample_ratios() {
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.first.type = "vrl"
+            route.first.source = 'true'
+
+            sample.first = 1.5
+            sample.second = 0.5
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.validate(&schema::Definition::any()),
+            Err(vec![
+                "the sample ratio of the route `first` must be greater than 0 and at most 1, value: 1.5".to_owned(),
+                "cannot sample the undefined route `second`".to_owned(),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn route_metrics_with_output_tag() {
         init_test();
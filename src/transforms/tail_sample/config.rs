@@ -0,0 +1,154 @@
+use std::{
+    num::{NonZeroU32, NonZeroUsize},
+    time::Duration,
+};
+
+use serde_with::serde_as;
+use vector_lib::{
+    config::{LogNamespace, clone_input_definitions},
+    configurable::configurable_component,
+};
+
+use super::transform::TailSample;
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    schema,
+    transforms::Transform,
+};
+
+/// Configuration for the `tail_sample` transform.
+#[serde_as]
+#[configurable_component(transform(
+    "tail_sample",
+    "Sample whole traces once all of their spans have been seen."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TailSampleConfig {
+    /// How long to wait for the spans of a trace, in seconds, before deciding whether to keep it.
+    ///
+    /// The wait starts when the first span of the trace is received. A decision is made earlier if
+    /// the root span of the trace, which is the span without a `parent_span_id`, is received.
+    #[serde(default = "default_decision_wait_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Decision Wait"))]
+    pub decision_wait_secs: Duration,
+
+    /// The maximum number of traces to hold in memory while waiting for their spans.
+    ///
+    /// When the limit is reached, a decision is made for the oldest trace to make room for the new
+    /// one. The same number of decisions is remembered, so that spans received after the decision
+    /// for their trace was made are kept or dropped along with the rest of the trace.
+    #[serde(default = "default_num_traces")]
+    pub num_traces: NonZeroUsize,
+
+    /// The policies used to decide whether to keep a trace.
+    ///
+    /// A trace is kept if any of the policies matches it, and dropped otherwise.
+    #[configurable(metadata(docs::examples = "policy_examples()"))]
+    pub policies: Vec<TailSamplePolicy>,
+}
+
+/// A policy used to decide whether to keep a trace.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+#[configurable(metadata(docs::enum_tag_description = "The type of the policy."))]
+pub enum TailSamplePolicy {
+    /// Keeps traces containing a span with an error status.
+    ///
+    /// A span has an error status if its `status.code` is `2`, as defined by OpenTelemetry.
+    StatusCode,
+
+    /// Keeps traces lasting longer than a threshold.
+    ///
+    /// The duration of a trace is the time between the earliest `start_time_unix_nano` and the
+    /// latest `end_time_unix_nano` of its spans.
+    Latency {
+        /// The duration above which traces are kept, in milliseconds.
+        #[configurable(metadata(docs::examples = 500))]
+        threshold_ms: u64,
+    },
+
+    /// Keeps up to a number of traces per second for each service.
+    ///
+    /// The service of a trace is taken from the `service.name` resource attribute of its root span,
+    /// or of its first span if the root span wasn't received. Traces kept by another policy don't
+    /// count towards the limit.
+    RateLimiting {
+        /// The number of traces to keep per second for each service.
+        #[configurable(metadata(docs::examples = 10))]
+        traces_per_second: NonZeroU32,
+    },
+}
+
+const fn default_decision_wait_secs() -> Duration {
+    Duration::from_secs(10)
+}
+
+const fn default_num_traces() -> NonZeroUsize {
+    NonZeroUsize::new(50_000).unwrap()
+}
+
+fn policy_examples() -> Vec<TailSamplePolicy> {
+    vec![
+        TailSamplePolicy::StatusCode,
+        TailSamplePolicy::Latency { threshold_ms: 500 },
+        TailSamplePolicy::RateLimiting {
+            traces_per_second: NonZeroU32::new(10).unwrap(),
+        },
+    ]
+}
+
+impl GenerateConfig for TailSampleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            decision_wait_secs: default_decision_wait_secs(),
+            num_traces: default_num_traces(),
+            policies: policy_examples(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "tail_sample")]
+impl TransformConfig for TailSampleConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.policies.is_empty() {
+            return Err("At least one policy must be configured in `policies`".into());
+        }
+
+        Ok(Transform::event_task(TailSample::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        // The events are not modified, so the definition is passed through as-is
+        vec![TransformOutput::new(
+            DataType::Trace,
+            clone_input_definitions(input_definitions),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TailSampleConfig;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TailSampleConfig>();
+    }
+}
@@ -0,0 +1,392 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use lru::LruCache;
+use vector_lib::{
+    lookup::event_path,
+    stream::expiration_map::{Emitter, map_with_expiration},
+};
+
+use super::config::{TailSampleConfig, TailSamplePolicy};
+use crate::{
+    event::{Event, TraceEvent, Value},
+    internal_events::TailSampleEventsDropped,
+    transforms::TaskTransform,
+};
+
+/// The OpenTelemetry status code of spans that failed.
+const STATUS_CODE_ERROR: i64 = 2;
+
+/// How often traces are checked for having waited long enough for a decision.
+const MAX_FLUSH_PERIOD: Duration = Duration::from_secs(1);
+
+/// The window over which traces are counted by the `rate_limiting` policy.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// The spans of a trace waiting for a sampling decision, along with what's known of the trace so
+/// far.
+struct PendingTrace {
+    spans: Vec<Event>,
+    received_at: Instant,
+    has_error: bool,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    service: Option<String>,
+    has_root: bool,
+}
+
+impl PendingTrace {
+    const fn new(received_at: Instant) -> Self {
+        Self {
+            spans: Vec::new(),
+            received_at,
+            has_error: false,
+            start: None,
+            end: None,
+            service: None,
+            has_root: false,
+        }
+    }
+
+    fn add_span(&mut self, span: Event) {
+        let trace = span.as_trace();
+
+        if let Some(Value::Integer(code)) = trace.get(event_path!("status", "code")) {
+            self.has_error |= *code == STATUS_CODE_ERROR;
+        }
+        if let Some(Value::Timestamp(start)) = trace.get(event_path!("start_time_unix_nano")) {
+            self.start = Some(self.start.map_or(*start, |s| s.min(*start)));
+        }
+        if let Some(Value::Timestamp(end)) = trace.get(event_path!("end_time_unix_nano")) {
+            self.end = Some(self.end.map_or(*end, |e| e.max(*end)));
+        }
+
+        let is_root = is_root_span(trace);
+        if (is_root || self.service.is_none())
+            && let Some(service) = trace.get(event_path!("resources", "service.name"))
+        {
+            self.service = Some(service.to_string_lossy().into_owned());
+        }
+        self.has_root |= is_root;
+
+        self.spans.push(span);
+    }
+
+    fn duration(&self) -> Option<chrono::Duration> {
+        Some(self.end? - self.start?)
+    }
+}
+
+fn is_root_span(trace: &TraceEvent) -> bool {
+    match trace.get(event_path!("parent_span_id")) {
+        None | Some(Value::Null) => true,
+        Some(Value::Bytes(parent)) => parent.is_empty(),
+        Some(_) => false,
+    }
+}
+
+/// The number of traces kept for a service during the current rate limiting window.
+struct RateLimitWindow {
+    started_at: Instant,
+    traces: u32,
+}
+
+pub struct TailSample {
+    decision_wait: Duration,
+    policies: Vec<TailSamplePolicy>,
+    pending: LruCache<String, PendingTrace>,
+    decisions: LruCache<String, bool>,
+    rate_limits: HashMap<(usize, String), RateLimitWindow>,
+}
+
+impl TailSample {
+    pub fn new(config: &TailSampleConfig) -> Self {
+        Self {
+            decision_wait: config.decision_wait_secs,
+            policies: config.policies.clone(),
+            pending: LruCache::new(config.num_traces),
+            decisions: LruCache::new(config.num_traces),
+            rate_limits: HashMap::new(),
+        }
+    }
+
+    fn flush_period(&self) -> Duration {
+        self.decision_wait.min(MAX_FLUSH_PERIOD)
+    }
+
+    fn transform_one_at(&mut self, event: Event, now: Instant, output: &mut Vec<Event>) {
+        let Some(trace_id) = event
+            .as_trace()
+            .get(event_path!("trace_id"))
+            .map(|id| id.to_string_lossy().into_owned())
+        else {
+            // Spans without a trace can't be sampled along with the rest of their trace.
+            output.push(event);
+            return;
+        };
+
+        if let Some(keep) = self.decisions.get(&trace_id) {
+            if *keep {
+                output.push(event);
+            } else {
+                emit!(TailSampleEventsDropped { count: 1 });
+            }
+            return;
+        }
+
+        if !self.pending.contains(&trace_id)
+            && let Some((evicted_id, evicted)) =
+                self.pending.push(trace_id.clone(), PendingTrace::new(now))
+        {
+            // The oldest trace is decided on early to make room for the new one.
+            self.decide(evicted_id, evicted, now, output);
+        }
+
+        let trace = self.pending.peek_mut(&trace_id).expect("trace is pending");
+        trace.add_span(event);
+
+        if trace.has_root {
+            let trace = self.pending.pop(&trace_id).expect("trace is pending");
+            self.decide(trace_id, trace, now, output);
+        }
+    }
+
+    fn flush_expired_at(&mut self, now: Instant, output: &mut Vec<Event>) {
+        // Traces are never promoted in the cache, so the least recently used trace is also the
+        // one that has been waiting the longest.
+        while let Some((_, trace)) = self.pending.peek_lru() {
+            if now.saturating_duration_since(trace.received_at) < self.decision_wait {
+                break;
+            }
+            let (trace_id, trace) = self.pending.pop_lru().expect("trace is pending");
+            self.decide(trace_id, trace, now, output);
+        }
+
+        self.rate_limits.retain(|_, window| {
+            now.saturating_duration_since(window.started_at) < RATE_LIMIT_WINDOW
+        });
+    }
+
+    fn flush_all(&mut self, output: &mut Vec<Event>) {
+        let now = Instant::now();
+        while let Some((trace_id, trace)) = self.pending.pop_lru() {
+            self.decide(trace_id, trace, now, output);
+        }
+    }
+
+    fn decide(
+        &mut self,
+        trace_id: String,
+        trace: PendingTrace,
+        now: Instant,
+        output: &mut Vec<Event>,
+    ) {
+        let keep = self.should_keep(&trace, now);
+        self.decisions.put(trace_id, keep);
+
+        if keep {
+            output.extend(trace.spans);
+        } else {
+            emit!(TailSampleEventsDropped {
+                count: trace.spans.len()
+            });
+        }
+    }
+
+    fn should_keep(&mut self, trace: &PendingTrace, now: Instant) -> bool {
+        let matched = self.policies.iter().any(|policy| match policy {
+            TailSamplePolicy::StatusCode => trace.has_error,
+            TailSamplePolicy::Latency { threshold_ms } => {
+                trace.duration().is_some_and(|duration| {
+                    duration.num_milliseconds() > i64::try_from(*threshold_ms).unwrap_or(i64::MAX)
+                })
+            }
+            TailSamplePolicy::RateLimiting { .. } => false,
+        });
+        if matched {
+            return true;
+        }
+
+        // Rate limits are only checked once no other policy matched, so that traces which would
+        // be kept anyway don't use up the limits.
+        let service = trace.service.clone().unwrap_or_default();
+        for (index, policy) in self.policies.iter().enumerate() {
+            let TailSamplePolicy::RateLimiting { traces_per_second } = policy else {
+                continue;
+            };
+
+            let window =
+                self.rate_limits
+                    .entry((index, service.clone()))
+                    .or_insert(RateLimitWindow {
+                        started_at: now,
+                        traces: 0,
+                    });
+            if now.saturating_duration_since(window.started_at) >= RATE_LIMIT_WINDOW {
+                *window = RateLimitWindow {
+                    started_at: now,
+                    traces: 0,
+                };
+            }
+            if window.traces < traces_per_second.get() {
+                window.traces += 1;
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl TaskTransform<Event> for TailSample {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let flush_period = self.flush_period();
+        Box::pin(map_with_expiration(
+            self,
+            input_rx,
+            flush_period,
+            |me, event, emitter| {
+                emit_all(emitter, |output| {
+                    me.transform_one_at(event, Instant::now(), output)
+                })
+            },
+            |me, emitter| {
+                emit_all(emitter, |output| {
+                    me.flush_expired_at(Instant::now(), output)
+                })
+            },
+            |me, emitter| emit_all(emitter, |output| me.flush_all(output)),
+        ))
+    }
+}
+
+fn emit_all(emitter: &mut Emitter<Event>, f: impl FnOnce(&mut Vec<Event>)) {
+    let mut output = Vec::new();
+    f(&mut output);
+    output.into_iter().for_each(|event| emitter.emit(event));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use chrono::TimeZone;
+    use vector_lib::event::ObjectMap;
+
+    use super::*;
+
+    fn tail_sample(policies: Vec<TailSamplePolicy>) -> TailSample {
+        TailSample::new(&TailSampleConfig {
+            decision_wait_secs: Duration::from_secs(10),
+            num_traces: NonZeroUsize::new(2).unwrap(),
+            policies,
+        })
+    }
+
+    fn span(trace_id: &str, parent_span_id: &str, duration_ms: i64, status_code: i64) -> Event {
+        let mut status = ObjectMap::new();
+        status.insert("code".into(), status_code.into());
+        let mut resources = ObjectMap::new();
+        resources.insert("service.name".into(), "checkout".into());
+        let start = Utc.timestamp_millis_opt(1_700_000_000_000).unwrap();
+
+        let mut trace = TraceEvent::default();
+        trace.insert(event_path!("trace_id"), trace_id);
+        trace.insert(event_path!("parent_span_id"), parent_span_id);
+        trace.insert(event_path!("start_time_unix_nano"), start);
+        trace.insert(
+            event_path!("end_time_unix_nano"),
+            start + chrono::Duration::milliseconds(duration_ms),
+        );
+        trace.insert(event_path!("status"), status);
+        trace.insert(event_path!("resources"), resources);
+        Event::Trace(trace)
+    }
+
+    fn transform(sampler: &mut TailSample, event: Event, now: Instant) -> usize {
+        let mut output = Vec::new();
+        sampler.transform_one_at(event, now, &mut output);
+        output.len()
+    }
+
+    #[test]
+    fn keeps_traces_with_errors() {
+        let mut sampler = tail_sample(vec![TailSamplePolicy::StatusCode]);
+        let now = Instant::now();
+
+        assert_eq!(transform(&mut sampler, span("a", "1", 10, 2), now), 0);
+        assert_eq!(transform(&mut sampler, span("a", "", 10, 0), now), 2);
+
+        assert_eq!(transform(&mut sampler, span("b", "1", 10, 0), now), 0);
+        assert_eq!(transform(&mut sampler, span("b", "", 10, 0), now), 0);
+
+        // Spans received after the decision follow the rest of their trace.
+        assert_eq!(transform(&mut sampler, span("a", "2", 10, 0), now), 1);
+        assert_eq!(transform(&mut sampler, span("b", "2", 10, 0), now), 0);
+    }
+
+    #[test]
+    fn keeps_slow_traces() {
+        let mut sampler = tail_sample(vec![TailSamplePolicy::Latency { threshold_ms: 100 }]);
+        let now = Instant::now();
+
+        assert_eq!(transform(&mut sampler, span("a", "", 50, 0), now), 0);
+        assert_eq!(transform(&mut sampler, span("b", "", 150, 0), now), 1);
+    }
+
+    #[test]
+    fn rate_limits_traces_per_service() {
+        let mut sampler = tail_sample(vec![
+            TailSamplePolicy::StatusCode,
+            TailSamplePolicy::RateLimiting {
+                traces_per_second: NonZeroU32::new(1).unwrap(),
+            },
+        ]);
+        let now = Instant::now();
+
+        assert_eq!(transform(&mut sampler, span("a", "", 10, 0), now), 1);
+        assert_eq!(transform(&mut sampler, span("b", "", 10, 0), now), 0);
+        // Traces kept by another policy aren't limited.
+        assert_eq!(transform(&mut sampler, span("c", "", 10, 2), now), 1);
+
+        let later = now + RATE_LIMIT_WINDOW;
+        assert_eq!(transform(&mut sampler, span("d", "", 10, 0), later), 1);
+    }
+
+    #[test]
+    fn decides_after_waiting() {
+        let mut sampler = tail_sample(vec![TailSamplePolicy::StatusCode]);
+        let now = Instant::now();
+
+        assert_eq!(transform(&mut sampler, span("a", "1", 10, 2), now), 0);
+
+        let mut output = Vec::new();
+        sampler.flush_expired_at(now + Duration::from_secs(5), &mut output);
+        assert!(output.is_empty());
+        sampler.flush_expired_at(now + Duration::from_secs(10), &mut output);
+        assert_eq!(output.len(), 1);
+    }
+
+    #[test]
+    fn decides_when_evicted() {
+        let mut sampler = tail_sample(vec![TailSamplePolicy::StatusCode]);
+        let now = Instant::now();
+
+        assert_eq!(transform(&mut sampler, span("a", "1", 10, 2), now), 0);
+        assert_eq!(transform(&mut sampler, span("b", "1", 10, 0), now), 0);
+        // Only two traces can be pending, so the oldest one is decided on.
+        assert_eq!(transform(&mut sampler, span("c", "1", 10, 0), now), 1);
+    }
+}
@@ -34,6 +34,52 @@ pub struct ThrottleInternalMetricsConfig {
     pub emit_events_discarded_per_key: bool,
 }
 
+/// Configuration of the Redis backend sharing the rate limits between Vector instances.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleRedisConfig {
+    /// The URL of the Redis endpoint to connect to.
+    ///
+    /// The URL _must_ take the form of `protocol://server:port/db` where the protocol can either be
+    /// `redis` or `rediss` for connections secured via TLS.
+    #[configurable(metadata(docs::examples = "redis://127.0.0.1:6379/0"))]
+    pub endpoint: String,
+
+    /// The prefix prepended to the keys of the buckets stored in Redis.
+    ///
+    /// The instances using the same endpoint and prefix share their buckets.
+    #[serde(default = "default_key_prefix")]
+    pub key_prefix: String,
+
+    /// The timeout for connecting to Redis and for each request, in milliseconds.
+    ///
+    /// The events are rate limited one at a time, so this bounds the delay added to each event
+    /// while Redis is slow to respond.
+    #[serde(default = "default_timeout_ms")]
+    #[configurable(metadata(docs::type_unit = "milliseconds"))]
+    pub timeout_ms: u64,
+
+    /// How long the local rate limiter is used after Redis couldn't be reached, in seconds.
+    ///
+    /// Redis is requested again once the interval elapsed.
+    #[serde(default = "default_retry_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub retry_interval_secs: u64,
+}
+
+fn default_key_prefix() -> String {
+    "vector:throttle:".to_owned()
+}
+
+const fn default_timeout_ms() -> u64 {
+    50
+}
+
+const fn default_retry_interval_secs() -> u64 {
+    5
+}
+
 /// Configuration for the `throttle` transform.
 #[serde_as]
 #[configurable_component(transform("throttle", "Rate limit logs passing through a topology."))]
@@ -63,6 +109,15 @@ pub struct ThrottleConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub internal_metrics: ThrottleInternalMetricsConfig,
+
+    /// Enforces the rate limits across all the Vector instances using the same Redis backend.
+    ///
+    /// The buckets are stored in Redis, so that the `threshold` applies to the events of all the
+    /// instances. While Redis can't be reached, each instance falls back to rate limiting its own
+    /// events, and the `threshold` applies to each instance.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis: Option<ThrottleRedisConfig>,
 }
 
 impl_generate_config_from_default!(ThrottleConfig);
@@ -100,4 +155,21 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<ThrottleConfig>();
     }
+
+    #[test]
+    fn parse_redis_config() {
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+redis.endpoint = "redis://127.0.0.1:6379/0"
+"#,
+        )
+        .unwrap();
+
+        let redis = config.redis.unwrap();
+        assert_eq!(redis.key_prefix, "vector:throttle:");
+        assert_eq!(redis.timeout_ms, 50);
+        assert_eq!(redis.retry_interval_secs, 5);
+    }
 }
@@ -1,3 +1,4 @@
 pub mod config;
 pub mod rate_limiter;
+pub mod redis_limiter;
 pub mod transform;
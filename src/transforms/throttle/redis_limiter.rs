@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use redis::{Client, RedisResult, aio::MultiplexedConnection};
+
+use super::config::ThrottleRedisConfig;
+use crate::internal_events::ThrottleRedisUnavailable;
+
+/// A token bucket refilled continuously, holding up to `threshold` tokens and refilling them over
+/// `window`. The time of the Redis server is used so that the clocks of the instances don't
+/// matter, and the bucket expires once it would be full again.
+const TOKEN_BUCKET_SCRIPT: &str = r"
+local threshold = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'updated_at')
+local tokens = tonumber(bucket[1]) or threshold
+local updated_at = tonumber(bucket[2]) or now
+tokens = math.min(threshold, tokens + math.max(0, now - updated_at) * threshold / window)
+local allowed = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated_at', tostring(now))
+redis.call('PEXPIRE', KEYS[1], window)
+return allowed
+";
+
+/// Rate limits keys with token buckets stored in Redis, so that they are shared by all the
+/// instances using the same Redis endpoint and key prefix.
+#[derive(Clone)]
+pub struct RedisRateLimiter {
+    client: Client,
+    /// The connection and the SHA1 digest of the loaded script.
+    connection: Option<(MultiplexedConnection, String)>,
+    key_prefix: String,
+    threshold: u32,
+    window_ms: u64,
+    timeout: Duration,
+    retry_interval: Duration,
+    unavailable_until: Option<Instant>,
+}
+
+impl RedisRateLimiter {
+    pub fn new(
+        config: &ThrottleRedisConfig,
+        threshold: u32,
+        window: Duration,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            client: Client::open(config.endpoint.as_str())?,
+            connection: None,
+            key_prefix: config.key_prefix.clone(),
+            threshold,
+            window_ms: (window.as_millis() as u64).max(1),
+            timeout: Duration::from_millis(config.timeout_ms),
+            retry_interval: Duration::from_secs(config.retry_interval_secs),
+            unavailable_until: None,
+        })
+    }
+
+    /// Takes a token from the bucket of the key, returning whether there was one.
+    ///
+    /// Returns `None` if Redis couldn't be reached, in which case it isn't requested again until
+    /// the retry interval elapsed and the caller is expected to fall back to a local rate limiter.
+    pub async fn check_key(&mut self, key: &Option<String>) -> Option<bool> {
+        if self
+            .unavailable_until
+            .is_some_and(|until| Instant::now() < until)
+        {
+            return None;
+        }
+
+        let error = match tokio::time::timeout(self.timeout, self.take_token(key)).await {
+            Ok(Ok(allowed)) => {
+                self.unavailable_until = None;
+                return Some(allowed);
+            }
+            Ok(Err(error)) => error.to_string(),
+            Err(_) => "Request timed out.".to_owned(),
+        };
+        emit!(ThrottleRedisUnavailable {
+            error,
+            retry_interval: self.retry_interval,
+        });
+        self.connection = None;
+        self.unavailable_until = Some(Instant::now() + self.retry_interval);
+        None
+    }
+
+    async fn take_token(&mut self, key: &Option<String>) -> RedisResult<bool> {
+        if self.connection.is_none() {
+            let mut connection = self.client.get_multiplexed_async_connection().await?;
+            let sha: String = redis::cmd("SCRIPT")
+                .arg("LOAD")
+                .arg(TOKEN_BUCKET_SCRIPT)
+                .query_async(&mut connection)
+                .await?;
+            self.connection = Some((connection, sha));
+        }
+        let (connection, sha) = self.connection.as_mut().expect("connection should be open");
+
+        let allowed: i64 = redis::cmd("EVALSHA")
+            .arg(&*sha)
+            .arg(1)
+            .arg(format!(
+                "{}{}",
+                self.key_prefix,
+                key.as_deref().unwrap_or_default()
+            ))
+            .arg(self.threshold)
+            .arg(self.window_ms)
+            .query_async(connection)
+            .await?;
+        Ok(allowed == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unreachable_redis_falls_back() {
+        let config = ThrottleRedisConfig {
+            endpoint: "redis://127.0.0.1:1".to_owned(),
+            key_prefix: String::new(),
+            timeout_ms: 100,
+            retry_interval_secs: 60,
+        };
+        let mut limiter = RedisRateLimiter::new(&config, 10, Duration::from_secs(1)).unwrap();
+
+        assert_eq!(limiter.check_key(&Some("key".to_owned())).await, None);
+        assert!(limiter.unavailable_until.is_some());
+        assert!(limiter.connection.is_none());
+        // Redis isn't requested again until the retry interval elapsed.
+        assert_eq!(limiter.check_key(&None).await, None);
+    }
+}
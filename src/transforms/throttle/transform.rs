@@ -8,6 +8,7 @@ use snafu::Snafu;
 use super::{
     config::{ThrottleConfig, ThrottleInternalMetricsConfig},
     rate_limiter::RateLimiterRunner,
+    redis_limiter::RedisRateLimiter,
 };
 use crate::{
     conditions::Condition,
@@ -26,6 +27,7 @@ pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
     exclude: Option<Condition>,
     pub clock: C,
     internal_metrics: ThrottleInternalMetricsConfig,
+    redis: Option<RedisRateLimiter>,
 }
 
 impl<C, I> Throttle<C, I>
@@ -56,6 +58,11 @@ where
             .as_ref()
             .map(|condition| condition.build(&context.enrichment_tables))
             .transpose()?;
+        let redis = config
+            .redis
+            .as_ref()
+            .map(|redis| RedisRateLimiter::new(redis, threshold.get(), flush_keys_interval))
+            .transpose()?;
 
         Ok(Self {
             quota,
@@ -64,6 +71,7 @@ where
             key_field: config.key_field.clone(),
             exclude,
             internal_metrics: config.internal_metrics.clone(),
+            redis,
         })
     }
 
@@ -96,6 +104,7 @@ where
         Self: 'static,
     {
         let limiter = self.start_rate_limiter();
+        let mut redis = self.redis.clone();
 
         Box::pin(stream! {
             while let Some(event) = input_rx.next().await {
@@ -119,7 +128,14 @@ where
                             .ok()
                     });
 
-                    if limiter.check_key(&key) {
+                    let allowed = match redis.as_mut() {
+                        Some(redis) => match redis.check_key(&key).await {
+                            Some(allowed) => allowed,
+                            None => limiter.check_key(&key),
+                        },
+                        None => limiter.check_key(&key),
+                    };
+                    if allowed {
                         Some(event)
                     } else {
                         self.emit_event_discarded(key.unwrap_or_else(|| "None".to_string()));
@@ -370,6 +386,7 @@ key_field = "{{ bucket }}"
                 key_field: None,
                 exclude: None,
                 internal_metrics: Default::default(),
+                redis: None,
             };
             let (tx, rx) = mpsc::channel(1);
             let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
@@ -0,0 +1,426 @@
+//! The `wasm` transform runs each log event through a WebAssembly module implementing the
+//! following guest ABI:
+//!
+//! ```text
+//! exports:
+//!   memory                                      the linear memory of the module
+//!   vector_alloc(len: i32) -> i32               allocates `len` bytes, returning their address
+//!   vector_free(ptr: i32, len: i32)             frees a buffer returned by `vector_alloc` or
+//!                                               `vector_process`
+//!   vector_process(ptr: i32, len: i32) -> i64   processes an event, returning `ptr << 32 | len`
+//! ```
+//!
+//! The event is passed to `vector_process` as a JSON object, in a buffer allocated with
+//! `vector_alloc`, and the module returns the events to send downstream as a JSON array of
+//! objects, which can be empty to drop the event. Both buffers are freed by the transform once the
+//! output is read. The module can't import anything, so it must be built for a freestanding
+//! target.
+
+use std::path::PathBuf;
+
+use snafu::Snafu;
+use vector_lib::{
+    config::LogNamespace,
+    configurable::configurable_component,
+    event::{LogEvent, Value},
+};
+use wasmtime::{
+    Engine, Error, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::Event,
+    internal_events::WasmProcessError,
+    schema::{self, Definition},
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// Configuration for the `wasm` transform.
+#[configurable_component(transform("wasm", "Modify event data with a WebAssembly module."))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WasmConfig {
+    /// The path to the WebAssembly module, in the binary or the text format.
+    ///
+    /// If a relative path is provided, its root is the current working directory.
+    #[configurable(metadata(docs::examples = "./transforms/redact.wasm"))]
+    module: PathBuf,
+
+    /// The amount of fuel the module can consume to process each event.
+    ///
+    /// Each WebAssembly instruction executed consumes about one unit of fuel. The event is dropped
+    /// if the module runs out of fuel, so that a module stuck in a loop can't stall the pipeline.
+    #[serde(default = "default_fuel_per_event")]
+    fuel_per_event: u64,
+
+    /// The maximum size of the linear memory of the module, in bytes.
+    ///
+    /// The module fails to allocate past this limit, and can't be loaded if its initial memory is
+    /// larger.
+    #[serde(default = "default_max_memory_bytes")]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_memory_bytes: usize,
+}
+
+const fn default_fuel_per_event() -> u64 {
+    10_000_000
+}
+
+const fn default_max_memory_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
+impl GenerateConfig for WasmConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"module = "./transforms/redact.wasm""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "wasm")]
+impl TransformConfig for WasmConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(Wasm::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        // The module can return any events, which resets the type definition.
+        let namespaces = input_definitions
+            .iter()
+            .flat_map(|(_output, definition)| definition.log_namespaces().clone())
+            .collect();
+
+        let definition = input_definitions
+            .iter()
+            .map(|(output, _definition)| {
+                (
+                    output.clone(),
+                    Definition::default_for_namespace(&namespaces),
+                )
+            })
+            .collect();
+
+        vec![TransformOutput::new(DataType::Log, definition)]
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Could not load WebAssembly module {:?}: {}", path, message))]
+    InvalidModule { path: PathBuf, message: String },
+    #[snafu(display("Could not instantiate WebAssembly module {:?}: {}", path, message))]
+    InstantiationFailed { path: PathBuf, message: String },
+}
+
+/// An instance of the module, with the exports of the guest ABI.
+struct Guest {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    free: TypedFunc<(i32, i32), ()>,
+    process: TypedFunc<(i32, i32), i64>,
+}
+
+impl Guest {
+    fn new(module: &Module, fuel: u64, max_memory_bytes: usize) -> Result<Self, Error> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(max_memory_bytes)
+            .build();
+        let mut store = Store::new(module.engine(), limits);
+        store.limiter(|limits| limits);
+        // The start function of the module is limited as well.
+        store.set_fuel(fuel)?;
+
+        let instance = Instance::new(&mut store, module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| Error::msg("module doesn't export its memory as `memory`"))?;
+        Ok(Self {
+            alloc: instance.get_typed_func(&mut store, "vector_alloc")?,
+            free: instance.get_typed_func(&mut store, "vector_free")?,
+            process: instance.get_typed_func(&mut store, "vector_process")?,
+            store,
+            memory,
+        })
+    }
+
+    /// Processes an event, returning the events to send downstream.
+    fn process(&mut self, fuel: u64, event: &[u8]) -> Result<Vec<Value>, Error> {
+        self.store.set_fuel(fuel)?;
+
+        let len = i32::try_from(event.len())
+            .map_err(|_| Error::msg("event is too large for the module"))?;
+        let ptr = self.alloc.call(&mut self.store, len)?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, event)?;
+        let output = self.process.call(&mut self.store, (ptr, len))?;
+        self.free.call(&mut self.store, (ptr, len))?;
+
+        let (ptr, len) = ((output >> 32) as u32, output as u32);
+        // The events are parsed in place, once their location is checked to be within the memory.
+        let events = (ptr as usize)
+            .checked_add(len as usize)
+            .and_then(|end| self.memory.data(&self.store).get(ptr as usize..end))
+            .ok_or_else(|| Error::msg("module returned events out of its memory"))
+            .map(serde_json::from_slice::<Vec<Value>>)?;
+        self.free.call(&mut self.store, (ptr as i32, len as i32))?;
+
+        let events = events
+            .map_err(|error| Error::msg(format!("module returned invalid events: {error}")))?;
+        if !events.iter().all(Value::is_object) {
+            return Err(Error::msg("module returned events that aren't objects"));
+        }
+        Ok(events)
+    }
+}
+
+pub struct Wasm {
+    module: Module,
+    fuel_per_event: u64,
+    max_memory_bytes: usize,
+    guest: Option<Guest>,
+}
+
+// Each copy of the transform runs its own instance of the module.
+impl Clone for Wasm {
+    fn clone(&self) -> Self {
+        Self {
+            module: self.module.clone(),
+            fuel_per_event: self.fuel_per_event,
+            max_memory_bytes: self.max_memory_bytes,
+            guest: None,
+        }
+    }
+}
+
+impl Wasm {
+    pub fn new(config: &WasmConfig) -> crate::Result<Self> {
+        let path = &config.module;
+        let mut engine_config = wasmtime::Config::new();
+        engine_config.consume_fuel(true);
+        let module = Engine::new(&engine_config)
+            .and_then(|engine| Module::from_file(&engine, path))
+            .map_err(|error| BuildError::InvalidModule {
+                path: path.clone(),
+                message: format!("{error:#}"),
+            })?;
+
+        // Instantiate the module right away, so that a module not implementing the guest ABI, or
+        // exceeding the limits on its own, is reported when the transform is built.
+        let guest = Guest::new(&module, config.fuel_per_event, config.max_memory_bytes).map_err(
+            |error| BuildError::InstantiationFailed {
+                path: path.clone(),
+                message: format!("{error:#}"),
+            },
+        )?;
+
+        Ok(Self {
+            module,
+            fuel_per_event: config.fuel_per_event,
+            max_memory_bytes: config.max_memory_bytes,
+            guest: Some(guest),
+        })
+    }
+
+    fn process(&mut self, event: &[u8]) -> Result<Vec<Value>, Error> {
+        let guest = match self.guest.take() {
+            Some(guest) => guest,
+            None => Guest::new(&self.module, self.fuel_per_event, self.max_memory_bytes)?,
+        };
+        self.guest.insert(guest).process(self.fuel_per_event, event)
+    }
+}
+
+impl FunctionTransform for Wasm {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        let (value, metadata) = event.into_log().into_parts();
+        let result = serde_json::to_vec(&value)
+            .map_err(Error::from)
+            .and_then(|event| self.process(&event));
+        match result {
+            Ok(events) => {
+                for value in events {
+                    output.push(Event::from(LogEvent::from_parts(value, metadata.clone())));
+                }
+            }
+            Err(error) => {
+                emit!(WasmProcessError { error });
+                // The instance can be left in any state by a trap, so the next event is processed
+                // by a new one.
+                self.guest = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use super::*;
+    use crate::{
+        event::LogEvent,
+        test_util::{components::assert_transform_compliance, temp_file},
+        transforms::test::create_topology,
+    };
+
+    /// A module returning each event twice, with a bump allocator.
+    const DUPLICATE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func $alloc (export "vector_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (local.get $ptr) (local.get $len)))
+            (local.get $ptr))
+          (func (export "vector_free") (param i32 i32))
+          (func (export "vector_process") (param $ptr i32) (param $len i32) (result i64)
+            (local $out i32)
+            (local $out_len i32)
+            (local.set $out_len (i32.add (i32.mul (local.get $len) (i32.const 2)) (i32.const 3)))
+            (local.set $out (call $alloc (local.get $out_len)))
+            (i32.store8 (local.get $out) (i32.const 91))
+            (memory.copy
+              (i32.add (local.get $out) (i32.const 1))
+              (local.get $ptr)
+              (local.get $len))
+            (i32.store8
+              (i32.add (local.get $out) (i32.add (local.get $len) (i32.const 1)))
+              (i32.const 44))
+            (memory.copy
+              (i32.add (local.get $out) (i32.add (local.get $len) (i32.const 2)))
+              (local.get $ptr)
+              (local.get $len))
+            (i32.store8
+              (i32.add (local.get $out) (i32.sub (local.get $out_len) (i32.const 1)))
+              (i32.const 93))
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $out)) (i64.const 32))
+              (i64.extend_i32_u (local.get $out_len)))))
+    "#;
+
+    /// A module looping forever on the events with a `loop` field.
+    const LOOP_ON_FIELD: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "[]")
+          (func (export "vector_alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "vector_free") (param i32 i32))
+          (func (export "vector_process") (param $ptr i32) (param $len i32) (result i64)
+            (if (i32.eq (i32.load8_u offset=2 (local.get $ptr)) (i32.const 108))
+              (then (loop $forever (br $forever))))
+            (i64.const 2)))
+    "#;
+
+    /// A module returning events beyond the end of its memory.
+    const OUT_OF_MEMORY: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "vector_alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "vector_free") (param i32 i32))
+          (func (export "vector_process") (param i32 i32) (result i64)
+            (i64.const 0x00000400ffffffff)))
+    "#;
+
+    fn config(module: &str) -> WasmConfig {
+        let path = temp_file().with_extension("wat");
+        fs::write(&path, module).unwrap();
+        WasmConfig {
+            module: path,
+            fuel_per_event: default_fuel_per_event(),
+            max_memory_bytes: default_max_memory_bytes(),
+        }
+    }
+
+    fn transform(wasm: &mut Wasm, event: LogEvent) -> Vec<Event> {
+        let mut output = OutputBuffer::default();
+        wasm.transform(&mut output, Event::from(event));
+        output.into_events().collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WasmConfig>();
+    }
+
+    #[tokio::test]
+    async fn wasm_returns_events_of_module() {
+        assert_transform_compliance(async {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) =
+                create_topology(ReceiverStream::new(rx), config(DUPLICATE)).await;
+
+            let mut log = LogEvent::from("hello");
+            log.insert("count", 1_i64);
+            tx.send(log.into()).await.unwrap();
+
+            for _ in 0..2 {
+                let event = out.recv().await.unwrap().into_log();
+                assert_eq!(event["message"], "hello".into());
+                assert_eq!(event["count"], 1_i64.into());
+            }
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[test]
+    fn wasm_drops_events_out_of_fuel() {
+        let mut wasm = Wasm::new(&config(LOOP_ON_FIELD)).unwrap();
+
+        let mut event = LogEvent::default();
+        event.insert("loop", true);
+        assert!(transform(&mut wasm, event).is_empty());
+        assert!(wasm.guest.is_none());
+
+        // The next event is processed by a new instance.
+        let mut event = LogEvent::default();
+        event.insert("message", "hello");
+        assert!(transform(&mut wasm, event).is_empty());
+        assert!(wasm.guest.is_some());
+    }
+
+    #[test]
+    fn wasm_drops_events_out_of_memory_of_module() {
+        let mut wasm = Wasm::new(&config(OUT_OF_MEMORY)).unwrap();
+        assert!(transform(&mut wasm, LogEvent::from("hello")).is_empty());
+        assert!(wasm.guest.is_none());
+    }
+
+    #[test]
+    fn wasm_rejects_invalid_modules() {
+        let mut limited = config(DUPLICATE);
+        limited.max_memory_bytes = 1024;
+        let error = Wasm::new(&limited).err().unwrap().to_string();
+        assert!(error.contains("Could not instantiate"), "{error}");
+
+        let error = Wasm::new(&config(r#"(module (memory (export "memory") 1))"#))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("vector_alloc"), "{error}");
+
+        let error = Wasm::new(&config("(module")).err().unwrap().to_string();
+        assert!(error.contains("Could not load"), "{error}");
+    }
+}
@@ -51,6 +51,11 @@ pub struct Opts {
     /// Output path for JUnit reports
     #[arg(id = "junit-report", long, value_delimiter(','))]
     junit_report_paths: Option<Vec<PathBuf>>,
+
+    /// Overwrite the snapshots of the test outputs with the events they receive, instead of
+    /// comparing the events to them
+    #[arg(long)]
+    update_snapshots: bool,
 }
 
 impl Opts {
@@ -151,7 +156,7 @@ pub async fn cmd(opts: &Opts, signal_handler: &mut signal::SignalHandler) -> exi
     {
         println!("Running tests");
     }
-    match config::build_unit_tests_main(&paths, signal_handler).await {
+    match config::build_unit_tests_main(&paths, opts.update_snapshots, signal_handler).await {
         Ok(tests) => {
             if tests.is_empty() {
                 #[allow(clippy::print_stdout)]
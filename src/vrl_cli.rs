@@ -0,0 +1,428 @@
+//! The `vector vrl` subcommand, which runs VRL programs in a REPL, or on a corpus of events to
+//! check their outcomes and find the events the programs are slow to handle.
+
+#![allow(missing_docs)]
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use vector_lib::TimeZone;
+use vrl::{
+    compiler::{
+        Function, Program, TargetValue,
+        runtime::{Runtime, Terminate},
+    },
+    value::{Secrets, Value},
+};
+
+use crate::format_vrl_diagnostics;
+
+/// The number of slowest events listed when benchmarking.
+const SLOWEST_EVENTS: usize = 5;
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The VRL program to run. The program `.foo = true`, for example, sets the `foo` field of the
+    /// events to `true`. The REPL is started if neither a program nor an input is set.
+    #[arg(id = "PROGRAM")]
+    program: Option<String>,
+
+    /// The file holding the events to run the program on, as one JSON object per line.
+    ///
+    /// The outcome of the program is printed for each event, followed by statistics on the
+    /// outcomes for the whole file.
+    #[arg(short, long = "input")]
+    input_file: Option<PathBuf>,
+
+    /// The file holding the VRL program to run, instead of `PROGRAM`.
+    #[arg(short, long = "program", conflicts_with("PROGRAM"))]
+    program_file: Option<PathBuf>,
+
+    /// Print the modified event instead of the result of the last expression of the program.
+    #[arg(short = 'o', long)]
+    print_object: bool,
+
+    /// The timezone used to parse dates.
+    #[arg(short = 'z', long)]
+    timezone: Option<String>,
+
+    /// Print the warnings of the compilation of the program.
+    #[arg(long)]
+    print_warnings: bool,
+
+    /// Measure how long the program takes to run on each event of the input, instead of printing
+    /// its outcomes.
+    #[arg(long, requires = "input_file")]
+    bench: bool,
+
+    /// The number of times the program runs on each event when benchmarking.
+    #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+    bench_iterations: u32,
+}
+
+impl Opts {
+    /// Converts the options into those of the VRL CLI, which runs the REPL and the programs without
+    /// an input.
+    fn vrl_opts(&self) -> vrl::cli::Opts {
+        let mut args = vec![OsString::from("vrl")];
+        if let Some(input_file) = &self.input_file {
+            args.extend([OsString::from("--input"), input_file.into()]);
+        }
+        if let Some(program_file) = &self.program_file {
+            args.extend([OsString::from("--program"), program_file.into()]);
+        }
+        if self.print_object {
+            args.push("--print-object".into());
+        }
+        if let Some(timezone) = &self.timezone {
+            args.extend([OsString::from("--timezone"), timezone.into()]);
+        }
+        if self.print_warnings {
+            args.push("--print-warnings".into());
+        }
+        if let Some(program) = &self.program {
+            args.extend([OsString::from("--"), program.into()]);
+        }
+        vrl::cli::Opts::parse_from(args)
+    }
+}
+
+/// Statistics on the outcomes of a program run on a corpus of events.
+#[derive(Debug, Default)]
+struct CorpusStats {
+    succeeded: usize,
+    aborted: usize,
+    failed: usize,
+    invalid: usize,
+    /// The number of events by error, for the events the program aborted or failed on.
+    errors: HashMap<String, usize>,
+    /// The mean time the program takes to run on each event, with the line of the event, when
+    /// benchmarking.
+    timings: Vec<(usize, Duration)>,
+}
+
+impl CorpusStats {
+    const fn events(&self) -> usize {
+        self.succeeded + self.aborted + self.failed + self.invalid
+    }
+
+    fn report(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "Ran the program on {} events: {} succeeded, {} aborted, {} failed, {} invalid.",
+            self.events(),
+            self.succeeded,
+            self.aborted,
+            self.failed,
+            self.invalid
+        )?;
+
+        if !self.errors.is_empty() {
+            let mut errors = self.errors.iter().collect::<Vec<_>>();
+            errors.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+            writeln!(out, "Errors:")?;
+            for (error, count) in errors {
+                writeln!(out, "  {error} ({count} events)")?;
+            }
+        }
+
+        if !self.timings.is_empty() {
+            let mut timings = self.timings.clone();
+            timings.sort_by_key(|(_, duration)| *duration);
+            let percentile = |percentile: f64| {
+                let rank = ((timings.len() - 1) as f64 * percentile).round() as usize;
+                timings[rank].1
+            };
+            let total = timings
+                .iter()
+                .map(|(_, duration)| *duration)
+                .sum::<Duration>();
+            writeln!(
+                out,
+                "Time per event: mean {:?}, p50 {:?}, p90 {:?}, p99 {:?}, max {:?}.",
+                total / timings.len() as u32,
+                percentile(0.5),
+                percentile(0.9),
+                percentile(0.99),
+                percentile(1.0)
+            )?;
+            writeln!(out, "Slowest events:")?;
+            for (line, duration) in timings.iter().rev().take(SLOWEST_EVENTS) {
+                writeln!(out, "  line {line}: {duration:?}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of a program run on an event.
+enum Outcome {
+    Succeeded(Value),
+    Aborted(String),
+    Failed(String),
+}
+
+struct CorpusRunner<'a> {
+    program: &'a Program,
+    timezone: TimeZone,
+    print_object: bool,
+    /// The number of times the program runs on each event to measure how long it takes, if
+    /// benchmarking.
+    bench_iterations: Option<u32>,
+    runtime: Runtime,
+}
+
+impl CorpusRunner<'_> {
+    /// Runs the program on each event of the corpus, writing the outcomes to `out`, or to `err` for
+    /// the events the program didn't succeed on, unless benchmarking.
+    fn run(
+        &mut self,
+        events: impl BufRead,
+        mut out: impl Write,
+        mut err: impl Write,
+    ) -> io::Result<CorpusStats> {
+        let mut stats = CorpusStats::default();
+        for (index, line) in events.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = match serde_json::from_str::<Value>(&line) {
+                Ok(event) => event,
+                Err(error) => {
+                    stats.invalid += 1;
+                    writeln!(err, "line {line_number}: invalid event: {error}")?;
+                    continue;
+                }
+            };
+
+            if let Some(iterations) = self.bench_iterations {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iterations {
+                    let event = event.clone();
+                    let start = Instant::now();
+                    _ = self.resolve(event);
+                    elapsed += start.elapsed();
+                }
+                stats.timings.push((line_number, elapsed / iterations));
+            }
+
+            match self.resolve(event) {
+                Outcome::Succeeded(value) => {
+                    stats.succeeded += 1;
+                    if self.bench_iterations.is_none() {
+                        let value = serde_json::to_string(&value)
+                            .unwrap_or_else(|error| format!("unserializable value: {error}"));
+                        writeln!(out, "{value}")?;
+                    }
+                }
+                Outcome::Aborted(error) => {
+                    stats.aborted += 1;
+                    if self.bench_iterations.is_none() {
+                        writeln!(err, "line {line_number}: aborted: {error}")?;
+                    }
+                    *stats.errors.entry(error).or_default() += 1;
+                }
+                Outcome::Failed(error) => {
+                    stats.failed += 1;
+                    if self.bench_iterations.is_none() {
+                        writeln!(err, "line {line_number}: failed: {error}")?;
+                    }
+                    *stats.errors.entry(error).or_default() += 1;
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    fn resolve(&mut self, event: Value) -> Outcome {
+        let mut target = TargetValue {
+            value: event,
+            metadata: Value::Object(Default::default()),
+            secrets: Secrets::default(),
+        };
+        let result = self
+            .runtime
+            .resolve(&mut target, self.program, &self.timezone);
+        self.runtime.clear();
+
+        match result {
+            Ok(_) if self.print_object => Outcome::Succeeded(target.value),
+            Ok(value) => Outcome::Succeeded(value),
+            Err(Terminate::Abort(error)) => Outcome::Aborted(error.to_string()),
+            Err(Terminate::Error(error)) => Outcome::Failed(error.to_string()),
+        }
+    }
+}
+
+/// Function used by the `vector vrl` subcommand, which runs the program on the events of the input
+/// if both are set, and otherwise hands over to the VRL CLI, which starts the REPL without program.
+pub fn cmd(opts: &Opts, functions: Vec<Box<dyn Function>>) -> exitcode::ExitCode {
+    let has_program = opts.program.is_some() || opts.program_file.is_some();
+    let Some(input_file) = opts
+        .input_file
+        .as_ref()
+        .filter(|_| has_program || opts.bench)
+    else {
+        return vrl::cli::cmd::cmd(&opts.vrl_opts(), functions);
+    };
+
+    #[allow(clippy::print_stderr)]
+    let error = |message: String| {
+        eprintln!("{message}");
+    };
+
+    let source = match (&opts.program, &opts.program_file) {
+        (Some(program), _) => program.clone(),
+        (None, Some(path)) => match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                error(format!("Could not read program file {path:?}: {err}"));
+                return exitcode::NOINPUT;
+            }
+        },
+        (None, None) => {
+            error("A program must be set to benchmark it.".to_owned());
+            return exitcode::USAGE;
+        }
+    };
+    let timezone = match opts.timezone.as_deref() {
+        None => TimeZone::default(),
+        Some(timezone) => match TimeZone::parse(timezone) {
+            Some(timezone) => timezone,
+            None => {
+                error(format!("Invalid timezone {timezone:?}."));
+                return exitcode::USAGE;
+            }
+        },
+    };
+
+    let program = match vrl::compiler::compile(&source, &functions) {
+        Ok(result) => {
+            if opts.print_warnings && !result.warnings.is_empty() {
+                error(format_vrl_diagnostics(&source, result.warnings));
+            }
+            result.program
+        }
+        Err(diagnostics) => {
+            error(format_vrl_diagnostics(&source, diagnostics));
+            return exitcode::DATAERR;
+        }
+    };
+
+    let events = match File::open(input_file) {
+        Ok(file) => BufReader::new(file),
+        Err(err) => {
+            error(format!("Could not open input file {input_file:?}: {err}"));
+            return exitcode::NOINPUT;
+        }
+    };
+    let mut runner = CorpusRunner {
+        program: &program,
+        timezone,
+        print_object: opts.print_object,
+        bench_iterations: opts.bench.then_some(opts.bench_iterations),
+        runtime: Runtime::default(),
+    };
+    let result = runner
+        .run(events, io::stdout().lock(), io::stderr().lock())
+        .and_then(|stats| stats.report(io::stderr().lock()));
+
+    match result {
+        Ok(()) => exitcode::OK,
+        Err(err) => {
+            error(format!("Could not run the program on the input: {err}"));
+            exitcode::IOERR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, events: &str, bench_iterations: Option<u32>) -> (CorpusStats, String) {
+        let program = vrl::compiler::compile(source, &vrl::stdlib::all())
+            .unwrap()
+            .program;
+        let mut runner = CorpusRunner {
+            program: &program,
+            timezone: TimeZone::default(),
+            print_object: true,
+            bench_iterations,
+            runtime: Runtime::default(),
+        };
+        let mut out = Vec::new();
+        let stats = runner.run(events.as_bytes(), &mut out, io::sink()).unwrap();
+        (stats, String::from_utf8(out).unwrap())
+    }
+
+    const SOURCE: &str = r#"
+        if .drop == true {
+            abort
+        }
+        .count = int!(.count) + 1
+    "#;
+
+    const EVENTS: &str = r#"{"count": 1}
+        {"count": "nope"}
+        {"drop": true}
+
+        not json
+        {"count": 2}
+    "#;
+
+    #[test]
+    fn runs_program_on_corpus() {
+        let (stats, out) = run(SOURCE, EVENTS, None);
+
+        assert_eq!(out, "{\"count\":2}\n{\"count\":3}\n");
+        assert_eq!(
+            (stats.succeeded, stats.aborted, stats.failed, stats.invalid),
+            (2, 1, 1, 1)
+        );
+        assert_eq!(stats.errors.len(), 2);
+        assert!(stats.timings.is_empty());
+
+        let mut report = Vec::new();
+        stats.report(&mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(
+            report.starts_with(
+                "Ran the program on 5 events: 2 succeeded, 1 aborted, 1 failed, 1 invalid."
+            ),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn benchmarks_program_on_corpus() {
+        let (stats, out) = run(SOURCE, EVENTS, Some(3));
+
+        assert!(out.is_empty());
+        assert_eq!(
+            stats
+                .timings
+                .iter()
+                .map(|(line, _)| *line)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 6]
+        );
+
+        let mut report = Vec::new();
+        stats.report(&mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.contains("Time per event: mean "), "{report}");
+        assert!(report.contains("Slowest events:"), "{report}");
+    }
+}
@@ -20,6 +20,56 @@ api: {
 	configuration:   generated.api.configuration.api
 
 	endpoints: {
+		"/config/reload": {
+			POST: {
+				description: """
+					Validates the configuration sent in the request body and, if it's
+					valid, reloads Vector with it. The configuration format is taken
+					from the `Content-Type` header, which can be one of
+					`application/toml`, `application/yaml` or `application/json`, and
+					defaults to TOML. Requests sent by web browsers, which carry an
+					`Origin` header, are rejected. Only available if `config_reload`
+					is enabled.
+					"""
+				responses: {
+					"202": {
+						description: """
+							The configuration is valid and is being applied. The
+							response body lists any warnings.
+							"""
+					}
+					"422": {
+						description: """
+							The configuration is invalid. The `errors` key of the
+							response body lists the errors.
+							"""
+					}
+				}
+			}
+		}
+		"/config/validate": {
+			POST: {
+				description: """
+					Validates the configuration sent in the request body, in the
+					same way as `/config/reload`, without applying it. Only available
+					if `config_reload` is enabled.
+					"""
+				responses: {
+					"200": {
+						description: """
+							The configuration is valid. The response body lists
+							any warnings.
+							"""
+					}
+					"422": {
+						description: """
+							The configuration is invalid. The `errors` key of the
+							response body lists the errors.
+							"""
+					}
+				}
+			}
+		}
 		"/graphql": {
 			POST: {
 				description: """
@@ -52,6 +102,40 @@ api: {
 				}
 			}
 		}
+		"/internal_log_rate_limit": {
+			GET: {
+				description: """
+					Returns the rate limits of the internal logs currently in
+					effect, in the format of the `internal_log_rate_limit` global
+					option.
+					"""
+				responses: {
+					"200": {
+						description: "The rate limits of the internal logs."
+					}
+				}
+			}
+			PUT: {
+				description: """
+					Replaces the rate limits of the internal logs with the JSON
+					object sent in the request body, in the format of the
+					`internal_log_rate_limit` global option, without restarting
+					Vector. The rate limits are in effect until they're changed
+					again, by this endpoint or by reloading a configuration
+					changing them. Requests sent by web browsers, which carry an
+					`Origin` header, are rejected. Only available if
+					`config_reload` is enabled.
+					"""
+				responses: {
+					"200": {
+						description: "The rate limits have been replaced."
+					}
+					"400": {
+						description: "The request body isn't valid rate limits."
+					}
+				}
+			}
+		}
 		"/playground": {
 			GET: {
 				description: """
@@ -204,6 +204,81 @@ cli: {
 	options: _core_options
 
 	commands: {
+		"buffer": {
+			description: """
+				Inspect and manage the disk buffers of the sinks of a Vector configuration. The following
+				subcommands are available:
+
+				* `ls`: list the disk buffers, along with the number of events they hold and their disk usage.
+				* `inspect <sink>`: show the state of the disk buffer of a sink. The `--head` and `--tail`
+				  options print the given number of the oldest or newest buffered events as JSON. Buffers
+				  can be inspected while Vector is running.
+				* `compact`: delete the data files which no longer hold any unacknowledged events, to reclaim
+				  disk space. Vector must not be running with the given configuration.
+				"""
+
+			example: "vector buffer inspect --config /etc/vector/vector.yaml my_sink --head 10"
+
+			args: {
+				subcommand: {
+					description: "The buffer operation to run: `ls`, `inspect`, or `compact`"
+					type:        "string"
+					required:    true
+				}
+			}
+
+			options: _core_config_options
+		}
+		"checkpoints": {
+			description: """
+				Export and import the file checkpoints of the `file` and `kubernetes_logs` sources, to
+				migrate Vector to another host or data directory without reading files again or skipping
+				them. The following subcommands are available:
+
+				* `export <source>`: print the checkpoints of a source as JSON, or write them to the file
+				  given with `--output`. Checkpoints can be exported while Vector is running.
+				* `import <source> <file>`: merge exported checkpoints into those of a source, keeping the
+				  most recently updated checkpoint of each file. Vector must not be running with the given
+				  configuration. Only checkpoints of files fingerprinted by checksum can be used on another
+				  host.
+				"""
+
+			example: "vector checkpoints export --config /etc/vector/vector.yaml my_file_source --output checkpoints.json"
+
+			args: {
+				subcommand: {
+					description: "The checkpoints operation to run: `export` or `import`"
+					type:        "string"
+					required:    true
+				}
+			}
+
+			options: _core_config_options
+		}
+		"config": {
+			description: """
+				Output a Vector configuration as a single JSON object. With the `schema` subcommand, output
+				the [JSON Schema](https://json-schema.org/) of the configuration instead, covering all the sources,
+				transforms, and sinks built into the Vector binary, to validate configurations with external
+				tools without running Vector. With the `render` subcommand, output the configuration as
+				loaded by Vector instead, with the environment variables interpolated, the secrets resolved,
+				and the configuration files merged, in the format set with `--format` (`json`, `yaml`, or
+				`toml`). The secrets, the values of the options and headers holding credentials, and the
+				credentials of URIs are masked.
+				"""
+
+			example: "vector config render --format yaml --config /etc/vector/vector.yaml"
+
+			args: {
+				subcommand: {
+					description: "`schema` to output the JSON Schema of the configuration, or `render` to output the loaded configuration"
+					type:        "string"
+					required:    false
+				}
+			}
+
+			options: _core_config_options
+		}
 		"graph": {
 			description: """
 				Generate a visual representation of topologies. The output is in the [DOT format](\(urls.dot_format)),
@@ -246,6 +321,80 @@ cli: {
 			description: "Prints this message or the help of the given subcommand(s)"
 		}
 
+		"kafka": {
+			description: """
+				Manage the Kafka consumer groups used by the `kafka` source. The following subcommands are
+				available:
+
+				* `seek`: reset the committed offsets of a consumer group for the partitions of a topic, to
+				  the earliest or latest offsets, the first messages at or after a timestamp, or an absolute
+				  offset. All the consumers of the group must be stopped. The new offsets are printed, and
+				  only committed if `--dry-run` isn't set.
+				"""
+
+			example: "vector kafka seek --bootstrap-servers localhost:9092 --group-id vector --topic logs --to-timestamp 1735689600000"
+
+			flags: _default_flags & {
+				"dry-run": {
+					description: "Print the new offsets without committing them"
+				}
+				"to-earliest": {
+					description: "Reset the offsets to the earliest offsets retained in the partitions"
+				}
+				"to-latest": {
+					description: "Reset the offsets to the ends of the partitions"
+				}
+			}
+
+			options: {
+				"bootstrap-servers": {
+					description: "A comma-separated list of Kafka bootstrap servers, in the form of `host:port`"
+					type:        "string"
+					required:    true
+				}
+				"group-id": {
+					description: "The consumer group to reset the offsets of"
+					type:        "string"
+					required:    true
+				}
+				"librdkafka-option": {
+					_short:      "X"
+					description: "An option set directly on the underlying librdkafka client, in the form of `key=value`. Can be repeated, for example to configure authentication"
+					type:        "string"
+				}
+				"partitions": {
+					description: "The partitions to reset the offsets of (comma-separated). All the partitions of the topic by default"
+					type:        "list"
+				}
+				"timeout-ms": {
+					description: "Timeout for requests to the Kafka brokers, in milliseconds"
+					type:        "integer"
+					default:     10000
+				}
+				"to-offset": {
+					description: "Reset the offsets to the given absolute offset"
+					type:        "integer"
+				}
+				"to-timestamp": {
+					description: "Reset the offsets to the first messages with a timestamp equal to or later than the given one, in milliseconds since the Unix epoch"
+					type:        "integer"
+				}
+				"topic": {
+					description: "The topic to reset the offsets of"
+					type:        "string"
+					required:    true
+				}
+			}
+
+			args: {
+				subcommand: {
+					description: "The Kafka operation to run: `seek`"
+					type:        "string"
+					required:    true
+				}
+			}
+		}
+
 		"list": {
 			description: "List available components, then exit"
 
@@ -271,6 +420,12 @@ cli: {
 				out the [unit testing documentation](\(urls.vector_unit_tests)).
 				"""
 
+			flags: _default_flags & {
+				"update-snapshots": {
+					description: "Overwrite the snapshots of the test outputs with the events they receive, instead of comparing the events to them"
+				}
+			}
+
 			options: {
 				"config-toml": {
 					description: env_vars.VECTOR_CONFIG_TOML.description
@@ -362,6 +517,17 @@ cli: {
 					description: "Components (sources, transforms) to observe for their inputs (comma-separated; accepts glob patterns)"
 					type:        "list"
 				}
+				"trace-sample-rate": {
+					description: """
+						Trace one in every N of the events sent by the sources, and output only
+						the traced events, grouped by trace. The events derived from a traced
+						event keep its trace ID, which is included in the metadata of the output,
+						so that the transforms it went through and what each of them emitted
+						can be followed. An event missing from the outputs of a transform was
+						dropped by it.
+						"""
+					type: "integer"
+				}
 			}
 
 			args: {
@@ -382,7 +548,9 @@ cli: {
 		"top": {
 			description: """
 				Display topology and metrics in the console, for a local or remote Vector
-				instance
+				instance. Alongside their throughput, the components show their errors by
+				type, their discarded events and the utilization of their buffers, and the
+				component that's the bottleneck of the backpressure is highlighted.
 				"""
 
 			flags: _default_flags & {
@@ -490,17 +658,35 @@ cli: {
 						The same result can be achieved by using `.` as the final expression.
 						"""
 				}
+				"bench": {
+					description: """
+						Measure how long the program takes to run on each event of the `input`
+						file, instead of printing its outcomes. The mean and percentiles of the
+						time per event are printed along with the slowest events.
+						"""
+				}
 			}
 
 			options: {
 				"input": {
 					_short: "i"
 					description: """
-						File containing the object(s) to manipulate. Leave empty to use stdin.
+						File containing the object(s) to manipulate, as one JSON object per line.
+						Leave empty to use stdin.
+
+						The outcome of the program is printed for each object, followed by the
+						number of objects the program succeeded, aborted, or failed on, and the
+						errors it failed with.
 						"""
 					type: "string"
 				}
 
+				"bench-iterations": {
+					description: "The number of times the program runs on each object when benchmarking."
+					type:        "integer"
+					default:     1000
+				}
+
 				"program": {
 					_short: "p"
 					description: """
@@ -1,6 +1,20 @@
 package metadata
 
 generated: components: sinks: configuration: {
+	acknowledgement_timeout_secs: {
+		description: """
+			The time after which the events that the sink holds without acknowledging them are
+			reported as stuck.
+
+			A warning is logged for each batch of events that the sink holds for longer, with the
+			number of events of the batch and how long the sink has held them, and the age of the
+			oldest event that the sink holds is reported by the
+			`oldest_unacknowledged_event_age_seconds` gauge. The events are acknowledged once the sink
+			delivered them or failed to, whether or not `acknowledgements` are enabled.
+			"""
+		required: false
+		type: uint: unit: "seconds"
+	}
 	buffer: {
 		description: """
 			Configures the buffering behavior for this sink.
@@ -12,6 +26,41 @@ generated: components: sinks: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			encryption: {
+				description: """
+					Encryption of the buffered events at rest.
+
+					The events are encrypted with AES-256-GCM before they're written to disk.
+					"""
+				relevant_when: "type = \"disk\""
+				required:      false
+				type: object: options: {
+					key: {
+						description: """
+							The key used to encrypt the buffered events.
+
+							Must be a base64-encoded, 32-byte key. The key can be sourced from a secrets backend with
+							the `SECRET[<backend>.<secret_key>]` syntax.
+							"""
+						required: true
+						type: string: examples: ["SECRET[vault.disk_buffer_key]"]
+					}
+					previous_keys: {
+						description: """
+							The keys previously used to encrypt the buffered events.
+
+							When the key is rotated, the events already buffered remain readable as long as the key they
+							were encrypted with is listed here, while the new events are encrypted with the current key.
+							A previous key can be removed once the buffer no longer holds events encrypted with it.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: examples: ["SECRET[vault.previous_disk_buffer_key]"]
+						}
+					}
+				}
+			}
 			max_events: {
 				description:   "The maximum number of events allowed in the buffer."
 				relevant_when: "type = \"memory\""
@@ -70,11 +119,36 @@ generated: components: sinks: configuration: {
 														highest priority, and it is preferable to temporarily lose events rather than cause a
 														slowdown in the acceptance/consumption of events.
 														"""
+						overflow: """
+														Overflows to the next stage in the buffer topology.
+
+														If the current buffer stage is full, attempt to send this event to the next buffer stage.
+														That stage may also be configured overflow, and so on, but ultimately the last stage in a
+														buffer topology must use one of the other handling behaviors. This means that next stage may
+														potentially be able to buffer the event, but it may also block or drop the event.
+
+														This mode can only be used when two or more buffer stages are configured.
+														"""
 					}
 				}
 			}
 		}
 	}
+	dead_letter: {
+		description: """
+			Whether or not to send the events that the sink fails to deliver to the `dropped` output.
+
+			The events that the sink rejects, or still fails to send once its retries are exhausted,
+			are sent to the `<sink_id>.dropped` output, which other components can use as an input.
+			Like for the events rerouted by the `remap` transform, the reason of the failure, the sink
+			and the time of the failure are added to the `dropped` field of the metadata of the events,
+			along with the error of the request and the status code of its response, if there is one.
+
+			The events sent to the output are considered delivered by the sink.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	graph: {
 		description: """
 			Extra graph configuration
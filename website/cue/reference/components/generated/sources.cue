@@ -88,4 +88,27 @@ generated: components: sources: configuration: {
 			}
 		}
 	}
+	wal: {
+		description: """
+			Write-ahead log configuration.
+
+			The events received by the source are persisted to a log on disk before they're acknowledged to
+			the client, and are replayed from the log on restart until they're delivered. This provides
+			durability for the sources whose clients can't send their events again, even when the sinks use
+			memory buffers.
+
+			When the log is full, the source stops accepting events until the events of the log are
+			delivered.
+			"""
+		required: false
+		type: object: options: max_size: {
+			description: """
+				The maximum size of the write-ahead log of each output of the source.
+
+				Must be at least ~256 megabytes (268435488 bytes).
+				"""
+			required: true
+			type: uint: unit: "bytes"
+		}
+	}
 }
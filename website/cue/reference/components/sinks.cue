@@ -662,10 +662,12 @@ components: sinks: [Name=string]: {
 
 	telemetry: metrics: {
 		buffer_byte_size:                     components.sources.internal_metrics.output.metrics.buffer_byte_size
+		buffer_compactions_total:             components.sources.internal_metrics.output.metrics.buffer_compactions_total
 		buffer_discarded_events_total:        components.sources.internal_metrics.output.metrics.buffer_discarded_events_total
 		buffer_events:                        components.sources.internal_metrics.output.metrics.buffer_events
 		buffer_received_events_total:         components.sources.internal_metrics.output.metrics.buffer_received_events_total
 		buffer_received_event_bytes_total:    components.sources.internal_metrics.output.metrics.buffer_received_event_bytes_total
+		buffer_reclaimed_bytes_total:         components.sources.internal_metrics.output.metrics.buffer_reclaimed_bytes_total
 		buffer_sent_events_total:             components.sources.internal_metrics.output.metrics.buffer_sent_events_total
 		buffer_sent_event_bytes_total:        components.sources.internal_metrics.output.metrics.buffer_sent_event_bytes_total
 		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
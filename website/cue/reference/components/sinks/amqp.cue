@@ -36,7 +36,10 @@ components: sinks: amqp: {
 					enum: ["json", "text"]
 				}
 			}
-			request: enabled: false
+			request: {
+				enabled: true
+				headers: false
+			}
 			tls: {
 				enabled:                true
 				can_verify_certificate: false
@@ -58,5 +61,17 @@ components: sinks: amqp: {
 		traces:  false
 	}
 
-	how_it_works: components._amqp.how_it_works
+	how_it_works: components._amqp.how_it_works & {
+		publisher_confirms: {
+			title: "Publisher confirms"
+			body:  """
+				The messages are published with [publisher confirms](\(urls.rabbitmq_publisher_confirms)).
+				The messages negatively acknowledged by the broker are published again, following the
+				`request` retry settings, and their events are acknowledged once the broker confirms them.
+
+				With `mandatory` set, the messages that can't be routed to any queue are returned by the
+				broker, and their events are rejected instead of being silently dropped.
+				"""
+		}
+	}
 }
@@ -192,6 +192,24 @@ components: sinks: aws_s3: components._aws & {
 				"""
 		}
 
+		parquet: {
+			title: "Parquet objects"
+			body:  """
+				Setting `batch_encoding.codec` to `parquet` writes each batch of events as an
+				Apache Parquet file, which can be queried in place by Athena. The schema of the
+				files is inferred from the events of each batch, unless one is given in
+				`batch_encoding.parquet.schema`.
+
+				To query the objects as a partitioned table, use a Hive-style `key_prefix`, such
+				as `year=%Y/month=%m/day=%d/`, and list the partition columns in
+				`encoding.except_fields` if they shouldn't also be written to the files.
+
+				The objects can also be committed to an existing Delta Lake table as they're
+				written, with `table_format.type` set to `delta_lake`. Each object is then
+				added with its own commit, so larger batches make for a smaller transaction log.
+				"""
+		}
+
 		server_side_encryption: {
 			title: "Server-Side Encryption (SSE)"
 			body:  """
@@ -80,4 +80,25 @@ components: sinks: clickhouse: {
 		metrics: null
 		traces:  false
 	}
+
+	how_it_works: {
+		native_protocol: {
+			title: "Native protocol"
+			body: """
+				With `protocol` set to `native`, events are sent over the ClickHouse native TCP protocol
+				instead of the HTTP interface. For each batch, the sink asks the server for the columns of
+				the table, encodes the events into a columnar block and compresses it with LZ4, unless
+				`compression` is set to `none`.
+
+				Event fields are matched to columns by name, and fields without a matching column are
+				discarded. Supported column types are integers, floats, `Bool`, `String`, `FixedString`,
+				`Date`, `Date32`, `DateTime`, `DateTime64`, `UUID`, and `Nullable`, `Array` and
+				`LowCardinality` of those.
+
+				Connections are kept open and reused across requests. When
+				`insert_deduplication_token` is enabled, each request carries a token that stays the same
+				across its retries, so that ClickHouse can discard data it already inserted.
+				"""
+		}
+	}
 }
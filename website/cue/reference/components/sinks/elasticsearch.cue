@@ -145,6 +145,13 @@ components: sinks: elasticsearch: {
 
 				By default, partial failures are not retried. To enable retries, set `request_retry_partial`. Once enabled it will
 				retry whole partially failed requests. As such it is advised to use `id_key` to avoid duplicates.
+
+				The documents that Elasticsearch rejects, such as the ones failing with a
+				`mapper_parsing_exception`, can instead be sent to another sink by setting
+				`reroute_rejected` to `true`. For a sink named `foo`, they are emitted as logs by the
+				`foo.rejected` output, which other components can use as an input, with the reason of
+				the rejection in the `dropped` field of their metadata. The requests whose only failures
+				are rerouted documents are then considered as delivered.
 				"""
 		}
 
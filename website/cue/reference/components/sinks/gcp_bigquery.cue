@@ -0,0 +1,129 @@
+package metadata
+
+components: sinks: gcp_bigquery: {
+	title: "GCP BigQuery"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+		stateful: false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    9_500_000
+				max_events:   10_000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: false
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+				enabled_by_scheme:      true
+			}
+			to: {
+				service: services.gcp_bigquery
+
+				interface: {
+					socket: {
+						api: {
+							title: "BigQuery Storage Write API"
+							url:   urls.gcp_bigquery_storage_write_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The table must already exist, as the events are encoded according to its schema, which
+				is read when the sink starts.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.sinks.gcp_bigquery.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		schema_mapping: {
+			title: "Schema mapping"
+			body: """
+				The events are written as rows of the table, whose schema is read when the sink starts.
+				Each top-level field of an event is written to the column with the same name once
+				sanitized: the name is lowercased, the characters other than ASCII letters, digits, and
+				underscores are replaced by underscores, and an underscore is prepended to names
+				starting with a digit. For example, the `Status-Code` field is written to the
+				`status_code` column. The fields without a column are ignored, and the columns without
+				a field are left null.
+
+				The values are converted to the types of the columns: timestamps and RFC 3339 strings
+				for `TIMESTAMP` columns, objects for `STRUCT` columns, arrays for `REPEATED` columns,
+				and so on. The events missing a `REQUIRED` column or with a value that can't be
+				converted are dropped.
+				"""
+		}
+
+		exactly_once: {
+			title: "Exactly-once retries"
+			body: """
+				The rows are appended to a committed write stream, at the offset following the rows of
+				the previous requests, so the requests are sent one at a time. When a request is
+				retried after its response was lost, BigQuery rejects its rows if they were already
+				appended instead of writing them twice. The events buffered while Vector restarts are
+				still delivered at least once.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "bigquery"
+
+			policies: [
+				{
+					_action: "tables.get"
+					required_for: ["healthcheck", "operation"]
+				},
+				{
+					_action: "tables.updateData"
+					required_for: ["operation"]
+				},
+			]
+		},
+	]
+}
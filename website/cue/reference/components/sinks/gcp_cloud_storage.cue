@@ -141,6 +141,19 @@ components: sinks: gcp_cloud_storage: {
 				"""
 		}
 
+		parquet: {
+			title: "Parquet objects"
+			body:  """
+				Setting `batch_encoding.codec` to `parquet` writes each batch of events as an
+				Apache Parquet file, which can be queried in place as a BigQuery external table.
+				The schema of the files is inferred from the events of each batch, unless one is
+				given in `batch_encoding.parquet.schema`.
+
+				To query the objects as a partitioned table, use a Hive-style `key_prefix`, such
+				as `year=%Y/month=%m/day=%d/`, and list the partition columns in
+				`encoding.except_fields` if they shouldn't also be written to the files.
+				"""
+		}
 		retry_policy: {
 			title: "Retry policy"
 			body: """
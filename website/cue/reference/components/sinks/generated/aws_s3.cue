@@ -256,6 +256,109 @@ generated: components: sinks: aws_s3: configuration: {
 			}
 		}
 	}
+	batch_encoding: {
+		description: """
+			Batch encoding configuration.
+
+			When set, each batch of events is written as a single object encoded with this codec, such
+			as an Apache Parquet file, instead of being encoded event by event with `encoding`. The
+			options of `encoding` that change events, such as `except_fields`, still apply.
+
+			Parquet files are compressed internally, so `compression` is not applied to them. Hive-style
+			partitioned prefixes, such as `year=%Y/month=%m/day=%d/`, can be used in `key_prefix` to
+			allow querying the objects as a partitioned table from Athena, and `except_fields` to leave
+			the partition columns out of the files.
+			"""
+		required: false
+		type: object: options: {
+			codec: {
+				description: "The codec to use for encoding batches of events."
+				required:    true
+				type: string: enum: parquet: """
+					Encodes a batch of events as an [Apache Parquet][apache_parquet] file.
+
+					The events are converted to Arrow record batches before being written, using either the
+					configured schema or one inferred from the events of the batch.
+
+					[apache_parquet]: https://parquet.apache.org/
+					"""
+			}
+			parquet: {
+				description:   "Apache Parquet-specific encoder options."
+				relevant_when: "codec = \"parquet\""
+				required:      false
+				type: object: options: {
+					compression: {
+						description: "The compression codec used for the column chunks of the Parquet files."
+						required:    false
+						type: string: {
+							default: "snappy"
+							enum: {
+								gzip: """
+									[Gzip][gzip] compression.
+
+									[gzip]: https://www.gzip.org/
+									"""
+								lz4: """
+									[LZ4][lz4] compression, without framing.
+
+									[lz4]: https://lz4.org/
+									"""
+								none: "No compression."
+								snappy: """
+									[Snappy][snappy] compression.
+
+									[snappy]: https://github.com/google/snappy
+									"""
+								zstd: """
+									[Zstandard][zstd] compression.
+
+									[zstd]: https://facebook.github.io/zstd/
+									"""
+							}
+						}
+					}
+					schema: {
+						description: """
+							The columns of the Parquet files, by name.
+
+							Fields of the events that aren't listed are not written, and missing fields are written as
+							null values. Columns are written in alphabetical order.
+
+							If not set, the schema is inferred from the events of each batch.
+							"""
+						required: false
+						type: object: {
+							examples: [{
+								message:   "string"
+								status:    "int64"
+								timestamp: "timestamp"
+							}]
+							options: "*": {
+								description: "The type of the column."
+								required:    true
+								type: string: enum: {
+									boolean: "A boolean value."
+									float64: "A 64-bit floating point number."
+									int64:   "A 64-bit signed integer."
+									string: """
+										A UTF-8 string.
+
+										Numbers and booleans are converted to strings.
+										"""
+									timestamp: """
+										A timestamp with microsecond precision, in UTC.
+
+										Values are parsed from RFC 3339 strings, which is how Vector encodes timestamps.
+										"""
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
 	bucket: {
 		description: """
 			The S3 bucket name.
@@ -323,6 +426,7 @@ generated: components: sinks: aws_s3: configuration: {
 
 			If not specified, the compression scheme used dictates this value.
 			When `compression` is set to `none`, the value `text/x-log` is used.
+			When `batch_encoding` is set, the MIME type of the batch encoding is used.
 			"""
 		required: false
 		type: string: examples: ["application/gzip"]
@@ -733,7 +837,8 @@ generated: components: sinks: aws_s3: configuration: {
 		description: """
 			The filename extension to use in the object key.
 
-			This overrides setting the extension based on the configured `compression`.
+			This overrides setting the extension based on the configured `compression`, or on the
+			configured `batch_encoding`.
 			"""
 		required: false
 		type: string: examples: [
@@ -1198,6 +1303,40 @@ generated: components: sinks: aws_s3: configuration: {
 			}
 		}
 	}
+	table_format: {
+		description: """
+			Table format configuration.
+
+			When set, each object is committed to a table in the given format once it's written, so
+			that its events can be queried right away, without a separate job to commit the objects.
+
+			This requires `batch_encoding` to be set, and `key_prefix` to start with the prefix of the
+			table.
+			"""
+		required: false
+		type: object: options: {
+			table_prefix: {
+				description:   "The prefix of the object keys of the table, which contains its `_delta_log` directory."
+				relevant_when: "type = \"delta_lake\""
+				required:      true
+				type: string: examples: ["tables/logs/"]
+			}
+			type: {
+				description: "The format of the table that objects are committed to."
+				required:    true
+				type: string: enum: delta_lake: """
+					Commit objects to a [Delta Lake][delta_lake] table.
+
+					Each object is added to the table with its own commit, appended to the transaction log of
+					the table with a conditional write so that concurrent writers can't overwrite each other's
+					commits. The table must already exist, and its partition columns must match the Hive-style
+					`column=value` directories of `key_prefix`.
+
+					[delta_lake]: https://delta.io/
+					"""
+			}
+		}
+	}
 	tags: {
 		description: "The tag-set for the object."
 		required:    false
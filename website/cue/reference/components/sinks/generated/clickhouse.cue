@@ -279,8 +279,12 @@ generated: components: sinks: clickhouse: configuration: {
 		}
 	}
 	date_time_best_effort: {
-		description: "Sets `date_time_input_format` to `best_effort`, allowing ClickHouse to properly parse RFC3339/ISO 8601."
-		required:    false
+		description: """
+			Sets `date_time_input_format` to `best_effort`, allowing ClickHouse to properly parse RFC3339/ISO 8601.
+
+			Only used by the `http` protocol, the `native` protocol always parses RFC 3339 timestamps.
+			"""
+		required: false
 		type: bool: default: false
 	}
 	encoding: {
@@ -314,13 +318,15 @@ generated: components: sinks: clickhouse: configuration: {
 	endpoint: {
 		description: "The endpoint of the ClickHouse server."
 		required:    true
-		type: string: examples: ["http://localhost:8123"]
+		type: string: examples: ["http://localhost:8123", "tcp://localhost:9000"]
 	}
 	format: {
 		description: """
 			Data format.
 
 			The format to parse input data.
+
+			Only used by the `http` protocol.
 			"""
 		required: false
 		type: string: {
@@ -332,11 +338,48 @@ generated: components: sinks: clickhouse: configuration: {
 			}
 		}
 	}
+	insert_deduplication_token: {
+		description: """
+			Sets `insert_deduplication_token` to an identifier generated for each request, so that ClickHouse discards the data of a retried request it already inserted.
+
+			Deduplication only happens for replicated tables, tables with `non_replicated_deduplication_window` set, and asynchronous inserts with `deduplicate` enabled.
+
+			Only used by the `native` protocol.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	insert_random_shard: {
 		description: "Sets `insert_distributed_one_random_shard`, allowing ClickHouse to insert data into a random shard when using Distributed Table Engine."
 		required:    false
 		type: bool: default: false
 	}
+	protocol: {
+		description: "The protocol used to send data to ClickHouse."
+		required:    false
+		type: string: {
+			default: "http"
+			enum: {
+				http: """
+					The [HTTP interface][http], sending rows encoded with the configured `format`.
+
+					[http]: https://clickhouse.com/docs/en/interfaces/http
+					"""
+				native: """
+					The [native TCP protocol][native], sending events as columnar blocks.
+
+					Blocks are compressed with LZ4 unless `compression` is set to `none`. Event fields are
+					matched to the columns of the table by name; columns without a matching field are filled
+					with the default value of their type.
+
+					The endpoint must point to the native protocol port of the server, usually `9000`, or
+					`9440` when TLS is enabled.
+
+					[native]: https://clickhouse.com/docs/en/native-protocol/basics
+					"""
+			}
+		}
+	}
 	query_settings: {
 		description: "Query settings for the `clickhouse` sink."
 		required:    false
@@ -592,6 +635,8 @@ generated: components: sinks: clickhouse: configuration: {
 			Sets `input_format_skip_unknown_fields`, allowing ClickHouse to discard fields not present in the table schema.
 
 			If left unspecified, use the default provided by the `ClickHouse` server.
+
+			Only used by the `http` protocol, the `native` protocol always discards unknown fields.
 			"""
 		required: false
 		type: bool: {}
@@ -325,6 +325,17 @@ generated: components: sinks: elasticsearch: configuration: {
 			}
 		}
 	}
+	capture_responses: {
+		description: """
+			Whether or not to send the responses to the bulk requests to the `responses` output.
+
+			The responses are sent as logs with their `status_code`, `headers`, and `body` to the
+			`<sink_id>.responses` output, which other components can use as an input, for example to
+			keep the identifiers assigned to the documents or the details of their errors.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	compression: {
 		description: """
 			Compression configuration.
@@ -386,6 +397,64 @@ generated: components: sinks: elasticsearch: configuration: {
 					syntax: "template"
 				}
 			}
+			index_template: {
+				description: """
+					An index template to create when the sink starts, so that the data streams receiving events
+					are created with the expected settings and mappings.
+
+					If the template can't be created on any of the endpoints, the sink fails to start.
+					"""
+				required: false
+				type: object: options: {
+					composed_of: {
+						description: "The component templates the index template is composed of, in the order they are merged."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: examples: ["logs-mappings", "logs-settings"]
+						}
+					}
+					index_patterns: {
+						description: "The patterns of the names of the data streams the index template applies to."
+						required:    true
+						type: array: items: type: string: examples: ["logs-nginx-*"]
+					}
+					lifecycle_policy: {
+						description: """
+							The name of the [index lifecycle management (ILM) policy][ilm] that manages the backing indices
+							of the data streams.
+
+							[ilm]: https://www.elastic.co/guide/en/elasticsearch/reference/current/index-lifecycle-management.html
+							"""
+						required: false
+						type: string: examples: ["logs"]
+					}
+					name: {
+						description: "The name of the index template."
+						required:    true
+						type: string: examples: ["vector-logs"]
+					}
+					overwrite: {
+						description: """
+							Whether to replace an existing index template with the same name.
+
+							By default, an existing index template is left untouched.
+							"""
+						required: false
+						type: bool: default: false
+					}
+					priority: {
+						description: """
+							The priority of the index template.
+
+							The index template with the highest priority applies when several of them match a data
+							stream. The built-in index templates of Elasticsearch have a priority of `100`.
+							"""
+						required: false
+						type: uint: default: 200
+					}
+				}
+			}
 			namespace: {
 				description: "The data stream namespace used to construct the data stream at index time."
 				required:    false
@@ -590,6 +659,9 @@ generated: components: sinks: elasticsearch: configuration: {
 
 					If the mode is set to `data_stream` and a `timestamp` field is present in a message,
 					Vector renames this field to the expected `@timestamp` to comply with the Elastic Common Schema.
+
+					Documents rejected with a `409 Conflict` status because they already exist, for example
+					when a request is retried with `id_key` set, are considered delivered.
 					"""
 			}
 		}
@@ -850,6 +922,21 @@ generated: components: sinks: elasticsearch: configuration: {
 		required: false
 		type: bool: default: false
 	}
+	reroute_rejected: {
+		description: """
+			Whether or not to send the documents rejected by Elasticsearch to the `rejected` output.
+
+			The documents that Elasticsearch refuses to index, such as the ones that don't match the
+			mapping of their index, are sent as logs to the `<sink_id>.rejected` output, which other
+			components can use as an input, for example to store them for reprocessing. Like for the
+			events rerouted by the `remap` transform, the reason of the rejection is added to the
+			`dropped` field of the metadata of the logs.
+
+			The documents that failed with a retriable error aren't rejected.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	suppress_type_name: {
 		deprecated:         true
 		deprecated_message: "This option has been deprecated, the `api_version` option should be used instead."
@@ -130,6 +130,109 @@ generated: components: sinks: gcp_cloud_storage: configuration: {
 			}
 		}
 	}
+	batch_encoding: {
+		description: """
+			Batch encoding configuration.
+
+			When set, each batch of events is written as a single object encoded with this codec, such
+			as an Apache Parquet file, instead of being encoded event by event with `encoding`. The
+			options of `encoding` that change events, such as `except_fields`, still apply.
+
+			Parquet files are compressed internally, so `compression` is not applied to them. Hive-style
+			partitioned prefixes, such as `year=%Y/month=%m/day=%d/`, can be used in `key_prefix` to
+			allow querying the objects as a partitioned BigQuery external table, and `except_fields` to
+			leave the partition columns out of the files.
+			"""
+		required: false
+		type: object: options: {
+			codec: {
+				description: "The codec to use for encoding batches of events."
+				required:    true
+				type: string: enum: parquet: """
+					Encodes a batch of events as an [Apache Parquet][apache_parquet] file.
+
+					The events are converted to Arrow record batches before being written, using either the
+					configured schema or one inferred from the events of the batch.
+
+					[apache_parquet]: https://parquet.apache.org/
+					"""
+			}
+			parquet: {
+				description:   "Apache Parquet-specific encoder options."
+				relevant_when: "codec = \"parquet\""
+				required:      false
+				type: object: options: {
+					compression: {
+						description: "The compression codec used for the column chunks of the Parquet files."
+						required:    false
+						type: string: {
+							default: "snappy"
+							enum: {
+								gzip: """
+									[Gzip][gzip] compression.
+
+									[gzip]: https://www.gzip.org/
+									"""
+								lz4: """
+									[LZ4][lz4] compression, without framing.
+
+									[lz4]: https://lz4.org/
+									"""
+								none: "No compression."
+								snappy: """
+									[Snappy][snappy] compression.
+
+									[snappy]: https://github.com/google/snappy
+									"""
+								zstd: """
+									[Zstandard][zstd] compression.
+
+									[zstd]: https://facebook.github.io/zstd/
+									"""
+							}
+						}
+					}
+					schema: {
+						description: """
+							The columns of the Parquet files, by name.
+
+							Fields of the events that aren't listed are not written, and missing fields are written as
+							null values. Columns are written in alphabetical order.
+
+							If not set, the schema is inferred from the events of each batch.
+							"""
+						required: false
+						type: object: {
+							examples: [{
+								message:   "string"
+								status:    "int64"
+								timestamp: "timestamp"
+							}]
+							options: "*": {
+								description: "The type of the column."
+								required:    true
+								type: string: enum: {
+									boolean: "A boolean value."
+									float64: "A 64-bit floating point number."
+									int64:   "A 64-bit signed integer."
+									string: """
+										A UTF-8 string.
+
+										Numbers and booleans are converted to strings.
+										"""
+									timestamp: """
+										A timestamp with microsecond precision, in UTC.
+
+										Values are parsed from RFC 3339 strings, which is how Vector encodes timestamps.
+										"""
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
 	bucket: {
 		description: "The GCS bucket name."
 		required:    true
@@ -597,7 +700,8 @@ generated: components: sinks: gcp_cloud_storage: configuration: {
 		description: """
 			The filename extension to use in the object key.
 
-			If not specified, the extension is determined by the compression scheme used.
+			If not specified, the extension is determined by the batch encoding or, without one, by the
+			compression scheme used.
 			"""
 		required: false
 		type: string: {}
@@ -236,6 +236,31 @@ generated: components: sinks: http: configuration: {
 			}
 		}
 	}
+	body: {
+		description: """
+			A template rendered from each event into the body of its request.
+
+			The body is compressed but not encoded, so the `encoding` options don't apply to it, and the
+			`Content-Type` header can be set in `request.headers`. Requires `per_event`.
+			"""
+		required: false
+		type: string: {
+			examples: ["{\"routing_key\":\"{{ routing_key }}\",\"event_action\":\"trigger\"}"]
+			syntax: "template"
+		}
+	}
+	capture_responses: {
+		description: """
+			Whether or not to send the responses to the requests to the `responses` output.
+
+			The responses are sent as logs with their `status_code`, `headers`, and `body` to the
+			`<sink_id>.responses` output, which other components can use as an input, for example to
+			keep the identifiers assigned by the endpoint or the details of the rejections. The
+			responses to the requests that are retried are sent too.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	compression: {
 		description: """
 			Compression configuration.
@@ -749,6 +774,27 @@ generated: components: sinks: http: configuration: {
 			}
 		}
 	}
+	ordering: {
+		description: "The order in which the requests of the sink are sent."
+		required:    false
+		type: string: {
+			default: "unordered"
+			enum: {
+				per_key: """
+					The requests holding events with the same value at `ordering_key_field` are sent one at a
+					time, in the order the events were received.
+
+					The requests of different keys are still sent concurrently.
+					"""
+				unordered: "The requests are sent concurrently, so the events can be delivered out of order."
+			}
+		}
+	}
+	ordering_key_field: {
+		description: "The field whose value groups the events sent in order when `ordering` is `per_key`."
+		required:    false
+		type: string: examples: ["user_id"]
+	}
 	payload_prefix: {
 		description: """
 			A string to prefix the payload with.
@@ -779,6 +825,29 @@ generated: components: sinks: http: configuration: {
 			]
 		}
 	}
+	per_endpoint_concurrency: {
+		description: """
+			Whether to adapt the concurrency of the requests to each endpoint of `uri` separately.
+
+			When `uri` is templated to send the events to several endpoints, this keeps a slow endpoint
+			from throttling the requests to the other ones. Endpoints are told apart by the scheme,
+			host, and port of the rendered URI, which tag the metrics of adaptive concurrency, and the
+			number of requests in flight over all the endpoints is bounded by
+			`request.adaptive_concurrency.max_concurrency_limit`.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	per_event: {
+		description: """
+			Whether to send one request per event instead of batches of events.
+
+			This overrides `batch.max_events`, and suits the webhooks that accept a single event per
+			request.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	request: {
 		description: "Outbound HTTP request settings."
 		required:    false
@@ -976,6 +1045,61 @@ generated: components: sinks: http: configuration: {
 			}
 		}
 	}
+	retry_policy: {
+		description: """
+			Configuration for the handling of the responses by status code.
+
+			By default, the requests are retried when the status of the response is `408`, `429`, or a
+			`5xx` one other than `501`, and the other statuses than the `2xx` ones reject the events.
+			"""
+		required: false
+		type: object: options: {
+			reject_codes: {
+				description: """
+					The status codes of the responses whose requests are not retried, and which reject the
+					events.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: uint: examples: [500]
+				}
+			}
+			respect_retry_after: {
+				description: """
+					Whether to wait for the delay of the `Retry-After` header of the retried responses,
+					instead of the backoff.
+
+					The delay is capped at `request.retry_max_duration_secs`.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			retry_codes: {
+				description: """
+					The status codes of the responses whose requests are retried, in addition to the default
+					ones.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: uint: examples: [409]
+				}
+			}
+			success_codes: {
+				description: """
+					The status codes of the responses that deliver the events, in addition to the `2xx` ones.
+
+					For example, `404` when the endpoint answers so for the resources already resolved.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: uint: examples: [404]
+				}
+			}
+		}
+	}
 	tls: {
 		description: "TLS configuration."
 		required:    false
@@ -528,6 +528,31 @@ generated: components: sinks: kafka: configuration: {
 			unit: "milliseconds"
 		}
 	}
+	ordering: {
+		description: "The order in which the requests of the sink are sent."
+		required:    false
+		type: string: {
+			default: "unordered"
+			enum: {
+				per_key: """
+					The requests holding events with the same value at `ordering_key_field` are sent one at a
+					time, in the order the events were received.
+
+					The requests of different keys are still sent concurrently.
+					"""
+				unordered: "The requests are sent concurrently, so the events can be delivered out of order."
+			}
+		}
+	}
+	ordering_key_field: {
+		description: """
+			The field whose value groups the events sent in order when `ordering` is `per_key`.
+
+			Defaults to `key_field`, so that the records of each key are produced in order.
+			"""
+		required: false
+		type: string: examples: ["user_id"]
+	}
 	rate_limit_duration_secs: {
 		description: "The time window used for the `rate_limit_num` option."
 		required:    false
@@ -703,4 +728,41 @@ generated: components: sinks: kafka: configuration: {
 			syntax: "template"
 		}
 	}
+	transactional: {
+		description: """
+			Transactional delivery configuration.
+
+			When set, events are produced within Kafka transactions. Each batch of events, as bounded by
+			the `batch` settings, is committed atomically once all its records are delivered, and the
+			transaction is aborted if any of them fails. Consumers reading with the `read_committed`
+			isolation level never see the records of aborted transactions.
+			"""
+		required: false
+		type: object: options: {
+			transaction_timeout_ms: {
+				description: """
+					Maximum time a transaction may remain open, in milliseconds.
+
+					This is also the timeout for committing and aborting transactions. The
+					`message_timeout_ms` option is capped to this value, as required by `librdkafka`.
+					"""
+				required: false
+				type: uint: {
+					default: 60000
+					examples: [60000]
+					unit: "milliseconds"
+				}
+			}
+			transactional_id: {
+				description: """
+					The transactional ID of the producer.
+
+					It must be unique to each Vector instance writing to the cluster, and stay the same across
+					restarts, so that transactions left in progress by a previous run are aborted.
+					"""
+				required: true
+				type: string: examples: ["vector-kafka-sink-0"]
+			}
+		}
+	}
 }
@@ -520,6 +520,49 @@ generated: components: sinks: nats: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			create_stream: {
+				description: """
+					Creates the stream if it doesn't exist.
+
+					By default, the stream must already exist. When the `subject` is templated, the
+					`subjects` of the stream must be set.
+					"""
+				required: false
+				type: object: options: {
+					max_age_secs: {
+						description: """
+							The maximum age of the messages kept in the stream, in seconds.
+
+							By default, the messages are kept until other limits of the stream are reached.
+							"""
+						required: false
+						type: uint: {}
+					}
+					storage: {
+						description: "The storage backend of a JetStream stream."
+						required:    false
+						type: string: {
+							default: "file"
+							enum: {
+								file:   "The messages are stored on disk."
+								memory: "The messages are stored in memory."
+							}
+						}
+					}
+					subjects: {
+						description: """
+							The subjects whose messages are stored in the stream.
+
+							By default, this is the `subject` of the component.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: examples: ["events.>"]
+						}
+					}
+				}
+			}
 			enabled: {
 				description: "Whether to enable Jetstream."
 				required:    false
@@ -541,6 +584,15 @@ generated: components: sinks: nats: configuration: {
 					}
 				}
 			}
+			stream: {
+				description: """
+					The name of the stream the messages are published to.
+
+					This is only used to create the stream with `create_stream`.
+					"""
+				required: false
+				type: string: examples: ["EVENTS"]
+			}
 		}
 	}
 	request: {
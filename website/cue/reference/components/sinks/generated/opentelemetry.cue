@@ -279,7 +279,8 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				Configures how events are encoded into raw bytes.
 				The selected encoding also determines which input types (logs, metrics, traces) are supported.
 				"""
-			required: true
+			relevant_when: "type = \"http\""
+			required:      true
 			type: object: options: {
 				avro: {
 					description:   "Apache Avro-specific encoder options."
@@ -656,6 +657,20 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				}
 			}
 		}
+		endpoint: {
+			description: """
+				The endpoint to send OTLP data to.
+
+				With the `grpc` protocol, this is the address of the OTLP/gRPC server, such as an
+				OpenTelemetry collector listening on port 4317.
+
+				With the `http_protobuf` protocol, this is the base URL of the OTLP/HTTP server. Log events
+				are sent to the `/v1/logs` path, and traces to the `/v1/traces` path, relative to it.
+				"""
+			relevant_when: "type = \"grpc\" or type = \"http_protobuf\""
+			required:      true
+			type: string: examples: ["http://localhost:4317", "https://otlp.example.com:4318"]
+		}
 		framing: {
 			description: "Framing configuration."
 			required:    false
@@ -752,6 +767,27 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				}
 			}
 		}
+		ordering: {
+			description: "The order in which the requests of the sink are sent."
+			required:    false
+			type: string: {
+				default: "unordered"
+				enum: {
+					per_key: """
+						The requests holding events with the same value at `ordering_key_field` are sent one at a
+						time, in the order the events were received.
+
+						The requests of different keys are still sent concurrently.
+						"""
+					unordered: "The requests are sent concurrently, so the events can be delivered out of order."
+				}
+			}
+		}
+		ordering_key_field: {
+			description: "The field whose value groups the events sent in order when `ordering` is `per_key`."
+			required:    false
+			type: string: examples: ["user_id"]
+		}
 		payload_prefix: {
 			description: """
 				A string to prefix the payload with.
@@ -780,6 +816,19 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				examples: ["}"]
 			}
 		}
+		per_endpoint_concurrency: {
+			description: """
+				Whether to adapt the concurrency of the requests to each endpoint of `uri` separately.
+
+				When `uri` is templated to send the events to several endpoints, this keeps a slow endpoint
+				from throttling the requests to the other ones. Endpoints are told apart by the scheme,
+				host, and port of the rendered URI, which tag the metrics of adaptive concurrency, and the
+				number of requests in flight over all the endpoints is bounded by
+				`request.adaptive_concurrency.max_concurrency_limit`.
+				"""
+			required: false
+			type: bool: default: false
+		}
 		request: {
 			description: "Outbound HTTP request settings."
 			required:    false
@@ -977,6 +1026,31 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				}
 			}
 		}
+		resource_attributes: {
+			description: """
+				Resource attributes to set on the data sent.
+
+				Values are [templates][template] rendered against each event, which allows mapping fields
+				of events to resource attributes, such as `service.name`. The attributes override the
+				resource attributes an event already has, such as those received by the `opentelemetry`
+				source. If a template fails to render, the attribute is left out for that event.
+
+				[template]: https://vector.dev/docs/reference/configuration/template-syntax/
+				"""
+			relevant_when: "type = \"grpc\" or type = \"http_protobuf\""
+			required:      false
+			type: object: {
+				examples: [{
+					"deployment.environment": "production"
+					"service.name":           "{{ service }}"
+				}]
+				options: "*": {
+					description: "An OpenTelemetry resource attribute."
+					required:    true
+					type: string: syntax: "template"
+				}
+			}
+		}
 		tls: {
 			description: "TLS configuration."
 			required:    false
@@ -1074,7 +1148,24 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 		type: {
 			description: "The communication protocol."
 			required:    true
-			type: string: enum: http: "Send data over HTTP."
+			type: string: enum: {
+				grpc: """
+					Send log events and traces as OTLP over gRPC.
+
+					Events are converted to OTLP log records and spans.
+					"""
+				http: """
+					Send data over HTTP.
+
+					Events are encoded with the configured codec, and must already be shaped as OTLP data to be
+					accepted by OpenTelemetry collectors.
+					"""
+				http_protobuf: """
+					Send log events and traces as OTLP over HTTP, encoded as Protobuf.
+
+					Events are converted to OTLP log records and spans.
+					"""
+			}
 		}
 		uri: {
 			description: """
@@ -1082,7 +1173,8 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 
 				This should include the protocol and host, but can also include the port, path, and any other valid part of a URI.
 				"""
-			required: true
+			relevant_when: "type = \"http\""
+			required:      true
 			type: string: {
 				examples: ["https://10.22.212.22:9000/endpoint"]
 				syntax: "template"
@@ -70,6 +70,53 @@ generated: components: sinks: postgres: configuration: {
 			}
 		}
 	}
+	columns: {
+		description: """
+			The columns of the table the events are written to, and their types.
+
+			By default, the fields of the events are matched to all the columns of the table by name.
+			"""
+		required: false
+		type: object: options: "*": {
+			description: "A column of the table."
+			required:    true
+			type: object: options: {
+				field: {
+					description: """
+						The field of the events written to the column.
+
+						Defaults to the top-level field named after the column.
+						"""
+					required: false
+					type: string: examples: ["message", "http.status_code"]
+				}
+				type: {
+					description: "The type of the column."
+					required:    true
+					type: string: enum: {
+						bigint:           "`bigint`."
+						boolean:          "`boolean`."
+						bytea:            "`bytea`."
+						date:             "`date`."
+						double_precision: "`double precision`."
+						integer:          "`integer`."
+						json:             "`json`."
+						jsonb:            "`jsonb`."
+						real:             "`real`."
+						smallint:         "`smallint`."
+						text: """
+							`text`, or any other character type.
+
+							The values that aren't strings are written as JSON.
+							"""
+						timestamp:   "`timestamp without time zone`, in UTC."
+						timestamptz: "`timestamp with time zone`."
+						uuid:        "`uuid`."
+					}
+				}
+			}
+		}
+	}
 	endpoint: {
 		description: """
 			The PostgreSQL server connection string. It can contain the username and password.
@@ -79,6 +126,27 @@ generated: components: sinks: postgres: configuration: {
 		required: true
 		type: string: {}
 	}
+	mode: {
+		description: "The way the events are written to the table."
+		required:    false
+		type: string: {
+			default: "insert"
+			enum: {
+				copy: """
+					The events are written with `COPY` statements in the binary format, which has a higher
+					throughput than `INSERT` statements.
+
+					Requires `columns`.
+					"""
+				insert: """
+					The events are inserted with `INSERT` statements.
+
+					Without `columns`, the fields of the events are matched to the columns of the table by
+					name.
+					"""
+			}
+		}
+	}
 	pool_size: {
 		description: """
 			The postgres connection pool size. See [this](https://docs.rs/sqlx/latest/sqlx/struct.Pool.html#why-use-a-pool) for more
@@ -279,8 +347,14 @@ generated: components: sinks: postgres: configuration: {
 			to SQL injection attacks as Vector does not validate or sanitize it, you must not use untrusted input.
 			This parameter will be directly interpolated in the SQL query statement,
 			as table names as parameters in prepared statements are not allowed in PostgreSQL.
+
+			The table can be templated, for example to write to the partitions of a partitioned table,
+			in which case the fields it is rendered from must not contain untrusted input either.
 			"""
 		required: true
-		type: string: {}
+		type: string: {
+			examples: ["logs", "logs_{{ application }}"]
+			syntax: "template"
+		}
 	}
 }
@@ -31,7 +31,8 @@ generated: components: sinks: prometheus_exporter: configuration: {
 		description: """
 			The address to expose for scraping.
 
-			The metrics are exposed at the typical Prometheus exporter path, `/metrics`.
+			The metrics are exposed at the typical Prometheus exporter path, `/metrics`, and at the
+			additional scrape paths, if any.
 			"""
 		required: false
 		type: string: {
@@ -272,6 +273,127 @@ generated: components: sinks: prometheus_exporter: configuration: {
 			unit:    "seconds"
 		}
 	}
+	native_histograms: {
+		description: """
+			Whether or not to expose the sketches, such as the distributions of the `datadog_agent`
+			source, as [native histograms][prom_native_hist_docs].
+
+			The native histograms are only supported by the protobuf format, which is used when the
+			client accepts it, like Prometheus with the native histograms enabled. Otherwise, the
+			sketches are exposed as aggregated summaries.
+
+			[prom_native_hist_docs]: https://prometheus.io/docs/specs/native_histograms/
+			"""
+		required: false
+		type: bool: default: false
+	}
+	paths: {
+		description: """
+			Additional scrape paths, each exposing the metrics selected and relabeled for it.
+
+			The metrics exposed on any path can be further selected by the scraper, with the `match[]`
+			query parameters of the [federation endpoint][federation] of Prometheus.
+
+			[federation]: https://prometheus.io/docs/prometheus/latest/federation/
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				path: {
+					description: "The path on which the metrics are exposed."
+					required:    true
+					type: string: examples: ["/federate"]
+				}
+				relabel_configs: {
+					description: "The rules relabeling the metrics exposed on the path, applied after those of the sink."
+					required:    false
+					type: array: {
+						default: []
+						items: type: object: options: {
+							action: {
+								description: "The action of a relabeling rule."
+								required:    false
+								type: string: {
+									default: "replace"
+									enum: {
+										drop:      "Drops the metrics whose values of the source labels match the regex."
+										keep:      "Keeps only the metrics whose values of the source labels match the regex."
+										labeldrop: "Removes the labels whose name matches the regex."
+										labelkeep: "Removes the labels whose name doesn't match the regex."
+										replace: """
+											Sets the target label to the replacement when the regex matches the values of the source
+											labels, removing it if the replacement is empty.
+											"""
+									}
+								}
+							}
+							regex: {
+								description: """
+									The regular expression matched against the values of the source labels, or against the
+									names of the labels for the `labeldrop` and `labelkeep` actions.
+
+									The regular expression is anchored at both ends.
+									"""
+								required: false
+								type: string: {
+									default: "(.*)"
+									examples: ["5.."]
+								}
+							}
+							replacement: {
+								description: """
+									The value of the target label set by the `replace` action, which can refer to the capture
+									groups of the regex, as `$1` or `${name}`.
+									"""
+								required: false
+								type: string: default: "$1"
+							}
+							separator: {
+								description: "The separator between the values of the source labels."
+								required:    false
+								type: string: default: ";"
+							}
+							source_labels: {
+								description: """
+									The labels whose values, joined by the separator, are matched against the regex.
+
+									The name of the metric, with its namespace, is the `__name__` label. A missing label has an
+									empty value.
+									"""
+								required: false
+								type: array: {
+									default: []
+									items: type: string: examples: ["__name__", "code"]
+								}
+							}
+							target_label: {
+								description: """
+									The label set by the `replace` action, which can refer to the capture groups of the regex,
+									as `$1` or `${name}`.
+									"""
+								required: false
+								type: string: examples: ["status"]
+							}
+						}
+					}
+				}
+				selectors: {
+					description: """
+						The series selectors of the metrics exposed on the path, after their relabeling.
+
+						A metric is exposed if it matches any of them, or if there are none. The selectors are
+						written as in PromQL, with the name of the metric followed by matchers of its labels.
+						"""
+					required: false
+					type: array: {
+						default: []
+						items: type: string: examples: ["http_requests_total{code=~\"5..\"}", "{job=\"vector\"}"]
+					}
+				}
+			}
+		}
+	}
 	quantiles: {
 		description: """
 			Quantiles to use for aggregating [distribution][dist_metric_docs] metrics into a summary.
@@ -284,6 +406,85 @@ generated: components: sinks: prometheus_exporter: configuration: {
 			items: type: float: {}
 		}
 	}
+	relabel_configs: {
+		description: """
+			The rules relabeling the metrics, applied in order before they're exposed on any path.
+
+			As with the relabeling of Prometheus, the metrics can be dropped or kept by the values of
+			their labels, and their labels replaced, dropped or kept. The name of the metric, with its
+			namespace, is the `__name__` label.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				action: {
+					description: "The action of a relabeling rule."
+					required:    false
+					type: string: {
+						default: "replace"
+						enum: {
+							drop:      "Drops the metrics whose values of the source labels match the regex."
+							keep:      "Keeps only the metrics whose values of the source labels match the regex."
+							labeldrop: "Removes the labels whose name matches the regex."
+							labelkeep: "Removes the labels whose name doesn't match the regex."
+							replace: """
+								Sets the target label to the replacement when the regex matches the values of the source
+								labels, removing it if the replacement is empty.
+								"""
+						}
+					}
+				}
+				regex: {
+					description: """
+						The regular expression matched against the values of the source labels, or against the
+						names of the labels for the `labeldrop` and `labelkeep` actions.
+
+						The regular expression is anchored at both ends.
+						"""
+					required: false
+					type: string: {
+						default: "(.*)"
+						examples: ["5.."]
+					}
+				}
+				replacement: {
+					description: """
+						The value of the target label set by the `replace` action, which can refer to the capture
+						groups of the regex, as `$1` or `${name}`.
+						"""
+					required: false
+					type: string: default: "$1"
+				}
+				separator: {
+					description: "The separator between the values of the source labels."
+					required:    false
+					type: string: default: ";"
+				}
+				source_labels: {
+					description: """
+						The labels whose values, joined by the separator, are matched against the regex.
+
+						The name of the metric, with its namespace, is the `__name__` label. A missing label has an
+						empty value.
+						"""
+					required: false
+					type: array: {
+						default: []
+						items: type: string: examples: ["__name__", "code"]
+					}
+				}
+				target_label: {
+					description: """
+						The label set by the `replace` action, which can refer to the capture groups of the regex,
+						as `$1` or `${name}`.
+						"""
+					required: false
+					type: string: examples: ["status"]
+				}
+			}
+		}
+	}
 	suppress_timestamp: {
 		description: """
 			Suppresses timestamps on the Prometheus output.
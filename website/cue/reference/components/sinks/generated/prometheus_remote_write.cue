@@ -328,6 +328,19 @@ generated: components: sinks: prometheus_remote_write: configuration: {
 		required: false
 		type: float: {}
 	}
+	native_histograms: {
+		description: """
+			Whether or not to send the sketches, such as the distributions of the `datadog_agent`
+			source, as [native histograms][prom_native_hist_docs].
+
+			The endpoint must accept the native histograms, like Prometheus with the native histograms
+			enabled. Otherwise, the sketches are sent as aggregated summaries.
+
+			[prom_native_hist_docs]: https://prometheus.io/docs/specs/native_histograms/
+			"""
+		required: false
+		type: bool: default: false
+	}
 	quantiles: {
 		description: """
 			Quantiles to use for aggregating [distribution][dist_metric_docs] metrics into a summary.
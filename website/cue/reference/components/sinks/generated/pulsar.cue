@@ -84,8 +84,13 @@ generated: components: sinks: pulsar: configuration: {
 		}
 	}
 	batch: {
-		description: "Event batching behavior."
-		required:    false
+		description: """
+			Event batching behavior.
+
+			With transactional delivery, each batch is produced within a transaction, and defaults to at
+			most 1000 events and 1 MiB.
+			"""
+		required: false
 		type: object: options: {
 			max_bytes: {
 				description: "The maximum size of a batch before it is flushed."
@@ -561,11 +566,29 @@ generated: components: sinks: pulsar: configuration: {
 
 			If omitted, the key is not sent.
 
-			Pulsar uses a hash of the key to choose the topic-partition or uses round-robin if the record has no key.
+			The key is used to choose the partition of partitioned topics when `partition_routing` is
+			set to `key_hash`.
 			"""
 		required: false
 		type: string: examples: ["message", "my_field"]
 	}
+	partition_routing: {
+		description: "How messages are distributed between the partitions of partitioned topics."
+		required:    false
+		type: string: {
+			default: "round_robin"
+			enum: {
+				key_hash: """
+					Sends messages with the same partition key to the same partition.
+
+					The partition is chosen with the same hash of the key as the default hashing scheme of the
+					Java client, so that messages are routed consistently with Java producers. Messages without
+					a partition key are sent to each partition in turn.
+					"""
+				round_robin: "Sends messages to each partition in turn."
+			}
+		}
+	}
 	producer_name: {
 		description: "The name of the producer. If not specified, the default name assigned by Pulsar is used."
 		required:    false
@@ -580,6 +603,33 @@ generated: components: sinks: pulsar: configuration: {
 		required: false
 		type: string: {}
 	}
+	schema: {
+		description: """
+			Schema registry configuration for events encoded with the `json` codec.
+
+			The schema is sent to the broker when the producer is created, which either registers it for
+			the topic or checks that it's compatible with the schema already registered, depending on the
+			schema policies of the namespace. Events encoded with the `avro` codec always use the schema of
+			the codec.
+			"""
+		required: false
+		type: object: options: {
+			definition: {
+				description: "The schema of the events, as an Avro schema definition in JSON."
+				required:    true
+				type: string: examples: ["{ \"type\": \"record\", \"name\": \"log\", \"fields\": [{ \"name\": \"message\", \"type\": \"string\" }] }"]
+			}
+			validate: {
+				description: """
+					Whether to check that events match the schema before sending them.
+
+					Events that don't match the schema are dropped, and an error is logged.
+					"""
+				required: false
+				type: bool: default: true
+			}
+		}
+	}
 	tls: {
 		description: "TLS options configuration for the Pulsar client."
 		required:    false
@@ -617,4 +667,41 @@ generated: components: sinks: pulsar: configuration: {
 			syntax: "template"
 		}
 	}
+	transactional: {
+		description: """
+			Transactional delivery configuration.
+
+			When set, events are produced within Pulsar transactions. Each batch of events, as bounded by
+			the `batch` settings, is committed atomically once all its messages are persisted, and the
+			transaction is aborted if any of them fails. Consumers only receive the messages of committed
+			transactions.
+
+			Transactions must be enabled on the brokers with the `transactionCoordinatorEnabled` setting.
+			Messages for partitioned topics are always sent to a single partition, chosen as configured by
+			`partition_routing`.
+			"""
+		required: false
+		type: object: options: {
+			batch_timeout_secs: {
+				description: "Maximum time a batch of events waits for more events before it is produced, in seconds."
+				required:    false
+				type: float: {
+					default: 1.0
+					unit:    "seconds"
+				}
+			}
+			transaction_timeout_secs: {
+				description: """
+					Maximum time a transaction may remain open, in seconds.
+
+					Transactions that aren't committed in time are aborted by the transaction coordinator.
+					"""
+				required: false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+		}
+	}
 }
@@ -5,6 +5,19 @@ generated: components: sinks: splunk_hec_metrics: configuration: {
 		description: "Splunk HEC acknowledgement configuration."
 		required:    false
 		type: object: options: {
+			channel_idle_timeout: {
+				description: """
+					The amount of time after which Splunk HEC removes idle channels and their pending acknowledgements.
+
+					This should match the `maxIdleTime` setting of Splunk, used when `ackIdleCleanup` is enabled,
+					so that channels with pending acknowledgements are always queried before they are removed.
+					"""
+				required: false
+				type: uint: {
+					default: 600
+					unit:    "seconds"
+				}
+			}
 			enabled: {
 				description: """
 					Controls whether or not end-to-end acknowledgements are enabled.
@@ -39,9 +52,24 @@ generated: components: sinks: splunk_hec_metrics: configuration: {
 				required: false
 				type: uint: default: 1000000
 			}
+			max_pending_acks_per_channel: {
+				description: """
+					The maximum number of pending acknowledgements on a single Splunk HEC channel.
+
+					Once reached, the following requests are sent on a new channel. This should not exceed the
+					`max_number_of_acked_requests_pending_query_per_ack_channel` limit of Splunk.
+					"""
+				required: false
+				type: uint: default: 1000000
+			}
 			query_interval: {
-				description: "The amount of time to wait between queries to the Splunk HEC indexer acknowledgement endpoint."
-				required:    false
+				description: """
+					The amount of time to wait between queries to the Splunk HEC indexer acknowledgement endpoint.
+
+					While queries don't resolve any acknowledgement ID, the amount of time between them is
+					doubled, up to half of `channel_idle_timeout`.
+					"""
+				required: false
 				type: uint: {
 					default: 10
 					unit:    "seconds"
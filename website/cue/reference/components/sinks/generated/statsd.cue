@@ -39,6 +39,26 @@ generated: components: sinks: statsd: configuration: {
 		required:      true
 		type: string: examples: ["92.12.333.224:5000", "somehost:5000"]
 	}
+	aggregation: {
+		description: """
+			Client-side aggregation of the metrics.
+
+			The metrics of each series are aggregated over the flush period before they're sent, which
+			reduces the number of packets sent for high-volume metrics. The counters are summed, the samples
+			of the timers, histograms and distributions are merged along with their sample rates, the
+			values of the sets are merged, and the gauges keep their last value, or the sum of their
+			changes.
+			"""
+		required: false
+		type: object: options: flush_period_secs: {
+			description: "The interval, in seconds, over which the metrics are aggregated before they're sent."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+	}
 	batch: {
 		description: "Event batching behavior."
 		required:    false
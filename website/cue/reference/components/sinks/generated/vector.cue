@@ -33,11 +33,42 @@ generated: components: sinks: vector: configuration: {
 
 			Both IP address and hostname are accepted formats.
 
-			The address _must_ include a port.
+			The address _must_ include a port. Either `address` or `endpoints` must be set.
 			"""
-		required: true
+		required: false
 		type: string: examples: ["92.12.333.224:6000", "https://somehost:6000"]
 	}
+	balance: {
+		description: "The balancing of the requests across the endpoints."
+		required:    false
+		type: object: options: {
+			key: {
+				description:   "The field of the logs and traces, or the tag of the metrics, whose value is hashed."
+				relevant_when: "policy = \"consistent_hash\""
+				required:      true
+				type: string: examples: ["host", ".tenant.id"]
+			}
+			policy: {
+				description: "The policy choosing the endpoint of each request."
+				required:    false
+				type: string: {
+					default: "round_robin"
+					enum: {
+						consistent_hash: """
+							The events with the same value of a key are sent to the same endpoint.
+
+							The endpoints own the ranges of a hash ring, so that adding or removing an endpoint only
+							moves the keys of its ranges. While an endpoint is ejected, the events of its keys are sent
+							to the next endpoint of the list. The events without the key are sent to each of the
+							endpoints in turn.
+							"""
+						least_in_flight: "The requests are sent to the endpoint with the fewest requests in flight."
+						round_robin:     "The requests are sent to each of the endpoints in turn."
+					}
+				}
+			}
+		}
+	}
 	batch: {
 		description: "Event batching behavior."
 		required:    false
@@ -72,14 +103,110 @@ generated: components: sinks: vector: configuration: {
 	}
 	compression: {
 		description: """
-			Whether or not to compress requests.
+			The compression of the requests.
+
+			If the source doesn't accept the configured scheme, the requests fall back to the preferred
+			scheme it accepts. For compatibility, `true` is accepted for `gzip`, and `false` for `none`.
+			"""
+		required: false
+		type: string: {
+			default: "none"
+			enum: {
+				gzip: """
+					The requests are compressed with [gzip][gzip_docs].
+
+					[gzip_docs]: https://www.gzip.org/
+					"""
+				lz4: """
+					The requests are compressed with the block format of [LZ4][lz4].
 
-			If set to `true`, requests are compressed with [`gzip`][gzip_docs].
+					LZ4 is the fastest of the schemes, at the expense of the ratio.
 
-			[gzip_docs]: https://www.gzip.org/
+					[lz4]: https://lz4.org/
+					"""
+				none: "The requests aren't compressed."
+				zstd: """
+					The requests are compressed with [Zstandard][zstd].
+
+					Zstandard compresses faster than gzip, at a similar ratio.
+
+					[zstd]: https://facebook.github.io/zstd/
+					"""
+			}
+		}
+	}
+	distribution: {
+		description: "Options for determining the health of an endpoint."
+		required:    false
+		type: object: options: {
+			retry_initial_backoff_secs: {
+				description: "Initial delay between attempts to reactivate endpoints once they become unhealthy."
+				required:    false
+				type: uint: {
+					default: 1
+					unit:    "seconds"
+				}
+			}
+			retry_max_duration_secs: {
+				description: "Maximum delay between attempts to reactivate endpoints once they become unhealthy."
+				required:    false
+				type: uint: {
+					default: 3600
+					unit:    "seconds"
+				}
+			}
+		}
+	}
+	endpoints: {
+		description: """
+			The addresses of the downstream Vector instances to balance the requests across.
+
+			The addresses have the same format as `address`. The endpoints that fail are ejected until
+			a request on probation succeeds, with the backoff set in `distribution`.
 			"""
 		required: false
-		type: bool: default: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["aggregator-0:6000", "aggregator-1:6000"]
+		}
+	}
+	http2: {
+		description: "HTTP/2 settings of the gRPC connections."
+		required:    false
+		type: object: options: {
+			adaptive_window: {
+				description: """
+					Whether to adapt the flow control windows of the connections to their bandwidth-delay
+					product.
+
+					The default windows limit the throughput of the connections with a high latency, such as the
+					ones across regions.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			keepalive_interval_secs: {
+				description: """
+					The interval between the HTTP/2 `PING` frames sent on the connections.
+
+					The pings keep the connections open through the proxies and load balancers closing the idle
+					ones, and detect the connections that are broken. If not set, no pings are sent.
+					"""
+				required: false
+				type: uint: {
+					examples: [30]
+					unit: "seconds"
+				}
+			}
+			keepalive_timeout_secs: {
+				description: "How long to wait for the acknowledgement of a ping before closing the connection."
+				required:    false
+				type: uint: {
+					default: 20
+					unit:    "seconds"
+				}
+			}
+		}
 	}
 	request: {
 		description: """
@@ -61,7 +61,17 @@ components: sinks: nats: {
 		traces:  false
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body:  """
+				When `jetstream.enabled` is set, each message is published to a
+				[JetStream](\(urls.nats_jetstream)) stream, and its events are only acknowledged once the
+				server confirms that the message was stored. The stream can be created by the sink with
+				`jetstream.create_stream`.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		send_errors_total: components.sources.internal_metrics.output.metrics.send_errors_total
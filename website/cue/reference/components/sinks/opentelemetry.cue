@@ -14,7 +14,7 @@ components: sinks: opentelemetry: {
 	features: {
 		auto_generated:   true
 		acknowledgements: true
-		healthcheck: enabled: false
+		healthcheck: enabled: true
 	}
 
 	input: {
@@ -31,13 +31,44 @@ components: sinks: opentelemetry: {
 	}
 
 	support: {
-		requirements: ["This sink accepts events conforming to the [OTEL proto format](\(urls.opentelemetry_proto)). You can use [Remap](\(urls.vector_remap_transform)) to prepare events for ingestion."]
+		requirements: ["With the `http` protocol, this sink accepts events conforming to the [OTEL proto format](\(urls.opentelemetry_proto)). You can use [Remap](\(urls.vector_remap_transform)) to prepare events for ingestion. The `grpc` and `http_protobuf` protocols accept log events and traces only."]
 		warnings: []
 		notices: []
 	}
 
 	configuration: generated.components.sinks.opentelemetry.configuration
 	how_it_works: {
+		native_otlp: {
+			title: "Native OTLP"
+			body: """
+				With the `grpc` and `http_protobuf` protocols, this sink converts log events and traces
+				into OTLP log records and spans itself, and exports them to any OTLP endpoint, such as an
+				OpenTelemetry collector or a vendor OTLP intake.
+
+				Log events received by the `opentelemetry` source are converted back into the log records
+				they were decoded from, including their resource and scope. For other log events, the
+				`message` is used as the body, and the remaining fields are sent as attributes. Traces are
+				converted into spans in the same way.
+
+				Data is grouped by resource and scope, and log events and traces are exported in separate
+				requests. Requests failing with the status codes that OTLP defines as retryable are
+				retried.
+
+				```yaml
+				sinks:
+					otlp:
+						inputs: ["my_logs"]
+						type: opentelemetry
+						protocol:
+							type: grpc
+							endpoint: http://localhost:4317
+							compression: gzip
+							resource_attributes:
+								service.name: "{{ service }}"
+				```
+				"""
+		}
+
 		quickstart: {
 			title: "Quickstart"
 			body: """
@@ -312,6 +312,21 @@ components: sinks: prometheus_exporter: {
 				values for each name, Vector will only send the last value specified.
 				"""
 		}
+
+		scrape_paths: {
+			title: "Relabeling and scrape paths"
+			body: """
+				The `relabel_configs` option relabels the metrics before they're exposed, dropping or
+				keeping them and replacing, dropping or keeping their labels, as the relabeling rules
+				of Prometheus do. The name of the metric, with its namespace, is the `__name__` label.
+
+				The `paths` option exposes the metrics on additional paths, each with its own series
+				selectors and relabeling rules applied after those of the sink, so that different
+				scrapers each get a tailored view of the metrics. On any path, the scraper can select
+				the metrics further with the `match[]` query parameters, as on the federation endpoint
+				of Prometheus.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -109,5 +109,15 @@ components: sinks: prometheus_remote_write: {
 				Vector also supports using Gzip and Zstd.
 				"""
 		}
+		metadata_and_exemplars: {
+			title: "Metadata and exemplars"
+			body: """
+				The metadata and exemplars received by the `prometheus_remote_write` source
+				are sent along with the metrics. The help and unit of the family are kept, as
+				is its type while the metric is still encoded as one of that type. The
+				exemplars of the histograms go to the series of the bucket holding their value,
+				and the exemplars of the summaries are dropped.
+				"""
+		}
 	}
 }
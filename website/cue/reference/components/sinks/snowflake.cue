@@ -0,0 +1,105 @@
+package metadata
+
+components: sinks: snowflake: {
+	title: "Snowflake"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Snowflake"]
+		stateful: false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+				enabled_by_scheme:      true
+			}
+			to: {
+				service: services.snowflake
+
+				interface: {
+					socket: {
+						api: {
+							title: "Snowpipe Streaming REST API"
+							url:   urls.snowpipe_streaming
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The user must be set up for [key-pair authentication](\(urls.snowflake_key_pair_auth)),
+				and be granted the `OPERATE` privilege on the default pipes of the tables.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.sinks.snowflake.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		channels: {
+			title: "Channels"
+			body: """
+				The events are encoded as JSON rows and appended to a channel of the default pipe of
+				their table, `<TABLE>-STREAMING`, which writes the fields of the events to the columns
+				with the same names. Each table has its own channel, named after the `channel` option,
+				so a channel must not be shared by several Vector instances.
+
+				The requests of a table are sent one at a time, in the order the events were received.
+				The batches of different tables are still sent concurrently.
+				"""
+		}
+
+		offset_tokens: {
+			title: "Offset tokens and acknowledgements"
+			body: """
+				The rows of each request are appended with an increasing offset token, and the events
+				are only acknowledged once Snowflake reports their offset token as committed. When a
+				request fails, the channel is reopened: the rows that weren't committed are discarded,
+				and the last committed offset token tells whether the retry of the request is needed,
+				so retries don't write the rows twice.
+				"""
+		}
+	}
+}
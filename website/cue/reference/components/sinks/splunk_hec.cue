@@ -14,7 +14,21 @@ components: sinks: _splunk_hec: {
 
 				The Splunk channel required for indexer acknowledgements is created using a randomly generated UUID. By default, this sink uses the
 				recommended Splunk indexer acknowledgements client behavior: querying for ack statuses every 10 seconds for a maximum of 30 attempts
-				(5 minutes) per `ackID`.
+				per `ackID`. While queries don't confirm the delivery of any `ackID`, the time between them is doubled, up to half of
+				`channel_idle_timeout` so that channels with pending acknowledgements are queried before Splunk removes them for being idle.
+				"""
+		}
+		indexer_acknowledgements_channels: {
+			title: "Indexer Acknowledgements Channels"
+			body:  """
+				When indexer acknowledgements are enabled, requests are sent on a new channel, with a new randomly generated UUID, when:
+
+				- The current channel has `max_pending_acks_per_channel` pending acknowledgements.
+				- Splunk lost the current channel, for example because an indexer restarted. This is detected when Splunk reports the
+				  channel as invalid, or returns an `ackID` that is already pending on the channel.
+
+				Previous channels are still queried for their pending acknowledgements. The events whose acknowledgements were pending
+				on a lost channel are finalized with an error, since their delivery can't be confirmed anymore.
 				"""
 		}
 		splunk_channel: {
@@ -84,6 +84,9 @@ components: sinks: splunk_hec_logs: {
 	telemetry: metrics: {
 		http_client_responses_total:      components.sources.internal_metrics.output.metrics.http_client_responses_total
 		http_client_response_rtt_seconds: components.sources.internal_metrics.output.metrics.http_client_response_rtt_seconds
+		splunk_ack_latency_seconds:       components.sources.internal_metrics.output.metrics.splunk_ack_latency_seconds
+		splunk_channel_rotations_total:   components.sources.internal_metrics.output.metrics.splunk_channel_rotations_total
+		splunk_pending_acks:              components.sources.internal_metrics.output.metrics.splunk_pending_acks
 	}
 
 	how_it_works: sinks._splunk_hec.how_it_works
@@ -78,7 +78,43 @@ components: sinks: vector: {
 
 	configuration: generated.components.sinks.vector.configuration
 
-	how_it_works: components.sinks.vector.how_it_works
+	how_it_works: {
+		compression: {
+			title: "Compression"
+			body: """
+				The requests are compressed with the scheme set in `compression`, which is sent to the
+				`vector` source in the `grpc-encoding` header. The sources reject the schemes they don't
+				support, listing the ones they accept in the `grpc-accept-encoding` header of the response,
+				and the sink then falls back to the first of `zstd`, `lz4`, and `gzip` they accept, or to no
+				compression. The older sources only accept `gzip`.
+				"""
+		}
+
+		load_balancing: {
+			title: "Load balancing"
+			body: """
+				With `endpoints`, the requests are spread across several `vector` sources, such as a fleet of
+				aggregators, without an external load balancer. The `balance.policy` option chooses the
+				endpoint of each request: `round_robin` sends them to each endpoint in turn,
+				`least_in_flight` to the endpoint with the fewest requests in flight, and `consistent_hash`
+				sends the events with the same value of `balance.key` to the same endpoint, so that the
+				aggregations of each key happen on a single aggregator.
+
+				The endpoints whose requests fail to connect or time out are ejected, and receive a single
+				request on probation after a backoff set in `distribution`, until one succeeds.
+				"""
+		}
+
+		multiplexing: {
+			title: "Multiplexing"
+			body: """
+				The concurrent requests, up to `request.concurrency`, are sent over the streams of the same
+				HTTP/2 connection, so that a slow request doesn't hold back the others. The `http2` options
+				keep the connection alive through the load balancers closing the idle ones, and adapt its
+				flow control windows to the latency of the links between regions.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		protobuf_decode_errors_total: components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
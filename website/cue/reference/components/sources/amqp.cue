@@ -85,5 +85,20 @@ components: sources: amqp: {
 		}
 	}
 
-	how_it_works: components._amqp.how_it_works
+	how_it_works: components._amqp.how_it_works & {
+		queue_declaration: {
+			title: "Queue declaration"
+			body:  """
+				When `declare` is set, the source declares the queue, and binds it to the exchanges, each
+				time it connects to the broker. With `declare.queue_type` set to `quorum`, the queue is a
+				[quorum queue](\(urls.rabbitmq_quorum_queues)), and `declare.delivery_limit` bounds the
+				redeliveries of the messages whose events failed to be delivered.
+
+				The messages whose events failed to be delivered are requeued, and the messages whose events
+				were rejected are dead-lettered, or dropped. `prefetch_count` bounds the messages delivered
+				to the source and not acknowledged yet. The source reconnects, with an exponential backoff,
+				when its connection to the broker is lost.
+				"""
+		}
+	}
 }
@@ -66,6 +66,27 @@ components: sources: aws_kinesis_firehose: {
 				description: "One event will be published per incoming AWS Kinesis Firehose record."
 				fields: {
 					timestamp: fields._current_timestamp
+					id: {
+						description: "The ID of the CloudWatch Logs event. Only set when `record_format` is `cloudwatch_logs_subscription`."
+						required:    false
+						type: string: {
+							examples: ["35683658089614582423604394983260738922885519999578275840"]
+						}
+					}
+					log_group: {
+						description: "The CloudWatch Logs log group of the event. Only set when `record_format` is `cloudwatch_logs_subscription`."
+						required:    false
+						type: string: {
+							examples: ["/lambda/test"]
+						}
+					}
+					log_stream: {
+						description: "The CloudWatch Logs log stream of the event. Only set when `record_format` is `cloudwatch_logs_subscription`."
+						required:    false
+						type: string: {
+							examples: ["2020/03/24/[$LATEST]794dbaf40a7846c4984ad80ebf110544"]
+						}
+					}
 					message: {
 						description: "The raw record from the incoming payload."
 						required:    true
@@ -73,6 +94,13 @@ components: sources: aws_kinesis_firehose: {
 							examples: ["Started GET / for 127.0.0.1 at 2012-03-10 14:28:14 +0100"]
 						}
 					}
+					owner: {
+						description: "The ID of the AWS account the CloudWatch Logs event originated from. Only set when `record_format` is `cloudwatch_logs_subscription`."
+						required:    false
+						type: string: {
+							examples: ["111111111111"]
+						}
+					}
 					request_id: {
 						description: "The AWS Kinesis Firehose request ID, value of the `X-Amz-Firehose-Request-Id` header."
 						required:    true
@@ -94,6 +122,11 @@ components: sources: aws_kinesis_firehose: {
 							examples: ["aws_kinesis_firehose"]
 						}
 					}
+					subscription_filters: {
+						description: "The names of the subscription filters that matched the CloudWatch Logs event. Only set when `record_format` is `cloudwatch_logs_subscription`."
+						required:    false
+						type: array: items: type: string: examples: ["Destination"]
+					}
 				}
 			}
 		}
@@ -137,6 +137,25 @@ components: sources: aws_s3: components._aws & {
 				```
 				"""
 		}
+		polling: {
+			title: "Polling buckets without SQS"
+			body:  """
+				For buckets where bucket notifications can't be configured, setting `strategy`
+				to `poll` makes the source list the bucket every `poll.interval_secs` seconds,
+				only considering the objects under `poll.prefix`. For buckets holding many
+				objects, the source can instead read the latest [S3 Inventory](\(urls.aws_s3_inventory))
+				report in the CSV format, found under `poll.inventory.manifest_prefix`.
+
+				The key and ETag of each object processed are checkpointed in the data
+				directory. Objects are processed only once, unless they're overwritten, and
+				objects that failed to be processed are retried on the next poll. The
+				checkpoints of objects deleted from the bucket are discarded.
+
+				Large objects can be read with concurrent range requests by setting
+				`poll.range_reads`, which speeds up the processing of objects many gigabytes in
+				size.
+				"""
+		}
 	}
 
 	permissions: iam: [
@@ -150,6 +169,10 @@ components: sources: aws_s3: components._aws & {
 				{
 					_action: "GetObject"
 				},
+				{
+					_action:       "ListBucket"
+					required_when: "[`strategy`](#strategy) is set to `poll`"
+				},
 			]
 		},
 		{
@@ -0,0 +1,111 @@
+package metadata
+
+components: sources: azure_blob: {
+	title: "Azure Blob Storage"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		multiline: enabled: true
+		receive: {
+			from: service: services.azure_blob
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The Azure Blob Storage source requires an Event Grid subscription delivering the
+				`BlobCreated` events of the storage account to Vector, unless `strategy` is set to
+				`poll`.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.azure_blob.configuration
+
+	output: logs: object: {
+		description: "A line from an Azure blob."
+		fields: {
+			message: {
+				description: "A line from the blob."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The Last-Modified time of the blob. Defaults the current timestamp if this information is missing."
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["azure_blob"]
+				}
+			}
+			container: {
+				description: "The container of the blob the line came from."
+				required:    true
+				type: string: {
+					examples: ["my-logs"]
+				}
+			}
+			blob: {
+				description: "The blob the line came from."
+				required:    true
+				type: string: {
+					examples: ["2024/01/01/app.log.gz"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		event_grid: {
+			title: "Event Grid events"
+			body:  """
+				By default, the source serves a webhook on `event_grid.address`, to which an Event
+				Grid subscription of the storage account delivers its
+				[`BlobCreated` events](\(urls.azure_event_grid_blob_events)). The subscription
+				validation handshake is answered automatically. A delivery succeeds once the events
+				of all its blobs are delivered, so Event Grid retries the deliveries of blobs that
+				failed to be processed. Events of other containers are ignored.
+
+				The metadata of the blob is added to its events.
+				"""
+		}
+		polling: {
+			title: "Polling containers"
+			body:  """
+				For containers where Event Grid can't deliver events to Vector, setting `strategy`
+				to `poll` makes the source list the container every `poll.interval_secs` seconds,
+				only considering the blobs under `poll.prefix`.
+
+				The name and ETag of each blob processed are checkpointed in the data directory.
+				Blobs are processed only once, unless they're overwritten, and blobs that failed to
+				be processed are retried on the next poll.
+				"""
+		}
+	}
+}
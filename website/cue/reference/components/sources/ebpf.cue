@@ -0,0 +1,222 @@
+package metadata
+
+components: sources: ebpf: {
+	title: "eBPF"
+
+	description: """
+		Collects the processes which start and exit and the TCP connections which open and close on
+		a Linux host, by attaching [eBPF](\(urls.ebpf)) programs to tracepoints of the kernel, without
+		relying on auditd.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		auto_generated:   true
+		collect: {
+			checkpoint: enabled: false
+			from: service: services.host
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"x86_64-pc-windows-msv": false
+		}
+
+		requirements: [
+			"""
+				Vector must run as `root`, or with the `CAP_BPF` and `CAP_PERFMON` [capabilities](\(urls.linux_capability))
+				on Linux 5.8 and later, to load the programs, and must be able to read the tracepoints of
+				[tracefs](\(urls.linux_tracefs)). The TCP probes require Linux 4.16 or later.
+				""",
+		]
+		warnings: [
+			"""
+				This source is experimental, and the fields of its events may change in future releases.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.ebpf.configuration
+
+	output: logs: event: {
+		description: "A process or TCP connection event of the host."
+		fields: {
+			command: {
+				description: "The command of the task which was running when the event happened, truncated to 15 bytes."
+				required:    true
+				type: string: {
+					examples: ["bash", "curl"]
+				}
+			}
+			destination_address: {
+				description: "The remote address of TCP connections."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["93.184.216.34", "2001:db8::1"]
+				}
+			}
+			destination_port: {
+				description: "The remote port of TCP connections."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [443]
+					unit: null
+				}
+			}
+			direction: {
+				description: "Whether a TCP connection was opened by the host or accepted from another host."
+				required:    false
+				type: string: {
+					default: null
+					enum: {
+						inbound:  "The connection was accepted from another host."
+						outbound: "The connection was opened by the host."
+					}
+				}
+			}
+			family: {
+				description: "The address family of TCP connections."
+				required:    false
+				type: string: {
+					default: null
+					enum: {
+						ipv4: "IPv4."
+						ipv6: "IPv6."
+					}
+				}
+			}
+			filename: {
+				description: "The path of the program executed by a process."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["/usr/bin/curl"]
+				}
+			}
+			gid: {
+				description: "The group ID of the task."
+				required:    true
+				type: uint: {
+					examples: [1000]
+					unit: null
+				}
+			}
+			host: fields._local_host
+			pid: {
+				description: "The ID of the process of the task."
+				required:    true
+				type: uint: {
+					examples: [4321]
+					unit: null
+				}
+			}
+			previous_state: {
+				description: "The state of TCP connections before they were closed, such as `established` or `last_ack`."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["established", "last_ack", "syn_sent"]
+				}
+			}
+			probe: {
+				description: "The probe which emitted the event."
+				required:    true
+				type: string: {
+					enum: {
+						process_exec: "A process executed a program."
+						process_exit: "A process exited."
+						tcp_close:    "A TCP connection was closed."
+						tcp_connect:  "A TCP connection was established."
+					}
+				}
+			}
+			source_address: {
+				description: "The local address of TCP connections."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["10.0.0.1"]
+				}
+			}
+			source_port: {
+				description: "The local port of TCP connections."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [51234]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["ebpf"]
+				}
+			}
+			tid: {
+				description: "The ID of the task, which is the ID of the thread in the process."
+				required:    true
+				type: uint: {
+					examples: [4321]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp
+			uid: {
+				description: "The user ID of the task."
+				required:    true
+				type: uint: {
+					examples: [1000]
+					unit: null
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		programs: {
+			title: "Programs"
+			body: """
+				The programs are assembled when the source starts, from the formats of the tracepoints
+				on the running kernel, so that the same Vector binary works across kernel versions
+				without BTF or a compiler. Each program copies the record of its tracepoint, along with
+				the process, user and command of the current task, to a perf buffer per CPU.
+				"""
+		}
+		tasks: {
+			title: "Tasks"
+			body: """
+				The `pid`, `uid`, `gid` and `command` fields are those of the task running when the
+				event happened. For connections accepted from another host, and for connections closed
+				by the kernel, this may be a task unrelated to the connection.
+				"""
+		}
+		lost_events: {
+			title: "Lost events"
+			body: """
+				Events are dropped when the perf buffer of a CPU is full, which is reported by the
+				`component_discarded_events_total` internal metric. Increase `buffer_pages` if events
+				are dropped during bursts of activity.
+				"""
+		}
+	}
+}
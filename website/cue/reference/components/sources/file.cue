@@ -419,6 +419,14 @@ components: sources: file: {
 				behavior, you can set the `ignore_checkpoints` option to `true`.  This
 				will cause Vector to disregard existing checkpoints when determining the
 				starting read position of a file.
+
+				When migrating Vector to another host or data directory, the checkpoints
+				of a source can be exported with `vector checkpoints export` and imported
+				with `vector checkpoints import`. Only checkpoints of files fingerprinted
+				by checksum can be imported on another host, as device and inode numbers
+				differ between hosts. Alternatively, the `seed_checkpoints_before` option
+				makes Vector read files without a checkpoint that were last modified
+				before the given time from their end.
 				"""
 		}
 	}
@@ -0,0 +1,114 @@
+package metadata
+
+components: sources: gcp_cloud_storage: {
+	title: "GCP Cloud Storage"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		multiline: enabled: true
+		collect: {
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+				enabled_by_scheme:      true
+			}
+			checkpoint: enabled: false
+			proxy: enabled:      true
+			from: service:       services.gcp_cloud_storage
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The GCP Cloud Storage source requires a Pub/Sub subscription receiving the
+				notifications of the bucket, unless `strategy` is set to `poll`.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.gcp_cloud_storage.configuration
+
+	output: logs: object: {
+		description: "A line from a Cloud Storage object."
+		fields: {
+			message: {
+				description: "A line from the Cloud Storage object."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The Last-Modified time of the object. Defaults the current timestamp if this information is missing."
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["gcp_cloud_storage"]
+				}
+			}
+			bucket: {
+				description: "The bucket of the object the line came from."
+				required:    true
+				type: string: {
+					examples: ["my-bucket"]
+				}
+			}
+			object: {
+				description: "The object the line came from."
+				required:    true
+				type: string: {
+					examples: ["logs/2024/01/01/app.log.gz"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		notifications: {
+			title: "Pub/Sub notifications"
+			body:  """
+				By default, the source pulls the [Pub/Sub notifications](\(urls.gcs_pubsub_notifications))
+				of the bucket from `pubsub.subscription`, and processes the object of every
+				`OBJECT_FINALIZE` notification. A notification is acknowledged once the events of
+				its object are delivered, so notifications of objects that failed to be processed
+				are delivered again by Pub/Sub. Notifications of other event types are acknowledged
+				and ignored.
+
+				The custom metadata of the object is added to its events.
+				"""
+		}
+		polling: {
+			title: "Polling buckets without notifications"
+			body:  """
+				For buckets where notifications can't be configured, setting `strategy` to `poll`
+				makes the source list the bucket every `poll.interval_secs` seconds, only
+				considering the objects under `poll.prefix`.
+
+				The name and generation of each object processed are checkpointed in the data
+				directory. Objects are processed only once, unless they're overwritten, and
+				objects that failed to be processed are retried on the next poll.
+				"""
+		}
+	}
+}
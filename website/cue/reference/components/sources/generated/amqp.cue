@@ -46,6 +46,75 @@ generated: components: sources: amqp: configuration: {
 			examples: ["consumer-group-name"]
 		}
 	}
+	declare: {
+		description: """
+			Declares the queue, and its bindings, whenever the source connects to the broker.
+
+			This recreates the queue if it was deleted while the source was disconnected. The
+			declaration fails if the queue already exists with different settings.
+			"""
+		required: false
+		type: object: options: {
+			bindings: {
+				description: "The bindings of the queue to exchanges."
+				required:    false
+				type: array: items: type: object: options: {
+					exchange: {
+						description: "The exchange the queue is bound to."
+						required:    true
+						type: string: examples: ["logs"]
+					}
+					routing_key: {
+						description: "The routing key of the binding."
+						required:    false
+						type: string: {
+							default: ""
+							examples: ["app.*"]
+						}
+					}
+				}
+			}
+			dead_letter_exchange: {
+				description: "The exchange the rejected messages are dead-lettered to."
+				required:    false
+				type: string: examples: ["logs.dead"]
+			}
+			delivery_limit: {
+				description: """
+					The number of times a message is delivered before it's dropped, or dead-lettered.
+
+					This only applies to quorum queues, and bounds the redeliveries of the messages whose events
+					failed to be delivered.
+					"""
+				required: false
+				type: uint: examples: [10]
+			}
+			durable: {
+				description: """
+					Whether the queue survives the restarts of the broker.
+
+					Quorum queues are always durable.
+					"""
+				required: false
+				type: bool: default: true
+			}
+			queue_type: {
+				description: "The type of a declared queue."
+				required:    false
+				type: string: {
+					default: "classic"
+					enum: {
+						classic: "A classic queue."
+						quorum: """
+															A [quorum queue][quorum_queues], replicated across the nodes of the cluster.
+
+															[quorum_queues]: https://www.rabbitmq.com/docs/quorum-queues
+															"""
+					}
+				}
+			}
+		}
+	}
 	decoding: {
 		description: """
 			Configures how events are decoded from raw bytes. Note some decoders can also determine the event output
@@ -541,6 +610,16 @@ generated: components: sources: amqp: configuration: {
 		required:    false
 		type: string: default: "offset"
 	}
+	prefetch_count: {
+		description: """
+			The maximum number of messages delivered to the source and not acknowledged yet.
+
+			By default, this is unlimited. Setting it bounds the events in flight, and lets the other
+			consumers of the queue receive the messages that this source can't keep up with.
+			"""
+		required: false
+		type: uint: examples: [500]
+	}
 	queue: {
 		description: "The name of the queue to consume."
 		required:    false
@@ -603,6 +603,26 @@ generated: components: sources: aws_kinesis_firehose: configuration: {
 			}
 		}
 	}
+	record_format: {
+		description: "The format of the records within the Firehose message, once decompressed."
+		required:    false
+		type: string: {
+			default: "raw"
+			enum: {
+				cloudwatch_logs_subscription: """
+					Each record is a [CloudWatch Logs subscription message][subscription_message].
+
+					Every log event of the message is decoded with the configured `framing` and `decoding`,
+					and annotated with the log group, log stream, owner, and subscription filters of the
+					message. The timestamp of the log event is used as the event timestamp. Control messages
+					are discarded.
+
+					[subscription_message]: https://docs.aws.amazon.com/AmazonCloudWatch/latest/logs/SubscriptionFilters.html#FirehoseExample
+					"""
+				raw: "Each record is decoded with the configured `framing` and `decoding`."
+			}
+		}
+	}
 	store_access_key: {
 		description: """
 			Whether or not to store the AWS Firehose Access Key in event secrets.
@@ -733,6 +733,115 @@ generated: components: sources: aws_s3: configuration: {
 			}
 		}
 	}
+	poll: {
+		description: "Configuration options for polling the bucket."
+		required:    false
+		type: object: options: {
+			bucket: {
+				description: "The name of the bucket to consume objects from."
+				required:    true
+				type: string: examples: ["my-bucket"]
+			}
+			client_concurrency: {
+				description: """
+					Number of objects to process concurrently.
+
+					Defaults to the number of available CPUs on the system.
+					"""
+				required: false
+				type: uint: {
+					examples: [5]
+					unit: "tasks"
+				}
+			}
+			data_dir: {
+				description: """
+					The directory used to persist the checkpoints of the processed objects.
+
+					By default, the [global `data_dir` option][global_data_dir] is used.
+					Make sure the running user has write permissions to this directory.
+
+					If this directory is specified, then Vector will attempt to create it.
+
+					[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+					"""
+				required: false
+				type: string: examples: ["/var/local/lib/vector/"]
+			}
+			interval_secs: {
+				description: "How often to look for new objects, in seconds."
+				required:    false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			inventory: {
+				description: """
+					Configuration for finding objects in [S3 Inventory][s3_inventory] reports instead of listing the bucket.
+
+					Only reports in the CSV format are supported. The latest report is processed once it is
+					delivered, which is more efficient than listing buckets holding many objects.
+
+					[s3_inventory]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-inventory.html
+					"""
+				required: false
+				type: object: options: {
+					bucket: {
+						description: """
+							The name of the bucket the inventory reports are delivered to.
+
+							Defaults to the bucket objects are consumed from.
+							"""
+						required: false
+						type: string: examples: ["my-inventory-bucket"]
+					}
+					manifest_prefix: {
+						description: """
+							The prefix of the inventory reports of the inventory configuration.
+
+							This is `<destination prefix>/<source bucket>/<configuration ID>/`, under which S3 delivers
+							the `manifest.json` file of each report.
+							"""
+						required: true
+						type: string: examples: ["inventory/my-bucket/daily/"]
+					}
+				}
+			}
+			prefix: {
+				description: "Only objects whose key starts with this prefix are consumed."
+				required:    false
+				type: string: examples: ["logs/"]
+			}
+			range_reads: {
+				description: "Configuration for reading large objects with concurrent range requests."
+				required:    false
+				type: object: options: {
+					concurrency: {
+						description: "The number of ranges of an object fetched concurrently."
+						required:    false
+						type: uint: {
+							default: 4
+							unit:    "requests"
+						}
+					}
+					part_size_bytes: {
+						description: """
+							The size of each range, in bytes.
+
+							Objects larger than this are fetched in ranges of this size. Ranges fetched ahead of the
+							one being decoded are buffered in memory.
+							"""
+						required: false
+						type: uint: {
+							default: 8388608
+							unit:    "bytes"
+						}
+					}
+				}
+			}
+		}
+	}
 	region: {
 		description: """
 			The [AWS region][aws_region] of the target service.
@@ -990,6 +1099,28 @@ generated: components: sources: aws_s3: configuration: {
 			}
 		}
 	}
+	strategy: {
+		description: "The strategy to use to consume objects from S3."
+		required:    false
+		type: string: {
+			default: "sqs"
+			enum: {
+				poll: """
+					Consumes objects by periodically listing the bucket, or by reading its [S3 Inventory][s3_inventory] reports.
+
+					This can be used for buckets where bucket notifications can't be configured. Processed
+					objects are tracked in checkpoints persisted in the data directory.
+
+					[s3_inventory]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-inventory.html
+					"""
+				sqs: """
+					Consumes objects by processing bucket notification events sent to an [AWS SQS queue][aws_sqs].
+
+					[aws_sqs]: https://aws.amazon.com/sqs/
+					"""
+			}
+		}
+	}
 	tls_options: {
 		description: "TLS configuration."
 		required:    false
@@ -706,4 +706,38 @@ generated: components: sources: datadog_agent: configuration: {
 			}
 		}
 	}
+	trace_obfuscation: {
+		description: "Configuration for the obfuscation of the traces."
+		required:    false
+		type: object: options: {
+			redis: {
+				description: """
+					Whether or not to obfuscate the Redis commands of the spans of the `redis` type.
+
+					The arguments of the commands in the `redis.raw_command` tag of the spans are replaced with
+					`?`, except for the key. All of the arguments of the `AUTH` command are replaced.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			remove_tags: {
+				description: "The tags removed from the spans and the traces."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["http.url", "usr.email"]
+				}
+			}
+			sql: {
+				description: """
+					Whether or not to obfuscate the SQL queries of the spans of the `sql` and `cassandra` types.
+
+					The literals of the queries, in the resource and the `sql.query` tag of the spans, are
+					replaced with `?`, their lists are collapsed, and their comments are removed.
+					"""
+				required: false
+				type: bool: default: false
+			}
+		}
+	}
 }
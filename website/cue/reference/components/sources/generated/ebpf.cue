@@ -0,0 +1,59 @@
+package metadata
+
+generated: components: sources: ebpf: configuration: {
+	buffer_pages: {
+		description: """
+			The number of memory pages of the buffer each CPU writes events to, which must be a
+			power of two.
+
+			Events are dropped when the buffer of a CPU is full, which can happen during bursts of
+			activity if Vector can't keep up.
+			"""
+		required: false
+		type: uint: {
+			default: 64
+			unit:    "pages"
+		}
+	}
+	probes: {
+		description: "The probes to attach, each emitting a kind of event."
+		required:    false
+		type: object: options: {
+			process_exec: {
+				description: "Emit an event when a process executes a program."
+				required:    false
+				type: bool: default: true
+			}
+			process_exit: {
+				description: "Emit an event when a process exits."
+				required:    false
+				type: bool: default: true
+			}
+			tcp_close: {
+				description: "Emit an event when a TCP connection is closed."
+				required:    false
+				type: bool: default: true
+			}
+			tcp_connect: {
+				description: """
+					Emit an event when a TCP connection is established, either by connecting to or by
+					accepting a connection from another host.
+					"""
+				required: false
+				type: bool: default: true
+			}
+		}
+	}
+	tracefs_path: {
+		description: """
+			The path where [tracefs][tracefs] is mounted, which has the formats of the tracepoints.
+
+			By default, `/sys/kernel/tracing` is used, or `/sys/kernel/debug/tracing` on systems
+			where tracefs is only mounted in debugfs.
+
+			[tracefs]: https://docs.kernel.org/trace/ftrace.html
+			"""
+		required: false
+		type: string: examples: ["/sys/kernel/tracing"]
+	}
+}
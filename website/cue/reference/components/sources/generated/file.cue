@@ -379,4 +379,18 @@ generated: components: sources: file: configuration: {
 			unit:    "seconds"
 		}
 	}
+	seed_checkpoints_before: {
+		description: """
+			Consider files without a checkpoint that were last modified before this time as already
+			read.
+
+			When Vector starts, such files are read from their end rather than as set by `read_from`.
+			This is useful to migrate Vector to another host or data directory without importing the
+			checkpoints of the previous instance: set it to the time the previous instance stopped, so
+			that files it had finished reading are not read again, and files written since are not
+			skipped.
+			"""
+		required: false
+		type: string: examples: ["2025-01-01T00:00:00Z"]
+	}
 }
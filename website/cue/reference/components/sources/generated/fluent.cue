@@ -74,6 +74,20 @@ generated: components: sources: fluent: configuration: {
 		required:      false
 		type: array: items: type: string: examples: ["192.168.0.0/16", "127.0.0.1/32", "::1/128", "9876:9ca3:99ab::23/128"]
 	}
+	proxy_protocol: {
+		description: """
+			Whether the connections start with a [PROXY protocol][proxy_protocol] header.
+
+			When the forwarders connect through a load balancer sending the version 1 or 2 of the
+			header, such as HAProxy, the address of each forwarder is read from it and used as the
+			`host` of the events. The connections without a valid header are closed.
+
+			[proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: bool: default: false
+	}
 	receive_buffer_bytes: {
 		description: """
 			The size of the receive buffer used for each connection.
@@ -630,6 +630,19 @@ generated: components: sources: http: configuration: {
 			}
 		}
 	}
+	max_body_size: {
+		description: """
+			The maximum size of the body of each request, before any decompression.
+
+			The requests whose body is larger are rejected with a `413` status code. The events already
+			decoded from a streamed body are kept.
+			"""
+		required: false
+		type: uint: {
+			examples: [10485760]
+			unit: "bytes"
+		}
+	}
 	method: {
 		description: "Specifies the action of the HTTP request."
 		required:    false
@@ -646,6 +659,54 @@ generated: components: sources: http: configuration: {
 			}
 		}
 	}
+	overload: {
+		description: """
+			Configuration for rejecting requests while the source is overloaded.
+
+			The source is overloaded when the components downstream of it can't keep up, and events
+			accumulate in its buffer. While overloaded, requests are rejected with a `429 Too Many Requests`
+			response, with a `Retry-After` header estimating how long it takes for the buffer to drain, so
+			that clients back off instead of having their requests time out.
+			"""
+		required: false
+		type: object: options: {
+			enabled: {
+				description: "Whether to reject requests while the source is overloaded."
+				required:    false
+				type: bool: default: false
+			}
+			max_retry_after_secs: {
+				description: """
+					The maximum delay, in seconds, sent in the `Retry-After` header.
+
+					This is also the delay sent before the drain rate of the buffer is known, or while the
+					buffer isn't draining at all.
+					"""
+				required: false
+				type: uint: {
+					default: 30
+					unit:    "seconds"
+				}
+			}
+			min_retry_after_secs: {
+				description: "The minimum delay, in seconds, sent in the `Retry-After` header."
+				required:    false
+				type: uint: {
+					default: 1
+					unit:    "seconds"
+				}
+			}
+			threshold: {
+				description: """
+					The fraction of the buffer above which requests are rejected.
+
+					Valid values are between `0` and `1`.
+					"""
+				required: false
+				type: float: default: 0.9
+			}
+		}
+	}
 	path: {
 		description: "The URL path on which log event POST requests are sent."
 		required:    false
@@ -701,6 +762,18 @@ generated: components: sources: http: configuration: {
 		required: false
 		type: bool: default: true
 	}
+	stream_chunked_bodies: {
+		description: """
+			Whether to decode the bodies sent with `Transfer-Encoding: chunked` while they're received.
+
+			The events are then sent as the chunks arrive, such as the lines of an NDJSON body,
+			rather than once the whole body is received, which suits large uploads and long-lived
+			streaming clients. The response is sent once the body ends, or fails to be decoded, in
+			which case the events decoded until then are still sent.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	tls: {
 		description: "Configures the TLS options for incoming/outgoing connections."
 		required:    false
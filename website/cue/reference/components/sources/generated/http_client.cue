@@ -173,6 +173,22 @@ generated: components: sources: http_client: configuration: {
 			}
 		}
 	}
+	data_dir: {
+		description: """
+			The directory used to persist the pagination cursor.
+
+			This is only used when `pagination` is set.
+
+			By default, the [global `data_dir` option][global_data_dir] is used.
+			Make sure the running user has write permissions to this directory.
+
+			If this directory is specified, then Vector will attempt to create it.
+
+			[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+			"""
+		required: false
+		type: string: examples: ["/var/lib/vector"]
+	}
 	decoding: {
 		description: """
 			Configures how events are decoded from raw bytes. Note some decoders can also determine the event output
@@ -696,6 +712,88 @@ generated: components: sources: http_client: configuration: {
 			}
 		}
 	}
+	oauth2: {
+		description: """
+			OAuth2 client credentials authentication.
+
+			This can't be used together with `auth`.
+			"""
+		required: false
+		type: object: options: {
+			client_id: {
+				description: "The client identifier."
+				required:    true
+				type: string: examples: ["${OAUTH2_CLIENT_ID}"]
+			}
+			client_secret: {
+				description: """
+					The client secret.
+
+					This can be omitted when the authorization server authenticates the client with the
+					certificate set in `tls.crt_file` instead.
+					"""
+				required: false
+				type: string: examples: ["${OAUTH2_CLIENT_SECRET}"]
+			}
+			refresh_before_expiry_secs: {
+				description: "How long before the expiry of the access token a new one is requested."
+				required:    false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			scopes: {
+				description: "The scopes to request for the access token."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["api"]
+				}
+			}
+			token_endpoint: {
+				description: "The URL of the token endpoint of the authorization server."
+				required:    true
+				type: string: examples: ["https://login.salesforce.com/services/oauth2/token"]
+			}
+		}
+	}
+	pagination: {
+		description: """
+			Pagination of the responses.
+
+			The pages are requested one after the other until a page has no events or no next page. The
+			position of the next page is persisted in the data directory, so that the next scrapes, and
+			Vector after a restart, continue from the last page.
+			"""
+		required: false
+		type: object: options: {
+			cursor_path: {
+				description: """
+					The path of the cursor in the JSON body of the responses.
+
+					There is no next page when the cursor is missing, null, or empty.
+					"""
+				relevant_when: "strategy = \"cursor\""
+				required:      true
+				type: string: examples: ["next_cursor", "meta.cursor"]
+			}
+			query_parameter: {
+				description:   "The query parameter to send the cursor with."
+				relevant_when: "strategy = \"cursor\""
+				required:      true
+				type: string: examples: ["cursor"]
+			}
+			strategy: {
+				description: "The pagination strategy to use."
+				required:    true
+				type: string: enum: {
+					cursor:      "Send the cursor read from the body of the previous response as a query parameter."
+					link_header: "Follow the `next` link of the `Link` header of the responses."
+				}
+			}
+		}
+	}
 	query: {
 		description: """
 			Custom parameters for the HTTP request query string.
@@ -642,6 +642,34 @@ generated: components: sources: kafka: configuration: {
 			]
 		}
 	}
+	partition_assignment_strategy: {
+		description: """
+			The partition assignment strategy of the consumer group.
+
+			All members of a consumer group must use the same rebalance protocol, so switching an
+			existing group from `eager` to `cooperative_sticky` requires restarting all its consumers.
+
+			Setting `partition.assignment.strategy` in `librdkafka_options` overrides this option.
+			"""
+		required: false
+		type: string: {
+			default: "eager"
+			enum: {
+				cooperative_sticky: """
+					Use the incremental `cooperative-sticky` assignor.
+
+					A rebalance only revokes the partitions that move to another member of the group, while
+					the other partitions are consumed without interruption.
+					"""
+				eager: """
+					Use the eager `range` and `roundrobin` assignors.
+
+					Every rebalance revokes all partitions from all members of the group, which stop
+					consuming until the new assignment is received.
+					"""
+			}
+		}
+	}
 	partition_key: {
 		description: """
 			Overrides the name of the log field used to add the partition to each event.
@@ -656,6 +684,76 @@ generated: components: sources: kafka: configuration: {
 			examples: ["partition"]
 		}
 	}
+	partitions: {
+		description: """
+			A static list of partitions to read events from, instead of subscribing to `topics`.
+
+			The consumer doesn't join the consumer group, so the partitions are never rebalanced, and
+			each one is consumed from its `start_offset`. This is useful to replay or backfill events
+			from given offsets or timestamps.
+
+			Offsets are still committed for `group_id`, which shouldn't be used by any other consumer.
+
+			Either `topics` or `partitions` must be set.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				partition: {
+					description: "The number of the partition."
+					required:    true
+					type: int: examples: [0]
+				}
+				start_offset: {
+					description: "The offset to start consuming a partition from."
+					required:    false
+					type: object: options: {
+						offset: {
+							description:   "The offset of the first message to consume."
+							relevant_when: "type = \"offset\""
+							required:      true
+							type: int: examples: [42]
+						}
+						timestamp_ms: {
+							description:   "The timestamp, in milliseconds since the Unix epoch."
+							relevant_when: "type = \"timestamp\""
+							required:      true
+							type: int: examples: [1735689600000]
+						}
+						type: {
+							description: "The kind of start offset."
+							required:    false
+							type: string: {
+								default: "committed"
+								enum: {
+									committed: """
+										The offset committed for the consumer group.
+
+										If the consumer group has no committed offset for the partition, `auto_offset_reset` is
+										used instead.
+										"""
+									earliest: "The earliest offset retained in the partition."
+									latest:   "The end of the partition, so that only new messages are consumed."
+									offset:   "An absolute offset."
+									timestamp: """
+										The offset of the first message with a timestamp equal to or later than the given one.
+
+										If there is no such message, the end of the partition is used.
+										"""
+								}
+							}
+						}
+					}
+				}
+				topic: {
+					description: "The topic of the partition."
+					required:    true
+					type: string: examples: ["topic-1"]
+				}
+			}
+		}
+	}
 	sasl: {
 		description: "Configuration for SASL authentication when interacting with Kafka."
 		required:    false
@@ -837,8 +935,13 @@ generated: components: sources: kafka: configuration: {
 			The Kafka topics names to read events from.
 
 			Regular expression syntax is supported if the topic begins with `^`.
+
+			Either `topics` or `partitions` must be set.
 			"""
-		required: true
-		type: array: items: type: string: examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+		}
 	}
 }
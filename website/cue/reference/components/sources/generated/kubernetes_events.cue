@@ -0,0 +1,53 @@
+package metadata
+
+generated: components: sources: kubernetes_events: configuration: {
+	field_selector: {
+		description: """
+			Specifies the [field selector][field_selector] to filter Events with.
+
+			[field_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/field-selectors/
+			"""
+		required: false
+		type: string: {
+			default: ""
+			examples: ["type=Warning", "metadata.namespace=default,regarding.kind=Pod"]
+		}
+	}
+	include_existing: {
+		description: """
+			Whether or not to emit the Events that already exist when the source starts.
+
+			By default, only Events that are observed after the source started are emitted.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	kube_config_file: {
+		description: """
+			Optional path to a readable [kubeconfig][kubeconfig] file.
+
+			If not set, a connection to Kubernetes is made using the in-cluster configuration.
+
+			[kubeconfig]: https://kubernetes.io/docs/concepts/configuration/organize-cluster-access-kubeconfig/
+			"""
+		required: false
+		type: string: examples: ["/path/to/.kube/config"]
+	}
+	label_selector: {
+		description: """
+			Specifies the [label selector][label_selector] to filter Events with.
+
+			[label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+			"""
+		required: false
+		type: string: {
+			default: ""
+			examples: ["my_custom_label!=my_value"]
+		}
+	}
+	use_apiserver_cache: {
+		description: "Determines if requests to the kube-apiserver can be served by a cache."
+		required:    false
+		type: bool: default: false
+	}
+}
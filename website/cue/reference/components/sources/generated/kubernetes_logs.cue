@@ -211,6 +211,8 @@ generated: components: sources: kubernetes_logs: configuration: {
 			Note that, if auto_partial_merge is false, this config will be ignored. Also, if max_line_bytes is too small to reach the continuation character, then this
 			config will have no practical impact (the same is true of `auto_partial_merge`). Finally, the smaller of `max_merged_line_bytes` and `max_line_bytes` will apply
 			if auto_partial_merge is true, so if this is set to be 1 MiB, for example, but `max_line_bytes` is set to ~2.5 MiB, then every line greater than 1 MiB will be dropped.
+
+			How lines exceeding this limit are handled can be changed with `oversize_line_behavior`.
 			"""
 		required: false
 		type: uint: unit: "bytes"
@@ -229,6 +231,79 @@ generated: components: sources: kubernetes_logs: configuration: {
 			unit:    "bytes"
 		}
 	}
+	multiline: {
+		description: """
+			Multiline aggregation configuration.
+
+			Lines are aggregated per container, after partial events are merged. This allows, for
+			example, merging Java stack traces without an additional `reduce` transform.
+
+			If not specified, multiline aggregation is disabled.
+			"""
+		required: false
+		type: object: options: {
+			condition_pattern: {
+				description: """
+					Regular expression pattern that is used to determine whether or not more lines should be read.
+
+					This setting must be configured in conjunction with `mode`.
+					"""
+				required: true
+				type: string: examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
+			}
+			mode: {
+				description: """
+					Aggregation mode.
+
+					This setting must be configured in conjunction with `condition_pattern`.
+					"""
+				required: true
+				type: string: enum: {
+					continue_past: """
+						All consecutive lines matching this pattern, plus one additional line, are included in the group.
+
+						This is useful in cases where a log message ends with a continuation marker, such as a backslash, indicating
+						that the following line is part of the same message.
+						"""
+					continue_through: """
+						All consecutive lines matching this pattern are included in the group.
+
+						The first line (the line that matched the start pattern) does not need to match the `ContinueThrough` pattern.
+
+						This is useful in cases such as a Java stack trace, where some indicator in the line (such as a leading
+						whitespace) indicates that it is an extension of the proceeding line.
+						"""
+					halt_before: """
+						All consecutive lines not matching this pattern are included in the group.
+
+						This is useful where a log line contains a marker indicating that it begins a new message.
+						"""
+					halt_with: """
+						All consecutive lines, up to and including the first line matching this pattern, are included in the group.
+
+						This is useful where a log line ends with a termination marker, such as a semicolon.
+						"""
+				}
+			}
+			start_pattern: {
+				description: "Regular expression pattern that is used to match the start of a new message."
+				required:    true
+				type: string: examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
+			}
+			timeout_ms: {
+				description: """
+					The maximum amount of time to wait for the next additional line, in milliseconds.
+
+					Once this timeout is reached, the buffered message is guaranteed to be flushed, even if incomplete.
+					"""
+				required: true
+				type: uint: {
+					examples: [1000, 600000]
+					unit: "milliseconds"
+				}
+			}
+		}
+	}
 	namespace_annotation_fields: {
 		description: "Configuration for how the events are enriched with Namespace metadata."
 		required:    false
@@ -266,6 +341,44 @@ generated: components: sources: kubernetes_logs: configuration: {
 		required:    false
 		type: bool: default: true
 	}
+	oversize_line_behavior: {
+		description: "What to do with a line that exceeds `max_merged_line_bytes` once merged."
+		required:    false
+		type: string: {
+			default: "drop"
+			enum: {
+				drop: "Drop the whole merged line."
+				emit_raw_parts: """
+					Emit the partial events making up the line individually, without merging them.
+
+					Parts that were already merged before the limit was reached are emitted as a single event.
+					"""
+				truncate: """
+					Emit the merged line truncated to `max_merged_line_bytes`.
+
+					The event is marked with a `truncated` metadata field set to `true`.
+					"""
+			}
+		}
+	}
+	parser_mode: {
+		description: "How the format of the log files is determined."
+		required:    false
+		type: string: {
+			default: "detect_once"
+			enum: {
+				auto: """
+					Detect the log format of each file separately, from the first line read from it.
+
+					This is useful in clusters running a mix of container runtimes, for example while
+					migrating from Docker to containerd or CRI-O.
+					"""
+				cri:         "Parse all files using the CRI log format, as written by containerd or CRI-O."
+				detect_once: "Detect the log format from the first line read by the source, and use it for all files."
+				docker:      "Parse all files as written by the Docker `json-file` log driver."
+			}
+		}
+	}
 	pod_annotation_fields: {
 		description: "Configuration for how the events are enriched with Pod metadata."
 		required:    false
@@ -428,6 +541,100 @@ generated: components: sources: kubernetes_logs: configuration: {
 			}
 		}
 	}
+	pod_filter: {
+		description: """
+			Configuration for filtering the Pods and the containers whose logs are collected.
+
+			The rules are evaluated against the metadata of the Pods before their log files are discovered,
+			so the logs of the filtered out Pods and containers are never read. An exclusion rule always
+			takes precedence over an inclusion one, and an empty list of inclusion rules includes
+			everything.
+			"""
+		required: false
+		type: object: options: {
+			exclude_containers: {
+				description: "Glob patterns matched against the name of the containers whose logs aren't collected."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["istio-*", "linkerd-proxy"]
+				}
+			}
+			exclude_namespaces: {
+				description: "Regular expressions matched against the Namespace of the Pods whose logs aren't collected."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["^kube-", "-staging$"]
+				}
+			}
+			exclude_workload_kinds: {
+				description: """
+					The kinds of the workloads whose logs aren't collected.
+
+					See `include_workload_kinds` for how the kind of the workload of a Pod is derived.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["DaemonSet", "Job"]
+				}
+			}
+			include_containers: {
+				description: "Glob patterns matched against the name of the containers whose logs are collected."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["app-*"]
+				}
+			}
+			include_namespaces: {
+				description: "Regular expressions matched against the Namespace of the Pods whose logs are collected."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["^team-"]
+				}
+			}
+			include_workload_kinds: {
+				description: """
+					The kinds of the workloads whose logs are collected.
+
+					The kind of the workload of a Pod is the kind of its controller, as found in its owner
+					references, where the Pods of the `ReplicaSet`s managed by a `Deployment` have the
+					`Deployment` kind, and the Pods without a controller have the `Pod` kind. Kinds are matched
+					case-insensitively.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["Deployment", "StatefulSet"]
+				}
+			}
+		}
+	}
+	rate_limit_lines_per_sec: {
+		description: """
+			The maximum number of lines read per second from the logs of each container.
+
+			The lines exceeding the rate limit are discarded, so that a single container writing
+			excessive logs can't starve the others. The rate limit of the containers of a Pod can be
+			overridden with the `vector.dev/rate-limit-lines-per-sec` annotation of the Pod, where `0`
+			disables the rate limit.
+
+			The lines are counted once partial lines are merged and multiline events are aggregated, so
+			the parts of a line are kept or discarded together.
+
+			By default, the lines aren't rate limited.
+			"""
+		required: false
+		type: uint: {
+			examples: [
+				1000,
+			]
+			unit: "lines"
+		}
+	}
 	read_from: {
 		description: "File position to use when reading a new file."
 		required:    false
@@ -450,6 +657,58 @@ generated: components: sources: kubernetes_logs: configuration: {
 			unit:    "seconds"
 		}
 	}
+	routes: {
+		description: """
+			A map of route names to label selectors matched against the Pod (and optionally the
+			Namespace) each event originates from.
+
+			The names of all matching routes are added to the event under `kubernetes.routes` (or the
+			`routes` source metadata field when using the Vector log namespace), in the order they are
+			configured, so downstream components can route events without re-implementing label
+			selector matching.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				"app-a": "app=foo,tier=backend"
+			}]
+			options: "*": {
+				description: "Label selectors an event has to match for the route."
+				required:    true
+				type: {
+					object: options: {
+						namespace_label_selector: {
+							description: """
+								The [label selector][label_selector] matched against the labels of the Pod's Namespace.
+
+								Namespace labels are only available when `insert_namespace_fields` is enabled.
+
+								[label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+								"""
+							required: false
+							type: string: {
+								default: ""
+								examples: ["team=a"]
+							}
+						}
+						pod_label_selector: {
+							description: """
+								The [label selector][label_selector] matched against the labels of the Pod.
+
+								[label_selector]: https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+								"""
+							required: false
+							type: string: {
+								default: ""
+								examples: ["app=foo,tier=backend"]
+							}
+						}
+					}
+					string: {}
+				}
+			}
+		}
+	}
 	self_node_name: {
 		description: """
 			The name of the Kubernetes [Node][node] that is running.
@@ -1,6 +1,27 @@
 package metadata
 
 generated: components: sources: mqtt: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
 	client_id: {
 		description: "MQTT client ID."
 		required:    false
@@ -516,6 +537,31 @@ generated: components: sources: mqtt: configuration: {
 		required:    false
 		type: uint: default: 1883
 	}
+	protocol_version: {
+		description: "The version of the MQTT protocol used to connect to the broker."
+		required:    false
+		type: string: {
+			default: "v3.1.1"
+			enum: {
+				"v3.1.1": "MQTT 3.1.1."
+				v5:       "MQTT 5."
+			}
+		}
+	}
+	shared_group: {
+		description: """
+			The group of the [shared subscription][shared_subscriptions] to the topic.
+
+			The messages of the topic are distributed between the members of the group, instead of
+			being delivered to each of them, so that several instances of Vector share the load. The
+			broker must support shared subscriptions, which are part of MQTT 5 and supported by most
+			brokers with MQTT 3.1.1.
+
+			[shared_subscriptions]: https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901250
+			"""
+		required: false
+		type: string: examples: ["vector"]
+	}
 	tls: {
 		description: "TLS configuration."
 		required:    false
@@ -641,6 +687,19 @@ generated: components: sources: mqtt: configuration: {
 			]
 		}
 	}
+	topic_template: {
+		description: """
+			A template the topics of the messages are matched against, to extract their levels into
+			fields.
+
+			Each level of the template is either `{name}`, which extracts the level into the `name`
+			field, `+`, which matches any level, or a level the topic must be equal to. The template
+			may end with `#`, which matches the remaining levels. The fields aren't extracted from the
+			topics that don't match the template.
+			"""
+		required: false
+		type: string: examples: ["sensors/{site}/{device}/#"]
+	}
 	user: {
 		description: "MQTT username."
 		required:    false
@@ -1,6 +1,27 @@
 package metadata
 
 generated: components: sources: nats: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
 	auth: {
 		description: "Configuration of the authentication strategy when interacting with NATS."
 		required:    false
@@ -592,7 +613,8 @@ generated: components: sources: nats: configuration: {
 					Batch settings for a JetStream pull consumer.
 
 					By default, messages are pulled in batches of up to 200.
-					Each pull request expires after 30 seconds if not fulfilled.
+					Each pull request expires after 30 seconds if not fulfilled, and
+					the server sends heartbeats every 15 seconds while it is pending.
 					There is no explicit maximum byte size per batch unless specified.
 
 					**Note:** These defaults follow the `async-nats` crate’s `StreamBuilder`.
@@ -604,6 +626,22 @@ generated: components: sources: nats: configuration: {
 						required:    false
 						type: uint: default: 200
 					}
+					expires_secs: {
+						description: "The time after which a pull request expires if it isn't fulfilled, in seconds."
+						required:    false
+						type: uint: default: 30
+					}
+					idle_heartbeat_secs: {
+						description: """
+							The interval at which the server sends heartbeats while a pull request is pending, in
+							seconds.
+
+							Missing heartbeats are reported, and the pull request is sent again. This must be at
+							most half of `expires_secs`.
+							"""
+						required: false
+						type: uint: default: 15
+					}
 					max_bytes: {
 						description: """
 																The maximum total byte size for a batch. The pull request will be
@@ -619,6 +657,86 @@ generated: components: sources: nats: configuration: {
 				required:    true
 				type: string: {}
 			}
+			create_consumer: {
+				description: """
+					Creates the durable consumer if it doesn't exist.
+
+					By default, the consumer must already exist.
+					"""
+				required: false
+				type: object: options: {
+					ack_wait_secs: {
+						description: """
+							The time after which a message that wasn't acknowledged is redelivered, in seconds.
+
+							With end-to-end acknowledgements, this must be longer than the time the sinks take to
+							deliver the events.
+							"""
+						required: false
+						type: uint: default: 30
+					}
+					max_ack_pending: {
+						description: """
+							The maximum number of messages delivered and not acknowledged yet.
+
+							The server stops delivering messages once it is reached, which bounds the events in flight.
+							"""
+						required: false
+						type: int: default: 1000
+					}
+					max_deliver: {
+						description: """
+							The maximum number of times a message is delivered.
+
+							By default, the messages are redelivered until they are acknowledged.
+							"""
+						required: false
+						type: int: {}
+					}
+				}
+			}
+			create_stream: {
+				description: """
+					Creates the stream if it doesn't exist.
+
+					By default, the stream must already exist.
+					"""
+				required: false
+				type: object: options: {
+					max_age_secs: {
+						description: """
+							The maximum age of the messages kept in the stream, in seconds.
+
+							By default, the messages are kept until other limits of the stream are reached.
+							"""
+						required: false
+						type: uint: {}
+					}
+					storage: {
+						description: "The storage backend of a JetStream stream."
+						required:    false
+						type: string: {
+							default: "file"
+							enum: {
+								file:   "The messages are stored on disk."
+								memory: "The messages are stored in memory."
+							}
+						}
+					}
+					subjects: {
+						description: """
+							The subjects whose messages are stored in the stream.
+
+							By default, this is the `subject` of the component.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: examples: ["events.>"]
+						}
+					}
+				}
+			}
 			stream: {
 				description: "The name of the stream to bind to."
 				required:    true
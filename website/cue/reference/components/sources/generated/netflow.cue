@@ -0,0 +1,42 @@
+package metadata
+
+generated: components: sources: netflow: configuration: {
+	address: {
+		description: """
+			The socket address to listen for flow packets on.
+
+			The format of each packet is detected from its version, so exporters of every format can
+			send to the same address.
+			"""
+		required: false
+		type: string: {
+			default: "0.0.0.0:2055"
+			examples: ["0.0.0.0:2055", "0.0.0.0:4739", "0.0.0.0:6343", "systemd"]
+		}
+	}
+	host_key: {
+		description: """
+			Overrides the name of the log field used to add the exporter host to each event.
+
+			The value is the IP address of the host that sent the packet.
+
+			By default, the [global `log_schema.host_key` option][global_host_key] is used.
+
+			Set to `""` to suppress this key.
+
+			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+			"""
+		required: false
+		type: string: {}
+	}
+	receive_buffer_bytes: {
+		description: """
+			The size of the receive buffer used for the listening socket.
+
+			Flow exporters send bursts of packets, which may be dropped by the operating system if the
+			buffer is too small.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+}
@@ -325,6 +325,56 @@ generated: components: sources: opentelemetry: configuration: {
 			}
 		}
 	}
+	metrics: {
+		description: """
+			Configuration for the conversion of OTLP metrics into metric events.
+
+			This has no effect if `use_otlp_decoding` is enabled.
+			"""
+		required: false
+		type: object: options: {
+			cache: {
+				description: """
+					Configuration for the cache holding the accumulated values of delta metrics.
+
+					By default, series are evicted after 5 minutes of not being updated, after which their
+					accumulated value starts again from zero.
+					"""
+				required: false
+				type: object: options: {
+					max_bytes: {
+						description: "The maximum size in bytes of the events in the metrics normalizer cache, excluding cache overhead."
+						required:    false
+						type: uint: unit: "bytes"
+					}
+					max_events: {
+						description: "The maximum number of events of the metrics normalizer cache"
+						required:    false
+						type: uint: unit: "events"
+					}
+					time_to_live: {
+						description: "The maximum age of a metric not being updated before it is evicted from the metrics normalizer cache."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+			delta_to_cumulative: {
+				description: """
+					Whether to convert metrics with delta aggregation temporality into cumulative metrics.
+
+					By default, delta sums and histograms are emitted as incremental metrics. When enabled,
+					the deltas of each series are accumulated, and emitted as absolute metrics instead, as
+					required by sinks that only accept cumulative metrics.
+					"""
+				required: false
+				type: bool: default: false
+			}
+		}
+	}
 	use_otlp_decoding: {
 		description: """
 			Setting this field will override the legacy mapping of OTEL protos to Vector events and use the proto directly.
@@ -1,8 +1,29 @@
 package metadata
 
 generated: components: sources: redis: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
 	data_type: {
-		description: "The Redis data type (`list` or `channel`) to use."
+		description: "The Redis data type (`list`, `channel` or `stream`) to use."
 		required:    false
 		type: string: {
 			default: "list"
@@ -13,6 +34,14 @@ generated: components: sources: redis: configuration: {
 					This is based on Redis' Pub/Sub capabilities.
 					"""
 				list: "The `list` data type."
+				stream: """
+					The `stream` data type.
+
+					The entries are read by a consumer of a [consumer group][consumer_groups], and
+					acknowledged once their events are processed.
+
+					[consumer_groups]: https://redis.io/docs/latest/develop/data-types/streams/#consumer-groups
+					"""
 			}
 		}
 	}
@@ -531,6 +560,61 @@ generated: components: sources: redis: configuration: {
 		required: false
 		type: string: examples: ["redis_key"]
 	}
+	stream: {
+		description: "Options for the Redis `stream` data type."
+		required:    false
+		type: object: options: {
+			batch_size: {
+				description: "The maximum number of entries read at once."
+				required:    false
+				type: uint: default: 100
+			}
+			claim_min_idle_secs: {
+				description: """
+					The time after which the entries left pending by other consumers of the group are
+					claimed, in seconds.
+
+					This is how the entries delivered to consumers that were removed, or died, are
+					eventually processed.
+					"""
+				required: false
+				type: uint: default: 60
+			}
+			consumer: {
+				description: """
+					The name of the consumer in the group.
+
+					It must be unique among the running instances of Vector reading from the group, and
+					stable across their restarts, so that the entries left pending are read again. By
+					default, the hostname is used.
+					"""
+				required: false
+				type: string: examples: ["vector-0"]
+			}
+			field: {
+				description: """
+					The field of the entries that holds the message to decode.
+
+					The entries without this field are acknowledged and skipped.
+					"""
+				required: false
+				type: string: {
+					default: "message"
+					examples: ["payload"]
+				}
+			}
+			group: {
+				description: """
+					The consumer group the entries are read with.
+
+					The group, and the stream, are created if they don't exist, in which case only the
+					entries added afterwards are read.
+					"""
+				required: true
+				type: string: examples: ["vector"]
+			}
+		}
+	}
 	url: {
 		description: """
 			The Redis URL to connect to.
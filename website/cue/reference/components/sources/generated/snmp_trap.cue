@@ -0,0 +1,146 @@
+package metadata
+
+generated: components: sources: snmp_trap: configuration: {
+	address: {
+		description: """
+			The socket address to listen for notifications on.
+
+			The standard port for SNMP notifications is `162`, which is a privileged port on most
+			systems.
+			"""
+		required: false
+		type: string: {
+			default: "0.0.0.0:162"
+			examples: ["0.0.0.0:162", "systemd", "systemd#3"]
+		}
+	}
+	communities: {
+		description: """
+			The communities accepted in SNMPv1 and SNMPv2c notifications.
+
+			If empty, SNMPv1 and SNMPv2c notifications are accepted regardless of their community.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["public"]
+		}
+	}
+	host_key: {
+		description: """
+			Overrides the name of the log field used to add the peer host to each event.
+
+			The value is the IP address of the host that sent the notification, which is not
+			necessarily the agent, if the notification was forwarded.
+
+			By default, the [global `log_schema.host_key` option][global_host_key] is used.
+
+			Set to `""` to suppress this key.
+
+			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+			"""
+		required: false
+		type: string: {}
+	}
+	mib_paths: {
+		description: """
+			Paths to MIB modules, or to directories of MIB modules, used to resolve OIDs to names.
+
+			OIDs are named after their closest ancestor defined in the modules, such as
+			`IF-MIB::ifIndex.2`. The common objects of `SNMPv2-SMI`, `SNMPv2-MIB` and `IF-MIB` are
+			resolved without loading any module.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["/usr/share/snmp/mibs"]
+		}
+	}
+	receive_buffer_bytes: {
+		description: "The size of the receive buffer used for the listening socket."
+		required:    false
+		type: uint: unit: "bytes"
+	}
+	users: {
+		description: """
+			The users accepted in SNMPv3 notifications.
+
+			SNMPv3 notifications are rejected unless they are sent by one of the users, with the
+			security level of the user.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				auth: {
+					description: """
+						The authentication settings of the user.
+
+						Messages from users without authentication settings must not be authenticated.
+						"""
+					required: false
+					type: object: options: {
+						passphrase: {
+							description: """
+								The authentication passphrase of the user.
+
+								It must be at least 8 characters long.
+								"""
+							required: true
+							type: string: examples: ["${SNMP_AUTH_PASSPHRASE}"]
+						}
+						protocol: {
+							description: "The protocol used to authenticate messages."
+							required:    true
+							type: string: enum: {
+								md5:    "HMAC-MD5-96."
+								sha:    "HMAC-SHA-96."
+								sha224: "HMAC-SHA-224-128."
+								sha256: "HMAC-SHA-256-192."
+								sha384: "HMAC-SHA-384-256."
+								sha512: "HMAC-SHA-512-384."
+							}
+						}
+					}
+				}
+				name: {
+					description: "The name of the user, also known as its security name."
+					required:    true
+					type: string: examples: ["vector"]
+				}
+				privacy: {
+					description: """
+						The privacy settings of the user.
+
+						Messages from users without privacy settings must not be encrypted. Privacy requires the
+						user to have authentication settings.
+						"""
+					required: false
+					type: object: options: {
+						passphrase: {
+							description: """
+								The privacy passphrase of the user.
+
+								It must be at least 8 characters long.
+								"""
+							required: true
+							type: string: examples: ["${SNMP_PRIVACY_PASSPHRASE}"]
+						}
+						protocol: {
+							description: "The protocol used to encrypt messages."
+							required:    true
+							type: string: enum: {
+								aes: "CFB128-AES-128."
+								des: """
+									CBC-DES.
+
+									DES is considered insecure, and is only supported for compatibility with older agents.
+									"""
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+}
@@ -23,7 +23,7 @@ generated: components: sources: socket: configuration: {
 			Configures how events are decoded from raw bytes. Note some decoders can also determine the event output
 			type (log, metric, trace).
 			"""
-		required: false
+		required:      false
 		type: object: options: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
@@ -325,7 +325,7 @@ generated: components: sources: socket: configuration: {
 			a frame that must be prefixed, or delimited, in a way that marks where an event begins and
 			ends within the byte stream.
 			"""
-		required: false
+		required:      false
 		type: object: options: {
 			character_delimited: {
 				description:   "Options for the character delimited decoder."
@@ -512,7 +512,7 @@ generated: components: sources: socket: configuration: {
 
 			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
 			"""
-		required: false
+		required:      false
 		type: string: {}
 	}
 	keepalive: {
@@ -595,6 +595,25 @@ generated: components: sources: socket: configuration: {
 		required:      false
 		type: array: items: type: string: examples: ["192.168.0.0/16", "127.0.0.1/32", "::1/128", "9876:9ca3:99ab::23/128"]
 	}
+	proxy_protocol: {
+		description: """
+			Whether the connections start with a [PROXY protocol][proxy_protocol] header.
+
+			Enable this when Vector is behind a load balancer, such as HAProxy or an AWS Network Load
+			Balancer, that sends the version 1 or 2 of the header. The address and port of the original
+			client are then read from the header, and used as the `host` and `port` of the events,
+			instead of those of the load balancer. The connections that don't start with a valid
+			header are closed.
+
+			The header is read before the TLS handshake, and `permit_origin` still applies to the
+			address of the load balancer.
+
+			[proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: bool: default: false
+	}
 	port_key: {
 		description: """
 			Overrides the name of the log field used to add the peer host's port to each event.
@@ -29,7 +29,7 @@ generated: components: sources: syslog: configuration: {
 
 			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
 			"""
-		required: false
+		required:      false
 		type: string: {}
 	}
 	keepalive: {
@@ -48,7 +48,7 @@ generated: components: sources: syslog: configuration: {
 
 			Messages larger than this are truncated.
 			"""
-		required: false
+		required:      false
 		type: uint: {
 			default: 102400
 			unit:    "bytes"
@@ -83,6 +83,21 @@ generated: components: sources: syslog: configuration: {
 		required:      false
 		type: array: items: type: string: examples: ["192.168.0.0/16", "127.0.0.1/32", "::1/128", "9876:9ca3:99ab::23/128"]
 	}
+	proxy_protocol: {
+		description: """
+			Whether the connections start with a [PROXY protocol][proxy_protocol] header, as sent
+			by load balancers such as HAProxy.
+
+			The address of the original client is read from the header of the versions 1 or 2 of
+			the protocol, and used as the `source_ip` of the events instead of the address of the
+			load balancer. The connections without a valid header are closed.
+
+			[proxy_protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: bool: default: false
+	}
 	receive_buffer_bytes: {
 		description: """
 			The size of the receive buffer used for each connection.
@@ -31,6 +31,54 @@ generated: components: sources: vector: configuration: {
 		required: true
 		type: string: {}
 	}
+	http2: {
+		description: "HTTP/2 settings of the gRPC connections."
+		required:    false
+		type: object: options: {
+			adaptive_window: {
+				description: """
+					Whether to adapt the flow control windows of the connections to their bandwidth-delay
+					product.
+
+					The default windows limit the throughput of the connections with a high latency, such as the
+					ones across regions.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			keepalive_interval_secs: {
+				description: """
+					The interval between the HTTP/2 `PING` frames sent on the connections.
+
+					The pings keep the connections open through the proxies and load balancers closing the idle
+					ones, and detect the connections that are broken. If not set, no pings are sent.
+					"""
+				required: false
+				type: uint: {
+					examples: [30]
+					unit: "seconds"
+				}
+			}
+			keepalive_timeout_secs: {
+				description: "How long to wait for the acknowledgement of a ping before closing the connection."
+				required:    false
+				type: uint: {
+					default: 20
+					unit:    "seconds"
+				}
+			}
+		}
+	}
+	max_concurrent_streams: {
+		description: """
+			The maximum number of concurrent streams on each connection.
+
+			The sinks send their requests over concurrent streams of the same connection, so that a slow
+			request doesn't hold back the others. If not set, the number of streams isn't limited.
+			"""
+		required: false
+		type: uint: examples: [100]
+	}
 	tls: {
 		description: "Configures the TLS options for incoming/outgoing connections."
 		required:    false
@@ -0,0 +1,71 @@
+package metadata
+
+generated: components: sources: windows_eventlog: configuration: {
+	batch_size: {
+		description: "The maximum number of events to read from the event log at once."
+		required:    false
+		type: uint: {
+			default: 100
+			unit:    "events"
+		}
+	}
+	channels: {
+		description: """
+			The channels to read events from.
+
+			Use `wevtutil enum-logs` to list the channels of a computer.
+			"""
+		required: false
+		type: array: {
+			default: ["Application", "System"]
+			items: type: string: examples: ["System", "Application", "Security", "Microsoft-Windows-Sysmon/Operational"]
+		}
+	}
+	data_dir: {
+		description: """
+			The directory used to persist the bookmark of the last event read.
+
+			By default, the [global `data_dir` option][global_data_dir] is used.
+			Make sure the running user has write permissions to this directory.
+
+			If this directory is specified, then Vector will attempt to create it.
+
+			[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+			"""
+		required: false
+		type: string: examples: ["C:\\ProgramData\\vector\\windows_eventlog"]
+	}
+	query: {
+		description: """
+			An [XPath query][xpath] selecting the events to read from each channel.
+
+			By default, all events are read.
+
+			[xpath]: https://learn.microsoft.com/en-us/windows/win32/wes/consuming-events#xpath-10-limitations
+			"""
+		required: false
+		type: string: {
+			default: "*"
+			examples: ["*[System[Level<=3]]", "*[System[(EventID=4624 or EventID=4625)]]"]
+		}
+	}
+	read_existing_events: {
+		description: """
+			Read the events that were logged before the source started.
+
+			Only used the first time the source runs: once a bookmark has been saved, reading
+			resumes after the last event that was sent.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	render_message: {
+		description: """
+			Format the message of each event with the message table of its provider.
+
+			Formatting is skipped for providers which are not installed on the computer.
+			"""
+		required: false
+		type: bool: default: true
+	}
+}
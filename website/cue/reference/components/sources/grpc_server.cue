@@ -0,0 +1,116 @@
+package metadata
+
+components: sources: grpc_server: {
+	_port: 50051
+
+	title: "gRPC Server"
+
+	description: """
+		Receives the messages of the methods of any [gRPC](\(urls.grpc)) service, decoding them
+		with the [protobuf](\(urls.protobuf)) descriptor set of the service, so that internal
+		protocols can be ingested without generating code.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.grpc
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: enabled: false
+			keepalive: enabled:            true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.grpc_server.configuration
+
+	output: logs: message: {
+		description: "A message received by one of the methods."
+		fields: {
+			grpc_method: {
+				description: "The fully qualified name of the method the message was received by."
+				required:    true
+				type: string: {
+					examples: ["package.Service.Push"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["grpc_server"]
+				}
+			}
+			timestamp: fields._current_timestamp
+			"*": {
+				description: "The fields of the message, with their protobuf names."
+				required:    true
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		descriptors: {
+			title: "Descriptors"
+			body: """
+				The service is described by a descriptor set, which `protoc` outputs with
+				`protoc --include_imports -o service.desc service.proto`. The source serves the
+				configured `methods` of the `service`, and decodes their request messages into events
+				with these descriptors.
+
+				The unary methods send an event for their request message. The client streaming methods
+				send an event for each message of the stream, as it's received. The server and
+				bidirectional streaming methods aren't supported.
+				"""
+		}
+		responses: {
+			title: "Responses"
+			body: """
+				Every request is answered with the `response` message, converted from its JSON mapping
+				to the response message of the method. With acknowledgements enabled, the response is
+				sent once the events of the request are delivered, and requests whose events can't be
+				delivered are answered with an error status.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		grpc_server_handler_duration_seconds: components.sources.internal_metrics.output.metrics.grpc_server_handler_duration_seconds
+		grpc_server_messages_received_total:  components.sources.internal_metrics.output.metrics.grpc_server_messages_received_total
+		grpc_server_messages_sent_total:      components.sources.internal_metrics.output.metrics.grpc_server_messages_sent_total
+	}
+}
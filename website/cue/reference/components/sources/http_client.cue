@@ -144,6 +144,55 @@ components: sources: http_client: {
 	}
 
 	how_it_works: {
+		oauth2: {
+			title: "OAuth2 authentication"
+			body: """
+				SaaS APIs such as Salesforce can be polled with OAuth2 client credentials. An access
+				token is requested from the token endpoint, sent as a bearer token with the requests,
+				and requested again before it expires or when a request is rejected as unauthorized.
+
+				```yaml
+				sources:
+					salesforce:
+						type: http_client
+						endpoint: https://example.my.salesforce.com/services/data/v60.0/query?q=SELECT+Id+FROM+EventLogFile
+						oauth2:
+							token_endpoint: https://example.my.salesforce.com/services/oauth2/token
+							client_id: ${SALESFORCE_CLIENT_ID}
+							client_secret: ${SALESFORCE_CLIENT_SECRET}
+				```
+
+				When the authorization server authenticates clients with mutual TLS, the
+				`client_secret` can be omitted: the certificate set in `tls.crt_file` is presented to
+				the token endpoint as well.
+				"""
+		}
+		pagination: {
+			title: "Pagination"
+			body: """
+				APIs that return their results over several pages can be followed with the `pagination`
+				option. Each scrape requests the pages one after the other, until a page has no events
+				or there is no next page.
+
+				With the `link_header` strategy, the `next` link of the `Link` header of the responses is
+				followed. With the `cursor` strategy, the cursor is read at `cursor_path` in the JSON body
+				of the responses and sent in the `query_parameter` of the next request.
+
+				```yaml
+				sources:
+					audit_logs:
+						type: http_client
+						endpoint: https://api.example.com/audit/events
+						pagination:
+							strategy: cursor
+							cursor_path: meta.next_cursor
+							query_parameter: cursor
+				```
+
+				The position of the next page is persisted in the `data_dir`, so that the next scrapes,
+				and Vector after a restart, continue from the last page instead of the first one.
+				"""
+		}
 		query_params_structure: {
 			title: "Query params structure"
 			body: """
@@ -210,5 +210,39 @@ components: sources: http_server: {
 				Supported algorithms are `gzip`, `deflate`, `snappy`, and `zstd`.
 				"""
 		}
+		multipart: {
+			title: "Multipart uploads"
+			body: """
+				The requests with a `multipart/form-data` body are decoded part by part, while they're
+				received. Only the parts that are files are decoded, with the configured `framing` and
+				`decoding`, and the other fields of the form are skipped. The events decoded from a file
+				have the name of its form field in `multipart_field`, and its name in `file_name`.
+				"""
+		}
+		streaming: {
+			title: "Streaming bodies"
+			body: """
+				With `stream_chunked_bodies` set to `true`, the bodies sent with `Transfer-Encoding: chunked`
+				are decoded while they're received, and their events are sent as the chunks arrive, such as
+				the lines of an NDJSON body. The response is only sent once the whole body is received.
+
+				The size of the bodies can be limited with `max_body_size`. The requests whose body is larger
+				are rejected with a `413 Payload Too Large` response.
+				"""
+		}
+		overload: {
+			title: "Overload protection"
+			body: """
+				When the components downstream of this source can't keep up, events accumulate in the
+				buffer of the source, and requests take longer to complete, until clients time out.
+
+				With `overload.enabled` set to `true`, requests are rejected with a `429 Too Many Requests`
+				response while more than `overload.threshold` of the buffer is in use. The response has a
+				`Retry-After` header estimating how long it takes for the buffer to drain, based on how fast
+				it drained recently, and bounded by `overload.min_retry_after_secs` and
+				`overload.max_retry_after_secs`. This lets well-behaved clients back off until the source
+				catches up.
+				"""
+		}
 	}
 }
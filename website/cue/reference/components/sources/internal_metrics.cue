@@ -54,6 +54,12 @@ components: sources: internal_metrics: {
 		}
 
 		// Instance-level "process" metrics
+		acknowledgement_timeouts_total: {
+			description:       "The number of batches of events that a sink held for longer than its `acknowledgement_timeout_secs` without acknowledging them."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		active_clients: {
 			description:       "Number of clients attached to a component."
 			type:              "gauge"
@@ -133,25 +139,62 @@ components: sources: internal_metrics: {
 			description:       "The average round-trip time (RTT) for the current window."
 			type:              "histogram"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _adaptive_concurrency_tags
+		}
+		adaptive_concurrency_health: {
+			description:       "The health score, between 0 and 1, of the service whose concurrency is adapted, from the fraction of its recent responses that were successful and not slower than expected. Balancers prefer the healthier endpoints."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _adaptive_concurrency_tags
 		}
 		adaptive_concurrency_in_flight: {
 			description:       "The number of outbound requests currently awaiting a response."
 			type:              "histogram"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _adaptive_concurrency_tags
 		}
 		adaptive_concurrency_limit: {
 			description:       "The concurrency limit that the adaptive concurrency feature has decided on for this current window."
 			type:              "histogram"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _adaptive_concurrency_tags
 		}
 		adaptive_concurrency_observed_rtt: {
 			description:       "The observed round-trip time (RTT) for requests."
 			type:              "histogram"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _adaptive_concurrency_tags
+		}
+		cardinality_limit_active_series: {
+			description:       "The number of active series tracked by a limit of a `cardinality_limit` transform."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				limit: {
+					description: "The name of the limit."
+					required:    true
+				}
+			}
+		}
+		cardinality_limit_exceeded_total: {
+			description:       "The total number of events of new series that exceeded a limit of a `cardinality_limit` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				action: {
+					description: "The action taken for the event."
+					required:    true
+					enum: {
+						drop_event: "The event was dropped."
+						drop_tag:   "Tags were dropped from the event."
+						overflow:   "The event was folded into an overflow series."
+					}
+				}
+				limit: {
+					description: "The name of the limit."
+					required:    true
+				}
+			}
 		}
 		checkpoints_total: {
 			description:       "The total number of files checkpointed."
@@ -223,6 +266,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		dedupe_estimated_cardinality: {
+			description:       "The estimated number of distinct events seen by a `dedupe` transform in probabilistic mode since the cache was last rotated."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		k8s_format_picker_edge_cases_total: {
 			description:       "The total number of edge cases encountered while picking format of the Kubernetes log message."
 			type:              "counter"
@@ -235,6 +284,25 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kubernetes_logs_rate_limited_lines_total: {
+			description:       "The total number of lines discarded for exceeding the rate limit of their container."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				pod_namespace: {
+					description: "The namespace of the pod from which the lines originated."
+					required:    true
+				}
+				pod_name: {
+					description: "The name of the pod from which the lines originated."
+					required:    true
+				}
+				container_name: {
+					description: "The name of the container from which the lines originated."
+					required:    true
+				}
+			}
+		}
 		events_discarded_total: {
 			description:       "The total number of events discarded by this component."
 			type:              "counter"
@@ -243,6 +311,12 @@ components: sources: internal_metrics: {
 				reason: _reason
 			}
 		}
+		buffer_active_stage: {
+			description:       "The index of the last stage of this buffer holding events, which is the stage that the events overflowed to."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		buffer_byte_size: {
 			description:       "The number of bytes current in the buffer."
 			type:              "gauge"
@@ -255,12 +329,24 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffer_compactions_total: {
+			description:       "The number of times a data file of this disk buffer was compacted to reclaim the disk space of already processed events."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		buffer_discarded_events_total: {
 			description:       "The number of events dropped by this non-blocking buffer."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffer_reclaimed_bytes_total: {
+			description:       "The number of bytes of disk space reclaimed by compacting this disk buffer."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		buffer_received_event_bytes_total: {
 			description:       "The number of bytes received by this buffer."
 			type:              "counter"
@@ -291,6 +377,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffer_utilization: {
+			description:       "The ratio of the capacity of this buffer stage that is in use, between `0` and `1`."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		component_build_duration_seconds: {
+			description:       "The time taken to build this component when loading or reloading the configuration."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		component_discarded_events_total: {
 			description:       "The number of events dropped by this component."
 			type:              "counter"
@@ -550,6 +648,39 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		geoip_database_age_seconds: {
+			description:       "The time elapsed since the automatically updated database of a `geoip` enrichment table was built."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags: _internal_metrics_tags & {
+				edition_id: {
+					description: "The MaxMind edition ID of the database."
+					required:    true
+				}
+			}
+		}
+		geoip_database_updates_total: {
+			description:       "The total number of times the database of a `geoip` enrichment table was automatically updated."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _internal_metrics_tags & {
+				edition_id: {
+					description: "The MaxMind edition ID of the database."
+					required:    true
+				}
+			}
+		}
+		geoip_database_update_errors_total: {
+			description:       "The total number of failed automatic updates of the database of a `geoip` enrichment table."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _internal_metrics_tags & {
+				edition_id: {
+					description: "The MaxMind edition ID of the database."
+					required:    true
+				}
+			}
+		}
 		files_unwatched_total: {
 			description:       "The total number of times Vector has stopped watching a file."
 			type:              "counter"
@@ -558,12 +689,34 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		files_lag_bytes: {
+			description:       "The number of bytes written to the Pod log files watched by the `kubernetes_logs` source that have not been read yet, by read priority."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				priority: {
+					description: "The read priority of the files."
+					required:    true
+					enum: {
+						high:   "Files read with a high priority."
+						low:    "Files read with a low priority."
+						normal: "Files read with the default priority."
+					}
+				}
+			}
+		}
 		open_files: {
 			description:       "The total number of open files."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		oldest_unacknowledged_event_age_seconds: {
+			description:       "The age of the oldest event that a sink with `acknowledgement_timeout_secs` holds without having acknowledged it."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		grpc_server_messages_received_total: {
 			description:       "The total number of gRPC messages received."
 			type:              "counter"
@@ -694,6 +847,60 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		remap_pattern_cache_hits_total: {
+			description:       "The total number of patterns only known at runtime that were found in the cache of a `remap` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				cache: {
+					description: "The kind of patterns looked up."
+					required:    true
+					enum: {
+						date_format: "The formats of `parse_timestamp_dynamic`."
+						grok:        "The grok patterns of `parse_grok_dynamic`."
+						regex:       "The regexes of `match_dynamic`."
+					}
+				}
+			}
+		}
+		remap_pattern_cache_misses_total: {
+			description:       "The total number of patterns only known at runtime that had to be parsed by a `remap` transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				cache: {
+					description: "The kind of patterns looked up."
+					required:    true
+					enum: {
+						date_format: "The formats of `parse_timestamp_dynamic`."
+						grok:        "The grok patterns of `parse_grok_dynamic`."
+						regex:       "The regexes of `match_dynamic`."
+					}
+				}
+			}
+		}
+		route_matched_events_total: {
+			description:       "The total number of events matching the condition of a route of a `route` transform with `route_metrics` enabled."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				route: {
+					description: "The identifier of the route."
+					required:    true
+				}
+			}
+		}
+		route_unmatched_events_total: {
+			description:       "The total number of events not matching the condition of a route of a `route` transform with `route_metrics` enabled."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				route: {
+					description: "The identifier of the route."
+					required:    true
+				}
+			}
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -706,6 +913,27 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		splunk_ack_latency_seconds: {
+			description:       "The time between receiving a Splunk HEC indexer acknowledgement ID and its confirmation of delivery."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		splunk_channel_rotations_total: {
+			description:       "The total number of times a Splunk HEC sink started sending requests on a new channel."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				reason: {
+					description: "The reason the channel was rotated."
+					required:    true
+					enum: {
+						full: "The channel reached `max_pending_acks_per_channel` pending acknowledgements."
+						lost: "Splunk lost the channel, for example when an indexer restarted."
+					}
+				}
+			}
+		}
 		splunk_pending_acks: {
 			description:       "The number of outstanding Splunk HEC indexer acknowledgement acks."
 			type:              "gauge"
@@ -912,6 +1140,13 @@ components: sources: internal_metrics: {
 			component_type: _component_type
 		}
 
+		_adaptive_concurrency_tags: _component_tags & {
+			endpoint: {
+				description: "The endpoint the requests are sent to, when the concurrency of each endpoint is adapted separately."
+				required:    false
+			}
+		}
+
 		// All available tags
 		_collector: {
 			description: "Which collector this metric comes from."
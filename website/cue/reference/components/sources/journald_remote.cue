@@ -0,0 +1,131 @@
+package metadata
+
+components: sources: journald_remote: {
+	_port: 19532
+
+	title: "Journald Remote"
+
+	description: """
+		Receives journal entries from hosts running `systemd-journal-upload`, using the systemd journal
+		remote protocol, without an intermediate `systemd-journal-remote` daemon.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		auto_generated:   true
+		has_auth:         true
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.journald
+
+				interface: socket: {
+					api: {
+						title: "Journal Export Format"
+						url:   urls.journal_export_format
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.journald_remote.configuration
+
+	output: logs: event: {
+		description: "A journal entry uploaded by `systemd-journal-upload`."
+		fields: {
+			host: {
+				description: "The `_HOSTNAME` field of the journal entry."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["web-1"]
+				}
+			}
+			message: {
+				description: "The `MESSAGE` field of the journal entry."
+				required:    true
+				type: string: {
+					examples: ["Started nginx.service."]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["journald_remote"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time at which the entry was written to the journal of the uploading host."
+			}
+			"*": {
+				common:      false
+				description: "Any other field of the journal entry. Fields occurring multiple times in an entry are collected into an array."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["nginx.service", "c36e9ea52800a19d214cb71b53263a28"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		journal_upload: {
+			title: "Uploading journals"
+			body: """
+				Configure `systemd-journal-upload` on each host to upload to this source, for example in
+				`/etc/systemd/journal-upload.conf`:
+
+				```ini
+				[Upload]
+				URL=https://vector.example.com:19532
+				```
+
+				Entries are uploaded in the journal export format with `POST` requests to the `/upload`
+				path, and `systemd-journal-upload` keeps track of the last uploaded entry itself. When
+				acknowledgements are enabled, uploads are only answered once their entries are
+				delivered, so entries are retried by `systemd-journal-upload` if delivery fails.
+
+				As with `systemd-journal-remote`, TLS client certificates can be required by setting
+				`tls.verify_certificate` together with a `tls.ca_file`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		http_server_handler_duration_seconds: components.sources.internal_metrics.output.metrics.http_server_handler_duration_seconds
+		http_server_requests_received_total:  components.sources.internal_metrics.output.metrics.http_server_requests_received_total
+		http_server_responses_sent_total:     components.sources.internal_metrics.output.metrics.http_server_responses_sent_total
+	}
+}
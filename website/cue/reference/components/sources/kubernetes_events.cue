@@ -0,0 +1,136 @@
+package metadata
+
+components: sources: kubernetes_events: {
+	title: "Kubernetes Events"
+
+	description: """
+		Collects Events from the Kubernetes Events API (`events.k8s.io/v1`), emitting each
+		one as a structured log.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.kubernetes
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				[Kubernetes](\(urls.kubernetes)) version `\(services.kubernetes.versions)` is required.
+				""",
+			"""
+				This source requires permissions to `list` and `watch` the `events` resource of the
+				`events.k8s.io` API group.
+				""",
+		]
+		warnings: [
+			"""
+				This source watches Events across the whole cluster, so it should only run on a single
+				instance to avoid emitting duplicated events.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: "kubernetes"
+	}
+
+	configuration: generated.components.sources.kubernetes_events.configuration
+
+	output: logs: event: {
+		description: "A Kubernetes Event."
+		fields: {
+			message: {
+				description: "The human-readable description of the Event."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["Back-off restarting failed container"]
+				}
+			}
+			reason: {
+				description: "Why the action was taken."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["BackOff"]
+				}
+			}
+			type: {
+				description: "The type of the Event."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["Normal", "Warning"]
+				}
+			}
+			involved_object: {
+				description: "The object this Event is about."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"kind": "Pod", "name": "my-pod", "namespace": "default"}]
+				}
+			}
+			count: {
+				description: "The number of times this Event has been observed."
+				required:    true
+				type: uint: {
+					examples: [1, 5]
+					unit: null
+				}
+			}
+			first_timestamp: {
+				description: "The time at which the Event was first observed."
+				required:    false
+				common:      true
+				type: timestamp: {}
+			}
+			last_timestamp: {
+				description: "The time at which the Event was most recently observed."
+				required:    false
+				common:      true
+				type: timestamp: {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["kubernetes_events"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time at which the Event was most recently observed."
+			}
+		}
+	}
+
+	how_it_works: {
+		deduplication: {
+			title: "Deduplication"
+			body: """
+				The Events API is re-listed on startup and whenever the watch has to be resynchronized.
+				Only listed Events newer than the most recently observed one are emitted, unless
+				`include_existing` is set, in which case all Events present on startup are emitted.
+				"""
+		}
+	}
+}
@@ -348,6 +348,65 @@ components: sources: kubernetes_logs: {
 					"""
 		}
 
+		pod_filtering: {
+			title: "Pod filtering"
+			body:  """
+					Beyond the label and field selectors, the `pod_filter` option selects the Pods
+					and the Containers whose logs are collected by their Namespace, the kind of
+					their workload, and the name of their Containers:
+
+					```yaml
+					pod_filter:
+					  exclude_namespaces: ["^kube-"]
+					  exclude_workload_kinds: ["Job"]
+					  exclude_containers: ["istio-*"]
+					```
+
+					The kind of the workload of a Pod is derived from its owner references, so the
+					Pods of a `Deployment` have the `Deployment` kind and the Pods without a
+					controller have the `Pod` kind. The rules are evaluated against the cached
+					metadata of the Pods before their log files are discovered.
+					"""
+		}
+
+		pod_priority: {
+			title: "Pod priority"
+			body:  """
+					When Vector falls behind, it reads from all the log files in turn. Add an
+					**annotation** `vector.dev/priority` to a Pod to change the share of read
+					bandwidth its logs get while Vector is catching up:
+
+					```yaml
+					vector.dev/priority: "high"
+					```
+
+					Files of `high` priority Pods are read up to four times `max_read_bytes` per
+					pass, while files of `low` priority Pods are read up to half of it. Any other
+					value is treated as `normal`. The `files_lag_bytes` internal metric reports how
+					far behind Vector is for each priority.
+					"""
+		}
+
+		rate_limiting: {
+			title: "Rate limiting"
+			body:  """
+					Set `rate_limit_lines_per_sec` to limit the number of lines read per second
+					from the logs of each Container, so that a single Pod writing excessive logs
+					can't starve the others. Add an **annotation**
+					`vector.dev/rate-limit-lines-per-sec` to a Pod to override the rate limit of
+					its Containers, or to disable it with `0`:
+
+					```yaml
+					vector.dev/rate-limit-lines-per-sec: "100"
+					```
+
+					The lines are counted once partial lines are merged and multiline events
+					are aggregated, so the parts of a line are kept or discarded together. The
+					lines exceeding the rate limit are discarded, and counted every second by
+					the `kubernetes_logs_rate_limited_lines_total` internal metric.
+					"""
+		}
+
 		kubernetes_api_communication: {
 			title: "Kubernetes API communication"
 			body:  """
@@ -473,7 +532,9 @@ components: sources: kubernetes_logs: {
 	}
 
 	telemetry: metrics: {
-		k8s_format_picker_edge_cases_total:     components.sources.internal_metrics.output.metrics.k8s_format_picker_edge_cases_total
-		k8s_docker_format_parse_failures_total: components.sources.internal_metrics.output.metrics.k8s_docker_format_parse_failures_total
+		files_lag_bytes:                          components.sources.internal_metrics.output.metrics.files_lag_bytes
+		k8s_format_picker_edge_cases_total:       components.sources.internal_metrics.output.metrics.k8s_format_picker_edge_cases_total
+		k8s_docker_format_parse_failures_total:   components.sources.internal_metrics.output.metrics.k8s_docker_format_parse_failures_total
+		kubernetes_logs_rate_limited_lines_total: components.sources.internal_metrics.output.metrics.kubernetes_logs_rate_limited_lines_total
 	}
 }
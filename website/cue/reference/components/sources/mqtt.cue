@@ -5,7 +5,7 @@ components: sources: mqtt: {
 
 	features: {
 		auto_generated:   true
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			from: {
@@ -30,7 +30,7 @@ components: sources: mqtt: {
 	classes: {
 		commonly_used: false
 		deployment_roles: ["aggregator"]
-		delivery:      "best_effort"
+		delivery:      "at_least_once"
 		development:   "beta"
 		egress_method: "stream"
 		stateful:      false
@@ -90,7 +90,37 @@ components: sources: mqtt: {
 		}
 	}
 
-	how_it_works: components._amqp.how_it_works
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				The source subscribes to the topic with the QoS 1. When end-to-end acknowledgements are
+				enabled, the messages are acknowledged once their events are delivered by the sinks, in
+				the order they were received. The messages whose events failed to be delivered aren't
+				acknowledged, and are delivered again by the broker when the source reconnects, as its
+				session is kept by the broker.
+				"""
+		}
+		shared_subscriptions: {
+			title: "Shared subscriptions"
+			body:  """
+				When `shared_group` is set, the source subscribes to the topic through a
+				[shared subscription](\(urls.mqtt_shared_subscriptions)), and the broker distributes the
+				messages between the instances of Vector that are members of the same group, each with
+				its own `client_id`.
+				"""
+		}
+		topic_template: {
+			title: "Topic fields"
+			body:  """
+				The levels of the topics can be extracted into fields with `topic_template`. For
+				example, with `topic_template` set to `sensors/{site}/{device}/#`, the messages
+				published to `sensors/paris/d42/temperature` have the `site` field set to `paris` and
+				the `device` field set to `d42`. With the `vector` log namespace, the fields are added
+				to the `topic_fields` object of the metadata of the events.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		open_connections:                 components.sources.internal_metrics.output.metrics.open_connections
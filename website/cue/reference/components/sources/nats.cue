@@ -5,7 +5,7 @@ components: sources: nats: {
 
 	features: {
 		auto_generated:   true
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			from: components._nats.features.collect.from
@@ -76,5 +76,21 @@ components: sources: nats: {
 		}
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body:  """
+				When `jetstream` is set, the messages are pulled from a durable consumer of a
+				[JetStream](\(urls.nats_jetstream)) stream, and acknowledged once their events are
+				processed. With end-to-end acknowledgements, this waits for the events to be delivered by
+				the sinks: the messages whose events failed to be delivered are redelivered, and the
+				messages whose events were rejected are terminated. The messages that fail to be decoded
+				aren't acknowledged, and are redelivered after the `ack_wait` of the consumer.
+
+				The stream and the consumer can be created by the source with `jetstream.create_stream`
+				and `jetstream.create_consumer`. The consumer's `max_ack_pending` bounds the messages in
+				flight, which provides flow control when the sinks are slower than the stream.
+				"""
+		}
+	}
 }
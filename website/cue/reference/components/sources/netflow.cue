@@ -0,0 +1,269 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	description: """
+		Receives flow records from routers, switches and firewalls, in the NetFlow v5, NetFlow v9,
+		IPFIX and sFlow v5 formats, and emits one log per flow with the same field names for all
+		formats.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.netflow
+
+				interface: socket: {
+					api: {
+						title: "IPFIX"
+						url:   urls.ipfix
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.netflow.configuration
+
+	output: logs: flow: {
+		description: """
+			A flow record. Only the fields sent by the exporter are set, so NetFlow v9 and IPFIX
+			records have the fields of their template.
+			"""
+		fields: {
+			bytes: {
+				description: "The number of bytes of the flow. For sFlow, the length of the sampled packet."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [1500]
+					unit: "bytes"
+				}
+			}
+			dst_addr: {
+				description: "The destination IP address."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["10.0.0.2", "2001:db8::2"]
+				}
+			}
+			dst_as: {
+				description: "The destination autonomous system number."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [15169]
+					unit: null
+				}
+			}
+			dst_port: {
+				description: "The destination port."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [443]
+					unit: null
+				}
+			}
+			flow_end: {
+				description: "The time of the last packet of the flow."
+				required:    false
+				type: timestamp: {}
+			}
+			flow_start: {
+				description: "The time of the first packet of the flow."
+				required:    false
+				type: timestamp: {}
+			}
+			flow_type: {
+				description: "The format of the flow record."
+				required:    true
+				type: string: {
+					enum: {
+						netflow_v5: "NetFlow v5"
+						netflow_v9: "NetFlow v9"
+						ipfix:      "IPFIX"
+						sflow_v5:   "sFlow v5"
+					}
+				}
+			}
+			host: {
+				description: "The IP address of the exporter that sent the packet."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+				}
+			}
+			input_interface: {
+				description: "The index of the interface the flow was received on."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [3]
+					unit: null
+				}
+			}
+			output_interface: {
+				description: "The index of the interface the flow was sent on."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [4]
+					unit: null
+				}
+			}
+			packets: {
+				description: "The number of packets of the flow."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [5]
+					unit: null
+				}
+			}
+			protocol: {
+				description: "The IP protocol number, such as `6` for TCP and `17` for UDP."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [6]
+					unit: null
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the packet, used by exporters to detect lost packets."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["netflow"]
+				}
+			}
+			src_addr: {
+				description: "The source IP address."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["10.0.0.1", "2001:db8::1"]
+				}
+			}
+			src_as: {
+				description: "The source autonomous system number."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [64512]
+					unit: null
+				}
+			}
+			src_port: {
+				description: "The source port."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [1024]
+					unit: null
+				}
+			}
+			tcp_flags: {
+				description: "The union of the TCP flags of the packets of the flow."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [27]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp
+			tos: {
+				description: "The type of service byte of the IP header."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [0]
+					unit: null
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		formats: {
+			title: "Formats"
+			body: """
+				The format of each packet is detected from its version, so a single source can receive
+				flows from exporters of every format. The common ports are `2055` for NetFlow, `4739`
+				for IPFIX and `6343` for sFlow.
+
+				Besides the fields above, each format adds fields of its own:
+
+				* NetFlow v5 records have the `engine_type`, `engine_id`, `sampling_interval`,
+				  `next_hop` and `src_mask`/`dst_mask` of the exporter.
+				* NetFlow v9 records have the `source_id` of the exporter, and IPFIX records its
+				  `observation_domain_id`.
+				* [sFlow](\(urls.sflow)) records have the `agent_address`, `sub_agent_id`,
+				  `sampling_rate`, `sample_pool` and `drops` of the agent, and the fields decoded from the
+				  header of the sampled packet, such as `src_mac`, `dst_mac` and `vlan_id`. Counter
+				  samples are skipped.
+				"""
+		}
+		templates: {
+			title: "Templates"
+			body: """
+				The records of [NetFlow v9](\(urls.netflow_v9)) and [IPFIX](\(urls.ipfix)) are
+				described by templates, which exporters send periodically. Templates are cached for each
+				exporter and observation domain, and records received before their template are
+				discarded. The records of options templates, which describe the exporter rather than
+				flows, are skipped.
+
+				The fields of templates are named after the [information elements](\(urls.ipfix_information_elements)) they
+				contain, using the same names as the fields of NetFlow v5, such as `src_addr` for both
+				`sourceIPv4Address` and `sourceIPv6Address`. Fields which are not known are kept as
+				colon separated hexadecimal, named `field_<id>`, or `field_<enterprise>_<id>` for
+				enterprise-specific fields.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_bytes: components.sources.internal_metrics.output.metrics.component_received_bytes
+	}
+}
@@ -294,6 +294,7 @@ components: sources: opentelemetry: {
 			body: """
 				Metrics support is experimental and subject to change due to structural differences between the internal Vector metric data model and OpenTelemetry.
 				If aggregation temporality is supported for an OpenTelemetry metric type, it influences the corresponding Vector metric kind as follows: If temporality is set to Delta, the metric kind is Incremental; otherwise, it is Absolute.
+				If `metrics.delta_to_cumulative` is enabled, Delta metrics are accumulated per series instead, and emitted as Absolute metrics. This is useful for sinks that only accept cumulative metrics.
 				Metric type mappings:
 				Gauge is mapped to a Vector Gauge;
 				Sum is mapped to a Vector Counter if `is_monotonic` is true, to Vector Gauge if `is_monotonic` is false;
@@ -0,0 +1,169 @@
+package metadata
+
+components: sources: postgres_cdc: {
+	title: "PostgreSQL CDC"
+
+	description: """
+		Captures the changes to the tables of a [PostgreSQL](\(urls.postgresql)) publication
+		with [logical replication](\(urls.postgresql_logical_replication)), emitting each
+		inserted, updated, or deleted row as a structured log.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.postgres
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp", "unix"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				PostgreSQL 11 or later is required, with `wal_level` set to `logical`.
+				""",
+			"""
+				The user must have the `REPLICATION` attribute, and the publication must already exist,
+				for example with `CREATE PUBLICATION vector FOR TABLE users, orders`.
+				""",
+		]
+		warnings: [
+			"""
+				The replication slot retains the write-ahead log until its changes are confirmed, so a
+				slot that is no longer read must be dropped with `pg_drop_replication_slot`.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.postgres_cdc.configuration
+
+	output: logs: change: {
+		description: "A change to a row."
+		fields: {
+			op: {
+				description: "The operation that changed the row."
+				required:    true
+				type: string: enum: {
+					insert: "The row was inserted."
+					update: "The row was updated."
+					delete: "The row was deleted."
+				}
+			}
+			schema: {
+				description: "The schema of the table."
+				required:    true
+				type: string: examples: ["public"]
+			}
+			table: {
+				description: "The table of the row."
+				required:    true
+				type: string: examples: ["users"]
+			}
+			before: {
+				description: """
+					The row before an update or a delete. Only the columns of the replica identity of
+					the table are included, unless it is set to `FULL`. For updates, it is only set when
+					the replica identity is `FULL` or was changed by the update.
+					"""
+				required: false
+				common:   true
+				type: object: {
+					examples: [{"id": 1}]
+				}
+			}
+			after: {
+				description: """
+					The row after an insert or an update. The TOASTed columns that an update didn't
+					change aren't included.
+					"""
+				required: false
+				common:   true
+				type: object: {
+					examples: [{"id": 1, "name": "Alice"}]
+				}
+			}
+			lsn: {
+				description: "The log sequence number of the change."
+				required:    true
+				type: string: examples: ["0/16B3748"]
+			}
+			xid: {
+				description: "The ID of the transaction of the change."
+				required:    true
+				type: uint: {
+					examples: [735]
+					unit: null
+				}
+			}
+			commit_timestamp: {
+				description: "The time at which the transaction of the change was committed."
+				required:    true
+				type: timestamp: {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["postgres_cdc"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time at which the transaction of the change was committed."
+			}
+		}
+	}
+
+	how_it_works: {
+		replication_slot: {
+			title: "Replication slot"
+			body: """
+				The changes are read from the replication slot with the `pgoutput` plugin, in whole
+				transactions, and the slot is only advanced past them once their events are
+				acknowledged, so no change is lost when Vector restarts. When the events aren't
+				delivered, the changes are read again.
+				"""
+		}
+		publication_changes: {
+			title: "Publication changes"
+			body: """
+				The tables added to the publication are captured from their next change, and the tables
+				removed from it stop being captured, without restarting Vector. The changes to the
+				columns of the tables are picked up as well.
+				"""
+		}
+		values: {
+			title: "Values"
+			body: """
+				The values of the `boolean`, integer, floating-point, `json`, `jsonb`, and timestamp
+				columns are converted to the matching Vector types, while the values of the other
+				columns, such as `numeric` ones, are kept as strings in the text format of PostgreSQL.
+				"""
+		}
+	}
+}
@@ -82,6 +82,21 @@ components: sources: prometheus_remote_write: {
 				but will only take the last value for each tag name specified.
 				"""
 		}
+
+		metadata_and_exemplars: {
+			title: "Metadata and exemplars"
+			body: """
+				The metadata of the metric families and the exemplars of the series sent
+				along with the samples are kept in the metadata of the metrics, under
+				`%prometheus_remote_write`. The `type`, `help` and `unit` fields hold the
+				metadata of the family of the metric, and the `exemplars` field the exemplars
+				of its series, each with its `labels`, `value` and `timestamp`. The exemplars
+				of a series go along with the metric of its latest sample.
+
+				The `prometheus_remote_write` sink sends them along with the metrics, so that
+				Vector can sit between Prometheus and a backend without losing them.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -5,7 +5,7 @@ components: sources: redis: {
 
 	features: {
 		auto_generated:   true
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			tls: enabled:        false
@@ -73,6 +73,15 @@ components: sources: redis: {
 						examples: ["redis"]
 					}
 				}
+				id: {
+					description: "The ID of the stream entry the event came from, with the `stream` data type."
+					required:    false
+					common:      false
+					type: string: {
+						examples: ["1526919030474-55"]
+						default: null
+					}
+				}
 				redis_key: {
 					description: "The Redis key the event came from"
 					required:    false
@@ -101,5 +110,21 @@ components: sources: redis: {
 				API.
 				"""
 		}
+		streams: {
+			title: "Streams"
+			body:  """
+				With the `stream` data type, the entries are read with `XREADGROUP` as a consumer of the
+				configured [consumer group](\(urls.redis_streams)), so that several instances of Vector can
+				share the entries of a stream.
+
+				Each entry is acknowledged with `XACK` once its events are processed. When end-to-end
+				acknowledgements are enabled, this waits for the events to be delivered by the sinks, and
+				the entries whose events failed to be delivered are left pending.
+
+				On startup, the entries left pending for the consumer are read again. The entries left
+				pending by other consumers for longer than `stream.claim_min_idle_secs` are claimed with
+				`XAUTOCLAIM`, so that the entries of the consumers that died are eventually processed.
+				"""
+		}
 	}
 }
@@ -0,0 +1,233 @@
+package metadata
+
+components: sources: snmp_trap: {
+	_port: 162
+
+	title: "SNMP Trap"
+
+	description: """
+		Receives SNMP notifications, that is SNMPv1 traps, and SNMPv2c and SNMPv3 traps and inform
+		requests, from network devices and emits them as structured logs, with their OIDs resolved to
+		names using MIB modules.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		auto_generated:   true
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.snmp
+
+				interface: socket: {
+					api: {
+						title: "SNMP notifications"
+						url:   urls.snmp_notifications
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.snmp_trap.configuration
+
+	output: logs: notification: {
+		description: "An SNMP notification."
+		fields: {
+			agent_address: {
+				description: "The address of the agent that generated the trap. Only set for SNMPv1 traps."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["192.0.2.1"]
+				}
+			}
+			context_name: {
+				description: "The context of the notification. Only set for SNMPv3 notifications."
+				required:    false
+				type: string: {
+					default: null
+					examples: [""]
+				}
+			}
+			enterprise: {
+				description: "The OID of the object that generated the trap. Only set for SNMPv1 traps."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["1.3.6.1.4.1.8072.4"]
+				}
+			}
+			generic_trap: {
+				description: "The generic trap type. Only set for SNMPv1 traps."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [6]
+					unit: null
+				}
+			}
+			host: {
+				description: "The IP address of the host that sent the notification."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+				}
+			}
+			pdu_type: {
+				description: "The type of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						trap:           "An SNMPv1 trap."
+						snmpv2_trap:    "An SNMPv2c or SNMPv3 trap."
+						inform_request: "An SNMPv2c or SNMPv3 inform request, which is acknowledged for SNMPv2c."
+					}
+				}
+			}
+			request_id: {
+				description: "The request ID of the notification. Not set for SNMPv1 traps."
+				required:    false
+				type: int: {
+					default: null
+					examples: [1804289383]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["snmp_trap"]
+				}
+			}
+			specific_trap: {
+				description: "The enterprise specific trap type. Only set for SNMPv1 traps."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [17]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp
+			trap_name: {
+				description: "The name of the notification, if its OID is known."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["IF-MIB::linkDown"]
+				}
+			}
+			trap_oid: {
+				description: "The OID of the notification. SNMPv1 traps are mapped to an OID as described in RFC 3584."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["1.3.6.1.6.3.1.1.5.3"]
+				}
+			}
+			uptime: {
+				description: "The time since the agent was (re-)initialized, in hundredths of a second."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [123456]
+					unit: null
+				}
+			}
+			user: {
+				description: "The user that sent the notification. Only set for SNMPv3 notifications."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["vector"]
+				}
+			}
+			varbinds: {
+				description: """
+					The variable bindings of the notification, as objects with the `oid`, `type` and
+					`value` of the variable, and its `name` if it is known. Variables of the
+					`object_identifier` type also have the name of their value as `value_name`, if it is
+					known.
+					"""
+				required: true
+				type: array: items: type: object: {
+					examples: [
+						{
+							"oid":   "1.3.6.1.2.1.2.2.1.1.2"
+							"name":  "IF-MIB::ifIndex.2"
+							"type":  "integer"
+							"value": 2
+						},
+					]
+					options: {}
+				}
+			}
+			version: {
+				description: "The SNMP version of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						"1":  "SNMPv1"
+						"2c": "SNMPv2c"
+						"3":  "SNMPv3"
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		security: {
+			title: "Security"
+			body: """
+				SNMPv1 and SNMPv2c notifications are only authenticated by their community, which is sent
+				in clear text. Set `communities` to only accept notifications with the given communities.
+
+				SNMPv3 notifications are authenticated and optionally encrypted with the keys of their
+				user, using the User-based Security Model described in [RFC 3414](\(urls.rfc_3414)).
+				The keys are localized with the engine ID of the agent sending the notification, so the
+				same `users` can be used for all agents. SNMPv3 inform requests are received, but not
+				acknowledged, as that requires Vector to have its own engine ID.
+				"""
+		}
+		mibs: {
+			title: "Resolving OIDs"
+			body: """
+				The OIDs of the notification and its variables are resolved to names using the MIB
+				modules in `mib_paths`, for example the ones installed by Net-SNMP in
+				`/usr/share/snmp/mibs`. OIDs which are not defined by the modules are named after their
+				closest ancestor that is, followed by the remaining arcs, such as `IF-MIB::ifIndex.2`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_received_bytes: components.sources.internal_metrics.output.metrics.component_received_bytes
+	}
+}
@@ -49,6 +49,20 @@ components: sources: syslog: {
 
 	configuration: generated.components.sources.syslog.configuration
 
+	outputs: [
+		components._default_output,
+		{
+			name: "decode_errors"
+			description: """
+				In the `tcp` and `udp` modes, the messages that fail to be parsed are sent to this
+				output instead of being dropped. Their events hold the raw message, and the reason
+				it failed to be parsed in the `decode_error` field, or in the `%syslog.decode_error`
+				metadata with the `vector` log namespace. For a source named `foo`, this output can be
+				accessed by specifying `foo.decode_errors` as the input to another component.
+				"""
+		},
+	]
+
 	output: logs: line: {
 		description: "An individual Syslog event"
 		fields: {
@@ -191,6 +205,27 @@ components: sources: syslog: {
 				"""
 		}
 
+		octet_counting: {
+			title: "Octet Counting"
+			body: """
+				In the `tcp` and `unix` modes, the framing of each connection is detected from its
+				first byte. Connections starting with a digit use the octet counting framing of
+				[RFC 6587](\(urls.syslog_6587)), where each message is prefixed with its length,
+				so that the messages can contain new lines. Other connections are read line by line.
+				"""
+		}
+
+		tls_client_authentication: {
+			title: "TLS Client Authentication"
+			body: """
+				Syslog over TLS, as described in [RFC 5425](\(urls.syslog_5425)), is supported in
+				the `tcp` mode with the `tls` options. Setting `tls.verify_certificate` to `true`
+				requires the clients to present a certificate signed by one of the authorities of
+				`tls.ca_file`, and setting `tls.client_metadata_key` adds the subject of the
+				certificate of each client to its events.
+				"""
+		}
+
 		parsing: {
 			title: "Parsing"
 			body:  """
@@ -200,7 +235,8 @@ components: sources: syslog: {
 				Syslog style). It's unfortunate that the Syslog specification isn't more
 				accurately followed, but we hope that Vector insulates you from these deviations.
 
-				If parsing fails, Vector will raise an error. If you find this happening often,
+				If parsing fails, Vector will raise an error, and send the message to the
+				`decode_errors` output in the `tcp` and `udp` modes. If you find this happening often,
 				we recommend using the [`socket` source](\(urls.vector_socket_source)) combined with
 				[regex parsing](\(urls.vrl_functions)/#parse_regex) to implement your own custom
 				ingestion and parsing scheme, or [syslog parsing](\(urls.vrl_functions)/#parse_syslog) and
@@ -0,0 +1,216 @@
+package metadata
+
+components: sources: windows_eventlog: {
+	title: "Windows Event Log"
+
+	description: """
+		Collects events from the channels of the Windows Event Log, such as the `System`,
+		`Application` and `Security` logs, with their system properties and event data as
+		structured fields.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		auto_generated:   true
+		collect: {
+			checkpoint: enabled: true
+			from: service: services.windows_eventlog
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      false
+			"aarch64-unknown-linux-musl":     false
+			"armv7-unknown-linux-gnueabihf":  false
+			"armv7-unknown-linux-musleabihf": false
+			"x86_64-unknown-linux-gnu":       false
+			"x86_64-unknown-linux-musl":      false
+		}
+
+		requirements: [
+			"""
+				Reading the `Security` channel requires the running user to be an administrator or a
+				member of the `Event Log Readers` group.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: generated.components.sources.windows_eventlog.configuration
+
+	output: logs: event: {
+		description: "An event of the Windows Event Log."
+		fields: {
+			channel: {
+				description: "The channel the event was logged to."
+				required:    true
+				type: string: {
+					examples: ["System", "Microsoft-Windows-Sysmon/Operational"]
+				}
+			}
+			computer: {
+				description: "The name of the computer which logged the event."
+				required:    true
+				type: string: {
+					examples: ["host.example.com"]
+				}
+			}
+			event_data: {
+				description: """
+					The data of the event. The values of events of classic providers, which have no
+					names, are named `param1`, `param2` and so on.
+					"""
+				required: false
+				type: object: {
+					examples: [{"TargetUserName": "alice", "LogonType": "2"}]
+					options: {}
+				}
+			}
+			event_id: {
+				description: "The identifier of the event, defined by its provider."
+				required:    true
+				type: uint: {
+					examples: [4624, 7036]
+					unit: null
+				}
+			}
+			host: {
+				description: "The name of the computer which logged the event."
+				required:    true
+				type: string: {
+					examples: ["host.example.com"]
+				}
+			}
+			keywords: {
+				description: "The keywords of the event, as a hexadecimal bit mask."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["0x8020000000000000"]
+				}
+			}
+			level: {
+				description: "The level of the event."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [4]
+					unit: null
+				}
+			}
+			level_name: {
+				description: "The name of the level of the event."
+				required:    false
+				type: string: {
+					default: null
+					enum: {
+						Critical:    "Level 1."
+						Error:       "Level 2."
+						Warning:     "Level 3."
+						Information: "Level 4, or 0 for classic providers."
+						Verbose:     "Level 5."
+					}
+				}
+			}
+			message: {
+				description: """
+					The message of the event, formatted with the message table of its provider. Not
+					set if the provider is not installed on the computer, or if `render_message` is
+					disabled.
+					"""
+				required: false
+				type: string: {
+					default: null
+					examples: ["The Print Spooler service entered the running state."]
+				}
+			}
+			provider_name: {
+				description: "The name of the provider which logged the event."
+				required:    true
+				type: string: {
+					examples: ["Service Control Manager", "Microsoft-Windows-Security-Auditing"]
+				}
+			}
+			record_id: {
+				description: "The number of the event in its channel."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["windows_eventlog"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time at which the event was logged."
+			}
+			user_data: {
+				description: """
+					The data of events whose structure is defined by their provider, with the
+					attributes and children of each element.
+					"""
+				required: false
+				type: object: {
+					examples: [{"SubjectUserName": "alice"}]
+					options: {}
+				}
+			}
+			user_id: {
+				description: "The security identifier of the user the event was logged for."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["S-1-5-18"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		bookmarks: {
+			title: "Bookmarks"
+			body: """
+				The position of the last event of each channel is saved in a bookmark in the data
+				directory once the events have been sent, and reading resumes after it when Vector
+				restarts. The `read_existing_events` option is only used when there is no bookmark yet.
+				"""
+		}
+		queries: {
+			title: "Queries"
+			body: """
+				The `query` option is an [XPath query](\(urls.windows_eventlog_xpath)) applied to
+				each channel, which selects events by their system properties and data. For example,
+				`*[System[Level<=3]]` selects the critical, error and warning events.
+				"""
+		}
+		fields: {
+			title: "Fields"
+			body: """
+				Besides the fields above, events have the `provider_guid`, `qualifiers`, `version`,
+				`task`, `opcode`, `activity_id`, `related_activity_id`, `process_id` and `thread_id`
+				system properties when they are set.
+				"""
+		}
+	}
+}
@@ -0,0 +1,112 @@
+package metadata
+
+components: transforms: aggregate_v2: {
+	title: "Aggregate V2"
+
+	description: """
+		Aggregates log and metric events over tumbling, hopping, or sliding time windows, and
+		emits a log event with the computed aggregates for each group once its window closes.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		aggregate: {}
+	}
+
+	support: {
+		requirements: []
+		notices: []
+		warnings: []
+	}
+
+	configuration: generated.components.transforms.aggregate_v2.configuration
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: false
+			gauge:        true
+			histogram:    false
+			set:          false
+			summary:      false
+		}
+		traces: false
+	}
+
+	output: {
+		logs: "": {
+			description: """
+				An aggregated event for each group and window, containing the values of the `group_by`
+				paths, the `window_start` and `window_end` timestamps, and the configured aggregates.
+				"""
+		}
+	}
+
+	examples: [
+		{
+			title: "Count requests and their latency per service"
+			configuration: {
+				window: {
+					type:    "tumbling"
+					size_ms: 60000
+				}
+				group_by: [".service"]
+				aggregations: [
+					{target: ".requests", function: "count"},
+					{target: ".latency_p95", function: "p95", field: ".duration_ms"},
+				]
+			}
+			input: [
+				{log: {timestamp: "2021-07-12T07:58:01Z", service: "api", duration_ms: 12}},
+				{log: {timestamp: "2021-07-12T07:58:20Z", service: "api", duration_ms: 30}},
+			]
+			output: [
+				{log: {
+					service:      "api"
+					window_start: "2021-07-12T07:58:00Z"
+					window_end:   "2021-07-12T07:59:00Z"
+					requests:     2
+					latency_p95:  30.0
+				}},
+			]
+		},
+	]
+
+	how_it_works: {
+		windows: {
+			title: "Windows"
+			body: """
+				Events are assigned to windows by their timestamp, falling back to the time they are
+				received. Tumbling windows are fixed-size and never overlap. Hopping windows start
+				every `hop_ms` and overlap when it is smaller than their size. Sliding windows end at
+				the timestamp of each event, and cover the preceding `size_ms`.
+				"""
+		}
+
+		late_events: {
+			title: "Late Events"
+			body: """
+				A window is closed and its aggregates are emitted once the watermark, which trails the
+				latest event timestamp by `allowed_lateness_ms`, passes its end. Events that only
+				belong to closed windows are dropped and counted in `component_discarded_events_total`.
+				While no new events arrive, the watermark advances with the wall clock. All open
+				windows are flushed when Vector shuts down.
+				"""
+		}
+
+		percentiles: {
+			title: "Percentiles"
+			body: """
+				The `p50`, `p95`, and `p99` functions are estimated with a DDSketch, which has a
+				relative error of about 1%, and keeps the memory used by each group bounded.
+				"""
+		}
+	}
+}
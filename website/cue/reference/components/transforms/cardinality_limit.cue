@@ -0,0 +1,80 @@
+package metadata
+
+components: transforms: cardinality_limit: {
+	title: "Cardinality Limit"
+
+	description: """
+		Limits the number of active series of metric events, protecting metrics
+		storages from series explosions. When a new series would exceed a limit,
+		the event is either dropped, folded into an overflow series, or stripped
+		of the offending tags.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.transforms.cardinality_limit.configuration
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: false
+	}
+
+	output: {
+		metrics: "": {
+			description: "The input `metric` event, possibly with modified tags."
+		}
+	}
+
+	how_it_works: {
+		active_series: {
+			title: "Active Series"
+			body: """
+				A series is identified by the namespace, name, and tags of a metric. Each
+				limit tracks the series it has accepted for each group of metrics, as
+				defined by `group_by` and `per_metric`, and a series stays active until no
+				event has been seen for it for `series_ttl_secs`. Events of active series
+				are always accepted, and events of new series are accepted as long as their
+				group has fewer than `max_series` active series.
+				"""
+		}
+		overflow: {
+			title: "Overflow Series"
+			body: """
+				With the `overflow` action, events exceeding a limit keep only their
+				`group_by` tags and are marked with the `overflow_tag` tag, so that each
+				group has at most one extra series. Overflow series, as well as events
+				stripped of tags by the `drop_tag` action, don't count towards the limit.
+				Note that this is mostly useful for incremental metrics, which metrics
+				storages can sum, as the values of absolute metrics overwrite each other.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		cardinality_limit_active_series:  components.sources.internal_metrics.output.metrics.cardinality_limit_active_series
+		cardinality_limit_exceeded_total: components.sources.internal_metrics.output.metrics.cardinality_limit_exceeded_total
+	}
+}
@@ -89,6 +89,34 @@ components: transforms: dedupe: {
 				"""
 		}
 
+		probabilistic_cache: {
+			title: "Probabilistic Cache"
+			body: """
+				For streams with too many distinct events to cache them all, the
+				`probabilistic` option replaces the LRU cache with two Bloom filters,
+				each sized to hold `cache.num_events` distinct events with the given
+				`probabilistic.false_positive_rate`. Their memory usage only depends on
+				these two options, and not on the size of the events:
+
+				```text
+				2 * -`cache.num_events` * ln(`probabilistic.false_positive_rate`) / (ln(2) ^ 2) bits
+				```
+
+				In exchange, an event that isn't a duplicate is occasionally dropped,
+				with a probability of about `probabilistic.false_positive_rate`, as
+				long as at most `cache.num_events` distinct events are seen per
+				rotation interval.
+
+				Events are added to the newest filter, and every
+				`probabilistic.rotation_interval_secs` seconds, the oldest filter is
+				discarded and replaced by an empty one. Events are therefore
+				remembered for between one and two rotation intervals after they
+				were last seen. The `dedupe_estimated_cardinality` internal metric
+				reports the number of distinct events seen since the last rotation,
+				to help size `cache.num_events`.
+				"""
+		}
+
 		missing_fields: {
 			title: "Missing Fields"
 			body: """
@@ -99,4 +127,8 @@ components: transforms: dedupe: {
 				"""
 		}
 	}
+
+	telemetry: metrics: {
+		dedupe_estimated_cardinality: components.sources.internal_metrics.output.metrics.dedupe_estimated_cardinality
+	}
 }
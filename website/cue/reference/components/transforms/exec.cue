@@ -0,0 +1,87 @@
+package metadata
+
+components: transforms: exec: {
+	title: "Exec"
+
+	description: """
+		Modify log events with a long-lived process running on the host, for enrichment logic that
+		already exists as a program, or that is impractical to write in VRL.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				Each event makes a round trip through a pipe to the process, so this transform is much
+				slower than `remap`. Raise `concurrency` to run several processes if the process can't
+				keep up with the events.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: generated.components.transforms.exec.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	output: {
+		logs: "": {
+			description: "The `log` events returned by the process."
+		}
+	}
+
+	how_it_works: {
+		line_protocol: {
+			title: "Line protocol"
+			body: """
+				Each event is written to the standard input of the process as a JSON object followed by a
+				newline, and the process must answer by writing exactly one line to its standard output
+				before reading the next event:
+
+				* A JSON object replaces the event.
+				* A JSON array of objects replaces the event with any number of events, and an empty
+				  array drops the event.
+
+				The returned events keep the metadata of the input event. The standard error of the
+				process is forwarded to the standard error of Vector, so it can be used for logging.
+
+				Processes must flush their standard output after each answer, as many languages buffer
+				their output when it isn't written to a terminal.
+				"""
+		}
+		failures: {
+			title: "Failures"
+			body: """
+				A process that exits, or doesn't answer within `timeout_secs`, is killed and restarted
+				with an exponential backoff capped at `max_respawn_backoff_secs`, as its next answer can't
+				be matched with an event anymore. A process that answers with a line that isn't valid is
+				kept running.
+
+				The events that failed to be transformed are sent downstream unmodified, or dropped if
+				`drop_on_error` is enabled.
+				"""
+		}
+		concurrency: {
+			title: "Concurrency"
+			body: """
+				The transform runs `concurrency` copies of the process, each processing one event at a
+				time, and sends the events downstream in the order they were received. The processes are
+				started when the transform is built, so a command that can't be run is reported when
+				Vector starts, and they are stopped when the transform is shut down or reloaded.
+				"""
+		}
+	}
+}
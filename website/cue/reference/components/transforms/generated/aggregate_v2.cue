@@ -0,0 +1,125 @@
+package metadata
+
+generated: components: transforms: aggregate_v2: configuration: {
+	aggregations: {
+		description: "The aggregates to compute for each group and window."
+		required:    true
+		type: array: items: type: object: options: {
+			field: {
+				description: """
+					The path of the numeric value to aggregate in log events.
+
+					Metric events are aggregated by their counter or gauge value instead. This option is not
+					used by the `count` function.
+					"""
+				required: false
+				type: string: examples: [".duration_ms"]
+			}
+			function: {
+				description: "The function used to aggregate values."
+				required:    true
+				type: string: enum: {
+					count: "The number of events."
+					max:   "The largest value."
+					mean:  "The arithmetic mean of the values."
+					min:   "The smallest value."
+					p50:   "The median of the values, estimated with a sketch."
+					p95:   "The 95th percentile of the values, estimated with a sketch."
+					p99:   "The 99th percentile of the values, estimated with a sketch."
+					sum:   "The sum of the values."
+				}
+			}
+			target: {
+				description: "The path the aggregate is written to in the aggregated events."
+				required:    true
+				type: string: examples: [".request_count", ".latency_p99"]
+			}
+		}
+	}
+	allowed_lateness_ms: {
+		description: """
+			How long to wait for events arriving out of order, in milliseconds.
+
+			The watermark of the transform trails the most recent event timestamp seen by this
+			amount, and a window is closed once the watermark passes its end. Events belonging only
+			to closed windows are dropped.
+
+			When no newer events are received, the watermark advances with the wall clock, so that
+			windows are closed even if the input goes idle.
+			"""
+		required: false
+		type: uint: {
+			default: 0
+			examples: [5000]
+		}
+	}
+	flush_period_ms: {
+		description: "The interval to check for and close expired windows, in milliseconds."
+		required:    false
+		type: uint: default: 1000
+	}
+	group_by: {
+		description: """
+			An ordered list of event paths by which to group events.
+
+			Each group with matching values at the specified paths is aggregated independently, and
+			the values are copied to the aggregated events. For metric events, the `.name`,
+			`.namespace`, and `.tags.<tag>` paths are supported.
+
+			When not specified, all events of a window are aggregated in a single group.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: [".service", ".tags.host"]
+		}
+	}
+	window: {
+		description: """
+			The time windows events are aggregated over.
+
+			Windows are aligned to the Unix epoch, and events are assigned to windows based on their
+			timestamp, or on the time they are received if they have none.
+			"""
+		required: true
+		type: object: options: {
+			hop_ms: {
+				description: """
+					The interval between the start of consecutive windows, in milliseconds.
+
+					Must not be larger than `size_ms`.
+					"""
+				relevant_when: "type = \"hopping\""
+				required:      true
+				type: uint: examples: [10000]
+			}
+			size_ms: {
+				description: "The size of each window, in milliseconds."
+				required:    true
+				type: uint: examples: [60000]
+			}
+			type: {
+				description: "The type of time window."
+				required:    true
+				type: string: enum: {
+					hopping: """
+						Fixed-size windows starting every `hop_ms`.
+
+						Windows overlap when `hop_ms` is smaller than `size_ms`, in which case each event
+						belongs to several windows.
+						"""
+					sliding: """
+						Windows ending at the timestamp of each event, covering the preceding `size_ms`.
+
+						An aggregated event is emitted for each distinct event timestamp of a group.
+						"""
+					tumbling: """
+						Fixed-size, non-overlapping windows.
+
+						Each event belongs to exactly one window.
+						"""
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,95 @@
+package metadata
+
+generated: components: transforms: cardinality_limit: configuration: {
+	limits: {
+		description: """
+			The limits to enforce.
+
+			Limits are applied in order, and each one sees the metrics as changed by the previous ones.
+			"""
+		required: true
+		type: array: items: type: object: options: {
+			drop_tags: {
+				description:   "The tags to drop."
+				relevant_when: "limit_exceeded_action = \"drop_tag\""
+				required:      true
+				type: array: items: type: string: examples: ["user_id"]
+			}
+			group_by: {
+				description: """
+					The tags that metrics are grouped by.
+
+					Each distinct combination of the values of these tags is limited separately. Metrics
+					without a tag are grouped with the other metrics without it.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["host"]
+				}
+			}
+			limit_exceeded_action: {
+				description: "The action to take when a new series would exceed the limit."
+				required:    true
+				type: string: enum: {
+					drop_event: "Drop the event."
+					drop_tag: """
+						Drop the given tags from the event.
+
+						The event is then sent on without counting towards the limit.
+						"""
+					overflow: """
+						Fold the event into a single overflow series for its group.
+
+						All the tags of the event are removed, except those in `group_by`, and the `overflow_tag`
+						tag is added with a value of `true`. Absolute metrics are first converted to incremental
+						ones, tracking the previous value of each overflowed series for `series_ttl_secs`, so that
+						the overflow series can be aggregated by downstream metrics stores. The first value of an
+						absolute series is dropped, as there is nothing to compare it to.
+						"""
+				}
+			}
+			max_series: {
+				description: "The maximum number of active series for each group of metrics."
+				required:    true
+				type: uint: examples: [10000]
+			}
+			name: {
+				description: """
+					The name of the limit.
+
+					This is added as the `limit` tag to the internal metrics of the limit.
+					"""
+				required: true
+				type: string: examples: ["per_host"]
+			}
+			overflow_tag: {
+				description:   "The name of the tag marking overflow series."
+				relevant_when: "limit_exceeded_action = \"overflow\""
+				required:      false
+				type: string: {
+					default: "cardinality_overflow"
+					examples: ["cardinality_overflow"]
+				}
+			}
+			per_metric: {
+				description: """
+					Whether the series of each metric are limited separately.
+
+					If false, the series of all metrics in a group count towards the same limit.
+					"""
+				required: false
+				type: bool: default: true
+			}
+		}
+	}
+	series_ttl_secs: {
+		description: """
+			How long a series is considered active after its last event, in seconds.
+
+			Series that have been inactive for longer no longer count towards the limits.
+			"""
+		required: false
+		type: uint: default: 300
+	}
+}
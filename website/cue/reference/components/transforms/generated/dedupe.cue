@@ -34,6 +34,39 @@ generated: components: transforms: dedupe: configuration: {
 			}
 		}
 	}
+	probabilistic: {
+		description: """
+			Configuration for deduplicating with a probabilistic cache.
+
+			Instead of remembering the last `cache.num_events` events exactly, seen events are remembered
+			in Bloom filters holding up to `cache.num_events` distinct events each. This bounds the memory
+			used to deduplicate streams of very high cardinality, at the cost of occasionally dropping an
+			event that isn't a duplicate.
+			"""
+		required: false
+		type: object: options: {
+			false_positive_rate: {
+				description: "The target probability of dropping an event that isn't a duplicate."
+				required: false
+				type: float: default: 0.001
+			}
+			rotation_interval_secs: {
+				description: """
+					How often to rotate the cache, in seconds.
+
+					Two Bloom filters are kept, and rotating the cache discards the oldest one. The cache is also
+					rotated as soon as `cache.num_events` distinct events have been seen since the last rotation.
+					Events are therefore remembered for at most two rotation intervals after they were last
+					seen.
+					"""
+				required: false
+				type: uint: {
+					default: 300
+					unit:    "seconds"
+				}
+			}
+		}
+	}
 	time_settings: {
 		description: "Configuration for time based cache."
 		required:    false
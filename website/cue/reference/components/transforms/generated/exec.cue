@@ -0,0 +1,88 @@
+package metadata
+
+generated: components: transforms: exec: configuration: {
+	clear_environment: {
+		description: "Whether or not to clear the environment before setting custom environment variables."
+		required:    false
+		type: bool: default: false
+	}
+	command: {
+		description: """
+			The command to run, plus any arguments required.
+
+			Each event is written to the standard input of the process as a line of JSON, and the
+			process must write one line to its standard output for each line it reads: either a JSON
+			object, which replaces the event, or a JSON array of objects, which is empty to drop the
+			event.
+			"""
+		required: true
+		type: array: items: type: string: examples: ["./enrich", "--format=json"]
+	}
+	concurrency: {
+		description: """
+			The number of processes to run.
+
+			Each process is sent one event at a time, and the events are sent downstream in the order
+			they were received.
+			"""
+		required: false
+		type: uint: default: 1
+	}
+	drop_on_error: {
+		description: """
+			Drops the events that the process failed to transform.
+
+			Otherwise, those events are sent downstream unmodified.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	environment: {
+		description: """
+			Custom environment variables to set or update when running the command.
+			If a variable name already exists in the environment, its value is replaced.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				LANG: "en_US.UTF-8"
+			}]
+			options: "*": {
+				description: "An environment variable."
+				required:    true
+				type: string: {}
+			}
+		}
+	}
+	max_respawn_backoff_secs: {
+		description: """
+			The maximum delay before restarting a process that exited or failed, in seconds.
+
+			The first restart is delayed by half a second, and the delay doubles each time the process
+			fails again without having answered an event, up to this delay.
+			"""
+		required: false
+		type: float: {
+			default: 30.0
+			unit:    "seconds"
+		}
+	}
+	timeout_secs: {
+		description: """
+			How long to wait for the process to answer an event, in seconds.
+
+			A process that doesn't answer in time is restarted, as its answer would be mistaken for the
+			answer to the next event.
+			"""
+		required: false
+		type: float: {
+			default: 10.0
+			unit:    "seconds"
+		}
+	}
+	working_directory: {
+		description: "The directory in which to run the command."
+		required:    false
+		type: string: {}
+	}
+}
@@ -0,0 +1,52 @@
+package metadata
+
+generated: components: transforms: kubernetes_metadata: configuration: {
+	pod_ip_field: {
+		description: """
+			The field of the events holding the IP address of the Pod they originate from.
+
+			The Pods using the network of their Node are never looked up by their IP address, as it's
+			shared with the Node.
+			"""
+		required: false
+		type: string: examples: [".host", ".source_ip"]
+	}
+	pod_name_field: {
+		description: """
+			The field of the events holding the name of the Pod they originate from.
+
+			The Pod is only looked up by its name if it isn't found by its IP address.
+			"""
+		required: false
+		type: string: examples: [".resource.attributes.\"k8s.pod.name\""]
+	}
+	pod_namespace_field: {
+		description: """
+			The field of the events holding the Namespace of the Pod they originate from.
+
+			If not set, or if the field is missing, the Pod is looked up by its name in all of the
+			Namespaces.
+			"""
+		required: false
+		type: string: examples: [".resource.attributes.\"k8s.namespace.name\""]
+	}
+	source: {
+		description: """
+			The ID of the `kubernetes_logs` source whose cached Pod and Namespace metadata are used.
+
+			The source must run in the same Vector instance, and the metadata of the Pods is the one it
+			watches, so only the Pods of its Node can be looked up. While the source isn't running, the
+			events are passed through without being enriched.
+			"""
+		required: true
+		type: string: examples: ["kubernetes_logs"]
+	}
+	target: {
+		description: "The field the metadata of the Pod is written to."
+		required:    false
+		type: string: {
+			default: ".kubernetes"
+			examples: [".k8s"]
+		}
+	}
+}
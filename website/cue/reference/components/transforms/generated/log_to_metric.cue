@@ -38,6 +38,18 @@ generated: components: transforms: log_to_metric: configuration: {
 		description: "A list of metrics to generate."
 		required:    false
 		type: array: items: type: object: options: {
+			buckets: {
+				description: """
+					The upper limits of the buckets of the histogram, in increasing order.
+
+					If set, an aggregated histogram is created, counting the value in the first bucket whose
+					upper limit it doesn't exceed. Otherwise, a distribution with the value as its sample is
+					created.
+					"""
+				relevant_when: "type = \"histogram\""
+				required:      false
+				type: array: items: type: float: {}
+			}
 			field: {
 				description: "Name of the field in the event to generate the metric."
 				required:    true
@@ -85,6 +97,17 @@ generated: components: transforms: log_to_metric: configuration: {
 				required:    false
 				type: string: syntax: "template"
 			}
+			quantiles: {
+				description: """
+					The quantiles of the summary, between `0` and `1`.
+
+					If set, an aggregated summary of the value is created, every quantile of a single value
+					being the value itself. Otherwise, a distribution with the value as its sample is created.
+					"""
+				relevant_when: "type = \"summary\""
+				required:      false
+				type: array: items: type: float: {}
+			}
 			tags: {
 				description: """
 					Tags to apply to the metric.
@@ -1,6 +1,52 @@
 package metadata
 
 generated: components: transforms: remap: configuration: {
+	dns: {
+		description: """
+			Cached, non-blocking DNS lookups for the `dns_lookup` and `reverse_dns` functions.
+
+			When set, the functions resolve their lookups with the name servers of the system in the
+			background, and cache the answers for the TTL of their records, so that enriching the events
+			with host names never stalls the transform. The functions fail for the events whose lookup
+			isn't cached yet, so the first events with a new host name or address aren't enriched.
+			Otherwise, the functions block until each lookup completes.
+			"""
+		required: false
+		type: object: options: {
+			cache_size: {
+				description: """
+					The number of lookups cached.
+
+					The answers are cached for the TTL of their records, and the lookups that found no records
+					for their negative TTL. The least recently used lookup is evicted once the cache is full.
+					"""
+				required: false
+				type: uint: default: 10000
+			}
+			max_concurrent_lookups: {
+				description: """
+					The maximum number of lookups in progress at once.
+
+					The functions fail right away when this many lookups are in progress, instead of waiting
+					for a slow name server.
+					"""
+				required: false
+				type: uint: default: 32
+			}
+			timeout_ms: {
+				description: """
+					How long a lookup can take, in milliseconds.
+
+					A lookup that times out isn't cached, and is started again for the next events needing it.
+					"""
+				required: false
+				type: uint: {
+					default: 500
+					unit:    "milliseconds"
+				}
+			}
+		}
+	}
 	drop_on_abort: {
 		description: """
 			Drops any event that is manually aborted during processing.
@@ -33,6 +79,28 @@ generated: components: transforms: remap: configuration: {
 		required: false
 		type: bool: default: false
 	}
+	encryption_keys: {
+		description: """
+			The keys available to the `encrypt_field` and `decrypt_field` functions, by name.
+
+			Each key must be a base64-encoded, 32-byte key, and can be sourced from a secrets backend
+			with the `SECRET[<backend>.<secret_key>]` syntax. The program only refers to the keys by
+			name, and the name of the key is stored along with each encrypted value, so that the values
+			encrypted before a key is rotated remain readable as long as the previous key is still
+			listed under its name.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				pii_v1: "SECRET[vault.pii_v1]"
+			}]
+			options: "*": {
+				description: "A base64-encoded, 32-byte encryption key."
+				required:    true
+				type: string: {}
+			}
+		}
+	}
 	file: {
 		description: """
 			File path to the [Vector Remap Language][vrl] (VRL) program to execute for each event.
@@ -59,6 +127,25 @@ generated: components: transforms: remap: configuration: {
 		required: false
 		type: array: items: type: string: examples: ["['./my/program.vrl', './my/program2.vrl']"]
 	}
+	includes: {
+		description: """
+			File paths to VRL libraries defining functions that the program can call.
+
+			A library defines functions with `fn <name>(<parameters>) { <body> }`. The body of a
+			function is a VRL program of its own, run with the arguments bound to the variables named
+			after the parameters, and returning the value of its last expression. It doesn't have access
+			to the event, and can only call the functions defined before it in the same library, and the
+			functions that don't depend on the options of the transform. Each library is only compiled
+			once, and shared by the programs including it.
+
+			If a relative path is provided, its root is the current working directory.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["lib/parsers.vrl"]
+		}
+	}
 	metric_tag_values: {
 		description: """
 			When set to `single`, metric tag values are exposed as single strings, the
@@ -81,6 +168,18 @@ generated: components: transforms: remap: configuration: {
 			}
 		}
 	}
+	pattern_cache_size: {
+		description: """
+			The number of patterns of each kind cached by the functions parsing patterns only known at
+			runtime.
+
+			The regexes of `match_dynamic`, the grok patterns of `parse_grok_dynamic` and the formats of
+			`parse_timestamp_dynamic` are each kept in a cache of this size, evicting the least recently
+			used pattern once full, so that they aren't parsed again for each event.
+			"""
+		required: false
+		type: uint: default: 100
+	}
 	reroute_dropped: {
 		description: """
 			Reroutes dropped events to a named output instead of halting processing on them.
@@ -116,6 +215,46 @@ generated: components: transforms: remap: configuration: {
 			syntax: "remap_program"
 		}
 	}
+	state: {
+		description: """
+			Persistent state shared across the events processed by the program.
+
+			When set, the program can read and write key-value state with the `get_state` and
+			`set_state` functions, for example to track sessions, count events, or detect the first
+			occurrence of a value. The state is persisted in the data directory, and restored when
+			Vector restarts.
+			"""
+		required: false
+		type: object: options: {
+			data_dir: {
+				description: """
+					The directory used to persist the state.
+
+					By default, the [global `data_dir` option][global_data_dir] is used.
+					Make sure the running user has write permissions to this directory.
+
+					If this directory is specified, then Vector will attempt to create it.
+
+					[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+					"""
+				required: false
+				type: string: examples: ["/var/local/lib/vector/"]
+			}
+			flush_interval_secs: {
+				description: """
+					How often the writes to the state are flushed to disk, in seconds.
+
+					Each value is written as it is set, and the writes are also flushed when the transform
+					stops.
+					"""
+				required: false
+				type: uint: {
+					default: 10
+					unit:    "seconds"
+				}
+			}
+		}
+	}
 	timezone: {
 		description: """
 			The name of the timezone to apply to timestamp conversions that do not contain an explicit
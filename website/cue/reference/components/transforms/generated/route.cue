@@ -48,4 +48,36 @@ generated: components: transforms: route: configuration: {
 			}
 		}
 	}
+	route_metrics: {
+		description: """
+			Whether or not to emit the number of events matching and not matching the condition of
+			each route.
+
+			The counts are emitted as the `route_matched_events_total` and
+			`route_unmatched_events_total` internal metrics, tagged with the `route` identifier.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	sample: {
+		description: """
+			A map from route identifiers to the ratio of their matching events that are forwarded.
+
+			For example, `sample.canary = 0.05` forwards 5% of the events matching the `canary` route to
+			the `<transform_name>.canary` output. The other events matching the route aren't forwarded
+			to it, but they aren't considered as unmatched either. The routes without a ratio forward
+			all of their matching events.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				"foo-exists": 0.1
+			}]
+			options: "*": {
+				description: "The ratio of the events forwarded by a route."
+				required:    true
+				type: float: {}
+			}
+		}
+	}
 }
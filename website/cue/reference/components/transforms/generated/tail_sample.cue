@@ -0,0 +1,74 @@
+package metadata
+
+generated: components: transforms: tail_sample: configuration: {
+	decision_wait_secs: {
+		description: """
+			How long to wait for the spans of a trace, in seconds, before deciding whether to keep it.
+
+			The wait starts when the first span of the trace is received. A decision is made earlier if
+			the root span of the trace, which is the span without a `parent_span_id`, is received.
+			"""
+		required: false
+		type: float: {
+			default: 10.0
+			unit:    "seconds"
+		}
+	}
+	num_traces: {
+		description: """
+			The maximum number of traces to hold in memory while waiting for their spans.
+
+			When the limit is reached, a decision is made for the oldest trace to make room for the new
+			one. The same number of decisions is remembered, so that spans received after the decision
+			for their trace was made are kept or dropped along with the rest of the trace.
+			"""
+		required: false
+		type: uint: default: 50000
+	}
+	policies: {
+		description: """
+			The policies used to decide whether to keep a trace.
+
+			A trace is kept if any of the policies matches it, and dropped otherwise.
+			"""
+		required: true
+		type: array: items: type: object: options: {
+			threshold_ms: {
+				description:   "The duration above which traces are kept, in milliseconds."
+				relevant_when: "type = \"latency\""
+				required:      true
+				type: uint: examples: [500]
+			}
+			traces_per_second: {
+				description:   "The number of traces to keep per second for each service."
+				relevant_when: "type = \"rate_limiting\""
+				required:      true
+				type: uint: examples: [10]
+			}
+			type: {
+				description: "The type of the policy."
+				required:    true
+				type: string: enum: {
+					latency: """
+						Keeps traces lasting longer than a threshold.
+
+						The duration of a trace is the time between the earliest `start_time_unix_nano` and the
+						latest `end_time_unix_nano` of its spans.
+						"""
+					rate_limiting: """
+						Keeps up to a number of traces per second for each service.
+
+						The service of a trace is taken from the `service.name` resource attribute of its root span,
+						or of its first span if the root span wasn't received. Traces kept by another policy don't
+						count towards the limit.
+						"""
+					status_code: """
+						Keeps traces containing a span with an error status.
+
+						A span has an error status if its `status.code` is `2`, as defined by OpenTelemetry.
+						"""
+				}
+			}
+		}
+	}
+}
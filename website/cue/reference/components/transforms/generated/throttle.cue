@@ -38,6 +38,62 @@ generated: components: transforms: throttle: configuration: {
 			syntax: "template"
 		}
 	}
+	redis: {
+		description: """
+			Enforces the rate limits across all the Vector instances using the same Redis backend.
+
+			The buckets are stored in Redis, so that the `threshold` applies to the events of all the
+			instances. While Redis can't be reached, each instance falls back to rate limiting its own
+			events, and the `threshold` applies to each instance.
+			"""
+		required: false
+		type: object: options: {
+			endpoint: {
+				description: """
+					The URL of the Redis endpoint to connect to.
+
+					The URL _must_ take the form of `protocol://server:port/db` where the protocol can either be
+					`redis` or `rediss` for connections secured via TLS.
+					"""
+				required: true
+				type: string: examples: ["redis://127.0.0.1:6379/0"]
+			}
+			key_prefix: {
+				description: """
+					The prefix prepended to the keys of the buckets stored in Redis.
+
+					The instances using the same endpoint and prefix share their buckets.
+					"""
+				required: false
+				type: string: default: "vector:throttle:"
+			}
+			retry_interval_secs: {
+				description: """
+					How long the local rate limiter is used after Redis couldn't be reached, in seconds.
+
+					Redis is requested again once the interval elapsed.
+					"""
+				required: false
+				type: uint: {
+					default: 5
+					unit:    "seconds"
+				}
+			}
+			timeout_ms: {
+				description: """
+					The timeout for connecting to Redis and for each request, in milliseconds.
+
+					The events are rate limited one at a time, so this bounds the delay added to each event
+					while Redis is slow to respond.
+					"""
+				required: false
+				type: uint: {
+					default: 50
+					unit:    "milliseconds"
+				}
+			}
+		}
+	}
 	threshold: {
 		description: """
 			The number of events allowed for a given bucket per configured `window_secs`.
@@ -0,0 +1,36 @@
+package metadata
+
+generated: components: transforms: wasm: configuration: {
+	fuel_per_event: {
+		description: """
+			The amount of fuel the module can consume to process each event.
+
+			Each WebAssembly instruction executed consumes about one unit of fuel. The event is dropped
+			if the module runs out of fuel, so that a module stuck in a loop can't stall the pipeline.
+			"""
+		required: false
+		type: uint: default: 10000000
+	}
+	max_memory_bytes: {
+		description: """
+			The maximum size of the linear memory of the module, in bytes.
+
+			The module fails to allocate past this limit, and can't be loaded if its initial memory is
+			larger.
+			"""
+		required: false
+		type: uint: {
+			default: 16777216
+			unit:    "bytes"
+		}
+	}
+	module: {
+		description: """
+			The path to the WebAssembly module, in the binary or the text format.
+
+			If a relative path is provided, its root is the current working directory.
+			"""
+		required: true
+		type: string: examples: ["./transforms/redact.wasm"]
+	}
+}
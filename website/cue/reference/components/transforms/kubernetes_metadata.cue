@@ -0,0 +1,116 @@
+package metadata
+
+components: transforms: kubernetes_metadata: {
+	title: "Kubernetes Metadata"
+
+	description: """
+		Enriches events with the metadata of the Kubernetes Pods they originate from, looked up by
+		the IP address or the name of the Pod in the metadata cached by a `kubernetes_logs` source.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		enrich: {
+			from: service: {
+				name:     "Kubernetes"
+				url:      urls.kubernetes
+				versions: ">= 1.19"
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				A [`kubernetes_logs` source](\(urls.vector_kubernetes_logs_source)) must be running in the
+				same Vector instance, and be referenced by the `source` option.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.transforms.kubernetes_metadata.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	output: logs: log: {
+		description: "Log event enriched with the metadata of its Pod"
+		fields: {
+			pod_name: {
+				description: "The name of the Pod, under the `target` field."
+				required:    false
+				type: string: examples: ["web-7b9f4d8c6-x2kqz"]
+			}
+			pod_namespace: {
+				description: "The Namespace of the Pod, under the `target` field."
+				required:    false
+				type: string: examples: ["team-a"]
+			}
+			pod_uid: {
+				description: "The UID of the Pod, under the `target` field."
+				required:    false
+				type: string: examples: ["ba46d8c9-9541-4f6b-bbf9-d23b36f2f136"]
+			}
+			pod_ip: {
+				description: "The IP address of the Pod, under the `target` field."
+				required:    false
+				type: string: examples: ["10.0.0.1"]
+			}
+			pod_node_name: {
+				description: "The name of the Node of the Pod, under the `target` field."
+				required:    false
+				type: string: examples: ["node-1"]
+			}
+			pod_owner: {
+				description: "The kind and the name of the owner of the Pod, under the `target` field."
+				required:    false
+				type: string: examples: ["ReplicaSet/web-7b9f4d8c6"]
+			}
+			pod_labels: {
+				description: "The labels of the Pod, under the `target` field."
+				required:    false
+				type: object: options: {}
+			}
+			pod_annotations: {
+				description: "The annotations of the Pod, under the `target` field."
+				required:    false
+				type: object: options: {}
+			}
+			namespace_labels: {
+				description: """
+					The labels of the Namespace of the Pod, under the `target` field. Only available when
+					`insert_namespace_fields` is enabled in the `kubernetes_logs` source.
+					"""
+				required: false
+				type: object: options: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		shared_state: {
+			title: "Shared state"
+			body: """
+				The transform doesn't watch the Kubernetes API itself. Instead, it looks up the Pods in the
+				metadata cached by the `kubernetes_logs` source referenced by the `source` option, which
+				only watches the Pods of its own Node. This makes the transform suited to enrich the
+				events received from the Pods of the same Node, for example by a `syslog` or an
+				`opentelemetry` source running in the agent.
+
+				The Pods are looked up by their IP address first, then by their name, and the events whose
+				Pod isn't found are passed through without being enriched.
+				"""
+		}
+	}
+}
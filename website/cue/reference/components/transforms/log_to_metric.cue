@@ -324,6 +324,15 @@ components: transforms: log_to_metric: {
 				individual metrics for reduction in the metrics storage itself.
 				"""
 		}
+		all_metrics: {
+			title: "Reconstructing Metrics"
+			body: """
+				With `all_metrics` enabled, the logs with the
+				[structure of the `metric_to_log` transform](\(urls.vector_transforms)/metric_to_log#structure-of-the-logs)
+				are converted back to metrics of any type, including aggregated histograms and
+				summaries and sketches, with their tags, timestamp and interval.
+				"""
+		}
 		null_fields: {
 			title: "Null Fields"
 			body: """
@@ -97,5 +97,39 @@ components: transforms: metric_to_log: {
 		},
 	]
 
-	how_it_works: {}
+	how_it_works: {
+		structure: {
+			title: "Structure of the Logs"
+			body: """
+				Each log has the `name`, `kind` (`absolute` or `incremental`) and, when set, the
+				`namespace`, `tags`, `timestamp` and `interval_ms` of the metric, along with a
+				single field holding its value:
+
+				* `counter` and `gauge`: `{"value": <float>}`
+				* `set`: `{"values": [<string>, ...]}`
+				* `distribution`: `{"samples": [{"value": <float>, "rate": <integer>}, ...], "statistic": "histogram" | "summary"}`
+				* `aggregated_histogram`: `{"buckets": [{"upper_limit": <float>, "count": <integer>}, ...], "count": <integer>, "sum": <float>}`
+				* `aggregated_summary`: `{"quantiles": [{"quantile": <float>, "value": <float>}, ...], "count": <integer>, "sum": <float>}`
+				* `sketch`: `{"AgentDDSketch": {"bins": {"k": [<integer>, ...], "n": [<integer>, ...]}, "count": <integer>, "min": <float>, "max": <float>, "sum": <float>, "avg": <float>}}`
+
+				The counts of the buckets of the histograms aren't cumulative, and an infinite upper
+				limit is `null`, as JSON has no infinity. With `metric_tag_values` set to `full`,
+				the tags with several values are arrays and the bare tags are `null`.
+				"""
+		}
+		round_trip: {
+			title: "Round Trip"
+			body: """
+				The [`log_to_metric` transform](\(urls.vector_transforms)/log_to_metric) with
+				`all_metrics` enabled reconstructs the metrics from these logs, including after
+				they were encoded with the `json` codec. Metrics can thus be sent through sinks
+				only supporting logs, like `aws_s3`, and read back as metrics with a source
+				decoding the JSON.
+
+				The metrics are reconstructed exactly when `metric_tag_values` is set to `full`
+				and `host_tag` isn't moving a tag out of the `tags`. A metric without timestamp
+				is given the current time.
+				"""
+		}
+	}
 }
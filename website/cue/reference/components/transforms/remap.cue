@@ -154,6 +154,32 @@ components: transforms: "remap": {
 				```
 				"""#
 		}
+		state: {
+			title: "Persistent state"
+			body: #"""
+				By default, every event is processed independently. Setting the `state` option gives
+				the program a key-value store shared across events, read with `get_state` and written
+				with `set_state`. For example, to count the events of each host over an hour:
+
+				```coffee
+				key = "count:" + string!(.host)
+				count = int(get_state!(key)) ?? 0
+				set_state!(key, count + 1, ttl: 3600)
+				.host_event_count = count + 1
+				```
+
+				Each value is written to an embedded key-value store in the data directory as it is
+				set, keeping its type, and only the most recently used entries are cached in memory.
+				Writes are flushed to disk every `state.flush_interval_secs` seconds and when the
+				transform stops, and the state is restored when Vector restarts. Expired values are
+				removed periodically.
+				"""#
+		}
+	}
+
+	telemetry: metrics: {
+		remap_pattern_cache_hits_total:   components.sources.internal_metrics.output.metrics.remap_pattern_cache_hits_total
+		remap_pattern_cache_misses_total: components.sources.internal_metrics.output.metrics.remap_pattern_cache_misses_total
 	}
 
 	outputs: [
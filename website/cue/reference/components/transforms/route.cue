@@ -111,7 +111,26 @@ components: transforms: route: {
 		},
 	]
 
+	telemetry: metrics: {
+		route_matched_events_total:   components.sources.internal_metrics.output.metrics.route_matched_events_total
+		route_unmatched_events_total: components.sources.internal_metrics.output.metrics.route_unmatched_events_total
+	}
+
 	how_it_works: {
+		sampling_routes: {
+			title: "Sampling routes"
+			body: """
+				Only a ratio of the events matching a route can be forwarded to it by setting the
+				ratio of the route in `sample`, for example to send a small share of the traffic to a
+				canary deployment without chaining a `sample` transform. The ratio is applied to each
+				route independently, and the events that aren't forwarded to a sampled route are still
+				forwarded to the other routes they match.
+
+				Setting `route_metrics` to `true` emits the number of events matching and not matching
+				each route, so that the split of the traffic can be monitored.
+				"""
+		}
+
 		routing_to_multiple_components: {
 			title: "Routing to multiple components"
 			body: """
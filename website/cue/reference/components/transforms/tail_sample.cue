@@ -0,0 +1,65 @@
+package metadata
+
+components: transforms: tail_sample: {
+	title: "Tail Sample"
+
+	description: """
+		Samples whole traces once all of their spans have been seen, keeping the traces that match
+		at least one of the configured policies.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.transforms.tail_sample.configuration
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	output: {
+		traces: "": {
+			description: "The input `trace` events of the traces that were kept."
+		}
+	}
+
+	how_it_works: {
+		buffering: {
+			title: "Buffering"
+			body: """
+				Spans are grouped into traces by their `trace_id` and held until the root span of the trace,
+				which is the span without a `parent_span_id`, is received, or until `decision_wait_secs`
+				have passed since the first span of the trace was received. At most `num_traces` traces are
+				held, and the oldest one is decided on early when a new trace would exceed that limit.
+
+				The decision made for a trace is remembered, so that spans received afterwards are kept or
+				dropped along with the rest of their trace. Spans without a `trace_id` are always kept.
+				"""
+		}
+		policies: {
+			title: "Policies"
+			body: """
+				A trace is kept if any of the configured policies matches it. The `status_code` and `latency`
+				policies are checked first, so that traces kept by them don't count towards the limits of
+				`rate_limiting` policies. The fields used by the policies are the ones of spans received from
+				the `opentelemetry` source.
+				"""
+		}
+	}
+}
@@ -116,6 +116,19 @@ components: transforms: throttle: {
 						by the bucket's `key`.
 						"""
 				},
+				{
+					title: "Distributed Rate Limiting"
+					body: """
+						Each Vector instance rate limits its own events, unless `redis` is set. The buckets are then
+						token buckets stored in Redis, holding up to `threshold` tokens and refilled over `window_secs`,
+						so that a fleet of instances using the same Redis endpoint and `key_prefix` enforces a global
+						limit per key. Redis is requested for each event, which adds the round trip to Redis to the
+						latency of the events.
+
+						When Redis can't be reached, or doesn't respond within `timeout_ms`, the instance falls back
+						to its local rate limiter for `retry_interval_secs` before requesting Redis again.
+						"""
+				},
 			]
 		}
 	}
@@ -0,0 +1,88 @@
+package metadata
+
+components: transforms: wasm: {
+	title: "WebAssembly"
+
+	description: """
+		Modify log events with a [WebAssembly](\(urls.wasm)) module, for logic that is impractical
+		to write in VRL, in any of the [languages compiling to WebAssembly](\(urls.wasm_languages)).
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		program: {
+			runtime: {
+				name:    "Wasmtime"
+				url:     "https://wasmtime.dev/"
+				version: "38"
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: generated.components.transforms.wasm.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	output: {
+		logs: "": {
+			description: "The `log` events returned by the module."
+		}
+	}
+
+	how_it_works: {
+		guest_abi: {
+			title: "Guest ABI"
+			body: """
+				The module must export its linear memory as `memory`, and the following functions:
+
+				* `vector_alloc(len: i32) -> i32` allocates `len` bytes and returns their address.
+				* `vector_free(ptr: i32, len: i32)` frees a buffer returned by `vector_alloc` or
+				  `vector_process`.
+				* `vector_process(ptr: i32, len: i32) -> i64` processes the event at `ptr`, and returns
+				  the address of its output in the upper 32 bits and its length in the lower 32 bits.
+
+				Each event is written as a JSON object to a buffer allocated with `vector_alloc`, and the
+				module returns the events to send downstream as a JSON array of objects, which is empty to
+				drop the event. Both buffers are freed by the transform once the output is read. The
+				returned events keep the metadata of the input event.
+
+				The module can't import any function, so it must be built for a freestanding target, such
+				as `wasm32-unknown-unknown` for Rust or `wasm-unknown` for TinyGo, or with `--use abort=`
+				for AssemblyScript.
+				"""
+		}
+		limits: {
+			title: "Limits"
+			body: """
+				The module is given `fuel_per_event` units of fuel to process each event, and its memory
+				can't grow past `max_memory_bytes`. An event is dropped when the module runs out of fuel,
+				traps, or returns invalid output, and the next event is processed by a new instance of the
+				module.
+				"""
+		}
+		state: {
+			title: "State"
+			body: """
+				The same instance of the module processes the events as long as it doesn't fail, so the
+				module can keep state in its memory across events. That state is lost when Vector restarts,
+				the configuration is reloaded, or the module fails.
+				"""
+		}
+	}
+}
@@ -14,6 +14,23 @@ generated: api: configuration: api: {
 			examples: ["0.0.0.0:8686", "127.0.0.1:1234"]
 		}
 	}
+	config_reload: {
+		common: false
+		description: """
+			Whether the `/config/validate` and `/config/reload` endpoints are enabled for the API.
+
+			The `/config/reload` endpoint replaces the running configuration with the one sent in the
+			request body, allowing anyone who can reach the API to change what Vector does, so only
+			enable it if the address set using the `bind` parameter is only reachable by trusted
+			clients. The configuration is replaced again by the configuration files on the next reload
+			triggered by a `SIGHUP` signal or by watching them.
+
+			Secrets of configurations sent to these endpoints can't be retrieved with the `directory`,
+			`exec`, or `file` backends, which would run commands or read files on the host.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	enabled: {
 		common:      true
 		description: "Whether the GraphQL API is enabled for this Vector instance."
@@ -30,6 +47,36 @@ generated: api: configuration: api: {
 		required: false
 		type: bool: default: true
 	}
+	metrics_history: {
+		common: false
+		description: """
+			The history of the throughput and errors of the components, kept in memory to be queried
+			over a window of time.
+			"""
+		required: false
+		type: object: options: {
+			resolution_secs: {
+				description: "The interval between the samples of the metrics."
+				required:    false
+				type: uint: {
+					default: 10
+					unit:    "seconds"
+				}
+			}
+			retention_secs: {
+				description: """
+					How long the samples of the metrics are kept.
+
+					Set to `0` to disable the history.
+					"""
+				required: false
+				type: uint: {
+					default: 900
+					unit:    "seconds"
+				}
+			}
+		}
+	}
 	playground: {
 		common: false
 		description: """
@@ -45,6 +45,7 @@ remap: {
 		"Object",
 		"Parse",
 		"Random",
+		"State",
 		"String",
 		"System",
 		"Timestamp",
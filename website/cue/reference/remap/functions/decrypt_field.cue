@@ -0,0 +1,36 @@
+package metadata
+
+remap: functions: decrypt_field: {
+	category: "Cryptography"
+	description: """
+		Decrypts a value encrypted by `encrypt_field`, with the key and the algorithm it was encrypted
+		with. The key must still be configured under the same name in the `encryption_keys` option of
+		the `remap` transform.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The base64-encoded envelope returned by `encrypt_field`."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't an envelope returned by `encrypt_field`.",
+		"`value` was encrypted with a key that isn't configured.",
+		"`value` was corrupted or tampered with.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decrypt a field"
+			source: #"""
+				.card_number = decrypt_field!(.card_number)
+				"""#
+			return:    "4111 1111 1111 1111"
+			skip_test: true
+		},
+	]
+}
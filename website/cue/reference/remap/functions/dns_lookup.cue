@@ -0,0 +1,40 @@
+package metadata
+
+remap: functions: dns_lookup: {
+	category: "System"
+	description: """
+		Resolves the IPv4 and IPv6 addresses of a host name with the name servers of the system.
+
+		In the `remap` transform with the `dns` option set, the lookups are resolved in the
+		background and cached for the TTL of their records, and the function never waits for them:
+		it fails until the answer of the lookup is cached, so handle its error to process the first
+		events with a new value.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The host name to resolve."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"The host name can't be resolved.",
+		"The lookup isn't cached yet, and is in progress.",
+		"Too many lookups are in progress.",
+	]
+	return: types: ["array"]
+	pure: false
+
+	examples: [
+		{
+			title: "Resolve the addresses of a host"
+			source: #"""
+				dns_lookup!("localhost")
+				"""#
+			return:    ["127.0.0.1", "::1"]
+			skip_test: true
+		},
+	]
+}
@@ -0,0 +1,64 @@
+package metadata
+
+remap: functions: encrypt_field: {
+	category: "Cryptography"
+	description: """
+		Encrypts a value with a key of the `remap` transform, returning a base64-encoded envelope that
+		can only be read back with `decrypt_field`. The key is referred to by its name in the
+		`encryption_keys` option of the transform, so that the key itself never appears in the program.
+
+		The envelope holds the name of the key and the algorithm along with the encrypted value and its
+		authentication tag, so that the values encrypted before a key is rotated remain readable as long
+		as the previous key is still configured under its name.
+
+		Supported Algorithms:
+
+		* AES-256-GCM (default)
+		* CHACHA20-POLY1305
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The value to encrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "key"
+			description: """
+				The name of the key in the `encryption_keys` option of the transform. This must be a
+				literal string.
+				"""
+			required: true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The algorithm to encrypt the value with. This must be a literal string."
+			required:    false
+			default:     "AES-256-GCM"
+			enum: {
+				"AES-256-GCM":       "AES in Galois/Counter Mode, with a 256-bit key."
+				"CHACHA20-POLY1305": "The ChaCha20 stream cipher with the Poly1305 authenticator."
+			}
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"A random nonce can't be generated.",
+	]
+	return: types: ["string"]
+	pure: false
+
+	examples: [
+		{
+			title: "Encrypt a field before it leaves the network"
+			source: #"""
+				.card_number = encrypt_field!(.card_number, "pii_v1")
+				"""#
+			return:    "AQEGcGlpX3YxRHGt7gW..."
+			skip_test: true
+		},
+	]
+}
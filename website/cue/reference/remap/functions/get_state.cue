@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: get_state: {
+	category: "State"
+	description: """
+		Returns the value of the given key from the state of the `remap` transform, or `null` if the key
+		is unset or expired. The state is shared across the events processed by the transform, and is only
+		available when its `state` option is set.
+		"""
+
+	arguments: [
+		{
+			name: "key"
+			description: """
+				The key of the value.
+				"""
+			required: true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"The state can't be read from the data directory.",
+	]
+	return: types: ["any"]
+	pure: false
+
+	examples: [
+		{
+			title: "Detect the first event of a user"
+			source: #"""
+				first_seen = get_state!("user:1234") == null
+				set_state!("user:1234", true)
+				first_seen
+				"""#
+			return: true
+		},
+	]
+}
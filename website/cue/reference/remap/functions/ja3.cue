@@ -0,0 +1,60 @@
+package metadata
+
+remap: functions: ja3: {
+	category: "String"
+	description: """
+		Computes the [JA3 fingerprint](https://github.com/salesforce/ja3) of a TLS client hello from
+		its fields, as logged by Zeek and Suricata. The fingerprint joins the decimal codes of the
+		fields, leaving out the GREASE values, and is hashed with MD5.
+		"""
+
+	arguments: [
+		{
+			name:        "version"
+			description: "The TLS version of the hello, such as `771` for TLS 1.2."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name:        "ciphers"
+			description: "The codes of the cipher suites, in the order of the hello."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "extensions"
+			description: "The codes of the extensions, in the order of the hello."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "curves"
+			description: "The codes of the supported elliptic curves, in the order of the hello."
+			required:    false
+			type: ["array"]
+		},
+		{
+			name:        "point_formats"
+			description: "The codes of the elliptic curve point formats, in the order of the hello."
+			required:    false
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"A field holds a value that isn't a 16-bit integer.",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Fingerprint a TLS client hello"
+			source: #"""
+				ja3!(769, [47, 53, 5, 10, 49161, 49162, 49171, 49172, 50, 56, 19, 4], [0, 10, 11], curves: [23, 24, 25], point_formats: [0])
+				"""#
+			return: {
+				hash:   "ada70206e40642a3e4461f35503241d5"
+				string: "769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0"
+			}
+		},
+	]
+}
@@ -0,0 +1,78 @@
+package metadata
+
+remap: functions: ja4: {
+	category: "String"
+	description: """
+		Computes the [JA4 fingerprint](https://github.com/FoxIO-LLC/ja4) of a TLS client hello from
+		its fields. The fingerprint describes the protocol, the highest supported version, the server
+		name indication, the number of cipher suites and extensions, and the first application-layer
+		protocol, followed by the truncated SHA-256 hashes of the sorted cipher suites, and of the
+		sorted extensions with the signature algorithms. The GREASE values are left out.
+		"""
+
+	arguments: [
+		{
+			name:        "version"
+			description: "The TLS version of the hello, used when `supported_versions` is empty."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name:        "ciphers"
+			description: "The codes of the cipher suites."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "extensions"
+			description: "The codes of the extensions."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "signature_algorithms"
+			description: "The codes of the signature algorithms, in the order of the hello."
+			required:    false
+			type: ["array"]
+		},
+		{
+			name:        "supported_versions"
+			description: "The codes of the versions of the `supported_versions` extension."
+			required:    false
+			type: ["array"]
+		},
+		{
+			name:        "alpn"
+			description: "The protocols of the application-layer protocol negotiation extension, in the order of the hello."
+			required:    false
+			type: ["array"]
+		},
+		{
+			name:        "protocol"
+			description: "The transport protocol of the hello."
+			required:    false
+			default:     "tcp"
+			enum: {
+				tcp:  "TLS over TCP."
+				quic: "QUIC."
+				dtls: "DTLS."
+			}
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"A field holds a value that isn't a 16-bit integer.",
+		"`alpn` holds a value that isn't a string.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Fingerprint a TLS client hello"
+			source: #"""
+				ja4!(771, [2570, 4865, 4866, 4867, 49195, 49199, 49196, 49200, 52393, 52392, 49171, 49172, 156, 157, 47, 53], [2570, 0, 23, 65281, 10, 11, 35, 16, 5, 13, 18, 51, 45, 43, 27, 17513, 21], signature_algorithms: [1027, 2052, 1025, 1283, 2053, 1281, 2054, 1537], supported_versions: [2570, 772, 771], alpn: ["h2", "http/1.1"])
+				"""#
+			return: "t13d1516h2_8daaf6152771_e5627efa2ab1"
+		},
+	]
+}
@@ -0,0 +1,40 @@
+package metadata
+
+remap: functions: match_dynamic: {
+	category: "String"
+	description: """
+		Determines whether the `value` matches the `pattern`, a regular expression only known at runtime,
+		such as one read from the event. In the `remap` transform, the compiled patterns are cached
+		according to its `pattern_cache_size` option.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The value to match."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "pattern"
+			description: "The regular expression pattern to match against."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`pattern` is not a valid regular expression.",
+	]
+	return: types: ["boolean"]
+
+	examples: [
+		{
+			title: "Match with a pattern built at runtime"
+			source: #"""
+				prefix = "user"
+				match_dynamic!("user-1234", "^" + prefix + "-\\d+$")
+				"""#
+			return: true
+		},
+	]
+}
@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: parse_grok_dynamic: {
+	category: "Parse"
+	description: """
+		Parses the `value` using a [`grok`](\(urls.grok)) `pattern` only known at runtime, such as one
+		read from the event. In the `remap` transform, the compiled patterns are cached according to its
+		`pattern_cache_size` option.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "pattern"
+			description: "The [Grok pattern](https://github.com/daschl/grok/tree/master/patterns)."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`pattern` is not a valid grok pattern.",
+		"`value` fails to parse using the provided `pattern`.",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse with a pattern built at runtime"
+			source: #"""
+				field = "status"
+				parse_grok_dynamic!("status=200", field + "=%{INT:" + field + "}")
+				"""#
+			return: {
+				status: "200"
+			}
+		},
+	]
+}
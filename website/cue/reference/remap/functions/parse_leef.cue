@@ -0,0 +1,68 @@
+package metadata
+
+remap: functions: parse_leef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in LEEF (Log Event Extended Format) format, versions 1.0 and 2.0. Ignores
+		everything up to the LEEF header, such as a syslog header. The fields of the header are returned
+		as `leefVersion`, `deviceVendor`, `deviceProduct`, `deviceVersion`, and `eventId`, along with the
+		attributes of the event. The attributes are separated by tabs, or by the delimiter set in the
+		header of LEEF 2.0 events, which is either a character or its hexadecimal code, such as `x5E`.
+		The backslashes escaping the delimiter, `=`, `|`, and backslashes are removed, and `\\n`,
+		`\\r`, and `\\t` are replaced by line breaks and tabs.
+		"""
+	notices: [
+		"""
+			All values are returned as strings. We recommend manually coercing values to desired types as you see fit.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not a properly formatted LEEF string.",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse a LEEF 1.0 event"
+			source: #"""
+				parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tusrName=joe.black")
+				"""#
+			return: {
+				"leefVersion":   "1.0"
+				"deviceVendor":  "Microsoft"
+				"deviceProduct": "MSExchange"
+				"deviceVersion": "4.0 SP1"
+				"eventId":       "15345"
+				"src":           "192.0.2.0"
+				"dst":           "172.50.123.1"
+				"sev":           "5"
+				"usrName":       "joe.black"
+			}
+		},
+		{
+			title: "Parse a LEEF 2.0 event with a custom delimiter"
+			source: #"""
+				parse_leef!(s'<13>Oct 16 12:00:00 host LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^msg=a \^ b^path=C:\\temp')
+				"""#
+			return: {
+				"leefVersion":   "2.0"
+				"deviceVendor":  "Lancope"
+				"deviceProduct": "StealthWatch"
+				"deviceVersion": "1.0"
+				"eventId":       "41"
+				"src":           "10.0.1.8"
+				"msg":           "a ^ b"
+				"path":          "C:\\temp"
+			}
+		},
+	]
+}
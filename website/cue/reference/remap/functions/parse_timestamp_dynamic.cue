@@ -0,0 +1,50 @@
+package metadata
+
+remap: functions: parse_timestamp_dynamic: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in [strptime](\(urls.strptime_specifiers)) `format`, where the format is only
+		known at runtime, such as one read from the event. In the `remap` transform, the parsed formats
+		are cached according to its `pattern_cache_size` option.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The text of the timestamp."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "format"
+			description: "The [strptime](\(urls.strptime_specifiers)) format."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "timezone"
+			description: """
+				The [TZ database](\(urls.tz_time_zones)) format used for timestamps without an offset. By
+				default, the [`timezone` option](\(urls.vector_configuration)/global-options#timezone) is used.
+				"""
+			required:    false
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`format` is not a valid format.",
+		"`value` fails to parse using the provided `format`.",
+		"`timezone` is not a known time zone.",
+	]
+	return: types: ["timestamp"]
+
+	examples: [
+		{
+			title: "Parse with a format read from the event"
+			source: #"""
+				parse_timestamp_dynamic!("16/10/2019 12:00:00", format: "%d/%m/%Y %H:%M:%S", timezone: "Asia/Taipei")
+				"""#
+			return: "2019-10-16T04:00:00Z"
+		},
+	]
+}
@@ -0,0 +1,41 @@
+package metadata
+
+remap: functions: reverse_dns: {
+	category: "System"
+	description: """
+		Resolves the host name of an IPv4 or IPv6 address with the name servers of the system.
+
+		In the `remap` transform with the `dns` option set, the lookups are resolved in the
+		background and cached for the TTL of their records, and the function never waits for them:
+		it fails until the answer of the lookup is cached, so handle its error to process the first
+		events with a new value.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to resolve."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address.",
+		"The address has no host name.",
+		"The lookup isn't cached yet, and is in progress.",
+		"Too many lookups are in progress.",
+	]
+	return: types: ["string"]
+	pure: false
+
+	examples: [
+		{
+			title: "Enrich an event with the host name of its client"
+			source: #"""
+				.client_host = reverse_dns(.client_ip) ?? null
+				"""#
+			return:    "dns.google"
+			skip_test: true
+		},
+	]
+}
@@ -0,0 +1,48 @@
+package metadata
+
+remap: functions: set_state: {
+	category: "State"
+	description: """
+		Sets the value of the given key in the state of the `remap` transform. The state is shared across
+		the events processed by the transform, is persisted in the data directory, and is only available when
+		its `state` option is set.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key of the value."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value to set."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name:        "ttl"
+			description: "The number of seconds after which the value expires. Values without a TTL never expire."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`ttl` is negative.",
+		"The state can't be written to the data directory.",
+	]
+	return: types: ["null"]
+	pure: false
+
+	examples: [
+		{
+			title: "Count the events of a host over an hour"
+			source: #"""
+				count = int(get_state!("host:my-host")) ?? 0
+				set_state!("host:my-host", count + 1, ttl: 3600)
+				"""#
+			return: null
+		},
+	]
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: snmp: {
+	name:     "SNMP"
+	thing:    "an \(name) agent"
+	url:      urls.snmp
+	versions: null
+
+	description: "[SNMP](\(urls.snmp)), the Simple Network Management Protocol, is the standard protocol used to monitor and manage network devices such as routers, switches, and printers. Devices run an SNMP agent which sends notifications, known as traps and inform requests, when events such as an interface going down occur."
+}
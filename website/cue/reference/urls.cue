@@ -76,6 +76,7 @@ urls: {
 	aws_s3_cross_account_tutorial:              "\(aws_docs)/AmazonS3/latest/dev/example-walkthroughs-managing-access-example3.html"
 	aws_s3_endpoints:                           "\(aws_docs)/general/latest/gr/rande.html#s3_endpoint"
 	aws_s3_grantee:                             "\(aws_docs)/AmazonS3/latest/dev/acl-overview.html#specifying-grantee"
+	aws_s3_inventory:                           "\(aws_docs)/AmazonS3/latest/userguide/storage-inventory.html"
 	aws_s3_metadata:                            "\(aws_docs)/AmazonS3/latest/dev/UsingMetadata.html#object-metadata"
 	aws_s3_regions:                             "\(aws_docs)/general/latest/gr/rande.html#s3_region"
 	aws_s3_server_access_logs:                  "\(aws_docs)/AmazonS3/latest/dev/ServerLogs.html"
@@ -93,6 +94,7 @@ urls: {
 	axiom_cloud:                                "https://cloud.axiom.co"
 	azure_blob:                                 "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	azure_blob_endpoints:                       "https://docs.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api"
+	azure_event_grid_blob_events:               "https://learn.microsoft.com/en-us/azure/storage/blobs/storage-blob-event-overview"
 	azure_monitor:                              "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:               "https://docs.microsoft.com/en-us/rest/api/monitor/"
 	base16:                                     "\(wikipedia)/wiki/Hexadecimal"
@@ -175,6 +177,7 @@ urls: {
 	dpkg:                                       "https://wiki.debian.org/dpkg"
 	dry_code:                                   "\(wikipedia)/wiki/Don%27t_repeat_yourself"
 	cidr:                                       "\(wikipedia)/wiki/Classless_Inter-Domain_Routing"
+	ebpf:                                       "https://ebpf.io"
 	elastic_beats:                              "https://www.elastic.co/beats/"
 	elasticsearch:                              "https://www.elastic.co/products/elasticsearch"
 	elasticsearch_bulk:                         "https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html"
@@ -209,6 +212,8 @@ urls: {
 	gcp_authentication_api_key:                 "\(gcp)/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:        "\(gcp)/docs/authentication/production"
 	gcp_authentication_service_account:         "\(gcp)/docs/authentication/production#obtaining_and_providing_service_account_credentials_manually"
+	gcp_bigquery:                               "\(gcp)/bigquery"
+	gcp_bigquery_storage_write_api:             "\(gcp)/bigquery/docs/write-api"
 	gcp_cloud_storage:                          "\(gcp)/storage"
 	gcp_chronicle:                              "https://chronicle.security"
 	gcp_folders:                                "\(gcp)/resource-manager/docs/creating-managing-folders"
@@ -228,6 +233,7 @@ urls: {
 	gcs_predefined_acl:                         "\(gcp)/storage/docs/access-control/lists#predefined-acl"
 	gcs_storage_classes:                        "\(gcp)/storage/docs/storage-classes"
 	gcs_custom_metadata:                        "\(gcp)/storage/docs/metadata#custom-metadata"
+	gcs_pubsub_notifications:                   "\(gcp)/storage/docs/pubsub-notifications"
 	git:                                        "https://git-scm.com/"
 	github:                                     "https://github.com"
 	github_protected_branches:                  "https://help.github.com/en/github/administering-a-repository/about-protected-branches"
@@ -240,6 +246,7 @@ urls: {
 	greptimecloud:                              "https://greptime.cloud"
 	greptimedb:                                 "https://github.com/greptimeteam/greptimedb"
 	greptimedb_docs:                            "https://docs.greptime.com/"
+	grpc:                                       "https://grpc.io"
 	grpc_status_code:                           "https://grpc.github.io/grpc/core/md_doc_statuscodes.html"
 	grok:                                       "https://github.com/daschl/grok/tree/master/patterns"
 	grok_debugger:                              "https://grokdebug.herokuapp.com/"
@@ -282,11 +289,14 @@ urls: {
 	ip_ntoa:                                    "https://linux.die.net/man/3/inet_ntoa"
 	ip_ntop:                                    "https://linux.die.net/man/3/inet_ntop"
 	ip_pton:                                    "https://linux.die.net/man/3/inet_pton"
+	ipfix:                                      "https://www.rfc-editor.org/rfc/rfc7011"
+	ipfix_information_elements:                 "https://www.iana.org/assignments/ipfix/ipfix.xhtml"
 	is_normal:                                  "https://doc.rust-lang.org/std/primitive.f64.html#method.is_normal"
 	iso_8601:                                   "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                  "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                 "\(vector_repo)/issues/1694"
 	journalctl:                                 "https://www.freedesktop.org/software/systemd/man/journalctl.html"
+	journal_export_format:                      "https://systemd.io/JOURNAL_EXPORT_FORMATS/#journal-export-format"
 	journald:                                   "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html"
 	json:                                       "\(wikipedia)/wiki/JSON"
 	json_types:                                 "\(wikipedia)/wiki/JSON#Data_types_and_syntax"
@@ -319,6 +329,7 @@ urls: {
 	librdkafka:                                 "\(github)/edenhill/librdkafka"
 	librdkafka_config:                          "\(github)/edenhill/librdkafka/blob/master/CONFIGURATION.md"
 	linux_capability:                           "https://man7.org/linux/man-pages/man7/capabilities.7.html"
+	linux_tracefs:                              "https://docs.kernel.org/trace/ftrace.html"
 	mezmo:                                      "https://mezmo.com/"
 	logfmt:                                     "https://brandur.org/logfmt"
 	logfmt_specs:                               "https://pkg.go.dev/github.com/kr/logfmt#section-documentation"
@@ -369,11 +380,15 @@ urls: {
 	mongodb_command_server_status:              "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:       "https://docs.mongodb.com/manual/reference/connection-string/"
 	mqtt:                                       "https://mqtt.org/"
+	mqtt_shared_subscriptions:                  "https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901250"
 	musl_builder_docker_image:                  "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	native_proto_schema:                        "\(vector_repo)/blob/master/lib/vector-core/proto/event.proto"
 	native_json_schema:                         "\(vector_repo)/blob/master/lib/codecs/tests/data/native_encoding/schema.cue"
 	nats:                                       "https://nats.io/"
+	nats_jetstream:                             "https://docs.nats.io/nats-concepts/jetstream"
 	nats_rs:                                    "\(github)/nats-io/nats.rs"
+	netflow:                                    "\(wikipedia)/wiki/NetFlow"
+	netflow_v9:                                 "https://www.rfc-editor.org/rfc/rfc3954"
 	new_bug_report:                             "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                        "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_relic:                                  "https://newrelic.com/"
@@ -415,10 +430,13 @@ urls: {
 	postgresql:                                 "https://www.postgresql.org/"
 	postgresql_composite_types:                 "https://www.postgresql.org/docs/current/rowtypes.html"
 	postgresql_constraint_trigger:              "https://www.postgresql.org/docs/9.0/sql-createconstraint.html"
+	postgresql_copy_binary_format:              "https://www.postgresql.org/docs/current/sql-copy.html"
 	postgresql_csvlog:                          "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
 	postgresql_default_values:                  "https://www.postgresql.org/docs/current/ddl-default.html"
 	postgresql_json_functions:                  "https://www.postgresql.org/docs/17/functions-json.html"
+	postgresql_logical_replication:             "https://www.postgresql.org/docs/current/logical-replication.html"
 	postgresql_matching:                        "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
+	postgresql_partitioning:                    "https://www.postgresql.org/docs/current/ddl-partitioning.html"
 	postgresql_sqlx_correspondence:             "https://docs.rs/sqlx/latest/sqlx/postgres/types/index.html"
 	procfs:                                     "https://en.wikipedia.org/wiki/Procfs#:~:text=The%20proc%20filesystem%20(procfs)%20is,in%20the%20kernel%20than%20traditional"
 	prometheus:                                 "https://prometheus.io/"
@@ -440,6 +458,8 @@ urls: {
 	pulsar:                                     "https://pulsar.apache.org/"
 	pulsar_protocol:                            "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
 	punycode:                                   "\(wikipedia)/wiki/Punycode"
+	rabbitmq_publisher_confirms:                "https://www.rabbitmq.com/docs/confirms#publisher-confirms"
+	rabbitmq_quorum_queues:                     "https://www.rabbitmq.com/docs/quorum-queues"
 	raspbian:                                   "https://www.raspbian.org/"
 	rdkafka:                                    "\(github)/edenhill/librdkafka"
 	regex:                                      "\(wikipedia)/wiki/Regular_expression"
@@ -455,6 +475,7 @@ urls: {
 	rfc_2460:                                   "https://tools.ietf.org/html/rfc2460"
 	rfc_2822:                                   "https://tools.ietf.org/html/rfc2822#section-3.3"
 	rfc_3339:                                   "https://tools.ietf.org/html/rfc3339"
+	rfc_3414:                                   "https://tools.ietf.org/html/rfc3414"
 	rfc_4180:                                   "https://tools.ietf.org/html/rfc4180"
 	rfc_6587_3_4_1:                             "https://tools.ietf.org/html/rfc6587#section-3.4.1"
 	rfc_6891:                                   "https://tools.ietf.org/html/rfc6891"
@@ -475,6 +496,7 @@ urls: {
 	rustup:                                     "https://rustup.rs"
 	redis:                                      "https://redis.io"
 	redis_rs:                                   "https://github.com/mitsuhiko/redis-rs"
+	redis_streams:                              "https://redis.io/docs/latest/develop/data-types/streams/#consumer-groups"
 	seahash:                                    "https://docs.rs/seahash/latest/seahash/"
 	sematext:                                   "https://sematext.com"
 	sematext_create_logs_app:                   "https://apps.sematext.com/ui/integrations"
@@ -483,6 +505,7 @@ urls: {
 	sematext_monitoring:                        "https://sematext.com/docs/monitoring/"
 	sematext_registration:                      "https://apps.sematext.com/ui/registration"
 	semver:                                     "https://semver.org/"
+	sflow:                                      "https://sflow.org/sflow_version_5.txt"
 	sha1:                                       "\(wikipedia)/wiki/SHA-1"
 	sha2:                                       "\(wikipedia)/wiki/SHA-2"
 	sha3:                                       "\(wikipedia)/wiki/SHA-3"
@@ -490,6 +513,11 @@ urls: {
 	signal:                                     "\(wikipedia)/wiki/Signal_(IPC)"
 	snake_case:                                 "\(wikipedia)/wiki/Snake_case"
 	snappy:                                     "https://google.github.io/snappy/"
+	snowflake:                                  "https://www.snowflake.com"
+	snowpipe_streaming:                         "https://docs.snowflake.com/en/user-guide/snowpipe-streaming/snowpipe-streaming-high-performance-overview"
+	snowflake_key_pair_auth:                    "https://docs.snowflake.com/en/user-guide/key-pair-auth"
+	snmp:                                       "\(wikipedia)/wiki/Simple_Network_Management_Protocol"
+	snmp_notifications:                         "https://tools.ietf.org/html/rfc3416#section-4.2.6"
 	socket:                                     "\(wikipedia)/wiki/Network_socket"
 	splunk:                                     "https://www.splunk.com"
 	splunk_hec:                                 "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
@@ -517,6 +545,7 @@ urls: {
 	syslog:                                     "\(wikipedia)/wiki/Syslog"
 	syslog_3164:                                "https://tools.ietf.org/html/rfc3164"
 	syslog_5424:                                "https://tools.ietf.org/html/rfc5424"
+	syslog_5425:                                "https://tools.ietf.org/html/rfc5425"
 	syslog_6587:                                "https://tools.ietf.org/html/rfc6587"
 	syslog_facility:                            "\(wikipedia)/wiki/Syslog#Facility"
 	syslog_levels:                              "\(wikipedia)/wiki/Syslog#Severity_level"
@@ -675,6 +704,8 @@ urls: {
 	websocket:                                  "\(wikipedia)/wiki/WebSocket"
 	wikipedia:                                  "https://en.wikipedia.org"
 	windows:                                    "https://www.microsoft.com/en-us/windows"
+	windows_eventlog:                           "https://learn.microsoft.com/en-us/windows/win32/wes/windows-event-log"
+	windows_eventlog_xpath:                     "https://learn.microsoft.com/en-us/windows/win32/wes/consuming-events"
 	windows_installer:                          "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                            "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                    "https://github.com/woothee/woothee"